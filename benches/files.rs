@@ -56,6 +56,8 @@ fn benchmark_files_without_partitions(c: &mut Criterion) {
                         idle_timeout_secs: None,
                         encoding: sinks::file::Encoding::Text.into(),
                         compression: sinks::file::Compression::None,
+                        rotation: Default::default(),
+                        fsync: Default::default(),
                     },
                 );
 
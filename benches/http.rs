@@ -43,11 +43,12 @@ fn benchmark_http(c: &mut Criterion) {
                             "out",
                             &["in"],
                             sinks::http::HttpSinkConfig {
-                                uri: out_addr.to_string().parse::<http::Uri>().unwrap().into(),
+                                uri: out_addr.to_string(),
                                 compression: *compression,
                                 method: Default::default(),
                                 healthcheck_uri: Default::default(),
                                 auth: Default::default(),
+                                oauth2: Default::default(),
                                 headers: Default::default(),
                                 batch: sinks::util::BatchConfig {
                                     max_bytes: Some(num_lines * line_size),
@@ -75,6 +75,7 @@ fn benchmark_remap(c: &mut Criterion) {
             .copy = .copy_from"#
                     .to_string(),
                 drop_on_err: true,
+                reroute_dropped: false,
             })
             .unwrap(),
         );
@@ -135,6 +136,7 @@ fn benchmark_remap(c: &mut Criterion) {
             Remap::new(RemapConfig {
                 source: ".bar = parse_json(.foo)".to_owned(),
                 drop_on_err: false,
+                reroute_dropped: false,
             })
             .unwrap(),
         );
@@ -161,6 +163,7 @@ fn benchmark_remap(c: &mut Criterion) {
             drop_field: false,
             drop_invalid: false,
             overwrite_target: None,
+            reroute_dropped: false,
         }));
 
         let event = {
@@ -203,6 +206,7 @@ fn benchmark_remap(c: &mut Criterion) {
                 "#
                 .to_owned(),
                 drop_on_err: true,
+                reroute_dropped: false,
             })
             .unwrap(),
         );
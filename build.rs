@@ -2,6 +2,10 @@ fn main() {
     println!("cargo:rerun-if-changed=proto/event.proto");
     println!("cargo:rerun-if-changed=proto/prometheus-remote.proto");
     println!("cargo:rerun-if-changed=proto/prometheus-types.proto");
+    println!("cargo:rerun-if-changed=proto/opentelemetry");
+    println!("cargo:rerun-if-changed=proto/google");
+    println!("cargo:rerun-if-changed=proto/aws");
+    println!("cargo:rerun-if-changed=proto/datadog");
     let mut prost_build = prost_build::Config::new();
     prost_build.btree_map(&["."]);
     // It would be nice to just add these derives to all the types, but
@@ -10,7 +14,15 @@ fn main() {
     prost_build.type_attribute(".prometheus.Label", "#[derive(Eq, Hash, Ord, PartialOrd)]");
     prost_build
         .compile_protos(
-            &["proto/event.proto", "proto/prometheus-remote.proto"],
+            &[
+                "proto/event.proto",
+                "proto/prometheus-remote.proto",
+                "proto/opentelemetry/proto/collector/logs/v1/logs_service.proto",
+                "proto/opentelemetry/proto/collector/metrics/v1/metrics_service.proto",
+                "proto/google/cloud/bigquery/storage/v1/storage.proto",
+                "proto/aws/kinesis/aggregation.proto",
+                "proto/datadog/agent_payload.proto",
+            ],
             &["proto/"],
         )
         .unwrap();
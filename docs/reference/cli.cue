@@ -103,6 +103,10 @@ cli: {
 			description: "Watch for changes in the configuration file, and reload accordingly"
 			env_var:     "VECTOR_WATCH_CONFIG"
 		}
+		"strict-env-vars": {
+			description: "Fail to load the configuration when it references environment variables that aren't set, instead of substituting an empty string for them"
+			env_var:     "VECTOR_STRICT_ENV_VARS"
+		}
 	}
 
 	options: {
@@ -155,11 +159,23 @@ cli: {
 			type:    "string"
 			env_var: "VECTOR_CONFIG_YAML"
 		}
+		"config-dir": {
+			_short: "C"
+			description: """
+				Read configuration from every file in one or more directories,
+				alongside any other config files. The format of each file is
+				determined from its extension, and files without a supported
+				one are ignored. Components defined in more than one file are
+				reported as duplicates.
+				"""
+			type:    "string"
+			env_var: "VECTOR_CONFIG_DIR"
+		}
 		"threads": {
 			_short: "t"
 			description: """
 				Number of threads to use for processing (default is number of
-				available cores)
+				available cores, taking container CPU limits into account)
 				"""
 			type:    "integer"
 			env_var: "VECTOR_THREADS"
@@ -285,6 +301,10 @@ cli: {
 					_short:      "d"
 					description: "Fail validation on warnings"
 				}
+				"strict-env-vars": {
+					description: "Fail validation when the configuration references environment variables that aren't set"
+					env_var:     "VECTOR_STRICT_ENV_VARS"
+				}
 			}
 
 			options: {
@@ -309,6 +329,14 @@ cli: {
 						"""
 					type: "string"
 				}
+				"config-dir": {
+					_short: "C"
+					description: """
+						Any number of directories of Vector config files to
+						validate.
+						"""
+					type: "string"
+				}
 			}
 
 			args: {
@@ -298,6 +298,10 @@ components: {
 			}
 		}
 
+		// `dead_letter` describes whether the component can send the events
+		// it gives up on to a `dead_letter` output.
+		dead_letter: bool | *false
+
 		// `encoding` describes how the component encodes data.
 		encoding: {
 			enabled: bool
@@ -127,6 +127,12 @@ components: sinks: [Name=string]: {
 							if list.Contains(sinks[Name].features.send.compression.algorithms, "gzip") {
 								gzip: "[Gzip](\(urls.gzip)) standard DEFLATE compression."
 							}
+							if list.Contains(sinks[Name].features.send.compression.algorithms, "zstd") {
+								zstd: "[Zstandard](\(urls.zstd)) compression."
+							}
+							if list.Contains(sinks[Name].features.send.compression.algorithms, "snappy") {
+								snappy: "[Snappy](\(urls.snappy)) compression, in its raw format without framing."
+							}
 						}
 					}
 				}
@@ -185,6 +191,17 @@ components: sinks: [Name=string]: {
 			}
 		}
 
+		if sinks[Name].features.send != _|_ {
+			if sinks[Name].features.send.dead_letter {
+				dead_letter: {
+					common:      false
+					description: "Sends the events of requests that fail after all retries, or are rejected, to the `<sink name>.dead_letter` output instead of dropping them. Other components can list that output as an input."
+					required:    false
+					type: bool: default: false
+				}
+			}
+		}
+
 		if sinks[Name].features.send != _|_ {
 			if sinks[Name].features.healthcheck.enabled {
 				healthcheck: {
@@ -224,6 +241,24 @@ components: sinks: [Name=string]: {
 											required:    false
 											type: float: default: 0.7
 										}
+										initial_concurrency: {
+											common:      false
+											description: "The concurrency limit to start from. Raising it lets Vector reach the capacity of services known to handle many requests sooner."
+											required:    false
+											type: uint: {
+												default: 1
+												unit:    "requests"
+											}
+										}
+										max_concurrency: {
+											common:      false
+											description: "The highest the concurrency limit is raised to, however quickly the service responds."
+											required:    false
+											type: uint: {
+												default: 200
+												unit:    "requests"
+											}
+										}
 										rtt_threshold_ratio: {
 											common:      false
 											description: "When comparing the past RTT average to the current measurements, we ignore changes that are less than this ratio higher than the past RTT. Valid values are greater than or equal to 0. Larger values cause the algorithm to ignore larger increases in the RTT."
@@ -233,10 +268,62 @@ components: sinks: [Name=string]: {
 									}
 								}
 							}
+							circuit_breaker: {
+								common:      false
+								description: "Stops sending requests for a while once too many of them fail, so a service that is down isn't flooded with hopeless retries."
+								required:    false
+								type: object: {
+									examples: []
+									options: {
+										cooldown_secs: {
+											common:      false
+											description: "How long no requests are sent once the breaker opens, before a single request probes the service."
+											required:    false
+											type: uint: {
+												default: 30
+												unit:    "seconds"
+											}
+										}
+										enabled: {
+											common:      false
+											description: "Enables the circuit breaker."
+											required:    false
+											type: bool: default: false
+										}
+										error_rate: {
+											common:      false
+											description: "The share of failed requests among the latest `window_requests` that opens the breaker. Valid values are greater than 0 and at most 1."
+											required:    false
+											type: float: default: 0.5
+										}
+										on_open: {
+											common:      false
+											description: "What happens to requests while the breaker is open."
+											required:    false
+											type: string: {
+												default: "wait"
+												enum: {
+													wait:   "Requests wait for the breaker to close, holding events in the buffer of the sink."
+													reject: "Requests fail without being sent, and their events are dropped, or sent to the `dead_letter` output where the sink supports it."
+												}
+											}
+										}
+										window_requests: {
+											common:      false
+											description: "The number of latest requests the error rate is measured over."
+											required:    false
+											type: uint: {
+												default: 20
+												unit:    "requests"
+											}
+										}
+									}
+								}
+							}
 							concurrency: {
 								common: true
 								if sinks[Name].features.send.request.adaptive_concurrency {
-									description: "The maximum number of in-flight requests allowed at any given time, or \"auto\" to allow Vector to automatically set the limit based on current network and service conditions."
+									description: "The maximum number of in-flight requests allowed at any given time, or \"adaptive\" to allow Vector to automatically set the limit based on current network and service conditions."
 								}
 								if !sinks[Name].features.send.request.adaptive_concurrency {
 									description: "The maximum number of in-flight requests allowed at any given time."
@@ -361,6 +448,23 @@ components: sinks: [Name=string]: {
 			}
 		}
 
+		if sinks[Name].features.send != _|_ {
+			if sinks[Name].features.send.dead_letter {
+				dead_letter: {
+					title: "Dead letters"
+					body: """
+						With `dead_letter` enabled, events of requests that fail
+						once retries are exhausted, or that the service rejects
+						outright, such as with a `400`, are sent to the
+						`<sink name>.dead_letter` output rather than dropped. It
+						can be consumed like any other component, for instance by a
+						`file` sink keeping the events for later inspection.
+						Events sent there are still acknowledged by this sink.
+						"""
+				}
+			}
+		}
+
 		if sinks[Name].features.healthcheck.enabled {
 			healthchecks: {
 				title: "Health checks"
@@ -430,7 +534,10 @@ components: sinks: [Name=string]: {
 								with static rate limits and automatically optimizes HTTP
 								concurrency limits based on downstream service responses. The
 								underlying mechanism is a feedback loop inspired by TCP congestion
-								control algorithms. Checkout the [announcement blog post](\(urls.adaptive_request_concurrency_post)),
+								control algorithms: the limit grows by one request while response
+								times hold steady, and shrinks by `decrease_ratio` when they rise or
+								the service responds with errors that are retried, such as timeouts,
+								`429`s and `5xx`s. Checkout the [announcement blog post](\(urls.adaptive_request_concurrency_post)),
 
 								We highly recommend enabling this feature as it improves
 								performance and reliability of Vector and the systems it
@@ -471,6 +578,19 @@ components: sinks: [Name=string]: {
 						`request.retry_backoff_secs` options.
 						"""
 				}
+
+				circuit_breaker: {
+					title: "Circuit breaker"
+					body: """
+						With `request.circuit_breaker.enabled`, requests stop once at
+						least `error_rate` of the latest `window_requests` requests
+						failed, either with an error or a response that would be
+						retried. After `cooldown_secs` a single request probes the
+						service. Its success resumes requests, while its failure
+						starts another cool-down. Meanwhile requests wait, letting
+						events pile up in the buffer, unless `on_open` is `reject`.
+						"""
+				}
 			}
 		}
 
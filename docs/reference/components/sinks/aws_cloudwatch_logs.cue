@@ -94,6 +94,25 @@ components: sinks: aws_cloudwatch_logs: components._aws & {
 			required:    false
 			type: bool: default: true
 		}
+		group_retention_days: {
+			common:      false
+			description: "The [retention](\(urls.aws_cloudwatch_logs_retention)) of the log groups the sink creates, in days. Must be one of 1, 3, 5, 7, 14, 30, 60, 90, 120, 150, 180, 365, 400, 545, 731, 1827 or 3653. Groups that already exist are left as they are."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [30, 365]
+				unit: null
+			}
+		}
+		group_tags: {
+			common:      false
+			description: "The tags of the log groups the sink creates. Groups that already exist are left as they are."
+			required:    false
+			type: object: {
+				examples: [{team: "payments", environment: "production"}]
+				options: {}
+			}
+		}
 		group_name: {
 			description: "The [group name](\(urls.aws_cloudwatch_logs_group_name)) of the target CloudWatch Logs stream."
 			required:    true
@@ -117,6 +136,46 @@ components: sinks: aws_cloudwatch_logs: components._aws & {
 		metrics: null
 	}
 
+	how_it_works: {
+		batch_limits: {
+			title: "Batch Limits"
+			body: """
+				A `PutLogEvents` request can hold at most 10,000 events and
+				1,048,576 bytes, counting 26 bytes per event, so `batch.max_events`
+				and `batch.max_bytes` can't be set any higher. The events of a
+				batch are sorted by their timestamps and split into requests
+				spanning at most 24 hours each, as CloudWatch Logs requires, and
+				events older than 14 days or more than 2 hours in the future are
+				dropped.
+				"""
+		}
+
+		sequence_tokens: {
+			title: "Sequence Tokens"
+			body: """
+				Each [`PutLogEvents`](\(urls.aws_cloudwatch_logs_sequence_token))
+				request to a log stream must carry the sequence token returned by
+				the previous one, so Vector sends the batches of each stream one at
+				a time. When another writer sent to the stream in between, the
+				batch is sent again with the token CloudWatch Logs expects, and a
+				batch it already accepted, like one whose request timed out, isn't
+				sent twice.
+				"""
+		}
+
+		group_creation: {
+			title: "Group Creation"
+			body: """
+				With `create_missing_group` enabled, log groups that don't exist
+				are created with the `group_tags`, and their retention is set to
+				`group_retention_days`. Vector needs the `logs:CreateLogGroup`,
+				`logs:TagLogGroup` and `logs:PutRetentionPolicy` permissions for
+				this. Failing to set the retention doesn't stop events from being
+				sent, and is logged as an error.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
 	}
@@ -89,12 +89,85 @@ components: sinks: aws_cloudwatch_metrics: components._aws & {
 		default_namespace: {
 			description: """
 				A [namespace](https://docs.aws.amazon.com/AmazonCloudWatch/latest/monitoring/cloudwatch_concepts.html#Namespace) that will isolate different metrics from each other.
-				Used as a namespace for metrics that don't have it.
+				Used as a namespace for metrics that don't have it. Templates are rendered against the tags of metrics.
 				"""
 			required: true
 			warnings: []
 			type: string: {
-				examples: ["service"]
+				examples: ["service", "{{ service }}"]
+				templateable: true
+			}
+		}
+		emf: {
+			common:      false
+			description: "Sends metrics through CloudWatch Logs in the [Embedded Metric Format](\(urls.aws_cloudwatch_metrics_emf)) instead of calling `PutMetricData`. CloudWatch extracts the metrics from the log events, which is much cheaper at high volumes."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					create_missing_group: {
+						common:      true
+						description: "Dynamically create a [log group](\(urls.aws_cloudwatch_logs_group_name)) if it does not already exist."
+						required:    false
+						type: bool: default: true
+					}
+					create_missing_stream: {
+						common:      true
+						description: "Dynamically create a [log stream](\(urls.aws_cloudwatch_logs_stream_name)) if it does not already exist."
+						required:    false
+						type: bool: default: true
+					}
+					dimensions: {
+						common:      false
+						description: "The sets of tags metrics are published with as [dimensions](\(urls.aws_cloudwatch_metrics_emf)), each of at most 30 tags. A metric is published once per set it has every tag of, and without dimensions if it has none. By default, metrics are published with a single set of their first 30 tags."
+						required:    false
+						warnings: []
+						type: array: {
+							default: []
+							items: type: array: items: type: string: examples: ["service", "region"]
+						}
+					}
+					group_name: {
+						description: "The [group name](\(urls.aws_cloudwatch_logs_group_name)) of the CloudWatch Logs stream the metrics are written to."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["metrics", "{{ service }}"]
+							templateable: true
+						}
+					}
+					group_retention_days: {
+						common:      false
+						description: "The [retention](\(urls.aws_cloudwatch_logs_retention)) of the log groups the sink creates, in days. Must be one of 1, 3, 5, 7, 14, 30, 60, 90, 120, 150, 180, 365, 400, 545, 731, 1827 or 3653."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [1, 7]
+							unit: null
+						}
+					}
+					group_tags: {
+						common:      false
+						description: "The tags of the log groups the sink creates."
+						required:    false
+						warnings: []
+						type: object: {
+							examples: [{team: "payments"}]
+							options: {}
+						}
+					}
+					stream_name: {
+						description: "The [stream name](\(urls.aws_cloudwatch_logs_stream_name)) of the CloudWatch Logs stream the metrics are written to."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["vector", "{{ host }}"]
+							templateable: true
+						}
+					}
+				}
 			}
 		}
 	}
@@ -110,4 +183,20 @@ components: sinks: aws_cloudwatch_metrics: components._aws & {
 			summary:      false
 		}
 	}
+
+	how_it_works: {
+		embedded_metric_format: {
+			title: "Embedded Metric Format"
+			body: """
+				With `emf` set, each metric is written to CloudWatch Logs as a
+				JSON log event in the [Embedded Metric
+				Format](\(urls.aws_cloudwatch_metrics_emf)), with its tags as
+				fields, and CloudWatch extracts the metric from it. Counters and
+				sets are written as a single value and distributions as the
+				list of their values, repeated by their sample rates and split
+				across events of up to 100 values. The log group is billed for
+				the ingested events, but there are no `PutMetricData` calls.
+				"""
+		}
+	}
 }
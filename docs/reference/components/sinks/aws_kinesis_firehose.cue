@@ -19,7 +19,7 @@ components: sinks: aws_kinesis_firehose: components._aws & {
 			batch: {
 				enabled:      true
 				common:       false
-				max_bytes:    4000000
+				max_bytes:    4194304
 				max_events:   500
 				timeout_secs: 1
 			}
@@ -81,6 +81,13 @@ components: sinks: aws_kinesis_firehose: components._aws & {
 	}
 
 	configuration: {
+		aggregate: {
+			common:      false
+			description: "Whether to pack the events of a batch into as few records as possible, each one followed by a newline."
+			required:    false
+			warnings: ["Only suited to destinations that split records on newlines, like Amazon S3. Amazon ES and Splunk expect one event per record."]
+			type: bool: default: false
+		}
 		stream_name: {
 			description: "The [stream name](\(urls.aws_cloudwatch_logs_stream_name)) of the target Kinesis Firehose delivery stream."
 			required:    true
@@ -95,4 +102,27 @@ components: sinks: aws_kinesis_firehose: components._aws & {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		aggregation: {
+			title: "Aggregation"
+			body:  """
+				Kinesis Firehose bills records in 5 KB increments, so sending many small
+				events as records of their own is costly. With `aggregate` enabled, Vector
+				joins the events of a batch into records of up to 1000 KiB, one event per
+				line.
+				"""
+		}
+
+		request_limits: {
+			title: "Request Limits"
+			body:  """
+				A `PutRecordBatch` request can contain at most 500 records and 4 MiB, so
+				`batch.max_events` and `batch.max_bytes` can't be set any higher. When
+				aggregating, only `batch.max_bytes` limits batches. Events larger than the
+				1000 KiB a record can hold are dropped. See the
+				[service limits](\(urls.aws_kinesis_firehose_service_limits)) for more.
+				"""
+		}
+	}
 }
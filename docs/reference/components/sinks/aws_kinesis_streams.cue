@@ -19,7 +19,7 @@ components: sinks: aws_kinesis_streams: components._aws & {
 			batch: {
 				enabled:      true
 				common:       false
-				max_bytes:    5000000
+				max_bytes:    5242880
 				max_events:   500
 				timeout_secs: 1
 			}
@@ -81,11 +81,29 @@ components: sinks: aws_kinesis_streams: components._aws & {
 	}
 
 	configuration: {
-		partition_key_field: {
+		aggregate: {
+			common:      false
+			description: "Whether to pack records with the same partition key into [aggregated records](\(urls.aws_kinesis_aggregation)), in the format of the Kinesis Producer Library. Consumers have to de-aggregate them, as the Kinesis Client Library and AWS Lambda do."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		partition_key: {
 			common:      true
-			description: "The log field used as the Kinesis record's partition key value."
+			description: "The partition key of the Kinesis records. Records with the same partition key are sent to the same shard."
 			required:    false
 			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ user_id }}", "{{ application }}-{{ host }}"]
+				templateable: true
+			}
+		}
+		partition_key_field: {
+			common:      false
+			description: "The log field used as the Kinesis record's partition key value. Deprecated, use `partition_key` instead."
+			required:    false
+			warnings: ["Can't be set together with `partition_key`."]
 			type: string: {
 				default: null
 				examples: ["user_id"]
@@ -110,31 +128,40 @@ components: sinks: aws_kinesis_streams: components._aws & {
 		partitioning: {
 			title: "Partitioning"
 			body:  """
-				By default, Vector issues random 16 byte values for each
+				By default, Vector issues random 16 character values for each
 				[Kinesis record's partition key](\(urls.aws_kinesis_partition_key)), evenly
-				distributing records across your Kinesis partitions. Depending on your use case
+				distributing records across your Kinesis shards. Depending on your use case
 				this might not be sufficient since random distribution does not preserve order.
-				To override this, you can supply the `partition_key_field` option. This option
-				presents an alternate field on your event to use as the partition key value instead.
-				This is useful if you have a field already on your event, and it also pairs
-				nicely with the [`add_fields` transform][docs.transforms.add_fields].
+				To override this, you can supply the `partition_key` option, a template
+				rendered from the fields of each event, such as `{{ user_id }}`. Records with
+				the same partition key are hashed to the same shard, in the order they were
+				sent. This pairs nicely with the [`add_fields` transform][docs.transforms.add_fields].
 				"""
 			sub_sections: [
 				{
 					title: "Missing partition keys"
 					body: """
-						Kenesis requires a value for the partition key and therefore if the key is
-						missing or the value is blank the event will be dropped and a
-						[`warning` level log event][docs.monitoring#logs] will be logged. As such,
-						the field specified in the `partition_key_field` option should always contain
-						a value.
+						Kinesis requires a value for the partition key, so if a field of the
+						template is missing, the event is dropped and a
+						[`warning` level log event][docs.monitoring#logs] is logged. If the
+						rendered key is blank, a random key is used instead.
 						"""
 				},
 				{
 					title: "Partition keys that exceed 256 characters"
 					body: """
-						If the value provided exceeds the maximum allowed length of 256 characters
-						Vector will slice the value and use the first 256 characters.
+						If the rendered key exceeds the maximum allowed length of 256 characters
+						Vector will slice the value and use the first 256 characters. Keys that
+						only differ after their first 256 characters are therefore sent to the
+						same shard.
+						"""
+				},
+				{
+					title: "Hot shards"
+					body: """
+						Each shard accepts at most 1 MiB or 1000 records per second, so a
+						partition key that few events share, like a user ID, spreads load better
+						than one many events share, like a host name.
 						"""
 				},
 				{
@@ -145,5 +172,32 @@ components: sinks: aws_kinesis_streams: components._aws & {
 				},
 			]
 		}
+
+		aggregation: {
+			title: "Aggregation"
+			body:  """
+				Kinesis bills records in 25 KB units, and limits shards to 1000 records
+				per second, so sending many small events as records of their own is costly.
+				With `aggregate` enabled, Vector packs the events of a batch into
+				[aggregated records](\(urls.aws_kinesis_aggregation)) of up to 1 MiB. Only
+				events with the same partition key are packed together, so each event still
+				lands on the shard its key hashes to. Without a `partition_key`, any events
+				can be packed together.
+
+				Consumers have to de-aggregate the records, which the Kinesis Client Library
+				and AWS Lambda event sources do. Events alone in a batch are sent as regular
+				records.
+				"""
+		}
+
+		request_limits: {
+			title: "Request Limits"
+			body:  """
+				A `PutRecords` request can contain at most 500 records and 5 MiB, so
+				`batch.max_events` and `batch.max_bytes` can't be set any higher. When
+				aggregating, only `batch.max_bytes` limits batches. Events larger than the
+				1 MiB a record can hold are dropped.
+				"""
+		}
 	}
 }
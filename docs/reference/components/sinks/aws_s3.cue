@@ -26,7 +26,7 @@ components: sinks: aws_s3: components._aws & {
 			compression: {
 				enabled: true
 				default: "gzip"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -34,7 +34,7 @@ components: sinks: aws_s3: components._aws & {
 				codec: {
 					enabled: true
 					default: "text"
-					enum: ["ndjson", "text"]
+					enum: ["ndjson", "parquet", "text"]
 				}
 			}
 			request: {
@@ -121,7 +121,7 @@ components: sinks: aws_s3: components._aws & {
 		content_type: {
 			category:    "Content Type"
 			common:      false
-			description: "A standard MIME type describing the format of the contents."
+			description: "A standard MIME type describing the format of the contents. Defaults to `application/vnd.apache.parquet` when `encoding.codec` is `parquet`."
 			required:    false
 			warnings: []
 			type: string: {
@@ -149,7 +149,7 @@ components: sinks: aws_s3: components._aws & {
 		filename_time_format: {
 			category:    "File Naming"
 			common:      false
-			description: "The format of the resulting object file name. [`strftime` specifiers](\(urls.strptime_specifiers)) are supported."
+			description: "The format of the resulting object file name. [`strftime` specifiers](\(urls.strptime_specifiers)) are supported, and are rendered with the time the batch is sent. If empty, the name is only the UUID."
 			required:    false
 			warnings: []
 			type: string: {
@@ -203,15 +203,61 @@ components: sinks: aws_s3: components._aws & {
 		key_prefix: {
 			category:    "File Naming"
 			common:      true
-			description: "A prefix to apply to all object key names. This should be used to partition your objects, and it's important to end this value with a `/` if you want this to be the root S3 \"folder\"."
+			description: "A prefix to apply to all object key names. This should be used to partition your objects, and it's important to end this value with a `/` if you want this to be the root S3 \"folder\". Events are batched separately for every rendered prefix, and `strftime` specifiers are rendered with the event's timestamp."
 			required:    false
 			warnings: []
 			type: string: {
 				default: "date=%F/"
-				examples: ["date=%F/", "date=%F/hour=%H/", "year=%Y/month=%m/day=%d/", "application_id={{ application_id }}/date=%F/"]
+				examples: ["date=%F/", "date=%F/hour=%H/", "year=%Y/month=%m/day=%d/", "application_id={{ application_id }}/date=%F/", "logs/{{ app }}/dt=%Y-%m-%d/hour=%H/"]
 				templateable: true
 			}
 		}
+		parquet: {
+			common:      false
+			description: "How batches are written when `encoding.codec` is `parquet`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					compression: {
+						common:      false
+						description: "The compression applied to the pages of each column. The `compression` option doesn't apply to Parquet files."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "snappy"
+							enum: {
+								none:   "No compression."
+								snappy: "[Snappy](\(urls.snappy)) compression."
+								gzip:   "[Gzip](\(urls.gzip)) compression."
+								zstd:   "[Zstandard](\(urls.zstd)) compression."
+							}
+						}
+					}
+					row_group_size: {
+						common:      false
+						description: "The most rows written to one row group."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 10000
+							unit:    "events"
+						}
+					}
+					schema: {
+						common:      true
+						description: "The columns of each file, in order, mapping the field each is read from to its type: one of `string`, `int64`, `double`, `boolean` or `timestamp`. Values that can't be converted to their column's type are written as null. When not set, a column is inferred from every top-level field in the batch."
+						required:    false
+						warnings: []
+						type: object: {
+							examples: [{"timestamp": "timestamp", "message": "string", "status": "int64"}]
+							options: {}
+						}
+					}
+				}
+			}
+		}
 		server_side_encryption: {
 			category:    "Encryption"
 			common:      false
@@ -229,12 +275,12 @@ components: sinks: aws_s3: components._aws & {
 		ssekms_key_id: {
 			category:    "Encryption"
 			common:      false
-			description: "If `server_side_encryption` has the value `\"aws.kms\"`, this specifies the ID of the AWS Key Management Service (AWS KMS) symmetrical customer managed customer master key (CMK) that will used for the created objects. If not specified, Amazon S3 uses the AWS managed CMK in AWS to protect the data."
+			description: "The ID or ARN of the AWS Key Management Service (AWS KMS) symmetrical customer managed customer master key (CMK) that will used for the created objects. Setting this implies `server_side_encryption = \"aws:kms\"`. If not specified with `\"aws:kms\"`, Amazon S3 uses the AWS managed CMK in AWS to protect the data."
 			required:    false
 			warnings: []
 			type: string: {
 				default: null
-				examples: ["abcd1234"]
+				examples: ["abcd1234", "arn:aws:kms:us-east-1:123456789012:key/abcd1234"]
 			}
 		}
 		storage_class: {
@@ -258,11 +304,11 @@ components: sinks: aws_s3: components._aws & {
 		}
 		tags: {
 			common:      false
-			description: "The tag-set for the object."
+			description: "The tag-set for the object. S3 allows at most 10 tags, with keys of up to 128 characters and values of up to 256 characters."
 			required:    false
 			warnings: []
 			type: object: {
-				examples: [{"Tag1": "Value1"}]
+				examples: [{"Tag1": "Value1"}, {"classification": "confidential", "retention": "7y"}]
 				options: {}
 			}
 		}
@@ -358,6 +404,38 @@ components: sinks: aws_s3: components._aws & {
 				"""
 		}
 
+		parquet: {
+			title: "Parquet"
+			body:  """
+				With `encoding.codec` set to `parquet`, each batch is written as one
+				[Parquet](\(urls.apache_parquet)) file, with a row per event. The columns
+				come from `parquet.schema` or, when it isn't set, from the top-level fields
+				of the batch: strings, maps and arrays become `string` columns (maps and
+				arrays as JSON), integers `int64`, floats `double`, booleans `boolean` and
+				timestamps `timestamp` (milliseconds since the Unix epoch). A field holding
+				both integers and floats is a `double` column, and any other mix of types a
+				`string` column.
+
+				Files roll over when a batch fills up: `batch.max_bytes` caps the size of
+				the values before compression, and `batch.timeout_secs` how long events wait
+				to be written. Larger batches make larger row groups and compress better.
+				"""
+		}
+
+		partitioning: {
+			title: "Partitioning"
+			body:  """
+				The `key_prefix` is rendered for every event, from its fields and timestamp,
+				and events are batched separately for every prefix they render. Prefixes
+				such as `logs/{{ app }}/dt=%Y-%m-%d/hour=%H/` give the Hive-style layout that
+				Athena, Glue, and Spark read as partition columns. Events that lack a field
+				used in the prefix are dropped.
+
+				Every prefix has its own batch, so a prefix with many values holds many
+				batches open until they fill or their `batch.timeout_secs` expires.
+				"""
+		}
+
 		object_tags_and_metadata: {
 			title: "Object Tags & metadata"
 			body:  """
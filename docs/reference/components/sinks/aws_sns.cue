@@ -0,0 +1,147 @@
+package metadata
+
+components: sinks: aws_sns: components._aws & {
+	title:       "Amazon Simple Notification Service (SNS)"
+	description: "[Amazon Simple Notification Service (SNS)](\(urls.aws_sns)) is a fully managed publish/subscribe messaging service that fans messages out to queues, functions, HTTP endpoints and people."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["AWS"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["json", "text"]
+				}
+			}
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               30
+			}
+			tls: enabled: false
+			to: {
+				service: {
+					name:     "Amazon Simple Notification Service"
+					thing:    "an \(name) topic"
+					url:      urls.aws_sns
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Amazon Simple Notification Service API"
+							url:   urls.aws_sns_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		message_attributes: {
+			common:      false
+			description: "The [message attributes](\(urls.aws_sns_message_attributes)) of the messages, and the fields of the events they're taken from. Numeric fields are sent as `Number` attributes, anything else as `String` attributes. Missing, null or empty fields are left out. A message can have at most 10 attributes."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{priority: "priority", "user-id": "user.id"}]
+				options: {}
+			}
+		}
+		message_deduplication_id: {
+			common:      false
+			description: "The token used to deduplicate messages sent within 5 minutes of each other. Can be applied only to [FIFO topics](\(urls.aws_sns_fifo)), and is required unless content-based deduplication is enabled on the topic."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ request_id }}"]
+				templateable: true
+			}
+		}
+		message_group_id: {
+			common:      false
+			description: "The tag that specifies that a message belongs to a specific message group. Can be applied only to [FIFO topics](\(urls.aws_sns_fifo)), where it's required."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["vector", "vector-%Y-%m-%d", "{{ user_id }}"]
+				templateable: true
+			}
+		}
+		topic_arn: {
+			description: "The ARN of the Amazon SNS topic to which messages are published."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["arn:aws:sns:us-east-2:123456789012:MyTopic"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		message_size: {
+			title: "Message Size"
+			body: """
+				A message, counting its body and the names, types and values of
+				its attributes, can be at most 256 KiB. Events that don't fit
+				are dropped, and a
+				[`warning` level log event][docs.monitoring#logs] is logged.
+				"""
+		}
+
+		fifo_topics: {
+			title: "FIFO Topics"
+			body: """
+				Topics with ARNs ending in `.fifo` are
+				[FIFO topics](\(urls.aws_sns_fifo)), which deliver the messages
+				of each message group to FIFO queues in order. Their
+				`message_group_id` and `message_deduplication_id` are rendered
+				from the fields of each event, before `encoding.except_fields`
+				removes any, and events missing the fields are dropped.
+				"""
+		}
+	}
+}
@@ -82,14 +82,36 @@ components: sinks: aws_sqs: components._aws & {
 				examples: ["https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue"]
 			}
 		}
+		message_attributes: {
+			common:      false
+			description: "The [message attributes](\(urls.aws_sqs_message_attributes)) of the messages, and the fields of the events they're taken from. Numeric fields are sent as `Number` attributes, anything else as `String` attributes. Missing, null or empty fields are left out. A message can have at most 10 attributes."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{priority: "priority", "user-id": "user.id"}]
+				options: {}
+			}
+		}
+		message_deduplication_id: {
+			common:      false
+			description: "The token used to deduplicate messages sent within 5 minutes of each other. Can be applied only to [FIFO queues](\(urls.aws_sqs_fifo)), and is required unless content-based deduplication is enabled on the queue."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ request_id }}"]
+				templateable: true
+			}
+		}
 		message_group_id: {
 			common:      false
-			description: "The tag that specifies that a message belongs to a specific message group. Can be applied only to FIFO queues."
+			description: "The tag that specifies that a message belongs to a specific message group. Can be applied only to [FIFO queues](\(urls.aws_sqs_fifo)), where it's required."
 			required:    false
 			warnings: []
 			type: string: {
 				default: null
-				examples: ["vector", "vector-%Y-%m-%d"]
+				examples: ["vector", "vector-%Y-%m-%d", "{{ user_id }}"]
+				templateable: true
 			}
 		}
 	}
@@ -98,4 +120,28 @@ components: sinks: aws_sqs: components._aws & {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		message_size: {
+			title: "Message Size"
+			body: """
+				A message, counting its body and the names, types and values of
+				its attributes, can be at most 256 KiB. Events that don't fit
+				are dropped, and a
+				[`warning` level log event][docs.monitoring#logs] is logged.
+				"""
+		}
+
+		fifo_queues: {
+			title: "FIFO Queues"
+			body: """
+				Queues with URLs ending in `.fifo` are
+				[FIFO queues](\(urls.aws_sqs_fifo)), which keep the order of the
+				messages of each message group. Their `message_group_id` and
+				`message_deduplication_id` are rendered from the fields of each
+				event, before `encoding.except_fields` removes any, and events
+				missing the fields are dropped.
+				"""
+		}
+	}
 }
@@ -24,6 +24,7 @@ components: sinks: azure_monitor_logs: {
 				timeout_secs: 1
 			}
 			compression: enabled: false
+			dead_letter:          true
 			encoding: {
 				enabled: true
 				codec: enabled: false
@@ -99,11 +100,12 @@ components: sinks: azure_monitor_logs: {
 			}
 		}
 		log_type: {
-			description: "The [record type of the data that is being submitted](https://docs.microsoft.com/en-us/azure/azure-monitor/platform/data-collector-api#request-headers). Can only contain letters, numbers, and underscore (_), and may not exceed 100 characters."
+			description: "The [record type of the data that is being submitted](https://docs.microsoft.com/en-us/azure/azure-monitor/platform/data-collector-api#request-headers). Can only contain letters, numbers, and underscore (_), and may not exceed 100 characters. Events are batched separately per rendered log type, and events rendering an invalid log type are dropped."
 			required:    true
 			warnings: []
 			type: string: {
-				examples: ["MyTableName", "MyRecordType"]
+				examples: ["MyTableName", "MyRecordType", "{{ application }}_Logs"]
+				templateable: true
 			}
 		}
 		shared_key: {
@@ -114,6 +116,16 @@ components: sinks: azure_monitor_logs: {
 				examples: ["${AZURE_MONITOR_SHARED_KEY_ENV_VAR}", "SERsIYhgMVlJB6uPsq49gCxNiruf6v0vhMYE+lfzbSGcXjdViZdV/e5pEMTYtw9f8SkVLf4LFlLCc2KxtRZfCA=="]
 			}
 		}
+		time_generated_key: {
+			common:      false
+			description: "The field containing the timestamp Azure Monitor should use as the record's [`TimeGenerated`](https://docs.microsoft.com/en-us/azure/azure-monitor/platform/data-collector-api#time-generated-field) time. Timestamps in this field are sent with millisecond precision. Defaults to the global `log_schema.timestamp_key`, which is set to the current time when missing."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "timestamp"
+				examples: ["time_generated", "occurred_at"]
+			}
+		}
 	}
 
 	input: {
@@ -38,6 +38,47 @@ components: sinks: blackhole: {
 	}
 
 	configuration: {
+		acknowledge: {
+			common:      false
+			description: "Whether events are acknowledged. Never acknowledging them holds them in disk buffers, as a downstream outage would."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		failure_rate: {
+			common:      false
+			description: "The share of events, between 0 and 1, that fail to be delivered."
+			required:    false
+			warnings: []
+			type: float: {
+				default: 0.0
+				examples: [0.01]
+			}
+		}
+		latency_ms: {
+			common:      false
+			description: "How long after it is received each event is acknowledged, as by a downstream service with this latency."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 0
+				examples: [250]
+				unit: "milliseconds"
+			}
+		}
+		on_failure: {
+			common:      false
+			description: "What happens to events that fail to be delivered."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "drop"
+				enum: {
+					drop: "The event is acknowledged and discarded, as it's lost."
+					stop: "The sink stops with an error."
+				}
+			}
+		}
 		print_amount: {
 			common:      false
 			description: "The number of events that must be received in order to print a summary of activity."
@@ -49,10 +90,34 @@ components: sinks: blackhole: {
 				unit: null
 			}
 		}
+		report_interval_secs: {
+			common:      true
+			description: "The interval at which the events and bytes received per second are reported."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [10]
+				unit: "seconds"
+			}
+		}
 	}
 
 	input: {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		testing: {
+			title: "Load and Chaos Testing"
+			body: """
+				With `report_interval_secs` set, the sink logs the rate of events
+				and bytes it receives, along with the number of events awaiting
+				acknowledgement. `latency_ms`, `failure_rate` and `acknowledge`
+				make it behave like a slow, unreliable or unavailable downstream
+				service, to see how buffers and the rest of the topology cope.
+				"""
+		}
+	}
 }
@@ -26,9 +26,10 @@ components: sinks: clickhouse: {
 			compression: {
 				enabled: true
 				default: "gzip"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
+			dead_letter: true
 			encoding: {
 				enabled: true
 				codec: enabled: false
@@ -91,14 +92,32 @@ components: sinks: clickhouse: {
 			password_example: "${CLICKHOUSE_PASSWORD}"
 			username_example: "${CLICKHOUSE_USERNAME}"
 		}}
+		async_insert: {
+			common:      false
+			description: "Use ClickHouse's [asynchronous inserts](\(urls.clickhouse_async_insert)), which buffer the rows on the server and write them in larger parts. This is useful with many small batches."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		columns: {
+			common:      false
+			description: "The names and types of the table's columns, in order. Required by the `native` format; fields of the event that aren't listed are not sent."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{message: "String", status: "UInt16", timestamp: "DateTime64(3, 'UTC')", user: "Nullable(String)"}]
+				options: {}
+			}
+		}
 		database: {
 			common:      true
-			description: "The database that contains the stable that data will be inserted into."
+			description: "The database that contains the table that data will be inserted into."
 			required:    false
 			warnings: []
 			type: string: {
-				default: null
-				examples: ["mydatabase"]
+				default: "default"
+				examples: ["mydatabase", "logs_{{ team }}"]
+				templateable: true
 			}
 		}
 		endpoint: {
@@ -108,18 +127,50 @@ components: sinks: clickhouse: {
 				examples: ["http://localhost:8123"]
 			}
 		}
+		format: {
+			common:      false
+			description: "The format the rows are inserted in."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "json_each_row"
+				enum: {
+					json_each_row: "[JSONEachRow](\(urls.clickhouse_formats)), one JSON object per event. The table's columns are filled from the fields with the same names."
+					native:        "ClickHouse's binary [Native](\(urls.clickhouse_formats)) format, which is cheaper for the server to parse. The columns must be listed in `columns`. The supported types are `String`, the integer and float types, `Bool`, `DateTime`, `DateTime64` and `Nullable` of any of these."
+				}
+			}
+		}
 		table: {
 			description: "The table that data will be inserted into."
 			required:    true
 			warnings: []
 			type: string: {
-				examples: ["mytable"]
+				examples: ["mytable", "{{ service }}_logs"]
+				templateable: true
 			}
 		}
+		wait_for_async_insert: {
+			common:      false
+			description: "Whether the server only responds to an asynchronous insert once the rows are written. The server's setting is used if unset."
+			required:    false
+			warnings: []
+			type: bool: default: null
+		}
 	}
 
 	input: {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		retries: {
+			title: "Retries"
+			body: """
+				Failed inserts are retried, except when ClickHouse's error code shows that
+				retrying can't help, such as when the data doesn't match the table's columns,
+				the table doesn't exist, or authentication failed. Those batches are dropped.
+				"""
+		}
+	}
 }
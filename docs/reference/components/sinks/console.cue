@@ -21,7 +21,7 @@ components: sinks: console: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["json", "text"]
+					enum: ["json", "pretty", "table", "text"]
 				}
 			}
 			request: enabled: false
@@ -50,6 +50,82 @@ components: sinks: console: {
 	}
 
 	configuration: {
+		pretty: {
+			common:      false
+			description: "Options of the `pretty` codec."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					color: {
+						common:      true
+						description: "Whether levels and field names are colored."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "auto"
+							enum: {
+								auto:   "Colored when `target` is a terminal."
+								always: "Always colored."
+								never:  "Never colored."
+							}
+						}
+					}
+					fold_depth: {
+						common:      false
+						description: "The levels of nested fields printed on lines of their own. Deeper fields are folded into JSON on the line of their parent."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 2
+							unit:    null
+						}
+					}
+					level_field: {
+						common:      true
+						description: "The field holding the level of logs."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "level"
+							examples: ["severity"]
+						}
+					}
+				}
+			}
+		}
+		table: {
+			common:      false
+			description: "Options of the `table` codec."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					fields: {
+						common:      true
+						description: "The fields printed in columns, in order."
+						required:    false
+						warnings: []
+						type: array: {
+							default: ["timestamp", "message"]
+							items: type: string: examples: ["timestamp", "level", "host", "message"]
+						}
+					}
+					max_column_width: {
+						common:      false
+						description: "The width longer values are cut short to. The last column is never cut short."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 40
+							unit:    null
+						}
+					}
+				}
+			}
+		}
 		target: {
 			common:      true
 			description: "The [standard stream](\(urls.standard_streams)) to write to."
@@ -77,6 +153,20 @@ components: sinks: console: {
 		}
 	}
 
+	how_it_works: {
+		development_output: {
+			title: "Development Output"
+			body: """
+				The `pretty` and `table` codecs are meant for reading events
+				during development. `pretty` prints the timestamp, level and
+				message of each log on one line, followed by its other fields,
+				while `table` prints the chosen fields in aligned columns, with
+				the header printed again whenever a column widens. Metrics are
+				printed as with the `text` codec.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
 	}
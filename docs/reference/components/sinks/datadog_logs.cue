@@ -13,14 +13,14 @@ components: sinks: datadog_logs: {
 			batch: {
 				enabled:      true
 				common:       false
-				max_bytes:    1049000
-				max_events:   null
+				max_bytes:    5000000
+				max_events:   1000
 				timeout_secs: 1
 			}
 			compression: {
 				enabled: true
 				default: "gzip"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -61,11 +61,76 @@ components: sinks: datadog_logs: {
 
 	configuration: {
 		api_key:  sinks._datadog.configuration.api_key
+		api_key_field: {
+			common:      false
+			description: "The field holding the API key to send an event with instead of `api_key`, to route events to several Datadog organizations. The field is removed from the event."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["dd_api_key", "_datadog.api_key"]
+			}
+		}
 		endpoint: sinks._datadog.configuration.endpoint
+		service: {
+			common:      true
+			description: "The `service` attribute of logs. Requires the `json` codec."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["checkout", "{{ kubernetes.pod_labels.app }}"]
+				templateable: true
+			}
+		}
+		source: {
+			common:      true
+			description: "The `ddsource` attribute of logs, which selects the Datadog integration that processes them. Requires the `json` codec."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["nginx", "{{ source_type }}"]
+				templateable: true
+			}
+		}
+		tags: {
+			common:      false
+			description: "The `ddtags` attribute of logs, comma separated `key:value` pairs. Requires the `json` codec."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["env:prod,team:payments", "env:{{ environment }}"]
+				templateable: true
+			}
+		}
 	}
 
 	input: {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		multiple_organizations: {
+			title: "Multiple Organizations"
+			body: """
+				Events with an API key in `api_key_field` are batched separately
+				for each key, and sent with it instead of `api_key`. This routes
+				them to the Datadog organization the key belongs to.
+				"""
+		}
+
+		request_size: {
+			title: "Request Size"
+			body: """
+				Logs are sent to the [v2 intake API](\(urls.datadog_logs_v2)),
+				which rejects requests of more than 1000 logs or 5MB before
+				compression. When it still answers that a request is too large,
+				the batch is split in two and each half sent separately, down to
+				single events. Rate limited requests are retried.
+				"""
+		}
+	}
 }
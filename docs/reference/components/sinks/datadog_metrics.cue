@@ -65,6 +65,13 @@ components: sinks: datadog_metrics: {
 				examples: ["service"]
 			}
 		}
+		histograms_as_distributions: {
+			common:      false
+			description: "Sends histograms as [distributions](\(urls.datadog_distribution_metrics)), keeping all their quantiles, rather than as gauges of their minimum, average, median, maximum and 95th percentile."
+			required:    false
+			warnings: ["Changes the names and type of the metrics histograms are sent as."]
+			type: bool: default: false
+		}
 	}
 
 	input: {
@@ -78,4 +85,17 @@ components: sinks: datadog_metrics: {
 			summary:      false
 		}
 	}
+
+	how_it_works: {
+		distributions: {
+			title: "Distributions"
+			body: """
+				Distributions are sent to the sketches endpoint as
+				[DDSketches](\(urls.ddsketch)) with the parameters of the Datadog
+				Agent, so Datadog merges them with the distributions of Agents,
+				and every quantile stays accurate to within 1% however the
+				sketches are aggregated.
+				"""
+		}
+	}
 }
@@ -19,7 +19,7 @@ components: sinks: file: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -51,6 +51,19 @@ components: sinks: file: {
 	}
 
 	configuration: {
+		fsync: {
+			common:      false
+			description: "When written data is synced to disk."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "on_close"
+				enum: {
+					on_close: "Sync files when they are closed, whether idle, rotated or on shutdown."
+					always:   "Sync files after every event written to them. This is much slower, but limits the data lost on a crash."
+				}
+			}
+		}
 		idle_timeout_secs: {
 			common:      false
 			description: "The amount of time a file can be idle  and stay open. After not receiving any events for this timeout, the file will be flushed and closed.\n"
@@ -70,6 +83,60 @@ components: sinks: file: {
 				templateable: true
 			}
 		}
+		rotation: {
+			common:      false
+			description: "Rotates files by size or age. Rotation is disabled unless `max_bytes` or `interval_secs` is set."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					interval_secs: {
+						common:      true
+						description: "Rotates files once they are this old, checked when writing to them."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [86400]
+							unit: "seconds"
+						}
+					}
+					max_bytes: {
+						common:      true
+						description: "Rotates files before writing an event would grow them beyond this size. Bytes written by Vector are counted before compression."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [104857600]
+							unit: "bytes"
+						}
+					}
+					max_files: {
+						common:      true
+						description: "The number of rotated files to keep for each path. The oldest ones are deleted after each rotation. All rotated files are kept if unset."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [7]
+							unit: null
+						}
+					}
+					suffix: {
+						common:      false
+						description: "The suffix appended to the path of rotated files, with [strftime specifiers](\(urls.strptime_specifiers)) rendered at rotation time. A counter is added if the rotated file already exists."
+						required:    false
+						warnings: []
+						type: string: {
+							default: ".%Y%m%d%H%M%S"
+							examples: [".%Y-%m-%d", ".old"]
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -87,5 +154,16 @@ components: sinks: file: {
 				to create and write to files in the specified directories.
 				"""
 		}
+		rotation: {
+			title: "Rotation"
+			body: """
+				When `rotation` is configured, the file is closed and renamed
+				with the rotation `suffix` appended once it is too large or too
+				old, and events are written to a new file at the original path.
+				Rotated files are those in the same directory whose names start
+				with the name of the active file, so with `max_files` set, the
+				directory should not hold other files sharing that prefix.
+				"""
+		}
 	}
 }
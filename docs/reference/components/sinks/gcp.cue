@@ -28,7 +28,13 @@ components: sinks: [Name=string]: {
 						1. If the [`credentials_path`](#credentials_path) option is set.
 						1. If the `api_key` option is set.
 						1. If the [`GOOGLE_APPLICATION_CREDENTIALS`](#google_application_credentials) envrionment variable is set.
-						1. Finally, Vector will check for an [instance service account](\(urls.gcp_authentication_service_account)).
+						1. If `gcloud auth application-default login` has written
+						   [application default credentials](\(urls.gcp_application_default_credentials)), in
+						   `~/.config/gcloud` or the directory named by `CLOUDSDK_CONFIG`. Both service
+						   account and user credentials are accepted.
+						1. Finally, Vector will check for an [instance service account](\(urls.gcp_authentication_service_account)),
+						   which is also how [workload identities](\(urls.gcp_workload_identity)) on GKE
+						   are found.
 
 						If credentials are not found the [healtcheck](#healthchecks) will fail and an
 						error will be [logged][docs.monitoring#logs].
@@ -0,0 +1,190 @@
+package metadata
+
+components: sinks: gcp_bigquery: {
+	title:       "GCP BigQuery"
+	description: "[BigQuery](\(urls.gcp_bigquery)) is Google Cloud's serverless data warehouse, which analyzes large datasets with SQL."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["GCP"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    9437184
+				max_events:   500
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: {
+					name:     "GCP BigQuery"
+					thing:    "a \(name) table"
+					url:      urls.gcp_bigquery
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Storage Write API"
+							url:   urls.gcp_bigquery_storage_write_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"""
+				The Storage Write API doesn't accept API keys, so
+				`credentials_path` or the `GOOGLE_APPLICATION_CREDENTIALS`
+				environment variable must point to service account
+				credentials, with the `bigquery.tables.get` and
+				`bigquery.tables.updateData` permissions on the tables.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		credentials_path: {
+			common:      true
+			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate access to the BigQuery APIs. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/path/to/credentials.json"]
+			}
+		}
+		dataset: {
+			description: "The dataset the tables belong to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["logs"]
+			}
+		}
+		ignore_unknown_fields: {
+			common:      false
+			description: "Whether to drop the fields of events that aren't columns of their table. When disabled, those events are sent to the `<sink name>.failed` output instead."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		project: {
+			description: "The project ID of the dataset. See the [Google Cloud Platform project management documentation][urls.gcp_projects] for more details."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector-123456"]
+			}
+		}
+		schema_refresh_secs: {
+			common:      false
+			description: "How long the schema of a table is trusted. Events that don't fit a schema older than this get it fetched again, in case columns were added since."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		table: {
+			description: "The table events are written to. It must already exist."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["events", "logs_{{ application }}"]
+				templateable: true
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		exactly_once: {
+			title: "Exactly-Once Writes"
+			body: """
+				Each batch is appended to a pending
+				[write stream](\(urls.gcp_bigquery_storage_write_api)) of its
+				own, at offset zero, and the stream is committed before the
+				events are acknowledged. Retries reuse the stream, so an append
+				or commit whose response got lost isn't repeated, and a batch
+				becomes visible in the table all at once, or not at all. Events
+				are only written twice when Vector stops between the commit and
+				the acknowledgement of their batch.
+				"""
+		}
+
+		schema_mapping: {
+			title: "Schema Mapping"
+			body: """
+				Vector fetches the [schema](\(urls.gcp_bigquery_schemas)) of
+				each table it writes to, and maps the top level fields of
+				events to the columns with the same names. Records are filled
+				from objects, repeated columns from arrays, timestamps from
+				timestamps or RFC 3339 strings, and string columns from any
+				scalar.
+				"""
+		}
+
+		schema_drift: {
+			title: "Schema Drift"
+			body: """
+				Events that don't fit their table, because they have fields it
+				has no columns for, miss required columns, or have values of
+				the wrong type, are sent to the `<sink name>.failed` output,
+				which other components can take as an input, and are otherwise
+				dropped. When the table's schema changed under Vector, the
+				schema is fetched again before giving up on the events, and
+				rows BigQuery rejects are sent to the same output.
+				"""
+		}
+	}
+}
@@ -0,0 +1,241 @@
+package metadata
+
+components: sinks: gcp_cloud_monitoring: {
+	title:       "GCP Cloud Monitoring"
+	description: "[Cloud Monitoring](\(urls.gcp_cloud_monitoring)), formerly Stackdriver Monitoring, collects metrics, events, and metadata from Google Cloud and applications, and charts and alerts on them."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["GCP"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    null
+				max_events:   200
+				timeout_secs: 10
+			}
+			compression: enabled: false
+			dead_letter:          true
+			encoding: enabled:    false
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             1000
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: {
+					name:     "GCP Cloud Monitoring"
+					thing:    "a \(name) account"
+					url:      urls.gcp_cloud_monitoring
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "REST Interface"
+							url:   urls.gcp_cloud_monitoring_rest
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: [
+			"""
+				Cloud Monitoring accepts at most one point every 5 seconds for
+				each series. Lowering `batch.timeout_secs` below that, or
+				sending more than 200 series, can get points rejected.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: {
+		api_key: {
+			common:      false
+			description: "A [Google Cloud API key][urls.gcp_authentication_api_key] used to authenticate access to the Cloud Monitoring API. Either this or `credentials_path` must be set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["${GCP_API_KEY}", "ef8d5de700e7989468166c40fc8a0ccd"]
+			}
+		}
+		buckets: {
+			common:      false
+			description: "The bucket bounds to count [distribution][docs.data-model.metric#distribution] metrics into."
+			required:    false
+			warnings: []
+			type: array: {
+				default: [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+				items: type: float: examples: [0.005, 0.01]
+			}
+		}
+		create_descriptors: {
+			common:      false
+			description: "Whether to create the [metric descriptor](\(urls.gcp_custom_metrics)) of each metric, or add its new labels, before writing it. When disabled, descriptors are created by Cloud Monitoring as metrics are written."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		credentials_path: {
+			common:      true
+			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate access to the Cloud Monitoring API. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/path/to/credentials.json"]
+			}
+		}
+		default_namespace: {
+			common:      true
+			description: "Used as a namespace for metrics that don't have it. A namespace will be prefixed to a metric's name, separated by a `/`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["service"]
+			}
+		}
+		metric_prefix: {
+			common:      false
+			description: "The domain the type of every metric is prefixed with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "custom.googleapis.com"
+				examples: ["custom.googleapis.com", "workload.googleapis.com"]
+			}
+		}
+		project_id: {
+			description: "The project ID to which to publish metrics. See the [Google Cloud Platform project management documentation][urls.gcp_projects] for more details."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector-123456"]
+			}
+		}
+		resource: {
+			common:      false
+			description: "The [monitored resource](\(urls.gcp_monitored_resources)) metrics are written for. When unset, it is detected from the metadata server."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						type:        "gce_instance"
+						project_id:  "vector-123456"
+						instance_id: "Twilight"
+						zone:        "us-central1-a"
+					},
+				]
+				options: {
+					type: {
+						description: "The monitored resource type."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["global", "gce_instance", "k8s_node"]
+						}
+					}
+					"*": {
+						common:      false
+						description: "Values for all of the labels listed in the associated monitored resource descriptor."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["vector-123456", "Twilight"]
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		batching: {
+			title: "Batching"
+			body: """
+				Cloud Monitoring accepts a single point per series in each
+				request, and at most 200 series. Points for the same series
+				within a batch are merged into one: incremental metrics are
+				added up, and absolute ones keep their latest value.
+				"""
+		}
+
+		metric_types: {
+			title: "Metric Types"
+			body: """
+				Cloud Monitoring has no delta custom metrics, so counters are
+				written as cumulative metrics starting when Vector started.
+				Absolute counters are written as their increase since then, and
+				incremental gauges as their running value. Distributions and
+				histograms are written as distributions, sets as the number of
+				their values, and summaries as a gauge for each quantile, plus
+				`_sum` and `_count` gauges. Tags are written as labels, with
+				their keys lowercased and other characters than letters, digits
+				and underscores replaced.
+				"""
+		}
+
+		resource_detection: {
+			title: "Resource Detection"
+			body: """
+				Without a `resource`, Vector asks the metadata server where
+				it's running at startup. On GKE it writes metrics for the
+				`k8s_node` it runs on, on Compute Engine for its
+				`gce_instance`, and elsewhere for the `global` resource.
+				"""
+		}
+	}
+}
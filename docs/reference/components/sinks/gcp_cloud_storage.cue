@@ -26,7 +26,7 @@ components: sinks: gcp_cloud_storage: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["gzip"]
+				algorithms: ["gzip", "zstd", "snappy"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -34,7 +34,7 @@ components: sinks: gcp_cloud_storage: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["ndjson", "text"]
+					enum: ["ndjson", "parquet", "text"]
 				}
 			}
 			request: {
@@ -116,7 +116,7 @@ components: sinks: gcp_cloud_storage: {
 		credentials_path: {
 			category:    "Auth"
 			common:      true
-			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate access to the Cloud Storage API. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename.\n\nIf no filename is named, Vector will attempt to fetch an instance service account for the compute instance the program is running on. If Vector is not running on a GCE instance, you must define a credentials file as above."
+			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate access to the Cloud Storage API. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename.\n\nIf no filename is named, Vector uses the application default credentials written by `gcloud auth application-default login`, if any, and otherwise fetches a token for the instance service account or GKE workload identity from the metadata server."
 			required:    false
 			warnings: []
 			type: string: {
@@ -164,6 +164,17 @@ components: sinks: gcp_cloud_storage: {
 				templateable: true
 			}
 		}
+		kms_key_name: {
+			category:    "Encryption"
+			common:      false
+			description: "The name of the [Cloud KMS key](\(urls.gcs_cmek)) the created objects are encrypted with, instead of the bucket's default encryption key. Vector's service account needs the `cloudkms.cryptoKeyEncrypterDecrypter` role on the key."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["projects/my-project/locations/us/keyRings/logs/cryptoKeys/vector"]
+			}
+		}
 		metadata: {
 			common:      false
 			description: "The set of metadata `key:value` pairs for the created objects. See the [GCS custom metadata][urls.gcs_custom_metadata] documentation for more details."
@@ -174,6 +185,52 @@ components: sinks: gcp_cloud_storage: {
 				examples: []
 			}
 		}
+		parquet: {
+			common:      false
+			description: "How batches are written when `encoding.codec` is `parquet`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					compression: {
+						common:      false
+						description: "The compression applied to the pages of each column. The `compression` option doesn't apply to Parquet files."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "snappy"
+							enum: {
+								none:   "No compression."
+								snappy: "[Snappy](\(urls.snappy)) compression."
+								gzip:   "[Gzip](\(urls.gzip)) compression."
+								zstd:   "[Zstandard](\(urls.zstd)) compression."
+							}
+						}
+					}
+					row_group_size: {
+						common:      false
+						description: "The most rows written to one row group."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 10000
+							unit:    "events"
+						}
+					}
+					schema: {
+						common:      true
+						description: "The columns of each file, in order, mapping the field each is read from to its type: one of `string`, `int64`, `double`, `boolean` or `timestamp`. Values that can't be converted to their column's type are written as null. When not set, a column is inferred from every top-level field in the batch."
+						required:    false
+						warnings: []
+						type: object: {
+							examples: [{"timestamp": "timestamp", "message": "string", "status": "int64"}]
+							options: {}
+						}
+					}
+				}
+			}
+		}
 		storage_class: {
 			category:    "Storage"
 			common:      false
@@ -259,6 +316,24 @@ components: sinks: gcp_cloud_storage: {
 				"""
 		}
 
+		parquet: {
+			title: "Parquet"
+			body:  """
+				With `encoding.codec` set to `parquet`, each batch is written as one
+				[Parquet](\(urls.apache_parquet)) file, with a row per event. The columns
+				come from `parquet.schema` or, when it isn't set, from the top-level fields
+				of the batch: strings, maps and arrays become `string` columns (maps and
+				arrays as JSON), integers `int64`, floats `double`, booleans `boolean` and
+				timestamps `timestamp` (milliseconds since the Unix epoch). A field holding
+				both integers and floats is a `double` column, and any other mix of types a
+				`string` column.
+
+				Files roll over when a batch fills up: `batch.max_bytes` caps the size of
+				the values before compression, and `batch.timeout_secs` how long events wait
+				to be written. Larger batches make larger row groups and compress better.
+				"""
+		}
+
 		storage_class: {
 			title: "Storage Class"
 			body:  """
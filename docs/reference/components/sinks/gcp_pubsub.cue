@@ -24,6 +24,7 @@ components: sinks: gcp_pubsub: {
 				timeout_secs: 1
 			}
 			compression: enabled: false
+			dead_letter:          true
 			encoding: {
 				enabled: true
 				codec: enabled: false
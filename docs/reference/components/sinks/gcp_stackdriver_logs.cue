@@ -24,6 +24,7 @@ components: sinks: gcp_stackdriver_logs: {
 				timeout_secs: 1
 			}
 			compression: enabled: false
+			dead_letter:          true
 			encoding: {
 				enabled: true
 				codec: enabled: false
@@ -118,7 +119,21 @@ components: sinks: gcp_stackdriver_logs: {
 			required:    true
 			warnings: []
 			type: string: {
-				examples: ["vector-logs"]
+				examples: ["vector-logs", "{{ app }}"]
+				templateable: true
+			}
+		}
+		oversized_entries: {
+			common:      false
+			description: "What to do with log entries over the [size limit][urls.gcp_stackdriver_quotas] of Cloud Logging."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "truncate"
+				enum: {
+					truncate: "Cut the message of the entry short."
+					split:    "Spread the message over several entries, linked by their `split` field."
+				}
 			}
 		}
 		organization_id: {
@@ -159,7 +174,8 @@ components: sinks: gcp_stackdriver_logs: {
 						required:    true
 						warnings: []
 						type: string: {
-							examples: ["global", "gce_instance"]
+							examples: ["global", "gce_instance", "{{ resource_type }}"]
+							templateable: true
 						}
 					}
 					"*": {
@@ -169,7 +185,8 @@ components: sinks: gcp_stackdriver_logs: {
 						warnings: []
 						type: string: {
 							default: null
-							examples: ["vector-123456", "Twilight"]
+							examples: ["vector-123456", "Twilight", "{{ pod }}"]
+							templateable: true
 						}
 					}
 				}
@@ -193,6 +210,28 @@ components: sinks: gcp_stackdriver_logs: {
 	}
 
 	how_it_works: {
+		oversized_entries: {
+			title: "Oversized Entries"
+			body: """
+				Cloud Logging rejects log entries over 256KB. Entries over that
+				limit have their message either truncated, or split over several
+				entries sharing the same `split.uid`, depending on
+				`oversized_entries`. The parts of a split entry are always sent
+				in the same request. Entries still too large without their
+				message are dropped.
+				"""
+		}
+
+		per_event_log_names_and_resources: {
+			title: "Per-Event Log Names and Resources"
+			body: """
+				When `log_id` or any part of `resource` is a template, it is
+				rendered against each event and sent as the `logName` or
+				`resource` of its entry, rather than once per request.
+				Events missing any of the fields referenced are dropped.
+				"""
+		}
+
 		severity_level_mapping: {
 			title: "Severity Level Mapping"
 			body: #"""
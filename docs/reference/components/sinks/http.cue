@@ -26,9 +26,10 @@ components: sinks: http: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
+			dead_letter: true
 			encoding: {
 				enabled: true
 				codec: {
@@ -94,7 +95,7 @@ components: sinks: http: {
 		}}
 		headers: {
 			common:      false
-			description: "Options for custom headers."
+			description: "Options for custom headers. Header values can reference event fields, in which case events are batched separately for each set of rendered headers."
 			required:    false
 			warnings: []
 			type: object: {
@@ -102,17 +103,88 @@ components: sinks: http: {
 					{
 						"Authorization": "${HTTP_TOKEN}"
 						"X-Powered-By":  "Vector"
+						"X-Tenant":      "{{ tenant }}"
 					},
 				]
 				options: {}
 			}
 		}
+		method: {
+			common:      false
+			description: "The HTTP method to send requests with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "post"
+				enum: {
+					post:  "HTTP POST"
+					put:   "HTTP PUT"
+					patch: "HTTP PATCH"
+				}
+			}
+		}
+		oauth2: {
+			common:      false
+			description: "Authenticates requests with a bearer token acquired through the [OAuth2 client credentials grant](\(urls.oauth2_client_credentials)). Tokens are cached until shortly before they expire, and requests rejected with `401 Unauthorized` are retried with a new token. Can not be used with `auth`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					audience: {
+						common:      false
+						description: "The audience to request the token for, as required by some providers."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["https://api.example.com"]
+						}
+					}
+					client_id: {
+						description: "The client ID, sent to the token endpoint with HTTP basic authentication."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${OAUTH2_CLIENT_ID}"]
+						}
+					}
+					client_secret: {
+						description: "The client secret, sent to the token endpoint with HTTP basic authentication."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${OAUTH2_CLIENT_SECRET}"]
+						}
+					}
+					scopes: {
+						common:      false
+						description: "The scopes to request for the token."
+						required:    false
+						warnings: []
+						type: array: {
+							default: []
+							items: type: string: examples: ["logs:write"]
+						}
+					}
+					token_endpoint: {
+						description: "The URI of the token endpoint of the authorization server."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["https://auth.example.com/oauth2/token"]
+						}
+					}
+				}
+			}
+		}
 		uri: {
-			description: "The full URI to make HTTP requests to. This should include the protocol and host, but can also include the port, path, and any other valid part of a URI."
+			description: "The full URI to make HTTP requests to. This should include the protocol and host, but can also include the port, path, and any other valid part of a URI. The path and query can reference event fields, in which case events are batched separately for each rendered URI, and the URI can't contain `%` escapes."
 			required:    true
 			warnings: []
 			type: string: {
-				examples: ["https://10.22.212.22:9000/endpoint"]
+				examples: ["https://10.22.212.22:9000/endpoint", "https://10.22.212.22:9000/{{ tenant }}/events"]
+				templateable: true
 			}
 		}
 	}
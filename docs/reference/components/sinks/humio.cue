@@ -23,7 +23,15 @@ components: sinks: _humio: {
 
 		requirements: []
 		warnings: []
-		notices: []
+		notices: [
+			"""
+				The `hec` API only supports `gzip` compression, `zstd` and
+				`snappy` are only supported with the `structured` API.
+				""",
+			"""
+				`dead_letter` is only supported with the `structured` API.
+				""",
+		]
 	}
 
 	features: {
@@ -40,9 +48,10 @@ components: sinks: _humio: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
+			dead_letter: true
 			encoding: {
 				enabled: true
 				codec: {
@@ -127,3 +136,57 @@ components: sinks: _humio: {
 		}
 	}
 }
+
+components: sinks: humio: {
+	title: "Humio"
+
+	description: sinks._humio.description
+	classes:     sinks._humio.classes
+	features:    sinks._humio.features
+	support:     sinks._humio.support
+
+	configuration: {
+		api: {
+			common:      true
+			description: "The API events are ingested through."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "hec"
+				enum: {
+					hec:        "The [HEC-compatible API](\(urls.humio_hec)), which runs events through a parser."
+					structured: "The [structured ingest API](\(urls.humio_structured_api)), which stores events as they are sent, with their fields as attributes. The `text` encoding sends only their message, as the raw string."
+				}
+			}
+		}
+		host_key: sinks._humio.configuration.host_key
+		index: {
+			common:      false
+			description: "The repository events are sent to. The token must have access to it. If unset, events are sent to the repository of the ingest token."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["logs", "{{ team }}-logs"]
+				templateable: true
+			}
+		}
+		parser: {
+			common:      false
+			description: "The name of the [parser](\(urls.humio_parsers)) Humio ingests events with. Only supported with the `hec` API. If unset, the parser assigned to the ingest token is used."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["json", "{{ app }}"]
+				templateable: true
+			}
+		}
+		token: sinks._humio.configuration.token
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+}
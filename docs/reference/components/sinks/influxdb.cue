@@ -135,3 +135,175 @@ components: sinks: _influxdb: {
 		}
 	}
 }
+
+components: sinks: influxdb: {
+	title:       "InfluxDB"
+	description: "[InfluxDB](\(urls.influxdb)) is an open-source time series database developed by InfluxData. It is written in Go and optimized for fast, high-availability storage and retrieval of time series data in fields such as operations monitoring, application metrics, Internet of Things sensor data, and real-time analytics."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["InfluxData"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    1049000
+				max_events:   null
+				timeout_secs: 1
+			}
+			compression: {
+				enabled: true
+				default: "none"
+				algorithms: ["none", "gzip"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: enabled: false
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: sinks._influxdb.features.send.tls
+			to:  sinks._influxdb.features.send.to
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"""
+				InfluxDB 2 or InfluxDB Cloud is required, as only the v2 write API is
+				spoken. Use the `influxdb_logs` or `influxdb_metrics` sinks for InfluxDB 1.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		bucket:   sinks._influxdb.configuration.bucket
+		endpoint: sinks._influxdb.configuration.endpoint
+		org:      sinks._influxdb.configuration.org
+		token:    sinks._influxdb.configuration.token
+		default_namespace: {
+			common: true
+			description: """
+				Used as a namespace for metrics that don't have it, and for the
+				measurement of logs. A namespace will be prefixed to the name.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["service"]
+			}
+		}
+		logs: {
+			common:      true
+			description: "How the fields of log events are mapped to points."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					fields: {
+						common:      true
+						description: "The fields written as point fields. When empty, every field that isn't a tag is written."
+						required:    false
+						warnings: []
+						type: array: {
+							default: []
+							items: type: string: examples: ["message", "parent.child_field"]
+						}
+					}
+					measurement: {
+						common:      true
+						description: "The measurement logs are written to."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "vector"
+							examples: ["logs"]
+						}
+					}
+					tags: {
+						common:      true
+						description: "The fields written as tags, in addition to the host and source type. Note: If the set of tag values has high cardinality this also increase cardinality in InfluxDB."
+						required:    false
+						warnings: []
+						type: array: {
+							default: []
+							items: type: string: examples: ["service", "parent.child_field"]
+						}
+					}
+				}
+			}
+		}
+		quantiles: {
+			common:      false
+			description: "Quantiles to use for aggregating [distribution][docs.data-model.metric#distribution] metrics into a summary."
+			required:    false
+			warnings: []
+			type: array: {
+				default: [0.5, 0.75, 0.9, 0.95, 0.99]
+				items: type: float: examples: [0.5, 0.75, 0.9, 0.95, 0.99]
+			}
+		}
+		tags: {
+			common:      false
+			description: "A map of additional tags, in the key/value pair format, attached to every point."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{region: "us-west-1"}]
+				options: {}
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		mapping: {
+			title: "Mapping Log Fields"
+			body: """
+				Every log event is written as one point of the `logs.measurement`
+				measurement, with a `metric_type=logs` tag. The host and source type
+				fields, plus the fields listed in `logs.tags`, are written as tags. The
+				fields listed in `logs.fields`, or every other field when it is empty,
+				are written as fields. The event timestamp becomes the point timestamp.
+				Events without any fields are dropped.
+				"""
+		}
+	}
+}
@@ -53,11 +53,46 @@ components: sinks: kafka: {
 
 	configuration: {
 		bootstrap_servers: components._kafka.configuration.bootstrap_servers
+		headers: {
+			common:      false
+			description: "Headers to set on each message, by name. Headers whose template fields don't exist on the log are left out."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"trace-id": "{{ trace_id }}", "source": "vector"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The value of the header."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["{{ trace_id }}", "vector"]
+							templateable: true
+						}
+					}
+				}
+			}
+		}
+		key: {
+			common:      false
+			description: "The message key. If unspecified, the key will be randomly generated. If a field does not exist on the log, a blank value will be used. Conflicts with `key_field`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ user_id }}", "{{ tenant }}-{{ user_id }}"]
+				templateable: true
+			}
+		}
 		key_field: {
-			description: "The log field name to use for the topic key. If unspecified, the key will be randomly generated. If the field does not exist on the log, a blank value will be used."
-			required:    true
+			common:      true
+			description: "The log field name to use for the topic key. If unspecified, the key will be randomly generated. If the field does not exist on the log, a blank value will be used. Conflicts with `key`."
+			required:    false
 			warnings: []
 			type: string: {
+				default: null
 				examples: ["user_id"]
 			}
 		}
@@ -128,6 +163,7 @@ components: sinks: kafka: {
 			warnings: []
 			type: string: {
 				examples: ["topic-1234", "logs-{{unit}}-%Y-%m-%d"]
+				templateable: true
 			}
 		}
 	}
@@ -137,5 +173,17 @@ components: sinks: kafka: {
 		metrics: null
 	}
 
-	how_it_works: components._kafka.how_it_works
+	how_it_works: components._kafka.how_it_works & {
+		producer_tuning: {
+			title: "Producer Tuning"
+			body: """
+				Any librdkafka producer property can be set through
+				`librdkafka_options`, for example `enable.idempotence`,
+				`compression.codec` or `linger.ms`. Setting a property
+				that is also set by another option, such as `linger.ms`
+				together with `batch.timeout_secs`, or `compression.codec`
+				together with `compression`, is a configuration error.
+				"""
+		}
+	}
 }
@@ -24,6 +24,7 @@ components: sinks: loki: {
 				timeout_secs: 1
 			}
 			compression: enabled: false
+			dead_letter:          true
 			encoding: {
 				enabled: true
 				codec: {
@@ -34,7 +35,7 @@ components: sinks: loki: {
 			}
 			request: {
 				enabled:                    true
-				concurrency:                5
+				concurrency:                1
 				rate_limit_duration_secs:   1
 				rate_limit_num:             5
 				retry_initial_backoff_secs: 1
@@ -71,8 +72,19 @@ components: sinks: loki: {
 			password_example: "${LOKI_PASSWORD}"
 			username_example: "${LOKI_USERNAME}"
 		}}
+		cardinality_limit: {
+			common:      false
+			description: "The maximum number of streams, that is distinct sets of label values per tenant, the sink will send to. Once reached, events that would start a new stream are sent with only the labels that aren't templated. By default the number of streams is unbounded."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [1000]
+				unit: null
+			}
+		}
 		labels: {
-			description: "A set of labels that will be attached to each batch of events. These values are also templateable to allow events to provide dynamic label values.Note: If the set of label values has high cardinality this can cause drastic performance issues with Loki. To ensure this does not happen one should try to reduce the amount of unique label values."
+			description: "A set of labels that will be attached to each batch of events. These values are also templateable to allow events to provide dynamic label values. Label names must match `[a-zA-Z_][a-zA-Z0-9_]*`. Note: If the set of label values has high cardinality this can cause drastic performance issues with Loki. To ensure this does not happen one should try to reduce the amount of unique label values, or set `cardinality_limit`."
 			required:    true
 			warnings: []
 			type: object: {
@@ -97,6 +109,20 @@ components: sinks: loki: {
 				}
 			}
 		}
+		out_of_order_action: {
+			common:      false
+			description: "What to do with events older than the newest event already sent to their stream."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "drop"
+				enum: {
+					drop:              "Drop the event."
+					rewrite_timestamp: "Send the event with the timestamp of the newest event of its stream."
+					accept:            "Send the event as is. Requires Loki 2.4 or later, which accepts out-of-order events within its ingestion window. Requests are also sent concurrently."
+				}
+			}
+		}
 		remove_label_fields: {
 			common:      false
 			description: "If this is set to `true` then when labels are collected from events those fields will also get removed from the event."
@@ -118,7 +144,8 @@ components: sinks: loki: {
 			warnings: []
 			type: string: {
 				default: null
-				examples: ["some_tenant_id"]
+				examples: ["some_tenant_id", "{{ event_field }}"]
+				templateable: true
 			}
 		}
 	}
@@ -132,8 +159,8 @@ components: sinks: loki: {
 		decentralized_deployments: {
 			title: "Decentralized Deployments"
 			body: """
-				Loki currently does not support out-of-order inserts. If
-				Vector is deployed in a decentralized setup then there is
+				Loki versions before 2.4 do not support out-of-order inserts.
+				If Vector is deployed in a decentralized setup then there is
 				the possibility that logs might get rejected due to data
 				races between Vector instances. To avoid this we suggest
 				either assigning each Vector instance with a unique label
@@ -150,6 +177,21 @@ components: sinks: loki: {
 				accepted by Loki. If no timestamp is supplied with events
 				then the Loki sink will supply its own monotonically
 				increasing timestamp.
+
+				Across batches, the sink remembers the newest timestamp
+				sent to each stream and applies the
+				[`out_of_order_action`](#out_of_order_action) to older
+				events. Unless that is `accept`, requests are sent one at
+				a time by default so that they reach Loki in order.
+				"""
+		}
+
+		rate_limits: {
+			title: "Rate Limits"
+			body: """
+				Requests rejected by Loki with a `429 Too Many Requests`
+				status are retried with exponential backoff, as configured
+				by the `request` options.
 				"""
 		}
 	}
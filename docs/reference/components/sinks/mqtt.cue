@@ -0,0 +1,216 @@
+package metadata
+
+components: sinks: mqtt: {
+	title:       "MQTT"
+	description: "[MQTT](\(urls.mqtt)) is a lightweight publish/subscribe messaging protocol, widely used to connect IoT devices through a broker."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      false
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: enabled:     false
+			to: {
+				service: {
+					name:     "MQTT"
+					thing:    "an \(name) broker"
+					url:      urls.mqtt
+					versions: "3.1.1"
+				}
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		clean_session: {
+			common:      false
+			description: "Start a clean session, instead of resuming the broker's session for `client_id`."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		client_id: {
+			common:      false
+			description: "The client id to connect with. It must be unique among the clients of the broker."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "vector"
+				examples: ["vector-gateway-1"]
+			}
+		}
+		host: {
+			description: "The host name or IP address of the broker."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["127.0.0.1", "mqtt.example.com"]
+			}
+		}
+		keep_alive: {
+			common:      false
+			description: "How often to ping the broker while the connection is idle, at least 5 seconds."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		password: {
+			common:      false
+			description: "The password to authenticate with. Requires `user`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["${MQTT_PASSWORD}"]
+			}
+		}
+		port: {
+			common:      true
+			description: "The port of the broker."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1883
+				unit:    null
+			}
+		}
+		qos: {
+			common:      true
+			description: "The quality of service to publish with: `0` delivers messages at most once, `1` at least once. See [Quality Of Service](#quality-of-service)."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 0
+				examples: [0, 1]
+				unit: null
+			}
+		}
+		retain: {
+			common:      false
+			description: "Have the broker retain the last message of each topic, and deliver it to new subscribers."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		tls: {
+			common:      false
+			description: "Options for connecting to the broker with TLS."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					ca_file: {
+						description: "Absolute path to the CA certificate file that signed the broker's certificate, in PEM format."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["/path/to/certificate_authority.crt"]
+						}
+					}
+					crt_file: {
+						common:      true
+						description: "Absolute path to a client certificate file, in PEM format. Requires `key_file`."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["/path/to/host_certificate.crt"]
+						}
+					}
+					key_file: {
+						common:      true
+						description: "Absolute path to the private key of the client certificate, as a PKCS#1 RSA key or a PKCS#8 key in PEM format."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["/path/to/host_certificate.key"]
+						}
+					}
+				}
+			}
+		}
+		topic: {
+			description: "The topic to publish events to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector", "devices/{{ device_id }}/telemetry"]
+				templateable: true
+			}
+		}
+		user: {
+			common:      false
+			description: "The user to authenticate with. Requires `password`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["vector"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		qos: {
+			title: "Quality Of Service"
+			body: """
+				With `qos = 1` the sink only acknowledges events, to disk
+				buffers for example, once the broker has acknowledged them.
+				Messages not acknowledged when the connection drops are
+				published again after reconnecting, so subscribers may see
+				duplicates. With the default `qos = 0` events are acknowledged
+				once written to the connection, and are lost if it drops.
+				"""
+		}
+	}
+}
@@ -26,7 +26,13 @@ components: sinks: nats: {
 				}
 			}
 			request: enabled: false
-			tls: enabled:     false
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
 			to: {
 				service: {
 					name:     "NATS"
@@ -39,7 +45,7 @@ components: sinks: nats: {
 					socket: {
 						direction: "outgoing"
 						protocols: ["tcp"]
-						ssl: "disabled"
+						ssl: "optional"
 					}
 				}
 			}
@@ -58,10 +64,74 @@ components: sinks: nats: {
 
 		requirements: []
 		warnings: []
-		notices: []
+		notices: [
+			"""
+				The NATS client always verifies the server's certificate and only
+				reads unencrypted private keys, so setting `tls.verify_certificate`,
+				`tls.verify_hostname` or `tls.key_pass` fails the sink.
+				""",
+		]
 	}
 
 	configuration: {
+		auth: {
+			common:      false
+			description: "Options for authenticating to the NATS server."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					strategy: {
+						description: "The authentication strategy."
+						required:    true
+						warnings: []
+						type: string: {
+							enum: {
+								credentials_file: "Authenticate with a user JWT and NKey seed, read from a credentials file as written by `nsc`."
+								user_password:    "Authenticate with a user name and password."
+								token:            "Authenticate with a token."
+							}
+						}
+					}
+					path: {
+						description: "Path to the credentials file."
+						required:    true
+						relevant_when: "strategy = \"credentials_file\""
+						warnings: []
+						type: string: examples: ["/etc/nats/vector.creds"]
+					}
+					user: {
+						description: "The user name."
+						required:    true
+						relevant_when: "strategy = \"user_password\""
+						warnings: []
+						type: string: examples: ["${NATS_USER}", "vector"]
+					}
+					password: {
+						description: "The password."
+						required:    true
+						relevant_when: "strategy = \"user_password\""
+						warnings: []
+						type: string: examples: ["${NATS_PASSWORD}"]
+					}
+					token: {
+						description: "The token."
+						required:    true
+						relevant_when: "strategy = \"token\""
+						warnings: []
+						type: string: examples: ["${NATS_TOKEN}"]
+					}
+				}
+			}
+		}
+		jetstream: {
+			common:      false
+			description: "Publish to [JetStream](\(urls.nats_jetstream)), acknowledging each event only once its stream has acknowledged the message. Subjects must be bound to a stream."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		url: {
 			description: "The NATS URL to connect to. The url _must_ take the form of `nats://server:port`."
 			groups: ["tcp"]
@@ -0,0 +1,162 @@
+package metadata
+
+components: sinks: new_relic: {
+	title:       "New Relic"
+	description: "[New Relic](\(urls.new_relic)) is a San Francisco, California-based technology company which develops cloud-based software to help website and application owners track the performances of their services."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["New Relic"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    1000000
+				max_events:   null
+				timeout_secs: 1
+			}
+			compression: {
+				enabled: true
+				default: "none"
+				algorithms: ["none", "gzip", "zstd", "snappy"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: enabled: false
+			request: {
+				enabled:                    true
+				concurrency:                100
+				rate_limit_duration_secs:   1
+				rate_limit_num:             100
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: enabled: false
+			to: {
+				service: {
+					name:     "New Relic"
+					thing:    "a \(name) account"
+					url:      urls.new_relic
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "New Relic Log and Metric APIs"
+							url:   urls.new_relic_metric_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		attributes: {
+			common:      false
+			description: "Renames fields of logs and tags of metrics to New Relic attributes. Nested fields are named by their path, such as `kubernetes.pod_name`. Fields and tags that aren't listed keep their names."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{host: "hostname", "kubernetes.pod_name": "pod"}]
+				options: {}
+			}
+		}
+		common_attributes: {
+			common:      false
+			description: "Attributes added to every log and metric, sent once per payload."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{environment: "production", service: "api"}]
+				options: {}
+			}
+		}
+		license_key: {
+			description: "Your New Relic [license key](\(urls.new_relic_license_key))."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["xxxx", "${NEW_RELIC_LICENSE_KEY}"]
+			}
+		}
+		region: {
+			common:      true
+			description: "The region of your New Relic account."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "us"
+				enum: {
+					us: "The US region, `log-api.newrelic.com` and `metric-api.newrelic.com`."
+					eu: "The EU region, `log-api.eu.newrelic.com` and `metric-api.eu.newrelic.com`."
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    false
+			set:          true
+			summary:      false
+		}
+	}
+
+	how_it_works: {
+		apis: {
+			title: "Log and Metric APIs"
+			body: """
+				Logs are sent to the [Log API](\(urls.new_relic_log_api)), with
+				their fields other than the message and timestamp as attributes.
+				Metrics are sent to the [Metric API](\(urls.new_relic_metric_api)),
+				with their tags as attributes: incremental counters as counts,
+				absolute counters and gauges as gauges, sets as gauges of their
+				size, and distributions as summaries. Counts and summaries cover
+				the batch timeout, `batch.timeout_secs`.
+				"""
+		}
+
+		payload_limits: {
+			title: "Payload Limits"
+			body: """
+				Both APIs accept payloads of up to 1MB. Batches larger than that
+				are split across as many payloads as needed, and events that
+				don't fit in a payload on their own are dropped. When any of the
+				payloads of a batch fails with a retriable error, the whole batch
+				is retried.
+				"""
+		}
+	}
+}
@@ -0,0 +1,231 @@
+package metadata
+
+components: sinks: opentelemetry: {
+	title:       "OpenTelemetry"
+	description: "Exports log and metric events to an [OpenTelemetry](\(urls.opentelemetry)) collector or backend with the [OpenTelemetry Protocol (OTLP)](\(urls.otlp))."
+
+	classes: {
+		commonly_used: false
+		service_providers: []
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    1048576
+				max_events:   null
+				timeout_secs: 1
+			}
+			compression: {
+				enabled: true
+				default: "none"
+				algorithms: ["none", "gzip", "zstd", "snappy"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: enabled: false
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: {
+					name:     "OpenTelemetry"
+					thing:    "an \(name) collector"
+					url:      urls.opentelemetry_collector
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: [
+			"""
+				Vector has no trace events, so this sink exports logs and
+				metrics only.
+				""",
+		]
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${OTLP_PASSWORD}"
+			username_example: "${OTLP_USERNAME}"
+		}}
+		buckets: {
+			common:      false
+			description: "Buckets to use for aggregating [distribution][docs.data-model.metric#distribution] metrics into histograms."
+			required:    false
+			warnings: []
+			type: array: {
+				default: [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+				items: type: float: examples: [0.005, 0.01]
+			}
+		}
+		default_namespace: {
+			common:      true
+			description: "Used as a namespace for metrics that don't have it. A namespace will be prefixed to a metric's name, separated by a `.`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["service"]
+			}
+		}
+		endpoint: {
+			description: "The base URL of the collector. The path of each signal, such as `/v1/logs`, is appended to it."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["http://localhost:4318", "https://otlp.example.com:4317"]
+			}
+		}
+		headers: {
+			common:      false
+			description: "Extra headers to send with every request, such as the API key of a hosted backend."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						"x-api-key": "${OTLP_API_KEY}"
+					},
+				]
+				options: {}
+			}
+		}
+		protocol: {
+			common:      true
+			description: "The OTLP transport to use."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "http"
+				enum: {
+					grpc: "OTLP/gRPC, usually served on port 4317."
+					http: "OTLP/HTTP with binary protobuf payloads, usually served on port 4318."
+				}
+			}
+		}
+		quantiles: {
+			common:      false
+			description: "Quantiles to use for aggregating [distribution][docs.data-model.metric#distribution] metrics into a summary."
+			required:    false
+			warnings: []
+			type: array: {
+				default: [0.5, 0.75, 0.9, 0.95, 0.99]
+				items: type: float: examples: [0.5, 0.75, 0.9, 0.95, 0.99]
+			}
+		}
+		resource: {
+			common:      true
+			description: "The attributes of the [resource](\(urls.otlp)) events are exported under, such as `service.name`. Values are templates rendered against the fields of logs and the tags of metrics; attributes whose template can't be rendered are left out."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						"service.name":           "vector"
+						"host.name":              "{{ host }}"
+						"deployment.environment": "production"
+					},
+				]
+				options: {}
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		logs: {
+			title: "Logs"
+			body: """
+				The timestamp of a log event becomes the time of its log
+				record, the message becomes its body, and every other field
+				becomes an attribute. Nested maps and arrays are kept as
+				such, and timestamps are written as RFC 3339 strings.
+				"""
+		}
+
+		metrics: {
+			title: "Metrics"
+			body: """
+				Counters are exported as monotonic sums, delta for incremental
+				counters and cumulative for absolute ones. Gauges and sets are
+				exported as gauges, the latter with the number of values in
+				the set. Distributions become histograms or summaries, using
+				`buckets` or `quantiles`, and the tags of a metric become the
+				attributes of its data point.
+				"""
+		}
+
+		resources: {
+			title: "Resources"
+			body: """
+				Each batch is sent as a single export request, with events
+				grouped by the resource rendered from the `resource` option.
+				Logs and metrics are batched separately.
+				"""
+		}
+
+		retries: {
+			title: "Retries"
+			body: """
+				Requests are retried on transport errors and on the statuses
+				OTLP marks as retryable: HTTP 429, 502, 503 and 504, and the
+				gRPC codes `CANCELLED`, `DEADLINE_EXCEEDED`, `RESOURCE_EXHAUSTED`,
+				`ABORTED`, `OUT_OF_RANGE`, `UNAVAILABLE` and `DATA_LOSS`.
+				Any other failure drops the batch.
+				"""
+		}
+	}
+}
@@ -0,0 +1,222 @@
+package metadata
+
+components: sinks: pagerduty: {
+	title:       "PagerDuty"
+	description: "[PagerDuty](\(urls.pagerduty)) is an incident response platform that notifies on-call responders based on alerts from monitoring tools."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["PagerDuty"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			compression: enabled: false
+			dead_letter:          true
+			encoding: enabled:    false
+			request: {
+				enabled:                    true
+				concurrency:                1
+				rate_limit_duration_secs:   60
+				rate_limit_num:             120
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: enabled: false
+			to: {
+				service: {
+					name:     "PagerDuty"
+					thing:    "a \(name) service"
+					url:      urls.pagerduty
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "PagerDuty Events API v2"
+							url:   urls.pagerduty_events_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		action_field: {
+			common:      true
+			description: "The field holding the action of each event: `trigger`, `acknowledge` or `resolve`, in any case. Events without the field use `default_action`, and events with any other value are dropped."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["alert.state"]
+			}
+		}
+		class: {
+			common:      false
+			description: "The class or type of the event."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ check }}"]
+				templateable: true
+			}
+		}
+		component: {
+			common:      false
+			description: "The part of the source that is responsible for the event."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["postgres", "{{ service }}"]
+				templateable: true
+			}
+		}
+		dedup_key: {
+			common:      true
+			description: "The key identifying the alert. Triggering with a key that has an open alert updates it, and it is required to acknowledge or resolve alerts. PagerDuty generates a key when it is unset."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ host }}-{{ check }}"]
+				templateable: true
+			}
+		}
+		default_action: {
+			common:      false
+			description: "The action of events without an `action_field`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "trigger"
+				enum: {
+					trigger:     "Open an alert, or update the open alert with the same `dedup_key`."
+					acknowledge: "Acknowledge the alert with the `dedup_key`."
+					resolve:     "Resolve the alert with the `dedup_key`."
+				}
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The Events API endpoint, such as `https://events.eu.pagerduty.com/v2/enqueue` for accounts in the EU service region."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "https://events.pagerduty.com/v2/enqueue"
+			}
+		}
+		group: {
+			common:      false
+			description: "The logical grouping of the components of a service."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["prod-datapipe", "{{ cluster }}"]
+				templateable: true
+			}
+		}
+		include_custom_details: {
+			common:      false
+			description: "Sends all the fields of the event as the `custom_details` of triggered alerts."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		routing_key: {
+			description: "The integration key of the service or ruleset events are sent to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["${PAGERDUTY_ROUTING_KEY}"]
+			}
+		}
+		severity: {
+			common:      true
+			description: "The severity of triggered alerts: `critical`, `error`, `warning` or `info`. Any other value is replaced with `error`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "error"
+				examples: ["critical", "{{ level }}"]
+				templateable: true
+			}
+		}
+		source: {
+			common:      false
+			description: "The affected system of triggered alerts. Defaults to the host field of the event, or the hostname of Vector."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ host }}", "db-1.example.com"]
+				templateable: true
+			}
+		}
+		summary: {
+			description: "The summary of triggered alerts, shown in notifications."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["{{ message }}", "Disk usage above 90% on {{ host }}"]
+				templateable: true
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		rate_limits: {
+			title: "Rate Limits"
+			body: """
+				The Events API [limits](\(urls.pagerduty_rate_limits)) how many
+				events each routing key accepts, so events are sent one at a time
+				and at most 120 a minute by default. Throttled (`429`) and failed
+				(`5xx`) requests are retried, while events the API rejects as
+				invalid (`400`) are dropped.
+				"""
+		}
+
+		ordering: {
+			title: "Ordering"
+			body: """
+				With the default `request.concurrency` of 1, events are sent in
+				the order they arrive, so an alert is never resolved before it is
+				triggered. Raising it trades that guarantee for throughput.
+				"""
+		}
+	}
+}
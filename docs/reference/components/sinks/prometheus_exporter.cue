@@ -75,6 +75,52 @@ components: sinks: prometheus_exporter: {
 				examples: ["0.0.0.0:\(_port)"]
 			}
 		}
+		auth: {
+			common:      false
+			description: "Requires scrapes to authenticate with these credentials. Requests without them are rejected with a 401 status."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					password: {
+						description: "The basic authentication password."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${PROMETHEUS_PASSWORD}", "password"]
+						}
+					}
+					strategy: {
+						description: "The authentication strategy to use."
+						required:    true
+						warnings: []
+						type: string: {
+							enum: {
+								basic:  "The [basic authentication strategy](\(urls.basic_auth))."
+								bearer: "Authentication with a bearer token, such as an OAuth2 access token."
+							}
+						}
+					}
+					token: {
+						description: "The bearer token."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${PROMETHEUS_TOKEN}"]
+						}
+					}
+					user: {
+						description: "The basic authentication user name."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${PROMETHEUS_USERNAME}", "username"]
+						}
+					}
+				}
+			}
+		}
 		buckets: {
 			common:      false
 			description: "Default buckets to use for aggregating [distribution][docs.data-model.metric#distribution] metrics into histograms."
@@ -85,6 +131,17 @@ components: sinks: prometheus_exporter: {
 				items: type: float: examples: [0.005, 0.01]
 			}
 		}
+		expire_metrics_secs: {
+			common:      false
+			description: "Drops series that haven't been updated for this long, so that they are no longer exposed. When unset, series are kept until Vector restarts."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [300]
+				unit: "seconds"
+			}
+		}
 		flush_period_secs: {
 			common:      false
 			description: "Time interval between [set][docs.data-model.metric#set] values are reset."
@@ -205,16 +262,16 @@ components: sinks: prometheus_exporter: {
 				# TYPE \(_name) histogram
 				\(_name)_bucket{le="0.005"} 0
 				\(_name)_bucket{le="0.01"} 1
-				\(_name)_bucket{le="0.025"} 0
-				\(_name)_bucket{le="0.05"} 1
-				\(_name)_bucket{le="0.1"} 0
-				\(_name)_bucket{le="0.25"} 0
-				\(_name)_bucket{le="0.5"} 0
-				\(_name)_bucket{le="1.0"} 0
-				\(_name)_bucket{le="2.5"} 0
-				\(_name)_bucket{le="5.0"} 0
-				\(_name)_bucket{le="10.0"} 0
-				\(_name)_bucket{le="+Inf"} 0
+				\(_name)_bucket{le="0.025"} 1
+				\(_name)_bucket{le="0.05"} 2
+				\(_name)_bucket{le="0.1"} 2
+				\(_name)_bucket{le="0.25"} 2
+				\(_name)_bucket{le="0.5"} 2
+				\(_name)_bucket{le="1"} 2
+				\(_name)_bucket{le="2.5"} 2
+				\(_name)_bucket{le="5"} 2
+				\(_name)_bucket{le="10"} 2
+				\(_name)_bucket{le="+Inf"} 2
 				\(_name)_sum 0.789
 				\(_name)_count 2
 				"""
@@ -334,6 +391,18 @@ components: sinks: prometheus_exporter: {
 			]
 		}
 
+		distributions: {
+			title: "Distributions"
+			body: """
+				[Distribution][docs.data-model.metric#distribution] metrics with the
+				`histogram` statistic are counted into the configured `buckets` as they
+				arrive, so each series keeps a fixed amount of state and is exposed as a
+				cumulative Prometheus histogram. Distributions with the `summary`
+				statistic keep their samples to compute the `quantiles`, and should be
+				paired with `expire_metrics_secs` to bound their memory use.
+				"""
+		}
+
 		memory_usage: {
 			title: "Memory Usage"
 			body: """
@@ -343,6 +412,9 @@ components: sinks: prometheus_exporter: {
 				downside is that data will be lost if Vector is restarted. This is by design of
 				Prometheus' pull model approach, but is worth noting if restart Vector
 				frequently.
+
+				Series are kept until Vector restarts unless `expire_metrics_secs` is set,
+				which matters when metric tags change over time.
 				"""
 		}
 	}
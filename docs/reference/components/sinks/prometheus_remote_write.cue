@@ -89,6 +89,84 @@ components: sinks: prometheus_remote_write: {
 	}
 
 	configuration: {
+		auth: {
+			common:      false
+			description: "Options for the authentication strategy."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					assume_role: {
+						common:      false
+						description: "The ARN of an [IAM role](\(urls.aws_iam_role)) to assume at startup."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["arn:aws:iam::123456789098:role/my_role"]
+						}
+					}
+					password: {
+						description: "The basic authentication password."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${PROMETHEUS_PASSWORD}", "password"]
+						}
+					}
+					strategy: {
+						description: "The authentication strategy to use."
+						required:    true
+						warnings: []
+						type: string: {
+							enum: {
+								aws:    "Signs requests with SigV4, for [Amazon Managed Service for Prometheus](\(urls.aws_amp))."
+								basic:  "The [basic authentication strategy](\(urls.basic_auth))."
+								bearer: "Authentication with a bearer token, such as an OAuth2 access token."
+							}
+						}
+					}
+					token: {
+						description: "The bearer token."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${PROMETHEUS_TOKEN}"]
+						}
+					}
+					user: {
+						description: "The basic authentication user name."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${PROMETHEUS_USERNAME}", "username"]
+						}
+					}
+				}
+			}
+		}
+		aws: {
+			common:      false
+			description: "Options for the AWS connections, used with the `aws` authentication strategy."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					region: {
+						common:      true
+						description: "The [AWS region][urls.aws_regions] of the workspace. This defaults to the region named in the endpoint parameter, or the value of the `$AWS_REGION` or `$AWS_DEFAULT_REGION` environment variables if that cannot be determined, or \"us-east-1\"."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["us-east-1"]
+						}
+					}
+				}
+			}
+		}
 		endpoint: {
 			description: "The endpoint URL to send data to."
 			required:    true
@@ -131,6 +209,17 @@ components: sinks: prometheus_remote_write: {
 				items: type: float: examples: [0.5, 0.75, 0.9, 0.95, 0.99]
 			}
 		}
+		stale_after_secs: {
+			common:      false
+			description: "Ends a series with a [staleness marker](\(urls.prometheus_staleness)) once it has received no samples for this long. When unset, series are never marked stale by Vector."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [300]
+				unit: "seconds"
+			}
+		}
 	}
 
 	input: {
@@ -144,4 +233,38 @@ components: sinks: prometheus_remote_write: {
 			summary:      true
 		}
 	}
+
+	how_it_works: {
+		aws_authentication: {
+			title: "AWS Authentication"
+			body: """
+				With the `aws` authentication strategy, every request is signed
+				with SigV4 for [Amazon Managed Service for Prometheus](\(urls.aws_amp)).
+				Credentials are looked up the same way as for the other AWS
+				components, and an IAM role can be assumed with `auth.assume_role`.
+				"""
+		}
+
+		retries: {
+			title: "Retries"
+			body: """
+				Following the [remote write specification](\(urls.prometheus_remote_write_spec)),
+				requests failing with a 5xx status or a 429 status, or that could
+				not be sent at all, are retried. Any other 4xx status means the
+				receiver will never accept the data, so the request is dropped.
+				"""
+		}
+
+		staleness: {
+			title: "Staleness Markers"
+			body: """
+				[Staleness markers](\(urls.prometheus_staleness)), the special NaN
+				value Prometheus writes when a series disappears, are forwarded
+				unchanged. With `stale_after_secs` set, Vector also writes one for
+				every series that stops receiving samples, so that queries stop
+				returning it right away instead of after the lookback period.
+				Series are only checked for staleness when a batch is sent.
+				"""
+		}
+	}
 }
@@ -22,11 +22,17 @@ components: sinks: pulsar: {
 				codec: {
 					enabled: true
 					default: "text"
-					enum: ["text", "json"]
+					enum: ["text", "json", "avro"]
 				}
 			}
 			request: enabled: false
-			tls: enabled:     false
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
 			to: {
 				service: {
 					name:     "Apache Pulsar"
@@ -43,7 +49,7 @@ components: sinks: pulsar: {
 						}
 						direction: "outgoing"
 						protocols: ["http"]
-						ssl: "disabled"
+						ssl: "optional"
 					}
 				}
 			}
@@ -62,7 +68,14 @@ components: sinks: pulsar: {
 
 		requirements: []
 		warnings: []
-		notices: []
+		notices: [
+			"""
+				The Pulsar client only trusts additional certificate authorities,
+				set with `tls.ca_file`. Client certificates aren't supported yet,
+				and setting `tls.crt_file`, `tls.key_file` or `tls.key_pass` fails
+				the sink.
+				""",
+		]
 	}
 
 	configuration: {
@@ -98,10 +111,43 @@ components: sinks: pulsar: {
 			}
 		}
 		endpoint: {
-			description: "Endpoint to which the pulsar client should connect to."
+			description: "Endpoint to which the pulsar client should connect to. Use the `pulsar+ssl` scheme to connect with TLS."
 			required:    true
 			type: string: {
-				examples: ["pulsar://127.0.0.1:6650"]
+				examples: ["pulsar://127.0.0.1:6650", "pulsar+ssl://pulsar.example.com:6651"]
+			}
+		}
+		key: {
+			common:      true
+			description: "The partition key of each message. Messages with the same key are written to the same partition, and delivered to the same consumer of a `Key_Shared` subscription. If a field does not exist on the log, the message is sent without a key."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ user_id }}"]
+				templateable: true
+			}
+		}
+		properties: {
+			common:      false
+			description: "Properties to set on each message, by name. Properties whose template fields don't exist on the log are left out."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"trace_id": "{{ trace_id }}", "source": "vector"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The value of the property."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["{{ trace_id }}", "vector"]
+							templateable: true
+						}
+					}
+				}
 			}
 		}
 		topic: {
@@ -109,7 +155,8 @@ components: sinks: pulsar: {
 			required:    true
 			warnings: []
 			type: string: {
-				examples: ["topic-1234"]
+				examples: ["topic-1234", "logs-{{ service }}"]
+				templateable: true
 			}
 		}
 	}
@@ -118,4 +165,17 @@ components: sinks: pulsar: {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		schemas: {
+			title: "Schemas"
+			body: """
+				With the `avro` or `json` codec, `encoding.schema` is
+				attached to the producer of each topic as the Pulsar schema
+				of that type, so that brokers and consumers can validate
+				and decode messages. The schema is an Avro schema definition
+				for both codecs, as Pulsar expects.
+				"""
+		}
+	}
 }
@@ -0,0 +1,261 @@
+package metadata
+
+components: sinks: sftp: {
+	title:       "SFTP"
+	description: "Uploads batches of events as files to a directory on an [SFTP](\(urls.sftp)) server, a common way of delivering data to partners who collect it from their own servers."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       true
+				max_bytes:    10485760
+				max_events:   null
+				timeout_secs: 300
+			}
+			compression: {
+				enabled: true
+				default: "none"
+				algorithms: ["none", "gzip", "zstd", "snappy"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: "text"
+					enum: ["ndjson", "text"]
+				}
+			}
+			request: {
+				enabled:                    true
+				concurrency:                1
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               300
+			}
+			tls: enabled: false
+			to: {
+				service: {
+					name:     "SFTP server"
+					thing:    "an \(name)"
+					url:      urls.sftp
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "SFTP"
+							url:   urls.sftp
+						}
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "disabled"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		address: {
+			description: "The host and port of the server. The port defaults to 22."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["sftp.example.com", "10.0.0.5:2222"]
+			}
+		}
+		auth: {
+			description: "How to authenticate with the server."
+			required:    true
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					passphrase: {
+						common:      false
+						description: "The passphrase of an encrypted private key."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["${SFTP_KEY_PASSPHRASE}"]
+						}
+					}
+					password: {
+						description: "The password of `username`."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${SFTP_PASSWORD}"]
+						}
+					}
+					private_key_path: {
+						description: "The private key authorized for `username`, in OpenSSH or PEM format."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["/etc/vector/id_ed25519"]
+						}
+					}
+					strategy: {
+						description: "The authentication strategy to use."
+						required:    true
+						warnings: []
+						type: string: {
+							enum: {
+								key:      "Public key authentication with `private_key_path`."
+								password: "Password authentication."
+							}
+						}
+					}
+				}
+			}
+		}
+		create_directories: {
+			common:      false
+			description: "Whether the directories of `path` are created when they don't exist."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		filename_append_uuid: {
+			category:    "File Naming"
+			common:      false
+			description: "Whether or not to append a UUID v4 token to the end of the file. This ensures there are no name collisions high volume use cases."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		filename_extension: {
+			category:    "File Naming"
+			common:      false
+			description: "The filename extension to use in the file name."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "log"
+			}
+		}
+		filename_time_format: {
+			category:    "File Naming"
+			common:      false
+			description: "The format of the file name. [`strftime` specifiers](\(urls.strptime_specifiers)) are supported, and are rendered with the time the batch is sent. If empty, the name is only the UUID."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "%s"
+			}
+		}
+		known_hosts: {
+			common:      false
+			description: "The [known hosts file](\(urls.openssh_known_hosts)) the host key of the server is verified against."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "~/.ssh/known_hosts"
+				examples: ["/etc/vector/known_hosts"]
+			}
+		}
+		path: {
+			description: "The directory files are uploaded to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["/upload/%F", "/partners/{{ partner }}/"]
+				templateable: true
+			}
+		}
+		temp_suffix: {
+			common:      false
+			description: "Appended to the name of each file while it's uploaded."
+			required:    false
+			warnings: []
+			type: string: {
+				default: ".part"
+				examples: [".tmp"]
+			}
+		}
+		username: {
+			description: "The user to log in as."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector"]
+			}
+		}
+		verify_host_key: {
+			common:      false
+			description: "Whether the host key of the server is verified. Without verification, uploads are open to man-in-the-middle attacks."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		atomic_uploads: {
+			title: "Atomic Uploads"
+			body: """
+				Each file is written under its final name followed by
+				`temp_suffix`, and renamed once it's complete, so whoever
+				collects files from the directory never picks up one that is
+				half written. Readers should skip names ending in the suffix.
+				"""
+		}
+
+		rotation: {
+			title: "File Rotation"
+			body: """
+				Every batch becomes a file of its own, so files are rotated
+				when they reach `batch.max_bytes`, or when `batch.timeout_secs`
+				have passed since their first event. Events are written to
+				separate files for each directory rendered from `path`.
+				"""
+		}
+
+		retries: {
+			title: "Retries"
+			body: """
+				Files keep their name when uploads are retried, and files that
+				already exist are left alone, so a retry after an upload whose
+				confirmation got lost doesn't upload the file twice. Wrong
+				credentials and host keys aren't retried.
+				"""
+		}
+	}
+}
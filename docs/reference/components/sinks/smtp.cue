@@ -0,0 +1,219 @@
+package metadata
+
+components: sinks: smtp: {
+	title:       "SMTP"
+	description: "[SMTP](\(urls.smtp)) is the protocol email is delivered with. Sending events as emails suits low-volume alert pipelines that end in a mailbox."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       true
+				max_bytes:    null
+				max_events:   1
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			request: {
+				enabled:                    true
+				concurrency:                1
+				rate_limit_duration_secs:   60
+				rate_limit_num:             10
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+			}
+			to: {
+				service: {
+					name:     "SMTP server"
+					thing:    "an \(name)"
+					url:      urls.smtp
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "SMTP"
+							url:   urls.smtp
+						}
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		address: {
+			description: "The address of the SMTP server. The address _must_ include a port."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["smtp.example.com:587", "smtp.example.com:465"]
+			}
+		}
+		auth: {
+			common:      true
+			description: "Authenticates with the `PLAIN` or `LOGIN` mechanism, whichever the server supports."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					password: {
+						description: "The password to authenticate with."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${SMTP_PASSWORD}"]
+						}
+					}
+					username: {
+						description: "The username to authenticate with."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["vector@example.com"]
+						}
+					}
+				}
+			}
+		}
+		body: {
+			common:      true
+			description: "The body of each email. Defaults to the event encoded as JSON."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ message }}"]
+				templateable: true
+			}
+		}
+		from: {
+			description: "The sender of the emails."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector@example.com"]
+			}
+		}
+		helo_name: {
+			common:      false
+			description: "The name the sink introduces itself to the server with. Defaults to the hostname."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["vector.example.com"]
+			}
+		}
+		security: {
+			common:      true
+			description: "How the connection to the server is secured. The `tls` options apply to both `starttls` and `tls`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "starttls"
+				enum: {
+					starttls:      "Upgrade the connection with [`STARTTLS`](\(urls.smtp_starttls)), failing if the server doesn't support it."
+					opportunistic: "Upgrade the connection with `STARTTLS` if the server supports it, and send in plain text otherwise."
+					tls:           "Negotiate TLS as soon as the connection is established, as expected on port 465."
+					none:          "Never use TLS."
+				}
+			}
+		}
+		subject: {
+			description: "The subject of each email."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["Alert from {{ host }}", "{{ alert_name }} is firing"]
+				templateable: true
+			}
+		}
+		to: {
+			description: "The recipients of the emails."
+			required:    true
+			warnings: []
+			type: array: {
+				items: type: string: examples: ["oncall@example.com"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		digests: {
+			title: "Digests"
+			body: """
+				By default every event is sent as an email of its own. Raising
+				`batch.max_events` and `batch.timeout_secs` collects the events
+				arriving within the timeout into a single digest email. Its
+				subject is the subject of the first event followed by the count
+				of the others, and its body lists the subject and body of every
+				event.
+				"""
+		}
+
+		rate_limiting: {
+			title: "Rate Limiting"
+			body: """
+				At most `request.rate_limit_num` emails are sent every
+				`request.rate_limit_duration_secs`, 10 a minute by default.
+				Further emails wait for the next window, so a burst of alerts
+				backs up in the buffer rather than flooding the mailbox; batching
+				them into digests keeps them timely.
+				"""
+		}
+
+		retries: {
+			title: "Retries"
+			body: """
+				Emails are retried after connection failures and transient
+				(`4xx`) replies. Permanent (`5xx`) replies, such as an unknown
+				recipient, drop the email.
+				"""
+		}
+	}
+}
@@ -0,0 +1,248 @@
+package metadata
+
+components: sinks: snowflake: {
+	title:       "Snowflake"
+	description: "[Snowflake](\(urls.snowflake)) is a cloud data warehouse. Events are loaded into its tables by [Snowpipe](\(urls.snowpipe_rest_api)) from files written to an external stage."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["Snowflake", "AWS"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       true
+				max_bytes:    10485760
+				max_events:   null
+				timeout_secs: 60
+			}
+			compression: {
+				enabled: true
+				default: "gzip"
+				algorithms: ["none", "gzip", "zstd", "snappy"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			request: {
+				enabled:                    true
+				concurrency:                10
+				rate_limit_duration_secs:   1
+				rate_limit_num:             100
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: enabled: false
+			to: {
+				service: {
+					name:     "Snowflake"
+					thing:    "a \(name) pipe"
+					url:      urls.snowflake
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Snowpipe REST API"
+							url:   urls.snowpipe_rest_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"""
+				The pipe must load from an [external stage on S3](\(urls.snowflake_s3_stage)).
+				Internal stages and Snowpipe Streaming are not supported, as
+				Snowflake only offers them through its client drivers.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		account: {
+			description: "The account identifier, as in the URL of the account."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["xy12345.us-east-2.aws", "myorg-myaccount"]
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The URL of the account."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "https://<account>.snowflakecomputing.com"
+			}
+		}
+		path: {
+			common:      true
+			description: "The path of files below the location of the stage. Events are written to separate files for each path rendered from them."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "%F/"
+				examples: ["{{ application }}/%F/"]
+				templateable: true
+			}
+		}
+		pipe: {
+			description: "The fully qualified name of the pipe that loads the files. The table events land in is the one the pipe copies into."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["EVENTS.PUBLIC.EVENTS_PIPE", "EVENTS.PUBLIC.{{ table }}_PIPE"]
+				templateable: true
+			}
+		}
+		private_key_passphrase: {
+			common:      false
+			description: "The passphrase of an encrypted `private_key_path`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["${SNOWFLAKE_KEY_PASSPHRASE}"]
+			}
+		}
+		private_key_path: {
+			description: "The PEM encoded private key of the user, whose public key is [registered](\(urls.snowflake_key_pair_auth)) with Snowflake."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["/etc/vector/snowflake_key.p8"]
+			}
+		}
+		stage: {
+			description: "The S3 location of the external stage the pipe loads from."
+			required:    true
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					assume_role: {
+						common:      false
+						description: "The ARN of an IAM role to assume for uploading files."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["arn:aws:iam::123456789098:role/snowflake-stage"]
+						}
+					}
+					bucket: {
+						description: "The bucket of the stage."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["my-stage-bucket"]
+						}
+					}
+					endpoint: {
+						common:      false
+						description: "A custom S3 endpoint to use instead of the one of `region`."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["127.0.0.0:5000/path/to/service"]
+						}
+					}
+					prefix: {
+						common:      true
+						description: "The key prefix of the stage location within the bucket, as in its URL."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["snowflake/events/"]
+						}
+					}
+					region: {
+						description: "The AWS region of the bucket."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["us-east-2"]
+						}
+					}
+				}
+			}
+		}
+		user: {
+			description: "The user to authenticate as."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		loading: {
+			title: "Loading"
+			body: """
+				Each batch is written to the stage bucket as a file of
+				newline-delimited JSON, and the pipe is then asked to load it.
+				The pipe's `COPY` statement maps the JSON to the columns of its
+				table. Loading happens in the background, usually within a
+				minute, and failed loads show in the pipe's load history rather
+				than in Vector.
+				"""
+		}
+
+		retries: {
+			title: "Retries"
+			body: """
+				Files keep their name when requests are retried, and Snowpipe
+				skips files it has already loaded, so retrying never loads events
+				twice.
+				"""
+		}
+
+		authentication: {
+			title: "Authentication"
+			body: """
+				Snowpipe is called with [key-pair authentication](\(urls.snowflake_key_pair_auth)),
+				while files are uploaded with the AWS credentials found the same
+				way as in the `aws_s3` sink.
+				"""
+		}
+	}
+}
@@ -103,6 +103,77 @@ components: sinks: splunk_hec: {
 	}
 
 	configuration: {
+		acknowledgements: {
+			common:      false
+			description: "Options for [indexer acknowledgement](#indexer-acknowledgement)."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					enabled: {
+						common:      true
+						description: "Only acknowledge events once Splunk has indexed them. Indexer acknowledgement must be enabled on the HEC token."
+						required:    false
+						warnings: []
+						type: bool: default: false
+					}
+					poll_interval_secs: {
+						common:      false
+						description: "How often to ask Splunk whether a batch has been indexed."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 1
+							unit:    "seconds"
+						}
+					}
+					timeout_secs: {
+						common:      false
+						description: "How long to wait for a batch to be indexed before sending it again."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
+		endpoint: {
+			common:      true
+			description: "The base URL of the Splunk instance. Required unless `endpoints` is set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["https://http-inputs-hec.splunkcloud.com", "https://hec.splunk.com:8088"]
+			}
+		}
+		endpoint_target: {
+			common:      false
+			description: "The HEC endpoint to send events to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "event"
+				enum: {
+					event: "Send events as JSON to `/services/collector/event`, each with its own metadata and indexed fields."
+					raw:   "Send events as lines to `/services/collector/raw`, leaving Splunk to extract timestamps and fields. See [Raw Endpoint](#raw-endpoint)."
+				}
+			}
+		}
+		endpoints: {
+			common:      false
+			description: "Further base URLs of Splunk instances, such as several indexers or heavy forwarders. Requests are spread over them and `endpoint` in turn."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: examples: ["https://splunk-indexer-1:8088", "https://splunk-indexer-2:8088"]
+			}
+		}
 		host_key: {
 			common:      true
 			description: "The name of the log field to be used as the hostname sent to Splunk HEC. This overrides the [global `host_key` option][docs.reference.global-options#host_key]."
@@ -120,7 +191,8 @@ components: sinks: splunk_hec: {
 			warnings: []
 			type: string: {
 				default: null
-				examples: ["custom_index"]
+				examples: ["custom_index", "{{ kubernetes.namespace }}"]
+				templateable: true
 			}
 		}
 		indexed_fields: {
@@ -140,7 +212,8 @@ components: sinks: splunk_hec: {
 			warnings: []
 			type: string: {
 				default: null
-				examples: ["/var/log/syslog", "UDP:514"]
+				examples: ["/var/log/syslog", "UDP:514", "{{ file }}"]
+				templateable: true
 			}
 		}
 		sourcetype: {
@@ -150,7 +223,8 @@ components: sinks: splunk_hec: {
 			warnings: []
 			type: string: {
 				default: null
-				examples: ["_json", "httpevent"]
+				examples: ["_json", "httpevent", "{{ sourcetype }}"]
+				templateable: true
 			}
 		}
 		token: {
@@ -168,6 +242,42 @@ components: sinks: splunk_hec: {
 		metrics: null
 	}
 
+	how_it_works: {
+		indexer_acknowledgement: {
+			title: "Indexer Acknowledgement"
+			body: """
+				With `acknowledgements.enabled`, requests are sent on a
+				[channel](\(urls.splunk_hec_indexer_acknowledgement)) and the
+				sink polls Splunk with the ack id of each batch until it has
+				been indexed. Only then are the events acknowledged, to disk
+				buffers for example. Batches that aren't indexed within
+				`acknowledgements.timeout_secs` are sent again, so events may
+				be duplicated.
+				"""
+		}
+
+		load_balancing: {
+			title: "Load Balancing"
+			body: """
+				Requests are sent to `endpoint` and each of `endpoints` in turn.
+				As every attempt goes to the next one, a failed request is
+				retried against another instance.
+				"""
+		}
+
+		raw_endpoint: {
+			title: "Raw Endpoint"
+			body: """
+				With `endpoint_target = "raw"` events are sent to the
+				[raw endpoint](\(urls.splunk_hec_raw_endpoint)), and the `sourcetype`, `source`,
+				host and `index` apply to whole requests, so events are
+				batched separately for each combination of them. Indexed
+				fields and the event timestamp aren't sent, Splunk extracts
+				them according to the sourcetype.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		http_request_errors_total: components.sources.internal_metrics.output.metrics.http_request_errors_total
 		http_requests_total:       components.sources.internal_metrics.output.metrics.http_requests_total
@@ -0,0 +1,204 @@
+package metadata
+
+components: sinks: syslog: {
+	title:       "Syslog"
+	description: "[Syslog](\(urls.syslog)) is a standard for message logging, still the only input spoken by many SIEMs and legacy log collectors."
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: {
+					name:     "syslog receiver"
+					thing:    "a \(name)"
+					url:      urls.syslog
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Syslog"
+							url:   urls.syslog_5424
+						}
+						direction: "outgoing"
+						protocols: ["tcp", "udp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		address: {
+			description: "The address to connect to. The address _must_ include a port."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["92.12.333.224:6514"]
+			}
+		}
+		app_name: {
+			common:      true
+			description: "The APP-NAME, or TAG in RFC 3164, of each message. Defaults to `vector` when unset or when the template fails to render."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["my-app", "{{ service }}"]
+				templateable: true
+			}
+		}
+		facility: {
+			common:      true
+			description: "The facility of each message, either its name (such as `local0`) or its numeric code. Defaults to `user` when unset or invalid."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["local0", "16", "{{ facility }}"]
+				templateable: true
+			}
+		}
+		format: {
+			common:      true
+			description: "The syslog format to encode messages with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "rfc5424"
+				enum: {
+					rfc5424: "The [RFC 5424](\(urls.syslog_5424)) format, including structured data."
+					rfc3164: "The legacy BSD [RFC 3164](\(urls.syslog_3164)) format."
+				}
+			}
+		}
+		framing: {
+			common:      false
+			description: "How messages are delimited over TCP, as described in [RFC 6587](\(urls.syslog_6587)). UDP datagrams always carry a single message."
+			groups: ["tcp"]
+			required: false
+			warnings: []
+			type: string: {
+				default: "octet_counting"
+				enum: {
+					octet_counting:    "Prefix each message with its length in bytes."
+					newline_delimited: "Terminate each message with a newline."
+				}
+			}
+		}
+		mode: {
+			description: "The type of socket to use."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					tcp: "TCP socket"
+					udp: "UDP socket"
+				}
+			}
+		}
+		msg_id: {
+			common:      false
+			description: "The MSGID of each message. Only used by the `rfc5424` format."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["ID47", "{{ event_type }}"]
+				templateable: true
+			}
+		}
+		proc_id: {
+			common:      false
+			description: "The PROCID of each message, appended to the tag in the `rfc3164` format."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["1234", "{{ pid }}"]
+				templateable: true
+			}
+		}
+		severity: {
+			common:      true
+			description: "The severity of each message, either its name (such as `err` or `warning`) or its numeric code. Defaults to `info` when unset or invalid."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["warning", "4", "{{ level }}"]
+				templateable: true
+			}
+		}
+		structured_data_key: {
+			common:      false
+			description: "A map field holding the structured data of each message, keyed by SD-ID with a map of parameters for each element. The field is removed from the event. Only used by the `rfc5424` format."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["structured_data"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		structured_data: {
+			title: "Structured Data"
+			body: """
+				When `structured_data_key` is set, each entry of that map field becomes an
+				SD-ELEMENT. For example, `{"origin@32473": {"ip": "10.0.0.1"}}` is written
+				as `[origin@32473 ip="10.0.0.1"]`, with `"`, `\\` and `]` escaped in
+				parameter values. Entries that aren't maps themselves are skipped.
+				"""
+		}
+	}
+}
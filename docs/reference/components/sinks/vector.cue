@@ -76,16 +76,104 @@ components: sinks: vector: {
 
 	configuration: {
 		address: {
-			description: "The downstream Vector address to connect to. The address _must_ include a port."
-			required:    true
+			description: "The downstream Vector address to connect to. The address _must_ include a port. Either this or `addresses` must be set."
+			required:    false
 			warnings: []
 			type: string: {
+				default: null
 				examples: ["92.12.333.224:5000"]
 			}
 		}
+		addresses: {
+			common:      false
+			description: "Several downstream Vector addresses to spread events over, as described in [load balancing](#load-balancing). Each address _must_ include a port."
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: examples: ["10.0.0.1:5000", "10.0.0.2:5000"]
+			}
+		}
+		load_balancing: {
+			common:      false
+			description: "How events are spread over `addresses`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					chunk_max_events: {
+						common:      false
+						description: "The most events written to an address at once."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 100
+							unit:    "events"
+						}
+					}
+					failure_threshold: {
+						common:      true
+						description: "The number of consecutive failures after which an address is ejected."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 3
+							unit:    null
+						}
+					}
+					probe_interval_secs: {
+						common:      false
+						description: "How often ejected addresses are checked for whether they accept connections again."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 5
+							unit:    "seconds"
+						}
+					}
+					strategy: {
+						common:      true
+						description: "How the address for each chunk of events is picked."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "round_robin"
+							enum: {
+								round_robin:  "Take turns between the addresses."
+								least_loaded: "Pick the address with the fewest events waiting to be written."
+							}
+						}
+					}
+					timeout_secs: {
+						common:      false
+						description: "How long writing a chunk of events may take before it counts as failed and is sent elsewhere."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	how_it_works: components.sources.vector.how_it_works
+	how_it_works: load_balancing: {
+		title: "Load Balancing"
+		body: """
+			With `addresses` set, events are written in chunks to each of the
+			downstream Vectors in turn, or to the one with the fewest events
+			waiting with the `least_loaded` strategy. A chunk that fails to be
+			written is sent to another address, and an address failing
+			`failure_threshold` times in a row is ejected until it accepts
+			connections again, so no load balancer is needed in front of
+			aggregators. Events are acknowledged in the order they came in
+			once written, but may arrive out of order across aggregators.
+			"""
+	}
 
 	telemetry: metrics: {
 		protobuf_decode_errors_total: components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
@@ -0,0 +1,204 @@
+package metadata
+
+components: sinks: webhook_notify: {
+	title:       "Webhook Notifications"
+	description: "Posts events as chat messages to [Slack](\(urls.slack_incoming_webhooks)), [Microsoft Teams](\(urls.teams_incoming_webhooks)) or [Discord](\(urls.discord_webhooks)) incoming webhooks, or as templated bodies to any other webhook."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["Slack", "Microsoft", "Discord"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			batch: {
+				enabled:      true
+				common:       true
+				max_bytes:    1048576
+				max_events:   1
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			dead_letter:          true
+			encoding: enabled:    false
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             1000
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: {
+					name:     "webhook"
+					thing:    "a chat \(name)"
+					url:      urls.slack_incoming_webhooks
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		body: {
+			common:      false
+			description: "The body of each request in the `raw` format."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{\"alert\": \"{{ message }}\", \"host\": \"{{ host }}\"}"]
+				templateable: true
+			}
+		}
+		content_type: {
+			common:      false
+			description: "The `Content-Type` of requests in the `raw` format."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "application/json"
+				examples: ["text/plain"]
+			}
+		}
+		format: {
+			description: "The kind of webhook messages are sent to."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					slack:   "A Slack incoming webhook, sent the `text` of a message."
+					teams:   "A Microsoft Teams incoming webhook, sent a message card."
+					discord: "A Discord webhook, sent the `content` of a message of at most 2000 characters."
+					raw:     "Any other webhook, sent the rendered `body`."
+				}
+			}
+		}
+		rate_limit: {
+			common:      false
+			description: "How many requests are sent to each webhook URI in a window. Defaults to the limit of the chat service: 1 a second for Slack, 4 a second for Teams and 5 every 2 seconds for Discord. Raw webhooks aren't limited by default."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					duration_secs: {
+						description: "The length of the window."
+						required:    true
+						warnings: []
+						type: uint: {
+							examples: [1]
+							unit: "seconds"
+						}
+					}
+					num: {
+						description: "The number of requests allowed in the window."
+						required:    true
+						warnings: []
+						type: uint: {
+							examples: [1]
+							unit: null
+						}
+					}
+				}
+			}
+		}
+		text: {
+			description: "The text of each message in the `slack`, `teams` and `discord` formats."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ message }}", "Disk usage above 90% on {{ host }}"]
+				templateable: true
+			}
+		}
+		title: {
+			common:      true
+			description: "The title of each message, shown in bold above its text. Ignored in the `raw` format."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ alert_name }} is firing"]
+				templateable: true
+			}
+		}
+		uri: {
+			description: "The URI of the webhook. URIs referencing event fields send each event to the webhook rendered from it."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["${SLACK_WEBHOOK_URI}", "https://discord.com/api/webhooks/{{ channel_id }}/{{ channel_token }}"]
+				templateable: true
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		digests: {
+			title: "Digests"
+			body: """
+				By default every event is sent as a message of its own. Raising
+				`batch.max_events` and `batch.timeout_secs` collects the events
+				arriving within the timeout into a single digest message, headed
+				by the title of the first event followed by the count of the
+				others. Bodies of the `raw` format are joined by newlines.
+				"""
+		}
+
+		rate_limiting: {
+			title: "Rate Limiting"
+			body: """
+				Chat services [limit](\(urls.slack_rate_limits)) how often each
+				webhook accepts messages, so requests are delayed to stay within
+				`rate_limit` separately for every webhook URI, and one noisy
+				channel doesn't hold back the others. Throttled (`429`) requests
+				are retried. Delays count towards `request.timeout_secs`, so the
+				window should be well below it.
+				"""
+		}
+	}
+}
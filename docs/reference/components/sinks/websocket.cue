@@ -0,0 +1,146 @@
+package metadata
+
+components: sinks: websocket: {
+	title:       "WebSocket"
+	description: "[WebSocket](\(urls.websocket)) is a protocol providing full-duplex communication over a single TCP connection, commonly used to stream live data to browsers and dashboards."
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: {
+					name:     "WebSocket server or client"
+					thing:    "a \(name)"
+					url:      urls.websocket
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "WebSocket"
+							url:   urls.websocket_rfc
+						}
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen for WebSocket clients on."
+			groups: ["listen"]
+			required: true
+			warnings: []
+			type: string: {
+				examples: ["0.0.0.0:9000"]
+			}
+		}
+		client_buffer: {
+			common:      false
+			description: "The number of events buffered for each connected client. Clients that fall further behind skip the oldest events instead of slowing down the sink."
+			groups: ["listen"]
+			required: false
+			warnings: []
+			type: uint: {
+				default: 1024
+				unit:    "events"
+			}
+		}
+		message_type: {
+			common:      false
+			description: "The type of WebSocket frame each event is sent in."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "text"
+				enum: {
+					text:   "Send each event as a text frame. Invalid UTF-8 is replaced."
+					binary: "Send each event as a binary frame."
+				}
+			}
+		}
+		mode: {
+			description: "Whether to connect to a WebSocket server or to accept WebSocket clients."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					connect: "Maintain a connection to the server at `uri`, reconnecting with a backoff whenever it is lost."
+					listen:  "Accept clients on `address` and send every event to all of them."
+				}
+			}
+		}
+		uri: {
+			description: "The `ws` or `wss` URI of the WebSocket server. TLS is enabled by default for `wss` URIs."
+			groups: ["connect"]
+			required: true
+			warnings: []
+			type: string: {
+				examples: ["ws://127.0.0.1:9000/events", "wss://example.com/ingest"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		live_tail: {
+			title: "Live Tail"
+			body: """
+				In `listen` mode the sink never blocks on its clients: events are
+				dropped while nobody is connected, and a client only receives the
+				events sent after it connected. This makes it suitable for feeding
+				live-tail dashboards, but not for delivering every event.
+				"""
+		}
+	}
+}
@@ -0,0 +1,259 @@
+package metadata
+
+components: sources: ebpf: {
+	title:       "eBPF"
+	description: "The eBPF source loads [eBPF](\(urls.ebpf)) programs into the Linux kernel to capture process executions, TCP connections and DNS lookups as structured security events, as a lightweight alternative to `auditd` for detection pipelines."
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: service: {
+				name:     "Linux kernel"
+				thing:    "the \(name)"
+				url:      urls.ebpf
+				versions: ">= 5.8"
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        false
+			"x86_64-pc-windows-msv":      false
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"""
+				Linux 5.8 or later, built with `CONFIG_DEBUG_INFO_BTF` so that the
+				kernel exposes its types at `/sys/kernel/btf/vmlinux`.
+				""",
+			"""
+				Vector must be built with the `sources-ebpf` feature, which needs
+				`clang` and `bpftool` at build time.
+				""",
+		]
+		warnings: [
+			"""
+				This source is experimental. Loading eBPF programs requires
+				running Vector with the `CAP_SYS_ADMIN` capability, or with
+				`CAP_BPF` and `CAP_PERFMON`.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		probes: {
+			common:      true
+			description: "The probes to enable."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					process_exec: {
+						common:      true
+						description: "Capture process executions, from the `sched_process_exec` tracepoint."
+						required:    false
+						warnings: []
+						type: bool: default: true
+					}
+					tcp_connect: {
+						common:      true
+						description: "Capture established outgoing TCP connections, from `tcp_v4_connect` and `tcp_v6_connect`."
+						required:    false
+						warnings: []
+						type: bool: default: true
+					}
+					tcp_accept: {
+						common:      true
+						description: "Capture accepted incoming TCP connections, from `inet_csk_accept`."
+						required:    false
+						warnings: []
+						type: bool: default: true
+					}
+					dns_lookup: {
+						common:      true
+						description: "Capture DNS lookups made through `getaddrinfo` in the system libc. Statically linked programs aren't captured."
+						required:    false
+						warnings: []
+						type: bool: default: false
+					}
+				}
+			}
+		}
+		ring_buffer_pages: {
+			common:      false
+			description: "The size of the ring buffer the kernel writes records to, in memory pages. Must be a power of two. Records that don't fit are dropped and counted."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 64
+				unit:    null
+			}
+		}
+	}
+
+	output: logs: {
+		event: {
+			description: "A process or network activity event. The fields after `comm` depend on the probe."
+			fields: {
+				timestamp: fields._current_timestamp
+				host:      fields._local_host
+				probe: {
+					description: "The probe that captured the event."
+					required:    true
+					type: string: enum: {
+						process_exec: "A process executed a program."
+						tcp_connect:  "A TCP connection was established."
+						tcp_accept:   "A TCP connection was accepted."
+						dns_lookup:   "A host name was looked up."
+					}
+				}
+				pid: {
+					description: "The ID of the process."
+					required:    true
+					type: uint: {
+						examples: [4242]
+						unit: null
+					}
+				}
+				uid: {
+					description: "The user ID of the process."
+					required:    true
+					type: uint: {
+						examples: [0]
+						unit: null
+					}
+				}
+				gid: {
+					description: "The group ID of the process."
+					required:    true
+					type: uint: {
+						examples: [0]
+						unit: null
+					}
+				}
+				comm: {
+					description: "The command name of the process, truncated to 15 characters."
+					required:    true
+					type: string: examples: ["bash"]
+				}
+				ppid: {
+					description: "The ID of the parent process, for `process_exec`."
+					required:    false
+					type: uint: {
+						examples: [1]
+						unit: null
+					}
+				}
+				filename: {
+					description: "The path of the executed program, for `process_exec`."
+					required:    false
+					type: string: examples: ["/usr/bin/curl"]
+				}
+				argv: {
+					description: "The arguments of the executed program, for `process_exec`. Only the first 512 bytes are captured."
+					required:    false
+					type: array: items: type: string: examples: ["curl", "-s"]
+				}
+				saddr: {
+					description: "The local address of the connection, for `tcp_connect` and `tcp_accept`."
+					required:    false
+					type: string: examples: ["10.0.0.1"]
+				}
+				sport: {
+					description: "The local port of the connection, for `tcp_connect` and `tcp_accept`."
+					required:    false
+					type: uint: {
+						examples: [51234]
+						unit: null
+					}
+				}
+				daddr: {
+					description: "The remote address of the connection, for `tcp_connect` and `tcp_accept`."
+					required:    false
+					type: string: examples: ["93.184.216.34"]
+				}
+				dport: {
+					description: "The remote port of the connection, for `tcp_connect` and `tcp_accept`."
+					required:    false
+					type: uint: {
+						examples: [443]
+						unit: null
+					}
+				}
+				query: {
+					description: "The host name looked up, for `dns_lookup`."
+					required:    false
+					type: string: examples: ["example.com"]
+				}
+			}
+		}
+	}
+
+	examples: [
+		{
+			title: "Process execution"
+
+			configuration: {}
+			input: """
+				```text
+				$ curl -s http://example.com
+				```
+				"""
+			output: [{
+				log: {
+					timestamp: _values.current_timestamp
+					host:      _values.local_host
+					probe:     "process_exec"
+					pid:       4242
+					uid:       0
+					gid:       0
+					comm:      "bash"
+					ppid:      4200
+					filename:  "/usr/bin/curl"
+					argv: ["curl", "-s", "http://example.com"]
+				}
+			}]
+		},
+	]
+
+	how_it_works: {
+		co_re: {
+			title: "Compile Once – Run Everywhere"
+			body: """
+				The eBPF programs are compiled when Vector is built and embedded in
+				it. Using the types the kernel exposes through BTF, libbpf relocates
+				them when they are loaded, so the same programs work across kernel
+				versions.
+				"""
+		}
+		back_pressure: {
+			title: "Back Pressure"
+			body: """
+				The kernel never waits for Vector. Records that don't fit in the
+				ring buffer, and events that can't be sent on because the pipeline
+				is applying back pressure, are dropped and counted in the
+				`events_lost_total` metric.
+				"""
+		}
+	}
+}
@@ -141,6 +141,24 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		anomalies_detected_total: {
+			description:       "The total number of anomalies detected by the anomaly transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		cache_hits_total: {
+			description:       "The total number of lookups answered from this component's cache."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		cache_misses_total: {
+			description:       "The total number of lookups not found in, or expired from, this component's cache."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		checkpoint_write_errors_total: {
 			description:       "The total number of errors writing checkpoints."
 			type:              "counter"
@@ -353,6 +371,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		late_events_dropped_total: {
+			description:       "The total number of events that arrived after their window was emitted."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		logging_driver_errors_total: {
 			description: """
 				The total number of logging driver errors encountered caused by not using either
@@ -404,6 +428,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		processes_started_total: {
+			description:       "The total number of processes started by this component, including restarts."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		processing_errors_total: {
 			description:       "The total number of processing errors encountered by this component."
 			type:              "counter"
@@ -418,6 +448,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		redactions_total: {
+			description:       "The total number of sensitive values redacted by this component."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		request_duration_nanoseconds: {
 			description:       "The request duration for this component (in nanoseconds)."
 			type:              "histogram"
@@ -532,6 +568,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		traces_decided_total: {
+			description:       "The total number of traces the trace sampler has decided on, by decision."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				decision: {
+					description: "Why the trace was kept, or `dropped`."
+					required:    true
+					options: ["error", "latency", "condition", "sampled", "dropped"]
+				}
+			}
+		}
 		uptime_seconds: {
 			description:       "The total number of seconds the Vector instance has been up."
 			type:              "gauge"
@@ -561,6 +609,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		windows_flushed_total: {
+			description:       "The total number of windows emitted by the window transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 
 		// Windows metrics
 		windows_service_does_not_exist_total: {
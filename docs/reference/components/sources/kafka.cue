@@ -36,6 +36,33 @@ components: sources: kafka: {
 	}
 
 	configuration: {
+		avro: {
+			common:      false
+			description: "Decode message payloads as Avro records in the Confluent wire format, placing the record's fields at the root of the event instead of the raw payload in `message`. Writer schemas are fetched from a Schema Registry by id and cached. Payloads that fail to decode are kept in `message`. Can't be combined with `protobuf`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					registry_url: {
+						description: "The base URL of the Confluent Schema Registry."
+						required:    true
+						warnings: []
+						type: string: examples: ["http://localhost:8081"]
+					}
+					auth: configuration._http_auth & {_args: {
+						password_example: "${SCHEMA_REGISTRY_PASSWORD}"
+						username_example: "${SCHEMA_REGISTRY_USERNAME}"
+					}}
+					tls: configuration._tls_connect & {_args: {
+						can_enable:             false
+						can_verify_certificate: true
+						can_verify_hostname:    true
+						enabled_default:        false
+					}}
+				}
+			}
+		}
 		auto_offset_reset: {
 			common:      false
 			description: "If offsets for consumer group do not exist, set them using this strategy. [librdkafka documentation][urls.librdkafka_config] for `auto.offset.reset` option for explanation."
@@ -118,6 +145,29 @@ components: sources: kafka: {
 			}
 		}
 		librdkafka_options: components._kafka.configuration.librdkafka_options
+		protobuf: {
+			common:      false
+			description: "Decode message payloads as a protobuf message, placing its fields at the root of the event instead of the raw payload in `message`. Payloads that fail to decode are kept in `message`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					descriptor_set: {
+						description: "The path to a compiled `FileDescriptorSet`, as written by `protoc --include_imports --descriptor_set_out`."
+						required:    true
+						warnings: []
+						type: string: examples: ["/etc/vector/descriptors.pb"]
+					}
+					message_type: {
+						description: "The fully qualified name of the message type to decode."
+						required:    true
+						warnings: []
+						type: string: examples: ["com.example.Event"]
+					}
+				}
+			}
+		}
 		sasl: {
 			common:      false
 			description: "Options for SASL/SCRAM authentication support."
@@ -0,0 +1,129 @@
+package metadata
+
+components: transforms: anomaly: {
+	title: "Anomaly"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		route: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		field: {
+			common:      true
+			description: "The numeric field to watch. When unset, the rate of events is watched instead. Events where the field isn't a number are passed on unscored."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["duration_ms", "response.bytes"]
+			}
+		}
+		flag_field: {
+			common:      false
+			description: "When watching a field, set this field on every event to whether it was anomalous."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["anomalous"]
+			}
+		}
+		group_by: {
+			common:      true
+			description: "An ordered list of fields to group events by. Each group has its own baseline, and its values are included in alerts."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: examples: ["service", "host"]
+			}
+		}
+		rate_interval_secs: {
+			common:      false
+			description: "When watching the event rate, the length of the intervals events are counted over."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		smoothing: {
+			common:      false
+			description: "How much weight each new sample gets in the moving baseline, between 0 and 1. Higher values adapt to changes faster."
+			required:    false
+			warnings: []
+			type: float: default: 0.1
+		}
+		threshold: {
+			common:      true
+			description: "How many standard deviations from the baseline a sample has to be to count as an anomaly."
+			required:    false
+			warnings: []
+			type: float: default: 3.0
+		}
+		warmup_samples: {
+			common:      false
+			description: "How many samples a baseline needs before anomalies are reported."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 30
+				unit:    null
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		baseline: {
+			title: "Baselines"
+			body: """
+				Each group keeps an [exponentially weighted moving mean and variance](\(urls.ewma))
+				of the watched field, or of the number of events per interval. Every sample is
+				given a [z-score](\(urls.standard_score)) against the baseline before it's added
+				to it, and samples scoring at least `threshold` in either direction are anomalous.
+				Baselines are kept in memory and start over when Vector restarts.
+				"""
+		}
+		alerts: {
+			title: "Alerts"
+			body: """
+				Events are passed through unchanged, apart from `flag_field`. For each anomaly an
+				alert event is sent to the `<transform name>.alerts` output, with the group's
+				fields, a readable `message`, and the sample's `value`, `mean`, `stddev` and
+				`score` under `anomaly`. An interval's event rate is scored when the first event
+				of a later interval arrives, so intervals without any events aren't scored.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		anomalies_detected_total: components.sources.internal_metrics.output.metrics.anomalies_detected_total
+	}
+}
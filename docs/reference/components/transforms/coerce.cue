@@ -0,0 +1,123 @@
+package metadata
+
+components: transforms: coerce: {
+	title: "Coerce"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	configuration: {
+		mode: {
+			common:      true
+			description: "What happens to events with values that can't be coerced."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "lenient"
+				enum: {
+					lenient: "Leave the values as they are, and pass the event on."
+					strict:  "Drop the event, unchanged."
+				}
+			}
+		}
+		reroute_dropped: {
+			common:      false
+			description: "Send events dropped in `strict` mode to the `dropped` output instead."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		schema_file: {
+			description: "A TOML, YAML or JSON file mapping fields to types, in its `fields` table. The format is guessed from the extension, and is TOML if there's none."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["/etc/vector/schemas/warehouse.toml"]
+			}
+		}
+	}
+
+	examples: [
+		{
+			title: "Warehouse Schema"
+			configuration: {
+				schema_file: "/etc/vector/schemas/warehouse.toml"
+			}
+			input: log: {
+				status:    "201"
+				client_ip: "::ffff:0a00:0001"
+				upstream: [{port: "8080"}, {port: "8081"}]
+			}
+			output: log: {
+				status:    201
+				client_ip: "::ffff:10.0.0.1"
+				upstream: [{port: 8080}, {port: 8081}]
+			}
+		},
+	]
+
+	how_it_works: {
+		schema_file: {
+			title: "Schema File"
+			body: """
+				The `fields` table maps each field to a type, or to a table giving the types
+				of the fields of a nested map:
+
+				```toml
+				[fields]
+				status = "int"
+				client_ip = "ip"
+				received = "timestamp|%d/%m/%Y:%H:%M:%S %z"
+
+				[fields.upstream]
+				port = "int"
+				```
+
+				The types are those of the `coercer` transform, plus `ip`, which checks the
+				value is an IPv4 or IPv6 address and writes it in its canonical form. Arrays
+				are coerced element by element. Missing and `null` fields are left alone,
+				as are values that already have their type.
+				"""
+		}
+		failures: {
+			title: "Failures"
+			body: """
+				In `lenient` mode, values that can't be coerced are left as they are. In
+				`strict` mode, the whole event is dropped unchanged, or sent to the `dropped`
+				output with `reroute_dropped`, with the failures in
+				`metadata.dropped.message`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
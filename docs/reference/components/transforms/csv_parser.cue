@@ -0,0 +1,155 @@
+package metadata
+
+components: transforms: csv_parser: {
+	title: "CSV Parser"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		parse: {
+			format: {
+				name:     "CSV"
+				url:      urls.csv
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		delimiter: {
+			common:      false
+			description: "The character separating the values of a row. Must be an ASCII character."
+			required:    false
+			warnings: []
+			type: string: {
+				default: ","
+				examples: [",", ";", "\t", "|"]
+			}
+		}
+		drop_field: {
+			common:      true
+			description: "If the specified `field` should be dropped (removed) after parsing. If parsing fails, the field will not be removed, irrespective of this setting."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		field: {
+			common:      true
+			description: "The log field to parse as a CSV row."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "message"
+				examples: ["message", "parent.child"]
+			}
+		}
+		field_names: {
+			common:      true
+			description: "The log field names assigned to the values of each row, in order. Required unless `header_row` is `true`."
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: examples: ["timestamp", "account_id", "cost", "parent.child"]
+			}
+		}
+		header_row: {
+			common:      true
+			description: "If `true`, the first row is a header and isn't emitted. When `field_names` isn't set the header gives the field names. Later rows identical to the header are discarded too."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		quote: {
+			common:      false
+			description: "The character used to quote values containing the delimiter. Must be an ASCII character."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "\""
+				examples: ["\"", "'"]
+			}
+		}
+		ragged_rows: {
+			common:      false
+			description: "What to do with rows that don't have exactly one value per field name."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "lenient"
+				enum: {
+					lenient: "Parse the values that are present. Missing fields are left out and extra values are ignored."
+					strict:  "Treat the row as a parse failure, passing the event through unparsed, or sending it to the `dropped` output if `reroute_dropped` is `true`."
+				}
+			}
+		}
+		reroute_dropped: {
+			common:      false
+			description: "If `true`, events that fail to parse are sent to the `<transform name>.dropped` output instead of being passed through, with the reason recorded under `metadata.dropped`."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		target_field: {
+			common:      false
+			description: "If this setting is present, the parsed fields will be inserted into the log as a sub-object with this name."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["root_field", "parent.child"]
+			}
+		}
+		types: configuration._types
+	}
+
+	examples: [
+		{
+			title: "Billing Export"
+			configuration: {
+				header_row: true
+				types: {
+					cost: "float"
+				}
+			}
+			input: [
+				{log: {message: "date,account,cost"}},
+				{log: {message: #"2020-11-01,"Acme, Inc.",12.5"#}},
+			]
+			output: log: {
+				date:    "2020-11-01"
+				account: "Acme, Inc."
+				cost:    12.5
+			}
+		},
+	]
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	telemetry: metrics: {
+		events_discarded_total:  components.sources.internal_metrics.output.metrics.events_discarded_total
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -0,0 +1,117 @@
+package metadata
+
+components: transforms: decrypt_fields: {
+	title: "Decrypt Fields"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		keys: {
+			description: "The keys events may have been encrypted with by the `encrypt_fields` transform. Keep retired keys here while events encrypted with them are in flight."
+			required:    true
+			warnings: []
+			type: array: items: type: object: options: {
+				key: {
+					description: "Where the 256 bit key is loaded from. Exactly one option must be set."
+					required:    true
+					warnings: []
+					type: object: options: {
+						env: {
+							common:      true
+							description: "The environment variable holding the base64 encoded 256 bit key."
+							required:    false
+							warnings: []
+							type: string: {
+								default: null
+								examples: ["VECTOR_FIELD_ENCRYPTION_KEY"]
+							}
+						}
+						file: {
+							common:      false
+							description: "A file holding the base64 encoded 256 bit key."
+							required:    false
+							warnings: []
+							type: string: {
+								default: null
+								examples: ["/etc/vector/field_encryption.key"]
+							}
+						}
+						kms: {
+							common:      false
+							description: "A [data key](\(urls.aws_kms_data_keys)) encrypted with [AWS KMS](\(urls.aws_kms)), decrypted once at startup."
+							required:    false
+							warnings: []
+							type: object: options: {
+								ciphertext: {
+									description: "The base64 encoded ciphertext blob of the data key, as returned by `GenerateDataKey`."
+									required:    true
+									warnings: []
+									type: string: examples: ["AQIDAHh..."]
+								}
+								assume_role: components._aws.configuration.assume_role
+								endpoint:    components._aws.configuration.endpoint
+								region:      components._aws.configuration.region
+							}
+						}
+					}
+				}
+				key_id: {
+					description: "The `key_id` the key was configured with in `encrypt_fields`."
+					required:    true
+					warnings: []
+					type: string: examples: ["2020-11"]
+				}
+			}
+		}
+		metadata_field: {
+			common:      false
+			description: "The field the encryption metadata is read from. It is removed once the event is decrypted."
+			required:    false
+			warnings: []
+			type: string: default: "encryption"
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		failures: {
+			title: "Failures"
+			body: """
+				Events without encryption metadata pass through untouched. If the key id is
+				unknown, or any field fails to decrypt because it was tampered with or encrypted
+				with another key, the whole event is passed on still encrypted.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -0,0 +1,155 @@
+package metadata
+
+components: transforms: encrypt_fields: {
+	title: "Encrypt Fields"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		algorithm: {
+			common:      false
+			description: "The authenticated encryption algorithm."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "aes_256_gcm"
+				enum: {
+					aes_256_gcm:       "AES-256 in Galois/Counter Mode."
+					chacha20_poly1305: "ChaCha20-Poly1305, faster than AES on CPUs without AES instructions."
+				}
+			}
+		}
+		fields: {
+			description: "The log fields to encrypt. Missing fields are skipped."
+			required:    true
+			warnings: []
+			type: array: items: type: string: examples: ["user.email", "card_number"]
+		}
+		key: {
+			description: "Where the 256 bit key is loaded from. Exactly one option must be set."
+			required:    true
+			warnings: []
+			type: object: options: {
+				env: {
+					common:      true
+					description: "The environment variable holding the base64 encoded 256 bit key."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["VECTOR_FIELD_ENCRYPTION_KEY"]
+					}
+				}
+				file: {
+					common:      false
+					description: "A file holding the base64 encoded 256 bit key."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["/etc/vector/field_encryption.key"]
+					}
+				}
+				kms: {
+					common:      false
+					description: "A [data key](\(urls.aws_kms_data_keys)) encrypted with [AWS KMS](\(urls.aws_kms)), decrypted once at startup."
+					required:    false
+					warnings: []
+					type: object: options: {
+						ciphertext: {
+							description: "The base64 encoded ciphertext blob of the data key, as returned by `GenerateDataKey`."
+							required:    true
+							warnings: []
+							type: string: examples: ["AQIDAHh..."]
+						}
+						assume_role: components._aws.configuration.assume_role
+						endpoint:    components._aws.configuration.endpoint
+						region:      components._aws.configuration.region
+					}
+				}
+			}
+		}
+		key_id: {
+			description: "Identifies the key in the event metadata, so `decrypt_fields` can pick the right key while keys are rotated."
+			required:    true
+			warnings: []
+			type: string: examples: ["2020-11"]
+		}
+		metadata_field: {
+			common:      false
+			description: "The field the encryption metadata is written to."
+			required:    false
+			warnings: []
+			type: string: default: "encryption"
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Encrypt Card Numbers"
+			configuration: {
+				fields: ["card"]
+				key_id: "2020-11"
+				key: env: "VECTOR_FIELD_ENCRYPTION_KEY"
+			}
+			input: log: {
+				message: "payment"
+				card:    "4111111111111111"
+			}
+			output: log: {
+				message: "payment"
+				card:    "7Xl0bV9Q3cKfU2pZ3n8Ebl0yPjE4qhR3tUoH5w6mV0cJ+6k1bG9Jz1wL"
+				encryption: {
+					key_id:    "2020-11"
+					algorithm: "aes_256_gcm"
+					fields: ["card"]
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		format: {
+			title: "Ciphertext Format"
+			body: """
+				Each value is JSON encoded, so its type is restored on decryption, then encrypted
+				with a random 96 bit nonce and the field name as associated data. Ciphertexts
+				can't be moved between fields without failing to decrypt. The field is replaced
+				with the base64 encoding of the nonce, ciphertext and 128 bit tag. If a value
+				fails to encrypt, the field is removed rather than sent in the clear.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
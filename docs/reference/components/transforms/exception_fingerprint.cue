@@ -0,0 +1,157 @@
+package metadata
+
+components: transforms: exception_fingerprint: {
+	title: "Exception Fingerprint"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		parse: {
+			format: {
+				name:     "Stack trace"
+				url:      null
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		field: {
+			common:      true
+			description: "The log field holding the stack trace."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "message"
+				examples: ["message", "error.stack"]
+			}
+		}
+		frames: {
+			common:      false
+			description: "The number of innermost frames that are extracted and make up the fingerprint."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 5
+				unit:    null
+			}
+		}
+		target_field: {
+			common:      false
+			description: "The field the parsed exception is written under."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "exception"
+				examples: ["exception", "crash"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	output: logs: exception: {
+		description: "The parsed exception, written to `target_field`."
+		fields: {
+			fingerprint: {
+				description: "The hex encoded SHA-256 of the language, exception type and frames."
+				required:    true
+				type: string: examples: ["3256f7ff5a6d4cf394afb845618f26f5c337fd930dd260dd0508f6b5f0250e43"]
+			}
+			frames: {
+				description: "The innermost frames, innermost first, without line numbers."
+				required:    true
+				type: array: items: type: string: examples: ["com.example.Queue.push", "/app/main.py:main"]
+			}
+			language: {
+				description: "The format the stack trace was recognized as."
+				required:    true
+				type: string: enum: {
+					go:         "A Go panic. Only the panicking goroutine is read."
+					java:       "A Java, or other JVM language, exception."
+					javascript: "A Node.js error."
+					python:     "A Python traceback."
+				}
+			}
+			message: {
+				description: "The exception message, if there is one."
+				required:    false
+				type: string: examples: ["Queue full"]
+			}
+			type: {
+				description: "The exception type, or `panic` for Go."
+				required:    true
+				type: string: examples: ["java.lang.IllegalStateException", "ValueError", "TypeError"]
+			}
+		}
+	}
+
+	examples: [
+		{
+			title: "Java Exception"
+			configuration: {
+				frames: 2
+			}
+			input: log: {
+				message: """
+					java.lang.IllegalStateException: Queue full
+					\tat com.example.Queue.push(Queue.java:42)
+					\tat com.example.Worker.run(Worker.java:17)
+					\tat java.lang.Thread.run(Thread.java:748)
+					"""
+			}
+			output: log: {
+				message: """
+					java.lang.IllegalStateException: Queue full
+					\tat com.example.Queue.push(Queue.java:42)
+					\tat com.example.Worker.run(Worker.java:17)
+					\tat java.lang.Thread.run(Thread.java:748)
+					"""
+				exception: {
+					fingerprint: "3256f7ff5a6d4cf394afb845618f26f5c337fd930dd260dd0508f6b5f0250e43"
+					frames: ["com.example.Queue.push", "com.example.Worker.run"]
+					language: "java"
+					message:  "Queue full"
+					type:     "java.lang.IllegalStateException"
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		fingerprints: {
+			title: "Fingerprints"
+			body: """
+				The fingerprint hashes the language, the exception type and the innermost frames,
+				identified by function, or file and function for Python. Messages and line
+				numbers are left out, so the same crash keeps its fingerprint across values and
+				releases that move code around, and events can be grouped or deduplicated on it
+				downstream. For Java exceptions with causes, only the outermost exception is
+				read. Events whose field doesn't hold a recognized stack trace are passed through
+				unchanged.
+				"""
+		}
+	}
+}
@@ -0,0 +1,148 @@
+package metadata
+
+components: transforms: exec: {
+	title: "Exec"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		program: {
+			runtime: {
+				name:    "Any executable"
+				url:     null
+				version: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: [
+			"""
+				Every event is written to and read back from a separate process, which is
+				much slower than Vector's built in transforms. Prefer those, or the
+				[programmable transforms](\(urls.vector_programmable_transforms)), where they're enough.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: {
+		command: {
+			description: "The program to run and its arguments. The program isn't run in a shell."
+			required:    true
+			warnings: []
+			type: array: items: type: string: examples: ["python3", "/etc/vector/enrich.py"]
+		}
+		concurrency: {
+			common:      false
+			description: "The number of processes run, each handling one event at a time. Events are emitted in the order they're received."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1
+				unit:    null
+			}
+		}
+		drop_on_error: {
+			common:      false
+			description: "Drop events that can't be transformed, instead of passing them through unchanged."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		restart_delay_secs: {
+			common:      false
+			description: "How long to wait before starting a process again after it exited, failed to respond in time, or couldn't be started."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1
+				unit:    "seconds"
+			}
+		}
+		timeout_secs: {
+			common:      true
+			description: "How long the process has to respond to an event before it's killed."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Python Enrichment"
+			configuration: {
+				command: ["python3", "-u", "/etc/vector/enrich.py"]
+			}
+			input: log: {
+				message: "login"
+				user_id: 42
+			}
+			output: log: {
+				message: "login"
+				user_id: 42
+				team:    "payments"
+			}
+		},
+	]
+
+	how_it_works: {
+		protocol: {
+			title: "Protocol"
+			body: """
+				Each event is written to the process's standard input as a single line of JSON.
+				For each line, the process must write a single line to its standard output: a
+				JSON object for one event, an array of objects for any number of events, or
+				`null` to drop the event. Make sure the process flushes its output after every
+				line, for example with `python3 -u`. Standard error is passed through to
+				Vector's.
+
+				```python
+				import json, sys
+
+				for line in sys.stdin:
+				    event = json.loads(line)
+				    event["team"] = "payments"
+				    print(json.dumps(event), flush=True)
+				```
+				"""
+		}
+		restarts: {
+			title: "Restarts"
+			body: """
+				When a process exits, or doesn't respond within `timeout_secs`, it's killed and
+				the event fails. A new process is started for the next event, after
+				`restart_delay_secs`. Events that fail are passed through unchanged, unless
+				`drop_on_error` is set. Processes are killed when Vector stops or reloads.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processes_started_total: components.sources.internal_metrics.output.metrics.processes_started_total
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -0,0 +1,105 @@
+package metadata
+
+components: transforms: explode: {
+	title: "Explode"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		field: {
+			description: "The array field to explode into one event per element. Events where it's missing or not an array are passed through unchanged, and events where it's empty are dropped."
+			required:    true
+			warnings: []
+			type: string: examples: ["records", "body.events"]
+		}
+		index_field: {
+			common:      false
+			description: "The field the element's position in the array is written to. Not written if unset."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["index"]
+			}
+		}
+		merge: {
+			common:      false
+			description: "Merge the fields of map elements into the top level of the event, instead of writing them to `target_field`. Merged fields replace siblings with the same name."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		siblings: {
+			common:      true
+			description: "What happens to the other fields of the event."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "copy"
+				enum: {
+					copy: "Every emitted event gets a copy of them."
+					drop: "Emitted events only hold the element, without even a timestamp."
+				}
+			}
+		}
+		target_field: {
+			common:      false
+			description: "The field each element is written to. Defaults to `field`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["record"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Batched Webhook"
+			configuration: {
+				field: "records"
+				merge: true
+			}
+			input: log: {
+				source: "webhook"
+				records: [
+					{id: 1, status: "ok"},
+					{id: 2, status: "failed"},
+				]
+			}
+			output: [
+				{log: {source: "webhook", id: 1, status: "ok"}},
+				{log: {source: "webhook", id: 2, status: "failed"}},
+			]
+		},
+	]
+}
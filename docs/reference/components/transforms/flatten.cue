@@ -0,0 +1,139 @@
+package metadata
+
+components: transforms: flatten: {
+	title: "Flatten"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		arrays: {
+			common:      false
+			description: "How arrays are handled."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "index"
+				enum: {
+					index: "Array elements are flattened with their index as the key. When unflattening, maps whose keys are all the indices from `0` are turned into arrays."
+					keep:  "Arrays are left as they are."
+				}
+			}
+		}
+		max_depth: {
+			common:      false
+			description: "The number of levels of nesting that are flattened, or created when unflattening. Deeper fields are left as they are. Unlimited if unset."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [1, 3]
+				unit: null
+			}
+		}
+		mode: {
+			common:      true
+			description: "Whether fields are flattened or unflattened."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "flatten"
+				enum: {
+					flatten:   "Nested fields are replaced by top level fields whose keys are joined with `separator`."
+					unflatten: "Top level keys are split on `separator` into nested fields."
+				}
+			}
+		}
+		separator: {
+			common:      true
+			description: "The string joining, or splitting, keys."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "."
+				examples: [".", "_", "/"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Flatten"
+			configuration: {
+				separator: "_"
+			}
+			input: log: {
+				message: "GET /"
+				http: {
+					status: 200
+					headers: host: "example.com"
+				}
+				tags: ["web", "prod"]
+			}
+			output: log: {
+				message:           "GET /"
+				http_status:       200
+				http_headers_host: "example.com"
+				tags_0:            "web"
+				tags_1:            "prod"
+			}
+		},
+		{
+			title: "Unflatten"
+			configuration: {
+				mode: "unflatten"
+			}
+			input: log: {
+				message:             "GET /"
+				"http.status":       200
+				"http.headers.host": "example.com"
+			}
+			output: log: {
+				message: "GET /"
+				http: {
+					status: 200
+					headers: host: "example.com"
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		conflicts: {
+			title: "Key Conflicts"
+			body: """
+				Empty maps and arrays are kept under their key, so that no field is lost. When
+				flattening creates a key that's already set, such as `a.b` from both `a.b` and
+				`a: {b}`, the field that was already flat wins. When unflattening runs into a field that
+				isn't a map, such as `a.b` when `a` is a string, the key is left as it is.
+				"""
+		}
+	}
+}
@@ -42,6 +42,16 @@ components: transforms: geoip: {
 				examples: ["/path/to/GeoLite2-City.mmdb"]
 			}
 		}
+		reload_interval_secs: {
+			common:      false
+			description: "How often to check whether the database file has changed on disk. When it has, the database is reloaded without restarting Vector. Set to `0` to disable reloading."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
 		source: {
 			description: "The field name that contains the IP address. This field should contain a valid IPv4 or IPv6 address."
 			required:    true
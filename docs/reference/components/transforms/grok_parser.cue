@@ -66,14 +66,43 @@ components: transforms: grok_parser: {
 				examples: ["message", "parent.child", "array[0]"]
 			}
 		}
+		custom_patterns: {
+			common:      false
+			description: "Pattern definitions to add to the bundled library, so they can be used as `%{NAME}` in `pattern` and `patterns`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{SERVICE: "[a-z]+-[0-9]+"}]
+				options: {}
+			}
+		}
 		pattern: {
-			description: "The [Grok pattern](\(urls.grok_patterns))"
-			required:    true
+			common:      true
+			description: "The [Grok pattern](\(urls.grok_patterns)). Either this or `patterns` has to be set."
+			required:    false
 			warnings: []
 			type: string: {
+				default: null
 				examples: ["%{TIMESTAMP_ISO8601:timestamp} %{LOGLEVEL:level} %{GREEDYDATA:message}"]
 			}
 		}
+		patterns: {
+			common:      false
+			description: "[Grok patterns](\(urls.grok_patterns)) tried in order after `pattern`. The captures of the first one matching are added to the event."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: examples: ["%{COMMONAPACHELOG}", "%{SYSLOGLINE}"]
+			}
+		}
+		reroute_dropped: {
+			common:      false
+			description: "If `true`, events no pattern matches are sent to the `<transform name>.dropped` output instead of being passed on unchanged, with the reason recorded under `metadata.dropped`."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		types: configuration._types
 	}
 
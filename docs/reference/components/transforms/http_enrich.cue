@@ -0,0 +1,186 @@
+package metadata
+
+components: transforms: http_enrich: {
+	title: "HTTP Enrich"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		enrich: {
+			from: service: {
+				name:     "HTTP"
+				url:      urls.http
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${CMDB_PASSWORD}"
+			username_example: "${CMDB_USERNAME}"
+		}}
+		cache_size: {
+			common:      false
+			description: "The maximum number of lookups cached. The least recently used are evicted first."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+		cache_ttl_secs: {
+			common:      true
+			description: "How long responses are cached."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 300
+				unit:    "seconds"
+			}
+		}
+		concurrency: {
+			common:      false
+			description: "The maximum number of requests in flight. Events are emitted in the order they're received."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    null
+			}
+		}
+		fields: {
+			common:      true
+			description: "The response fields merged into the event. All of them are merged if empty."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: examples: ["owner", "team.name"]
+			}
+		}
+		headers: {
+			common:      false
+			description: "Headers sent with every request."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"Accept": "application/json"}]
+				options: {}
+			}
+		}
+		negative_cache_ttl_secs: {
+			common:      false
+			description: "How long lookups the endpoint answered with 404 are cached."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		target_field: {
+			common:      true
+			description: "The field response fields are written under. They're written to the top level of the event if unset."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["host_info"]
+			}
+		}
+		timeout_secs: {
+			common:      false
+			description: "How long to wait for a response."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 5
+				unit:    "seconds"
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             false
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		url: {
+			description: "The URL looked up for each event. Event fields can be inserted with `{{ field }}`, and each distinct URL is cached separately."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["http://cmdb.internal/hosts/{{ host }}"]
+				templateable: true
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "CMDB Lookup"
+			configuration: {
+				url: "http://cmdb.internal/hosts/{{ host }}"
+				fields: ["owner", "environment"]
+				target_field: "host_info"
+			}
+			input: log: {
+				host:    "web-1"
+				message: "disk full"
+			}
+			output: log: {
+				host:    "web-1"
+				message: "disk full"
+				host_info: {
+					owner:       "payments"
+					environment: "production"
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		lookups: {
+			title: "Lookups"
+			body: """
+				A `GET` request is sent to the rendered `url`, and the endpoint must answer with
+				a JSON object. Responses are cached for `cache_ttl_secs`, and 404 responses for
+				`negative_cache_ttl_secs`, leaving the event unchanged. Events are passed
+				through unchanged when a field in `url` is missing, or the request fails,
+				times out or gets any other status; those lookups aren't cached.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		cache_hits_total:        components.sources.internal_metrics.output.metrics.cache_hits_total
+		cache_misses_total:      components.sources.internal_metrics.output.metrics.cache_misses_total
+		missing_keys_total:      components.sources.internal_metrics.output.metrics.missing_keys_total
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -0,0 +1,155 @@
+package metadata
+
+components: transforms: ip_classify: {
+	title: "IP Classify"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		enrich: {
+			from: service: {
+				name:     "Named CIDR sets"
+				url:      urls.cidr
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		all_matches: {
+			common:      false
+			description: "Write the names of all matching sets, as an array, rather than only the first."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		default_class: {
+			common:      true
+			description: "Written to `target_field` when the address is in none of the sets. Nothing is written if unset."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["public", "external"]
+			}
+		}
+		field: {
+			description: "The field holding the IPv4 or IPv6 address."
+			required:    true
+			warnings: []
+			type: string: examples: ["src_ip", "client.address"]
+		}
+		sets: {
+			description: "A table of class names to CIDR sets, checked in order. An address matches a set if it is in any of the set's CIDRs."
+			required:    true
+			warnings: []
+			type: object: {
+				options: {
+					"*": {
+						description: "The CIDRs of the set. At least one must be given."
+						required:    true
+						warnings: []
+						type: object: options: {
+							builtin: {
+								common:      true
+								description: "Well-known ranges included in the set."
+								required:    false
+								warnings: []
+								type: array: {
+									default: []
+									items: type: string: enum: {
+										private:              "The RFC 1918 ranges and unique local IPv6 addresses."
+										loopback:             "`127.0.0.0/8` and `::1`."
+										link_local:           "`169.254.0.0/16` and `fe80::/10`."
+										multicast:            "`224.0.0.0/4` and `ff00::/8`."
+										shared_address_space: "The RFC 6598 range used for carrier-grade NAT."
+										documentation:        "The ranges reserved for documentation by RFC 5737 and RFC 3849."
+									}
+								}
+							}
+							cidrs: {
+								common:      true
+								description: "CIDRs or single addresses included in the set."
+								required:    false
+								warnings: []
+								type: array: {
+									default: []
+									items: type: string: examples: ["10.20.0.0/16", "2001:db8:1::/48"]
+								}
+							}
+							file: {
+								common:      false
+								description: "A file with a CIDR or address per line, included in the set. Blank lines and lines starting with `#` are ignored. The file is read when Vector starts or reloads its configuration."
+								required:    false
+								warnings: []
+								type: string: {
+									default: null
+									examples: ["/etc/vector/corporate_ranges.txt"]
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+		target_field: {
+			common:      true
+			description: "The field the class is written to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "ip_class"
+				examples: ["src_class"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Internal and external traffic"
+			configuration: {
+				field:         "src_ip"
+				default_class: "public"
+				sets: {
+					private: builtin: ["private", "loopback"]
+					corporate: cidrs: ["203.0.113.0/24"]
+				}
+			}
+			input: log: {
+				src_ip: "192.168.1.7"
+			}
+			output: log: {
+				src_ip:   "192.168.1.7"
+				ip_class: "private"
+			}
+		},
+	]
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
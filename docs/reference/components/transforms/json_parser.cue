@@ -65,6 +65,13 @@ components: transforms: json_parser: {
 			warnings: []
 			type: bool: default: false
 		}
+		reroute_dropped: {
+			common:      false
+			description: "If `true`, events that fail to parse are sent to the `<transform name>.dropped` output instead of being passed through or dropped, with the reason recorded under `metadata.dropped`."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		target_field: {
 			common:      false
 			description: "If this setting is present, the parsed JSON will be inserted into the log as a sub-object with this name. If a field with the same name already exists, the parser will fail and produce an error."
@@ -83,6 +83,17 @@ components: transforms: key_value_parser: {
 			type: bool: default: false
 		}
 
+		respect_quotes: {
+			common: false
+			description: """
+					If `true`, separators inside double quoted values don't split pairs,
+					and the quotes around keys and values are removed. This handles
+					formats like `msg="user logged in"` used by Fortinet and Checkpoint.
+				"""
+			required: false
+			type: bool: default: false
+		}
+
 		separator: {
 			common:      false
 			description: "The character(s) that separate key/value pairs. Must be a `string` value."
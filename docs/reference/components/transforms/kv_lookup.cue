@@ -0,0 +1,202 @@
+package metadata
+
+components: transforms: kv_lookup: {
+	title: "Key/Value Lookup"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		enrich: {
+			from: service: {
+				name:     "Redis or memcached"
+				url:      urls.redis
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		backend: {
+			description: "The store values are looked up in."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					redis:     "A [Redis](\(urls.redis)) server."
+					memcached: "A memcached server, spoken to with the [text protocol](\(urls.memcached_protocol))."
+				}
+			}
+		}
+		cache_size: {
+			common:      false
+			description: "The maximum number of lookups cached. The least recently used are evicted first."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+		cache_ttl_secs: {
+			common:      true
+			description: "How long values, and keys that weren't found, are cached."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		concurrency: {
+			common:      false
+			description: "The maximum number of lookups in flight. Events are emitted in the order they're received."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    null
+			}
+		}
+		endpoint: {
+			description: "A `redis://` URL for Redis, or the `host:port` of a memcached server."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["redis://127.0.0.1:6379/0", "127.0.0.1:11211"]
+			}
+		}
+		key: {
+			description: "The key looked up for each event. Event fields can be inserted with `{{ field }}`."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["user:{{ user_id }}"]
+				templateable: true
+			}
+		}
+		mark_field: {
+			common:      false
+			description: "The field set to `true` when `miss_policy` is `mark`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "lookup_miss"
+			}
+		}
+		miss_policy: {
+			common:      true
+			description: "What happens to events whose key isn't found."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "pass"
+				enum: {
+					pass: "Pass the event through unchanged."
+					drop: "Drop the event."
+					mark: "Set `mark_field` to `true`."
+				}
+			}
+		}
+		target_field: {
+			description: "The field the value is written to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["user"]
+			}
+		}
+		timeout_secs: {
+			common:      false
+			description: "How long to wait for a lookup."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1
+				unit:    "seconds"
+			}
+		}
+		value_type: {
+			common:      true
+			description: "How the value is written."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "string"
+				enum: {
+					string: "Written as a string."
+					json:   "Parsed as a JSON object and written as a map."
+					hash:   "A Redis hash, written as a map. Not supported by memcached."
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "User Lookup"
+			configuration: {
+				backend:      "redis"
+				endpoint:     "redis://127.0.0.1:6379"
+				key:          "user:{{ user_id }}"
+				target_field: "user"
+				value_type:   "hash"
+			}
+			input: log: {
+				user_id: "42"
+				message: "login"
+			}
+			output: log: {
+				user_id: "42"
+				message: "login"
+				user: {
+					name: "Jane"
+					team: "payments"
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		lookups: {
+			title: "Lookups"
+			body: """
+				Values are fetched with `GET`, or `HGETALL` for Redis hashes, and cached for
+				`cache_ttl_secs`. Keys that aren't found are cached too, and handled with
+				`miss_policy`; Redis doesn't tell an empty hash from a missing one. Events are
+				passed through unchanged when a field in `key` is missing, or the lookup fails
+				or times out; those lookups aren't cached.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		cache_hits_total:        components.sources.internal_metrics.output.metrics.cache_hits_total
+		cache_misses_total:      components.sources.internal_metrics.output.metrics.cache_misses_total
+		missing_keys_total:      components.sources.internal_metrics.output.metrics.missing_keys_total
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -36,6 +36,17 @@ components: transforms: log_to_metric: {
 			type: array: items: type: object: {
 				examples: []
 				options: {
+					buckets: {
+						description: "The upper bounds of the buckets to count the `field` values into, in increasing order. If set, the metric is an [aggregated histogram][docs.data-model.metric#histogram], otherwise every value is passed on in a distribution."
+						required:    false
+						common:      false
+						warnings: []
+						relevant_when: #"type = "histogram" or type = "distribution""#
+						type: array: {
+							default: null
+							items: type: float: examples: [0.005, 0.01, 0.1, 1.0, 10.0]
+						}
+					}
 					field: {
 						description: "The log field to use as the metric."
 						required:    true
@@ -111,11 +122,12 @@ components: transforms: log_to_metric: {
 						warnings: []
 						type: string: {
 							enum: {
-								counter:   "A [counter metric type][docs.data-model.metric#counter]."
-								gauge:     "A [gauge metric type][docs.data-model.metric#gauge]."
-								histogram: "A [distribution metric type][docs.data-model.metric#distribution] with histogram statistic."
-								set:       "A [set metric type][docs.data-model.metric#set]."
-								summary:   "A [distribution metric type][docs.data-model.metric#distribution] with summary statistic."
+								counter:      "A [counter metric type][docs.data-model.metric#counter]."
+								distribution: "An alias of `histogram`."
+								gauge:        "A [gauge metric type][docs.data-model.metric#gauge]."
+								histogram:    "A [distribution metric type][docs.data-model.metric#distribution] with histogram statistic, or an [aggregated histogram][docs.data-model.metric#histogram] if `buckets` are set."
+								set:          "A [set metric type][docs.data-model.metric#set] counting the unique values of `field`."
+								summary:      "A [distribution metric type][docs.data-model.metric#distribution] with summary statistic."
 							}
 						}
 					}
@@ -170,7 +170,7 @@ components: transforms: lua: {
 							}
 						}
 						interval_seconds: {
-							description: "Defines the interval at which the timer handler would be executed."
+							description: "Defines the interval at which the timer handler would be executed. Must be greater than zero."
 							required:    true
 							warnings: []
 							type: uint: {
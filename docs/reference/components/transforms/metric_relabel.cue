@@ -0,0 +1,130 @@
+package metadata
+
+components: transforms: metric_relabel: {
+	title: "Metric Relabel"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		relabel: {
+			description: "The [Prometheus-style relabeling rules](\(urls.prometheus_relabel_config)), applied in order. The `__name__` label is the metric's name, and the other labels are its tags."
+			required:    true
+			warnings: []
+			type: array: items: type: object: options: {
+				action: {
+					common:      true
+					description: "What the rule does."
+					required:    false
+					warnings: []
+					type: string: {
+						default: "replace"
+						enum: {
+							replace:  "Set `target_label` to `replacement` when `regex` matches the source labels. Setting a tag to an empty value removes it."
+							keep:     "Drop metrics whose source labels `regex` doesn't match."
+							drop:     "Drop metrics whose source labels `regex` matches."
+							labelmap: "Copy the tags whose names `regex` matches to the names given by `replacement`."
+							hashmod:  "Set `target_label` to the MD5 hash of the source labels modulo `modulus`."
+						}
+					}
+				}
+				modulus: {
+					common:      false
+					description: "The modulus of the hash. Required by the `hashmod` action."
+					required:    false
+					warnings: []
+					type: uint: {
+						default: null
+						examples: [4]
+						unit: null
+					}
+				}
+				regex: {
+					common:      true
+					description: "The regular expression the joined source labels, or tag names for `labelmap`, must match in full."
+					required:    false
+					warnings: []
+					type: string: {
+						default: "(.*)"
+						examples: ["([^:]+):\\d+"]
+					}
+				}
+				replacement: {
+					common:      true
+					description: "The value written, in which `$1` or `${1}` is replaced by the regex's capture groups."
+					required:    false
+					warnings: []
+					type: string: {
+						default: "$1"
+						examples: ["${1}_total"]
+					}
+				}
+				separator: {
+					common:      false
+					description: "The string the values of the source labels are joined with."
+					required:    false
+					warnings: []
+					type: string: default: ";"
+				}
+				source_labels: {
+					common:      true
+					description: "The labels whose values are joined and matched against `regex`. Missing labels have empty values."
+					required:    false
+					warnings: []
+					type: array: {
+						default: []
+						items: type: string: examples: ["__name__", "instance"]
+					}
+				}
+				target_label: {
+					common:      true
+					description: "The label written. Required by the `replace` and `hashmod` actions."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["host"]
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
@@ -0,0 +1,106 @@
+package metadata
+
+components: transforms: project: {
+	title: "Project"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		route: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		variants: {
+			description: "A table of variant identifiers to the fields each variant keeps. Every event is sent to every variant, and each variant can be referenced as an input by other components with the name `<transform_name>.<variant_id>`."
+			required:    true
+			warnings: []
+			type: object: {
+				options: {
+					"*": {
+						description: "The fields the variant keeps. An empty table sends the events unchanged."
+						required:    true
+						warnings: []
+						type: object: options: {
+							drop_fields: {
+								common:      false
+								description: "Fields removed from the variant, after `fields` is applied."
+								required:    false
+								warnings: []
+								type: array: {
+									default: []
+									items: type: string: examples: ["user.email"]
+								}
+							}
+							fields: {
+								common:      true
+								description: "The only fields kept. All fields are kept if empty."
+								required:    false
+								warnings: []
+								type: array: {
+									default: []
+									items: type: string: examples: ["timestamp", "message", "request.status"]
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Minimal Variant"
+			configuration: {
+				variants: {
+					full: {}
+					minimal: fields: ["message", "status"]
+				}
+			}
+			input: log: {
+				message: "GET /"
+				status:  200
+				body:    "<html>"
+			}
+			output: log: {
+				message: "GET /"
+				status:  200
+			}
+		},
+	]
+
+	how_it_works: {
+		variants: {
+			title: "Variants"
+			body: """
+				Each variant is a copy of the event stream with its own shape, so complete
+				events can be archived from `my_project.full` while only a few fields are
+				sent on from `my_project.minimal`, without parsing the events twice.
+				"""
+		}
+	}
+}
@@ -0,0 +1,107 @@
+package metadata
+
+components: transforms: protobuf_parser: {
+	title: "Protobuf Parser"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		parse: {
+			format: {
+				name:     "Protocol Buffers"
+				url:      urls.protobuf
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		descriptor_set: {
+			description: "The path to a compiled `FileDescriptorSet` holding the message type and every type it references, as written by `protoc --include_imports --descriptor_set_out`."
+			required:    true
+			warnings: []
+			type: string: examples: ["/etc/vector/descriptors.pb"]
+		}
+		drop_field: {
+			common:      true
+			description: "If the specified `field` should be dropped (removed) after decoding. If decoding fails, the field will not be removed, irrespective of this setting."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		field: {
+			common:      true
+			description: "The log field holding the encoded message."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "message"
+				examples: ["message", "parent.child"]
+			}
+		}
+		message_type: {
+			description: "The fully qualified name of the message type to decode."
+			required:    true
+			warnings: []
+			type: string: examples: ["com.example.Event"]
+		}
+		reroute_dropped: {
+			common:      false
+			description: "If `true`, events that fail to decode are sent to the `<transform name>.dropped` output instead of being passed through, with the reason recorded under `metadata.dropped`."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		target_field: {
+			common:      false
+			description: "If this setting is present, the decoded fields will be inserted into the log as a sub-object with this name."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["root_field", "parent.child"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		type_mapping: {
+			title: "Type Mapping"
+			body: """
+				Scalar fields are mapped to integers, floats, booleans and strings. Enum
+				values are mapped to their name, or to their number if it isn't declared.
+				Nested messages become objects, repeated fields arrays, and map fields
+				objects keyed by the map key. Fields not set in the message are left out,
+				and fields unknown to the descriptor set are skipped.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
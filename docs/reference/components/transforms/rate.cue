@@ -0,0 +1,82 @@
+package metadata
+
+components: transforms: rate: {
+	title: "Rate"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		expire_after_secs: {
+			common:      false
+			description: "Series that haven't been seen for this long are forgotten, and their next sample is treated as their first."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 300
+				unit:    "seconds"
+			}
+		}
+		mode: {
+			common:      true
+			description: "What counters are converted into."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "rate"
+				enum: {
+					rate:  "The increase per second since the previous sample."
+					delta: "The increase since the previous sample."
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		conversion: {
+			title: "Conversion"
+			body: """
+				Counters are converted into absolute gauges with the same name and tags. The
+				previous sample of each series, identified by its name, namespace and tags, is
+				kept, so nothing is emitted for the first sample of a series. The time between
+				samples is taken from their timestamps, or from when they were received if
+				either has none. An absolute counter that decreases is assumed to have been
+				reset to zero. Other metrics are passed through unchanged.
+				"""
+		}
+	}
+}
@@ -0,0 +1,140 @@
+package metadata
+
+components: transforms: redact: {
+	title: "Redact"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		fields: {
+			common:      true
+			description: "The log fields to scrub. Fields that aren't strings are skipped."
+			required:    false
+			warnings: []
+			type: array: {
+				default: ["message"]
+				items: type: string: examples: ["message", "request.body"]
+			}
+		}
+		hash_key: {
+			common:      false
+			description: "The key for the HMAC-SHA256 used by the `hash` action. Required if any matcher uses it."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["${REDACT_HASH_KEY}"]
+			}
+		}
+		matchers: {
+			description: "The values to redact, applied in order."
+			required:    true
+			warnings: []
+			type: array: items: type: object: options: {
+				action: {
+					common:      true
+					description: "How matched values are redacted."
+					required:    false
+					warnings: []
+					type: string: {
+						default: "replace"
+						enum: {
+							replace:  "Replace the value with `placeholder`."
+							hash:     "Replace the value with its hex encoded HMAC-SHA256, keyed with `hash_key`, so equal values can still be correlated."
+							truncate: "Mask all but the last `keep` characters with `*`."
+						}
+					}
+				}
+				keep: {
+					common:      false
+					description: "The number of trailing characters the `truncate` action keeps."
+					required:    false
+					warnings: []
+					type: uint: {
+						default: 4
+						unit:    null
+					}
+				}
+				pattern: {
+					common:      false
+					description: "The regular expression to match. Required for `regex` matchers."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["token=\\\\w+"]
+					}
+				}
+				placeholder: {
+					common:      false
+					description: "The replacement used by the `replace` action."
+					required:    false
+					warnings: []
+					type: string: default: "[REDACTED]"
+				}
+				type: {
+					description: "The kind of value to match."
+					required:    true
+					warnings: []
+					type: string: enum: {
+						credit_card: "Payment card numbers, optionally separated by spaces or dashes, that pass the Luhn check."
+						email:       "Email addresses."
+						ip:          "IPv4 and IPv6 addresses."
+						us_ssn:      "US Social Security numbers in the `AAA-GG-SSSS` format."
+						regex:       "Values matching `pattern`."
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Payment Log"
+			configuration: {
+				matchers: [
+					{type: "credit_card", action: "truncate"},
+					{type: "email"},
+				]
+			}
+			input: log: {
+				message: "card 4111 1111 1111 1111 paid by jane.doe@example.com"
+			}
+			output: log: {
+				message: "card ***************1111 paid by [REDACTED]"
+			}
+		},
+	]
+
+	telemetry: metrics: {
+		redactions_total: components.sources.internal_metrics.output.metrics.redactions_total
+	}
+}
@@ -103,7 +103,8 @@ components: transforms: reduce: {
 								array:          "Each value is appended to an array."
 								concat:         "Concatenate each string value (delimited with a space)."
 								concat_newline: "Concatenate each string value (delimited with a newline)."
-								discard:        "Discard all but the first value found."
+								discard:        "Discard all but the first value found. Can also be written as `first`."
+								retain:         "Discard all but the last value found. Can also be written as `last`."
 								sum:            "Sum all numeric values."
 								max:            "The maximum of all numeric values."
 								min:            "The minimum of all numeric values."
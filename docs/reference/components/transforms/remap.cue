@@ -35,6 +35,20 @@ components: transforms: remap: {
 	}
 
 	configuration: {
+		drop_on_err: {
+			common:      false
+			description: "Drop the event if the program fails at runtime. If `false`, the event is forwarded as it was at the time of the failure."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		reroute_dropped: {
+			common:      false
+			description: "If `true`, events the program fails on are sent to the `<transform name>.dropped` output as they were before the program ran, with the reason recorded under `metadata.dropped`."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		source: {
 			description: "The remap source/instruction set to execute for each event"
 			required:    true
@@ -111,9 +125,45 @@ components: transforms: remap: {
 				timestamp: "2020-10-01T02:22:11.223212Z"
 			}
 		},
+		{
+			title: "Handle Errors"
+			configuration: {
+				source: #"""
+					.parsed = parse_json(.message) ?? .message
+					.code = to_int(.code) ?? 0
+					"""#
+			}
+			input: log: {
+				message: "not json"
+				code:    "abc"
+			}
+			output: log: {
+				message: "not json"
+				code:    0
+				parsed:  "not json"
+			}
+		},
 	]
 
 	how_it_works: {
+		error_handling: {
+			title: "Error Handling"
+			body: #"""
+				Expressions that can fail at runtime, such as parsing or coercing
+				values, can be given a fallback with the error coalescing operator
+				`??`. The right-hand side is only evaluated when the left-hand side
+				fails:
+
+				```
+				.status = to_int(.status) ?? 0
+				```
+
+				Programs are compiled and type checked when the configuration is
+				loaded, so syntax errors, unknown functions, and invalid arguments
+				are reported at boot rather than when events are processed.
+				"""#
+		}
+
 		remap_language: {
 			title: "Remap Language"
 			body: #"""
@@ -0,0 +1,93 @@
+package metadata
+
+components: transforms: route: {
+	title: "Route"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		route: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		route: {
+			description: "A table of route identifiers to logical conditions representing the filter of the route. Each route can then be referenced as an input by other components with the name `<transform_name>.<route_id>`. Events matching none of the routes are sent to `<transform_name>._unmatched`, the `_unmatched` name is reserved."
+			required:    true
+			warnings: []
+			type: object: {
+				options: {
+					"*": {
+						description: "The condition events must match to be sent to the route."
+						required:    true
+						warnings: []
+						type: object: configuration._conditions
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	examples: [
+		{
+			title: "Split by log level"
+			configuration: {
+				route: {
+					error: "level.eq": "error"
+					warn: "level.eq":  "warn"
+				}
+			}
+			input: log: {
+				level: "error"
+			}
+			output: log: {
+				level: "error"
+			}
+		},
+	]
+
+	how_it_works: {
+		unmatched: {
+			title: "Unmatched events"
+			body: """
+				Events are sent to every route whose condition they match, so an event can be
+				sent to more than one route. Events matching none of the routes are sent to the
+				`_unmatched` output, for example `my_route._unmatched`, instead of being dropped.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
@@ -25,7 +25,11 @@ components: transforms: sampler: {
 
 		requirements: []
 		warnings: []
-		notices: []
+		notices: [
+			"""
+				This transform is also available under the name `sample`.
+				""",
+		]
 	}
 
 	configuration: {
@@ -47,23 +51,40 @@ components: transforms: sampler: {
 		}
 		exclude: {
 			common:      true
-			description: "The set of logical conditions to exclude events from sampling."
+			description: "The set of logical conditions to exclude events from sampling, for example to never sample errors."
 			required:    false
 			warnings: []
 			type: object: configuration._conditions
 		}
 		rate: {
+			common: true
 			description: """
 				The rate at which events will be forwarded, expressed as 1/N. For example,
 				`rate = 10` means 1 out of every 10 events will be forwarded and the rest will be dropped.
+				Exactly one of `rate` and `ratio` must be set.
 				"""
-			required: true
+			required: false
 			warnings: []
 			type: uint: {
+				default: null
 				examples: [10]
 				unit: null
 			}
 		}
+		ratio: {
+			common: false
+			description: """
+				The fraction of events which will be forwarded, between 0 and 1. For example,
+				`ratio = 0.1` means 10% of events will be forwarded and the rest will be dropped.
+				Exactly one of `rate` and `ratio` must be set.
+				"""
+			required: false
+			warnings: []
+			type: float: {
+				default: null
+				examples: [0.1, 0.25]
+			}
+		}
 	}
 
 	input: {
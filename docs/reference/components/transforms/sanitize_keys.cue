@@ -0,0 +1,132 @@
+package metadata
+
+components: transforms: sanitize_keys: {
+	title: "Sanitize Keys"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		allowed_characters: {
+			common:      false
+			description: "The characters allowed in keys, as the body of a regular expression character class. Other characters are replaced with `replacement`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["a-zA-Z0-9_", "\\\\w\\\\-"]
+			}
+		}
+		dot_replacement: {
+			common:      true
+			description: "The string dots in keys are replaced with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["_"]
+			}
+		}
+		lowercase: {
+			common:      false
+			description: "Lowercase keys."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		max_length: {
+			common:      false
+			description: "The maximum number of characters in a key. Longer keys are truncated."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [64]
+				unit: null
+			}
+		}
+		preset: {
+			common:      true
+			description: "Sets the other options to satisfy a destination. Options that are set explicitly take precedence."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				enum: {
+					elasticsearch: "Replace dots with `_`, as Elasticsearch reads them as object paths, and keys like `a` and `a.b` cause mapping conflicts."
+					prometheus:    "Only allow [label name](\(urls.prometheus_label_names)) characters, replacing others with `_` and prefixing keys starting with a digit with `_`."
+				}
+			}
+		}
+		replacement: {
+			common:      false
+			description: "The string characters outside of `allowed_characters` are replaced with. Set to `\"\"` to strip them."
+			required:    false
+			warnings: []
+			type: string: default: "_"
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	examples: [
+		{
+			title: "Elasticsearch"
+			configuration: {
+				preset: "elasticsearch"
+			}
+			input: log: {
+				message: "GET /"
+				kubernetes: labels: "app.kubernetes.io/name": "web"
+			}
+			output: log: {
+				message: "GET /"
+				kubernetes: labels: "app_kubernetes_io/name": "web"
+			}
+		},
+	]
+
+	how_it_works: {
+		keys: {
+			title: "Sanitized Keys"
+			body: """
+				The keys of log fields, including nested ones, and the tag names of metrics are
+				sanitized. Dots are replaced first, then disallowed characters, before keys are
+				lowercased and truncated. Keys that end up empty are replaced with `_`. When two
+				keys end up the same, the value of the key that sorts last is kept.
+				"""
+		}
+	}
+}
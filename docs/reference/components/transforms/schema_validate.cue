@@ -0,0 +1,110 @@
+package metadata
+
+components: transforms: schema_validate: {
+	title: "Schema Validate"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		errors_field: {
+			common:      true
+			description: "If set, the validation errors of invalid events are written to this field as an array of strings."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["schema_errors"]
+			}
+		}
+		reroute_dropped: {
+			common:      false
+			description: "Send invalid events to the `dropped` output instead of passing them on."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		schema: {
+			common:      false
+			description: "The [JSON Schema](\(urls.json_schema)) events must match, inline. Exactly one of `schema` and `schema_file` must be set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{\"type\": \"object\", \"required\": [\"message\"]}"]
+			}
+		}
+		schema_file: {
+			common:      true
+			description: "A file holding the [JSON Schema](\(urls.json_schema)) events must match. Exactly one of `schema` and `schema_file` must be set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/etc/vector/schemas/event.json"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Annotate Invalid Events"
+			configuration: {
+				schema:       "{\"type\": \"object\", \"properties\": {\"status\": {\"type\": \"integer\"}}}"
+				errors_field: "schema_errors"
+			}
+			input: log: {
+				message: "request"
+				status:  "ok"
+			}
+			output: log: {
+				message: "request"
+				status:  "ok"
+				schema_errors: ["'\"ok\"' is not of type 'integer'"]
+			}
+		},
+	]
+
+	how_it_works: {
+		invalid_events: {
+			title: "Invalid Events"
+			body: """
+				Events that don't match the schema are passed on, with their errors in
+				`errors_field` when it's set. With `reroute_dropped`, they are sent to the
+				`dropped` output instead, with the errors in `metadata.dropped.message`, so
+				they can be routed to a sink for events that break the contract.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -0,0 +1,128 @@
+package metadata
+
+components: transforms: session: {
+	title: "Session"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		first_fields: {
+			common:      true
+			description: "Fields whose values in the first event of a session are kept under `session.first`."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: examples: ["referrer", "landing_page"]
+			}
+		}
+		flush_period_ms: {
+			common:      false
+			description: "Controls the frequency that Vector checks for (and closes) inactive sessions."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		group_by: {
+			common:      true
+			description: "The fields identifying whose session an event belongs to."
+			required:    true
+			warnings: []
+			type: array: items: type: string: examples: ["user_id", "client_ip"]
+		}
+		inactivity_gap_secs: {
+			common:      true
+			description: "A session is closed once no events have been received for it for this long."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1800
+				unit:    "seconds"
+			}
+		}
+		last_fields: {
+			common:      true
+			description: "Fields whose values in the last event of a session are kept under `session.last`."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: examples: ["path", "status"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Clickstream"
+			configuration: {
+				group_by: ["user_id"]
+				inactivity_gap_secs: 1800
+				first_fields: ["path"]
+				last_fields: ["path"]
+			}
+			input: [
+				{log: {timestamp: "2020-10-07T12:00:00Z", user_id: "jane", path: "/"}},
+				{log: {timestamp: "2020-10-07T12:00:30Z", user_id: "jane", path: "/pricing"}},
+				{log: {timestamp: "2020-10-07T12:00:50Z", user_id: "jane", path: "/signup"}},
+			]
+			output: log: {
+				timestamp: "2020-10-07T12:00:50Z"
+				user_id:   "jane"
+				session: {
+					start:         "2020-10-07T12:00:00Z"
+					end:           "2020-10-07T12:00:50Z"
+					duration_secs: 50.0
+					event_count:   3
+					first: path: "/"
+					last: path:  "/signup"
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		sessions: {
+			title: "Sessions"
+			body: """
+				Events with the same values for the `group_by` fields belong to the same
+				session. A session is closed, and a single summary event emitted for it, when
+				no events have been received for it for `inactivity_gap_secs`, when an event's
+				timestamp is more than `inactivity_gap_secs` after the previous one, as when
+				replaying old logs, or when Vector shuts down. Durations are taken from the
+				events' timestamps, or from when they were received if they have none.
+				"""
+		}
+	}
+}
@@ -0,0 +1,99 @@
+package metadata
+
+components: transforms: size_guard: {
+	title: "Size Guard"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		action: {
+			common:      true
+			description: "What happens to events over `max_bytes`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "truncate"
+				enum: {
+					truncate: "Shorten `fields`, in order, until the event fits, and set `truncated_field` to `true`."
+					split:    "Split the first of `fields` across as many events as needed, with the rest of the event copied to each, and set `part_field` to their `index` and `count`."
+					reroute:  "Send the event to the `overflow` output, referenced as `<transform_name>.overflow`."
+				}
+			}
+		}
+		fields: {
+			common:      true
+			description: "The string fields shortened or split."
+			required:    false
+			warnings: []
+			type: array: {
+				default: ["message"]
+				items: type: string: examples: ["message", "stack_trace"]
+			}
+		}
+		max_bytes: {
+			description: "The maximum size of an event, serialized as JSON."
+			required:    true
+			warnings: []
+			type: uint: {
+				examples: [1048576]
+				unit: "bytes"
+			}
+		}
+		part_field: {
+			common:      false
+			description: "The field set to the `index` and `count` of the parts of split events."
+			required:    false
+			warnings: []
+			type: string: default: "part"
+		}
+		truncated_field: {
+			common:      false
+			description: "The field set to `true` on truncated events."
+			required:    false
+			warnings: []
+			type: string: default: "truncated"
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		oversized_events: {
+			title: "Oversized Events"
+			body: """
+				Events that still don't fit once `fields` are emptied, or whose other fields
+				leave no room for a part when splitting, are discarded.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
@@ -0,0 +1,124 @@
+package metadata
+
+components: transforms: sql: {
+	title: "SQL"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		program: {
+			runtime: {
+				name:    "SQL"
+				url:     urls.sql_select
+				version: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		query: {
+			description: "The `SELECT` statement run over the events. It's parsed when the configuration is loaded."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: [
+					"SELECT * FROM events WHERE status >= 500",
+					"SELECT service, count(*) AS errors, avg(duration) FROM events WHERE level = 'error' GROUP BY service",
+				]
+			}
+		}
+		window_secs: {
+			common:      false
+			description: "The length of the windows aggregates are computed over."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Filter and Project"
+			configuration: {
+				query: "SELECT service, request.path AS path FROM events WHERE status >= 500 AND path NOT LIKE '/health%'"
+			}
+			input: log: {
+				service: "api"
+				status:  503
+				request: path: "/users"
+			}
+			output: log: {
+				service: "api"
+				path:    "/users"
+			}
+		},
+	]
+
+	how_it_works: {
+		syntax: {
+			title: "Syntax"
+			body: """
+				Statements take the form
+				`SELECT <projections> [FROM <name>] [WHERE <condition>] [GROUP BY <fields>]`,
+				with case-insensitive keywords. The `FROM` name is ignored. Fields are paths
+				like `request.status`, and names that are keywords or hold other characters
+				are quoted with `"` or `` ` ``. Strings are quoted with `'`.
+
+				Projections are `*`, fields and aggregates, optionally renamed with `AS`.
+				Conditions combine `=`, `!=` or `<>`, `<`, `<=`, `>`, `>=`, `IS [NOT] NULL`,
+				`[NOT] IN (...)`, `[NOT] LIKE` and bare boolean fields with `AND`, `OR`,
+				`NOT` and parentheses. Strings holding numbers compare as numbers, and
+				missing fields compare as `NULL`, so only `IS NULL` matches them.
+				"""
+		}
+		projections: {
+			title: "Projections"
+			body: """
+				Without aggregates, each event matching the condition is emitted with only
+				the selected fields, or whole with `*`, in which case renamed fields are
+				copied.
+				"""
+		}
+		aggregates: {
+			title: "Aggregates"
+			body: """
+				`count`, `sum`, `avg`, `min` and `max` are computed over tumbling windows of
+				`window_secs`, emitting one event per `GROUP BY` group at the end of each
+				window, and a `timestamp`. Only grouped fields can be selected alongside
+				aggregates. Aggregates are named like `avg_duration` unless renamed, and
+				`count(*)` is named `count`. Windows without events emit nothing.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
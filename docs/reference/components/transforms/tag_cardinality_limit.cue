@@ -29,7 +29,7 @@ components: transforms: tag_cardinality_limit: {
 	}
 
 	configuration: {
-		cache_size_per_tag: {
+		cache_size_per_key: {
 			common:        false
 			description:   "The size of the cache in bytes to use to detect duplicate tags. The bigger the cache the less likely it is to have a 'false positive' or a case where we allow a new value for tag even after we have reached the configured limits."
 			relevant_when: "mode = \"probabilistic\""
@@ -60,7 +60,7 @@ components: transforms: tag_cardinality_limit: {
 			type: string: {
 				enum: {
 					exact:         "Has higher memory requirements than `probabilistic`, but never falsely outputs metrics with new tags after the limit has been hit."
-					probabilistic: "Has lower memory requirements than `exact`, but may occasionally allow metric events to pass through the transform even when they contain new tags that exceed the configured limit.  The rate at which this happens can be controlled by changing the value of `cache_size_per_tag`."
+					probabilistic: "Has lower memory requirements than `exact`, but may occasionally allow metric events to pass through the transform even when they contain new tags that exceed the configured limit.  The rate at which this happens can be controlled by changing the value of `cache_size_per_key`."
 				}
 			}
 		}
@@ -187,15 +187,15 @@ components: transforms: tag_cardinality_limit: {
 				```text
 				(number of distinct field names in the tags for your metrics * average length of
 				the field names for the tags) + (number of distinct field names in the tags of
-				-your metrics * `cache_size_per_tag`)
+				-your metrics * `cache_size_per_key`)
 				```
 
-				The `cache_size_per_tag` option controls the size of the bloom filter used
+				The `cache_size_per_key` option controls the size of the bloom filter used
 				for storing the set of acceptable values for any single key. The larger the
 				bloom filter the lower the false positive rate, which in our case means the less
 				likely we are to allow a new tag value that would otherwise violate a
 				configured limit. If you want to know the exact false positive rate for a given
-				`cache_size_per_tag` and `value_limit`, there are many free on-line bloom filter
+				`cache_size_per_key` and `value_limit`, there are many free on-line bloom filter
 				calculators that can answer this. The formula is generally presented in terms of
 				'n', 'p', 'k', and 'm' where 'n' is the number of items in the filter
 				(`value_limit` in our case), 'p' is the probability of false positives (what we
@@ -0,0 +1,94 @@
+package metadata
+
+components: transforms: throttle: {
+	title: "Throttle"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		exclude: {
+			common:      true
+			description: "The set of logical conditions to exclude events from throttling."
+			required:    false
+			warnings: []
+			type: object: configuration._conditions
+		}
+		key_field: {
+			common: false
+			description: """
+				A template rendered for each event, events are throttled separately for each
+				distinct value. If left unspecified, or if the template can't be rendered,
+				events share a single limit.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ app }}", "{{ customer_id }}"]
+			}
+		}
+		on_excess: {
+			common:      false
+			description: "What to do with events over the threshold."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "drop"
+				enum: {
+					drop:      "Drop the events."
+					summarize: "Drop the events, and at the end of the window emit one event per key with a `suppressed_count` field holding the number of events dropped, and a `key` field holding the key."
+				}
+			}
+		}
+		threshold: {
+			description: "The number of events allowed for each key per window."
+			required:    true
+			warnings: []
+			type: uint: {
+				examples: [100, 1000]
+				unit: null
+			}
+		}
+		window_secs: {
+			description: "The length of the window over which events are counted."
+			required:    true
+			warnings: []
+			type: uint: {
+				examples: [1, 60]
+				unit: "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
@@ -0,0 +1,141 @@
+package metadata
+
+components: transforms: timestamp: {
+	title: "Timestamp"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		parse: {
+			format: {
+				name:     "Timestamp"
+				url:      urls.rfc_3339
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		drop_field: {
+			common:      false
+			description: "Remove `field` once it's parsed. It's never removed when it's the same as `target_field`."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		field: {
+			common:      true
+			description: "The log field to parse."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "timestamp"
+				examples: ["time", "request.received_at"]
+			}
+		}
+		formats: {
+			common:      true
+			description: "The formats to try, in order. The first one that parses the field is used. See [Formats](#formats) for the names, and any format containing `%` is read with [`strptime` specifiers](\(urls.strptime_specifiers))."
+			required:    false
+			warnings: []
+			type: array: {
+				default: ["rfc3339", "unix"]
+				items: type: string: examples: ["rfc3339", "unix_ms", "%d/%b/%Y:%T %z"]
+			}
+		}
+		reroute_dropped: {
+			common:      false
+			description: "Send events where the field is missing or matches no format to the `<transform name>.dropped` output, instead of passing them through unchanged."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		target_field: {
+			common:      false
+			description: "The field the parsed timestamp is written to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "timestamp"
+				examples: ["timestamp", "event_time"]
+			}
+		}
+		timezone: {
+			common:      true
+			description: "The time zone of timestamps that don't include an offset, as an [IANA name](\(urls.iana_time_zones)), or `local` for the time zone of the host."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "UTC"
+				examples: ["America/New_York", "local"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Application Time"
+			configuration: {
+				field: "time"
+				formats: ["%Y-%m-%d %H:%M:%S", "unix_ms"]
+				timezone:   "Europe/Berlin"
+				drop_field: true
+			}
+			input: log: {
+				message: "Order placed"
+				time:    "2020-07-01 12:00:00"
+			}
+			output: log: {
+				message:   "Order placed"
+				timestamp: "2020-07-01T10:00:00Z"
+			}
+		},
+	]
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+
+	how_it_works: {
+		formats: {
+			title: "Formats"
+			body: """
+				Besides `strptime` formats, these named formats are supported:
+
+				* `rfc3339`: [RFC 3339](\(urls.rfc_3339)), or ISO 8601 with a `T` or space separator
+				  and no offset.
+				* `rfc2822`: RFC 2822, as used by email and HTTP headers.
+				* `unix`: seconds since the Unix epoch, with an optional fraction.
+				* `unix_ms`, `unix_us` and `unix_ns`: milliseconds, microseconds and nanoseconds
+				  since the Unix epoch.
+
+				Timestamps without an offset are read in `timezone`. Fields that are already
+				timestamps are kept as they are.
+				"""
+		}
+	}
+}
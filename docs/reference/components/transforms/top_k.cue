@@ -0,0 +1,119 @@
+package metadata
+
+components: transforms: top_k: {
+	title: "Top K"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		counters: {
+			common:      false
+			description: "How many values are counted at once. Defaults to ten times `k`. More counters give more accurate counts at the cost of memory."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				unit:    null
+			}
+		}
+		field: {
+			description: "The field whose most frequent values are tracked."
+			required:    true
+			warnings: []
+			type: string: examples: ["host", "request.path"]
+		}
+		k: {
+			common:      true
+			description: "How many values each summary lists."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    null
+			}
+		}
+		window_secs: {
+			common:      true
+			description: "How often a summary is emitted. Counts are reset after each summary."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Noisiest Hosts"
+			configuration: {
+				field: "host"
+				k:     2
+			}
+			input: [
+				{log: {host: "web-1"}},
+				{log: {host: "web-2"}},
+				{log: {host: "web-1"}},
+				{log: {host: "db-1"}},
+				{log: {host: "web-1"}},
+			]
+			output: [
+				{log: {
+					timestamp: "2020-11-01T12:01:00Z"
+					field:     "host"
+					window: {
+						start: "2020-11-01T12:00:00Z"
+						end:   "2020-11-01T12:01:00Z"
+					}
+					total: 5
+					top: [
+						{value: "web-1", count: 3},
+						{value: "web-2", count: 1},
+					]
+				}},
+			]
+		},
+	]
+
+	how_it_works: {
+		accuracy: {
+			title: "Accuracy"
+			body: """
+				Values are counted with a [Misra-Gries summary](\(urls.misra_gries)), which uses a
+				fixed amount of memory however many distinct values there are. Any value making up
+				more than `1 / (counters + 1)` of the events in a window is guaranteed to be
+				counted, and its count is low by at most `total / (counters + 1)`. Input events
+				are consumed; only the summaries are emitted.
+				"""
+		}
+	}
+}
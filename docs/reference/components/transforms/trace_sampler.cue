@@ -0,0 +1,135 @@
+package metadata
+
+components: transforms: trace_sampler: {
+	title: "Trace Sampler"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: [
+			"""
+				Vector doesn't have a dedicated trace data type yet, so spans are log events with
+				a trace id field, as produced by sources decoding spans into logs.
+				""",
+		]
+	}
+
+	configuration: {
+		decision_wait_secs: {
+			common:      true
+			description: "How long to buffer a trace's spans, from its first span, before deciding whether to keep it. Spans arriving after the decision follow it for as long again."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+		duration_field: {
+			common:      false
+			description: "The span field holding its duration in milliseconds."
+			required:    false
+			warnings: []
+			type: string: default: "duration_ms"
+		}
+		error_field: {
+			common:      false
+			description: "Traces with a span where this field is `true` are kept."
+			required:    false
+			warnings: []
+			type: string: default: "error"
+		}
+		flush_period_ms: {
+			common:      false
+			description: "How often to check for traces to decide."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		keep_when: {
+			common:      false
+			description: "Traces with a span matching this condition are kept."
+			required:    false
+			warnings: []
+			type: object: configuration._conditions
+		}
+		latency_threshold_ms: {
+			common:      true
+			description: "Traces with a span lasting at least this long are kept."
+			required:    false
+			warnings: []
+			type: float: {
+				default: null
+				examples: [1000.0]
+			}
+		}
+		max_traces: {
+			common:      false
+			description: "The most traces to buffer at once. Past it, the oldest traces are decided early."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 100000
+				unit:    null
+			}
+		}
+		sample_ratio: {
+			common:      true
+			description: "The fraction of the remaining traces to keep, between 0 and 1."
+			required:    false
+			warnings: []
+			type: float: default: 0.1
+		}
+		trace_id_field: {
+			common:      true
+			description: "The span field holding its trace id. Events without it are passed through."
+			required:    false
+			warnings: []
+			type: string: default: "trace_id"
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		decisions: {
+			title: "Decisions"
+			body: """
+				A trace is kept whole if any of its spans is an error, is slower than
+				`latency_threshold_ms`, or matches `keep_when`. The rest are kept with probability
+				`sample_ratio`, decided by a hash of the trace id so that every Vector instance
+				makes the same decision for a trace. Kept spans get a `sampling_reason` field
+				and a `sample_rate` field, which is `1` unless the trace was sampled.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		traces_decided_total: components.sources.internal_metrics.output.metrics.traces_decided_total
+	}
+}
@@ -0,0 +1,122 @@
+package metadata
+
+components: transforms: unit_convert: {
+	title: "Unit Convert"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		conversions: {
+			description: "The conversions. Each metric is converted by the first conversion whose `name` matches it, and passed on unchanged if none does."
+			required:    true
+			warnings: []
+			type: array: items: type: object: options: {
+				from: {
+					description: "The unit the metrics are in. Units can also be given by their abbreviations, such as `ms` or `MiB`."
+					required:    true
+					warnings: []
+					type: string: enum: {
+						bits:         "Data, 1/8 byte."
+						bytes:        "Data."
+						kilobytes:    "Data, 1000 bytes."
+						megabytes:    "Data, 1000² bytes."
+						gigabytes:    "Data, 1000³ bytes."
+						terabytes:    "Data, 1000⁴ bytes."
+						kibibytes:    "Data, 1024 bytes."
+						mebibytes:    "Data, 1024² bytes."
+						gibibytes:    "Data, 1024³ bytes."
+						tebibytes:    "Data, 1024⁴ bytes."
+						nanoseconds:  "Time."
+						microseconds: "Time."
+						milliseconds: "Time."
+						seconds:      "Time."
+						minutes:      "Time."
+						hours:        "Time."
+						days:         "Time."
+						celsius:      "Temperature."
+						fahrenheit:   "Temperature."
+						kelvin:       "Temperature."
+						ratio:        "A fraction, where 1 is the whole."
+						percent:      "A fraction, where 100 is the whole."
+					}
+				}
+				name: {
+					description: "A regular expression matching the whole name of the metrics to convert."
+					required:    true
+					warnings: []
+					type: string: examples: ["memory_used", "(.*)_ms"]
+				}
+				rename: {
+					common:      false
+					description: "The new name of the converted metrics, in which `$1` or `${1}` is replaced by the capture groups of `name`."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["${1}_seconds"]
+					}
+				}
+				to: {
+					description: "The unit the metrics are converted to, of the same kind as `from`."
+					required:    true
+					warnings: []
+					type: string: examples: ["seconds", "mebibytes", "celsius"]
+				}
+			}
+		}
+		unit_tag: {
+			common:      false
+			description: "The tag holding the metrics' unit. Metrics tagged with another unit than `from` aren't converted, and converted metrics are tagged with the new unit. Set to `\"\"` to ignore units in tags."
+			required:    false
+			warnings: []
+			type: string: default: "unit"
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          false
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		values: {
+			title: "Converted Values"
+			body: """
+				Gauges, distribution samples, histogram buckets and summary quantiles are
+				converted as values. Counters, incremental gauges and the sums of histograms
+				and summaries are converted as differences or totals, so conversions with an
+				offset, like Celsius to Fahrenheit, stay consistent.
+				"""
+		}
+	}
+}
@@ -0,0 +1,120 @@
+package metadata
+
+components: transforms: url_parser: {
+	title: "URL Parser"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		parse: {
+			format: {
+				name:     "URL"
+				url:      urls.url
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		drop_field: {
+			common:      true
+			description: "If the specified `field` should be dropped (removed) after parsing. If parsing fails, the field will not be removed, irrespective of this setting."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		field: {
+			description: "The log field holding the URL. Relative URLs, such as the request path of access logs, are supported."
+			required:    true
+			warnings: []
+			type: string: examples: ["request", "http.url"]
+		}
+		redact_query_keys: {
+			common:      false
+			description: "Query parameters whose values are replaced with `[REDACTED]`, in the parsed `query` and in `field` when it's kept."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: examples: ["token", "password", "api_key"]
+			}
+		}
+		target_field: {
+			common:      true
+			description: "The log field the parsed URL components are inserted under."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "url"
+				examples: ["url", "http.request"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Access Log Request"
+			configuration: {
+				field: "request"
+				redact_query_keys: ["token"]
+			}
+			input: log: {
+				request: "https://example.com/search?q=vector&tag=a&tag=b&token=s3cret"
+			}
+			output: log: {
+				url: {
+					scheme: "https"
+					host:   "example.com"
+					port:   443
+					path:   "/search"
+					query: {
+						q: "vector"
+						tag: ["a", "b"]
+						token: "[REDACTED]"
+					}
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		fields: {
+			title: "Parsed Fields"
+			body: """
+				The `scheme`, `host`, `port`, `user`, `path`, `fragment` and `query` fields are
+				set when present in the URL. The port defaults to the scheme's well known port.
+				Query parameters are percent-decoded, and repeated parameters are collected
+				into an array. Relative URLs only have the `path`, `query` and `fragment`
+				fields.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
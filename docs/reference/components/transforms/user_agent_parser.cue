@@ -0,0 +1,134 @@
+package metadata
+
+components: transforms: user_agent_parser: {
+	title: "User Agent Parser"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		enrich: {
+			from: service: {
+				name:     "uap-core"
+				url:      urls.uap_core
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		detect_bots: {
+			common:      false
+			description: "If `true`, an `is_bot` field is added, set to `true` for crawlers and other automated clients."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		drop_field: {
+			common:      true
+			description: "If the specified `field` should be dropped (removed) after parsing."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		field: {
+			description: "The log field holding the user agent string."
+			required:    true
+			warnings: []
+			type: string: examples: ["agent", "http.user_agent"]
+		}
+		regexes: {
+			description: "The path to uap-core's `regexes.yaml`."
+			required:    true
+			warnings: []
+			type: string: examples: ["/etc/vector/regexes.yaml"]
+		}
+		target_field: {
+			common:      true
+			description: "The log field the parsed `browser`, `os` and `device` fields are inserted under."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "user_agent"
+				examples: ["user_agent", "client.agent"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Browser"
+			configuration: {
+				field:       "agent"
+				regexes:     "/etc/vector/regexes.yaml"
+				detect_bots: true
+			}
+			input: log: {
+				agent: "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36"
+			}
+			output: log: {
+				user_agent: {
+					is_bot: false
+					browser: {
+						family:  "Chrome"
+						major:   "87"
+						minor:   "0"
+						patch:   "4280"
+						version: "87.0.4280"
+					}
+					os: {
+						family:  "Mac OS X"
+						major:   "10"
+						minor:   "15"
+						patch:   "7"
+						version: "10.15.7"
+					}
+					device: {
+						family: "Mac"
+						brand:  "Apple"
+						model:  "Mac"
+					}
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		regexes: {
+			title: "Regexes"
+			body: """
+				User agents are matched against the regular expressions maintained by the
+				[uap-core](\(urls.uap_core)) project, which must be downloaded separately.
+				Agents no expression matches have the family `Other`, and the version
+				fields are only set when known.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -0,0 +1,171 @@
+package metadata
+
+components: transforms: window: {
+	title: "Window"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		aggregates: {
+			description: "The aggregates to compute for each window, keyed by the field they are written to."
+			required:    true
+			warnings: []
+			type: object: options: {
+				"*": {
+					description: "An aggregate."
+					required:    true
+					warnings: []
+					type: object: options: {
+						field: {
+							common:      true
+							description: "The field to aggregate. Required by every function but `count`. Values that aren't numbers are ignored by the numeric functions."
+							required:    false
+							warnings: []
+							type: string: {
+								default: null
+								examples: ["duration_ms"]
+							}
+						}
+						function: {
+							description: "The aggregate function."
+							required:    true
+							warnings: []
+							type: string: enum: {
+								count:          "The number of events."
+								sum:            "The sum of `field`."
+								avg:            "The mean of `field`."
+								min:            "The smallest value of `field`."
+								max:            "The largest value of `field`."
+								distinct_count: "The number of distinct values of `field`."
+							}
+						}
+					}
+				}
+			}
+		}
+		allowed_lateness_secs: {
+			common:      false
+			description: "How long to wait past the end of a window for late events before emitting it. Events arriving later are dropped from that window."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 0
+				unit:    "seconds"
+			}
+		}
+		flush_period_ms: {
+			common:      false
+			description: "How often to check for windows to emit."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		group_by: {
+			common:      true
+			description: "An ordered list of fields to group events by. Each group has its own windows, and its values are included in the aggregate events."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: examples: ["service", "status"]
+			}
+		}
+		slide_secs: {
+			common:      false
+			description: "How far apart windows start. Defaults to `window_secs`, giving tumbling windows. Smaller values give overlapping sliding windows, and each event is counted in every window it falls in."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				unit:    "seconds"
+			}
+		}
+		window_secs: {
+			common:      true
+			description: "The length of each window."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Errors Per Minute"
+			configuration: {
+				group_by: ["service"]
+				aggregates: {
+					errors: {function: "count"}
+					slowest: {function: "max", field: "duration_ms"}
+				}
+			}
+			input: [
+				{log: {timestamp: "2020-11-01T12:00:05Z", service: "api", duration_ms: 120}},
+				{log: {timestamp: "2020-11-01T12:00:40Z", service: "api", duration_ms: 800}},
+			]
+			output: [
+				{log: {
+					timestamp: "2020-11-01T12:01:00Z"
+					service:   "api"
+					window: {
+						start: "2020-11-01T12:00:00Z"
+						end:   "2020-11-01T12:01:00Z"
+					}
+					errors:  2
+					slowest: 800
+				}},
+			]
+		},
+	]
+
+	how_it_works: {
+		event_time: {
+			title: "Event Time"
+			body: """
+				Events are assigned to windows by their `timestamp` field, or by the time they
+				arrive if they don't have one. Windows are aligned to the Unix epoch and are
+				emitted once the clock passes their end plus `allowed_lateness_secs`, and all
+				open windows are emitted when Vector shuts down.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		late_events_dropped_total: components.sources.internal_metrics.output.metrics.late_events_dropped_total
+		windows_flushed_total:     components.sources.internal_metrics.output.metrics.windows_flushed_total
+	}
+}
@@ -17,4 +17,194 @@ configuration: {
 			examples: ["/var/lib/vector", "/var/local/lib/vector/", "/home/user/vector/"]
 		}
 	}
+
+	enrichment_tables: {
+		common: false
+		description: """
+			Tables of data, loaded from files, which can be looked up from
+			the `remap` transform with the `get_enrichment_table_record` and
+			`find_enrichment_table_records` functions. Tables are reloaded
+			when their file changes.
+			"""
+		required: false
+		type: object: {
+			examples: [
+				{
+					users: {
+						type: "file"
+						path: "/etc/vector/users.csv"
+						schema: joined_at: "timestamp"
+					}
+				},
+			]
+			options: {
+				"*": {
+					description: "The name of the table, as referred to by the lookup functions."
+					required:    true
+					type: object: options: {
+						type: {
+							description: "The kind of table."
+							required:    true
+							type: string: enum: {
+								file:  "A CSV file."
+								geoip: "A MaxMind GeoIP2 or GeoLite2 database, queried by the `ip` column."
+							}
+						}
+						path: {
+							description: "The path of the file holding the table."
+							required:    true
+							type: string: examples: ["/etc/vector/users.csv", "/path/to/GeoLite2-City.mmdb"]
+						}
+						encoding: {
+							common:        false
+							description:   "How a `file` table is encoded."
+							relevant_when: "type = \"file\""
+							required:      false
+							type: object: options: {
+								type: {
+									description: "The encoding of the file."
+									required:    true
+									type: string: enum: csv: "Comma (or otherwise) separated values."
+								}
+								delimiter: {
+									common:      false
+									description: "The character separating columns."
+									required:    false
+									type: string: default: ","
+								}
+								include_headers: {
+									common:      false
+									description: "Whether the first line holds the column names. Otherwise columns are named by their position, starting at `0`."
+									required:    false
+									type: bool: default: true
+								}
+							}
+						}
+						schema: {
+							common:        false
+							description:   "Types to convert the columns of a `file` table to, columns that aren't listed are kept as strings. Uses the same types as the `coercer` transform, such as `integer` or `timestamp|%Y-%m-%d`."
+							relevant_when: "type = \"file\""
+							required:      false
+							type: object: {
+								examples: [{id: "integer", joined_at: "timestamp"}]
+								options: {}
+							}
+						}
+						reload_interval_secs: {
+							common:      false
+							description: "How often to check if the file changed, and reload it if so. Set to `0` to disable reloading."
+							required:    false
+							type: uint: {
+								default: 60
+								unit:    "seconds"
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	secret: {
+		common: false
+		description: """
+			Backends that secrets are retrieved from while loading the
+			configuration, so they don't have to be written in it. Every
+			`SECRET[<backend>.<key>]` in a string value of the configuration
+			is replaced with the secret of the key, on startup and on every
+			reload. Secrets need no escaping, and references in comments or
+			in the `secret` section itself are left alone.
+			"""
+		required: false
+		type: object: {
+			examples: [
+				{
+					vault: {
+						type:    "vault"
+						address: "https://vault.example.com:8200"
+						token:   "${VAULT_TOKEN}"
+						path:    "vector/sinks"
+					}
+				},
+			]
+			options: {
+				"*": {
+					description: "The name of the backend, as referred to by secret references."
+					required:    true
+					type: object: options: {
+						type: {
+							description: "The kind of backend."
+							required:    true
+							type: string: enum: {
+								aws_secrets_manager: "The key/value pairs of a secret of AWS Secrets Manager."
+								exec:                "A command sent the keys on its stdin as `{\"version\": \"1.0\", \"secrets\": [...]}`, answering on its stdout with `{\"<key>\": {\"value\": \"...\", \"error\": null}}`."
+								file:                "A JSON file holding an object of keys and secrets."
+								vault:               "A secret of the key/value version 2 secrets engine of HashiCorp Vault."
+							}
+						}
+						command: {
+							description:   "The command to run and its arguments."
+							relevant_when: "type = \"exec\""
+							required:      true
+							type: array: items: type: string: examples: ["/usr/local/bin/vector-secrets", "--stage=prod"]
+						}
+						timeout_secs: {
+							common:        false
+							description:   "How long the command may take to answer."
+							relevant_when: "type = \"exec\""
+							required:      false
+							type: uint: {
+								default: 5
+								unit:    "seconds"
+							}
+						}
+						path: {
+							description:   "The path of the JSON file for `file` backends, or of the secret within the mount for `vault` backends."
+							relevant_when: "type = \"file\" or type = \"vault\""
+							required:      true
+							type: string: examples: ["/etc/vector/secrets.json", "vector/sinks"]
+						}
+						address: {
+							description:   "The address of the Vault server."
+							relevant_when: "type = \"vault\""
+							required:      true
+							type: string: examples: ["https://vault.example.com:8200"]
+						}
+						token: {
+							description:   "The token to authenticate to Vault with, best taken from an environment variable."
+							relevant_when: "type = \"vault\""
+							required:      true
+							type: string: examples: ["${VAULT_TOKEN}"]
+						}
+						mount: {
+							common:        false
+							description:   "The path the key/value secrets engine is mounted at."
+							relevant_when: "type = \"vault\""
+							required:      false
+							type: string: default: "secret"
+						}
+						secret_id: {
+							description:   "The name or ARN of the secret."
+							relevant_when: "type = \"aws_secrets_manager\""
+							required:      true
+							type: string: examples: ["vector/sinks"]
+						}
+						region: {
+							description:   "The AWS region of the secret."
+							relevant_when: "type = \"aws_secrets_manager\""
+							required:      true
+							type: string: examples: ["us-east-1"]
+						}
+						assume_role: {
+							common:        false
+							description:   "The ARN of an IAM role to assume for retrieving the secret."
+							relevant_when: "type = \"aws_secrets_manager\""
+							required:      false
+							type: string: default: null
+						}
+					}
+				}
+			}
+		}
+	}
 }
@@ -0,0 +1,33 @@
+package metadata
+
+remap: functions: find_enrichment_table_records: {
+	arguments: remap.functions.get_enrichment_table_record.arguments
+	return: ["array"]
+	category: "enrichment"
+	description: #"""
+		Returns every row of the enrichment table where `column` equals
+		`value`, as an array of maps. The array is empty if no row matches.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			configuration: {
+				"enrichment_tables.users.type": "file"
+				"enrichment_tables.users.path": "/etc/vector/users.csv"
+			}
+			input: {
+				team: "platform"
+			}
+			source: #"""
+				.members = find_enrichment_table_records("users", "team", .team)
+				"""#
+			output: {
+				team: "platform"
+				members: [
+					{id: "42", name: "Jane Doe", team: "platform"},
+					{id: "43", name: "John Doe", team: "platform"},
+				]
+			}
+		},
+	]
+}
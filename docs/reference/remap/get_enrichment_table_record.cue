@@ -0,0 +1,74 @@
+package metadata
+
+remap: functions: get_enrichment_table_record: {
+	arguments: [
+		{
+			name:        "table"
+			description: "The name of the enrichment table to look up, as configured in `enrichment_tables`."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "column"
+			description: "The column to match."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "value"
+			description: "The value the column must be equal to."
+			required:    true
+			type: ["string", "integer", "float", "boolean", "timestamp"]
+		},
+		{
+			name:        "date_column"
+			description: "A column holding timestamps that must fall between `from` and `to`."
+			required:    false
+			type: ["string"]
+		},
+		{
+			name:        "from"
+			description: "The start of the date range, inclusive. Required with `date_column`."
+			required:    false
+			type: ["timestamp"]
+		},
+		{
+			name:        "to"
+			description: "The end of the date range, inclusive. Required with `date_column`."
+			required:    false
+			type: ["timestamp"]
+		},
+	]
+	return: ["map"]
+	category: "enrichment"
+	description: #"""
+		Returns the single row of the enrichment table where `column` equals
+		`value` as a map of column names to values.
+
+		An error is raised if no row, or more than one row, matches. The table
+		must be configured, otherwise the program fails to compile.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			configuration: {
+				"enrichment_tables.users.type": "file"
+				"enrichment_tables.users.path": "/etc/vector/users.csv"
+			}
+			input: {
+				user_id: "42"
+			}
+			source: #"""
+				.user = get_enrichment_table_record("users", "id", .user_id)
+				"""#
+			output: {
+				user_id: "42"
+				user: {
+					id:   "42"
+					name: "Jane Doe"
+					team: "platform"
+				}
+			}
+		},
+	]
+}
@@ -0,0 +1,8 @@
+package metadata
+
+services: opensearch: {
+	name:     "OpenSearch"
+	thing:    "an \(name) cluster"
+	url:      urls.opensearch
+	versions: null
+}
@@ -3,18 +3,24 @@ package metadata
 urls: {
 	adaptive_request_concurrency_post:                        "/blog/adaptive-request-concurrency/"
 	amazon_linux:                                             "https://aws.amazon.com/amazon-linux-ami/"
+	amqp:                                                     "https://www.amqp.org/"
 	apache:                                                   "https://httpd.apache.org/"
 	apache_extended_status:                                   "https://httpd.apache.org/docs/current/mod/core.html#extendedstatus"
 	apache_install:                                           "https://httpd.apache.org/docs/current/install.html"
 	apache_mod_status:                                        "http://httpd.apache.org/docs/current/mod/mod_status.html"
+	apache_parquet:                                           "https://parquet.apache.org/"
 	apt:                                                      "https://en.wikipedia.org/wiki/APT_(software)"
 	arm:                                                      "https://en.wikipedia.org/wiki/ARM_architecture"
+	aws_amp:                                                  "https://aws.amazon.com/prometheus/"
 	aws_arm_g2_announcement:                                  "https://aws.amazon.com/about-aws/whats-new/2019/12/announcing-new-amazon-ec2-m6g-c6g-and-r6g-instances-powered-by-next-generation-arm-based-aws-graviton2-processors/"
 	aws_athena:                                               "https://aws.amazon.com/athena/"
 	aws_athena_console:                                       "https://console.aws.amazon.com/athena/home"
 	aws_access_keys:                                          "https://docs.aws.amazon.com/IAM/latest/UserGuide/id_credentials_access-keys.html"
 	aws_canonical_user_id:                                    "https://docs.aws.amazon.com/general/latest/gr/acct-identifiers.html#FindingCanonicalId"
+	aws_cloudwatch_logs_retention:                            "https://docs.aws.amazon.com/AmazonCloudWatchLogs/latest/APIReference/API_PutRetentionPolicy.html"
+	aws_cloudwatch_logs_sequence_token:                       "https://docs.aws.amazon.com/AmazonCloudWatchLogs/latest/APIReference/API_PutLogEvents.html"
 	aws_cloudwatch_logs_sink_source:                          "https://github.com/timberio/vector/blob/master/src/sinks/aws_cloudwatch_logs/"
+	aws_cloudwatch_metrics_emf:                               "https://docs.aws.amazon.com/AmazonCloudWatch/latest/monitoring/CloudWatch_Embedded_Metric_Format_Specification.html"
 	aws_ec2_instance_metadata:                                "https://docs.aws.amazon.com/AWSEC2/latest/UserGuide/ec2-instance-metadata.html"
 	aws_ecs:                                                  "https://aws.amazon.com/ecs/"
 	aws_ecs_task_metadata:                                    "https://docs.aws.amazon.com/AmazonECS/latest/developerguide/task-metadata-endpoint.html"
@@ -38,6 +44,7 @@ urls: {
 	aws_iam:                                                  "https://docs.aws.amazon.com/IAM/latest/UserGuide/introduction.html"
 	aws_iam_role:                                             "https://docs.aws.amazon.com/IAM/latest/UserGuide/id_roles.html"
 	aws_imds_v1_security_problems:                            "https://aws.amazon.com/blogs/security/defense-in-depth-open-firewalls-reverse-proxies-ssrf-vulnerabilities-ec2-instance-metadata-service/"
+	aws_kinesis_aggregation:                                  "https://docs.aws.amazon.com/streams/latest/dev/kinesis-kpl-concepts.html#kinesis-kpl-concepts-aggretation"
 	aws_kinesis_firehose:                                     "https://aws.amazon.com/kinesis/data-firehose/"
 	aws_kinesis_firehose_http_protocol:                       "https://docs.aws.amazon.com/firehose/latest/dev/create-destination.html#create-destination-http"
 	aws_firehose_http_request_spec:                           "https://docs.aws.amazon.com/firehose/latest/dev/httpdeliveryrequestresponse.html"
@@ -49,6 +56,9 @@ urls: {
 	aws_kinesis_streams_api:                                  "https://docs.aws.amazon.com/kinesis/latest/APIReference/API_PutRecords.html"
 	aws_kinesis_streams_service_limits:                       "https://docs.aws.amazon.com/streams/latest/dev/service-sizes-and-limits.html"
 	aws_kinesis_split_shards:                                 "https://docs.aws.amazon.com/streams/latest/dev/kinesis-using-sdk-java-resharding-split.html"
+	aws_kms:                                                  "https://aws.amazon.com/kms/"
+	aws_kms_data_keys:                                        "https://docs.aws.amazon.com/kms/latest/developerguide/concepts.html#data-keys"
+	aws_opensearch:                                           "https://aws.amazon.com/opensearch-service/"
 	aws_regions:                                              "https://docs.aws.amazon.com/AmazonRDS/latest/UserGuide/Concepts.RegionsAndAvailabilityZones.html"
 	aws_s3:                                                   "https://aws.amazon.com/s3/"
 	aws_s3_acl:                                               "https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-overview.html"
@@ -62,8 +72,15 @@ urls: {
 	aws_s3_sse:                                               "https://docs.aws.amazon.com/AmazonS3/latest/dev/UsingServerSideEncryption.html"
 	aws_s3_storage_classes:                                   "https://aws.amazon.com/s3/storage-classes/"
 	aws_s3_tags:                                              "https://docs.aws.amazon.com/AmazonS3/latest/user-guide/add-object-tags.html"
+	aws_sigv4:                                                "https://docs.aws.amazon.com/general/latest/gr/signature-version-4.html"
+	aws_sns:                                                  "https://aws.amazon.com/sns/"
+	aws_sns_api:                                              "https://docs.aws.amazon.com/sns/latest/api/API_Publish.html"
+	aws_sns_fifo:                                             "https://docs.aws.amazon.com/sns/latest/dg/sns-fifo-topics.html"
+	aws_sns_message_attributes:                               "https://docs.aws.amazon.com/sns/latest/dg/sns-message-attributes.html"
 	aws_sqs:                                                  "https://aws.amazon.com/sqs/"
 	aws_sqs_api:                                              "https://docs.aws.amazon.com/AWSSimpleQueueService/latest/APIReference/Welcome.html"
+	aws_sqs_fifo:                                             "https://docs.aws.amazon.com/AWSSimpleQueueService/latest/SQSDeveloperGuide/FIFO-queues.html"
+	aws_sqs_message_attributes:                               "https://docs.aws.amazon.com/AWSSimpleQueueService/latest/SQSDeveloperGuide/sqs-message-metadata.html"
 	azure_monitor:                                            "https://azure.microsoft.com/en-us/services/monitor/"
 	azure_monitor_logs_endpoints:                             "https://docs.microsoft.com/en-us/rest/api/monitor/"
 	basic_auth:                                               "https://en.wikipedia.org/wiki/Basic_access_authentication"
@@ -72,21 +89,28 @@ urls: {
 	centos:                                                   "https://www.centos.org/"
 	cgroups_limit_resources:                                  "https://the.binbashtheory.com/control-resources-cgroups/"
 	clickhouse:                                               "https://clickhouse.yandex/"
+	clickhouse_async_insert:                                  "https://clickhouse.com/docs/en/operations/settings/settings/#async-insert"
+	clickhouse_formats:                                       "https://clickhouse.com/docs/en/interfaces/formats/"
 	clickhouse_http:                                          "https://clickhouse.yandex/docs/en/interfaces/http/"
 	cloudsmith:                                               "https://cloudsmith.io/~timber/repos/vector/packages/"
 	console:                                                  "https://en.wikipedia.org/wiki/System_console"
 	conventional_commits:                                     "https://www.conventionalcommits.org"
 	contributing:                                             "https://github.com/timberio/vector/blob/master/CONTRIBUTING.md#setup"
 	crc:                                                      "https://en.wikipedia.org/wiki/Cyclic_redundancy_check"
+	csv:                                                      "https://tools.ietf.org/html/rfc4180"
 	cue:                                                      "https://cuelang.org/"
 	datadog:                                                  "https://www.datadoghq.com"
 	datadog_distribution:                                     "https://docs.datadoghq.com/developers/metrics/types/?tab=distribution#definition"
+	datadog_distribution_metrics:                             "https://docs.datadoghq.com/metrics/distributions/"
 	datadog_logs:                                             "https://docs.datadoghq.com/logs/"
 	datadog_logs_endpoints:                                   "https://docs.datadoghq.com/logs/log_collection/?tab=http#datadog-logs-endpoints"
+	datadog_logs_v2:                                          "https://docs.datadoghq.com/api/latest/logs/#send-logs"
 	datadog_metrics:                                          "https://docs.datadoghq.com/metrics/"
 	datadog_metrics_endpoints:                                "https://docs.datadoghq.com/api/v1/metrics/"
+	ddsketch:                                                 "https://www.vldb.org/pvldb/vol12/p2195-masson.pdf"
 	debian:                                                   "https://www.debian.org/"
 	default_configuration:                                    "https://github.com/timberio/vector/blob/master/config/vector.toml"
+	discord_webhooks:                                         "https://discord.com/developers/docs/resources/webhook"
 	docker:                                                   "https://www.docker.com/"
 	docker_alpine:                                            "https://hub.docker.com/_/alpine"
 	docker_cli:                                               "https://docs.docker.com/engine/reference/commandline/cli/"
@@ -111,26 +135,40 @@ urls: {
 	dogstatsd:                                                "https://docs.datadoghq.com/developers/dogstatsd/?tab=hostagent"
 	dpkg:                                                     "https://wiki.debian.org/dpkg"
 	dry_code:                                                 "https://en.wikipedia.org/wiki/Don%27t_repeat_yourself"
+	ebpf:                                                     "https://ebpf.io/"
 	cidr:                                                     "https://en.wikipedia.org/wiki/Classless_Inter-Domain_Routing"
 	elasticsearch:                                            "https://www.elastic.co/products/elasticsearch"
+	elasticsearch_api_key:                                    "https://www.elastic.co/guide/en/elasticsearch/reference/current/security-api-create-api-key.html"
 	elasticsearch_bulk:                                       "https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-bulk.html"
+	elasticsearch_data_streams:                               "https://www.elastic.co/guide/en/elasticsearch/reference/current/data-streams.html"
 	elasticsearch_id_field:                                   "https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping-id-field.html"
 	elasticsearch_id_performance:                             "https://www.elastic.co/guide/en/elasticsearch/reference/master/tune-for-indexing-speed.html#_use_auto_generated_ids"
 	elasticsearch_ignore_malformed:                           "https://www.elastic.co/guide/en/elasticsearch/reference/current/ignore-malformed.html"
+	elasticsearch_ilm:                                        "https://www.elastic.co/guide/en/elasticsearch/reference/current/index-lifecycle-management.html"
 	endler_dev:                                               "https://endler.dev/"
 	etsy:                                                     "https://www.etsy.com"
 	event_proto:                                              "https://github.com/timberio/vector/blob/master/proto/event.proto"
+	ewma:                                                     "https://en.wikipedia.org/wiki/Moving_average#Exponentially_weighted_moving_variance_and_standard_deviation"
 	exit_codes:                                               "https://docs.rs/exitcode/1.1.2/exitcode/#constants"
 	externally_tagged_representation:                         "https://serde.rs/enum-representations.html#externally-tagged"
 	file:                                                     "https://en.wikipedia.org/wiki/Computer_file"
 	file_system:                                              "https://en.wikipedia.org/wiki/File_system"
 	freebsd:                                                  "https://www.freebsd.org/"
+	gcp_application_default_credentials:                      "https://cloud.google.com/docs/authentication/application-default-credentials"
 	gcp_authentication:                                       "https://cloud.google.com/docs/authentication/"
 	gcp_authentication_api_key:                               "https://cloud.google.com/docs/authentication/api-keys"
 	gcp_authentication_server_to_server:                      "https://cloud.google.com/docs/authentication/production"
 	gcp_authentication_service_account:                       "https://cloud.google.com/docs/authentication/production#obtaining_and_providing_service_account_credentials_manually"
+	gcp_bigquery:                                             "https://cloud.google.com/bigquery"
+	gcp_bigquery_schemas:                                     "https://cloud.google.com/bigquery/docs/schemas"
+	gcp_bigquery_storage_write_api:                           "https://cloud.google.com/bigquery/docs/write-api"
+	gcp_cloud_monitoring:                                     "https://cloud.google.com/monitoring"
+	gcp_cloud_monitoring_rest:                                "https://cloud.google.com/monitoring/api/ref_v3/rest"
 	gcp_cloud_storage:                                        "https://cloud.google.com/storage"
+	gcp_custom_metrics:                                       "https://cloud.google.com/monitoring/custom-metrics"
 	gcp_folders:                                              "https://cloud.google.com/resource-manager/docs/creating-managing-folders"
+	gcp_monitored_resources:                                  "https://cloud.google.com/monitoring/api/resources"
+	gcp_monitoring_quotas:                                    "https://cloud.google.com/monitoring/quotas"
 	gcp_pubsub:                                               "https://cloud.google.com/pubsub/"
 	gcp_pubsub_rest:                                          "https://cloud.google.com/pubsub/docs/reference/rest/"
 	gcp_projects:                                             "https://cloud.google.com/resource-manager/docs/creating-managing-projects"
@@ -138,8 +176,11 @@ urls: {
 	gcp_stackdriver:                                          "https://cloud.google.com/products/operations"
 	gcp_stackdriver_logging:                                  "https://cloud.google.com/logging/docs/reference/v2/rest/"
 	gcp_stackdriver_logging_rest:                             "https://cloud.google.com/logging/"
+	gcp_stackdriver_quotas:                                   "https://cloud.google.com/logging/quotas"
 	gcp_stackdriver_severity:                                 "https://cloud.google.com/logging/docs/reference/v2/rest/v2/LogEntry#logseverity"
+	gcp_workload_identity:                                    "https://cloud.google.com/kubernetes-engine/docs/how-to/workload-identity"
 	gcp_xml_interface:                                        "https://cloud.google.com/storage/docs/xml-api/overview"
+	gcs_cmek:                                                 "https://cloud.google.com/storage/docs/encryption/customer-managed-keys"
 	gcs_predefined_acl:                                       "https://cloud.google.com/storage/docs/access-control/lists#predefined-acl"
 	gcs_storage_classes:                                      "https://cloud.google.com/storage/docs/storage-classes"
 	gcs_custom_metadata:                                      "https://cloud.google.com/storage/docs/metadata#custom-metadata"
@@ -167,6 +208,8 @@ urls: {
 	http_server:                                              "https://en.wikipedia.org/wiki/Web_server"
 	humio:                                                    "https://humio.com"
 	humio_hec:                                                "https://docs.humio.com/integrations/data-shippers/hec/"
+	humio_parsers:                                            "https://docs.humio.com/parsers/"
+	humio_structured_api:                                     "https://docs.humio.com/api/ingest/#structured-data"
 	iam_instance_profile:                                     "https://docs.aws.amazon.com/IAM/latest/UserGuide/id_roles_use_switch-role-ec2_instance-profiles.html"
 	iana_time_zone_format:                                    "https://en.wikipedia.org/wiki/Tz_database#Names_of_time_zones"
 	iana_time_zones:                                          "https://en.wikipedia.org/wiki/List_of_tz_database_time_zones"
@@ -185,8 +228,11 @@ urls: {
 	journald:                                                 "https://www.freedesktop.org/software/systemd/man/systemd-journald.service.html"
 	json:                                                     "https://en.wikipedia.org/wiki/JSON"
 	json:                                                     "https://en.wikipedia.org/wiki/JSON"
+	json_schema:                                              "https://json-schema.org/"
 	json_types:                                               "https://en.wikipedia.org/wiki/JSON#Data_types_and_syntax"
 	jsonnet:                                                  "https://jsonnet.org/"
+	jwks:                                                     "https://tools.ietf.org/html/rfc7517"
+	jwt:                                                      "https://tools.ietf.org/html/rfc7519"
 	kafka:                                                    "https://kafka.apache.org/"
 	kafka_partitioning_docs:                                  "https://cwiki.apache.org/confluence/display/KAFKA/A+Guide+To+The+Kafka+Protocol#AGuideToTheKafkaProtocol-Partitioningandbootstrapping"
 	kafka_protocol:                                           "https://kafka.apache.org/protocol"
@@ -243,16 +289,22 @@ urls: {
 	maxmind_geoip2_isp:                                       "https://www.maxmind.com/en/geoip2-isp-database"
 	maxmind_geolite2_asn:                                     "https://dev.maxmind.com/geoip/geoip2/geolite2/#Download_Access"
 	maxmind_geolite2_city:                                    "https://dev.maxmind.com/geoip/geoip2/geolite2/#Download_Access"
+	memcached_protocol:                                       "https://github.com/memcached/memcached/blob/master/doc/protocol.txt"
 	metric_event_source:                                      "https://github.com/timberio/vector/blob/master/src/event/metric.rs"
+	misra_gries:                                              "https://en.wikipedia.org/wiki/Misra%E2%80%93Gries_summary"
 	mongodb:                                                  "https://www.mongodb.com"
 	mongodb_command_server_status:                            "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:                     "https://docs.mongodb.com/manual/reference/connection-string/"
+	mqtt:                                                     "https://mqtt.org/"
 	musl_builder_docker_image:                                "https://github.com/timberio/vector/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
 	nats:                                                     "https://nats.io/"
+	nats_jetstream:                                           "https://docs.nats.io/jetstream/jetstream"
 	new_bug_report:                                           "https://github.com/timberio/vector/issues/new?labels=type%3A+bug"
 	new_feature_request:                                      "https://github.com/timberio/vector/issues/new?labels=type%3A+new+feature"
 	new_relic:                                                "https://newrelic.com/"
+	new_relic_license_key:                                    "https://docs.newrelic.com/docs/accounts/accounts-billing/account-setup/new-relic-license-key"
 	new_relic_log_api:                                        "https://docs.newrelic.com/docs/logs/new-relic-logs/log-api/introduction-log-api"
+	new_relic_metric_api:                                     "https://docs.newrelic.com/docs/telemetry-data-platform/ingest-apis/report-metrics-metric-api"
 	new_security_report:                                      "https://github.com/timberio/vector/issues/new?labels=domain%3A+security"
 	new_sink:                                                 "https://github.com/timberio/vector/issues/new?labels=type%3A+new+feature"
 	new_source:                                               "https://github.com/timberio/vector/issues/new?labels=type%3A+new+feature"
@@ -263,10 +315,26 @@ urls: {
 	nix:                                                      "https://nixos.org/nix/"
 	nixos:                                                    "https://nixos.org/"
 	nixpkgs_9682:                                             "https://github.com/NixOS/nixpkgs/issues/9682"
+	oauth2_client_credentials:                                "https://tools.ietf.org/html/rfc6749#section-4.4"
+	opensearch:                                               "https://opensearch.org/"
+	opensearch_bulk:                                          "https://opensearch.org/docs/latest/api-reference/document-apis/bulk/"
+	opensearch_data_streams:                                  "https://opensearch.org/docs/latest/im-plugin/data-streams/"
+	opensearch_ism:                                           "https://opensearch.org/docs/latest/im-plugin/ism/index/"
+	openssh_known_hosts:                                      "https://man.openbsd.org/sshd.8#SSH_KNOWN_HOSTS_FILE_FORMAT"
 	openssl:                                                  "https://www.openssl.org/"
+	opentelemetry:                                            "https://opentelemetry.io/"
+	opentelemetry_collector:                                  "https://opentelemetry.io/docs/collector/"
+	otlp:                                                     "https://opentelemetry.io/docs/specs/otlp/"
+	pagerduty:                                                "https://www.pagerduty.com/"
+	pagerduty_events_api:                                     "https://developer.pagerduty.com/docs/events-api-v2/overview/"
+	pagerduty_rate_limits:                                    "https://developer.pagerduty.com/docs/events-api-v2/rate-limits/"
 	papertrail:                                               "https://www.papertrail.com/"
 	papertrail_syslog:                                        "https://help.papertrailapp.com/kb/how-it-works/http-api/#submitting-log-messages"
 	perl_windows:                                             "https://www.perl.org/get.html#win32"
+	postgres:                                                 "https://www.postgresql.org"
+	postgres_connection_string:                               "https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-CONNSTRING"
+	postgres_copy:                                            "https://www.postgresql.org/docs/current/sql-copy.html"
+	postgres_on_conflict:                                     "https://www.postgresql.org/docs/current/sql-insert.html#SQL-ON-CONFLICT"
 	postgresql_csvlog:                                        "https://www.postgresql.org/docs/current/runtime-config-logging.html#RUNTIME-CONFIG-LOGGING-CSVLOG"
 	prometheus:                                               "https://prometheus.io/"
 	prometheus_client:                                        "https://prometheus.io/docs/instrumenting/clientlibs/"
@@ -275,16 +343,25 @@ urls: {
 	prometheus_high_cardinality:                              "https://prometheus.io/docs/practices/naming/#labels"
 	prometheus_histogram:                                     "https://prometheus.io/docs/concepts/metric_types/#histogram"
 	prometheus_histograms_guide:                              "https://prometheus.io/docs/practices/histograms/"
+	prometheus_label_names:                                   "https://prometheus.io/docs/concepts/data_model/#metric-names-and-labels"
+	prometheus_remote_write_spec:                             "https://prometheus.io/docs/concepts/remote_write_spec/"
+	prometheus_staleness:                                     "https://prometheus.io/docs/prometheus/latest/querying/basics/#staleness"
 	prometheus_summary:                                       "https://prometheus.io/docs/concepts/metric_types/#summary"
 	prometheus_text_based_exposition_format:                  "https://github.com/prometheus/docs/blob/master/content/docs/instrumenting/exposition_formats.md#text-based-format"
 	prometheus_metric_naming:                                 "https://prometheus.io/docs/practices/naming/#metric-names"
+	prometheus_relabel_config:                                "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#relabel_config"
 	prometheus_remote_integrations:                           "https://prometheus.io/docs/operating/integrations/#remote-endpoints-and-storage"
 	prometheus_remote_write:                                  "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#remote_write"
 	protobuf:                                                 "https://developers.google.com/protocol-buffers"
 	pulsar:                                                   "https://pulsar.apache.org/"
 	pulsar_protocol:                                          "https://pulsar.apache.org/docs/en/develop-binary-protocol/"
+	quickwit:                                                 "https://quickwit.io/"
+	quickwit_es_bulk:                                         "https://quickwit.io/docs/reference/es_compatible_api"
+	rabbitmq:                                                 "https://www.rabbitmq.com/"
+	rabbitmq_confirms:                                        "https://www.rabbitmq.com/confirms.html"
 	raspbian:                                                 "https://www.raspbian.org/"
 	rdkafka:                                                  "https://github.com/edenhill/librdkafka"
+	redis:                                                    "https://redis.io/"
 	regex:                                                    "https://en.wikipedia.org/wiki/Regular_expression"
 	regex_grouping_and_flags:                                 "https://docs.rs/regex/1.3.9/regex/#grouping-and-flags"
 	regex_tester:                                             "https://rustexp.lpil.uk/"
@@ -312,15 +389,27 @@ urls: {
 	sematext_monitoring:                                      "https://sematext.com/docs/monitoring/"
 	sematext_registration:                                    "https://apps.sematext.com/ui/registration"
 	semver:                                                   "https://semver.org/"
+	sftp:                                                     "https://datatracker.ietf.org/doc/html/draft-ietf-secsh-filexfer-02"
+	slack_incoming_webhooks:                                  "https://api.slack.com/messaging/webhooks"
+	slack_rate_limits:                                        "https://api.slack.com/docs/rate-limits"
+	smtp:                                                     "https://tools.ietf.org/html/rfc5321"
+	smtp_starttls:                                            "https://tools.ietf.org/html/rfc3207"
 	snappy:                                                   "https://google.github.io/snappy/"
+	snowflake:                                                "https://www.snowflake.com/"
+	snowflake_key_pair_auth:                                  "https://docs.snowflake.com/en/user-guide/key-pair-auth.html"
+	snowflake_s3_stage:                                       "https://docs.snowflake.com/en/user-guide/data-load-s3-create-stage.html"
+	snowpipe_rest_api:                                        "https://docs.snowflake.com/en/user-guide/data-load-snowpipe-rest-apis.html"
 	socket:                                                   "https://en.wikipedia.org/wiki/Network_socket"
 	splunk:                                                   "https://www.splunk.com"
 	splunk_hec:                                               "https://dev.splunk.com/enterprise/docs/dataapps/httpeventcollector/"
 	splunk_hec_event_endpoint:                                "https://docs.splunk.com/Documentation/Splunk/8.0.0/RESTREF/RESTinput#services.2Fcollector.2Fevent"
 	splunk_hec_indexed_fields:                                "https://docs.splunk.com/Documentation/Splunk/8.0.0/Data/IFXandHEC"
+	splunk_hec_indexer_acknowledgement:                       "https://docs.splunk.com/Documentation/Splunk/latest/Data/AboutHECIDXAck"
 	splunk_hec_protocol:                                      "https://docs.splunk.com/Documentation/Splunk/8.0.0/Data/HECRESTendpoints"
 	splunk_hec_raw_endpoint:                                  "https://docs.splunk.com/Documentation/Splunk/8.0.0/RESTREF/RESTinput#services.2Fcollector.2Fraw"
 	splunk_hec_setup:                                         "https://docs.splunk.com/Documentation/Splunk/latest/Data/UsetheHTTPEventCollector"
+	sql_select:                                               "https://en.wikipedia.org/wiki/Select_(SQL)"
+	standard_score:                                           "https://en.wikipedia.org/wiki/Standard_score"
 	standard_streams:                                         "https://en.wikipedia.org/wiki/Standard_streams"
 	statsd:                                                   "https://github.com/statsd/statsd"
 	statsd_multi:                                             "https://github.com/statsd/statsd/blob/master/docs/metric_types.md#multi-metric-packets"
@@ -340,6 +429,7 @@ urls: {
 	systemd_resolved:                                         "https://wiki.archlinux.org/index.php/Systemd-resolved"
 	tcp:                                                      "https://en.wikipedia.org/wiki/Transmission_Control_Protocol"
 	team:                                                     "/community#team"
+	teams_incoming_webhooks:                                  "https://docs.microsoft.com/en-us/microsoftteams/platform/webhooks-and-connectors/how-to/add-incoming-webhook"
 	timber:                                                   "https://timber.io"
 	timber_remap_language_reference:                          "https://vector.dev/docs/reference/remap/"
 	toml:                                                     "https://github.com/toml-lang/toml"
@@ -348,10 +438,12 @@ urls: {
 	toml_types:                                               "https://github.com/toml-lang/toml#table-of-contents"
 	trl:                                                      "https://vector.dev/docs/reference/timber_remap_language"
 	twelve_factor_app:                                        "https://12factor.net/"
+	uap_core:                                                 "https://github.com/ua-parser/uap-core"
 	ubuntu:                                                   "https://ubuntu.com/"
 	udp:                                                      "https://en.wikipedia.org/wiki/User_Datagram_Protocol"
 	uds:                                                      "https://en.wikipedia.org/wiki/Unix_domain_socket"
 	unicode_whitespace:                                       "https://en.wikipedia.org/wiki/Unicode_character_property#Whitespace"
+	url:                                                      "https://en.wikipedia.org/wiki/URL"
 	uuidv4:                                                   "https://en.wikipedia.org/wiki/Universally_unique_identifier#Version_4_(random)"
 	vector_aggregator_role:                                   "https://vector.dev/docs/setup/deployment/roles/#aggregator"
 	vector_blog:                                              "https://vector.dev/blog/"
@@ -422,11 +514,15 @@ urls: {
 	vector_website:                                           "https://vector.dev"
 	vote_feature:                                             "https://github.com/timberio/vector/issues?q=is%3Aissue+is%3Aopen+sort%3Areactions-%2B1-desc+label%3A%22Type%3A+New+Feature%22"
 	wasm:                                                     "https://webassembly.org/"
+	websocket:                                                "https://en.wikipedia.org/wiki/WebSocket"
+	websocket_rfc:                                            "https://tools.ietf.org/html/rfc6455"
 	windows:                                                  "https://www.microsoft.com/en-us/windows"
 	windows_installer:                                        "https://en.wikipedia.org/wiki/Windows_Installer"
 	windows_service:                                          "https://docs.microsoft.com/en-us/powershell/module/microsoft.powershell.management/new-service"
 	yaml:                                                     "https://yaml.org/"
 	yum:                                                      "https://en.wikipedia.org/wiki/Yum_(software)"
+	zincsearch:                                               "https://zincsearch-docs.zinc.dev/"
+	zincsearch_es_bulk:                                       "https://zincsearch-docs.zinc.dev/api-es-compatible/document/bulk/"
 	zlib:                                                     "https://www.zlib.net"
 	zstd:                                                     "https://zstd.net"
 }
@@ -0,0 +1,81 @@
+//! Compiles the eBPF programs with `clang` into an object that's embedded in
+//! the library.
+//!
+//! The programs include a `vmlinux.h` with the kernel types, which is
+//! generated from the BTF of the build host with `bpftool` unless
+//! `EBPF_PROBES_VMLINUX_H` points to one. Thanks to CO-RE relocations the
+//! object works on any kernel with BTF. The libbpf headers are looked up in
+//! the system include paths, or in `LIBBPF_INCLUDE` if set.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+const SOURCE: &str = "src/bpf/probes.bpf.c";
+const HEADER: &str = "src/bpf/probes.h";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", SOURCE);
+    println!("cargo:rerun-if-changed={}", HEADER);
+    println!("cargo:rerun-if-env-changed=CLANG");
+    println!("cargo:rerun-if-env-changed=BPFTOOL");
+    println!("cargo:rerun-if-env-changed=EBPF_PROBES_VMLINUX_H");
+    println!("cargo:rerun-if-env-changed=LIBBPF_INCLUDE");
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    let arch = match env::var("CARGO_CFG_TARGET_ARCH").unwrap().as_str() {
+        "x86_64" => "x86",
+        "aarch64" => "arm64",
+        arch => panic!("eBPF probes aren't supported on {}", arch),
+    };
+
+    let vmlinux = out_dir.join("vmlinux.h");
+    match env::var_os("EBPF_PROBES_VMLINUX_H") {
+        Some(path) => {
+            fs::copy(&path, &vmlinux).expect("Failed to copy EBPF_PROBES_VMLINUX_H");
+        }
+        None => generate_vmlinux(&vmlinux),
+    }
+
+    let mut clang = Command::new(env::var_os("CLANG").unwrap_or_else(|| "clang".into()));
+    clang
+        .args(&["-g", "-O2", "-target", "bpf"])
+        .arg(format!("-D__TARGET_ARCH_{}", arch))
+        .arg("-I")
+        .arg(&out_dir);
+    if let Some(include) = env::var_os("LIBBPF_INCLUDE") {
+        clang.arg("-I").arg(include);
+    }
+    run(clang
+        .args(&["-c", SOURCE, "-o"])
+        .arg(out_dir.join("probes.bpf.o")));
+}
+
+fn generate_vmlinux(path: &Path) {
+    let output = Command::new(env::var_os("BPFTOOL").unwrap_or_else(|| "bpftool".into()))
+        .args(&[
+            "btf",
+            "dump",
+            "file",
+            "/sys/kernel/btf/vmlinux",
+            "format",
+            "c",
+        ])
+        .output()
+        .expect("Failed to run bpftool, set EBPF_PROBES_VMLINUX_H to skip it");
+    assert!(
+        output.status.success(),
+        "bpftool failed to dump the kernel BTF: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    fs::write(path, output.stdout).expect("Failed to write vmlinux.h");
+}
+
+fn run(command: &mut Command) {
+    let status = command
+        .status()
+        .unwrap_or_else(|error| panic!("Failed to run {:?}: {}", command, error));
+    assert!(status.success(), "{:?} failed with {}", command, status);
+}
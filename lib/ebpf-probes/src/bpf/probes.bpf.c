@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: (LGPL-2.1 OR BSD-2-Clause)
+/*
+ * Probes for the `ebpf` source, based on `execsnoop`, `tcpconnect` and
+ * `gethostlatency` from libbpf-tools.
+ */
+#include "vmlinux.h"
+#include <bpf/bpf_core_read.h>
+#include <bpf/bpf_endian.h>
+#include <bpf/bpf_helpers.h>
+#include <bpf/bpf_tracing.h>
+#include "probes.h"
+
+#define AF_INET 2
+#define AF_INET6 10
+
+char LICENSE[] SEC("license") = "Dual BSD/GPL";
+
+/* Resized from user space according to `ring_buffer_pages`. */
+struct {
+	__uint(type, BPF_MAP_TYPE_RINGBUF);
+	__uint(max_entries, 256 * 1024);
+} events SEC(".maps");
+
+/* Records that didn't fit in the ring buffer. */
+struct {
+	__uint(type, BPF_MAP_TYPE_PERCPU_ARRAY);
+	__uint(max_entries, 1);
+	__type(key, __u32);
+	__type(value, __u64);
+} lost_records SEC(".maps");
+
+/* Sockets being connected, by thread. */
+struct {
+	__uint(type, BPF_MAP_TYPE_HASH);
+	__uint(max_entries, 10240);
+	__type(key, __u32);
+	__type(value, struct sock *);
+} connecting SEC(".maps");
+
+static __always_inline void *reserve(__u64 size)
+{
+	void *record = bpf_ringbuf_reserve(&events, size, 0);
+
+	if (!record) {
+		__u32 zero = 0;
+		__u64 *lost = bpf_map_lookup_elem(&lost_records, &zero);
+
+		if (lost)
+			(*lost)++;
+	}
+	return record;
+}
+
+static __always_inline void fill_header(struct header *header, __u32 probe)
+{
+	__u64 uid_gid = bpf_get_current_uid_gid();
+
+	header->probe = probe;
+	header->pid = bpf_get_current_pid_tgid() >> 32;
+	header->uid = uid_gid;
+	header->gid = uid_gid >> 32;
+	bpf_get_current_comm(header->comm, sizeof(header->comm));
+}
+
+SEC("tp/sched/sched_process_exec")
+int process_exec(struct trace_event_raw_sched_process_exec *ctx)
+{
+	struct task_struct *task = (struct task_struct *)bpf_get_current_task();
+	unsigned int filename_loc = ctx->__data_loc_filename & 0xFFFF;
+	unsigned long arg_start, arg_end, size;
+	struct exec_record *record;
+
+	record = reserve(sizeof(*record));
+	if (!record)
+		return 0;
+
+	fill_header(&record->header, PROBE_PROCESS_EXEC);
+	record->ppid = BPF_CORE_READ(task, real_parent, tgid);
+	bpf_probe_read_kernel_str(record->filename, sizeof(record->filename),
+				  (void *)ctx + filename_loc);
+
+	arg_start = BPF_CORE_READ(task, mm, arg_start);
+	arg_end = BPF_CORE_READ(task, mm, arg_end);
+	size = arg_end - arg_start;
+	if (size > ARGS_LEN)
+		size = ARGS_LEN;
+	if (bpf_probe_read_user(record->args, size, (void *)arg_start))
+		size = 0;
+	record->args_size = size;
+
+	bpf_ringbuf_submit(record, 0);
+	return 0;
+}
+
+static __always_inline void submit_tcp(struct sock *sk, __u32 probe)
+{
+	struct tcp_record *record;
+
+	record = reserve(sizeof(*record));
+	if (!record)
+		return;
+
+	fill_header(&record->header, probe);
+	record->family = BPF_CORE_READ(sk, __sk_common.skc_family);
+	record->sport = BPF_CORE_READ(sk, __sk_common.skc_num);
+	record->dport = bpf_ntohs(BPF_CORE_READ(sk, __sk_common.skc_dport));
+	if (record->family == AF_INET) {
+		bpf_core_read(record->saddr, 4, &sk->__sk_common.skc_rcv_saddr);
+		bpf_core_read(record->daddr, 4, &sk->__sk_common.skc_daddr);
+	} else {
+		bpf_core_read(record->saddr, 16, &sk->__sk_common.skc_v6_rcv_saddr);
+		bpf_core_read(record->daddr, 16, &sk->__sk_common.skc_v6_daddr);
+	}
+
+	bpf_ringbuf_submit(record, 0);
+}
+
+static __always_inline int enter_connect(struct sock *sk)
+{
+	__u32 tid = bpf_get_current_pid_tgid();
+
+	bpf_map_update_elem(&connecting, &tid, &sk, BPF_ANY);
+	return 0;
+}
+
+static __always_inline int exit_connect(int ret)
+{
+	__u32 tid = bpf_get_current_pid_tgid();
+	struct sock **skp;
+
+	skp = bpf_map_lookup_elem(&connecting, &tid);
+	if (!skp)
+		return 0;
+
+	/* The addresses are only known once the connect succeeded. */
+	if (ret == 0)
+		submit_tcp(*skp, PROBE_TCP_CONNECT);
+	bpf_map_delete_elem(&connecting, &tid);
+	return 0;
+}
+
+SEC("kprobe/tcp_v4_connect")
+int BPF_KPROBE(tcp_v4_connect, struct sock *sk)
+{
+	return enter_connect(sk);
+}
+
+SEC("kretprobe/tcp_v4_connect")
+int BPF_KRETPROBE(tcp_v4_connect_ret, int ret)
+{
+	return exit_connect(ret);
+}
+
+SEC("kprobe/tcp_v6_connect")
+int BPF_KPROBE(tcp_v6_connect, struct sock *sk)
+{
+	return enter_connect(sk);
+}
+
+SEC("kretprobe/tcp_v6_connect")
+int BPF_KRETPROBE(tcp_v6_connect_ret, int ret)
+{
+	return exit_connect(ret);
+}
+
+SEC("kretprobe/inet_csk_accept")
+int BPF_KRETPROBE(inet_csk_accept_ret, struct sock *sk)
+{
+	if (sk)
+		submit_tcp(sk, PROBE_TCP_ACCEPT);
+	return 0;
+}
+
+/* Attached from user space to `getaddrinfo` in the system libc. */
+SEC("uprobe/getaddrinfo")
+int BPF_KPROBE(getaddrinfo, const char *node)
+{
+	struct dns_record *record;
+
+	record = reserve(sizeof(*record));
+	if (!record)
+		return 0;
+
+	fill_header(&record->header, PROBE_DNS_LOOKUP);
+	bpf_probe_read_user_str(record->query, sizeof(record->query), node);
+
+	bpf_ringbuf_submit(record, 0);
+	return 0;
+}
@@ -0,0 +1,55 @@
+/* SPDX-License-Identifier: (LGPL-2.1 OR BSD-2-Clause) */
+#ifndef __PROBES_H
+#define __PROBES_H
+
+/*
+ * Records written to the `events` ring buffer. Their layout is decoded by
+ * `src/lib.rs`, which has to be kept in sync.
+ */
+
+#define COMM_LEN 16
+#define FILENAME_LEN 256
+#define ARGS_LEN 512
+#define QUERY_LEN 256
+
+enum probe {
+	PROBE_PROCESS_EXEC = 1,
+	PROBE_TCP_CONNECT = 2,
+	PROBE_TCP_ACCEPT = 3,
+	PROBE_DNS_LOOKUP = 4,
+};
+
+struct header {
+	__u32 probe;
+	__u32 pid;
+	__u32 uid;
+	__u32 gid;
+	char comm[COMM_LEN];
+};
+
+struct exec_record {
+	struct header header;
+	__u32 ppid;
+	/* The arguments are NUL separated, as in `/proc/<pid>/cmdline`. */
+	__u32 args_size;
+	char filename[FILENAME_LEN];
+	char args[ARGS_LEN];
+};
+
+/* The source is the local end of the connection, for accepts too. */
+struct tcp_record {
+	struct header header;
+	__u16 family;
+	__u16 sport;
+	__u16 dport;
+	__u16 pad;
+	__u8 saddr[16];
+	__u8 daddr[16];
+};
+
+struct dns_record {
+	struct header header;
+	char query[QUERY_LEN];
+};
+
+#endif /* __PROBES_H */
@@ -0,0 +1,280 @@
+//! eBPF programs capturing process and network activity, and the decoding of
+//! the records they write to their ring buffer.
+
+use std::{
+    convert::TryInto,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+/// The compiled programs, to be loaded with libbpf.
+pub const OBJECT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/probes.bpf.o"));
+
+/// Name of the ring buffer map the records are written to.
+pub const EVENTS_MAP: &str = "events";
+
+/// Name of the per-CPU array map counting the records that didn't fit in the
+/// ring buffer.
+pub const LOST_RECORDS_MAP: &str = "lost_records";
+
+/// The symbol in libc the `dns_lookup` probe is attached to.
+pub const DNS_LOOKUP_SYMBOL: &str = "getaddrinfo";
+
+const COMM_LEN: usize = 16;
+const FILENAME_LEN: usize = 256;
+const ARGS_LEN: usize = 512;
+const QUERY_LEN: usize = 256;
+
+const HEADER_SIZE: usize = 16 + COMM_LEN;
+const EXEC_RECORD_SIZE: usize = HEADER_SIZE + 8 + FILENAME_LEN + ARGS_LEN;
+const TCP_RECORD_SIZE: usize = HEADER_SIZE + 8 + 16 + 16;
+const DNS_RECORD_SIZE: usize = HEADER_SIZE + QUERY_LEN;
+
+const AF_INET: u16 = 2;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Probe {
+    ProcessExec,
+    TcpConnect,
+    TcpAccept,
+    DnsLookup,
+}
+
+impl Probe {
+    pub const ALL: [Probe; 4] = [
+        Probe::ProcessExec,
+        Probe::TcpConnect,
+        Probe::TcpAccept,
+        Probe::DnsLookup,
+    ];
+
+    fn from_id(id: u32) -> Option<Self> {
+        match id {
+            1 => Some(Probe::ProcessExec),
+            2 => Some(Probe::TcpConnect),
+            3 => Some(Probe::TcpAccept),
+            4 => Some(Probe::DnsLookup),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Probe::ProcessExec => "process_exec",
+            Probe::TcpConnect => "tcp_connect",
+            Probe::TcpAccept => "tcp_accept",
+            Probe::DnsLookup => "dns_lookup",
+        }
+    }
+
+    /// The programs in `OBJECT` implementing the probe.
+    pub fn programs(self) -> &'static [&'static str] {
+        match self {
+            Probe::ProcessExec => &["process_exec"],
+            Probe::TcpConnect => &[
+                "tcp_v4_connect",
+                "tcp_v4_connect_ret",
+                "tcp_v6_connect",
+                "tcp_v6_connect_ret",
+            ],
+            Probe::TcpAccept => &["inet_csk_accept_ret"],
+            Probe::DnsLookup => &["getaddrinfo"],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub probe: Probe,
+    pub pid: u32,
+    pub uid: u32,
+    pub gid: u32,
+    pub comm: String,
+    pub data: RecordData,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordData {
+    ProcessExec {
+        ppid: u32,
+        filename: String,
+        argv: Vec<String>,
+    },
+    /// The source is the local end of the connection, for accepts too.
+    Tcp {
+        saddr: IpAddr,
+        sport: u16,
+        daddr: IpAddr,
+        dport: u16,
+    },
+    DnsLookup {
+        query: String,
+    },
+}
+
+impl Record {
+    /// Decodes a record as laid out in `src/bpf/probes.h`, returning `None`
+    /// if it's truncated or of an unknown probe.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_SIZE {
+            return None;
+        }
+        let probe = Probe::from_id(u32_at(bytes, 0))?;
+        let data = match probe {
+            Probe::ProcessExec if bytes.len() >= EXEC_RECORD_SIZE => {
+                let args_size = (u32_at(bytes, HEADER_SIZE + 4) as usize).min(ARGS_LEN);
+                let args = &bytes[HEADER_SIZE + 8 + FILENAME_LEN..][..args_size];
+                RecordData::ProcessExec {
+                    ppid: u32_at(bytes, HEADER_SIZE),
+                    filename: c_string(&bytes[HEADER_SIZE + 8..][..FILENAME_LEN]),
+                    argv: args
+                        .split(|byte| *byte == 0)
+                        .filter(|arg| !arg.is_empty())
+                        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                        .collect(),
+                }
+            }
+            Probe::TcpConnect | Probe::TcpAccept if bytes.len() >= TCP_RECORD_SIZE => {
+                let family = u16_at(bytes, HEADER_SIZE);
+                RecordData::Tcp {
+                    saddr: ip_addr(family, &bytes[HEADER_SIZE + 8..][..16]),
+                    sport: u16_at(bytes, HEADER_SIZE + 2),
+                    daddr: ip_addr(family, &bytes[HEADER_SIZE + 24..][..16]),
+                    dport: u16_at(bytes, HEADER_SIZE + 4),
+                }
+            }
+            Probe::DnsLookup if bytes.len() >= DNS_RECORD_SIZE => RecordData::DnsLookup {
+                query: c_string(&bytes[HEADER_SIZE..][..QUERY_LEN]),
+            },
+            _ => return None,
+        };
+
+        Some(Record {
+            probe,
+            pid: u32_at(bytes, 4),
+            uid: u32_at(bytes, 8),
+            gid: u32_at(bytes, 12),
+            comm: c_string(&bytes[16..][..COMM_LEN]),
+            data,
+        })
+    }
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_ne_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let len = bytes
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+fn ip_addr(family: u16, bytes: &[u8]) -> IpAddr {
+    if family == AF_INET {
+        let octets: [u8; 4] = bytes[..4].try_into().unwrap();
+        Ipv4Addr::from(octets).into()
+    } else {
+        let octets: [u8; 16] = bytes.try_into().unwrap();
+        Ipv6Addr::from(octets).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_record(probe: u32, size: usize) -> Vec<u8> {
+        let mut bytes = vec![0; size];
+        bytes[0..4].copy_from_slice(&probe.to_ne_bytes());
+        bytes[4..8].copy_from_slice(&4242u32.to_ne_bytes());
+        bytes[8..12].copy_from_slice(&1000u32.to_ne_bytes());
+        bytes[12..16].copy_from_slice(&100u32.to_ne_bytes());
+        bytes[16..20].copy_from_slice(b"bash");
+        bytes
+    }
+
+    #[test]
+    fn decodes_process_exec() {
+        let mut bytes = raw_record(1, EXEC_RECORD_SIZE);
+        let args = b"curl\0-s\0http://example.com\0";
+        bytes[HEADER_SIZE..HEADER_SIZE + 4].copy_from_slice(&1u32.to_ne_bytes());
+        bytes[HEADER_SIZE + 4..HEADER_SIZE + 8].copy_from_slice(&(args.len() as u32).to_ne_bytes());
+        bytes[HEADER_SIZE + 8..][..13].copy_from_slice(b"/usr/bin/curl");
+        bytes[HEADER_SIZE + 8 + FILENAME_LEN..][..args.len()].copy_from_slice(args);
+
+        assert_eq!(
+            Record::decode(&bytes),
+            Some(Record {
+                probe: Probe::ProcessExec,
+                pid: 4242,
+                uid: 1000,
+                gid: 100,
+                comm: "bash".into(),
+                data: RecordData::ProcessExec {
+                    ppid: 1,
+                    filename: "/usr/bin/curl".into(),
+                    argv: vec!["curl".into(), "-s".into(), "http://example.com".into()],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_tcp() {
+        let mut bytes = raw_record(2, TCP_RECORD_SIZE);
+        bytes[HEADER_SIZE..][..2].copy_from_slice(&AF_INET.to_ne_bytes());
+        bytes[HEADER_SIZE + 2..][..2].copy_from_slice(&51234u16.to_ne_bytes());
+        bytes[HEADER_SIZE + 4..][..2].copy_from_slice(&443u16.to_ne_bytes());
+        bytes[HEADER_SIZE + 8..][..4].copy_from_slice(&[10, 0, 0, 1]);
+        bytes[HEADER_SIZE + 24..][..4].copy_from_slice(&[93, 184, 216, 34]);
+
+        let record = Record::decode(&bytes).unwrap();
+        assert_eq!(record.probe, Probe::TcpConnect);
+        assert_eq!(
+            record.data,
+            RecordData::Tcp {
+                saddr: Ipv4Addr::new(10, 0, 0, 1).into(),
+                sport: 51234,
+                daddr: Ipv4Addr::new(93, 184, 216, 34).into(),
+                dport: 443,
+            }
+        );
+
+        let mut bytes = raw_record(3, TCP_RECORD_SIZE);
+        bytes[HEADER_SIZE..][..2].copy_from_slice(&10u16.to_ne_bytes());
+        bytes[HEADER_SIZE + 8 + 15] = 1;
+        let record = Record::decode(&bytes).unwrap();
+        assert_eq!(record.probe, Probe::TcpAccept);
+        assert!(matches!(
+            record.data,
+            RecordData::Tcp { saddr, .. } if saddr == IpAddr::from(Ipv6Addr::LOCALHOST)
+        ));
+    }
+
+    #[test]
+    fn decodes_dns_lookup() {
+        let mut bytes = raw_record(4, DNS_RECORD_SIZE);
+        bytes[HEADER_SIZE..][..11].copy_from_slice(b"example.com");
+
+        let record = Record::decode(&bytes).unwrap();
+        assert_eq!(
+            record.data,
+            RecordData::DnsLookup {
+                query: "example.com".into()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_and_unknown_records() {
+        assert_eq!(Record::decode(&raw_record(1, EXEC_RECORD_SIZE - 1)), None);
+        assert_eq!(Record::decode(&raw_record(9, DNS_RECORD_SIZE)), None);
+        assert_eq!(Record::decode(&[0; 4]), None);
+    }
+}
@@ -18,6 +18,15 @@ impl Expression for Arithmetic {
     fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
         use Operator::*;
 
+        // The right-hand side of an error coalescing operation is only
+        // evaluated if the left-hand side fails.
+        if let ErrorOr = self.op {
+            return self
+                .lhs
+                .execute(state, object)
+                .or_else(|_| self.rhs.execute(state, object));
+        }
+
         let lhs = self.lhs.execute(state, object)?;
         let rhs = self.rhs.execute(state, object)?;
 
@@ -35,6 +44,7 @@ impl Expression for Arithmetic {
             GreaterOrEqual => lhs.try_ge(rhs),
             Less => lhs.try_lt(rhs),
             LessOrEqual => lhs.try_le(rhs),
+            ErrorOr => unreachable!("handled above"),
         }
         .map_err(Into::into)
     }
@@ -48,6 +58,11 @@ impl Expression for Arithmetic {
         let type_def = lhs_def | rhs_def;
 
         match self.op {
+            ErrorOr if !lhs_def.is_fallible() => lhs_def,
+            ErrorOr => TypeDef {
+                fallible: rhs_def.is_fallible(),
+                kind: type_def.kind,
+            },
             Or if lhs_def.kind.is_null() => rhs_def,
             Or if !lhs_def.kind.is_boolean() => lhs_def,
             Or => type_def,
@@ -91,6 +106,34 @@ mod tests {
             },
         }
 
+        error_or_infallible {
+            expr: |_| Arithmetic::new(
+                Box::new(Literal::from("foo").into()),
+                Box::new(Literal::from(true).into()),
+                Operator::ErrorOr,
+            ),
+            def: TypeDef {
+                fallible: false,
+                kind: Kind::Bytes,
+            },
+        }
+
+        error_or_fallible {
+            expr: |_| Arithmetic::new(
+                Box::new(Arithmetic::new(
+                    Box::new(Noop.into()),
+                    Box::new(Noop.into()),
+                    Operator::Multiply,
+                ).into()),
+                Box::new(Literal::from(true).into()),
+                Operator::ErrorOr,
+            ),
+            def: TypeDef {
+                fallible: false,
+                kind: Kind::Bytes | Kind::Integer | Kind::Float | Kind::Boolean,
+            },
+        }
+
         or_null {
             expr: |_| Arithmetic::new(
                 Box::new(Noop.into()),
@@ -240,6 +240,9 @@ mod tests {
             (r#"false || null"#, Ok(()), Ok(().into())),
             (r#"null || "foo""#, Ok(()), Ok("foo".into())),
             (r#". = "bar""#, Ok(()), Ok("bar".into())),
+            (r#"1 + "foo" ?? "bar""#, Ok(()), Ok("bar".into())),
+            (r#""foo" ?? "bar""#, Ok(()), Ok("foo".into())),
+            (r#".foo = 1 + "foo" ?? 2 + "bar" ?? 3"#, Ok(()), Ok(3.into())),
         ];
 
         for (script, compile_expected, runtime_expected) in cases {
@@ -2,10 +2,10 @@ use crate::cli::{handle_config_errors, Color, LogFormat, Opts, RootOpts, SubComm
 use crate::signal::SignalTo;
 use crate::topology::RunningTopology;
 use crate::{
-    config, generate, heartbeat, list, metrics, signal, topology, trace, unit_test, validate,
+    config, generate, heartbeat, list, metrics, resources, signal, topology, trace, unit_test,
+    validate,
 };
-use std::cmp::max;
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use futures::{
     compat::{Future01CompatExt, Stream01CompatExt},
@@ -92,8 +92,10 @@ impl Application {
             }
         }
 
+        resources::init(resources::Resources::detect());
+
         let mut rt = {
-            let threads = root_opts.threads.unwrap_or_else(|| max(1, num_cpus::get()));
+            let threads = root_opts.threads.unwrap_or_else(resources::default_threads);
             runtime::Builder::new()
                 .threaded_scheduler()
                 .enable_all()
@@ -104,7 +106,9 @@ impl Application {
 
         let config = {
             let config_paths = root_opts.config_paths_with_formats();
+            let config_dirs = root_opts.config_dirs.clone();
             let watch_config = root_opts.watch_config;
+            let strict_env_vars = root_opts.strict_env_vars;
             let require_healthy = root_opts.require_healthy;
 
             rt.block_on(async move {
@@ -128,12 +132,16 @@ impl Application {
                 let config_paths = config::process_paths(&config_paths).ok_or(exitcode::CONFIG)?;
 
                 if watch_config {
-                    // Start listening for config changes immediately.
-                    config::watcher::spawn_thread(config_paths.iter().map(|(path, _)| path), None)
-                        .map_err(|error| {
-                            error!(message = "Unable to start config watcher.", %error);
-                            exitcode::CONFIG
-                        })?;
+                    // Start listening for config changes immediately. Config
+                    // directories are watched too, for files added to them.
+                    let watched_paths = config_paths
+                        .iter()
+                        .map(|(path, _)| path)
+                        .chain(&config_dirs);
+                    config::watcher::spawn_thread(watched_paths, None).map_err(|error| {
+                        error!(message = "Unable to start config watcher.", %error);
+                        exitcode::CONFIG
+                    })?;
                 }
 
                 info!(
@@ -141,15 +149,16 @@ impl Application {
                     path = ?config_paths
                 );
 
-                let config =
-                    config::load_from_paths(&config_paths, false).map_err(handle_config_errors)?;
+                let config = config::load_from_paths(&config_paths, false, strict_env_vars)
+                    .await
+                    .map_err(handle_config_errors)?;
 
                 config::LOG_SCHEMA
                     .set(config.global.log_schema.clone())
                     .expect("Couldn't set schema");
 
                 let diff = config::ConfigDiff::initial(&config);
-                let pieces = topology::build_or_log_errors(&config, &diff)
+                let pieces = topology::build_or_log_errors(&config, &diff, HashMap::new())
                     .await
                     .ok_or(exitcode::CONFIG)?;
 
@@ -219,7 +228,7 @@ impl Application {
                         // Reload paths
                         config_paths = config::process_paths(&opts.config_paths_with_formats()).unwrap_or(config_paths);
                         // Reload config
-                        let new_config = config::load_from_paths(&config_paths, false).map_err(handle_config_errors).ok();
+                        let new_config = config::load_from_paths(&config_paths, false, opts.strict_env_vars).await.map_err(handle_config_errors).ok();
 
                         if let Some(new_config) = new_config {
                             match topology
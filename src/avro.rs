@@ -0,0 +1,291 @@
+//! Decoding of Avro records framed in the Confluent wire format: a zero magic
+//! byte, the big-endian id of the writer schema in a Schema Registry, then
+//! the Avro encoded datum.
+
+use crate::{
+    event::Value,
+    http::{Auth, HttpClient},
+    tls::{TlsOptions, TlsSettings},
+};
+use avro_rs::{types::Value as AvroValue, Schema};
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use http::{Request, StatusCode};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+#[derive(Debug, Snafu)]
+pub enum DecodeError {
+    #[snafu(display("Message is not in the Confluent wire format"))]
+    NotFramed,
+    #[snafu(display("Failed to fetch schema {}: {}", id, source))]
+    FetchSchema { id: u32, source: crate::Error },
+    #[snafu(display("Schema registry returned {} for schema {}", status, id))]
+    SchemaStatus { id: u32, status: StatusCode },
+    #[snafu(display("Invalid schema {}: {}", id, source))]
+    InvalidSchema { id: u32, source: avro_rs::Error },
+    #[snafu(display("Failed to decode Avro datum: {}", source))]
+    InvalidDatum { source: avro_rs::Error },
+    #[snafu(display("Decoded Avro value is not a record"))]
+    NotARecord,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AvroConfig {
+    /// Base URL of the Confluent Schema Registry.
+    pub registry_url: String,
+    pub auth: Option<Auth>,
+    pub tls: Option<TlsOptions>,
+}
+
+impl AvroConfig {
+    pub fn build(&self) -> crate::Result<AvroDecoder> {
+        let tls = TlsSettings::from_options(&self.tls)?;
+        Ok(AvroDecoder {
+            client: HttpClient::new(tls)?,
+            registry_url: self.registry_url.trim_end_matches('/').to_owned(),
+            auth: self.auth.clone(),
+            schemas: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+}
+
+/// Decodes Avro records, fetching writer schemas from the registry the
+/// first time they are seen.
+#[derive(Debug, Clone)]
+pub struct AvroDecoder {
+    client: HttpClient,
+    registry_url: String,
+    auth: Option<Auth>,
+    schemas: Arc<Mutex<HashMap<u32, Arc<Schema>>>>,
+}
+
+#[derive(Deserialize)]
+struct SchemaResponse {
+    schema: String,
+}
+
+impl AvroDecoder {
+    pub async fn decode(&self, bytes: &[u8]) -> Result<BTreeMap<String, Value>, DecodeError> {
+        let (id, mut datum) = match bytes {
+            [0, a, b, c, d, datum @ ..] => (u32::from_be_bytes([*a, *b, *c, *d]), datum),
+            _ => return Err(DecodeError::NotFramed),
+        };
+
+        let schema = self.schema(id).await?;
+        let value = avro_rs::from_avro_datum(&schema, &mut datum, None).context(InvalidDatum)?;
+        match convert(value) {
+            Value::Map(fields) => Ok(fields),
+            _ => Err(DecodeError::NotARecord),
+        }
+    }
+
+    async fn schema(&self, id: u32) -> Result<Arc<Schema>, DecodeError> {
+        let cached = self.schemas.lock().unwrap().get(&id).cloned();
+        if let Some(schema) = cached {
+            return Ok(schema);
+        }
+
+        let schema = self.fetch_schema(id).await?;
+        let schema = Arc::new(Schema::parse_str(&schema).context(InvalidSchema { id })?);
+        self.schemas.lock().unwrap().insert(id, Arc::clone(&schema));
+        Ok(schema)
+    }
+
+    async fn fetch_schema(&self, id: u32) -> Result<String, DecodeError> {
+        let uri = format!("{}/schemas/ids/{}", self.registry_url, id);
+        let mut request = Request::get(uri)
+            .header("Accept", "application/vnd.schemaregistry.v1+json")
+            .body(Body::empty())
+            .map_err(|error| fetch_failed(id, error))?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self
+            .client
+            .send(request)
+            .await
+            .map_err(|error| fetch_failed(id, error))?;
+        let status = response.status();
+        if status != StatusCode::OK {
+            return Err(DecodeError::SchemaStatus { id, status });
+        }
+
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|error| fetch_failed(id, error))?;
+        let response: SchemaResponse =
+            serde_json::from_slice(&body).map_err(|error| fetch_failed(id, error))?;
+        Ok(response.schema)
+    }
+}
+
+fn fetch_failed(id: u32, error: impl Into<crate::Error>) -> DecodeError {
+    DecodeError::FetchSchema {
+        id,
+        source: error.into(),
+    }
+}
+
+fn convert(value: AvroValue) -> Value {
+    match value {
+        AvroValue::Null => Value::Null,
+        AvroValue::Boolean(value) => Value::Boolean(value),
+        AvroValue::Int(value) | AvroValue::Date(value) | AvroValue::TimeMillis(value) => {
+            Value::Integer(value as i64)
+        }
+        AvroValue::Long(value) | AvroValue::TimeMicros(value) => Value::Integer(value),
+        AvroValue::Float(value) => Value::Float(value as f64),
+        AvroValue::Double(value) => Value::Float(value),
+        AvroValue::Bytes(value) | AvroValue::Fixed(_, value) => Value::Bytes(Bytes::from(value)),
+        AvroValue::String(value) | AvroValue::Enum(_, value) => Value::from(value),
+        AvroValue::Uuid(value) => Value::from(value.to_string()),
+        AvroValue::TimestampMillis(millis) => Value::Timestamp(Utc.timestamp_millis(millis)),
+        AvroValue::TimestampMicros(micros) => Value::Timestamp(Utc.timestamp_nanos(micros * 1000)),
+        AvroValue::Union(value) => convert(*value),
+        AvroValue::Array(values) => Value::Array(values.into_iter().map(convert).collect()),
+        AvroValue::Map(values) => Value::Map(
+            values
+                .into_iter()
+                .map(|(key, value)| (key, convert(value)))
+                .collect(),
+        ),
+        AvroValue::Record(fields) => Value::Map(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key, convert(value)))
+                .collect(),
+        ),
+        // Decimals and durations have no equivalent event value.
+        AvroValue::Decimal(_) | AvroValue::Duration(_) => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::next_addr;
+    use avro_rs::types::Record;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use std::{
+        convert::Infallible,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    const SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Event",
+        "fields": [
+            {"name": "message", "type": "string"},
+            {"name": "count", "type": "long"},
+            {"name": "host", "type": ["null", "string"]},
+            {"name": "level", "type": {"type": "enum", "name": "Level", "symbols": ["INFO", "ERROR"]}}
+        ]
+    }"#;
+
+    fn encoded_event(id: u32) -> Vec<u8> {
+        let schema = Schema::parse_str(SCHEMA).unwrap();
+        let mut record = Record::new(&schema).unwrap();
+        record.put("message", "hello");
+        record.put("count", 3i64);
+        record.put(
+            "host",
+            AvroValue::Union(Box::new(AvroValue::String("web".into()))),
+        );
+        record.put("level", AvroValue::Enum(1, "ERROR".into()));
+
+        let mut bytes = vec![0];
+        bytes.extend_from_slice(&id.to_be_bytes());
+        bytes.extend(avro_rs::to_avro_datum(&schema, record).unwrap());
+        bytes
+    }
+
+    /// Serves the schema with id 7, counting the requests.
+    async fn registry() -> (String, Arc<AtomicUsize>) {
+        let addr = next_addr();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let make_svc = make_service_fn(move |_| {
+            let counter = Arc::clone(&counter);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        let response = if req.uri().path() == "/schemas/ids/7" {
+                            let body = serde_json::json!({ "schema": SCHEMA }).to_string();
+                            Response::new(Body::from(body))
+                        } else {
+                            let mut response = Response::new(Body::empty());
+                            *response.status_mut() = StatusCode::NOT_FOUND;
+                            response
+                        };
+                        Ok::<_, Infallible>(response)
+                    }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(make_svc));
+        (format!("http://{}", addr), requests)
+    }
+
+    #[tokio::test]
+    async fn decodes_and_caches_schema() {
+        let (registry_url, requests) = registry().await;
+        let decoder = AvroConfig {
+            registry_url,
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        for _ in 0..2 {
+            let fields = decoder.decode(&encoded_event(7)).await.unwrap();
+            assert_eq!(fields["message"], "hello".into());
+            assert_eq!(fields["count"], Value::Integer(3));
+            assert_eq!(fields["host"], "web".into());
+            assert_eq!(fields["level"], "ERROR".into());
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_schema() {
+        let (registry_url, _) = registry().await;
+        let decoder = AvroConfig {
+            registry_url,
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        assert!(matches!(
+            decoder.decode(&encoded_event(8)).await,
+            Err(DecodeError::SchemaStatus { id: 8, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_unframed_message() {
+        let decoder = AvroConfig {
+            registry_url: "http://localhost:1".into(),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        assert!(matches!(
+            decoder.decode(b"{\"message\":\"hello\"}").await,
+            Err(DecodeError::NotFramed)
+        ));
+    }
+}
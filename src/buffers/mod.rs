@@ -50,6 +50,7 @@ impl Default for WhenFull {
     }
 }
 
+#[derive(Clone)]
 pub enum BufferInputCloner {
     Memory(mpsc::Sender<Event>, WhenFull),
     #[cfg(feature = "leveldb")]
@@ -85,9 +86,10 @@ impl BufferInputCloner {
 }
 
 impl BufferConfig {
+    /// Scaled down when running with less memory than the reference amount.
     #[inline]
-    const fn memory_max_events() -> usize {
-        500
+    fn memory_max_events() -> usize {
+        crate::resources::get().scale(500)
     }
 
     #[cfg_attr(not(feature = "leveldb"), allow(unused))]
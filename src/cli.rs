@@ -79,11 +79,18 @@ pub struct RootOpts {
     #[structopt(name = "config-yaml", long, env = "VECTOR_CONFIG_YAML")]
     pub config_paths_yaml: Vec<PathBuf>,
 
+    /// Read configuration from every file in one or more directories.
+    /// File format is detected from the file name, and files not ending in
+    /// `.toml`, `.json`, `.yaml` or `.yml` are ignored.
+    #[structopt(name = "config-dir", short = "C", long, env = "VECTOR_CONFIG_DIR")]
+    pub config_dirs: Vec<PathBuf>,
+
     /// Exit on startup if any sinks fail healthchecks
     #[structopt(short, long, env = "VECTOR_REQUIRE_HEALTHY")]
     pub require_healthy: bool,
 
-    /// Number of threads to use for processing (default is number of available cores)
+    /// Number of threads to use for processing (default is number of available cores,
+    /// taking container CPU limits into account)
     #[structopt(short, long, env = "VECTOR_THREADS")]
     pub threads: Option<usize>,
 
@@ -112,6 +119,11 @@ pub struct RootOpts {
     /// Watch for changes in configuration file, and reload accordingly.
     #[structopt(short, long, env = "VECTOR_WATCH_CONFIG")]
     pub watch_config: bool,
+
+    /// Fail to load the configuration when it references environment variables
+    /// that aren't set, instead of substituting an empty string for them.
+    #[structopt(long, env = "VECTOR_STRICT_ENV_VARS")]
+    pub strict_env_vars: bool,
 }
 
 impl RootOpts {
@@ -122,6 +134,7 @@ impl RootOpts {
             (&self.config_paths_toml, Some(config::Format::TOML)),
             (&self.config_paths_json, Some(config::Format::JSON)),
             (&self.config_paths_yaml, Some(config::Format::YAML)),
+            (&self.config_dirs, None),
         ])
     }
 }
@@ -1,8 +1,8 @@
 #[cfg(feature = "api")]
 use super::api;
 use super::{
-    compiler, default_data_dir, Config, GlobalOptions, SinkConfig, SinkOuter, SourceConfig,
-    TestDefinition, TransformConfig, TransformOuter,
+    compiler, default_data_dir, Config, EnrichmentTableConfig, GlobalOptions, SecretBackendConfig,
+    SinkConfig, SinkOuter, SourceConfig, TestDefinition, TransformConfig, TransformOuter,
 };
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
@@ -23,6 +23,12 @@ pub struct ConfigBuilder {
     pub transforms: IndexMap<String, TransformOuter>,
     #[serde(default)]
     pub tests: Vec<TestDefinition>,
+    #[serde(default)]
+    pub enrichment_tables: IndexMap<String, EnrichmentTableConfig>,
+    /// Secret backends are only used while loading, to resolve the secret
+    /// references of configs.
+    #[serde(default)]
+    pub secret: IndexMap<String, SecretBackendConfig>,
 }
 
 impl Clone for ConfigBuilder {
@@ -62,6 +68,7 @@ impl ConfigBuilder {
             healthcheck: true,
             inner: Box::new(sink),
             inputs,
+            dead_letter: false,
         };
 
         self.sinks.insert(name.into(), sink);
@@ -126,6 +133,16 @@ impl ConfigBuilder {
                 errors.push(format!("duplicate test name found: {}", wt.name));
             }
         });
+        with.enrichment_tables.keys().for_each(|k| {
+            if self.enrichment_tables.contains_key(k) {
+                errors.push(format!("duplicate enrichment table name found: {}", k));
+            }
+        });
+        with.secret.keys().for_each(|k| {
+            if self.secret.contains_key(k) {
+                errors.push(format!("duplicate secret backend name found: {}", k));
+            }
+        });
         if !errors.is_empty() {
             return Err(errors);
         }
@@ -134,6 +151,8 @@ impl ConfigBuilder {
         self.sinks.extend(with.sinks);
         self.transforms.extend(with.transforms);
         self.tests.extend(with.tests);
+        self.enrichment_tables.extend(with.enrichment_tables);
+        self.secret.extend(with.secret);
 
         Ok(())
     }
@@ -10,6 +10,7 @@ pub fn compile(raw: ConfigBuilder, deny_warnings: bool) -> Result<Config, Vec<St
         sinks: raw.sinks,
         transforms: raw.transforms,
         tests: raw.tests,
+        enrichment_tables: raw.enrichment_tables,
         expansions: Default::default(),
     };
 
@@ -14,9 +14,24 @@ impl ConfigDiff {
     }
 
     pub fn new(old: &Config, new: &Config) -> Self {
+        let mut transforms = Difference::new(&old.transforms, &new.transforms);
+
+        // Transforms hold on to the enrichment tables they were built with, so
+        // all of them are rebuilt when the tables change.
+        let old_tables = serde_json::to_vec(&old.enrichment_tables).unwrap();
+        let new_tables = serde_json::to_vec(&new.enrichment_tables).unwrap();
+        if old_tables != new_tables {
+            transforms.to_change.extend(
+                new.transforms
+                    .keys()
+                    .filter(|name| old.transforms.contains_key(*name))
+                    .cloned(),
+            );
+        }
+
         ConfigDiff {
             sources: Difference::new(&old.sources, &new.sources),
-            transforms: Difference::new(&old.transforms, &new.transforms),
+            transforms,
             sinks: Difference::new(&old.sinks, &new.sinks),
         }
     }
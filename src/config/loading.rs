@@ -1,9 +1,10 @@
 use super::{builder::ConfigBuilder, format, handle_warnings, vars, Config, Format, FormatHint};
+use crate::secrets;
 use glob::glob;
 use lazy_static::lazy_static;
 use std::{
     collections::HashMap,
-    fs::File,
+    fs::{self, File},
     path::{Path, PathBuf},
     sync::Mutex,
 };
@@ -66,7 +67,17 @@ pub fn process_paths(config_paths: &[(PathBuf, FormatHint)]) -> Option<Vec<(Path
         }
 
         for path in matches {
-            paths.push((path, *format));
+            if path.is_dir() {
+                match config_dir_files(&path) {
+                    Ok(files) => paths.extend(files.into_iter().map(|file| (file, *format))),
+                    Err(error) => {
+                        error!(message = "Failed to read config directory.", path = ?path, %error);
+                        return None;
+                    }
+                }
+            } else {
+                paths.push((path, *format));
+            }
         }
     }
 
@@ -78,48 +89,73 @@ pub fn process_paths(config_paths: &[(PathBuf, FormatHint)]) -> Option<Vec<(Path
     Some(paths)
 }
 
-pub fn load_from_paths(
+/// The files of a config directory whose format is known from their name.
+fn config_dir_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && Format::from_path(&path).is_ok() {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Loads the config from the paths. With `strict_env_vars`, referencing an
+/// environment variable that isn't set is an error rather than a warning.
+pub async fn load_from_paths(
     config_paths: &[(PathBuf, FormatHint)],
     deny_warnings: bool,
+    strict_env_vars: bool,
 ) -> Result<Config, Vec<String>> {
-    load_builder_from_paths(config_paths, deny_warnings)?.build_with(deny_warnings)
+    load_builder_from_paths(config_paths, deny_warnings, strict_env_vars)
+        .await?
+        .build_with(deny_warnings)
 }
 
-pub fn load_builder_from_paths(
+/// Loads the configs from the paths, resolving the secret references in
+/// any of them with the secret backends declared in any of them.
+pub async fn load_builder_from_paths(
     config_paths: &[(PathBuf, FormatHint)],
     deny_warnings: bool,
+    strict_env_vars: bool,
 ) -> Result<ConfigBuilder, Vec<String>> {
     let mut inputs = Vec::new();
     let mut errors = Vec::new();
 
     for (path, format) in config_paths {
         if let Some(file) = open_config(&path) {
-            inputs.push((file, format.or_else(move || Format::from_path(&path).ok())));
+            let format = format.or_else(move || Format::from_path(&path).ok());
+            match prepare_input(file, deny_warnings, strict_env_vars) {
+                Ok(input) => inputs.push((input, format)),
+                Err(errs) => errors.extend(errs),
+            }
         } else {
             errors.push(format!("Config file not found in path: {:?}.", path));
         };
     }
 
-    if errors.is_empty() {
-        load_from_inputs(inputs, deny_warnings)
-    } else {
-        Err(errors)
+    if !errors.is_empty() {
+        return Err(errors);
     }
+
+    let inputs = secrets::interpolate(inputs).await?;
+    load_from_inputs(inputs)
 }
 
 pub fn load_from_str(input: &str, format: FormatHint) -> Result<Config, Vec<String>> {
-    load_from_inputs(std::iter::once((input.as_bytes(), format)), false)?.build()
+    let input = prepare_input(input.as_bytes(), false, false)?;
+    load_from_inputs(std::iter::once((input, format)))?.build()
 }
 
 fn load_from_inputs(
-    inputs: impl IntoIterator<Item = (impl std::io::Read, FormatHint)>,
-    deny_warnings: bool,
+    inputs: impl IntoIterator<Item = (String, FormatHint)>,
 ) -> Result<ConfigBuilder, Vec<String>> {
     let mut config = Config::builder();
     let mut errors = Vec::new();
 
     for (input, format) in inputs {
-        if let Err(errs) = load(input, format, deny_warnings).and_then(|n| config.append(n)) {
+        if let Err(errs) = format::deserialize(&input, format).and_then(|n| config.append(n)) {
             // TODO: add back paths
             errors.extend(errs.iter().map(|e| e.to_string()));
         }
@@ -147,11 +183,12 @@ fn open_config(path: &Path) -> Option<File> {
     }
 }
 
-fn load(
+/// Reads the input, interpolating environment variables.
+fn prepare_input(
     mut input: impl std::io::Read,
-    format: FormatHint,
     deny_warnings: bool,
-) -> Result<ConfigBuilder, Vec<String>> {
+    strict_env_vars: bool,
+) -> Result<String, Vec<String>> {
     let mut source_string = String::new();
     input
         .read_to_string(&mut source_string)
@@ -164,7 +201,24 @@ fn load(
         }
     }
     let (with_vars, warnings) = vars::interpolate(&source_string, &vars);
-    handle_warnings(warnings, deny_warnings)?;
+    // The only warnings of interpolation are unknown variables.
+    handle_warnings(warnings, deny_warnings || strict_env_vars)?;
+
+    Ok(with_vars)
+}
 
-    format::deserialize(&with_vars, format)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict_env_vars() {
+        let config = r#"data_dir = "${VECTOR_NOT_A_SET_VAR:-/var/lib}/${VECTOR_NOT_A_SET_VAR}""#;
+        assert!(prepare_input(config.as_bytes(), false, false).is_ok());
+        let errors = prepare_input(config.as_bytes(), false, true).unwrap_err();
+        assert_eq!(
+            errors,
+            vec!["Unknown env var in config. name = \"VECTOR_NOT_A_SET_VAR\"".to_owned()]
+        );
+    }
 }
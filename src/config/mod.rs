@@ -19,7 +19,7 @@ mod builder;
 mod compiler;
 pub mod component;
 mod diff;
-mod format;
+pub(crate) mod format;
 mod loading;
 mod log_schema;
 mod unit_test;
@@ -27,6 +27,8 @@ mod validation;
 mod vars;
 pub mod watcher;
 
+pub use crate::enrichment_tables::EnrichmentTableConfig;
+pub use crate::secrets::SecretBackendConfig;
 pub use builder::ConfigBuilder;
 pub use diff::ConfigDiff;
 pub use format::{Format, FormatHint};
@@ -44,6 +46,7 @@ pub struct Config {
     pub sinks: IndexMap<String, SinkOuter>,
     pub transforms: IndexMap<String, TransformOuter>,
     tests: Vec<TestDefinition>,
+    pub enrichment_tables: IndexMap<String, EnrichmentTableConfig>,
     expansions: IndexMap<String, Vec<String>>,
 }
 
@@ -183,10 +186,27 @@ pub struct SinkOuter {
     #[serde(default = "healthcheck_default")]
     pub healthcheck: bool,
     pub inputs: Vec<String>,
+    /// Exposes the events the sink gives up on as its `dead_letter` output.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub dead_letter: bool,
     #[serde(flatten)]
     pub inner: Box<dyn SinkConfig>,
 }
 
+impl SinkOuter {
+    /// The names of the outputs of the sink, including the dead letter one.
+    pub fn named_outputs(&self) -> Vec<String> {
+        let mut outputs = self.inner.named_outputs();
+        if self.dead_letter {
+            outputs.push(DEAD_LETTER.to_owned());
+        }
+        outputs
+    }
+}
+
 #[async_trait]
 #[typetag::serde(tag = "type")]
 pub trait SinkConfig: core::fmt::Debug + Send + Sync {
@@ -203,22 +223,52 @@ pub trait SinkConfig: core::fmt::Debug + Send + Sync {
     fn resources(&self) -> Vec<Resource> {
         Vec::new()
     }
+
+    /// The names of the outputs this sink exposes. Other components consume
+    /// them as `<sink>.<output>`, and they carry events of the sink's input
+    /// type that it couldn't deliver.
+    fn named_outputs(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Whether the sink sends the events it gives up on to the `dead_letter`
+    /// output when `dead_letter` is set.
+    fn supports_dead_letter(&self) -> bool {
+        false
+    }
 }
 
+/// The output of sinks with `dead_letter` set.
+pub const DEAD_LETTER: &str = "dead_letter";
+
 #[derive(Debug, Clone)]
 pub struct SinkContext {
     pub(super) acker: Acker,
+    pub(super) outputs: HashMap<String, Pipeline>,
 }
 
 impl SinkContext {
     #[cfg(test)]
     pub fn new_test() -> Self {
-        Self { acker: Acker::Null }
+        Self {
+            acker: Acker::Null,
+            outputs: HashMap::new(),
+        }
     }
 
     pub fn acker(&self) -> Acker {
         self.acker.clone()
     }
+
+    /// The pipeline of one of the sink's named outputs, if it declared it.
+    pub fn output(&self, name: &str) -> Option<Pipeline> {
+        self.outputs.get(name).cloned()
+    }
+
+    /// The pipeline of the dead letter output, if the sink has one.
+    pub fn dead_letter(&self) -> Option<Pipeline> {
+        self.output(DEAD_LETTER)
+    }
 }
 
 pub type SinkDescription = ComponentDescription<Box<dyn SinkConfig>>;
@@ -249,6 +299,13 @@ pub trait TransformConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone
     fn expand(&mut self) -> crate::Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
         Ok(None)
     }
+
+    /// The names of the outputs this transform exposes besides its main one.
+    /// Other components consume them as `<transform>.<output>`, and they
+    /// carry events of the transform's input type.
+    fn named_outputs(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 dyn_clone::clone_trait_object!(TransformConfig);
@@ -497,6 +554,59 @@ mod test {
         assert_eq!("then", config.global.log_schema.timestamp_key().to_string());
     }
 
+    #[test]
+    #[cfg(feature = "sinks-http")]
+    fn dead_letter_output() {
+        let config = load_from_str(
+            r#"
+            [sources.in]
+            type = "file"
+            include = ["/var/log/messages"]
+
+            [sinks.out]
+            type = "http"
+            inputs = ["in"]
+            uri = "http://localhost:8080"
+            encoding = "json"
+            dead_letter = true
+
+            [sinks.failed]
+            type = "console"
+            inputs = ["out.dead_letter"]
+            encoding = "json"
+            "#,
+            Some(Format::TOML),
+        )
+        .unwrap();
+
+        assert_eq!(config.sinks["out"].named_outputs(), vec!["dead_letter"]);
+        assert!(config.sinks["failed"].named_outputs().is_empty());
+    }
+
+    #[test]
+    fn dead_letter_output_unsupported() {
+        let errors = load_from_str(
+            r#"
+            [sources.in]
+            type = "file"
+            include = ["/var/log/messages"]
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding = "json"
+            dead_letter = true
+            "#,
+            Some(Format::TOML),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec!["Sink \"out\" doesn't support `dead_letter`.".to_owned()]
+        );
+    }
+
     #[test]
     fn config_append() {
         let mut config: ConfigBuilder = format::deserialize(
@@ -3,7 +3,7 @@ use crate::config::{self, TransformConfig};
 use crate::{
     conditions::{Condition, ConditionConfig},
     event::{Event, Value},
-    transforms::Transform,
+    transforms::{Transform, TransformOutputs},
 };
 use indexmap::IndexMap;
 use std::{collections::HashMap, path::PathBuf};
@@ -12,7 +12,7 @@ pub async fn build_unit_tests_main(
     path: PathBuf,
     format: config::FormatHint,
 ) -> Result<Vec<UnitTest>, Vec<String>> {
-    let config = super::loading::load_builder_from_paths(&[(path, format)], false)?;
+    let config = super::loading::load_builder_from_paths(&[(path, format)], false, false).await?;
 
     // Ignore failures on calls other than the first
     crate::config::LOG_SCHEMA
@@ -35,10 +35,13 @@ async fn build_unit_tests(builder: ConfigBuilder) -> Result<Vec<UnitTest>, Vec<S
         sinks: builder.sinks,
         transforms: builder.transforms,
         tests: builder.tests,
+        enrichment_tables: builder.enrichment_tables,
         expansions: Default::default(),
     };
 
     super::compiler::expand_macros(&mut config)?;
+    crate::enrichment_tables::stage(&config.enrichment_tables)?;
+    crate::enrichment_tables::commit();
 
     for test in &config.tests {
         match build_unit_test(test, &config).await {
@@ -72,6 +75,9 @@ struct UnitTestTransform {
     transform: Transform,
     config: Box<dyn TransformConfig>,
     next: Vec<String>,
+    /// The named outputs of the transform that are part of the test, and the
+    /// transforms consuming each of them.
+    named: Vec<(String, Vec<String>)>,
 }
 
 struct UnitTestCheck {
@@ -107,20 +113,27 @@ fn events_to_string(name: &str, events: &[Event]) -> String {
 
 fn walk(
     node: &str,
-    mut inputs: Vec<Event>,
+    inputs: Vec<Event>,
     transforms: &mut IndexMap<String, UnitTestTransform>,
     aggregated_results: &mut HashMap<String, (Vec<Event>, Vec<Event>)>,
 ) {
     let mut results = Vec::new();
     let mut targets = Vec::new();
+    let mut named = Vec::new();
 
     // Use `remove` to take ownership.
     if let Some((key, mut target)) = transforms.remove_entry(node) {
         match target.transform {
             Transform::Function(ref mut t) => {
+                let mut outputs = TransformOutputs::new(node);
                 for input in inputs.clone() {
-                    t.transform(&mut results, input)
+                    t.transform_outputs(&mut outputs, input)
+                }
+                for (output, next) in &target.named {
+                    let events = outputs.named.remove(output).unwrap_or_default();
+                    named.push((format!("{}.{}", node, output), events, next.clone()));
                 }
+                results = outputs.primary;
                 targets = target.next.clone();
                 transforms.insert(key, target);
             }
@@ -146,7 +159,8 @@ fn walk(
                     transform:  futures::executor::block_on(target.config.clone().build())
                         .expect("Failed to build a known valid transform config. Things may have changed during runtime."),
                     config: target.config,
-                    next: target.next
+                    next: target.next,
+                    named: target.named,
                 });
             }
         }
@@ -156,6 +170,22 @@ fn walk(
         walk(&child, results.clone(), transforms, aggregated_results);
     }
 
+    for (output, events, next) in named {
+        for child in next {
+            walk(&child, events.clone(), transforms, aggregated_results);
+        }
+        aggregate(&output, inputs.clone(), events, aggregated_results);
+    }
+
+    aggregate(node, inputs, results, aggregated_results);
+}
+
+fn aggregate(
+    node: &str,
+    mut inputs: Vec<Event>,
+    mut results: Vec<Event>,
+    aggregated_results: &mut HashMap<String, (Vec<Event>, Vec<Event>)>,
+) {
     if let Some((mut e_inputs, mut e_results)) = aggregated_results.remove(node) {
         inputs.append(&mut e_inputs);
         results.append(&mut e_results);
@@ -317,6 +347,15 @@ fn reduce_transforms(
     });
 }
 
+/// The full names, `<transform>.<output>`, of the named outputs of a transform.
+fn named_output_names(name: &str, transform: &dyn TransformConfig) -> Vec<String> {
+    transform
+        .named_outputs()
+        .into_iter()
+        .map(|output| format!("{}.{}", name, output))
+        .collect()
+}
+
 fn build_input(config: &Config, input: &TestInput) -> Result<(Vec<String>, Event), String> {
     let target = config.get_inputs(&input.insert_at);
 
@@ -400,12 +439,19 @@ async fn build_unit_test(
     };
 
     // Maps transform names with their output targets (transforms that use it as
-    // an input).
-    let mut transform_outputs: IndexMap<String, IndexMap<String, ()>> = config
-        .transforms
-        .iter()
-        .map(|(k, _)| (k.clone(), IndexMap::new()))
-        .collect();
+    // an input). The named outputs of a transform, `<transform>.<output>`, are
+    // targets of the transform with targets of their own.
+    let mut transform_outputs: IndexMap<String, IndexMap<String, ()>> = IndexMap::new();
+    for (name, transform) in &config.transforms {
+        let named = named_output_names(name, transform.inner.as_ref());
+        transform_outputs.insert(
+            name.clone(),
+            named.iter().map(|n| (n.clone(), ())).collect(),
+        );
+        for output in named {
+            transform_outputs.insert(output, IndexMap::new());
+        }
+    }
 
     config.transforms.iter().for_each(|(k, t)| {
         t.inputs.iter().for_each(|i| {
@@ -417,7 +463,7 @@ async fn build_unit_test(
 
     for (i, (input_target, _)) in inputs.iter().enumerate() {
         for target in input_target {
-            if !transform_outputs.contains_key(target) {
+            if !config.transforms.contains_key(target) {
                 errors.push(format!(
                     "inputs[{}]: unable to locate target transform '{}'",
                     i, target
@@ -453,7 +499,23 @@ async fn build_unit_test(
     // Build reduced transforms.
     let mut transforms: IndexMap<String, UnitTestTransform> = IndexMap::new();
     for (name, transform_config) in &config.transforms {
-        if let Some(outputs) = transform_outputs.remove(name) {
+        if let Some(outputs) = transform_outputs.get(name) {
+            let named_outputs = transform_config.inner.named_outputs();
+            let named_names = named_output_names(name, transform_config.inner.as_ref());
+            let next = outputs
+                .keys()
+                .filter(|k| !named_names.contains(*k))
+                .cloned()
+                .collect();
+            let named = named_outputs
+                .into_iter()
+                .zip(&named_names)
+                .filter_map(|(output, full_name)| {
+                    transform_outputs
+                        .get(full_name)
+                        .map(|next| (output, next.keys().cloned().collect()))
+                })
+                .collect();
             match transform_config.inner.build().await {
                 Ok(transform) => {
                     transforms.insert(
@@ -461,7 +523,8 @@ async fn build_unit_test(
                         UnitTestTransform {
                             transform,
                             config: transform_config.inner.clone(),
-                            next: outputs.into_iter().map(|(k, _)| k).collect(),
+                            next,
+                            named,
                         },
                     );
                 }
@@ -481,7 +544,13 @@ async fn build_unit_test(
     }
 
     definition.outputs.iter().for_each(|o| {
-        if !transforms.contains_key(&o.extract_from) {
+        let built = transforms.contains_key(&o.extract_from)
+            || transforms.iter().any(|(name, t)| {
+                t.named
+                    .iter()
+                    .any(|(output, _)| format!("{}.{}", name, output) == o.extract_from)
+            });
+        if !built {
             let targets = inputs.iter().map(|(i, _)| i).flatten().collect::<Vec<_>>();
             if targets.len() == 1 {
                 errors.push(format!(
@@ -1052,6 +1121,110 @@ mod tests {
         assert_eq!(tests[0].run().1, Vec::<String>::new());
     }
 
+    #[cfg(feature = "transforms-route")]
+    #[tokio::test]
+    async fn test_route_outputs() {
+        let config: ConfigBuilder = toml::from_str(
+            r#"
+[transforms.foo]
+  inputs = ["ignored"]
+  type = "route"
+  route.errors."message.eq" = "error"
+  route.warnings."message.eq" = "warning"
+
+[transforms.bar]
+  inputs = ["foo.errors"]
+  type = "add_fields"
+  [transforms.bar.fields]
+    new_field = "new field added"
+
+[[tests]]
+  name = "successful route test"
+
+  [tests.input]
+    insert_at = "foo"
+    value = "error"
+
+  [[tests.outputs]]
+    extract_from = "foo.errors"
+    [[tests.outputs.conditions]]
+      type = "check_fields"
+      "message.equals" = "error"
+
+  [[tests.outputs]]
+    extract_from = "bar"
+    [[tests.outputs.conditions]]
+      type = "check_fields"
+      "message.equals" = "error"
+      "new_field.equals" = "new field added"
+
+[[tests]]
+  name = "unmatched route test"
+  no_outputs_from = ["foo.errors", "foo.warnings"]
+
+  [tests.input]
+    insert_at = "foo"
+    value = "info"
+
+  [[tests.outputs]]
+    extract_from = "foo._unmatched"
+    [[tests.outputs.conditions]]
+      type = "check_fields"
+      "message.equals" = "info"
+      "#,
+        )
+        .unwrap();
+
+        let mut tests = build_unit_tests(config).await.unwrap();
+        assert_eq!(tests[0].run().1, Vec::<String>::new());
+        assert_eq!(tests[1].run().1, Vec::<String>::new());
+    }
+
+    #[cfg(feature = "transforms-json_parser")]
+    #[tokio::test]
+    async fn test_dropped_output() {
+        let config: ConfigBuilder = toml::from_str(
+            r#"
+[transforms.foo]
+  inputs = ["ignored"]
+  type = "json_parser"
+  reroute_dropped = true
+
+[transforms.bar]
+  inputs = ["foo.dropped"]
+  type = "add_fields"
+  [transforms.bar.fields]
+    new_field = "new field added"
+
+[[tests]]
+  name = "successful dropped test"
+  no_outputs_from = ["foo"]
+
+  [tests.input]
+    insert_at = "foo"
+    value = "not json"
+
+  [[tests.outputs]]
+    extract_from = "foo.dropped"
+    [[tests.outputs.conditions]]
+      type = "check_fields"
+      "message.equals" = "not json"
+      "metadata.dropped.component.equals" = "foo"
+
+  [[tests.outputs]]
+    extract_from = "bar"
+    [[tests.outputs.conditions]]
+      type = "check_fields"
+      "message.equals" = "not json"
+      "new_field.equals" = "new field added"
+      "#,
+        )
+        .unwrap();
+
+        let mut tests = build_unit_tests(config).await.unwrap();
+        assert_eq!(tests[0].run().1, Vec::<String>::new());
+    }
+
     #[tokio::test]
     async fn test_fail_no_outputs() {
         let config: ConfigBuilder = toml::from_str(
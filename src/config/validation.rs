@@ -1,5 +1,5 @@
 use super::{Config, DataType, Resource};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub fn check_shape(config: &Config) -> Result<(), Vec<String>> {
     let mut errors = vec![];
@@ -20,6 +20,12 @@ pub fn check_shape(config: &Config) -> Result<(), Vec<String>> {
         .transforms
         .iter()
         .map(|(name, transform)| ("transform", name.clone(), transform.inputs.clone()));
+    for (name, sink) in &config.sinks {
+        if sink.dead_letter && !sink.inner.supports_dead_letter() {
+            errors.push(format!("Sink {:?} doesn't support `dead_letter`.", name));
+        }
+    }
+
     for (output_type, name, inputs) in sink_inputs.chain(transform_inputs) {
         if inputs.is_empty() {
             errors.push(format!(
@@ -30,7 +36,10 @@ pub fn check_shape(config: &Config) -> Result<(), Vec<String>> {
         }
 
         for input in inputs {
-            if !config.sources.contains_key(&input) && !config.transforms.contains_key(&input) {
+            if !config.sources.contains_key(&input)
+                && !config.transforms.contains_key(&input)
+                && !named_outputs(config).any(|(_, output)| output == input)
+            {
                 errors.push(format!(
                     "Input {:?} for {} {:?} doesn't exist.",
                     input, output_type, name
@@ -81,15 +90,28 @@ pub fn warnings(config: &Config) -> Vec<String> {
         .transforms
         .keys()
         .map(|name| ("transform", name.clone()));
+    // Consuming any of a transform's named outputs counts as consuming it.
+    let mut outputs = HashMap::<String, HashSet<String>>::new();
+    for (name, output) in named_outputs(config) {
+        outputs.entry(name).or_default().insert(output);
+    }
     for (input_type, name) in transform_names.chain(source_names) {
+        let is_consumed = |inputs: &Vec<String>| {
+            inputs.iter().any(|input| {
+                input == &name
+                    || outputs
+                        .get(&name)
+                        .map_or(false, |outputs| outputs.contains(input))
+            })
+        };
         if !config
             .transforms
             .iter()
-            .any(|(_, transform)| transform.inputs.contains(&name))
+            .any(|(_, transform)| is_consumed(&transform.inputs))
             && !config
                 .sinks
                 .iter()
-                .any(|(_, sink)| sink.inputs.contains(&name))
+                .any(|(_, sink)| is_consumed(&sink.inputs))
         {
             warnings.push(format!(
                 "{} {:?} has no consumers",
@@ -102,6 +124,24 @@ pub fn warnings(config: &Config) -> Vec<String> {
     warnings
 }
 
+/// Pairs of transform and sink names and the full names of their named
+/// outputs.
+fn named_outputs(config: &Config) -> impl Iterator<Item = (String, String)> + '_ {
+    let transforms = config
+        .transforms
+        .iter()
+        .map(|(name, transform)| (name, transform.inner.named_outputs()));
+    let sinks = config
+        .sinks
+        .iter()
+        .map(|(name, sink)| (name, sink.named_outputs()));
+    transforms.chain(sinks).flat_map(|(name, outputs)| {
+        outputs
+            .into_iter()
+            .map(move |output| (name.clone(), format!("{}.{}", name, output)))
+    })
+}
+
 pub fn typecheck(config: &Config) -> Result<(), Vec<String>> {
     Graph::from(config).typecheck()
 }
@@ -239,10 +279,28 @@ impl From<&Config> for Graph {
                 config.inner.output_type(),
                 config.inputs.clone(),
             );
+
+            // Named outputs pass on events the transform received, so they
+            // behave like a transform with the same inputs that doesn't
+            // change the event type.
+            for output in config.inner.named_outputs() {
+                graph.add_transform(
+                    &format!("{}.{}", name, output),
+                    config.inner.input_type(),
+                    config.inner.input_type(),
+                    config.inputs.clone(),
+                );
+            }
         }
 
         for (name, config) in config.sinks.iter() {
             graph.add_sink(name, config.inner.input_type(), config.inputs.clone());
+
+            // Named outputs of sinks carry events that didn't make it out of
+            // the sink, so they start new paths like a source would.
+            for output in config.named_outputs() {
+                graph.add_source(&format!("{}.{}", name, output), config.inner.input_type());
+            }
         }
 
         graph
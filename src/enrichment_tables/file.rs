@@ -0,0 +1,362 @@
+use super::{default_reload_interval_secs, Condition, Reloadable, Row, Table};
+use crate::{event::Value, types::parse_conversion_map};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub encoding: Encoding,
+    /// Column names mapped to the type their values are converted to.
+    /// Columns not listed are kept as strings.
+    #[serde(default)]
+    pub schema: HashMap<String, String>,
+    #[serde(default = "default_reload_interval_secs")]
+    pub reload_interval_secs: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Encoding {
+    Csv {
+        #[serde(default = "default_delimiter")]
+        delimiter: char,
+        #[serde(default = "crate::serde::default_true")]
+        include_headers: bool,
+    },
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Encoding::Csv {
+            delimiter: default_delimiter(),
+            include_headers: true,
+        }
+    }
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+impl FileConfig {
+    pub fn build(&self) -> crate::Result<FileTable> {
+        let Encoding::Csv {
+            delimiter,
+            include_headers,
+        } = self.encoding;
+        if !delimiter.is_ascii() {
+            return Err("The CSV delimiter must be a single ASCII character".into());
+        }
+
+        // Validate the schema up front so a bad type is reported at load time.
+        parse_conversion_map(&self.schema)?;
+
+        let settings = CsvSettings {
+            delimiter: delimiter as u8,
+            include_headers,
+            schema: self.schema.clone(),
+        };
+
+        Ok(FileTable {
+            data: Reloadable::open(
+                &self.path,
+                Duration::from_secs(self.reload_interval_secs),
+                move |path| load_csv(path, &settings),
+            )?,
+            indexes: Mutex::new(HashMap::new()),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct CsvSettings {
+    delimiter: u8,
+    include_headers: bool,
+    schema: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+struct Data {
+    rows: Vec<Row>,
+}
+
+fn load_csv(path: &Path, settings: &CsvSettings) -> crate::Result<Data> {
+    let conversions = parse_conversion_map(&settings.schema)?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(settings.delimiter)
+        .has_headers(settings.include_headers)
+        .from_path(path)?;
+
+    let headers = if settings.include_headers {
+        reader
+            .headers()?
+            .iter()
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let mut row = Row::new();
+        for (i, field) in record.iter().enumerate() {
+            // Without headers, columns are named by their position.
+            let name = headers.get(i).cloned().unwrap_or_else(|| i.to_string());
+            let value = Value::from(field.to_owned());
+            let value = match conversions.get(&name) {
+                Some(conversion) => conversion.convert(value).map_err(|error| {
+                    format!(
+                        "Invalid value in column {:?} on line {}: {}",
+                        name,
+                        record.position().map_or(0, |position| position.line()),
+                        error
+                    )
+                })?,
+                None => value,
+            };
+            row.insert(name, value);
+        }
+        rows.push(row);
+    }
+
+    Ok(Data { rows })
+}
+
+/// Maps the string form of a column's values to the indices of the rows
+/// holding them.
+type Index = HashMap<String, Vec<usize>>;
+
+/// A table loaded from a CSV file. Indexes for exact matches are built the
+/// first time a column is used in a condition.
+#[derive(Debug)]
+pub struct FileTable {
+    data: Reloadable<Data>,
+    indexes: Mutex<HashMap<String, (usize, Arc<Index>)>>,
+}
+
+impl FileTable {
+    fn index(&self, data: &Arc<Data>, field: &str) -> Arc<Index> {
+        // Indexes are tagged with the address of the data they were built
+        // from so they are rebuilt after the file is reloaded.
+        let generation = Arc::as_ptr(data) as usize;
+        let mut indexes = self.indexes.lock().expect("lock poisoned");
+        if let Some((built_from, index)) = indexes.get(field) {
+            if *built_from == generation {
+                return Arc::clone(index);
+            }
+        }
+
+        let mut index = Index::new();
+        for (i, row) in data.rows.iter().enumerate() {
+            if let Some(value) = row.get(field) {
+                index.entry(value.to_string_lossy()).or_default().push(i);
+            }
+        }
+        let index = Arc::new(index);
+        indexes.insert(field.to_owned(), (generation, Arc::clone(&index)));
+        index
+    }
+}
+
+fn matches(row: &Row, condition: &Condition<'_>) -> bool {
+    match condition {
+        Condition::Equals { field, value } => row
+            .get(*field)
+            .map_or(false, |v| v.to_string_lossy() == value.to_string_lossy()),
+        Condition::BetweenDates { field, from, to } => match row.get(*field) {
+            Some(Value::Timestamp(timestamp)) => from <= timestamp && timestamp <= to,
+            _ => false,
+        },
+    }
+}
+
+impl Table for FileTable {
+    fn find_table_rows(&self, conditions: &[Condition<'_>]) -> Result<Vec<Row>, String> {
+        let data = self.data.get();
+
+        // Narrow down candidates with the index of the first exact match
+        // condition, and check the remaining conditions on those.
+        let candidates = conditions.iter().find_map(|condition| match condition {
+            Condition::Equals { field, value } => Some(
+                self.index(&data, field)
+                    .get(&value.to_string_lossy())
+                    .cloned()
+                    .unwrap_or_default(),
+            ),
+            Condition::BetweenDates { .. } => None,
+        });
+
+        let rows = match candidates {
+            Some(candidates) => candidates
+                .into_iter()
+                .map(|i| &data.rows[i])
+                .filter(|row| conditions.iter().all(|condition| matches(row, condition)))
+                .cloned()
+                .collect(),
+            None => data
+                .rows
+                .iter()
+                .filter(|row| conditions.iter().all(|condition| matches(row, condition)))
+                .cloned()
+                .collect(),
+        };
+
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::io::Write;
+
+    fn table(content: &str, schema: &[(&str, &str)]) -> (tempfile::TempDir, FileTable) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("table.csv");
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+
+        let config = FileConfig {
+            path,
+            encoding: Encoding::default(),
+            schema: schema
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            reload_interval_secs: 0,
+        };
+        let table = config.build().unwrap();
+        (dir, table)
+    }
+
+    #[test]
+    fn parses_config() {
+        let config: FileConfig = toml::from_str(
+            r#"
+            path = "/etc/vector/users.csv"
+            encoding.type = "csv"
+            encoding.delimiter = ";"
+            schema.joined = "timestamp|%Y-%m-%d"
+            "#,
+        )
+        .unwrap();
+
+        match config.encoding {
+            Encoding::Csv {
+                delimiter,
+                include_headers,
+            } => {
+                assert_eq!(delimiter, ';');
+                assert!(include_headers);
+            }
+        }
+        assert_eq!(config.reload_interval_secs, 60);
+    }
+
+    #[test]
+    fn finds_row_by_exact_match() {
+        let (_dir, table) = table("id,name\n1,Alice\n2,Bob\n", &[("id", "integer")]);
+
+        let row = table
+            .find_table_row(&[Condition::Equals {
+                field: "id",
+                value: Value::Integer(2),
+            }])
+            .unwrap();
+        assert_eq!(row["name"], Value::from("Bob"));
+        assert_eq!(row["id"], Value::Integer(2));
+
+        assert!(table
+            .find_table_row(&[Condition::Equals {
+                field: "id",
+                value: Value::Integer(3),
+            }])
+            .is_err());
+    }
+
+    #[test]
+    fn finds_rows_within_date_range() {
+        let (_dir, table) = table(
+            "user,team,joined\n\
+             alice,a,2020-01-01T00:00:00Z\n\
+             alice,b,2020-06-01T00:00:00Z\n\
+             bob,a,2020-03-01T00:00:00Z\n",
+            &[("joined", "timestamp")],
+        );
+
+        let rows = table
+            .find_table_rows(&[
+                Condition::Equals {
+                    field: "user",
+                    value: Value::from("alice"),
+                },
+                Condition::BetweenDates {
+                    field: "joined",
+                    from: Utc.ymd(2020, 5, 1).and_hms(0, 0, 0),
+                    to: Utc.ymd(2020, 7, 1).and_hms(0, 0, 0),
+                },
+            ])
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["team"], Value::from("b"));
+    }
+
+    #[test]
+    fn names_columns_by_position_without_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("table.tsv");
+        std::fs::write(&path, "a\t1\nb\t2\n").unwrap();
+
+        let table = FileConfig {
+            path,
+            encoding: Encoding::Csv {
+                delimiter: '\t',
+                include_headers: false,
+            },
+            schema: HashMap::new(),
+            reload_interval_secs: 0,
+        }
+        .build()
+        .unwrap();
+
+        let row = table
+            .find_table_row(&[Condition::Equals {
+                field: "0",
+                value: Value::from("b"),
+            }])
+            .unwrap();
+        assert_eq!(row["1"], Value::from("2"));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("table.csv");
+        std::fs::write(&path, "id\nnot a number\n").unwrap();
+
+        let mut schema = HashMap::new();
+        schema.insert("id".to_owned(), "integer".to_owned());
+        assert!(FileConfig {
+            path,
+            encoding: Encoding::default(),
+            schema,
+            reload_interval_secs: 0,
+        }
+        .build()
+        .is_err());
+    }
+}
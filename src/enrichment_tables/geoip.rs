@@ -0,0 +1,135 @@
+use super::{default_reload_interval_secs, Condition, Reloadable, Row, Table};
+use crate::{event::Value, transforms::geoip::lookup};
+use maxminddb::MaxMindDBError;
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+use std::{net::IpAddr, path::PathBuf, str::FromStr, time::Duration};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GeoipConfig {
+    pub path: PathBuf,
+    #[serde(default = "default_reload_interval_secs")]
+    pub reload_interval_secs: u64,
+}
+
+impl GeoipConfig {
+    pub fn build(&self) -> crate::Result<GeoipTable> {
+        Ok(GeoipTable {
+            reader: Reloadable::open(
+                &self.path,
+                Duration::from_secs(self.reload_interval_secs),
+                |path| Ok(maxminddb::Reader::open_readfile(path)?),
+            )?,
+        })
+    }
+}
+
+/// A table backed by a MaxMind database. It is queried with a single `ip`
+/// condition and returns the same fields the `geoip` transform adds, or no
+/// rows if the address isn't in the database.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct GeoipTable {
+    #[derivative(Debug = "ignore")]
+    reader: Reloadable<maxminddb::Reader<Vec<u8>>>,
+}
+
+impl Table for GeoipTable {
+    fn find_table_rows(&self, conditions: &[Condition<'_>]) -> Result<Vec<Row>, String> {
+        let ip = match conditions {
+            [Condition::Equals { field: "ip", value }] => {
+                IpAddr::from_str(&value.to_string_lossy())
+                    .map_err(|_| format!("invalid IP address {:?}", value.to_string_lossy()))?
+            }
+            _ => return Err("GeoIP tables only support a single condition on \"ip\"".to_owned()),
+        };
+
+        let reader = self.reader.get();
+        match reader.lookup::<IgnoredAny>(ip) {
+            Ok(_) => (),
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(Vec::new()),
+            Err(error) => return Err(error.to_string()),
+        }
+
+        let row = match lookup(&reader, Some(ip)).map_err(|error| error.to_string())? {
+            serde_json::Value::Object(fields) => fields
+                .into_iter()
+                .map(|(key, value)| (key, Value::from(value)))
+                .collect(),
+            _ => Row::new(),
+        };
+
+        Ok(vec![row])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(path: &str) -> GeoipTable {
+        GeoipConfig {
+            path: path.into(),
+            reload_interval_secs: 0,
+        }
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_city() {
+        let table = table("tests/data/GeoIP2-City-Test.mmdb");
+        let row = table
+            .find_table_row(&[Condition::Equals {
+                field: "ip",
+                value: Value::from("2.125.160.216"),
+            }])
+            .unwrap();
+
+        assert_eq!(row["city_name"], Value::from("Boxford"));
+        assert_eq!(row["country_code"], Value::from("GB"));
+    }
+
+    #[test]
+    fn finds_isp() {
+        let table = table("tests/data/GeoIP2-ISP-Test.mmdb");
+        let row = table
+            .find_table_row(&[Condition::Equals {
+                field: "ip",
+                value: Value::from("208.192.1.2"),
+            }])
+            .unwrap();
+
+        assert_eq!(row["autonomous_system_number"], Value::Integer(701));
+    }
+
+    #[test]
+    fn finds_nothing_for_unknown_ip() {
+        let table = table("tests/data/GeoIP2-City-Test.mmdb");
+        let rows = table
+            .find_table_rows(&[Condition::Equals {
+                field: "ip",
+                value: Value::from("10.1.12.1"),
+            }])
+            .unwrap();
+
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn rejects_other_conditions() {
+        let table = table("tests/data/GeoIP2-City-Test.mmdb");
+        assert!(table
+            .find_table_rows(&[Condition::Equals {
+                field: "address",
+                value: Value::from("2.125.160.216"),
+            }])
+            .is_err());
+        assert!(table
+            .find_table_rows(&[Condition::Equals {
+                field: "ip",
+                value: Value::from("not an ip"),
+            }])
+            .is_err());
+    }
+}
@@ -0,0 +1,237 @@
+//! Enrichment tables are datasets loaded from files at startup which can be
+//! queried by transforms, for example to join events to an asset inventory
+//! or user directory. Tables are configured in the global
+//! `enrichment_tables` section and are reloaded when their file changes.
+
+use crate::{
+    event::Value,
+    internal_events::{EnrichmentTableReloadFailed, EnrichmentTableReloaded},
+};
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock, Weak},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+pub mod file;
+#[cfg(feature = "transforms-geoip")]
+pub mod geoip;
+
+type Tables = HashMap<String, Arc<dyn Table>>;
+
+/// The tables of the running topology.
+static TABLES: Lazy<RwLock<Tables>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// The tables of a topology being built, which replace `TABLES` only once it
+/// has been started.
+static PENDING: Lazy<RwLock<Option<Tables>>> = Lazy::new(|| RwLock::new(None));
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EnrichmentTableConfig {
+    File(file::FileConfig),
+    #[cfg(feature = "transforms-geoip")]
+    Geoip(geoip::GeoipConfig),
+}
+
+impl EnrichmentTableConfig {
+    pub fn build(&self) -> crate::Result<Arc<dyn Table>> {
+        Ok(match self {
+            EnrichmentTableConfig::File(config) => Arc::new(config.build()?),
+            #[cfg(feature = "transforms-geoip")]
+            EnrichmentTableConfig::Geoip(config) => Arc::new(config.build()?),
+        })
+    }
+}
+
+/// A single record of a table.
+pub type Row = BTreeMap<String, Value>;
+
+/// A condition a row has to satisfy to be returned by a lookup.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition<'a> {
+    /// The value of the field is equal to the given value.
+    Equals { field: &'a str, value: Value },
+    /// The field holds a timestamp within the given range, inclusive.
+    BetweenDates {
+        field: &'a str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+}
+
+pub trait Table: Send + Sync + fmt::Debug {
+    /// Returns every row matching all of the given conditions.
+    fn find_table_rows(&self, conditions: &[Condition<'_>]) -> Result<Vec<Row>, String>;
+
+    /// Returns the single row matching all of the given conditions, failing
+    /// if there is no match or more than one.
+    fn find_table_row(&self, conditions: &[Condition<'_>]) -> Result<Row, String> {
+        let mut rows = self.find_table_rows(conditions)?;
+        match rows.len() {
+            0 => Err("no rows found".to_owned()),
+            1 => Ok(rows.remove(0)),
+            n => Err(format!("{} rows found, expected one", n)),
+        }
+    }
+}
+
+/// Builds the configured tables and makes them available to the transforms
+/// built next. The running topology keeps using the previous tables until
+/// the new ones are committed.
+pub fn stage(configs: &IndexMap<String, EnrichmentTableConfig>) -> Result<(), Vec<String>> {
+    let mut tables = HashMap::new();
+    let mut errors = Vec::new();
+
+    for (name, config) in configs {
+        match config.build() {
+            Ok(table) => {
+                tables.insert(name.clone(), table);
+            }
+            Err(error) => errors.push(format!("Enrichment table \"{}\": {}", name, error)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    *PENDING.write().expect("lock poisoned") = Some(tables);
+    Ok(())
+}
+
+/// Replaces the tables with the staged ones, once the topology built with
+/// them has been started.
+pub fn commit() {
+    if let Some(tables) = PENDING.write().expect("lock poisoned").take() {
+        *TABLES.write().expect("lock poisoned") = tables;
+    }
+}
+
+/// Drops the staged tables, keeping the current ones.
+pub fn discard() {
+    PENDING.write().expect("lock poisoned").take();
+}
+
+/// Returns the table with the given name, preferring the staged tables to
+/// the current ones.
+pub fn get(name: &str) -> Option<Arc<dyn Table>> {
+    if let Some(tables) = PENDING.read().expect("lock poisoned").as_ref() {
+        return tables.get(name).cloned();
+    }
+    TABLES.read().expect("lock poisoned").get(name).cloned()
+}
+
+/// The contents of a file, which are loaded again when the file is modified.
+///
+/// The modification time is checked every `reload_interval` by a background
+/// thread, which stops once the `Reloadable` is dropped. A zero interval
+/// disables reloading.
+pub(crate) struct Reloadable<T> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T> {
+    path: PathBuf,
+    load: Box<dyn Fn(&Path) -> crate::Result<T> + Send + Sync>,
+    on_reload: fn(&Path, crate::Result<()>),
+    current: RwLock<Arc<T>>,
+}
+
+impl<T> fmt::Debug for Reloadable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reloadable")
+            .field("path", &self.inner.path)
+            .finish()
+    }
+}
+
+impl<T: Send + Sync + 'static> Reloadable<T> {
+    pub(crate) fn open(
+        path: impl Into<PathBuf>,
+        reload_interval: Duration,
+        load: impl Fn(&Path) -> crate::Result<T> + Send + Sync + 'static,
+    ) -> crate::Result<Self> {
+        Self::with_on_reload(path, reload_interval, load, emit_table_reload)
+    }
+
+    /// Like `open`, calling `on_reload` with the outcome of each reload
+    /// instead of emitting the enrichment table events.
+    pub(crate) fn with_on_reload(
+        path: impl Into<PathBuf>,
+        reload_interval: Duration,
+        load: impl Fn(&Path) -> crate::Result<T> + Send + Sync + 'static,
+        on_reload: fn(&Path, crate::Result<()>),
+    ) -> crate::Result<Self> {
+        let path = path.into();
+        let modified = modified(&path);
+        let current = load(&path)?;
+        let inner = Arc::new(Inner {
+            path,
+            load: Box::new(load),
+            on_reload,
+            current: RwLock::new(Arc::new(current)),
+        });
+
+        if reload_interval > Duration::from_secs(0) {
+            let inner = Arc::downgrade(&inner);
+            thread::spawn(move || watch(inner, reload_interval, modified));
+        }
+
+        Ok(Self { inner })
+    }
+
+    /// Returns the current contents.
+    pub(crate) fn get(&self) -> Arc<T> {
+        Arc::clone(&self.inner.current.read().expect("lock poisoned"))
+    }
+}
+
+fn watch<T>(
+    inner: Weak<Inner<T>>,
+    reload_interval: Duration,
+    mut last_modified: Option<SystemTime>,
+) {
+    loop {
+        thread::sleep(reload_interval);
+        let inner = match inner.upgrade() {
+            Some(inner) => inner,
+            None => return,
+        };
+
+        let modified = modified(&inner.path);
+        if modified.is_none() || modified == last_modified {
+            continue;
+        }
+
+        let result = (inner.load)(&inner.path).map(|current| {
+            *inner.current.write().expect("lock poisoned") = Arc::new(current);
+            last_modified = modified;
+        });
+        (inner.on_reload)(&inner.path, result);
+    }
+}
+
+fn emit_table_reload(path: &Path, result: crate::Result<()>) {
+    match result {
+        Ok(()) => emit!(EnrichmentTableReloaded { path }),
+        Err(error) => emit!(EnrichmentTableReloadFailed { path, error }),
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+pub(crate) fn default_reload_interval_secs() -> u64 {
+    60
+}
@@ -0,0 +1,269 @@
+//! Authenticated encryption of individual event fields, shared by the
+//! `encrypt_fields` and `decrypt_fields` transforms.
+//!
+//! Values are JSON encoded so their type survives the round trip, then
+//! sealed with the field name as associated data, so that ciphertexts can't
+//! be moved between fields. The result is the base64 encoding of the random
+//! nonce followed by the ciphertext and tag.
+
+use crate::{event::Value, rusoto};
+use bytes::Bytes;
+use openssl::{
+    error::ErrorStack,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use rusoto_core::Region;
+use rusoto_kms::{DecryptRequest, Kms, KmsClient};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{convert::TryInto, path::PathBuf};
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+#[derive(Debug, Snafu)]
+pub enum KeyError {
+    #[snafu(display("Environment variable {:?} is not set", name))]
+    MissingEnv { name: String },
+    #[snafu(display("Failed to read key file {:?}: {}", path.display(), source))]
+    ReadFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Key is not valid base64: {}", source))]
+    InvalidBase64 { source: base64::DecodeError },
+    #[snafu(display("Failed to decrypt key with KMS: {}", source))]
+    KmsDecrypt { source: crate::Error },
+    #[snafu(display("Key must be {} bytes, got {}", KEY_LEN, len))]
+    InvalidLength { len: usize },
+}
+
+#[derive(Debug, Snafu)]
+pub enum CryptError {
+    #[snafu(display("Failed to encode value: {}", source))]
+    Encode { source: serde_json::Error },
+    #[snafu(display("Ciphertext is not valid base64: {}", source))]
+    CiphertextBase64 { source: base64::DecodeError },
+    #[snafu(display("Ciphertext is too short"))]
+    Truncated,
+    #[snafu(display("Encryption failed: {}", source))]
+    Seal { source: ErrorStack },
+    #[snafu(display("Decryption failed, the key or ciphertext is wrong: {}", source))]
+    Open { source: ErrorStack },
+    #[snafu(display("Decrypted value is not valid JSON: {}", source))]
+    Decode { source: serde_json::Error },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Algorithm {
+    Aes256Gcm,
+    Chacha20Poly1305,
+}
+
+impl Default for Algorithm {
+    fn default() -> Self {
+        Algorithm::Aes256Gcm
+    }
+}
+
+impl Algorithm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Algorithm::Aes256Gcm => "aes_256_gcm",
+            Algorithm::Chacha20Poly1305 => "chacha20_poly1305",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "aes_256_gcm" => Some(Algorithm::Aes256Gcm),
+            "chacha20_poly1305" => Some(Algorithm::Chacha20Poly1305),
+            _ => None,
+        }
+    }
+
+    fn cipher(self) -> Cipher {
+        match self {
+            Algorithm::Aes256Gcm => Cipher::aes_256_gcm(),
+            Algorithm::Chacha20Poly1305 => Cipher::chacha20_poly1305(),
+        }
+    }
+}
+
+/// The field encryption metadata is written under by default.
+pub fn default_metadata_field() -> String {
+    "encryption".to_owned()
+}
+
+/// Where a 256 bit key is loaded from.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// An environment variable holding the base64 encoded key.
+    Env(String),
+    /// A file holding the base64 encoded key.
+    File(PathBuf),
+    /// A data key encrypted with AWS KMS, decrypted once at startup.
+    Kms(KmsKey),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct KmsKey {
+    /// The base64 encoded ciphertext blob of the data key.
+    pub ciphertext: String,
+    #[serde(flatten)]
+    pub region: rusoto::RegionOrEndpoint,
+    pub assume_role: Option<String>,
+}
+
+impl KeySource {
+    pub async fn load(&self) -> Result<Key, KeyError> {
+        let key = match self {
+            KeySource::Env(name) => {
+                let encoded =
+                    std::env::var(name).map_err(|_| KeyError::MissingEnv { name: name.clone() })?;
+                base64::decode(encoded.trim()).context(InvalidBase64)?
+            }
+            KeySource::File(path) => {
+                let encoded = std::fs::read_to_string(path).context(ReadFile { path })?;
+                base64::decode(encoded.trim()).context(InvalidBase64)?
+            }
+            KeySource::Kms(kms) => kms.decrypt().await.context(KmsDecrypt)?,
+        };
+
+        if key.len() != KEY_LEN {
+            return Err(KeyError::InvalidLength { len: key.len() });
+        }
+        Ok(Key(key))
+    }
+}
+
+impl KmsKey {
+    async fn decrypt(&self) -> crate::Result<Vec<u8>> {
+        let region: Region = (&self.region).try_into()?;
+        let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
+        let client = KmsClient::new_with(rusoto::client()?, creds, region);
+
+        let response = client
+            .decrypt(DecryptRequest {
+                ciphertext_blob: Bytes::from(base64::decode(&self.ciphertext)?),
+                ..Default::default()
+            })
+            .await?;
+        Ok(response
+            .plaintext
+            .map(|plaintext| plaintext.to_vec())
+            .unwrap_or_default())
+    }
+}
+
+#[derive(Clone)]
+pub struct Key(Vec<u8>);
+
+impl std::fmt::Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+impl Key {
+    #[cfg(test)]
+    pub fn new(key: Vec<u8>) -> Self {
+        Self(key)
+    }
+
+    pub fn encrypt(
+        &self,
+        algorithm: Algorithm,
+        field: &str,
+        value: &Value,
+    ) -> Result<String, CryptError> {
+        let plaintext = serde_json::to_vec(value).context(Encode)?;
+
+        let mut nonce = [0; NONCE_LEN];
+        openssl::rand::rand_bytes(&mut nonce).context(Seal)?;
+        let mut tag = [0; TAG_LEN];
+        let ciphertext = encrypt_aead(
+            algorithm.cipher(),
+            &self.0,
+            Some(&nonce),
+            field.as_bytes(),
+            &plaintext,
+            &mut tag,
+        )
+        .context(Seal)?;
+
+        let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len() + TAG_LEN);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend(ciphertext);
+        sealed.extend_from_slice(&tag);
+        Ok(base64::encode(sealed))
+    }
+
+    pub fn decrypt(
+        &self,
+        algorithm: Algorithm,
+        field: &str,
+        sealed: &[u8],
+    ) -> Result<Value, CryptError> {
+        let sealed = base64::decode(sealed).context(CiphertextBase64)?;
+        if sealed.len() < NONCE_LEN + TAG_LEN {
+            return Err(CryptError::Truncated);
+        }
+        let (nonce, rest) = sealed.split_at(NONCE_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+
+        let plaintext = decrypt_aead(
+            algorithm.cipher(),
+            &self.0,
+            Some(nonce),
+            field.as_bytes(),
+            ciphertext,
+            tag,
+        )
+        .context(Open)?;
+        let value: serde_json::Value = serde_json::from_slice(&plaintext).context(Decode)?;
+        Ok(value.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_values() {
+        let key = Key::new(vec![7; KEY_LEN]);
+        for &algorithm in &[Algorithm::Aes256Gcm, Algorithm::Chacha20Poly1305] {
+            for value in &[Value::from("secret"), Value::Integer(42)] {
+                let sealed = key.encrypt(algorithm, "card", value).unwrap();
+                let opened = key.decrypt(algorithm, "card", sealed.as_bytes()).unwrap();
+                assert_eq!(&opened, value);
+            }
+        }
+    }
+
+    #[test]
+    fn binds_ciphertext_to_field() {
+        let key = Key::new(vec![7; KEY_LEN]);
+        let sealed = key
+            .encrypt(Algorithm::Aes256Gcm, "card", &"secret".into())
+            .unwrap();
+        assert!(matches!(
+            key.decrypt(Algorithm::Aes256Gcm, "email", sealed.as_bytes()),
+            Err(CryptError::Open { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_short_keys() {
+        std::env::set_var("FIELD_ENCRYPTION_TEST_KEY", base64::encode(&[1; 16]));
+        let source = KeySource::Env("FIELD_ENCRYPTION_TEST_KEY".into());
+        assert!(matches!(
+            source.load().await,
+            Err(KeyError::InvalidLength { len: 16 })
+        ));
+    }
+}
@@ -50,11 +50,27 @@ where
     B::Error: Into<crate::Error>,
 {
     pub fn new(tls_settings: impl Into<MaybeTlsSettings>) -> Result<HttpClient<B>, HttpError> {
+        Self::new_with_options(tls_settings.into(), false)
+    }
+
+    /// Builds a client that only speaks HTTP/2, as required by gRPC.
+    pub fn new_http2(
+        tls_settings: impl Into<MaybeTlsSettings>,
+    ) -> Result<HttpClient<B>, HttpError> {
+        Self::new_with_options(tls_settings.into(), true)
+    }
+
+    fn new_with_options(
+        settings: MaybeTlsSettings,
+        http2_only: bool,
+    ) -> Result<HttpClient<B>, HttpError> {
         let mut http = HttpConnector::new_with_resolver(Resolver);
         http.enforce_http(false);
 
-        let settings = tls_settings.into();
-        let tls = tls_connector_builder(&settings).context(BuildTlsConnector)?;
+        let mut tls = tls_connector_builder(&settings).context(BuildTlsConnector)?;
+        if http2_only {
+            tls.set_alpn_protos(b"\x02h2").context(MakeHttpsConnector)?;
+        }
         let mut https = HttpsConnector::with_connector(http, tls).context(MakeHttpsConnector)?;
 
         let settings = settings.tls().cloned();
@@ -66,7 +82,7 @@ where
             Ok(())
         });
 
-        let client = Client::builder().build(https);
+        let client = Client::builder().http2_only(http2_only).build(https);
 
         let version = crate::get_version();
         let user_agent = HeaderValue::from_str(&format!("Vector/{}", version))
@@ -0,0 +1,38 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct AnomalyEventProcessed;
+
+impl InternalEvent for AnomalyEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AnomalyDetected<'a> {
+    pub field: &'a str,
+    pub value: f64,
+    pub score: f64,
+}
+
+impl InternalEvent for AnomalyDetected<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Anomaly detected.",
+            field = %self.field,
+            value = %self.value,
+            score = %self.score,
+            rate_limit_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("anomalies_detected_total", 1);
+    }
+}
@@ -0,0 +1,24 @@
+use super::InternalEvent;
+use crate::avro::DecodeError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct AvroDecodeFailed<'a> {
+    pub error: &'a DecodeError,
+}
+
+impl InternalEvent for AvroDecodeFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to decode Avro message.",
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "decode_failed",
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct AwsCloudwatchMetricsNamespaceMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for AwsCloudwatchMetricsNamespaceMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys in namespace template do not exist on the metric's tags; dropping metric.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
@@ -41,3 +41,24 @@ impl<'a> InternalEvent for AwsKinesisFirehoseRequestError<'a> {
         counter!("request_read_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct AwsKinesisFirehoseRecordTooLarge {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AwsKinesisFirehoseRecordTooLarge {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Record is larger than Kinesis Firehose accepts; dropping event.",
+            byte_size = %self.byte_size,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "record_too_large",
+        );
+    }
+}
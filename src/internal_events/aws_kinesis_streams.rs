@@ -12,3 +12,43 @@ impl InternalEvent for AwsKinesisStreamsEventSent {
         counter!("processed_bytes_total", self.byte_size as u64);
     }
 }
+
+#[derive(Debug)]
+pub struct AwsKinesisStreamsMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for AwsKinesisStreamsMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys for the partition key do not exist on the event; dropping event.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsKinesisStreamsRecordTooLarge {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AwsKinesisStreamsRecordTooLarge {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Record is larger than Kinesis accepts; dropping event.",
+            byte_size = %self.byte_size,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "record_too_large",
+        );
+    }
+}
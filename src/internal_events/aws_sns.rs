@@ -0,0 +1,61 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct AwsSnsEventSent<'a> {
+    pub byte_size: usize,
+    pub message_id: Option<&'a String>,
+}
+
+impl InternalEvent for AwsSnsEventSent<'_> {
+    fn emit_logs(&self) {
+        trace!(message = "Event sent.", message_id = ?self.message_id);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsSnsMissingKeys<'a> {
+    pub template: &'static str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for AwsSnsMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; dropping event.",
+            template = %self.template,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsSnsMessageTooLarge {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AwsSnsMessageTooLarge {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Message is larger than SNS accepts; dropping event.",
+            byte_size = %self.byte_size,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "message_too_large",
+        );
+    }
+}
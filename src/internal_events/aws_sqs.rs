@@ -19,14 +19,16 @@ impl InternalEvent for AwsSqsEventSent<'_> {
 }
 
 #[derive(Debug)]
-pub struct AwsSqsMessageGroupIdMissingKeys<'a> {
+pub struct AwsSqsMissingKeys<'a> {
+    pub template: &'static str,
     pub keys: &'a [String],
 }
 
-impl<'a> InternalEvent for AwsSqsMessageGroupIdMissingKeys<'a> {
+impl<'a> InternalEvent for AwsSqsMissingKeys<'a> {
     fn emit_logs(&self) {
         warn!(
             message = "Keys do not exist on the event; dropping event.",
+            template = %self.template,
             missing_keys = ?self.keys,
             rate_limit_secs = 30,
         )
@@ -36,3 +38,24 @@ impl<'a> InternalEvent for AwsSqsMessageGroupIdMissingKeys<'a> {
         counter!("missing_keys_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct AwsSqsMessageTooLarge {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AwsSqsMessageTooLarge {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Message is larger than SQS accepts; dropping event.",
+            byte_size = %self.byte_size,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "message_too_large",
+        );
+    }
+}
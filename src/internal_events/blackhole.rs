@@ -1,5 +1,6 @@
 use super::InternalEvent;
-use metrics::counter;
+use crate::sinks::blackhole::FailureAction;
+use metrics::{counter, gauge};
 
 #[derive(Debug)]
 pub struct BlackholeEventReceived {
@@ -12,3 +13,46 @@ impl InternalEvent for BlackholeEventReceived {
         counter!("processed_bytes_total", self.byte_size as u64);
     }
 }
+
+#[derive(Debug)]
+pub struct BlackholeEventFailed {
+    pub action: FailureAction,
+}
+
+impl InternalEvent for BlackholeEventFailed {
+    fn emit_logs(&self) {
+        match self.action {
+            FailureAction::Drop => warn!(
+                message = "Injected failure; dropping event.",
+                rate_limit_secs = 30
+            ),
+            FailureAction::Stop => error!(message = "Injected failure; stopping sink."),
+        }
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "injected_failure");
+    }
+}
+
+#[derive(Debug)]
+pub struct BlackholeThroughput {
+    pub events_per_sec: f64,
+    pub bytes_per_sec: f64,
+    pub in_flight: usize,
+}
+
+impl InternalEvent for BlackholeThroughput {
+    fn emit_logs(&self) {
+        info!(
+            message = "Throughput.",
+            events_per_sec = self.events_per_sec.round() as u64,
+            bytes_per_sec = self.bytes_per_sec.round() as u64,
+            in_flight = self.in_flight
+        );
+    }
+
+    fn emit_metrics(&self) {
+        gauge!("events_in_flight", self.in_flight as f64);
+    }
+}
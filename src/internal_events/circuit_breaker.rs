@@ -0,0 +1,54 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct CircuitBreakerOpened {
+    pub failures: usize,
+    pub requests: usize,
+    pub cooldown: Duration,
+}
+
+impl InternalEvent for CircuitBreakerOpened {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Too many requests failed; pausing requests.",
+            failures = %self.failures,
+            requests = %self.requests,
+            cooldown_secs = %self.cooldown.as_secs(),
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("circuit_breaker_opened_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitBreakerClosed;
+
+impl InternalEvent for CircuitBreakerClosed {
+    fn emit_logs(&self) {
+        info!("Probe request succeeded; resuming requests.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("circuit_breaker_closed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitBreakerRequestRejected;
+
+impl InternalEvent for CircuitBreakerRequestRejected {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Rejecting request while requests are paused.",
+            rate_limit_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("circuit_breaker_rejected_requests_total", 1);
+    }
+}
@@ -0,0 +1,39 @@
+use super::InternalEvent;
+use crate::transforms::coerce::CoerceError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct CoerceEventProcessed;
+
+impl InternalEvent for CoerceEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CoerceFailed<'a> {
+    pub field: &'a str,
+    pub error: &'a CoerceError,
+}
+
+impl InternalEvent for CoerceFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Could not coerce field.",
+            field = %self.field,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "type_conversion_failed",
+        );
+    }
+}
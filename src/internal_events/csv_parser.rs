@@ -0,0 +1,93 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct CsvParserEventProcessed;
+
+impl InternalEvent for CsvParserEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CsvParserHeaderRow;
+
+impl InternalEvent for CsvParserHeaderRow {
+    fn emit_logs(&self) {
+        debug!(message = "Discarded header row.", rate_limit_secs = 30);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CsvParserFailedParse<'a> {
+    pub error: &'a csv::Error,
+}
+
+impl InternalEvent for CsvParserFailedParse<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event failed to parse as CSV.",
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "failed_parse",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CsvParserFieldMissing<'a> {
+    pub field: &'a str,
+}
+
+impl InternalEvent for CsvParserFieldMissing<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field does not exist.",
+            field = %self.field,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "missing_field",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CsvParserConversionFailed<'a> {
+    pub name: &'a str,
+    pub error: crate::types::Error,
+}
+
+impl<'a> InternalEvent for CsvParserConversionFailed<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Could not convert types.",
+            name = %self.name,
+            error = ?self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "type_conversion_failed",
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct DatadogLogsTemplateMissingKeys<'a> {
+    pub attribute: &'static str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for DatadogLogsTemplateMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template, leaving attribute unset.",
+            attribute = %self.attribute,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogLogsBatchSplit {
+    pub events: usize,
+}
+
+impl InternalEvent for DatadogLogsBatchSplit {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Request was too large; splitting batch in two.",
+            events = %self.events,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("batch_splits_total", 1);
+    }
+}
@@ -0,0 +1,17 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct DeadLetterEventsSent {
+    pub count: usize,
+}
+
+impl InternalEvent for DeadLetterEventsSent {
+    fn emit_logs(&self) {
+        debug!(message = "Sending events to dead letter output.", count = %self.count, rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("dead_letter_events_total", self.count as u64);
+    }
+}
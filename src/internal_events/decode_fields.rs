@@ -0,0 +1,90 @@
+use super::InternalEvent;
+use crate::transforms::decode_fields::{DecodeError, JwtError};
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct DecodeFieldsEventProcessed;
+
+impl InternalEvent for DecodeFieldsEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DecodeFieldsFailed<'a> {
+    pub field: &'a str,
+    pub error: &'a DecodeError,
+}
+
+impl InternalEvent for DecodeFieldsFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Could not decode field.",
+            field = %self.field,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "decode_failed",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DecodeFieldsUnverified<'a> {
+    pub field: &'a str,
+    pub error: &'a JwtError,
+}
+
+impl InternalEvent for DecodeFieldsUnverified<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Token failed verification.",
+            field = %self.field,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("unverified_tokens_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DecodeFieldsEventDiscarded;
+
+impl InternalEvent for DecodeFieldsEventDiscarded {
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DecodeFieldsJwksRefreshFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for DecodeFieldsJwksRefreshFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to refresh key set, keeping the previous one.",
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "jwks_refresh_failed",
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use super::InternalEvent;
+use crate::field_encryption::CryptError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct DecryptFieldsEventProcessed;
+
+impl InternalEvent for DecryptFieldsEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DecryptFieldsFailed<'a> {
+    pub field: &'a str,
+    pub error: CryptError,
+}
+
+impl InternalEvent for DecryptFieldsFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to decrypt field, leaving event encrypted.",
+            field = %self.field,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "decrypt_failed",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DecryptFieldsUnknownKey<'a> {
+    pub key_id: &'a str,
+}
+
+impl InternalEvent for DecryptFieldsUnknownKey<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event was encrypted with an unknown key, leaving it encrypted.",
+            key_id = %self.key_id,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "unknown_key",
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct EbpfEventReceived {
+    pub probe: &'static str,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for EbpfEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received record.", probe = %self.probe, byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1, "probe" => self.probe);
+        counter!("processed_bytes_total", self.byte_size as u64, "probe" => self.probe);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct EbpfEventsLost {
+    pub count: u64,
+    pub reason: &'static str,
+}
+
+impl InternalEvent for EbpfEventsLost {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Lost eBPF records.",
+            count = %self.count,
+            reason = %self.reason,
+            rate_limit_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_lost_total", self.count, "reason" => self.reason);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct EbpfProbeAttachFailed {
+    pub probe: &'static str,
+    pub program: &'static str,
+    pub error: libbpf_rs::Error,
+}
+
+impl InternalEvent for EbpfProbeAttachFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to attach eBPF program, the probe won't capture all events.",
+            probe = %self.probe,
+            program = %self.program,
+            error = %self.error,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("probe_attach_errors_total", 1, "probe" => self.probe);
+    }
+}
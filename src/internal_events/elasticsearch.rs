@@ -36,3 +36,64 @@ impl<'a> InternalEvent for ElasticSearchMissingKeys<'a> {
         counter!("missing_keys_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct ElasticSearchInvalidBulkAction<'a> {
+    pub action: &'a str,
+}
+
+impl<'a> InternalEvent for ElasticSearchInvalidBulkAction<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Invalid bulk action; dropping event.",
+            action = %self.action,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "invalid_bulk_action");
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchBulkItemsRetried {
+    pub count: usize,
+}
+
+impl InternalEvent for ElasticSearchBulkItemsRetried {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Retrying items rejected by Elasticsearch.",
+            count = %self.count,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("bulk_items_retried_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchBulkItemsFailed<'a> {
+    pub count: usize,
+    pub error_type: &'a str,
+    pub reason: &'a str,
+}
+
+impl<'a> InternalEvent for ElasticSearchBulkItemsFailed<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Items were not indexed; dropping them.",
+            count = %self.count,
+            error_type = %self.error_type,
+            reason = %self.reason,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("bulk_items_failed_total", self.count as u64);
+    }
+}
@@ -0,0 +1,39 @@
+use super::InternalEvent;
+use crate::field_encryption::CryptError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct EncryptFieldsEventProcessed;
+
+impl InternalEvent for EncryptFieldsEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct EncryptFieldsFailed<'a> {
+    pub field: &'a str,
+    pub error: CryptError,
+}
+
+impl InternalEvent for EncryptFieldsFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to encrypt field, removing it.",
+            field = %self.field,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "encrypt_failed",
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::path::Path;
+
+#[derive(Debug)]
+pub(crate) struct EnrichmentTableReloaded<'a> {
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for EnrichmentTableReloaded<'a> {
+    fn emit_logs(&self) {
+        info!(message = "Enrichment table reloaded.", path = ?self.path);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("enrichment_table_reloads_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct EnrichmentTableReloadFailed<'a> {
+    pub path: &'a Path,
+    pub error: crate::Error,
+}
+
+impl<'a> InternalEvent for EnrichmentTableReloadFailed<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to reload enrichment table, keeping the previous contents.",
+            path = ?self.path,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("enrichment_table_reload_errors_total", 1);
+    }
+}
@@ -0,0 +1,26 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct ExceptionFingerprintEventProcessed;
+
+impl InternalEvent for ExceptionFingerprintEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ExceptionFingerprintNoStackTrace<'a> {
+    pub field: &'a str,
+}
+
+impl InternalEvent for ExceptionFingerprintNoStackTrace<'_> {
+    fn emit_logs(&self) {
+        trace!(message = "Field does not hold a stack trace.", field = %self.field);
+    }
+}
@@ -0,0 +1,52 @@
+use super::InternalEvent;
+use crate::transforms::exec::ExecError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct ExecEventProcessed;
+
+impl InternalEvent for ExecEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ExecProcessSpawned<'a> {
+    pub command: &'a str,
+}
+
+impl InternalEvent for ExecProcessSpawned<'_> {
+    fn emit_logs(&self) {
+        debug!(message = "Started process.", command = %self.command);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processes_started_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ExecFailed<'a> {
+    pub error: &'a ExecError,
+}
+
+impl InternalEvent for ExecFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event could not be transformed.",
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "exec_failed",
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct ExplodeEventProcessed;
+
+impl InternalEvent for ExplodeEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ExplodeFieldNotArray<'a> {
+    pub field: &'a str,
+}
+
+impl InternalEvent for ExplodeFieldNotArray<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Field is missing or not an array, passing event through.",
+            field = %self.field,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "field_not_array");
+    }
+}
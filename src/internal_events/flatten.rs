@@ -0,0 +1,30 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct FlattenEventProcessed;
+
+impl InternalEvent for FlattenEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct FlattenKeyConflict<'a> {
+    pub key: &'a str,
+}
+
+impl InternalEvent for FlattenKeyConflict<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Field conflicts with another field.",
+            key = %self.key,
+            rate_limit_secs = 30
+        );
+    }
+}
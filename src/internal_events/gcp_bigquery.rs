@@ -0,0 +1,80 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct BigqueryTemplateMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for BigqueryTemplateMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; dropping event.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct BigqueryEventRejected<'a> {
+    pub table: &'a str,
+    pub reason: &'a str,
+}
+
+impl<'a> InternalEvent for BigqueryEventRejected<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event does not fit the table; sending it to the failed output.",
+            table = %self.table,
+            reason = %self.reason,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "schema_mismatch",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct BigquerySchemaFetched<'a> {
+    pub table: &'a str,
+    pub columns: usize,
+}
+
+impl<'a> InternalEvent for BigquerySchemaFetched<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Fetched table schema.",
+            table = %self.table,
+            columns = %self.columns,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct BigqueryStreamCommitted<'a> {
+    pub stream: &'a str,
+    pub rows: usize,
+}
+
+impl<'a> InternalEvent for BigqueryStreamCommitted<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Committed write stream.",
+            stream = %self.stream,
+            rows = %self.rows,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.rows as u64);
+    }
+}
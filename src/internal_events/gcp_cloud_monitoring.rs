@@ -0,0 +1,37 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct CloudMonitoringResourceDetected<'a> {
+    pub resource_type: &'a str,
+}
+
+impl<'a> InternalEvent for CloudMonitoringResourceDetected<'a> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Detected monitored resource.",
+            resource_type = %self.resource_type,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct CloudMonitoringDescriptorFailed<'a> {
+    pub metric_type: &'a str,
+    pub error: crate::Error,
+}
+
+impl<'a> InternalEvent for CloudMonitoringDescriptorFailed<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to create metric descriptor; leaving it to be created on write.",
+            metric_type = %self.metric_type,
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("request_errors_total", 1);
+    }
+}
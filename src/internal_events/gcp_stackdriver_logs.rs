@@ -0,0 +1,82 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct StackdriverLogsTemplateMissingKeys<'a> {
+    pub template: &'static str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for StackdriverLogsTemplateMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; dropping event.",
+            template = %self.template,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct StackdriverLogsEntryTruncated {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for StackdriverLogsEntryTruncated {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Log entry exceeds the size limit; truncating message.",
+            byte_size = %self.byte_size,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("truncated_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct StackdriverLogsEntrySplit {
+    pub byte_size: usize,
+    pub parts: usize,
+}
+
+impl InternalEvent for StackdriverLogsEntrySplit {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Log entry exceeds the size limit; splitting message.",
+            byte_size = %self.byte_size,
+            parts = %self.parts,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("split_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct StackdriverLogsEntryTooLarge {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for StackdriverLogsEntryTooLarge {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Log entry exceeds the size limit even without its message; dropping event.",
+            byte_size = %self.byte_size,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
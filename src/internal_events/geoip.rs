@@ -1,5 +1,6 @@
 use super::InternalEvent;
 use metrics::counter;
+use std::path::Path;
 
 #[derive(Debug)]
 pub struct GeoipEventProcessed;
@@ -47,3 +48,39 @@ impl<'a> InternalEvent for GeoipFieldDoesNotExist<'a> {
         counter!("processing_errors_total", 1, "error_type" => "type_field_does_not_exist");
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct GeoipDatabaseReloaded<'a> {
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for GeoipDatabaseReloaded<'a> {
+    fn emit_logs(&self) {
+        info!(message = "GeoIP database reloaded.", path = ?self.path);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("database_reloads_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct GeoipDatabaseReloadFailed<'a> {
+    pub path: &'a Path,
+    pub error: crate::Error,
+}
+
+impl<'a> InternalEvent for GeoipDatabaseReloadFailed<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to reload GeoIP database, keeping the previous one.",
+            path = ?self.path,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "database_reload_failed");
+    }
+}
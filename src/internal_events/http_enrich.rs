@@ -0,0 +1,74 @@
+use super::InternalEvent;
+use crate::transforms::http_enrich::LookupError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct HttpEnrichEventProcessed;
+
+impl InternalEvent for HttpEnrichEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct HttpEnrichCacheHit;
+
+impl InternalEvent for HttpEnrichCacheHit {
+    fn emit_metrics(&self) {
+        counter!("cache_hits_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct HttpEnrichCacheMiss;
+
+impl InternalEvent for HttpEnrichCacheMiss {
+    fn emit_metrics(&self) {
+        counter!("cache_misses_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct HttpEnrichMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl InternalEvent for HttpEnrichMissingKeys<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; not enriching event.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct HttpEnrichRequestFailed {
+    pub error: LookupError,
+}
+
+impl InternalEvent for HttpEnrichRequestFailed {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Lookup failed; not enriching event.",
+            error = %self.error,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "lookup_failed",
+        );
+    }
+}
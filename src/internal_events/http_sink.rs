@@ -0,0 +1,65 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct HttpSinkTemplateMissingKeys<'a> {
+    pub field: &'a str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for HttpSinkTemplateMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template; dropping event.",
+            field = %self.field,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "render_error");
+    }
+}
+
+#[derive(Debug)]
+pub struct HttpSinkInvalidUri<'a> {
+    pub uri: &'a str,
+    pub error: &'a http::uri::InvalidUri,
+}
+
+impl<'a> InternalEvent for HttpSinkInvalidUri<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rendered URI is invalid; dropping event.",
+            uri = %self.uri,
+            error = %self.error,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "invalid_uri");
+    }
+}
+
+#[derive(Debug)]
+pub struct HttpSinkInvalidHeaderValue<'a> {
+    pub name: &'a str,
+    pub error: &'a http::header::InvalidHeaderValue,
+}
+
+impl<'a> InternalEvent for HttpSinkInvalidHeaderValue<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rendered header value is invalid; dropping event.",
+            header = %self.name,
+            error = %self.error,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "invalid_header_value");
+    }
+}
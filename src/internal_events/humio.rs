@@ -0,0 +1,21 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct HumioIndexMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for HumioIndexMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template for index, leaving empty.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("index_missing_keys_total", 1);
+    }
+}
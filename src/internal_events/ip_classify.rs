@@ -0,0 +1,57 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct IpClassifyEventProcessed;
+
+impl InternalEvent for IpClassifyEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct IpClassifyFieldMissing<'a> {
+    pub field: &'a str,
+}
+
+impl InternalEvent for IpClassifyFieldMissing<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Field does not exist.",
+            field = %self.field,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "field_missing",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct IpClassifyInvalidAddress<'a> {
+    pub address: &'a str,
+}
+
+impl InternalEvent for IpClassifyInvalidAddress<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Could not parse IP address.",
+            address = %self.address,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "ip_address_parse_failed",
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use super::InternalEvent;
+use crate::transforms::kv_lookup::LookupError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct KvLookupEventProcessed;
+
+impl InternalEvent for KvLookupEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct KvLookupCacheHit;
+
+impl InternalEvent for KvLookupCacheHit {
+    fn emit_metrics(&self) {
+        counter!("cache_hits_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct KvLookupCacheMiss;
+
+impl InternalEvent for KvLookupCacheMiss {
+    fn emit_metrics(&self) {
+        counter!("cache_misses_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct KvLookupMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl InternalEvent for KvLookupMissingKeys<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; not enriching event.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct KvLookupFailed {
+    pub error: LookupError,
+}
+
+impl InternalEvent for KvLookupFailed {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Lookup failed; not enriching event.",
+            error = %self.error,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "lookup_failed",
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct LokiOutOfOrderEventDropped;
+
+impl InternalEvent for LokiOutOfOrderEventDropped {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Received out-of-order event; dropping event.",
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct LokiOutOfOrderEventRewritten;
+
+impl InternalEvent for LokiOutOfOrderEventRewritten {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Received out-of-order event, rewriting timestamp.",
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("rewritten_timestamp_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct LokiCardinalityLimitReached {
+    pub limit: usize,
+}
+
+impl InternalEvent for LokiCardinalityLimitReached {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Stream cardinality limit reached; sending event with static labels only.",
+            limit = %self.limit,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("cardinality_limited_events_total", 1);
+    }
+}
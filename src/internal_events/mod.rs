@@ -3,37 +3,82 @@ use std::borrow::Cow;
 mod adaptive_concurrency;
 mod add_fields;
 mod add_tags;
+#[cfg(feature = "sinks-amqp")]
+mod amqp;
+#[cfg(feature = "transforms-anomaly")]
+mod anomaly;
 mod ansi_stripper;
 #[cfg(feature = "sources-apache_metrics")]
 mod apache_metrics;
 #[cfg(feature = "api")]
 mod api;
+#[cfg(feature = "sources-kafka")]
+mod avro;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
 mod aws_cloudwatch_logs_subscription_parser;
+#[cfg(feature = "sinks-aws_cloudwatch_metrics")]
+mod aws_cloudwatch_metrics;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
 mod aws_ec2_metadata;
 #[cfg(feature = "sources-aws_ecs_metrics")]
 mod aws_ecs_metrics;
-#[cfg(feature = "sources-aws_kinesis_firehose")]
+#[cfg(any(
+    feature = "sources-aws_kinesis_firehose",
+    feature = "sinks-aws_kinesis_firehose"
+))]
 mod aws_kinesis_firehose;
 #[cfg(feature = "sinks-aws_kinesis_streams")]
 mod aws_kinesis_streams;
 #[cfg(any(feature = "sources-aws_s3", feature = "sinks-aws_s3"))]
 pub(crate) mod aws_s3;
+#[cfg(feature = "sinks-aws_sns")]
+mod aws_sns;
 #[cfg(feature = "sinks-aws_sqs")]
 mod aws_sqs;
+#[cfg(feature = "sinks-blackhole")]
 mod blackhole;
+mod circuit_breaker;
+#[cfg(feature = "transforms-coerce")]
+mod coerce;
 #[cfg(feature = "transforms-coercer")]
 mod coercer;
 #[cfg(feature = "transforms-concat")]
 mod concat;
 #[cfg(feature = "sinks-console")]
 mod console;
+#[cfg(feature = "transforms-csv_parser")]
+mod csv_parser;
+#[cfg(feature = "sinks-datadog")]
+mod datadog_logs;
+mod dead_letter;
+#[cfg(feature = "transforms-decode_fields")]
+mod decode_fields;
+#[cfg(feature = "transforms-decrypt_fields")]
+mod decrypt_fields;
 #[cfg(feature = "transforms-dedupe")]
 mod dedupe;
 #[cfg(feature = "sources-docker_logs")]
 mod docker_logs;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+mod ebpf;
 mod elasticsearch;
+#[cfg(feature = "transforms-encrypt_fields")]
+mod encrypt_fields;
+mod enrichment_tables;
+#[cfg(feature = "transforms-exception_fingerprint")]
+mod exception_fingerprint;
+#[cfg(feature = "transforms-exec")]
+mod exec;
+#[cfg(feature = "transforms-explode")]
+mod explode;
+#[cfg(feature = "transforms-flatten")]
+mod flatten;
+#[cfg(feature = "sinks-gcp")]
+mod gcp_bigquery;
+#[cfg(feature = "sinks-gcp")]
+mod gcp_cloud_monitoring;
+#[cfg(feature = "sinks-gcp")]
+mod gcp_stackdriver_logs;
 #[cfg(feature = "sources-generator")]
 mod generator;
 #[cfg(feature = "transforms-geoip")]
@@ -45,6 +90,14 @@ mod heartbeat;
 mod host_metrics;
 mod http;
 pub mod http_client;
+#[cfg(feature = "transforms-http_enrich")]
+mod http_enrich;
+#[cfg(feature = "sinks-http")]
+mod http_sink;
+#[cfg(feature = "sinks-humio")]
+mod humio;
+#[cfg(feature = "transforms-ip_classify")]
+mod ip_classify;
 #[cfg(all(unix, feature = "sources-journald"))]
 mod journald;
 #[cfg(feature = "transforms-json_parser")]
@@ -55,26 +108,52 @@ mod kafka;
 mod key_value_parser;
 #[cfg(feature = "sources-kubernetes-logs")]
 mod kubernetes_logs;
+#[cfg(feature = "transforms-kv_lookup")]
+mod kv_lookup;
 #[cfg(feature = "transforms-log_to_metric")]
 mod log_to_metric;
 #[cfg(feature = "transforms-logfmt_parser")]
 mod logfmt_parser;
 mod logplex;
+#[cfg(feature = "sinks-loki")]
+mod loki;
 #[cfg(feature = "transforms-lua")]
 mod lua;
+#[cfg(feature = "transforms-metric_relabel")]
+mod metric_relabel;
 #[cfg(feature = "transforms-metric_to_log")]
 mod metric_to_log;
 #[cfg(feature = "sources-mongodb_metrics")]
 mod mongodb_metrics;
+#[cfg(feature = "sinks-mqtt")]
+mod mqtt;
 #[cfg(feature = "sinks-nats")]
 mod nats;
+#[cfg(feature = "sinks-new_relic")]
+mod new_relic;
 #[cfg(feature = "sources-nginx_metrics")]
 mod nginx_metrics;
 mod open;
+#[cfg(feature = "sinks-opentelemetry")]
+mod opentelemetry;
+#[cfg(feature = "sinks-pagerduty")]
+mod pagerduty;
+#[cfg(feature = "sinks-postgres")]
+mod postgres;
 mod process;
+#[cfg(feature = "transforms-project")]
+mod project;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 mod prometheus;
+#[cfg(any(feature = "sources-kafka", feature = "transforms-protobuf_parser"))]
+mod protobuf;
+#[cfg(feature = "transforms-protobuf_parser")]
+mod protobuf_parser;
 mod pulsar;
+#[cfg(feature = "transforms-rate")]
+mod rate;
+#[cfg(feature = "transforms-redact")]
+mod redact;
 #[cfg(feature = "transforms-reduce")]
 mod reduce;
 #[cfg(feature = "transforms-regex_parser")]
@@ -86,13 +165,31 @@ mod remove_fields;
 mod remove_tags;
 #[cfg(feature = "transforms-rename_fields")]
 mod rename_fields;
+#[cfg(feature = "transforms-route")]
+mod route;
 mod sampler;
+#[cfg(feature = "transforms-sanitize_keys")]
+mod sanitize_keys;
+#[cfg(feature = "transforms-schema_validate")]
+mod schema_validate;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
+#[cfg(feature = "transforms-session")]
+mod session;
+#[cfg(feature = "sinks-sftp")]
+mod sftp;
+#[cfg(feature = "transforms-size_guard")]
+mod size_guard;
+#[cfg(feature = "sinks-smtp")]
+mod smtp;
+#[cfg(feature = "sinks-snowflake")]
+mod snowflake;
 mod socket;
 mod split;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
+#[cfg(feature = "transforms-sql")]
+mod sql;
 #[cfg(feature = "sinks-statsd")]
 mod statsd_sink;
 #[cfg(feature = "sources-statsd")]
@@ -101,57 +198,124 @@ mod stdin;
 #[cfg(feature = "transforms-swimlanes")]
 mod swimlanes;
 mod syslog;
+#[cfg(feature = "sinks-syslog")]
+mod syslog_sink;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 mod tag_cardinality_limit;
 mod tcp;
+#[cfg(feature = "transforms-throttle")]
+mod throttle;
+#[cfg(feature = "transforms-timestamp")]
+mod timestamp;
 #[cfg(feature = "transforms-tokenizer")]
 mod tokenizer;
+#[cfg(feature = "transforms-top_k")]
+mod top_k;
+#[cfg(feature = "transforms-trace_sampler")]
+mod trace_sampler;
 mod udp;
+#[cfg(feature = "transforms-unit_convert")]
+mod unit_convert;
 mod unix;
+#[cfg(feature = "transforms-url_parser")]
+mod url_parser;
+#[cfg(feature = "transforms-user_agent_parser")]
+mod user_agent_parser;
 mod vector;
 #[cfg(feature = "wasm")]
 mod wasm;
+#[cfg(feature = "sinks-webhook_notify")]
+mod webhook_notify;
+#[cfg(feature = "sinks-websocket")]
+mod websocket;
+#[cfg(feature = "transforms-window")]
+mod window;
 
 pub mod kubernetes;
 
 pub use self::adaptive_concurrency::*;
 pub use self::add_fields::*;
 pub use self::add_tags::*;
+#[cfg(feature = "sinks-amqp")]
+pub use self::amqp::*;
+#[cfg(feature = "transforms-anomaly")]
+pub(crate) use self::anomaly::*;
 pub use self::ansi_stripper::*;
 #[cfg(feature = "sources-apache_metrics")]
 pub use self::apache_metrics::*;
 #[cfg(feature = "api")]
 pub use self::api::*;
+#[cfg(feature = "sources-kafka")]
+pub(crate) use self::avro::*;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
 pub(crate) use self::aws_cloudwatch_logs_subscription_parser::*;
+#[cfg(feature = "sinks-aws_cloudwatch_metrics")]
+pub use self::aws_cloudwatch_metrics::*;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
 pub use self::aws_ec2_metadata::*;
 #[cfg(feature = "sources-aws_ecs_metrics")]
 pub use self::aws_ecs_metrics::*;
-#[cfg(feature = "sources-aws_kinesis_firehose")]
+#[cfg(any(
+    feature = "sources-aws_kinesis_firehose",
+    feature = "sinks-aws_kinesis_firehose"
+))]
 pub use self::aws_kinesis_firehose::*;
 #[cfg(feature = "sinks-aws_kinesis_streams")]
 pub use self::aws_kinesis_streams::*;
+#[cfg(feature = "sinks-aws_sns")]
+pub use self::aws_sns::*;
 #[cfg(feature = "sinks-aws_sqs")]
 pub use self::aws_sqs::*;
+#[cfg(feature = "sinks-blackhole")]
 pub use self::blackhole::*;
+pub use self::circuit_breaker::*;
+#[cfg(feature = "transforms-coerce")]
+pub(crate) use self::coerce::*;
 #[cfg(feature = "transforms-coercer")]
 pub(crate) use self::coercer::*;
 #[cfg(feature = "transforms-concat")]
 pub use self::concat::*;
 #[cfg(feature = "sinks-console")]
 pub use self::console::*;
+#[cfg(feature = "transforms-csv_parser")]
+pub(crate) use self::csv_parser::*;
+#[cfg(feature = "sinks-datadog")]
+pub use self::datadog_logs::*;
+pub use self::dead_letter::*;
+#[cfg(feature = "transforms-decode_fields")]
+pub(crate) use self::decode_fields::*;
+#[cfg(feature = "transforms-decrypt_fields")]
+pub(crate) use self::decrypt_fields::*;
 #[cfg(feature = "transforms-dedupe")]
 pub(crate) use self::dedupe::*;
 #[cfg(feature = "sources-docker_logs")]
 pub use self::docker_logs::*;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+pub(crate) use self::ebpf::*;
 pub use self::elasticsearch::*;
+#[cfg(feature = "transforms-encrypt_fields")]
+pub(crate) use self::encrypt_fields::*;
+pub(crate) use self::enrichment_tables::*;
+#[cfg(feature = "transforms-exception_fingerprint")]
+pub(crate) use self::exception_fingerprint::*;
+#[cfg(feature = "transforms-exec")]
+pub(crate) use self::exec::*;
+#[cfg(feature = "transforms-explode")]
+pub(crate) use self::explode::*;
 #[cfg(any(
     feature = "sources-file",
     feature = "sources-kubernetes-logs",
     feature = "sinks-file",
 ))]
 pub use self::file::*;
+#[cfg(feature = "transforms-flatten")]
+pub(crate) use self::flatten::*;
+#[cfg(feature = "sinks-gcp")]
+pub use self::gcp_bigquery::*;
+#[cfg(feature = "sinks-gcp")]
+pub use self::gcp_cloud_monitoring::*;
+#[cfg(feature = "sinks-gcp")]
+pub use self::gcp_stackdriver_logs::*;
 #[cfg(feature = "sources-generator")]
 pub use self::generator::*;
 #[cfg(feature = "transforms-geoip")]
@@ -162,6 +326,14 @@ pub use self::heartbeat::*;
 #[cfg(feature = "sources-host_metrics")]
 pub(crate) use self::host_metrics::*;
 pub use self::http::*;
+#[cfg(feature = "transforms-http_enrich")]
+pub(crate) use self::http_enrich::*;
+#[cfg(feature = "sinks-http")]
+pub use self::http_sink::*;
+#[cfg(feature = "sinks-humio")]
+pub use self::humio::*;
+#[cfg(feature = "transforms-ip_classify")]
+pub(crate) use self::ip_classify::*;
 #[cfg(all(unix, feature = "sources-journald"))]
 pub(crate) use self::journald::*;
 #[cfg(feature = "transforms-json_parser")]
@@ -172,24 +344,50 @@ pub use self::kafka::*;
 pub(crate) use self::key_value_parser::*;
 #[cfg(feature = "sources-kubernetes-logs")]
 pub use self::kubernetes_logs::*;
+#[cfg(feature = "transforms-kv_lookup")]
+pub(crate) use self::kv_lookup::*;
 #[cfg(feature = "transforms-log_to_metric")]
 pub(crate) use self::log_to_metric::*;
 #[cfg(feature = "transforms-logfmt_parser")]
 pub use self::logfmt_parser::*;
 pub use self::logplex::*;
+#[cfg(feature = "sinks-loki")]
+pub(crate) use self::loki::*;
 #[cfg(feature = "transforms-lua")]
 pub use self::lua::*;
+#[cfg(feature = "transforms-metric_relabel")]
+pub(crate) use self::metric_relabel::*;
 #[cfg(feature = "transforms-metric_to_log")]
 pub(crate) use self::metric_to_log::*;
+#[cfg(feature = "sinks-mqtt")]
+pub use self::mqtt::*;
 #[cfg(feature = "sinks-nats")]
 pub use self::nats::*;
+#[cfg(feature = "sinks-new_relic")]
+pub use self::new_relic::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
 pub use self::open::*;
+#[cfg(feature = "sinks-opentelemetry")]
+pub use self::opentelemetry::*;
+#[cfg(feature = "sinks-pagerduty")]
+pub use self::pagerduty::*;
+#[cfg(feature = "sinks-postgres")]
+pub use self::postgres::*;
 pub use self::process::*;
+#[cfg(feature = "transforms-project")]
+pub(crate) use self::project::*;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 pub(crate) use self::prometheus::*;
+#[cfg(any(feature = "sources-kafka", feature = "transforms-protobuf_parser"))]
+pub(crate) use self::protobuf::*;
+#[cfg(feature = "transforms-protobuf_parser")]
+pub(crate) use self::protobuf_parser::*;
 pub use self::pulsar::*;
+#[cfg(feature = "transforms-rate")]
+pub(crate) use self::rate::*;
+#[cfg(feature = "transforms-redact")]
+pub(crate) use self::redact::*;
 #[cfg(feature = "transforms-reduce")]
 pub(crate) use self::reduce::*;
 #[cfg(feature = "transforms-regex_parser")]
@@ -201,13 +399,31 @@ pub use self::remove_fields::*;
 pub use self::remove_tags::*;
 #[cfg(feature = "transforms-rename_fields")]
 pub use self::rename_fields::*;
+#[cfg(feature = "transforms-route")]
+pub use self::route::*;
 pub use self::sampler::*;
+#[cfg(feature = "transforms-sanitize_keys")]
+pub(crate) use self::sanitize_keys::*;
+#[cfg(feature = "transforms-schema_validate")]
+pub(crate) use self::schema_validate::*;
 #[cfg(feature = "sinks-sematext")]
 pub use self::sematext_metrics::*;
+#[cfg(feature = "transforms-session")]
+pub(crate) use self::session::*;
+#[cfg(feature = "sinks-sftp")]
+pub use self::sftp::*;
+#[cfg(feature = "transforms-size_guard")]
+pub(crate) use self::size_guard::*;
+#[cfg(feature = "sinks-smtp")]
+pub use self::smtp::*;
+#[cfg(feature = "sinks-snowflake")]
+pub use self::snowflake::*;
 pub(crate) use self::socket::*;
 pub use self::split::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
+#[cfg(feature = "transforms-sql")]
+pub(crate) use self::sql::*;
 #[cfg(feature = "sinks-statsd")]
 pub use self::statsd_sink::*;
 #[cfg(feature = "sources-statsd")]
@@ -216,16 +432,38 @@ pub use self::stdin::*;
 #[cfg(feature = "transforms-swimlanes")]
 pub use self::swimlanes::*;
 pub use self::syslog::*;
+#[cfg(feature = "sinks-syslog")]
+pub use self::syslog_sink::*;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub(crate) use self::tag_cardinality_limit::*;
 pub use self::tcp::*;
+#[cfg(feature = "transforms-throttle")]
+pub(crate) use self::throttle::*;
+#[cfg(feature = "transforms-timestamp")]
+pub(crate) use self::timestamp::*;
 #[cfg(feature = "transforms-tokenizer")]
 pub(crate) use self::tokenizer::*;
+#[cfg(feature = "transforms-top_k")]
+pub(crate) use self::top_k::*;
+#[cfg(feature = "transforms-trace_sampler")]
+pub(crate) use self::trace_sampler::*;
 pub use self::udp::*;
+#[cfg(feature = "transforms-unit_convert")]
+pub(crate) use self::unit_convert::*;
 pub use self::unix::*;
+#[cfg(feature = "transforms-url_parser")]
+pub(crate) use self::url_parser::*;
+#[cfg(feature = "transforms-user_agent_parser")]
+pub(crate) use self::user_agent_parser::*;
 pub use self::vector::*;
 #[cfg(feature = "wasm")]
 pub use self::wasm::*;
+#[cfg(feature = "sinks-webhook_notify")]
+pub use self::webhook_notify::*;
+#[cfg(feature = "sinks-websocket")]
+pub use self::websocket::*;
+#[cfg(feature = "transforms-window")]
+pub(crate) use self::window::*;
 #[cfg(windows)]
 pub use self::windows::*;
 #[cfg(feature = "sources-mongodb_metrics")]
@@ -0,0 +1,56 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct MqttEventSent {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for MqttEventSent {
+    fn emit_logs(&self) {
+        trace!(message = "Event sent.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttConnectionError {
+    pub error: rumqttc::ConnectionError,
+}
+
+impl InternalEvent for MqttConnectionError {
+    fn emit_logs(&self) {
+        error!(
+            message = "MQTT connection error; reconnecting.",
+            error = %self.error,
+            rate_limit_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttEventMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for MqttEventMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; dropping event.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
@@ -0,0 +1,25 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct NewRelicRecordTooLarge {
+    pub byte_size: usize,
+    pub max_bytes: usize,
+}
+
+impl InternalEvent for NewRelicRecordTooLarge {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Encoded event is larger than a payload can be; dropping event.",
+            byte_size = %self.byte_size,
+            max_bytes = %self.max_bytes,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "record_too_large",
+        );
+    }
+}
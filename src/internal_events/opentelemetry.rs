@@ -0,0 +1,23 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct OpenTelemetryResourceMissingKeys<'a> {
+    pub attribute: &'a str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for OpenTelemetryResourceMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; leaving out resource attribute.",
+            attribute = %self.attribute,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
@@ -0,0 +1,80 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct PagerDutyTemplateMissingKeys<'a> {
+    pub field: &'a str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for PagerDutyTemplateMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template; dropping event.",
+            field = %self.field,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "render_error");
+    }
+}
+
+#[derive(Debug)]
+pub struct PagerDutyInvalidAction<'a> {
+    pub value: &'a str,
+}
+
+impl<'a> InternalEvent for PagerDutyInvalidAction<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Invalid action; dropping event.",
+            value = %self.value,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "invalid_action");
+    }
+}
+
+#[derive(Debug)]
+pub struct PagerDutyInvalidSeverity<'a> {
+    pub value: &'a str,
+}
+
+impl<'a> InternalEvent for PagerDutyInvalidSeverity<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Invalid severity; using \"error\" instead.",
+            value = %self.value,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "invalid_severity");
+    }
+}
+
+#[derive(Debug)]
+pub struct PagerDutyMissingDedupKey {
+    pub action: &'static str,
+}
+
+impl InternalEvent for PagerDutyMissingDedupKey {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Events need a dedup key to be acknowledged or resolved; dropping event.",
+            action = %self.action,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "missing_dedup_key");
+    }
+}
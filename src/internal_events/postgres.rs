@@ -0,0 +1,61 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct PostgresEventsSent {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for PostgresEventsSent {
+    fn emit_logs(&self) {
+        trace!(message = "Events sent.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresInvalidValue<'a> {
+    pub column: &'a str,
+    pub column_type: &'a str,
+}
+
+impl<'a> InternalEvent for PostgresInvalidValue<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Value can't be converted to the type of its column; dropping event.",
+            column = %self.column,
+            column_type = %self.column_type,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "invalid_value",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresConnectionError {
+    pub error: tokio_postgres::Error,
+}
+
+impl InternalEvent for PostgresConnectionError {
+    fn emit_logs(&self) {
+        error!(
+            message = "PostgreSQL connection failed.",
+            error = %self.error,
+            rate_limit_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
@@ -0,0 +1,24 @@
+use super::InternalEvent;
+use crate::protobuf::DecodeError;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct ProtobufDecodeFailed<'a> {
+    pub error: &'a DecodeError,
+}
+
+impl InternalEvent for ProtobufDecodeFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to decode protobuf message.",
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "decode_failed",
+        );
+    }
+}
@@ -10,3 +10,20 @@ impl<'a> InternalEvent for PulsarEncodeEventFailed<'a> {
         debug!(message = "Event encode failed.", error = ?self.error);
     }
 }
+
+#[derive(Debug)]
+pub struct PulsarPropertyMissingKeys<'a> {
+    pub property: &'a str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for PulsarPropertyMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; leaving out property.",
+            property = %self.property,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct RateEventProcessed;
+
+impl InternalEvent for RateEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RateCounterReset<'a> {
+    pub name: &'a str,
+    pub previous: f64,
+    pub value: f64,
+}
+
+impl InternalEvent for RateCounterReset<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Counter decreased; assuming it was reset.",
+            name = %self.name,
+            previous = %self.previous,
+            value = %self.value,
+            rate_limit_secs = 30
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct RedactEventProcessed;
+
+impl InternalEvent for RedactEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RedactFieldsRedacted {
+    pub count: usize,
+}
+
+impl InternalEvent for RedactFieldsRedacted {
+    fn emit_logs(&self) {
+        trace!(message = "Redacted sensitive values.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("redactions_total", self.count as u64);
+    }
+}
@@ -0,0 +1,20 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct RouteEventProcessed;
+
+impl InternalEvent for RouteEventProcessed {
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct RouteEventDiscarded;
+
+impl InternalEvent for RouteEventDiscarded {
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
@@ -0,0 +1,30 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct SanitizeKeysEventProcessed;
+
+impl InternalEvent for SanitizeKeysEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SanitizeKeysConflict<'a> {
+    pub key: &'a str,
+}
+
+impl InternalEvent for SanitizeKeysConflict<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Sanitized key conflicts with another key, replacing its value.",
+            key = %self.key,
+            rate_limit_secs = 30
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct SchemaValidateEventProcessed;
+
+impl InternalEvent for SchemaValidateEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SchemaValidateInvalidEvent<'a> {
+    pub errors: &'a [String],
+}
+
+impl InternalEvent for SchemaValidateInvalidEvent<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event does not match the schema.",
+            errors = ?self.errors,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "schema_violation",
+        );
+    }
+}
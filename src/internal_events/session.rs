@@ -0,0 +1,28 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct SessionEventProcessed;
+
+impl InternalEvent for SessionEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SessionClosed;
+
+impl InternalEvent for SessionClosed {
+    fn emit_logs(&self) {
+        trace!(message = "Closed session.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("sessions_closed_total", 1);
+    }
+}
@@ -0,0 +1,38 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct SftpFileUploaded<'a> {
+    pub path: &'a str,
+    pub byte_size: usize,
+}
+
+impl<'a> InternalEvent for SftpFileUploaded<'a> {
+    fn emit_logs(&self) {
+        debug!(message = "Uploaded file.", path = %self.path, byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("files_uploaded_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct SftpTemplateMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for SftpTemplateMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template; dropping event.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "render_error");
+    }
+}
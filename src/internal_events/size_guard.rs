@@ -0,0 +1,48 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct SizeGuardEventProcessed;
+
+impl InternalEvent for SizeGuardEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SizeGuardOversizedEvent {
+    pub size: usize,
+    pub max_bytes: usize,
+}
+
+impl InternalEvent for SizeGuardOversizedEvent {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Event is over the size limit.",
+            size = %self.size,
+            max_bytes = %self.max_bytes,
+            rate_limit_secs = 30
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SizeGuardEventDiscarded;
+
+impl InternalEvent for SizeGuardEventDiscarded {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event could not be made to fit the size limit; discarding.",
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
@@ -0,0 +1,39 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct SmtpTemplateMissingKeys<'a> {
+    pub field: &'a str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for SmtpTemplateMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template; dropping event.",
+            field = %self.field,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "render_error");
+    }
+}
+
+#[derive(Debug)]
+pub struct SmtpEmailSent {
+    pub events: usize,
+}
+
+impl InternalEvent for SmtpEmailSent {
+    fn emit_logs(&self) {
+        debug!(message = "Email sent.", events = %self.events);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("emails_sent_total", 1);
+        counter!("processed_events_total", self.events as u64);
+    }
+}
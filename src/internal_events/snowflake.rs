@@ -0,0 +1,46 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct SnowflakeTemplateMissingKeys<'a> {
+    pub field: &'a str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for SnowflakeTemplateMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template; dropping event.",
+            field = %self.field,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "render_error");
+    }
+}
+
+#[derive(Debug)]
+pub struct SnowflakeFileQueued<'a> {
+    pub pipe: &'a str,
+    pub path: &'a str,
+    pub byte_size: usize,
+}
+
+impl<'a> InternalEvent for SnowflakeFileQueued<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "File queued for ingestion.",
+            pipe = %self.pipe,
+            path = %self.path,
+            byte_size = %self.byte_size,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("files_queued_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
@@ -74,6 +74,25 @@ impl<'a> InternalEvent for SplunkSourceMissingKeys<'a> {
     }
 }
 
+#[derive(Debug)]
+pub struct SplunkIndexMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for SplunkIndexMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template for index, leaving empty.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("index_missing_keys_total", 1);
+    }
+}
+
 #[cfg(feature = "sources-splunk_hec")]
 mod source {
     use super::InternalEvent;
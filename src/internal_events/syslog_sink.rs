@@ -0,0 +1,44 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct SyslogHeaderMissingKeys<'a> {
+    pub field: &'static str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for SyslogHeaderMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template for header field, using default.",
+            field = %self.field,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("header_missing_keys_total", 1, "field" => self.field);
+    }
+}
+
+#[derive(Debug)]
+pub struct SyslogInvalidPriority<'a> {
+    pub field: &'static str,
+    pub value: &'a str,
+}
+
+impl<'a> InternalEvent for SyslogInvalidPriority<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Invalid priority value, using default.",
+            field = %self.field,
+            value = %self.value,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "invalid_priority");
+    }
+}
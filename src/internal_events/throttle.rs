@@ -0,0 +1,24 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct ThrottleEventProcessed;
+
+impl InternalEvent for ThrottleEventProcessed {
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ThrottleEventDiscarded;
+
+impl InternalEvent for ThrottleEventDiscarded {
+    fn emit_logs(&self) {
+        debug!(message = "Rate limit exceeded.", rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
@@ -0,0 +1,39 @@
+use super::InternalEvent;
+use crate::event::Value;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct TimestampEventProcessed;
+
+impl InternalEvent for TimestampEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TimestampParseFailed<'a> {
+    pub field: &'a str,
+    pub value: &'a Value,
+}
+
+impl InternalEvent for TimestampParseFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field does not match any timestamp format.",
+            field = %self.field,
+            value = ?self.value,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "failed_parse",
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct TopKEventProcessed;
+
+impl InternalEvent for TopKEventProcessed {
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TopKFieldMissing<'a> {
+    pub field: &'a str,
+}
+
+impl InternalEvent for TopKFieldMissing<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Field does not exist.",
+            field = %self.field,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "missing_field",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TopKSummaryEmitted;
+
+impl InternalEvent for TopKSummaryEmitted {
+    fn emit_logs(&self) {
+        trace!(message = "Emitted top values summary.");
+    }
+}
@@ -0,0 +1,33 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct TraceSamplerEventProcessed;
+
+impl InternalEvent for TraceSamplerEventProcessed {
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TraceSamplerTraceDecided {
+    pub decision: &'static str,
+    pub spans: usize,
+}
+
+impl InternalEvent for TraceSamplerTraceDecided {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Decided trace.",
+            decision = %self.decision,
+            spans = %self.spans
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("traces_decided_total", 1,
+            "decision" => self.decision,
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct UrlParserEventProcessed;
+
+impl InternalEvent for UrlParserEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct UrlParserFailedParse<'a> {
+    pub field: &'a str,
+    pub error: url::ParseError,
+}
+
+impl InternalEvent for UrlParserFailedParse<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field failed to parse as a URL.",
+            field = %self.field,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "failed_parse",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct UrlParserFieldMissing<'a> {
+    pub field: &'a str,
+}
+
+impl InternalEvent for UrlParserFieldMissing<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field does not exist.",
+            field = %self.field,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "missing_field",
+        );
+    }
+}
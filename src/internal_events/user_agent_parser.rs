@@ -0,0 +1,36 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct UserAgentParserEventProcessed;
+
+impl InternalEvent for UserAgentParserEventProcessed {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct UserAgentParserFieldMissing<'a> {
+    pub field: &'a str,
+}
+
+impl InternalEvent for UserAgentParserFieldMissing<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field does not exist.",
+            field = %self.field,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "missing_field",
+        );
+    }
+}
@@ -44,3 +44,46 @@ impl InternalEvent for VectorProtoDecodeError {
         counter!("protobuf_decode_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct VectorSendFailed<'a> {
+    pub address: &'a str,
+    pub error: &'a str,
+}
+
+impl InternalEvent for VectorSendFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(message = "Failed to send events to endpoint.", address = %self.address, error = %self.error, rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("send_errors_total", 1, "address" => self.address.to_owned());
+    }
+}
+
+#[derive(Debug)]
+pub struct VectorEndpointEjected<'a> {
+    pub address: &'a str,
+    pub failures: usize,
+}
+
+impl InternalEvent for VectorEndpointEjected<'_> {
+    fn emit_logs(&self) {
+        error!(message = "Endpoint ejected; sending events to the others.", address = %self.address, failures = %self.failures);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("endpoint_ejections_total", 1, "address" => self.address.to_owned());
+    }
+}
+
+#[derive(Debug)]
+pub struct VectorEndpointRestored<'a> {
+    pub address: &'a str,
+}
+
+impl InternalEvent for VectorEndpointRestored<'_> {
+    fn emit_logs(&self) {
+        info!(message = "Endpoint is healthy again.", address = %self.address);
+    }
+}
@@ -0,0 +1,63 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct WebhookNotifyTemplateMissingKeys<'a> {
+    pub field: &'a str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for WebhookNotifyTemplateMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template; dropping event.",
+            field = %self.field,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "render_error");
+    }
+}
+
+/// The URI itself isn't logged, as webhook URIs hold the credentials of the
+/// webhook.
+#[derive(Debug)]
+pub struct WebhookNotifyInvalidUri<'a> {
+    pub error: &'a http::uri::InvalidUri,
+}
+
+impl<'a> InternalEvent for WebhookNotifyInvalidUri<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rendered URI is invalid; dropping event.",
+            error = %self.error,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "invalid_uri");
+    }
+}
+
+#[derive(Debug)]
+pub struct WebhookNotifyRateLimited {
+    pub delay: std::time::Duration,
+}
+
+impl InternalEvent for WebhookNotifyRateLimited {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Destination rate limit reached; delaying request.",
+            delay_ms = %self.delay.as_millis(),
+            rate_limit_secs = 10,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("webhook_notify_rate_limited_total", 1);
+    }
+}
@@ -0,0 +1,116 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::net::SocketAddr;
+
+#[derive(Debug)]
+pub struct WebSocketConnectionEstablished;
+
+impl InternalEvent for WebSocketConnectionEstablished {
+    fn emit_logs(&self) {
+        debug!(message = "Connected.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_established_total", 1, "mode" => "websocket");
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketConnectionFailed<E> {
+    pub error: E,
+}
+
+impl<E> InternalEvent for WebSocketConnectionFailed<E>
+where
+    E: std::error::Error,
+{
+    fn emit_logs(&self) {
+        error!(message = "Unable to connect.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_failed_total", 1, "mode" => "websocket");
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketConnectionShutdown;
+
+impl InternalEvent for WebSocketConnectionShutdown {
+    fn emit_logs(&self) {
+        debug!(message = "Connection closed by the server.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_shutdown_total", 1, "mode" => "websocket");
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketConnectionError<E> {
+    pub error: E,
+}
+
+impl<E> InternalEvent for WebSocketConnectionError<E>
+where
+    E: std::error::Error,
+{
+    fn emit_logs(&self) {
+        warn!(message = "Connection error.", error = %self.error, rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1, "mode" => "websocket");
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketClientConnected {
+    pub peer_addr: SocketAddr,
+}
+
+impl InternalEvent for WebSocketClientConnected {
+    fn emit_logs(&self) {
+        debug!(message = "Client connected.", peer_addr = %self.peer_addr);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_established_total", 1, "mode" => "websocket");
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketClientDisconnected {
+    pub peer_addr: SocketAddr,
+}
+
+impl InternalEvent for WebSocketClientDisconnected {
+    fn emit_logs(&self) {
+        debug!(message = "Client disconnected.", peer_addr = %self.peer_addr);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_shutdown_total", 1, "mode" => "websocket");
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketClientLagged {
+    pub peer_addr: SocketAddr,
+    pub skipped: u64,
+}
+
+impl InternalEvent for WebSocketClientLagged {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Client is too slow; skipping events.",
+            peer_addr = %self.peer_addr,
+            skipped = %self.skipped,
+            rate_limit_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", self.skipped, "reason" => "client_lagged");
+    }
+}
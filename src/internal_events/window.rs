@@ -0,0 +1,42 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct WindowEventProcessed;
+
+impl InternalEvent for WindowEventProcessed {
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct WindowFlushed {
+    pub count: usize,
+}
+
+impl InternalEvent for WindowFlushed {
+    fn emit_logs(&self) {
+        trace!(message = "Flushed closed windows.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("windows_flushed_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct WindowLateEventDropped;
+
+impl InternalEvent for WindowLateEventDropped {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Event arrived after its window was emitted; dropping it from that window.",
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("late_events_dropped_total", 1);
+    }
+}
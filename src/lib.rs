@@ -27,6 +27,7 @@ pub mod buffers;
 pub mod cli;
 pub mod conditions;
 pub mod dns;
+pub mod enrichment_tables;
 pub mod event;
 pub mod expiring_hash_map;
 pub mod generate;
@@ -38,6 +39,13 @@ pub mod internal_events;
 pub mod api;
 pub mod app;
 pub mod async_read;
+#[cfg(feature = "sources-kafka")]
+pub mod avro;
+#[cfg(any(
+    feature = "transforms-encrypt_fields",
+    feature = "transforms-decrypt_fields"
+))]
+pub mod field_encryption;
 pub mod heartbeat;
 pub mod http;
 #[cfg(feature = "rdkafka")]
@@ -50,9 +58,13 @@ pub mod metrics;
 pub(crate) mod pipeline;
 #[cfg(any(feature = "sinks-prometheus", feature = "sources-prometheus"))]
 pub(crate) mod prometheus;
+#[cfg(any(feature = "sources-kafka", feature = "transforms-protobuf_parser"))]
+pub mod protobuf;
 pub mod remap;
+pub mod resources;
 #[cfg(feature = "rusoto_core")]
 pub mod rusoto;
+pub mod secrets;
 pub mod serde;
 pub mod service;
 pub mod shutdown;
@@ -1,5 +1,8 @@
 pub const METRIC_NAME_LABEL: &str = "__name__";
 
+/// The bit pattern of the NaN Prometheus uses to mark a series as stale.
+pub const STALE_NAN: u64 = 0x7ff0_0000_0000_0002;
+
 pub mod proto {
     include!(concat!(env!("OUT_DIR"), "/prometheus.rs"));
 }
@@ -0,0 +1,525 @@
+//! Decoding of protobuf messages whose schema is only known at runtime, from
+//! a compiled `FileDescriptorSet` as written by
+//! `protoc --include_imports --descriptor_set_out`.
+
+use crate::event::Value;
+use bytes::Bytes;
+use prost::Message;
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorSet,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    sync::Arc,
+};
+
+#[derive(Debug, Snafu)]
+pub enum BuildError {
+    #[snafu(display("Could not read descriptor set {:?}: {}", path, source))]
+    ReadDescriptorSet {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Invalid descriptor set {:?}: {}", path, source))]
+    InvalidDescriptorSet {
+        path: PathBuf,
+        source: prost::DecodeError,
+    },
+    #[snafu(display("Message type {:?} not found in the descriptor set", name))]
+    UnknownMessageType { name: String },
+}
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum DecodeError {
+    #[snafu(display("Message is truncated"))]
+    Truncated,
+    #[snafu(display("Invalid varint"))]
+    InvalidVarint,
+    #[snafu(display("Unsupported wire type {} for field {}", wire_type, field))]
+    UnsupportedWireType { field: u32, wire_type: u8 },
+    #[snafu(display("Message type {:?} not found in the descriptor set", name))]
+    MissingMessageType { name: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ProtobufConfig {
+    /// Path to the compiled `FileDescriptorSet`.
+    pub descriptor_set: PathBuf,
+    /// Fully qualified name of the message type to decode, e.g.
+    /// `com.example.Event`.
+    pub message_type: String,
+}
+
+impl ProtobufConfig {
+    pub fn build(&self) -> Result<ProtobufDecoder, BuildError> {
+        let path = &self.descriptor_set;
+        let bytes = std::fs::read(path).context(ReadDescriptorSet { path })?;
+        let set = FileDescriptorSet::decode(&bytes[..]).context(InvalidDescriptorSet { path })?;
+        ProtobufDecoder::new(&set, &self.message_type)
+    }
+}
+
+/// Decodes one message type, and the types it references, into `Value`s.
+#[derive(Debug, Clone)]
+pub struct ProtobufDecoder {
+    message_type: String,
+    messages: Arc<HashMap<String, DescriptorProto>>,
+    enums: Arc<HashMap<String, EnumDescriptorProto>>,
+}
+
+impl ProtobufDecoder {
+    pub fn new(set: &FileDescriptorSet, message_type: &str) -> Result<Self, BuildError> {
+        let mut messages = HashMap::new();
+        let mut enums = HashMap::new();
+        for file in &set.file {
+            let prefix = match file.package() {
+                "" => String::new(),
+                package => format!(".{}", package),
+            };
+            index(
+                &prefix,
+                &file.message_type,
+                &file.enum_type,
+                &mut messages,
+                &mut enums,
+            );
+        }
+
+        let message_type = format!(".{}", message_type.trim_start_matches('.'));
+        if !messages.contains_key(&message_type) {
+            return Err(BuildError::UnknownMessageType {
+                name: message_type[1..].to_owned(),
+            });
+        }
+
+        Ok(Self {
+            message_type,
+            messages: Arc::new(messages),
+            enums: Arc::new(enums),
+        })
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> Result<BTreeMap<String, Value>, DecodeError> {
+        self.decode_message(&self.message_type, bytes)
+    }
+
+    fn decode_message(
+        &self,
+        name: &str,
+        mut bytes: &[u8],
+    ) -> Result<BTreeMap<String, Value>, DecodeError> {
+        let message = self
+            .messages
+            .get(name)
+            .ok_or_else(|| DecodeError::MissingMessageType {
+                name: name.trim_start_matches('.').to_owned(),
+            })?;
+
+        let mut fields = BTreeMap::new();
+        while !bytes.is_empty() {
+            let tag = read_varint(&mut bytes)?;
+            let number = (tag >> 3) as u32;
+            let wire_type = (tag & 0x07) as u8;
+
+            let field = match message
+                .field
+                .iter()
+                .find(|field| field.number() as u32 == number)
+            {
+                Some(field) => field,
+                None => {
+                    skip(&mut bytes, number, wire_type)?;
+                    continue;
+                }
+            };
+
+            let values = self.decode_field(field, wire_type, &mut bytes)?;
+            if field.label() == Label::Repeated {
+                let entry = fields
+                    .entry(field.name().to_owned())
+                    .or_insert_with(|| self.empty_repeated(field));
+                for value in values {
+                    match (&mut *entry, value) {
+                        (Value::Map(map), Value::Map(mut pair)) => {
+                            let key = pair
+                                .remove("key")
+                                .map(|key| key.to_string_lossy())
+                                .unwrap_or_default();
+                            map.insert(key, pair.remove("value").unwrap_or(Value::Null));
+                        }
+                        (Value::Array(array), value) => array.push(value),
+                        _ => (),
+                    }
+                }
+            } else if let Some(value) = values.into_iter().last() {
+                fields.insert(field.name().to_owned(), value);
+            }
+        }
+
+        Ok(fields)
+    }
+
+    /// Map fields are repeated key/value entry messages, they are decoded
+    /// into a map instead of an array.
+    fn empty_repeated(&self, field: &FieldDescriptorProto) -> Value {
+        let map_entry = field.r#type() == Type::Message
+            && self
+                .messages
+                .get(field.type_name())
+                .and_then(|message| message.options.as_ref())
+                .map(|options| options.map_entry())
+                .unwrap_or(false);
+        if map_entry {
+            Value::Map(BTreeMap::new())
+        } else {
+            Value::Array(Vec::new())
+        }
+    }
+
+    fn decode_field(
+        &self,
+        field: &FieldDescriptorProto,
+        wire_type: u8,
+        bytes: &mut &[u8],
+    ) -> Result<Vec<Value>, DecodeError> {
+        let number = field.number() as u32;
+        let unsupported = || DecodeError::UnsupportedWireType {
+            field: number,
+            wire_type,
+        };
+
+        match (field.r#type(), wire_type) {
+            (Type::String, 2) | (Type::Bytes, 2) => {
+                let data = read_length_delimited(bytes)?;
+                Ok(vec![Value::Bytes(Bytes::copy_from_slice(data))])
+            }
+            (Type::Message, 2) => {
+                let data = read_length_delimited(bytes)?;
+                let message = self.decode_message(field.type_name(), data)?;
+                Ok(vec![Value::Map(message)])
+            }
+            (Type::Group, _) => Err(unsupported()),
+            // Packed repeated scalars.
+            (_, 2) => {
+                let mut data = read_length_delimited(bytes)?;
+                let wire_type = match field.r#type() {
+                    Type::Double | Type::Fixed64 | Type::Sfixed64 => 1,
+                    Type::Float | Type::Fixed32 | Type::Sfixed32 => 5,
+                    _ => 0,
+                };
+                let mut values = Vec::new();
+                while !data.is_empty() {
+                    values.push(self.decode_scalar(field, wire_type, &mut data)?);
+                }
+                Ok(values)
+            }
+            (_, 0) | (_, 1) | (_, 5) => Ok(vec![self.decode_scalar(field, wire_type, bytes)?]),
+            _ => Err(unsupported()),
+        }
+    }
+
+    fn decode_scalar(
+        &self,
+        field: &FieldDescriptorProto,
+        wire_type: u8,
+        bytes: &mut &[u8],
+    ) -> Result<Value, DecodeError> {
+        let raw = match wire_type {
+            0 => read_varint(bytes)?,
+            1 => read_fixed(bytes, 8)?,
+            5 => read_fixed(bytes, 4)?,
+            _ => {
+                return Err(DecodeError::UnsupportedWireType {
+                    field: field.number() as u32,
+                    wire_type,
+                })
+            }
+        };
+
+        Ok(match field.r#type() {
+            Type::Double => Value::Float(f64::from_bits(raw)),
+            Type::Float => Value::Float(f32::from_bits(raw as u32) as f64),
+            Type::Int64 | Type::Uint64 | Type::Fixed64 | Type::Sfixed64 => {
+                Value::Integer(raw as i64)
+            }
+            Type::Int32 | Type::Sfixed32 => Value::Integer(raw as i32 as i64),
+            Type::Uint32 | Type::Fixed32 => Value::Integer(raw as u32 as i64),
+            Type::Sint32 | Type::Sint64 => Value::Integer((raw >> 1) as i64 ^ -((raw & 1) as i64)),
+            Type::Bool => Value::Boolean(raw != 0),
+            Type::Enum => {
+                let number = raw as i32;
+                self.enums
+                    .get(field.type_name())
+                    .and_then(|e| e.value.iter().find(|value| value.number() == number))
+                    .map(|value| Value::from(value.name().to_owned()))
+                    .unwrap_or_else(|| Value::Integer(number as i64))
+            }
+            Type::String | Type::Bytes | Type::Message | Type::Group => {
+                return Err(DecodeError::UnsupportedWireType {
+                    field: field.number() as u32,
+                    wire_type,
+                })
+            }
+        })
+    }
+}
+
+/// Registers the messages and enums under their fully qualified names, as
+/// used by `type_name` in field descriptors.
+fn index(
+    prefix: &str,
+    message_types: &[DescriptorProto],
+    enum_types: &[EnumDescriptorProto],
+    messages: &mut HashMap<String, DescriptorProto>,
+    enums: &mut HashMap<String, EnumDescriptorProto>,
+) {
+    for enum_type in enum_types {
+        enums.insert(
+            format!("{}.{}", prefix, enum_type.name()),
+            enum_type.clone(),
+        );
+    }
+    for message in message_types {
+        let name = format!("{}.{}", prefix, message.name());
+        index(
+            &name,
+            &message.nested_type,
+            &message.enum_type,
+            messages,
+            enums,
+        );
+        messages.insert(name, message.clone());
+    }
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, DecodeError> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return Ok(value);
+        }
+    }
+    if bytes.len() < 10 {
+        Err(DecodeError::Truncated)
+    } else {
+        Err(DecodeError::InvalidVarint)
+    }
+}
+
+fn read_fixed(bytes: &mut &[u8], len: usize) -> Result<u64, DecodeError> {
+    if bytes.len() < len {
+        return Err(DecodeError::Truncated);
+    }
+    let value = bytes[..len]
+        .iter()
+        .rev()
+        .fold(0u64, |value, &byte| (value << 8) | byte as u64);
+    *bytes = &bytes[len..];
+    Ok(value)
+}
+
+fn read_length_delimited<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8], DecodeError> {
+    let len = read_varint(bytes)? as usize;
+    if bytes.len() < len {
+        return Err(DecodeError::Truncated);
+    }
+    let (data, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(data)
+}
+
+fn skip(bytes: &mut &[u8], field: u32, wire_type: u8) -> Result<(), DecodeError> {
+    match wire_type {
+        0 => read_varint(bytes).map(|_| ()),
+        1 => read_fixed(bytes, 8).map(|_| ()),
+        2 => read_length_delimited(bytes).map(|_| ()),
+        5 => read_fixed(bytes, 4).map(|_| ()),
+        _ => Err(DecodeError::UnsupportedWireType { field, wire_type }),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use prost_types::{
+        EnumValueDescriptorProto, FieldDescriptorProto, FileDescriptorProto, MessageOptions,
+    };
+
+    fn field(
+        name: &str,
+        number: i32,
+        ty: Type,
+        label: Label,
+        type_name: &str,
+    ) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.into()),
+            number: Some(number),
+            r#type: Some(ty as i32),
+            label: Some(label as i32),
+            type_name: if type_name.is_empty() {
+                None
+            } else {
+                Some(type_name.into())
+            },
+            ..Default::default()
+        }
+    }
+
+    /// package test;
+    /// message Event {
+    ///   enum Level { INFO = 0; ERROR = 1; }
+    ///   message Host { string name = 1; }
+    ///   string message = 1;
+    ///   sint64 delta = 2;
+    ///   Level level = 3;
+    ///   Host host = 4;
+    ///   repeated int32 codes = 5;
+    ///   map<string, string> labels = 6;
+    ///   double ratio = 7;
+    /// }
+    pub(crate) fn descriptor_set() -> FileDescriptorSet {
+        let labels_entry = DescriptorProto {
+            name: Some("LabelsEntry".into()),
+            field: vec![
+                field("key", 1, Type::String, Label::Optional, ""),
+                field("value", 2, Type::String, Label::Optional, ""),
+            ],
+            options: Some(MessageOptions {
+                map_entry: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let host = DescriptorProto {
+            name: Some("Host".into()),
+            field: vec![field("name", 1, Type::String, Label::Optional, "")],
+            ..Default::default()
+        };
+        let level = EnumDescriptorProto {
+            name: Some("Level".into()),
+            value: vec![
+                EnumValueDescriptorProto {
+                    name: Some("INFO".into()),
+                    number: Some(0),
+                    ..Default::default()
+                },
+                EnumValueDescriptorProto {
+                    name: Some("ERROR".into()),
+                    number: Some(1),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let event = DescriptorProto {
+            name: Some("Event".into()),
+            field: vec![
+                field("message", 1, Type::String, Label::Optional, ""),
+                field("delta", 2, Type::Sint64, Label::Optional, ""),
+                field("level", 3, Type::Enum, Label::Optional, ".test.Event.Level"),
+                field(
+                    "host",
+                    4,
+                    Type::Message,
+                    Label::Optional,
+                    ".test.Event.Host",
+                ),
+                field("codes", 5, Type::Int32, Label::Repeated, ""),
+                field(
+                    "labels",
+                    6,
+                    Type::Message,
+                    Label::Repeated,
+                    ".test.Event.LabelsEntry",
+                ),
+                field("ratio", 7, Type::Double, Label::Optional, ""),
+            ],
+            nested_type: vec![host, labels_entry],
+            enum_type: vec![level],
+            ..Default::default()
+        };
+
+        FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("test.proto".into()),
+                package: Some("test".into()),
+                message_type: vec![event],
+                ..Default::default()
+            }],
+        }
+    }
+
+    /// An `Event` with every field set, as encoded by `protoc`.
+    pub(crate) fn encoded_event() -> Vec<u8> {
+        let mut bytes = vec![0x0a, 0x05];
+        bytes.extend_from_slice(b"hello");
+        // delta = -3, zigzag encoded.
+        bytes.extend_from_slice(&[0x10, 0x05]);
+        // level = ERROR
+        bytes.extend_from_slice(&[0x18, 0x01]);
+        // host { name: "web" }
+        bytes.extend_from_slice(&[0x22, 0x05, 0x0a, 0x03]);
+        bytes.extend_from_slice(b"web");
+        // codes = [1, 300], packed.
+        bytes.extend_from_slice(&[0x2a, 0x03, 0x01, 0xac, 0x02]);
+        // labels { key: "env" value: "prod" }
+        bytes.extend_from_slice(&[0x32, 0x0b, 0x0a, 0x03]);
+        bytes.extend_from_slice(b"env");
+        bytes.extend_from_slice(&[0x12, 0x04]);
+        bytes.extend_from_slice(b"prod");
+        // ratio = 0.5
+        bytes.push(0x39);
+        bytes.extend_from_slice(&0.5f64.to_bits().to_le_bytes());
+        // An unknown field 15 = 1, skipped.
+        bytes.extend_from_slice(&[0x78, 0x01]);
+        bytes
+    }
+
+    #[test]
+    fn decodes_message() {
+        let decoder = ProtobufDecoder::new(&descriptor_set(), "test.Event").unwrap();
+        let fields = decoder.decode(&encoded_event()).unwrap();
+
+        assert_eq!(fields["message"], "hello".into());
+        assert_eq!(fields["delta"], Value::Integer(-3));
+        assert_eq!(fields["level"], "ERROR".into());
+        assert_eq!(fields["ratio"], Value::Float(0.5));
+        assert_eq!(
+            fields["codes"],
+            Value::Array(vec![Value::Integer(1), Value::Integer(300)])
+        );
+
+        let mut host = BTreeMap::new();
+        host.insert("name".to_owned(), Value::from("web"));
+        assert_eq!(fields["host"], Value::Map(host));
+
+        let mut labels = BTreeMap::new();
+        labels.insert("env".to_owned(), Value::from("prod"));
+        assert_eq!(fields["labels"], Value::Map(labels));
+
+        assert_eq!(fields.len(), 7);
+    }
+
+    #[test]
+    fn rejects_truncated_message() {
+        let decoder = ProtobufDecoder::new(&descriptor_set(), "test.Event").unwrap();
+        let bytes = encoded_event();
+        assert_eq!(
+            decoder.decode(&bytes[..4]).unwrap_err(),
+            DecodeError::Truncated
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_message_type() {
+        assert!(ProtobufDecoder::new(&descriptor_set(), "test.Missing").is_err());
+    }
+}
@@ -8,10 +8,12 @@ mod del;
 mod downcase;
 mod ends_with;
 mod exists;
+mod find_enrichment_table_records;
 mod flatten;
 mod floor;
 mod format_number;
 mod format_timestamp;
+mod get_enrichment_table_record;
 mod ip_cidr_contains;
 mod ip_subnet;
 mod ip_to_ipv6;
@@ -60,10 +62,12 @@ pub use del::Del;
 pub use downcase::Downcase;
 pub use ends_with::EndsWith;
 pub use exists::Exists;
+pub use find_enrichment_table_records::FindEnrichmentTableRecords;
 pub use flatten::Flatten;
 pub use floor::Floor;
 pub use format_number::FormatNumber;
 pub use format_timestamp::FormatTimestamp;
+pub use get_enrichment_table_record::GetEnrichmentTableRecord;
 pub use ip_cidr_contains::IpCidrContains;
 pub use ip_subnet::IpSubnet;
 pub use ip_to_ipv6::IpToIpv6;
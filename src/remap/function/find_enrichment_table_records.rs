@@ -0,0 +1,88 @@
+use super::get_enrichment_table_record::{Lookup, PARAMETERS};
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct FindEnrichmentTableRecords;
+
+impl Function for FindEnrichmentTableRecords {
+    fn identifier(&self) -> &'static str {
+        "find_enrichment_table_records"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        PARAMETERS
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let lookup = Lookup::compile(&mut arguments)?;
+
+        Ok(Box::new(FindEnrichmentTableRecordsFn { lookup }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FindEnrichmentTableRecordsFn {
+    lookup: Lookup,
+}
+
+impl Expression for FindEnrichmentTableRecordsFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        self.lookup.find_rows(state, object).map(Value::Array)
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.lookup
+            .type_def(state)
+            .into_fallible(true) // the table can fail to look up rows
+            .with_constraint(value::Kind::Array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{map, remap::function::get_enrichment_table_record::tests::table};
+
+    remap::test_type_def![value_string {
+        expr: |_| FindEnrichmentTableRecordsFn {
+            lookup: Lookup::new(table(), "team", Literal::from("a").boxed()),
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: value::Kind::Array,
+        },
+    }];
+
+    #[test]
+    fn find_enrichment_table_records() {
+        let cases = vec![
+            (
+                map!["team": "a"],
+                Ok(Value::Array(vec![
+                    map!["id": 1, "name": "alice", "team": "a"].into(),
+                    map!["id": 2, "name": "bob", "team": "a"].into(),
+                ])),
+                FindEnrichmentTableRecordsFn {
+                    lookup: Lookup::new(table(), "team", Box::new(Path::from("team"))),
+                },
+            ),
+            (
+                map!["team": "c"],
+                Ok(Value::Array(vec![])),
+                FindEnrichmentTableRecordsFn {
+                    lookup: Lookup::new(table(), "team", Box::new(Path::from("team"))),
+                },
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use crate::enrichment_tables::{self, Condition, Row, Table};
+use remap::prelude::*;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug)]
+pub struct GetEnrichmentTableRecord;
+
+impl Function for GetEnrichmentTableRecord {
+    fn identifier(&self) -> &'static str {
+        "get_enrichment_table_record"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        PARAMETERS
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let lookup = Lookup::compile(&mut arguments)?;
+
+        Ok(Box::new(GetEnrichmentTableRecordFn { lookup }))
+    }
+}
+
+pub(super) const PARAMETERS: &[Parameter] = &[
+    Parameter {
+        keyword: "table",
+        accepts: |v| matches!(v, Value::Bytes(_)),
+        required: true,
+    },
+    Parameter {
+        keyword: "column",
+        accepts: |v| matches!(v, Value::Bytes(_)),
+        required: true,
+    },
+    Parameter {
+        keyword: "value",
+        accepts: |_| true,
+        required: true,
+    },
+    Parameter {
+        keyword: "date_column",
+        accepts: |v| matches!(v, Value::Bytes(_)),
+        required: false,
+    },
+    Parameter {
+        keyword: "from",
+        accepts: |v| matches!(v, Value::Timestamp(_)),
+        required: false,
+    },
+    Parameter {
+        keyword: "to",
+        accepts: |v| matches!(v, Value::Timestamp(_)),
+        required: false,
+    },
+];
+
+/// The conditions shared by the enrichment table functions: `column` has to
+/// equal `value`, and optionally `date_column` has to fall between `from`
+/// and `to`.
+#[derive(Debug, Clone)]
+pub(super) struct Lookup {
+    table: Arc<dyn Table>,
+    column: String,
+    value: Box<dyn Expression>,
+    date_range: Option<DateRange>,
+}
+
+#[derive(Debug, Clone)]
+struct DateRange {
+    column: String,
+    from: Box<dyn Expression>,
+    to: Box<dyn Expression>,
+}
+
+impl Lookup {
+    pub(super) fn compile(arguments: &mut ArgumentList) -> Result<Self> {
+        let name = literal_string(arguments.required_literal("table")?)?;
+        let table = enrichment_tables::get(&name)
+            .ok_or_else(|| format!(r#"unknown enrichment table "{}""#, name))?;
+        let column = literal_string(arguments.required_literal("column")?)?;
+        let value = arguments.required_expr("value")?;
+
+        let date_range = match arguments.optional_literal("date_column")? {
+            Some(column) => Some(DateRange {
+                column: literal_string(column)?,
+                from: arguments.required_expr("from")?,
+                to: arguments.required_expr("to")?,
+            }),
+            None => None,
+        };
+
+        Ok(Self {
+            table,
+            column,
+            value,
+            date_range,
+        })
+    }
+
+    #[cfg(test)]
+    pub(super) fn new(table: Arc<dyn Table>, column: &str, value: Box<dyn Expression>) -> Self {
+        Self {
+            table,
+            column: column.to_owned(),
+            value,
+            date_range: None,
+        }
+    }
+
+    pub(super) fn find_rows(
+        &self,
+        state: &mut state::Program,
+        object: &mut dyn Object,
+    ) -> Result<Vec<Value>> {
+        let value = self.value.execute(state, object)?;
+        let mut conditions = vec![Condition::Equals {
+            field: &self.column,
+            value: value.into(),
+        }];
+
+        if let Some(range) = &self.date_range {
+            conditions.push(Condition::BetweenDates {
+                field: &range.column,
+                from: range.from.execute(state, object)?.try_timestamp()?,
+                to: range.to.execute(state, object)?.try_timestamp()?,
+            });
+        }
+
+        Ok(self
+            .table
+            .find_table_rows(&conditions)?
+            .into_iter()
+            .map(row_to_value)
+            .collect())
+    }
+
+    pub(super) fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        let type_def = self.value.type_def(state);
+
+        match &self.date_range {
+            Some(range) => type_def
+                .merge(
+                    range
+                        .from
+                        .type_def(state)
+                        .fallible_unless(value::Kind::Timestamp),
+                )
+                .merge(
+                    range
+                        .to
+                        .type_def(state)
+                        .fallible_unless(value::Kind::Timestamp),
+                ),
+            None => type_def,
+        }
+    }
+}
+
+fn literal_string(literal: Literal) -> Result<String> {
+    let bytes = literal.as_value().clone().try_bytes()?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn row_to_value(row: Row) -> Value {
+    Value::Map(
+        row.into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect(),
+    )
+}
+
+#[derive(Debug, Clone)]
+struct GetEnrichmentTableRecordFn {
+    lookup: Lookup,
+}
+
+impl Expression for GetEnrichmentTableRecordFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let mut rows = self.lookup.find_rows(state, object)?;
+        match rows.len() {
+            1 => Ok(rows.remove(0)),
+            0 => Err("no rows found".into()),
+            n => Err(format!("{} rows found, expected one", n).into()),
+        }
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.lookup
+            .type_def(state)
+            .into_fallible(true) // no or too many matching rows
+            .with_constraint(value::Kind::Map)
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::{event, map};
+
+    /// A table matching rows on exact values only.
+    #[derive(Debug)]
+    pub struct TestTable(pub Vec<Row>);
+
+    impl Table for TestTable {
+        fn find_table_rows(
+            &self,
+            conditions: &[Condition<'_>],
+        ) -> std::result::Result<Vec<Row>, String> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|row| {
+                    conditions.iter().all(|condition| match condition {
+                        Condition::Equals { field, value } => row.get(*field) == Some(value),
+                        Condition::BetweenDates { .. } => true,
+                    })
+                })
+                .cloned()
+                .collect())
+        }
+    }
+
+    pub fn table() -> Arc<dyn Table> {
+        let row = |id: i64, name: &str, team: &str| -> Row {
+            vec![
+                ("id".to_owned(), event::Value::Integer(id)),
+                ("name".to_owned(), event::Value::from(name)),
+                ("team".to_owned(), event::Value::from(team)),
+            ]
+            .into_iter()
+            .collect()
+        };
+        Arc::new(TestTable(vec![
+            row(1, "alice", "a"),
+            row(2, "bob", "a"),
+            row(3, "carol", "b"),
+        ]))
+    }
+
+    remap::test_type_def![value_integer {
+        expr: |_| GetEnrichmentTableRecordFn {
+            lookup: Lookup::new(table(), "id", Literal::from(1).boxed()),
+        },
+        def: TypeDef {
+            fallible: true,
+            kind: value::Kind::Map,
+        },
+    }];
+
+    #[test]
+    fn get_enrichment_table_record() {
+        let cases = vec![
+            (
+                map!["user": 3],
+                Ok(map!["id": 3, "name": "carol", "team": "b"].into()),
+                GetEnrichmentTableRecordFn {
+                    lookup: Lookup::new(table(), "id", Box::new(Path::from("user"))),
+                },
+            ),
+            (
+                map!["user": 4],
+                Err("function call error: no rows found".to_owned()),
+                GetEnrichmentTableRecordFn {
+                    lookup: Lookup::new(table(), "id", Box::new(Path::from("user"))),
+                },
+            ),
+            (
+                map!["team": "a"],
+                Err("function call error: 2 rows found, expected one".to_owned()),
+                GetEnrichmentTableRecordFn {
+                    lookup: Lookup::new(table(), "team", Box::new(Path::from("team"))),
+                },
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+
+    #[test]
+    fn unknown_table() {
+        assert!(remap::Program::new(
+            r#"get_enrichment_table_record("no_such_table", "id", .id)"#,
+            &crate::remap::FUNCTIONS,
+            None,
+        )
+        .is_err());
+    }
+}
@@ -51,6 +51,8 @@ lazy_static! {
         Box::new(Log),
         Box::new(Flatten),
         Box::new(Merge),
+        Box::new(GetEnrichmentTableRecord),
+        Box::new(FindEnrichmentTableRecords),
     ];
 
     // List of both mutable, and immutable functions that can be loaded into a
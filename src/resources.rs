@@ -0,0 +1,362 @@
+//! Detection of the CPU and memory resources available to the process.
+//!
+//! When running inside a container the number of cores and the amount of
+//! memory reported by the host are rarely what Vector is actually allowed to
+//! use. The limits imposed through cgroups (v1 or v2) are taken into account
+//! here so that default thread counts, buffer sizes and batch sizes can be
+//! scaled to what is really available.
+
+use once_cell::sync::OnceCell;
+use std::cmp::max;
+#[cfg(target_os = "linux")]
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+static RESOURCES: OnceCell<Resources> = OnceCell::new();
+
+/// Amount of memory at or above which defaults are used unscaled.
+const REFERENCE_MEMORY: u64 = 1024 * 1024 * 1024;
+
+/// Defaults are never scaled below this fraction of their original value.
+const MIN_SCALE: f64 = 0.125;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LimitSource {
+    Host,
+    CgroupV1,
+    CgroupV2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Resources {
+    /// Number of CPUs the process may use, rounded up.
+    pub cpus: usize,
+    pub cpus_source: LimitSource,
+    /// Memory in bytes the process may use, if it could be determined.
+    pub memory: Option<u64>,
+    pub memory_source: LimitSource,
+}
+
+impl Default for Resources {
+    fn default() -> Self {
+        Self {
+            cpus: max(1, num_cpus::get()),
+            cpus_source: LimitSource::Host,
+            memory: None,
+            memory_source: LimitSource::Host,
+        }
+    }
+}
+
+impl Resources {
+    /// Inspect the host and any cgroup limits applied to this process.
+    pub fn detect() -> Self {
+        #[cfg_attr(not(target_os = "linux"), allow(unused_mut))]
+        let mut resources = Self::default();
+
+        #[cfg(target_os = "linux")]
+        {
+            resources.memory = read_host_memory(Path::new("/proc/meminfo"));
+
+            let root = Path::new("/sys/fs/cgroup");
+            let proc_cgroup = fs::read_to_string("/proc/self/cgroup").unwrap_or_default();
+
+            if let Some((cpus, source)) = read_cgroup_cpus(root, &proc_cgroup) {
+                if cpus < resources.cpus {
+                    resources.cpus = cpus;
+                    resources.cpus_source = source;
+                }
+            }
+
+            if let Some((memory, source)) = read_cgroup_memory(root, &proc_cgroup) {
+                if resources.memory.map_or(true, |host| memory < host) {
+                    resources.memory = Some(memory);
+                    resources.memory_source = source;
+                }
+            }
+        }
+
+        resources
+    }
+
+    /// Factor in `[0.125, 1.0]` by which memory-bound defaults are scaled.
+    pub fn memory_scale(&self) -> f64 {
+        match self.memory {
+            Some(memory) if memory < REFERENCE_MEMORY => {
+                (memory as f64 / REFERENCE_MEMORY as f64).max(MIN_SCALE)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Scale a memory-bound default, never going below one.
+    pub fn scale(&self, default: usize) -> usize {
+        if default == usize::max_value() {
+            return default;
+        }
+        max(1, (default as f64 * self.memory_scale()) as usize)
+    }
+}
+
+/// Record the resources detected at startup. Only the first call has an effect.
+pub fn init(resources: Resources) {
+    let _ = RESOURCES.set(resources);
+
+    let resources = get();
+    info!(
+        message = "Detected available resources.",
+        cpus = resources.cpus,
+        cpus_source = ?resources.cpus_source,
+        memory = ?resources.memory,
+        memory_source = ?resources.memory_source,
+        memory_scale = resources.memory_scale()
+    );
+}
+
+/// Resources recorded at startup. If none were recorded, which is the case
+/// when running tests, defaults are left unscaled.
+pub fn get() -> Resources {
+    RESOURCES.get().copied().unwrap_or_default()
+}
+
+/// Number of worker threads to use when not explicitly configured.
+pub fn default_threads() -> usize {
+    get().cpus
+}
+
+#[cfg(target_os = "linux")]
+fn read_host_memory(meminfo: &Path) -> Option<u64> {
+    parse_meminfo(&fs::read_to_string(meminfo).ok()?)
+}
+
+/// Reads the CPU limit of the cgroup of the process, given its
+/// `/proc/self/cgroup`, and of the cgroups above it, as the tightest of them
+/// applies.
+#[cfg(target_os = "linux")]
+fn read_cgroup_cpus(root: &Path, proc_cgroup: &str) -> Option<(usize, LimitSource)> {
+    let path = parse_proc_cgroup(proc_cgroup, None).unwrap_or("/");
+    let limits = read_along(root, path, "cpu.max");
+    if !limits.is_empty() {
+        return limits
+            .iter()
+            .filter_map(|content| parse_cpu_max(content))
+            .min()
+            .map(|cpus| (cpus, LimitSource::CgroupV2));
+    }
+
+    let path = parse_proc_cgroup(proc_cgroup, Some("cpu")).unwrap_or("/");
+    for dir in &["cpu", "cpu,cpuacct"] {
+        let quotas = read_along(&root.join(dir), path, "cpu.cfs_quota_us");
+        let periods = read_along(&root.join(dir), path, "cpu.cfs_period_us");
+        if !quotas.is_empty() && quotas.len() == periods.len() {
+            return quotas
+                .iter()
+                .zip(&periods)
+                .filter_map(|(quota, period)| parse_cfs_quota(quota, period))
+                .min()
+                .map(|cpus| (cpus, LimitSource::CgroupV1));
+        }
+    }
+
+    None
+}
+
+/// Reads the memory limit like `read_cgroup_cpus` reads the CPU limit.
+#[cfg(target_os = "linux")]
+fn read_cgroup_memory(root: &Path, proc_cgroup: &str) -> Option<(u64, LimitSource)> {
+    let path = parse_proc_cgroup(proc_cgroup, None).unwrap_or("/");
+    let limits = read_along(root, path, "memory.max");
+    if !limits.is_empty() {
+        return limits
+            .iter()
+            .filter_map(|content| parse_memory_limit(content))
+            .min()
+            .map(|memory| (memory, LimitSource::CgroupV2));
+    }
+
+    let path = parse_proc_cgroup(proc_cgroup, Some("memory")).unwrap_or("/");
+    read_along(&root.join("memory"), path, "memory.limit_in_bytes")
+        .iter()
+        .filter_map(|content| parse_memory_limit(content))
+        .min()
+        .map(|memory| (memory, LimitSource::CgroupV1))
+}
+
+/// Reads `file` from the cgroup at `path` and from each of its ancestors up
+/// to the hierarchy mounted at `mount`. Within a cgroup namespace the cgroup
+/// of the process is the root of the mount, so only that one is found.
+#[cfg(target_os = "linux")]
+fn read_along(mount: &Path, path: &str, file: &str) -> Vec<String> {
+    cgroup_dirs(mount, path)
+        .iter()
+        .filter_map(|dir| fs::read_to_string(dir.join(file)).ok())
+        .collect()
+}
+
+/// The directories of the cgroup at `path` and of its ancestors, innermost
+/// first.
+#[cfg(target_os = "linux")]
+fn cgroup_dirs(mount: &Path, path: &str) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let mut path = Path::new(path.trim_start_matches('/'));
+    loop {
+        dirs.push(mount.join(path));
+        match path.parent() {
+            Some(parent) => path = parent,
+            None => return dirs,
+        }
+    }
+}
+
+/// Finds the path of the cgroup of the process in the hierarchy of
+/// `controller` (or the unified cgroup v2 hierarchy if `None`) in
+/// `/proc/self/cgroup`, whose lines look like `4:cpu,cpuacct:/docker/abc`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_cgroup<'a>(content: &'a str, controller: Option<&str>) -> Option<&'a str> {
+    content.lines().find_map(|line| {
+        let mut parts = line.splitn(3, ':');
+        let _id = parts.next()?;
+        let controllers = parts.next()?;
+        let path = parts.next()?;
+        let matches = match controller {
+            None => controllers.is_empty(),
+            Some(controller) => controllers.split(',').any(|name| name == controller),
+        };
+        if matches {
+            Some(path)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo(content: &str) -> Option<u64> {
+    content
+        .lines()
+        .find(|line| line.starts_with("MemTotal:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+/// Parses the cgroup v2 `cpu.max` file, formatted as `$MAX $PERIOD`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cpu_max(content: &str) -> Option<usize> {
+    let mut parts = content.split_whitespace();
+    let quota = parts.next()?;
+    let period = parts.next().unwrap_or("100000");
+    parse_cfs_quota(quota, period)
+}
+
+/// Parses cgroup v1 CFS quota and period, a quota of `-1` (or `max`) means unlimited.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cfs_quota(quota: &str, period: &str) -> Option<usize> {
+    let quota = quota
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|quota| *quota > 0)?;
+    let period = period
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|period| *period > 0)?;
+    Some(max(1, ((quota + period - 1) / period) as usize))
+}
+
+/// cgroup v1 reports "unlimited" as a very large number rather than `max`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_memory_limit(content: &str) -> Option<u64> {
+    content
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|limit| *limit < (1 << 62))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cgroup_v2_cpu_max() {
+        assert_eq!(parse_cpu_max("max 100000\n"), None);
+        assert_eq!(parse_cpu_max("200000 100000\n"), Some(2));
+        assert_eq!(parse_cpu_max("150000 100000\n"), Some(2));
+        assert_eq!(parse_cpu_max("50000 100000\n"), Some(1));
+    }
+
+    #[test]
+    fn parses_cgroup_v1_cfs_quota() {
+        assert_eq!(parse_cfs_quota("-1\n", "100000\n"), None);
+        assert_eq!(parse_cfs_quota("400000\n", "100000\n"), Some(4));
+    }
+
+    #[test]
+    fn parses_memory_limits() {
+        assert_eq!(parse_memory_limit("max\n"), None);
+        assert_eq!(parse_memory_limit("9223372036854771712\n"), None);
+        assert_eq!(parse_memory_limit("536870912\n"), Some(536_870_912));
+    }
+
+    #[test]
+    fn parses_proc_cgroup() {
+        let content =
+            "12:memory:/docker/abc\n4:cpu,cpuacct:/docker/abc\n0::/system.slice/vector.service\n";
+        assert_eq!(parse_proc_cgroup(content, Some("cpu")), Some("/docker/abc"));
+        assert_eq!(
+            parse_proc_cgroup(content, Some("memory")),
+            Some("/docker/abc")
+        );
+        assert_eq!(
+            parse_proc_cgroup(content, None),
+            Some("/system.slice/vector.service")
+        );
+        assert_eq!(parse_proc_cgroup(content, Some("pids")), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_tightest_limit_along_cgroup() {
+        let root = tempfile::tempdir().unwrap();
+        let service = root.path().join("system.slice/vector.service");
+        fs::create_dir_all(&service).unwrap();
+        fs::write(root.path().join("system.slice/cpu.max"), "200000 100000\n").unwrap();
+        fs::write(service.join("cpu.max"), "max 100000\n").unwrap();
+        fs::write(service.join("memory.max"), "536870912\n").unwrap();
+
+        let proc_cgroup = "0::/system.slice/vector.service\n";
+        assert_eq!(
+            read_cgroup_cpus(root.path(), proc_cgroup),
+            Some((2, LimitSource::CgroupV2))
+        );
+        assert_eq!(
+            read_cgroup_memory(root.path(), proc_cgroup),
+            Some((536_870_912, LimitSource::CgroupV2))
+        );
+        // Inside a cgroup namespace only the mount root is there.
+        assert_eq!(read_cgroup_cpus(root.path(), "0::/\n"), None);
+    }
+
+    #[test]
+    fn parses_meminfo() {
+        let content = "MemTotal:       16316412 kB\nMemFree:         1234567 kB\n";
+        assert_eq!(parse_meminfo(content), Some(16_316_412 * 1024));
+    }
+
+    #[test]
+    fn scales_defaults_by_memory() {
+        let mut resources = Resources::default();
+        assert_eq!(resources.scale(500), 500);
+
+        resources.memory = Some(REFERENCE_MEMORY / 2);
+        assert_eq!(resources.scale(500), 250);
+        assert_eq!(resources.scale(usize::max_value()), usize::max_value());
+
+        resources.memory = Some(1024);
+        assert_eq!(resources.scale(500), 62);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::rusoto::{self, RegionOrEndpoint};
+use rusoto_core::Region;
+use rusoto_secretsmanager::{GetSecretValueRequest, SecretsManager, SecretsManagerClient};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::{BTreeSet, HashMap},
+    convert::TryInto,
+};
+
+/// Reads secrets from the key/value pairs of a secret of AWS Secrets
+/// Manager, which holds them as a JSON object.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AwsSecretsManagerBackendConfig {
+    secret_id: String,
+    #[serde(flatten)]
+    region: RegionOrEndpoint,
+    assume_role: Option<String>,
+}
+
+#[derive(Debug, Snafu)]
+enum AwsSecretsManagerError {
+    #[snafu(display("The secret has no string value"))]
+    NoSecretString,
+}
+
+impl AwsSecretsManagerBackendConfig {
+    fn create_client(&self) -> crate::Result<SecretsManagerClient> {
+        let region: Region = (&self.region).try_into()?;
+        let client = rusoto::client()?;
+        let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
+        Ok(SecretsManagerClient::new_with(client, creds, region))
+    }
+
+    pub(super) async fn retrieve(
+        &self,
+        keys: &BTreeSet<String>,
+    ) -> crate::Result<HashMap<String, String>> {
+        let response = self
+            .create_client()?
+            .get_secret_value(GetSecretValueRequest {
+                secret_id: self.secret_id.clone(),
+                ..Default::default()
+            })
+            .await?;
+        let secret_string = response
+            .secret_string
+            .ok_or(AwsSecretsManagerError::NoSecretString)?;
+
+        let mut secrets: HashMap<String, String> = serde_json::from_str(&secret_string)?;
+        secrets.retain(|key, _| keys.contains(key));
+        Ok(secrets)
+    }
+}
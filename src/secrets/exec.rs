@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::{BTreeSet, HashMap},
+    io,
+    process::{ExitStatus, Stdio},
+    time::Duration,
+};
+use tokio::{io::AsyncWriteExt, process::Command, time::timeout};
+
+/// Runs a command that is sent the keys to retrieve on its stdin, as
+/// `{"version": "1.0", "secrets": ["<key>", ...]}`, and answers on its
+/// stdout with `{"<key>": {"value": "<secret>", "error": null}, ...}`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExecBackendConfig {
+    command: Vec<String>,
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    5
+}
+
+#[derive(Debug, Snafu)]
+enum ExecError {
+    #[snafu(display("The command is empty"))]
+    EmptyCommand,
+    #[snafu(display("The command didn't finish within {} seconds", timeout_secs))]
+    TimedOut { timeout_secs: u64 },
+    #[snafu(display("The command exited with {}", status))]
+    Failed { status: ExitStatus },
+    #[snafu(display("The command failed to retrieve {:?}: {}", key, error))]
+    Secret { key: String, error: String },
+}
+
+#[derive(Serialize)]
+struct ExecRequest<'a> {
+    version: &'static str,
+    secrets: &'a BTreeSet<String>,
+}
+
+#[derive(Deserialize)]
+struct ExecResponse {
+    value: Option<String>,
+    error: Option<String>,
+}
+
+impl ExecBackendConfig {
+    pub(super) async fn retrieve(
+        &self,
+        keys: &BTreeSet<String>,
+    ) -> crate::Result<HashMap<String, String>> {
+        let (program, args) = self.command.split_first().ok_or(ExecError::EmptyCommand)?;
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let request = serde_json::to_vec(&ExecRequest {
+            version: "1.0",
+            secrets: keys,
+        })?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        if let Err(error) = stdin.write_all(&request).await {
+            // Commands that don't need the keys may exit without reading them.
+            if error.kind() != io::ErrorKind::BrokenPipe {
+                return Err(error.into());
+            }
+        }
+        drop(stdin);
+
+        let output = timeout(
+            Duration::from_secs(self.timeout_secs),
+            child.wait_with_output(),
+        )
+        .await
+        .map_err(|_| ExecError::TimedOut {
+            timeout_secs: self.timeout_secs,
+        })??;
+        if !output.status.success() {
+            return Err(ExecError::Failed {
+                status: output.status,
+            }
+            .into());
+        }
+
+        let response: HashMap<String, ExecResponse> = serde_json::from_slice(&output.stdout)?;
+        let mut secrets = HashMap::new();
+        for (key, ExecResponse { value, error }) in response {
+            if let Some(error) = error {
+                return Err(ExecError::Secret { key, error }.into());
+            }
+            if let Some(value) = value {
+                secrets.insert(key, value);
+            }
+        }
+        Ok(secrets)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn backend(script: &str) -> ExecBackendConfig {
+        ExecBackendConfig {
+            command: vec!["sh".into(), "-c".into(), script.into()],
+            timeout_secs: 1,
+        }
+    }
+
+    fn keys() -> BTreeSet<String> {
+        vec!["api_key".to_owned()].into_iter().collect()
+    }
+
+    #[tokio::test]
+    async fn retrieves_secrets() {
+        let config = backend(
+            r#"grep -q '"secrets":\["api_key"\]' && echo '{"api_key": {"value": "abc123", "error": null}}'"#,
+        );
+        let secrets = config.retrieve(&keys()).await.unwrap();
+        assert_eq!(secrets["api_key"], "abc123");
+    }
+
+    #[tokio::test]
+    async fn reports_errors() {
+        let config = backend(r#"echo '{"api_key": {"value": null, "error": "denied"}}'"#);
+        let error = config.retrieve(&keys()).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The command failed to retrieve \"api_key\": denied"
+        );
+
+        let config = backend("exit 3");
+        assert!(config.retrieve(&keys()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn times_out() {
+        let config = backend("sleep 5");
+        let error = config.retrieve(&keys()).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The command didn't finish within 1 seconds"
+        );
+    }
+}
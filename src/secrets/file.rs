@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
+};
+
+/// Reads secrets from a JSON file holding an object of keys and secrets,
+/// such as one mounted from a Kubernetes secret.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FileBackendConfig {
+    path: PathBuf,
+}
+
+impl FileBackendConfig {
+    pub(super) async fn retrieve(
+        &self,
+        keys: &BTreeSet<String>,
+    ) -> crate::Result<HashMap<String, String>> {
+        let contents = tokio::fs::read(&self.path).await?;
+        let mut secrets: HashMap<String, String> = serde_json::from_slice(&contents)?;
+        secrets.retain(|key, _| keys.contains(key));
+        Ok(secrets)
+    }
+}
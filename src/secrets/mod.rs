@@ -0,0 +1,362 @@
+//! Secrets keep sensitive values such as API keys out of config files.
+//! Backends are configured in the `secret` section of the config, and every
+//! `SECRET[<backend>.<key>]` in a string value of the config is replaced with
+//! the secret retrieved from the backend each time the config is loaded or
+//! reloaded.
+
+use crate::config::{format, Format, FormatHint};
+use indexmap::IndexMap;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+#[cfg(feature = "secrets-aws_secrets_manager")]
+pub mod aws_secrets_manager;
+pub mod exec;
+pub mod file;
+pub mod vault;
+
+lazy_static! {
+    static ref SECRET_REFERENCE: Regex =
+        Regex::new(r"SECRET\[([[:word:]]+)\.([[:word:].\-/]+)\]").unwrap();
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SecretBackendConfig {
+    #[cfg(feature = "secrets-aws_secrets_manager")]
+    AwsSecretsManager(aws_secrets_manager::AwsSecretsManagerBackendConfig),
+    Exec(exec::ExecBackendConfig),
+    File(file::FileBackendConfig),
+    Vault(vault::VaultBackendConfig),
+}
+
+impl SecretBackendConfig {
+    /// Retrieves the secrets of the keys. Keys the backend doesn't know are
+    /// left out rather than failing.
+    async fn retrieve(&self, keys: &BTreeSet<String>) -> crate::Result<HashMap<String, String>> {
+        match self {
+            #[cfg(feature = "secrets-aws_secrets_manager")]
+            SecretBackendConfig::AwsSecretsManager(config) => config.retrieve(keys).await,
+            SecretBackendConfig::Exec(config) => config.retrieve(keys).await,
+            SecretBackendConfig::File(config) => config.retrieve(keys).await,
+            SecretBackendConfig::Vault(config) => config.retrieve(keys).await,
+        }
+    }
+}
+
+/// A config value. Unlike `serde_json::Value` it keeps the fields of tables in
+/// order, as that is significant for some options, such as the `sets` of
+/// `ip_classify` which are checked in order.
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(untagged)]
+enum Value {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Value>),
+    Table(IndexMap<String, Value>),
+}
+
+/// The part of a config declaring secret backends, which are needed before
+/// the rest of it can be deserialized.
+#[derive(Deserialize)]
+struct SecretSection {
+    #[serde(default)]
+    secret: IndexMap<String, SecretBackendConfig>,
+}
+
+/// Replaces the secret references in the string values of the configs with
+/// the secrets retrieved from the backends declared in any of them. Configs
+/// with references are returned as JSON, so the secrets need no escaping.
+pub async fn interpolate(
+    inputs: Vec<(String, FormatHint)>,
+) -> Result<Vec<(String, FormatHint)>, Vec<String>> {
+    let mut values = Vec::with_capacity(inputs.len());
+    let mut references = IndexMap::<String, BTreeSet<String>>::new();
+    for (input, format) in &inputs {
+        let value = if SECRET_REFERENCE.is_match(input) {
+            let value: Value = format::deserialize(input, *format)?;
+            visit_references(&value, &mut |caps: &Captures<'_>| {
+                references
+                    .entry(caps[1].to_owned())
+                    .or_default()
+                    .insert(caps[2].to_owned());
+            });
+            Some(value)
+        } else {
+            None
+        };
+        values.push(value);
+    }
+    if references.is_empty() {
+        return Ok(inputs);
+    }
+
+    let mut backends = IndexMap::new();
+    let mut errors = Vec::new();
+    for (input, format) in &inputs {
+        let section: SecretSection = format::deserialize(input, *format)?;
+        for (name, backend) in section.secret {
+            if backends.contains_key(&name) {
+                errors.push(format!("duplicate secret backend name found: {}", name));
+            } else {
+                backends.insert(name, backend);
+            }
+        }
+    }
+
+    let mut secrets = HashMap::new();
+    for (name, keys) in &references {
+        match backends.get(name) {
+            Some(backend) => match backend.retrieve(keys).await {
+                Ok(retrieved) => {
+                    secrets.insert(name.as_str(), retrieved);
+                }
+                Err(error) => errors.push(format!(
+                    "Failed to retrieve secrets from backend {:?}: {}",
+                    name, error
+                )),
+            },
+            None => errors.push(format!(
+                "Unknown secret backend in config. name = {:?}",
+                name
+            )),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let inputs = inputs
+        .into_iter()
+        .zip(values)
+        .map(|(input, value)| match value {
+            Some(mut value) => {
+                replace_references(
+                    &mut value,
+                    &mut |caps: &Captures<'_>| match secrets[&caps[1]].get(&caps[2]) {
+                        Some(secret) => secret.clone(),
+                        None => {
+                            errors.push(format!(
+                                "Secret not found in backend {:?}. key = {:?}",
+                                &caps[1], &caps[2]
+                            ));
+                            String::new()
+                        }
+                    },
+                );
+                let output = serde_json::to_string(&value).expect("Config should serialize");
+                (output, Some(Format::JSON))
+            }
+            None => input,
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(inputs)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Calls `f` with the secret references in the string values of the config,
+/// outside of the `secret` section.
+fn visit_references(config: &Value, f: &mut impl FnMut(&Captures<'_>)) {
+    fn visit(value: &Value, f: &mut impl FnMut(&Captures<'_>)) {
+        match value {
+            Value::String(string) => SECRET_REFERENCE
+                .captures_iter(string)
+                .for_each(|caps| f(&caps)),
+            Value::Array(values) => values.iter().for_each(|value| visit(value, f)),
+            Value::Table(fields) => fields.values().for_each(|value| visit(value, f)),
+            _ => (),
+        }
+    }
+
+    if let Value::Table(fields) = config {
+        for (key, value) in fields {
+            if key != "secret" {
+                visit(value, f);
+            }
+        }
+    }
+}
+
+/// Replaces the secret references in the string values of the config with
+/// the results of `f`, outside of the `secret` section.
+fn replace_references(config: &mut Value, f: &mut impl FnMut(&Captures<'_>) -> String) {
+    fn replace(value: &mut Value, f: &mut impl FnMut(&Captures<'_>) -> String) {
+        match value {
+            Value::String(string) => {
+                *string = SECRET_REFERENCE
+                    .replace_all(string, |caps: &Captures<'_>| f(caps))
+                    .into_owned();
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| replace(value, f)),
+            Value::Table(fields) => fields.values_mut().for_each(|value| replace(value, f)),
+            _ => (),
+        }
+    }
+
+    if let Value::Table(fields) = config {
+        for (key, value) in fields {
+            if key != "secret" {
+                replace(value, f);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn secrets_file(contents: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    #[tokio::test]
+    async fn interpolates_secrets() {
+        let file = secrets_file(r#"{"api_key": "abc123", "other": "def456"}"#);
+        let backends = format!(
+            r#"
+            [secret.local]
+            type = "file"
+            path = '{}'
+            "#,
+            file.path().display()
+        );
+        let sink = r#"
+            [sinks.out]
+            type = "blackhole"
+            api_key = "SECRET[local.api_key]"
+            "#;
+
+        let inputs = interpolate(vec![
+            (backends.clone(), Some(Format::TOML)),
+            (sink.to_owned(), Some(Format::TOML)),
+        ])
+        .await
+        .unwrap();
+
+        assert_eq!(inputs[0].0, backends);
+        let sink: serde_json::Value = serde_json::from_str(&inputs[1].0).unwrap();
+        assert_eq!(inputs[1].1, Some(Format::JSON));
+        assert_eq!(sink["sinks"]["out"]["api_key"], "abc123");
+    }
+
+    #[tokio::test]
+    async fn interpolates_secrets_needing_escaping() {
+        let file = secrets_file(r#"{"password": "a\"b'c\nd = 1"}"#);
+        let input = format!(
+            r#"
+            # The password is SECRET[local.unknown]
+            [sinks.out]
+            type = "blackhole"
+            auth.password = "SECRET[local.password]"
+            auth.user = 'SECRET[local.password]'
+
+            [secret.local]
+            type = "file"
+            path = '{}'
+            "#,
+            file.path().display()
+        );
+
+        let inputs = interpolate(vec![(input, Some(Format::TOML))])
+            .await
+            .unwrap();
+
+        let config: serde_json::Value = format::deserialize(&inputs[0].0, inputs[0].1).unwrap();
+        assert_eq!(config["sinks"]["out"]["auth"]["password"], "a\"b'c\nd = 1");
+        assert_eq!(config["sinks"]["out"]["auth"]["user"], "a\"b'c\nd = 1");
+        assert!(config["sinks"]["out"].get("d").is_none());
+    }
+
+    #[tokio::test]
+    async fn keeps_order_of_tables() {
+        let file = secrets_file(r#"{"field": "ip"}"#);
+        let input = format!(
+            r#"
+            [transforms.classify]
+            type = "ip_classify"
+            inputs = ["in"]
+            field = "SECRET[local.field]"
+            sets.private.cidrs = ["10.0.0.0/8"]
+            sets.corporate.cidrs = ["10.1.0.0/16"]
+            sets.any.cidrs = ["0.0.0.0/0"]
+
+            [secret.local]
+            type = "file"
+            path = '{}'
+            "#,
+            file.path().display()
+        );
+
+        let inputs = interpolate(vec![(input, Some(Format::TOML))])
+            .await
+            .unwrap();
+
+        let config: IndexMap<String, IndexMap<String, IndexMap<String, Value>>> =
+            format::deserialize(&inputs[0].0, inputs[0].1).unwrap();
+        let classify = &config["transforms"]["classify"];
+        assert_eq!(classify["field"], Value::String("ip".to_owned()));
+        match &classify["sets"] {
+            Value::Table(sets) => assert_eq!(
+                sets.keys().collect::<Vec<_>>(),
+                vec!["private", "corporate", "any"]
+            ),
+            sets => panic!("Unexpected sets: {:?}", sets),
+        }
+    }
+
+    #[tokio::test]
+    async fn leaves_configs_without_references() {
+        let input = "data_dir = \"/var/lib/vector\"\n".to_owned();
+        let inputs = interpolate(vec![(input.clone(), Some(Format::TOML))])
+            .await
+            .unwrap();
+        assert_eq!(inputs[0].0, input);
+    }
+
+    #[tokio::test]
+    async fn fails_on_missing_secrets() {
+        let file = secrets_file(r#"{"api_key": "abc123"}"#);
+        let input = format!(
+            r#"
+            api_key = "SECRET[local.token]"
+            password = "SECRET[remote.password]"
+
+            [secret.local]
+            type = "file"
+            path = '{}'
+            "#,
+            file.path().display()
+        );
+
+        let errors = interpolate(vec![(input.clone(), Some(Format::TOML))])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            errors,
+            vec!["Unknown secret backend in config. name = \"remote\"".to_owned()]
+        );
+
+        let input = input.replace("SECRET[remote.password]", "");
+        let errors = interpolate(vec![(input, Some(Format::TOML))])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            errors,
+            vec!["Secret not found in backend \"local\". key = \"token\"".to_owned()]
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{
+    http::HttpClient,
+    tls::{TlsOptions, TlsSettings},
+};
+use http::{Request, StatusCode};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::collections::{BTreeSet, HashMap};
+
+/// Reads secrets from a secret of the key/value version 2 secrets engine of
+/// [HashiCorp Vault](https://www.vaultproject.io/docs/secrets/kv/kv-v2).
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VaultBackendConfig {
+    address: String,
+    token: String,
+    #[serde(default = "default_mount")]
+    mount: String,
+    path: String,
+    tls: Option<TlsOptions>,
+}
+
+fn default_mount() -> String {
+    "secret".to_owned()
+}
+
+#[derive(Debug, Snafu)]
+enum VaultError {
+    #[snafu(display("Vault responded with {}", status))]
+    Status { status: StatusCode },
+}
+
+#[derive(Deserialize)]
+struct SecretResponse {
+    data: SecretData,
+}
+
+#[derive(Deserialize)]
+struct SecretData {
+    data: HashMap<String, serde_json::Value>,
+}
+
+impl VaultBackendConfig {
+    fn uri(&self) -> String {
+        format!(
+            "{}/v1/{}/data/{}",
+            self.address.trim_end_matches('/'),
+            self.mount.trim_matches('/'),
+            self.path.trim_start_matches('/')
+        )
+    }
+
+    pub(super) async fn retrieve(
+        &self,
+        keys: &BTreeSet<String>,
+    ) -> crate::Result<HashMap<String, String>> {
+        let client = HttpClient::new(TlsSettings::from_options(&self.tls)?)?;
+        let request = Request::get(self.uri())
+            .header("X-Vault-Token", &self.token)
+            .body(Body::empty())?;
+        let response = client.send(request).await?;
+        let status = response.status();
+        if status != StatusCode::OK {
+            return Err(VaultError::Status { status }.into());
+        }
+
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let response: SecretResponse = serde_json::from_slice(&body)?;
+        Ok(response
+            .data
+            .data
+            .into_iter()
+            .filter(|(key, _)| keys.contains(key))
+            .map(|(key, value)| match value {
+                serde_json::Value::String(value) => (key, value),
+                value => (key, value.to_string()),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::next_addr;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use std::convert::Infallible;
+
+    /// Holds the secret `vector/sinks` in the `secret` mount.
+    async fn vault() -> String {
+        let addr = next_addr();
+        let make_svc = make_service_fn(|_| async {
+            Ok::<_, Infallible>(service_fn(|req: Request<Body>| async move {
+                let authorized = req
+                    .headers()
+                    .get("X-Vault-Token")
+                    .map_or(false, |token| token == "s.token");
+                let response = if !authorized {
+                    let mut response = Response::new(Body::empty());
+                    *response.status_mut() = StatusCode::FORBIDDEN;
+                    response
+                } else if req.uri().path() == "/v1/secret/data/vector/sinks" {
+                    let body = serde_json::json!({
+                        "data": {
+                            "data": {"api_key": "abc123", "port": 5432},
+                            "metadata": {"version": 1},
+                        },
+                    });
+                    Response::new(Body::from(body.to_string()))
+                } else {
+                    let mut response = Response::new(Body::empty());
+                    *response.status_mut() = StatusCode::NOT_FOUND;
+                    response
+                };
+                Ok::<_, Infallible>(response)
+            }))
+        });
+        tokio::spawn(Server::bind(&addr).serve(make_svc));
+        format!("http://{}/", addr)
+    }
+
+    fn config(address: String, token: &str) -> VaultBackendConfig {
+        VaultBackendConfig {
+            address,
+            token: token.to_owned(),
+            mount: default_mount(),
+            path: "vector/sinks".to_owned(),
+            tls: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn retrieves_secrets() {
+        let address = vault().await;
+        let keys = vec!["api_key".to_owned(), "port".to_owned()]
+            .into_iter()
+            .collect();
+
+        let secrets = config(address.clone(), "s.token")
+            .retrieve(&keys)
+            .await
+            .unwrap();
+        assert_eq!(secrets.len(), 2);
+        assert_eq!(secrets["api_key"], "abc123");
+        assert_eq!(secrets["port"], "5432");
+
+        let error = config(address, "s.other")
+            .retrieve(&keys)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Vault responded with 403 Forbidden");
+    }
+}
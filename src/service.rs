@@ -215,7 +215,12 @@ fn create_service_arguments(
     config_paths: &[(PathBuf, config::FormatHint)],
 ) -> Option<Vec<OsString>> {
     let config_paths = config::process_paths(&config_paths)?;
-    match config::load_from_paths(&config_paths, false) {
+    // Service commands run within the runtime, which has to keep running
+    // while secrets are retrieved.
+    let config = tokio::task::block_in_place(|| {
+        futures::executor::block_on(config::load_from_paths(&config_paths, false, false))
+    });
+    match config {
         Ok(_) => Some(
             config_paths
                 .iter()
@@ -41,6 +41,13 @@ use tower::{
 const EVENT_SIZE_OVERHEAD: usize = 50;
 const MAX_EVENT_SIZE: usize = 256 * 1024;
 const MAX_MESSAGE_SIZE: usize = MAX_EVENT_SIZE - EVENT_SIZE_OVERHEAD;
+// The most a `PutLogEvents` request can hold, counting 26 bytes per event.
+const MAX_BATCH_BYTES: u64 = 1_048_576;
+const MAX_BATCH_EVENTS: usize = 10_000;
+// The retention periods a log group can have.
+const RETENTION_DAYS: [u32; 17] = [
+    1, 3, 5, 7, 14, 30, 60, 90, 120, 150, 180, 365, 400, 545, 731, 1827, 3653,
+];
 
 #[derive(Debug, Snafu)]
 pub(self) enum CloudwatchLogsError {
@@ -54,6 +61,12 @@ pub(self) enum CloudwatchLogsError {
     },
     #[snafu(display("Encoded event is too long, length={}", length))]
     EventTooLong { length: usize },
+    #[snafu(display(
+        "`group_retention_days` must be one of {:?}, got {}",
+        RETENTION_DAYS,
+        days
+    ))]
+    InvalidRetentionDays { days: u32 },
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -66,6 +79,8 @@ pub struct CloudwatchLogsSinkConfig {
     pub encoding: EncodingConfig<Encoding>,
     pub create_missing_group: Option<bool>,
     pub create_missing_stream: Option<bool>,
+    pub group_retention_days: Option<u32>,
+    pub group_tags: Option<HashMap<String, String>>,
     #[serde(default)]
     pub compression: Compression,
     #[serde(default)]
@@ -93,6 +108,8 @@ fn default_config(e: Encoding) -> CloudwatchLogsSinkConfig {
         encoding: e.into(),
         create_missing_group: Default::default(),
         create_missing_stream: Default::default(),
+        group_retention_days: Default::default(),
+        group_tags: Default::default(),
         compression: Default::default(),
         batch: Default::default(),
         request: Default::default(),
@@ -112,6 +129,8 @@ pub struct CloudwatchLogsSvc {
     group_name: String,
     create_missing_group: bool,
     create_missing_stream: bool,
+    retention_days: Option<i64>,
+    tags: Option<HashMap<String, String>>,
     token: Option<String>,
     token_rx: Option<oneshot::Receiver<Option<String>>>,
 }
@@ -160,7 +179,8 @@ impl CloudwatchLogsSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let encoding = self.compression.try_into()?;
+        let client = rusoto_core::Client::new_with_encoding(creds, client, encoding);
         Ok(CloudWatchLogsClient::new_with_client(client, region))
     }
 }
@@ -173,12 +193,19 @@ impl SinkConfig for CloudwatchLogsSinkConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let batch = BatchSettings::default()
-            .bytes(1_048_576)
-            .events(10_000)
+            .bytes(MAX_BATCH_BYTES)
+            .events(MAX_BATCH_EVENTS)
             .timeout(1)
-            .parse_config(self.batch)?;
+            .parse_config(self.batch)?
+            .limit(MAX_BATCH_BYTES, MAX_BATCH_EVENTS)?;
         let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
 
+        if let Some(days) = self.group_retention_days {
+            if !RETENTION_DAYS.contains(&days) {
+                return Err(CloudwatchLogsError::InvalidRetentionDays { days }.into());
+            }
+        }
+
         let log_group = self.group_name.clone();
         let log_stream = self.stream_name.clone();
 
@@ -289,6 +316,8 @@ impl CloudwatchLogsSvc {
             group_name,
             create_missing_group,
             create_missing_stream,
+            retention_days: config.group_retention_days.map(Into::into),
+            tags: config.group_tags.clone(),
             token: None,
             token_rx: None,
         }
@@ -385,6 +414,8 @@ impl Service<Vec<InputLogEvent>> for CloudwatchLogsSvc {
                 self.group_name.clone(),
                 self.create_missing_group,
                 self.create_missing_stream,
+                self.retention_days,
+                self.tags.clone(),
                 event_batches,
                 self.token.take(),
                 tx,
@@ -542,6 +573,12 @@ impl RetryLogic for CloudwatchRetryLogic {
                     true
                 }
 
+                // The retry gets the token from the stream again.
+                RusotoError::Service(PutLogEventsError::InvalidSequenceToken(error)) => {
+                    error!(message = "Put logs invalid sequence token.", %error);
+                    true
+                }
+
                 RusotoError::HttpDispatch(error) => {
                     error!(message = "Put logs HTTP dispatch.", %error);
                     true
@@ -845,6 +882,41 @@ mod tests {
 
         assert_eq!(batches.len(), 5);
     }
+
+    async fn build_error(config: CloudwatchLogsSinkConfig) -> String {
+        match config.build(crate::config::SinkContext::new_test()).await {
+            Ok(_) => panic!("Config should be invalid"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn cloudwatch_invalid_retention_days() {
+        let config = CloudwatchLogsSinkConfig {
+            group_retention_days: Some(10),
+            ..default_config(Encoding::Text)
+        };
+
+        assert!(build_error(config)
+            .await
+            .starts_with("`group_retention_days` must be one of"));
+    }
+
+    #[tokio::test]
+    async fn cloudwatch_batch_limits() {
+        let config = CloudwatchLogsSinkConfig {
+            batch: BatchConfig {
+                max_events: Some(MAX_BATCH_EVENTS + 1),
+                ..Default::default()
+            },
+            ..default_config(Encoding::Text)
+        };
+
+        assert_eq!(
+            build_error(config).await,
+            "`max_events` can't be larger than 10000 for this sink"
+        );
+    }
 }
 
 #[cfg(feature = "aws-cloudwatch-logs-integration-tests")]
@@ -878,6 +950,8 @@ mod integration_tests {
             encoding: Encoding::Text.into(),
             create_missing_group: None,
             create_missing_stream: None,
+            group_retention_days: None,
+            group_tags: None,
             compression: Default::default(),
             batch: Default::default(),
             request: Default::default(),
@@ -922,6 +996,8 @@ mod integration_tests {
             encoding: Encoding::Text.into(),
             create_missing_group: None,
             create_missing_stream: None,
+            group_retention_days: None,
+            group_tags: None,
             compression: Default::default(),
             batch: Default::default(),
             request: Default::default(),
@@ -985,6 +1061,8 @@ mod integration_tests {
             encoding: Encoding::Text.into(),
             create_missing_group: None,
             create_missing_stream: None,
+            group_retention_days: None,
+            group_tags: None,
             compression: Default::default(),
             batch: Default::default(),
             request: Default::default(),
@@ -1054,6 +1132,8 @@ mod integration_tests {
             encoding: Encoding::Text.into(),
             create_missing_group: None,
             create_missing_stream: None,
+            group_retention_days: None,
+            group_tags: None,
             compression: Default::default(),
             batch: Default::default(),
             request: Default::default(),
@@ -1100,6 +1180,8 @@ mod integration_tests {
             encoding: Encoding::Text.into(),
             create_missing_group: None,
             create_missing_stream: None,
+            group_retention_days: None,
+            group_tags: None,
             compression: Default::default(),
             batch: BatchConfig {
                 max_events: Some(2),
@@ -1148,6 +1230,8 @@ mod integration_tests {
             encoding: Encoding::Text.into(),
             create_missing_group: None,
             create_missing_stream: None,
+            group_retention_days: None,
+            group_tags: None,
             compression: Default::default(),
             batch: Default::default(),
             request: Default::default(),
@@ -1229,6 +1313,8 @@ mod integration_tests {
             encoding: Encoding::Text.into(),
             create_missing_group: None,
             create_missing_stream: None,
+            group_retention_days: None,
+            group_tags: None,
             compression: Default::default(),
             batch: Default::default(),
             request: Default::default(),
@@ -5,21 +5,28 @@ use rusoto_logs::{
     CloudWatchLogs, CloudWatchLogsClient, CreateLogGroupError, CreateLogGroupRequest,
     CreateLogStreamError, CreateLogStreamRequest, DescribeLogStreamsError,
     DescribeLogStreamsRequest, DescribeLogStreamsResponse, InputLogEvent, PutLogEventsError,
-    PutLogEventsRequest, PutLogEventsResponse,
+    PutLogEventsRequest, PutLogEventsResponse, PutRetentionPolicyError, PutRetentionPolicyRequest,
 };
 use std::{
+    collections::HashMap,
     future::Future,
     pin::Pin,
     task::{Context, Poll},
 };
 use tokio::sync::oneshot;
 
+/// How many times a batch is sent again with the sequence token the stream
+/// expects, when another writer took the one it was sent with.
+const MAX_SEQUENCE_TOKEN_RETRIES: usize = 5;
+
 pub struct CloudwatchFuture {
     client: Client,
     state: State,
     create_missing_group: bool,
     create_missing_stream: bool,
+    /// The batches left to send. The last one is being sent.
     events: Vec<Vec<InputLogEvent>>,
+    sequence_token_retries: usize,
     token_tx: Option<oneshot::Sender<Option<String>>>,
 }
 
@@ -27,12 +34,15 @@ struct Client {
     client: CloudWatchLogsClient,
     stream_name: String,
     group_name: String,
+    retention_days: Option<i64>,
+    tags: Option<HashMap<String, String>>,
 }
 
 type ClientResult<T, E> = BoxFuture<'static, RusotoResult<T, E>>;
 
 enum State {
     CreateGroup(ClientResult<(), CreateLogGroupError>),
+    PutRetentionPolicy(ClientResult<(), PutRetentionPolicyError>),
     CreateStream(ClientResult<(), CreateLogStreamError>),
     DescribeStream(ClientResult<DescribeLogStreamsResponse, DescribeLogStreamsError>),
     Put(ClientResult<PutLogEventsResponse, PutLogEventsError>),
@@ -46,7 +56,9 @@ impl CloudwatchFuture {
         group_name: String,
         create_missing_group: bool,
         create_missing_stream: bool,
-        mut events: Vec<Vec<InputLogEvent>>,
+        retention_days: Option<i64>,
+        tags: Option<HashMap<String, String>>,
+        events: Vec<Vec<InputLogEvent>>,
         token: Option<String>,
         token_tx: oneshot::Sender<Option<String>>,
     ) -> Self {
@@ -54,10 +66,15 @@ impl CloudwatchFuture {
             client,
             stream_name,
             group_name,
+            retention_days,
+            tags,
         };
 
         let state = if let Some(token) = token {
-            State::Put(client.put_logs(Some(token), events.pop().expect("No Events to send")))
+            State::Put(client.put_logs(
+                Some(token),
+                events.last().expect("No Events to send").clone(),
+            ))
         } else {
             State::DescribeStream(client.describe_stream())
         };
@@ -66,6 +83,7 @@ impl CloudwatchFuture {
             client,
             events,
             state,
+            sequence_token_retries: 0,
             token_tx: Some(token_tx),
             create_missing_group,
             create_missing_stream,
@@ -103,8 +121,9 @@ impl Future for CloudwatchFuture {
 
                         let events = self
                             .events
-                            .pop()
-                            .expect("Token got called multiple times, self is a bug!");
+                            .last()
+                            .expect("Token got called multiple times, self is a bug!")
+                            .clone();
 
                         let token = stream.upload_sequence_token;
 
@@ -119,22 +138,47 @@ impl Future for CloudwatchFuture {
                 }
 
                 State::CreateGroup(fut) => {
-                    match ready!(fut.poll_unpin(cx)) {
-                        Ok(_) => {}
+                    let created = match ready!(fut.poll_unpin(cx)) {
+                        Ok(_) => true,
                         Err(RusotoError::Service(CreateLogGroupError::ResourceAlreadyExists(
                             _,
-                        ))) => {}
+                        ))) => false,
                         Err(err) => return Poll::Ready(Err(CloudwatchError::CreateGroup(err))),
                     };
 
                     info!(message = "Group created.", name = %self.client.group_name);
 
+                    // Only the group's creator sets its retention, so that
+                    // it's set once.
+                    if created && self.client.retention_days.is_some() {
+                        self.state = State::PutRetentionPolicy(self.client.put_retention_policy());
+                        continue;
+                    }
+
                     // self does not abide by `create_missing_stream` since a group
                     // never has any streams and thus we need to create one if a group
                     // is created no matter what.
                     self.state = State::CreateStream(self.client.create_log_stream());
                 }
 
+                State::PutRetentionPolicy(fut) => {
+                    // The group exists either way, so the events are still sent.
+                    match ready!(fut.poll_unpin(cx)) {
+                        Ok(_) => info!(
+                            message = "Retention policy set.",
+                            name = %self.client.group_name,
+                            days = ?self.client.retention_days,
+                        ),
+                        Err(error) => error!(
+                            message = "Unable to set the retention policy of the group.",
+                            name = %self.client.group_name,
+                            %error,
+                        ),
+                    };
+
+                    self.state = State::CreateStream(self.client.create_log_stream());
+                }
+
                 State::CreateStream(fut) => {
                     match ready!(fut.poll_unpin(cx)) {
                         Ok(_) => {}
@@ -152,11 +196,36 @@ impl Future for CloudwatchFuture {
                 State::Put(fut) => {
                     let next_token = match ready!(fut.poll_unpin(cx)) {
                         Ok(resp) => resp.next_sequence_token,
+                        // The batch was sent before, by a request that timed out.
+                        Err(RusotoError::Service(PutLogEventsError::DataAlreadyAccepted(
+                            message,
+                        ))) => {
+                            debug!(message = "Logs were already accepted.");
+                            expected_sequence_token(&message)
+                        }
+                        Err(RusotoError::Service(PutLogEventsError::InvalidSequenceToken(
+                            message,
+                        ))) if self.sequence_token_retries < MAX_SEQUENCE_TOKEN_RETRIES => {
+                            self.sequence_token_retries += 1;
+                            let token = expected_sequence_token(&message);
+                            warn!(
+                                message = "Sequence token is invalid; putting logs again.",
+                                token = ?token,
+                            );
+
+                            let events = self.events.last().expect("No Events to send").clone();
+                            self.state = State::Put(self.client.put_logs(token, events));
+                            continue;
+                        }
                         Err(err) => return Poll::Ready(Err(CloudwatchError::Put(err))),
                     };
 
-                    if let Some(events) = self.events.pop() {
+                    self.events.pop();
+                    self.sequence_token_retries = 0;
+
+                    if let Some(events) = self.events.last() {
                         debug!(message = "Putting logs.", next_token = ?next_token);
+                        let events = events.clone();
                         self.state = State::Put(self.client.put_logs(next_token, events));
                     } else {
                         info!(message = "Putting logs was successful.", next_token = ?next_token);
@@ -209,6 +278,7 @@ impl Client {
     pub fn create_log_group(&self) -> ClientResult<(), CreateLogGroupError> {
         let request = CreateLogGroupRequest {
             log_group_name: self.group_name.clone(),
+            tags: self.tags.clone(),
             ..Default::default()
         };
 
@@ -216,6 +286,16 @@ impl Client {
         Box::pin(async move { client.create_log_group(request).await })
     }
 
+    pub fn put_retention_policy(&self) -> ClientResult<(), PutRetentionPolicyError> {
+        let request = PutRetentionPolicyRequest {
+            log_group_name: self.group_name.clone(),
+            retention_in_days: self.retention_days.expect("No retention to set"),
+        };
+
+        let client = self.client.clone();
+        Box::pin(async move { client.put_retention_policy(request).await })
+    }
+
     pub fn create_log_stream(&self) -> ClientResult<(), CreateLogStreamError> {
         let request = CreateLogStreamRequest {
             log_group_name: self.group_name.clone(),
@@ -226,3 +306,43 @@ impl Client {
         Box::pin(async move { client.create_log_stream(request).await })
     }
 }
+
+/// Finds the sequence token at the end of the messages of
+/// `InvalidSequenceTokenException` and `DataAlreadyAcceptedException`, like
+/// "The next expected sequenceToken is: 4959...". New streams expect no
+/// token, which the message gives as `null`.
+fn expected_sequence_token(message: &str) -> Option<String> {
+    let token = message.rsplit(':').next()?.trim();
+    if token.is_empty() || token == "null" {
+        None
+    } else {
+        Some(token.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_expected_sequence_token() {
+        assert_eq!(
+            expected_sequence_token(
+                "The given sequenceToken is invalid. The next expected sequenceToken is: 49590302"
+            ),
+            Some("49590302".into())
+        );
+        assert_eq!(
+            expected_sequence_token(
+                "The given batch of log events has already been accepted. The next batch can be sent with sequenceToken: 49590303"
+            ),
+            Some("49590303".into())
+        );
+        assert_eq!(
+            expected_sequence_token(
+                "The given sequenceToken is invalid. The next expected sequenceToken is: null"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,400 @@
+//! Sends metrics through CloudWatch Logs in the [Embedded Metric Format][emf],
+//! which CloudWatch extracts metrics from without any `PutMetricData` calls.
+//!
+//! [emf]: https://docs.aws.amazon.com/AmazonCloudWatch/latest/monitoring/CloudWatch_Embedded_Metric_Format_Specification.html
+
+use super::{take_namespace, CloudWatchMetricsSinkConfig};
+use crate::{
+    config::{log_schema, SinkConfig, SinkContext},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, LogEvent, Value,
+    },
+    sinks::{
+        aws_cloudwatch_logs::{CloudwatchLogsSinkConfig, Encoding},
+        util::{Concurrency, TowerRequestConfig},
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+};
+use chrono::Utc;
+use futures::{stream, SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::collections::{BTreeMap, HashMap};
+
+// The most dimensions a dimension set can have.
+const MAX_DIMENSIONS: usize = 30;
+// The most values a metric can have in a single log event.
+const MAX_VALUES: usize = 100;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display(
+        "Dimension sets can't have more than {} dimensions, got {}",
+        MAX_DIMENSIONS,
+        count
+    ))]
+    TooManyDimensions { count: usize },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EmfConfig {
+    pub group_name: Template,
+    pub stream_name: Template,
+    #[serde(default)]
+    pub dimensions: Vec<Vec<String>>,
+    pub create_missing_group: Option<bool>,
+    pub create_missing_stream: Option<bool>,
+    pub group_retention_days: Option<u32>,
+    pub group_tags: Option<HashMap<String, String>>,
+}
+
+pub(super) async fn build(
+    config: &CloudWatchMetricsSinkConfig,
+    emf: &EmfConfig,
+    cx: SinkContext,
+) -> crate::Result<(VectorSink, Healthcheck)> {
+    if let Some(set) = emf.dimensions.iter().find(|set| set.len() > MAX_DIMENSIONS) {
+        return Err(BuildError::TooManyDimensions { count: set.len() }.into());
+    }
+
+    let sink = CloudwatchLogsSinkConfig {
+        group_name: emf.group_name.clone(),
+        stream_name: emf.stream_name.clone(),
+        region: config.region.clone(),
+        encoding: Encoding::Json.into(),
+        create_missing_group: emf.create_missing_group,
+        create_missing_stream: emf.create_missing_stream,
+        group_retention_days: emf.group_retention_days,
+        group_tags: emf.group_tags.clone(),
+        compression: config.compression,
+        batch: config.batch,
+        request: logs_request(config.request),
+        assume_role: config.assume_role.clone(),
+    };
+
+    let (sink, healthcheck) = sink.build(cx).await?;
+
+    let encoder = EmfEncoder {
+        default_namespace: config.default_namespace.clone(),
+        dimensions: emf.dimensions.clone(),
+    };
+    let sink = Box::new(sink.into_sink().with_flat_map(move |event: Event| {
+        stream::iter(encoder.encode(event.into_metric())).map(Ok)
+    }));
+
+    Ok((VectorSink::Sink(sink), healthcheck))
+}
+
+// The CloudWatch Logs sink only supports a fixed concurrency.
+fn logs_request(request: TowerRequestConfig) -> TowerRequestConfig<Option<usize>> {
+    TowerRequestConfig {
+        concurrency: match request.concurrency {
+            Concurrency::Fixed(concurrency) => Some(concurrency),
+            Concurrency::None | Concurrency::Adaptive => None,
+        },
+        timeout_secs: request.timeout_secs,
+        rate_limit_duration_secs: request.rate_limit_duration_secs,
+        rate_limit_num: request.rate_limit_num,
+        retry_attempts: request.retry_attempts,
+        retry_max_duration_secs: request.retry_max_duration_secs,
+        retry_initial_backoff_secs: request.retry_initial_backoff_secs,
+        adaptive_concurrency: request.adaptive_concurrency,
+        circuit_breaker: request.circuit_breaker,
+    }
+}
+
+struct EmfEncoder {
+    default_namespace: Template,
+    dimensions: Vec<Vec<String>>,
+}
+
+impl EmfEncoder {
+    /// Encodes the metric as log events, more than one when it has more
+    /// values than fit in a single one.
+    fn encode(&self, mut metric: Metric) -> Vec<Event> {
+        let values = match (metric.kind, &metric.value) {
+            (MetricKind::Incremental, MetricValue::Counter { value }) => vec![*value],
+            (MetricKind::Incremental, MetricValue::Set { values }) => vec![values.len() as f64],
+            (
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    values,
+                    sample_rates,
+                    ..
+                },
+            ) => values
+                .iter()
+                .zip(sample_rates.iter())
+                .flat_map(|(value, rate)| std::iter::repeat(*value).take(*rate as usize))
+                .collect(),
+            (MetricKind::Absolute, MetricValue::Gauge { value }) => vec![*value],
+            _ => return Vec::new(),
+        };
+        if values.is_empty() {
+            return Vec::new();
+        }
+
+        let namespace = match take_namespace(&mut metric, &self.default_namespace) {
+            Some(namespace) => namespace,
+            None => return Vec::new(),
+        };
+        let tags = metric.tags.unwrap_or_default();
+        let timestamp = metric.timestamp.unwrap_or_else(Utc::now);
+        let metadata = Value::from(btreemap(vec![
+            ("Timestamp", Value::Integer(timestamp.timestamp_millis())),
+            (
+                "CloudWatchMetrics",
+                Value::Array(vec![Value::from(btreemap(vec![
+                    ("Namespace", Value::from(namespace)),
+                    (
+                        "Dimensions",
+                        self.dimension_sets(&tags)
+                            .into_iter()
+                            .map(|set| set.into_iter().map(Value::from).collect::<Value>())
+                            .collect(),
+                    ),
+                    (
+                        "Metrics",
+                        Value::Array(vec![Value::from(btreemap(vec![(
+                            "Name",
+                            Value::from(metric.name.clone()),
+                        )]))]),
+                    ),
+                ]))]),
+            ),
+        ]));
+
+        values
+            .chunks(MAX_VALUES)
+            .map(|chunk| {
+                let mut log = LogEvent::default();
+                for (key, value) in &tags {
+                    log.insert_flat(key.clone(), value.clone());
+                }
+                let value = match chunk {
+                    [value] => Value::from(*value),
+                    values => values.iter().map(|value| Value::from(*value)).collect(),
+                };
+                log.insert_flat(metric.name.clone(), value);
+                log.insert_flat("_aws", metadata.clone());
+                log.insert(log_schema().timestamp_key(), timestamp);
+                Event::Log(log)
+            })
+            .collect()
+    }
+
+    /// The configured dimension sets the metric has every tag of, or a
+    /// single set of all of its tags when none are configured. Metrics
+    /// without any matching set are sent without dimensions.
+    fn dimension_sets(&self, tags: &BTreeMap<String, String>) -> Vec<Vec<String>> {
+        if self.dimensions.is_empty() {
+            return vec![tags.keys().take(MAX_DIMENSIONS).cloned().collect()];
+        }
+
+        let sets: Vec<_> = self
+            .dimensions
+            .iter()
+            .filter(|set| set.iter().all(|key| tags.contains_key(key)))
+            .cloned()
+            .collect();
+        if sets.is_empty() {
+            vec![Vec::new()]
+        } else {
+            sets
+        }
+    }
+}
+
+fn btreemap(entries: Vec<(&str, Value)>) -> BTreeMap<String, Value> {
+    entries
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::StatisticKind;
+    use chrono::{offset::TimeZone, DateTime};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::convert::TryFrom;
+
+    fn encoder(dimensions: Vec<Vec<&str>>) -> EmfEncoder {
+        EmfEncoder {
+            default_namespace: Template::try_from("{{ service }}").unwrap(),
+            dimensions: dimensions
+                .into_iter()
+                .map(|set| set.into_iter().map(Into::into).collect())
+                .collect(),
+        }
+    }
+
+    fn timestamp() -> DateTime<Utc> {
+        Utc.ymd(2020, 12, 1).and_hms_milli(10, 20, 30, 400)
+    }
+
+    fn metric(kind: MetricKind, value: MetricValue) -> Metric {
+        Metric {
+            name: "requests".into(),
+            namespace: None,
+            timestamp: Some(timestamp()),
+            tags: Some(
+                vec![
+                    ("service".to_owned(), "api".to_owned()),
+                    ("region".to_owned(), "us-east-1".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            kind,
+            value,
+        }
+    }
+
+    fn encode(encoder: &EmfEncoder, metric: Metric) -> Vec<serde_json::Value> {
+        encoder
+            .encode(metric)
+            .into_iter()
+            .map(|event| {
+                let mut log = event.into_log();
+                assert_eq!(
+                    log.remove(log_schema().timestamp_key()),
+                    Some(Value::from(timestamp()))
+                );
+                serde_json::to_value(&log).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn encodes_counters() {
+        let metric = metric(MetricKind::Incremental, MetricValue::Counter { value: 2.0 });
+
+        assert_eq!(
+            encode(&encoder(vec![]), metric),
+            vec![json!({
+                "_aws": {
+                    "Timestamp": 1606818030400i64,
+                    "CloudWatchMetrics": [{
+                        "Namespace": "api",
+                        "Dimensions": [["region", "service"]],
+                        "Metrics": [{"Name": "requests"}],
+                    }],
+                },
+                "region": "us-east-1",
+                "service": "api",
+                "requests": 2.0,
+            })]
+        );
+    }
+
+    #[test]
+    fn keeps_dimension_sets_of_present_tags() {
+        let encoder = encoder(vec![vec!["service"], vec!["service", "host"], vec![]]);
+        let metric = metric(MetricKind::Absolute, MetricValue::Gauge { value: 1.5 });
+
+        let events = encode(&encoder, metric);
+        assert_eq!(
+            events[0]["_aws"]["CloudWatchMetrics"][0]["Dimensions"],
+            json!([["service"], []])
+        );
+        assert_eq!(events[0]["requests"], json!(1.5));
+    }
+
+    #[test]
+    fn sends_metrics_without_matching_dimension_sets_without_dimensions() {
+        let encoder = encoder(vec![vec!["host"]]);
+        let metric = metric(MetricKind::Incremental, MetricValue::Counter { value: 1.0 });
+
+        let events = encode(&encoder, metric);
+        assert_eq!(
+            events[0]["_aws"]["CloudWatchMetrics"][0]["Dimensions"],
+            json!([[]])
+        );
+    }
+
+    #[test]
+    fn splits_distributions_into_chunks() {
+        let metric = metric(
+            MetricKind::Incremental,
+            MetricValue::Distribution {
+                values: vec![1.0, 2.0],
+                sample_rates: vec![99, 3],
+                statistic: StatisticKind::Histogram,
+            },
+        );
+
+        let events = encode(&encoder(vec![]), metric);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["requests"].as_array().unwrap().len(), 100);
+        assert_eq!(events[1]["requests"], json!([2.0, 2.0]));
+    }
+
+    #[test]
+    fn encodes_sets_as_their_size() {
+        let metric = metric(
+            MetricKind::Incremental,
+            MetricValue::Set {
+                values: vec!["alice".into(), "bob".into()].into_iter().collect(),
+            },
+        );
+
+        let events = encode(&encoder(vec![]), metric);
+        assert_eq!(events[0]["requests"], json!(2.0));
+    }
+
+    #[test]
+    fn drops_unsupported_metrics() {
+        let metric = metric(MetricKind::Absolute, MetricValue::Counter { value: 1.0 });
+
+        assert!(encoder(vec![]).encode(metric).is_empty());
+    }
+
+    #[test]
+    fn prefers_the_namespace_of_the_metric() {
+        let mut metric = metric(MetricKind::Incremental, MetricValue::Counter { value: 1.0 });
+        metric.namespace = Some("custom".into());
+        metric.tags = None;
+
+        let events = encode(&encoder(vec![]), metric);
+        assert_eq!(
+            events[0]["_aws"]["CloudWatchMetrics"][0]["Namespace"],
+            json!("custom")
+        );
+        assert_eq!(
+            events[0]["_aws"]["CloudWatchMetrics"][0]["Dimensions"],
+            json!([[]])
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_large_dimension_sets() {
+        let config = CloudWatchMetricsSinkConfig {
+            emf: Some(EmfConfig {
+                group_name: Template::try_from("metrics").unwrap(),
+                stream_name: Template::try_from("vector").unwrap(),
+                dimensions: vec![(0..31).map(|i| i.to_string()).collect()],
+                create_missing_group: None,
+                create_missing_stream: None,
+                group_retention_days: None,
+                group_tags: None,
+            }),
+            ..Default::default()
+        };
+
+        let error = config
+            .build(SinkContext::new_test())
+            .await
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Dimension sets can't have more than 30 dimensions, got 31"
+        );
+    }
+}
@@ -1,14 +1,18 @@
+mod emf;
+
 use crate::{
-    config::{DataType, SinkConfig, SinkContext, SinkDescription},
+    config::{log_schema, DataType, SinkConfig, SinkContext, SinkDescription},
     event::{
         metric::{Metric, MetricKind, MetricValue},
-        Event,
+        Event, LogEvent,
     },
+    internal_events::AwsCloudwatchMetricsNamespaceMissingKeys,
     rusoto::{self, RegionOrEndpoint},
     sinks::util::{
         retries::RetryLogic, BatchConfig, BatchSettings, Compression, MetricBuffer,
         PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig,
     },
+    template::Template,
 };
 use chrono::{DateTime, SecondsFormat, Utc};
 use futures::{future, future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
@@ -25,6 +29,8 @@ use std::{
 };
 use tower::Service;
 
+pub use emf::EmfConfig;
+
 #[derive(Clone)]
 pub struct CloudWatchMetricsSvc {
     client: CloudWatchClient,
@@ -34,7 +40,7 @@ pub struct CloudWatchMetricsSvc {
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct CloudWatchMetricsSinkConfig {
-    pub default_namespace: String,
+    pub default_namespace: Template,
     #[serde(flatten)]
     pub region: RegionOrEndpoint,
     #[serde(default)]
@@ -44,6 +50,7 @@ pub struct CloudWatchMetricsSinkConfig {
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub assume_role: Option<String>,
+    pub emf: Option<EmfConfig>,
 }
 
 lazy_static! {
@@ -67,6 +74,10 @@ impl SinkConfig for CloudWatchMetricsSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        if let Some(emf) = &self.emf {
+            return emf::build(self, emf, cx).await;
+        }
+
         let client = self.create_client()?;
         let healthcheck = self.clone().healthcheck(client.clone()).boxed();
         let sink = CloudWatchMetricsSvc::new(self.clone(), client, cx)?;
@@ -84,13 +95,18 @@ impl SinkConfig for CloudWatchMetricsSinkConfig {
 
 impl CloudWatchMetricsSinkConfig {
     async fn healthcheck(self, client: CloudWatchClient) -> crate::Result<()> {
+        if self.default_namespace.is_dynamic() {
+            info!("Default namespace is dynamic; skipping healthcheck.");
+            return Ok(());
+        }
+
         let datum = MetricDatum {
             metric_name: "healthcheck".into(),
             value: Some(1.0),
             ..Default::default()
         };
         let request = PutMetricDataInput {
-            namespace: self.default_namespace.clone(),
+            namespace: self.default_namespace.get_ref().to_owned(),
             metric_data: vec![datum],
         };
 
@@ -115,7 +131,8 @@ impl CloudWatchMetricsSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let encoding = self.compression.try_into()?;
+        let client = rusoto_core::Client::new_with_encoding(creds, client, encoding);
         Ok(CloudWatchClient::new_with_client(client, region))
     }
 }
@@ -142,12 +159,9 @@ impl CloudWatchMetricsSvc {
         let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
             .sink_map_err(|error| error!(message = "Fatal CloudwatchMetrics sink error.", %error))
             .with_flat_map(move |mut event: Event| {
-                let namespace = event
-                    .as_mut_metric()
-                    .namespace
-                    .take()
-                    .unwrap_or_else(|| default_namespace.clone());
-                stream::iter(Some(PartitionInnerBuffer::new(event, namespace))).map(Ok)
+                let buffer = take_namespace(event.as_mut_metric(), &default_namespace)
+                    .map(|namespace| PartitionInnerBuffer::new(event, namespace));
+                stream::iter(buffer).map(Ok)
             });
 
         Ok(super::VectorSink::Sink(Box::new(sink)))
@@ -248,6 +262,26 @@ impl RetryLogic for CloudWatchMetricsRetryLogic {
     }
 }
 
+/// Takes the namespace of the metric, or renders `default_namespace` when it
+/// has none. Templates are rendered against the tags of metrics.
+fn take_namespace(metric: &mut Metric, default_namespace: &Template) -> Option<String> {
+    if let Some(namespace) = metric.namespace.take() {
+        return Some(namespace);
+    }
+
+    let mut tags = LogEvent::default();
+    for (key, value) in metric.tags.iter().flatten() {
+        tags.insert(key, value.clone());
+    }
+    if let Some(timestamp) = metric.timestamp {
+        tags.insert(log_schema().timestamp_key(), timestamp);
+    }
+    default_namespace
+        .render_string(&Event::Log(tags))
+        .map_err(|keys| emit!(AwsCloudwatchMetricsNamespaceMissingKeys { keys: &keys }))
+        .ok()
+}
+
 fn timestamp_to_string(timestamp: DateTime<Utc>) -> String {
     timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
 }
@@ -269,6 +303,7 @@ mod tests {
     use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
     use chrono::offset::TimeZone;
     use pretty_assertions::assert_eq;
+    use std::convert::TryFrom;
 
     #[test]
     fn generate_config() {
@@ -277,7 +312,7 @@ mod tests {
 
     fn config() -> CloudWatchMetricsSinkConfig {
         CloudWatchMetricsSinkConfig {
-            default_namespace: "vector".into(),
+            default_namespace: Template::try_from("vector").unwrap(),
             region: RegionOrEndpoint::with_endpoint("local".to_owned()),
             ..Default::default()
         }
@@ -419,6 +454,38 @@ mod tests {
             }]
         );
     }
+
+    #[test]
+    fn renders_default_namespace_from_tags() {
+        let default_namespace = Template::try_from("{{ service }}-metrics").unwrap();
+        let mut metric = Metric {
+            name: "requests".into(),
+            namespace: None,
+            timestamp: None,
+            tags: Some(
+                vec![("service".to_owned(), "api".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        };
+
+        assert_eq!(
+            take_namespace(&mut metric, &default_namespace),
+            Some("api-metrics".into())
+        );
+
+        metric.namespace = Some("custom".into());
+        assert_eq!(
+            take_namespace(&mut metric, &default_namespace),
+            Some("custom".into())
+        );
+        assert_eq!(metric.namespace, None);
+
+        metric.tags = None;
+        assert_eq!(take_namespace(&mut metric, &default_namespace), None);
+    }
 }
 
 #[cfg(feature = "aws-cloudwatch-metrics-integration-tests")]
@@ -428,10 +495,11 @@ mod integration_tests {
     use crate::{event::metric::StatisticKind, test_util::random_string, Event};
     use chrono::offset::TimeZone;
     use rand::seq::SliceRandom;
+    use std::convert::TryFrom;
 
     fn config() -> CloudWatchMetricsSinkConfig {
         CloudWatchMetricsSinkConfig {
-            default_namespace: "vector".into(),
+            default_namespace: Template::try_from("vector").unwrap(),
             region: RegionOrEndpoint::with_endpoint("http://localhost:4566".to_owned()),
             ..Default::default()
         }
@@ -1,6 +1,7 @@
 use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
+    internal_events::AwsKinesisFirehoseRecordTooLarge,
     rusoto::{self, RegionOrEndpoint},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
@@ -9,7 +10,7 @@ use crate::{
         BatchConfig, BatchSettings, Compression, EncodedLength, TowerRequestConfig, VecBuffer,
     },
 };
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use futures::{future::BoxFuture, stream, FutureExt, Sink, SinkExt, StreamExt};
 use lazy_static::lazy_static;
 use rusoto_core::RusotoError;
@@ -27,6 +28,13 @@ use std::{
 use tower::Service;
 use tracing_futures::Instrument;
 
+/// The most records a `PutRecordBatch` request can have.
+const MAX_RECORDS: usize = 500;
+/// The largest a `PutRecordBatch` request can be.
+const MAX_REQUEST_BYTES: u64 = 4 * 1024 * 1024;
+/// The largest a record can be.
+const MAX_RECORD_BYTES: usize = 1000 * 1024;
+
 #[derive(Clone)]
 pub struct KinesisFirehoseService {
     client: KinesisFirehoseClient,
@@ -42,6 +50,10 @@ pub struct KinesisFirehoseSinkConfig {
     pub encoding: EncodingConfig<Encoding>,
     #[serde(default)]
     pub compression: Compression,
+    /// Packs events into records as newline delimited lines, as Firehose
+    /// bills records in 5KB increments.
+    #[serde(default)]
+    pub aggregate: bool,
     #[serde(default)]
     pub batch: BatchConfig,
     #[serde(default)]
@@ -129,7 +141,8 @@ impl KinesisFirehoseSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let encoding = self.compression.try_into()?;
+        let client = rusoto_core::Client::new_with_encoding(creds, client, encoding);
         Ok(KinesisFirehoseClient::new_with_client(client, region))
     }
 }
@@ -140,11 +153,19 @@ impl KinesisFirehoseService {
         client: KinesisFirehoseClient,
         cx: SinkContext,
     ) -> crate::Result<impl Sink<Event, Error = ()>> {
+        // Aggregated records hold many events, so only the size of the
+        // request limits batches.
+        let events = if config.aggregate {
+            usize::max_value()
+        } else {
+            MAX_RECORDS
+        };
         let batch = BatchSettings::default()
-            .bytes(4_000_000)
-            .events(500)
+            .bytes(MAX_REQUEST_BYTES)
+            .events(events)
             .timeout(1)
-            .parse_config(config.batch)?;
+            .parse_config(config.batch)?
+            .limit(MAX_REQUEST_BYTES, events)?;
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
         let encoding = config.encoding.clone();
 
@@ -180,6 +201,12 @@ impl Service<Vec<Record>> for KinesisFirehoseService {
             events = %records.len(),
         );
 
+        let records = if self.config.aggregate {
+            aggregate(records)
+        } else {
+            records
+        };
+
         let client = self.client.clone();
         let request = PutRecordBatchInput {
             records,
@@ -197,8 +224,9 @@ impl Service<Vec<Record>> for KinesisFirehoseService {
 
 impl EncodedLength for Record {
     fn encoded_length(&self) -> usize {
-        // data is simply base64 encoded, quoted, and comma separated
-        (self.data.len() + 2) / 3 * 4 + 3
+        // The request limit counts the data, and aggregated records add a
+        // newline to it.
+        self.data.len() + 1
     }
 }
 
@@ -249,11 +277,45 @@ fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option
             .unwrap_or_default(),
     };
 
+    if data.len() > MAX_RECORD_BYTES {
+        emit!(AwsKinesisFirehoseRecordTooLarge {
+            byte_size: data.len()
+        });
+        return None;
+    }
+
     let data = Bytes::from(data);
 
     Some(Record { data })
 }
 
+/// Packs the events into as few records as possible, each followed by a
+/// newline.
+fn aggregate(records: Vec<Record>) -> Vec<Record> {
+    let mut aggregated = Vec::new();
+    let mut data = BytesMut::new();
+    for record in records {
+        if !data.is_empty() && data.len() + record.data.len() + 1 > MAX_RECORD_BYTES {
+            aggregated.push(Record {
+                data: data.split().freeze(),
+            });
+        }
+        // There's no room left for the newline, so it's sent as is.
+        if record.data.len() == MAX_RECORD_BYTES {
+            aggregated.push(record);
+            continue;
+        }
+        data.extend_from_slice(&record.data);
+        data.extend_from_slice(b"\n");
+    }
+    if !data.is_empty() {
+        aggregated.push(Record {
+            data: data.freeze(),
+        });
+    }
+    aggregated
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -287,6 +349,38 @@ mod tests {
         );
         assert_eq!(map["key"], "value".to_string());
     }
+
+    #[test]
+    fn firehose_encode_event_too_large() {
+        let message = "a".repeat(MAX_RECORD_BYTES + 1);
+        assert!(encode_event(message.into(), &Encoding::Text.into()).is_none());
+    }
+
+    #[test]
+    fn firehose_aggregate_records() {
+        let record = |len| Record {
+            data: Bytes::from(vec![b'a'; len]),
+        };
+        let records = vec![
+            record(3),
+            record(4),
+            record(MAX_RECORD_BYTES - 10),
+            record(MAX_RECORD_BYTES - 1),
+            record(MAX_RECORD_BYTES),
+            record(5),
+        ];
+
+        let aggregated = aggregate(records);
+
+        let lengths = aggregated.iter().map(|r| r.data.len()).collect::<Vec<_>>();
+        assert_eq!(
+            lengths,
+            vec![MAX_RECORD_BYTES, MAX_RECORD_BYTES, MAX_RECORD_BYTES, 6]
+        );
+        assert_eq!(&aggregated[0].data[..9], b"aaa\naaaa\n");
+        assert_eq!(aggregated[0].data.last(), Some(&b'\n'));
+        assert_eq!(aggregated[2].data.last(), Some(&b'a'));
+    }
 }
 
 #[cfg(feature = "aws-kinesis-firehose-integration-tests")]
@@ -323,6 +417,7 @@ mod integration_tests {
             region: RegionOrEndpoint::with_endpoint("http://localhost:4566".into()),
             encoding: EncodingConfig::from(Encoding::Json), // required for ES destination w/ localstack
             compression: Compression::None,
+            aggregate: false,
             batch: BatchConfig {
                 max_events: Some(2),
                 ..Default::default()
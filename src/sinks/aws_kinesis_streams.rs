@@ -1,7 +1,9 @@
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
-    internal_events::AwsKinesisStreamsEventSent,
+    internal_events::{
+        AwsKinesisStreamsEventSent, AwsKinesisStreamsMissingKeys, AwsKinesisStreamsRecordTooLarge,
+    },
     rusoto::{self, RegionOrEndpoint},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
@@ -9,10 +11,14 @@ use crate::{
         sink::Response,
         BatchConfig, BatchSettings, Compression, EncodedLength, TowerRequestConfig, VecBuffer,
     },
+    template::Template,
 };
 use bytes::Bytes;
-use futures::{future::BoxFuture, stream, FutureExt, Sink, SinkExt, StreamExt, TryFutureExt};
+use futures::{future::BoxFuture, stream, FutureExt, Sink, SinkExt, StreamExt};
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
+use md5::{Digest, Md5};
+use prost::{encoding::encoded_len_varint, Message};
 use rand::random;
 use rusoto_core::RusotoError;
 use rusoto_kinesis::{
@@ -22,24 +28,52 @@ use rusoto_kinesis::{
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::{
-    convert::TryInto,
+    convert::{TryFrom, TryInto},
     fmt,
     task::{Context, Poll},
 };
 use tower::Service;
 use tracing_futures::Instrument;
 
+/// The most records a `PutRecords` request can have.
+const MAX_RECORDS: usize = 500;
+/// The largest a `PutRecords` request can be, counting the data and partition
+/// keys of its records.
+const MAX_REQUEST_BYTES: u64 = 5 * 1024 * 1024;
+/// The largest a record can be, counting its data and partition key.
+const MAX_RECORD_BYTES: usize = 1024 * 1024;
+/// The most characters a partition key can have.
+const MAX_PARTITION_KEY_CHARS: usize = 256;
+/// The prefix of aggregated records.
+const AGGREGATION_MAGIC: [u8; 4] = [0xF3, 0x89, 0x9A, 0xC2];
+
+mod proto {
+    include!(concat!(env!("OUT_DIR"), "/aws.kinesis.protobuf.rs"));
+}
+
 #[derive(Clone)]
 pub struct KinesisService {
     client: KinesisClient,
     config: KinesisSinkConfig,
 }
 
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Cannot set both `partition_key` and `partition_key_field`"))]
+    PartitionKeyAndField,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct KinesisSinkConfig {
     pub stream_name: String,
+    pub partition_key: Option<Template>,
+    /// Deprecated, use `partition_key` instead.
     pub partition_key_field: Option<String>,
+    /// Packs records into aggregated records, which the Kinesis Client
+    /// Library de-aggregates.
+    #[serde(default)]
+    pub aggregate: bool,
     #[serde(flatten)]
     pub region: RegionOrEndpoint,
     pub encoding: EncodingConfig<Encoding>,
@@ -104,6 +138,15 @@ impl SinkConfig for KinesisSinkConfig {
 }
 
 impl KinesisSinkConfig {
+    fn partition_key_template(&self) -> crate::Result<Option<Template>> {
+        match (&self.partition_key, &self.partition_key_field) {
+            (Some(_), Some(_)) => Err(BuildError::PartitionKeyAndField.into()),
+            (Some(template), None) => Ok(Some(template.clone())),
+            (None, Some(field)) => Ok(Some(Template::try_from(format!("{{{{ {} }}}}", field))?)),
+            (None, None) => Ok(None),
+        }
+    }
+
     async fn healthcheck(self, client: KinesisClient) -> crate::Result<()> {
         let stream_name = self.stream_name;
 
@@ -132,7 +175,8 @@ impl KinesisSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let encoding = self.compression.try_into()?;
+        let client = rusoto_core::Client::new_with_encoding(creds, client, encoding);
         Ok(KinesisClient::new_with_client(client, region))
     }
 }
@@ -143,14 +187,22 @@ impl KinesisService {
         client: KinesisClient,
         cx: SinkContext,
     ) -> crate::Result<impl Sink<Event, Error = ()>> {
+        // Aggregated records hold many events, so only the size of the
+        // request limits batches.
+        let events = if config.aggregate {
+            usize::max_value()
+        } else {
+            MAX_RECORDS
+        };
         let batch = BatchSettings::default()
-            .bytes(5_000_000)
-            .events(500)
+            .bytes(MAX_REQUEST_BYTES)
+            .events(events)
             .timeout(1)
-            .parse_config(config.batch)?;
+            .parse_config(config.batch)?
+            .limit(MAX_REQUEST_BYTES, events)?;
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
         let encoding = config.encoding.clone();
-        let partition_key_field = config.partition_key_field.clone();
+        let partition_key = config.partition_key_template()?;
 
         let kinesis = KinesisService { client, config };
 
@@ -164,7 +216,7 @@ impl KinesisService {
             )
             .sink_map_err(|error| error!(message = "Fatal kinesis streams sink error.", %error))
             .with_flat_map(move |e| {
-                stream::iter(encode_event(e, &partition_key_field, &encoding)).map(Ok)
+                stream::iter(encode_event(e, &partition_key, &encoding)).map(Ok)
             });
 
         Ok(sink)
@@ -187,23 +239,45 @@ impl Service<Vec<PutRecordsRequestEntry>> for KinesisService {
         );
 
         let sizes: Vec<usize> = records.iter().map(|record| record.data.len()).collect();
+        let mut records = if self.config.aggregate {
+            let random_keys =
+                self.config.partition_key.is_none() && self.config.partition_key_field.is_none();
+            aggregate(records, random_keys)
+        } else {
+            records
+        };
 
         let client = self.client.clone();
-        let request = PutRecordsInput {
-            records,
-            stream_name: self.config.stream_name.clone(),
-        };
+        let stream_name = self.config.stream_name.clone();
 
         Box::pin(async move {
-            client
-                .put_records(request)
-                .inspect_ok(|_| {
-                    for byte_size in sizes {
-                        emit!(AwsKinesisStreamsEventSent { byte_size });
-                    }
-                })
-                .instrument(info_span!("request"))
-                .await
+            // Aggregated records with different partition keys can still be
+            // too many for one request.
+            let mut output = PutRecordsOutput::default();
+            while !records.is_empty() {
+                let rest = records.split_off(records.len().min(MAX_RECORDS));
+                let request = PutRecordsInput {
+                    records,
+                    stream_name: stream_name.clone(),
+                };
+                let response = client
+                    .put_records(request)
+                    .instrument(info_span!("request"))
+                    .await?;
+
+                output.failed_record_count = Some(
+                    output.failed_record_count.unwrap_or(0)
+                        + response.failed_record_count.unwrap_or(0),
+                );
+                output.records.extend(response.records);
+                output.encryption_type = response.encryption_type;
+                records = rest;
+            }
+
+            for byte_size in sizes {
+                emit!(AwsKinesisStreamsEventSent { byte_size });
+            }
+            Ok(output)
         })
     }
 }
@@ -218,15 +292,9 @@ impl fmt::Debug for KinesisService {
 
 impl EncodedLength for PutRecordsRequestEntry {
     fn encoded_length(&self) -> usize {
-        // data is base64 encoded
-        (self.data.len() + 2) / 3 * 4
-            + self
-                .explicit_hash_key
-                .as_ref()
-                .map(|s| s.len())
-                .unwrap_or_default()
-            + self.partition_key.len()
-            + 10
+        // The request limit counts the data and partition keys. The rest
+        // leaves room for the fields of the record once aggregated.
+        self.data.len() + self.partition_key.len() + 10
     }
 }
 
@@ -264,28 +332,25 @@ enum HealthcheckError {
 
 fn encode_event(
     mut event: Event,
-    partition_key_field: &Option<String>,
+    partition_key: &Option<Template>,
     encoding: &EncodingConfig<Encoding>,
 ) -> Option<PutRecordsRequestEntry> {
-    let partition_key = if let Some(partition_key_field) = partition_key_field {
-        if let Some(v) = event.as_log().get(&partition_key_field) {
-            v.to_string_lossy()
-        } else {
-            warn!(
-                message = "Partition key does not exist; dropping event.",
-                %partition_key_field,
-                rate_limit_secs = 30,
-            );
-            return None;
-        }
-    } else {
-        gen_partition_key()
+    let partition_key = match partition_key {
+        Some(partition_key) => match partition_key.render_string(&event) {
+            Ok(key) => key,
+            Err(keys) => {
+                emit!(AwsKinesisStreamsMissingKeys { keys: &keys });
+                return None;
+            }
+        },
+        None => gen_partition_key(),
     };
 
-    let partition_key = if partition_key.len() >= 256 {
-        partition_key[..256].to_string()
-    } else {
-        partition_key
+    // Keys are limited in characters, and can't be empty.
+    let partition_key = match partition_key.char_indices().nth(MAX_PARTITION_KEY_CHARS) {
+        Some((end, _)) => partition_key[..end].to_owned(),
+        None if partition_key.is_empty() => gen_partition_key(),
+        None => partition_key,
     };
 
     encoding.apply_rules(&mut event);
@@ -299,6 +364,12 @@ fn encode_event(
             .unwrap_or_default(),
     };
 
+    let byte_size = data.len() + partition_key.len();
+    if byte_size > MAX_RECORD_BYTES {
+        emit!(AwsKinesisStreamsRecordTooLarge { byte_size });
+        return None;
+    }
+
     Some(PutRecordsRequestEntry {
         data: Bytes::from(data),
         partition_key,
@@ -306,6 +377,107 @@ fn encode_event(
     })
 }
 
+/// Packs records into aggregated records. A record is only aggregated with
+/// records with the same partition key, unless keys are random, so that it
+/// lands on the shard it would have alone.
+fn aggregate(
+    records: Vec<PutRecordsRequestEntry>,
+    random_keys: bool,
+) -> Vec<PutRecordsRequestEntry> {
+    let mut groups = IndexMap::<_, Vec<_>>::new();
+    for record in records {
+        let key = if random_keys {
+            String::new()
+        } else {
+            record.partition_key.clone()
+        };
+        groups.entry(key).or_default().push(record);
+    }
+
+    let mut aggregated = Vec::new();
+    for (_, records) in groups {
+        let mut aggregator = Aggregator::default();
+        for record in records {
+            if !aggregator.fits(&record) {
+                aggregated.extend(aggregator.finish());
+                aggregator = Aggregator::default();
+            }
+            aggregator.push(record);
+        }
+        aggregated.extend(aggregator.finish());
+    }
+    aggregated
+}
+
+#[derive(Default)]
+struct Aggregator {
+    records: Vec<PutRecordsRequestEntry>,
+    /// The size of the aggregated record, with its partition key.
+    size: usize,
+}
+
+impl Aggregator {
+    /// The size of the record's entry in the aggregated record.
+    fn record_len(record: &PutRecordsRequestEntry) -> usize {
+        // The partition key index, which is always 0, and the data.
+        let len = 2 + 1 + encoded_len_varint(record.data.len() as u64) + record.data.len();
+        1 + encoded_len_varint(len as u64) + len
+    }
+
+    /// Whether the record fits in the aggregated record. The first one
+    /// always does, as records are sent as they are when alone.
+    fn fits(&self, record: &PutRecordsRequestEntry) -> bool {
+        self.records.is_empty() || self.size + Self::record_len(record) <= MAX_RECORD_BYTES
+    }
+
+    fn push(&mut self, record: PutRecordsRequestEntry) {
+        if self.records.is_empty() {
+            let key = record.partition_key.len();
+            // The magic number, digest, partition key, and its entry in the
+            // partition key table.
+            self.size =
+                AGGREGATION_MAGIC.len() + 16 + key + 1 + encoded_len_varint(key as u64) + key;
+        }
+        self.size += Self::record_len(&record);
+        self.records.push(record);
+    }
+
+    fn finish(mut self) -> Option<PutRecordsRequestEntry> {
+        if self.records.len() <= 1 {
+            return self.records.pop();
+        }
+
+        let partition_key = self.records[0].partition_key.clone();
+        let message = proto::AggregatedRecord {
+            partition_key_table: vec![partition_key.clone()],
+            explicit_hash_key_table: Vec::new(),
+            records: self
+                .records
+                .into_iter()
+                .map(|record| proto::Record {
+                    partition_key_index: 0,
+                    explicit_hash_key_index: None,
+                    data: record.data.to_vec(),
+                    tags: Vec::new(),
+                })
+                .collect(),
+        };
+
+        let mut data = AGGREGATION_MAGIC.to_vec();
+        message
+            .encode(&mut data)
+            .expect("Encoding into a Vec can't fail.");
+        let digest = Md5::digest(&data[AGGREGATION_MAGIC.len()..]);
+        data.extend_from_slice(&digest);
+
+        Some(PutRecordsRequestEntry {
+            data: Bytes::from(data),
+            partition_key,
+            ..Default::default()
+        })
+    }
+}
+
 fn gen_partition_key() -> String {
     random::<[char; 16]>()
         .iter()
@@ -326,6 +498,44 @@ mod tests {
         crate::test_util::test_generate_config::<KinesisSinkConfig>();
     }
 
+    fn key_template() -> Option<Template> {
+        Some(Template::try_from("{{ key }}").unwrap())
+    }
+
+    fn record(key: &str, data: &[u8]) -> PutRecordsRequestEntry {
+        PutRecordsRequestEntry {
+            data: Bytes::from(data.to_vec()),
+            partition_key: key.into(),
+            ..Default::default()
+        }
+    }
+
+    fn deaggregate(record: &PutRecordsRequestEntry) -> proto::AggregatedRecord {
+        let data = &record.data[..];
+        let (message, digest) = data[AGGREGATION_MAGIC.len()..].split_at(data.len() - 20);
+        assert_eq!(&data[..AGGREGATION_MAGIC.len()], &AGGREGATION_MAGIC);
+        assert_eq!(&Md5::digest(message)[..], digest);
+        proto::AggregatedRecord::decode(message).unwrap()
+    }
+
+    #[test]
+    fn kinesis_partition_key_field_is_template() {
+        let mut config: KinesisSinkConfig = toml::from_str(
+            r#"region = "us-east-1"
+            stream_name = "my-stream"
+            partition_key_field = "key"
+            encoding.codec = "json""#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.partition_key_template().unwrap().unwrap().get_ref(),
+            "{{ key }}"
+        );
+
+        config.partition_key = key_template();
+        assert!(config.partition_key_template().is_err());
+    }
+
     #[test]
     fn kinesis_encode_event_text() {
         let message = "hello world".to_string();
@@ -351,7 +561,7 @@ mod tests {
     fn kinesis_encode_event_custom_partition_key() {
         let mut event = Event::from("hello world");
         event.as_mut_log().insert("key", "some_key");
-        let event = encode_event(event, &Some("key".into()), &Encoding::Text.into()).unwrap();
+        let event = encode_event(event, &key_template(), &Encoding::Text.into()).unwrap();
 
         assert_eq!(&event.data[..], b"hello world");
         assert_eq!(&event.partition_key, &"some_key".to_string());
@@ -361,7 +571,7 @@ mod tests {
     fn kinesis_encode_event_custom_partition_key_limit() {
         let mut event = Event::from("hello world");
         event.as_mut_log().insert("key", random_string(300));
-        let event = encode_event(event, &Some("key".into()), &Encoding::Text.into()).unwrap();
+        let event = encode_event(event, &key_template(), &Encoding::Text.into()).unwrap();
 
         assert_eq!(&event.data[..], b"hello world");
         assert_eq!(event.partition_key.len(), 256);
@@ -375,12 +585,109 @@ mod tests {
         let mut encoding: EncodingConfig<_> = Encoding::Json.into();
         encoding.except_fields = Some(vec!["key".into()]);
 
-        let event = encode_event(event, &Some("key".into()), &encoding).unwrap();
+        let event = encode_event(event, &key_template(), &encoding).unwrap();
         let map: BTreeMap<String, String> = serde_json::from_slice(&event.data[..]).unwrap();
 
         assert_eq!(&event.partition_key, &"some_key".to_string());
         assert!(!map.contains_key("key"));
     }
+
+    #[test]
+    fn kinesis_encode_event_partition_key_template() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("app", "api");
+        event.as_mut_log().insert("host", "a");
+        let template = Some(Template::try_from("{{ app }}-{{ host }}").unwrap());
+        let event = encode_event(event, &template, &Encoding::Text.into()).unwrap();
+
+        assert_eq!(&event.partition_key, "api-a");
+    }
+
+    #[test]
+    fn kinesis_encode_event_missing_partition_key() {
+        let event = Event::from("hello world");
+        assert!(encode_event(event, &key_template(), &Encoding::Text.into()).is_none());
+    }
+
+    #[test]
+    fn kinesis_encode_event_partition_key_limit_chars() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("key", "é".repeat(300));
+        let event = encode_event(event, &key_template(), &Encoding::Text.into()).unwrap();
+
+        assert_eq!(event.partition_key, "é".repeat(256));
+    }
+
+    #[test]
+    fn kinesis_encode_event_empty_partition_key() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("key", "");
+        let event = encode_event(event, &key_template(), &Encoding::Text.into()).unwrap();
+
+        assert_eq!(event.partition_key.chars().count(), 16);
+    }
+
+    #[test]
+    fn kinesis_encode_event_too_large() {
+        let event = Event::from("a".repeat(MAX_RECORD_BYTES));
+        assert!(encode_event(event, &None, &Encoding::Text.into()).is_none());
+    }
+
+    #[test]
+    fn kinesis_aggregate_by_partition_key() {
+        let records = vec![
+            record("a", b"1"),
+            record("b", b"2"),
+            record("a", b"3"),
+            record("c", b"4"),
+        ];
+
+        let aggregated = aggregate(records, false);
+
+        assert_eq!(aggregated.len(), 3);
+        assert_eq!(&aggregated[0].partition_key, "a");
+        let message = deaggregate(&aggregated[0]);
+        assert_eq!(message.partition_key_table, vec!["a".to_string()]);
+        let data = message
+            .records
+            .into_iter()
+            .map(|record| record.data)
+            .collect::<Vec<_>>();
+        assert_eq!(data, vec![b"1".to_vec(), b"3".to_vec()]);
+
+        // Records alone aren't aggregated.
+        assert_eq!(&aggregated[1].data[..], b"2");
+        assert_eq!(&aggregated[2].data[..], b"4");
+    }
+
+    #[test]
+    fn kinesis_aggregate_random_keys() {
+        let records = vec![record("a", b"1"), record("b", b"2")];
+
+        let aggregated = aggregate(records, true);
+
+        assert_eq!(aggregated.len(), 1);
+        assert_eq!(&aggregated[0].partition_key, "a");
+        assert_eq!(deaggregate(&aggregated[0]).records.len(), 2);
+    }
+
+    #[test]
+    fn kinesis_aggregate_record_limit() {
+        let data = vec![b'a'; MAX_RECORD_BYTES / 3];
+        let records = (0..4).map(|_| record("a", &data)).collect();
+
+        let aggregated = aggregate(records, false);
+
+        assert_eq!(aggregated.len(), 2);
+        for record in &aggregated {
+            assert!(record.data.len() + record.partition_key.len() <= MAX_RECORD_BYTES);
+        }
+        assert_eq!(
+            deaggregate(&aggregated[0]).encoded_len(),
+            aggregated[0].data.len() - 20
+        );
+        assert_eq!(deaggregate(&aggregated[1]).records.len(), 2);
+    }
 }
 
 #[cfg(feature = "aws-kinesis-streams-integration-tests")]
@@ -410,7 +717,9 @@ mod integration_tests {
 
         let config = KinesisSinkConfig {
             stream_name: stream.clone(),
+            partition_key: None,
             partition_key_field: None,
+            aggregate: false,
             region: RegionOrEndpoint::with_endpoint("http://localhost:4566".into()),
             encoding: Encoding::Text.into(),
             compression: Compression::None,
@@ -1,19 +1,24 @@
 use crate::{
     config::{log_schema, DataType, SinkConfig, SinkContext, SinkDescription},
+    event::LogEvent,
     rusoto::{self, RegionOrEndpoint},
     serde::to_string,
     sinks::util::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+        parquet::{BuildError as ParquetBuildError, ParquetConfig, ParquetRow},
         retries::RetryLogic,
         sink::Response,
         BatchConfig, BatchSettings, Buffer, Compression, Concurrency, PartitionBatchSink,
-        PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+        PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig, VecBuffer,
     },
-    template::Template,
+    template::{Template, TemplateError},
     Event,
 };
 use bytes::Bytes;
-use chrono::Utc;
+use chrono::{
+    format::{strftime::StrftimeItems, Item},
+    Utc,
+};
 use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
 use http::StatusCode;
 use lazy_static::lazy_static;
@@ -22,7 +27,7 @@ use rusoto_s3::{
     HeadBucketRequest, PutObjectError, PutObjectOutput, PutObjectRequest, S3Client, S3,
 };
 use serde::{Deserialize, Serialize};
-use snafu::Snafu;
+use snafu::{ResultExt, Snafu};
 use std::{
     collections::BTreeMap,
     convert::{TryFrom, TryInto},
@@ -54,6 +59,12 @@ pub struct S3SinkConfig {
         default
     )]
     pub encoding: EncodingConfigWithDefault<Encoding>,
+    /// Used when `encoding.codec` is `parquet`.
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub parquet: ParquetConfig,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
     #[serde(default)]
@@ -78,6 +89,52 @@ struct S3Options {
     content_type: Option<String>,     // default `text/x-log`
 }
 
+const PARQUET_CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+
+// S3's limits on the tags of an object.
+const MAX_TAGS: usize = 10;
+const MAX_TAG_KEY_LENGTH: usize = 128;
+const MAX_TAG_VALUE_LENGTH: usize = 256;
+
+impl S3Options {
+    /// Rejects settings S3 would refuse for every object.
+    fn validate(&self) -> Result<(), BuildError> {
+        if let Some(tags) = &self.tags {
+            if tags.len() > MAX_TAGS {
+                return Err(BuildError::TooManyTags { count: tags.len() });
+            }
+            for (key, value) in tags {
+                if key.is_empty() || key.chars().count() > MAX_TAG_KEY_LENGTH {
+                    return Err(BuildError::InvalidTagKey { key: key.clone() });
+                }
+                if value.chars().count() > MAX_TAG_VALUE_LENGTH {
+                    return Err(BuildError::InvalidTagValue { key: key.clone() });
+                }
+            }
+        }
+
+        if self.ssekms_key_id.is_some()
+            && matches!(
+                self.server_side_encryption,
+                Some(S3ServerSideEncryption::AES256)
+            )
+        {
+            return Err(BuildError::KmsKeyWithoutKms);
+        }
+
+        Ok(())
+    }
+
+    /// A KMS key implies KMS encryption.
+    fn server_side_encryption(&self) -> Option<S3ServerSideEncryption> {
+        self.server_side_encryption.or_else(|| {
+            self.ssekms_key_id
+                .as_ref()
+                .map(|_| S3ServerSideEncryption::AwsKms)
+        })
+    }
+}
+
 #[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize)]
 #[derivative(Default)]
 #[serde(rename_all = "kebab-case")]
@@ -132,6 +189,9 @@ pub enum Encoding {
     #[derivative(Default)]
     Text,
     Ndjson,
+    /// Batches are written whole as Parquet files, compressed as set in
+    /// `parquet.compression` rather than `compression`.
+    Parquet,
 }
 
 inventory::submit! {
@@ -162,6 +222,32 @@ impl SinkConfig for S3SinkConfig {
     }
 }
 
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid key prefix template: {}", source))]
+    KeyPrefixTemplate { source: TemplateError },
+    #[snafu(display(
+        "`filename_time_format` can't be empty unless `filename_append_uuid` is set"
+    ))]
+    EmptyFilename,
+    #[snafu(display("Invalid strftime item in `filename_time_format`"))]
+    FilenameTimeFormat,
+    #[snafu(display("Objects can have at most {} tags, but {} are set", MAX_TAGS, count))]
+    TooManyTags { count: usize },
+    #[snafu(display("Tag keys must have 1 to {} characters: {:?}", MAX_TAG_KEY_LENGTH, key))]
+    InvalidTagKey { key: String },
+    #[snafu(display(
+        "The value of tag {:?} is longer than {} characters",
+        key,
+        MAX_TAG_VALUE_LENGTH
+    ))]
+    InvalidTagValue { key: String },
+    #[snafu(display("`ssekms_key_id` requires `server_side_encryption` to be \"aws:kms\""))]
+    KmsKeyWithoutKms,
+    #[snafu(display("Invalid Parquet settings: {}", source))]
+    Parquet { source: ParquetBuildError },
+}
+
 #[derive(Debug, Snafu)]
 enum HealthcheckError {
     #[snafu(display("Invalid credentials"))]
@@ -183,19 +269,70 @@ impl S3SinkConfig {
             .clone()
             .unwrap_or_else(|| "%s".into());
         let filename_append_uuid = self.filename_append_uuid.unwrap_or(true);
-        let batch = BatchSettings::default()
-            .bytes(10_000_000)
-            .timeout(300)
-            .parse_config(self.batch)?;
-
+        self.options.validate()?;
+        if StrftimeItems::new(&filename_time_format).any(|item| matches!(item, Item::Error)) {
+            return Err(BuildError::FilenameTimeFormat.into());
+        }
+        if filename_time_format.is_empty() && !filename_append_uuid {
+            return Err(BuildError::EmptyFilename.into());
+        }
         let key_prefix = self.key_prefix.as_deref().unwrap_or("date=%F/");
-        let key_prefix = Template::try_from(key_prefix)?;
+        let key_prefix = Template::try_from(key_prefix).context(KeyPrefixTemplate)?;
 
         let s3 = S3Sink { client };
 
         let filename_extension = self.filename_extension.clone();
         let bucket = self.bucket.clone();
-        let options = self.options.clone();
+        let mut options = self.options.clone();
+
+        if let Encoding::Parquet = encoding.codec() {
+            let parquet = self.parquet.build().context(Parquet)?;
+            let batch = BatchSettings::default()
+                .bytes(10_000_000)
+                .timeout(300)
+                .parse_config(self.batch)?;
+            let filename_extension = filename_extension.unwrap_or_else(|| "parquet".into());
+            options
+                .content_type
+                .get_or_insert_with(|| PARQUET_CONTENT_TYPE.into());
+
+            let svc = ServiceBuilder::new()
+                .map(move |req: PartitionInnerBuffer<Vec<ParquetRow>, Bytes>| {
+                    let (rows, key) = req.into_parts();
+                    let events = rows.into_iter().map(LogEvent::from).collect::<Vec<_>>();
+                    // Every value is converted to the type of its column, so
+                    // writing to memory can only fail on a bug.
+                    let body = parquet
+                        .encode(&events)
+                        .expect("Failed to encode batch as Parquet, this is a bug!");
+                    build_request(
+                        PartitionInnerBuffer::new(body, key),
+                        filename_time_format.clone(),
+                        Some(filename_extension.clone()),
+                        filename_append_uuid,
+                        Compression::None,
+                        bucket.clone(),
+                        options.clone(),
+                    )
+                })
+                .settings(request, S3RetryLogic)
+                .service(s3);
+
+            let buffer = PartitionBuffer::new(VecBuffer::new(batch.size));
+
+            let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                .with_flat_map(move |e| {
+                    stream::iter(encode_parquet_event(e, &key_prefix, &encoding)).map(Ok)
+                })
+                .sink_map_err(|error| error!(message = "Sink failed to flush.", %error));
+
+            return Ok(super::VectorSink::Sink(Box::new(sink)));
+        }
+
+        let batch = BatchSettings::default()
+            .bytes(10_000_000)
+            .timeout(300)
+            .parse_config(self.batch)?;
 
         let svc = ServiceBuilder::new()
             .map(move |req| {
@@ -263,6 +400,7 @@ impl Service<Request> for S3Sink {
 
     fn call(&mut self, request: Request) -> Self::Future {
         let options = request.options;
+        let server_side_encryption = options.server_side_encryption();
 
         let content_encoding = request.content_encoding;
         let content_encoding = options
@@ -292,10 +430,10 @@ impl Service<Request> for S3Sink {
             grant_read: options.grant_read,
             grant_read_acp: options.grant_read_acp,
             grant_write_acp: options.grant_write_acp,
-            server_side_encryption: options.server_side_encryption.map(to_string),
+            server_side_encryption: server_side_encryption.map(to_string),
             ssekms_key_id: options.ssekms_key_id,
             storage_class: options.storage_class.map(to_string),
-            tagging: Some(tagging),
+            tagging: Some(tagging).filter(|tagging| !tagging.is_empty()),
             ..Default::default()
         };
 
@@ -321,13 +459,12 @@ fn build_request(
 
     // TODO: pull the seconds from the last event
     let filename = {
-        let seconds = Utc::now().format(&time_format);
+        let seconds = Utc::now().format(&time_format).to_string();
 
-        if uuid {
-            let uuid = Uuid::new_v4();
-            format!("{}-{}", seconds, uuid.to_hyphenated())
-        } else {
-            seconds.to_string()
+        match (seconds.is_empty(), uuid) {
+            (false, true) => format!("{}-{}", seconds, Uuid::new_v4().to_hyphenated()),
+            (true, true) => Uuid::new_v4().to_hyphenated().to_string(),
+            (_, false) => seconds,
         }
     };
 
@@ -374,13 +511,9 @@ impl RetryLogic for S3RetryLogic {
     }
 }
 
-fn encode_event(
-    mut event: Event,
-    key_prefix: &Template,
-    encoding: &EncodingConfigWithDefault<Encoding>,
-) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
-    let key = key_prefix
-        .render_string(&event)
+fn render_key(event: &Event, key_prefix: &Template) -> Option<String> {
+    key_prefix
+        .render_string(event)
         .map_err(|missing_keys| {
             warn!(
                 message = "Keys do not exist on the event; dropping event.",
@@ -388,7 +521,15 @@ fn encode_event(
                 rate_limit_secs = 30,
             );
         })
-        .ok()?;
+        .ok()
+}
+
+fn encode_event(
+    mut event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfigWithDefault<Encoding>,
+) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
+    let key = render_key(&event, key_prefix)?;
 
     encoding.apply_rules(&mut event);
 
@@ -408,14 +549,31 @@ fn encode_event(
             bytes.push(b'\n');
             bytes
         }
+        Encoding::Parquet => unreachable!("Parquet batches are encoded whole."),
     };
 
     Some(PartitionInnerBuffer::new(bytes, key.into()))
 }
 
+fn encode_parquet_event(
+    mut event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfigWithDefault<Encoding>,
+) -> Option<PartitionInnerBuffer<ParquetRow, Bytes>> {
+    let key = render_key(&event, key_prefix)?;
+
+    encoding.apply_rules(&mut event);
+
+    Some(PartitionInnerBuffer::new(
+        event.into_log().into(),
+        key.into(),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn generate_config() {
@@ -477,6 +635,27 @@ mod tests {
         // assert_eq!(map["key"], "value".to_string());
     }
 
+    #[test]
+    fn s3_encode_event_parquet() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("app", "api");
+        event.as_mut_log().insert("secret", "hunter2");
+
+        let key_prefix = Template::try_from("{{ app }}/").unwrap();
+        let encoding = EncodingConfigWithDefault {
+            codec: Encoding::Parquet,
+            except_fields: Some(vec!["secret".into()]),
+            ..Default::default()
+        };
+
+        let row = encode_parquet_event(event, &key_prefix, &encoding).unwrap();
+        let (row, key) = row.into_parts();
+        let log = LogEvent::from(row);
+        assert_eq!(key, "api/");
+        assert_eq!(log["app"], "api".into());
+        assert!(log.get("secret").is_none());
+    }
+
     #[test]
     fn s3_build_request() {
         let buf = PartitionInnerBuffer::new(vec![0u8; 10], Bytes::from("key/"));
@@ -526,6 +705,128 @@ mod tests {
         assert_ne!(req.key, "key/date.log.gz".to_string());
     }
 
+    #[test]
+    fn s3_partitions_by_rendered_key_prefix() {
+        let key_prefix = Template::try_from("logs/{{ app }}/dt=%Y-%m-%d/hour=%H/").unwrap();
+        let encoding = Encoding::Ndjson.into();
+
+        let keys = [
+            ("api", 1_600_000_000),
+            ("api", 1_600_003_600),
+            ("web", 1_600_000_000),
+        ]
+        .iter()
+        .map(|&(app, seconds)| {
+            let mut event = Event::from("hello world");
+            event.as_mut_log().insert("app", app);
+            event
+                .as_mut_log()
+                .insert(log_schema().timestamp_key(), Utc.timestamp(seconds, 0));
+            let (_, key) = encode_event(event, &key_prefix, &encoding)
+                .unwrap()
+                .into_parts();
+            key
+        })
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            keys,
+            vec![
+                Bytes::from("logs/api/dt=2020-09-13/hour=12/"),
+                Bytes::from("logs/api/dt=2020-09-13/hour=13/"),
+                Bytes::from("logs/web/dt=2020-09-13/hour=12/"),
+            ]
+        );
+
+        let event = Event::from("hello world");
+        assert!(encode_event(event, &key_prefix, &encoding).is_none());
+    }
+
+    #[test]
+    fn s3_build_request_without_timestamp() {
+        let buf = PartitionInnerBuffer::new(vec![0u8; 10], Bytes::from("key/"));
+
+        let req = build_request(
+            buf,
+            "".into(),
+            None,
+            true,
+            Compression::None,
+            "bucket".into(),
+            S3Options::default(),
+        );
+        let filename = req.key.trim_start_matches("key/").trim_end_matches(".log");
+        assert!(Uuid::parse_str(filename).is_ok());
+    }
+
+    #[tokio::test]
+    async fn s3_rejects_invalid_filenames() {
+        let config = S3SinkConfig {
+            bucket: "bucket".into(),
+            region: RegionOrEndpoint::with_endpoint("http://localhost:4566".to_owned()),
+            filename_time_format: Some("".into()),
+            filename_append_uuid: Some(false),
+            ..Default::default()
+        };
+        let client = config.create_client().unwrap();
+        assert!(config.new(client.clone(), SinkContext::new_test()).is_err());
+
+        let config = S3SinkConfig {
+            filename_time_format: Some("%E".into()),
+            filename_append_uuid: None,
+            ..config
+        };
+        assert!(config.new(client, SinkContext::new_test()).is_err());
+    }
+
+    #[test]
+    fn s3_validates_tags() {
+        let tags = |count: usize, key_length: usize, value_length: usize| S3Options {
+            tags: Some(
+                (0..count)
+                    .map(|i| (format!("{:0>1$}", i, key_length), "v".repeat(value_length)))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+
+        assert!(tags(10, 128, 256).validate().is_ok());
+        assert!(matches!(
+            tags(11, 3, 3).validate(),
+            Err(BuildError::TooManyTags { count: 11 })
+        ));
+        assert!(matches!(
+            tags(1, 129, 3).validate(),
+            Err(BuildError::InvalidTagKey { .. })
+        ));
+        assert!(matches!(
+            tags(1, 3, 257).validate(),
+            Err(BuildError::InvalidTagValue { .. })
+        ));
+    }
+
+    #[test]
+    fn s3_kms_key_implies_kms_encryption() {
+        let options = S3Options {
+            ssekms_key_id: Some("arn:aws:kms:us-east-1:123456789012:key/abcd1234".into()),
+            ..Default::default()
+        };
+        assert!(options.validate().is_ok());
+        assert!(matches!(
+            options.server_side_encryption(),
+            Some(S3ServerSideEncryption::AwsKms)
+        ));
+
+        let options = S3Options {
+            server_side_encryption: Some(S3ServerSideEncryption::AES256),
+            ..options
+        };
+        assert!(matches!(
+            options.validate(),
+            Err(BuildError::KmsKeyWithoutKms)
+        ));
+    }
+
     #[test]
     fn storage_class_names() {
         for &(name, storage_class) in &[
@@ -0,0 +1,652 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    internal_events::{AwsSnsEventSent, AwsSnsMessageTooLarge, AwsSnsMissingKeys},
+    rusoto::{self, RegionOrEndpoint},
+    sinks::util::{
+        aws_messages::{
+            message_attributes, message_size, MessageAttribute, MAX_MESSAGE_ATTRIBUTES,
+            MAX_MESSAGE_BYTES,
+        },
+        encoding::{EncodingConfig, EncodingConfiguration},
+        retries::RetryLogic,
+        sink::Response,
+        BatchSettings, EncodedLength, TowerRequestConfig, VecBuffer,
+    },
+    template::{Template, TemplateError},
+    Event,
+};
+use futures::{future::BoxFuture, stream, FutureExt, Sink, SinkExt, StreamExt};
+use indexmap::IndexMap;
+use lazy_static::lazy_static;
+use rusoto_core::{Region, RusotoError};
+use rusoto_signature::SignedRequest;
+use rusoto_sns::{
+    GetTopicAttributesError, GetTopicAttributesInput, PublishError, PublishResponse, Sns, SnsClient,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    task::{Context, Poll},
+};
+use tower::Service;
+use tracing_futures::Instrument;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`message_group_id` should be defined for FIFO topic."))]
+    MessageGroupIdMissing,
+    #[snafu(display("`message_group_id` is not allowed with non-FIFO topic."))]
+    MessageGroupIdNotAllowed,
+    #[snafu(display("`message_deduplication_id` is not allowed with non-FIFO topic."))]
+    MessageDeduplicationIdNotAllowed,
+    #[snafu(display("invalid message_group_id template: {}", source))]
+    MessageGroupIdTemplate { source: TemplateError },
+    #[snafu(display("invalid message_deduplication_id template: {}", source))]
+    MessageDeduplicationIdTemplate { source: TemplateError },
+    #[snafu(display("A message can't have more than {} `message_attributes`.", limit))]
+    TooManyMessageAttributes { limit: usize },
+}
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("GetTopicAttributes failed: {}", source))]
+    GetTopicAttributes {
+        source: RusotoError<GetTopicAttributesError>,
+    },
+}
+
+#[derive(Clone)]
+pub struct SnsSink {
+    client: rusoto_core::Client,
+    region: Region,
+    topic_arn: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SnsSinkConfig {
+    pub topic_arn: String,
+    #[serde(flatten)]
+    pub region: RegionOrEndpoint,
+    pub encoding: EncodingConfig<Encoding>,
+    pub message_group_id: Option<String>,
+    pub message_deduplication_id: Option<String>,
+    /// The names of message attributes, and the fields they're taken from.
+    #[serde(default)]
+    pub message_attributes: IndexMap<String, String>,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub assume_role: Option<String>,
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        timeout_secs: Some(30),
+        ..Default::default()
+    };
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+inventory::submit! {
+    SinkDescription::new::<SnsSinkConfig>("aws_sns")
+}
+
+impl GenerateConfig for SnsSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"topic_arn = "arn:aws:sns:us-east-2:123456789012:MyTopic"
+            region = "us-east-2"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aws_sns")]
+impl SinkConfig for SnsSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let (client, region) = self.create_client()?;
+        let healthcheck = self
+            .clone()
+            .healthcheck(SnsClient::new_with_client(client.clone(), region.clone()));
+        let sink = SnsSink::new(self.clone(), cx, client, region)?;
+        Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck.boxed()))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "aws_sns"
+    }
+}
+
+impl SnsSinkConfig {
+    pub async fn healthcheck(self, client: SnsClient) -> crate::Result<()> {
+        client
+            .get_topic_attributes(GetTopicAttributesInput {
+                topic_arn: self.topic_arn.clone(),
+            })
+            .await
+            .map(|_| ())
+            .context(GetTopicAttributes)
+            .map_err(Into::into)
+    }
+
+    pub fn create_client(&self) -> crate::Result<(rusoto_core::Client, Region)> {
+        let region = (&self.region).try_into()?;
+        let client = rusoto::client()?;
+
+        let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
+
+        Ok((rusoto_core::Client::new_with(creds, client), region))
+    }
+}
+
+impl SnsSink {
+    pub fn new(
+        config: SnsSinkConfig,
+        cx: SinkContext,
+        client: rusoto_core::Client,
+        region: Region,
+    ) -> crate::Result<impl Sink<Event, Error = ()>> {
+        // `Publish` sends a single message.
+        let batch = BatchSettings::default()
+            .events(1)
+            .bytes(MAX_MESSAGE_BYTES as u64);
+
+        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let encoding = config.encoding;
+        let fifo = config.topic_arn.ends_with(".fifo");
+        let message_group_id = match (config.message_group_id, fifo) {
+            (Some(value), true) => Some(Template::try_from(value).context(MessageGroupIdTemplate)?),
+            (Some(_), false) => return Err(Box::new(BuildError::MessageGroupIdNotAllowed)),
+            (None, true) => return Err(Box::new(BuildError::MessageGroupIdMissing)),
+            (None, false) => None,
+        };
+        let message_deduplication_id = match (config.message_deduplication_id, fifo) {
+            (Some(value), true) => {
+                Some(Template::try_from(value).context(MessageDeduplicationIdTemplate)?)
+            }
+            (Some(_), false) => return Err(Box::new(BuildError::MessageDeduplicationIdNotAllowed)),
+            (None, _) => None,
+        };
+        if config.message_attributes.len() > MAX_MESSAGE_ATTRIBUTES {
+            return Err(Box::new(BuildError::TooManyMessageAttributes {
+                limit: MAX_MESSAGE_ATTRIBUTES,
+            }));
+        }
+        let options = MessageOptions {
+            message_group_id,
+            message_deduplication_id,
+            message_attributes: config.message_attributes,
+        };
+
+        let sns = SnsSink {
+            client,
+            region,
+            topic_arn: config.topic_arn,
+        };
+
+        let sink = request
+            .batch_sink(
+                SnsRetryLogic,
+                sns,
+                VecBuffer::new(batch.size),
+                batch.timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal sns sink error.", %error))
+            .with_flat_map(move |event| {
+                stream::iter(encode_event(event, &encoding, &options)).map(Ok)
+            });
+
+        Ok(sink)
+    }
+}
+
+impl Service<Vec<PublishEntry>> for SnsSink {
+    type Response = PublishResponse;
+    type Error = RusotoError<PublishError>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut entries: Vec<PublishEntry>) -> Self::Future {
+        assert_eq!(entries.len(), 1, "Sending batch is not supported.");
+
+        let entry = entries.remove(0);
+        let byte_size = entry.message.len();
+
+        // `SnsClient::publish` predates FIFO topics and can't set their
+        // message group and deduplication IDs, so the request is built here.
+        let mut request = SignedRequest::new("POST", "sns", &self.region, "/");
+        request.set_content_type("application/x-www-form-urlencoded".to_owned());
+        request.set_payload(Some(entry.encode_form(&self.topic_arn)));
+
+        let client = self.client.clone();
+
+        Box::pin(async move {
+            let response = client
+                .sign_and_dispatch(request)
+                .instrument(info_span!("request"))
+                .await?
+                .buffer()
+                .await
+                .map_err(RusotoError::HttpDispatch)?;
+
+            if !response.status.is_success() {
+                return Err(PublishError::from_response(response));
+            }
+
+            let message_id = parse_message_id(&response.body);
+            emit!(AwsSnsEventSent {
+                byte_size,
+                message_id: message_id.as_ref()
+            });
+            Ok(PublishResponse {
+                message_id,
+                ..Default::default()
+            })
+        })
+    }
+}
+
+impl fmt::Debug for SnsSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnsSink")
+            .field("region", &self.region)
+            .field("topic_arn", &self.topic_arn)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct MessageOptions {
+    message_group_id: Option<Template>,
+    message_deduplication_id: Option<Template>,
+    message_attributes: IndexMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+struct PublishEntry {
+    message: String,
+    message_group_id: Option<String>,
+    message_deduplication_id: Option<String>,
+    message_attributes: Vec<MessageAttribute>,
+}
+
+impl PublishEntry {
+    /// Encodes the entry as the form of a `Publish` request.
+    fn encode_form(&self, topic_arn: &str) -> String {
+        let mut form = url::form_urlencoded::Serializer::new(String::new());
+        form.append_pair("Action", "Publish")
+            .append_pair("Version", "2010-03-31")
+            .append_pair("TopicArn", topic_arn)
+            .append_pair("Message", &self.message);
+        if let Some(message_group_id) = &self.message_group_id {
+            form.append_pair("MessageGroupId", message_group_id);
+        }
+        if let Some(message_deduplication_id) = &self.message_deduplication_id {
+            form.append_pair("MessageDeduplicationId", message_deduplication_id);
+        }
+        for (i, attribute) in self.message_attributes.iter().enumerate() {
+            let prefix = format!("MessageAttributes.entry.{}", i + 1);
+            form.append_pair(&format!("{}.Name", prefix), &attribute.name)
+                .append_pair(&format!("{}.Value.DataType", prefix), attribute.data_type)
+                .append_pair(&format!("{}.Value.StringValue", prefix), &attribute.value);
+        }
+        form.finish()
+    }
+}
+
+impl EncodedLength for PublishEntry {
+    fn encoded_length(&self) -> usize {
+        message_size(&self.message, &self.message_attributes)
+    }
+}
+
+impl Response for PublishResponse {}
+
+#[derive(Debug, Clone)]
+struct SnsRetryLogic;
+
+impl RetryLogic for SnsRetryLogic {
+    type Error = RusotoError<PublishError>;
+    type Response = PublishResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            RusotoError::Service(PublishError::InternalError(_)) => true,
+            RusotoError::Service(PublishError::KMSThrottling(_)) => true,
+            // Throttling isn't one of the errors of `Publish`.
+            RusotoError::Unknown(response) => {
+                response.body_as_str().contains("<Code>Throttling</Code>")
+                    || rusoto::is_retriable_error(error)
+            }
+            error => rusoto::is_retriable_error(error),
+        }
+    }
+}
+
+/// Finds the ID of the message in the `Publish` response.
+fn parse_message_id(body: &[u8]) -> Option<String> {
+    let body = std::str::from_utf8(body).ok()?;
+    let start = body.find("<MessageId>")? + "<MessageId>".len();
+    let end = start + body[start..].find("</MessageId>")?;
+    Some(body[start..end].to_owned())
+}
+
+fn encode_event(
+    mut event: Event,
+    encoding: &EncodingConfig<Encoding>,
+    options: &MessageOptions,
+) -> Option<PublishEntry> {
+    // Attributes and IDs are taken from the event before `except_fields`
+    // removes the fields they come from.
+    let message_group_id = render(
+        options.message_group_id.as_ref(),
+        &event,
+        "message_group_id",
+    )?;
+    let message_deduplication_id = render(
+        options.message_deduplication_id.as_ref(),
+        &event,
+        "message_deduplication_id",
+    )?;
+    let message_attributes = message_attributes(&event, &options.message_attributes);
+
+    encoding.apply_rules(&mut event);
+
+    let log = event.into_log();
+    let message = match encoding.codec() {
+        Encoding::Text => log
+            .get(log_schema().message_key())
+            .map(|v| v.to_string_lossy())
+            .unwrap_or_else(|| "".into()),
+        Encoding::Json => serde_json::to_string(&log).expect("Error encoding event as json."),
+    };
+
+    let byte_size = message_size(&message, &message_attributes);
+    if byte_size > MAX_MESSAGE_BYTES {
+        emit!(AwsSnsMessageTooLarge { byte_size });
+        return None;
+    }
+
+    Some(PublishEntry {
+        message,
+        message_group_id,
+        message_deduplication_id,
+        message_attributes,
+    })
+}
+
+/// Renders the template, if there is one. The outer `None` drops the event.
+fn render(
+    template: Option<&Template>,
+    event: &Event,
+    name: &'static str,
+) -> Option<Option<String>> {
+    match template.map(|template| template.render_string(event)) {
+        Some(Ok(value)) => Some(Some(value)),
+        Some(Err(missing_keys)) => {
+            emit!(AwsSnsMissingKeys {
+                template: name,
+                keys: &missing_keys
+            });
+            None
+        }
+        None => Some(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnsSinkConfig>();
+    }
+
+    #[test]
+    fn sns_encode_event_text() {
+        let message = "hello world".to_string();
+        let event = encode_event(
+            message.clone().into(),
+            &Encoding::Text.into(),
+            &Default::default(),
+        )
+        .unwrap();
+
+        assert_eq!(&event.message, &message);
+    }
+
+    #[test]
+    fn sns_encode_event_json() {
+        let message = "hello world".to_string();
+        let mut event = Event::from(message.clone());
+        event.as_mut_log().insert("key", "value");
+        let event = encode_event(event, &Encoding::Json.into(), &Default::default()).unwrap();
+
+        let map: BTreeMap<String, String> = serde_json::from_str(&event.message).unwrap();
+
+        assert_eq!(map[&log_schema().message_key().to_string()], message);
+        assert_eq!(map["key"], "value".to_string());
+    }
+
+    #[test]
+    fn sns_encode_event_missing_keys() {
+        let options = MessageOptions {
+            message_group_id: Some(Template::try_from("{{ user }}").unwrap()),
+            ..Default::default()
+        };
+        let event = Event::from("hello world");
+
+        assert!(encode_event(event, &Encoding::Text.into(), &options).is_none());
+    }
+
+    #[test]
+    fn sns_encode_event_too_large() {
+        let event = Event::from("a".repeat(MAX_MESSAGE_BYTES + 1));
+
+        assert!(encode_event(event, &Encoding::Text.into(), &Default::default()).is_none());
+    }
+
+    #[test]
+    fn sns_encode_form() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("user", "bob");
+        event.as_mut_log().insert("status", 200);
+        let options = MessageOptions {
+            message_group_id: Some(Template::try_from("{{ user }}").unwrap()),
+            message_deduplication_id: None,
+            message_attributes: vec![("status".to_owned(), "status".to_owned())]
+                .into_iter()
+                .collect(),
+        };
+        let entry = encode_event(event, &Encoding::Text.into(), &options).unwrap();
+
+        let form = entry.encode_form("arn:aws:sns:us-east-2:123456789012:MyTopic.fifo");
+        let form = url::form_urlencoded::parse(form.as_bytes())
+            .into_owned()
+            .collect::<BTreeMap<_, _>>();
+        let expected = vec![
+            ("Action", "Publish"),
+            ("Version", "2010-03-31"),
+            (
+                "TopicArn",
+                "arn:aws:sns:us-east-2:123456789012:MyTopic.fifo",
+            ),
+            ("Message", "hello world"),
+            ("MessageGroupId", "bob"),
+            ("MessageAttributes.entry.1.Name", "status"),
+            ("MessageAttributes.entry.1.Value.DataType", "Number"),
+            ("MessageAttributes.entry.1.Value.StringValue", "200"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect::<BTreeMap<_, _>>();
+        assert_eq!(form, expected);
+    }
+
+    #[test]
+    fn sns_parse_message_id() {
+        let body = br#"<PublishResponse xmlns="https://sns.amazonaws.com/doc/2010-03-31/">
+  <PublishResult>
+    <MessageId>567910cd-659e-55d4-8ccb-5aaf14679dc0</MessageId>
+  </PublishResult>
+</PublishResponse>"#;
+
+        assert_eq!(
+            parse_message_id(body),
+            Some("567910cd-659e-55d4-8ccb-5aaf14679dc0".into())
+        );
+        assert_eq!(parse_message_id(b"<PublishResponse/>"), None);
+    }
+}
+
+#[cfg(feature = "aws-sns-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::test_util::{random_lines_with_stream, random_string};
+    use rusoto_sns::{CreateTopicInput, SubscribeInput};
+    use rusoto_sqs::{
+        CreateQueueRequest, GetQueueAttributesRequest, ReceiveMessageRequest, Sqs, SqsClient,
+    };
+    use std::collections::HashMap;
+    use tokio::time::{delay_for, Duration};
+
+    #[tokio::test]
+    async fn sns_publish() {
+        let cx = SinkContext::new_test();
+
+        let region = Region::Custom {
+            name: "localstack".into(),
+            endpoint: "http://localhost:4566".into(),
+        };
+
+        let name = format!("test-{}", random_string(10).to_lowercase());
+        let topic_arn = ensure_topic(region.clone(), name.clone()).await;
+        let queue_url = subscribe_queue(region.clone(), name, topic_arn.clone()).await;
+
+        let config = SnsSinkConfig {
+            topic_arn,
+            region: RegionOrEndpoint::with_endpoint("http://localhost:4566".into()),
+            encoding: Encoding::Text.into(),
+            message_group_id: None,
+            message_deduplication_id: None,
+            message_attributes: Default::default(),
+            request: Default::default(),
+            assume_role: None,
+        };
+
+        let (client, client_region) = config.create_client().unwrap();
+        config
+            .clone()
+            .healthcheck(SnsClient::new_with_client(
+                client.clone(),
+                client_region.clone(),
+            ))
+            .await
+            .unwrap();
+
+        let mut sink = SnsSink::new(config, cx, client, client_region).unwrap();
+
+        let (mut input_lines, events) = random_lines_with_stream(100, 10);
+        sink.send_all(&mut events.map(Ok)).await.unwrap();
+
+        delay_for(Duration::from_secs(1)).await;
+
+        let response = SqsClient::new(region)
+            .receive_message(ReceiveMessageRequest {
+                max_number_of_messages: Some(input_lines.len() as i64),
+                queue_url,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let mut output_lines = response
+            .messages
+            .unwrap()
+            .into_iter()
+            .map(|e| e.body.unwrap())
+            .collect::<Vec<_>>();
+
+        input_lines.sort();
+        output_lines.sort();
+
+        assert_eq!(output_lines, input_lines);
+    }
+
+    async fn ensure_topic(region: Region, name: String) -> String {
+        SnsClient::new(region)
+            .create_topic(CreateTopicInput {
+                name,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .topic_arn
+            .unwrap()
+    }
+
+    /// Subscribes a new queue to the topic, with raw delivery so that
+    /// messages are received as they were published.
+    async fn subscribe_queue(region: Region, name: String, topic_arn: String) -> String {
+        let sqs = SqsClient::new(region.clone());
+        let queue_url = sqs
+            .create_queue(CreateQueueRequest {
+                queue_name: name,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .queue_url
+            .unwrap();
+        let queue_arn = sqs
+            .get_queue_attributes(GetQueueAttributesRequest {
+                attribute_names: Some(vec!["QueueArn".into()]),
+                queue_url: queue_url.clone(),
+            })
+            .await
+            .unwrap()
+            .attributes
+            .unwrap()
+            .remove("QueueArn")
+            .unwrap();
+
+        let mut attributes = HashMap::new();
+        attributes.insert("RawMessageDelivery".to_owned(), "true".to_owned());
+        SnsClient::new(region)
+            .subscribe(SubscribeInput {
+                attributes: Some(attributes),
+                endpoint: Some(queue_arn),
+                protocol: "sqs".into(),
+                topic_arn,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        queue_url
+    }
+}
@@ -1,8 +1,12 @@
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    internal_events::{AwsSqsEventSent, AwsSqsMessageGroupIdMissingKeys},
+    internal_events::{AwsSqsEventSent, AwsSqsMessageTooLarge, AwsSqsMissingKeys},
     rusoto,
     sinks::util::{
+        aws_messages::{
+            message_attributes, message_size, MessageAttribute, MAX_MESSAGE_ATTRIBUTES,
+            MAX_MESSAGE_BYTES,
+        },
         encoding::{EncodingConfig, EncodingConfiguration},
         retries::RetryLogic,
         sink::Response,
@@ -12,11 +16,12 @@ use crate::{
     Event,
 };
 use futures::{future::BoxFuture, stream, FutureExt, Sink, SinkExt, StreamExt, TryFutureExt};
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use rusoto_core::RusotoError;
 use rusoto_sqs::{
-    GetQueueAttributesError, GetQueueAttributesRequest, SendMessageError, SendMessageRequest,
-    SendMessageResult, Sqs, SqsClient,
+    GetQueueAttributesError, GetQueueAttributesRequest, MessageAttributeValue, SendMessageError,
+    SendMessageRequest, SendMessageResult, Sqs, SqsClient,
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -33,8 +38,14 @@ enum BuildError {
     MessageGroupIdMissing,
     #[snafu(display("`message_group_id` is not allowed with non-FIFO queue."))]
     MessageGroupIdNotAllowed,
+    #[snafu(display("`message_deduplication_id` is not allowed with non-FIFO queue."))]
+    MessageDeduplicationIdNotAllowed,
     #[snafu(display("invalid topic template: {}", source))]
     TopicTemplate { source: TemplateError },
+    #[snafu(display("invalid message_deduplication_id template: {}", source))]
+    MessageDeduplicationIdTemplate { source: TemplateError },
+    #[snafu(display("A message can't have more than {} `message_attributes`.", limit))]
+    TooManyMessageAttributes { limit: usize },
 }
 
 #[derive(Debug, Snafu)]
@@ -59,6 +70,10 @@ pub struct SqsSinkConfig {
     pub region: rusoto::RegionOrEndpoint,
     pub encoding: EncodingConfig<Encoding>,
     pub message_group_id: Option<String>,
+    pub message_deduplication_id: Option<String>,
+    /// The names of message attributes, and the fields they're taken from.
+    #[serde(default)]
+    pub message_attributes: IndexMap<String, String>,
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub assume_role: Option<String>,
@@ -147,7 +162,9 @@ impl SqsSink {
         // Currently we do not use batching, so this mostly for future. Also implement `Service` is simpler than `Sink`.
         // https://docs.aws.amazon.com/AWSSimpleQueueService/latest/SQSDeveloperGuide/sqs-batch-api-actions.html
         // Up to 10 events, not more than 256KB as total size.
-        let batch = BatchSettings::default().events(1).bytes(262_144);
+        let batch = BatchSettings::default()
+            .events(1)
+            .bytes(MAX_MESSAGE_BYTES as u64);
 
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
         let encoding = config.encoding;
@@ -158,6 +175,23 @@ impl SqsSink {
             (None, true) => return Err(Box::new(BuildError::MessageGroupIdMissing)),
             (None, false) => None,
         };
+        let message_deduplication_id = match (config.message_deduplication_id, fifo) {
+            (Some(value), true) => {
+                Some(Template::try_from(value).context(MessageDeduplicationIdTemplate)?)
+            }
+            (Some(_), false) => return Err(Box::new(BuildError::MessageDeduplicationIdNotAllowed)),
+            (None, _) => None,
+        };
+        if config.message_attributes.len() > MAX_MESSAGE_ATTRIBUTES {
+            return Err(Box::new(BuildError::TooManyMessageAttributes {
+                limit: MAX_MESSAGE_ATTRIBUTES,
+            }));
+        }
+        let options = MessageOptions {
+            message_group_id,
+            message_deduplication_id,
+            message_attributes: config.message_attributes,
+        };
 
         let sqs = SqsSink {
             client,
@@ -174,7 +208,7 @@ impl SqsSink {
             )
             .sink_map_err(|error| error!(message = "Fatal sqs sink error.", %error))
             .with_flat_map(move |event| {
-                stream::iter(encode_event(event, &encoding, message_group_id.as_ref())).map(Ok)
+                stream::iter(encode_event(event, &encoding, &options)).map(Ok)
             });
 
         Ok(sink)
@@ -196,9 +230,30 @@ impl Service<Vec<SendMessageEntry>> for SqsSink {
         let entry = entries.remove(0);
         let byte_size = entry.message_body.len();
 
+        let message_attributes = if entry.message_attributes.is_empty() {
+            None
+        } else {
+            Some(
+                entry
+                    .message_attributes
+                    .into_iter()
+                    .map(|attribute| {
+                        let value = MessageAttributeValue {
+                            data_type: attribute.data_type.into(),
+                            string_value: Some(attribute.value),
+                            ..Default::default()
+                        };
+                        (attribute.name, value)
+                    })
+                    .collect(),
+            )
+        };
+
         let client = self.client.clone();
         let request = SendMessageRequest {
+            message_attributes,
             message_body: entry.message_body,
+            message_deduplication_id: entry.message_deduplication_id,
             message_group_id: entry.message_group_id,
             queue_url: self.queue_url.clone(),
             ..Default::default()
@@ -219,15 +274,24 @@ impl Service<Vec<SendMessageEntry>> for SqsSink {
     }
 }
 
+#[derive(Debug, Clone, Default)]
+struct MessageOptions {
+    message_group_id: Option<Template>,
+    message_deduplication_id: Option<Template>,
+    message_attributes: IndexMap<String, String>,
+}
+
 #[derive(Debug, Clone)]
 struct SendMessageEntry {
     message_body: String,
     message_group_id: Option<String>,
+    message_deduplication_id: Option<String>,
+    message_attributes: Vec<MessageAttribute>,
 }
 
 impl EncodedLength for SendMessageEntry {
     fn encoded_length(&self) -> usize {
-        self.message_body.len()
+        message_size(&self.message_body, &self.message_attributes)
     }
 }
 
@@ -248,22 +312,23 @@ impl RetryLogic for SqsRetryLogic {
 fn encode_event(
     mut event: Event,
     encoding: &EncodingConfig<Encoding>,
-    message_group_id: Option<&Template>,
+    options: &MessageOptions,
 ) -> Option<SendMessageEntry> {
-    encoding.apply_rules(&mut event);
+    // Attributes and IDs are taken from the event before `except_fields`
+    // removes the fields they come from.
+    let message_group_id = render(
+        options.message_group_id.as_ref(),
+        &event,
+        "message_group_id",
+    )?;
+    let message_deduplication_id = render(
+        options.message_deduplication_id.as_ref(),
+        &event,
+        "message_deduplication_id",
+    )?;
+    let message_attributes = message_attributes(&event, &options.message_attributes);
 
-    let message_group_id = match message_group_id {
-        Some(tpl) => match tpl.render_string(&event) {
-            Ok(value) => Some(value),
-            Err(missing_keys) => {
-                emit!(AwsSqsMessageGroupIdMissingKeys {
-                    keys: &missing_keys
-                });
-                return None;
-            }
-        },
-        None => None,
-    };
+    encoding.apply_rules(&mut event);
 
     let log = event.into_log();
     let message_body = match encoding.codec() {
@@ -274,12 +339,39 @@ fn encode_event(
         Encoding::Json => serde_json::to_string(&log).expect("Error encoding event as json."),
     };
 
+    let byte_size = message_size(&message_body, &message_attributes);
+    if byte_size > MAX_MESSAGE_BYTES {
+        emit!(AwsSqsMessageTooLarge { byte_size });
+        return None;
+    }
+
     Some(SendMessageEntry {
         message_body,
         message_group_id,
+        message_deduplication_id,
+        message_attributes,
     })
 }
 
+/// Renders the template, if there is one. The outer `None` drops the event.
+fn render(
+    template: Option<&Template>,
+    event: &Event,
+    name: &'static str,
+) -> Option<Option<String>> {
+    match template.map(|template| template.render_string(event)) {
+        Some(Ok(value)) => Some(Some(value)),
+        Some(Err(missing_keys)) => {
+            emit!(AwsSqsMissingKeys {
+                template: name,
+                keys: &missing_keys
+            });
+            None
+        }
+        None => Some(None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,7 +380,12 @@ mod tests {
     #[test]
     fn sqs_encode_event_text() {
         let message = "hello world".to_string();
-        let event = encode_event(message.clone().into(), &Encoding::Text.into(), None).unwrap();
+        let event = encode_event(
+            message.clone().into(),
+            &Encoding::Text.into(),
+            &Default::default(),
+        )
+        .unwrap();
 
         assert_eq!(&event.message_body, &message);
     }
@@ -298,13 +395,62 @@ mod tests {
         let message = "hello world".to_string();
         let mut event = Event::from(message.clone());
         event.as_mut_log().insert("key", "value");
-        let event = encode_event(event, &Encoding::Json.into(), None).unwrap();
+        let event = encode_event(event, &Encoding::Json.into(), &Default::default()).unwrap();
 
         let map: BTreeMap<String, String> = serde_json::from_str(&event.message_body).unwrap();
 
         assert_eq!(map[&log_schema().message_key().to_string()], message);
         assert_eq!(map["key"], "value".to_string());
     }
+
+    #[test]
+    fn sqs_encode_event_fifo() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("user", "bob");
+        event.as_mut_log().insert("request_id", "abc");
+        event.as_mut_log().insert("status", 200);
+
+        let mut encoding: EncodingConfig<_> = Encoding::Json.into();
+        encoding.except_fields = Some(vec!["request_id".into(), "status".into()]);
+        let options = MessageOptions {
+            message_group_id: Some(Template::try_from("{{ user }}").unwrap()),
+            message_deduplication_id: Some(Template::try_from("{{ request_id }}").unwrap()),
+            message_attributes: vec![("status".to_owned(), "status".to_owned())]
+                .into_iter()
+                .collect(),
+        };
+        let event = encode_event(event, &encoding, &options).unwrap();
+
+        assert_eq!(event.message_group_id, Some("bob".into()));
+        assert_eq!(event.message_deduplication_id, Some("abc".into()));
+        assert_eq!(
+            event.message_attributes,
+            vec![MessageAttribute {
+                name: "status".into(),
+                data_type: "Number",
+                value: "200".into(),
+            }]
+        );
+        assert!(!event.message_body.contains("request_id"));
+    }
+
+    #[test]
+    fn sqs_encode_event_missing_keys() {
+        let options = MessageOptions {
+            message_deduplication_id: Some(Template::try_from("{{ request_id }}").unwrap()),
+            ..Default::default()
+        };
+        let event = Event::from("hello world");
+
+        assert!(encode_event(event, &Encoding::Text.into(), &options).is_none());
+    }
+
+    #[test]
+    fn sqs_encode_event_too_large() {
+        let event = Event::from("a".repeat(MAX_MESSAGE_BYTES + 1));
+
+        assert!(encode_event(event, &Encoding::Text.into(), &Default::default()).is_none());
+    }
 }
 
 #[cfg(feature = "aws-sqs-integration-tests")]
@@ -337,6 +483,8 @@ mod integration_tests {
             region: rusoto::RegionOrEndpoint::with_endpoint("http://localhost:4566".into()),
             encoding: Encoding::Text.into(),
             message_group_id: None,
+            message_deduplication_id: None,
+            message_attributes: Default::default(),
             request: Default::default(),
             assume_role: None,
         };
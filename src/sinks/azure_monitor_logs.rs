@@ -5,11 +5,13 @@ use crate::{
     sinks::{
         util::{
             encoding::{EncodingConfigWithDefault, EncodingConfiguration},
-            http::{BatchedHttpSink, HttpSink},
-            BatchConfig, BatchSettings, BoxedRawValue, JsonArrayBuffer, TowerRequestConfig,
+            http::{HttpSink, PartitionHttpSink},
+            BatchConfig, BatchSettings, BoxedRawValue, JsonArrayBuffer, PartitionBuffer,
+            PartitionInnerBuffer, TowerRequestConfig,
         },
         Healthcheck, VectorSink,
     },
+    template::Template,
     tls::{TlsOptions, TlsSettings},
 };
 use bytesize::ByteSize;
@@ -23,6 +25,7 @@ use openssl::{base64, hash, pkey, sign};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::convert::TryFrom;
 
 fn default_host() -> String {
     "ods.opinsights.azure.com".into()
@@ -33,7 +36,12 @@ fn default_host() -> String {
 pub struct AzureMonitorLogsConfig {
     pub customer_id: String,
     pub shared_key: String,
+    /// The name of the custom log table, without its `_CL` suffix. May be
+    /// a template, with events batched by their rendered log type.
     pub log_type: String,
+    /// The field Log Analytics takes `TimeGenerated` from, rather than the
+    /// time the events are ingested at. Defaults to the timestamp field.
+    pub time_generated_key: Option<String>,
     pub azure_resource_id: Option<String>,
     #[serde(default = "default_host")]
     pub host: String,
@@ -88,6 +96,8 @@ const X_MS_DATE: &str = "x-ms-date";
 const SHARED_KEY: &str = "SharedKey";
 /// API version
 const API_VERSION: &str = "2016-04-01";
+/// Log type used by the healthcheck when `log_type` is a template
+const HEALTHCHECK_LOG_TYPE: &str = "Vector";
 
 #[async_trait::async_trait]
 #[typetag::serde(name = "azure_monitor_logs")]
@@ -117,14 +127,15 @@ impl SinkConfig for AzureMonitorLogsConfig {
 
         let healthcheck = healthcheck(sink.clone(), client.clone()).boxed();
 
-        let sink = BatchedHttpSink::new(
+        let sink = PartitionHttpSink::new(
             sink,
-            JsonArrayBuffer::new(batch_settings.size),
+            PartitionBuffer::new(JsonArrayBuffer::new(batch_settings.size)),
             request_settings,
             batch_settings.timeout,
             client,
             cx.acker(),
         )
+        .with_dead_letter(cx.dead_letter())
         .sink_map_err(|error| error!(message = "Fatal azure_monitor_logs sink error.", %error));
 
         Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
@@ -137,6 +148,10 @@ impl SinkConfig for AzureMonitorLogsConfig {
     fn sink_type(&self) -> &'static str {
         "azure_monitor_logs"
     }
+
+    fn supports_dead_letter(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Clone)]
@@ -145,40 +160,81 @@ struct AzureMonitorLogsSink {
     customer_id: String,
     encoding: EncodingConfigWithDefault<Encoding>,
     shared_key: pkey::PKey<pkey::Private>,
+    log_type: Template,
+    time_generated_key: String,
     default_headers: HeaderMap,
 }
 
+fn validate_log_type(log_type: &str) -> crate::Result<()> {
+    if log_type.len() > 100 || !LOG_TYPE_REGEX.is_match(log_type) {
+        return Err(format!(
+            "invalid log_type \"{}\": log type can only contain letters, numbers, and underscore (_), and may not exceed 100 characters",
+            log_type
+        ).into());
+    }
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl HttpSink for AzureMonitorLogsSink {
-    type Input = serde_json::Value;
-    type Output = Vec<BoxedRawValue>;
+    type Input = PartitionInnerBuffer<serde_json::Value, String>;
+    type Output = PartitionInnerBuffer<Vec<BoxedRawValue>, String>;
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
+        let log_type = self
+            .log_type
+            .render_string(&event)
+            .map_err(|missing_keys| {
+                warn!(
+                    message = "Keys do not exist on the event; dropping event.",
+                    ?missing_keys,
+                    rate_limit_secs = 30,
+                );
+            })
+            .ok()?;
+        if let Err(error) = validate_log_type(&log_type) {
+            warn!(
+                message = "Rendered an invalid log type; dropping event.",
+                %error,
+                rate_limit_secs = 30,
+            );
+            return None;
+        }
+
         self.encoding.apply_rules(&mut event);
 
         // it seems like Azure Monitor doesn't support full 9-digit nanosecond precision
         // adjust the timestamp format accordingly, keeping only milliseconds
         let mut log = event.into_log();
-        let timestamp_key = log_schema().timestamp_key();
-
-        let timestamp = if let Some(Value::Timestamp(ts)) = log.remove(timestamp_key) {
-            ts
-        } else {
-            chrono::Utc::now()
+        let time_generated_key = self.time_generated_key.as_str();
+
+        let timestamp = match log.remove(time_generated_key) {
+            Some(Value::Timestamp(ts)) => Some(ts),
+            // Events without a timestamp of their own are stamped with the
+            // current time.
+            None if time_generated_key == log_schema().timestamp_key() => Some(chrono::Utc::now()),
+            Some(value) => {
+                log.insert(time_generated_key, value);
+                None
+            }
+            None => None,
         };
 
         let mut entry = serde_json::json!(log);
-        let object_entry = entry.as_object_mut().unwrap();
-        object_entry.insert(
-            timestamp_key.to_string(),
-            JsonValue::String(timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
-        );
+        if let Some(timestamp) = timestamp {
+            let object_entry = entry.as_object_mut().unwrap();
+            object_entry.insert(
+                time_generated_key.to_string(),
+                JsonValue::String(timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+            );
+        }
 
-        Some(entry)
+        Some(PartitionInnerBuffer::new(entry, log_type))
     }
 
     async fn build_request(&self, events: Self::Output) -> crate::Result<Request<Vec<u8>>> {
-        self.build_request_sync(events)
+        let (events, log_type) = events.into_parts();
+        self.build_request_sync(events, &log_type)
     }
 }
 
@@ -198,20 +254,18 @@ impl AzureMonitorLogsSink {
         let shared_key = pkey::PKey::hmac(&shared_key_bytes)?;
         let mut default_headers = HeaderMap::with_capacity(3);
 
-        if config.log_type.len() > 100 || !LOG_TYPE_REGEX.is_match(&config.log_type) {
-            return Err(format!(
-                "invalid log_type \"{}\": log type can only contain letters, numbers, and underscore (_), and may not exceed 100 characters",
-                config.log_type
-            ).into());
+        let log_type = Template::try_from(config.log_type.as_str())?;
+        if !log_type.is_dynamic() {
+            validate_log_type(&config.log_type)?;
         }
 
-        let log_type = HeaderValue::from_str(&config.log_type)?;
-        default_headers.insert(LOG_TYPE_HEADER.clone(), log_type);
-
-        let timestamp_key = log_schema().timestamp_key();
+        let time_generated_key = config
+            .time_generated_key
+            .clone()
+            .unwrap_or_else(|| log_schema().timestamp_key().to_string());
         default_headers.insert(
             TIME_GENERATED_FIELD_HEADER.clone(),
-            HeaderValue::from_str(timestamp_key)?,
+            HeaderValue::from_str(&time_generated_key)?,
         );
 
         if let Some(azure_resource_id) = &config.azure_resource_id {
@@ -232,11 +286,17 @@ impl AzureMonitorLogsSink {
             encoding: config.encoding.clone(),
             customer_id: config.customer_id.clone(),
             shared_key,
+            log_type,
+            time_generated_key,
             default_headers,
         })
     }
 
-    fn build_request_sync(&self, events: Vec<BoxedRawValue>) -> crate::Result<Request<Vec<u8>>> {
+    fn build_request_sync(
+        &self,
+        events: Vec<BoxedRawValue>,
+        log_type: &str,
+    ) -> crate::Result<Request<Vec<u8>>> {
         let body = serde_json::to_vec(&events)?;
         let len = body.len();
 
@@ -254,6 +314,9 @@ impl AzureMonitorLogsSink {
         request
             .headers_mut()
             .insert(X_MS_DATE_HEADER.clone(), rfc1123date.parse()?);
+        request
+            .headers_mut()
+            .insert(LOG_TYPE_HEADER.clone(), HeaderValue::from_str(log_type)?);
 
         Ok(request)
     }
@@ -281,7 +344,14 @@ impl AzureMonitorLogsSink {
 }
 
 async fn healthcheck(sink: AzureMonitorLogsSink, client: HttpClient) -> crate::Result<()> {
-    let request = sink.build_request(vec![]).await?.map(Body::from);
+    // An empty batch doesn't create the table, so any valid log type will
+    // do when it depends on the events.
+    let log_type = if sink.log_type.is_dynamic() {
+        HEALTHCHECK_LOG_TYPE
+    } else {
+        sink.log_type.get_ref()
+    };
+    let request = sink.build_request_sync(vec![], log_type)?.map(Body::from);
 
     let res = client.send(request).await?;
 
@@ -308,6 +378,7 @@ async fn healthcheck(sink: AzureMonitorLogsSink, client: HttpClient) -> crate::R
 mod tests {
     use super::*;
     use crate::event::LogEvent;
+    use chrono::TimeZone;
     use serde_json::value::RawValue;
     use std::iter::FromIterator;
 
@@ -343,7 +414,8 @@ mod tests {
         let (timestamp_key, timestamp_value) = insert_timestamp_kv(&mut log);
 
         let event = Event::from(log);
-        let json = sink.encode_event(event).unwrap();
+        let (json, log_type) = sink.encode_event(event).unwrap().into_parts();
+        assert_eq!(log_type, "Vector");
         let expected_json = serde_json::json!({
             timestamp_key: timestamp_value,
             "message": "hello world"
@@ -372,8 +444,8 @@ mod tests {
         let mut log2 = LogEvent::from_iter([("message", "world")].iter().copied());
         let (timestamp_key2, timestamp_value2) = insert_timestamp_kv(&mut log2);
 
-        let event1 = sink.encode_event(Event::from(log1)).unwrap();
-        let event2 = sink.encode_event(Event::from(log2)).unwrap();
+        let (event1, _) = sink.encode_event(Event::from(log1)).unwrap().into_parts();
+        let (event2, _) = sink.encode_event(Event::from(log2)).unwrap().into_parts();
 
         let json1 = serde_json::to_string(&event1).unwrap();
         let json2 = serde_json::to_string(&event2).unwrap();
@@ -382,7 +454,7 @@ mod tests {
 
         let events = vec![raw1, raw2];
 
-        let request = sink.build_request_sync(events);
+        let request = sink.build_request_sync(events, "Vector");
 
         let (parts, body) = request.unwrap().into_parts();
         assert_eq!(&parts.method.to_string(), "POST");
@@ -429,6 +501,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn partitions_by_log_type() {
+        let config: AzureMonitorLogsConfig = toml::from_str(
+            r#"
+            customer_id = "97ce69d9-b4be-4241-8dbd-d265edcf06c4"
+            shared_key = "SERsIYhgMVlJB6uPsq49gCxNiruf6v0vhMYE+lfzbSGcXjdViZdV/e5pEMTYtw9f8SkVLf4LFlLCc2KxtRZfCA=="
+            log_type = "Vector_{{ app }}"
+        "#,
+        )
+        .unwrap();
+        let sink = AzureMonitorLogsSink::new(&config).unwrap();
+
+        let mut log = LogEvent::from_iter([("message", "hello"), ("app", "api")].iter().copied());
+        insert_timestamp_kv(&mut log);
+        let (_, log_type) = sink.encode_event(Event::from(log)).unwrap().into_parts();
+        assert_eq!(log_type, "Vector_api");
+
+        // Rendered log types are validated too.
+        let log = LogEvent::from_iter([("message", "hello"), ("app", "a-b")].iter().copied());
+        assert!(sink.encode_event(Event::from(log)).is_none());
+        let log = LogEvent::from_iter([("message", "hello")].iter().copied());
+        assert!(sink.encode_event(Event::from(log)).is_none());
+
+        let request = sink.build_request_sync(vec![], "Vector_api").unwrap();
+        assert_eq!(request.headers()["log-type"], "Vector_api");
+    }
+
+    #[test]
+    fn designates_time_generated_field() {
+        let config: AzureMonitorLogsConfig = toml::from_str(
+            r#"
+            customer_id = "97ce69d9-b4be-4241-8dbd-d265edcf06c4"
+            shared_key = "SERsIYhgMVlJB6uPsq49gCxNiruf6v0vhMYE+lfzbSGcXjdViZdV/e5pEMTYtw9f8SkVLf4LFlLCc2KxtRZfCA=="
+            log_type = "Vector"
+            time_generated_key = "occurred_at"
+        "#,
+        )
+        .unwrap();
+        let sink = AzureMonitorLogsSink::new(&config).unwrap();
+
+        let occurred_at = chrono::Utc
+            .ymd(2020, 11, 1)
+            .and_hms_nano(12, 30, 0, 123_456_789);
+        let mut log = LogEvent::from_iter([("message", "hello")].iter().copied());
+        log.insert("occurred_at", occurred_at);
+        let (json, _) = sink.encode_event(Event::from(log)).unwrap().into_parts();
+        assert_eq!(json["occurred_at"], "2020-11-01T12:30:00.123Z");
+
+        let request = sink.build_request_sync(vec![], "Vector").unwrap();
+        assert_eq!(request.headers()["time-generated-field"], "occurred_at");
+    }
+
     #[tokio::test]
     async fn fails_missing_creds() {
         let config: AzureMonitorLogsConfig = toml::from_str(
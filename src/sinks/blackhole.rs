@@ -2,19 +2,26 @@ use crate::{
     buffers::Acker,
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     emit,
-    internal_events::BlackholeEventReceived,
+    internal_events::{BlackholeEventFailed, BlackholeEventReceived, BlackholeThroughput},
     sinks::util::StreamSink,
     Event,
 };
 use async_trait::async_trait;
 use futures::{future, stream::BoxStream, FutureExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{collections::VecDeque, time::Duration};
+use tokio::time::{delay_until, interval_at, Instant, Interval};
 
 pub struct BlackholeSink {
     total_events: usize,
     total_raw_bytes: usize,
     config: BlackholeConfig,
     acker: Acker,
+    /// Counts since the last throughput report.
+    window: Window,
+    /// The times events are acknowledged at, in order of receipt.
+    in_flight: VecDeque<Instant>,
 }
 
 #[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
@@ -24,6 +31,36 @@ pub struct BlackholeConfig {
     #[derivative(Default(value = "1000"))]
     #[serde(default = "default_print_amount")]
     pub print_amount: usize,
+    /// Reports events and bytes per second at this interval.
+    pub report_interval_secs: Option<u64>,
+    /// Delays the acknowledgement of each event, as a downstream service
+    /// with this latency would.
+    pub latency_ms: u64,
+    /// The share of events, between 0 and 1, that fail to be delivered.
+    pub failure_rate: f64,
+    pub on_failure: FailureAction,
+    /// Never acknowledging events holds them in disk buffers, as a
+    /// downstream outage would.
+    #[derivative(Default(value = "true"))]
+    #[serde(default = "crate::serde::default_true")]
+    pub acknowledge: bool,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureAction {
+    /// Acknowledges and discards the event, as it's lost.
+    #[derivative(Default)]
+    Drop,
+    /// Stops the sink with an error.
+    Stop,
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("failure_rate must be between 0 and 1, got {}", rate))]
+    InvalidFailureRate { rate: f64 },
 }
 
 fn default_print_amount() -> usize {
@@ -47,6 +84,13 @@ impl SinkConfig for BlackholeConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        if !(0.0..=1.0).contains(&self.failure_rate) {
+            return Err(BuildError::InvalidFailureRate {
+                rate: self.failure_rate,
+            }
+            .into());
+        }
+
         let sink = BlackholeSink::new(self.clone(), cx.acker());
         let healthcheck = future::ok(()).boxed();
 
@@ -69,36 +113,135 @@ impl BlackholeSink {
             total_events: 0,
             total_raw_bytes: 0,
             acker,
+            window: Window::new(),
+            in_flight: VecDeque::new(),
+        }
+    }
+
+    fn receive(&mut self, event: Event) -> Result<(), ()> {
+        if self.config.failure_rate > 0.0 && rand::random::<f64>() < self.config.failure_rate {
+            emit!(BlackholeEventFailed {
+                action: self.config.on_failure
+            });
+            return match self.config.on_failure {
+                FailureAction::Drop => {
+                    self.acknowledge();
+                    Ok(())
+                }
+                FailureAction::Stop => Err(()),
+            };
+        }
+
+        let message_len = match event {
+            Event::Log(log) => serde_json::to_string(&log),
+            Event::Metric(metric) => serde_json::to_string(&metric),
+        }
+        .map(|v| v.len())
+        .unwrap_or(0);
+
+        self.total_events += 1;
+        self.total_raw_bytes += message_len;
+        self.window.events += 1;
+        self.window.bytes += message_len;
+
+        emit!(BlackholeEventReceived {
+            byte_size: message_len
+        });
+
+        if self.total_events % self.config.print_amount == 0 {
+            info!({
+                events = self.total_events,
+                raw_bytes_collected = self.total_raw_bytes
+            }, "Total events collected");
+        }
+
+        self.acknowledge();
+        Ok(())
+    }
+
+    fn acknowledge(&mut self) {
+        if !self.config.acknowledge {
+            return;
+        }
+        if self.config.latency_ms == 0 {
+            self.acker.ack(1);
+        } else {
+            let latency = Duration::from_millis(self.config.latency_ms);
+            self.in_flight.push_back(Instant::now() + latency);
+        }
+    }
+
+    /// Acknowledges the events whose latency has passed.
+    fn acknowledge_due(&mut self, now: Instant) {
+        let due = self.in_flight.iter().take_while(|at| **at <= now).count();
+        if due > 0 {
+            self.in_flight.drain(..due);
+            self.acker.ack(due);
+        }
+    }
+
+    fn report(&mut self) {
+        let window = std::mem::replace(&mut self.window, Window::new());
+        let elapsed = window.start.elapsed().as_secs_f64();
+        if elapsed > 0.0 {
+            emit!(BlackholeThroughput {
+                events_per_sec: window.events as f64 / elapsed,
+                bytes_per_sec: window.bytes as f64 / elapsed,
+                in_flight: self.in_flight.len(),
+            });
         }
     }
 }
 
+struct Window {
+    start: Instant,
+    events: usize,
+    bytes: usize,
+}
+
+impl Window {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            events: 0,
+            bytes: 0,
+        }
+    }
+}
+
+/// Never completes without an interval, disabling reports.
+async fn tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => future::pending().await,
+    }
+}
+
 #[async_trait]
 impl StreamSink for BlackholeSink {
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
-        while let Some(event) = input.next().await {
-            let message_len = match event {
-                Event::Log(log) => serde_json::to_string(&log),
-                Event::Metric(metric) => serde_json::to_string(&metric),
-            }
-            .map(|v| v.len())
-            .unwrap_or(0);
-
-            self.total_events += 1;
-            self.total_raw_bytes += message_len;
+        let mut reports = self.config.report_interval_secs.map(|secs| {
+            // The first tick of an interval is immediate.
+            let period = Duration::from_secs(secs);
+            interval_at(Instant::now() + period, period)
+        });
+        let mut input_done = false;
 
-            emit!(BlackholeEventReceived {
-                byte_size: message_len
-            });
-
-            if self.total_events % self.config.print_amount == 0 {
-                info!({
-                    events = self.total_events,
-                    raw_bytes_collected = self.total_raw_bytes
-                }, "Total events collected");
+        // Events still in flight are acknowledged before the sink finishes.
+        while !input_done || !self.in_flight.is_empty() {
+            let next_ack = self.in_flight.front().copied();
+            tokio::select! {
+                event = input.next(), if !input_done => match event {
+                    Some(event) => self.receive(event)?,
+                    None => input_done = true,
+                },
+                _ = delay_until(next_ack.unwrap_or_else(Instant::now)), if next_ack.is_some() => {
+                    self.acknowledge_due(Instant::now());
+                }
+                _ = tick(&mut reports) => self.report(),
             }
-
-            self.acker.ack(1);
         }
         Ok(())
     }
@@ -108,6 +251,7 @@ impl StreamSink for BlackholeSink {
 mod tests {
     use super::*;
     use crate::test_util::random_events_with_stream;
+    use std::sync::atomic::Ordering;
 
     #[test]
     fn generate_config() {
@@ -116,10 +260,70 @@ mod tests {
 
     #[tokio::test]
     async fn blackhole() {
-        let config = BlackholeConfig { print_amount: 10 };
+        let config = BlackholeConfig {
+            print_amount: 10,
+            ..Default::default()
+        };
         let mut sink = BlackholeSink::new(config, Acker::Null);
 
         let (_input_lines, events) = random_events_with_stream(100, 10);
         let _ = sink.run(Box::pin(events)).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn acknowledges_after_latency() {
+        tokio::time::pause();
+        let config = BlackholeConfig {
+            latency_ms: 100,
+            ..Default::default()
+        };
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let mut sink = BlackholeSink::new(config, acker);
+
+        sink.receive(Event::from("a")).unwrap();
+        sink.receive(Event::from("b")).unwrap();
+        sink.acknowledge_due(Instant::now());
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 0);
+
+        tokio::time::advance(Duration::from_millis(100)).await;
+        sink.acknowledge_due(Instant::now());
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 2);
+        assert!(sink.in_flight.is_empty());
+    }
+
+    #[tokio::test]
+    async fn injects_failures() {
+        let config = BlackholeConfig {
+            failure_rate: 1.0,
+            ..Default::default()
+        };
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let mut sink = BlackholeSink::new(config, acker);
+        sink.receive(Event::from("a")).unwrap();
+        assert_eq!(sink.total_events, 0);
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 1);
+
+        let config = BlackholeConfig {
+            failure_rate: 1.0,
+            on_failure: FailureAction::Stop,
+            ..Default::default()
+        };
+        let mut sink = BlackholeSink::new(config, Acker::Null);
+        let (_input_lines, events) = random_events_with_stream(100, 10);
+        assert!(sink.run(Box::pin(events)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn withholds_acknowledgements() {
+        let config = BlackholeConfig {
+            acknowledge: false,
+            ..Default::default()
+        };
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let mut sink = BlackholeSink::new(config, acker);
+        let (_input_lines, events) = random_events_with_stream(100, 10);
+        sink.run(Box::pin(events)).await.unwrap();
+        assert_eq!(sink.total_events, 10);
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 0);
+    }
 }
@@ -4,19 +4,32 @@ use crate::{
     http::{Auth, HttpClient},
     sinks::util::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpRetryLogic, HttpSink},
+        http::{HttpRetryLogic, HttpSink, PartitionHttpSink},
         retries::{RetryAction, RetryLogic},
-        BatchConfig, BatchSettings, Buffer, Compression, TowerRequestConfig,
+        BatchConfig, BatchSettings, Buffer, Compression, PartitionBuffer, PartitionInnerBuffer,
+        TowerRequestConfig,
     },
+    template::Template,
     tls::{TlsOptions, TlsSettings},
 };
 use bytes::Bytes;
 use futures::{FutureExt, SinkExt};
 use http::{Request, StatusCode, Uri};
 use hyper::Body;
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
-use snafu::ResultExt;
+use snafu::{ResultExt, Snafu};
+
+mod native;
+
+use self::native::NativeEncoder;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("The native format needs the table's `columns`"))]
+    MissingColumns,
+}
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
@@ -24,8 +37,19 @@ pub struct ClickhouseConfig {
     // Deprecated name
     #[serde(alias = "host")]
     pub endpoint: String,
-    pub table: String,
-    pub database: Option<String>,
+    pub table: Template,
+    pub database: Option<Template>,
+    #[serde(default)]
+    pub format: Format,
+    /// The names and types of the columns written by the native format.
+    #[serde(default)]
+    pub columns: IndexMap<String, String>,
+    /// Let ClickHouse buffer the inserted rows server side.
+    #[serde(default)]
+    pub async_insert: bool,
+    /// Whether responses to asynchronous inserts wait until the rows are
+    /// written. The server's setting is used if unset.
+    pub wait_for_async_insert: Option<bool>,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
     #[serde(
@@ -61,6 +85,32 @@ pub enum Encoding {
     Default,
 }
 
+/// The format rows are inserted in.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Format {
+    #[derivative(Default)]
+    JsonEachRow,
+    Native,
+}
+
+impl Format {
+    fn name(self) -> &'static str {
+        match self {
+            Format::JsonEachRow => "JSONEachRow",
+            Format::Native => "Native",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Format::JsonEachRow => "application/x-ndjson",
+            Format::Native => "application/octet-stream",
+        }
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "clickhouse")]
 impl SinkConfig for ClickhouseConfig {
@@ -76,15 +126,27 @@ impl SinkConfig for ClickhouseConfig {
         let tls_settings = TlsSettings::from_options(&self.tls)?;
         let client = HttpClient::new(tls_settings)?;
 
-        let sink = BatchedHttpSink::with_retry_logic(
-            self.clone(),
-            Buffer::new(batch.size, self.compression),
+        let native = match self.format {
+            Format::Native if self.columns.is_empty() => {
+                return Err(BuildError::MissingColumns.into())
+            }
+            Format::Native => Some(NativeEncoder::new(&self.columns)?),
+            Format::JsonEachRow => None,
+        };
+
+        let sink = PartitionHttpSink::with_retry_logic(
+            ClickhouseSink {
+                config: self.clone(),
+                native,
+            },
+            PartitionBuffer::new(Buffer::new(batch.size, self.compression)),
             ClickhouseRetryLogic::default(),
             request,
             batch.timeout,
             client.clone(),
             cx.acker(),
         )
+        .with_dead_letter(cx.dead_letter())
         .sink_map_err(|error| error!(message = "Fatal clickhouse sink error.", %error));
 
         let healthcheck = healthcheck(client, self.clone()).boxed();
@@ -99,41 +161,108 @@ impl SinkConfig for ClickhouseConfig {
     fn sink_type(&self) -> &'static str {
         "clickhouse"
     }
+
+    fn supports_dead_letter(&self) -> bool {
+        true
+    }
 }
 
-#[async_trait::async_trait]
-impl HttpSink for ClickhouseConfig {
-    type Input = Vec<u8>;
-    type Output = Vec<u8>;
+/// The database and table the events are inserted into.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PartitionKey {
+    database: String,
+    table: String,
+}
 
-    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
-        self.encoding.apply_rules(&mut event);
+struct ClickhouseSink {
+    config: ClickhouseConfig,
+    native: Option<NativeEncoder>,
+}
 
-        let mut body =
-            serde_json::to_vec(&event.as_log().all_fields()).expect("Events should be valid json!");
-        body.push(b'\n');
+impl ClickhouseSink {
+    fn render_key(&self, event: &Event) -> Result<PartitionKey, Vec<String>> {
+        let database = match &self.config.database {
+            Some(database) => database.render_string(event)?,
+            None => "default".into(),
+        };
+        let table = self.config.table.render_string(event)?;
+        Ok(PartitionKey { database, table })
+    }
 
-        Some(body)
+    /// The ClickHouse settings sent with each insert.
+    fn settings(&self) -> Vec<(&'static str, &'static str)> {
+        let mut settings = Vec::new();
+        if self.config.async_insert {
+            settings.push(("async_insert", "1"));
+            if let Some(wait) = self.config.wait_for_async_insert {
+                settings.push(("wait_for_async_insert", if wait { "1" } else { "0" }));
+            }
+        }
+        settings
     }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for ClickhouseSink {
+    type Input = PartitionInnerBuffer<Vec<u8>, PartitionKey>;
+    type Output = PartitionInnerBuffer<Vec<u8>, PartitionKey>;
 
-    async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
-        let database = if let Some(database) = &self.database {
-            database.as_str()
-        } else {
-            "default"
+    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
+        let key = self
+            .render_key(&event)
+            .map_err(|missing_keys| {
+                warn!(
+                    message = "Keys do not exist on the event; dropping event.",
+                    ?missing_keys,
+                    rate_limit_secs = 30,
+                );
+            })
+            .ok()?;
+
+        self.config.encoding.apply_rules(&mut event);
+
+        let body = match &self.native {
+            Some(native) => native
+                .encode(event.as_log())
+                .map_err(|error| {
+                    warn!(
+                        message = "Failed to encode event; dropping event.",
+                        %error,
+                        rate_limit_secs = 30,
+                    );
+                })
+                .ok()?,
+            None => {
+                let mut body = serde_json::to_vec(&event.as_log().all_fields())
+                    .expect("Events should be valid json!");
+                body.push(b'\n');
+                body
+            }
         };
 
-        let uri = encode_uri(&self.endpoint, database, &self.table).expect("Unable to encode uri");
+        Some(PartitionInnerBuffer::new(body, key))
+    }
+
+    async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        let (events, key) = output.into_parts();
+        let uri = encode_uri(
+            &self.config.endpoint,
+            &key.database,
+            &key.table,
+            self.config.format,
+            &self.settings(),
+        )?;
 
-        let mut builder = Request::post(&uri).header("Content-Type", "application/x-ndjson");
+        let mut builder =
+            Request::post(&uri).header("Content-Type", self.config.format.content_type());
 
-        if let Some(ce) = self.compression.content_encoding() {
+        if let Some(ce) = self.config.compression.content_encoding() {
             builder = builder.header("Content-Encoding", ce);
         }
 
         let mut request = builder.body(events).unwrap();
 
-        if let Some(auth) = &self.auth {
+        if let Some(auth) = &self.config.auth {
             auth.apply(&mut request);
         }
 
@@ -158,18 +287,28 @@ async fn healthcheck(client: HttpClient, config: ClickhouseConfig) -> crate::Res
     }
 }
 
-fn encode_uri(host: &str, database: &str, table: &str) -> crate::Result<Uri> {
-    let query = url::form_urlencoded::Serializer::new(String::new())
-        .append_pair(
-            "query",
-            format!(
-                "INSERT INTO \"{}\".\"{}\" FORMAT JSONEachRow",
-                database,
-                table.replace("\"", "\\\"")
-            )
-            .as_str(),
+fn encode_uri(
+    host: &str,
+    database: &str,
+    table: &str,
+    format: Format,
+    settings: &[(&str, &str)],
+) -> crate::Result<Uri> {
+    let mut query = url::form_urlencoded::Serializer::new(String::new());
+    query.append_pair(
+        "query",
+        format!(
+            "INSERT INTO \"{}\".\"{}\" FORMAT {}",
+            database.replace("\"", "\\\""),
+            table.replace("\"", "\\\""),
+            format.name()
         )
-        .finish();
+        .as_str(),
+    );
+    for (name, value) in settings {
+        query.append_pair(name, value);
+    }
+    let query = query.finish();
 
     let url = if host.ends_with('/') {
         format!("{}?{}", host, query)
@@ -180,6 +319,52 @@ fn encode_uri(host: &str, database: &str, table: &str) -> crate::Result<Uri> {
     Ok(url.parse::<Uri>().context(super::UriParseError)?)
 }
 
+/// ClickHouse errors that retrying won't fix, mostly caused by data that
+/// doesn't fit the table, or by the configuration.
+///
+/// Error code definitions: https://github.com/ClickHouse/ClickHouse/blob/master/src/Common/ErrorCodes.cpp
+const PERMANENT_ERROR_CODES: &[u32] = &[
+    6,   // CANNOT_PARSE_TEXT
+    8,   // THERE_IS_NO_COLUMN
+    16,  // NO_SUCH_COLUMN_IN_TABLE
+    26,  // CANNOT_PARSE_QUOTED_STRING
+    27,  // CANNOT_PARSE_INPUT_ASSERTION_FAILED
+    33,  // CANNOT_READ_ALL_DATA
+    38,  // CANNOT_PARSE_DATE
+    41,  // CANNOT_PARSE_DATETIME
+    53,  // TYPE_MISMATCH
+    60,  // UNKNOWN_TABLE
+    62,  // SYNTAX_ERROR
+    70,  // CANNOT_CONVERT_TYPE
+    72,  // CANNOT_PARSE_NUMBER
+    73,  // UNKNOWN_FORMAT
+    81,  // UNKNOWN_DATABASE
+    115, // UNKNOWN_SETTING
+    117, // INCORRECT_DATA
+    164, // READONLY
+    192, // UNKNOWN_USER
+    193, // WRONG_PASSWORD
+    497, // ACCESS_DENIED
+    516, // AUTHENTICATION_FAILED
+];
+
+/// Finds the ClickHouse error code of a failed request, from the
+/// `X-ClickHouse-Exception-Code` header or the `Code: N` the body starts
+/// with.
+fn error_code(response: &http::Response<Bytes>) -> Option<u32> {
+    if let Some(code) = response.headers().get("X-ClickHouse-Exception-Code") {
+        return code.to_str().ok()?.trim().parse().ok();
+    }
+
+    let body = response.body();
+    if !body.starts_with(b"Code: ") {
+        return None;
+    }
+    let code = &body[6..];
+    let digits = code.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    std::str::from_utf8(&code[..digits]).ok()?.parse().ok()
+}
+
 #[derive(Debug, Default, Clone)]
 struct ClickhouseRetryLogic {
     inner: HttpRetryLogic,
@@ -194,26 +379,14 @@ impl RetryLogic for ClickhouseRetryLogic {
     }
 
     fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
-        match response.status() {
-            StatusCode::INTERNAL_SERVER_ERROR => {
-                let body = response.body();
-
-                // Currently, clickhouse returns 500's incorrect data and type mismatch errors.
-                // This attempts to check if the body starts with `Code: {code_num}` and to not
-                // retry those errors.
-                //
-                // Reference: https://github.com/timberio/vector/pull/693#issuecomment-517332654
-                // Error code definitions: https://github.com/ClickHouse/ClickHouse/blob/master/dbms/src/Common/ErrorCodes.cpp
-                //
-                // Fix already merged: https://github.com/ClickHouse/ClickHouse/pull/6271
-                if body.starts_with(b"Code: 117") {
-                    RetryAction::DontRetry("incorrect data".into())
-                } else if body.starts_with(b"Code: 53") {
-                    RetryAction::DontRetry("type mismatch".into())
-                } else {
-                    RetryAction::Retry(String::from_utf8_lossy(body).to_string())
-                }
-            }
+        // ClickHouse returns 500 for many errors caused by the data, which
+        // would otherwise be retried forever.
+        match error_code(response) {
+            Some(code) if PERMANENT_ERROR_CODES.contains(&code) => RetryAction::DontRetry(format!(
+                "ClickHouse error {}: {}",
+                code,
+                String::from_utf8_lossy(response.body())
+            )),
             _ => self.inner.should_retry_response(response),
         }
     }
@@ -230,16 +403,125 @@ mod tests {
 
     #[test]
     fn encode_valid() {
-        let uri = encode_uri("http://localhost:80", "my_database", "my_table").unwrap();
+        let uri = encode_uri(
+            "http://localhost:80",
+            "my_database",
+            "my_table",
+            Format::JsonEachRow,
+            &[],
+        )
+        .unwrap();
         assert_eq!(uri, "http://localhost:80/?query=INSERT+INTO+%22my_database%22.%22my_table%22+FORMAT+JSONEachRow");
 
-        let uri = encode_uri("http://localhost:80", "my_database", "my_\"table\"").unwrap();
+        let uri = encode_uri(
+            "http://localhost:80",
+            "my_database",
+            "my_\"table\"",
+            Format::JsonEachRow,
+            &[],
+        )
+        .unwrap();
         assert_eq!(uri, "http://localhost:80/?query=INSERT+INTO+%22my_database%22.%22my_%5C%22table%5C%22%22+FORMAT+JSONEachRow");
     }
 
+    #[test]
+    fn encode_native_with_settings() {
+        let uri = encode_uri(
+            "http://localhost:80/",
+            "logs",
+            "events",
+            Format::Native,
+            &[("async_insert", "1"), ("wait_for_async_insert", "0")],
+        )
+        .unwrap();
+        assert_eq!(uri, "http://localhost:80/?query=INSERT+INTO+%22logs%22.%22events%22+FORMAT+Native&async_insert=1&wait_for_async_insert=0");
+    }
+
     #[test]
     fn encode_invalid() {
-        encode_uri("localhost:80", "my_database", "my_table").unwrap_err();
+        encode_uri(
+            "localhost:80",
+            "my_database",
+            "my_table",
+            Format::JsonEachRow,
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn renders_partition_key() {
+        let config: ClickhouseConfig = toml::from_str(
+            r#"
+            endpoint = "http://localhost:8123"
+            database = "logs_{{ team }}"
+            table = "{{ service }}"
+            "#,
+        )
+        .unwrap();
+        let sink = ClickhouseSink {
+            config,
+            native: None,
+        };
+
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("team", "web");
+        event.as_mut_log().insert("service", "api");
+        assert_eq!(
+            sink.render_key(&event).unwrap(),
+            PartitionKey {
+                database: "logs_web".into(),
+                table: "api".into(),
+            }
+        );
+
+        event.as_mut_log().remove("service");
+        assert!(sink.encode_event(event).is_none());
+    }
+
+    fn response(status: StatusCode, code: Option<&str>, body: &str) -> http::Response<Bytes> {
+        let mut builder = http::Response::builder().status(status);
+        if let Some(code) = code {
+            builder = builder.header("X-ClickHouse-Exception-Code", code);
+        }
+        builder.body(Bytes::from(body.to_owned())).unwrap()
+    }
+
+    #[test]
+    fn retries_depend_on_error_code() {
+        let logic = ClickhouseRetryLogic::default();
+
+        let incorrect_data = response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            None,
+            "Code: 117, e.displayText() = DB::Exception: Unknown field",
+        );
+        assert!(matches!(
+            logic.should_retry_response(&incorrect_data),
+            RetryAction::DontRetry(_)
+        ));
+
+        let unknown_table = response(StatusCode::NOT_FOUND, Some("60"), "Table doesn't exist");
+        assert!(matches!(
+            logic.should_retry_response(&unknown_table),
+            RetryAction::DontRetry(_)
+        ));
+
+        let too_many_parts = response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some("252"),
+            "Code: 252, Too many parts",
+        );
+        assert!(matches!(
+            logic.should_retry_response(&too_many_parts),
+            RetryAction::Retry(_)
+        ));
+
+        let ok = response(StatusCode::OK, None, "");
+        assert!(matches!(
+            logic.should_retry_response(&ok),
+            RetryAction::Successful
+        ));
     }
 }
 
@@ -256,7 +538,7 @@ mod integration_tests {
     use futures::{future, stream};
     use serde_json::Value;
     use std::{
-        convert::Infallible,
+        convert::{Infallible, TryInto},
         future::ready,
         net::SocketAddr,
         sync::{
@@ -276,7 +558,7 @@ mod integration_tests {
 
         let config = ClickhouseConfig {
             endpoint: host.clone(),
-            table: table.clone(),
+            table: table.as_str().try_into().unwrap(),
             compression: Compression::None,
             batch: BatchConfig {
                 max_events: Some(1),
@@ -321,7 +603,7 @@ mod integration_tests {
 
         let config = ClickhouseConfig {
             endpoint: host.clone(),
-            table: table.clone(),
+            table: table.as_str().try_into().unwrap(),
             compression: Compression::None,
             encoding,
             batch: BatchConfig {
@@ -433,6 +715,65 @@ timestamp_format = "unix""#,
         assert_eq!(expected, output.data[0]);
     }
 
+    #[tokio::test]
+    async fn insert_events_native() {
+        trace_init();
+
+        let table = gen_table();
+        let host = String::from("http://localhost:8123");
+
+        let config: ClickhouseConfig = toml::from_str(&format!(
+            r#"
+endpoint = "{}"
+table = "{}"
+format = "native"
+compression = "none"
+[columns]
+host = "String"
+timestamp = "DateTime64(6, 'UTC')"
+message = "String"
+[request]
+retry_attempts = 1
+[batch]
+max_events = 2"#,
+            host, table
+        ))
+        .unwrap();
+
+        let client = ClickhouseClient::new(host);
+        client
+            .create_table(
+                &table,
+                "host String, timestamp DateTime64(6, 'UTC'), message String",
+            )
+            .await;
+
+        let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let events = (0..3)
+            .map(|index| {
+                let mut event = Event::from(format!("raw log line {}", index));
+                event.as_mut_log().insert("host", "example.com");
+                event
+            })
+            .collect::<Vec<_>>();
+
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let output = client.select_all(&table).await;
+        assert_eq!(3, output.rows);
+        let mut messages = output
+            .data
+            .iter()
+            .map(|row| row["message"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        messages.sort();
+        assert_eq!(
+            messages,
+            vec!["raw log line 0", "raw log line 1", "raw log line 2"]
+        );
+    }
+
     #[tokio::test]
     async fn no_retry_on_incorrect_data() {
         trace_init();
@@ -442,7 +783,7 @@ timestamp_format = "unix""#,
 
         let config = ClickhouseConfig {
             endpoint: host.clone(),
-            table: table.clone(),
+            table: table.as_str().try_into().unwrap(),
             compression: Compression::None,
             batch: BatchConfig {
                 max_events: Some(1),
@@ -495,7 +836,7 @@ timestamp_format = "unix""#,
 
         let config = ClickhouseConfig {
             endpoint: host,
-            table: gen_table(),
+            table: gen_table().try_into().unwrap(),
             batch: BatchConfig {
                 max_events: Some(1),
                 ..Default::default()
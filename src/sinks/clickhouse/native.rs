@@ -0,0 +1,353 @@
+//! Encoding of events in ClickHouse's `Native` format. Every event is
+//! encoded as a block of its own, as the format is columnar; ClickHouse
+//! squashes the blocks into larger ones on insert.
+
+use crate::event::{LogEvent, Value};
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use snafu::Snafu;
+use std::convert::TryFrom;
+
+#[derive(Debug, Snafu)]
+pub enum NativeError {
+    #[snafu(display("Unsupported column type {:?} for column {:?}", type_name, column))]
+    UnsupportedType { column: String, type_name: String },
+    #[snafu(display("Can't convert {:?} to {} for column {:?}", value, type_name, column))]
+    Conversion {
+        column: String,
+        type_name: String,
+        value: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ColumnType {
+    String,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Float32,
+    Float64,
+    Bool,
+    DateTime,
+    /// The precision, as a power of ten of a second.
+    DateTime64(u32),
+    Nullable(Box<ColumnType>),
+}
+
+impl ColumnType {
+    fn parse(type_name: &str) -> Option<Self> {
+        let type_name = type_name.trim();
+        if let Some(inner) = strip_wrapper(type_name, "Nullable") {
+            return Self::parse(inner).map(|inner| ColumnType::Nullable(Box::new(inner)));
+        }
+        if let Some(args) = strip_wrapper(type_name, "DateTime64") {
+            let precision = args.split(',').next()?.trim().parse().ok()?;
+            return if precision <= 9 {
+                Some(ColumnType::DateTime64(precision))
+            } else {
+                None
+            };
+        }
+        // The time zone only affects how the value is displayed.
+        if type_name == "DateTime" || strip_wrapper(type_name, "DateTime").is_some() {
+            return Some(ColumnType::DateTime);
+        }
+
+        Some(match type_name {
+            "String" => ColumnType::String,
+            "Int8" => ColumnType::Int8,
+            "Int16" => ColumnType::Int16,
+            "Int32" => ColumnType::Int32,
+            "Int64" => ColumnType::Int64,
+            "UInt8" => ColumnType::UInt8,
+            "UInt16" => ColumnType::UInt16,
+            "UInt32" => ColumnType::UInt32,
+            "UInt64" => ColumnType::UInt64,
+            "Float32" => ColumnType::Float32,
+            "Float64" => ColumnType::Float64,
+            "Bool" => ColumnType::Bool,
+            _ => return None,
+        })
+    }
+}
+
+/// Returns the arguments of `wrapper(...)`.
+fn strip_wrapper<'a>(type_name: &'a str, wrapper: &str) -> Option<&'a str> {
+    type_name
+        .strip_prefix(wrapper)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+#[derive(Clone, Debug)]
+struct Column {
+    name: String,
+    /// The type as configured, which is what's sent to ClickHouse.
+    type_name: String,
+    column_type: ColumnType,
+}
+
+#[derive(Clone, Debug)]
+pub struct NativeEncoder {
+    columns: Vec<Column>,
+}
+
+impl NativeEncoder {
+    pub fn new(columns: &IndexMap<String, String>) -> Result<Self, NativeError> {
+        let columns = columns
+            .iter()
+            .map(|(name, type_name)| match ColumnType::parse(type_name) {
+                Some(column_type) => Ok(Column {
+                    name: name.clone(),
+                    type_name: type_name.clone(),
+                    column_type,
+                }),
+                None => Err(NativeError::UnsupportedType {
+                    column: name.clone(),
+                    type_name: type_name.clone(),
+                }),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { columns })
+    }
+
+    /// Encodes the event as a block with one row. Missing fields get the
+    /// column type's default value.
+    pub fn encode(&self, log: &LogEvent) -> Result<Vec<u8>, NativeError> {
+        let mut block = Vec::new();
+        write_varuint(&mut block, self.columns.len() as u64);
+        write_varuint(&mut block, 1);
+        for column in &self.columns {
+            write_string(&mut block, column.name.as_bytes());
+            write_string(&mut block, column.type_name.as_bytes());
+            write_value(&mut block, &column.column_type, log.get(&column.name)).map_err(
+                |value| NativeError::Conversion {
+                    column: column.name.clone(),
+                    type_name: column.type_name.clone(),
+                    value,
+                },
+            )?;
+        }
+        Ok(block)
+    }
+}
+
+fn write_varuint(buffer: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buffer.push(byte);
+            return;
+        }
+        buffer.push(byte | 0x80);
+    }
+}
+
+fn write_string(buffer: &mut Vec<u8>, value: &[u8]) {
+    write_varuint(buffer, value.len() as u64);
+    buffer.extend_from_slice(value);
+}
+
+fn as_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Integer(value) => Some(*value),
+        Value::Float(value) => Some(*value as i64),
+        Value::Boolean(value) => Some(*value as i64),
+        Value::Timestamp(value) => Some(value.timestamp()),
+        Value::Bytes(value) => std::str::from_utf8(value).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(value) => Some(*value),
+        Value::Integer(value) => Some(*value as f64),
+        Value::Bytes(value) => std::str::from_utf8(value).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Timestamp(value) => Some(*value),
+        Value::Bytes(value) => DateTime::parse_from_rfc3339(std::str::from_utf8(value).ok()?)
+            .ok()
+            .map(|value| value.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
+/// Writes the value, or the type's default if it's missing, returning the
+/// value as a string if it can't be converted.
+fn write_value(
+    buffer: &mut Vec<u8>,
+    column_type: &ColumnType,
+    value: Option<&Value>,
+) -> Result<(), String> {
+    let value = match value {
+        Some(Value::Null) | None => None,
+        Some(value) => Some(value),
+    };
+
+    if let ColumnType::Nullable(inner) = column_type {
+        buffer.push(value.is_none() as u8);
+        return write_value(buffer, inner, value);
+    }
+
+    let value = match value {
+        Some(value) => value,
+        None => {
+            write_default(buffer, column_type);
+            return Ok(());
+        }
+    };
+    let failed = || value.to_string_lossy();
+
+    macro_rules! integer {
+        ($type:ty) => {{
+            let value = as_i64(value).ok_or_else(failed)?;
+            let value = <$type>::try_from(value).map_err(|_| failed())?;
+            buffer.extend_from_slice(&value.to_le_bytes());
+        }};
+    }
+
+    match column_type {
+        ColumnType::String => write_string(buffer, &value.as_bytes()),
+        ColumnType::Int8 => integer!(i8),
+        ColumnType::Int16 => integer!(i16),
+        ColumnType::Int32 => integer!(i32),
+        ColumnType::Int64 => integer!(i64),
+        ColumnType::UInt8 => integer!(u8),
+        ColumnType::UInt16 => integer!(u16),
+        ColumnType::UInt32 => integer!(u32),
+        ColumnType::UInt64 => integer!(u64),
+        ColumnType::Float32 => {
+            let value = as_f64(value).ok_or_else(failed)? as f32;
+            buffer.extend_from_slice(&value.to_le_bytes());
+        }
+        ColumnType::Float64 => {
+            let value = as_f64(value).ok_or_else(failed)?;
+            buffer.extend_from_slice(&value.to_le_bytes());
+        }
+        ColumnType::Bool => {
+            let value = match value {
+                Value::Boolean(value) => *value,
+                Value::Bytes(bytes) if &bytes[..] == b"true" => true,
+                Value::Bytes(bytes) if &bytes[..] == b"false" => false,
+                value => as_i64(value).ok_or_else(failed)? != 0,
+            };
+            buffer.push(value as u8);
+        }
+        ColumnType::DateTime => {
+            let seconds = match as_timestamp(value) {
+                Some(timestamp) => timestamp.timestamp(),
+                None => as_i64(value).ok_or_else(failed)?,
+            };
+            let seconds = u32::try_from(seconds).map_err(|_| failed())?;
+            buffer.extend_from_slice(&seconds.to_le_bytes());
+        }
+        ColumnType::DateTime64(precision) => {
+            let scale = 10i64.pow(*precision);
+            let ticks = match as_timestamp(value) {
+                Some(timestamp) => {
+                    timestamp.timestamp() * scale
+                        + i64::from(timestamp.timestamp_subsec_nanos()) / 10i64.pow(9 - *precision)
+                }
+                None => as_i64(value).ok_or_else(failed)?,
+            };
+            buffer.extend_from_slice(&ticks.to_le_bytes());
+        }
+        ColumnType::Nullable(_) => unreachable!("handled above"),
+    }
+    Ok(())
+}
+
+fn write_default(buffer: &mut Vec<u8>, column_type: &ColumnType) {
+    let width = match column_type {
+        ColumnType::String => 1,
+        ColumnType::Int8 | ColumnType::UInt8 | ColumnType::Bool => 1,
+        ColumnType::Int16 | ColumnType::UInt16 => 2,
+        ColumnType::Int32 | ColumnType::UInt32 | ColumnType::Float32 | ColumnType::DateTime => 4,
+        ColumnType::Int64
+        | ColumnType::UInt64
+        | ColumnType::Float64
+        | ColumnType::DateTime64(_) => 8,
+        ColumnType::Nullable(inner) => return write_default(buffer, inner),
+    };
+    // Zero is the default of every type, and the empty string's length.
+    buffer.extend(std::iter::repeat(0).take(width));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn encoder(columns: &[(&str, &str)]) -> NativeEncoder {
+        let columns = columns
+            .iter()
+            .map(|&(name, type_name)| (name.to_owned(), type_name.to_owned()))
+            .collect();
+        NativeEncoder::new(&columns).unwrap()
+    }
+
+    #[test]
+    fn encodes_block() {
+        let encoder = encoder(&[
+            ("message", "String"),
+            ("status", "UInt16"),
+            ("timestamp", "DateTime64(3, 'UTC')"),
+            ("user", "Nullable(String)"),
+        ]);
+        let mut log = LogEvent::default();
+        log.insert("message", "hi");
+        log.insert("status", 200);
+        log.insert("timestamp", Utc.timestamp(1_600_000_000, 123_456_789));
+
+        let block = encoder.encode(&log).unwrap();
+
+        let mut expected = vec![4, 1];
+        expected.extend(b"\x07message\x06String\x02hi");
+        expected.extend(b"\x06status\x06UInt16");
+        expected.extend(&200u16.to_le_bytes());
+        expected.extend(b"\x09timestamp\x14DateTime64(3, 'UTC')");
+        expected.extend(&1_600_000_000_123i64.to_le_bytes());
+        expected.extend(b"\x04user\x10Nullable(String)\x01\x00");
+        assert_eq!(block, expected);
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        let encoder = encoder(&[("status", "UInt8")]);
+        let mut log = LogEvent::default();
+        log.insert("status", 300);
+        assert!(matches!(
+            encoder.encode(&log),
+            Err(NativeError::Conversion { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_unsupported_types() {
+        let columns = vec![("tags".to_owned(), "Array(String)".to_owned())]
+            .into_iter()
+            .collect();
+        assert!(NativeEncoder::new(&columns).is_err());
+    }
+
+    #[test]
+    fn encodes_varuint() {
+        let mut buffer = Vec::new();
+        write_varuint(&mut buffer, 300);
+        assert_eq!(buffer, vec![0xac, 0x02]);
+    }
+}
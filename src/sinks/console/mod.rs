@@ -1,7 +1,7 @@
 use crate::{
     buffers::Acker,
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    event::{Event, Value},
     internal_events::{ConsoleEventProcessed, ConsoleFieldNotFound},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
@@ -18,6 +18,12 @@ use serde::{Deserialize, Serialize};
 
 use tokio::io::{self, AsyncWriteExt};
 
+mod pretty;
+mod table;
+
+use pretty::PrettyFormatter;
+use table::TableFormatter;
+
 #[derive(Debug, Derivative, Deserialize, Serialize)]
 #[derivative(Default)]
 #[serde(rename_all = "lowercase")]
@@ -33,6 +39,18 @@ pub struct ConsoleSinkConfig {
     #[serde(default)]
     pub target: Target,
     pub encoding: EncodingConfig<Encoding>,
+    /// Used when `encoding.codec` is `pretty`.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub pretty: PrettyConfig,
+    /// Used when `encoding.codec` is `table`.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub table: TableConfig,
 }
 
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
@@ -40,6 +58,60 @@ pub struct ConsoleSinkConfig {
 pub enum Encoding {
     Text,
     Json,
+    /// Human friendly logs, for reading during development.
+    Pretty,
+    /// Selected fields of logs in columns.
+    Table,
+}
+
+#[derive(Deserialize, Serialize, Debug, Derivative, Eq, PartialEq, Clone)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct PrettyConfig {
+    #[serde(default)]
+    pub color: Color,
+    #[serde(default = "default_level_field")]
+    #[derivative(Default(value = "default_level_field()"))]
+    pub level_field: String,
+    /// The levels of nested fields printed on lines of their own. Deeper
+    /// fields are folded into JSON on the line of their parent.
+    #[serde(default = "default_fold_depth")]
+    #[derivative(Default(value = "default_fold_depth()"))]
+    pub fold_depth: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Derivative, Eq, PartialEq, Clone)]
+#[derivative(Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Color {
+    /// Colors output written to a terminal.
+    #[derivative(Default)]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Deserialize, Serialize, Debug, Derivative, Eq, PartialEq, Clone)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct TableConfig {
+    /// Defaults to the timestamp and message keys of the log schema.
+    pub fields: Option<Vec<String>>,
+    #[serde(default = "default_max_column_width")]
+    #[derivative(Default(value = "default_max_column_width()"))]
+    pub max_column_width: usize,
+}
+
+fn default_level_field() -> String {
+    "level".into()
+}
+
+fn default_fold_depth() -> usize {
+    2
+}
+
+fn default_max_column_width() -> usize {
+    40
 }
 
 inventory::submit! {
@@ -51,6 +123,8 @@ impl GenerateConfig for ConsoleSinkConfig {
         toml::Value::try_from(Self {
             target: Target::Stdout,
             encoding: Encoding::Json.into(),
+            pretty: Default::default(),
+            table: Default::default(),
         })
         .unwrap()
     }
@@ -70,10 +144,17 @@ impl SinkConfig for ConsoleSinkConfig {
             Target::Stderr => Box::new(io::stderr()),
         };
 
+        let formatter = match encoding.codec() {
+            Encoding::Pretty => Some(Formatter::Pretty(self.pretty_formatter())),
+            Encoding::Table => Some(Formatter::Table(self.table_formatter())),
+            Encoding::Text | Encoding::Json => None,
+        };
+
         let sink = WriterSink {
             acker: cx.acker(),
             output,
             encoding,
+            formatter,
         };
 
         Ok((
@@ -91,6 +172,67 @@ impl SinkConfig for ConsoleSinkConfig {
     }
 }
 
+impl ConsoleSinkConfig {
+    fn pretty_formatter(&self) -> PrettyFormatter {
+        let color = match self.pretty.color {
+            #[cfg(unix)]
+            Color::Auto => atty::is(match self.target {
+                Target::Stdout => atty::Stream::Stdout,
+                Target::Stderr => atty::Stream::Stderr,
+            }),
+            #[cfg(windows)]
+            Color::Auto => false, // ANSI colors are not supported by cmd.exe
+            Color::Always => true,
+            Color::Never => false,
+        };
+        let schema = crate::config::log_schema();
+        PrettyFormatter {
+            color,
+            timestamp_key: schema.timestamp_key().to_owned(),
+            level_key: self.pretty.level_field.clone(),
+            message_key: schema.message_key().to_owned(),
+            fold_depth: self.pretty.fold_depth,
+        }
+    }
+
+    fn table_formatter(&self) -> TableFormatter {
+        let fields = self.table.fields.clone().unwrap_or_else(|| {
+            let schema = crate::config::log_schema();
+            vec![
+                schema.timestamp_key().to_owned(),
+                schema.message_key().to_owned(),
+            ]
+        });
+        TableFormatter::new(fields, self.table.max_column_width)
+    }
+}
+
+/// Writes logs for people rather than programs to read. Metrics are written
+/// as text.
+enum Formatter {
+    Pretty(PrettyFormatter),
+    Table(TableFormatter),
+}
+
+impl Formatter {
+    fn format(&mut self, mut event: Event, encoding: &EncodingConfig<Encoding>) -> String {
+        encoding.apply_rules(&mut event);
+        match (self, event) {
+            (Formatter::Pretty(formatter), Event::Log(log)) => formatter.format(&log),
+            (Formatter::Table(formatter), Event::Log(log)) => formatter.format(&log),
+            (_, Event::Metric(metric)) => format!("{}", metric),
+        }
+    }
+}
+
+/// Timestamps are shortened to milliseconds, so that they line up.
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Timestamp(timestamp) => timestamp.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        value => value.to_string_lossy(),
+    }
+}
+
 fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option<String> {
     encoding.apply_rules(&mut event);
     match event {
@@ -100,6 +242,7 @@ fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option
                     error!(message = "Error encoding json.", %error);
                 })
                 .ok(),
+            Encoding::Pretty | Encoding::Table => unreachable!("Formatted by the sink."),
             Encoding::Text => {
                 let field = crate::config::log_schema().message_key();
                 match log.get(field) {
@@ -120,6 +263,7 @@ fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option
                 })
                 .ok(),
             Encoding::Text => Some(format!("{}", metric)),
+            Encoding::Pretty | Encoding::Table => unreachable!("Formatted by the sink."),
         },
     }
 }
@@ -128,6 +272,7 @@ struct WriterSink {
     acker: Acker,
     output: Box<dyn io::AsyncWrite + Send + Sync + Unpin>,
     encoding: EncodingConfig<Encoding>,
+    formatter: Option<Formatter>,
 }
 
 #[async_trait]
@@ -135,7 +280,11 @@ impl StreamSink for WriterSink {
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
         while let Some(event) = input.next().await {
             self.acker.ack(1);
-            if let Some(mut buf) = encode_event(event, &self.encoding) {
+            let buf = match &mut self.formatter {
+                Some(formatter) => Some(formatter.format(event, &self.encoding)),
+                None => encode_event(event, &self.encoding),
+            };
+            if let Some(mut buf) = buf {
                 buf.push('\n');
                 if let Err(error) = self.output.write_all(buf.as_bytes()).await {
                     // Error when writing to stdout/stderr is likely irrecoverable,
@@ -0,0 +1,144 @@
+use super::display_value;
+use crate::event::{LogEvent, Value};
+use colored::Colorize;
+use std::fmt::Write;
+
+/// The width of timestamps, which are printed with millisecond precision.
+const TIMESTAMP_WIDTH: usize = 24;
+const LEVEL_WIDTH: usize = 5;
+const INDENT: &str = "  ";
+
+/// Prints the timestamp, level and message of each event on a line of its
+/// own, followed by its other fields, one per line.
+pub struct PrettyFormatter {
+    pub color: bool,
+    pub timestamp_key: String,
+    pub level_key: String,
+    pub message_key: String,
+    /// The levels of maps expanded onto lines of their own. Deeper maps are
+    /// printed on the line of their key.
+    pub fold_depth: usize,
+}
+
+impl PrettyFormatter {
+    pub fn format(&self, log: &LogEvent) -> String {
+        let timestamp = log
+            .get(&self.timestamp_key)
+            .map(display_value)
+            .unwrap_or_default();
+        let level = log
+            .get(&self.level_key)
+            .map(|level| level.to_string_lossy().to_uppercase())
+            .unwrap_or_default();
+        let level = self.colorize_level(format!("{:<width$}", level, width = LEVEL_WIDTH));
+        let message = log
+            .get(&self.message_key)
+            .map(display_value)
+            .unwrap_or_default();
+
+        let mut out = format!(
+            "{:<width$} {} {}",
+            timestamp,
+            level,
+            message,
+            width = TIMESTAMP_WIDTH
+        );
+        let fields = log.as_map().iter().filter(|(key, _)| {
+            **key != self.timestamp_key && **key != self.level_key && **key != self.message_key
+        });
+        for (key, value) in fields {
+            self.write_field(&mut out, key, value, 1);
+        }
+        out
+    }
+
+    fn write_field(&self, out: &mut String, key: &str, value: &Value, depth: usize) {
+        let indent = INDENT.repeat(depth);
+        let key = if self.color {
+            key.dimmed().to_string()
+        } else {
+            key.to_owned()
+        };
+        match value {
+            Value::Map(map) if depth <= self.fold_depth && !map.is_empty() => {
+                let _ = write!(out, "\n{}{}:", indent, key);
+                for (key, value) in map {
+                    self.write_field(out, key, value, depth + 1);
+                }
+            }
+            value => {
+                let _ = write!(out, "\n{}{}: {}", indent, key, display_value(value));
+            }
+        }
+    }
+
+    fn colorize_level(&self, level: String) -> String {
+        if !self.color {
+            return level;
+        }
+        match level.trim_end() {
+            "EMERG" | "ALERT" | "CRIT" | "CRITICAL" | "FATAL" | "PANIC" | "ERR" | "ERROR" => {
+                level.red().bold().to_string()
+            }
+            "WARN" | "WARNING" => level.yellow().to_string(),
+            "INFO" | "NOTICE" => level.green().to_string(),
+            "DEBUG" => level.blue().to_string(),
+            "TRACE" => level.magenta().to_string(),
+            _ => level,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Event;
+    use chrono::{offset::TimeZone, Utc};
+
+    fn formatter(color: bool) -> PrettyFormatter {
+        PrettyFormatter {
+            color,
+            timestamp_key: "timestamp".into(),
+            level_key: "level".into(),
+            message_key: "message".into(),
+            fold_depth: 1,
+        }
+    }
+
+    #[test]
+    fn prints_fields_below_message() {
+        let mut event = Event::from("Disk full");
+        let log = event.as_mut_log();
+        log.insert("timestamp", Utc.ymd(2020, 12, 1).and_hms_milli(10, 0, 0, 5));
+        log.insert("level", "warn");
+        log.insert("host", "db-1");
+        log.insert("http.method", "GET");
+        log.insert("http.headers.accept", "*/*");
+
+        assert_eq!(
+            formatter(false).format(event.as_log()),
+            [
+                "2020-12-01T10:00:00.005Z WARN  Disk full",
+                "  host: db-1",
+                "  http:",
+                r#"    headers: {"accept":"*/*"}"#,
+                "    method: GET",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn aligns_events_without_timestamp_or_level() {
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("message", "hello");
+        assert_eq!(
+            formatter(false).format(event.as_log()),
+            format!(
+                "{} {} hello",
+                " ".repeat(TIMESTAMP_WIDTH),
+                " ".repeat(LEVEL_WIDTH)
+            )
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use super::display_value;
+use crate::event::LogEvent;
+
+const SEPARATOR: &str = " | ";
+/// Printed for fields the event doesn't have.
+const MISSING: &str = "-";
+
+/// Prints the selected fields of each event in columns, under a header that
+/// is printed again whenever the columns widen.
+pub struct TableFormatter {
+    fields: Vec<String>,
+    /// Values longer than this are cut short, except in the last column.
+    max_width: usize,
+    widths: Vec<usize>,
+    print_header: bool,
+}
+
+impl TableFormatter {
+    pub fn new(fields: Vec<String>, max_width: usize) -> Self {
+        let widths = fields
+            .iter()
+            .map(|field| field.chars().count().min(max_width))
+            .collect();
+        Self {
+            fields,
+            max_width,
+            widths,
+            print_header: true,
+        }
+    }
+
+    pub fn format(&mut self, log: &LogEvent) -> String {
+        let last = self.fields.len().saturating_sub(1);
+        let values = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let value = log
+                    .get(field)
+                    .map(|value| display_value(value).replace('\n', "\\n"))
+                    .unwrap_or_else(|| MISSING.into());
+                if index == last {
+                    value
+                } else {
+                    truncate(value, self.max_width)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for (width, value) in self.widths.iter_mut().zip(&values).take(last) {
+            let len = value.chars().count();
+            if len > *width {
+                *width = len;
+                self.print_header = true;
+            }
+        }
+
+        let row = self.row(&values);
+        if self.print_header {
+            self.print_header = false;
+            let header = self.row(&self.fields);
+            let rule = self
+                .widths
+                .iter()
+                .enumerate()
+                .map(|(index, width)| {
+                    let width = if index == last {
+                        self.fields[index].chars().count()
+                    } else {
+                        *width
+                    };
+                    "-".repeat(width)
+                })
+                .collect::<Vec<_>>()
+                .join(SEPARATOR);
+            format!("{}\n{}\n{}", header, rule, row)
+        } else {
+            row
+        }
+    }
+
+    fn row(&self, values: &[String]) -> String {
+        let last = values.len().saturating_sub(1);
+        values
+            .iter()
+            .zip(&self.widths)
+            .enumerate()
+            .map(|(index, (value, width))| {
+                if index == last {
+                    value.clone()
+                } else {
+                    format!("{:<width$}", value, width = width)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(SEPARATOR)
+    }
+}
+
+fn truncate(value: String, max_width: usize) -> String {
+    if value.chars().count() <= max_width {
+        return value;
+    }
+    let mut value = value
+        .chars()
+        .take(max_width.saturating_sub(1))
+        .collect::<String>();
+    value.push('…');
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Event;
+
+    fn event(level: &str, message: &str) -> Event {
+        let mut event = Event::from(message);
+        event.as_mut_log().insert("level", level);
+        event
+    }
+
+    #[test]
+    fn prints_columns() {
+        let mut table = TableFormatter::new(vec!["level".into(), "message".into()], 10);
+        assert_eq!(
+            table.format(event("info", "started").as_log()),
+            "level | message\n----- | -------\ninfo  | started"
+        );
+        assert_eq!(
+            table.format(Event::from("no level").as_log()),
+            "-     | no level"
+        );
+    }
+
+    #[test]
+    fn widens_columns() {
+        let mut table = TableFormatter::new(vec!["level".into(), "message".into()], 7);
+        table.format(event("info", "started").as_log());
+        assert_eq!(
+            table.format(event("warning", "slow").as_log()),
+            "level   | message\n------- | -------\nwarning | slow"
+        );
+        assert_eq!(
+            table.format(event("critical", "down").as_log()),
+            "critic… | down"
+        );
+    }
+}
@@ -1,28 +1,42 @@
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    emit,
+    event::{Event, LogEvent},
     http::HttpClient,
+    internal_events::{DatadogLogsBatchSplit, DatadogLogsTemplateMissingKeys},
     sinks::{
         util::{
             batch::{Batch, BatchError},
             encode_event,
             encoding::{EncodingConfig, EncodingConfiguration},
-            http::{BatchedHttpSink, HttpSink},
+            http::HttpRetryLogic,
             BatchConfig, BatchSettings, BoxedRawValue, Compression, Encoding, JsonArrayBuffer,
-            TowerRequestConfig, VecBuffer,
+            PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig, VecBuffer,
         },
         Healthcheck, VectorSink,
     },
+    template::Template,
     tls::{MaybeTlsSettings, TlsConfig},
 };
 use bytes::Bytes;
-use flate2::write::GzEncoder;
-use futures::{FutureExt, SinkExt};
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
 use http::{Request, StatusCode};
 use hyper::body::Body;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{io::Write, time::Duration};
+use snafu::Snafu;
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tower::Service;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`source`, `service` and `tags` require the `json` codec"))]
+    AttributesRequireJson,
+}
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -30,7 +44,15 @@ pub struct DatadogLogsConfig {
     endpoint: Option<String>,
     region: Option<super::Region>,
     api_key: String,
+    /// A field holding the API key to send the event with instead of
+    /// `api_key`, to route events to several organizations.
+    api_key_field: Option<String>,
     encoding: EncodingConfig<Encoding>,
+    /// Sets the `ddsource` attribute.
+    source: Option<Template>,
+    service: Option<Template>,
+    /// Sets the `ddtags` attribute, comma separated `key:value` pairs.
+    tags: Option<Template>,
     tls: Option<TlsConfig>,
 
     #[serde(default)]
@@ -43,15 +65,8 @@ pub struct DatadogLogsConfig {
     request: TowerRequestConfig,
 }
 
-#[derive(Clone)]
-pub struct DatadogLogsJsonService {
-    config: DatadogLogsConfig,
-}
-
-#[derive(Clone)]
-pub struct DatadogLogsTextService {
-    config: DatadogLogsConfig,
-}
+/// Batches are partitioned by the API key override of their events.
+type ApiKey = Option<String>;
 
 inventory::submit! {
     SinkDescription::new::<DatadogLogsConfig>("datadog_logs")
@@ -78,29 +93,31 @@ impl DatadogLogsConfig {
     }
 
     fn batch_settings<T: Batch>(&self) -> Result<BatchSettings<T>, BatchError> {
+        // The limits of the v2 intake API.
         BatchSettings::default()
-            .bytes(bytesize::kib(100u64))
-            .events(20)
+            .bytes(bytesize::mb(5u64))
+            .events(1000)
             .timeout(1)
             .parse_config(self.batch)
     }
 
-    /// Builds the required BatchedHttpSink.
-    /// Since the DataDog sink can create one of two different sinks, this
+    /// Builds a sink sending batches of `B` through the `DatadogLogsService`.
+    /// Since the Datadog sink batches JSON and text differently, this
     /// extracts most of the shared functionality required to create either sink.
-    fn build_sink<T, B, O>(
+    fn build_sink<B, O, F>(
         &self,
         cx: SinkContext,
-        service: T,
         batch: B,
         timeout: Duration,
+        content_type: &'static str,
+        encode_body: fn(&[O]) -> crate::Result<Vec<u8>>,
+        encode_event: F,
     ) -> crate::Result<(VectorSink, Healthcheck)>
     where
-        O: 'static,
-        B: Batch<Output = Vec<O>> + std::marker::Send + 'static,
-        B::Output: std::marker::Send + Clone,
-        B::Input: std::marker::Send,
-        T: HttpSink<Input = B::Input, Output = B::Output> + Clone,
+        B: Batch<Output = Vec<O>> + Send + 'static,
+        B::Input: Send + 'static,
+        O: Clone + Send + Sync + 'static,
+        F: Fn(Event) -> Option<PartitionInnerBuffer<B::Input, ApiKey>> + Send + Sync + 'static,
     {
         let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
 
@@ -110,16 +127,23 @@ impl DatadogLogsConfig {
         )?;
 
         let client = HttpClient::new(tls_settings)?;
-        let healthcheck = healthcheck(service.clone(), client.clone()).boxed();
-        let sink = BatchedHttpSink::new(
-            service,
-            batch,
-            request_settings,
-            timeout,
+        let service = DatadogLogsService {
+            config: Arc::new(self.clone()),
             client,
-            cx.acker(),
-        )
-        .sink_map_err(|error| error!(message = "Fatal datadog_logs text sink error.", %error));
+            content_type,
+            encode_body,
+        };
+        let healthcheck = healthcheck(service.clone()).boxed();
+        let sink = request_settings
+            .partition_sink(
+                HttpRetryLogic,
+                service,
+                PartitionBuffer::new(batch),
+                timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal datadog_logs sink error.", %error))
+            .with_flat_map(move |event| stream::iter(encode_event(event)).map(Ok));
 
         Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
     }
@@ -127,38 +151,92 @@ impl DatadogLogsConfig {
     /// Build the request, GZipping the contents if the config specifies.
     fn build_request(
         &self,
+        api_key: &str,
         content_type: &str,
         body: Vec<u8>,
     ) -> crate::Result<http::Request<Vec<u8>>> {
-        let uri = format!("{}/v1/input", self.get_endpoint());
+        let uri = format!("{}/api/v2/logs", self.get_endpoint());
         let request = Request::post(uri)
             .header("Content-Type", content_type)
-            .header("DD-API-KEY", self.api_key.clone());
-
-        let compression = self.compression.unwrap_or(Compression::Gzip(None));
-
-        let (request, body) = match compression {
-            Compression::None => (request, body),
-            Compression::Gzip(level) => {
-                // Default the compression level to 6, which is similar to datadog agent.
-                // https://docs.datadoghq.com/agent/logs/log_transport/?tab=https#log-compression
-                let level = level.unwrap_or(6);
-                let mut encoder =
-                    GzEncoder::new(Vec::new(), flate2::Compression::new(level as u32));
-
-                encoder.write_all(&body)?;
-                (
-                    request.header("Content-Encoding", "gzip"),
-                    encoder.finish()?,
-                )
-            }
+            .header("DD-API-KEY", api_key);
+
+        // Default to gzip, whose default level of 6 is similar to datadog agent.
+        // https://docs.datadoghq.com/agent/logs/log_transport/?tab=https#log-compression
+        let compression = self.compression.unwrap_or_else(Compression::gzip_default);
+
+        let request = match compression.content_encoding() {
+            Some(ce) => request.header("Content-Encoding", ce),
+            None => request,
         };
+        let body = compression.compress(body);
 
         request
             .header("Content-Length", body.len())
             .body(body)
             .map_err(Into::into)
     }
+
+    /// Takes the API key override out of the event, so that it isn't sent.
+    fn take_api_key(&self, log: &mut LogEvent) -> ApiKey {
+        self.api_key_field
+            .as_ref()
+            .and_then(|field| log.remove(field))
+            .map(|api_key| api_key.to_string_lossy())
+    }
+
+    fn encode_json_event(
+        &self,
+        mut event: Event,
+    ) -> Option<PartitionInnerBuffer<serde_json::Value, ApiKey>> {
+        let attributes = [
+            ("ddsource", &self.source),
+            ("service", &self.service),
+            ("ddtags", &self.tags),
+        ]
+        .iter()
+        .filter_map(|(attribute, template)| {
+            template
+                .as_ref()?
+                .render_string(&event)
+                .map_err(|missing_keys| {
+                    emit!(DatadogLogsTemplateMissingKeys {
+                        attribute: *attribute,
+                        keys: &missing_keys
+                    });
+                })
+                .ok()
+                .map(|value| (*attribute, value))
+        })
+        .collect::<Vec<_>>();
+
+        let log = event.as_mut_log();
+        let api_key = self.take_api_key(log);
+
+        if let Some(message) = log.remove(log_schema().message_key()) {
+            log.insert("message", message);
+        }
+
+        if let Some(timestamp) = log.remove(log_schema().timestamp_key()) {
+            log.insert("date", timestamp);
+        }
+
+        if let Some(host) = log.remove(log_schema().host_key()) {
+            log.insert("host", host);
+        }
+
+        for (attribute, value) in attributes {
+            log.insert(attribute, value);
+        }
+
+        self.encoding.apply_rules(&mut event);
+
+        Some(PartitionInnerBuffer::new(json!(event.into_log()), api_key))
+    }
+
+    fn encode_text_event(&self, mut event: Event) -> Option<PartitionInnerBuffer<Bytes, ApiKey>> {
+        let api_key = self.take_api_key(event.as_mut_log());
+        encode_event(event, &self.encoding).map(|line| PartitionInnerBuffer::new(line, api_key))
+    }
 }
 
 #[async_trait::async_trait]
@@ -171,24 +249,31 @@ impl SinkConfig for DatadogLogsConfig {
         match self.encoding.codec {
             Encoding::Json => {
                 let batch_settings = self.batch_settings()?;
+                let config = self.clone();
                 self.build_sink(
                     cx,
-                    DatadogLogsJsonService {
-                        config: self.clone(),
-                    },
                     JsonArrayBuffer::new(batch_settings.size),
                     batch_settings.timeout,
+                    "application/json",
+                    encode_json_body,
+                    move |event| config.encode_json_event(event),
                 )
             }
             Encoding::Text => {
+                // Text is sent without attributes.
+                if self.source.is_some() || self.service.is_some() || self.tags.is_some() {
+                    return Err(BuildError::AttributesRequireJson.into());
+                }
+
                 let batch_settings = self.batch_settings()?;
+                let config = self.clone();
                 self.build_sink(
                     cx,
-                    DatadogLogsTextService {
-                        config: self.clone(),
-                    },
                     VecBuffer::new(batch_settings.size),
                     batch_settings.timeout,
+                    "text/plain",
+                    encode_text_body,
+                    move |event| config.encode_text_event(event),
                 )
             }
         }
@@ -203,76 +288,114 @@ impl SinkConfig for DatadogLogsConfig {
     }
 }
 
-#[async_trait::async_trait]
-impl HttpSink for DatadogLogsJsonService {
-    type Input = serde_json::Value;
-    type Output = Vec<BoxedRawValue>;
+fn encode_json_body(events: &[BoxedRawValue]) -> crate::Result<Vec<u8>> {
+    serde_json::to_vec(events).map_err(Into::into)
+}
 
-    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
-        let log = event.as_mut_log();
+fn encode_text_body(events: &[Bytes]) -> crate::Result<Vec<u8>> {
+    Ok(events
+        .iter()
+        .flat_map(|line| line.iter().copied())
+        .collect())
+}
 
-        if let Some(message) = log.remove(log_schema().message_key()) {
-            log.insert("message", message);
-        }
+/// Sends batches to the intake API, splitting those it rejects as too large
+/// in two until they fit.
+#[derive(Clone)]
+struct DatadogLogsService<O> {
+    config: Arc<DatadogLogsConfig>,
+    client: HttpClient,
+    content_type: &'static str,
+    encode_body: fn(&[O]) -> crate::Result<Vec<u8>>,
+}
 
-        if let Some(timestamp) = log.remove(log_schema().timestamp_key()) {
-            log.insert("date", timestamp);
-        }
+impl<O> DatadogLogsService<O>
+where
+    O: Clone + Send + Sync + 'static,
+{
+    fn send(
+        self,
+        api_key: ApiKey,
+        events: Vec<O>,
+    ) -> BoxFuture<'static, crate::Result<http::Response<Bytes>>> {
+        async move {
+            let body = (self.encode_body)(&events)?;
+            let request = self
+                .config
+                .build_request(
+                    api_key.as_deref().unwrap_or(&self.config.api_key),
+                    self.content_type,
+                    body,
+                )?
+                .map(Body::from);
+
+            let response = self.client.send(request).await?;
+            let (parts, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+
+            if parts.status == StatusCode::PAYLOAD_TOO_LARGE && events.len() > 1 {
+                emit!(DatadogLogsBatchSplit {
+                    events: events.len()
+                });
+
+                // A retry after only the second half failed sends the first
+                // half again too.
+                let mut first = events;
+                let second = first.split_off(first.len() / 2);
+                let response = self.clone().send(api_key.clone(), first).await?;
+                if !response.status().is_success() {
+                    return Ok(response);
+                }
+                return self.send(api_key, second).await;
+            }
 
-        if let Some(host) = log.remove(log_schema().host_key()) {
-            log.insert("host", host);
+            Ok(http::Response::from_parts(parts, body))
         }
-
-        self.config.encoding.apply_rules(&mut event);
-
-        Some(json!(event.into_log()))
-    }
-
-    async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
-        let body = serde_json::to_vec(&events)?;
-        self.config.build_request("application/json", body)
+        .boxed()
     }
 }
 
-#[async_trait::async_trait]
-impl HttpSink for DatadogLogsTextService {
-    type Input = Bytes;
-    type Output = Vec<Bytes>;
+impl<O> Service<PartitionInnerBuffer<Vec<O>, ApiKey>> for DatadogLogsService<O>
+where
+    O: Clone + Send + Sync + 'static,
+{
+    type Response = http::Response<Bytes>;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
-    fn encode_event(&self, event: Event) -> Option<Self::Input> {
-        encode_event(event, &self.config.encoding)
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
     }
 
-    async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
-        let body: Vec<u8> = events.into_iter().flat_map(Bytes::into_iter).collect();
-        self.config.build_request("text/plain", body)
+    fn call(&mut self, request: PartitionInnerBuffer<Vec<O>, ApiKey>) -> Self::Future {
+        let (events, api_key) = request.into_parts();
+        self.clone().send(api_key, events)
     }
 }
 
 /// The healthcheck is performed by sending an empty request to Datadog and checking
 /// the return.
-async fn healthcheck<T, O>(sink: T, client: HttpClient) -> crate::Result<()>
+async fn healthcheck<O>(service: DatadogLogsService<O>) -> crate::Result<()>
 where
-    T: HttpSink<Output = Vec<O>>,
+    O: Clone + Send + Sync + 'static,
 {
-    let req = sink.build_request(Vec::new()).await?.map(Body::from);
-
-    let res = client.send(req).await?;
+    let res = service.send(None, Vec::new()).await?;
 
     let status = res.status();
-    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let body = res.into_body();
 
     match status {
-        StatusCode::OK => Ok(()),
-        StatusCode::UNAUTHORIZED => {
-            let json: serde_json::Value = serde_json::from_slice(&body[..])?;
+        StatusCode::OK | StatusCode::ACCEPTED => Ok(()),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            let json = serde_json::from_slice::<serde_json::Value>(&body[..]).ok();
 
             Err(json
-                .as_object()
+                .as_ref()
+                .and_then(|json| json.as_object())
                 .and_then(|o| o.get("error"))
                 .and_then(|s| s.as_str())
-                .unwrap_or("Token is not valid, 401 returned.")
-                .to_string()
+                .map(str::to_owned)
+                .unwrap_or_else(|| format!("API key is not valid, {} returned.", status))
                 .into())
         }
         _ => {
@@ -295,7 +418,6 @@ mod tests {
         sinks::util::test::{build_test_server, load_sink},
         test_util::{next_addr, random_lines_with_stream},
     };
-    use futures::StreamExt;
 
     #[test]
     fn generate_config() {
@@ -391,4 +513,108 @@ mod tests {
             assert_eq!(message, expected[i]);
         }
     }
+
+    #[test]
+    fn json_attributes_and_api_key() {
+        let (config, _cx) = load_sink::<DatadogLogsConfig>(
+            r#"
+            api_key = "atoken"
+            api_key_field = "dd_api_key"
+            encoding = "json"
+            source = "{{ app }}"
+            service = "checkout"
+            tags = "env:{{ env }},team:payments"
+            "#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("app", "nginx");
+        event.as_mut_log().insert("dd_api_key", "another-org");
+        let (json, api_key) = config.encode_json_event(event).unwrap().into_parts();
+
+        assert_eq!(api_key, Some("another-org".to_owned()));
+        assert_eq!(json["message"], "hello");
+        assert_eq!(json["ddsource"], "nginx");
+        assert_eq!(json["service"], "checkout");
+        // `env` is missing, so the tags are left unset.
+        assert!(json.get("ddtags").is_none());
+        assert!(json.get("dd_api_key").is_none());
+    }
+
+    #[tokio::test]
+    async fn text_rejects_attributes() {
+        let (config, cx) = load_sink::<DatadogLogsConfig>(
+            r#"
+            api_key = "atoken"
+            encoding = "text"
+            service = "checkout"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.build(cx).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn splits_too_large_batches() {
+        use hyper::{
+            service::{make_service_fn, service_fn},
+            Response, Server,
+        };
+        use std::{convert::Infallible, sync::Mutex};
+
+        // Rejects batches of more than two events.
+        let addr = next_addr();
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let received = Arc::clone(&batches);
+        let make_svc = make_service_fn(move |_| {
+            let received = Arc::clone(&received);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    let received = Arc::clone(&received);
+                    async move {
+                        let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+                        let events = serde_json::from_slice::<Vec<String>>(&body).unwrap();
+                        let mut response = Response::new(Body::empty());
+                        if events.len() > 2 {
+                            *response.status_mut() = StatusCode::PAYLOAD_TOO_LARGE;
+                        } else {
+                            *response.status_mut() = StatusCode::ACCEPTED;
+                            received.lock().unwrap().push(events);
+                        }
+                        Ok::<_, Infallible>(response)
+                    }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(make_svc));
+
+        let (mut config, _cx) = load_sink::<DatadogLogsConfig>(
+            r#"
+            api_key = "atoken"
+            encoding = "json"
+            compression = "none"
+            "#,
+        )
+        .unwrap();
+        config.endpoint = Some(format!("http://{}", addr));
+        let service = DatadogLogsService {
+            config: Arc::new(config),
+            client: HttpClient::new(None).unwrap(),
+            content_type: "application/json",
+            encode_body: encode_json_body,
+        };
+
+        let events = (0..5)
+            .map(|i| serde_json::value::to_raw_value(&i.to_string()).unwrap())
+            .collect::<Vec<_>>();
+        let response = service.send(None, events).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(
+            *batches.lock().unwrap(),
+            vec![vec!["0", "1"], vec!["2"], vec!["3", "4"]]
+        );
+    }
 }
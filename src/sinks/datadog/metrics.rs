@@ -1,3 +1,4 @@
+use super::sketch::AgentSketch;
 use crate::{
     config::{DataType, SinkConfig, SinkContext, SinkDescription},
     event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
@@ -17,6 +18,7 @@ use chrono::{DateTime, Utc};
 use futures::{stream, FutureExt, SinkExt, StreamExt};
 use http::{uri::InvalidUri, Request, StatusCode, Uri};
 use lazy_static::lazy_static;
+use prost::Message;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::{
@@ -26,6 +28,10 @@ use std::{
     sync::atomic::{AtomicI64, Ordering::SeqCst},
 };
 
+mod proto {
+    include!(concat!(env!("OUT_DIR"), "/datadog.agentpayload.rs"));
+}
+
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("Invalid host {:?}: {:?}", host, source))]
@@ -46,6 +52,10 @@ pub struct DatadogConfig {
     pub endpoint: Option<String>,
     pub region: Option<super::Region>,
     pub api_key: String,
+    /// Sends histograms as distributions, rather than as gauges of their
+    /// statistics.
+    #[serde(default)]
+    pub histograms_as_distributions: bool,
     #[serde(default)]
     pub batch: BatchConfig,
     #[serde(default)]
@@ -82,15 +92,6 @@ impl DatadogConfig {
     }
 }
 
-// https://github.com/DataDog/datadogpy/blob/1f143ab875e5994a94345ed373ac308c9f69b0ec/datadog/api/distributions.py#L9-L11
-#[derive(Debug, Clone, PartialEq, Serialize)]
-struct DatadogDistributionMetric {
-    metric: String,
-    interval: Option<i64>,
-    points: Vec<DatadogPoint<Vec<f64>>>,
-    tags: Option<Vec<String>>,
-}
-
 #[derive(Debug, Clone, PartialEq, Serialize)]
 struct DatadogMetric {
     metric: String,
@@ -125,7 +126,7 @@ struct DatadogStats {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum DatadogEndpoint {
     Series,
-    Distribution,
+    Sketches,
 }
 
 impl DatadogEndpoint {
@@ -133,18 +134,22 @@ impl DatadogEndpoint {
         Ok(vec![
             (DatadogEndpoint::Series, build_uri(host, "/api/v1/series")?),
             (
-                DatadogEndpoint::Distribution,
-                build_uri(host, "/api/v1/distribution_points")?,
+                DatadogEndpoint::Sketches,
+                build_uri(host, "/api/beta/sketches")?,
             ),
         ])
     }
 
-    fn from_metric(event: &Event) -> Self {
+    fn from_metric(event: &Event, histograms_as_distributions: bool) -> Self {
         match event.as_metric().value {
             MetricValue::Distribution {
                 statistic: StatisticKind::Summary,
                 ..
-            } => Self::Distribution,
+            } => Self::Sketches,
+            MetricValue::Distribution {
+                statistic: StatisticKind::Histogram,
+                ..
+            } if histograms_as_distributions => Self::Sketches,
             _ => Self::Series,
         }
     }
@@ -186,11 +191,12 @@ impl SinkConfig for DatadogConfig {
         );
 
         let buffer = PartitionBuffer::new(MetricBuffer::new(batch.size));
+        let histograms_as_distributions = self.histograms_as_distributions;
 
         let svc_sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
             .sink_map_err(|error| error!(message = "Fatal datadog metric sink error.", %error))
             .with_flat_map(move |event: Event| {
-                let ep = DatadogEndpoint::from_metric(&event);
+                let ep = DatadogEndpoint::from_metric(&event, histograms_as_distributions);
                 stream::iter(Some(PartitionInnerBuffer::new(event, ep))).map(Ok)
             });
 
@@ -221,24 +227,24 @@ impl DatadogSink {
         let interval = now - endpoint_data.1.load(SeqCst);
         endpoint_data.1.store(now, SeqCst);
 
-        let body = match endpoint {
+        let (body, content_type) = match endpoint {
             DatadogEndpoint::Series => {
                 let input =
                     encode_events(events, self.config.default_namespace.as_deref(), interval);
-                serde_json::to_vec(&input).unwrap()
+                (serde_json::to_vec(&input).unwrap(), "application/json")
             }
-            DatadogEndpoint::Distribution => {
-                let input = encode_distribution_events(
-                    events,
-                    self.config.default_namespace.as_deref(),
-                    interval,
-                );
-                serde_json::to_vec(&input).unwrap()
+            DatadogEndpoint::Sketches => {
+                let input = encode_sketches(events, self.config.default_namespace.as_deref());
+                let mut body = Vec::with_capacity(input.encoded_len());
+                input
+                    .encode(&mut body)
+                    .expect("Encoding to a Vec can't fail");
+                (body, "application/x-protobuf")
             }
         };
 
         Request::post(endpoint_data.0.clone())
-            .header("Content-Type", "application/json")
+            .header("Content-Type", content_type)
             .header("DD-API-KEY", self.config.api_key.clone())
             .body(body)
             .map_err(Into::into)
@@ -454,13 +460,11 @@ fn encode_events(
     DatadogRequest { series }
 }
 
-fn encode_distribution_events(
-    events: Vec<Metric>,
-    default_namespace: Option<&str>,
-    interval: i64,
-) -> DatadogRequest<DatadogDistributionMetric> {
-    debug!(message = "Distribution.", count = events.len());
-    let series = events
+/// Encodes distributions as sketches, which keep their quantiles accurate
+/// however Datadog aggregates them.
+fn encode_sketches(events: Vec<Metric>, default_namespace: Option<&str>) -> proto::SketchPayload {
+    debug!(message = "Sketches.", count = events.len());
+    let sketches = events
         .into_iter()
         .filter_map(|event| {
             let fullname = encode_namespace(
@@ -469,31 +473,38 @@ fn encode_distribution_events(
                 &event.name,
             );
             let ts = encode_timestamp(event.timestamp);
-            let tags = event.tags.clone().map(encode_tags);
+            let tags = event.tags.clone().map(encode_tags).unwrap_or_default();
             match event.kind {
                 MetricKind::Incremental => match event.value {
                     MetricValue::Distribution {
                         values,
                         sample_rates,
-                        statistic: StatisticKind::Summary,
+                        ..
                     } => {
-                        let samples = values
-                            .iter()
-                            .zip(sample_rates.iter())
-                            .map(|(&value, &rate)| (0..rate).map(move |_| value))
-                            .flatten()
-                            .collect::<Vec<_>>();
-
-                        if samples.is_empty() {
-                            None
-                        } else {
-                            Some(DatadogDistributionMetric {
-                                metric: fullname,
-                                interval: Some(interval),
-                                points: vec![DatadogPoint(ts, samples)],
-                                tags,
-                            })
+                        let mut sketch = AgentSketch::default();
+                        for (value, rate) in values.iter().zip(sample_rates.iter()) {
+                            sketch.insert_n(*value, *rate);
                         }
+                        if sketch.is_empty() {
+                            return None;
+                        }
+
+                        let (k, n) = sketch.bins();
+                        Some(proto::sketch_payload::Sketch {
+                            metric: fullname,
+                            host: String::new(),
+                            tags,
+                            dogsketches: vec![proto::sketch_payload::sketch::Dogsketch {
+                                ts,
+                                cnt: sketch.count() as i64,
+                                min: sketch.min(),
+                                max: sketch.max(),
+                                avg: sketch.avg(),
+                                sum: sketch.sum(),
+                                k,
+                                n,
+                            }],
+                        })
                     }
                     _ => None,
                 },
@@ -502,7 +513,7 @@ fn encode_distribution_events(
         })
         .collect();
 
-    DatadogRequest { series }
+    proto::SketchPayload { sketches }
 }
 
 #[cfg(test)]
@@ -803,6 +814,90 @@ mod tests {
     #[test]
     fn encode_datadog_distribution() {
         // https://docs.datadoghq.com/developers/metrics/types/?tab=distribution#definition
+        let events = vec![
+            Metric {
+                name: "requests".into(),
+                namespace: None,
+                timestamp: Some(ts()),
+                tags: Some(tags()),
+                kind: MetricKind::Incremental,
+                value: MetricValue::Distribution {
+                    values: vec![1.0, 2.0, 3.0],
+                    sample_rates: vec![3, 3, 2],
+                    statistic: StatisticKind::Summary,
+                },
+            },
+            Metric {
+                name: "empty".into(),
+                namespace: None,
+                timestamp: Some(ts()),
+                tags: None,
+                kind: MetricKind::Incremental,
+                value: MetricValue::Distribution {
+                    values: vec![1.0],
+                    sample_rates: vec![0],
+                    statistic: StatisticKind::Summary,
+                },
+            },
+        ];
+        let payload = encode_sketches(events, Some("ns"));
+
+        assert_eq!(payload.sketches.len(), 1);
+        let sketch = &payload.sketches[0];
+        assert_eq!(sketch.metric, "ns.requests");
+        assert_eq!(
+            sketch.tags,
+            vec!["empty_tag:", "normal_tag:value", "true_tag:true"]
+        );
+        let dogsketch = &sketch.dogsketches[0];
+        assert_eq!(dogsketch.ts, 1542182950);
+        assert_eq!(dogsketch.cnt, 8);
+        assert_eq!(dogsketch.min, 1.0);
+        assert_eq!(dogsketch.max, 3.0);
+        assert_eq!(dogsketch.sum, 15.0);
+        assert_eq!(dogsketch.avg, 1.875);
+        assert_eq!(dogsketch.k.len(), 3);
+        assert_eq!(dogsketch.n, vec![3, 3, 2]);
+    }
+
+    #[test]
+    fn routes_histograms_as_distributions() {
+        let histogram = Event::Metric(Metric {
+            name: "requests".into(),
+            namespace: None,
+            timestamp: None,
+            tags: None,
+            kind: MetricKind::Incremental,
+            value: MetricValue::Distribution {
+                values: vec![1.0],
+                sample_rates: vec![1],
+                statistic: StatisticKind::Histogram,
+            },
+        });
+
+        assert_eq!(
+            DatadogEndpoint::from_metric(&histogram, false),
+            DatadogEndpoint::Series
+        );
+        assert_eq!(
+            DatadogEndpoint::from_metric(&histogram, true),
+            DatadogEndpoint::Sketches
+        );
+    }
+
+    #[test]
+    fn sketch_request() {
+        let sink = DatadogSink {
+            config: DatadogConfig {
+                api_key: "test".into(),
+                ..Default::default()
+            },
+            endpoint_data: DatadogEndpoint::build_uri("https://api.datadoghq.com")
+                .unwrap()
+                .into_iter()
+                .map(|(endpoint, uri)| (endpoint, (uri, AtomicI64::new(0))))
+                .collect(),
+        };
         let events = vec![Metric {
             name: "requests".into(),
             namespace: None,
@@ -810,17 +905,21 @@ mod tests {
             tags: None,
             kind: MetricKind::Incremental,
             value: MetricValue::Distribution {
-                values: vec![1.0, 2.0, 3.0],
-                sample_rates: vec![3, 3, 2],
+                values: vec![1.0, 2.0],
+                sample_rates: vec![1, 1],
                 statistic: StatisticKind::Summary,
             },
         }];
-        let input = encode_distribution_events(events, None, 60);
-        let json = serde_json::to_string(&input).unwrap();
+        let req = sink
+            .build_request(PartitionInnerBuffer::new(events, DatadogEndpoint::Sketches))
+            .unwrap();
 
         assert_eq!(
-            json,
-            r#"{"series":[{"metric":"requests","interval":60,"points":[[1542182950,[1.0,1.0,1.0,2.0,2.0,2.0,3.0,3.0]]],"tags":null}]}"#
+            req.uri(),
+            &Uri::from_static("https://api.datadoghq.com/api/beta/sketches")
         );
+        assert_eq!(req.headers()["Content-Type"], "application/x-protobuf");
+        let payload = proto::SketchPayload::decode(req.body().as_slice()).unwrap();
+        assert_eq!(payload.sketches[0].dogsketches[0].cnt, 2);
     }
 }
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 pub mod logs;
 pub mod metrics;
+mod sketch;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
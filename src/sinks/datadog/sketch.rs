@@ -0,0 +1,226 @@
+//! DDSketch with the parameters of the Datadog Agent, whose sketches the
+//! sketches endpoint merges with ours. Values are counted into bins whose
+//! bounds grow by a factor of `1 + 2 * EPS`, so any quantile is accurate to
+//! within `EPS` of the actual value.
+
+use lazy_static::lazy_static;
+use std::collections::BTreeMap;
+
+/// The relative accuracy of quantiles.
+const EPS: f64 = 1.0 / 128.0;
+/// The smallest magnitude with a bin of its own. Smaller values count as 0.
+const MIN_VALUE: f64 = 1.0e-9;
+/// The most bins a sketch holds. The lowest bins are merged beyond it.
+const BIN_LIMIT: usize = 4096;
+const MAX_KEY: i32 = i16::MAX as i32;
+
+struct Config {
+    /// The logarithm of the growth factor of bins, `1 + 2 * EPS`.
+    gamma_ln: f64,
+    /// Offsets keys so that `MIN_VALUE` falls in the bin with key 1.
+    bias: i32,
+    /// The value of the bin with key 1.
+    norm_min: f64,
+}
+
+lazy_static! {
+    static ref CONFIG: Config = {
+        let gamma_ln = (1.0 + 2.0 * EPS).ln();
+        let bias = 1 - (MIN_VALUE.ln() / gamma_ln).floor() as i32;
+        let norm_min = ((1 - bias) as f64 * gamma_ln).exp();
+        Config {
+            gamma_ln,
+            bias,
+            norm_min,
+        }
+    };
+}
+
+impl Config {
+    /// The key of the bin of the value. Negative values have negative keys,
+    /// and values closer to 0 than `norm_min` have the key 0.
+    fn key(&self, value: f64) -> i16 {
+        if value < 0.0 {
+            return -self.key(-value);
+        }
+        if value < self.norm_min {
+            return 0;
+        }
+        let key = (value.ln() / self.gamma_ln).round() as i32 + self.bias;
+        key.max(1).min(MAX_KEY) as i16
+    }
+
+    /// The value the bin of the key stands for, within `EPS` of every value
+    /// counted into it.
+    #[cfg(test)]
+    fn value(&self, key: i16) -> f64 {
+        match key {
+            0 => 0.0,
+            key if key < 0 => -self.value(-key),
+            key => ((i32::from(key) - self.bias) as f64 * self.gamma_ln).exp(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AgentSketch {
+    bins: BTreeMap<i16, u32>,
+    count: u64,
+    min: f64,
+    max: f64,
+    sum: f64,
+}
+
+impl AgentSketch {
+    /// Counts the value `n` times. Values that aren't finite are skipped, as
+    /// they have no bin.
+    pub fn insert_n(&mut self, value: f64, n: u32) {
+        if n == 0 || !value.is_finite() {
+            return;
+        }
+
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += u64::from(n);
+        self.sum += value * f64::from(n);
+
+        let bin = self.bins.entry(CONFIG.key(value)).or_insert(0);
+        *bin = bin.saturating_add(n);
+
+        while self.bins.len() > BIN_LIMIT {
+            self.collapse_lowest();
+        }
+    }
+
+    /// Merges the lowest bin into the next one, losing accuracy only for the
+    /// smallest values.
+    fn collapse_lowest(&mut self) {
+        let mut keys = self.bins.keys().copied();
+        if let (Some(lowest), Some(next)) = (keys.next(), keys.next()) {
+            let count = self.bins.remove(&lowest).unwrap_or(0);
+            let bin = self.bins.entry(next).or_insert(0);
+            *bin = bin.saturating_add(count);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    pub fn avg(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f64
+        }
+    }
+
+    /// The keys and counts of the bins, in ascending order of keys.
+    pub fn bins(&self) -> (Vec<i32>, Vec<u32>) {
+        self.bins
+            .iter()
+            .map(|(key, count)| (i32::from(*key), *count))
+            .unzip()
+    }
+
+    /// Estimates the quantile from the bins.
+    #[cfg(test)]
+    fn quantile(&self, q: f64) -> f64 {
+        let rank = (q * (self.count - 1) as f64).round() as u64;
+        let mut seen = 0;
+        for (key, count) in &self.bins {
+            seen += u64::from(*count);
+            if seen > rank {
+                return CONFIG.value(*key);
+            }
+        }
+        self.max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_grow_with_values() {
+        assert_eq!(CONFIG.key(0.0), 0);
+        assert_eq!(CONFIG.key(MIN_VALUE / 10.0), 0);
+        assert_eq!(CONFIG.key(CONFIG.norm_min), 1);
+        assert!(CONFIG.key(1.0) < CONFIG.key(1.1));
+        assert_eq!(CONFIG.key(-5.0), -CONFIG.key(5.0));
+        assert_eq!(CONFIG.key(f64::MAX), MAX_KEY as i16);
+    }
+
+    #[test]
+    fn tracks_summary() {
+        let mut sketch = AgentSketch::default();
+        sketch.insert_n(1.0, 3);
+        sketch.insert_n(2.0, 3);
+        sketch.insert_n(3.0, 2);
+        sketch.insert_n(f64::NAN, 1);
+
+        assert_eq!(sketch.count(), 8);
+        assert_eq!(sketch.min(), 1.0);
+        assert_eq!(sketch.max(), 3.0);
+        assert_eq!(sketch.sum(), 15.0);
+        assert_eq!(sketch.avg(), 1.875);
+        let (keys, counts) = sketch.bins();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(counts, vec![3, 3, 2]);
+    }
+
+    #[test]
+    fn quantiles_are_accurate() {
+        let mut sketch = AgentSketch::default();
+        for value in 1..=10_000 {
+            sketch.insert_n(f64::from(value), 1);
+        }
+
+        for &q in &[0.01, 0.5, 0.9, 0.99] {
+            let expected = (q * 9_999.0).round() + 1.0;
+            let estimate = sketch.quantile(q);
+            assert!(
+                (estimate - expected).abs() <= expected * EPS,
+                "q{}: {} is not within {} of {}",
+                q,
+                estimate,
+                EPS,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn limits_bins() {
+        let mut sketch = AgentSketch::default();
+        for exponent in 0..10_000 {
+            sketch.insert_n(1.02f64.powi(exponent), 1);
+        }
+
+        let (keys, counts) = sketch.bins();
+        assert_eq!(keys.len(), BIN_LIMIT);
+        assert_eq!(counts.iter().map(|n| u64::from(*n)).sum::<u64>(), 10_000);
+    }
+}
@@ -1,21 +1,25 @@
 use crate::{
-    config::{DataType, SinkConfig, SinkContext, SinkDescription},
+    config::{log_schema, DataType, SinkConfig, SinkContext, SinkDescription},
     emit,
     event::Event,
     http::{Auth, HttpClient},
-    internal_events::{ElasticSearchEventReceived, ElasticSearchMissingKeys},
+    internal_events::{
+        ElasticSearchBulkItemsFailed, ElasticSearchBulkItemsRetried, ElasticSearchEventReceived,
+        ElasticSearchInvalidBulkAction, ElasticSearchMissingKeys,
+    },
     rusoto::{self, region_from_endpoint, RegionOrEndpoint},
     sinks::util::{
+        buffer::GZIP_FAST,
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpSink},
+        http::HttpSink,
         retries::{RetryAction, RetryLogic},
-        BatchConfig, BatchSettings, Buffer, Compression, TowerRequestConfig,
+        BatchConfig, BatchSettings, Buffer, Compression, TowerRequestConfig, TowerRequestSettings,
     },
     template::{Template, TemplateError},
     tls::{TlsOptions, TlsSettings},
 };
 use bytes::Bytes;
-use futures::{FutureExt, SinkExt};
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
 use http::{
     header::{HeaderName, HeaderValue},
     uri::InvalidUri,
@@ -29,8 +33,16 @@ use rusoto_signature::{SignedRequest, SignedRequestPayload};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use snafu::{ResultExt, Snafu};
-use std::collections::HashMap;
-use std::convert::TryFrom;
+use std::{
+    cmp,
+    collections::HashMap,
+    convert::TryFrom,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::delay_for;
+use tower::Service;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
@@ -42,6 +54,26 @@ pub struct ElasticSearchConfig {
     pub doc_type: Option<String>,
     pub id_key: Option<String>,
     pub pipeline: Option<String>,
+    #[serde(default)]
+    pub mode: ElasticSearchMode,
+    pub data_stream: Option<DataStreamConfig>,
+    /// Either `index` or `create`, may be a template.
+    pub bulk_action: Option<String>,
+    /// Leave `_type` out of the bulk actions, as Elasticsearch 8 requires.
+    #[serde(default)]
+    pub suppress_type_name: bool,
+    /// Only write to aliases, such as the rollover alias of an ILM policy,
+    /// so a missing alias doesn't get a concrete index created in its place.
+    #[serde(default)]
+    pub require_alias: bool,
+    /// The store serving the bulk API at `endpoint`.
+    #[serde(default)]
+    pub flavor: Flavor,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub compatibility: CompatibilityConfig,
 
     #[serde(default)]
     pub compression: Compression,
@@ -77,11 +109,103 @@ pub enum Encoding {
     Default,
 }
 
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum ElasticSearchMode {
+    #[derivative(Default)]
+    Normal,
+    DataStream,
+}
+
+/// Stores that accept the Elasticsearch bulk API without being
+/// Elasticsearch, each with its own paths and limitations.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Flavor {
+    #[derivative(Default)]
+    Elasticsearch,
+    Quickwit,
+    #[serde(rename = "zincsearch")]
+    ZincSearch,
+}
+
+impl Flavor {
+    fn name(self) -> &'static str {
+        match self {
+            Flavor::Elasticsearch => "elasticsearch",
+            Flavor::Quickwit => "quickwit",
+            Flavor::ZincSearch => "zincsearch",
+        }
+    }
+
+    fn bulk_path(self) -> &'static str {
+        match self {
+            Flavor::Elasticsearch => "/_bulk",
+            Flavor::Quickwit => "/api/v1/_elastic/_bulk",
+            Flavor::ZincSearch => "/api/_bulk",
+        }
+    }
+
+    fn healthcheck_path(self) -> &'static str {
+        match self {
+            Flavor::Elasticsearch => "/_cluster/health",
+            Flavor::Quickwit => "/health/readyz",
+            Flavor::ZincSearch => "/healthz",
+        }
+    }
+
+    /// Only Elasticsearch still has mapping types, and knows the `timeout`,
+    /// `pipeline` and `require_alias` options and data streams.
+    fn is_elasticsearch(self) -> bool {
+        self == Flavor::Elasticsearch
+    }
+}
+
+/// Overrides the quirks `flavor` implies, for stores or versions that
+/// diverge from them.
+#[derive(Deserialize, Serialize, Debug, Default, Eq, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CompatibilityConfig {
+    pub bulk_path: Option<String>,
+    pub healthcheck_path: Option<String>,
+    /// Whether the request timeout is passed in the `timeout` parameter.
+    pub timeout_param: Option<bool>,
+}
+
+/// The parts of the data stream name, `{type}-{dataset}-{namespace}`.
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct DataStreamConfig {
+    #[serde(rename = "type")]
+    #[derivative(Default(value = "\"logs\".into()"))]
+    pub dtype: String,
+    #[derivative(Default(value = "\"generic\".into()"))]
+    pub dataset: String,
+    #[derivative(Default(value = "\"default\".into()"))]
+    pub namespace: String,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
 pub enum ElasticSearchAuth {
-    Basic { user: String, password: String },
-    Aws { assume_role: Option<String> },
+    Basic {
+        user: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    /// Without `id`, `api_key` is the already encoded key.
+    ApiKey {
+        id: Option<String>,
+        api_key: String,
+    },
+    Aws {
+        assume_role: Option<String>,
+    },
 }
 
 inventory::submit! {
@@ -102,24 +226,26 @@ impl SinkConfig for ElasticSearchConfig {
 
         let healthcheck = healthcheck(client.clone(), common).boxed();
 
-        let common = ElasticSearchCommon::parse_config(&self)?;
-        let compression = common.compression;
+        let common = Arc::new(ElasticSearchCommon::parse_config(&self)?);
         let batch = BatchSettings::default()
             .bytes(bytesize::mib(10u64))
             .timeout(1)
             .parse_config(self.batch)?;
         let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
 
-        let sink = BatchedHttpSink::with_retry_logic(
-            common,
-            Buffer::new(batch.size, compression),
-            ElasticSearchRetryLogic,
-            request,
-            batch.timeout,
-            client,
-            cx.acker(),
-        )
-        .sink_map_err(|error| error!(message = "Fatal elasticsearch sink error.", %error));
+        // The batch is compressed when the request is built, so the items
+        // Elasticsearch rejects can be picked out of it to be sent again.
+        let service = ElasticSearchService::new(Arc::clone(&common), client, &request);
+        let sink = request
+            .batch_sink(
+                ElasticSearchRetryLogic,
+                service,
+                Buffer::new(batch.size, Compression::None),
+                batch.timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal elasticsearch sink error.", %error))
+            .with_flat_map(move |event| stream::iter(common.encode_event(event)).map(Ok));
 
         Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck))
     }
@@ -137,9 +263,13 @@ impl SinkConfig for ElasticSearchConfig {
 pub struct ElasticSearchCommon {
     pub base_url: String,
     bulk_uri: Uri,
-    authorization: Option<Auth>,
+    healthcheck_uri: String,
+    suppress_type_name: bool,
+    authorization: Option<Authorization>,
     credentials: Option<rusoto::AwsCredentialsProvider>,
+    aws_service: &'static str,
     index: Template,
+    bulk_action: Template,
     doc_type: String,
     tls_settings: TlsSettings,
     config: ElasticSearchConfig,
@@ -148,6 +278,47 @@ pub struct ElasticSearchCommon {
     query_params: HashMap<String, String>,
 }
 
+/// How requests are authorized when they aren't signed for AWS.
+#[derive(Debug)]
+enum Authorization {
+    Http(Auth),
+    ApiKey(String),
+}
+
+impl Authorization {
+    fn apply(&self, builder: http::request::Builder) -> http::request::Builder {
+        match self {
+            Authorization::Http(auth) => auth.apply_builder(builder),
+            Authorization::ApiKey(key) => {
+                builder.header("Authorization", format!("ApiKey {}", key))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BulkAction {
+    Index,
+    Create,
+}
+
+impl BulkAction {
+    fn parse(action: &str) -> Option<Self> {
+        match action {
+            "index" => Some(BulkAction::Index),
+            "create" => Some(BulkAction::Create),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            BulkAction::Index => "index",
+            BulkAction::Create => "create",
+        }
+    }
+}
+
 #[derive(Debug, Snafu)]
 enum ParseError {
     #[snafu(display("Invalid host {:?}: {:?}", host, source))]
@@ -158,6 +329,19 @@ enum ParseError {
     AWSCredentialsGenerateFailed { source: CredentialsError },
     #[snafu(display("Index template parse error: {}", source))]
     IndexTemplate { source: TemplateError },
+    #[snafu(display("Bulk action template parse error: {}", source))]
+    BulkActionTemplate { source: TemplateError },
+    #[snafu(display("Invalid bulk action {:?}, expected \"index\" or \"create\"", action))]
+    InvalidBulkAction { action: String },
+    #[snafu(display("Data streams only accept the \"create\" bulk action"))]
+    DataStreamBulkAction,
+    #[snafu(display("`index` can't be set in data stream mode, set `data_stream` instead"))]
+    DataStreamIndex,
+    #[snafu(display("`{}` isn't supported by {}", option, flavor))]
+    UnsupportedOption {
+        option: &'static str,
+        flavor: &'static str,
+    },
 }
 
 #[async_trait::async_trait]
@@ -175,24 +359,32 @@ impl HttpSink for ElasticSearchCommon {
                 });
             })
             .ok()?;
+        let bulk_action = self.bulk_action(&event)?;
 
-        let mut action = json!({
-            "index": {
-                "_index": index,
-                "_type": self.doc_type,
-            }
-        });
-        maybe_set_id(
-            self.config.id_key.as_ref(),
-            action.pointer_mut("/index").unwrap(),
-            &mut event,
-        );
+        let data_stream = self.config.mode == ElasticSearchMode::DataStream;
+        let mut metadata = json!({ "_index": index });
+        if !self.suppress_type_name && !data_stream {
+            metadata["_type"] = json!(self.doc_type);
+        }
+        if self.config.require_alias {
+            metadata["require_alias"] = json!(true);
+        }
+        maybe_set_id(self.config.id_key.as_ref(), &mut metadata, &mut event);
 
+        let action = json!({ bulk_action.as_str(): metadata });
         let mut body = serde_json::to_vec(&action).unwrap();
         body.push(b'\n');
 
         self.config.encoding.apply_rules(&mut event);
 
+        if data_stream {
+            // Data streams need the timestamp in `@timestamp`.
+            let log = event.as_mut_log();
+            if let Some(timestamp) = log.remove(log_schema().timestamp_key()) {
+                log.insert("@timestamp", timestamp);
+            }
+        }
+
         serde_json::to_writer(&mut body, &event.into_log()).unwrap();
         body.push(b'\n');
 
@@ -205,6 +397,7 @@ impl HttpSink for ElasticSearchCommon {
     }
 
     async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        let events = self.compression.compress(events);
         let mut builder = Request::post(&self.bulk_uri);
 
         if let Some(credentials_provider) = &self.credentials {
@@ -212,6 +405,10 @@ impl HttpSink for ElasticSearchCommon {
 
             request.add_header("Content-Type", "application/x-ndjson");
 
+            if let Some(ce) = self.compression.content_encoding() {
+                request.add_header("Content-Encoding", ce);
+            }
+
             if let Some(headers) = &self.config.headers {
                 for (header, value) in headers {
                     request.add_header(header, value);
@@ -245,8 +442,8 @@ impl HttpSink for ElasticSearchCommon {
                 }
             }
 
-            if let Some(auth) = &self.authorization {
-                builder = auth.apply_builder(builder);
+            if let Some(authorization) = &self.authorization {
+                builder = authorization.apply(builder);
             }
 
             builder.body(events).map_err(Into::into)
@@ -254,28 +451,161 @@ impl HttpSink for ElasticSearchCommon {
     }
 }
 
+/// Sends bulk requests, then sends the items Elasticsearch rejected for
+/// temporary reasons again, so only those are retried rather than the
+/// whole batch.
 #[derive(Clone)]
-struct ElasticSearchRetryLogic;
+struct ElasticSearchService {
+    common: Arc<ElasticSearchCommon>,
+    client: HttpClient,
+    retry_attempts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
 
-#[derive(Deserialize, Debug)]
-struct ESResultResponse {
-    items: Vec<ESResultItem>,
+impl ElasticSearchService {
+    fn new(
+        common: Arc<ElasticSearchCommon>,
+        client: HttpClient,
+        request: &TowerRequestSettings,
+    ) -> Self {
+        Self {
+            common,
+            client,
+            retry_attempts: request.retry_attempts,
+            initial_backoff: request.retry_initial_backoff_secs,
+            max_backoff: request.retry_max_duration_secs,
+        }
+    }
+
+    async fn send(mut self, mut body: Vec<u8>) -> crate::Result<http::Response<Bytes>> {
+        let mut attempts = self.retry_attempts;
+        let mut backoff = self.initial_backoff;
+
+        loop {
+            let request = self.common.build_request(body.clone()).await?;
+            let response = self.client.call(request.map(Body::from)).await?;
+            let (parts, response_body) = response.into_parts();
+            let response_body = hyper::body::to_bytes(response_body).await?;
+            let response = http::Response::from_parts(parts, response_body);
+
+            if attempts == 0 || !response.status().is_success() {
+                return Ok(response);
+            }
+            let bulk = match serde_json::from_slice::<BulkResponse>(response.body()) {
+                Ok(bulk) if bulk.errors => bulk,
+                _ => return Ok(response),
+            };
+            let retry_body = match bulk.retry_body(&body) {
+                Some(retry_body) => retry_body,
+                None => return Ok(response),
+            };
+
+            let (retried, failed): (Vec<_>, Vec<_>) = bulk
+                .results()
+                .flatten()
+                .filter(|result| result.error.is_some())
+                .partition(|result| result.is_retriable());
+            if let Some(error) = failed.first().and_then(|result| result.error.as_ref()) {
+                emit!(ElasticSearchBulkItemsFailed {
+                    count: failed.len(),
+                    error_type: &error.err_type,
+                    reason: &error.reason,
+                });
+            }
+            emit!(ElasticSearchBulkItemsRetried {
+                count: retried.len()
+            });
+
+            delay_for(backoff).await;
+            body = retry_body;
+            attempts -= 1;
+            backoff = cmp::min(backoff * 2, self.max_backoff);
+        }
+    }
 }
+
+impl Service<Vec<u8>> for ElasticSearchService {
+    type Response = http::Response<Bytes>;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, body: Vec<u8>) -> Self::Future {
+        Box::pin(self.clone().send(body))
+    }
+}
+
+#[derive(Clone)]
+struct ElasticSearchRetryLogic;
+
 #[derive(Deserialize, Debug)]
-struct ESResultItem {
-    index: ESIndexResult,
+struct BulkResponse {
+    errors: bool,
+    /// One result per item, keyed by its action. Some stores only report
+    /// whether there were errors.
+    #[serde(default)]
+    items: Vec<HashMap<String, BulkItemResult>>,
 }
+
 #[derive(Deserialize, Debug)]
-struct ESIndexResult {
-    error: Option<ESErrorDetails>,
+struct BulkItemResult {
+    status: u16,
+    error: Option<BulkItemError>,
 }
+
 #[derive(Deserialize, Debug)]
-struct ESErrorDetails {
+struct BulkItemError {
     reason: String,
     #[serde(rename = "type")]
     err_type: String,
 }
 
+impl BulkResponse {
+    fn results(&self) -> impl Iterator<Item = Option<&BulkItemResult>> {
+        self.items.iter().map(|item| item.values().next())
+    }
+
+    /// Returns the lines of the request `body` for the items that can be
+    /// retried, if there are any. Every item is an action line followed by
+    /// the document.
+    fn retry_body(&self, body: &[u8]) -> Option<Vec<u8>> {
+        let mut lines = body.split(|&byte| byte == b'\n').collect::<Vec<_>>();
+        if lines.last().map_or(false, |line| line.is_empty()) {
+            lines.pop();
+        }
+        if lines.len() != self.items.len() * 2 {
+            return None;
+        }
+
+        let mut retry_body = Vec::new();
+        for (result, item) in self.results().zip(lines.chunks(2)) {
+            if result.map_or(false, BulkItemResult::is_retriable) {
+                for line in item {
+                    retry_body.extend_from_slice(line);
+                    retry_body.push(b'\n');
+                }
+            }
+        }
+
+        if retry_body.is_empty() {
+            None
+        } else {
+            Some(retry_body)
+        }
+    }
+}
+
+impl BulkItemResult {
+    /// Items are rejected with `429` when Elasticsearch's queues are full.
+    fn is_retriable(&self) -> bool {
+        self.status == 429 || self.status >= 500
+    }
+}
+
 impl RetryLogic for ElasticSearchRetryLogic {
     type Error = hyper::Error;
     type Response = hyper::Response<Bytes>;
@@ -299,11 +629,16 @@ impl RetryLogic for ElasticSearchRetryLogic {
             )),
             _ if status.is_client_error() => {
                 let body = String::from_utf8_lossy(response.body());
-                RetryAction::DontRetry(format!("client-side error, {}: {}", status, body))
+                RetryAction::DontRetry(format!(
+                    "client-side error, {}: {}",
+                    status,
+                    get_request_error(&body)
+                ))
             }
             _ if status.is_success() => {
                 let body = String::from_utf8_lossy(response.body());
 
+                // The rejected items have already been retried by the service.
                 if body.contains("\"errors\":true") {
                     RetryAction::DontRetry(get_error_reason(&body))
                 } else {
@@ -316,18 +651,43 @@ impl RetryLogic for ElasticSearchRetryLogic {
 }
 
 fn get_error_reason(body: &str) -> String {
-    match serde_json::from_str::<ESResultResponse>(&body) {
+    match serde_json::from_str::<BulkResponse>(&body) {
         Err(json_error) => format!(
             "some messages failed, could not parse response, error: {}",
             json_error
         ),
-        Ok(resp) => match resp.items.into_iter().find_map(|item| item.index.error) {
+        Ok(resp) => match resp
+            .results()
+            .find_map(|result| result.and_then(|result| result.error.as_ref()))
+        {
             Some(error) => format!("error type: {}, reason: {}", error.err_type, error.reason),
             None => format!("error response: {}", body),
         },
     }
 }
 
+/// Request errors are shaped differently by every store: Elasticsearch
+/// describes them in an `error` object, ZincSearch in an `error` string and
+/// Quickwit in a `message`.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum RequestError {
+    Elasticsearch { error: BulkItemError },
+    Text { error: String },
+    Message { message: String },
+}
+
+fn get_request_error(body: &str) -> String {
+    match serde_json::from_str::<RequestError>(body) {
+        Ok(RequestError::Elasticsearch { error }) => {
+            format!("error type: {}, reason: {}", error.err_type, error.reason)
+        }
+        Ok(RequestError::Text { error: reason })
+        | Ok(RequestError::Message { message: reason }) => reason,
+        Err(_) => body.into(),
+    }
+}
+
 impl ElasticSearchCommon {
     pub fn parse_config(config: &ElasticSearchConfig) -> crate::Result<Self> {
         // Test the configured host, but ignore the result
@@ -342,13 +702,29 @@ impl ElasticSearchCommon {
             .into());
         }
 
-        let (base_url, mut authorization) = Auth::get_and_strip_basic_auth(&config.endpoint);
+        let (base_url, authorization) = Auth::get_and_strip_basic_auth(&config.endpoint);
+        let mut authorization = authorization.map(Authorization::Http);
 
-        if let Some(ElasticSearchAuth::Basic { user, password }) = config.auth.clone() {
+        let configured = match config.auth.clone() {
+            Some(ElasticSearchAuth::Basic { user, password }) => {
+                Some(Authorization::Http(Auth::Basic { user, password }))
+            }
+            Some(ElasticSearchAuth::Bearer { token }) => {
+                Some(Authorization::Http(Auth::Bearer { token }))
+            }
+            Some(ElasticSearchAuth::ApiKey { id, api_key }) => {
+                Some(Authorization::ApiKey(match id {
+                    Some(id) => base64::encode(format!("{}:{}", id, api_key)),
+                    None => api_key,
+                }))
+            }
+            Some(ElasticSearchAuth::Aws { .. }) | None => None,
+        };
+        if configured.is_some() {
             if authorization.is_some() {
                 warn!("Overwriting authorization config in `endpoint`.");
             }
-            authorization = Some(Auth::Basic { user, password });
+            authorization = configured;
         }
 
         let region = match &config.aws {
@@ -357,22 +733,81 @@ impl ElasticSearchCommon {
         };
 
         let credentials = match &config.auth {
-            Some(ElasticSearchAuth::Basic { .. }) | None => None,
             Some(ElasticSearchAuth::Aws { assume_role }) => Some(
                 rusoto::AwsCredentialsProvider::new(&region, assume_role.clone())?,
             ),
+            _ => None,
+        };
+        let aws_service = aws_service(&base_url);
+
+        let compression = config.compression.with_default_gzip_level(GZIP_FAST);
+
+        let flavor = config.flavor;
+        if !flavor.is_elasticsearch() {
+            let unsupported = if config.mode == ElasticSearchMode::DataStream {
+                Some("mode")
+            } else if config.pipeline.is_some() {
+                Some("pipeline")
+            } else if config.require_alias {
+                Some("require_alias")
+            } else {
+                None
+            };
+            if let Some(option) = unsupported {
+                return Err(ParseError::UnsupportedOption {
+                    option,
+                    flavor: flavor.name(),
+                }
+                .into());
+            }
+        }
+        let suppress_type_name = config.suppress_type_name || !flavor.is_elasticsearch();
+
+        let (index, bulk_action) = match config.mode {
+            ElasticSearchMode::Normal => {
+                let index = config.index.as_deref().unwrap_or("vector-%Y.%m.%d");
+                (
+                    index.to_owned(),
+                    config.bulk_action.as_deref().unwrap_or("index"),
+                )
+            }
+            ElasticSearchMode::DataStream => {
+                if config.index.is_some() {
+                    return Err(ParseError::DataStreamIndex.into());
+                }
+                let bulk_action = config.bulk_action.as_deref().unwrap_or("create");
+                if bulk_action != "create" {
+                    return Err(ParseError::DataStreamBulkAction.into());
+                }
+                let data_stream = config.data_stream.clone().unwrap_or_default();
+                let index = format!(
+                    "{}-{}-{}",
+                    data_stream.dtype, data_stream.dataset, data_stream.namespace
+                );
+                (index, bulk_action)
+            }
         };
-
-        let compression = config.compression;
-        let index = config.index.as_deref().unwrap_or("vector-%Y.%m.%d");
         let index = Template::try_from(index).context(IndexTemplate)?;
+        let bulk_action = Template::try_from(bulk_action).context(BulkActionTemplate)?;
+        if !bulk_action.is_dynamic() && BulkAction::parse(bulk_action.get_ref()).is_none() {
+            return Err(ParseError::InvalidBulkAction {
+                action: bulk_action.get_ref().into(),
+            }
+            .into());
+        }
 
         let doc_type = config.doc_type.clone().unwrap_or_else(|| "_doc".into());
 
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
 
         let mut query_params = config.query.clone().unwrap_or_default();
-        query_params.insert("timeout".into(), format!("{}s", request.timeout.as_secs()));
+        let timeout_param = config
+            .compatibility
+            .timeout_param
+            .unwrap_or_else(|| flavor.is_elasticsearch());
+        if timeout_param {
+            query_params.insert("timeout".into(), format!("{}s", request.timeout.as_secs()));
+        }
 
         if let Some(pipeline) = &config.pipeline {
             query_params.insert("pipeline".into(), pipeline.into());
@@ -382,8 +817,26 @@ impl ElasticSearchCommon {
         for (p, v) in &query_params {
             query.append_pair(&p[..], &v[..]);
         }
-        let bulk_url = format!("{}/_bulk?{}", base_url, query.finish());
-        let bulk_uri = bulk_url.parse::<Uri>().unwrap();
+        let bulk_path = config
+            .compatibility
+            .bulk_path
+            .as_deref()
+            .unwrap_or_else(|| flavor.bulk_path());
+        let query = query.finish();
+        let bulk_url = if query.is_empty() {
+            format!("{}{}", base_url, bulk_path)
+        } else {
+            format!("{}{}?{}", base_url, bulk_path, query)
+        };
+        let bulk_uri = bulk_url.parse::<Uri>().with_context(|| InvalidHost {
+            host: &config.endpoint,
+        })?;
+        let healthcheck_path = config
+            .compatibility
+            .healthcheck_path
+            .as_deref()
+            .unwrap_or_else(|| flavor.healthcheck_path());
+        let healthcheck_uri = format!("{}{}", base_url, healthcheck_path);
 
         let tls_settings = TlsSettings::from_options(&config.tls)?;
         let config = config.clone();
@@ -391,9 +844,13 @@ impl ElasticSearchCommon {
         Ok(Self {
             base_url,
             bulk_uri,
+            healthcheck_uri,
+            suppress_type_name,
             authorization,
             credentials,
+            aws_service,
             index,
+            bulk_action,
             doc_type,
             tls_settings,
             config,
@@ -403,8 +860,26 @@ impl ElasticSearchCommon {
         })
     }
 
+    fn bulk_action(&self, event: &Event) -> Option<BulkAction> {
+        let action = self
+            .bulk_action
+            .render_string(event)
+            .map_err(|missing_keys| {
+                emit!(ElasticSearchMissingKeys {
+                    keys: &missing_keys
+                });
+            })
+            .ok()?;
+
+        let bulk_action = BulkAction::parse(&action);
+        if bulk_action.is_none() {
+            emit!(ElasticSearchInvalidBulkAction { action: &action });
+        }
+        bulk_action
+    }
+
     fn signed_request(&self, method: &str, uri: &Uri, use_params: bool) -> SignedRequest {
-        let mut request = SignedRequest::new(method, "es", &self.region, uri.path());
+        let mut request = SignedRequest::new(method, self.aws_service, &self.region, uri.path());
         if use_params {
             for (key, value) in &self.query_params {
                 request.add_param(key, value);
@@ -415,12 +890,17 @@ impl ElasticSearchCommon {
 }
 
 async fn healthcheck(client: HttpClient, common: ElasticSearchCommon) -> crate::Result<()> {
-    let mut builder = Request::get(format!("{}/_cluster/health", common.base_url));
+    if common.aws_service == AOSS_SERVICE {
+        debug!("Amazon OpenSearch Serverless has no cluster health API; skipping healthcheck.");
+        return Ok(());
+    }
+
+    let mut builder = Request::get(&common.healthcheck_uri);
 
     match &common.credentials {
         None => {
             if let Some(authorization) = &common.authorization {
-                builder = authorization.apply_builder(builder);
+                builder = authorization.apply(builder);
             }
         }
         Some(credentials_provider) => {
@@ -437,6 +917,23 @@ async fn healthcheck(client: HttpClient, common: ElasticSearchCommon) -> crate::
     }
 }
 
+const AOSS_SERVICE: &str = "aoss";
+
+/// Collections of Amazon OpenSearch Serverless are signed for a different
+/// service than the managed domains.
+fn aws_service(endpoint: &str) -> &'static str {
+    let serverless = endpoint
+        .parse::<Uri>()
+        .ok()
+        .and_then(|uri| uri.host().map(|host| host.ends_with(".aoss.amazonaws.com")))
+        .unwrap_or(false);
+    if serverless {
+        AOSS_SERVICE
+    } else {
+        "es"
+    }
+}
+
 async fn finish_signer(
     signer: &mut SignedRequest,
     credentials_provider: &rusoto::AwsCredentialsProvider,
@@ -561,6 +1058,220 @@ mod tests {
 "#;
         assert_eq!(std::str::from_utf8(&encoded).unwrap(), &expected[..]);
     }
+
+    #[test]
+    fn encodes_data_stream_actions() {
+        let config = ElasticSearchConfig {
+            endpoint: String::from("https://example.com"),
+            mode: ElasticSearchMode::DataStream,
+            data_stream: Some(DataStreamConfig {
+                dataset: "{{ service }}".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let es = ElasticSearchCommon::parse_config(&config).unwrap();
+
+        let mut event = Event::from("hello there");
+        event.as_mut_log().insert("service", "api");
+        let timestamp = event.as_log()[log_schema().timestamp_key()].clone();
+
+        let encoded = es.encode_event(event).unwrap();
+        let mut lines = encoded.split(|&byte| byte == b'\n');
+        let action: serde_json::Value = serde_json::from_slice(lines.next().unwrap()).unwrap();
+        let document: serde_json::Value = serde_json::from_slice(lines.next().unwrap()).unwrap();
+
+        assert_eq!(json!({"create": {"_index": "logs-api-default"}}), action);
+        assert_eq!(json!(timestamp), document["@timestamp"]);
+        assert!(document.get("timestamp").is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_data_stream_configs() {
+        let config = ElasticSearchConfig {
+            endpoint: String::from("https://example.com"),
+            mode: ElasticSearchMode::DataStream,
+            bulk_action: Some("index".into()),
+            ..Default::default()
+        };
+        assert!(ElasticSearchCommon::parse_config(&config).is_err());
+
+        let config = ElasticSearchConfig {
+            bulk_action: None,
+            index: Some("logs".into()),
+            ..config
+        };
+        assert!(ElasticSearchCommon::parse_config(&config).is_err());
+    }
+
+    #[test]
+    fn renders_bulk_action_per_event() {
+        let config = ElasticSearchConfig {
+            endpoint: String::from("https://example.com"),
+            index: Some(String::from("alias")),
+            bulk_action: Some(String::from("{{ action }}")),
+            suppress_type_name: true,
+            require_alias: true,
+            ..Default::default()
+        };
+        let es = ElasticSearchCommon::parse_config(&config).unwrap();
+
+        let mut event = Event::from("hello there");
+        event.as_mut_log().insert("action", "create");
+        let encoded = es.encode_event(event).unwrap();
+        let expected = r#"{"create":{"_index":"alias","require_alias":true}}"#;
+        assert!(std::str::from_utf8(&encoded).unwrap().starts_with(expected));
+
+        let mut event = Event::from("hello there");
+        event.as_mut_log().insert("action", "delete");
+        assert!(es.encode_event(event).is_none());
+
+        let config = ElasticSearchConfig {
+            bulk_action: Some(String::from("update")),
+            ..config
+        };
+        assert!(ElasticSearchCommon::parse_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn authorizes_with_api_key() {
+        let config = ElasticSearchConfig {
+            endpoint: String::from("https://example.com"),
+            auth: Some(ElasticSearchAuth::ApiKey {
+                id: Some("id".into()),
+                api_key: "key".into(),
+            }),
+            ..Default::default()
+        };
+        let es = ElasticSearchCommon::parse_config(&config).unwrap();
+
+        let request = es.build_request(Vec::new()).await.unwrap();
+        assert_eq!(
+            request.headers()["Authorization"],
+            format!("ApiKey {}", base64::encode("id:key"))
+        );
+    }
+
+    #[test]
+    fn detects_serverless_endpoints() {
+        assert_eq!(
+            aws_service("https://abc123.us-east-1.aoss.amazonaws.com"),
+            "aoss"
+        );
+        assert_eq!(
+            aws_service("https://search-logs-abc123.us-east-1.es.amazonaws.com"),
+            "es"
+        );
+        assert_eq!(aws_service("http://localhost:4571"), "es");
+    }
+
+    #[test]
+    fn picks_out_rejected_items() {
+        let body = concat!(
+            "{\"index\":{}}\n{\"message\":\"a\"}\n",
+            "{\"create\":{}}\n{\"message\":\"b\"}\n",
+            "{\"index\":{}}\n{\"message\":\"c\"}\n",
+        );
+        let response: BulkResponse = serde_json::from_value(json!({
+            "errors": true,
+            "items": [
+                {"index": {"status": 201}},
+                {"create": {"status": 429, "error": {"type": "es_rejected_execution_exception", "reason": "queue is full"}}},
+                {"index": {"status": 400, "error": {"type": "mapper_parsing_exception", "reason": "failed to parse"}}},
+            ]
+        }))
+        .unwrap();
+
+        let retry_body = response.retry_body(body.as_bytes()).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&retry_body).unwrap(),
+            "{\"create\":{}}\n{\"message\":\"b\"}\n"
+        );
+
+        // Nothing is retried if the response doesn't match the request.
+        assert!(response.retry_body(b"{\"index\":{}}\n{}\n").is_none());
+    }
+
+    #[test]
+    fn applies_flavor_quirks() {
+        let config = ElasticSearchConfig {
+            endpoint: String::from("http://localhost:7280"),
+            index: Some(String::from("logs")),
+            flavor: Flavor::Quickwit,
+            ..Default::default()
+        };
+        let es = ElasticSearchCommon::parse_config(&config).unwrap();
+        assert_eq!(
+            es.bulk_uri.to_string(),
+            "http://localhost:7280/api/v1/_elastic/_bulk"
+        );
+        assert_eq!(es.healthcheck_uri, "http://localhost:7280/health/readyz");
+
+        let encoded = es.encode_event(Event::from("hello there")).unwrap();
+        let expected = r#"{"index":{"_index":"logs"}}"#;
+        assert!(std::str::from_utf8(&encoded).unwrap().starts_with(expected));
+
+        let config = ElasticSearchConfig {
+            endpoint: String::from("http://localhost:4080"),
+            flavor: Flavor::ZincSearch,
+            compatibility: CompatibilityConfig {
+                bulk_path: Some(String::from("/es/_bulk")),
+                timeout_param: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let es = ElasticSearchCommon::parse_config(&config).unwrap();
+        assert_eq!(
+            es.bulk_uri.to_string(),
+            "http://localhost:4080/es/_bulk?timeout=60s"
+        );
+        assert_eq!(es.healthcheck_uri, "http://localhost:4080/healthz");
+
+        let config = ElasticSearchConfig {
+            pipeline: Some(String::from("parse")),
+            ..config
+        };
+        assert!(ElasticSearchCommon::parse_config(&config).is_err());
+    }
+
+    #[test]
+    fn reports_request_errors() {
+        assert_eq!(
+            get_request_error(
+                r#"{"error":{"type":"index_not_found_exception","reason":"no such index [logs]"},"status":404}"#
+            ),
+            "error type: index_not_found_exception, reason: no such index [logs]"
+        );
+        assert_eq!(
+            get_request_error(r#"{"error":"index name cannot start with _"}"#),
+            "index name cannot start with _"
+        );
+        assert_eq!(
+            get_request_error(r#"{"message":"index `logs` not found"}"#),
+            "index `logs` not found"
+        );
+        assert_eq!(get_request_error("Bad Request"), "Bad Request");
+
+        // Errors without per-item results still fail the request.
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .body(Bytes::from(r#"{"errors":true}"#))
+            .unwrap();
+        assert!(matches!(
+            ElasticSearchRetryLogic.should_retry_response(&response),
+            RetryAction::DontRetry(_)
+        ));
+    }
+
+    #[test]
+    fn reports_create_errors() {
+        let json = r#"{"took":3,"errors":true,"items":[{"create":{"_index":"logs-generic-default","status":400,"error":{"type":"illegal_argument_exception","reason":"only write ops with an op_type of create are allowed in data streams"}}}]}"#;
+        assert_eq!(
+            get_error_reason(json),
+            "error type: illegal_argument_exception, reason: only write ops with an op_type of create are allowed in data streams"
+        );
+    }
 }
 
 #[cfg(test)]
@@ -10,16 +10,21 @@ use crate::{
     },
     template::Template,
 };
-use async_compression::tokio_02::write::GzipEncoder;
+use async_compression::tokio_02::write::{GzipEncoder, ZstdEncoder};
 use async_trait::async_trait;
 use bytes::Bytes;
+use chrono::Utc;
 use futures::{
     future,
     stream::{BoxStream, StreamExt},
     FutureExt,
 };
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use std::{
+    ffi::OsString,
+    path::Path,
+    time::{Duration, Instant, SystemTime},
+};
 
 use tokio::{
     fs::{self, File},
@@ -44,6 +49,16 @@ pub struct FileSinkConfig {
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
     pub compression: Compression,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub rotation: RotationConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub fsync: FsyncPolicy,
 }
 
 inventory::submit! {
@@ -57,6 +72,8 @@ impl GenerateConfig for FileSinkConfig {
             idle_timeout_secs: None,
             encoding: Default::default(),
             compression: Default::default(),
+            rotation: Default::default(),
+            fsync: Default::default(),
         })
         .unwrap()
     }
@@ -79,6 +96,7 @@ impl Default for Encoding {
 #[serde(rename_all = "snake_case")]
 pub enum Compression {
     Gzip,
+    Zstd,
     None,
 }
 
@@ -88,9 +106,44 @@ impl Default for Compression {
     }
 }
 
+/// Files are rotated once they reach `max_bytes` or are older than
+/// `interval_secs`, by renaming them with `suffix` appended to their path.
+#[derive(Deserialize, Serialize, Debug, Default, Eq, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RotationConfig {
+    pub max_bytes: Option<u64>,
+    pub interval_secs: Option<u64>,
+    pub suffix: Option<String>,
+    pub max_files: Option<usize>,
+}
+
+const DEFAULT_ROTATION_SUFFIX: &str = ".%Y%m%d%H%M%S";
+
+impl RotationConfig {
+    fn is_enabled(&self) -> bool {
+        self.max_bytes.is_some() || self.interval_secs.is_some()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum FsyncPolicy {
+    /// Only sync files when they're closed, rotated or idle.
+    OnClose,
+    /// Sync files after every event written to them.
+    Always,
+}
+
+impl Default for FsyncPolicy {
+    fn default() -> Self {
+        FsyncPolicy::OnClose
+    }
+}
+
 enum OutFile {
     Regular(File),
     Gzip(GzipEncoder<File>),
+    Zstd(ZstdEncoder<File>),
 }
 
 impl OutFile {
@@ -98,6 +151,7 @@ impl OutFile {
         match compression {
             Compression::None => OutFile::Regular(file),
             Compression::Gzip => OutFile::Gzip(GzipEncoder::new(file)),
+            Compression::Zstd => OutFile::Zstd(ZstdEncoder::new(file)),
         }
     }
 
@@ -105,6 +159,7 @@ impl OutFile {
         match self {
             OutFile::Regular(file) => file.sync_all().await,
             OutFile::Gzip(gzip) => gzip.get_mut().sync_all().await,
+            OutFile::Zstd(zstd) => zstd.get_mut().sync_all().await,
         }
     }
 
@@ -112,6 +167,7 @@ impl OutFile {
         match self {
             OutFile::Regular(file) => file.shutdown().await,
             OutFile::Gzip(gzip) => gzip.shutdown().await,
+            OutFile::Zstd(zstd) => zstd.shutdown().await,
         }
     }
 
@@ -119,9 +175,21 @@ impl OutFile {
         match self {
             OutFile::Regular(file) => file.write_all(src).await,
             OutFile::Gzip(gzip) => gzip.write_all(src).await,
+            OutFile::Zstd(zstd) => zstd.write_all(src).await,
         }
     }
 
+    /// Flushes any data buffered by the encoder and syncs it to the
+    /// filesystem, leaving the file open.
+    async fn sync(&mut self) -> Result<(), std::io::Error> {
+        match self {
+            OutFile::Regular(file) => file.flush().await?,
+            OutFile::Gzip(gzip) => gzip.flush().await?,
+            OutFile::Zstd(zstd) => zstd.flush().await?,
+        }
+        self.sync_all().await
+    }
+
     /// Shutdowns by flushing data, writing headers, and syncing all of that
     /// data and metadata to the filesystem.
     async fn close(&mut self) -> Result<(), std::io::Error> {
@@ -130,6 +198,53 @@ impl OutFile {
     }
 }
 
+/// An open file, along with what's needed to decide when to rotate it.
+struct ActiveFile {
+    file: OutFile,
+    /// The size of the file when it was opened, plus the bytes written to it
+    /// since, counted before compression.
+    size: u64,
+    created: SystemTime,
+}
+
+impl ActiveFile {
+    async fn open(path: BytesPath, compression: Compression) -> std::io::Result<Self> {
+        let file = open_file(path).await?;
+        let metadata = file.metadata().await?;
+        Ok(Self {
+            file: OutFile::new(file, compression),
+            size: metadata.len(),
+            created: metadata.created().unwrap_or_else(|_| SystemTime::now()),
+        })
+    }
+
+    /// Whether writing `len` more bytes requires rotating the file first. A
+    /// file is never rotated while empty, so events larger than `max_bytes`
+    /// still get written.
+    fn should_rotate(&self, rotation: &RotationConfig, len: usize) -> bool {
+        let too_large = rotation.max_bytes.map_or(false, |max_bytes| {
+            self.size > 0 && self.size + len as u64 > max_bytes
+        });
+        let too_old = rotation.interval_secs.map_or(false, |secs| {
+            SystemTime::now()
+                .duration_since(self.created)
+                .unwrap_or_default()
+                >= Duration::from_secs(secs)
+        });
+        too_large || too_old
+    }
+
+    async fn write_all(&mut self, src: &[u8]) -> Result<(), std::io::Error> {
+        self.file.write_all(src).await?;
+        self.size += src.len() as u64;
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<(), std::io::Error> {
+        self.file.close().await
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "file")]
 impl SinkConfig for FileSinkConfig {
@@ -159,8 +274,10 @@ pub struct FileSink {
     path: Template,
     encoding: EncodingConfigWithDefault<Encoding>,
     idle_timeout: Duration,
-    files: ExpiringHashMap<Bytes, OutFile>,
+    files: ExpiringHashMap<Bytes, ActiveFile>,
     compression: Compression,
+    rotation: RotationConfig,
+    fsync: FsyncPolicy,
 }
 
 impl FileSink {
@@ -172,6 +289,8 @@ impl FileSink {
             idle_timeout: Duration::from_secs(config.idle_timeout_secs.unwrap_or(30)),
             files: ExpiringHashMap::default(),
             compression: config.compression,
+            rotation: config.rotation.clone(),
+            fsync: config.fsync,
         }
     }
 
@@ -269,6 +388,19 @@ impl FileSink {
             }
         };
 
+        let mut buf = encode_event(&self.encoding, event);
+        buf.push(b'\n');
+
+        if self.rotation.is_enabled() {
+            let should_rotate = self
+                .files
+                .get(&path)
+                .map_or(false, |file| file.should_rotate(&self.rotation, buf.len()));
+            if should_rotate {
+                self.rotate(&path).await;
+            }
+        }
+
         let next_deadline = self.deadline_at();
         trace!(message = "Computed next deadline.", next_deadline = ?next_deadline, path = ?path);
 
@@ -277,7 +409,8 @@ impl FileSink {
             file
         } else {
             trace!(message = "Opening new file.", ?path);
-            let file = match open_file(BytesPath::new(path.clone())).await {
+            let file = match ActiveFile::open(BytesPath::new(path.clone()), self.compression).await
+            {
                 Ok(file) => file,
                 Err(error) => {
                     // We couldn't open the file for this event.
@@ -288,9 +421,7 @@ impl FileSink {
                 }
             };
 
-            let outfile = OutFile::new(file, self.compression);
-
-            self.files.insert_at(path.clone(), outfile, next_deadline);
+            self.files.insert_at(path.clone(), file, next_deadline);
             emit!(FileOpen {
                 count: self.files.len()
             });
@@ -298,12 +429,96 @@ impl FileSink {
         };
 
         trace!(message = "Writing an event to file.", path = ?path);
-        if let Err(error) = write_event_to_file(file, event, &self.encoding).await {
+        if let Err(error) = file.write_all(&buf[..]).await {
             error!(message = "Failed to write file.", path = ?path, %error);
+        } else if self.fsync == FsyncPolicy::Always {
+            if let Err(error) = file.file.sync().await {
+                error!(message = "Failed to sync file.", path = ?path, %error);
+            }
+        }
+    }
+
+    /// Closes the file at `path` and renames it with the rotation suffix,
+    /// then removes the oldest rotated files beyond `max_files`.
+    async fn rotate(&mut self, path: &Bytes) {
+        if let Some((mut file, _)) = self.files.remove(path) {
+            if let Err(error) = file.close().await {
+                error!(message = "Failed to close file.", path = ?path, %error);
+            }
+            emit!(FileOpen {
+                count: self.files.len()
+            });
+        }
+
+        let active = BytesPath::new(path.clone());
+        let suffix = self
+            .rotation
+            .suffix
+            .as_deref()
+            .unwrap_or(DEFAULT_ROTATION_SUFFIX);
+        let rotated = rotated_path(active.as_ref(), suffix).await;
+        debug!(message = "Rotating file.", path = ?path, rotated = ?rotated);
+        if let Err(error) = fs::rename(&active, &rotated).await {
+            error!(message = "Failed to rotate file.", path = ?path, %error);
+            return;
+        }
+
+        if let Some(max_files) = self.rotation.max_files {
+            if let Err(error) = remove_rotated_files(active.as_ref(), max_files).await {
+                error!(message = "Failed to remove rotated files.", path = ?path, %error);
+            }
         }
     }
 }
 
+/// Appends the rendered `suffix` to `path`, along with a counter if a file
+/// was already rotated to that name.
+async fn rotated_path(path: &Path, suffix: &str) -> OsString {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(Utc::now().format(suffix).to_string());
+
+    let mut candidate = rotated.clone();
+    let mut counter = 1;
+    while fs::metadata(&candidate).await.is_ok() {
+        candidate = rotated.clone();
+        candidate.push(format!(".{}", counter));
+        counter += 1;
+    }
+    candidate
+}
+
+/// Removes the oldest files rotated from `path`, keeping `keep` of them.
+/// Rotated files are those in the same directory whose names extend the
+/// name of the active file.
+async fn remove_rotated_files(path: &Path, keep: usize) -> std::io::Result<()> {
+    let name = match path.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => return Ok(()),
+    };
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+
+    let mut rotated = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let entry_name = entry.file_name().to_string_lossy().into_owned();
+        if entry_name.len() > name.len() && entry_name.starts_with(&name) {
+            let modified = entry.metadata().await?.modified()?;
+            rotated.push((modified, entry.path()));
+        }
+    }
+
+    rotated.sort();
+    let excess = rotated.len().saturating_sub(keep);
+    for (_, path) in rotated.into_iter().take(excess) {
+        trace!(message = "Removing rotated file.", path = ?path);
+        fs::remove_file(path).await?;
+    }
+    Ok(())
+}
+
 async fn open_file(path: impl AsRef<std::path::Path>) -> std::io::Result<File> {
     let parent = path.as_ref().parent();
 
@@ -332,16 +547,6 @@ pub fn encode_event(encoding: &EncodingConfigWithDefault<Encoding>, mut event: E
     }
 }
 
-async fn write_event_to_file(
-    file: &mut OutFile,
-    event: Event,
-    encoding: &EncodingConfigWithDefault<Encoding>,
-) -> Result<(), std::io::Error> {
-    let mut buf = encode_event(encoding, event);
-    buf.push(b'\n');
-    file.write_all(&buf[..]).await
-}
-
 #[async_trait]
 impl StreamSink for FileSink {
     async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()> {
@@ -376,6 +581,8 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotation: Default::default(),
+            fsync: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -401,6 +608,8 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::Gzip,
+            rotation: Default::default(),
+            fsync: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -415,6 +624,71 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn single_partition_zstd() {
+        trace_init();
+
+        let template = temp_file();
+
+        let config = FileSinkConfig {
+            path: template.clone().try_into().unwrap(),
+            idle_timeout_secs: None,
+            encoding: Encoding::Text.into(),
+            compression: Compression::Zstd,
+            rotation: Default::default(),
+            fsync: Default::default(),
+        };
+
+        let mut sink = FileSink::new(&config, Acker::Null);
+        let (input, _) = random_lines_with_stream(100, 64);
+
+        let events = Box::pin(stream::iter(input.clone().into_iter().map(Event::from)));
+        sink.run(events).await.unwrap();
+
+        let output = zstd::decode_all(std::fs::File::open(template).unwrap()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        for (input, output) in input.into_iter().zip(output.lines()) {
+            assert_eq!(input, output);
+        }
+    }
+
+    #[tokio::test]
+    async fn rotates_by_size() {
+        trace_init();
+
+        let directory = temp_dir();
+        let path = directory.join("app.log");
+
+        let config = FileSinkConfig {
+            path: path.clone().try_into().unwrap(),
+            idle_timeout_secs: None,
+            encoding: Encoding::Text.into(),
+            compression: Compression::None,
+            rotation: RotationConfig {
+                // Two lines per file.
+                max_bytes: Some(20),
+                interval_secs: None,
+                suffix: Some(".rotated".into()),
+                max_files: Some(1),
+            },
+            fsync: FsyncPolicy::Always,
+        };
+
+        let mut sink = FileSink::new(&config, Acker::Null);
+        let input: Vec<_> = (0..5).map(|i| format!("line {:04}", i)).collect();
+
+        let events = Box::pin(stream::iter(input.clone().into_iter().map(Event::from)));
+        sink.run(events).await.unwrap();
+
+        assert_eq!(lines_from_file(&path), &input[4..]);
+        assert_eq!(
+            lines_from_file(directory.join("app.log.rotated.1")),
+            &input[2..4]
+        );
+        // Only the newest rotated file is kept.
+        assert!(!directory.join("app.log.rotated").exists());
+    }
+
     #[tokio::test]
     async fn many_partitions() {
         trace_init();
@@ -431,6 +705,8 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotation: Default::default(),
+            fsync: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -512,6 +788,8 @@ mod tests {
             idle_timeout_secs: Some(1),
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotation: Default::default(),
+            fsync: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
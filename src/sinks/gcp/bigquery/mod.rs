@@ -0,0 +1,889 @@
+mod proto;
+mod schema;
+
+use self::schema::TableSchema;
+use super::{healthcheck_response, GcpAuthConfig, GcpCredentials, Scope};
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::Event,
+    http::{HttpClient, HttpError},
+    internal_events::{
+        BigqueryEventRejected, BigquerySchemaFetched, BigqueryStreamCommitted,
+        BigqueryTemplateMissingKeys,
+    },
+    sinks::{
+        util::{
+            grpc, retries::RetryLogic, sink::Response, BatchConfig, BatchSettings, EncodedLength,
+            Partition, PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer,
+            ServiceBuilderExt, TowerRequestConfig, VecBuffer,
+        },
+        Healthcheck, UriParseError, VectorSink,
+    },
+    template::Template,
+    tls::{TlsOptions, TlsSettings},
+    Pipeline,
+};
+use futures::{compat::Sink01CompatExt, future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use http::{Request, StatusCode, Uri};
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use prost::Message;
+use prost_types::DescriptorProto;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex as AsyncMutex;
+use tower::{Service, ServiceBuilder};
+
+const WRITE_URL: &str = "https://bigquerystorage.googleapis.com";
+const API_URL: &str = "https://bigquery.googleapis.com/bigquery/v2";
+const WRITE_SERVICE: &str = "google.cloud.bigquery.storage.v1.BigQueryWrite";
+
+/// The output events that don't fit their table are sent to.
+const FAILED: &str = "failed";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("The Storage Write API doesn't accept API keys, credentials are required"))]
+    ApiKeyUnsupported,
+}
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("Dataset or table not found"))]
+    NotFound,
+}
+
+#[derive(Debug, Snafu)]
+enum WriteError {
+    #[snafu(display("Failed to build request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Failed to send request: {}", source))]
+    SendRequest { source: HttpError },
+    #[snafu(display("Failed to read response: {}", reason))]
+    ReadResponse { reason: String },
+    #[snafu(display("Unexpected response status: {}", status))]
+    UnexpectedStatus { status: StatusCode },
+    #[snafu(display("{} failed with {}", method, status))]
+    Call {
+        method: &'static str,
+        status: grpc::Status,
+    },
+    #[snafu(display("Invalid {} response", method))]
+    InvalidResponse { method: &'static str },
+    #[snafu(display("Invalid table schema: {}", source))]
+    InvalidSchema { source: serde_json::Error },
+    #[snafu(display("Failed to commit stream {:?}: {}", stream, reason))]
+    Commit { stream: String, reason: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BigqueryConfig {
+    pub project: String,
+    pub dataset: String,
+    pub table: Template,
+    /// Drop fields that aren't columns of the table, instead of sending the
+    /// events to the failed output.
+    #[serde(default)]
+    pub ignore_unknown_fields: bool,
+    /// How long a table's schema is trusted before events that don't fit it
+    /// get it fetched again.
+    #[serde(default = "default_schema_refresh_secs")]
+    pub schema_refresh_secs: u64,
+
+    #[serde(flatten)]
+    pub auth: GcpAuthConfig,
+
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    pub tls: Option<TlsOptions>,
+}
+
+fn default_schema_refresh_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    SinkDescription::new::<BigqueryConfig>("gcp_bigquery")
+}
+
+impl GenerateConfig for BigqueryConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"project = "my-project"
+            dataset = "my-dataset"
+            table = "my-table"
+            credentials_path = "/path/to/credentials.json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_bigquery")]
+impl SinkConfig for BigqueryConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        // Without a credentials file, an API key is all `make_credentials`
+        // looks at.
+        let creds = self
+            .auth
+            .make_credentials(Scope::BigQuery)
+            .await?
+            .ok_or(BuildError::ApiKeyUnsupported)?;
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let service = BigqueryService {
+            client: HttpClient::new_http2(tls.clone())?,
+            api_client: HttpClient::new(tls)?,
+            creds: Some(creds),
+            write_url: WRITE_URL.into(),
+            api_url: API_URL.into(),
+            project: self.project.clone(),
+            dataset: self.dataset.clone(),
+            ignore_unknown_fields: self.ignore_unknown_fields,
+            schema_refresh: Duration::from_secs(self.schema_refresh_secs),
+            schemas: Default::default(),
+            failed: cx.output(FAILED),
+        };
+
+        let table = if self.table.is_dynamic() {
+            None
+        } else {
+            Some(self.table.get_ref().to_owned())
+        };
+        let healthcheck = service.clone().healthcheck(table).boxed();
+
+        // Appends are limited to 10MB, and the size of events is estimated
+        // from JSON, which is larger than the rows they become.
+        let batch = BatchSettings::default()
+            .events(500)
+            .bytes(bytesize::mib(9u64))
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&TowerRequestConfig::default());
+
+        let svc = ServiceBuilder::new()
+            .map(WriteRequest::new)
+            .settings(request, BigqueryRetryLogic)
+            .service(service);
+
+        let table = self.table.clone();
+        let sink = PartitionBatchSink::new(
+            svc,
+            PartitionBuffer::new(VecBuffer::new(batch.size)),
+            batch.timeout,
+            cx.acker(),
+        )
+        .sink_map_err(|error| error!(message = "Fatal gcp_bigquery sink error.", %error))
+        .with_flat_map(move |event| stream::iter(TableEvent::new(event, &table)).map(Ok));
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "gcp_bigquery"
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        vec![FAILED.to_owned()]
+    }
+}
+
+/// An event with the table it's written to.
+#[derive(Clone, Debug)]
+struct TableEvent {
+    event: Event,
+    table: String,
+    size: usize,
+}
+
+impl TableEvent {
+    fn new(event: Event, table: &Template) -> Option<Self> {
+        let table = table
+            .render_string(&event)
+            .map_err(|keys| emit!(BigqueryTemplateMissingKeys { keys: &keys }))
+            .ok()?;
+        let size = serde_json::to_vec(event.as_log()).map_or(0, |json| json.len());
+        Some(Self { event, table, size })
+    }
+}
+
+impl Partition<String> for TableEvent {
+    fn partition(&self) -> String {
+        self.table.clone()
+    }
+}
+
+impl EncodedLength for TableEvent {
+    fn encoded_length(&self) -> usize {
+        self.size
+    }
+}
+
+/// A batch of events for one table. Its progress is shared by the retries
+/// of the request, so that the batch is appended to a single pending stream
+/// and that stream is committed at most once.
+#[derive(Clone)]
+struct WriteRequest {
+    table: String,
+    state: Arc<AsyncMutex<WriteState>>,
+}
+
+impl WriteRequest {
+    fn new(request: PartitionInnerBuffer<Vec<TableEvent>, String>) -> Self {
+        let (events, table) = request.into_parts();
+        let state = WriteState {
+            events: events.into_iter().map(|event| event.event).collect(),
+            ..Default::default()
+        };
+        Self {
+            table,
+            state: Arc::new(AsyncMutex::new(state)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct WriteState {
+    step: Step,
+    /// Events waiting to be encoded.
+    events: Vec<Event>,
+    /// Rows waiting to be appended, with the events they were encoded from.
+    rows: Vec<(Event, Vec<u8>)>,
+    schema: Option<Arc<CachedSchema>>,
+    stream: Option<String>,
+    /// Whether the rows were encoded again after BigQuery rejected the
+    /// schema they were encoded with.
+    reencoded: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Derivative)]
+#[derivative(Default)]
+enum Step {
+    #[derivative(Default)]
+    Encode,
+    Create,
+    Append,
+    Finalize,
+    Commit,
+    Done,
+}
+
+struct CachedSchema {
+    schema: TableSchema,
+    descriptor: DescriptorProto,
+    fetched_at: Instant,
+}
+
+/// The body of a `tables.get` response, limited to the schema.
+#[derive(Deserialize)]
+struct TableResource {
+    schema: Option<TableSchema>,
+}
+
+#[derive(Debug)]
+struct WriteResponse {
+    rows: usize,
+}
+
+impl Response for WriteResponse {}
+
+#[derive(Clone)]
+struct BigqueryService {
+    client: HttpClient,
+    api_client: HttpClient,
+    creds: Option<GcpCredentials>,
+    write_url: String,
+    api_url: String,
+    project: String,
+    dataset: String,
+    ignore_unknown_fields: bool,
+    schema_refresh: Duration,
+    schemas: Arc<Mutex<HashMap<String, Arc<CachedSchema>>>>,
+    failed: Option<Pipeline>,
+}
+
+impl BigqueryService {
+    fn table_path(&self, table: &str) -> String {
+        format!(
+            "projects/{}/datasets/{}/tables/{}",
+            self.project, self.dataset, table
+        )
+    }
+
+    async fn write(self, request: WriteRequest) -> crate::Result<WriteResponse> {
+        let table = request.table.as_str();
+        let mut state = request.state.lock().await;
+        loop {
+            state.step = match state.step {
+                Step::Encode => {
+                    self.encode(table, &mut state).await?;
+                    match (&state.stream, state.rows.is_empty()) {
+                        (_, true) => Step::Done,
+                        (None, false) => Step::Create,
+                        (Some(_), false) => Step::Append,
+                    }
+                }
+                Step::Create => {
+                    state.stream = Some(self.create_stream(table).await?);
+                    Step::Append
+                }
+                Step::Append => self.append(table, &mut state).await?,
+                Step::Finalize => {
+                    let stream = state.stream.clone().expect("Streams are created first.");
+                    self.finalize_stream(&stream).await?;
+                    Step::Commit
+                }
+                Step::Commit => {
+                    let stream = state.stream.clone().expect("Streams are created first.");
+                    self.commit_stream(table, &stream).await?;
+                    emit!(BigqueryStreamCommitted {
+                        stream: &stream,
+                        rows: state.rows.len(),
+                    });
+                    Step::Done
+                }
+                Step::Done => {
+                    return Ok(WriteResponse {
+                        rows: state.rows.len(),
+                    })
+                }
+            };
+        }
+    }
+
+    /// Encodes the events waiting in `state` as rows, and sends the ones
+    /// that don't fit the table to the failed output. Those may only not fit
+    /// because columns were added since the schema was fetched, so if it was
+    /// a while ago, it's fetched again first.
+    async fn encode(&self, table: &str, state: &mut WriteState) -> Result<(), WriteError> {
+        let events = std::mem::take(&mut state.events);
+        let mut schema = match self.schema(table, false).await? {
+            Some(schema) => schema,
+            None => {
+                let reason = "Table not found.".to_owned();
+                let rejected = events.into_iter().map(|event| (event, reason.clone()));
+                self.reject(table, rejected.collect()).await;
+                return Ok(());
+            }
+        };
+
+        let (mut rows, mut rejected) = self.encode_rows(&schema.schema, events);
+        if !rejected.is_empty() && schema.fetched_at.elapsed() >= self.schema_refresh {
+            if let Some(fresh) = self.schema(table, true).await? {
+                let events = rows
+                    .into_iter()
+                    .map(|(event, _)| event)
+                    .chain(rejected.into_iter().map(|(event, _)| event))
+                    .collect();
+                let encoded = self.encode_rows(&fresh.schema, events);
+                rows = encoded.0;
+                rejected = encoded.1;
+                schema = fresh;
+            }
+        }
+
+        self.reject(table, rejected).await;
+        state.rows = rows;
+        state.schema = Some(schema);
+        Ok(())
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn encode_rows(
+        &self,
+        schema: &TableSchema,
+        events: Vec<Event>,
+    ) -> (Vec<(Event, Vec<u8>)>, Vec<(Event, String)>) {
+        let mut rows = Vec::new();
+        let mut rejected = Vec::new();
+        for event in events {
+            match schema.encode(event.as_log(), self.ignore_unknown_fields) {
+                Ok(row) => rows.push((event, row)),
+                Err(mismatch) => rejected.push((event, mismatch.to_string())),
+            }
+        }
+        (rows, rejected)
+    }
+
+    async fn reject(&self, table: &str, rejected: Vec<(Event, String)>) {
+        for (_, reason) in &rejected {
+            emit!(BigqueryEventRejected { table, reason });
+        }
+
+        if let Some(failed) = &self.failed {
+            let mut events = stream::iter(rejected.into_iter().map(|(event, _)| Ok(event)));
+            // This only fails when the topology is shutting down.
+            let _ = failed.clone().sink_compat().send_all(&mut events).await;
+        }
+    }
+
+    /// The schema of the table, from the cache unless `refresh` is set, or
+    /// `None` if the table doesn't exist.
+    async fn schema(
+        &self,
+        table: &str,
+        refresh: bool,
+    ) -> Result<Option<Arc<CachedSchema>>, WriteError> {
+        if !refresh {
+            if let Some(schema) = self.schemas.lock().unwrap().get(table) {
+                return Ok(Some(Arc::clone(schema)));
+            }
+        }
+
+        let schema = match self.fetch_schema(table).await? {
+            Some(schema) => schema,
+            None => {
+                self.schemas.lock().unwrap().remove(table);
+                return Ok(None);
+            }
+        };
+        emit!(BigquerySchemaFetched {
+            table,
+            columns: schema.fields.len(),
+        });
+
+        let schema = Arc::new(CachedSchema {
+            descriptor: schema.descriptor(),
+            schema,
+            fetched_at: Instant::now(),
+        });
+        self.schemas
+            .lock()
+            .unwrap()
+            .insert(table.to_owned(), Arc::clone(&schema));
+        Ok(Some(schema))
+    }
+
+    async fn fetch_schema(&self, table: &str) -> Result<Option<TableSchema>, WriteError> {
+        let uri = format!("{}/{}?fields=schema", self.api_url, self.table_path(table));
+        let mut request = Request::get(uri)
+            .body(Body::empty())
+            .context(BuildRequest)?;
+        if let Some(creds) = &self.creds {
+            creds.apply(&mut request);
+        }
+
+        let response = self.api_client.send(request).await.context(SendRequest)?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => {
+                let body = hyper::body::to_bytes(response.into_body())
+                    .await
+                    .map_err(|error| WriteError::ReadResponse {
+                        reason: error.to_string(),
+                    })?;
+                let table: TableResource = serde_json::from_slice(&body).context(InvalidSchema)?;
+                Ok(Some(table.schema.unwrap_or_default()))
+            }
+            status => Err(WriteError::UnexpectedStatus { status }),
+        }
+    }
+
+    async fn create_stream(&self, table: &str) -> Result<String, WriteError> {
+        let parent = self.table_path(table);
+        let request = proto::CreateWriteStreamRequest {
+            parent: parent.clone(),
+            write_stream: Some(proto::WriteStream {
+                r#type: proto::write_stream::Type::Pending as i32,
+                ..Default::default()
+            }),
+        };
+        let stream: proto::WriteStream = self
+            .call("CreateWriteStream", ("parent", &parent), &request)
+            .await?;
+        Ok(stream.name)
+    }
+
+    /// Appends the rows at the start of the stream, which makes appending
+    /// them again after a lost response fail with `ALREADY_EXISTS`. Rows
+    /// BigQuery rejects are sent to the failed output, and the others are
+    /// appended again, as nothing was appended.
+    async fn append(&self, table: &str, state: &mut WriteState) -> Result<Step, WriteError> {
+        let stream = state.stream.clone().expect("Streams are created first.");
+        let schema = state.schema.clone().expect("Rows are encoded first.");
+        let request = proto::AppendRowsRequest {
+            write_stream: stream.clone(),
+            offset: Some(0),
+            rows: Some(proto::append_rows_request::Rows::ProtoRows(
+                proto::append_rows_request::ProtoData {
+                    writer_schema: Some(proto::ProtoSchema {
+                        proto_descriptor: Some(schema.descriptor.clone()),
+                    }),
+                    rows: Some(proto::ProtoRows {
+                        serialized_rows: state.rows.iter().map(|(_, row)| row.clone()).collect(),
+                    }),
+                },
+            )),
+            trace_id: String::new(),
+        };
+
+        let response: proto::AppendRowsResponse = match self
+            .call("AppendRows", ("write_stream", &stream), &request)
+            .await
+        {
+            Ok(response) => response,
+            Err(WriteError::Call { status, .. }) => proto::AppendRowsResponse {
+                response: Some(proto::append_rows_response::Response::Error(
+                    proto::google::rpc::Status {
+                        code: status.code as i32,
+                        message: status.message.unwrap_or_default(),
+                    },
+                )),
+                row_errors: Vec::new(),
+            },
+            Err(error) => return Err(error),
+        };
+
+        if !response.row_errors.is_empty() {
+            let mut errors = response
+                .row_errors
+                .into_iter()
+                .map(|error| (error.index as usize, error.message))
+                .collect::<HashMap<_, _>>();
+            let mut rejected = Vec::new();
+            let mut rows = Vec::new();
+            for (index, (event, row)) in state.rows.drain(..).enumerate() {
+                match errors.remove(&index) {
+                    Some(reason) => rejected.push((event, reason)),
+                    None => rows.push((event, row)),
+                }
+            }
+            state.rows = rows;
+            self.reject(table, rejected).await;
+            return Ok(if state.rows.is_empty() {
+                Step::Done
+            } else {
+                Step::Append
+            });
+        }
+
+        match response.response {
+            Some(proto::append_rows_response::Response::AppendResult(_)) => Ok(Step::Finalize),
+            Some(proto::append_rows_response::Response::Error(status)) => {
+                let status = grpc::Status {
+                    code: status.code as u32,
+                    message: Some(status.message),
+                };
+                if status.code == grpc::Status::ALREADY_EXISTS {
+                    Ok(Step::Finalize)
+                } else if status.code == grpc::Status::INVALID_ARGUMENT && !state.reencoded {
+                    // The rows were encoded with a schema the table no longer
+                    // has, so they're encoded again with its current one.
+                    self.schemas.lock().unwrap().remove(table);
+                    state.reencoded = true;
+                    state.events = state.rows.drain(..).map(|(event, _)| event).collect();
+                    Ok(Step::Encode)
+                } else {
+                    Err(WriteError::Call {
+                        method: "AppendRows",
+                        status,
+                    })
+                }
+            }
+            None => Err(WriteError::InvalidResponse {
+                method: "AppendRows",
+            }),
+        }
+    }
+
+    async fn finalize_stream(&self, stream: &str) -> Result<(), WriteError> {
+        let request = proto::FinalizeWriteStreamRequest {
+            name: stream.to_owned(),
+        };
+        let _: proto::FinalizeWriteStreamResponse = self
+            .call("FinalizeWriteStream", ("name", stream), &request)
+            .await?;
+        Ok(())
+    }
+
+    async fn commit_stream(&self, table: &str, stream: &str) -> Result<(), WriteError> {
+        let parent = self.table_path(table);
+        let request = proto::BatchCommitWriteStreamsRequest {
+            parent: parent.clone(),
+            write_streams: vec![stream.to_owned()],
+        };
+        let response: proto::BatchCommitWriteStreamsResponse = self
+            .call("BatchCommitWriteStreams", ("parent", &parent), &request)
+            .await?;
+
+        // A stream committed by an earlier attempt, whose response was lost,
+        // is as good as committed now.
+        let already_committed = proto::storage_error::StorageErrorCode::StreamAlreadyCommitted;
+        match response
+            .stream_errors
+            .into_iter()
+            .find(|error| error.code != already_committed as i32)
+        {
+            None => Ok(()),
+            Some(error) => Err(WriteError::Commit {
+                stream: stream.to_owned(),
+                reason: error.error_message,
+            }),
+        }
+    }
+
+    /// Builds the request of a call to the Write API. The resource it's
+    /// about is repeated in a header, for it to be routed.
+    fn build_call<M: Message>(
+        &self,
+        method: &str,
+        resource: (&str, &str),
+        message: &M,
+    ) -> Result<Request<Body>, WriteError> {
+        let mut body = Vec::with_capacity(message.encoded_len());
+        message
+            .encode(&mut body)
+            .expect("Encoding into a Vec can't fail.");
+
+        let params = format!(
+            "{}={}",
+            resource.0,
+            utf8_percent_encode(resource.1, NON_ALPHANUMERIC)
+        );
+        let mut request = Request::post(format!("{}/{}/{}", self.write_url, WRITE_SERVICE, method))
+            .header("Content-Type", "application/grpc")
+            .header("te", "trailers")
+            .header("x-goog-request-params", params)
+            .body(Body::from(grpc::frame(false, body)))
+            .context(BuildRequest)?;
+        if let Some(creds) = &self.creds {
+            creds.apply(&mut request);
+        }
+        Ok(request)
+    }
+
+    async fn call<M: Message, R: Message + Default>(
+        &self,
+        method: &'static str,
+        resource: (&str, &str),
+        message: &M,
+    ) -> Result<R, WriteError> {
+        let request = self.build_call(method, resource, message)?;
+        let response = self.client.send(request).await.context(SendRequest)?;
+        if !response.status().is_success() {
+            return Err(WriteError::UnexpectedStatus {
+                status: response.status(),
+            });
+        }
+
+        let (messages, status) =
+            grpc::read_response(response)
+                .await
+                .map_err(|error| WriteError::ReadResponse {
+                    reason: error.to_string(),
+                })?;
+        if !status.is_ok() {
+            return Err(WriteError::Call { method, status });
+        }
+        messages
+            .into_iter()
+            .next()
+            .and_then(|message| R::decode(message).ok())
+            .ok_or(WriteError::InvalidResponse { method })
+    }
+
+    /// Checks that the table exists, or the dataset when the table is
+    /// templated.
+    async fn healthcheck(self, table: Option<String>) -> crate::Result<()> {
+        let path = match &table {
+            Some(table) => self.table_path(table),
+            None => format!("projects/{}/datasets/{}", self.project, self.dataset),
+        };
+        let uri = format!("{}/{}", self.api_url, path)
+            .parse::<Uri>()
+            .context(UriParseError)?;
+        let mut request = Request::get(uri).body(Body::empty())?;
+        if let Some(creds) = &self.creds {
+            creds.apply(&mut request);
+        }
+
+        let response = self.api_client.send(request).await?;
+        healthcheck_response(self.creds, HealthcheckError::NotFound.into())(response)
+    }
+}
+
+impl Service<WriteRequest> for BigqueryService {
+    type Response = WriteResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: WriteRequest) -> Self::Future {
+        self.clone().write(request).boxed()
+    }
+}
+
+#[derive(Clone)]
+struct BigqueryRetryLogic;
+
+impl RetryLogic for BigqueryRetryLogic {
+    type Error = WriteError;
+    type Response = WriteResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            WriteError::SendRequest { .. } | WriteError::ReadResponse { .. } => true,
+            WriteError::UnexpectedStatus { status } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            WriteError::Call { status, .. } => status.is_retriable(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::LogEvent, test_util::collect_ready};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<BigqueryConfig>();
+    }
+
+    fn service(failed: Option<Pipeline>) -> BigqueryService {
+        BigqueryService {
+            client: HttpClient::new(None).unwrap(),
+            api_client: HttpClient::new(None).unwrap(),
+            creds: None,
+            write_url: "http://localhost:9999".into(),
+            api_url: "http://localhost:9998".into(),
+            project: "my-project".into(),
+            dataset: "my-dataset".into(),
+            ignore_unknown_fields: false,
+            schema_refresh: Duration::from_secs(60),
+            schemas: Default::default(),
+            failed,
+        }
+    }
+
+    fn cache_schema(service: &BigqueryService, table: &str, schema: &str) {
+        let schema: TableSchema = serde_json::from_str(schema).unwrap();
+        service.schemas.lock().unwrap().insert(
+            table.into(),
+            Arc::new(CachedSchema {
+                descriptor: schema.descriptor(),
+                schema,
+                fetched_at: Instant::now(),
+            }),
+        );
+    }
+
+    fn event(fields: &[(&str, &str)]) -> Event {
+        let mut log = LogEvent::default();
+        for (key, value) in fields {
+            log.insert(*key, *value);
+        }
+        Event::from(log)
+    }
+
+    #[test]
+    fn renders_tables() {
+        let table = Template::try_from("logs_{{ app }}").unwrap();
+
+        let event = TableEvent::new(event(&[("app", "api")]), &table).unwrap();
+        assert_eq!(event.partition(), "logs_api");
+        assert!(event.encoded_length() > 0);
+
+        assert!(TableEvent::new(event(&[]), &table).is_none());
+    }
+
+    #[test]
+    fn builds_calls() {
+        let service = service(None);
+        let request = service
+            .build_call(
+                "FinalizeWriteStream",
+                ("name", "projects/p/streams/s"),
+                &proto::FinalizeWriteStreamRequest {
+                    name: "projects/p/streams/s".into(),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            request.uri(),
+            "http://localhost:9999/google.cloud.bigquery.storage.v1.BigQueryWrite/FinalizeWriteStream"
+        );
+        assert_eq!(request.headers()["Content-Type"], "application/grpc");
+        assert_eq!(
+            request.headers()["x-goog-request-params"],
+            "name=projects%2Fp%2Fstreams%2Fs"
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_mismatched_events_to_failed_output() {
+        let (failed, rx) = Pipeline::new_test();
+        let service = service(Some(failed));
+        cache_schema(
+            &service,
+            "logs",
+            r#"{"fields": [{"name": "message", "type": "STRING", "mode": "REQUIRED"}]}"#,
+        );
+
+        let mut state = WriteState {
+            events: vec![
+                event(&[("message", "hello")]),
+                event(&[("message", "hello"), ("extra", "field")]),
+                event(&[]),
+            ],
+            ..Default::default()
+        };
+        service.encode("logs", &mut state).await.unwrap();
+        drop(service);
+
+        assert_eq!(state.rows.len(), 1);
+        assert_eq!(state.rows[0].0, event(&[("message", "hello")]));
+
+        let failed = collect_ready(rx).await.unwrap();
+        assert_eq!(
+            failed,
+            vec![
+                event(&[("message", "hello"), ("extra", "field")]),
+                event(&[])
+            ]
+        );
+    }
+
+    #[test]
+    fn retries_transient_errors() {
+        let logic = BigqueryRetryLogic;
+        let call = |code| WriteError::Call {
+            method: "AppendRows",
+            status: grpc::Status {
+                code,
+                message: None,
+            },
+        };
+
+        assert!(logic.is_retriable_error(&call(14)));
+        assert!(!logic.is_retriable_error(&call(3)));
+        assert!(logic.is_retriable_error(&WriteError::UnexpectedStatus {
+            status: StatusCode::SERVICE_UNAVAILABLE
+        }));
+        assert!(!logic.is_retriable_error(&WriteError::Commit {
+            stream: "s".into(),
+            reason: "failed".into()
+        }));
+    }
+}
@@ -0,0 +1,23 @@
+//! The Storage Write API messages, laid out in the module tree of their
+//! protobuf packages so the generated code can refer to each other.
+
+pub mod google {
+    pub mod cloud {
+        pub mod bigquery {
+            pub mod storage {
+                pub mod v1 {
+                    include!(concat!(
+                        env!("OUT_DIR"),
+                        "/google.cloud.bigquery.storage.v1.rs"
+                    ));
+                }
+            }
+        }
+    }
+
+    pub mod rpc {
+        include!(concat!(env!("OUT_DIR"), "/google.rpc.rs"));
+    }
+}
+
+pub use self::google::cloud::bigquery::storage::v1::*;
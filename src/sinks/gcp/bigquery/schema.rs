@@ -0,0 +1,508 @@
+//! Maps log events to rows of a table, as protobuf messages described by a
+//! descriptor built from the table's schema.
+
+use crate::event::{LogEvent, Value};
+use chrono::{DateTime, NaiveDate, Utc};
+use prost::encoding::{encode_key, encode_varint, WireType};
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, FieldDescriptorProto,
+};
+use serde::Deserialize;
+use snafu::Snafu;
+use std::collections::BTreeMap;
+
+/// The schema of a table, as returned by the `tables.get` API.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct TableSchema {
+    #[serde(default)]
+    pub fields: Vec<TableField>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct TableField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: FieldType,
+    #[serde(default)]
+    pub mode: FieldMode,
+    #[serde(default)]
+    pub fields: Vec<TableField>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum FieldType {
+    String,
+    Bytes,
+    #[serde(alias = "INT64")]
+    Integer,
+    #[serde(alias = "FLOAT64")]
+    Float,
+    Numeric,
+    #[serde(alias = "BIGDECIMAL")]
+    Bignumeric,
+    #[serde(alias = "BOOL")]
+    Boolean,
+    Timestamp,
+    Date,
+    Time,
+    Datetime,
+    Geography,
+    Json,
+    #[serde(alias = "STRUCT")]
+    Record,
+    /// Types added to BigQuery since, which are written as strings.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Derivative)]
+#[derivative(Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum FieldMode {
+    #[derivative(Default)]
+    Nullable,
+    Required,
+    Repeated,
+}
+
+/// Why an event doesn't fit a table.
+#[derive(Debug, Eq, PartialEq, Snafu)]
+pub enum Mismatch {
+    #[snafu(display("Field {:?} isn't a column of the table", field))]
+    UnknownField { field: String },
+    #[snafu(display("Required column {:?} is missing", field))]
+    MissingRequired { field: String },
+    #[snafu(display("Field {:?} can't be written to a {} column", field, column_type))]
+    InvalidValue {
+        field: String,
+        column_type: &'static str,
+    },
+}
+
+impl FieldType {
+    fn name(self) -> &'static str {
+        match self {
+            FieldType::String => "STRING",
+            FieldType::Bytes => "BYTES",
+            FieldType::Integer => "INTEGER",
+            FieldType::Float => "FLOAT",
+            FieldType::Numeric => "NUMERIC",
+            FieldType::Bignumeric => "BIGNUMERIC",
+            FieldType::Boolean => "BOOLEAN",
+            FieldType::Timestamp => "TIMESTAMP",
+            FieldType::Date => "DATE",
+            FieldType::Time => "TIME",
+            FieldType::Datetime => "DATETIME",
+            FieldType::Geography => "GEOGRAPHY",
+            FieldType::Json => "JSON",
+            FieldType::Record => "RECORD",
+            FieldType::Other => "unsupported",
+        }
+    }
+
+    /// The protobuf types the Storage Write API accepts for each column type.
+    fn proto_type(self) -> Type {
+        match self {
+            FieldType::Bytes => Type::Bytes,
+            FieldType::Integer | FieldType::Timestamp => Type::Int64,
+            FieldType::Float => Type::Double,
+            FieldType::Boolean => Type::Bool,
+            FieldType::Date => Type::Int32,
+            FieldType::Record => Type::Message,
+            FieldType::String
+            | FieldType::Numeric
+            | FieldType::Bignumeric
+            | FieldType::Time
+            | FieldType::Datetime
+            | FieldType::Geography
+            | FieldType::Json
+            | FieldType::Other => Type::String,
+        }
+    }
+}
+
+impl TableSchema {
+    /// Describes rows as a self-contained message, with the messages of
+    /// record columns nested in it. Columns are numbered in schema order.
+    pub fn descriptor(&self) -> DescriptorProto {
+        let mut nested = Vec::new();
+        let mut descriptor = message_descriptor("Row", &self.fields, &mut nested);
+        descriptor.nested_type = nested;
+        descriptor
+    }
+
+    /// Encodes the fields of the event as a row. Fields that aren't columns
+    /// are a mismatch, unless `ignore_unknown_fields` is set.
+    pub fn encode(&self, log: &LogEvent, ignore_unknown_fields: bool) -> Result<Vec<u8>, Mismatch> {
+        let mut buf = Vec::new();
+        Encoder {
+            ignore_unknown_fields,
+        }
+        .encode_message(&self.fields, log.as_map(), "", &mut buf)?;
+        Ok(buf)
+    }
+}
+
+fn message_descriptor(
+    name: &str,
+    fields: &[TableField],
+    nested: &mut Vec<DescriptorProto>,
+) -> DescriptorProto {
+    let field = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let type_name = if field.type_ == FieldType::Record {
+                let type_name = format!("{}_{}", name, field.name);
+                let descriptor = message_descriptor(&type_name, &field.fields, nested);
+                nested.push(descriptor);
+                Some(type_name)
+            } else {
+                None
+            };
+            let label = match field.mode {
+                FieldMode::Repeated => Label::Repeated,
+                FieldMode::Nullable | FieldMode::Required => Label::Optional,
+            };
+            FieldDescriptorProto {
+                name: Some(field.name.clone()),
+                number: Some(index as i32 + 1),
+                label: Some(label as i32),
+                r#type: Some(field.type_.proto_type() as i32),
+                type_name,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    DescriptorProto {
+        name: Some(name.into()),
+        field,
+        ..Default::default()
+    }
+}
+
+struct Encoder {
+    ignore_unknown_fields: bool,
+}
+
+impl Encoder {
+    fn encode_message(
+        &self,
+        fields: &[TableField],
+        values: &BTreeMap<String, Value>,
+        path: &str,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Mismatch> {
+        if !self.ignore_unknown_fields {
+            if let Some(key) = values
+                .keys()
+                .find(|key| !fields.iter().any(|field| &field.name == *key))
+            {
+                return Err(Mismatch::UnknownField {
+                    field: join(path, key),
+                });
+            }
+        }
+
+        for (index, field) in fields.iter().enumerate() {
+            let tag = index as u32 + 1;
+            let path = join(path, &field.name);
+            match (values.get(&field.name), field.mode) {
+                (None, FieldMode::Required) | (Some(Value::Null), FieldMode::Required) => {
+                    return Err(Mismatch::MissingRequired { field: path });
+                }
+                (None, _) | (Some(Value::Null), _) => (),
+                (Some(Value::Array(items)), FieldMode::Repeated) => {
+                    for item in items {
+                        self.encode_value(field, tag, item, &path, buf)?;
+                    }
+                }
+                // Single values go in repeated columns as they are.
+                (Some(value), _) => self.encode_value(field, tag, value, &path, buf)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn encode_value(
+        &self,
+        field: &TableField,
+        tag: u32,
+        value: &Value,
+        path: &str,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Mismatch> {
+        let invalid = || Mismatch::InvalidValue {
+            field: path.to_owned(),
+            column_type: field.type_.name(),
+        };
+
+        match (field.type_, value) {
+            (FieldType::Record, Value::Map(values)) => {
+                let mut message = Vec::new();
+                self.encode_message(&field.fields, values, path, &mut message)?;
+                encode_bytes(tag, &message, buf);
+            }
+            (FieldType::Json, value) => {
+                let json = serde_json::to_vec(value).map_err(|_| invalid())?;
+                encode_bytes(tag, &json, buf);
+            }
+            (FieldType::Bytes, Value::Bytes(bytes)) => encode_bytes(tag, bytes, buf),
+            (FieldType::Integer, value) => {
+                let value = match value {
+                    Value::Integer(value) => Some(*value),
+                    Value::Float(value) if value.fract() == 0.0 && value.abs() < 9.2e18 => {
+                        Some(*value as i64)
+                    }
+                    Value::Bytes(bytes) => String::from_utf8_lossy(bytes).parse().ok(),
+                    _ => None,
+                };
+                encode_varint_field(tag, value.ok_or_else(invalid)? as u64, buf);
+            }
+            (FieldType::Float, value) => {
+                let value = match value {
+                    Value::Float(value) => Some(*value),
+                    Value::Integer(value) => Some(*value as f64),
+                    Value::Bytes(bytes) => String::from_utf8_lossy(bytes).parse().ok(),
+                    _ => None,
+                };
+                encode_key(tag, WireType::SixtyFourBit, buf);
+                buf.extend_from_slice(&value.ok_or_else(invalid)?.to_le_bytes());
+            }
+            (FieldType::Boolean, value) => {
+                let value = match value {
+                    Value::Boolean(value) => Some(*value),
+                    Value::Bytes(bytes) => {
+                        match String::from_utf8_lossy(bytes).to_lowercase().as_str() {
+                            "true" => Some(true),
+                            "false" => Some(false),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                encode_varint_field(tag, value.ok_or_else(invalid)? as u64, buf);
+            }
+            (FieldType::Timestamp, value) => {
+                let timestamp = timestamp(value).ok_or_else(invalid)?;
+                let micros =
+                    timestamp.timestamp() * 1_000_000 + timestamp.timestamp_subsec_micros() as i64;
+                encode_varint_field(tag, micros as u64, buf);
+            }
+            (FieldType::Date, value) => {
+                let date = match value {
+                    Value::Bytes(bytes) => {
+                        NaiveDate::parse_from_str(&String::from_utf8_lossy(bytes), "%Y-%m-%d").ok()
+                    }
+                    value => timestamp(value).map(|timestamp| timestamp.naive_utc().date()),
+                };
+                let days = date
+                    .ok_or_else(invalid)?
+                    .signed_duration_since(NaiveDate::from_ymd(1970, 1, 1))
+                    .num_days();
+                encode_varint_field(tag, days as i32 as u64, buf);
+            }
+            (FieldType::Datetime, Value::Timestamp(timestamp)) => {
+                let datetime = timestamp.format("%Y-%m-%d %H:%M:%S%.6f").to_string();
+                encode_bytes(tag, datetime.as_bytes(), buf);
+            }
+            (FieldType::Time, Value::Timestamp(timestamp)) => {
+                let time = timestamp.format("%H:%M:%S%.6f").to_string();
+                encode_bytes(tag, time.as_bytes(), buf);
+            }
+            (column_type, value) if column_type.proto_type() == Type::String => {
+                let string = match value {
+                    Value::Bytes(bytes) => bytes.to_vec(),
+                    Value::Integer(_) | Value::Float(_) | Value::Boolean(_) => {
+                        value.to_string_lossy().into_bytes()
+                    }
+                    Value::Timestamp(timestamp) if column_type == FieldType::String => {
+                        timestamp.to_rfc3339().into_bytes()
+                    }
+                    _ => return Err(invalid()),
+                };
+                encode_bytes(tag, &string, buf);
+            }
+            _ => return Err(invalid()),
+        }
+
+        Ok(())
+    }
+}
+
+fn timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Timestamp(timestamp) => Some(*timestamp),
+        Value::Bytes(bytes) => DateTime::parse_from_rfc3339(&String::from_utf8_lossy(bytes))
+            .ok()
+            .map(|timestamp| timestamp.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+fn encode_varint_field(tag: u32, value: u64, buf: &mut Vec<u8>) {
+    encode_key(tag, WireType::Varint, buf);
+    encode_varint(value, buf);
+}
+
+fn encode_bytes(tag: u32, bytes: &[u8], buf: &mut Vec<u8>) {
+    encode_key(tag, WireType::LengthDelimited, buf);
+    encode_varint(bytes.len() as u64, buf);
+    buf.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use prost::Message;
+
+    fn schema() -> TableSchema {
+        serde_json::from_str(
+            r#"{"fields": [
+                {"name": "message", "type": "STRING", "mode": "REQUIRED"},
+                {"name": "status", "type": "INTEGER"},
+                {"name": "duration", "type": "FLOAT64"},
+                {"name": "timestamp", "type": "TIMESTAMP"},
+                {"name": "tags", "type": "STRING", "mode": "REPEATED"},
+                {"name": "request", "type": "RECORD", "fields": [
+                    {"name": "path", "type": "STRING"},
+                    {"name": "secure", "type": "BOOL"}
+                ]},
+                {"name": "day", "type": "DATE"}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Row {
+        #[prost(string, optional, tag = "1")]
+        message: Option<String>,
+        #[prost(int64, optional, tag = "2")]
+        status: Option<i64>,
+        #[prost(double, optional, tag = "3")]
+        duration: Option<f64>,
+        #[prost(int64, optional, tag = "4")]
+        timestamp: Option<i64>,
+        #[prost(string, repeated, tag = "5")]
+        tags: Vec<String>,
+        #[prost(message, optional, tag = "6")]
+        request: Option<Request>,
+        #[prost(int32, optional, tag = "7")]
+        day: Option<i32>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Request {
+        #[prost(string, optional, tag = "1")]
+        path: Option<String>,
+        #[prost(bool, optional, tag = "2")]
+        secure: Option<bool>,
+    }
+
+    fn log(fields: serde_json::Value) -> LogEvent {
+        let mut log = LogEvent::default();
+        for (key, value) in fields.as_object().unwrap() {
+            log.insert(key, value.clone());
+        }
+        log
+    }
+
+    #[test]
+    fn describes_rows() {
+        let descriptor = schema().descriptor();
+
+        assert_eq!(descriptor.name.as_deref(), Some("Row"));
+        assert_eq!(descriptor.field.len(), 7);
+        assert_eq!(descriptor.field[4].label, Some(Label::Repeated as i32));
+        assert_eq!(descriptor.field[5].r#type, Some(Type::Message as i32));
+        assert_eq!(
+            descriptor.field[5].type_name.as_deref(),
+            Some("Row_request")
+        );
+        assert_eq!(descriptor.nested_type.len(), 1);
+        assert_eq!(
+            descriptor.nested_type[0].name.as_deref(),
+            Some("Row_request")
+        );
+        assert_eq!(
+            descriptor.nested_type[0].field[1].r#type,
+            Some(Type::Bool as i32)
+        );
+    }
+
+    #[test]
+    fn encodes_rows() {
+        let mut log = log(serde_json::json!({
+            "message": "hello",
+            "status": "200",
+            "duration": 12,
+            "tags": ["a", "b"],
+            "request": {"path": "/", "secure": true},
+            "day": "2020-01-02",
+        }));
+        log.insert("timestamp", Utc.ymd(2020, 1, 2).and_hms_micro(3, 4, 5, 6));
+
+        let row = Row::decode(&schema().encode(&log, false).unwrap()[..]).unwrap();
+
+        assert_eq!(
+            row,
+            Row {
+                message: Some("hello".into()),
+                status: Some(200),
+                duration: Some(12.0),
+                timestamp: Some(1_577_934_245_000_006),
+                tags: vec!["a".into(), "b".into()],
+                request: Some(Request {
+                    path: Some("/".into()),
+                    secure: Some(true),
+                }),
+                day: Some(18263),
+            }
+        );
+    }
+
+    #[test]
+    fn reports_mismatches() {
+        let schema = schema();
+
+        let unknown = log(serde_json::json!({"message": "hello", "request": {"method": "GET"}}));
+        assert_eq!(
+            schema.encode(&unknown, false),
+            Err(Mismatch::UnknownField {
+                field: "request.method".into()
+            })
+        );
+        assert!(schema.encode(&unknown, true).is_ok());
+
+        let missing = log(serde_json::json!({"status": 200}));
+        assert_eq!(
+            schema.encode(&missing, false),
+            Err(Mismatch::MissingRequired {
+                field: "message".into()
+            })
+        );
+
+        let invalid = log(serde_json::json!({"message": "hello", "status": "OK"}));
+        assert_eq!(
+            schema.encode(&invalid, false),
+            Err(Mismatch::InvalidValue {
+                field: "status".into(),
+                column_type: "INTEGER"
+            })
+        );
+    }
+}
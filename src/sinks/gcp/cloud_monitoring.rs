@@ -0,0 +1,1104 @@
+use super::{healthcheck_response, GcpAuthConfig, GcpCredentials, Scope};
+use crate::{
+    config::{DataType, SinkConfig, SinkContext, SinkDescription},
+    event::metric::{Metric, MetricKind, MetricValue},
+    http::HttpClient,
+    internal_events::{CloudMonitoringDescriptorFailed, CloudMonitoringResourceDetected},
+    sinks::{
+        util::{
+            batch::{Batch, BatchConfig, BatchError, BatchSettings, BatchSize, PushResult},
+            encode_namespace,
+            http::{BatchedHttpSink, HttpSink},
+            MetricEntry, TowerRequestConfig,
+        },
+        Healthcheck, UriParseError, VectorSink,
+    },
+    tls::{TlsOptions, TlsSettings},
+    Event,
+};
+use chrono::{DateTime, Duration as ChronoDuration, SecondsFormat, Utc};
+use futures::{FutureExt, SinkExt};
+use http::{Request, Uri};
+use hyper::Body;
+use indexmap::IndexSet;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Mutex,
+    time::Duration,
+};
+
+const API_URL: &str = "https://monitoring.googleapis.com/v3";
+const METADATA_URL: &str = "http://metadata.google.internal/computeMetadata/v1";
+const METADATA_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The most time series Cloud Monitoring accepts in a single request.
+const MAX_TIME_SERIES: usize = 200;
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("Project not found"))]
+    NotFound,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CloudMonitoringConfig {
+    pub project_id: String,
+    pub resource: Option<CloudMonitoringResource>,
+    #[serde(default = "default_metric_prefix")]
+    pub metric_prefix: String,
+    pub default_namespace: Option<String>,
+    #[serde(default = "default_buckets")]
+    pub buckets: Vec<f64>,
+    #[serde(default = "crate::serde::default_true")]
+    pub create_descriptors: bool,
+
+    #[serde(flatten)]
+    pub auth: GcpAuthConfig,
+
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    pub tls: Option<TlsOptions>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct CloudMonitoringResource {
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(flatten)]
+    pub labels: HashMap<String, String>,
+}
+
+fn default_metric_prefix() -> String {
+    "custom.googleapis.com".into()
+}
+
+fn default_buckets() -> Vec<f64> {
+    vec![
+        0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+    ]
+}
+
+inventory::submit! {
+    SinkDescription::new::<CloudMonitoringConfig>("gcp_cloud_monitoring")
+}
+
+impl_generate_config_from_default!(CloudMonitoringConfig);
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        rate_limit_num: Some(1000),
+        rate_limit_duration_secs: Some(1),
+        ..Default::default()
+    };
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_cloud_monitoring")]
+impl SinkConfig for CloudMonitoringConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let creds = self.auth.make_credentials(Scope::MonitoringWrite).await?;
+
+        // Each series may only appear once in a request, and Cloud Monitoring
+        // rejects points written to a series more often than every 5 seconds.
+        let batch = BatchSettings::default()
+            .events(MAX_TIME_SERIES)
+            .timeout(10)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls_settings)?;
+
+        let resource = match &self.resource {
+            Some(resource) => resource.clone(),
+            None => {
+                let resource = detect_resource(&self.project_id).await;
+                emit!(CloudMonitoringResourceDetected {
+                    resource_type: &resource.type_,
+                });
+                resource
+            }
+        };
+
+        let sink = CloudMonitoringSink {
+            config: self.clone(),
+            api_key: self.auth.api_key.clone(),
+            creds,
+            client: client.clone(),
+            resource,
+            start_time: Utc::now(),
+            state: Default::default(),
+            descriptors: Default::default(),
+        };
+
+        let healthcheck = healthcheck(
+            client.clone(),
+            sink.uri("/metricDescriptors?pageSize=1")?,
+            sink.creds.clone(),
+        )
+        .boxed();
+
+        let sink = BatchedHttpSink::new(
+            sink,
+            TimeSeriesBuffer::new(batch.size),
+            request,
+            batch.timeout,
+            client,
+            cx.acker(),
+        )
+        .with_dead_letter(cx.dead_letter())
+        .sink_map_err(|error| error!(message = "Fatal gcp_cloud_monitoring sink error.", %error));
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "gcp_cloud_monitoring"
+    }
+
+    fn supports_dead_letter(&self) -> bool {
+        true
+    }
+}
+
+struct CloudMonitoringSink {
+    config: CloudMonitoringConfig,
+    api_key: Option<String>,
+    creds: Option<GcpCredentials>,
+    client: HttpClient,
+    resource: CloudMonitoringResource,
+    /// The start of every cumulative series.
+    start_time: DateTime<Utc>,
+    state: Mutex<SeriesState>,
+    /// The label keys of the metric descriptors created so far.
+    descriptors: Mutex<HashMap<String, BTreeSet<String>>>,
+}
+
+/// Turns incremental counters and gauges into absolute ones, as Cloud
+/// Monitoring has no delta custom metrics.
+#[derive(Default)]
+struct SeriesState {
+    counters: HashMap<MetricEntry, CounterState>,
+    gauges: HashMap<MetricEntry, f64>,
+}
+
+#[derive(Default)]
+struct CounterState {
+    last: Option<f64>,
+    total: f64,
+}
+
+impl SeriesState {
+    fn normalize(&mut self, metric: Metric) -> Metric {
+        let mut absolute = metric.to_absolute();
+        match metric.value {
+            MetricValue::Counter { value } => {
+                let state = self
+                    .counters
+                    .entry(MetricEntry(absolute.clone()))
+                    .or_default();
+                // Absolute counters are reported as their increase since
+                // startup, so that they all share the same start time.
+                let delta = match (&metric.kind, state.last) {
+                    (MetricKind::Incremental, _) => value,
+                    (MetricKind::Absolute, Some(last)) if value >= last => value - last,
+                    (MetricKind::Absolute, Some(_)) => value,
+                    (MetricKind::Absolute, None) => 0.0,
+                };
+                if metric.kind.is_absolute() {
+                    state.last = Some(value);
+                }
+                state.total += delta;
+                absolute.value = MetricValue::Counter { value: state.total };
+                absolute
+            }
+            MetricValue::Gauge { value } if metric.kind.is_incremental() => {
+                let state = self
+                    .gauges
+                    .entry(MetricEntry(absolute.clone()))
+                    .or_default();
+                *state += value;
+                absolute.value = MetricValue::Gauge { value: *state };
+                absolute
+            }
+            _ => metric,
+        }
+    }
+}
+
+impl CloudMonitoringSink {
+    fn uri(&self, suffix: &str) -> crate::Result<Uri> {
+        let mut uri = format!("{}/projects/{}{}", API_URL, self.config.project_id, suffix);
+        if let Some(key) = &self.api_key {
+            let separator = if uri.contains('?') { '&' } else { '?' };
+            uri = format!("{}{}key={}", uri, separator, key);
+        }
+        uri.parse::<Uri>()
+            .context(UriParseError)
+            .map_err(Into::into)
+    }
+
+    fn encode_metrics(&self, metrics: Vec<Metric>) -> Vec<TimeSeries<'_>> {
+        metrics
+            .into_iter()
+            .flat_map(|metric| self.encode_metric(metric))
+            .collect()
+    }
+
+    fn encode_metric(&self, metric: Metric) -> Vec<TimeSeries<'_>> {
+        let metric_type = format!("{}/{}", self.config.metric_prefix, metric.name);
+        let labels = metric
+            .tags
+            .iter()
+            .flatten()
+            .map(|(key, value)| (encode_label_key(key), value.clone()))
+            .collect::<BTreeMap<_, _>>();
+        let end_time = metric.timestamp.unwrap_or_else(Utc::now);
+        let series = |metric_type: String,
+                      labels: BTreeMap<String, String>,
+                      kind: SeriesKind,
+                      value: TypedValue| {
+            let interval = match kind {
+                SeriesKind::Gauge => Interval {
+                    start_time: None,
+                    end_time: encode_time(end_time),
+                },
+                // The end of a cumulative interval has to come after its start.
+                SeriesKind::Cumulative => Interval {
+                    start_time: Some(encode_time(self.start_time)),
+                    end_time: encode_time(
+                        end_time.max(self.start_time + ChronoDuration::milliseconds(1)),
+                    ),
+                },
+            };
+            TimeSeries {
+                metric: SeriesMetric {
+                    type_: metric_type,
+                    labels,
+                },
+                resource: &self.resource,
+                metric_kind: kind,
+                value_type: ValueType::of(&value),
+                points: vec![Point { interval, value }],
+            }
+        };
+
+        match metric.value {
+            MetricValue::Counter { value } => vec![series(
+                metric_type,
+                labels,
+                SeriesKind::Cumulative,
+                TypedValue::DoubleValue(value),
+            )],
+            MetricValue::Gauge { value } => vec![series(
+                metric_type,
+                labels,
+                SeriesKind::Gauge,
+                TypedValue::DoubleValue(value),
+            )],
+            MetricValue::Set { values } => vec![series(
+                metric_type,
+                labels,
+                SeriesKind::Gauge,
+                TypedValue::Int64Value(values.len() as i64),
+            )],
+            MetricValue::Distribution {
+                values,
+                sample_rates,
+                ..
+            } => {
+                let distribution =
+                    Distribution::from_samples(&values, &sample_rates, &self.config.buckets);
+                vec![series(
+                    metric_type,
+                    labels,
+                    SeriesKind::Gauge,
+                    TypedValue::DistributionValue(distribution),
+                )]
+            }
+            MetricValue::AggregatedHistogram {
+                buckets,
+                counts,
+                count,
+                sum,
+            } => {
+                let distribution = Distribution::from_histogram(&buckets, &counts, count, sum);
+                vec![series(
+                    metric_type,
+                    labels,
+                    SeriesKind::Gauge,
+                    TypedValue::DistributionValue(distribution),
+                )]
+            }
+            // Summaries have no counterpart, so each of their values is
+            // written to a gauge of its own.
+            MetricValue::AggregatedSummary {
+                quantiles,
+                values,
+                count,
+                sum,
+            } => quantiles
+                .iter()
+                .zip(values.iter())
+                .map(|(quantile, value)| {
+                    let mut labels = labels.clone();
+                    labels.insert("quantile".into(), quantile.to_string());
+                    series(
+                        metric_type.clone(),
+                        labels,
+                        SeriesKind::Gauge,
+                        TypedValue::DoubleValue(*value),
+                    )
+                })
+                .chain(vec![
+                    series(
+                        format!("{}_sum", metric_type),
+                        labels.clone(),
+                        SeriesKind::Gauge,
+                        TypedValue::DoubleValue(sum),
+                    ),
+                    series(
+                        format!("{}_count", metric_type),
+                        labels.clone(),
+                        SeriesKind::Gauge,
+                        TypedValue::Int64Value(count as i64),
+                    ),
+                ])
+                .collect(),
+        }
+    }
+
+    /// Creates the descriptors of new metrics, or of metrics with new labels,
+    /// so their kind and value type are set explicitly.
+    async fn create_descriptors(&self, series: &[TimeSeries<'_>]) {
+        let mut needed: BTreeMap<&str, MetricDescriptor> = BTreeMap::new();
+        {
+            let descriptors = self.descriptors.lock().unwrap();
+            for series in series {
+                let labels = series.metric.labels.keys();
+                let known = descriptors.get(&series.metric.type_);
+                if known.map_or(false, |known| labels.clone().all(|key| known.contains(key))) {
+                    continue;
+                }
+                let descriptor = needed
+                    .entry(series.metric.type_.as_str())
+                    .or_insert_with(|| MetricDescriptor {
+                        type_: series.metric.type_.clone(),
+                        metric_kind: series.metric_kind,
+                        value_type: series.value_type,
+                        labels: known.cloned().unwrap_or_default(),
+                        description: "Written by Vector.",
+                    });
+                descriptor.labels.extend(labels.cloned());
+            }
+        }
+
+        for (metric_type, descriptor) in needed {
+            match self.create_descriptor(&descriptor).await {
+                Ok(()) => {
+                    self.descriptors
+                        .lock()
+                        .unwrap()
+                        .insert(descriptor.type_, descriptor.labels);
+                }
+                Err(error) => emit!(CloudMonitoringDescriptorFailed { metric_type, error }),
+            }
+        }
+    }
+
+    async fn create_descriptor(&self, descriptor: &MetricDescriptor) -> crate::Result<()> {
+        let body = serde_json::to_vec(descriptor)?;
+        let mut request = Request::post(self.uri("/metricDescriptors")?)
+            .header("Content-Type", "application/json")
+            .body(Body::from(body))?;
+        if let Some(creds) = &self.creds {
+            creds.apply(&mut request);
+        }
+
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            let body = hyper::body::to_bytes(response.into_body()).await?;
+            Err(format!("{}: {}", status, String::from_utf8_lossy(&body)).into())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for CloudMonitoringSink {
+    type Input = Metric;
+    type Output = Vec<Metric>;
+
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        let mut metric = event.into_metric();
+        metric.name = encode_namespace(
+            metric
+                .namespace
+                .take()
+                .as_deref()
+                .or_else(|| self.config.default_namespace.as_deref()),
+            '/',
+            &metric.name,
+        );
+        Some(self.state.lock().unwrap().normalize(metric))
+    }
+
+    async fn build_request(&self, metrics: Self::Output) -> crate::Result<Request<Vec<u8>>> {
+        let time_series = self.encode_metrics(metrics);
+        if self.config.create_descriptors {
+            self.create_descriptors(&time_series).await;
+        }
+
+        let body = serde_json::to_vec(&serde_json::json!({ "timeSeries": time_series }))?;
+        let mut request = Request::post(self.uri("/timeSeries")?)
+            .header("Content-Type", "application/json")
+            .body(body)?;
+        if let Some(creds) = &self.creds {
+            creds.apply(&mut request);
+        }
+
+        Ok(request)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeSeries<'a> {
+    metric: SeriesMetric,
+    #[serde(serialize_with = "serialize_resource")]
+    resource: &'a CloudMonitoringResource,
+    metric_kind: SeriesKind,
+    value_type: ValueType,
+    points: Vec<Point>,
+}
+
+#[derive(Debug, Serialize)]
+struct SeriesMetric {
+    #[serde(rename = "type")]
+    type_: String,
+    labels: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum SeriesKind {
+    Gauge,
+    Cumulative,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum ValueType {
+    Int64,
+    Double,
+    Distribution,
+}
+
+impl ValueType {
+    fn of(value: &TypedValue) -> Self {
+        match value {
+            TypedValue::Int64Value(_) => ValueType::Int64,
+            TypedValue::DoubleValue(_) => ValueType::Double,
+            TypedValue::DistributionValue(_) => ValueType::Distribution,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Point {
+    interval: Interval,
+    value: TypedValue,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Interval {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<String>,
+    end_time: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum TypedValue {
+    Int64Value(i64),
+    DoubleValue(f64),
+    DistributionValue(Distribution),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Distribution {
+    count: u64,
+    mean: f64,
+    sum_of_squared_deviation: f64,
+    bucket_options: BucketOptions,
+    bucket_counts: Vec<u64>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BucketOptions {
+    explicit_buckets: ExplicitBuckets,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct ExplicitBuckets {
+    bounds: Vec<f64>,
+}
+
+impl Distribution {
+    fn from_samples(values: &[f64], sample_rates: &[u32], bounds: &[f64]) -> Self {
+        // The first bucket holds values below the first bound, and the last
+        // those above the last bound.
+        let mut bucket_counts = vec![0; bounds.len() + 1];
+        let mut count = 0;
+        let mut sum = 0.0;
+        for (value, rate) in values.iter().zip(sample_rates.iter()) {
+            let bucket = bounds
+                .iter()
+                .position(|bound| value < bound)
+                .unwrap_or(bounds.len());
+            bucket_counts[bucket] += *rate as u64;
+            count += *rate as u64;
+            sum += value * *rate as f64;
+        }
+        let mean = if count > 0 { sum / count as f64 } else { 0.0 };
+        let sum_of_squared_deviation = values
+            .iter()
+            .zip(sample_rates.iter())
+            .map(|(value, rate)| (value - mean).powi(2) * *rate as f64)
+            .sum();
+
+        Distribution {
+            count,
+            mean,
+            sum_of_squared_deviation,
+            bucket_options: BucketOptions {
+                explicit_buckets: ExplicitBuckets {
+                    bounds: bounds.to_vec(),
+                },
+            },
+            bucket_counts,
+        }
+    }
+
+    fn from_histogram(buckets: &[f64], counts: &[u32], count: u32, sum: f64) -> Self {
+        let (bounds, mut bucket_counts): (Vec<f64>, Vec<u64>) = buckets
+            .iter()
+            .zip(counts.iter())
+            .filter(|(bound, _)| bound.is_finite())
+            .map(|(bound, count)| (*bound, *count as u64))
+            .unzip();
+        let counted: u64 = bucket_counts.iter().sum();
+        bucket_counts.push((count as u64).saturating_sub(counted));
+        let mean = if count > 0 { sum / count as f64 } else { 0.0 };
+
+        Distribution {
+            count: count as u64,
+            mean,
+            // The spread of the values isn't known.
+            sum_of_squared_deviation: 0.0,
+            bucket_options: BucketOptions {
+                explicit_buckets: ExplicitBuckets { bounds },
+            },
+            bucket_counts,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MetricDescriptor {
+    #[serde(rename = "type")]
+    type_: String,
+    metric_kind: SeriesKind,
+    value_type: ValueType,
+    #[serde(serialize_with = "serialize_label_descriptors")]
+    labels: BTreeSet<String>,
+    description: &'static str,
+}
+
+fn serialize_resource<S: serde::Serializer>(
+    resource: &&CloudMonitoringResource,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serde_json::json!({
+        "type": resource.type_,
+        "labels": resource.labels,
+    })
+    .serialize(serializer)
+}
+
+fn serialize_label_descriptors<S: serde::Serializer>(
+    labels: &BTreeSet<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    labels
+        .iter()
+        .map(|key| serde_json::json!({ "key": key, "valueType": "STRING" }))
+        .collect::<Vec<_>>()
+        .serialize(serializer)
+}
+
+/// Label keys may only hold lowercase letters, digits and underscores.
+fn encode_label_key(key: &str) -> String {
+    key.chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' | '_' => c,
+            'A'..='Z' => c.to_ascii_lowercase(),
+            _ => '_',
+        })
+        .collect()
+}
+
+fn encode_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+/// Keeps a single point per series, as Cloud Monitoring rejects requests
+/// with several points for the same series.
+#[derive(Debug)]
+struct TimeSeriesBuffer {
+    metrics: IndexSet<MetricEntry>,
+    max_events: usize,
+}
+
+impl TimeSeriesBuffer {
+    fn new(settings: BatchSize<Self>) -> Self {
+        Self {
+            metrics: IndexSet::with_capacity(settings.events),
+            max_events: settings.events,
+        }
+    }
+}
+
+impl Batch for TimeSeriesBuffer {
+    type Input = Metric;
+    type Output = Vec<Metric>;
+
+    fn get_settings_defaults(
+        config: BatchConfig,
+        defaults: BatchSettings<Self>,
+    ) -> Result<BatchSettings<Self>, BatchError> {
+        Ok(config
+            .disallow_max_bytes()?
+            .use_size_as_events()?
+            .get_settings_or_default(defaults))
+    }
+
+    fn push(&mut self, item: Self::Input) -> PushResult<Self::Input> {
+        let entry = MetricEntry(item);
+        match self.metrics.swap_take(&entry) {
+            // Incremental metrics are merged, absolute ones replaced.
+            Some(MetricEntry(mut existing)) if entry.0.kind.is_incremental() => {
+                existing.add(&entry.0);
+                self.metrics.insert(MetricEntry(existing));
+            }
+            Some(_) => {
+                self.metrics.insert(entry);
+            }
+            None if self.metrics.len() >= self.max_events => {
+                return PushResult::Overflow(entry.0);
+            }
+            None => {
+                self.metrics.insert(entry);
+            }
+        }
+        PushResult::Ok(self.metrics.len() >= self.max_events)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+
+    fn fresh(&self) -> Self {
+        Self {
+            metrics: IndexSet::with_capacity(self.max_events),
+            max_events: self.max_events,
+        }
+    }
+
+    fn finish(self) -> Self::Output {
+        self.metrics.into_iter().map(|entry| entry.0).collect()
+    }
+
+    fn num_items(&self) -> usize {
+        self.metrics.len()
+    }
+}
+
+/// The metadata of the instance Vector runs on, when on Google Cloud.
+#[derive(Debug, Default)]
+struct InstanceMetadata {
+    instance_id: Option<String>,
+    instance_name: Option<String>,
+    zone: Option<String>,
+    cluster_name: Option<String>,
+    cluster_location: Option<String>,
+}
+
+impl InstanceMetadata {
+    async fn fetch() -> Self {
+        let client = match HttpClient::new(None) {
+            Ok(client) => client,
+            Err(_) => return Self::default(),
+        };
+        let get = |path| get_metadata(&client, path);
+        // Instances not on Google Cloud stop here, without waiting on the
+        // other requests to time out.
+        let instance_id = match get("instance/id").await {
+            Some(id) => id,
+            None => return Self::default(),
+        };
+        Self {
+            instance_id: Some(instance_id),
+            instance_name: get("instance/name").await,
+            // Zones are given as `projects/<number>/zones/<zone>`.
+            zone: get("instance/zone")
+                .await
+                .and_then(|zone| zone.rsplit('/').next().map(Into::into)),
+            cluster_name: get("instance/attributes/cluster-name").await,
+            cluster_location: get("instance/attributes/cluster-location").await,
+        }
+    }
+
+    fn into_resource(self, project_id: &str) -> CloudMonitoringResource {
+        let mut labels = HashMap::new();
+        labels.insert("project_id".to_owned(), project_id.to_owned());
+        let type_ = match self {
+            InstanceMetadata {
+                instance_name: Some(node_name),
+                zone,
+                cluster_name: Some(cluster_name),
+                cluster_location,
+                ..
+            } => {
+                let location = cluster_location.or(zone).unwrap_or_default();
+                labels.insert("location".into(), location);
+                labels.insert("cluster_name".into(), cluster_name);
+                labels.insert("node_name".into(), node_name);
+                "k8s_node"
+            }
+            InstanceMetadata {
+                instance_id: Some(instance_id),
+                zone: Some(zone),
+                ..
+            } => {
+                labels.insert("instance_id".into(), instance_id);
+                labels.insert("zone".into(), zone);
+                "gce_instance"
+            }
+            _ => "global",
+        };
+        CloudMonitoringResource {
+            type_: type_.into(),
+            labels,
+        }
+    }
+}
+
+async fn detect_resource(project_id: &str) -> CloudMonitoringResource {
+    InstanceMetadata::fetch().await.into_resource(project_id)
+}
+
+async fn get_metadata(client: &HttpClient, path: &str) -> Option<String> {
+    let request = Request::get(format!("{}/{}", METADATA_URL, path))
+        .header("Metadata-Flavor", "Google")
+        .body(Body::empty())
+        .ok()?;
+    let response = tokio::time::timeout(METADATA_TIMEOUT, client.send(request))
+        .await
+        .ok()?
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let body = hyper::body::to_bytes(response.into_body()).await.ok()?;
+    String::from_utf8(body.to_vec()).ok()
+}
+
+async fn healthcheck(
+    client: HttpClient,
+    uri: Uri,
+    creds: Option<GcpCredentials>,
+) -> crate::Result<()> {
+    let mut request = Request::get(uri).body(Body::empty()).unwrap();
+    if let Some(creds) = creds.as_ref() {
+        creds.apply(&mut request);
+    }
+
+    let response = client.send(request).await?;
+    healthcheck_response(creds, HealthcheckError::NotFound.into())(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::StatisticKind;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<CloudMonitoringConfig>();
+    }
+
+    fn sink() -> CloudMonitoringSink {
+        let config: CloudMonitoringConfig = toml::from_str(
+            r#"
+            project_id = "project"
+            resource.type = "global"
+            resource.project_id = "project"
+            buckets = [1.0, 5.0]
+            "#,
+        )
+        .unwrap();
+        CloudMonitoringSink {
+            api_key: None,
+            creds: None,
+            client: HttpClient::new(None).unwrap(),
+            resource: config.resource.clone().unwrap(),
+            start_time: Utc.timestamp(1_600_000_000, 0),
+            state: Default::default(),
+            descriptors: Default::default(),
+            config,
+        }
+    }
+
+    fn metric(kind: MetricKind, value: MetricValue) -> Event {
+        Metric {
+            name: "requests".into(),
+            namespace: Some("app".into()),
+            timestamp: Some(Utc.timestamp(1_600_000_060, 0)),
+            tags: Some(
+                vec![("Status.Code".to_owned(), "200".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind,
+            value,
+        }
+        .into()
+    }
+
+    fn encode(sink: &CloudMonitoringSink, metric: Metric) -> serde_json::Value {
+        serde_json::to_value(sink.encode_metric(metric)).unwrap()
+    }
+
+    #[test]
+    fn accumulates_counters() {
+        let sink = sink();
+        let first = sink
+            .encode_event(metric(
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.5 },
+            ))
+            .unwrap();
+        let second = sink
+            .encode_event(metric(
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.5 },
+            ))
+            .unwrap();
+
+        assert_eq!(first.kind, MetricKind::Absolute);
+        assert_eq!(second.value, MetricValue::Counter { value: 3.0 });
+        assert_eq!(
+            encode(&sink, second),
+            json!([{
+                "metric": {
+                    "type": "custom.googleapis.com/app/requests",
+                    "labels": { "status_code": "200" },
+                },
+                "resource": {
+                    "type": "global",
+                    "labels": { "project_id": "project" },
+                },
+                "metricKind": "CUMULATIVE",
+                "valueType": "DOUBLE",
+                "points": [{
+                    "interval": {
+                        "startTime": "2020-09-13T12:26:40.000000000Z",
+                        "endTime": "2020-09-13T12:27:40.000000000Z",
+                    },
+                    "value": { "doubleValue": 3.0 },
+                }],
+            }])
+        );
+    }
+
+    #[test]
+    fn reports_increase_of_absolute_counters() {
+        let sink = sink();
+        let values = vec![10.0, 15.0, 3.0]
+            .into_iter()
+            .map(|value| {
+                sink.encode_event(metric(MetricKind::Absolute, MetricValue::Counter { value }))
+                    .unwrap()
+                    .value
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            values,
+            vec![
+                MetricValue::Counter { value: 0.0 },
+                MetricValue::Counter { value: 5.0 },
+                MetricValue::Counter { value: 8.0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_distributions() {
+        let sink = sink();
+        let metric = sink
+            .encode_event(metric(
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    values: vec![0.5, 2.0, 7.0],
+                    sample_rates: vec![2, 1, 1],
+                    statistic: StatisticKind::Histogram,
+                },
+            ))
+            .unwrap();
+
+        let series = encode(&sink, metric);
+        assert_eq!(series[0]["metricKind"], "GAUGE");
+        assert_eq!(series[0]["valueType"], "DISTRIBUTION");
+        assert_eq!(
+            series[0]["points"][0]["value"]["distributionValue"],
+            json!({
+                "count": 4,
+                "mean": 2.5,
+                "sumOfSquaredDeviation": 28.5,
+                "bucketOptions": { "explicitBuckets": { "bounds": [1.0, 5.0] } },
+                "bucketCounts": [2, 1, 1],
+            })
+        );
+    }
+
+    #[test]
+    fn encodes_aggregated_histograms() {
+        let distribution =
+            Distribution::from_histogram(&[1.0, 2.0, f64::INFINITY], &[1, 2, 3], 6, 12.0);
+
+        assert_eq!(
+            distribution.bucket_options.explicit_buckets.bounds,
+            vec![1.0, 2.0]
+        );
+        assert_eq!(distribution.bucket_counts, vec![1, 2, 3]);
+        assert_eq!(distribution.mean, 2.0);
+    }
+
+    #[test]
+    fn keeps_one_point_per_series() {
+        let sink = sink();
+        let mut buffer = TimeSeriesBuffer::new(BatchSettings::default().events(2).size);
+        for value in &[1.0, 2.0] {
+            let metric = sink
+                .encode_event(metric(
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value: *value },
+                ))
+                .unwrap();
+            assert!(matches!(buffer.push(metric), PushResult::Ok(false)));
+        }
+        let mut other = sink
+            .encode_event(metric(
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 3.0 },
+            ))
+            .unwrap();
+        other.name = "other".into();
+        assert!(matches!(buffer.push(other.clone()), PushResult::Ok(true)));
+        other.name = "third".into();
+        assert!(matches!(buffer.push(other), PushResult::Overflow(_)));
+
+        let metrics = buffer.finish();
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].value, MetricValue::Gauge { value: 2.0 });
+    }
+
+    #[tokio::test]
+    async fn builds_write_requests() {
+        let sink = sink();
+        let metric = sink
+            .encode_event(metric(
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 1.0 },
+            ))
+            .unwrap();
+
+        let sink = CloudMonitoringSink {
+            api_key: Some("key".into()),
+            config: CloudMonitoringConfig {
+                create_descriptors: false,
+                ..sink.config.clone()
+            },
+            ..sink
+        };
+        let request = sink.build_request(vec![metric]).await.unwrap();
+
+        assert_eq!(
+            request.uri(),
+            "https://monitoring.googleapis.com/v3/projects/project/timeSeries?key=key"
+        );
+        let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+        assert_eq!(body["timeSeries"][0]["metricKind"], "GAUGE");
+        assert_eq!(
+            body["timeSeries"][0]["points"][0]["interval"],
+            json!({ "endTime": "2020-09-13T12:27:40.000000000Z" })
+        );
+    }
+
+    #[test]
+    fn builds_resources_from_metadata() {
+        let gke = InstanceMetadata {
+            instance_id: Some("123".into()),
+            instance_name: Some("node-1".into()),
+            zone: Some("us-central1-a".into()),
+            cluster_name: Some("cluster".into()),
+            cluster_location: Some("us-central1".into()),
+        };
+        let resource = gke.into_resource("project");
+        assert_eq!(resource.type_, "k8s_node");
+        assert_eq!(resource.labels["location"], "us-central1");
+        assert_eq!(resource.labels["cluster_name"], "cluster");
+        assert_eq!(resource.labels["node_name"], "node-1");
+
+        let gce = InstanceMetadata {
+            instance_id: Some("123".into()),
+            instance_name: Some("vm-1".into()),
+            zone: Some("us-central1-a".into()),
+            ..Default::default()
+        };
+        let resource = gce.into_resource("project");
+        assert_eq!(resource.type_, "gce_instance");
+        assert_eq!(resource.labels["instance_id"], "123");
+        assert_eq!(resource.labels["zone"], "us-central1-a");
+
+        let resource = InstanceMetadata::default().into_resource("project");
+        assert_eq!(resource.type_, "global");
+        assert_eq!(resource.labels["project_id"], "project");
+    }
+
+    #[test]
+    fn encodes_label_keys() {
+        assert_eq!(encode_label_key("Status.Code"), "status_code");
+        assert_eq!(encode_label_key("host-name_1"), "host_name_1");
+    }
+}
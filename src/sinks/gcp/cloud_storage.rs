@@ -1,14 +1,17 @@
 use super::{healthcheck_response, GcpAuthConfig, GcpCredentials, Scope};
 use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::LogEvent,
     http::{HttpClient, HttpClientFuture, HttpError},
     serde::to_string,
     sinks::{
         util::{
             encoding::{EncodingConfig, EncodingConfiguration},
+            parquet::{BuildError as ParquetBuildError, ParquetConfig, ParquetRow},
             retries::{RetryAction, RetryLogic},
             BatchConfig, BatchSettings, Buffer, Compression, Concurrency, PartitionBatchSink,
             PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+            VecBuffer,
         },
         Healthcheck, VectorSink,
     },
@@ -55,12 +58,21 @@ pub struct GcsSinkConfig {
     bucket: String,
     acl: Option<GcsPredefinedAcl>,
     storage_class: Option<GcsStorageClass>,
+    /// The Cloud KMS key objects are encrypted with, instead of the bucket's
+    /// default key.
+    kms_key_name: Option<String>,
     metadata: Option<HashMap<String, String>>,
     key_prefix: Option<String>,
     filename_time_format: Option<String>,
     filename_append_uuid: Option<bool>,
     filename_extension: Option<String>,
     encoding: EncodingConfig<Encoding>,
+    /// Used when `encoding.codec` is `parquet`.
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    parquet: ParquetConfig,
     #[serde(default)]
     compression: Compression,
     #[serde(default)]
@@ -78,12 +90,14 @@ fn default_config(e: Encoding) -> GcsSinkConfig {
         bucket: Default::default(),
         acl: Default::default(),
         storage_class: Default::default(),
+        kms_key_name: Default::default(),
         metadata: Default::default(),
         key_prefix: Default::default(),
         filename_time_format: Default::default(),
         filename_append_uuid: Default::default(),
         filename_extension: Default::default(),
         encoding: e.into(),
+        parquet: Default::default(),
         compression: Compression::gzip_default(),
         batch: Default::default(),
         request: Default::default(),
@@ -129,6 +143,9 @@ lazy_static! {
 enum Encoding {
     Text,
     Ndjson,
+    /// Batches are written whole as Parquet files, compressed as set in
+    /// `parquet.compression` rather than `compression`.
+    Parquet,
 }
 
 impl Encoding {
@@ -136,6 +153,7 @@ impl Encoding {
         match self {
             Self::Text => "text/plain",
             Self::Ndjson => "application/x-ndjson",
+            Self::Parquet => "application/vnd.apache.parquet",
         }
     }
 }
@@ -183,6 +201,8 @@ enum HealthcheckError {
     UnknownBucket { bucket: String },
     #[snafu(display("key_prefix template parse error: {}", source))]
     KeyPrefixTemplate { source: TemplateError },
+    #[snafu(display("Invalid Parquet settings: {}", source))]
+    Parquet { source: ParquetBuildError },
 }
 
 impl GcsSink {
@@ -209,16 +229,48 @@ impl GcsSink {
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
         let encoding = config.encoding.clone();
 
-        let batch = BatchSettings::default()
-            .bytes(bytesize::mib(10u64))
-            .timeout(300)
-            .parse_config(config.batch)?;
-
         let key_prefix = config.key_prefix.as_deref().unwrap_or("date=%F/");
         let key_prefix = Template::try_from(key_prefix).context(KeyPrefixTemplate)?;
 
         let settings = self.settings.clone();
 
+        if let Encoding::Parquet = encoding.codec() {
+            let parquet = config.parquet.build().context(Parquet)?;
+            let batch = BatchSettings::default()
+                .bytes(bytesize::mib(10u64))
+                .timeout(300)
+                .parse_config(config.batch)?;
+
+            let svc = ServiceBuilder::new()
+                .map(move |req: PartitionInnerBuffer<Vec<ParquetRow>, Bytes>| {
+                    let (rows, key) = req.into_parts();
+                    let events = rows.into_iter().map(LogEvent::from).collect::<Vec<_>>();
+                    // Every value is converted to the type of its column, so
+                    // writing to memory can only fail on a bug.
+                    let body = parquet
+                        .encode(&events)
+                        .expect("Failed to encode batch as Parquet, this is a bug!");
+                    RequestWrapper::new(PartitionInnerBuffer::new(body, key), settings.clone())
+                })
+                .settings(request, GcsRetryLogic)
+                .service(self);
+
+            let buffer = PartitionBuffer::new(VecBuffer::new(batch.size));
+
+            let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                .sink_map_err(|error| error!(message = "Fatal gcp_cloud_storage error.", %error))
+                .with_flat_map(move |e| {
+                    stream::iter(encode_parquet_event(e, &key_prefix, &encoding)).map(Ok)
+                });
+
+            return Ok(VectorSink::Sink(Box::new(sink)));
+        }
+
+        let batch = BatchSettings::default()
+            .bytes(bytesize::mib(10u64))
+            .timeout(300)
+            .parse_config(config.batch)?;
+
         let svc = ServiceBuilder::new()
             .map(move |req| RequestWrapper::new(req, settings.clone()))
             .settings(request, GcsRetryLogic)
@@ -276,6 +328,9 @@ impl Service<RequestWrapper> for GcsSink {
             .map(|ce| headers.insert("content-encoding", ce));
         settings.acl.map(|acl| headers.insert("x-goog-acl", acl));
         headers.insert("x-goog-storage-class", settings.storage_class);
+        settings
+            .kms_key_name
+            .map(|key| headers.insert("x-goog-encryption-kms-key-name", key));
         for (p, v) in settings.metadata {
             headers.insert(p, v);
         }
@@ -338,6 +393,7 @@ struct RequestSettings {
     content_type: HeaderValue,
     content_encoding: Option<HeaderValue>,
     storage_class: HeaderValue,
+    kms_key_name: Option<HeaderValue>,
     metadata: Vec<(HeaderName, HeaderValue)>,
     extension: String,
     time_format: String,
@@ -350,12 +406,20 @@ impl RequestSettings {
             .acl
             .map(|acl| HeaderValue::from_str(&to_string(acl)).unwrap());
         let content_type = HeaderValue::from_str(config.encoding.codec().content_type()).unwrap();
-        let content_encoding = config
-            .compression
+        let compression = match config.encoding.codec() {
+            Encoding::Parquet => Compression::None,
+            _ => config.compression,
+        };
+        let content_encoding = compression
             .content_encoding()
             .map(|ce| HeaderValue::from_str(&to_string(ce)).unwrap());
         let storage_class = config.storage_class.unwrap_or_default();
         let storage_class = HeaderValue::from_str(&to_string(storage_class)).unwrap();
+        let kms_key_name = config
+            .kms_key_name
+            .as_deref()
+            .map(HeaderValue::from_str)
+            .transpose()?;
         let metadata = config
             .metadata
             .as_ref()
@@ -366,10 +430,14 @@ impl RequestSettings {
                     .collect::<Result<Vec<_>, _>>()
             })
             .unwrap_or_else(|| Ok(vec![]))?;
+        let extension = match config.encoding.codec() {
+            Encoding::Parquet => "parquet",
+            _ => compression.extension(),
+        };
         let extension = config
             .filename_extension
             .clone()
-            .unwrap_or_else(|| config.compression.extension().into());
+            .unwrap_or_else(|| extension.into());
         let time_format = config
             .filename_time_format
             .clone()
@@ -380,6 +448,7 @@ impl RequestSettings {
             content_type,
             content_encoding,
             storage_class,
+            kms_key_name,
             metadata,
             extension,
             time_format,
@@ -396,13 +465,9 @@ fn make_header((name, value): (&String, &String)) -> crate::Result<(HeaderName,
     ))
 }
 
-fn encode_event(
-    mut event: Event,
-    key_prefix: &Template,
-    encoding: &EncodingConfig<Encoding>,
-) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
-    let key = key_prefix
-        .render_string(&event)
+fn render_key(event: &Event, key_prefix: &Template) -> Option<String> {
+    key_prefix
+        .render_string(event)
         .map_err(|missing_keys| {
             warn!(
                 message = "Keys do not exist on the event; dropping event.",
@@ -410,7 +475,15 @@ fn encode_event(
                 rate_limit_secs = 30,
             );
         })
-        .ok()?;
+        .ok()
+}
+
+fn encode_event(
+    mut event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
+    let key = render_key(&event, key_prefix)?;
     encoding.apply_rules(&mut event);
     let log = event.into_log();
     let bytes = match encoding.codec() {
@@ -428,11 +501,25 @@ fn encode_event(
             bytes.push(b'\n');
             bytes
         }
+        Encoding::Parquet => unreachable!("Parquet batches are encoded whole."),
     };
 
     Some(PartitionInnerBuffer::new(bytes, key.into()))
 }
 
+fn encode_parquet_event(
+    mut event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<PartitionInnerBuffer<ParquetRow, Bytes>> {
+    let key = render_key(&event, key_prefix)?;
+    encoding.apply_rules(&mut event);
+    Some(PartitionInnerBuffer::new(
+        event.into_log().into(),
+        key.into(),
+    ))
+}
+
 #[derive(Clone)]
 struct GcsRetryLogic;
 
@@ -563,4 +650,45 @@ mod tests {
         );
         assert_ne!(req.key, "key/date.log.gz".to_string());
     }
+
+    #[test]
+    fn gcs_zstd_with_kms_key() {
+        let settings = RequestSettings::new(&GcsSinkConfig {
+            key_prefix: Some("key/".into()),
+            filename_time_format: Some("date".into()),
+            filename_append_uuid: Some(false),
+            compression: Compression::Zstd(None),
+            kms_key_name: Some(
+                "projects/my-project/locations/us/keyRings/logs/cryptoKeys/vector".into(),
+            ),
+            ..default_config(Encoding::Ndjson)
+        })
+        .unwrap();
+        assert_eq!(settings.content_encoding.as_ref().unwrap(), "zstd");
+        assert_eq!(
+            settings.kms_key_name.as_ref().unwrap(),
+            "projects/my-project/locations/us/keyRings/logs/cryptoKeys/vector"
+        );
+
+        let buf = PartitionInnerBuffer::new(vec![0u8; 10], Bytes::from("key/"));
+        let req = RequestWrapper::new(buf, settings);
+        assert_eq!(req.key, "key/date.log.zst".to_string());
+    }
+
+    #[test]
+    fn gcs_parquet_ignores_compression() {
+        let settings = RequestSettings::new(&GcsSinkConfig {
+            key_prefix: Some("key/".into()),
+            filename_time_format: Some("date".into()),
+            filename_append_uuid: Some(false),
+            ..default_config(Encoding::Parquet)
+        })
+        .unwrap();
+        assert!(settings.content_encoding.is_none());
+        assert_eq!(settings.content_type, "application/vnd.apache.parquet");
+
+        let buf = PartitionInnerBuffer::new(vec![0u8; 10], Bytes::from("key/"));
+        let req = RequestWrapper::new(buf, settings);
+        assert_eq!(req.key, "key/date.parquet".to_string());
+    }
 }
@@ -13,15 +13,22 @@ use hyper::{header::AUTHORIZATION, StatusCode};
 use serde::{Deserialize, Serialize};
 use smpl_jwt::Jwt;
 use snafu::{ResultExt, Snafu};
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
+pub mod bigquery;
+pub mod cloud_monitoring;
 pub mod cloud_storage;
 pub mod pubsub;
 pub mod stackdriver_logs;
 
 const SERVICE_ACCOUNT_TOKEN_URL: &str =
     "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+const OAUTH_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+// Where `gcloud auth application-default login` writes credentials, in the
+// gcloud config directory.
+const WELL_KNOWN_CREDENTIALS_FILE: &str = "application_default_credentials.json";
 
 #[derive(Debug, Snafu)]
 enum GcpError {
@@ -39,6 +46,12 @@ enum GcpError {
     GetTokenBytes { source: hyper::Error },
     #[snafu(display("Failed to get implicit GCP token"))]
     GetImplicitToken { source: HttpError },
+    #[snafu(display("Failed to get GCP token for user credentials"))]
+    GetUserToken { source: HttpError },
+    #[snafu(display("Failed to read GCP credentials file"))]
+    ReadCredentials { source: std::io::Error },
+    #[snafu(display("Invalid GCP user credentials"))]
+    InvalidUserCredentials { source: serde_json::Error },
     #[snafu(display("Failed to parse OAuth token JSON"))]
     TokenFromJson { source: TokenErr },
     #[snafu(display("Failed to parse OAuth token JSON text"))]
@@ -54,24 +67,87 @@ pub struct GcpAuthConfig {
 }
 
 impl GcpAuthConfig {
+    /// Finds credentials the way Application Default Credentials do: from
+    /// `credentials_path` or `GOOGLE_APPLICATION_CREDENTIALS`, then from
+    /// the file written by `gcloud auth application-default login`, and
+    /// finally from the metadata server, which also serves GKE workload
+    /// identities.
     pub async fn make_credentials(&self, scope: Scope) -> crate::Result<Option<GcpCredentials>> {
         let gap = std::env::var("GOOGLE_APPLICATION_CREDENTIALS").ok();
         let creds_path = self.credentials_path.as_ref().or_else(|| gap.as_ref());
         Ok(match (&creds_path, &self.api_key) {
             (Some(path), _) => Some(GcpCredentials::from_file(path, scope).await?),
             (None, Some(_)) => None,
-            (None, None) => Some(GcpCredentials::new_implicit(scope).await?),
+            (None, None) => match well_known_credentials_path().filter(|path| path.is_file()) {
+                Some(path) => Some(GcpCredentials::from_file(&path, scope).await?),
+                None => Some(GcpCredentials::new_implicit(scope).await?),
+            },
         })
     }
 }
 
+fn well_known_credentials_path() -> Option<PathBuf> {
+    let gcloud_dir = match std::env::var_os("CLOUDSDK_CONFIG") {
+        Some(dir) => PathBuf::from(dir),
+        None if cfg!(windows) => PathBuf::from(std::env::var_os("APPDATA")?).join("gcloud"),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config/gcloud"),
+    };
+    Some(gcloud_dir.join(WELL_KNOWN_CREDENTIALS_FILE))
+}
+
 #[derive(Clone, Debug)]
 pub struct GcpCredentials {
-    creds: Option<Credentials>,
+    source: CredentialsSource,
     scope: Scope,
     token: Arc<RwLock<Token>>,
 }
 
+#[derive(Clone, Debug)]
+enum CredentialsSource {
+    ServiceAccount(Credentials),
+    AuthorizedUser(AuthorizedUser),
+    Metadata,
+}
+
+/// The user credentials written by `gcloud auth application-default login`.
+#[derive(Clone, Debug, Deserialize)]
+struct AuthorizedUser {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+}
+
+impl CredentialsSource {
+    fn from_file(path: &std::path::Path) -> crate::Result<Self> {
+        let contents = std::fs::read(path).context(ReadCredentials)?;
+        let kind = serde_json::from_slice::<serde_json::Value>(&contents)
+            .ok()
+            .and_then(|value| value.get("type")?.as_str().map(String::from));
+        Ok(match kind.as_deref() {
+            Some("authorized_user") => CredentialsSource::AuthorizedUser(
+                serde_json::from_slice(&contents).context(InvalidUserCredentials)?,
+            ),
+            _ => {
+                let path = path.to_string_lossy();
+                CredentialsSource::ServiceAccount(
+                    Credentials::from_file(&path).context(InvalidCredentials1)?,
+                )
+            }
+        })
+    }
+
+    async fn get_token(&self, scope: &Scope) -> crate::Result<Token> {
+        Ok(match self {
+            CredentialsSource::ServiceAccount(creds) => {
+                let jwt = make_jwt(creds, scope)?;
+                goauth::get_token(&jwt, creds).await.context(GetToken)?
+            }
+            CredentialsSource::AuthorizedUser(user) => get_token_refresh(user).await?,
+            CredentialsSource::Metadata => get_token_implicit().await?,
+        })
+    }
+}
+
 async fn get_token_implicit() -> Result<Token, GcpError> {
     let req = http::Request::get(SERVICE_ACCOUNT_TOKEN_URL)
         .header("Metadata-Flavor", "Google")
@@ -84,7 +160,32 @@ async fn get_token_implicit() -> Result<Token, GcpError> {
         .await
         .context(GetImplicitToken)?;
 
-    let body = res.into_body();
+    parse_token(res.into_body()).await
+}
+
+/// Exchanges the refresh token of user credentials for an access token.
+async fn get_token_refresh(user: &AuthorizedUser) -> Result<Token, GcpError> {
+    let body = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("client_id", &user.client_id)
+        .append_pair("client_secret", &user.client_secret)
+        .append_pair("refresh_token", &user.refresh_token)
+        .append_pair("grant_type", "refresh_token")
+        .finish();
+    let req = http::Request::post(OAUTH_TOKEN_URL)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .body(hyper::Body::from(body))
+        .unwrap();
+
+    let res = HttpClient::new(None)
+        .context(BuildHttpClient)?
+        .send(req)
+        .await
+        .context(GetUserToken)?;
+
+    parse_token(res.into_body()).await
+}
+
+async fn parse_token(body: hyper::Body) -> Result<Token, GcpError> {
     let bytes = hyper::body::to_bytes(body).await.context(GetTokenBytes)?;
 
     // Token::from_str is irresponsible and may panic!
@@ -98,21 +199,19 @@ async fn get_token_implicit() -> Result<Token, GcpError> {
 }
 
 impl GcpCredentials {
-    async fn from_file(path: &str, scope: Scope) -> crate::Result<Self> {
-        let creds = Credentials::from_file(path).context(InvalidCredentials1)?;
-        let jwt = make_jwt(&creds, &scope)?;
-        let token = goauth::get_token(&jwt, &creds).await.context(GetToken)?;
-        Ok(Self {
-            creds: Some(creds),
-            scope,
-            token: Arc::new(RwLock::new(token)),
-        })
+    async fn from_file(path: impl AsRef<std::path::Path>, scope: Scope) -> crate::Result<Self> {
+        let source = CredentialsSource::from_file(path.as_ref())?;
+        Self::new(source, scope).await
     }
 
     async fn new_implicit(scope: Scope) -> crate::Result<Self> {
-        let token = get_token_implicit().await?;
+        Self::new(CredentialsSource::Metadata, scope).await
+    }
+
+    async fn new(source: CredentialsSource, scope: Scope) -> crate::Result<Self> {
+        let token = source.get_token(&scope).await?;
         Ok(Self {
-            creds: None,
+            source,
             scope,
             token: Arc::new(RwLock::new(token)),
         })
@@ -127,13 +226,7 @@ impl GcpCredentials {
     }
 
     async fn regenerate_token(&self) -> crate::Result<()> {
-        let token = match &self.creds {
-            Some(creds) => {
-                let jwt = make_jwt(creds, &self.scope).unwrap(); // Errors caught above
-                goauth::get_token(&jwt, creds).await?
-            }
-            None => get_token_implicit().await?,
-        };
+        let token = self.source.get_token(&self.scope).await?;
         *self.token.write().unwrap() = token;
         Ok(())
     }
@@ -202,4 +295,38 @@ mod tests {
             Err(err) => assert_downcast_matches!(err, GcpError, GcpError::GetImplicitToken { .. }), // This should be a more relevant error
         }
     }
+
+    #[test]
+    fn reads_user_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("application_default_credentials.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "client_id": "123.apps.googleusercontent.com",
+                "client_secret": "secret",
+                "refresh_token": "refresh",
+                "type": "authorized_user"
+            }"#,
+        )
+        .unwrap();
+
+        match CredentialsSource::from_file(&path).unwrap() {
+            CredentialsSource::AuthorizedUser(user) => {
+                assert_eq!(user.client_id, "123.apps.googleusercontent.com");
+                assert_eq!(user.refresh_token, "refresh");
+            }
+            source => panic!("Unexpected credentials {:?}", source),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_service_account() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.json");
+        std::fs::write(&path, r#"{"type": "service_account"}"#).unwrap();
+
+        let error = CredentialsSource::from_file(&path).unwrap_err();
+        assert_downcast_matches!(error, GcpError, GcpError::InvalidCredentials1 { .. });
+    }
 }
@@ -99,6 +99,7 @@ impl SinkConfig for PubsubConfig {
             client,
             cx.acker(),
         )
+        .with_dead_letter(cx.dead_letter())
         .sink_map_err(|error| error!(message = "Fatal gcp_pubsub sink error.", %error));
 
         Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
@@ -111,6 +112,10 @@ impl SinkConfig for PubsubConfig {
     fn sink_type(&self) -> &'static str {
         "gcp_pubsub"
     }
+
+    fn supports_dead_letter(&self) -> bool {
+        true
+    }
 }
 
 struct PubsubSink {
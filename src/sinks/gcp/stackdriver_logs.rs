@@ -3,6 +3,10 @@ use crate::{
     config::{log_schema, DataType, SinkConfig, SinkContext, SinkDescription},
     event::{Event, Value},
     http::HttpClient,
+    internal_events::{
+        StackdriverLogsEntrySplit, StackdriverLogsEntryTooLarge, StackdriverLogsEntryTruncated,
+        StackdriverLogsTemplateMissingKeys,
+    },
     sinks::{
         util::{
             encoding::{EncodingConfigWithDefault, EncodingConfiguration},
@@ -11,6 +15,7 @@ use crate::{
         },
         Healthcheck, VectorSink,
     },
+    template::Template,
     tls::{TlsOptions, TlsSettings},
 };
 use futures::{FutureExt, SinkExt};
@@ -21,6 +26,17 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, map};
 use snafu::Snafu;
 use std::collections::HashMap;
+use uuid::Uuid;
+
+/// The largest log entry Cloud Logging accepts.
+const MAX_ENTRY_BYTES: usize = 256_000;
+
+/// Room left in split entries for their `split` field.
+const SPLIT_OVERHEAD_BYTES: usize = 128;
+
+/// The smallest share of an entry its message is cut down to before the
+/// entry is dropped instead.
+const MIN_MESSAGE_BYTES: usize = 1024;
 
 #[derive(Debug, Snafu)]
 enum HealthcheckError {
@@ -33,10 +49,12 @@ enum HealthcheckError {
 pub struct StackdriverConfig {
     #[serde(flatten)]
     pub log_name: StackdriverLogName,
-    pub log_id: String,
+    pub log_id: Template,
 
     pub resource: StackdriverResource,
     pub severity_key: Option<String>,
+    #[serde(default)]
+    pub oversized_entries: OversizedEntries,
 
     #[serde(flatten)]
     pub auth: GcpAuthConfig,
@@ -86,9 +104,51 @@ pub enum StackdriverLogName {
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct StackdriverResource {
     #[serde(rename = "type")]
-    pub type_: String,
+    pub type_: Template,
     #[serde(flatten)]
-    pub labels: HashMap<String, String>,
+    pub labels: HashMap<String, Template>,
+}
+
+/// What to do with entries over the size limit of Cloud Logging.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Derivative)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizedEntries {
+    /// Cut the message short.
+    #[derivative(Default)]
+    Truncate,
+    /// Spread the message over several entries linked by their `split` field.
+    Split,
+}
+
+impl StackdriverResource {
+    fn is_dynamic(&self) -> bool {
+        self.type_.is_dynamic() || self.labels.values().any(Template::is_dynamic)
+    }
+
+    fn render(&self, event: &Event) -> Option<serde_json::Value> {
+        let labels = self
+            .labels
+            .iter()
+            .map(|(key, template)| Some((key.clone(), json!(render(template, "resource", event)?))))
+            .collect::<Option<map::Map<_, _>>>()?;
+        Some(json!({
+            "type": render(&self.type_, "resource", event)?,
+            "labels": labels,
+        }))
+    }
+}
+
+fn render(template: &Template, name: &'static str, event: &Event) -> Option<String> {
+    template
+        .render_string(event)
+        .map_err(|keys| {
+            emit!(StackdriverLogsTemplateMissingKeys {
+                template: name,
+                keys: &keys,
+            })
+        })
+        .ok()
 }
 
 inventory::submit! {
@@ -138,6 +198,7 @@ impl SinkConfig for StackdriverConfig {
             client,
             cx.acker(),
         )
+        .with_dead_letter(cx.dead_letter())
         .sink_map_err(|error| error!(message = "Fatal gcp_stackdriver_logs sink error.", %error));
 
         Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
@@ -150,6 +211,10 @@ impl SinkConfig for StackdriverConfig {
     fn sink_type(&self) -> &'static str {
         "gcp_stackdriver_logs"
     }
+
+    fn supports_dead_letter(&self) -> bool {
+        true
+    }
 }
 
 #[async_trait::async_trait]
@@ -167,11 +232,22 @@ impl HttpSink for StackdriverSink {
             .unwrap_or_else(|| 0.into());
 
         let mut event = Event::Log(log);
+        let mut entry = map::Map::with_capacity(5);
+
+        // Templates are rendered before the encoding rules can drop the
+        // fields they refer to.
+        if self.config.log_id.is_dynamic() {
+            let log_id = render(&self.config.log_id, "log_id", &event)?;
+            entry.insert("logName".into(), json!(self.config.log_name(&log_id)));
+        }
+        if self.config.resource.is_dynamic() {
+            entry.insert("resource".into(), self.config.resource.render(&event)?);
+        }
+
         self.config.encoding.apply_rules(&mut event);
 
         let log = event.into_log();
 
-        let mut entry = map::Map::with_capacity(3);
         entry.insert("jsonPayload".into(), json!(log));
         entry.insert("severity".into(), json!(severity));
 
@@ -180,18 +256,44 @@ impl HttpSink for StackdriverSink {
             entry.insert("timestamp".into(), json!(timestamp));
         }
 
-        Some(json!(entry))
+        self.limit_size(json!(entry))
     }
 
     async fn build_request(&self, events: Self::Output) -> crate::Result<Request<Vec<u8>>> {
-        let events = serde_json::json!({
-            "log_name": self.config.log_name(),
-            "entries": events,
-            "resource": {
-                "type": self.config.resource.type_,
-                "labels": self.config.resource.labels,
+        // Entries split in several parts are batched together, as an array.
+        let mut entries = Vec::with_capacity(events.len());
+        for event in events {
+            if event.get().starts_with('[') {
+                entries.extend(serde_json::from_str::<Vec<BoxedRawValue>>(event.get())?);
+            } else {
+                entries.push(event);
             }
-        });
+        }
+
+        let mut events = map::Map::with_capacity(3);
+        if !self.config.log_id.is_dynamic() {
+            events.insert(
+                "log_name".into(),
+                json!(self.config.log_name(self.config.log_id.get_ref())),
+            );
+        }
+        events.insert("entries".into(), json!(entries));
+        if !self.config.resource.is_dynamic() {
+            let labels = self
+                .config
+                .resource
+                .labels
+                .iter()
+                .map(|(key, template)| (key.clone(), json!(template.get_ref())))
+                .collect::<map::Map<_, _>>();
+            events.insert(
+                "resource".into(),
+                json!({
+                    "type": self.config.resource.type_.get_ref(),
+                    "labels": labels,
+                }),
+            );
+        }
 
         let body = serde_json::to_vec(&events).unwrap();
 
@@ -208,6 +310,88 @@ impl HttpSink for StackdriverSink {
     }
 }
 
+impl StackdriverSink {
+    /// Brings entries over the size limit under it, by truncating or
+    /// splitting their message.
+    fn limit_size(&self, entry: serde_json::Value) -> Option<serde_json::Value> {
+        let byte_size = serde_json::to_string(&entry).unwrap().len();
+        if byte_size <= MAX_ENTRY_BYTES {
+            return Some(entry);
+        }
+
+        let message = entry["jsonPayload"]
+            .get(log_schema().message_key())
+            .and_then(|message| message.as_str())
+            .unwrap_or_default();
+        let message_size = serde_json::to_string(message).unwrap().len();
+        let overhead = match self.config.oversized_entries {
+            OversizedEntries::Truncate => byte_size - message_size + 2,
+            OversizedEntries::Split => byte_size - message_size + 2 + SPLIT_OVERHEAD_BYTES,
+        };
+        if overhead + MIN_MESSAGE_BYTES > MAX_ENTRY_BYTES {
+            emit!(StackdriverLogsEntryTooLarge { byte_size });
+            return None;
+        }
+
+        let parts = split_message(message, MAX_ENTRY_BYTES - overhead);
+        let with_message = |part: &str| {
+            let mut entry = entry.clone();
+            entry["jsonPayload"][log_schema().message_key()] = json!(part);
+            entry
+        };
+        match self.config.oversized_entries {
+            OversizedEntries::Truncate => {
+                emit!(StackdriverLogsEntryTruncated { byte_size });
+                Some(with_message(parts[0]))
+            }
+            OversizedEntries::Split => {
+                emit!(StackdriverLogsEntrySplit {
+                    byte_size,
+                    parts: parts.len(),
+                });
+                let uid = Uuid::new_v4().to_string();
+                let entries = parts
+                    .iter()
+                    .enumerate()
+                    .map(|(index, part)| {
+                        let mut entry = with_message(part);
+                        entry["split"] = json!({
+                            "uid": uid,
+                            "index": index,
+                            "totalSplits": parts.len(),
+                        });
+                        entry
+                    })
+                    .collect();
+                Some(serde_json::Value::Array(entries))
+            }
+        }
+    }
+}
+
+/// Splits a message into parts whose JSON encoding is at most `limit` bytes,
+/// not counting quotes.
+fn split_message(message: &str, limit: usize) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut len = 0;
+    for (index, c) in message.char_indices() {
+        let char_len = match c {
+            '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 2,
+            c if (c as u32) < 0x20 => 6,
+            c => c.len_utf8(),
+        };
+        if len + char_len > limit && index > start {
+            parts.push(&message[start..index]);
+            start = index;
+            len = 0;
+        }
+        len += char_len;
+    }
+    parts.push(&message[start..]);
+    parts
+}
+
 fn remap_severity(severity: Value) -> Value {
     let n = match severity {
         Value::Integer(n) => n - n % 100,
@@ -256,13 +440,13 @@ async fn healthcheck(client: HttpClient, sink: StackdriverSink) -> crate::Result
 }
 
 impl StackdriverConfig {
-    fn log_name(&self) -> String {
+    fn log_name(&self, log_id: &str) -> String {
         use StackdriverLogName::*;
         match &self.log_name {
-            BillingAccount(acct) => format!("billingAccounts/{}/logs/{}", acct, self.log_id),
-            Folder(folder) => format!("folders/{}/logs/{}", folder, self.log_id),
-            Organization(org) => format!("organizations/{}/logs/{}", org, self.log_id),
-            Project(project) => format!("projects/{}/logs/{}", project, self.log_id),
+            BillingAccount(acct) => format!("billingAccounts/{}/logs/{}", acct, log_id),
+            Folder(folder) => format!("folders/{}/logs/{}", folder, log_id),
+            Organization(org) => format!("organizations/{}/logs/{}", org, log_id),
+            Project(project) => format!("projects/{}/logs/{}", project, log_id),
         }
     }
 }
@@ -442,6 +626,122 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn renders_log_name_and_resource_per_event() {
+        let config: StackdriverConfig = toml::from_str(
+            r#"
+           project_id = "project"
+           log_id = "{{ app }}"
+           resource.type = "k8s_container"
+           resource.container_name = "{{ app }}"
+           resource.location = "europe-west1"
+           encoding.except_fields = ["app"]
+        "#,
+        )
+        .unwrap();
+
+        let sink = StackdriverSink {
+            config,
+            creds: None,
+            severity_key: None,
+        };
+
+        let log = LogEvent::from_iter([("message", "hello"), ("app", "api")].iter().copied());
+        let entry = sink.encode_event(Event::from(log)).unwrap();
+        assert_eq!(
+            entry,
+            serde_json::json!({
+                "logName": "projects/project/logs/api",
+                "resource": {
+                    "type": "k8s_container",
+                    "labels": {
+                        "container_name": "api",
+                        "location": "europe-west1",
+                    },
+                },
+                "jsonPayload": {
+                    "message": "hello"
+                },
+                "severity": 0,
+            })
+        );
+
+        let log = LogEvent::from_iter([("message", "hello")].iter().copied());
+        assert!(sink.encode_event(Event::from(log)).is_none());
+
+        let request = sink.build_request(vec![]).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+        assert_eq!(json, serde_json::json!({ "entries": [] }));
+    }
+
+    fn oversized_sink(oversized_entries: &str) -> StackdriverSink {
+        let config: StackdriverConfig = toml::from_str(&format!(
+            r#"
+           project_id = "project"
+           log_id = "testlogs"
+           resource.type = "global"
+           oversized_entries = "{}"
+        "#,
+            oversized_entries
+        ))
+        .unwrap();
+
+        StackdriverSink {
+            config,
+            creds: None,
+            severity_key: None,
+        }
+    }
+
+    fn oversized_event() -> Event {
+        let mut log = LogEvent::default();
+        log.insert("message", "a".repeat(MAX_ENTRY_BYTES * 2));
+        log.insert("host", "localhost");
+        Event::from(log)
+    }
+
+    #[test]
+    fn truncates_oversized_entries() {
+        let sink = oversized_sink("truncate");
+
+        let entry = sink.encode_event(oversized_event()).unwrap();
+
+        let size = serde_json::to_string(&entry).unwrap().len();
+        assert!(size <= MAX_ENTRY_BYTES);
+        assert!(size > MAX_ENTRY_BYTES - 10);
+        assert_eq!(entry["jsonPayload"]["host"], "localhost");
+    }
+
+    #[tokio::test]
+    async fn splits_oversized_entries() {
+        let sink = oversized_sink("split");
+
+        let entries = sink.encode_event(oversized_event()).unwrap();
+        let raw = RawValue::from_string(serde_json::to_string(&entries).unwrap()).unwrap();
+        let request = sink.build_request(vec![raw]).await.unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+        let entries = json["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 3);
+        let mut message = String::new();
+        for (index, entry) in entries.iter().enumerate() {
+            assert!(serde_json::to_string(entry).unwrap().len() <= MAX_ENTRY_BYTES);
+            assert_eq!(entry["split"]["index"], index);
+            assert_eq!(entry["split"]["totalSplits"], 3);
+            assert_eq!(entry["split"]["uid"], entries[0]["split"]["uid"]);
+            message.push_str(entry["jsonPayload"]["message"].as_str().unwrap());
+        }
+        assert_eq!(message.len(), MAX_ENTRY_BYTES * 2);
+    }
+
+    #[test]
+    fn splits_messages_on_escaped_length() {
+        assert_eq!(split_message("abcdef", 4), vec!["abcd", "ef"]);
+        assert_eq!(split_message("a\"b\"c", 4), vec!["a\"b", "\"c"]);
+        assert_eq!(split_message("héllo", 3), vec!["hé", "llo"]);
+        assert_eq!(split_message("", 4), vec![""]);
+    }
+
     #[tokio::test]
     async fn fails_missing_creds() {
         let config: StackdriverConfig = toml::from_str(
@@ -2,15 +2,20 @@ use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
     http::{Auth, HttpClient},
+    internal_events::{
+        HttpSinkInvalidHeaderValue, HttpSinkInvalidUri, HttpSinkTemplateMissingKeys,
+    },
     sinks::util::{
-        buffer::compression::GZIP_DEFAULT,
         encoding::{EncodingConfig, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpSink},
-        BatchConfig, BatchSettings, Buffer, Compression, Concurrency, TowerRequestConfig, UriSerde,
+        http::{HttpRetryLogic, HttpSink, PartitionHttpSink},
+        retries::{RetryAction, RetryLogic},
+        BatchConfig, BatchSettings, Buffer, Compression, Concurrency, PartitionBuffer,
+        PartitionInnerBuffer, TowerRequestConfig, UriSerde,
     },
+    template::Template,
     tls::{TlsOptions, TlsSettings},
 };
-use flate2::write::GzEncoder;
+use bytes::Bytes;
 use futures::{future, FutureExt, SinkExt};
 use http::{
     header::{self, HeaderName, HeaderValue},
@@ -21,7 +26,12 @@ use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::io::Write;
+use std::convert::TryFrom;
+
+mod oauth2;
+
+pub use oauth2::OAuth2Config;
+use oauth2::OAuth2Token;
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -35,15 +45,28 @@ enum BuildError {
         value: String,
         source: header::InvalidHeaderValue,
     },
+    #[snafu(display("{}: {}", source, uri))]
+    InvalidUri {
+        uri: String,
+        source: http::uri::InvalidUri,
+    },
+    #[snafu(display("Invalid template {:?}: {}", template, source))]
+    InvalidTemplate {
+        template: String,
+        source: crate::template::TemplateError,
+    },
+    #[snafu(display("The auth and oauth2 options can not be used together"))]
+    AuthConflict,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct HttpSinkConfig {
-    pub uri: UriSerde,
+    pub uri: String,
     pub method: Option<HttpMethod>,
     pub healthcheck_uri: Option<UriSerde>,
     pub auth: Option<Auth>,
+    pub oauth2: Option<OAuth2Config>,
     pub headers: Option<IndexMap<String, String>>,
     #[serde(default)]
     pub compression: Compression,
@@ -62,6 +85,7 @@ fn default_config(e: Encoding) -> HttpSinkConfig {
         method: Default::default(),
         healthcheck_uri: Default::default(),
         auth: Default::default(),
+        oauth2: Default::default(),
         headers: Default::default(),
         compression: Default::default(),
         batch: Default::default(),
@@ -87,6 +111,7 @@ pub enum HttpMethod {
     #[derivative(Default)]
     Post,
     Put,
+    Patch,
 }
 
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
@@ -119,33 +144,56 @@ impl SinkConfig for HttpSinkConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         validate_headers(&self.headers, &self.auth)?;
+        if self.oauth2.is_some() {
+            if self.auth.is_some() {
+                return Err(BuildError::AuthConflict.into());
+            }
+            if self
+                .headers
+                .iter()
+                .flatten()
+                .any(|(name, _)| name.eq_ignore_ascii_case("Authorization"))
+            {
+                return Err(
+                    "Authorization header can not be used with defined oauth2 options".into(),
+                );
+            }
+        }
         let tls = TlsSettings::from_options(&self.tls)?;
         let client = HttpClient::new(tls)?;
 
-        let mut config = self.clone();
-        config.uri = build_uri(config.uri.clone()).into();
+        let token = self
+            .oauth2
+            .clone()
+            .map(|oauth2| OAuth2Token::new(oauth2, client.clone()));
+        let sink = HttpRequestSink::new(self, token.clone())?;
 
         let batch = BatchSettings::default()
             .bytes(bytesize::mib(10u64))
             .timeout(1)
-            .parse_config(config.batch)?;
-        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
-
-        let sink = BatchedHttpSink::new(
-            config,
-            Buffer::new(batch.size, Compression::None),
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+
+        let sink = PartitionHttpSink::with_retry_logic(
+            sink,
+            PartitionBuffer::new(Buffer::new(batch.size, Compression::None)),
+            HttpSinkRetryLogic {
+                token: token.clone(),
+            },
             request,
             batch.timeout,
             client.clone(),
             cx.acker(),
         )
+        .with_dead_letter(cx.dead_letter())
         .sink_map_err(|error| error!(message = "Fatal HTTP sink error.", %error));
 
         let sink = super::VectorSink::Sink(Box::new(sink));
 
         match self.healthcheck_uri.clone() {
             Some(healthcheck_uri) => {
-                let healthcheck = healthcheck(healthcheck_uri, self.auth.clone(), client).boxed();
+                let healthcheck =
+                    healthcheck(healthcheck_uri, self.auth.clone(), token, client).boxed();
                 Ok((sink, healthcheck))
             }
             None => Ok((sink, future::ok(()).boxed())),
@@ -159,14 +207,136 @@ impl SinkConfig for HttpSinkConfig {
     fn sink_type(&self) -> &'static str {
         "http"
     }
+
+    fn supports_dead_letter(&self) -> bool {
+        true
+    }
+}
+
+/// A URI or header value, rendered for each event when it references event
+/// fields. Only values with field references are parsed as templates, since
+/// URIs and header values commonly contain `%`, which would otherwise be
+/// taken for strftime specifiers.
+#[derive(Clone, Debug)]
+enum Templated {
+    Static(String),
+    Dynamic(Template),
+}
+
+impl Templated {
+    fn new(src: &str) -> crate::Result<Self> {
+        if src.contains("{{") {
+            let template = Template::try_from(src).context(InvalidTemplate { template: src })?;
+            Ok(Templated::Dynamic(template))
+        } else {
+            Ok(Templated::Static(src.to_owned()))
+        }
+    }
+
+    fn render(&self, event: &Event, field: &str) -> Option<String> {
+        match self {
+            Templated::Static(value) => Some(value.clone()),
+            Templated::Dynamic(template) => template
+                .render_string(event)
+                .map_err(|keys| emit!(HttpSinkTemplateMissingKeys { field, keys: &keys }))
+                .ok(),
+        }
+    }
+}
+
+/// Events are batched separately for each distinct URI and set of headers
+/// rendered from them.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct PartitionKey {
+    uri: Uri,
+    headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+struct HttpRequestSink {
+    uri: Templated,
+    method: Method,
+    headers: Vec<(HeaderName, Templated)>,
+    auth: Option<Auth>,
+    token: Option<OAuth2Token>,
+    compression: Compression,
+    encoding: EncodingConfig<Encoding>,
+}
+
+impl HttpRequestSink {
+    fn new(config: &HttpSinkConfig, token: Option<OAuth2Token>) -> crate::Result<Self> {
+        let uri = Templated::new(&config.uri)?;
+        if let Templated::Static(uri) = &uri {
+            uri.parse::<Uri>().context(InvalidUri { uri })?;
+        }
+
+        let headers = config
+            .headers
+            .iter()
+            .flatten()
+            .map(|(name, value)| {
+                let name = HeaderName::from_bytes(name.as_bytes())
+                    .with_context(|| InvalidHeaderName { name })?;
+                Ok((name, Templated::new(value)?))
+            })
+            .collect::<crate::Result<_>>()?;
+
+        let method = match config.method.clone().unwrap_or(HttpMethod::Post) {
+            HttpMethod::Post => Method::POST,
+            HttpMethod::Put => Method::PUT,
+            HttpMethod::Patch => Method::PATCH,
+        };
+
+        Ok(Self {
+            uri,
+            method,
+            headers,
+            auth: config.auth.clone(),
+            token,
+            compression: config.compression,
+            encoding: config.encoding.clone(),
+        })
+    }
+
+    fn partition_key(&self, event: &Event) -> Option<PartitionKey> {
+        let uri = self.uri.render(event, "uri")?;
+        let uri = match uri.parse::<Uri>() {
+            Ok(uri) => build_uri(uri.into()),
+            Err(error) => {
+                emit!(HttpSinkInvalidUri {
+                    uri: &uri,
+                    error: &error
+                });
+                return None;
+            }
+        };
+
+        let mut headers = Vec::with_capacity(self.headers.len());
+        for (name, value) in &self.headers {
+            let value = value.render(event, name.as_str())?;
+            match HeaderValue::from_str(&value) {
+                Ok(value) => headers.push((name.clone(), value)),
+                Err(error) => {
+                    emit!(HttpSinkInvalidHeaderValue {
+                        name: name.as_str(),
+                        error: &error
+                    });
+                    return None;
+                }
+            }
+        }
+
+        Some(PartitionKey { uri, headers })
+    }
 }
 
 #[async_trait::async_trait]
-impl HttpSink for HttpSinkConfig {
-    type Input = Vec<u8>;
-    type Output = Vec<u8>;
+impl HttpSink for HttpRequestSink {
+    type Input = PartitionInnerBuffer<Vec<u8>, PartitionKey>;
+    type Output = PartitionInnerBuffer<Vec<u8>, PartitionKey>;
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
+        let key = self.partition_key(&event)?;
+
         self.encoding.apply_rules(&mut event);
         let event = event.into_log();
 
@@ -202,15 +372,11 @@ impl HttpSink for HttpSinkConfig {
             }
         };
 
-        Some(body)
+        Some(PartitionInnerBuffer::new(body, key))
     }
 
-    async fn build_request(&self, mut body: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
-        let method = match &self.method.clone().unwrap_or(HttpMethod::Post) {
-            HttpMethod::Post => Method::POST,
-            HttpMethod::Put => Method::PUT,
-        };
-        let uri: Uri = self.uri.clone().into();
+    async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        let (mut body, key) = output.into_parts();
 
         let ct = match self.encoding.codec() {
             Encoding::Text => "text/plain",
@@ -224,26 +390,17 @@ impl HttpSink for HttpSinkConfig {
         };
 
         let mut builder = Request::builder()
-            .method(method)
-            .uri(uri)
+            .method(self.method.clone())
+            .uri(key.uri)
             .header("Content-Type", ct);
 
-        match self.compression {
-            Compression::Gzip(level) => {
-                builder = builder.header("Content-Encoding", "gzip");
-
-                let level = level.unwrap_or(GZIP_DEFAULT) as u32;
-                let mut w = GzEncoder::new(Vec::new(), flate2::Compression::new(level));
-                w.write_all(&body).expect("Writing to Vec can't fail");
-                body = w.finish().expect("Writing to Vec can't fail");
-            }
-            Compression::None => {}
+        if let Some(encoding) = self.compression.content_encoding() {
+            builder = builder.header("Content-Encoding", encoding);
         }
+        let body = self.compression.compress(body);
 
-        if let Some(headers) = &self.headers {
-            for (header, value) in headers.iter() {
-                builder = builder.header(header.as_str(), value.as_str());
-            }
+        for (header, value) in key.headers {
+            builder = builder.header(header, value);
         }
 
         let mut request = builder.body(body).unwrap();
@@ -251,18 +408,61 @@ impl HttpSink for HttpSinkConfig {
         if let Some(auth) = &self.auth {
             auth.apply(&mut request);
         }
+        if let Some(token) = &self.token {
+            Auth::Bearer {
+                token: token.get().await?,
+            }
+            .apply(&mut request);
+        }
 
         Ok(request)
     }
 }
 
-async fn healthcheck(uri: UriSerde, auth: Option<Auth>, client: HttpClient) -> crate::Result<()> {
+/// Retries requests rejected with `401 Unauthorized` when using OAuth2, after
+/// dropping the rejected token so a new one gets requested.
+#[derive(Clone)]
+struct HttpSinkRetryLogic {
+    token: Option<OAuth2Token>,
+}
+
+impl RetryLogic for HttpSinkRetryLogic {
+    type Error = hyper::Error;
+    type Response = hyper::Response<Bytes>;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        HttpRetryLogic.is_retriable_error(error)
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        match &self.token {
+            Some(token) if response.status() == StatusCode::UNAUTHORIZED => {
+                token.invalidate();
+                RetryAction::Retry("access token rejected".into())
+            }
+            _ => HttpRetryLogic.should_retry_response(response),
+        }
+    }
+}
+
+async fn healthcheck(
+    uri: UriSerde,
+    auth: Option<Auth>,
+    token: Option<OAuth2Token>,
+    client: HttpClient,
+) -> crate::Result<()> {
     let uri = build_uri(uri);
     let mut request = Request::head(&uri).body(Body::empty()).unwrap();
 
     if let Some(auth) = auth {
         auth.apply(&mut request);
     }
+    if let Some(token) = token {
+        Auth::Bearer {
+            token: token.get().await?,
+        }
+        .apply(&mut request);
+    }
 
     let response = client.send(request).await?;
 
@@ -333,8 +533,10 @@ mod tests {
         let event = Event::from("hello world");
 
         let mut config = default_config(Encoding::Text);
+        config.uri = "http://localhost/".into();
         config.encoding = encoding;
-        let bytes = config.encode_event(event).unwrap();
+        let sink = HttpRequestSink::new(&config, None).unwrap();
+        let (bytes, _) = sink.encode_event(event).unwrap().into_parts();
 
         assert_eq!(bytes, Vec::from(&"hello world\n"[..]));
     }
@@ -345,8 +547,10 @@ mod tests {
         let event = Event::from("hello world");
 
         let mut config = default_config(Encoding::Json);
+        config.uri = "http://localhost/".into();
         config.encoding = encoding;
-        let bytes = config.encode_event(event).unwrap();
+        let sink = HttpRequestSink::new(&config, None).unwrap();
+        let (bytes, _) = sink.encode_event(event).unwrap().into_parts();
 
         #[derive(Deserialize, Debug)]
         #[serde(deny_unknown_fields)]
@@ -360,6 +564,47 @@ mod tests {
         assert_eq!(output.message, "hello world".to_string());
     }
 
+    #[test]
+    fn http_renders_uri_and_headers() {
+        let config = r#"
+        uri = "http://localhost/{{ tenant }}/events?source={{ app }}"
+        encoding = "text"
+        [headers]
+        X-Tenant = "{{ tenant }}"
+        X-Static = "%_"
+        "#;
+        let config: HttpSinkConfig = toml::from_str(&config).unwrap();
+        let sink = HttpRequestSink::new(&config, None).unwrap();
+
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("tenant", "acme");
+        event.as_mut_log().insert("app", "api");
+        let (_, key) = sink.encode_event(event).unwrap().into_parts();
+
+        assert_eq!(
+            key.uri,
+            "http://localhost/acme/events?source=api"
+                .parse::<Uri>()
+                .unwrap()
+        );
+        assert_eq!(
+            key.headers,
+            vec![
+                (
+                    HeaderName::from_static("x-tenant"),
+                    HeaderValue::from_static("acme")
+                ),
+                (
+                    HeaderName::from_static("x-static"),
+                    HeaderValue::from_static("%_")
+                ),
+            ]
+        );
+
+        // Events missing the fields are dropped.
+        assert!(sink.encode_event(Event::from("hello world")).is_none());
+    }
+
     #[test]
     fn http_validates_normal_headers() {
         let config = r#"
@@ -522,6 +767,57 @@ mod tests {
         assert_eq!(input_lines, output_lines);
     }
 
+    #[tokio::test]
+    async fn http_partitions_templated_requests() {
+        let in_addr = next_addr();
+
+        let config = r#"
+        uri = "http://$IN_ADDR/{{ tenant }}/events"
+        method = "patch"
+        encoding = "ndjson"
+        [headers]
+        X-Tenant = "{{ tenant }}"
+    "#
+        .replace("$IN_ADDR", &format!("{}", in_addr));
+        let config: HttpSinkConfig = toml::from_str(&config).unwrap();
+
+        let cx = SinkContext::new_test();
+
+        let (sink, _) = config.build(cx).await.unwrap();
+        let (rx, trigger, server) = build_test_server(in_addr);
+
+        let events = (0..10).map(|i| {
+            let mut event = Event::from(format!("line {}", i));
+            let tenant = if i % 2 == 0 { "even" } else { "odd" };
+            event.as_mut_log().insert("tenant", tenant);
+            event
+        });
+        let pump = sink.run(stream::iter(events));
+
+        tokio::spawn(server);
+
+        pump.await.unwrap();
+        drop(trigger);
+
+        let output_lines = rx
+            .flat_map(|(parts, body)| {
+                assert_eq!(Method::PATCH, parts.method);
+                let tenant = parts.headers["X-Tenant"].to_str().unwrap().to_owned();
+                assert_eq!(format!("/{}/events", tenant), parts.uri.path());
+                stream::iter(BufReader::new(body.reader()).lines())
+                    .map(move |line| (tenant.clone(), line.unwrap()))
+            })
+            .map(|(tenant, line)| {
+                let val: serde_json::Value = serde_json::from_str(&line).unwrap();
+                assert_eq!(val["tenant"], serde_json::json!(tenant));
+                val["message"].as_str().unwrap().to_owned()
+            })
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(output_lines.len(), 10);
+    }
+
     #[tokio::test]
     async fn http_passes_custom_headers() {
         let num_lines = 1000;
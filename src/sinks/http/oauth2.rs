@@ -0,0 +1,204 @@
+use crate::{
+    http::{Auth, HttpClient, HttpError},
+    sinks::util::UriSerde,
+};
+use http::{Request, StatusCode, Uri};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Tokens are refreshed this long before they expire, so they don't expire
+/// while a request is in flight.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OAuth2Config {
+    pub token_endpoint: UriSerde,
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub audience: Option<String>,
+}
+
+#[derive(Debug, Snafu)]
+enum OAuth2Error {
+    #[snafu(display("Failed to request an access token: {}", source))]
+    TokenRequest { source: HttpError },
+    #[snafu(display("Failed to read the access token response: {}", source))]
+    TokenResponseBody { source: hyper::Error },
+    #[snafu(display("Access token request failed with {}: {}", status, body))]
+    TokenRequestFailed { status: StatusCode, body: String },
+    #[snafu(display("Invalid access token response: {}", source))]
+    InvalidTokenResponse { source: serde_json::Error },
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+struct CachedToken {
+    token: String,
+    expires_at: Option<Instant>,
+}
+
+impl CachedToken {
+    fn is_fresh(&self) -> bool {
+        self.expires_at.map_or(true, |expires_at| {
+            Instant::now() + EXPIRY_MARGIN < expires_at
+        })
+    }
+}
+
+/// Acquires access tokens with the OAuth2 client credentials grant, caching
+/// them until shortly before they expire or until they get rejected.
+#[derive(Clone)]
+pub(super) struct OAuth2Token {
+    config: OAuth2Config,
+    client: HttpClient,
+    cached: Arc<Mutex<Option<CachedToken>>>,
+}
+
+impl OAuth2Token {
+    pub(super) fn new(config: OAuth2Config, client: HttpClient) -> Self {
+        Self {
+            config,
+            client,
+            cached: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Returns the cached token, requesting a new one if it is missing or
+    /// about to expire.
+    pub(super) async fn get(&self) -> crate::Result<String> {
+        let cached = self
+            .cached
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|cached| cached.is_fresh())
+            .map(|cached| cached.token.clone());
+        if let Some(token) = cached {
+            return Ok(token);
+        }
+
+        let cached = self.request().await?;
+        let token = cached.token.clone();
+        *self.cached.lock().unwrap() = Some(cached);
+        Ok(token)
+    }
+
+    /// Drops the cached token, so a new one is requested for the next request.
+    pub(super) fn invalidate(&self) {
+        self.cached.lock().unwrap().take();
+    }
+
+    async fn request(&self) -> crate::Result<CachedToken> {
+        let mut body = url::form_urlencoded::Serializer::new(String::new());
+        body.append_pair("grant_type", "client_credentials");
+        if !self.config.scopes.is_empty() {
+            body.append_pair("scope", &self.config.scopes.join(" "));
+        }
+        if let Some(audience) = &self.config.audience {
+            body.append_pair("audience", audience);
+        }
+
+        let uri: Uri = self.config.token_endpoint.clone().into();
+        let mut request = Request::post(uri)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("Accept", "application/json")
+            .body(hyper::Body::from(body.finish()))
+            .unwrap();
+        Auth::Basic {
+            user: self.config.client_id.clone(),
+            password: self.config.client_secret.clone(),
+        }
+        .apply(&mut request);
+
+        debug!(message = "Requesting OAuth2 access token.", token_endpoint = %self.config.token_endpoint);
+        let response = self.client.send(request).await.context(TokenRequest)?;
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body)
+            .await
+            .context(TokenResponseBody)?;
+        if !parts.status.is_success() {
+            return Err(OAuth2Error::TokenRequestFailed {
+                status: parts.status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }
+            .into());
+        }
+
+        let response: TokenResponse =
+            serde_json::from_slice(&body).context(InvalidTokenResponse)?;
+        Ok(CachedToken {
+            token: response.access_token,
+            expires_at: response
+                .expires_in
+                .map(|secs| Instant::now() + Duration::from_secs(secs)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::next_addr;
+    use futures::FutureExt;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Response, Server,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn caches_tokens_until_invalidated() {
+        let addr = next_addr();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let server = Server::bind(&addr).serve(make_service_fn(move |_| {
+            let counter = Arc::clone(&counter);
+            async move {
+                Ok::<_, hyper::Error>(service_fn(move |request: Request<Body>| {
+                    let count = counter.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        let body = hyper::body::to_bytes(request.into_body()).await?;
+                        assert_eq!(
+                            &body[..],
+                            b"grant_type=client_credentials&scope=read+write"
+                        );
+                        Ok::<_, hyper::Error>(Response::new(Body::from(format!(
+                            r#"{{"access_token":"token-{}","token_type":"bearer","expires_in":3600}}"#,
+                            count
+                        ))))
+                    }
+                }))
+            }
+        }));
+        tokio::spawn(server.map(|_| ()));
+
+        let config: OAuth2Config = toml::from_str(&format!(
+            r#"
+            token_endpoint = "http://{}/token"
+            client_id = "id"
+            client_secret = "secret"
+            scopes = ["read", "write"]
+            "#,
+            addr
+        ))
+        .unwrap();
+        let token = OAuth2Token::new(config, HttpClient::new(None).unwrap());
+
+        assert_eq!(token.get().await.unwrap(), "token-0");
+        assert_eq!(token.get().await.unwrap(), "token-0");
+        token.invalidate();
+        assert_eq!(token.get().await.unwrap(), "token-1");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
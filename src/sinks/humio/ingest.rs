@@ -0,0 +1,390 @@
+use super::{default_host_key, Encoding, HOST};
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, Value},
+    http::{Auth, HttpClient},
+    internal_events::HumioIndexMissingKeys,
+    sinks::{
+        splunk_hec::HecSinkConfig,
+        util::{
+            buffer::GZIP_FAST,
+            encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+            http::{HttpSink, PartitionHttpSink},
+            BatchConfig, BatchSettings, BoxedRawValue, Compression, Concurrency, JsonArrayBuffer,
+            PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+};
+use chrono::{SecondsFormat, Utc};
+use futures::{FutureExt, SinkExt};
+use http::Request;
+use lazy_static::lazy_static;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Parsers can only be assigned with the `hec` API"))]
+    ParserWithStructuredApi,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HumioConfig {
+    token: String,
+    endpoint: Option<String>,
+    #[serde(default)]
+    api: HumioApi,
+    parser: Option<Template>,
+    index: Option<Template>,
+    #[serde(default = "default_host_key")]
+    host_key: String,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    encoding: EncodingConfigWithDefault<Encoding>,
+    #[serde(default)]
+    compression: Compression,
+    #[serde(default)]
+    request: TowerRequestConfig,
+    #[serde(default)]
+    batch: BatchConfig,
+}
+
+/// The API events are ingested through.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum HumioApi {
+    /// The HEC-compatible API, which runs events through parsers.
+    #[derivative(Default)]
+    Hec,
+    /// The structured ingest API, which stores events as they are sent.
+    Structured,
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        concurrency: Concurrency::Fixed(10),
+        rate_limit_num: Some(10),
+        ..Default::default()
+    };
+}
+
+inventory::submit! {
+    SinkDescription::new::<HumioConfig>("humio")
+}
+
+impl GenerateConfig for HumioConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"token = "${HUMIO_TOKEN}"
+            api = "hec""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "humio")]
+impl SinkConfig for HumioConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        match self.api {
+            HumioApi::Hec => self.build_hec_config().build(cx).await,
+            HumioApi::Structured => {
+                if self.parser.is_some() {
+                    return Err(BuildError::ParserWithStructuredApi.into());
+                }
+                let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+                let batch = BatchSettings::default()
+                    .bytes(bytesize::mib(1u64))
+                    .timeout(1)
+                    .parse_config(self.batch)?;
+                let client = HttpClient::new(None)?;
+
+                let sink = PartitionHttpSink::new(
+                    self.clone(),
+                    PartitionBuffer::new(JsonArrayBuffer::new(batch.size)),
+                    request,
+                    batch.timeout,
+                    client.clone(),
+                    cx.acker(),
+                )
+                .with_dead_letter(cx.dead_letter())
+                .sink_map_err(|error| error!(message = "Fatal humio sink error.", %error));
+
+                let healthcheck = healthcheck(self.endpoint(), client).boxed();
+
+                Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+            }
+        }
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "humio"
+    }
+
+    fn supports_dead_letter(&self) -> bool {
+        matches!(self.api, HumioApi::Structured)
+    }
+}
+
+impl HumioConfig {
+    fn endpoint(&self) -> String {
+        self.endpoint
+            .as_deref()
+            .unwrap_or(HOST)
+            .trim_end_matches('/')
+            .to_owned()
+    }
+
+    fn build_hec_config(&self) -> HecSinkConfig {
+        HecSinkConfig {
+            token: self.token.clone(),
+            endpoint: self.endpoint(),
+            sourcetype: self.parser.clone(),
+            index: self.index.clone(),
+            encoding: self.encoding.clone().transmute(),
+            compression: self.compression,
+            batch: self.batch,
+            request: self.request,
+            host_key: self.host_key.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn build_uri(&self, index: Option<&str>) -> String {
+        match index {
+            Some(index) => format!(
+                "{}/api/v1/repositories/{}/ingest",
+                self.endpoint(),
+                utf8_percent_encode(index, NON_ALPHANUMERIC)
+            ),
+            None => format!("{}/api/v1/ingest/humio-structured", self.endpoint()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for HumioConfig {
+    type Input = PartitionInnerBuffer<serde_json::Value, Option<String>>;
+    type Output = PartitionInnerBuffer<Vec<BoxedRawValue>, Option<String>>;
+
+    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
+        let index = self.index.as_ref().and_then(|index| {
+            index
+                .render_string(&event)
+                .map_err(|missing_keys| {
+                    emit!(HumioIndexMissingKeys {
+                        keys: &missing_keys
+                    });
+                })
+                .ok()
+        });
+
+        self.encoding.apply_rules(&mut event);
+        let mut log = event.into_log();
+
+        let timestamp = match log.remove(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(ts)) => ts,
+            _ => Utc::now(),
+        };
+        let timestamp = timestamp.to_rfc3339_opts(SecondsFormat::Millis, true);
+
+        let event = match self.encoding.codec() {
+            Encoding::Json => json!({
+                "timestamp": timestamp,
+                "attributes": log,
+            }),
+            Encoding::Text => json!({
+                "timestamp": timestamp,
+                "rawstring": log
+                    .get(log_schema().message_key())
+                    .map(|v| v.to_string_lossy())
+                    .unwrap_or_default(),
+            }),
+        };
+
+        Some(PartitionInnerBuffer::new(event, index))
+    }
+
+    async fn build_request(&self, output: Self::Output) -> crate::Result<Request<Vec<u8>>> {
+        let (events, index) = output.into_parts();
+        let body = serde_json::to_vec(&json!([{ "events": events }])).unwrap();
+
+        let mut builder = Request::post(self.build_uri(index.as_deref()))
+            .header("Content-Type", "application/json");
+        let compression = self.compression.with_default_gzip_level(GZIP_FAST);
+        if let Some(ce) = compression.content_encoding() {
+            builder = builder.header("Content-Encoding", ce);
+        }
+        let mut request = builder.body(compression.compress(body))?;
+
+        Auth::Bearer {
+            token: self.token.clone(),
+        }
+        .apply(&mut request);
+
+        Ok(request)
+    }
+}
+
+async fn healthcheck(endpoint: String, client: HttpClient) -> crate::Result<()> {
+    let request = Request::get(format!("{}/api/v1/status", endpoint)).body(hyper::Body::empty())?;
+    let response = client.send(request).await?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Unexpected status: {}", response.status()).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::util::test::{build_test_server, load_sink};
+    use crate::test_util::next_addr;
+    use chrono::offset::TimeZone;
+    use flate2::read::GzDecoder;
+    use futures::{stream, StreamExt};
+    use std::io::Read;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<HumioConfig>();
+    }
+
+    #[test]
+    fn maps_hec_options() {
+        let (config, _cx) = load_sink::<HumioConfig>(
+            r#"
+            token = "secret"
+            parser = "{{ app }}"
+            index = "{{ team }}-logs"
+            compression = "gzip"
+            "#,
+        )
+        .unwrap();
+
+        let hec = config.build_hec_config();
+        assert_eq!(hec.endpoint, HOST);
+        assert_eq!(hec.sourcetype.unwrap().get_ref(), "{{ app }}");
+        assert_eq!(hec.index.unwrap().get_ref(), "{{ team }}-logs");
+        assert_eq!(hec.compression, config.compression);
+    }
+
+    #[tokio::test]
+    async fn rejects_parsers_with_structured_api() {
+        let (config, cx) = load_sink::<HumioConfig>(
+            r#"
+            token = "secret"
+            api = "structured"
+            parser = "json"
+            "#,
+        )
+        .unwrap();
+
+        let error = config.build(cx).await.map(|_| ()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Parsers can only be assigned with the `hec` API"
+        );
+    }
+
+    #[test]
+    fn encodes_structured_events() {
+        let (config, _cx) = load_sink::<HumioConfig>(
+            r#"
+            token = "secret"
+            api = "structured"
+            index = "{{ team }}-logs"
+            encoding.except_fields = ["secret"]
+            "#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("hello world");
+        let log = event.as_mut_log();
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.ymd(2020, 12, 1).and_hms_milli(10, 20, 30, 400),
+        );
+        log.insert("team", "payments");
+        log.insert("secret", "hunter2");
+
+        let (event, index) = config.encode_event(event).unwrap().into_parts();
+        assert_eq!(index.as_deref(), Some("payments-logs"));
+        assert_eq!(
+            event,
+            json!({
+                "timestamp": "2020-12-01T10:20:30.400Z",
+                "attributes": {
+                    "message": "hello world",
+                    "team": "payments",
+                },
+            })
+        );
+
+        assert_eq!(
+            config.build_uri(index.as_deref()),
+            "https://cloud.humio.com/api/v1/repositories/payments%2Dlogs/ingest"
+        );
+        assert_eq!(
+            config.build_uri(None),
+            "https://cloud.humio.com/api/v1/ingest/humio-structured"
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_structured_events() {
+        let addr = next_addr();
+        let (config, cx) = load_sink::<HumioConfig>(&format!(
+            r#"
+            token = "secret"
+            endpoint = "http://{}/"
+            api = "structured"
+            compression = "gzip"
+            encoding.codec = "text"
+            "#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _) = config.build(cx).await.unwrap();
+        let (rx, trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let events = (0..3).map(|i| Event::from(format!("line {}", i)));
+        sink.run(stream::iter(events)).await.unwrap();
+        drop(trigger);
+
+        let requests = rx.collect::<Vec<_>>().await;
+        assert_eq!(requests.len(), 1);
+        let (parts, body) = &requests[0];
+        assert_eq!(parts.uri.path(), "/api/v1/ingest/humio-structured");
+        assert_eq!(parts.headers["Authorization"], "Bearer secret");
+        assert_eq!(parts.headers["Content-Encoding"], "gzip");
+
+        let mut payload = String::new();
+        GzDecoder::new(&body[..])
+            .read_to_string(&mut payload)
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        let lines = payload[0]["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|event| event["rawstring"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec!["line 0", "line 1", "line 2"]);
+    }
+}
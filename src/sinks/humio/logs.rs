@@ -1,4 +1,4 @@
-use super::{default_host_key, Encoding};
+use super::{default_host_key, Encoding, HOST};
 use crate::{
     config::{DataType, SinkConfig, SinkContext, SinkDescription},
     sinks::splunk_hec::HecSinkConfig,
@@ -10,8 +10,6 @@ use crate::{
 };
 use serde::{Deserialize, Serialize};
 
-const HOST: &str = "https://cloud.humio.com";
-
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
 pub struct HumioLogsConfig {
     pub(in crate::sinks::humio) token: String,
@@ -85,7 +83,7 @@ impl HumioLogsConfig {
 mod tests {
     use super::*;
     use crate::event::Event;
-    use crate::sinks::util::{http::HttpSink, test::load_sink};
+    use crate::sinks::util::test::load_sink;
     use chrono::Utc;
     use serde::Deserialize;
 
@@ -112,7 +110,7 @@ mod tests {
         .unwrap();
         let config = config.build_hec_config();
 
-        let bytes = config.encode_event(event).unwrap();
+        let (bytes, _) = config.encode_event(event).unwrap().into_parts();
         let hec_event = serde_json::from_slice::<HecEventJson>(&bytes[..]).unwrap();
 
         let now = Utc::now().timestamp_millis() as f64 / 1000f64;
@@ -1,9 +1,12 @@
+pub mod ingest;
 pub mod logs;
 pub mod metrics;
 
 use crate::sinks::splunk_hec;
 use serde::{Deserialize, Serialize};
 
+const HOST: &str = "https://cloud.humio.com";
+
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
 #[serde(rename_all = "snake_case")]
 #[derivative(Default)]
@@ -138,6 +138,7 @@ impl SinkConfig for InfluxDBLogsConfig {
             client,
             cx.acker(),
         )
+        .with_dead_letter(cx.dead_letter())
         .sink_map_err(|error| error!(message = "Fatal influxdb_logs sink error.", %error));
 
         Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
@@ -150,6 +151,10 @@ impl SinkConfig for InfluxDBLogsConfig {
     fn sink_type(&self) -> &'static str {
         "influxdb_logs"
     }
+
+    fn supports_dead_letter(&self) -> bool {
+        true
+    }
 }
 
 #[async_trait::async_trait]
@@ -226,7 +231,7 @@ impl InfluxDBLogsConfig {
 }
 
 impl Value {
-    fn to_field(&self) -> Field {
+    pub(super) fn to_field(&self) -> Field {
         match self {
             Value::Integer(num) => Field::Int(*num),
             Value::Float(num) => Field::Float(*num),
@@ -209,7 +209,7 @@ fn merge_tags(
     }
 }
 
-fn encode_events(
+pub(super) fn encode_events(
     protocol_version: ProtocolVersion,
     events: Vec<Metric>,
     default_namespace: Option<&str>,
@@ -1,5 +1,6 @@
 pub mod logs;
 pub mod metrics;
+pub mod v2;
 
 use crate::http::HttpClient;
 use chrono::{DateTime, Utc};
@@ -0,0 +1,410 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, LogEvent, Value},
+    http::HttpClient,
+    sinks::{
+        influxdb::{
+            encode_timestamp, healthcheck, influx_line_protocol, metrics::encode_events, Field,
+            InfluxDB2Settings, InfluxDBSettings, ProtocolVersion,
+        },
+        util::{
+            encode_namespace,
+            http::{BatchedHttpSink, HttpSink},
+            statistic::validate_quantiles,
+            BatchConfig, BatchSettings, Buffer, Compression, TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::{TlsOptions, TlsSettings},
+};
+use futures::SinkExt;
+use http::{Request, Uri};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Writes both logs and metrics to the InfluxDB 2 write API.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct InfluxDBV2Config {
+    pub endpoint: String,
+    pub org: String,
+    pub bucket: String,
+    pub token: String,
+    pub default_namespace: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub logs: LogFieldMapping,
+    #[serde(default = "super::metrics::default_summary_quantiles")]
+    pub quantiles: Vec<f64>,
+    #[serde(default)]
+    pub compression: Compression,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsOptions>,
+}
+
+/// How the fields of a log event are written as a point.
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[derivative(Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogFieldMapping {
+    #[derivative(Default(value = "default_measurement()"))]
+    pub measurement: String,
+    pub tags: Vec<String>,
+    pub fields: Vec<String>,
+}
+
+fn default_measurement() -> String {
+    "vector".into()
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        retry_attempts: Some(5),
+        ..Default::default()
+    };
+}
+
+inventory::submit! {
+    SinkDescription::new::<InfluxDBV2Config>("influxdb")
+}
+
+impl GenerateConfig for InfluxDBV2Config {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "http://localhost:8086/"
+            org = "my-org"
+            bucket = "my-bucket"
+            token = "${INFLUXDB_TOKEN}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "influxdb")]
+impl SinkConfig for InfluxDBV2Config {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        validate_quantiles(&self.quantiles)?;
+        let compression = self.compression.gzip_only()?;
+
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls_settings)?;
+        let healthcheck = healthcheck(
+            self.endpoint.clone(),
+            None,
+            Some(self.settings()),
+            client.clone(),
+        )?;
+
+        let batch = BatchSettings::default()
+            .bytes(bytesize::mib(1u64))
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+
+        let mut tags: HashSet<String> = self.logs.tags.iter().cloned().collect();
+        tags.insert(log_schema().host_key().to_string());
+        tags.insert(log_schema().source_type_key().to_string());
+
+        let sink = InfluxDBV2Sink {
+            uri: self.settings().write_uri(self.endpoint.clone())?,
+            token: self.token.clone(),
+            default_namespace: self.default_namespace.clone(),
+            tags: self.tags.clone(),
+            measurement: self.logs.measurement.clone(),
+            log_tags: tags,
+            log_fields: self.logs.fields.clone(),
+            quantiles: self.quantiles.clone(),
+            compression,
+        };
+
+        let sink = BatchedHttpSink::new(
+            sink,
+            Buffer::new(batch.size, compression),
+            request,
+            batch.timeout,
+            client,
+            cx.acker(),
+        )
+        .with_dead_letter(cx.dead_letter())
+        .sink_map_err(|error| error!(message = "Fatal influxdb sink error.", %error));
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "influxdb"
+    }
+
+    fn supports_dead_letter(&self) -> bool {
+        true
+    }
+}
+
+impl InfluxDBV2Config {
+    fn settings(&self) -> InfluxDB2Settings {
+        InfluxDB2Settings {
+            org: self.org.clone(),
+            bucket: self.bucket.clone(),
+            token: self.token.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct InfluxDBV2Sink {
+    uri: Uri,
+    token: String,
+    default_namespace: Option<String>,
+    tags: Option<HashMap<String, String>>,
+    measurement: String,
+    log_tags: HashSet<String>,
+    log_fields: Vec<String>,
+    quantiles: Vec<f64>,
+    compression: Compression,
+}
+
+impl InfluxDBV2Sink {
+    fn encode_log(&self, mut log: LogEvent) -> String {
+        let mut output = String::new();
+
+        let measurement =
+            encode_namespace(self.default_namespace.as_deref(), '.', &self.measurement);
+
+        let timestamp = encode_timestamp(match log.remove(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(ts)) => Some(ts),
+            _ => None,
+        });
+
+        let mut tags: BTreeMap<String, String> = self
+            .tags
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let mut fields: HashMap<String, Field> = HashMap::new();
+        if self.log_fields.is_empty() {
+            for (key, value) in log.all_fields() {
+                if self.log_tags.contains(&key) {
+                    tags.insert(key, value.to_string_lossy());
+                } else {
+                    fields.insert(key, value.to_field());
+                }
+            }
+        } else {
+            for key in &self.log_tags {
+                if let Some(value) = log.get(key) {
+                    tags.insert(key.clone(), value.to_string_lossy());
+                }
+            }
+            for key in &self.log_fields {
+                if let Some(value) = log.get(key) {
+                    fields.insert(key.clone(), value.to_field());
+                }
+            }
+        }
+
+        influx_line_protocol(
+            ProtocolVersion::V2,
+            measurement,
+            "logs",
+            Some(tags),
+            Some(fields),
+            timestamp,
+            &mut output,
+        );
+
+        output
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for InfluxDBV2Sink {
+    type Input = Vec<u8>;
+    type Output = Vec<u8>;
+
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        let output = match event {
+            Event::Log(log) => self.encode_log(log),
+            Event::Metric(metric) => encode_events(
+                ProtocolVersion::V2,
+                vec![metric],
+                self.default_namespace.as_deref(),
+                self.tags.as_ref(),
+                &self.quantiles,
+            ),
+        };
+
+        // Points without any fields are dropped by the line protocol encoder.
+        if output.is_empty() {
+            None
+        } else {
+            Some(output.into_bytes())
+        }
+    }
+
+    async fn build_request(&self, events: Self::Output) -> crate::Result<Request<Vec<u8>>> {
+        let mut builder = Request::post(&self.uri)
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .header("Authorization", format!("Token {}", &self.token));
+        if let Some(ce) = self.compression.content_encoding() {
+            builder = builder.header("Content-Encoding", ce);
+        }
+        builder.body(events).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::metric::{Metric, MetricKind, MetricValue},
+        sinks::influxdb::test_util::{assert_fields, split_line_protocol, ts},
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<InfluxDBV2Config>();
+    }
+
+    fn create_sink(config: &str) -> InfluxDBV2Sink {
+        let config: InfluxDBV2Config = toml::from_str(config).unwrap();
+        let mut log_tags: HashSet<String> = config.logs.tags.iter().cloned().collect();
+        log_tags.insert("host".into());
+        log_tags.insert("source_type".into());
+        InfluxDBV2Sink {
+            uri: config
+                .settings()
+                .write_uri(config.endpoint.clone())
+                .unwrap(),
+            token: config.token.clone(),
+            default_namespace: config.default_namespace.clone(),
+            tags: config.tags.clone(),
+            measurement: config.logs.measurement.clone(),
+            log_tags,
+            log_fields: config.logs.fields.clone(),
+            quantiles: config.quantiles.clone(),
+            compression: config.compression,
+        }
+    }
+
+    const CONFIG: &str = r#"
+        endpoint = "http://localhost:9999"
+        org = "my-org"
+        bucket = "my-bucket"
+        token = "my-token"
+    "#;
+
+    fn log_event() -> Event {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("host", "aws.cloud.eur");
+        event.as_mut_log().insert("service", "api");
+        event.as_mut_log().insert("status", 200);
+        event.as_mut_log().insert("timestamp", ts());
+        event
+    }
+
+    #[test]
+    fn encodes_logs() {
+        let sink = create_sink(CONFIG);
+
+        let bytes = sink.encode_event(log_event()).unwrap();
+        let line_protocol = split_line_protocol(std::str::from_utf8(&bytes).unwrap());
+
+        assert_eq!("vector", line_protocol.0);
+        assert_eq!("host=aws.cloud.eur,metric_type=logs", line_protocol.1);
+        assert_fields(
+            line_protocol.2.to_string(),
+            vec!["message=\"hello\"", "service=\"api\"", "status=200i"],
+        );
+        assert_eq!("1542182950000000011\n", line_protocol.3);
+    }
+
+    #[test]
+    fn encodes_logs_with_field_mapping() {
+        let sink = create_sink(&format!(
+            r#"{}
+            default_namespace = "ns"
+            tags.region = "us-west-1"
+            logs.measurement = "requests"
+            logs.tags = ["service"]
+            logs.fields = ["status"]
+            "#,
+            CONFIG
+        ));
+
+        let bytes = sink.encode_event(log_event()).unwrap();
+        let line_protocol = split_line_protocol(std::str::from_utf8(&bytes).unwrap());
+
+        assert_eq!("ns.requests", line_protocol.0);
+        assert_eq!(
+            "host=aws.cloud.eur,metric_type=logs,region=us-west-1,service=api",
+            line_protocol.1
+        );
+        assert_fields(line_protocol.2.to_string(), vec!["status=200i"]);
+    }
+
+    #[test]
+    fn skips_logs_without_fields() {
+        let sink = create_sink(&format!(
+            r#"{}
+            logs.fields = ["missing"]
+            "#,
+            CONFIG
+        ));
+
+        assert!(sink.encode_event(log_event()).is_none());
+    }
+
+    #[test]
+    fn encodes_metrics() {
+        let sink = create_sink(&format!(
+            r#"{}
+            default_namespace = "ns"
+            "#,
+            CONFIG
+        ));
+        let event = Event::Metric(Metric {
+            name: "requests".into(),
+            namespace: None,
+            timestamp: Some(ts()),
+            tags: None,
+            kind: MetricKind::Absolute,
+            value: MetricValue::Counter { value: 1.5 },
+        });
+
+        let bytes = sink.encode_event(event).unwrap();
+
+        assert_eq!(
+            std::str::from_utf8(&bytes).unwrap(),
+            "ns.requests,metric_type=counter value=1.5 1542182950000000011\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn compresses_requests() {
+        let sink = create_sink(&format!(
+            r#"{}
+            compression = "gzip"
+            "#,
+            CONFIG
+        ));
+
+        let request = sink.build_request(vec![]).await.unwrap();
+
+        assert_eq!(
+            request.uri(),
+            "http://localhost:9999/api/v2/write?org=my-org&bucket=my-bucket&precision=ns"
+        );
+        assert_eq!(request.headers()["Content-Encoding"], "gzip");
+        assert_eq!(request.headers()["Authorization"], "Token my-token");
+    }
+}
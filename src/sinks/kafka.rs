@@ -17,6 +17,7 @@ use futures::{
 use rdkafka::{
     consumer::{BaseConsumer, Consumer},
     error::{KafkaError, RDKafkaError},
+    message::OwnedHeaders,
     producer::{DeliveryFuture, FutureProducer, FutureRecord},
     ClientConfig,
 };
@@ -40,13 +41,20 @@ enum BuildError {
     KafkaCreateFailed { source: KafkaError },
     #[snafu(display("invalid topic template: {}", source))]
     TopicTemplate { source: TemplateError },
+    #[snafu(display("`key` and `key_field` can't both be set"))]
+    KeyConflict,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct KafkaSinkConfig {
     bootstrap_servers: String,
     topic: String,
+    /// The message key, rendered from the event.
+    key: Option<Template>,
     key_field: Option<String>,
+    /// Message headers, by name, rendered from the event.
+    #[serde(default)]
+    headers: HashMap<String, Template>,
     encoding: EncodingConfigWithDefault<Encoding>,
     /// These batching options will **not** override librdkafka_options values.
     #[serde(default)]
@@ -83,7 +91,8 @@ pub enum Encoding {
 pub struct KafkaSink {
     producer: Arc<FutureProducer>,
     topic: Template,
-    key_field: Option<String>,
+    key: Option<Template>,
+    headers: Vec<(String, Template)>,
     encoding: EncodingConfig<Encoding>,
     flush_signal: Arc<Notify>,
     delivery_fut: FuturesUnordered<BoxFuture<'static, (usize, Result<DeliveryFuture, KafkaError>)>>,
@@ -152,10 +161,17 @@ impl KafkaSinkConfig {
             // messages to accumulate at the expense of increased message delivery latency.
             // Type: float
             let key = "queue.buffering.max.ms";
-            if let Some(val) = self.librdkafka_options.get(key) {
-                return Err(format!("Batching setting `batch.timeout_secs` sets `librdkafka_options.{}={}`.\
-                                    The config already sets this as `librdkafka_options.queue.buffering.max.ms={}`.\
-                                    Please delete one.", key, queue_buffering_max_ms, val).into());
+            // `linger.ms` is an alias of the same property.
+            for option in &[key, "linger.ms"] {
+                if let Some(val) = self.librdkafka_options.get(*option) {
+                    return Err(format!(
+                        "Batching setting `batch.timeout_secs` sets `librdkafka_options.{}={}`.\
+                                        The config already sets this as `librdkafka_options.{}={}`.\
+                                        Please delete one.",
+                        key, queue_buffering_max_ms, option, val
+                    )
+                    .into());
+                }
             }
             client_config.set(key, &(queue_buffering_max_ms * 1000).to_string());
         }
@@ -187,6 +203,22 @@ impl KafkaSinkConfig {
             client_config.set(key, &batch_size.to_string());
         }
 
+        if !matches!(self.compression, KafkaCompression::None) {
+            let key = "compression.codec";
+            if let Some(val) = self.librdkafka_options.get(key) {
+                return Err(format!(
+                    "Setting `compression` sets `librdkafka_options.{}={}`. \
+                     The config already sets this as `librdkafka_options.{}={}`. \
+                     Please delete one.",
+                    key,
+                    to_string(self.compression),
+                    key,
+                    val
+                )
+                .into());
+            }
+        }
+
         for (key, value) in self.librdkafka_options.iter() {
             client_config.set(key.as_str(), value.as_str());
         }
@@ -197,11 +229,21 @@ impl KafkaSinkConfig {
 
 impl KafkaSink {
     fn new(config: KafkaSinkConfig, acker: Acker) -> crate::Result<Self> {
+        let key = match (config.key, config.key_field) {
+            (Some(_), Some(_)) => return Err(BuildError::KeyConflict.into()),
+            (Some(key), None) => Some(key),
+            (None, Some(field)) => Some(Template::try_from(format!("{{{{ {} }}}}", field))?),
+            (None, None) => None,
+        };
+        let mut headers = config.headers.into_iter().collect::<Vec<_>>();
+        headers.sort_by(|a, b| a.0.cmp(&b.0));
+
         let producer = config.to_rdkafka()?.create().context(KafkaCreateFailed)?;
         Ok(KafkaSink {
             producer: Arc::new(producer),
             topic: Template::try_from(config.topic).context(TopicTemplate)?,
-            key_field: config.key_field,
+            key,
+            headers,
             encoding: config.encoding.into(),
             flush_signal: Arc::new(Notify::new()),
             delivery_fut: FuturesUnordered::new(),
@@ -252,7 +294,8 @@ impl Sink<Event> for KafkaSink {
         let topic = self.topic.render_string(&item).map_err(|missing_keys| {
             error!(message = "Missing keys for topic.", missing_keys = ?missing_keys);
         })?;
-        let (key, body) = encode_event(item.clone(), &self.key_field, &self.encoding);
+        let headers = render_headers(&item, &self.headers);
+        let (key, body) = encode_event(item.clone(), &self.key, &self.encoding);
 
         let seqno = self.seq_head;
         self.seq_head += 1;
@@ -261,6 +304,9 @@ impl Sink<Event> for KafkaSink {
         let flush_signal = Arc::clone(&self.flush_signal);
         self.delivery_fut.push(Box::pin(async move {
             let mut record = FutureRecord::to(&topic).key(&key).payload(&body[..]);
+            if let Some(headers) = headers {
+                record = record.headers(headers);
+            }
             if let Some(Value::Timestamp(timestamp)) =
                 item.as_log().get(log_schema().timestamp_key())
             {
@@ -362,15 +408,37 @@ async fn healthcheck(config: KafkaSinkConfig) -> crate::Result<()> {
     Ok(())
 }
 
+/// Renders the configured headers, leaving out those whose fields are missing.
+fn render_headers(event: &Event, headers: &[(String, Template)]) -> Option<OwnedHeaders> {
+    if headers.is_empty() {
+        return None;
+    }
+
+    let mut owned = OwnedHeaders::new_with_capacity(headers.len());
+    for (name, template) in headers {
+        match template.render(event) {
+            Ok(value) => owned = owned.add(name.as_str(), &value[..]),
+            Err(missing_keys) => warn!(
+                message = "Keys do not exist on the event; leaving out header.",
+                header = %name,
+                ?missing_keys,
+                rate_limit_secs = 30,
+            ),
+        }
+    }
+    Some(owned)
+}
+
 fn encode_event(
     mut event: Event,
-    key_field: &Option<String>,
+    key: &Option<Template>,
     encoding: &EncodingConfig<Encoding>,
 ) -> (Vec<u8>, Vec<u8>) {
-    let key = key_field
+    // A key with missing fields is left blank.
+    let key = key
         .as_ref()
-        .and_then(|f| event.as_log().get(f))
-        .map(|v| v.as_bytes().to_vec())
+        .and_then(|key| key.render(&event).ok())
+        .map(|key| key.to_vec())
         .unwrap_or_default();
 
     encoding.apply_rules(&mut event);
@@ -421,7 +489,7 @@ mod tests {
 
         let (key, bytes) = encode_event(
             event,
-            &Some("key".into()),
+            &Some(Template::try_from("{{ key }}").unwrap()),
             &EncodingConfig::from(Encoding::Json),
         );
 
@@ -440,7 +508,7 @@ mod tests {
 
         let (key, bytes) = encode_event(
             event,
-            &Some("key".into()),
+            &Some(Template::try_from("{{ key }}").unwrap()),
             &EncodingConfigWithDefault {
                 codec: Encoding::Json,
                 except_fields: Some(vec!["key".into()]),
@@ -454,6 +522,109 @@ mod tests {
         assert_eq!(&key[..], b"value");
         assert!(!map.contains_key("key"));
     }
+
+    #[test]
+    fn kafka_encode_event_key_template() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("tenant", "acme");
+        event.as_mut_log().insert("user_id", 42);
+
+        let key = Template::try_from("{{ tenant }}-{{ user_id }}").unwrap();
+        let (key_bytes, _) = encode_event(event, &Some(key), &EncodingConfig::from(Encoding::Text));
+        assert_eq!(&key_bytes[..], b"acme-42");
+
+        let key = Template::try_from("{{ missing }}").unwrap();
+        let (key_bytes, _) = encode_event(
+            Event::from("hello"),
+            &Some(key),
+            &EncodingConfig::from(Encoding::Text),
+        );
+        assert!(key_bytes.is_empty());
+    }
+
+    #[test]
+    fn kafka_render_headers() {
+        use rdkafka::message::Headers;
+
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("trace_id", "abc123");
+        let headers = vec![
+            (
+                "missing".to_string(),
+                Template::try_from("{{ span }}").unwrap(),
+            ),
+            ("source".to_string(), Template::try_from("vector").unwrap()),
+            (
+                "trace-id".to_string(),
+                Template::try_from("{{ trace_id }}").unwrap(),
+            ),
+        ];
+
+        let headers = render_headers(&event, &headers).unwrap();
+        assert_eq!(headers.count(), 2);
+        assert_eq!(headers.get(0), Some(("source", &b"vector"[..])));
+        assert_eq!(headers.get(1), Some(("trace-id", &b"abc123"[..])));
+
+        assert!(render_headers(&event, &[]).is_none());
+    }
+
+    #[test]
+    fn kafka_rejects_key_and_key_field() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "logs"
+            key = "{{ user_id }}"
+            key_field = "user_id"
+            encoding.codec = "json"
+            "#,
+        )
+        .unwrap();
+        let (acker, _) = Acker::new_for_testing();
+
+        assert!(KafkaSink::new(config, acker).is_err());
+    }
+
+    #[test]
+    fn kafka_librdkafka_options_conflicts() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "logs"
+            encoding.codec = "json"
+            batch.timeout_secs = 1
+            librdkafka_options."linger.ms" = "50"
+            "#,
+        )
+        .unwrap();
+        assert!(config.to_rdkafka().is_err());
+
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "logs"
+            encoding.codec = "json"
+            compression = "gzip"
+            librdkafka_options."compression.codec" = "lz4"
+            "#,
+        )
+        .unwrap();
+        assert!(config.to_rdkafka().is_err());
+
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "logs"
+            encoding.codec = "json"
+            librdkafka_options."compression.codec" = "lz4"
+            librdkafka_options."enable.idempotence" = "true"
+            "#,
+        )
+        .unwrap();
+        let client_config = config.to_rdkafka().unwrap();
+        assert_eq!(client_config.get("compression.codec"), Some("lz4"));
+        assert_eq!(client_config.get("enable.idempotence"), Some("true"));
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -526,7 +697,9 @@ mod integration_test {
             topic: format!("{}-%Y%m%d", topic),
             compression: KafkaCompression::None,
             encoding: EncodingConfigWithDefault::from(Encoding::Text),
+            key: None,
             key_field: None,
+            headers: HashMap::new(),
             auth: KafkaAuthConfig {
                 sasl: None,
                 tls: None,
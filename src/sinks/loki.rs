@@ -11,17 +11,26 @@
 //!
 //! If an event produces no labels, this can happen if the template
 //! does not match, we will add a default label `{agent="vector"}`.
+//!
+//! Loki rejects entries older than the newest entry of their stream, unless
+//! its out-of-order window is enabled. The sink tracks the newest timestamp
+//! sent to each stream and applies the configured `out_of_order_action` to
+//! older events, and caps the number of streams it creates at
+//! `cardinality_limit`.
 
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::{self, Event, Value},
     http::{Auth, HttpClient},
+    internal_events::{
+        LokiCardinalityLimitReached, LokiOutOfOrderEventDropped, LokiOutOfOrderEventRewritten,
+    },
     sinks::util::{
-        buffer::loki::{LokiBuffer, LokiEvent, LokiRecord},
+        buffer::loki::{Labels, LokiBuffer, LokiEvent, LokiRecord},
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
         http::{HttpSink, PartitionHttpSink},
-        BatchConfig, BatchSettings, PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig,
-        UriSerde,
+        BatchConfig, BatchSettings, Concurrency, PartitionBuffer, PartitionInnerBuffer,
+        TowerRequestConfig, UriSerde,
     },
     template::Template,
     tls::{TlsOptions, TlsSettings},
@@ -29,7 +38,7 @@ use crate::{
 use derivative::Derivative;
 use futures::{FutureExt, SinkExt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Mutex};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -46,6 +55,12 @@ pub struct LokiConfig {
     #[serde(default = "crate::serde::default_true")]
     remove_timestamp: bool,
 
+    #[serde(default)]
+    out_of_order_action: OutOfOrderAction,
+    /// The maximum number of streams, that is distinct sets of labels per
+    /// tenant, the sink will send to.
+    cardinality_limit: Option<usize>,
+
     auth: Option<Auth>,
 
     #[serde(default)]
@@ -66,6 +81,20 @@ enum Encoding {
     Text,
 }
 
+/// What to do with events older than the newest event already sent to their
+/// stream.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum OutOfOrderAction {
+    #[derivative(Default)]
+    Drop,
+    /// Send them with the timestamp of the newest event instead.
+    RewriteTimestamp,
+    /// Send them as they are, for Loki servers with an out-of-order window.
+    Accept,
+}
+
 inventory::submit! {
     SinkDescription::new::<LokiConfig>("loki")
 }
@@ -90,8 +119,26 @@ impl SinkConfig for LokiConfig {
         if self.labels.is_empty() {
             return Err("`labels` must include at least one label.".into());
         }
+        if let Some(name) = self.labels.keys().find(|name| !valid_label_name(name)) {
+            return Err(format!(
+                "Invalid label name {:?}: label names must match `[a-zA-Z_][a-zA-Z0-9_]*`.",
+                name
+            )
+            .into());
+        }
+        if self.cardinality_limit == Some(0) {
+            return Err("`cardinality_limit` must be greater than zero.".into());
+        }
 
-        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let request_defaults = match self.out_of_order_action {
+            OutOfOrderAction::Accept => TowerRequestConfig::default(),
+            // Concurrent requests could reach Loki in any order.
+            _ => TowerRequestConfig {
+                concurrency: Concurrency::Fixed(1),
+                ..Default::default()
+            },
+        };
+        let request_settings = self.request.unwrap_with(&request_defaults);
         let batch_settings = BatchSettings::default()
             .bytes(102_400)
             .events(100_000)
@@ -101,13 +148,14 @@ impl SinkConfig for LokiConfig {
         let client = HttpClient::new(tls)?;
 
         let sink = PartitionHttpSink::new(
-            self.clone(),
+            LokiSink::new(self.clone()),
             PartitionBuffer::new(LokiBuffer::new(batch_settings.size)),
             request_settings,
             batch_settings.timeout,
             client.clone(),
             cx.acker(),
         )
+        .with_dead_letter(cx.dead_letter())
         .sink_map_err(|error| error!(message = "Fatal loki sink error.", %error));
 
         let healthcheck = healthcheck(self.clone(), client).boxed();
@@ -122,6 +170,16 @@ impl SinkConfig for LokiConfig {
     fn sink_type(&self) -> &'static str {
         "loki"
     }
+
+    fn supports_dead_letter(&self) -> bool {
+        true
+    }
+}
+
+fn valid_label_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
 #[derive(Hash, Eq, PartialEq, Clone)]
@@ -129,13 +187,88 @@ pub struct PartitionKey {
     tenant_id: Option<String>,
 }
 
+struct LokiSink {
+    config: LokiConfig,
+    /// The non-templated labels, sent alone once the cardinality limit is
+    /// reached.
+    static_labels: Labels,
+    /// The newest timestamp sent to each stream, keyed by tenant and sorted
+    /// labels.
+    streams: Mutex<HashMap<(Option<String>, Labels), i64>>,
+}
+
+impl LokiSink {
+    fn new(config: LokiConfig) -> Self {
+        let mut static_labels = config
+            .labels
+            .iter()
+            .filter(|(_, template)| !template.is_dynamic())
+            .map(|(key, template)| (key.clone(), template.get_ref().to_owned()))
+            .collect::<Labels>();
+        if static_labels.is_empty() {
+            static_labels.push(("agent".to_string(), "vector".to_string()));
+        }
+        static_labels.sort();
+
+        Self {
+            config,
+            static_labels,
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Enforces the cardinality limit and the out-of-order action, returning
+    /// the labels and timestamp to send the event with, or `None` if it must
+    /// be dropped.
+    fn track(
+        &self,
+        tenant_id: &Option<String>,
+        mut labels: Labels,
+        timestamp: i64,
+    ) -> Option<(Labels, i64)> {
+        let action = self.config.out_of_order_action;
+        if action == OutOfOrderAction::Accept && self.config.cardinality_limit.is_none() {
+            return Some((labels, timestamp));
+        }
+
+        labels.sort();
+        let mut streams = self.streams.lock().unwrap();
+        let mut key = (tenant_id.clone(), labels);
+        if let Some(limit) = self.config.cardinality_limit {
+            if streams.len() >= limit && !streams.contains_key(&key) {
+                emit!(LokiCardinalityLimitReached { limit });
+                key.1 = self.static_labels.clone();
+            }
+        }
+
+        let labels = key.1.clone();
+        let latest = streams.entry(key).or_insert(timestamp);
+        if timestamp >= *latest {
+            *latest = timestamp;
+            return Some((labels, timestamp));
+        }
+        match action {
+            OutOfOrderAction::Drop => {
+                emit!(LokiOutOfOrderEventDropped);
+                None
+            }
+            OutOfOrderAction::RewriteTimestamp => {
+                emit!(LokiOutOfOrderEventRewritten);
+                Some((labels, *latest))
+            }
+            OutOfOrderAction::Accept => Some((labels, timestamp)),
+        }
+    }
+}
+
 #[async_trait::async_trait]
-impl HttpSink for LokiConfig {
+impl HttpSink for LokiSink {
     type Input = PartitionInnerBuffer<LokiRecord, PartitionKey>;
     type Output = PartitionInnerBuffer<serde_json::Value, PartitionKey>;
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
-        let tenant_id = self.tenant_id.as_ref().and_then(|t| {
+        let config = &self.config;
+        let tenant_id = config.tenant_id.as_ref().and_then(|t| {
             t.render_string(&event)
                 .map_err(|missing| {
                     error!(
@@ -150,12 +283,12 @@ impl HttpSink for LokiConfig {
 
         let mut labels = Vec::new();
 
-        for (key, template) in &self.labels {
+        for (key, template) in &config.labels {
             if let Ok(value) = template.render_string(&event) {
                 labels.push((key.clone(), value));
             }
 
-            if self.remove_label_fields {
+            if config.remove_label_fields {
                 if let Some(fields) = template.get_fields() {
                     for field in fields {
                         event.as_mut_log().remove(&field);
@@ -169,12 +302,12 @@ impl HttpSink for LokiConfig {
             _ => chrono::Utc::now().timestamp_nanos(),
         };
 
-        if self.remove_timestamp {
+        if config.remove_timestamp {
             event.as_mut_log().remove(log_schema().timestamp_key());
         }
 
-        self.encoding.apply_rules(&mut event);
-        let event = match &self.encoding.codec() {
+        config.encoding.apply_rules(&mut event);
+        let event = match &config.encoding.codec() {
             Encoding::Json => serde_json::to_string(&event.as_log().all_fields())
                 .expect("json encoding should never fail"),
 
@@ -192,6 +325,7 @@ impl HttpSink for LokiConfig {
             labels = vec![("agent".to_string(), "vector".to_string())]
         }
 
+        let (labels, timestamp) = self.track(&key.tenant_id, labels, timestamp)?;
         let event = LokiEvent { timestamp, event };
         Some(PartitionInnerBuffer::new(LokiRecord { labels, event }, key))
     }
@@ -202,7 +336,7 @@ impl HttpSink for LokiConfig {
 
         let body = serde_json::to_vec(&json).unwrap();
 
-        let uri = format!("{}loki/api/v1/push", self.endpoint);
+        let uri = format!("{}loki/api/v1/push", self.config.endpoint);
 
         let mut req = http::Request::post(uri).header("Content-Type", "application/json");
 
@@ -212,7 +346,7 @@ impl HttpSink for LokiConfig {
 
         let mut req = req.body(body).unwrap();
 
-        if let Some(auth) = &self.auth {
+        if let Some(auth) = &self.config.auth {
             auth.apply(&mut req);
         }
 
@@ -241,10 +375,12 @@ async fn healthcheck(config: LokiConfig, client: HttpClient) -> crate::Result<()
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::SinkConfig;
     use crate::sinks::util::http::HttpSink;
     use crate::sinks::util::test::{build_test_server, load_sink};
     use crate::test_util;
     use crate::Event;
+    use chrono::TimeZone;
     use futures::StreamExt;
 
     #[test]
@@ -268,7 +404,8 @@ mod tests {
 
         e1.as_mut_log().insert("foo", "bar");
 
-        let mut record = config.encode_event(e1).unwrap().into_parts().0;
+        let sink = LokiSink::new(config);
+        let mut record = sink.encode_event(e1).unwrap().into_parts().0;
 
         // HashMap -> Vec doesn't like keeping ordering
         record.labels.sort();
@@ -304,7 +441,8 @@ mod tests {
 
         e1.as_mut_log().insert("foo", "bar");
 
-        let record = config.encode_event(e1).unwrap().into_parts().0;
+        let sink = LokiSink::new(config);
+        let record = sink.encode_event(e1).unwrap().into_parts().0;
 
         let expected_line = serde_json::to_string(&serde_json::json!({
             "message": "hello world",
@@ -316,6 +454,121 @@ mod tests {
         assert_eq!(record.labels[0], ("bar".to_string(), "bar".to_string()));
     }
 
+    fn event_at(message: &str, timestamp: i64) -> Event {
+        let mut event = Event::from(message);
+        event.as_mut_log().insert(
+            log_schema().timestamp_key(),
+            chrono::Utc.timestamp(timestamp, 0),
+        );
+        event
+    }
+
+    fn encoded_timestamp(sink: &LokiSink, event: Event) -> Option<i64> {
+        sink.encode_event(event)
+            .map(|record| record.into_parts().0.event.timestamp)
+    }
+
+    #[test]
+    fn drops_out_of_order_events() {
+        let (config, _cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels = {app = "{{ app }}"}
+        "#,
+        )
+        .unwrap();
+        let sink = LokiSink::new(config);
+
+        assert!(encoded_timestamp(&sink, event_at("first", 20)).is_some());
+        assert!(encoded_timestamp(&sink, event_at("late", 10)).is_none());
+        assert!(encoded_timestamp(&sink, event_at("same", 20)).is_some());
+
+        // Streams are tracked separately.
+        let mut event = event_at("other stream", 10);
+        event.as_mut_log().insert("app", "api");
+        assert!(encoded_timestamp(&sink, event).is_some());
+    }
+
+    #[test]
+    fn rewrites_out_of_order_timestamps() {
+        let (config, _cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels = {app = "web"}
+            out_of_order_action = "rewrite_timestamp"
+        "#,
+        )
+        .unwrap();
+        let sink = LokiSink::new(config);
+
+        let newest = chrono::Utc.timestamp(20, 0).timestamp_nanos();
+        assert_eq!(
+            encoded_timestamp(&sink, event_at("first", 20)),
+            Some(newest)
+        );
+        assert_eq!(encoded_timestamp(&sink, event_at("late", 10)), Some(newest));
+    }
+
+    #[test]
+    fn accepts_out_of_order_events() {
+        let (config, _cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels = {app = "web"}
+            out_of_order_action = "accept"
+        "#,
+        )
+        .unwrap();
+        let sink = LokiSink::new(config);
+
+        let late = chrono::Utc.timestamp(10, 0).timestamp_nanos();
+        assert!(encoded_timestamp(&sink, event_at("first", 20)).is_some());
+        assert_eq!(encoded_timestamp(&sink, event_at("late", 10)), Some(late));
+    }
+
+    #[test]
+    fn limits_stream_cardinality() {
+        let (config, _cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels = {app = "web", request_id = "{{ request_id }}"}
+            cardinality_limit = 2
+        "#,
+        )
+        .unwrap();
+        let sink = LokiSink::new(config);
+
+        let mut labels = (1..=3)
+            .map(|n| {
+                let mut event = Event::from("hello");
+                event.as_mut_log().insert("request_id", n.to_string());
+                sink.encode_event(event).unwrap().into_parts().0.labels
+            })
+            .collect::<Vec<_>>();
+
+        let web = ("app".to_string(), "web".to_string());
+        assert_eq!(labels.pop().unwrap(), vec![web.clone()]);
+        assert_eq!(
+            labels.pop().unwrap(),
+            vec![web, ("request_id".to_string(), "2".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_label_names() {
+        let (config, cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels = {"service-name" = "web"}
+        "#,
+        )
+        .unwrap();
+
+        assert!(config.build(cx).await.is_err());
+        assert!(valid_label_name("_service_name2"));
+        assert!(!valid_label_name("2service"));
+    }
+
     #[tokio::test]
     async fn healthcheck_includes_auth() {
         let (mut config, _cx) = load_sink::<LokiConfig>(
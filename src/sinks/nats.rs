@@ -7,17 +7,25 @@ use crate::{
     sinks::util::encoding::{EncodingConfig, EncodingConfigWithDefault, EncodingConfiguration},
     sinks::util::StreamSink,
     template::{Template, TemplateError},
+    tls::TlsConfig,
 };
 use async_trait::async_trait;
 use futures::{stream::BoxStream, FutureExt, StreamExt, TryFutureExt};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::convert::TryFrom;
+use std::{convert::TryFrom, io, path::PathBuf, time::Duration};
+
+/// How long to wait for JetStream to acknowledge a message.
+const JETSTREAM_ACK_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("invalid subject template: {}", source))]
     SubjectTemplate { source: TemplateError },
+    #[snafu(display("`tls.{}` isn't supported by the nats client", option))]
+    TlsOptionNotSupported { option: &'static str },
+    #[snafu(display("`tls.crt_file` and `tls.key_file` must be set together"))]
+    IncompleteIdentity,
 }
 
 /**
@@ -31,6 +39,28 @@ pub struct NatsSinkConfig {
     name: String,
     subject: String,
     url: String,
+    /// Publish to JetStream, waiting for the stream to acknowledge each
+    /// message before acknowledging the event.
+    #[serde(default)]
+    jetstream: bool,
+    auth: Option<NatsAuthConfig>,
+    tls: Option<TlsConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub enum NatsAuthConfig {
+    /// A credentials file holding a user JWT and NKey seed.
+    CredentialsFile {
+        path: PathBuf,
+    },
+    UserPassword {
+        user: String,
+        password: String,
+    },
+    Token {
+        token: String,
+    },
 }
 
 fn default_name() -> String {
@@ -86,13 +116,7 @@ impl SinkConfig for NatsSinkConfig {
 
 impl NatsSinkConfig {
     fn to_nats_options(&self) -> crate::Result<nats::Options> {
-        // Set reconnect_buffer_size on the nats client to 0 bytes so that the
-        // client doesn't buffer internally (to avoid message loss).
-        let options = nats::Options::new()
-            .with_name(&self.name)
-            .reconnect_buffer_size(0);
-
-        Ok(options)
+        NatsOptions::from(self).to_nats_options()
     }
 
     async fn connect(&self) -> crate::Result<nats::asynk::Connection> {
@@ -114,6 +138,57 @@ async fn healthcheck(config: NatsSinkConfig) -> crate::Result<()> {
 #[derive(Clone)]
 struct NatsOptions {
     name: String,
+    auth: Option<NatsAuthConfig>,
+    tls: Option<TlsConfig>,
+}
+
+impl NatsOptions {
+    fn to_nats_options(&self) -> crate::Result<nats::Options> {
+        // Set reconnect_buffer_size on the nats client to 0 bytes so that the
+        // client doesn't buffer internally (to avoid message loss).
+        let mut options = match &self.auth {
+            None => nats::Options::new(),
+            Some(NatsAuthConfig::CredentialsFile { path }) => nats::Options::with_credentials(path),
+            Some(NatsAuthConfig::UserPassword { user, password }) => {
+                nats::Options::with_user_pass(user, password)
+            }
+            Some(NatsAuthConfig::Token { token }) => nats::Options::with_token(token),
+        }
+        .with_name(&self.name)
+        .reconnect_buffer_size(0);
+
+        if let Some(tls) = &self.tls {
+            let tls_options = &tls.options;
+            // The client always verifies the server and only reads
+            // unencrypted keys.
+            let unsupported = [
+                (
+                    "verify_certificate",
+                    tls_options.verify_certificate.is_some(),
+                ),
+                ("verify_hostname", tls_options.verify_hostname.is_some()),
+                ("key_pass", tls_options.key_pass.is_some()),
+            ];
+            if let Some((option, _)) = unsupported.iter().find(|(_, set)| *set) {
+                return Err(BuildError::TlsOptionNotSupported { option: *option }.into());
+            }
+
+            // Otherwise TLS is used whenever the server asks for it.
+            options = options.tls_required(tls.enabled.unwrap_or(false));
+            if let Some(ca_file) = &tls_options.ca_file {
+                options = options.add_root_certificate(ca_file);
+            }
+            match (&tls_options.crt_file, &tls_options.key_file) {
+                (Some(crt_file), Some(key_file)) => {
+                    options = options.client_cert(crt_file, key_file);
+                }
+                (None, None) => (),
+                _ => return Err(BuildError::IncompleteIdentity.into()),
+            }
+        }
+
+        Ok(options)
+    }
 }
 
 pub struct NatsSink {
@@ -121,16 +196,22 @@ pub struct NatsSink {
     options: NatsOptions,
     subject: Template,
     url: String,
+    jetstream: bool,
     acker: Acker,
 }
 
 impl NatsSink {
     fn new(config: NatsSinkConfig, acker: Acker) -> crate::Result<Self> {
+        let options = NatsOptions::from(&config);
+        // Catch invalid options before the sink runs.
+        options.to_nats_options()?;
+
         Ok(NatsSink {
             acker,
-            options: (&config).into(),
+            options,
             subject: Template::try_from(config.subject).context(SubjectTemplate)?,
             url: config.url,
+            jetstream: config.jetstream,
 
             // DEV: the following causes a move; needs to be last.
             encoding: config.encoding.into(),
@@ -138,26 +219,67 @@ impl NatsSink {
     }
 }
 
-impl From<NatsOptions> for nats::Options {
-    fn from(options: NatsOptions) -> Self {
-        nats::Options::new()
-            .with_name(&options.name)
-            .reconnect_buffer_size(0)
-    }
-}
-
 impl From<&NatsSinkConfig> for NatsOptions {
     fn from(options: &NatsSinkConfig) -> Self {
         Self {
             name: options.name.clone(),
+            auth: options.auth.clone(),
+            tls: options.tls.clone(),
+        }
+    }
+}
+
+/// The reply of JetStream to a published message.
+#[derive(Deserialize)]
+struct PubAck {
+    error: Option<PubAckError>,
+}
+
+#[derive(Deserialize)]
+struct PubAckError {
+    description: String,
+}
+
+async fn publish_jetstream(
+    nc: &nats::asynk::Connection,
+    subject: &str,
+    message: String,
+) -> io::Result<()> {
+    let reply = tokio::time::timeout(JETSTREAM_ACK_TIMEOUT, nc.request(subject, message))
+        .await
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                "timed out waiting for JetStream to acknowledge the message",
+            )
+        })??;
+    check_pub_ack(&reply.data)
+}
+
+fn check_pub_ack(data: &[u8]) -> io::Result<()> {
+    // Servers before NATS 2.2 acknowledge with a bare `+OK`.
+    if data.starts_with(b"+OK") {
+        return Ok(());
+    }
+
+    match serde_json::from_slice::<PubAck>(data) {
+        Ok(PubAck { error: None }) => Ok(()),
+        Ok(PubAck { error: Some(error) }) => {
+            Err(io::Error::new(io::ErrorKind::Other, error.description))
         }
+        Err(error) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid JetStream acknowledgement: {}", error),
+        )),
     }
 }
 
 #[async_trait]
 impl StreamSink for NatsSink {
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
-        let nats_options: nats::Options = self.options.clone().into();
+        let nats_options = self.options.to_nats_options().map_err(|error| {
+            error!(message = "Invalid NATS options.", %error);
+        })?;
 
         let nc = nats_options
             .connect_async(&self.url)
@@ -174,7 +296,13 @@ impl StreamSink for NatsSink {
             let log = encode_event(event, &self.encoding);
             let message_len = log.len();
 
-            match nc.publish(&subject, log).await {
+            let result = if self.jetstream {
+                publish_jetstream(&nc, &subject, log).await
+            } else {
+                nc.publish(&subject, log).await
+            };
+
+            match result {
                 Ok(_) => {
                     emit!(NatsEventSendSuccess {
                         byte_size: message_len,
@@ -236,6 +364,42 @@ mod tests {
         let expected = r#"{"a":"0","x":"23","z":25}"#;
         assert_eq!(encoded, expected);
     }
+
+    #[test]
+    fn checks_jetstream_pub_acks() {
+        assert!(check_pub_ack(br#"{"stream":"LOGS","seq":42}"#).is_ok());
+        assert!(check_pub_ack(b"+OK").is_ok());
+
+        let error =
+            check_pub_ack(br#"{"error":{"code":503,"description":"no responders"}}"#).unwrap_err();
+        assert_eq!(error.to_string(), "no responders");
+        assert!(check_pub_ack(b"-ERR").is_err());
+    }
+
+    #[test]
+    fn parses_auth_and_tls() {
+        let config: NatsSinkConfig = toml::from_str(
+            r#"
+            encoding.codec = "json"
+            subject = "logs"
+            url = "nats://127.0.0.1:4222"
+            jetstream = true
+            auth.strategy = "credentials_file"
+            auth.path = "/etc/nats/vector.creds"
+            tls.enabled = true
+            tls.crt_file = "/etc/nats/client.crt"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.jetstream);
+        assert!(matches!(
+            config.auth,
+            Some(NatsAuthConfig::CredentialsFile { .. })
+        ));
+        // A certificate needs its key.
+        assert!(config.to_nats_options().is_err());
+    }
 }
 
 #[cfg(feature = "nats-integration-tests")]
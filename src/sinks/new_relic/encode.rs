@@ -0,0 +1,376 @@
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, LogEvent, Value,
+    },
+    internal_events::NewRelicRecordTooLarge,
+    sinks::util::{
+        encode_namespace, statistic::DistributionStatistic, EncodedLength, PartitionInnerBuffer,
+    },
+};
+use chrono::{SecondsFormat, Utc};
+use indexmap::IndexMap;
+use serde_json::{json, Map};
+
+/// The API a record is sent to, as each one has its own endpoint.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(super) enum Api {
+    Logs,
+    Metrics,
+}
+
+impl Api {
+    fn key(self) -> &'static str {
+        match self {
+            Api::Logs => "logs",
+            Api::Metrics => "metrics",
+        }
+    }
+}
+
+/// A single log or metric, already encoded as JSON.
+#[derive(Clone, Debug)]
+pub(super) struct Record(String);
+
+impl EncodedLength for Record {
+    fn encoded_length(&self) -> usize {
+        // Counting the comma separating it from the next record.
+        self.0.len() + 1
+    }
+}
+
+pub(super) struct Encoder {
+    pub(super) attributes: IndexMap<String, String>,
+    pub(super) interval_ms: i64,
+}
+
+impl Encoder {
+    pub(super) fn encode_event(&self, event: Event) -> Option<PartitionInnerBuffer<Record, Api>> {
+        let (record, api) = match event {
+            Event::Log(log) => (self.encode_log(log), Api::Logs),
+            Event::Metric(metric) => (self.encode_metric(metric)?, Api::Metrics),
+        };
+        let record = Record(serde_json::Value::Object(record).to_string());
+        Some(PartitionInnerBuffer::new(record, api))
+    }
+
+    fn encode_log(&self, mut log: LogEvent) -> Map<String, serde_json::Value> {
+        let timestamp = match log.remove(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(ts)) => ts,
+            _ => Utc::now(),
+        };
+
+        let mut record = Map::new();
+        record.insert("timestamp".into(), json!(timestamp.timestamp_millis()));
+        if let Some(message) = log.remove(log_schema().message_key()) {
+            record.insert("message".into(), json!(message.to_string_lossy()));
+        }
+        let attributes = log
+            .all_fields()
+            .filter_map(|(key, value)| Some((self.attribute_name(key), attribute_value(value)?)))
+            .collect();
+        record.insert("attributes".into(), serde_json::Value::Object(attributes));
+        record
+    }
+
+    fn encode_metric(&self, metric: Metric) -> Option<Map<String, serde_json::Value>> {
+        let (metric_type, value) = match (metric.kind, &metric.value) {
+            (MetricKind::Incremental, MetricValue::Counter { value }) => ("count", json!(value)),
+            (MetricKind::Absolute, MetricValue::Counter { value })
+            | (MetricKind::Absolute, MetricValue::Gauge { value }) => ("gauge", json!(value)),
+            (MetricKind::Incremental, MetricValue::Set { values }) => {
+                ("gauge", json!(values.len()))
+            }
+            (
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    values,
+                    sample_rates,
+                    ..
+                },
+            ) => {
+                let statistic = DistributionStatistic::new(values, sample_rates, &[])?;
+                (
+                    "summary",
+                    json!({
+                        "count": statistic.count,
+                        "sum": statistic.sum,
+                        "min": statistic.min,
+                        "max": statistic.max,
+                    }),
+                )
+            }
+            _ => return None,
+        };
+
+        let mut record = Map::new();
+        record.insert(
+            "name".into(),
+            json!(encode_namespace(
+                metric.namespace.as_deref(),
+                '.',
+                &metric.name
+            )),
+        );
+        record.insert("type".into(), json!(metric_type));
+        record.insert("value".into(), value);
+        record.insert(
+            "timestamp".into(),
+            json!(metric.timestamp.unwrap_or_else(Utc::now).timestamp_millis()),
+        );
+        // Counts and summaries cover the time since the previous flush.
+        if metric_type != "gauge" {
+            record.insert("interval.ms".into(), json!(self.interval_ms));
+        }
+        let attributes = metric
+            .tags
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| (self.attribute_name(key), json!(value)))
+            .collect();
+        record.insert("attributes".into(), serde_json::Value::Object(attributes));
+        Some(record)
+    }
+
+    fn attribute_name(&self, key: String) -> String {
+        self.attributes.get(&key).cloned().unwrap_or(key)
+    }
+}
+
+fn attribute_value(value: &Value) -> Option<serde_json::Value> {
+    match value {
+        Value::Bytes(_) => Some(json!(value.to_string_lossy())),
+        Value::Integer(value) => Some(json!(value)),
+        Value::Float(value) => Some(json!(value)),
+        Value::Boolean(value) => Some(json!(value)),
+        Value::Timestamp(ts) => Some(json!(ts.to_rfc3339_opts(SecondsFormat::AutoSi, true))),
+        Value::Map(_) | Value::Array(_) | Value::Null => None,
+    }
+}
+
+/// Joins the records into payloads of at most `max_bytes`, splitting them
+/// across as many payloads as needed. Records that don't fit in a payload on
+/// their own are dropped.
+pub(super) fn encode_payloads(
+    api: Api,
+    common: Option<&str>,
+    records: Vec<Record>,
+    max_bytes: usize,
+) -> Vec<Vec<u8>> {
+    let prefix = match common {
+        Some(common) => format!(r#"[{{"common":{},"{}":["#, common, api.key()),
+        None => format!(r#"[{{"{}":["#, api.key()),
+    };
+    let suffix = "]}]";
+
+    let mut payloads = Vec::new();
+    let mut payload = prefix.clone();
+    let mut empty = true;
+    for Record(record) in records {
+        if prefix.len() + record.len() + suffix.len() > max_bytes {
+            emit!(NewRelicRecordTooLarge {
+                byte_size: record.len(),
+                max_bytes,
+            });
+            continue;
+        }
+        if !empty && payload.len() + 1 + record.len() + suffix.len() > max_bytes {
+            payload.push_str(suffix);
+            payloads.push(payload.into_bytes());
+            payload = prefix.clone();
+            empty = true;
+        }
+        if !empty {
+            payload.push(',');
+        }
+        payload.push_str(&record);
+        empty = false;
+    }
+    if !empty {
+        payload.push_str(suffix);
+        payloads.push(payload.into_bytes());
+    }
+    payloads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::StatisticKind;
+    use chrono::{offset::TimeZone, DateTime};
+    use pretty_assertions::assert_eq;
+
+    fn encoder(attributes: &[(&str, &str)]) -> Encoder {
+        Encoder {
+            attributes: attributes
+                .iter()
+                .map(|(key, name)| (key.to_string(), name.to_string()))
+                .collect(),
+            interval_ms: 1000,
+        }
+    }
+
+    fn timestamp() -> DateTime<Utc> {
+        Utc.ymd(2020, 12, 1).and_hms_milli(10, 20, 30, 400)
+    }
+
+    fn encode(encoder: &Encoder, event: Event) -> (serde_json::Value, Api) {
+        let (Record(record), api) = encoder.encode_event(event).unwrap().into_parts();
+        (serde_json::from_str(&record).unwrap(), api)
+    }
+
+    #[test]
+    fn encodes_logs() {
+        let mut event = Event::from("hello world");
+        event
+            .as_mut_log()
+            .insert(log_schema().timestamp_key(), timestamp());
+        event.as_mut_log().insert("host", "web-1");
+        event.as_mut_log().insert("http.status", 200);
+        event.as_mut_log().insert("ok", true);
+
+        let (record, api) = encode(&encoder(&[("host", "hostname")]), event);
+
+        assert_eq!(api, Api::Logs);
+        assert_eq!(
+            record,
+            json!({
+                "timestamp": 1606818030400i64,
+                "message": "hello world",
+                "attributes": {
+                    "hostname": "web-1",
+                    "http.status": 200,
+                    "ok": true,
+                },
+            })
+        );
+    }
+
+    fn metric(kind: MetricKind, value: MetricValue) -> Event {
+        Event::Metric(Metric {
+            name: "requests".into(),
+            namespace: Some("api".into()),
+            timestamp: Some(timestamp()),
+            tags: Some(
+                vec![("host".to_owned(), "web-1".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind,
+            value,
+        })
+    }
+
+    #[test]
+    fn encodes_counters_as_counts() {
+        let event = metric(MetricKind::Incremental, MetricValue::Counter { value: 2.0 });
+
+        let (record, api) = encode(&encoder(&[("host", "hostname")]), event);
+
+        assert_eq!(api, Api::Metrics);
+        assert_eq!(
+            record,
+            json!({
+                "name": "api.requests",
+                "type": "count",
+                "value": 2.0,
+                "timestamp": 1606818030400i64,
+                "interval.ms": 1000,
+                "attributes": {"hostname": "web-1"},
+            })
+        );
+    }
+
+    #[test]
+    fn encodes_gauges() {
+        let event = metric(MetricKind::Absolute, MetricValue::Gauge { value: 1.5 });
+
+        let (record, _) = encode(&encoder(&[]), event);
+
+        assert_eq!(record["type"], json!("gauge"));
+        assert_eq!(record["value"], json!(1.5));
+        assert!(record.get("interval.ms").is_none());
+    }
+
+    #[test]
+    fn encodes_distributions_as_summaries() {
+        let event = metric(
+            MetricKind::Incremental,
+            MetricValue::Distribution {
+                values: vec![1.0, 4.0],
+                sample_rates: vec![2, 1],
+                statistic: StatisticKind::Histogram,
+            },
+        );
+
+        let (record, _) = encode(&encoder(&[]), event);
+
+        assert_eq!(record["type"], json!("summary"));
+        assert_eq!(
+            record["value"],
+            json!({"count": 3, "sum": 6.0, "min": 1.0, "max": 4.0})
+        );
+        assert_eq!(record["interval.ms"], json!(1000));
+    }
+
+    #[test]
+    fn drops_unsupported_metrics() {
+        let event = metric(MetricKind::Incremental, MetricValue::Gauge { value: 1.0 });
+
+        assert!(encoder(&[]).encode_event(event).is_none());
+    }
+
+    fn records(count: usize) -> Vec<Record> {
+        (0..count)
+            .map(|i| Record(format!(r#"{{"i":{}}}"#, i)))
+            .collect()
+    }
+
+    #[test]
+    fn encodes_payloads() {
+        let payloads = encode_payloads(
+            Api::Logs,
+            Some(r#"{"attributes":{"a":1}}"#),
+            records(2),
+            1000,
+        );
+
+        assert_eq!(
+            payloads,
+            vec![br#"[{"common":{"attributes":{"a":1}},"logs":[{"i":0},{"i":1}]}]"#.to_vec()]
+        );
+    }
+
+    #[test]
+    fn splits_large_payloads() {
+        // The envelope takes 16 bytes and each record 7, plus a comma.
+        let payloads = encode_payloads(Api::Metrics, None, records(5), 16 + 7 * 2 + 1);
+
+        let payloads: Vec<_> = payloads
+            .into_iter()
+            .map(|payload| String::from_utf8(payload).unwrap())
+            .collect();
+        assert_eq!(
+            payloads,
+            vec![
+                r#"[{"metrics":[{"i":0},{"i":1}]}]"#,
+                r#"[{"metrics":[{"i":2},{"i":3}]}]"#,
+                r#"[{"metrics":[{"i":4}]}]"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_records_larger_than_payloads() {
+        let mut records = records(2);
+        records.insert(1, Record(format!(r#"{{"i":"{}"}}"#, "x".repeat(100))));
+
+        let payloads = encode_payloads(Api::Metrics, None, records, 50);
+
+        assert_eq!(
+            payloads,
+            vec![br#"[{"metrics":[{"i":0},{"i":1}]}]"#.to_vec()]
+        );
+    }
+}
@@ -0,0 +1,439 @@
+mod encode;
+
+use self::encode::{encode_payloads, Api, Encoder, Record};
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    http::{HttpClient, HttpError},
+    sinks::{
+        util::{
+            buffer::GZIP_FAST, retries::RetryAction, sink::Response, BatchConfig, BatchSettings,
+            Compression, Concurrency, PartitionBuffer, PartitionInnerBuffer, RetryLogic,
+            TowerRequestConfig, VecBuffer,
+        },
+        Healthcheck, VectorSink,
+    },
+};
+use futures::{
+    future::{self, BoxFuture},
+    stream, FutureExt, SinkExt, StreamExt,
+};
+use http::{Request, StatusCode};
+use hyper::Body;
+use indexmap::IndexMap;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::task::{Context, Poll};
+use tower::Service;
+
+// Both APIs accept payloads of up to 1MB (10^6 bytes).
+const MAX_PAYLOAD_BYTES: usize = 1_000_000;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NewRelicConfig {
+    pub license_key: String,
+    #[serde(default)]
+    pub region: NewRelicRegion,
+    #[serde(default)]
+    pub attributes: IndexMap<String, String>,
+    #[serde(default)]
+    pub common_attributes: IndexMap<String, String>,
+    #[serde(default)]
+    pub compression: Compression,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Derivative)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NewRelicRegion {
+    #[derivative(Default)]
+    Us,
+    Eu,
+}
+
+impl NewRelicRegion {
+    fn endpoint(self, api: Api) -> &'static str {
+        match (self, api) {
+            (NewRelicRegion::Us, Api::Logs) => "https://log-api.newrelic.com/log/v1",
+            (NewRelicRegion::Us, Api::Metrics) => "https://metric-api.newrelic.com/metric/v1",
+            (NewRelicRegion::Eu, Api::Logs) => "https://log-api.eu.newrelic.com/log/v1",
+            (NewRelicRegion::Eu, Api::Metrics) => "https://metric-api.eu.newrelic.com/metric/v1",
+        }
+    }
+}
+
+lazy_static! {
+    // The default throughput ceiling defaults are relatively conservative so
+    // we crank them up for New Relic.
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        concurrency: Concurrency::Fixed(100),
+        rate_limit_num: Some(100),
+        ..Default::default()
+    };
+}
+
+inventory::submit! {
+    SinkDescription::new::<NewRelicConfig>("new_relic")
+}
+
+impl GenerateConfig for NewRelicConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"license_key = "${NEW_RELIC_LICENSE_KEY}""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "new_relic")]
+impl SinkConfig for NewRelicConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let service = NewRelicService {
+            client: HttpClient::new(None)?,
+            region: self.region,
+            endpoints: None,
+            license_key: self.license_key.clone(),
+            common: self.common(),
+            compression: self.compression.with_default_gzip_level(GZIP_FAST),
+        };
+        let healthcheck = healthcheck(service.clone()).boxed();
+        let sink = self.build_sink(service, cx)?;
+
+        Ok((sink, healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "new_relic"
+    }
+}
+
+impl NewRelicConfig {
+    fn build_sink(&self, service: NewRelicService, cx: SinkContext) -> crate::Result<VectorSink> {
+        let batch = BatchSettings::default()
+            .bytes(MAX_PAYLOAD_BYTES as u64)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+
+        let encoder = Encoder {
+            attributes: self.attributes.clone(),
+            interval_ms: batch.timeout.as_millis() as i64,
+        };
+
+        let sink = request
+            .partition_sink(
+                NewRelicRetryLogic,
+                service,
+                PartitionBuffer::new(VecBuffer::new(batch.size)),
+                batch.timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal new_relic sink error.", %error))
+            .with_flat_map(move |event| stream::iter(encoder.encode_event(event)).map(Ok));
+
+        Ok(VectorSink::Sink(Box::new(sink)))
+    }
+
+    /// The attributes shared by every record, encoded as the `common` block of
+    /// payloads.
+    fn common(&self) -> Option<String> {
+        if self.common_attributes.is_empty() {
+            None
+        } else {
+            Some(serde_json::json!({ "attributes": self.common_attributes }).to_string())
+        }
+    }
+}
+
+/// The outcome of sending a batch, which may have taken several payloads.
+#[derive(Debug, Eq, PartialEq)]
+enum NewRelicResponse {
+    Accepted,
+    Retry(String),
+    Rejected(String),
+}
+
+impl Response for NewRelicResponse {
+    fn is_successful(&self) -> bool {
+        matches!(self, NewRelicResponse::Accepted)
+    }
+}
+
+impl NewRelicResponse {
+    fn from_status(status: StatusCode) -> Self {
+        match status {
+            status if status.is_success() => NewRelicResponse::Accepted,
+            StatusCode::REQUEST_TIMEOUT | StatusCode::TOO_MANY_REQUESTS => {
+                NewRelicResponse::Retry(format!("{}", status))
+            }
+            status if status.is_server_error() => NewRelicResponse::Retry(format!("{}", status)),
+            status => NewRelicResponse::Rejected(format!("{}", status)),
+        }
+    }
+
+    /// Payloads that can be retried take precedence, as retrying the batch
+    /// sends the rejected ones again too.
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (retry @ NewRelicResponse::Retry(_), _) | (_, retry @ NewRelicResponse::Retry(_)) => {
+                retry
+            }
+            (rejected @ NewRelicResponse::Rejected(_), _)
+            | (_, rejected @ NewRelicResponse::Rejected(_)) => rejected,
+            (NewRelicResponse::Accepted, NewRelicResponse::Accepted) => NewRelicResponse::Accepted,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct NewRelicService {
+    client: HttpClient,
+    region: NewRelicRegion,
+    // Overrides the endpoints of the region in tests.
+    endpoints: Option<(String, String)>,
+    license_key: String,
+    common: Option<String>,
+    compression: Compression,
+}
+
+impl NewRelicService {
+    fn build_request(&self, api: Api, payload: Vec<u8>) -> crate::Result<Request<Body>> {
+        let uri = match (&self.endpoints, api) {
+            (Some((logs, _)), Api::Logs) => logs.as_str(),
+            (Some((_, metrics)), Api::Metrics) => metrics.as_str(),
+            (None, api) => self.region.endpoint(api),
+        };
+        // The Metric API only takes keys in the `Api-Key` header.
+        let auth_header = match api {
+            Api::Logs => "X-License-Key",
+            Api::Metrics => "Api-Key",
+        };
+
+        let mut builder = Request::post(uri)
+            .header("Content-Type", "application/json")
+            .header(auth_header, &self.license_key);
+        if let Some(ce) = self.compression.content_encoding() {
+            builder = builder.header("Content-Encoding", ce);
+        }
+        builder
+            .body(Body::from(self.compression.compress(payload)))
+            .map_err(Into::into)
+    }
+
+    fn send(
+        self,
+        api: Api,
+        payload: Vec<u8>,
+    ) -> BoxFuture<'static, crate::Result<NewRelicResponse>> {
+        async move {
+            let request = self.build_request(api, payload)?;
+            let response = self.client.send(request).await?;
+            Ok(NewRelicResponse::from_status(response.status()))
+        }
+        .boxed()
+    }
+}
+
+impl Service<PartitionInnerBuffer<Vec<Record>, Api>> for NewRelicService {
+    type Response = NewRelicResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: PartitionInnerBuffer<Vec<Record>, Api>) -> Self::Future {
+        let (records, api) = request.into_parts();
+        let payloads = encode_payloads(api, self.common.as_deref(), records, MAX_PAYLOAD_BYTES);
+        let sends = payloads
+            .into_iter()
+            .map(|payload| self.clone().send(api, payload))
+            .collect::<Vec<_>>();
+
+        async move {
+            let mut response = NewRelicResponse::Accepted;
+            for result in future::join_all(sends).await {
+                response = response.merge(result?);
+            }
+            Ok(response)
+        }
+        .boxed()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct NewRelicRetryLogic;
+
+impl RetryLogic for NewRelicRetryLogic {
+    type Error = HttpError;
+    type Response = NewRelicResponse;
+
+    fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+        true
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        match response {
+            NewRelicResponse::Accepted => RetryAction::Successful,
+            NewRelicResponse::Retry(reason) => RetryAction::Retry(reason.clone()),
+            NewRelicResponse::Rejected(reason) => RetryAction::DontRetry(reason.clone()),
+        }
+    }
+}
+
+/// The healthcheck sends an empty payload to the Log API, which checks the
+/// license key without storing anything.
+async fn healthcheck(service: NewRelicService) -> crate::Result<()> {
+    let payload = br#"[{"logs":[]}]"#.to_vec();
+    match service.send(Api::Logs, payload).await? {
+        NewRelicResponse::Accepted => Ok(()),
+        NewRelicResponse::Retry(reason) | NewRelicResponse::Rejected(reason) => {
+            Err(format!("Payload was rejected: {}", reason).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::{
+            metric::{Metric, MetricKind, MetricValue},
+            Event,
+        },
+        sinks::util::test::build_test_server,
+        test_util::next_addr,
+    };
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<NewRelicConfig>();
+    }
+
+    fn service(config: &NewRelicConfig) -> NewRelicService {
+        NewRelicService {
+            client: HttpClient::new(None).unwrap(),
+            region: config.region,
+            endpoints: None,
+            license_key: config.license_key.clone(),
+            common: config.common(),
+            compression: config.compression,
+        }
+    }
+
+    #[test]
+    fn builds_requests() {
+        let config: NewRelicConfig = toml::from_str(
+            r#"
+            license_key = "secret"
+            region = "eu"
+            compression = "gzip"
+            "#,
+        )
+        .unwrap();
+        let service = service(&config);
+
+        let request = service.build_request(Api::Logs, vec![]).unwrap();
+        assert_eq!(request.uri(), "https://log-api.eu.newrelic.com/log/v1");
+        assert_eq!(request.headers()["X-License-Key"], "secret");
+        assert_eq!(request.headers()["Content-Encoding"], "gzip");
+
+        let request = service.build_request(Api::Metrics, vec![]).unwrap();
+        assert_eq!(
+            request.uri(),
+            "https://metric-api.eu.newrelic.com/metric/v1"
+        );
+        assert_eq!(request.headers()["Api-Key"], "secret");
+        assert_eq!(request.headers()["Content-Type"], "application/json");
+    }
+
+    #[test]
+    fn merges_responses() {
+        let retry = || NewRelicResponse::Retry("429 Too Many Requests".into());
+        let rejected = || NewRelicResponse::Rejected("400 Bad Request".into());
+
+        assert_eq!(
+            NewRelicResponse::from_status(StatusCode::ACCEPTED),
+            NewRelicResponse::Accepted
+        );
+        assert_eq!(
+            NewRelicResponse::from_status(StatusCode::TOO_MANY_REQUESTS),
+            retry()
+        );
+        assert_eq!(
+            NewRelicResponse::from_status(StatusCode::BAD_REQUEST),
+            rejected()
+        );
+        assert_eq!(rejected().merge(retry()), retry());
+        assert_eq!(NewRelicResponse::Accepted.merge(rejected()), rejected());
+        assert_eq!(
+            NewRelicResponse::Accepted.merge(NewRelicResponse::Accepted),
+            NewRelicResponse::Accepted
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_logs_and_metrics() {
+        let addr = next_addr();
+        let config: NewRelicConfig = toml::from_str(
+            r#"
+            license_key = "secret"
+            compression = "gzip"
+            common_attributes.service = "api"
+            "#,
+        )
+        .unwrap();
+        let mut service = service(&config);
+        service.endpoints = Some((
+            format!("http://{}/log/v1", addr),
+            format!("http://{}/metric/v1", addr),
+        ));
+        let sink = config.build_sink(service, SinkContext::new_test()).unwrap();
+        let (rx, trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let events = vec![
+            Event::from("hello world"),
+            Event::Metric(Metric {
+                name: "requests".into(),
+                namespace: None,
+                timestamp: None,
+                tags: None,
+                kind: MetricKind::Incremental,
+                value: MetricValue::Counter { value: 1.0 },
+            }),
+        ];
+        sink.run(stream::iter(events)).await.unwrap();
+        drop(trigger);
+
+        let mut requests = rx
+            .map(|(parts, body)| {
+                let mut payload = String::new();
+                GzDecoder::new(&body[..])
+                    .read_to_string(&mut payload)
+                    .unwrap();
+                let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+                (parts.uri.path().to_owned(), payload)
+            })
+            .collect::<Vec<_>>()
+            .await;
+        requests.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].0, "/log/v1");
+        assert_eq!(requests[0].1[0]["common"]["attributes"]["service"], "api");
+        assert_eq!(requests[0].1[0]["logs"][0]["message"], "hello world");
+        assert_eq!(requests[1].0, "/metric/v1");
+        assert_eq!(requests[1].1[0]["metrics"][0]["name"], "requests");
+        assert_eq!(requests[1].1[0]["metrics"][0]["interval.ms"], 1000);
+    }
+}
@@ -8,7 +8,6 @@ use crate::{
         },
     },
 };
-use http::Uri;
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
@@ -117,8 +116,8 @@ impl NewRelicLogsConfig {
         }
 
         let uri = match self.region.as_ref().unwrap_or(&NewRelicLogsRegion::Us) {
-            NewRelicLogsRegion::Us => Uri::from_static("https://log-api.newrelic.com/log/v1"),
-            NewRelicLogsRegion::Eu => Uri::from_static("https://log-api.eu.newrelic.com/log/v1"),
+            NewRelicLogsRegion::Us => "https://log-api.newrelic.com/log/v1",
+            NewRelicLogsRegion::Eu => "https://log-api.eu.newrelic.com/log/v1",
         };
 
         let batch = self.batch.use_size_as_bytes()?;
@@ -145,8 +144,9 @@ impl NewRelicLogsConfig {
             method: Some(HttpMethod::Post),
             healthcheck_uri: None,
             auth: None,
+            oauth2: None,
             headers: Some(headers),
-            compression: self.compression,
+            compression: self.compression.gzip_only()?,
             encoding: self.encoding.clone().without_default(),
 
             batch,
@@ -300,10 +300,7 @@ mod tests {
         let mut nr_config = NewRelicLogsConfig::default();
         nr_config.license_key = Some("foo".to_owned());
         let mut http_config = nr_config.create_config().unwrap();
-        http_config.uri = format!("http://{}/fake_nr", in_addr)
-            .parse::<http::Uri>()
-            .unwrap()
-            .into();
+        http_config.uri = format!("http://{}/fake_nr", in_addr);
 
         let (sink, _healthcheck) = http_config.build(SinkContext::new_test()).await.unwrap();
         let (rx, trigger, server) = build_test_server(in_addr);
@@ -0,0 +1,162 @@
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    rusoto::RegionOrEndpoint,
+    sinks::elasticsearch::{
+        DataStreamConfig, ElasticSearchAuth, ElasticSearchConfig, ElasticSearchMode, Encoding,
+    },
+    sinks::util::{
+        encoding::EncodingConfigWithDefault, BatchConfig, Compression, TowerRequestConfig,
+    },
+    sinks::{Healthcheck, VectorSink},
+    tls::TlsOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Writes to OpenSearch through the `elasticsearch` sink, leaving out the
+/// mapping types OpenSearch no longer accepts.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OpenSearchConfig {
+    pub endpoint: String,
+    pub index: Option<String>,
+    pub id_key: Option<String>,
+    pub pipeline: Option<String>,
+    #[serde(default)]
+    pub mode: ElasticSearchMode,
+    pub data_stream: Option<DataStreamConfig>,
+    pub bulk_action: Option<String>,
+    #[serde(default)]
+    pub require_alias: bool,
+
+    #[serde(default)]
+    pub compression: Compression,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub encoding: EncodingConfigWithDefault<Encoding>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub auth: Option<OpenSearchAuth>,
+
+    pub headers: Option<HashMap<String, String>>,
+    pub query: Option<HashMap<String, String>>,
+
+    pub aws: Option<RegionOrEndpoint>,
+    pub tls: Option<TlsOptions>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub enum OpenSearchAuth {
+    Basic {
+        user: String,
+        password: String,
+    },
+    /// Signs requests with SigV4, for Amazon OpenSearch Service domains and
+    /// Serverless collections.
+    Aws {
+        assume_role: Option<String>,
+    },
+}
+
+impl From<OpenSearchAuth> for ElasticSearchAuth {
+    fn from(auth: OpenSearchAuth) -> Self {
+        match auth {
+            OpenSearchAuth::Basic { user, password } => ElasticSearchAuth::Basic { user, password },
+            OpenSearchAuth::Aws { assume_role } => ElasticSearchAuth::Aws { assume_role },
+        }
+    }
+}
+
+inventory::submit! {
+    SinkDescription::new::<OpenSearchConfig>("opensearch")
+}
+
+impl GenerateConfig for OpenSearchConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "https://search-my-domain.us-east-1.es.amazonaws.com"
+            auth = { strategy = "aws" }"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opensearch")]
+impl SinkConfig for OpenSearchConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        self.elasticsearch_config().build(cx).await
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "opensearch"
+    }
+}
+
+impl OpenSearchConfig {
+    fn elasticsearch_config(&self) -> ElasticSearchConfig {
+        ElasticSearchConfig {
+            endpoint: self.endpoint.clone(),
+            index: self.index.clone(),
+            id_key: self.id_key.clone(),
+            pipeline: self.pipeline.clone(),
+            mode: self.mode,
+            data_stream: self.data_stream.clone(),
+            bulk_action: self.bulk_action.clone(),
+            suppress_type_name: true,
+            require_alias: self.require_alias,
+            compression: self.compression,
+            encoding: self.encoding.clone(),
+            batch: self.batch,
+            request: self.request,
+            auth: self.auth.clone().map(Into::into),
+            headers: self.headers.clone(),
+            query: self.query.clone(),
+            aws: self.aws.clone(),
+            tls: self.tls.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sinks::{elasticsearch::ElasticSearchCommon, util::http::HttpSink},
+        Event,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OpenSearchConfig>();
+    }
+
+    #[test]
+    fn leaves_out_mapping_types() {
+        let config: OpenSearchConfig = toml::from_str(
+            r#"
+            endpoint = "https://abc123.us-east-1.aoss.amazonaws.com"
+            index = "logs-{{ service }}"
+            auth = { strategy = "aws" }
+            "#,
+        )
+        .unwrap();
+        let common = ElasticSearchCommon::parse_config(&config.elasticsearch_config()).unwrap();
+
+        let mut event = Event::from("hello there");
+        event.as_mut_log().insert("service", "api");
+        let encoded = common.encode_event(event).unwrap();
+        let expected = r#"{"index":{"_index":"logs-api"}}"#;
+        assert!(std::str::from_utf8(&encoded).unwrap().starts_with(expected));
+    }
+}
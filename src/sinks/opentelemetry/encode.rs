@@ -0,0 +1,593 @@
+use super::proto::{
+    collector::{logs::v1::ExportLogsServiceRequest, metrics::v1::ExportMetricsServiceRequest},
+    common::v1::{any_value, AnyValue, ArrayValue, KeyValue, KeyValueList},
+    logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+    metrics::v1::{
+        self as otlp, metric::Data, number_data_point, summary_data_point::ValueAtQuantile, Gauge,
+        Histogram, HistogramDataPoint, NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum,
+        Summary, SummaryDataPoint,
+    },
+    resource::v1::Resource,
+};
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Metric, MetricKind, MetricValue, StatisticKind},
+        Event, LogEvent, Value,
+    },
+    internal_events::OpenTelemetryResourceMissingKeys,
+    sinks::util::{
+        encode_namespace, statistic::DistributionStatistic, EncodedLength, PartitionInnerBuffer,
+    },
+    template::Template,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use indexmap::IndexMap;
+use prost::Message;
+
+// Values of `AggregationTemporality`.
+const DELTA: i32 = 1;
+const CUMULATIVE: i32 = 2;
+
+/// The kind of data a request carries, as each is exported separately.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(super) enum Signal {
+    Logs,
+    Metrics,
+}
+
+impl Signal {
+    pub(super) fn http_path(self) -> &'static str {
+        match self {
+            Signal::Logs => "/v1/logs",
+            Signal::Metrics => "/v1/metrics",
+        }
+    }
+
+    pub(super) fn grpc_path(self) -> &'static str {
+        match self {
+            Signal::Logs => "/opentelemetry.proto.collector.logs.v1.LogsService/Export",
+            Signal::Metrics => "/opentelemetry.proto.collector.metrics.v1.MetricsService/Export",
+        }
+    }
+}
+
+/// A single log record or metric, along with the resource it came from.
+#[derive(Clone, Debug)]
+pub(super) enum Record {
+    Log(Resource, LogRecord),
+    Metric(Resource, otlp::Metric),
+}
+
+impl EncodedLength for Record {
+    fn encoded_length(&self) -> usize {
+        match self {
+            Record::Log(resource, record) => resource.encoded_len() + record.encoded_len(),
+            Record::Metric(resource, metric) => resource.encoded_len() + metric.encoded_len(),
+        }
+    }
+}
+
+pub(super) struct Encoder {
+    pub(super) resource: IndexMap<String, Template>,
+    pub(super) default_namespace: Option<String>,
+    pub(super) buckets: Vec<f64>,
+    pub(super) quantiles: Vec<f64>,
+}
+
+impl Encoder {
+    pub(super) fn encode_event(
+        &self,
+        event: Event,
+    ) -> Option<PartitionInnerBuffer<Record, Signal>> {
+        match event {
+            Event::Log(log) => {
+                let resource = self.resource(&Event::Log(log.clone()));
+                let record = Record::Log(resource, encode_log(log));
+                Some(PartitionInnerBuffer::new(record, Signal::Logs))
+            }
+            Event::Metric(metric) => {
+                // Templates are rendered against the tags of metrics.
+                let mut tags = LogEvent::default();
+                for (key, value) in metric.tags.iter().flatten() {
+                    tags.insert(key, value.clone());
+                }
+                let resource = self.resource(&Event::Log(tags));
+                let metric = self.encode_metric(metric)?;
+                let record = Record::Metric(resource, metric);
+                Some(PartitionInnerBuffer::new(record, Signal::Metrics))
+            }
+        }
+    }
+
+    fn resource(&self, event: &Event) -> Resource {
+        let attributes = self
+            .resource
+            .iter()
+            .filter_map(|(key, template)| match template.render_string(event) {
+                Ok(value) => Some(string_attribute(key, value)),
+                Err(keys) => {
+                    emit!(OpenTelemetryResourceMissingKeys {
+                        attribute: key,
+                        keys: &keys,
+                    });
+                    None
+                }
+            })
+            .collect();
+        Resource {
+            attributes,
+            dropped_attributes_count: 0,
+        }
+    }
+
+    fn encode_metric(&self, metric: Metric) -> Option<otlp::Metric> {
+        let name = encode_namespace(
+            metric
+                .namespace
+                .as_deref()
+                .or_else(|| self.default_namespace.as_deref()),
+            '.',
+            &metric.name,
+        );
+        let time_unix_nano = unix_nanos(metric.timestamp.unwrap_or_else(Utc::now));
+        let attributes = metric
+            .tags
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| string_attribute(&key, value))
+            .collect::<Vec<_>>();
+        let aggregation_temporality = match metric.kind {
+            MetricKind::Incremental => DELTA,
+            MetricKind::Absolute => CUMULATIVE,
+        };
+        let number = |value: f64| NumberDataPoint {
+            attributes: attributes.clone(),
+            start_time_unix_nano: 0,
+            time_unix_nano,
+            value: Some(number_data_point::Value::AsDouble(value)),
+            flags: 0,
+        };
+
+        let data = match metric.value {
+            MetricValue::Counter { value } => Data::Sum(Sum {
+                data_points: vec![number(value)],
+                aggregation_temporality,
+                is_monotonic: true,
+            }),
+            MetricValue::Gauge { value } => Data::Gauge(Gauge {
+                data_points: vec![number(value)],
+            }),
+            MetricValue::Set { values } => Data::Gauge(Gauge {
+                data_points: vec![number(values.len() as f64)],
+            }),
+            MetricValue::Distribution {
+                values,
+                sample_rates,
+                statistic: StatisticKind::Histogram,
+            } => {
+                let mut counts = vec![0; self.buckets.len() + 1];
+                let mut sum = 0.0;
+                for (value, rate) in values.iter().zip(sample_rates.iter()) {
+                    let bucket = self
+                        .buckets
+                        .iter()
+                        .position(|bound| value <= bound)
+                        .unwrap_or(self.buckets.len());
+                    counts[bucket] += *rate as u64;
+                    sum += value * (*rate as f64);
+                }
+                Data::Histogram(Histogram {
+                    data_points: vec![HistogramDataPoint {
+                        attributes,
+                        start_time_unix_nano: 0,
+                        time_unix_nano,
+                        count: counts.iter().sum(),
+                        sum,
+                        bucket_counts: counts,
+                        explicit_bounds: self.buckets.clone(),
+                        flags: 0,
+                    }],
+                    aggregation_temporality,
+                })
+            }
+            MetricValue::Distribution {
+                values,
+                sample_rates,
+                statistic: StatisticKind::Summary,
+            } => {
+                let statistic =
+                    DistributionStatistic::new(&values, &sample_rates, &self.quantiles)?;
+                Data::Summary(Summary {
+                    data_points: vec![SummaryDataPoint {
+                        attributes,
+                        start_time_unix_nano: 0,
+                        time_unix_nano,
+                        count: statistic.count,
+                        sum: statistic.sum,
+                        quantile_values: statistic
+                            .quantiles
+                            .into_iter()
+                            .map(|(quantile, value)| ValueAtQuantile { quantile, value })
+                            .collect(),
+                        flags: 0,
+                    }],
+                })
+            }
+            MetricValue::AggregatedHistogram {
+                buckets,
+                counts,
+                count,
+                sum,
+            } => {
+                // The `+Inf` bucket is implicit in OTLP, and holds whatever
+                // isn't counted in the other buckets.
+                let (explicit_bounds, mut bucket_counts): (Vec<f64>, Vec<u64>) = buckets
+                    .into_iter()
+                    .zip(counts.into_iter())
+                    .filter(|(bound, _)| bound.is_finite())
+                    .map(|(bound, count)| (bound, count as u64))
+                    .unzip();
+                let counted: u64 = bucket_counts.iter().sum();
+                bucket_counts.push((count as u64).saturating_sub(counted));
+                Data::Histogram(Histogram {
+                    data_points: vec![HistogramDataPoint {
+                        attributes,
+                        start_time_unix_nano: 0,
+                        time_unix_nano,
+                        count: count as u64,
+                        sum,
+                        bucket_counts,
+                        explicit_bounds,
+                        flags: 0,
+                    }],
+                    aggregation_temporality,
+                })
+            }
+            MetricValue::AggregatedSummary {
+                quantiles,
+                values,
+                count,
+                sum,
+            } => Data::Summary(Summary {
+                data_points: vec![SummaryDataPoint {
+                    attributes,
+                    start_time_unix_nano: 0,
+                    time_unix_nano,
+                    count: count as u64,
+                    sum,
+                    quantile_values: quantiles
+                        .into_iter()
+                        .zip(values.into_iter())
+                        .map(|(quantile, value)| ValueAtQuantile { quantile, value })
+                        .collect(),
+                    flags: 0,
+                }],
+            }),
+        };
+
+        Some(otlp::Metric {
+            name,
+            description: String::new(),
+            unit: String::new(),
+            data: Some(data),
+        })
+    }
+}
+
+fn encode_log(mut log: LogEvent) -> LogRecord {
+    let time_unix_nano = match log.remove(log_schema().timestamp_key()) {
+        Some(Value::Timestamp(timestamp)) => unix_nanos(timestamp),
+        _ => 0,
+    };
+    let body = log
+        .remove(log_schema().message_key())
+        .map(|message| any_value(&message));
+    let attributes = log
+        .as_map()
+        .iter()
+        .map(|(key, value)| KeyValue {
+            key: key.clone(),
+            value: Some(any_value(value)),
+        })
+        .collect();
+
+    LogRecord {
+        time_unix_nano,
+        observed_time_unix_nano: unix_nanos(Utc::now()),
+        body,
+        attributes,
+        ..Default::default()
+    }
+}
+
+fn any_value(value: &Value) -> AnyValue {
+    let value = match value {
+        Value::Bytes(bytes) => match std::str::from_utf8(bytes) {
+            Ok(string) => any_value::Value::StringValue(string.into()),
+            Err(_) => any_value::Value::BytesValue(bytes.to_vec()),
+        },
+        Value::Integer(integer) => any_value::Value::IntValue(*integer),
+        Value::Float(float) => any_value::Value::DoubleValue(*float),
+        Value::Boolean(boolean) => any_value::Value::BoolValue(*boolean),
+        Value::Timestamp(timestamp) => {
+            any_value::Value::StringValue(timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+        }
+        Value::Map(map) => any_value::Value::KvlistValue(KeyValueList {
+            values: map
+                .iter()
+                .map(|(key, value)| KeyValue {
+                    key: key.clone(),
+                    value: Some(any_value(value)),
+                })
+                .collect(),
+        }),
+        Value::Array(values) => any_value::Value::ArrayValue(ArrayValue {
+            values: values.iter().map(any_value).collect(),
+        }),
+        Value::Null => return AnyValue { value: None },
+    };
+    AnyValue { value: Some(value) }
+}
+
+fn string_attribute(key: &str, value: String) -> KeyValue {
+    KeyValue {
+        key: key.into(),
+        value: Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value)),
+        }),
+    }
+}
+
+fn unix_nanos(timestamp: DateTime<Utc>) -> u64 {
+    timestamp.timestamp_nanos() as u64
+}
+
+/// Groups the records of a batch by resource into an export request.
+pub(super) fn encode_request(signal: Signal, records: Vec<Record>) -> Vec<u8> {
+    match signal {
+        Signal::Logs => {
+            let mut resource_logs: Vec<ResourceLogs> = Vec::new();
+            for record in records {
+                if let Record::Log(resource, record) = record {
+                    let resource = Some(resource);
+                    match resource_logs.iter_mut().find(|r| r.resource == resource) {
+                        Some(logs) => logs.scope_logs[0].log_records.push(record),
+                        None => resource_logs.push(ResourceLogs {
+                            resource,
+                            scope_logs: vec![ScopeLogs {
+                                log_records: vec![record],
+                                ..Default::default()
+                            }],
+                            schema_url: String::new(),
+                        }),
+                    }
+                }
+            }
+            ExportLogsServiceRequest { resource_logs }.encode_to_vec()
+        }
+        Signal::Metrics => {
+            let mut resource_metrics: Vec<ResourceMetrics> = Vec::new();
+            for record in records {
+                if let Record::Metric(resource, metric) = record {
+                    let resource = Some(resource);
+                    match resource_metrics.iter_mut().find(|r| r.resource == resource) {
+                        Some(metrics) => metrics.scope_metrics[0].metrics.push(metric),
+                        None => resource_metrics.push(ResourceMetrics {
+                            resource,
+                            scope_metrics: vec![ScopeMetrics {
+                                metrics: vec![metric],
+                                ..Default::default()
+                            }],
+                            schema_url: String::new(),
+                        }),
+                    }
+                }
+            }
+            ExportMetricsServiceRequest { resource_metrics }.encode_to_vec()
+        }
+    }
+}
+
+trait EncodeToVec {
+    fn encode_to_vec(&self) -> Vec<u8>;
+}
+
+impl<M: Message> EncodeToVec for M {
+    fn encode_to_vec(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.encoded_len());
+        self.encode(&mut out).expect("Out of memory");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::convert::TryFrom;
+
+    fn encoder(resource: &[(&str, &str)]) -> Encoder {
+        Encoder {
+            resource: resource
+                .iter()
+                .map(|(key, template)| (key.to_string(), Template::try_from(*template).unwrap()))
+                .collect(),
+            default_namespace: None,
+            buckets: vec![1.0, 5.0],
+            quantiles: vec![0.5],
+        }
+    }
+
+    fn timestamp() -> DateTime<Utc> {
+        Utc.timestamp(1_600_000_000, 0)
+    }
+
+    fn string_value(value: &AnyValue) -> &str {
+        match &value.value {
+            Some(any_value::Value::StringValue(string)) => string,
+            other => panic!("Not a string: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn encodes_logs() {
+        let encoder = encoder(&[("service.name", "{{ service }}"), ("env", "prod")]);
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("timestamp", timestamp());
+        event.as_mut_log().insert("service", "api");
+        event.as_mut_log().insert("status", 200);
+
+        let (record, signal) = encoder.encode_event(event).unwrap().into_parts();
+
+        assert_eq!(signal, Signal::Logs);
+        let (resource, record) = match record {
+            Record::Log(resource, record) => (resource, record),
+            _ => panic!("Not a log record"),
+        };
+        assert_eq!(resource.attributes[0].key, "service.name");
+        assert_eq!(
+            string_value(resource.attributes[0].value.as_ref().unwrap()),
+            "api"
+        );
+        assert_eq!(resource.attributes[1].key, "env");
+        assert_eq!(record.time_unix_nano, 1_600_000_000_000_000_000);
+        assert_eq!(string_value(record.body.as_ref().unwrap()), "hello");
+        let keys = record
+            .attributes
+            .iter()
+            .map(|attribute| attribute.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["service", "status"]);
+    }
+
+    #[test]
+    fn leaves_out_unrenderable_resource_attributes() {
+        let encoder = encoder(&[("service.name", "{{ service }}")]);
+
+        let (record, _) = encoder
+            .encode_event(Event::from("hello"))
+            .unwrap()
+            .into_parts();
+
+        match record {
+            Record::Log(resource, _) => assert!(resource.attributes.is_empty()),
+            _ => panic!("Not a log record"),
+        }
+    }
+
+    fn encode_metric(kind: MetricKind, value: MetricValue) -> otlp::Metric {
+        let metric = Metric {
+            name: "requests".into(),
+            namespace: Some("app".into()),
+            timestamp: Some(timestamp()),
+            tags: Some(
+                vec![("host".to_owned(), "a".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind,
+            value,
+        };
+        let (record, signal) = encoder(&[("host.name", "{{ host }}")])
+            .encode_event(metric.into())
+            .unwrap()
+            .into_parts();
+        assert_eq!(signal, Signal::Metrics);
+        match record {
+            Record::Metric(resource, metric) => {
+                assert_eq!(
+                    string_value(resource.attributes[0].value.as_ref().unwrap()),
+                    "a"
+                );
+                metric
+            }
+            _ => panic!("Not a metric"),
+        }
+    }
+
+    #[test]
+    fn encodes_counters_as_sums() {
+        let metric = encode_metric(MetricKind::Incremental, MetricValue::Counter { value: 2.0 });
+
+        assert_eq!(metric.name, "app.requests");
+        match metric.data {
+            Some(Data::Sum(sum)) => {
+                assert_eq!(sum.aggregation_temporality, DELTA);
+                assert!(sum.is_monotonic);
+                let point = &sum.data_points[0];
+                assert_eq!(point.time_unix_nano, 1_600_000_000_000_000_000);
+                assert_eq!(point.attributes[0].key, "host");
+                assert_eq!(point.value, Some(number_data_point::Value::AsDouble(2.0)));
+            }
+            other => panic!("Not a sum: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn encodes_distributions_as_histograms() {
+        let metric = encode_metric(
+            MetricKind::Incremental,
+            MetricValue::Distribution {
+                values: vec![0.5, 2.0, 7.0],
+                sample_rates: vec![2, 1, 1],
+                statistic: StatisticKind::Histogram,
+            },
+        );
+
+        match metric.data {
+            Some(Data::Histogram(histogram)) => {
+                let point = &histogram.data_points[0];
+                assert_eq!(point.explicit_bounds, vec![1.0, 5.0]);
+                assert_eq!(point.bucket_counts, vec![2, 1, 1]);
+                assert_eq!(point.count, 4);
+                assert_eq!(point.sum, 10.0);
+            }
+            other => panic!("Not a histogram: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn encodes_aggregated_histograms() {
+        let metric = encode_metric(
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![1.0, 2.0, f64::INFINITY],
+                counts: vec![1, 2, 3],
+                count: 6,
+                sum: 12.0,
+            },
+        );
+
+        match metric.data {
+            Some(Data::Histogram(histogram)) => {
+                assert_eq!(histogram.aggregation_temporality, CUMULATIVE);
+                let point = &histogram.data_points[0];
+                assert_eq!(point.explicit_bounds, vec![1.0, 2.0]);
+                assert_eq!(point.bucket_counts, vec![1, 2, 3]);
+                assert_eq!(point.count, 6);
+            }
+            other => panic!("Not a histogram: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn groups_requests_by_resource() {
+        let encoder = encoder(&[("service.name", "{{ service }}")]);
+        let records = vec!["api", "web", "api"]
+            .into_iter()
+            .map(|service| {
+                let mut event = Event::from("hello");
+                event.as_mut_log().insert("service", service);
+                encoder.encode_event(event).unwrap().into_parts().0
+            })
+            .collect();
+
+        let body = encode_request(Signal::Logs, records);
+        let request = ExportLogsServiceRequest::decode(&body[..]).unwrap();
+
+        assert_eq!(request.resource_logs.len(), 2);
+        assert_eq!(request.resource_logs[0].scope_logs[0].log_records.len(), 2);
+        assert_eq!(request.resource_logs[1].scope_logs[0].log_records.len(), 1);
+    }
+}
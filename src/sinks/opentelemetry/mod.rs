@@ -0,0 +1,444 @@
+mod encode;
+mod proto;
+
+use self::encode::{encode_request, Encoder, Record, Signal};
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    http::{Auth, HttpClient, HttpError},
+    sinks::{
+        util::{
+            buffer::GZIP_FAST, grpc, retries::RetryAction, sink::Response, BatchConfig,
+            BatchSettings, Compression, PartitionBuffer, PartitionInnerBuffer, RetryLogic,
+            TowerRequestConfig, VecBuffer,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+    tls::{TlsOptions, TlsSettings},
+};
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use http::{
+    header::{HeaderName, HeaderValue},
+    HeaderMap, Request, StatusCode, Uri,
+};
+use hyper::Body;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::Service;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid endpoint {:?}: {}", endpoint, source))]
+    InvalidEndpoint {
+        endpoint: String,
+        source: http::uri::InvalidUri,
+    },
+    #[snafu(display("Authorization header can not be used with defined auth options"))]
+    AuthorizationHeaderConflict,
+    #[snafu(display("Invalid header {:?}", name))]
+    InvalidHeader { name: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpenTelemetryConfig {
+    pub endpoint: String,
+    #[serde(default)]
+    pub protocol: Protocol,
+    #[serde(default)]
+    pub resource: IndexMap<String, Template>,
+    pub default_namespace: Option<String>,
+    #[serde(default = "default_histogram_buckets")]
+    pub buckets: Vec<f64>,
+    #[serde(default = "default_summary_quantiles")]
+    pub quantiles: Vec<f64>,
+    #[serde(default)]
+    pub compression: Compression,
+    pub auth: Option<Auth>,
+    pub headers: Option<IndexMap<String, String>>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsOptions>,
+}
+
+/// The OTLP transport used to export data.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Derivative)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    #[derivative(Default)]
+    Http,
+    Grpc,
+}
+
+fn default_histogram_buckets() -> Vec<f64> {
+    vec![
+        0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+    ]
+}
+
+fn default_summary_quantiles() -> Vec<f64> {
+    vec![0.5, 0.75, 0.9, 0.95, 0.99]
+}
+
+inventory::submit! {
+    SinkDescription::new::<OpenTelemetryConfig>("opentelemetry")
+}
+
+impl GenerateConfig for OpenTelemetryConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "http://localhost:4318"
+            protocol = "http"
+            resource."service.name" = "vector""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SinkConfig for OpenTelemetryConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let compression = self.compression.with_default_gzip_level(GZIP_FAST);
+        let headers = self.headers()?;
+        let endpoint = self.endpoint.trim_end_matches('/').to_owned();
+        endpoint.parse::<Uri>().context(InvalidEndpoint {
+            endpoint: &endpoint,
+        })?;
+
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = match self.protocol {
+            Protocol::Http => HttpClient::new(tls_settings)?,
+            Protocol::Grpc => HttpClient::new_http2(tls_settings)?,
+        };
+
+        let service = OpenTelemetryService {
+            client,
+            endpoint,
+            protocol: self.protocol,
+            compression,
+            auth: self.auth.clone(),
+            headers: Arc::new(headers),
+        };
+        let healthcheck = healthcheck(service.clone()).boxed();
+
+        let batch = BatchSettings::default()
+            .bytes(bytesize::mib(1u64))
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&TowerRequestConfig::default());
+
+        let encoder = Encoder {
+            resource: self.resource.clone(),
+            default_namespace: self.default_namespace.clone(),
+            buckets: self.buckets.clone(),
+            quantiles: self.quantiles.clone(),
+        };
+
+        let sink = request
+            .partition_sink(
+                OpenTelemetryRetryLogic,
+                service,
+                PartitionBuffer::new(VecBuffer::new(batch.size)),
+                batch.timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal opentelemetry sink error.", %error))
+            .with_flat_map(move |event| stream::iter(encoder.encode_event(event)).map(Ok));
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "opentelemetry"
+    }
+}
+
+impl OpenTelemetryConfig {
+    fn headers(&self) -> crate::Result<HeaderMap> {
+        let mut map = HeaderMap::new();
+        for (name, value) in self.headers.iter().flatten() {
+            if self.auth.is_some() && name.eq_ignore_ascii_case("Authorization") {
+                return Err(BuildError::AuthorizationHeaderConflict.into());
+            }
+            let header = HeaderName::from_bytes(name.as_bytes())
+                .ok()
+                .zip(HeaderValue::from_bytes(value.as_bytes()).ok())
+                .ok_or_else(|| BuildError::InvalidHeader { name: name.clone() })?;
+            map.insert(header.0, header.1);
+        }
+        Ok(map)
+    }
+}
+
+/// The outcome of an export, as reported by the collector.
+#[derive(Debug, Eq, PartialEq)]
+enum ExportResponse {
+    Accepted,
+    Retry(String),
+    Rejected(String),
+}
+
+impl Response for ExportResponse {
+    fn is_successful(&self) -> bool {
+        matches!(self, ExportResponse::Accepted)
+    }
+}
+
+impl ExportResponse {
+    fn from_http_status(status: StatusCode) -> Self {
+        match status {
+            status if status.is_success() => ExportResponse::Accepted,
+            // The statuses the OTLP/HTTP specification marks as retryable.
+            StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => ExportResponse::Retry(format!("{}", status)),
+            status => ExportResponse::Rejected(format!("{}", status)),
+        }
+    }
+
+    fn from_grpc_status(status: grpc::Status) -> Self {
+        if status.is_ok() {
+            ExportResponse::Accepted
+        } else if status.is_retriable() {
+            ExportResponse::Retry(status.to_string())
+        } else {
+            ExportResponse::Rejected(status.to_string())
+        }
+    }
+}
+
+#[derive(Clone)]
+struct OpenTelemetryService {
+    client: HttpClient,
+    endpoint: String,
+    protocol: Protocol,
+    compression: Compression,
+    auth: Option<Auth>,
+    headers: Arc<HeaderMap>,
+}
+
+impl OpenTelemetryService {
+    fn build_request(&self, signal: Signal, body: Vec<u8>) -> crate::Result<Request<Body>> {
+        let body = self.compression.compress(body);
+        let (path, content_type, body) = match self.protocol {
+            Protocol::Http => (signal.http_path(), "application/x-protobuf", body),
+            Protocol::Grpc => (
+                signal.grpc_path(),
+                "application/grpc",
+                grpc::frame(self.compression != Compression::None, body),
+            ),
+        };
+
+        let mut builder = Request::post(format!("{}{}", self.endpoint, path))
+            .header("Content-Type", content_type);
+        if let Some(ce) = self.compression.content_encoding() {
+            builder = match self.protocol {
+                Protocol::Http => builder.header("Content-Encoding", ce),
+                Protocol::Grpc => builder.header("grpc-encoding", ce),
+            };
+        }
+        if self.protocol == Protocol::Grpc {
+            builder = builder.header("te", "trailers");
+        }
+
+        let mut request = builder.body(Body::from(body))?;
+        for (name, value) in self.headers.iter() {
+            request.headers_mut().insert(name, value.clone());
+        }
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+        Ok(request)
+    }
+
+    fn send(
+        self,
+        signal: Signal,
+        records: Vec<Record>,
+    ) -> BoxFuture<'static, crate::Result<ExportResponse>> {
+        async move {
+            let request = self.build_request(signal, encode_request(signal, records))?;
+            let response = self.client.send(request).await?;
+
+            match self.protocol {
+                Protocol::Http => Ok(ExportResponse::from_http_status(response.status())),
+                Protocol::Grpc => {
+                    if !response.status().is_success() {
+                        return Ok(ExportResponse::from_http_status(response.status()));
+                    }
+                    let (_, status) = grpc::read_response(response).await?;
+                    Ok(ExportResponse::from_grpc_status(status))
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+impl Service<PartitionInnerBuffer<Vec<Record>, Signal>> for OpenTelemetryService {
+    type Response = ExportResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: PartitionInnerBuffer<Vec<Record>, Signal>) -> Self::Future {
+        let (records, signal) = request.into_parts();
+        self.clone().send(signal, records)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct OpenTelemetryRetryLogic;
+
+impl RetryLogic for OpenTelemetryRetryLogic {
+    type Error = HttpError;
+    type Response = ExportResponse;
+
+    fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+        true
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        match response {
+            ExportResponse::Accepted => RetryAction::Successful,
+            ExportResponse::Retry(reason) => RetryAction::Retry(reason.clone()),
+            ExportResponse::Rejected(reason) => RetryAction::DontRetry(reason.clone()),
+        }
+    }
+}
+
+/// The healthcheck exports an empty batch of logs, which collectors accept
+/// without side effects.
+async fn healthcheck(service: OpenTelemetryService) -> crate::Result<()> {
+    match service.send(Signal::Logs, Vec::new()).await? {
+        ExportResponse::Accepted => Ok(()),
+        ExportResponse::Retry(reason) | ExportResponse::Rejected(reason) => {
+            Err(format!("Export was rejected: {}", reason).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OpenTelemetryConfig>();
+    }
+
+    fn service(config: &str) -> OpenTelemetryService {
+        let config: OpenTelemetryConfig = toml::from_str(config).unwrap();
+        OpenTelemetryService {
+            client: HttpClient::new(None).unwrap(),
+            endpoint: config.endpoint.clone(),
+            protocol: config.protocol,
+            compression: config.compression,
+            auth: config.auth.clone(),
+            headers: Arc::new(config.headers().unwrap()),
+        }
+    }
+
+    #[test]
+    fn builds_http_requests() {
+        let service = service(
+            r#"
+            endpoint = "http://localhost:4318"
+            compression = "gzip"
+            headers.x-tenant = "team-a"
+            auth.strategy = "bearer"
+            auth.token = "secret"
+            "#,
+        );
+
+        let request = service.build_request(Signal::Metrics, vec![]).unwrap();
+
+        assert_eq!(request.uri(), "http://localhost:4318/v1/metrics");
+        assert_eq!(request.headers()["Content-Type"], "application/x-protobuf");
+        assert_eq!(request.headers()["Content-Encoding"], "gzip");
+        assert_eq!(request.headers()["x-tenant"], "team-a");
+        assert_eq!(request.headers()["Authorization"], "Bearer secret");
+    }
+
+    #[test]
+    fn builds_grpc_requests() {
+        let service = service(
+            r#"
+            endpoint = "http://localhost:4317"
+            protocol = "grpc"
+            "#,
+        );
+
+        let request = service.build_request(Signal::Logs, vec![]).unwrap();
+
+        assert_eq!(
+            request.uri(),
+            "http://localhost:4317/opentelemetry.proto.collector.logs.v1.LogsService/Export"
+        );
+        assert_eq!(request.headers()["Content-Type"], "application/grpc");
+        assert_eq!(request.headers()["te"], "trailers");
+    }
+
+    #[test]
+    fn rejects_conflicting_authorization_header() {
+        let config: OpenTelemetryConfig = toml::from_str(
+            r#"
+            endpoint = "http://localhost:4318"
+            headers.Authorization = "Bearer secret"
+            auth.strategy = "bearer"
+            auth.token = "secret"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.headers().is_err());
+    }
+
+    #[test]
+    fn maps_export_statuses() {
+        assert_eq!(
+            ExportResponse::from_http_status(StatusCode::OK),
+            ExportResponse::Accepted
+        );
+        assert!(matches!(
+            ExportResponse::from_http_status(StatusCode::SERVICE_UNAVAILABLE),
+            ExportResponse::Retry(_)
+        ));
+        assert!(matches!(
+            ExportResponse::from_http_status(StatusCode::BAD_REQUEST),
+            ExportResponse::Rejected(_)
+        ));
+
+        assert_eq!(
+            ExportResponse::from_grpc_status(grpc::Status {
+                code: 14,
+                message: None
+            }),
+            ExportResponse::Retry("grpc-status 14".into())
+        );
+        assert_eq!(
+            ExportResponse::from_grpc_status(grpc::Status {
+                code: 3,
+                message: Some("bad data".into())
+            }),
+            ExportResponse::Rejected("grpc-status 3: bad data".into())
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! The OTLP messages, laid out in the module tree of their protobuf packages
+//! so the generated code can refer to each other.
+
+pub mod common {
+    pub mod v1 {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/opentelemetry.proto.common.v1.rs"
+        ));
+    }
+}
+
+pub mod resource {
+    pub mod v1 {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/opentelemetry.proto.resource.v1.rs"
+        ));
+    }
+}
+
+pub mod logs {
+    pub mod v1 {
+        include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.logs.v1.rs"));
+    }
+}
+
+pub mod metrics {
+    pub mod v1 {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/opentelemetry.proto.metrics.v1.rs"
+        ));
+    }
+}
+
+pub mod collector {
+    pub mod logs {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.collector.logs.v1.rs"
+            ));
+        }
+    }
+
+    pub mod metrics {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.collector.metrics.v1.rs"
+            ));
+        }
+    }
+}
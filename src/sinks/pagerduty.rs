@@ -0,0 +1,492 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, Value},
+    http::HttpClient,
+    internal_events::{
+        PagerDutyInvalidAction, PagerDutyInvalidSeverity, PagerDutyMissingDedupKey,
+        PagerDutyTemplateMissingKeys,
+    },
+    sinks::{
+        util::{
+            http::{BatchedHttpSink, HttpRetryLogic, HttpSink},
+            retries::{RetryAction, RetryLogic},
+            BatchSettings, BoxedRawValue, Concurrency, JsonArrayBuffer, TowerRequestConfig,
+            UriSerde,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+};
+use bytes::Bytes;
+use chrono::{SecondsFormat, Utc};
+use futures::{future, FutureExt, SinkExt};
+use http::{Request, StatusCode, Uri};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// The largest event the Events API accepts.
+const MAX_EVENT_BYTES: u64 = 512 * 1024;
+
+lazy_static! {
+    static ref ENDPOINT: UriSerde =
+        Uri::from_static("https://events.pagerduty.com/v2/enqueue").into();
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        // Events are sent one at a time, so an alert is never resolved
+        // before it is triggered.
+        concurrency: Concurrency::Fixed(1),
+        // The Events API accepts 120 events a minute for each routing key.
+        rate_limit_duration_secs: Some(60),
+        rate_limit_num: Some(120),
+        ..Default::default()
+    };
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PagerDutyConfig {
+    pub routing_key: String,
+    pub endpoint: Option<UriSerde>,
+    /// The field holding the action of each event.
+    pub action_field: Option<String>,
+    #[serde(default)]
+    pub default_action: Action,
+    pub summary: Template,
+    pub severity: Option<Template>,
+    pub source: Option<Template>,
+    pub dedup_key: Option<Template>,
+    pub component: Option<Template>,
+    pub group: Option<Template>,
+    pub class: Option<Template>,
+    #[serde(default = "crate::serde::default_true")]
+    pub include_custom_details: bool,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    #[derivative(Default)]
+    Trigger,
+    Acknowledge,
+    Resolve,
+}
+
+impl Action {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "trigger" => Some(Action::Trigger),
+            "acknowledge" => Some(Action::Acknowledge),
+            "resolve" => Some(Action::Resolve),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Action::Trigger => "trigger",
+            Action::Acknowledge => "acknowledge",
+            Action::Resolve => "resolve",
+        }
+    }
+}
+
+const SEVERITIES: [&str; 4] = ["critical", "error", "warning", "info"];
+const DEFAULT_SEVERITY: &str = "error";
+
+inventory::submit! {
+    SinkDescription::new::<PagerDutyConfig>("pagerduty")
+}
+
+impl GenerateConfig for PagerDutyConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"routing_key = "${PAGERDUTY_ROUTING_KEY}"
+            summary = "{{ message }}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "pagerduty")]
+impl SinkConfig for PagerDutyConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        // The Events API takes a single event per request.
+        let batch = BatchSettings::default()
+            .bytes(MAX_EVENT_BYTES)
+            .events(1)
+            .timeout(1);
+        let client = HttpClient::new(None)?;
+
+        let sink = BatchedHttpSink::with_retry_logic(
+            PagerDutySink {
+                config: self.clone(),
+                hostname: crate::get_hostname()?,
+            },
+            JsonArrayBuffer::new(batch.size),
+            PagerDutyRetryLogic,
+            request,
+            batch.timeout,
+            client,
+            cx.acker(),
+        )
+        .with_dead_letter(cx.dead_letter())
+        .sink_map_err(|error| error!(message = "Fatal pagerduty sink error.", %error));
+
+        // The Events API has no way to check a routing key without sending
+        // an event.
+        let healthcheck = future::ok(()).boxed();
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "pagerduty"
+    }
+
+    fn supports_dead_letter(&self) -> bool {
+        true
+    }
+}
+
+struct PagerDutySink {
+    config: PagerDutyConfig,
+    hostname: String,
+}
+
+impl PagerDutySink {
+    fn render(&self, template: &Template, field: &str, event: &Event) -> Option<String> {
+        template
+            .render_string(event)
+            .map_err(|keys| emit!(PagerDutyTemplateMissingKeys { field, keys: &keys }))
+            .ok()
+    }
+
+    fn render_optional(
+        &self,
+        template: &Option<Template>,
+        field: &str,
+        event: &Event,
+    ) -> Option<Option<String>> {
+        match template {
+            Some(template) => self.render(template, field, event).map(Some),
+            None => Some(None),
+        }
+    }
+
+    fn action(&self, event: &Event) -> Option<Action> {
+        let value = match &self.config.action_field {
+            Some(field) => event.as_log().get(field),
+            None => None,
+        };
+        match value {
+            Some(value) => {
+                let value = value.to_string_lossy();
+                let action = Action::parse(&value);
+                if action.is_none() {
+                    emit!(PagerDutyInvalidAction { value: &value });
+                }
+                action
+            }
+            None => Some(self.config.default_action),
+        }
+    }
+
+    fn severity(&self, event: &Event) -> Option<String> {
+        let severity = match &self.config.severity {
+            Some(template) => self
+                .render(template, "severity", event)?
+                .to_ascii_lowercase(),
+            None => return Some(DEFAULT_SEVERITY.into()),
+        };
+        if SEVERITIES.contains(&severity.as_str()) {
+            Some(severity)
+        } else {
+            emit!(PagerDutyInvalidSeverity { value: &severity });
+            Some(DEFAULT_SEVERITY.into())
+        }
+    }
+
+    fn source(&self, event: &Event) -> Option<String> {
+        match &self.config.source {
+            Some(template) => self.render(template, "source", event),
+            None => Some(
+                event
+                    .as_log()
+                    .get(log_schema().host_key())
+                    .map(|host| host.to_string_lossy())
+                    .unwrap_or_else(|| self.hostname.clone()),
+            ),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for PagerDutySink {
+    type Input = serde_json::Value;
+    type Output = Vec<BoxedRawValue>;
+
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        let action = self.action(&event)?;
+        let dedup_key = self.render_optional(&self.config.dedup_key, "dedup_key", &event)?;
+
+        let mut body = json!({
+            "routing_key": self.config.routing_key,
+            "event_action": action.as_str(),
+        });
+        if action != Action::Trigger {
+            // Acknowledging or resolving needs the key of the alert.
+            match dedup_key {
+                Some(dedup_key) => body["dedup_key"] = json!(dedup_key),
+                None => {
+                    emit!(PagerDutyMissingDedupKey {
+                        action: action.as_str()
+                    });
+                    return None;
+                }
+            }
+            return Some(body);
+        }
+        if let Some(dedup_key) = dedup_key {
+            body["dedup_key"] = json!(dedup_key);
+        }
+
+        let timestamp = match event.as_log().get(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(timestamp)) => *timestamp,
+            _ => Utc::now(),
+        };
+        let mut payload = json!({
+            "summary": self.render(&self.config.summary, "summary", &event)?,
+            "severity": self.severity(&event)?,
+            "source": self.source(&event)?,
+            "timestamp": timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+        });
+        for (field, template) in &[
+            ("component", &self.config.component),
+            ("group", &self.config.group),
+            ("class", &self.config.class),
+        ] {
+            if let Some(value) = self.render_optional(template, field, &event)? {
+                payload[*field] = json!(value);
+            }
+        }
+        if self.config.include_custom_details {
+            payload["custom_details"] = json!(event.as_log().all_fields());
+        }
+        body["payload"] = payload;
+
+        Some(body)
+    }
+
+    async fn build_request(&self, events: Self::Output) -> crate::Result<Request<Vec<u8>>> {
+        let uri: Uri = self
+            .config
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| ENDPOINT.clone())
+            .into();
+        // Batches hold a single event, which is sent as is.
+        let body = match events.first() {
+            Some(event) => event.get().as_bytes().to_vec(),
+            None => b"{}".to_vec(),
+        };
+
+        Request::post(uri)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .map_err(Into::into)
+    }
+}
+
+/// Retries like other HTTP sinks, but logs why the API rejected an event, as
+/// it only ever rejects events that are invalid.
+#[derive(Clone, Copy, Debug)]
+struct PagerDutyRetryLogic;
+
+impl RetryLogic for PagerDutyRetryLogic {
+    type Error = hyper::Error;
+    type Response = http::Response<Bytes>;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        HttpRetryLogic.is_retriable_error(error)
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        match response.status() {
+            StatusCode::BAD_REQUEST => RetryAction::DontRetry(format!(
+                "invalid event: {}",
+                String::from_utf8_lossy(response.body())
+            )),
+            _ => HttpRetryLogic.should_retry_response(response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::next_addr;
+    use futures::{stream, StreamExt};
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Response, Server,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PagerDutyConfig>();
+    }
+
+    fn sink(config: &str) -> PagerDutySink {
+        PagerDutySink {
+            config: toml::from_str(config).unwrap(),
+            hostname: "vector-host".into(),
+        }
+    }
+
+    #[test]
+    fn encodes_trigger() {
+        let sink = sink(
+            r#"
+            routing_key = "key"
+            summary = "{{ message }}"
+            severity = "{{ level }}"
+            dedup_key = "{{ alert }}"
+            component = "db"
+            include_custom_details = false
+            "#,
+        );
+        let mut event = Event::from("Disk full");
+        event.as_mut_log().insert("level", "Warning");
+        event.as_mut_log().insert("alert", "disk-1");
+        event.as_mut_log().insert(
+            log_schema().timestamp_key(),
+            chrono::DateTime::parse_from_rfc3339("2020-12-01T10:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        );
+
+        assert_eq!(
+            sink.encode_event(event),
+            Some(json!({
+                "routing_key": "key",
+                "event_action": "trigger",
+                "dedup_key": "disk-1",
+                "payload": {
+                    "summary": "Disk full",
+                    "severity": "warning",
+                    "source": "vector-host",
+                    "timestamp": "2020-12-01T10:00:00.000Z",
+                    "component": "db",
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn maps_actions_from_field() {
+        let sink = sink(
+            r#"
+            routing_key = "key"
+            action_field = "state"
+            summary = "{{ message }}"
+            dedup_key = "{{ alert }}"
+            "#,
+        );
+
+        let mut event = Event::from("Disk ok");
+        event.as_mut_log().insert("state", "RESOLVE");
+        event.as_mut_log().insert("alert", "disk-1");
+        assert_eq!(
+            sink.encode_event(event),
+            Some(json!({
+                "routing_key": "key",
+                "event_action": "resolve",
+                "dedup_key": "disk-1",
+            }))
+        );
+
+        // Resolving needs a dedup key.
+        let mut event = Event::from("Disk ok");
+        event.as_mut_log().insert("state", "resolve");
+        assert_eq!(sink.encode_event(event), None);
+
+        let mut event = Event::from("Disk ok");
+        event.as_mut_log().insert("state", "escalate");
+        event.as_mut_log().insert("alert", "disk-1");
+        assert_eq!(sink.encode_event(event), None);
+    }
+
+    #[test]
+    fn defaults_invalid_severity() {
+        let sink = sink(
+            r#"
+            routing_key = "key"
+            summary = "{{ message }}"
+            severity = "{{ level }}"
+            "#,
+        );
+        let mut event = Event::from("Disk full");
+        event.as_mut_log().insert("level", "fatal");
+        event.as_mut_log().insert("host", "db-1");
+
+        let body = sink.encode_event(event).unwrap();
+        assert_eq!(body["payload"]["severity"], "error");
+        assert_eq!(body["payload"]["source"], "db-1");
+        assert_eq!(body["payload"]["custom_details"]["level"], "fatal");
+    }
+
+    #[tokio::test]
+    async fn sends_one_event_per_request() {
+        let addr = next_addr();
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let received = Arc::clone(&bodies);
+        let server = Server::bind(&addr).serve(make_service_fn(move |_| {
+            let received = Arc::clone(&received);
+            async move {
+                Ok::<_, hyper::Error>(service_fn(move |request: Request<Body>| {
+                    let received = Arc::clone(&received);
+                    async move {
+                        let body = hyper::body::to_bytes(request.into_body()).await?;
+                        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                        received.lock().unwrap().push(body);
+                        let mut response = Response::new(Body::from(r#"{"status":"success"}"#));
+                        *response.status_mut() = StatusCode::ACCEPTED;
+                        Ok::<_, hyper::Error>(response)
+                    }
+                }))
+            }
+        }));
+        tokio::spawn(server.map(|_| ()));
+
+        let config: PagerDutyConfig = toml::from_str(&format!(
+            r#"
+            routing_key = "key"
+            endpoint = "http://{}/v2/enqueue"
+            summary = "{{{{ message }}}}"
+            "#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let events = stream::iter(vec!["one", "two", "three"]).map(Event::from);
+        sink.run(events).await.unwrap();
+
+        let bodies = bodies.lock().unwrap();
+        let summaries = bodies
+            .iter()
+            .map(|body| body["payload"]["summary"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(summaries, vec!["one", "two", "three"]);
+    }
+}
@@ -73,6 +73,10 @@ pub(super) trait MetricCollector {
                     }
 
                     for (b, c) in buckets.iter().zip(counts.iter()) {
+                        // the `+Inf` bucket is always written from the total count
+                        if b.is_infinite() {
+                            continue;
+                        }
                         self.emit(
                             timestamp,
                             &name,
@@ -139,6 +143,9 @@ pub(super) trait MetricCollector {
                         // prometheus uses cumulative histogram
                         // https://prometheus.io/docs/concepts/metric_types/#histogram
                         value += *c as f64;
+                        if b.is_infinite() {
+                            continue;
+                        }
                         self.emit(
                             timestamp,
                             &name,
@@ -297,7 +304,11 @@ impl TimeSeries {
     pub(super) fn finish(self) -> Vec<proto::TimeSeries> {
         self.buffer
             .into_iter()
-            .map(|(labels, samples)| proto::TimeSeries { labels, samples })
+            .map(|(labels, mut samples)| {
+                // Receivers reject samples that go back in time within a series.
+                samples.sort_by_key(|sample| sample.timestamp);
+                proto::TimeSeries { labels, samples }
+            })
             .collect()
     }
 }
@@ -507,6 +518,34 @@ vector_requests_count 6
         );
     }
 
+    #[test]
+    fn test_encode_histogram_with_infinite_bucket() {
+        let metric = Metric {
+            name: "requests".to_owned(),
+            namespace: None,
+            timestamp: None,
+            tags: None,
+            kind: MetricKind::Absolute,
+            value: MetricValue::AggregatedHistogram {
+                buckets: vec![1.0, f64::INFINITY],
+                counts: vec![1, 2],
+                count: 3,
+                sum: 12.5,
+            },
+        };
+
+        let frame = encode_metric_datum(None, &[], &[], false, &metric);
+
+        assert_eq!(
+            frame,
+            r#"requests_bucket{le="1"} 1
+requests_bucket{le="+Inf"} 3
+requests_sum 12.5
+requests_count 3
+"#
+        );
+    }
+
     #[test]
     fn test_encode_summary() {
         let metric = Metric {
@@ -1,7 +1,8 @@
 use crate::{
     buffers::Acker,
     config::{DataType, GenerateConfig, Resource, SinkConfig, SinkContext, SinkDescription},
-    event::metric::MetricKind,
+    event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
+    http::Auth,
     internal_events::PrometheusServerRequestComplete,
     sinks::{
         util::{statistic::validate_quantiles, MetricEntry, StreamSink},
@@ -13,11 +14,11 @@ use async_trait::async_trait;
 use chrono::Utc;
 use futures::{future, stream::BoxStream, FutureExt, StreamExt, TryFutureExt};
 use hyper::{
-    header::HeaderValue,
+    header::{self, HeaderMap, HeaderValue},
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
 };
-use indexmap::IndexSet;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::{
@@ -50,6 +51,8 @@ pub struct PrometheusExporterConfig {
     pub quantiles: Vec<f64>,
     #[serde(default = "default_flush_period_secs")]
     pub flush_period_secs: u64,
+    pub expire_metrics_secs: Option<u64>,
+    pub auth: Option<Auth>,
 }
 
 impl std::default::Default for PrometheusExporterConfig {
@@ -60,6 +63,8 @@ impl std::default::Default for PrometheusExporterConfig {
             buckets: super::default_histogram_buckets(),
             quantiles: super::default_summary_quantiles(),
             flush_period_secs: default_flush_period_secs(),
+            expire_metrics_secs: None,
+            auth: None,
         }
     }
 }
@@ -147,24 +152,92 @@ impl SinkConfig for PrometheusCompatConfig {
     }
 }
 
+/// Every series the exporter knows about, with the unix timestamp of its
+/// last update.
+type Metrics = IndexMap<MetricEntry, i64>;
+
 struct PrometheusExporter {
     server_shutdown_trigger: Option<Trigger>,
     config: PrometheusExporterConfig,
-    metrics: Arc<RwLock<IndexSet<MetricEntry>>>,
+    metrics: Arc<RwLock<Metrics>>,
     last_flush_timestamp: Arc<RwLock<i64>>,
     acker: Acker,
 }
 
+fn authorization_header(auth: &Auth) -> Option<HeaderValue> {
+    let mut headers = HeaderMap::new();
+    auth.apply_headers_map(&mut headers);
+    headers.remove(header::AUTHORIZATION)
+}
+
+fn authenticate_challenge(auth: &Auth) -> HeaderValue {
+    match auth {
+        Auth::Basic { .. } => HeaderValue::from_static(r#"Basic realm="vector""#),
+        Auth::Bearer { .. } => HeaderValue::from_static("Bearer"),
+    }
+}
+
+/// Drops every series that hasn't been updated in `expire_secs`.
+fn expire_metrics(metrics: &mut Metrics, now: i64, expire_secs: u64) {
+    metrics.retain(|_, updated| now - *updated < expire_secs as i64);
+}
+
+/// Counts the samples of a histogram distribution into the configured
+/// buckets, so that the exporter keeps a fixed amount of state per series
+/// instead of every sample it has ever seen.
+fn distribution_to_histogram(metric: Metric, buckets: &[f64]) -> Metric {
+    match metric.value {
+        MetricValue::Distribution {
+            values,
+            sample_rates,
+            statistic: StatisticKind::Histogram,
+        } => {
+            let mut counts = vec![0; buckets.len()];
+            let mut count = 0;
+            let mut sum = 0.0;
+            for (value, rate) in values.iter().zip(sample_rates.iter()) {
+                // values above the last bucket are only counted in `+Inf`
+                if let Some(i) = buckets.iter().position(|bucket| value <= bucket) {
+                    counts[i] += rate;
+                }
+                count += rate;
+                sum += value * (*rate as f64);
+            }
+            Metric {
+                value: MetricValue::AggregatedHistogram {
+                    buckets: buckets.to_vec(),
+                    counts,
+                    count,
+                    sum,
+                },
+                ..metric
+            }
+        }
+        value => Metric { value, ..metric },
+    }
+}
+
 fn handle(
     req: Request<Body>,
     default_namespace: Option<&str>,
     buckets: &[f64],
     quantiles: &[f64],
     expired: bool,
-    metrics: &IndexSet<MetricEntry>,
+    authorization: Option<&(HeaderValue, HeaderValue)>,
+    metrics: &Metrics,
 ) -> Response<Body> {
     let mut response = Response::new(Body::empty());
 
+    if let Some((expected, challenge)) = authorization {
+        if req.headers().get(header::AUTHORIZATION) != Some(expected) {
+            *response.status_mut() = StatusCode::UNAUTHORIZED;
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, challenge.clone());
+            return response;
+        }
+    }
+
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/metrics") => {
             let mut s = collector::StringCollector::new();
@@ -172,7 +245,7 @@ fn handle(
             // output headers only once
             let mut processed_headers = HashSet::new();
 
-            for metric in metrics {
+            for metric in metrics.keys() {
                 let name = &metric.0.name;
                 if !processed_headers.contains(&name) {
                     s.encode_header(default_namespace, &metric.0);
@@ -202,7 +275,7 @@ impl PrometheusExporter {
         Self {
             server_shutdown_trigger: None,
             config,
-            metrics: Arc::new(RwLock::new(IndexSet::new())),
+            metrics: Arc::new(RwLock::new(IndexMap::new())),
             last_flush_timestamp: Arc::new(RwLock::new(Utc::now().timestamp())),
             acker,
         }
@@ -219,6 +292,10 @@ impl PrometheusExporter {
         let quantiles = self.config.quantiles.clone();
         let last_flush_timestamp = Arc::clone(&self.last_flush_timestamp);
         let flush_period_secs = self.config.flush_period_secs;
+        let expire_metrics_secs = self.config.expire_metrics_secs;
+        let authorization = self.config.auth.as_ref().and_then(|auth| {
+            authorization_header(auth).map(|header| (header, authenticate_challenge(auth)))
+        });
 
         let new_service = make_service_fn(move |_| {
             let metrics = Arc::clone(&metrics);
@@ -227,10 +304,14 @@ impl PrometheusExporter {
             let quantiles = quantiles.clone();
             let last_flush_timestamp = Arc::clone(&last_flush_timestamp);
             let flush_period_secs = flush_period_secs;
+            let authorization = authorization.clone();
 
             async move {
                 Ok::<_, Infallible>(service_fn(move |req| {
-                    let metrics = metrics.read().unwrap();
+                    let mut metrics = metrics.write().unwrap();
+                    if let Some(expire_secs) = expire_metrics_secs {
+                        expire_metrics(&mut metrics, Utc::now().timestamp(), expire_secs);
+                    }
                     let last_flush_timestamp = last_flush_timestamp.read().unwrap();
                     let interval = (Utc::now().timestamp() - *last_flush_timestamp) as u64;
                     let expired = interval > flush_period_secs;
@@ -247,6 +328,7 @@ impl PrometheusExporter {
                             &buckets,
                             &quantiles,
                             expired,
+                            authorization.as_ref(),
                             &metrics,
                         )
                     });
@@ -277,17 +359,21 @@ impl StreamSink for PrometheusExporter {
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
         self.start_server_if_needed();
         while let Some(event) = input.next().await {
-            let item = event.into_metric();
+            let item = distribution_to_histogram(event.into_metric(), &self.config.buckets);
+            let now = Utc::now().timestamp();
             let mut metrics = self.metrics.write().unwrap();
 
+            if let Some(expire_secs) = self.config.expire_metrics_secs {
+                expire_metrics(&mut metrics, now, expire_secs);
+            }
+
             match item.kind {
                 MetricKind::Incremental => {
                     let new = MetricEntry(item.to_absolute());
-                    if let Some(MetricEntry(mut existing)) = metrics.take(&new) {
+                    if let Some((MetricEntry(mut existing), _)) = metrics.swap_remove_entry(&new) {
                         if item.value.is_set() {
                             // sets need to be expired from time to time
                             // because otherwise they could grow infinitelly
-                            let now = Utc::now().timestamp();
                             let interval = now - *self.last_flush_timestamp.read().unwrap();
                             if interval > self.config.flush_period_secs as i64 {
                                 *self.last_flush_timestamp.write().unwrap() = now;
@@ -295,14 +381,16 @@ impl StreamSink for PrometheusExporter {
                             }
                         }
                         existing.add(&item);
-                        metrics.insert(MetricEntry(existing));
+                        metrics.insert(MetricEntry(existing), now);
                     } else {
-                        metrics.insert(new);
+                        metrics.insert(new, now);
                     };
                 }
                 MetricKind::Absolute => {
+                    // inserting would keep the old value in the key
                     let new = MetricEntry(item);
-                    metrics.replace(new);
+                    metrics.swap_remove(&new);
+                    metrics.insert(new, now);
                 }
             };
 
@@ -320,6 +408,105 @@ mod tests {
     fn generate_config() {
         crate::test_util::test_generate_config::<PrometheusExporterConfig>();
     }
+
+    fn gauge(name: &str, value: f64) -> Metric {
+        Metric {
+            name: name.into(),
+            namespace: None,
+            timestamp: None,
+            tags: None,
+            kind: MetricKind::Absolute,
+            value: MetricValue::Gauge { value },
+        }
+    }
+
+    fn scrape(
+        authorization: Option<&str>,
+        auth: Option<&Auth>,
+        metrics: &Metrics,
+    ) -> Response<Body> {
+        let mut request = Request::get("/metrics");
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        let authorization = auth.and_then(|auth| {
+            authorization_header(auth).map(|header| (header, authenticate_challenge(auth)))
+        });
+        handle(
+            request.body(Body::empty()).unwrap(),
+            None,
+            &[],
+            &[],
+            false,
+            authorization.as_ref(),
+            metrics,
+        )
+    }
+
+    #[test]
+    fn requires_authorization() {
+        let auth = Auth::Basic {
+            user: "user".into(),
+            password: "password".into(),
+        };
+        let mut metrics = Metrics::new();
+        metrics.insert(MetricEntry(gauge("load", 1.0)), 0);
+
+        let response = scrape(None, Some(&auth), &metrics);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()[header::WWW_AUTHENTICATE],
+            r#"Basic realm="vector""#
+        );
+
+        let response = scrape(Some("Basic dXNlcjp3cm9uZw=="), Some(&auth), &metrics);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = scrape(Some("Basic dXNlcjpwYXNzd29yZA=="), Some(&auth), &metrics);
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = scrape(None, None, &metrics);
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn expires_stale_metrics() {
+        let mut metrics = Metrics::new();
+        metrics.insert(MetricEntry(gauge("old", 1.0)), 100);
+        metrics.insert(MetricEntry(gauge("new", 1.0)), 150);
+
+        expire_metrics(&mut metrics, 160, 30);
+
+        let names = metrics
+            .keys()
+            .map(|entry| entry.0.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["new"]);
+    }
+
+    #[test]
+    fn counts_distributions_into_buckets() {
+        let metric = Metric {
+            value: MetricValue::Distribution {
+                values: vec![0.5, 1.0, 2.0, 7.0],
+                sample_rates: vec![1, 2, 1, 3],
+                statistic: StatisticKind::Histogram,
+            },
+            ..gauge("latency", 0.0)
+        };
+
+        let metric = distribution_to_histogram(metric, &[1.0, 5.0]);
+
+        assert_eq!(
+            metric.value,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![1.0, 5.0],
+                counts: vec![3, 1],
+                count: 7,
+                sum: 25.5,
+            }
+        );
+    }
 }
 
 #[cfg(all(test, feature = "prometheus-integration-tests"))]
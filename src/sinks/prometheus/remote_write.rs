@@ -2,28 +2,54 @@ use super::collector::{self, MetricCollector as _};
 use crate::{
     config::{self, SinkConfig, SinkDescription},
     event::Metric,
-    http::HttpClient,
-    prometheus::proto,
+    http::{Auth, HttpClient, HttpError},
+    prometheus::{proto, STALE_NAN},
+    rusoto::{self, region_from_endpoint, RegionOrEndpoint},
     sinks::{
         self,
         util::{
-            http::HttpRetryLogic, BatchConfig, BatchSettings, MetricBuffer, TowerRequestConfig,
+            retries::{RetryAction, RetryLogic},
+            BatchConfig, BatchSettings, MetricBuffer, TowerRequestConfig,
         },
     },
     tls::{TlsOptions, TlsSettings},
 };
 use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
 use futures::{future::BoxFuture, FutureExt, SinkExt};
-use http::Uri;
+use http::{
+    header::{HeaderName, HeaderValue},
+    StatusCode, Uri,
+};
 use prost::Message;
+use rusoto_core::Region;
+use rusoto_credential::{CredentialsError, ProvideAwsCredentials};
+use rusoto_signature::{SignedRequest, SignedRequestPayload};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::task;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+    task,
+};
+
+/// The service name Amazon Managed Service for Prometheus requests are
+/// signed for.
+const AWS_SERVICE: &str = "aps";
+
+const HEADERS: [(&str, &str); 3] = [
+    ("X-Prometheus-Remote-Write-Version", "0.1.0"),
+    ("Content-Encoding", "snappy"),
+    ("Content-Type", "application/x-protobuf"),
+];
 
 #[derive(Debug, Snafu)]
 enum Errors {
     #[snafu(display(r#"Prometheus remote_write sink cannot accept "set" metrics"#))]
     SetMetricInvalid,
+    #[snafu(display("Could not generate AWS credentials: {:?}", source))]
+    AwsCredentialsGenerateFailed { source: CredentialsError },
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -37,14 +63,37 @@ pub(crate) struct RemoteWriteConfig {
     #[serde(default = "super::default_summary_quantiles")]
     pub quantiles: Vec<f64>,
 
+    /// Series that receive no samples for this long are ended with a
+    /// staleness marker.
+    pub stale_after_secs: Option<u64>,
+
     #[serde(default)]
     pub batch: BatchConfig,
     #[serde(default)]
     pub request: TowerRequestConfig,
 
+    pub auth: Option<RemoteWriteAuth>,
+    pub aws: Option<RegionOrEndpoint>,
+
     pub tls: Option<TlsOptions>,
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub(crate) enum RemoteWriteAuth {
+    Basic {
+        user: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    /// Signs requests with SigV4, for Amazon Managed Service for Prometheus.
+    Aws {
+        assume_role: Option<String>,
+    },
+}
+
 inventory::submit! {
     SinkDescription::new::<RemoteWriteConfig>("prometheus_remote_write")
 }
@@ -72,18 +121,59 @@ impl SinkConfig for RemoteWriteConfig {
         let buckets = self.buckets.clone();
         let quantiles = self.quantiles.clone();
 
+        let (auth, signer) = match &self.auth {
+            Some(RemoteWriteAuth::Basic { user, password }) => (
+                Some(Auth::Basic {
+                    user: user.clone(),
+                    password: password.clone(),
+                }),
+                None,
+            ),
+            Some(RemoteWriteAuth::Bearer { token }) => (
+                Some(Auth::Bearer {
+                    token: token.clone(),
+                }),
+                None,
+            ),
+            Some(RemoteWriteAuth::Aws { assume_role }) => {
+                let region = match &self.aws {
+                    Some(region) => Region::try_from(region)?,
+                    None => region_from_endpoint(&self.endpoint)?,
+                };
+                let credentials =
+                    rusoto::AwsCredentialsProvider::new(&region, assume_role.clone())?;
+                (
+                    None,
+                    Some(AwsSigner {
+                        region,
+                        credentials: Arc::new(credentials),
+                    }),
+                )
+            }
+            None => (None, None),
+        };
+
         let client = HttpClient::new(tls_settings)?;
-        let healthcheck = healthcheck(endpoint.clone(), client.clone()).boxed();
+        let healthcheck = healthcheck(
+            endpoint.clone(),
+            client.clone(),
+            auth.clone(),
+            signer.clone(),
+        )
+        .boxed();
         let service = RemoteWriteService {
             endpoint,
             default_namespace: self.default_namespace.clone(),
             client,
             buckets,
             quantiles,
+            auth,
+            signer,
+            series: self.stale_after_secs.map(SeriesTracker::new),
         };
         let sink = request
             .batch_sink(
-                HttpRetryLogic,
+                RemoteWriteRetryLogic,
                 service,
                 MetricBuffer::new(batch.size),
                 batch.timeout,
@@ -103,19 +193,167 @@ impl SinkConfig for RemoteWriteConfig {
     }
 }
 
-async fn healthcheck(endpoint: Uri, client: HttpClient) -> crate::Result<()> {
-    let request = http::Request::get(endpoint)
-        .body(hyper::Body::empty())
-        .unwrap();
+async fn healthcheck(
+    endpoint: Uri,
+    client: HttpClient,
+    auth: Option<Auth>,
+    signer: Option<AwsSigner>,
+) -> crate::Result<()> {
+    let mut builder = http::Request::get(endpoint.clone());
+    if let Some(auth) = &auth {
+        builder = auth.apply_builder(builder);
+    }
+    if let Some(signer) = &signer {
+        let mut request = signer.signed_request("GET", &endpoint);
+        builder = signer.sign(&mut request, builder).await?;
+    }
+    let request = builder.body(hyper::Body::empty()).unwrap();
 
     let response = client.send(request).await?;
 
     match response.status() {
-        http::StatusCode::OK => Ok(()),
+        StatusCode::OK => Ok(()),
         other => Err(sinks::HealthcheckError::UnexpectedStatus { status: other }.into()),
     }
 }
 
+/// Follows the retry semantics of the remote write specification: server
+/// errors and throttling are retried, while any other client error means the
+/// data will never be accepted and is dropped.
+#[derive(Clone, Copy, Debug)]
+struct RemoteWriteRetryLogic;
+
+impl RetryLogic for RemoteWriteRetryLogic {
+    type Error = HttpError;
+    type Response = http::Response<Bytes>;
+
+    fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+        true
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        let status = response.status();
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => RetryAction::Retry("too many requests".into()),
+            _ if status.is_server_error() => RetryAction::Retry(format!(
+                "{}: {}",
+                status,
+                String::from_utf8_lossy(response.body())
+            )),
+            _ if status.is_success() => RetryAction::Successful,
+            _ => RetryAction::DontRetry(format!(
+                "{}: {}",
+                status,
+                String::from_utf8_lossy(response.body())
+            )),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct AwsSigner {
+    region: Region,
+    credentials: Arc<rusoto::AwsCredentialsProvider>,
+}
+
+impl AwsSigner {
+    fn signed_request(&self, method: &str, uri: &Uri) -> SignedRequest {
+        let mut request = SignedRequest::new(method, AWS_SERVICE, &self.region, uri.path());
+        if let Some(query) = uri.query() {
+            for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+                request.add_param(key, value);
+            }
+        }
+        request
+    }
+
+    async fn sign(
+        &self,
+        request: &mut SignedRequest,
+        mut builder: http::request::Builder,
+    ) -> crate::Result<http::request::Builder> {
+        let credentials = self
+            .credentials
+            .credentials()
+            .await
+            .context(AwsCredentialsGenerateFailed)?;
+
+        request.sign(&credentials);
+
+        for (name, values) in request.headers() {
+            let header_name = name
+                .parse::<HeaderName>()
+                .expect("Could not parse header name.");
+            for value in values {
+                let header_value =
+                    HeaderValue::from_bytes(value).expect("Could not parse header value.");
+                builder = builder.header(&header_name, header_value);
+            }
+        }
+
+        Ok(builder)
+    }
+}
+
+/// Remembers when each series last received a sample, so that series which
+/// disappear can be ended with a staleness marker the way Prometheus itself
+/// does when a target goes away.
+#[derive(Clone)]
+struct SeriesTracker {
+    stale_after_millis: i64,
+    last_seen: Arc<Mutex<HashMap<Vec<proto::Label>, i64>>>,
+}
+
+impl SeriesTracker {
+    fn new(stale_after_secs: u64) -> Self {
+        Self {
+            stale_after_millis: stale_after_secs as i64 * 1000,
+            last_seen: Default::default(),
+        }
+    }
+
+    /// Records the series in this request and appends a staleness marker
+    /// for every series that has gone quiet.
+    fn update(&self, timeseries: &mut Vec<proto::TimeSeries>, now_millis: i64) {
+        let mut last_seen = self.last_seen.lock().unwrap();
+
+        for series in timeseries.iter() {
+            match series.samples.last() {
+                Some(sample) if is_stale_marker(sample.value) => {
+                    last_seen.remove(&series.labels);
+                }
+                Some(sample) => {
+                    let seen = last_seen.entry(series.labels.clone()).or_insert(0);
+                    *seen = (*seen).max(sample.timestamp);
+                }
+                None => (),
+            }
+        }
+
+        let cutoff = now_millis - self.stale_after_millis;
+        let stale = last_seen
+            .iter()
+            .filter(|(_, seen)| **seen < cutoff)
+            .map(|(labels, _)| labels.clone())
+            .collect::<Vec<_>>();
+        for labels in stale {
+            last_seen.remove(&labels);
+            timeseries.push(proto::TimeSeries {
+                labels,
+                samples: vec![proto::Sample {
+                    value: f64::from_bits(STALE_NAN),
+                    timestamp: now_millis,
+                }],
+            });
+        }
+    }
+}
+
+fn is_stale_marker(value: f64) -> bool {
+    value.to_bits() == STALE_NAN
+}
+
 #[derive(Clone)]
 struct RemoteWriteService {
     endpoint: Uri,
@@ -123,12 +361,18 @@ struct RemoteWriteService {
     client: HttpClient,
     buckets: Vec<f64>,
     quantiles: Vec<f64>,
+    auth: Option<Auth>,
+    signer: Option<AwsSigner>,
+    series: Option<SeriesTracker>,
 }
 
 impl RemoteWriteService {
-    fn encode_events(&self, metrics: Vec<Metric>) -> Bytes {
+    fn encode_events(&self, metrics: Vec<Metric>, now: DateTime<Utc>) -> Bytes {
         let mut time_series = collector::TimeSeries::new();
-        for metric in metrics {
+        for mut metric in metrics {
+            // Samples without a timestamp would be written at the epoch,
+            // which every receiver rejects as out of bounds.
+            metric.timestamp.get_or_insert(now);
             time_series.encode_metric(
                 self.default_namespace.as_deref(),
                 &self.buckets,
@@ -137,7 +381,10 @@ impl RemoteWriteService {
                 &metric,
             );
         }
-        let timeseries = time_series.finish();
+        let mut timeseries = time_series.finish();
+        if let Some(series) = &self.series {
+            series.update(&mut timeseries, now.timestamp_millis());
+        }
 
         let request = proto::WriteRequest { timeseries };
         let mut out = BytesMut::with_capacity(request.encoded_len());
@@ -156,18 +403,45 @@ impl tower::Service<Vec<Metric>> for RemoteWriteService {
     }
 
     fn call(&mut self, events: Vec<Metric>) -> Self::Future {
-        let body = self.encode_events(events);
+        let body = self.encode_events(events, Utc::now());
         let body = snap_block(body);
 
-        let request = http::Request::post(self.endpoint.clone())
-            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
-            .header("Content-Encoding", "snappy")
-            .header("Content-Type", "application/x-protobuf")
-            .body(body.into())
-            .unwrap();
+        let mut builder = http::Request::post(self.endpoint.clone());
+        let signing = match &self.signer {
+            Some(signer) => {
+                let mut request = signer.signed_request("POST", &self.endpoint);
+                for (name, value) in &HEADERS {
+                    request.add_header(name, value);
+                }
+                Some((signer.clone(), request))
+            }
+            None => {
+                for (name, value) in &HEADERS {
+                    builder = builder.header(*name, *value);
+                }
+                if let Some(auth) = &self.auth {
+                    builder = auth.apply_builder(builder);
+                }
+                None
+            }
+        };
         let client = self.client.clone();
 
         Box::pin(async move {
+            let request = match signing {
+                Some((signer, mut request)) => {
+                    request.set_payload(Some(body));
+                    let builder = signer.sign(&mut request, builder).await?;
+                    // The signed request takes ownership of the body, so
+                    // it has to be taken back out of it.
+                    match request.payload.take() {
+                        Some(SignedRequestPayload::Buffer(body)) => builder.body(body.into())?,
+                        _ => unreachable!("Signed request payload is always a buffer"),
+                    }
+                }
+                None => builder.body(body.into())?,
+            };
+
             let response = client.send(request).await?;
             let (parts, body) = response.into_parts();
             let body = hyper::body::to_bytes(body).await?;
@@ -185,11 +459,150 @@ fn snap_block(data: Bytes) -> Vec<u8> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event::metric::{MetricKind, MetricValue};
+    use chrono::TimeZone;
 
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<RemoteWriteConfig>();
     }
+
+    #[test]
+    fn parses_aws_auth() {
+        let config: RemoteWriteConfig = toml::from_str(
+            r#"
+            endpoint = "https://aps-workspaces.us-west-2.amazonaws.com/workspaces/ws-abc/api/v1/remote_write"
+            auth.strategy = "aws"
+            auth.assume_role = "arn:aws:iam::123456789012:role/vector"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            config.auth,
+            Some(RemoteWriteAuth::Aws {
+                assume_role: Some(_),
+            })
+        ));
+    }
+
+    fn response(status: StatusCode) -> http::Response<Bytes> {
+        http::Response::builder()
+            .status(status)
+            .body(Bytes::from("rejected"))
+            .unwrap()
+    }
+
+    #[test]
+    fn retries_server_errors_only() {
+        let logic = RemoteWriteRetryLogic;
+        let action = |status| logic.should_retry_response(&response(status));
+
+        assert!(matches!(
+            action(StatusCode::NO_CONTENT),
+            RetryAction::Successful
+        ));
+        assert!(matches!(
+            action(StatusCode::INTERNAL_SERVER_ERROR),
+            RetryAction::Retry(_)
+        ));
+        assert!(matches!(
+            action(StatusCode::NOT_IMPLEMENTED),
+            RetryAction::Retry(_)
+        ));
+        assert!(matches!(
+            action(StatusCode::TOO_MANY_REQUESTS),
+            RetryAction::Retry(_)
+        ));
+        assert!(matches!(
+            action(StatusCode::BAD_REQUEST),
+            RetryAction::DontRetry(_)
+        ));
+    }
+
+    fn service(stale_after_secs: Option<u64>) -> RemoteWriteService {
+        RemoteWriteService {
+            endpoint: "http://localhost:9090/api/v1/write".parse().unwrap(),
+            default_namespace: None,
+            client: HttpClient::new(None).unwrap(),
+            buckets: super::super::default_histogram_buckets(),
+            quantiles: super::super::default_summary_quantiles(),
+            auth: None,
+            signer: None,
+            series: stale_after_secs.map(SeriesTracker::new),
+        }
+    }
+
+    fn gauge(name: &str, value: f64, timestamp: Option<DateTime<Utc>>) -> Metric {
+        Metric {
+            name: name.into(),
+            namespace: None,
+            timestamp,
+            tags: None,
+            kind: MetricKind::Absolute,
+            value: MetricValue::Gauge { value },
+        }
+    }
+
+    fn decode(
+        service: &RemoteWriteService,
+        metrics: Vec<Metric>,
+        now: i64,
+    ) -> Vec<proto::TimeSeries> {
+        let body = service.encode_events(metrics, Utc.timestamp_millis(now));
+        let mut timeseries = proto::WriteRequest::decode(body).unwrap().timeseries;
+        timeseries.sort_by(|a, b| a.labels.cmp(&b.labels));
+        timeseries
+    }
+
+    #[test]
+    fn fills_in_missing_timestamps() {
+        let timeseries = decode(&service(None), vec![gauge("load", 1.5, None)], 1_000);
+        assert_eq!(timeseries[0].samples[0].timestamp, 1_000);
+    }
+
+    #[test]
+    fn passes_through_staleness_markers() {
+        let service = service(Some(60));
+        let timestamp = Some(Utc.timestamp_millis(1_000));
+        let stale = f64::from_bits(STALE_NAN);
+
+        decode(&service, vec![gauge("load", 1.5, timestamp)], 1_000);
+        let timeseries = decode(&service, vec![gauge("load", stale, timestamp)], 2_000);
+        assert_eq!(timeseries.len(), 1);
+        assert_eq!(timeseries[0].samples[0].value.to_bits(), STALE_NAN);
+
+        // The series was ended upstream, so no second marker is sent.
+        let timeseries = decode(&service, vec![], 100_000);
+        assert!(timeseries.is_empty());
+    }
+
+    #[test]
+    fn marks_quiet_series_stale() {
+        let service = service(Some(60));
+        let timestamp = Some(Utc.timestamp_millis(1_000));
+
+        decode(
+            &service,
+            vec![
+                gauge("load", 1.5, timestamp),
+                gauge("users", 3.0, timestamp),
+            ],
+            1_000,
+        );
+        let users = Some(Utc.timestamp_millis(59_000));
+        let timeseries = decode(&service, vec![gauge("users", 4.0, users)], 61_001);
+
+        assert_eq!(timeseries.len(), 2);
+        let load = &timeseries[0];
+        assert_eq!(load.labels[0].value, "load");
+        assert_eq!(load.samples.len(), 1);
+        assert!(is_stale_marker(load.samples[0].value));
+        assert_eq!(load.samples[0].timestamp, 61_001);
+        assert_eq!(timeseries[1].samples[0].value, 4.0);
+
+        let timeseries = decode(&service, vec![], 62_000);
+        assert!(timeseries.is_empty());
+    }
 }
 
 #[cfg(all(test, feature = "prometheus-integration-tests"))]
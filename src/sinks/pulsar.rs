@@ -1,19 +1,25 @@
 use crate::{
     buffers::Acker,
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
-    internal_events::PulsarEncodeEventFailed,
+    event::{Event, Value},
+    internal_events::{PulsarEncodeEventFailed, PulsarPropertyMissingKeys},
     sinks::util::encoding::{EncodingConfig, EncodingConfigWithDefault, EncodingConfiguration},
+    template::{Template, TemplateError},
+    tls::TlsOptions,
 };
 use futures::{future::BoxFuture, ready, stream::FuturesUnordered, FutureExt, Sink, Stream};
 use pulsar::{
-    message::proto, producer::SendFuture, proto::CommandSendReceipt, Authentication,
-    Error as PulsarError, Producer, Pulsar, TokioExecutor,
+    message::proto,
+    producer::{self, MultiTopicProducer, SendFuture},
+    proto::CommandSendReceipt,
+    Authentication, Error as PulsarError, Pulsar, TokioExecutor,
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    path::PathBuf,
     pin::Pin,
     task::{Context, Poll},
 };
@@ -22,6 +28,17 @@ use std::{
 enum BuildError {
     #[snafu(display("creating pulsar producer failed: {}", source))]
     CreatePulsarSink { source: PulsarError },
+    #[snafu(display("invalid topic template: {}", source))]
+    TopicTemplate { source: TemplateError },
+    #[snafu(display("could not read CA file {:?}: {}", path, source))]
+    ReadCaFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("`encoding.schema` is only supported by the `avro` and `json` codecs"))]
+    SchemaNotSupported,
+    #[snafu(display("`tls.{}` isn't supported by the pulsar client", option))]
+    TlsOptionNotSupported { option: &'static str },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -30,8 +47,15 @@ pub struct PulsarSinkConfig {
     #[serde(alias = "address")]
     endpoint: String,
     topic: String,
+    /// The partition key of each message, which `key_shared` subscriptions
+    /// also use to route messages to consumers.
+    key: Option<Template>,
+    /// Message properties, by name, rendered from the event.
+    #[serde(default)]
+    properties: HashMap<String, Template>,
     encoding: EncodingConfigWithDefault<Encoding>,
     auth: Option<AuthConfig>,
+    tls: Option<TlsOptions>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -50,7 +74,7 @@ pub enum Encoding {
     Avro,
 }
 
-type PulsarProducer = Producer<TokioExecutor>;
+type PulsarProducer = MultiTopicProducer<TokioExecutor>;
 type BoxedPulsarProducer = Box<PulsarProducer>;
 
 enum PulsarSinkState {
@@ -60,6 +84,9 @@ enum PulsarSinkState {
 }
 
 struct PulsarSink {
+    topic: Template,
+    key: Option<Template>,
+    properties: Vec<(String, Template)>,
     encoding: EncodingConfig<Encoding>,
     avro_schema: Option<avro_rs::Schema>,
     state: PulsarSinkState,
@@ -81,8 +108,11 @@ impl GenerateConfig for PulsarSinkConfig {
         toml::Value::try_from(Self {
             endpoint: "pulsar://127.0.0.1:6650".to_string(),
             topic: "topic-1234".to_string(),
+            key: None,
+            properties: HashMap::new(),
             encoding: Encoding::Text.into(),
             auth: None,
+            tls: None,
         })
         .unwrap()
     }
@@ -95,17 +125,11 @@ impl SinkConfig for PulsarSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let producer = self
-            .create_pulsar_producer()
-            .await
-            .context(CreatePulsarSink)?;
-        let sink = PulsarSink::new(producer, self.encoding.clone().into(), cx.acker())?;
+        let producer = self.create_pulsar_producer().await?;
+        let sink = PulsarSink::new(self.clone(), producer, cx.acker())?;
 
-        let producer = self
-            .create_pulsar_producer()
-            .await
-            .context(CreatePulsarSink)?;
-        let healthcheck = healthcheck(producer).boxed();
+        let pulsar = self.create_pulsar_client().await?;
+        let healthcheck = healthcheck(pulsar, self.topic.clone()).boxed();
 
         Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck))
     }
@@ -120,7 +144,7 @@ impl SinkConfig for PulsarSinkConfig {
 }
 
 impl PulsarSinkConfig {
-    async fn create_pulsar_producer(&self) -> Result<PulsarProducer, PulsarError> {
+    async fn create_pulsar_client(&self) -> crate::Result<Pulsar<TokioExecutor>> {
         let mut builder = Pulsar::builder(&self.endpoint, TokioExecutor);
         if let Some(auth) = &self.auth {
             builder = builder.with_auth(Authentication {
@@ -128,39 +152,79 @@ impl PulsarSinkConfig {
                 data: auth.token.as_bytes().to_vec(),
             });
         }
+        if let Some(tls) = &self.tls {
+            // The client only takes the certificate chain to trust.
+            let unsupported = [
+                ("verify_certificate", tls.verify_certificate.is_some()),
+                ("verify_hostname", tls.verify_hostname.is_some()),
+                ("crt_file", tls.crt_file.is_some()),
+                ("key_file", tls.key_file.is_some()),
+                ("key_pass", tls.key_pass.is_some()),
+            ];
+            if let Some((option, _)) = unsupported.iter().find(|(_, set)| *set) {
+                return Err(BuildError::TlsOptionNotSupported { option: *option }.into());
+            }
+            if let Some(ca_file) = &tls.ca_file {
+                builder = builder
+                    .with_certificate_chain_file(ca_file)
+                    .context(ReadCaFile {
+                        path: ca_file.clone(),
+                    })?;
+            }
+        }
+
+        Ok(builder.build().await.context(CreatePulsarSink)?)
+    }
 
-        if let Some(avro_schema) = &self.encoding.schema() {
-            let pulsar = builder.build().await?;
-            pulsar
-                .producer()
-                .with_options(pulsar::producer::ProducerOptions {
-                    schema: Some(proto::Schema {
-                        schema_data: avro_schema.to_string().into_bytes(),
-                        type_: proto::schema::Type::Avro as i32,
-                        ..Default::default()
-                    }),
+    /// Creates a producer for all the topics rendered from `topic`, attaching
+    /// `encoding.schema` to each of them.
+    async fn create_pulsar_producer(&self) -> crate::Result<PulsarProducer> {
+        let schema_type = match (self.encoding.schema(), self.encoding.codec()) {
+            (None, _) => None,
+            (Some(_), Encoding::Avro) => Some(proto::schema::Type::Avro),
+            (Some(_), Encoding::Json) => Some(proto::schema::Type::Json),
+            (Some(_), Encoding::Text) => return Err(BuildError::SchemaNotSupported.into()),
+        };
+
+        let pulsar = self.create_pulsar_client().await?;
+        let mut builder = pulsar.producer();
+        if let (Some(schema), Some(schema_type)) = (self.encoding.schema(), schema_type) {
+            builder = builder.with_options(producer::ProducerOptions {
+                schema: Some(proto::Schema {
+                    schema_data: schema.to_string().into_bytes(),
+                    type_: schema_type as i32,
                     ..Default::default()
-                })
-                .with_topic(&self.topic)
-                .build()
-                .await
-        } else {
-            let pulsar = builder.build().await?;
-            pulsar.producer().with_topic(&self.topic).build().await
+                }),
+                ..Default::default()
+            });
         }
+        Ok(builder.build_multi_topic())
     }
 }
 
-async fn healthcheck(producer: PulsarProducer) -> crate::Result<()> {
+async fn healthcheck(pulsar: Pulsar<TokioExecutor>, topic: String) -> crate::Result<()> {
+    // A templated topic isn't known until events are rendered, in which case
+    // connecting the client is all that can be checked.
+    if Template::try_from(topic.as_str())?.is_dynamic() {
+        return Ok(());
+    }
+
+    let producer = pulsar
+        .producer()
+        .with_topic(&topic)
+        .build()
+        .await
+        .context(CreatePulsarSink)?;
     producer.check_connection().await.map_err(Into::into)
 }
 
 impl PulsarSink {
     fn new(
+        config: PulsarSinkConfig,
         producer: PulsarProducer,
-        encoding: EncodingConfig<Encoding>,
         acker: Acker,
     ) -> crate::Result<Self> {
+        let encoding: EncodingConfig<Encoding> = config.encoding.into();
         let schema = match &encoding.codec() {
             Encoding::Avro => {
                 if let Some(schema) = &encoding.schema() {
@@ -175,7 +239,13 @@ impl PulsarSink {
             _ => None,
         };
 
+        let mut properties = config.properties.into_iter().collect::<Vec<_>>();
+        properties.sort_by(|a, b| a.0.cmp(&b.0));
+
         Ok(Self {
+            topic: Template::try_from(config.topic).context(TopicTemplate)?,
+            key: config.key,
+            properties,
             encoding,
             avro_schema: schema,
             state: PulsarSinkState::Ready(Box::new(producer)),
@@ -206,6 +276,41 @@ impl PulsarSink {
 
         Poll::Ready(())
     }
+
+    fn build_message(&self, event: Event) -> crate::Result<producer::Message> {
+        // A key with missing fields is left out, so the message is routed
+        // as if it had no key.
+        let partition_key = self
+            .key
+            .as_ref()
+            .and_then(|key| key.render_string(&event).ok());
+
+        let mut properties = HashMap::new();
+        for (name, template) in &self.properties {
+            match template.render_string(&event) {
+                Ok(value) => {
+                    properties.insert(name.clone(), value);
+                }
+                Err(missing_keys) => emit!(PulsarPropertyMissingKeys {
+                    property: name,
+                    keys: &missing_keys,
+                }),
+            }
+        }
+
+        let event_time = match event.as_log().get(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(timestamp)) => Some(timestamp.timestamp_millis() as u64),
+            _ => None,
+        };
+
+        Ok(producer::Message {
+            payload: encode_event(event, &self.encoding, &self.avro_schema)?,
+            properties,
+            partition_key,
+            event_time,
+            ..Default::default()
+        })
+    }
 }
 
 impl Sink<Event> for PulsarSink {
@@ -222,7 +327,10 @@ impl Sink<Event> for PulsarSink {
             "Expected `poll_ready` to be called first."
         );
 
-        let message = encode_event(item, &self.encoding, &self.avro_schema).map_err(|e| {
+        let topic = self.topic.render_string(&item).map_err(|missing_keys| {
+            error!(message = "Missing keys for topic.", missing_keys = ?missing_keys);
+        })?;
+        let message = self.build_message(item).map_err(|e| {
             emit!(PulsarEncodeEventFailed {
                 error: &*e.to_string()
             })
@@ -236,7 +344,7 @@ impl Sink<Event> for PulsarSink {
         let _ = std::mem::replace(
             &mut self.state,
             PulsarSinkState::Sending(Box::pin(async move {
-                let result = producer.send(message).await;
+                let result = producer.send(topic, message).await;
                 (producer, result)
             })),
         );
@@ -367,6 +475,22 @@ mod tests {
         assert_eq!(result, must_be);
     }
 
+    #[tokio::test]
+    async fn pulsar_rejects_schema_for_text() {
+        let cnf: PulsarSinkConfig = toml::from_str(
+            r#"
+            endpoint = "pulsar://127.0.0.1:6650"
+            topic = "logs"
+            encoding.codec = "text"
+            encoding.schema = "{}"
+            "#,
+        )
+        .unwrap();
+
+        let error = cnf.create_pulsar_producer().await.err().unwrap();
+        assert!(error.to_string().contains("only supported"));
+    }
+
     #[test]
     fn pulsar_encode_event() {
         let msg = "hello_world";
@@ -410,8 +534,11 @@ mod integration_tests {
         let cnf = PulsarSinkConfig {
             endpoint: "pulsar://127.0.0.1:6650".to_owned(),
             topic: topic.clone(),
+            key: None,
+            properties: HashMap::new(),
             encoding: Encoding::Text.into(),
             auth: None,
+            tls: None,
         };
 
         let pulsar = Pulsar::<TokioExecutor>::builder(&cnf.endpoint, TokioExecutor)
@@ -430,7 +557,7 @@ mod integration_tests {
 
         let (acker, ack_counter) = Acker::new_for_testing();
         let producer = cnf.create_pulsar_producer().await.unwrap();
-        let sink = PulsarSink::new(producer, cnf.encoding.clone().into(), acker).unwrap();
+        let sink = PulsarSink::new(cnf.clone(), producer, acker).unwrap();
         events.map(Ok).forward(sink).await.unwrap();
 
         assert_eq!(
@@ -446,4 +573,58 @@ mod integration_tests {
             consumer.ack(&msg).await.unwrap();
         }
     }
+
+    #[tokio::test]
+    async fn pulsar_templated_topic_key_and_properties() {
+        trace_init();
+
+        let service = random_string(10);
+        let cnf: PulsarSinkConfig = toml::from_str(
+            r#"
+            endpoint = "pulsar://127.0.0.1:6650"
+            topic = "test-{{ service }}"
+            key = "{{ user_id }}"
+            properties = { trace_id = "{{ trace_id }}", source = "vector" }
+            encoding.codec = "text"
+            "#,
+        )
+        .unwrap();
+
+        let pulsar = Pulsar::<TokioExecutor>::builder(&cnf.endpoint, TokioExecutor)
+            .build()
+            .await
+            .unwrap();
+        let mut consumer = pulsar
+            .consumer()
+            .with_topic(format!("test-{}", service))
+            .with_consumer_name("VectorTestConsumer")
+            .with_subscription_type(SubType::KeyShared)
+            .with_subscription("VectorTestSub")
+            .build::<String>()
+            .await
+            .unwrap();
+
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("service", service.clone());
+        event.as_mut_log().insert("user_id", "42");
+
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let producer = cnf.create_pulsar_producer().await.unwrap();
+        let sink = PulsarSink::new(cnf.clone(), producer, acker).unwrap();
+        futures::stream::iter(vec![Ok(event)])
+            .forward(sink)
+            .await
+            .unwrap();
+        assert_eq!(ack_counter.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        let msg = consumer.next().await.unwrap().unwrap();
+        let metadata = &msg.payload.metadata;
+        assert_eq!(msg.payload.data, b"hello");
+        assert_eq!(metadata.partition_key.as_deref(), Some("42"));
+        assert_eq!(metadata.properties.len(), 1);
+        assert_eq!(metadata.properties[0].key, "source");
+        assert_eq!(metadata.properties[0].value, "vector");
+        assert!(metadata.event_time.is_some());
+        consumer.ack(&msg).await.unwrap();
+    }
 }
@@ -0,0 +1,260 @@
+use super::SftpAuth;
+use snafu::{OptionExt, ResultExt, Snafu};
+use ssh2::{CheckResult, KnownHostFileKind, RenameFlags, Session, Sftp};
+use std::{
+    io::{self, Write},
+    net::TcpStream,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+#[derive(Debug, Snafu)]
+pub enum SftpError {
+    #[snafu(display("Could not connect to {}: {}", address, source))]
+    Connect { address: String, source: io::Error },
+    #[snafu(display("SSH handshake failed: {}", source))]
+    Handshake { source: ssh2::Error },
+    #[snafu(display("Could not read known hosts from {:?}: {}", path, source))]
+    KnownHosts { path: PathBuf, source: ssh2::Error },
+    #[snafu(display("The host key of {} isn't in the known hosts", host))]
+    UnknownHostKey { host: String },
+    #[snafu(display("The host key of {} doesn't match the known hosts", host))]
+    HostKeyMismatch { host: String },
+    #[snafu(display("Authentication failed: {}", source))]
+    Authenticate { source: ssh2::Error },
+    #[snafu(display("Could not start SFTP: {}", source))]
+    StartSftp { source: ssh2::Error },
+    #[snafu(display("Could not create directory {:?}: {}", path, source))]
+    CreateDirectory { path: String, source: ssh2::Error },
+    #[snafu(display("Could not create {:?}: {}", path, source))]
+    CreateFile { path: String, source: ssh2::Error },
+    #[snafu(display("Could not write {:?}: {}", path, source))]
+    WriteFile { path: String, source: io::Error },
+    #[snafu(display("Could not rename {:?} to {:?}: {}", from, to, source))]
+    Rename {
+        from: String,
+        to: String,
+        source: ssh2::Error,
+    },
+    #[snafu(display("Upload task failed: {}", source))]
+    Task { source: tokio::task::JoinError },
+}
+
+impl SftpError {
+    /// Host keys and credentials stay wrong until the config is fixed.
+    pub fn is_transient(&self) -> bool {
+        !matches!(
+            self,
+            SftpError::KnownHosts { .. }
+                | SftpError::UnknownHostKey { .. }
+                | SftpError::HostKeyMismatch { .. }
+                | SftpError::Authenticate { .. }
+        )
+    }
+}
+
+/// Uploads files with libssh2, whose calls block, so they're made on the
+/// blocking thread pool. Every upload opens a session of its own, as
+/// batches are large and rare enough for that not to matter, and a broken
+/// session can't hold up later uploads.
+#[derive(Clone, Debug)]
+pub struct SftpClient {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub auth: SftpAuth,
+    /// Host keys aren't verified without known hosts.
+    pub known_hosts: Option<PathBuf>,
+    pub timeout: Duration,
+}
+
+impl SftpClient {
+    pub async fn healthcheck(&self) -> Result<(), SftpError> {
+        let client = self.clone();
+        tokio::task::spawn_blocking(move || client.connect().map(|_| ()))
+            .await
+            .context(Task)?
+    }
+
+    pub async fn upload(&self, upload: Upload) -> Result<(), SftpError> {
+        let client = self.clone();
+        tokio::task::spawn_blocking(move || client.upload_blocking(&upload))
+            .await
+            .context(Task)?
+    }
+
+    fn connect(&self) -> Result<Sftp, SftpError> {
+        let address = format!("{}:{}", self.host, self.port);
+        let tcp = TcpStream::connect(&address).context(Connect { address })?;
+        let mut session = Session::new().context(Handshake)?;
+        session.set_tcp_stream(tcp);
+        session.set_timeout(self.timeout.as_millis() as u32);
+        session.handshake().context(Handshake)?;
+        self.verify_host_key(&session)?;
+
+        match &self.auth {
+            SftpAuth::Password { password } => session.userauth_password(&self.username, password),
+            SftpAuth::Key {
+                private_key_path,
+                passphrase,
+            } => session.userauth_pubkey_file(
+                &self.username,
+                None,
+                private_key_path,
+                passphrase.as_deref(),
+            ),
+        }
+        .context(Authenticate)?;
+
+        session.sftp().context(StartSftp)
+    }
+
+    fn verify_host_key(&self, session: &Session) -> Result<(), SftpError> {
+        let path = match &self.known_hosts {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let mut known_hosts = session
+            .known_hosts()
+            .context(KnownHosts { path: path.clone() })?;
+        known_hosts
+            .read_file(path, KnownHostFileKind::OpenSSH)
+            .context(KnownHosts { path: path.clone() })?;
+
+        let host = self.host.clone();
+        let (key, _) = session
+            .host_key()
+            .context(UnknownHostKey { host: host.clone() })?;
+        match known_hosts.check_port(&self.host, self.port, key) {
+            CheckResult::Match => Ok(()),
+            CheckResult::Mismatch => Err(SftpError::HostKeyMismatch { host }),
+            CheckResult::NotFound | CheckResult::Failure => Err(SftpError::UnknownHostKey { host }),
+        }
+    }
+
+    /// Writes the file under a temporary name first, and renames it once
+    /// it's complete, so readers never see it half written.
+    fn upload_blocking(&self, upload: &Upload) -> Result<(), SftpError> {
+        let sftp = self.connect()?;
+        let path = upload.path();
+
+        // The rename of an earlier attempt may have succeeded without its
+        // response making it back.
+        if sftp.stat(Path::new(&path)).is_ok() {
+            return Ok(());
+        }
+
+        if upload.create_directories {
+            create_directories(&sftp, &upload.directory)?;
+        }
+
+        let temp_path = format!("{}{}", path, upload.temp_suffix);
+        let mut file = sftp
+            .create(Path::new(&temp_path))
+            .context(CreateFile { path: &temp_path })?;
+        file.write_all(&upload.body)
+            .context(WriteFile { path: &temp_path })?;
+        drop(file);
+
+        // Servers speaking SFTP 3, such as OpenSSH, ignore the flags and
+        // rename atomically when the target doesn't exist, which it
+        // doesn't, as file names are unique.
+        sftp.rename(
+            Path::new(&temp_path),
+            Path::new(&path),
+            Some(RenameFlags::ATOMIC | RenameFlags::OVERWRITE | RenameFlags::NATIVE),
+        )
+        .context(Rename {
+            from: &temp_path,
+            to: &path,
+        })
+    }
+}
+
+/// Creates the directory and its missing parents.
+fn create_directories(sftp: &Sftp, directory: &str) -> Result<(), SftpError> {
+    for parent in parents(directory) {
+        if sftp.stat(Path::new(&parent)).is_ok() {
+            continue;
+        }
+        if let Err(source) = sftp.mkdir(Path::new(&parent), 0o755) {
+            // Another upload may have created it in the meantime.
+            if sftp.stat(Path::new(&parent)).is_err() {
+                return Err(SftpError::CreateDirectory {
+                    path: parent,
+                    source,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The directory and each of its parents, shortest first.
+fn parents(directory: &str) -> Vec<String> {
+    let mut parents = Vec::new();
+    let mut current = String::new();
+    if directory.starts_with('/') {
+        current.push('/');
+    }
+    for component in directory
+        .split('/')
+        .filter(|component| !component.is_empty())
+    {
+        current.push_str(component);
+        parents.push(current.clone());
+        current.push('/');
+    }
+    parents
+}
+
+#[derive(Clone, Debug)]
+pub struct Upload {
+    pub directory: String,
+    pub filename: String,
+    pub body: Vec<u8>,
+    pub temp_suffix: String,
+    pub create_directories: bool,
+}
+
+impl Upload {
+    pub fn path(&self) -> String {
+        if self.directory.is_empty() || self.directory.ends_with('/') {
+            format!("{}{}", self.directory, self.filename)
+        } else {
+            format!("{}/{}", self.directory, self.filename)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_parents() {
+        assert_eq!(
+            parents("/upload//2020-12-01/"),
+            vec!["/upload", "/upload/2020-12-01"]
+        );
+        assert_eq!(parents("upload/a"), vec!["upload", "upload/a"]);
+        assert!(parents("/").is_empty());
+    }
+
+    #[test]
+    fn joins_paths() {
+        let upload = Upload {
+            directory: "/upload".into(),
+            filename: "1606780800-a.log".into(),
+            body: Vec::new(),
+            temp_suffix: ".part".into(),
+            create_directories: true,
+        };
+        assert_eq!(upload.path(), "/upload/1606780800-a.log");
+        let upload = Upload {
+            directory: "upload/".into(),
+            ..upload
+        };
+        assert_eq!(upload.path(), "upload/1606780800-a.log");
+    }
+}
@@ -0,0 +1,364 @@
+mod client;
+
+use self::client::{SftpClient, SftpError, Upload};
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::Event,
+    internal_events::{SftpFileUploaded, SftpTemplateMissingKeys},
+    sinks::{
+        util::{
+            encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+            retries::RetryLogic,
+            BatchConfig, BatchSettings, Buffer, Compression, Concurrency, PartitionBatchSink,
+            PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+};
+use chrono::{
+    format::{strftime::StrftimeItems, Item},
+    Utc,
+};
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    path::PathBuf,
+    task::{Context, Poll},
+};
+use tower::{Service, ServiceBuilder};
+use uuid::Uuid;
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        concurrency: Concurrency::Fixed(1),
+        timeout_secs: Some(300),
+        ..Default::default()
+    };
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SftpSinkConfig {
+    /// The host and port of the server, the port defaulting to 22.
+    pub address: String,
+    pub username: String,
+    pub auth: SftpAuth,
+    /// Defaults to `~/.ssh/known_hosts`.
+    pub known_hosts: Option<PathBuf>,
+    #[serde(default = "crate::serde::default_true")]
+    pub verify_host_key: bool,
+    /// The directory files are uploaded to.
+    pub path: Template,
+    #[serde(default = "crate::serde::default_true")]
+    pub create_directories: bool,
+    /// Appended to the names of files while they're uploaded.
+    pub temp_suffix: Option<String>,
+    pub filename_time_format: Option<String>,
+    pub filename_append_uuid: Option<bool>,
+    pub filename_extension: Option<String>,
+    #[serde(default)]
+    pub compression: Compression,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub encoding: EncodingConfigWithDefault<Encoding>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub enum SftpAuth {
+    Password {
+        password: String,
+    },
+    Key {
+        private_key_path: PathBuf,
+        passphrase: Option<String>,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Encoding {
+    #[derivative(Default)]
+    Text,
+    Ndjson,
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid strftime item in `filename_time_format`"))]
+    FilenameTimeFormat,
+    #[snafu(display(
+        "`filename_time_format` can't be empty unless `filename_append_uuid` is set"
+    ))]
+    EmptyFilename,
+    #[snafu(display("`known_hosts` must be set to verify host keys, as $HOME isn't"))]
+    NoKnownHosts,
+    #[snafu(display("Missing host in address field"))]
+    MissingHost,
+}
+
+inventory::submit! {
+    SinkDescription::new::<SftpSinkConfig>("sftp")
+}
+
+impl GenerateConfig for SftpSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "sftp.example.com:22"
+            username = "vector"
+            auth.strategy = "key"
+            auth.private_key_path = "/etc/vector/id_ed25519"
+            path = "/upload/%F""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sftp")]
+impl SinkConfig for SftpSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let client = self.client()?;
+        let healthcheck = {
+            let client = client.clone();
+            async move { client.healthcheck().await.map_err(Into::into) }.boxed()
+        };
+        let sink = self.sink(client, cx)?;
+        Ok((sink, healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "sftp"
+    }
+}
+
+impl SftpSinkConfig {
+    fn client(&self) -> crate::Result<SftpClient> {
+        let uri = self.address.parse::<http::Uri>()?;
+        let host = uri.host().ok_or(BuildError::MissingHost)?.to_string();
+        let known_hosts = if self.verify_host_key {
+            match &self.known_hosts {
+                Some(path) => Some(path.clone()),
+                None => {
+                    let home = std::env::var_os("HOME").ok_or(BuildError::NoKnownHosts)?;
+                    Some(PathBuf::from(home).join(".ssh/known_hosts"))
+                }
+            }
+        } else {
+            warn!("Host keys aren't verified, so uploads are open to man-in-the-middle attacks.");
+            None
+        };
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+
+        Ok(SftpClient {
+            host,
+            port: uri.port_u16().unwrap_or(22),
+            username: self.username.clone(),
+            auth: self.auth.clone(),
+            known_hosts,
+            timeout: request.timeout,
+        })
+    }
+
+    fn sink(&self, client: SftpClient, cx: SinkContext) -> crate::Result<VectorSink> {
+        let filename = FilenameSettings {
+            time_format: self
+                .filename_time_format
+                .clone()
+                .unwrap_or_else(|| "%s".into()),
+            append_uuid: self.filename_append_uuid.unwrap_or(true),
+            extension: self
+                .filename_extension
+                .clone()
+                .unwrap_or_else(|| self.compression.extension().into()),
+        };
+        if StrftimeItems::new(&filename.time_format).any(|item| matches!(item, Item::Error)) {
+            return Err(BuildError::FilenameTimeFormat.into());
+        }
+        if filename.time_format.is_empty() && !filename.append_uuid {
+            return Err(BuildError::EmptyFilename.into());
+        }
+
+        // Files are rotated when they reach the size or age of a batch.
+        let batch = BatchSettings::default()
+            .bytes(bytesize::mib(10u64))
+            .timeout(300)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let compression = self.compression;
+        let temp_suffix = self.temp_suffix.clone().unwrap_or_else(|| ".part".into());
+        let create_directories = self.create_directories;
+        let path = self.path.clone();
+        let encoding = self.encoding.clone();
+
+        let svc = ServiceBuilder::new()
+            .map(move |buffer| filename.build_upload(buffer, &temp_suffix, create_directories))
+            .settings(request, SftpRetryLogic)
+            .service(SftpService { client });
+
+        let buffer = PartitionBuffer::new(Buffer::new(batch.size, compression));
+
+        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+            .with_flat_map(move |event| stream::iter(encode_event(event, &path, &encoding)).map(Ok))
+            .sink_map_err(|error| error!(message = "Fatal sftp sink error.", %error));
+
+        Ok(VectorSink::Sink(Box::new(sink)))
+    }
+}
+
+fn encode_event(
+    mut event: Event,
+    path: &Template,
+    encoding: &EncodingConfigWithDefault<Encoding>,
+) -> Option<PartitionInnerBuffer<Vec<u8>, String>> {
+    let directory = path
+        .render_string(&event)
+        .map_err(|keys| emit!(SftpTemplateMissingKeys { keys: &keys }))
+        .ok()?;
+
+    encoding.apply_rules(&mut event);
+    let log = event.into_log();
+    let mut bytes = match encoding.codec() {
+        Encoding::Ndjson => {
+            serde_json::to_vec(&log).expect("Failed to encode event as json, this is a bug!")
+        }
+        Encoding::Text => log
+            .get(log_schema().message_key())
+            .map(|v| v.as_bytes().to_vec())
+            .unwrap_or_default(),
+    };
+    bytes.push(b'\n');
+
+    Some(PartitionInnerBuffer::new(bytes, directory))
+}
+
+#[derive(Clone, Debug)]
+struct FilenameSettings {
+    time_format: String,
+    append_uuid: bool,
+    extension: String,
+}
+
+impl FilenameSettings {
+    /// Names the file when the batch is sent, so that retries upload it
+    /// again under the same name.
+    fn build_upload(
+        &self,
+        buffer: PartitionInnerBuffer<Vec<u8>, String>,
+        temp_suffix: &str,
+        create_directories: bool,
+    ) -> Upload {
+        let (body, directory) = buffer.into_parts();
+        let time = Utc::now().format(&self.time_format).to_string();
+        let name = match (time.is_empty(), self.append_uuid) {
+            (false, true) => format!("{}-{}", time, Uuid::new_v4().to_hyphenated()),
+            (true, true) => Uuid::new_v4().to_hyphenated().to_string(),
+            (_, false) => time,
+        };
+
+        Upload {
+            directory,
+            filename: format!("{}.{}", name, self.extension),
+            body,
+            temp_suffix: temp_suffix.into(),
+            create_directories,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct SftpService {
+    client: SftpClient,
+}
+
+impl Service<Upload> for SftpService {
+    type Response = ();
+    type Error = SftpError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, upload: Upload) -> Self::Future {
+        let client = self.client.clone();
+        Box::pin(async move {
+            let path = upload.path();
+            let byte_size = upload.body.len();
+            client.upload(upload).await?;
+            emit!(SftpFileUploaded {
+                path: &path,
+                byte_size
+            });
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone)]
+struct SftpRetryLogic;
+
+impl RetryLogic for SftpRetryLogic {
+    type Error = SftpError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_transient()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SftpSinkConfig>();
+    }
+
+    #[test]
+    fn partitions_by_directory() {
+        let path = Template::try_from("/upload/{{ partner }}").unwrap();
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("partner", "acme");
+
+        let (bytes, directory) = encode_event(event, &path, &Default::default())
+            .unwrap()
+            .into_parts();
+        assert_eq!(directory, "/upload/acme");
+        assert_eq!(bytes, b"hello\n");
+
+        assert!(encode_event(Event::from("hello"), &path, &Default::default()).is_none());
+    }
+
+    #[test]
+    fn names_files() {
+        let settings = FilenameSettings {
+            time_format: "%Y".into(),
+            append_uuid: false,
+            extension: "log.gz".into(),
+        };
+        let buffer = PartitionInnerBuffer::new(b"hello\n".to_vec(), "/upload".to_owned());
+        let upload = settings.build_upload(buffer, ".part", true);
+        assert_eq!(
+            upload.path(),
+            format!("/upload/{}.log.gz", Utc::now().format("%Y"))
+        );
+        assert_eq!(upload.temp_suffix, ".part");
+    }
+}
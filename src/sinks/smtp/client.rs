@@ -0,0 +1,292 @@
+use super::{Security, SmtpAuth};
+use crate::{
+    dns,
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsError},
+};
+use snafu::{ResultExt, Snafu};
+use std::net::SocketAddr;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+#[derive(Debug, Snafu)]
+pub enum SmtpError {
+    #[snafu(display("Unable to resolve DNS: {}", source))]
+    DnsError { source: dns::DnsError },
+    #[snafu(display("No addresses returned."))]
+    NoAddresses,
+    #[snafu(display("Connect error: {}", source))]
+    ConnectError { source: TlsError },
+    #[snafu(display("I/O error: {}", source))]
+    IoError { source: std::io::Error },
+    #[snafu(display("Connection closed by the server."))]
+    ConnectionClosed,
+    #[snafu(display("Malformed reply: {:?}", line))]
+    MalformedReply { line: String },
+    #[snafu(display("{} rejected with {}: {}", command, code, message))]
+    Rejected {
+        command: &'static str,
+        code: u16,
+        message: String,
+    },
+    #[snafu(display("The server doesn't support STARTTLS."))]
+    StartTlsUnsupported,
+    #[snafu(display(
+        "The server supports neither the PLAIN nor the LOGIN authentication mechanism."
+    ))]
+    AuthUnsupported,
+}
+
+impl SmtpError {
+    /// Whether sending again may succeed: connection failures and transient
+    /// (4xx) replies, but not permanent (5xx) replies or missing features.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            SmtpError::Rejected { code, .. } => *code < 500,
+            SmtpError::MalformedReply { .. }
+            | SmtpError::StartTlsUnsupported
+            | SmtpError::AuthUnsupported => false,
+            _ => true,
+        }
+    }
+}
+
+/// A reply to a command, with the text of each of its lines.
+struct Reply {
+    code: u16,
+    lines: Vec<String>,
+}
+
+struct Connection {
+    stream: BufReader<MaybeTlsStream<TcpStream>>,
+    /// The extensions advertised in the last EHLO reply, uppercased.
+    extensions: Vec<String>,
+}
+
+impl Connection {
+    async fn read_reply(&mut self) -> Result<Reply, SmtpError> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await.context(IoError)? == 0 {
+                return Err(SmtpError::ConnectionClosed);
+            }
+            let line = line.trim_end();
+            let code = line
+                .get(..3)
+                .and_then(|code| code.parse::<u16>().ok())
+                .ok_or_else(|| SmtpError::MalformedReply { line: line.into() })?;
+            lines.push(line.get(4..).unwrap_or_default().to_owned());
+            // Every line but the last has a `-` after the code.
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok(Reply { code, lines });
+            }
+        }
+    }
+
+    /// Checks that the reply is in the same class (2xx, 3xx) as `expected`.
+    fn check(command: &'static str, reply: Reply, expected: u16) -> Result<Reply, SmtpError> {
+        if reply.code / 100 == expected / 100 {
+            Ok(reply)
+        } else {
+            Err(SmtpError::Rejected {
+                command,
+                code: reply.code,
+                message: reply.lines.join(" "),
+            })
+        }
+    }
+
+    async fn command(
+        &mut self,
+        command: &'static str,
+        line: &str,
+        expected: u16,
+    ) -> Result<Reply, SmtpError> {
+        let stream = self.stream.get_mut();
+        stream.write_all(line.as_bytes()).await.context(IoError)?;
+        stream.write_all(b"\r\n").await.context(IoError)?;
+        stream.flush().await.context(IoError)?;
+        let reply = self.read_reply().await?;
+        Self::check(command, reply, expected)
+    }
+
+    async fn ehlo(&mut self, helo_name: &str) -> Result<(), SmtpError> {
+        let reply = self
+            .command("EHLO", &format!("EHLO {}", helo_name), 250)
+            .await?;
+        // The first line is the greeting of the server.
+        self.extensions = reply
+            .lines
+            .iter()
+            .skip(1)
+            .map(|line| line.to_ascii_uppercase())
+            .collect();
+        Ok(())
+    }
+
+    fn supports(&self, keyword: &str) -> bool {
+        self.extensions
+            .iter()
+            .any(|extension| extension.split_whitespace().next() == Some(keyword))
+    }
+
+    fn supports_auth(&self, mechanism: &str) -> bool {
+        self.extensions.iter().any(|extension| {
+            let mut words = extension.split_whitespace();
+            words.next() == Some("AUTH") && words.any(|word| word == mechanism)
+        })
+    }
+
+    async fn starttls(
+        mut self,
+        host: &str,
+        tls: &MaybeTlsSettings,
+    ) -> Result<Connection, SmtpError> {
+        self.command("STARTTLS", "STARTTLS", 220).await?;
+        let stream = match self.stream.into_inner() {
+            MaybeTlsStream::Raw(stream) => tls.upgrade(host, stream).await.context(ConnectError)?,
+            stream => stream,
+        };
+        Ok(Connection {
+            stream: BufReader::new(stream),
+            extensions: Vec::new(),
+        })
+    }
+
+    async fn authenticate(&mut self, auth: &SmtpAuth) -> Result<(), SmtpError> {
+        if self.supports_auth("PLAIN") {
+            let credentials = format!("\0{}\0{}", auth.username, auth.password);
+            self.command(
+                "AUTH",
+                &format!("AUTH PLAIN {}", base64::encode(credentials)),
+                235,
+            )
+            .await?;
+        } else if self.supports_auth("LOGIN") {
+            self.command("AUTH", "AUTH LOGIN", 334).await?;
+            self.command("AUTH", &base64::encode(&auth.username), 334)
+                .await?;
+            self.command("AUTH", &base64::encode(&auth.password), 235)
+                .await?;
+        } else {
+            return Err(SmtpError::AuthUnsupported);
+        }
+        Ok(())
+    }
+
+    /// Sends the message after `DATA`, dot-stuffing lines that start with a
+    /// period and terminating it with a line holding a single period.
+    async fn data(&mut self, message: &str) -> Result<(), SmtpError> {
+        self.command("DATA", "DATA", 354).await?;
+
+        let mut data = String::with_capacity(message.len() + 16);
+        for line in message.lines() {
+            if line.starts_with('.') {
+                data.push('.');
+            }
+            data.push_str(line);
+            data.push_str("\r\n");
+        }
+        data.push_str(".\r\n");
+
+        let stream = self.stream.get_mut();
+        stream.write_all(data.as_bytes()).await.context(IoError)?;
+        stream.flush().await.context(IoError)?;
+        let reply = self.read_reply().await?;
+        Self::check("DATA", reply, 250).map(|_| ())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SmtpClient {
+    host: String,
+    port: u16,
+    security: Security,
+    tls: MaybeTlsSettings,
+    auth: Option<SmtpAuth>,
+    helo_name: String,
+}
+
+impl SmtpClient {
+    pub fn new(
+        host: String,
+        port: u16,
+        security: Security,
+        tls: MaybeTlsSettings,
+        auth: Option<SmtpAuth>,
+        helo_name: String,
+    ) -> Self {
+        Self {
+            host,
+            port,
+            security,
+            tls,
+            auth,
+            helo_name,
+        }
+    }
+
+    /// Opens a session, ready for a `MAIL` command.
+    async fn connect(&self) -> Result<Connection, SmtpError> {
+        let ip = dns::Resolver
+            .lookup_ip(self.host.clone())
+            .await
+            .context(DnsError)?
+            .next()
+            .ok_or(SmtpError::NoAddresses)?;
+        let addr = SocketAddr::new(ip, self.port);
+
+        let stream = match self.security {
+            Security::Tls => self.tls.connect(&self.host, &addr).await,
+            _ => MaybeTlsSettings::Raw(()).connect(&self.host, &addr).await,
+        }
+        .context(ConnectError)?;
+        let mut connection = Connection {
+            stream: BufReader::new(stream),
+            extensions: Vec::new(),
+        };
+
+        let greeting = connection.read_reply().await?;
+        Connection::check("Connection", greeting, 220)?;
+        connection.ehlo(&self.helo_name).await?;
+
+        match self.security {
+            Security::Starttls | Security::Opportunistic if connection.supports("STARTTLS") => {
+                connection = connection.starttls(&self.host, &self.tls).await?;
+                connection.ehlo(&self.helo_name).await?;
+            }
+            Security::Starttls => return Err(SmtpError::StartTlsUnsupported),
+            _ => {}
+        }
+
+        if let Some(auth) = &self.auth {
+            connection.authenticate(auth).await?;
+        }
+
+        Ok(connection)
+    }
+
+    pub async fn send(&self, from: &str, to: &[String], message: &str) -> Result<(), SmtpError> {
+        let mut connection = self.connect().await?;
+        connection
+            .command("MAIL", &format!("MAIL FROM:<{}>", from), 250)
+            .await?;
+        for recipient in to {
+            connection
+                .command("RCPT", &format!("RCPT TO:<{}>", recipient), 250)
+                .await?;
+        }
+        connection.data(message).await?;
+        // The message is accepted, so a failed goodbye doesn't matter.
+        let _ = connection.command("QUIT", "QUIT", 221).await;
+        Ok(())
+    }
+
+    pub async fn healthcheck(&self) -> Result<(), SmtpError> {
+        let mut connection = self.connect().await?;
+        let _ = connection.command("QUIT", "QUIT", 221).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,482 @@
+mod client;
+
+use self::client::{SmtpClient, SmtpError};
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::Event,
+    internal_events::{SmtpEmailSent, SmtpTemplateMissingKeys},
+    sinks::{
+        util::{
+            retries::RetryLogic, BatchConfig, BatchSettings, Concurrency, EncodedLength,
+            TowerRequestConfig, VecBuffer,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+    tls::{MaybeTlsSettings, TlsOptions, TlsSettings},
+};
+use chrono::Utc;
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::task::{Context, Poll};
+use tower::Service;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one recipient must be configured"))]
+    NoRecipients,
+    #[snafu(display("Missing host in address field"))]
+    MissingHost,
+    #[snafu(display("Missing port in address field"))]
+    MissingPort,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpSinkConfig {
+    pub address: String,
+    #[serde(default)]
+    pub security: Security,
+    pub tls: Option<TlsOptions>,
+    pub auth: Option<SmtpAuth>,
+    /// The name the sink introduces itself with, defaults to the hostname.
+    pub helo_name: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    pub subject: Template,
+    pub body: Option<Template>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+}
+
+/// How the connection to the server is secured.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Security {
+    /// Upgrade the connection with `STARTTLS`, failing if the server doesn't
+    /// support it.
+    #[derivative(Default)]
+    Starttls,
+    /// Upgrade the connection with `STARTTLS` if the server supports it.
+    Opportunistic,
+    /// Negotiate TLS as soon as the connection is established.
+    Tls,
+    /// Never use TLS.
+    None,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpAuth {
+    pub username: String,
+    pub password: String,
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        concurrency: Concurrency::Fixed(1),
+        timeout_secs: Some(60),
+        rate_limit_duration_secs: Some(60),
+        rate_limit_num: Some(10),
+        ..Default::default()
+    };
+}
+
+inventory::submit! {
+    SinkDescription::new::<SmtpSinkConfig>("smtp")
+}
+
+impl GenerateConfig for SmtpSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "smtp.example.com:587"
+            from = "vector@example.com"
+            to = ["oncall@example.com"]
+            subject = "Alert from {{ host }}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "smtp")]
+impl SinkConfig for SmtpSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if self.to.is_empty() {
+            return Err(BuildError::NoRecipients.into());
+        }
+
+        let uri = self.address.parse::<http::Uri>()?;
+        let host = uri.host().ok_or(BuildError::MissingHost)?.to_string();
+        let port = uri.port_u16().ok_or(BuildError::MissingPort)?;
+        let tls = MaybeTlsSettings::Tls(TlsSettings::from_options(&self.tls)?);
+        let helo_name = match &self.helo_name {
+            Some(helo_name) => helo_name.clone(),
+            None => crate::get_hostname()?,
+        };
+        let client = SmtpClient::new(
+            host,
+            port,
+            self.security,
+            tls,
+            self.auth.clone(),
+            helo_name.clone(),
+        );
+
+        // Each event is its own email unless batching is configured, in
+        // which case a batch makes up a digest.
+        let batch = BatchSettings::default()
+            .events(1)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+
+        let service = SmtpService {
+            client: client.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+            helo_name,
+        };
+        let renderer = EmailRenderer {
+            subject: self.subject.clone(),
+            body: self.body.clone(),
+        };
+
+        let sink = request
+            .batch_sink(
+                SmtpRetryLogic,
+                service,
+                VecBuffer::new(batch.size),
+                batch.timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal smtp sink error.", %error))
+            .with_flat_map(move |event| stream::iter(renderer.render(event)).map(Ok));
+
+        let healthcheck = async move { client.healthcheck().await.map_err(Into::into) }.boxed();
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "smtp"
+    }
+}
+
+/// The subject and body rendered from an event.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Email {
+    subject: String,
+    body: String,
+}
+
+impl EncodedLength for Email {
+    fn encoded_length(&self) -> usize {
+        self.subject.len() + self.body.len()
+    }
+}
+
+struct EmailRenderer {
+    subject: Template,
+    body: Option<Template>,
+}
+
+impl EmailRenderer {
+    fn render(&self, event: Event) -> Option<Email> {
+        let subject = self
+            .subject
+            .render_string(&event)
+            .map_err(|keys| {
+                emit!(SmtpTemplateMissingKeys {
+                    field: "subject",
+                    keys: &keys
+                })
+            })
+            .ok()?;
+        let body = match &self.body {
+            Some(body) => body
+                .render_string(&event)
+                .map_err(|keys| {
+                    emit!(SmtpTemplateMissingKeys {
+                        field: "body",
+                        keys: &keys
+                    })
+                })
+                .ok()?,
+            None => serde_json::to_string_pretty(event.as_log())
+                .map_err(|error| error!(message = "Unable to encode.", %error))
+                .ok()?,
+        };
+        Some(Email { subject, body })
+    }
+}
+
+#[derive(Clone)]
+struct SmtpService {
+    client: SmtpClient,
+    from: String,
+    to: Vec<String>,
+    helo_name: String,
+}
+
+impl SmtpService {
+    /// Formats the emails of a batch as a single message, listing each of
+    /// them in the body of a digest when there are several.
+    fn format_message(&self, emails: &[Email]) -> String {
+        let (subject, body) = match emails {
+            [email] => (email.subject.clone(), email.body.clone()),
+            _ => (
+                format!("{} (and {} more)", emails[0].subject, emails.len() - 1),
+                emails
+                    .iter()
+                    .map(|email| format!("{}\n\n{}", email.subject, email.body))
+                    .collect::<Vec<_>>()
+                    .join("\n\n----------\n\n"),
+            ),
+        };
+
+        let mut message = String::new();
+        message.push_str(&format!("From: {}\n", self.from));
+        message.push_str(&format!("To: {}\n", self.to.join(", ")));
+        message.push_str(&format!("Subject: {}\n", encode_header(&subject)));
+        message.push_str(&format!("Date: {}\n", Utc::now().to_rfc2822()));
+        message.push_str(&format!(
+            "Message-ID: <{}@{}>\n",
+            uuid::Uuid::new_v4(),
+            self.helo_name
+        ));
+        message.push_str("MIME-Version: 1.0\n");
+        message.push_str("Content-Type: text/plain; charset=utf-8\n");
+        message.push_str("Content-Transfer-Encoding: base64\n\n");
+        let body = base64::encode(body.replace("\r\n", "\n").replace('\n', "\r\n"));
+        for line in body.as_bytes().chunks(76) {
+            // Base64 is ASCII, so chunks are valid UTF-8.
+            message.push_str(std::str::from_utf8(line).unwrap());
+            message.push('\n');
+        }
+        message
+    }
+}
+
+/// Makes a header value safe to send: line breaks would start new headers,
+/// and anything but ASCII needs an RFC 2047 encoded word.
+fn encode_header(value: &str) -> String {
+    let value = value.replace(|c: char| c.is_control(), " ");
+    if value.is_ascii() {
+        value
+    } else {
+        format!("=?utf-8?B?{}?=", base64::encode(value))
+    }
+}
+
+impl Service<Vec<Email>> for SmtpService {
+    type Response = ();
+    type Error = SmtpError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, emails: Vec<Email>) -> Self::Future {
+        let message = self.format_message(&emails);
+        let service = self.clone();
+        Box::pin(async move {
+            service
+                .client
+                .send(&service.from, &service.to, &message)
+                .await?;
+            emit!(SmtpEmailSent {
+                events: emails.len()
+            });
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone)]
+struct SmtpRetryLogic;
+
+impl RetryLogic for SmtpRetryLogic {
+    type Error = SmtpError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_transient()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{next_addr, trace_init};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SmtpSinkConfig>();
+    }
+
+    fn service() -> SmtpService {
+        SmtpService {
+            client: SmtpClient::new(
+                "localhost".into(),
+                25,
+                Security::None,
+                MaybeTlsSettings::Raw(()),
+                None,
+                "localhost".into(),
+            ),
+            from: "vector@example.com".into(),
+            to: vec!["a@example.com".into(), "b@example.com".into()],
+            helo_name: "localhost".into(),
+        }
+    }
+
+    fn decode_body(message: &str) -> String {
+        let (_headers, body) = message.split_at(message.find("\n\n").unwrap() + 2);
+        let body = base64::decode(body.replace('\n', "")).unwrap();
+        String::from_utf8(body).unwrap().replace("\r\n", "\n")
+    }
+
+    #[test]
+    fn formats_single_email() {
+        let message = service().format_message(&[Email {
+            subject: "Disk full\r\nBcc: evil@example.com".into(),
+            body: "Only 1% left.\nAct now.".into(),
+        }]);
+
+        assert!(message.starts_with(
+            "From: vector@example.com\nTo: a@example.com, b@example.com\nSubject: Disk full  Bcc: evil@example.com\n"
+        ));
+        assert_eq!(decode_body(&message), "Only 1% left.\nAct now.");
+    }
+
+    #[test]
+    fn formats_digest() {
+        let emails = vec![
+            Email {
+                subject: "Alert 1".into(),
+                body: "first".into(),
+            },
+            Email {
+                subject: "Alert 2".into(),
+                body: "second".into(),
+            },
+        ];
+        let message = service().format_message(&emails);
+
+        assert!(message.contains("\nSubject: Alert 1 (and 1 more)\n"));
+        assert_eq!(
+            decode_body(&message),
+            "Alert 1\n\nfirst\n\n----------\n\nAlert 2\n\nsecond"
+        );
+    }
+
+    #[test]
+    fn encodes_non_ascii_subject() {
+        assert_eq!(encode_header("Größe"), "=?utf-8?B?R3LDtsOfZQ==?=");
+    }
+
+    #[tokio::test]
+    async fn sends_email() {
+        trace_init();
+
+        let addr = next_addr();
+        let mut listener = TcpListener::bind(addr).await.unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            let mut commands = Vec::new();
+            let mut data = String::new();
+            stream
+                .get_mut()
+                .write_all(b"220 localhost ESMTP\r\n")
+                .await
+                .unwrap();
+            loop {
+                let mut line = String::new();
+                stream.read_line(&mut line).await.unwrap();
+                let line = line.trim_end().to_owned();
+                let quit = line == "QUIT";
+                let reply: &[u8] = match line.as_str() {
+                    line if line.starts_with("EHLO") => b"250-localhost\r\n250 AUTH PLAIN\r\n",
+                    line if line.starts_with("AUTH") => b"235 Authenticated\r\n",
+                    "DATA" => {
+                        stream
+                            .get_mut()
+                            .write_all(b"354 Go ahead\r\n")
+                            .await
+                            .unwrap();
+                        loop {
+                            let mut line = String::new();
+                            stream.read_line(&mut line).await.unwrap();
+                            if line == ".\r\n" {
+                                break;
+                            }
+                            data.push_str(&line);
+                        }
+                        b"250 Queued\r\n"
+                    }
+                    "QUIT" => b"221 Bye\r\n",
+                    _ => b"250 OK\r\n",
+                };
+                stream.get_mut().write_all(reply).await.unwrap();
+                commands.push(line);
+                if quit {
+                    break;
+                }
+            }
+            (commands, data)
+        });
+
+        let config: SmtpSinkConfig = toml::from_str(&format!(
+            r#"
+            address = "{}"
+            security = "opportunistic"
+            helo_name = "vector.local"
+            from = "vector@example.com"
+            to = ["oncall@example.com"]
+            subject = "Alert from {{{{ host }}}}"
+            body = "{{{{ message }}}}"
+            auth.username = "user"
+            auth.password = "pass"
+            "#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let mut event = Event::from("Disk is full");
+        event.as_mut_log().insert("host", "web-1");
+        sink.run(stream::once(async { event })).await.unwrap();
+
+        let (commands, data) = server.await.unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                "EHLO vector.local".to_owned(),
+                format!("AUTH PLAIN {}", base64::encode("\0user\0pass")),
+                "MAIL FROM:<vector@example.com>".to_owned(),
+                "RCPT TO:<oncall@example.com>".to_owned(),
+                "DATA".to_owned(),
+                "QUIT".to_owned(),
+            ]
+        );
+        let data = data.replace("\r\n", "\n");
+        assert!(data.contains("\nSubject: Alert from web-1\n"));
+        assert_eq!(decode_body(&data), "Disk is full");
+    }
+}
@@ -0,0 +1,366 @@
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::Event,
+    http::HttpClient,
+    internal_events::SnowflakeTemplateMissingKeys,
+    rusoto::{self, RegionOrEndpoint},
+    sinks::{
+        util::{
+            encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+            BatchConfig, BatchSettings, Buffer, Compression, Concurrency, PartitionBatchSink,
+            PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::{Template, TemplateError},
+};
+use chrono::Utc;
+use futures::{stream, FutureExt, SinkExt, StreamExt};
+use http::{Request, StatusCode};
+use hyper::Body;
+use lazy_static::lazy_static;
+use rusoto_core::RusotoError;
+use rusoto_s3::{HeadBucketRequest, S3Client, S3};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    convert::{TryFrom, TryInto},
+    path::PathBuf,
+    sync::Arc,
+};
+use tower::ServiceBuilder;
+use uuid::Uuid;
+
+mod auth;
+mod service;
+
+use auth::KeyPairAuth;
+use service::{SnowflakeRequest, SnowflakeRetryLogic, SnowflakeService};
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        concurrency: Concurrency::Fixed(10),
+        rate_limit_num: Some(100),
+        ..Default::default()
+    };
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SnowflakeConfig {
+    /// The account identifier, such as `xy12345.us-east-2.aws`.
+    pub account: String,
+    pub user: String,
+    pub private_key_path: PathBuf,
+    pub private_key_passphrase: Option<String>,
+    /// Overrides `https://<account>.snowflakecomputing.com`.
+    pub endpoint: Option<String>,
+    pub pipe: String,
+    pub stage: StageConfig,
+    /// The path of files below the location of the stage.
+    pub path: Option<String>,
+    #[serde(default = "Compression::gzip_default")]
+    pub compression: Compression,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub encoding: EncodingConfigWithDefault<Encoding>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+}
+
+/// The S3 location of the external stage the pipe loads from.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct StageConfig {
+    pub bucket: String,
+    /// The key prefix of the stage location, such as `snowflake/events/`.
+    pub prefix: Option<String>,
+    #[serde(flatten)]
+    pub region: RegionOrEndpoint,
+    pub assume_role: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Encoding {
+    #[derivative(Default)]
+    Ndjson,
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid {} template: {}", field, source))]
+    InvalidTemplate {
+        field: &'static str,
+        source: TemplateError,
+    },
+    #[snafu(display("Could not load the private key: {}", source))]
+    PrivateKey { source: auth::KeyError },
+}
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("Invalid AWS credentials"))]
+    InvalidCredentials,
+    #[snafu(display("Unknown bucket: {:?}", bucket))]
+    UnknownBucket { bucket: String },
+    #[snafu(display("Unknown status code from S3: {}", status))]
+    UnknownStatus { status: StatusCode },
+    #[snafu(display("Snowpipe responded with {} for pipe {:?}", status, pipe))]
+    PipeStatus { status: StatusCode, pipe: String },
+}
+
+inventory::submit! {
+    SinkDescription::new::<SnowflakeConfig>("snowflake")
+}
+
+impl GenerateConfig for SnowflakeConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"account = "xy12345.us-east-2.aws"
+            user = "vector"
+            private_key_path = "/etc/vector/snowflake_key.p8"
+            pipe = "EVENTS.PUBLIC.EVENTS_PIPE"
+            stage.bucket = "my-stage-bucket"
+            stage.region = "us-east-2""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snowflake")]
+impl SinkConfig for SnowflakeConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let pipe =
+            Template::try_from(self.pipe.as_str()).context(InvalidTemplate { field: "pipe" })?;
+        let service = self.service()?;
+        let healthcheck = healthcheck(service.clone(), pipe.clone()).boxed();
+        let sink = self.sink(service, pipe, cx)?;
+        Ok((sink, healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "snowflake"
+    }
+}
+
+impl SnowflakeConfig {
+    fn service(&self) -> crate::Result<SnowflakeService> {
+        let region = (&self.stage.region).try_into()?;
+        let credentials =
+            rusoto::AwsCredentialsProvider::new(&region, self.stage.assume_role.clone())?;
+        let s3 = S3Client::new_with(rusoto::client()?, credentials, region);
+
+        let auth = KeyPairAuth::new(
+            &self.account,
+            &self.user,
+            &self.private_key_path,
+            self.private_key_passphrase.as_deref(),
+        )
+        .context(PrivateKey)?;
+
+        Ok(SnowflakeService {
+            s3,
+            client: HttpClient::new(None)?,
+            auth: Arc::new(auth),
+            endpoint: self
+                .endpoint
+                .clone()
+                .unwrap_or_else(|| format!("https://{}.snowflakecomputing.com", self.account)),
+            bucket: self.stage.bucket.clone(),
+            prefix: self.stage.prefix.clone().unwrap_or_default(),
+        })
+    }
+
+    fn sink(
+        &self,
+        service: SnowflakeService,
+        pipe: Template,
+        cx: SinkContext,
+    ) -> crate::Result<VectorSink> {
+        let path = self.path.as_deref().unwrap_or("%F/");
+        let path = Template::try_from(path).context(InvalidTemplate { field: "path" })?;
+
+        // Snowflake loads files of 100MB to 250MB compressed most
+        // efficiently, but waiting for that much data delays loading.
+        let batch = BatchSettings::default()
+            .bytes(bytesize::mib(10u64))
+            .timeout(60)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let compression = self.compression;
+        let encoding = self.encoding.clone();
+
+        let svc = ServiceBuilder::new()
+            .map(move |buffer| build_request(buffer, compression))
+            .settings(request, SnowflakeRetryLogic)
+            .service(service);
+
+        let buffer = PartitionBuffer::new(Buffer::new(batch.size, compression));
+
+        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+            .with_flat_map(move |event| {
+                stream::iter(encode_event(event, &pipe, &path, &encoding)).map(Ok)
+            })
+            .sink_map_err(|error| error!(message = "Fatal snowflake sink error.", %error));
+
+        Ok(VectorSink::Sink(Box::new(sink)))
+    }
+}
+
+/// Events are written to a file for each distinct pipe and path rendered
+/// from them.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct PartitionKey {
+    pipe: String,
+    path: String,
+}
+
+fn render(template: &Template, field: &str, event: &Event) -> Option<String> {
+    template
+        .render_string(event)
+        .map_err(|keys| emit!(SnowflakeTemplateMissingKeys { field, keys: &keys }))
+        .ok()
+}
+
+fn encode_event(
+    mut event: Event,
+    pipe: &Template,
+    path: &Template,
+    encoding: &EncodingConfigWithDefault<Encoding>,
+) -> Option<PartitionInnerBuffer<Vec<u8>, PartitionKey>> {
+    let key = PartitionKey {
+        pipe: render(pipe, "pipe", &event)?,
+        path: render(path, "path", &event)?,
+    };
+
+    encoding.apply_rules(&mut event);
+    let mut bytes = serde_json::to_vec(&event.into_log())
+        .expect("Failed to encode event as json, this is a bug!");
+    bytes.push(b'\n');
+
+    Some(PartitionInnerBuffer::new(bytes, key))
+}
+
+/// Names the file when the batch is sent, so that retries upload it again
+/// under the same name.
+fn build_request(
+    buffer: PartitionInnerBuffer<Vec<u8>, PartitionKey>,
+    compression: Compression,
+) -> SnowflakeRequest {
+    let (body, key) = buffer.into_parts();
+    let extension = match compression {
+        Compression::None => "json",
+        Compression::Gzip(_) => "json.gz",
+        Compression::Zstd(_) => "json.zst",
+        Compression::Snappy => "json.sz",
+    };
+    let path = format!(
+        "{}{}-{}.{}",
+        key.path,
+        Utc::now().timestamp(),
+        Uuid::new_v4().to_hyphenated(),
+        extension
+    );
+
+    SnowflakeRequest {
+        pipe: key.pipe,
+        path,
+        body,
+        content_encoding: compression.content_encoding(),
+    }
+}
+
+/// Checks access to the bucket, and to the pipe unless it is rendered from
+/// events.
+async fn healthcheck(service: SnowflakeService, pipe: Template) -> crate::Result<()> {
+    let head = service.s3.head_bucket(HeadBucketRequest {
+        bucket: service.bucket.clone(),
+    });
+    if let Err(error) = head.await {
+        return Err(match error {
+            RusotoError::Unknown(response) => match response.status {
+                StatusCode::FORBIDDEN => HealthcheckError::InvalidCredentials.into(),
+                StatusCode::NOT_FOUND => HealthcheckError::UnknownBucket {
+                    bucket: service.bucket,
+                }
+                .into(),
+                status => HealthcheckError::UnknownStatus { status }.into(),
+            },
+            error => error.into(),
+        });
+    }
+
+    if pipe.is_dynamic() {
+        return Ok(());
+    }
+    let pipe = pipe.get_ref().to_owned();
+    let request = service
+        .authorize(Request::get(service.pipe_uri(&pipe, "insertReport")))?
+        .body(Body::empty())?;
+    let response = service.client.send(request).await?;
+    match response.status() {
+        status if status.is_success() => Ok(()),
+        status => Err(HealthcheckError::PipeStatus { status, pipe }.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnowflakeConfig>();
+    }
+
+    #[test]
+    fn partitions_by_pipe_and_path() {
+        let pipe = Template::try_from("DB.PUBLIC.{{ table }}_PIPE").unwrap();
+        let path = Template::try_from("{{ table }}/").unwrap();
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("table", "LOGS");
+
+        let (bytes, key) = encode_event(event, &pipe, &path, &Default::default())
+            .unwrap()
+            .into_parts();
+        assert_eq!(
+            key,
+            PartitionKey {
+                pipe: "DB.PUBLIC.LOGS_PIPE".into(),
+                path: "LOGS/".into(),
+            }
+        );
+        assert!(bytes.ends_with(b"\n"));
+        let log: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(log["message"], "hello");
+
+        assert!(encode_event(Event::from("hello"), &pipe, &path, &Default::default()).is_none());
+    }
+
+    #[test]
+    fn names_files_below_path() {
+        let key = PartitionKey {
+            pipe: "DB.PUBLIC.PIPE".into(),
+            path: "2020-12-01/".into(),
+        };
+        let request = build_request(
+            PartitionInnerBuffer::new(b"{}\n".to_vec(), key),
+            Compression::gzip_default(),
+        );
+        assert_eq!(request.pipe, "DB.PUBLIC.PIPE");
+        assert!(request.path.starts_with("2020-12-01/"));
+        assert!(request.path.ends_with(".json.gz"));
+        assert_eq!(request.content_encoding, Some("gzip"));
+    }
+}
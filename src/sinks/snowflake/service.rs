@@ -0,0 +1,172 @@
+use super::auth::KeyPairAuth;
+use crate::{
+    http::{HttpClient, HttpError},
+    internal_events::SnowflakeFileQueued,
+    rusoto,
+    sinks::util::retries::RetryLogic,
+};
+use futures::future::BoxFuture;
+use http::{Request, StatusCode};
+use hyper::Body;
+use openssl::error::ErrorStack;
+use rusoto_core::RusotoError;
+use rusoto_s3::{PutObjectError, PutObjectRequest, S3Client, S3};
+use serde_json::json;
+use snafu::{ResultExt, Snafu};
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::Service;
+use tracing_futures::Instrument;
+use uuid::Uuid;
+
+#[derive(Debug, Snafu)]
+pub enum SnowflakeError {
+    #[snafu(display("Failed to upload file: {}", source))]
+    Upload { source: RusotoError<PutObjectError> },
+    #[snafu(display("Failed to sign token: {}", source))]
+    SignToken { source: ErrorStack },
+    #[snafu(display("Failed to call Snowpipe: {}", source))]
+    CallSnowpipe { source: HttpError },
+    #[snafu(display("Failed to read Snowpipe response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Snowpipe responded with {}: {}", status, body))]
+    SnowpipeStatus { status: StatusCode, body: String },
+}
+
+/// A batch of events, written to a file in the stage and queued for
+/// ingestion by the pipe.
+#[derive(Clone, Debug)]
+pub struct SnowflakeRequest {
+    pub pipe: String,
+    /// The path of the file, relative to the location of the stage.
+    pub path: String,
+    pub body: Vec<u8>,
+    pub content_encoding: Option<&'static str>,
+}
+
+#[derive(Clone)]
+pub struct SnowflakeService {
+    pub s3: S3Client,
+    pub client: HttpClient,
+    pub auth: Arc<KeyPairAuth>,
+    pub endpoint: String,
+    pub bucket: String,
+    /// The key prefix of the location of the stage in the bucket.
+    pub prefix: String,
+}
+
+impl SnowflakeService {
+    pub fn pipe_uri(&self, pipe: &str, method: &str) -> String {
+        format!(
+            "{}/v1/data/pipes/{}/{}?requestId={}",
+            self.endpoint.trim_end_matches('/'),
+            pipe,
+            method,
+            Uuid::new_v4().to_hyphenated()
+        )
+    }
+
+    pub fn authorize(
+        &self,
+        builder: http::request::Builder,
+    ) -> Result<http::request::Builder, SnowflakeError> {
+        let token = self.auth.token().context(SignToken)?;
+        Ok(builder
+            .header("Authorization", format!("Bearer {}", token))
+            .header("X-Snowflake-Authorization-Token-Type", "KEYPAIR_JWT")
+            .header("Accept", "application/json"))
+    }
+
+    async fn upload(&self, request: &SnowflakeRequest) -> Result<(), SnowflakeError> {
+        let put = PutObjectRequest {
+            body: Some(request.body.clone().into()),
+            bucket: self.bucket.clone(),
+            key: format!("{}{}", self.prefix, request.path),
+            content_encoding: request.content_encoding.map(Into::into),
+            content_type: Some("application/x-ndjson".into()),
+            ..Default::default()
+        };
+        self.s3
+            .put_object(put)
+            .instrument(info_span!("upload"))
+            .await
+            .context(Upload)?;
+        Ok(())
+    }
+
+    /// Queues the file for ingestion. Snowpipe skips files it has already
+    /// loaded, so files uploaded again by retries are only loaded once.
+    async fn insert_file(&self, request: &SnowflakeRequest) -> Result<(), SnowflakeError> {
+        let body = json!({ "files": [{ "path": request.path }] }).to_string();
+        let builder = Request::post(self.pipe_uri(&request.pipe, "insertFiles"))
+            .header("Content-Type", "application/json");
+        let http_request = self
+            .authorize(builder)?
+            .body(Body::from(body))
+            .expect("Building the request can't fail");
+
+        let response = self
+            .client
+            .send(http_request)
+            .instrument(info_span!("insert_files"))
+            .await
+            .context(CallSnowpipe)?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(ReadResponse)?;
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(SnowflakeError::SnowpipeStatus {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            })
+        }
+    }
+}
+
+impl Service<SnowflakeRequest> for SnowflakeService {
+    type Response = ();
+    type Error = SnowflakeError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: SnowflakeRequest) -> Self::Future {
+        let service = self.clone();
+        Box::pin(async move {
+            service.upload(&request).await?;
+            service.insert_file(&request).await?;
+            emit!(SnowflakeFileQueued {
+                pipe: &request.pipe,
+                path: &request.path,
+                byte_size: request.body.len(),
+            });
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SnowflakeRetryLogic;
+
+impl RetryLogic for SnowflakeRetryLogic {
+    type Error = SnowflakeError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            SnowflakeError::Upload { source } => rusoto::is_retriable_error(source),
+            SnowflakeError::SignToken { .. } => false,
+            SnowflakeError::CallSnowpipe { .. } | SnowflakeError::ReadResponse { .. } => true,
+            SnowflakeError::SnowpipeStatus { status, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+        }
+    }
+}
@@ -3,30 +3,45 @@ use crate::{
     event::{Event, LogEvent, Value},
     http::HttpClient,
     internal_events::{
-        SplunkEventEncodeError, SplunkEventSent, SplunkSourceMissingKeys,
+        SplunkEventEncodeError, SplunkEventSent, SplunkIndexMissingKeys, SplunkSourceMissingKeys,
         SplunkSourceTypeMissingKeys,
     },
     sinks::util::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpSink},
-        BatchConfig, BatchSettings, Buffer, Compression, Concurrency, TowerRequestConfig,
+        http::HttpRetryLogic,
+        retries::{RetryAction, RetryLogic},
+        BatchConfig, BatchSettings, Buffer, Compression, Concurrency, PartitionBuffer,
+        PartitionInnerBuffer, TowerRequestConfig,
     },
     template::Template,
     tls::{TlsOptions, TlsSettings},
 };
-use futures::{FutureExt, SinkExt};
+use bytes::Bytes;
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
 use http::{Request, StatusCode, Uri};
 use hyper::Body;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use snafu::{ResultExt, Snafu};
-use std::convert::TryFrom;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::Service;
 
 #[derive(Debug, Snafu)]
 pub enum BuildError {
     #[snafu(display("Host must include a scheme (https:// or http://)"))]
     UriMissingScheme,
+    #[snafu(display("At least one of `endpoint` and `endpoints` must be set"))]
+    MissingEndpoint,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
@@ -34,13 +49,19 @@ pub enum BuildError {
 pub struct HecSinkConfig {
     pub token: String,
     // Deprecated name
-    #[serde(alias = "host")]
+    #[serde(alias = "host", default)]
     pub endpoint: String,
+    /// Further endpoints, to spread requests over several indexers or heavy
+    /// forwarders.
+    #[serde(default)]
+    pub endpoints: Vec<String>,
+    #[serde(default)]
+    pub endpoint_target: EndpointTarget,
     #[serde(default = "default_host_key")]
     pub host_key: String,
     #[serde(default)]
     pub indexed_fields: Vec<String>,
-    pub index: Option<String>,
+    pub index: Option<Template>,
     pub sourcetype: Option<Template>,
     pub source: Option<Template>,
     #[serde(
@@ -54,9 +75,37 @@ pub struct HecSinkConfig {
     pub batch: BatchConfig,
     #[serde(default)]
     pub request: TowerRequestConfig,
+    #[serde(default)]
+    pub acknowledgements: HecAcknowledgementsConfig,
     pub tls: Option<TlsOptions>,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum EndpointTarget {
+    /// Sends events as JSON to `/services/collector/event`.
+    #[derivative(Default)]
+    Event,
+    /// Sends events as lines to `/services/collector/raw`, with the metadata
+    /// in the query string.
+    Raw,
+}
+
+/// Indexer acknowledgements, to only acknowledge events once Splunk has
+/// indexed them.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Derivative)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct HecAcknowledgementsConfig {
+    pub enabled: bool,
+    #[derivative(Default(value = "1"))]
+    pub poll_interval_secs: u64,
+    /// How long to wait for a batch to be indexed before sending it again.
+    #[derivative(Default(value = "30"))]
+    pub timeout_secs: u64,
+}
+
 lazy_static! {
     static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
         concurrency: Concurrency::Fixed(10),
@@ -91,7 +140,11 @@ impl SinkConfig for HecSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        validate_host(&self.endpoint)?;
+        let endpoints = self.endpoints()?;
+        for endpoint in &endpoints {
+            validate_host(endpoint)?;
+        }
+        self.compression.gzip_only()?;
 
         let batch = BatchSettings::default()
             .bytes(bytesize::mib(1u64))
@@ -101,15 +154,13 @@ impl SinkConfig for HecSinkConfig {
         let tls_settings = TlsSettings::from_options(&self.tls)?;
         let client = HttpClient::new(tls_settings)?;
 
-        let sink = BatchedHttpSink::new(
-            self.clone(),
-            Buffer::new(batch.size, self.compression),
-            request,
-            batch.timeout,
-            client.clone(),
-            cx.acker(),
-        )
-        .sink_map_err(|error| error!(message = "Fatal splunk_hec sink error.", %error));
+        let service = HecService::new(self.clone(), endpoints, client.clone());
+        let buffer = PartitionBuffer::new(Buffer::new(batch.size, self.compression));
+        let config = self.clone();
+        let sink = request
+            .partition_sink(HecRetryLogic, service, buffer, batch.timeout, cx.acker())
+            .sink_map_err(|error| error!(message = "Fatal splunk_hec sink error.", %error))
+            .with_flat_map(move |event| stream::iter(config.encode_event(event)).map(Ok));
 
         let healthcheck = healthcheck(self.clone(), client).boxed();
 
@@ -125,12 +176,31 @@ impl SinkConfig for HecSinkConfig {
     }
 }
 
-#[async_trait::async_trait]
-impl HttpSink for HecSinkConfig {
-    type Input = Vec<u8>;
-    type Output = Vec<u8>;
+/// The query string metadata of a batch sent to the raw endpoint. Batches
+/// for the event endpoint all have the default, as each event carries its
+/// own metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RawMetadata {
+    sourcetype: Option<String>,
+    source: Option<String>,
+    host: Option<String>,
+    index: Option<String>,
+}
 
-    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+impl HecSinkConfig {
+    fn endpoints(&self) -> crate::Result<Vec<String>> {
+        let endpoints = std::iter::once(&self.endpoint)
+            .filter(|endpoint| !endpoint.is_empty())
+            .chain(&self.endpoints)
+            .cloned()
+            .collect::<Vec<_>>();
+        if endpoints.is_empty() {
+            return Err(BuildError::MissingEndpoint.into());
+        }
+        Ok(endpoints)
+    }
+
+    pub fn encode_event(&self, event: Event) -> Option<PartitionInnerBuffer<Vec<u8>, RawMetadata>> {
         let sourcetype = self.sourcetype.as_ref().and_then(|sourcetype| {
             sourcetype
                 .render_string(&event)
@@ -153,10 +223,60 @@ impl HttpSink for HecSinkConfig {
                 .ok()
         });
 
-        let mut event = event.into_log();
+        let index = self.index.as_ref().and_then(|index| {
+            index
+                .render_string(&event)
+                .map_err(|missing_keys| {
+                    emit!(SplunkIndexMissingKeys {
+                        keys: &missing_keys
+                    });
+                })
+                .ok()
+        });
+
+        let event = event.into_log();
 
-        let host = event.get(self.host_key.to_owned()).cloned();
+        let host = event
+            .get(self.host_key.to_owned())
+            .map(|host| host.to_string_lossy());
+
+        let encoded = match self.endpoint_target {
+            EndpointTarget::Event => self
+                .encode_hec_event(event, host, source, sourcetype, index)
+                .map(|body| (body, RawMetadata::default())),
+            EndpointTarget::Raw => self.encode_raw_event(event).map(|line| {
+                let metadata = RawMetadata {
+                    sourcetype,
+                    source,
+                    host,
+                    index,
+                };
+                (line, metadata)
+            }),
+        };
 
+        match encoded {
+            Ok((value, metadata)) => {
+                emit!(SplunkEventSent {
+                    byte_size: value.len()
+                });
+                Some(PartitionInnerBuffer::new(value, metadata))
+            }
+            Err(e) => {
+                emit!(SplunkEventEncodeError { error: e });
+                None
+            }
+        }
+    }
+
+    fn encode_hec_event(
+        &self,
+        mut event: LogEvent,
+        host: Option<String>,
+        source: Option<String>,
+        sourcetype: Option<String>,
+        index: Option<String>,
+    ) -> Result<Vec<u8>, serde_json::Error> {
         let timestamp = match event.remove(log_schema().timestamp_key()) {
             Some(Value::Timestamp(ts)) => ts,
             _ => chrono::Utc::now(),
@@ -188,11 +308,10 @@ impl HttpSink for HecSinkConfig {
         });
 
         if let Some(host) = host {
-            let host = host.to_string_lossy();
             body["host"] = json!(host);
         }
 
-        if let Some(index) = &self.index {
+        if let Some(index) = index {
             body["index"] = json!(index);
         }
 
@@ -204,27 +323,63 @@ impl HttpSink for HecSinkConfig {
             body["sourcetype"] = json!(sourcetype);
         }
 
-        match serde_json::to_vec(&body) {
-            Ok(value) => {
-                emit!(SplunkEventSent {
-                    byte_size: value.len()
-                });
-                Some(value)
-            }
-            Err(e) => {
-                emit!(SplunkEventEncodeError { error: e });
-                None
-            }
-        }
+        serde_json::to_vec(&body)
     }
 
-    async fn build_request(&self, events: Self::Output) -> crate::Result<Request<Vec<u8>>> {
-        let uri =
-            build_uri(&self.endpoint, "/services/collector/event").expect("Unable to parse URI");
+    /// Encodes the event as a line, leaving Splunk to extract the timestamp
+    /// and fields.
+    fn encode_raw_event(&self, event: LogEvent) -> Result<Vec<u8>, serde_json::Error> {
+        let mut event = Event::Log(event);
+        self.encoding.apply_rules(&mut event);
+        let event = event.into_log();
+
+        let mut line = match self.encoding.codec() {
+            Encoding::Json => serde_json::to_vec(&event)?,
+            Encoding::Text => event
+                .get(log_schema().message_key())
+                .map(|v| v.as_bytes().to_vec())
+                .unwrap_or_default(),
+        };
+        line.push(b'\n');
+        Ok(line)
+    }
+
+    fn build_request(
+        &self,
+        endpoint: &str,
+        channel: &str,
+        events: Vec<u8>,
+        metadata: &RawMetadata,
+    ) -> crate::Result<Request<Vec<u8>>> {
+        let (path, content_type) = match self.endpoint_target {
+            EndpointTarget::Event => ("/services/collector/event".to_owned(), "application/json"),
+            EndpointTarget::Raw => {
+                let mut query = url::form_urlencoded::Serializer::new(String::new());
+                query.append_pair("channel", channel);
+                if let Some(sourcetype) = &metadata.sourcetype {
+                    query.append_pair("sourcetype", sourcetype);
+                }
+                if let Some(source) = &metadata.source {
+                    query.append_pair("source", source);
+                }
+                if let Some(host) = &metadata.host {
+                    query.append_pair("host", host);
+                }
+                if let Some(index) = &metadata.index {
+                    query.append_pair("index", index);
+                }
+                (
+                    format!("/services/collector/raw?{}", query.finish()),
+                    "text/plain",
+                )
+            }
+        };
+        let uri = build_uri(endpoint, &path).context(super::UriParseError)?;
 
         let mut builder = Request::post(uri)
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Splunk {}", self.token));
+            .header("Content-Type", content_type)
+            .header("Authorization", format!("Splunk {}", self.token))
+            .header("X-Splunk-Request-Channel", channel);
 
         if let Some(ce) = self.compression.content_encoding() {
             builder = builder.header("Content-Encoding", ce);
@@ -234,6 +389,158 @@ impl HttpSink for HecSinkConfig {
     }
 }
 
+/// Sends batches to the endpoints in turn, so that retries go to the next
+/// one, and waits for Splunk to index them when acknowledgements are enabled.
+#[derive(Clone)]
+struct HecService {
+    config: Arc<HecSinkConfig>,
+    endpoints: Arc<Vec<String>>,
+    next_endpoint: Arc<AtomicUsize>,
+    client: HttpClient,
+    /// Identifies this sink to Splunk, which tracks acknowledgements per
+    /// channel.
+    channel: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HecResponse {
+    ack_id: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct HecAckStatus {
+    acks: HashMap<u64, bool>,
+}
+
+#[derive(Debug, Snafu)]
+enum AckError {
+    #[snafu(display(
+        "Splunk didn't return an ack id, enable indexer acknowledgement on the HEC token"
+    ))]
+    MissingAckId,
+    #[snafu(display("Timed out waiting for Splunk to index ack id {}", ack_id))]
+    AckTimeout { ack_id: u64 },
+    #[snafu(display("Polling the status of ack id {} failed: {}", ack_id, source))]
+    AckPoll { ack_id: u64, source: crate::Error },
+}
+
+impl HecService {
+    fn new(config: HecSinkConfig, endpoints: Vec<String>, client: HttpClient) -> Self {
+        HecService {
+            config: Arc::new(config),
+            endpoints: Arc::new(endpoints),
+            next_endpoint: Arc::new(AtomicUsize::new(0)),
+            client,
+            channel: uuid::Uuid::new_v4().to_hyphenated().to_string(),
+        }
+    }
+
+    fn next_endpoint(&self) -> &str {
+        let index = self.next_endpoint.fetch_add(1, Ordering::Relaxed);
+        &self.endpoints[index % self.endpoints.len()]
+    }
+
+    async fn send(
+        self,
+        request: PartitionInnerBuffer<Vec<u8>, RawMetadata>,
+    ) -> crate::Result<http::Response<Bytes>> {
+        let endpoint = self.next_endpoint().to_owned();
+        let (events, metadata) = request.into_parts();
+        let request = self
+            .config
+            .build_request(&endpoint, &self.channel, events, &metadata)?
+            .map(Body::from);
+
+        let response = self.client.send(request).await?;
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body).await?;
+
+        if parts.status.is_success() && self.config.acknowledgements.enabled {
+            // Acknowledgement ids are only known to the indexer that
+            // returned them.
+            let ack_id = serde_json::from_slice::<HecResponse>(&body)
+                .ok()
+                .and_then(|response| response.ack_id)
+                .ok_or(AckError::MissingAckId)?;
+            self.await_indexed(&endpoint, ack_id).await?;
+        }
+
+        Ok(http::Response::from_parts(parts, body))
+    }
+
+    async fn await_indexed(&self, endpoint: &str, ack_id: u64) -> Result<(), AckError> {
+        let acknowledgements = self.config.acknowledgements;
+        let deadline = Instant::now() + Duration::from_secs(acknowledgements.timeout_secs);
+        loop {
+            tokio::time::delay_for(Duration::from_secs(acknowledgements.poll_interval_secs)).await;
+            let indexed = self
+                .ack_status(endpoint, ack_id)
+                .await
+                .context(AckPoll { ack_id })?;
+            if indexed {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(AckError::AckTimeout { ack_id });
+            }
+        }
+    }
+
+    async fn ack_status(&self, endpoint: &str, ack_id: u64) -> crate::Result<bool> {
+        let path = format!("/services/collector/ack?channel={}", self.channel);
+        let uri = build_uri(endpoint, &path).context(super::UriParseError)?;
+        let body = json!({ "acks": [ack_id] }).to_string();
+        let request = Request::post(uri)
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Splunk {}", self.config.token))
+            .header("X-Splunk-Request-Channel", &self.channel)
+            .body(Body::from(body))?;
+
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(super::HealthcheckError::UnexpectedStatus { status }.into());
+        }
+
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let statuses = serde_json::from_slice::<HecAckStatus>(&body)?;
+        Ok(statuses.acks.get(&ack_id).copied().unwrap_or(false))
+    }
+}
+
+impl Service<PartitionInnerBuffer<Vec<u8>, RawMetadata>> for HecService {
+    type Response = http::Response<Bytes>;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: PartitionInnerBuffer<Vec<u8>, RawMetadata>) -> Self::Future {
+        self.clone().send(request).boxed()
+    }
+}
+
+/// Retries like other HTTP sinks, and also sends batches again when Splunk
+/// doesn't confirm they were indexed.
+#[derive(Debug, Clone)]
+struct HecRetryLogic;
+
+impl RetryLogic for HecRetryLogic {
+    type Error = AckError;
+    type Response = http::Response<Bytes>;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        !matches!(error, AckError::MissingAckId)
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        HttpRetryLogic.should_retry_response(response)
+    }
+}
+
 #[derive(Debug, Snafu)]
 enum HealthcheckError {
     #[snafu(display("Invalid HEC token"))]
@@ -243,8 +550,19 @@ enum HealthcheckError {
 }
 
 pub async fn healthcheck(config: HecSinkConfig, client: HttpClient) -> crate::Result<()> {
-    let uri = build_uri(&config.endpoint, "/services/collector/health/1.0")
-        .context(super::UriParseError)?;
+    for endpoint in config.endpoints()? {
+        healthcheck_endpoint(&config, &endpoint, &client).await?;
+    }
+    Ok(())
+}
+
+async fn healthcheck_endpoint(
+    config: &HecSinkConfig,
+    endpoint: &str,
+    client: &HttpClient,
+) -> crate::Result<()> {
+    let uri =
+        build_uri(endpoint, "/services/collector/health/1.0").context(super::UriParseError)?;
 
     let request = Request::get(uri)
         .header("Authorization", format!("Splunk {}", config.token))
@@ -277,7 +595,7 @@ fn build_uri(host: &str, path: &str) -> Result<Uri, http::uri::InvalidUri> {
 mod tests {
     use super::*;
     use crate::event::Event;
-    use crate::sinks::util::{http::HttpSink, test::load_sink};
+    use crate::sinks::util::test::load_sink;
     use chrono::Utc;
     use serde::Deserialize;
     use std::collections::BTreeMap;
@@ -293,6 +611,7 @@ mod tests {
         event: BTreeMap<String, String>,
         fields: BTreeMap<String, String>,
         source: Option<String>,
+        index: Option<String>,
     }
 
     #[derive(Deserialize, Debug)]
@@ -315,6 +634,7 @@ mod tests {
             host_key = "host"
             indexed_fields = ["key"]
             source = "{{ magic }}"
+            index = "{{ magic }}_index"
 
             [encoding]
             codec = "json"
@@ -323,7 +643,7 @@ mod tests {
         )
         .unwrap();
 
-        let bytes = config.encode_event(event).unwrap();
+        let (bytes, _) = config.encode_event(event).unwrap().into_parts();
 
         let hec_event = serde_json::from_slice::<HecEventJson>(&bytes[..]).unwrap();
 
@@ -341,6 +661,7 @@ mod tests {
 
         assert!(!event.contains_key("magic"));
         assert_eq!(hec_event.source, Some("vector".to_string()));
+        assert_eq!(hec_event.index, Some("vector_index".to_string()));
 
         assert_eq!(
             hec_event.fields.get("key").map(|s| s.as_str()),
@@ -373,7 +694,7 @@ mod tests {
         )
         .unwrap();
 
-        let bytes = config.encode_event(event).unwrap();
+        let (bytes, _) = config.encode_event(event).unwrap().into_parts();
 
         let hec_event = serde_json::from_slice::<HecEventText>(&bytes[..]).unwrap();
 
@@ -410,6 +731,152 @@ mod tests {
         assert!(uri.is_ok());
         assert_eq!(format!("{}", uri.unwrap()), "http://test.com/a");
     }
+
+    #[test]
+    fn splunk_encode_event_raw() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("host", "web-1");
+        event.as_mut_log().insert("app", "api");
+
+        let (config, _cx) = load_sink::<HecSinkConfig>(
+            r#"
+            endpoint = "https://splunk.example.com:8088"
+            token = "alksjdfo"
+            endpoint_target = "raw"
+            sourcetype = "{{ app }}_logs"
+            index = "main"
+
+            [encoding]
+            codec = "text"
+        "#,
+        )
+        .unwrap();
+
+        let (line, metadata) = config.encode_event(event).unwrap().into_parts();
+        assert_eq!(line, b"hello world\n");
+        assert_eq!(
+            metadata,
+            RawMetadata {
+                sourcetype: Some("api_logs".into()),
+                source: None,
+                host: Some("web-1".into()),
+                index: Some("main".into()),
+            }
+        );
+
+        let request = config
+            .build_request(&config.endpoint, "some-channel", line, &metadata)
+            .unwrap();
+        assert_eq!(
+            request.uri().to_string(),
+            "https://splunk.example.com:8088/services/collector/raw?channel=some-channel&sourcetype=api_logs&host=web-1&index=main"
+        );
+        assert_eq!(
+            request.headers()["X-Splunk-Request-Channel"],
+            "some-channel"
+        );
+    }
+
+    #[test]
+    fn splunk_combines_endpoints() {
+        let (config, _cx) = load_sink::<HecSinkConfig>(
+            r#"
+            endpoint = "https://splunk-1:8088"
+            endpoints = ["https://splunk-2:8088"]
+            token = "alksjdfo"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.endpoints().unwrap(),
+            vec!["https://splunk-1:8088", "https://splunk-2:8088"]
+        );
+
+        let (config, _cx) = load_sink::<HecSinkConfig>(r#"token = "alksjdfo""#).unwrap();
+        assert!(config.endpoints().is_err());
+    }
+
+    /// Serves the event and ack endpoints, reporting the ack id as indexed
+    /// on the second poll, and records the paths requested.
+    async fn acknowledging_server() -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        use hyper::{
+            service::{make_service_fn, service_fn},
+            Response, Server,
+        };
+        use std::convert::Infallible;
+
+        let addr = crate::test_util::next_addr();
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = Arc::clone(&requests);
+        let make_svc = make_service_fn(move |_| {
+            let log = Arc::clone(&log);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    let log = Arc::clone(&log);
+                    async move {
+                        let path = req.uri().path().to_owned();
+                        let polls = {
+                            let mut log = log.lock().unwrap();
+                            log.push(path.clone());
+                            log.iter().filter(|p| p.ends_with("/ack")).count()
+                        };
+                        let body = if path.ends_with("/ack") {
+                            json!({ "acks": { "3": polls >= 2 } })
+                        } else {
+                            json!({ "text": "Success", "code": 0, "ackId": 3 })
+                        };
+                        Ok::<_, Infallible>(Response::new(Body::from(body.to_string())))
+                    }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(make_svc));
+        (format!("http://{}", addr), requests)
+    }
+
+    #[tokio::test]
+    async fn splunk_waits_for_indexer_acknowledgement() {
+        let (endpoint, requests) = acknowledging_server().await;
+        let (config, _cx) = load_sink::<HecSinkConfig>(&format!(
+            r#"
+            endpoint = "{}"
+            token = "alksjdfo"
+            acknowledgements.enabled = true
+            acknowledgements.poll_interval_secs = 0
+        "#,
+            endpoint
+        ))
+        .unwrap();
+        let endpoints = config.endpoints().unwrap();
+        let service = HecService::new(config, endpoints, HttpClient::new(None).unwrap());
+
+        let body = PartitionInnerBuffer::new(b"{}".to_vec(), RawMetadata::default());
+        let response = service.send(body).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![
+                "/services/collector/event",
+                "/services/collector/ack",
+                "/services/collector/ack"
+            ]
+        );
+    }
+
+    #[test]
+    fn splunk_balances_endpoints() {
+        let endpoints = vec!["http://a".to_owned(), "http://b".to_owned()];
+        let service = HecService::new(
+            HecSinkConfig::default(),
+            endpoints,
+            HttpClient::new(None).unwrap(),
+        );
+
+        let picked = (0..4)
+            .map(|_| service.next_endpoint().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(picked, vec!["http://a", "http://b", "http://a", "http://b"]);
+    }
 }
 
 #[cfg(test)]
@@ -489,7 +956,7 @@ mod integration_tests {
         let cx = SinkContext::new_test();
 
         let mut config = config(Encoding::Text, vec![]).await;
-        config.index = Some("custom_index".to_string());
+        config.index = Template::try_from("custom_index").ok();
         let (sink, _) = config.build(cx).await.unwrap();
 
         let message = random_string(100);
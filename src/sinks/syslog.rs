@@ -0,0 +1,527 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, Value},
+    internal_events::{SyslogHeaderMissingKeys, SyslogInvalidPriority},
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        tcp::TcpSinkConfig,
+        udp::UdpSinkConfig,
+        Encoding,
+    },
+    template::Template,
+};
+use bytes::Bytes;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+#[derive(Deserialize, Serialize, Debug)]
+// TODO: add back when serde-rs/serde#1358 is addressed
+// #[serde(deny_unknown_fields)]
+pub struct SyslogSinkConfig {
+    #[serde(flatten)]
+    pub mode: Mode,
+    #[serde(default)]
+    pub format: SyslogFormat,
+    #[serde(default)]
+    pub framing: Framing,
+    pub facility: Option<Template>,
+    pub severity: Option<Template>,
+    pub app_name: Option<Template>,
+    pub proc_id: Option<Template>,
+    pub msg_id: Option<Template>,
+    pub structured_data_key: Option<String>,
+    pub encoding: EncodingConfig<Encoding>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Mode {
+    Tcp(TcpSinkConfig),
+    Udp(UdpSinkConfig),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyslogFormat {
+    Rfc5424,
+    Rfc3164,
+}
+
+impl Default for SyslogFormat {
+    fn default() -> Self {
+        SyslogFormat::Rfc5424
+    }
+}
+
+/// How messages are delimited on stream transports, as described in RFC 6587.
+/// Datagrams always carry a single message and are never framed.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Framing {
+    OctetCounting,
+    NewlineDelimited,
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Framing::OctetCounting
+    }
+}
+
+inventory::submit! {
+    SinkDescription::new::<SyslogSinkConfig>("syslog")
+}
+
+impl GenerateConfig for SyslogSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "127.0.0.1:6514"
+            mode = "tcp"
+            encoding.codec = "text""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "syslog")]
+impl SinkConfig for SyslogSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let framing = match self.mode {
+            Mode::Tcp(_) => Some(self.framing),
+            Mode::Udp(_) => None,
+        };
+        let encoder = SyslogEncoder {
+            format: self.format,
+            framing,
+            facility: self.facility.clone(),
+            severity: self.severity.clone(),
+            app_name: self.app_name.clone(),
+            proc_id: self.proc_id.clone(),
+            msg_id: self.msg_id.clone(),
+            structured_data_key: self.structured_data_key.clone(),
+            encoding: self.encoding.clone(),
+        };
+        let encode_event = move |event| encoder.encode_event(event);
+        match &self.mode {
+            Mode::Tcp(config) => config.build(cx, encode_event),
+            Mode::Udp(config) => config.build(cx, encode_event),
+        }
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "syslog"
+    }
+}
+
+const DEFAULT_FACILITY: u8 = 1; // user
+const DEFAULT_SEVERITY: u8 = 6; // informational
+const DEFAULT_APP_NAME: &str = "vector";
+
+const FACILITIES: [&str; 24] = [
+    "kern",
+    "user",
+    "mail",
+    "daemon",
+    "auth",
+    "syslog",
+    "lpr",
+    "news",
+    "uucp",
+    "cron",
+    "authpriv",
+    "ftp",
+    "ntp",
+    "security",
+    "console",
+    "solaris-cron",
+    "local0",
+    "local1",
+    "local2",
+    "local3",
+    "local4",
+    "local5",
+    "local6",
+    "local7",
+];
+
+fn parse_facility(value: &str) -> Option<u8> {
+    parse_code(value, FACILITIES.len()).or_else(|| {
+        FACILITIES
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+            .map(|code| code as u8)
+    })
+}
+
+fn parse_severity(value: &str) -> Option<u8> {
+    parse_code(value, 8).or_else(|| {
+        let code = match value.to_ascii_lowercase().as_str() {
+            "emerg" | "emergency" | "panic" => 0,
+            "alert" => 1,
+            "crit" | "critical" => 2,
+            "err" | "error" => 3,
+            "warning" | "warn" => 4,
+            "notice" => 5,
+            "info" | "informational" => 6,
+            "debug" => 7,
+            _ => return None,
+        };
+        Some(code)
+    })
+}
+
+fn parse_code(value: &str, count: usize) -> Option<u8> {
+    value
+        .parse::<u8>()
+        .ok()
+        .filter(|code| (*code as usize) < count)
+}
+
+#[derive(Clone)]
+struct SyslogEncoder {
+    format: SyslogFormat,
+    framing: Option<Framing>,
+    facility: Option<Template>,
+    severity: Option<Template>,
+    app_name: Option<Template>,
+    proc_id: Option<Template>,
+    msg_id: Option<Template>,
+    structured_data_key: Option<String>,
+    encoding: EncodingConfig<Encoding>,
+}
+
+impl SyslogEncoder {
+    fn encode_event(&self, mut event: Event) -> Option<Bytes> {
+        let facility = self.render_priority("facility", &self.facility, &event, parse_facility);
+        let severity = self.render_priority("severity", &self.severity, &event, parse_severity);
+        let priority =
+            facility.unwrap_or(DEFAULT_FACILITY) * 8 + severity.unwrap_or(DEFAULT_SEVERITY);
+
+        let app_name = self.render_header("app_name", &self.app_name, &event);
+        let proc_id = self.render_header("proc_id", &self.proc_id, &event);
+        let msg_id = self.render_header("msg_id", &self.msg_id, &event);
+
+        let log = event.as_mut_log();
+        let timestamp = log
+            .get(log_schema().timestamp_key())
+            .and_then(Value::as_timestamp)
+            .cloned()
+            .unwrap_or_else(Utc::now);
+        let hostname = log
+            .get(log_schema().host_key())
+            .map(|host| host.to_string_lossy());
+        let structured_data = self
+            .structured_data_key
+            .as_ref()
+            .and_then(|key| log.remove(key));
+
+        self.encoding.apply_rules(&mut event);
+        let log = event.into_log();
+        let message = match self.encoding.codec() {
+            Encoding::Json => serde_json::to_string(&log).ok()?,
+            Encoding::Text => log
+                .get(log_schema().message_key())
+                .map(|v| v.to_string_lossy())
+                .unwrap_or_default(),
+        };
+
+        let header = Header {
+            priority,
+            timestamp,
+            hostname,
+            app_name: app_name.unwrap_or_else(|| DEFAULT_APP_NAME.into()),
+            proc_id,
+            msg_id,
+        };
+        let line = match self.format {
+            SyslogFormat::Rfc5424 => encode_rfc5424(&header, structured_data.as_ref(), &message),
+            SyslogFormat::Rfc3164 => encode_rfc3164(&header, &message),
+        };
+
+        let framed = match self.framing {
+            Some(Framing::OctetCounting) => format!("{} {}", line.len(), line),
+            Some(Framing::NewlineDelimited) => line + "\n",
+            None => line,
+        };
+        Some(framed.into())
+    }
+
+    fn render_priority(
+        &self,
+        field: &'static str,
+        template: &Option<Template>,
+        event: &Event,
+        parse: fn(&str) -> Option<u8>,
+    ) -> Option<u8> {
+        let value = self.render_header(field, template, event)?;
+        let code = parse(&value);
+        if code.is_none() {
+            emit!(SyslogInvalidPriority {
+                field,
+                value: &value,
+            });
+        }
+        code
+    }
+
+    fn render_header(
+        &self,
+        field: &'static str,
+        template: &Option<Template>,
+        event: &Event,
+    ) -> Option<String> {
+        template
+            .as_ref()?
+            .render_string(event)
+            .map_err(|keys| emit!(SyslogHeaderMissingKeys { field, keys: &keys }))
+            .ok()
+    }
+}
+
+struct Header {
+    priority: u8,
+    timestamp: DateTime<Utc>,
+    hostname: Option<String>,
+    app_name: String,
+    proc_id: Option<String>,
+    msg_id: Option<String>,
+}
+
+fn encode_rfc5424(header: &Header, structured_data: Option<&Value>, message: &str) -> String {
+    format!(
+        "<{}>1 {} {} {} {} {} {} {}",
+        header.priority,
+        header
+            .timestamp
+            .to_rfc3339_opts(SecondsFormat::Micros, true),
+        header_field(header.hostname.as_deref(), 255),
+        header_field(Some(header.app_name.as_str()), 48),
+        header_field(header.proc_id.as_deref(), 128),
+        header_field(header.msg_id.as_deref(), 32),
+        encode_structured_data(structured_data),
+        message
+    )
+}
+
+fn encode_rfc3164(header: &Header, message: &str) -> String {
+    let mut tag = header_field(Some(header.app_name.as_str()), 32);
+    if let Some(proc_id) = &header.proc_id {
+        write!(tag, "[{}]", header_field(Some(proc_id.as_str()), 128)).unwrap();
+    }
+    format!(
+        "<{}>{} {} {}: {}",
+        header.priority,
+        header.timestamp.format("%b %e %H:%M:%S"),
+        header_field(header.hostname.as_deref(), 255),
+        tag,
+        message
+    )
+}
+
+/// Header fields are limited to printable US-ASCII, so anything else is
+/// replaced, and an absent or empty value is written as the nil value `-`.
+fn header_field(value: Option<&str>, max_len: usize) -> String {
+    let value: String = value
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+        .take(max_len)
+        .collect();
+    if value.is_empty() {
+        "-".into()
+    } else {
+        value
+    }
+}
+
+/// SD-IDs and parameter names additionally can't contain `=`, `]` or `"`.
+fn sd_name(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '=' | ']' | '"' => '_',
+            c if c.is_ascii_graphic() => c,
+            _ => '_',
+        })
+        .take(32)
+        .collect()
+}
+
+/// Encodes a map of SD-IDs to maps of parameters, such as
+/// `{"exampleSDID@32473": {"iut": "3"}}`, as `[exampleSDID@32473 iut="3"]`.
+/// Elements that aren't maps themselves are skipped.
+fn encode_structured_data(structured_data: Option<&Value>) -> String {
+    let elements = match structured_data {
+        Some(Value::Map(elements)) => elements,
+        _ => return "-".into(),
+    };
+
+    let mut encoded = String::new();
+    for (id, params) in elements {
+        let params = match params {
+            Value::Map(params) => params,
+            _ => continue,
+        };
+        write!(encoded, "[{}", sd_name(id)).unwrap();
+        for (name, value) in params {
+            let value = value
+                .to_string_lossy()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace(']', "\\]");
+            write!(encoded, " {}=\"{}\"", sd_name(name), value).unwrap();
+        }
+        encoded.push(']');
+    }
+
+    if encoded.is_empty() {
+        "-".into()
+    } else {
+        encoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{next_addr, trace_init};
+    use chrono::offset::TimeZone;
+    use futures::stream;
+    use std::{convert::TryFrom, future::ready, net::UdpSocket};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SyslogSinkConfig>();
+    }
+
+    fn encoder(format: SyslogFormat, framing: Option<Framing>) -> SyslogEncoder {
+        SyslogEncoder {
+            format,
+            framing,
+            facility: Some(Template::try_from("{{ facility }}").unwrap()),
+            severity: Some(Template::try_from("{{ level }}").unwrap()),
+            app_name: Some(Template::try_from("{{ app }}").unwrap()),
+            proc_id: Some(Template::try_from("1234").unwrap()),
+            msg_id: None,
+            structured_data_key: Some("sd".into()),
+            encoding: Encoding::Text.into(),
+        }
+    }
+
+    fn event() -> Event {
+        let mut event = Event::from("hello world");
+        let log = event.as_mut_log();
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.ymd(2020, 12, 1).and_hms_micro(10, 20, 30, 400),
+        );
+        log.insert(log_schema().host_key(), "web-1");
+        log.insert("facility", "local4");
+        log.insert("level", "err");
+        log.insert("app", "api");
+        event
+    }
+
+    fn encode(encoder: &SyslogEncoder, event: Event) -> String {
+        String::from_utf8(encoder.encode_event(event).unwrap().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn encodes_rfc5424() {
+        let mut event = event();
+        event.as_mut_log().insert("sd.origin@1.ip", "10.0.0.1");
+        event.as_mut_log().insert("sd.meta@1.path", r#"a"b]c\d"#);
+
+        let line = encode(&encoder(SyslogFormat::Rfc5424, None), event);
+
+        assert_eq!(
+            line,
+            r#"<163>1 2020-12-01T10:20:30.000400Z web-1 api 1234 - [meta@1 path="a\"b\]c\\d"][origin@1 ip="10.0.0.1"] hello world"#
+        );
+    }
+
+    #[test]
+    fn encodes_rfc3164() {
+        let line = encode(&encoder(SyslogFormat::Rfc3164, None), event());
+
+        assert_eq!(line, "<163>Dec  1 10:20:30 web-1 api[1234]: hello world");
+    }
+
+    #[test]
+    fn falls_back_to_defaults() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("facility", "nope");
+
+        let line = encode(&encoder(SyslogFormat::Rfc5424, None), event);
+
+        assert!(line.starts_with("<14>1 "), "{}", line);
+        assert!(line.ends_with(" - vector 1234 - - hello world"), "{}", line);
+    }
+
+    #[test]
+    fn accepts_numeric_priorities() {
+        assert_eq!(parse_facility("23"), Some(23));
+        assert_eq!(parse_facility("24"), None);
+        assert_eq!(parse_severity("WARNING"), Some(4));
+        assert_eq!(parse_severity("8"), None);
+    }
+
+    #[test]
+    fn sanitizes_header_fields() {
+        assert_eq!(header_field(Some("my app"), 48), "my_app");
+        assert_eq!(header_field(Some(""), 48), "-");
+        assert_eq!(header_field(Some("a".repeat(40).as_str()), 32).len(), 32);
+    }
+
+    #[test]
+    fn frames_messages() {
+        let line = encode(
+            &encoder(SyslogFormat::Rfc3164, Some(Framing::OctetCounting)),
+            event(),
+        );
+        assert_eq!(line, "49 <163>Dec  1 10:20:30 web-1 api[1234]: hello world");
+
+        let line = encode(
+            &encoder(SyslogFormat::Rfc3164, Some(Framing::NewlineDelimited)),
+            event(),
+        );
+        assert_eq!(line, "<163>Dec  1 10:20:30 web-1 api[1234]: hello world\n");
+    }
+
+    #[tokio::test]
+    async fn udp_datagrams() {
+        trace_init();
+
+        let addr = next_addr();
+        let receiver = UdpSocket::bind(addr).unwrap();
+
+        let config: SyslogSinkConfig = toml::from_str(&format!(
+            r#"mode = "udp"
+            address = "{}"
+            format = "rfc3164"
+            app_name = "{{{{ app }}}}"
+            encoding.codec = "text""#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        sink.run(stream::once(ready(event()))).await.unwrap();
+
+        let mut buf = [0; 256];
+        let (size, _src_addr) = receiver
+            .recv_from(&mut buf)
+            .expect("Did not receive message");
+        let packet = String::from_utf8(buf[..size].to_vec()).unwrap();
+        assert_eq!(packet, "<14>Dec  1 10:20:30 web-1 api: hello world");
+    }
+}
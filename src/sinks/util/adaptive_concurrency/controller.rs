@@ -58,9 +58,9 @@ impl<L> Controller<L> {
     ) -> Self {
         // If a `concurrency` is specified, it becomes both the
         // current limit and the maximum, effectively bypassing all the
-        // mechanisms. Otherwise, the current limit is set to 1 and the
-        // maximum to MAX_CONCURRENCY.
-        let current_limit = concurrency.unwrap_or(1);
+        // mechanisms. Otherwise, the current limit starts at the
+        // initial concurrency of the settings.
+        let current_limit = concurrency.unwrap_or_else(|| settings.limits().0);
         Self {
             semaphore: Arc::new(ShrinkableSemaphore::new(current_limit)),
             concurrency,
@@ -191,7 +191,7 @@ impl<L> Controller<L> {
         // concurrency limit. Note that we only check this if we had
         // requests to go beyond the current limit to prevent
         // increasing the limit beyond what we have evidence for.
-        if inner.current_limit < super::MAX_CONCURRENCY
+        if inner.current_limit < self.settings.limits().1
             && inner.reached_limit
             && !inner.had_back_pressure
             && current_rtt.is_some()
@@ -337,6 +337,24 @@ mod tests {
         assert_eq!(mean.sum, 6.0);
     }
 
+    #[test]
+    fn starts_at_initial_concurrency() {
+        let limit = |initial_concurrency, max_concurrency| {
+            let settings = AdaptiveConcurrencySettings {
+                initial_concurrency,
+                max_concurrency,
+                ..Default::default()
+            };
+            let controller = Controller::new(None, settings, ());
+            let inner = controller.inner.lock().unwrap();
+            inner.current_limit
+        };
+        assert_eq!(limit(1, 200), 1);
+        assert_eq!(limit(10, 200), 10);
+        assert_eq!(limit(10, 5), 5);
+        assert_eq!(limit(0, 0), 1);
+    }
+
     #[test]
     fn ewma_update_works() {
         let mut mean = EWMA::new(0.5);
@@ -23,22 +23,40 @@ pub(self) fn instant_now() -> std::time::Instant {
 // values are the best balances found between competing outcomes.
 #[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize)]
 #[derivative(Default)]
+#[serde(default)]
 pub struct AdaptiveConcurrencySettings {
     // This value maintained high concurrency without holding it too
     // high under adverse conditions.
-    #[serde(default)]
     #[derivative(Default(value = "0.9"))]
     pub(super) decrease_ratio: f64,
 
     // This value achieved the best balance between quick response and
     // stability.
-    #[serde(default)]
     #[derivative(Default(value = "0.7"))]
     pub(super) ewma_alpha: f64,
 
     // This value avoided changing concurrency too aggressively when
     // there is fluctuation in the RTT measurements.
-    #[serde(default)]
     #[derivative(Default(value = "0.05"))]
     pub(super) rtt_threshold_ratio: f64,
+
+    // Starting from a single request avoids overwhelming a service
+    // before anything is known about it, but services known to handle
+    // many requests can be ramped up to faster.
+    #[derivative(Default(value = "1"))]
+    pub(super) initial_concurrency: usize,
+
+    // Bounds the limit for services whose response times don't grow
+    // with load until well after they start failing.
+    #[derivative(Default(value = "MAX_CONCURRENCY"))]
+    pub(super) max_concurrency: usize,
+}
+
+impl AdaptiveConcurrencySettings {
+    /// The bounds of the limit, keeping the initial one within the
+    /// maximum and both at least 1.
+    pub(super) fn limits(&self) -> (usize, usize) {
+        let max = self.max_concurrency.max(1);
+        (self.initial_concurrency.max(1).min(max), max)
+    }
 }
@@ -167,6 +167,7 @@ impl SinkConfig for TestConfig {
                 .get_ref()
                 .get_ref()
                 .get_ref()
+                .get_ref()
                 .controller
                 .stats,
         );
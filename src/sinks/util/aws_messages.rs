@@ -0,0 +1,97 @@
+//! Message attributes and size limits shared by the `aws_sqs` and `aws_sns`
+//! sinks, whose messages follow the same rules.
+
+use crate::event::{Event, Value};
+use indexmap::IndexMap;
+
+/// The largest a message can be, counting its body and attributes.
+pub const MAX_MESSAGE_BYTES: usize = 256 * 1024;
+/// The most attributes a message can have.
+pub const MAX_MESSAGE_ATTRIBUTES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageAttribute {
+    pub name: String,
+    pub data_type: &'static str,
+    pub value: String,
+}
+
+/// Maps the fields of the event to message attributes. Numbers are sent as
+/// `Number` attributes, anything else as `String` attributes, and missing,
+/// null or empty fields are left out, as attributes can't be empty.
+pub fn message_attributes(
+    event: &Event,
+    fields: &IndexMap<String, String>,
+) -> Vec<MessageAttribute> {
+    let log = event.as_log();
+    fields
+        .iter()
+        .filter_map(|(name, field)| {
+            let (data_type, value) = match log.get(field)? {
+                Value::Null => return None,
+                value @ Value::Integer(_) | value @ Value::Float(_) => {
+                    ("Number", value.to_string_lossy())
+                }
+                value => ("String", value.to_string_lossy()),
+            };
+            if value.is_empty() {
+                return None;
+            }
+            Some(MessageAttribute {
+                name: name.clone(),
+                data_type,
+                value,
+            })
+        })
+        .collect()
+}
+
+/// The size of a message as counted against `MAX_MESSAGE_BYTES`.
+pub fn message_size(body: &str, attributes: &[MessageAttribute]) -> usize {
+    body.len()
+        + attributes
+            .iter()
+            .map(|attribute| {
+                attribute.name.len() + attribute.data_type.len() + attribute.value.len()
+            })
+            .sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_fields_to_attributes() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("status", 404);
+        event.as_mut_log().insert("latency", 1.5);
+        event.as_mut_log().insert("user", "bob");
+        event.as_mut_log().insert("empty", "");
+        event.as_mut_log().insert("null", Value::Null);
+
+        let fields = vec!["status", "latency", "user", "empty", "null", "missing"]
+            .into_iter()
+            .map(|field| (format!("x-{}", field), field.to_owned()))
+            .collect();
+        let attributes = message_attributes(&event, &fields);
+
+        let attribute = |name: &str, data_type, value: &str| MessageAttribute {
+            name: name.into(),
+            data_type,
+            value: value.into(),
+        };
+        assert_eq!(
+            attributes,
+            vec![
+                attribute("x-status", "Number", "404"),
+                attribute("x-latency", "Number", "1.5"),
+                attribute("x-user", "String", "bob"),
+            ]
+        );
+        assert_eq!(
+            message_size("hello world", &attributes),
+            11 + (8 + 6 + 3) + (9 + 6 + 3) + (6 + 6 + 3)
+        );
+    }
+}
@@ -12,6 +12,10 @@ pub enum BatchError {
     EventsAndSize,
     #[snafu(display("This sink does not allow setting `max_bytes`"))]
     BytesNotAllowed,
+    #[snafu(display("`max_bytes` can't be larger than {} for this sink", limit))]
+    MaxBytesExceeded { limit: usize },
+    #[snafu(display("`max_events` can't be larger than {} for this sink", limit))]
+    MaxEventsExceeded { limit: usize },
 }
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
@@ -67,9 +71,14 @@ impl BatchConfig {
         &self,
         defaults: BatchSettings<T>,
     ) -> BatchSettings<T> {
+        // Only the defaults are scaled to the available memory, explicitly
+        // configured sizes are always honored.
+        let resources = crate::resources::get();
         BatchSettings {
             size: BatchSize {
-                bytes: self.max_bytes.unwrap_or(defaults.size.bytes),
+                bytes: self
+                    .max_bytes
+                    .unwrap_or_else(|| resources.scale(defaults.size.bytes)),
                 events: self.max_events.unwrap_or(defaults.size.events),
                 ..Default::default()
             },
@@ -138,6 +147,19 @@ impl<B> BatchSettings<B> {
         }
     }
 
+    /// Checks that batches are no larger than the service accepts.
+    pub fn limit(self, bytes: u64, events: usize) -> Result<Self, BatchError> {
+        if self.size.bytes > bytes as usize {
+            Err(BatchError::MaxBytesExceeded {
+                limit: bytes as usize,
+            })
+        } else if self.size.events > events {
+            Err(BatchError::MaxEventsExceeded { limit: events })
+        } else {
+            Ok(self)
+        }
+    }
+
     // Would like to use `trait From` here, but that results in
     // "conflicting implementations of trait"
     pub const fn into<B2>(self) -> BatchSettings<B2> {
@@ -1,17 +1,34 @@
+use flate2::write::GzEncoder;
 use serde::{de, ser};
-use std::fmt;
+use snafu::Snafu;
+use std::{fmt, io::Write};
 
 pub const GZIP_NONE: usize = 0;
 pub const GZIP_FAST: usize = 1;
 pub const GZIP_DEFAULT: usize = 6;
 pub const GZIP_BEST: usize = 9;
 
+pub const ZSTD_FAST: i32 = 1;
+pub const ZSTD_DEFAULT: i32 = 3;
+pub const ZSTD_BEST: i32 = 19;
+pub const ZSTD_MAX: i32 = 21;
+
 #[derive(Debug, Derivative, Copy, Clone, Eq, PartialEq)]
 #[derivative(Default)]
 pub enum Compression {
     #[derivative(Default)]
     None,
     Gzip(Option<usize>),
+    Zstd(Option<i32>),
+    /// The raw format of Snappy, without framing, as in `Content-Encoding:
+    /// snappy`. It has no levels.
+    Snappy,
+}
+
+#[derive(Debug, Snafu)]
+pub enum CompressionError {
+    #[snafu(display("This sink doesn't support {} compression", algorithm))]
+    Unsupported { algorithm: &'static str },
 }
 
 impl Compression {
@@ -19,10 +36,21 @@ impl Compression {
         Compression::Gzip(None)
     }
 
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gzip(_) => "gzip",
+            Self::Zstd(_) => "zstd",
+            Self::Snappy => "snappy",
+        }
+    }
+
     pub fn content_encoding(&self) -> Option<&'static str> {
         match self {
             Self::None => None,
             Self::Gzip(_) => Some("gzip"),
+            Self::Zstd(_) => Some("zstd"),
+            Self::Snappy => Some("snappy"),
         }
     }
 
@@ -30,27 +58,82 @@ impl Compression {
         match self {
             Self::None => "log",
             Self::Gzip(_) => "log.gz",
+            Self::Zstd(_) => "log.zst",
+            Self::Snappy => "log.sz",
+        }
+    }
+
+    /// Compresses a whole request body, for sinks that build bodies
+    /// without a `Buffer`. The body goes with `content_encoding` as its
+    /// `Content-Encoding`.
+    pub fn compress(&self, body: Vec<u8>) -> Vec<u8> {
+        match self {
+            Self::None => body,
+            Self::Gzip(level) => {
+                let level = level.unwrap_or(GZIP_DEFAULT) as u32;
+                let mut encoder = GzEncoder::new(
+                    Vec::with_capacity(body.len()),
+                    flate2::Compression::new(level),
+                );
+                encoder.write_all(&body).expect("Writing to Vec can't fail");
+                encoder.finish().expect("Writing to Vec can't fail")
+            }
+            Self::Zstd(level) => {
+                let level = level.unwrap_or(ZSTD_DEFAULT);
+                zstd::encode_all(body.as_slice(), level).expect("Writing to Vec can't fail")
+            }
+            Self::Snappy => snap::raw::Encoder::new()
+                .compress_vec(&body)
+                .expect("Bodies are far below the size limit of Snappy"),
+        }
+    }
+
+    /// Sets the gzip level used when none is configured, for sinks that
+    /// default to another level than `GZIP_DEFAULT`.
+    pub fn with_default_gzip_level(self, level: usize) -> Self {
+        match self {
+            Self::Gzip(None) => Self::Gzip(Some(level)),
+            _ => self,
+        }
+    }
+
+    /// Rejects the algorithms besides gzip, for sinks whose services only
+    /// accept gzip.
+    pub fn gzip_only(self) -> Result<Self, CompressionError> {
+        match self {
+            Self::None | Self::Gzip(_) => Ok(self),
+            _ => Err(CompressionError::Unsupported {
+                algorithm: self.algorithm(),
+            }),
         }
     }
 }
+
 impl fmt::Display for Compression {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Compression::None => write!(f, "none"),
             Compression::Gzip(ref level) => write!(f, "gzip({})", level.unwrap_or(GZIP_DEFAULT)),
+            Compression::Zstd(ref level) => write!(f, "zstd({})", level.unwrap_or(ZSTD_DEFAULT)),
+            Compression::Snappy => write!(f, "snappy"),
         }
     }
 }
 
 #[cfg(feature = "rusoto_core")]
-impl From<Compression> for rusoto_core::encoding::ContentEncoding {
-    fn from(compression: Compression) -> Self {
-        match compression {
-            Compression::None => rusoto_core::encoding::ContentEncoding::Identity,
+impl std::convert::TryFrom<Compression> for rusoto_core::encoding::ContentEncoding {
+    type Error = CompressionError;
+
+    fn try_from(compression: Compression) -> Result<Self, Self::Error> {
+        match compression.gzip_only()? {
             Compression::Gzip(level) => {
                 let level = level.unwrap_or(GZIP_DEFAULT);
-                rusoto_core::encoding::ContentEncoding::Gzip(None, level as u32)
+                Ok(rusoto_core::encoding::ContentEncoding::Gzip(
+                    None,
+                    level as u32,
+                ))
             }
+            _ => Ok(rusoto_core::encoding::ContentEncoding::Identity),
         }
     }
 }
@@ -76,6 +159,8 @@ impl<'de> de::Deserialize<'de> for Compression {
                 match s {
                     "none" => Ok(Compression::None),
                     "gzip" => Ok(Compression::gzip_default()),
+                    "zstd" => Ok(Compression::Zstd(None)),
+                    "snappy" => Ok(Compression::Snappy),
                     _ => Err(de::Error::invalid_value(de::Unexpected::Str(s), &self)),
                 }
             }
@@ -137,7 +222,37 @@ impl<'de> de::Deserialize<'de> for Compression {
                         }),
                         None => None,
                     })),
-                    algorithm => Err(de::Error::unknown_variant(algorithm, &["none", "gzip"])),
+                    "zstd" => Ok(Compression::Zstd(match level {
+                        Some(level) => Some(match level.as_str() {
+                            "fast" => ZSTD_FAST,
+                            "default" => ZSTD_DEFAULT,
+                            "best" => ZSTD_BEST,
+                            value => match value.parse::<i32>() {
+                                Ok(level) if (1..=ZSTD_MAX).contains(&level) => level,
+                                Ok(level) => {
+                                    return Err(de::Error::invalid_value(
+                                        de::Unexpected::Signed(level as i64),
+                                        &self,
+                                    ))
+                                }
+                                Err(_) => {
+                                    return Err(de::Error::invalid_value(
+                                        de::Unexpected::Str(value),
+                                        &self,
+                                    ))
+                                }
+                            },
+                        }),
+                        None => None,
+                    })),
+                    "snappy" => match level {
+                        Some(_) => Err(de::Error::unknown_field("level", &["algorithm"])),
+                        None => Ok(Compression::Snappy),
+                    },
+                    algorithm => Err(de::Error::unknown_variant(
+                        algorithm,
+                        &["none", "gzip", "zstd", "snappy"],
+                    )),
                 }
             }
         }
@@ -166,6 +281,16 @@ impl ser::Serialize for Compression {
                     level => map.serialize_entry("level", &level)?,
                 };
             }
+            Compression::Zstd(level) => {
+                map.serialize_entry("algorithm", "zstd")?;
+                match level.unwrap_or(ZSTD_DEFAULT) {
+                    ZSTD_FAST => map.serialize_entry("level", "fast")?,
+                    ZSTD_DEFAULT => map.serialize_entry("level", "default")?,
+                    ZSTD_BEST => map.serialize_entry("level", "best")?,
+                    level => map.serialize_entry("level", &level)?,
+                };
+            }
+            Compression::Snappy => map.serialize_entry("algorithm", "snappy")?,
         };
         map.end()
     }
@@ -11,7 +11,7 @@ pub mod metrics;
 pub mod partition;
 pub mod vec;
 
-pub use compression::{Compression, GZIP_FAST};
+pub use compression::{Compression, CompressionError, GZIP_FAST, ZSTD_DEFAULT};
 pub use partition::{Partition, PartitionBuffer, PartitionInnerBuffer};
 
 #[derive(Debug)]
@@ -27,6 +27,10 @@ pub struct Buffer {
 pub enum InnerBuffer {
     Plain(Vec<u8>),
     Gzip(GzEncoder<Vec<u8>>),
+    Zstd(zstd::Encoder<Vec<u8>>),
+    /// Raw Snappy compresses whole blocks, so the batch is compressed once
+    /// it's finished.
+    Snappy(Vec<u8>),
 }
 
 impl Buffer {
@@ -41,6 +45,14 @@ impl Buffer {
                     flate2::Compression::new(level as u32),
                 ))
             }
+            Compression::Zstd(level) => {
+                let level = level.unwrap_or(ZSTD_DEFAULT);
+                InnerBuffer::Zstd(
+                    zstd::Encoder::new(buffer, level)
+                        .expect("This can't fail because the inner writer is a Vec"),
+                )
+            }
+            Compression::Snappy => InnerBuffer::Snappy(buffer),
         };
         Self {
             inner,
@@ -54,19 +66,23 @@ impl Buffer {
     pub fn push(&mut self, input: &[u8]) {
         self.num_items += 1;
         match &mut self.inner {
-            InnerBuffer::Plain(inner) => {
+            InnerBuffer::Plain(inner) | InnerBuffer::Snappy(inner) => {
                 inner.extend_from_slice(input);
             }
             InnerBuffer::Gzip(inner) => {
                 inner.write_all(input).unwrap();
             }
+            InnerBuffer::Zstd(inner) => {
+                inner.write_all(input).unwrap();
+            }
         }
     }
 
     pub fn is_empty(&self) -> bool {
         match &self.inner {
-            InnerBuffer::Plain(inner) => inner.is_empty(),
+            InnerBuffer::Plain(inner) | InnerBuffer::Snappy(inner) => inner.is_empty(),
             InnerBuffer::Gzip(inner) => inner.get_ref().is_empty(),
+            InnerBuffer::Zstd(inner) => inner.get_ref().is_empty(),
         }
     }
 }
@@ -116,6 +132,10 @@ impl Batch for Buffer {
             InnerBuffer::Gzip(inner) => inner
                 .finish()
                 .expect("This can't fail because the inner writer is a Vec"),
+            InnerBuffer::Zstd(inner) => inner
+                .finish()
+                .expect("This can't fail because the inner writer is a Vec"),
+            InnerBuffer::Snappy(inner) => self.compression.compress(inner),
         }
     }
 
@@ -195,4 +215,88 @@ mod test {
         .take(100_000)
         .flatten()));
     }
+
+    #[tokio::test]
+    async fn zstd() {
+        let (acker, _) = Acker::new_for_testing();
+        let sent_requests = Arc::new(Mutex::new(Vec::new()));
+
+        let svc = tower::service_fn(|req| {
+            let sent_requests = Arc::clone(&sent_requests);
+            sent_requests.lock().unwrap().push(req);
+            future::ok::<_, std::io::Error>(())
+        });
+        let batch_size = BatchSettings::default().bytes(100_000).events(1_000).size;
+        let timeout = Duration::from_secs(0);
+
+        let buffered = BatchSink::new(
+            svc,
+            Buffer::new(batch_size, Compression::Zstd(None)),
+            timeout,
+            acker,
+        );
+
+        let line =
+            b"It's going down, I'm yelling timber, You better move, you better dance".to_vec();
+        let input = std::iter::repeat(line.clone()).take(10_000);
+
+        let _ = buffered
+            .sink_map_err(drop)
+            .send_all(&mut stream::iter(input).map(Ok))
+            .await
+            .unwrap();
+
+        let output = Arc::try_unwrap(sent_requests)
+            .unwrap()
+            .into_inner()
+            .unwrap();
+
+        assert!(output.len() > 1);
+        let decompressed = output
+            .into_iter()
+            .flat_map(|batch| zstd::decode_all(batch.as_slice()).unwrap());
+        assert!(decompressed.eq(std::iter::repeat(line).take(10_000).flatten()));
+    }
+
+    #[tokio::test]
+    async fn snappy() {
+        let (acker, _) = Acker::new_for_testing();
+        let sent_requests = Arc::new(Mutex::new(Vec::new()));
+
+        let svc = tower::service_fn(|req| {
+            let sent_requests = Arc::clone(&sent_requests);
+            sent_requests.lock().unwrap().push(req);
+            future::ok::<_, std::io::Error>(())
+        });
+        let batch_size = BatchSettings::default().bytes(100_000).events(1_000).size;
+        let timeout = Duration::from_secs(0);
+
+        let buffered = BatchSink::new(
+            svc,
+            Buffer::new(batch_size, Compression::Snappy),
+            timeout,
+            acker,
+        );
+
+        let line =
+            b"It's going down, I'm yelling timber, You better move, you better dance".to_vec();
+        let input = std::iter::repeat(line.clone()).take(10_000);
+
+        let _ = buffered
+            .sink_map_err(drop)
+            .send_all(&mut stream::iter(input).map(Ok))
+            .await
+            .unwrap();
+
+        let output = Arc::try_unwrap(sent_requests)
+            .unwrap()
+            .into_inner()
+            .unwrap();
+
+        assert!(output.len() > 1);
+        let decompressed = output
+            .into_iter()
+            .flat_map(|batch| snap::raw::Decoder::new().decompress_vec(&batch).unwrap());
+        assert!(decompressed.eq(std::iter::repeat(line).take(10_000).flatten()));
+    }
 }
@@ -0,0 +1,472 @@
+//! Stop sending requests to a failing service for a while.
+//!
+//! The breaker is closed while requests succeed. Once too many of the latest
+//! requests fail it opens, and no requests are sent until the cool-down
+//! passes. It is then half-open: a single request probes the service, and
+//! closes the breaker if it succeeds or opens it again if it fails.
+
+use super::retries::RetryLogic;
+use crate::internal_events::{
+    CircuitBreakerClosed, CircuitBreakerOpened, CircuitBreakerRequestRejected,
+};
+use futures::{future::BoxFuture, ready, FutureExt, TryFutureExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::VecDeque,
+    mem,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use tokio::time::{delay_until, Delay, Instant};
+use tower::{Layer, Service};
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(default)]
+pub struct CircuitBreakerSettings {
+    pub enabled: bool,
+
+    /// The share of failed requests among the latest `window_requests`
+    /// that opens the breaker.
+    #[derivative(Default(value = "0.5"))]
+    pub error_rate: f64,
+
+    #[derivative(Default(value = "20"))]
+    pub window_requests: usize,
+
+    #[derivative(Default(value = "30"))]
+    pub cooldown_secs: u64,
+
+    pub on_open: OnOpen,
+}
+
+/// What happens to requests while the breaker is open.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Eq, PartialEq, Serialize)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OnOpen {
+    /// Requests wait, which holds events in the buffer of the sink.
+    #[derivative(Default)]
+    Wait,
+    /// Requests fail without being sent, and their events are dropped or
+    /// sent to the `dead_letter` output of the sink.
+    Reject,
+}
+
+#[derive(Debug, Snafu)]
+pub enum CircuitBreakerError {
+    #[snafu(display("Circuit breaker is open"))]
+    Open,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CircuitBreakerLayer<L> {
+    settings: CircuitBreakerSettings,
+    logic: L,
+}
+
+impl<L> CircuitBreakerLayer<L> {
+    pub fn new(settings: CircuitBreakerSettings, logic: L) -> Self {
+        Self { settings, logic }
+    }
+}
+
+impl<S, L: RetryLogic> Layer<S> for CircuitBreakerLayer<L> {
+    type Service = CircuitBreaker<S, L>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CircuitBreaker::new(inner, self.logic.clone(), self.settings)
+    }
+}
+
+/// The state shared by all clones of the service.
+#[derive(Debug)]
+struct Breaker {
+    settings: CircuitBreakerSettings,
+    state: State,
+    /// Tasks waiting for the probe request to complete.
+    waiters: Vec<Waker>,
+}
+
+#[derive(Debug)]
+enum State {
+    /// The outcomes of the latest requests, `true` for failures.
+    Closed(VecDeque<bool>),
+    Open {
+        until: Instant,
+    },
+    HalfOpen {
+        probing: bool,
+    },
+}
+
+enum Admit {
+    Send,
+    Probe,
+    Wait(Instant),
+    Pending,
+    Reject,
+}
+
+impl Breaker {
+    fn admit(&mut self, cx: &Context<'_>) -> Admit {
+        if let State::Open { until } = self.state {
+            if Instant::now() < until {
+                return match self.settings.on_open {
+                    OnOpen::Wait => Admit::Wait(until),
+                    OnOpen::Reject => Admit::Reject,
+                };
+            }
+            self.state = State::HalfOpen { probing: false };
+        }
+
+        match &mut self.state {
+            State::Closed(_) => Admit::Send,
+            State::HalfOpen { probing } if !*probing => {
+                *probing = true;
+                Admit::Probe
+            }
+            _ => match self.settings.on_open {
+                OnOpen::Wait => {
+                    self.waiters.push(cx.waker().clone());
+                    Admit::Pending
+                }
+                OnOpen::Reject => Admit::Reject,
+            },
+        }
+    }
+
+    fn record(&mut self, failed: bool) {
+        let window = self.settings.window_requests.max(1);
+        if let State::Closed(outcomes) = &mut self.state {
+            outcomes.push_back(failed);
+            if outcomes.len() > window {
+                outcomes.pop_front();
+            }
+
+            let failures = outcomes.iter().filter(|failed| **failed).count();
+            if outcomes.len() == window
+                && failures as f64 >= self.settings.error_rate * window as f64
+            {
+                emit!(CircuitBreakerOpened {
+                    failures,
+                    requests: window,
+                    cooldown: self.cooldown(),
+                });
+                self.open();
+            }
+        }
+    }
+
+    fn finish_probe(&mut self, failed: Option<bool>) {
+        match failed {
+            Some(true) => {
+                debug!(message = "Probe request failed.");
+                self.open();
+            }
+            Some(false) => {
+                emit!(CircuitBreakerClosed);
+                self.state = State::Closed(VecDeque::new());
+            }
+            // The probe was never sent, so another request probes instead.
+            None => self.state = State::HalfOpen { probing: false },
+        }
+
+        for waker in self.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+
+    fn open(&mut self) {
+        self.state = State::Open {
+            until: Instant::now() + self.cooldown(),
+        };
+    }
+
+    fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.settings.cooldown_secs)
+    }
+}
+
+/// The claim of a clone of the service on the probe request. Dropping it
+/// unfinished lets another request probe.
+#[derive(Debug)]
+struct Probe(Option<Arc<Mutex<Breaker>>>);
+
+impl Probe {
+    fn finish(mut self, failed: bool) {
+        if let Some(breaker) = self.0.take() {
+            breaker.lock().unwrap().finish_probe(Some(failed));
+        }
+    }
+}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        if let Some(breaker) = self.0.take() {
+            breaker.lock().unwrap().finish_probe(None);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker<S, L> {
+    inner: S,
+    logic: L,
+    /// `None` when the breaker is disabled.
+    breaker: Option<Arc<Mutex<Breaker>>>,
+    delay: Option<Delay>,
+    probe: Option<Probe>,
+    reject: bool,
+}
+
+impl<S, L> CircuitBreaker<S, L> {
+    pub(crate) fn new(inner: S, logic: L, settings: CircuitBreakerSettings) -> Self {
+        let breaker = if settings.enabled {
+            Some(Arc::new(Mutex::new(Breaker {
+                settings,
+                state: State::Closed(VecDeque::new()),
+                waiters: Vec::new(),
+            })))
+        } else {
+            None
+        };
+
+        Self {
+            inner,
+            logic,
+            breaker,
+            delay: None,
+            probe: None,
+            reject: false,
+        }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S: Clone, L: Clone> Clone for CircuitBreaker<S, L> {
+    /// Clones share the state of the breaker, but not the admission of
+    /// their next request.
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            logic: self.logic.clone(),
+            breaker: self.breaker.clone(),
+            delay: None,
+            probe: None,
+            reject: false,
+        }
+    }
+}
+
+impl<S, L, Request> Service<Request> for CircuitBreaker<S, L>
+where
+    S: Service<Request>,
+    S::Error: Into<crate::Error>,
+    S::Response: Send + 'static,
+    S::Future: Send + 'static,
+    L: RetryLogic<Response = S::Response>,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<S::Response, crate::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if let Some(breaker) = &self.breaker {
+            while self.probe.is_none() && !self.reject {
+                if let Some(delay) = &mut self.delay {
+                    ready!(delay.poll_unpin(cx));
+                    self.delay = None;
+                }
+
+                match breaker.lock().unwrap().admit(cx) {
+                    Admit::Send => break,
+                    Admit::Probe => self.probe = Some(Probe(Some(Arc::clone(breaker)))),
+                    Admit::Wait(until) => self.delay = Some(delay_until(until)),
+                    Admit::Pending => return Poll::Pending,
+                    Admit::Reject => self.reject = true,
+                }
+            }
+
+            // Rejected requests never reach the inner service.
+            if self.reject {
+                return Poll::Ready(Ok(()));
+            }
+        }
+
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let breaker = match &self.breaker {
+            Some(breaker) => Arc::clone(breaker),
+            None => return self.inner.call(request).err_into().boxed(),
+        };
+
+        if mem::replace(&mut self.reject, false) {
+            emit!(CircuitBreakerRequestRejected);
+            return futures::future::err(CircuitBreakerError::Open.into()).boxed();
+        }
+
+        let probe = self.probe.take();
+        let logic = self.logic.clone();
+        let response = self.inner.call(request);
+        async move {
+            let output = response.await.map_err(Into::into);
+            let failed = match &output {
+                Ok(response) => logic.should_retry_response(response).is_retryable(),
+                Err(_) => true,
+            };
+            match probe {
+                Some(probe) => probe.finish(failed),
+                None => breaker.lock().unwrap().record(failed),
+            }
+            output
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_downcast_matches, test_util::trace_init};
+    use std::fmt;
+    use tokio::time;
+    use tokio_test::{assert_pending, assert_ready_ok};
+    use tower_test::{assert_request_eq, mock};
+
+    fn settings(on_open: OnOpen) -> CircuitBreakerSettings {
+        CircuitBreakerSettings {
+            enabled: true,
+            error_rate: 0.5,
+            window_requests: 4,
+            cooldown_secs: 10,
+            on_open,
+        }
+    }
+
+    type TestService =
+        mock::Spawn<CircuitBreaker<mock::Mock<&'static str, &'static str>, TestRetryLogic>>;
+
+    async fn send(
+        svc: &mut TestService,
+        handle: &mut mock::Handle<&'static str, &'static str>,
+        fail: bool,
+    ) {
+        assert_ready_ok!(svc.poll_ready());
+        let response = svc.call("request");
+        let sent = assert_request_eq!(handle, "request");
+        if fail {
+            sent.send_error(TestError);
+            response.await.unwrap_err();
+        } else {
+            sent.send_response("response");
+            response.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn opens_above_error_rate() {
+        trace_init();
+        time::pause();
+
+        let layer = CircuitBreakerLayer::new(settings(OnOpen::Wait), TestRetryLogic);
+        let (mut svc, mut handle) = mock::spawn_layer(layer);
+
+        // One failure among the latest four requests keeps it closed.
+        for &fail in &[true, false, false, false, true, false] {
+            send(&mut svc, &mut handle, fail).await;
+        }
+        assert_ready_ok!(svc.poll_ready());
+
+        send(&mut svc, &mut handle, true).await;
+        assert_pending!(svc.poll_ready());
+    }
+
+    #[tokio::test]
+    async fn probes_after_cooldown() {
+        trace_init();
+        time::pause();
+
+        let layer = CircuitBreakerLayer::new(settings(OnOpen::Wait), TestRetryLogic);
+        let (mut svc, mut handle) = mock::spawn_layer(layer);
+        let mut other = mock::Spawn::new(svc.get_ref().clone());
+
+        for _ in 0..4 {
+            send(&mut svc, &mut handle, true).await;
+        }
+        assert_pending!(svc.poll_ready());
+
+        time::advance(Duration::from_secs(10)).await;
+        assert_ready_ok!(svc.poll_ready());
+        assert_pending!(other.poll_ready());
+
+        // A failed probe opens the breaker again.
+        let probe = svc.call("probe");
+        assert_request_eq!(handle, "probe").send_error(TestError);
+        probe.await.unwrap_err();
+        assert!(other.is_woken());
+        assert_pending!(other.poll_ready());
+
+        time::advance(Duration::from_secs(10)).await;
+        assert_ready_ok!(other.poll_ready());
+        assert_pending!(svc.poll_ready());
+
+        let probe = other.call("probe");
+        assert_request_eq!(handle, "probe").send_response("response");
+        probe.await.unwrap();
+        assert!(svc.is_woken());
+        send(&mut svc, &mut handle, false).await;
+    }
+
+    #[tokio::test]
+    async fn rejects_while_open() {
+        trace_init();
+        time::pause();
+
+        let layer = CircuitBreakerLayer::new(settings(OnOpen::Reject), TestRetryLogic);
+        let (mut svc, mut handle) = mock::spawn_layer(layer);
+
+        for _ in 0..4 {
+            send(&mut svc, &mut handle, true).await;
+        }
+        assert_ready_ok!(svc.poll_ready());
+        assert_downcast_matches!(
+            svc.call("request").await.unwrap_err(),
+            CircuitBreakerError,
+            CircuitBreakerError::Open
+        );
+
+        time::advance(Duration::from_secs(10)).await;
+        send(&mut svc, &mut handle, false).await;
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    struct TestRetryLogic;
+
+    impl RetryLogic for TestRetryLogic {
+        type Error = TestError;
+        type Response = &'static str;
+
+        fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+            true
+        }
+    }
+
+    #[derive(Debug)]
+    struct TestError;
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "error")
+        }
+    }
+
+    impl std::error::Error for TestError {}
+}
@@ -0,0 +1,252 @@
+//! Holds on to the events of each batch until its request is done, so those
+//! the sink gives up on can be sent to its `dead_letter` output instead of
+//! being dropped.
+
+use super::{
+    batch::{Batch, BatchConfig, BatchError, BatchSettings, PushResult},
+    sink::Response,
+    Partition,
+};
+use crate::{internal_events::DeadLetterEventsSent, Event, Pipeline};
+use futures::{compat::Sink01CompatExt, future::BoxFuture, stream, SinkExt, StreamExt};
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tower::Service;
+
+/// The number of batches of failed events that may wait for the dead letter
+/// output. Past that, requests wait on it, so a slow output holds up the sink
+/// instead of the events piling up in memory.
+const BUFFER_SIZE: usize = 1;
+
+/// Creates the channel the events of failed requests are sent over.
+pub fn channel() -> (mpsc::Sender<Vec<Event>>, mpsc::Receiver<Vec<Event>>) {
+    mpsc::channel(BUFFER_SIZE)
+}
+
+/// An encoded event, along with the event itself if the sink has a dead
+/// letter output.
+#[derive(Debug)]
+pub struct DeadLetterItem<T> {
+    pub item: T,
+    pub event: Option<Event>,
+}
+
+impl<T: Partition<K>, K> Partition<K> for DeadLetterItem<T> {
+    fn partition(&self) -> K {
+        self.item.partition()
+    }
+}
+
+/// A request, along with the events it was built from.
+#[derive(Debug)]
+pub struct DeadLetterRequest<R> {
+    pub request: R,
+    pub events: Vec<Event>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DeadLetterBatch<B> {
+    inner: B,
+    events: Vec<Event>,
+}
+
+impl<B> From<B> for DeadLetterBatch<B> {
+    fn from(inner: B) -> Self {
+        Self {
+            inner,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl<B> Batch for DeadLetterBatch<B>
+where
+    B: Batch,
+{
+    type Input = DeadLetterItem<B::Input>;
+    type Output = DeadLetterRequest<B::Output>;
+
+    fn get_settings_defaults(
+        config: BatchConfig,
+        defaults: BatchSettings<Self>,
+    ) -> Result<BatchSettings<Self>, BatchError> {
+        Ok(B::get_settings_defaults(config, defaults.into())?.into())
+    }
+
+    fn push(&mut self, item: Self::Input) -> PushResult<Self::Input> {
+        let DeadLetterItem { item, event } = item;
+        match self.inner.push(item) {
+            PushResult::Overflow(item) => PushResult::Overflow(DeadLetterItem { item, event }),
+            result => {
+                self.events.extend(event);
+                result
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    fn fresh(&self) -> Self {
+        Self::from(self.inner.fresh())
+    }
+
+    fn finish(self) -> Self::Output {
+        DeadLetterRequest {
+            request: self.inner.finish(),
+            events: self.events,
+        }
+    }
+
+    fn num_items(&self) -> usize {
+        self.inner.num_items()
+    }
+}
+
+/// Passes requests on to the inner service, and the events of those that
+/// fail or are rejected on to the dead letter channel.
+#[derive(Clone, Debug)]
+pub struct DeadLetterService<S> {
+    inner: S,
+    dead_letter: mpsc::Sender<Vec<Event>>,
+}
+
+impl<S> DeadLetterService<S> {
+    pub fn new(inner: S, dead_letter: mpsc::Sender<Vec<Event>>) -> Self {
+        Self { inner, dead_letter }
+    }
+}
+
+impl<S, R> Service<DeadLetterRequest<R>> for DeadLetterService<S>
+where
+    S: Service<R>,
+    S::Response: Response + Send + 'static,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: DeadLetterRequest<R>) -> Self::Future {
+        let DeadLetterRequest { request, events } = request;
+        let mut dead_letter = self.dead_letter.clone();
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let result = future.await;
+            let delivered = matches!(&result, Ok(response) if response.is_successful());
+            if !delivered && !events.is_empty() {
+                emit!(DeadLetterEventsSent {
+                    count: events.len()
+                });
+                let _ = dead_letter.send(events).await;
+            }
+            result
+        })
+    }
+}
+
+/// Sends the events coming in over the dead letter channel to the output.
+pub async fn forward(mut events: mpsc::Receiver<Vec<Event>>, output: Pipeline) {
+    let mut output = output.sink_compat();
+    while let Some(events) = events.recv().await {
+        let mut events = stream::iter(events).map(Ok);
+        if output.send_all(&mut events).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sinks::util::VecBuffer, test_util::collect_ready};
+    use bytes::Bytes;
+    use futures::{compat::Stream01CompatExt, future};
+    use std::time::Duration;
+    use tower::{util::service_fn, ServiceExt};
+
+    #[test]
+    fn keeps_events_of_batch() {
+        let mut batch =
+            DeadLetterBatch::from(VecBuffer::new(BatchSettings::default().events(2).size));
+        let items = vec![("one", true), ("two", false), ("three", true)];
+        for (message, keep) in items {
+            let item = DeadLetterItem {
+                item: Bytes::from(message),
+                event: Some(Event::from(message)).filter(|_| keep),
+            };
+            if let PushResult::Overflow(item) = batch.push(item) {
+                assert_eq!(item.item, "three");
+                assert!(item.event.is_some());
+            }
+        }
+
+        let request = batch.finish();
+        assert_eq!(request.request, vec!["one", "two"]);
+        assert_eq!(request.events.len(), 1);
+        assert_eq!(request.events[0].as_log()["message"], "one".into());
+    }
+
+    #[tokio::test]
+    async fn sends_events_of_failed_requests() {
+        let (tx, rx) = channel();
+        let inner = service_fn(|succeed: bool| {
+            future::ready(if succeed {
+                Ok(())
+            } else {
+                Err(crate::Error::from("failed"))
+            })
+        });
+        let service = DeadLetterService::new(inner, tx);
+        for (succeed, message) in vec![(true, "sent"), (false, "failed")] {
+            let request = DeadLetterRequest {
+                request: succeed,
+                events: vec![Event::from(message)],
+            };
+            let _ = service.clone().oneshot(request).await;
+        }
+        drop(service);
+
+        let (output, output_rx) = Pipeline::new_test();
+        forward(rx, output).await;
+        let events = collect_ready(output_rx).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_log()["message"], "failed".into());
+    }
+
+    #[tokio::test]
+    async fn waits_for_slow_output() {
+        let (tx, rx) = channel();
+        let inner = service_fn(|_: ()| future::ready(Err::<(), _>(crate::Error::from("failed"))));
+        let service = DeadLetterService::new(inner, tx);
+        let (output, output_rx) = Pipeline::new_with_buffer(1, vec![]);
+        tokio::spawn(forward(rx, output));
+
+        let mut pending = None;
+        for sent in 0..10 {
+            let request = DeadLetterRequest {
+                request: (),
+                events: vec![Event::from(format!("event {}", sent))],
+            };
+            let mut call = Box::pin(service.clone().oneshot(request));
+            let timeout = tokio::time::timeout(Duration::from_millis(100), &mut call);
+            if timeout.await.is_err() {
+                pending = Some((sent, call));
+                break;
+            }
+        }
+        let (sent, call) = pending.expect("Requests should wait for the output");
+
+        let events = output_rx.compat().take(sent + 1).collect::<Vec<_>>();
+        let (result, events) = future::join(call, events).await;
+        assert!(result.is_err());
+        assert_eq!(events.len(), sent + 1);
+    }
+}
@@ -0,0 +1,142 @@
+//! Just enough of gRPC to call unary methods with the HTTP client, for sinks
+//! that would otherwise need a generated client.
+
+use bytes::{Buf, Bytes, BytesMut};
+use http::HeaderMap;
+use hyper::body::{Body, HttpBody};
+use std::fmt;
+
+/// The status a call finished with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Status {
+    pub code: u32,
+    pub message: Option<String>,
+}
+
+impl Status {
+    pub const OK: u32 = 0;
+    pub const INVALID_ARGUMENT: u32 = 3;
+    pub const ALREADY_EXISTS: u32 = 6;
+
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let code = headers.get("grpc-status")?.to_str().ok()?.parse().ok()?;
+        let message = headers
+            .get("grpc-message")
+            .and_then(|message| message.to_str().ok())
+            .map(|message| {
+                percent_encoding::percent_decode_str(message)
+                    .decode_utf8_lossy()
+                    .into_owned()
+            });
+        Some(Self { code, message })
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.code == Self::OK
+    }
+
+    /// CANCELLED, DEADLINE_EXCEEDED, RESOURCE_EXHAUSTED, ABORTED,
+    /// OUT_OF_RANGE, UNAVAILABLE and DATA_LOSS are worth retrying.
+    pub fn is_retriable(&self) -> bool {
+        matches!(self.code, 1 | 4 | 8 | 10 | 11 | 14 | 15)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "grpc-status {}: {}", self.code, message),
+            None => write!(f, "grpc-status {}", self.code),
+        }
+    }
+}
+
+/// Prefixes a message with the gRPC length-prefixed framing.
+pub fn frame(compressed: bool, message: Vec<u8>) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(message.len() + 5);
+    frame.push(compressed as u8);
+    frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&message);
+    frame
+}
+
+/// Splits the body of a response into the messages it frames. Compressed
+/// messages aren't supported, as requests never ask for them.
+pub fn unframe(mut body: Bytes) -> crate::Result<Vec<Bytes>> {
+    let mut messages = Vec::new();
+    while body.has_remaining() {
+        if body.len() < 5 {
+            return Err("Truncated gRPC message.".into());
+        }
+        if body[0] != 0 {
+            return Err("Unexpected compressed gRPC message.".into());
+        }
+        let len = u32::from_be_bytes([body[1], body[2], body[3], body[4]]) as usize;
+        body.advance(5);
+        if body.len() < len {
+            return Err("Truncated gRPC message.".into());
+        }
+        messages.push(body.split_to(len));
+    }
+    Ok(messages)
+}
+
+/// Reads the messages of a successful HTTP response, and the status the call
+/// finished with. That comes in the trailers, unless the server had nothing
+/// else to send.
+pub async fn read_response(response: http::Response<Body>) -> crate::Result<(Vec<Bytes>, Status)> {
+    let (parts, mut body) = response.into_parts();
+
+    let mut data = BytesMut::new();
+    while let Some(chunk) = body.data().await {
+        data.extend_from_slice(&chunk?);
+    }
+    let trailers = body.trailers().await?.unwrap_or_default();
+
+    let status = Status::from_headers(&trailers)
+        .or_else(|| Status::from_headers(&parts.headers))
+        .ok_or("Missing grpc-status.")?;
+    Ok((unframe(data.freeze())?, status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn frames_messages() {
+        assert_eq!(frame(true, vec![1, 2, 3]), vec![1, 0, 0, 0, 3, 1, 2, 3]);
+        assert_eq!(frame(false, vec![]), vec![0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn unframes_messages() {
+        let mut body = frame(false, vec![1, 2, 3]);
+        body.extend(frame(false, vec![]));
+
+        assert_eq!(
+            unframe(body.into()).unwrap(),
+            vec![Bytes::from(vec![1, 2, 3]), Bytes::new()]
+        );
+        assert!(unframe(vec![0, 0, 0, 0, 3, 1].into()).is_err());
+        assert!(unframe(frame(true, vec![1]).into()).is_err());
+    }
+
+    #[test]
+    fn reads_statuses() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(Status::from_headers(&headers), None);
+
+        headers.insert("grpc-status", HeaderValue::from_static("14"));
+        let status = Status::from_headers(&headers).unwrap();
+        assert!(status.is_retriable());
+        assert_eq!(status.to_string(), "grpc-status 14");
+
+        headers.insert("grpc-status", HeaderValue::from_static("3"));
+        headers.insert("grpc-message", HeaderValue::from_static("bad%20data"));
+        let status = Status::from_headers(&headers).unwrap();
+        assert!(!status.is_retriable());
+        assert_eq!(status.to_string(), "grpc-status 3: bad data");
+    }
+}
@@ -1,8 +1,10 @@
 use super::{
+    dead_letter::{self, DeadLetterBatch, DeadLetterItem, DeadLetterRequest, DeadLetterService},
     retries::{RetryAction, RetryLogic},
-    sink, Batch, Partition, TowerBatchedSink, TowerPartitionSink, TowerRequestSettings,
+    service::Svc,
+    sink, Batch, BatchSink, Partition, PartitionBatchSink, TowerRequestSettings,
 };
-use crate::{buffers::Acker, http::HttpClient, Event};
+use crate::{buffers::Acker, http::HttpClient, Event, Pipeline};
 use bytes::{Buf, Bytes};
 use futures::{future::BoxFuture, ready, Sink};
 use http::StatusCode;
@@ -17,8 +19,13 @@ use std::{
     task::{Context, Poll},
     time::Duration,
 };
+use tokio::sync::mpsc;
 use tower::Service;
 
+type HttpSvc<B, L> = DeadLetterService<
+    Svc<HttpBatchService<BoxFuture<'static, crate::Result<hyper::Request<Vec<u8>>>>, B>, L>,
+>;
+
 #[async_trait::async_trait]
 pub trait HttpSink: Send + Sync + 'static {
     type Input;
@@ -50,16 +57,12 @@ where
 {
     sink: Arc<T>,
     #[pin]
-    inner: TowerBatchedSink<
-        HttpBatchService<BoxFuture<'static, crate::Result<hyper::Request<Vec<u8>>>>, B::Output>,
-        B,
-        L,
-        B::Output,
-    >,
+    inner: BatchSink<HttpSvc<B::Output, L>, DeadLetterBatch<B>, DeadLetterRequest<B::Output>>,
     // An empty slot is needed to buffer an item where we encoded it but
     // the inner sink is applying back pressure. This trick is used in the `WithFlatMap`
     // sink combinator. https://docs.rs/futures/0.1.29/src/futures/sink/with_flat_map.rs.html#20
-    slot: Option<B::Input>,
+    slot: Option<DeadLetterItem<B::Input>>,
+    dead_letter: DeadLetter,
 }
 
 impl<T, B> BatchedHttpSink<T, B, HttpRetryLogic>
@@ -113,15 +116,25 @@ where
                 Box::pin(async move { sink.build_request(b).await })
             };
 
+        let (dead_letter, failed) = DeadLetter::new();
         let svc = HttpBatchService::new(client, request_builder);
-        let inner = request_settings.batch_sink(logic, svc, batch, batch_timeout, acker);
+        let svc = DeadLetterService::new(request_settings.service(logic, svc), failed);
+        let inner = BatchSink::new(svc, DeadLetterBatch::from(batch), batch_timeout, acker);
 
         Self {
             sink,
             inner,
             slot: None,
+            dead_letter,
         }
     }
+
+    /// Sends the events of requests that fail or are rejected to the
+    /// `dead_letter` output of the sink, if it has one.
+    pub fn with_dead_letter(mut self, output: Option<Pipeline>) -> Self {
+        self.dead_letter.connect(output);
+        self
+    }
 }
 
 impl<T, B, L> Sink<Event> for BatchedHttpSink<T, B, L>
@@ -149,9 +162,11 @@ where
         Poll::Ready(Ok(()))
     }
 
-    fn start_send(self: Pin<&mut Self>, item: Event) -> Result<(), Self::Error> {
-        if let Some(item) = self.sink.encode_event(item) {
-            *self.project().slot = Some(item);
+    fn start_send(self: Pin<&mut Self>, event: Event) -> Result<(), Self::Error> {
+        let this = self.project();
+        let kept = this.dead_letter.keep(&event);
+        if let Some(item) = this.sink.encode_event(event) {
+            *this.slot = Some(DeadLetterItem { item, event: kept });
         }
 
         Ok(())
@@ -185,14 +200,14 @@ where
 {
     sink: Arc<T>,
     #[pin]
-    inner: TowerPartitionSink<
-        HttpBatchService<BoxFuture<'static, crate::Result<hyper::Request<Vec<u8>>>>, B::Output>,
-        B,
-        L,
+    inner: PartitionBatchSink<
+        HttpSvc<B::Output, L>,
+        DeadLetterBatch<B>,
         K,
-        B::Output,
+        DeadLetterRequest<B::Output>,
     >,
-    slot: Option<B::Input>,
+    slot: Option<DeadLetterItem<B::Input>>,
+    dead_letter: DeadLetter,
 }
 
 impl<T, B, K> PartitionHttpSink<T, B, K, HttpRetryLogic>
@@ -250,15 +265,26 @@ where
                 Box::pin(async move { sink.build_request(b).await })
             };
 
+        let (dead_letter, failed) = DeadLetter::new();
         let svc = HttpBatchService::new(client, request_builder);
-        let inner = request_settings.partition_sink(logic, svc, batch, batch_timeout, acker);
+        let svc = DeadLetterService::new(request_settings.service(logic, svc), failed);
+        let inner =
+            PartitionBatchSink::new(svc, DeadLetterBatch::from(batch), batch_timeout, acker);
 
         Self {
             sink,
             inner,
             slot: None,
+            dead_letter,
         }
     }
+
+    /// Sends the events of requests that fail or are rejected to the
+    /// `dead_letter` output of the sink, if it has one.
+    pub fn with_dead_letter(mut self, output: Option<Pipeline>) -> Self {
+        self.dead_letter.connect(output);
+        self
+    }
 }
 
 impl<T, B, K, L> Sink<Event> for PartitionHttpSink<T, B, K, L>
@@ -288,9 +314,11 @@ where
         Poll::Ready(Ok(()))
     }
 
-    fn start_send(self: Pin<&mut Self>, item: Event) -> Result<(), Self::Error> {
-        if let Some(item) = self.sink.encode_event(item) {
-            *self.project().slot = Some(item);
+    fn start_send(self: Pin<&mut Self>, event: Event) -> Result<(), Self::Error> {
+        let this = self.project();
+        let kept = this.dead_letter.keep(&event);
+        if let Some(item) = this.sink.encode_event(event) {
+            *this.slot = Some(DeadLetterItem { item, event: kept });
         }
 
         Ok(())
@@ -312,6 +340,40 @@ where
     }
 }
 
+/// Keeps the events of requests when the sink has a dead letter output.
+struct DeadLetter {
+    failed: Option<mpsc::Receiver<Vec<Event>>>,
+    keep_events: bool,
+}
+
+impl DeadLetter {
+    fn new() -> (Self, mpsc::Sender<Vec<Event>>) {
+        let (tx, rx) = dead_letter::channel();
+        let dead_letter = Self {
+            failed: Some(rx),
+            keep_events: false,
+        };
+        (dead_letter, tx)
+    }
+
+    fn connect(&mut self, output: Option<Pipeline>) {
+        if let (Some(output), Some(failed)) = (output, self.failed.take()) {
+            tokio::spawn(dead_letter::forward(failed, output));
+            self.keep_events = true;
+        }
+    }
+
+    /// A copy of the event, made before encoding consumes it, if it may
+    /// need to go to the output.
+    fn keep(&self, event: &Event) -> Option<Event> {
+        if self.keep_events {
+            Some(event.clone())
+        } else {
+            None
+        }
+    }
+}
+
 pub struct HttpBatchService<F, B = Vec<u8>> {
     inner: HttpClient<Body>,
     request_builder: Arc<dyn Fn(B) -> F + Send + Sync>,
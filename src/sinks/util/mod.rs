@@ -1,8 +1,16 @@
 pub mod adaptive_concurrency;
+#[cfg(any(feature = "sinks-aws_sqs", feature = "sinks-aws_sns"))]
+pub mod aws_messages;
 pub mod batch;
 pub mod buffer;
+pub mod circuit_breaker;
+pub mod dead_letter;
 pub mod encoding;
+#[cfg(any(feature = "sinks-gcp", feature = "sinks-opentelemetry"))]
+pub mod grpc;
 pub mod http;
+#[cfg(any(feature = "sinks-aws_s3", feature = "sinks-gcp"))]
+pub mod parquet;
 pub mod retries;
 pub mod service;
 pub mod sink;
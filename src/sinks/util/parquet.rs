@@ -0,0 +1,437 @@
+//! Encoding of batches of log events as Parquet files, for the sinks writing
+//! objects to blob storage.
+
+use super::EncodedLength;
+use crate::event::{LogEvent, Value};
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use parquet::{
+    basic::{Compression, LogicalType, Repetition, Type as PhysicalType},
+    column::writer::ColumnWriter,
+    data_type::ByteArray,
+    errors::ParquetError,
+    file::{
+        properties::WriterProperties,
+        writer::{FileWriter, InMemoryWriteableCursor, RowGroupWriter, SerializedFileWriter},
+    },
+    schema::types::Type,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{collections::BTreeMap, rc::Rc};
+
+pub const DEFAULT_ROW_GROUP_SIZE: usize = 10_000;
+
+#[derive(Debug, Snafu)]
+pub enum BuildError {
+    #[snafu(display("`row_group_size` must be greater than zero"))]
+    ZeroRowGroupSize,
+    #[snafu(display("Invalid Parquet schema: {}", source))]
+    InvalidSchema { source: ParquetError },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct ParquetConfig {
+    /// The columns, in order, with the field each is read from. Inferred
+    /// from the top-level fields of each batch when empty.
+    pub schema: IndexMap<String, ColumnType>,
+    pub compression: ParquetCompression,
+    /// The most rows written to one row group.
+    #[derivative(Default(value = "DEFAULT_ROW_GROUP_SIZE"))]
+    pub row_group_size: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    /// UTF-8 text. Maps and arrays are written as JSON.
+    String,
+    Int64,
+    Double,
+    Boolean,
+    /// Milliseconds since the Unix epoch.
+    Timestamp,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum ParquetCompression {
+    None,
+    #[derivative(Default)]
+    Snappy,
+    Gzip,
+    Zstd,
+}
+
+impl From<ParquetCompression> for Compression {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::None => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Gzip => Compression::GZIP,
+            ParquetCompression::Zstd => Compression::ZSTD,
+        }
+    }
+}
+
+impl ParquetConfig {
+    pub fn build(&self) -> Result<ParquetEncoder, BuildError> {
+        if self.row_group_size == 0 {
+            return Err(BuildError::ZeroRowGroupSize);
+        }
+        if !self.schema.is_empty() {
+            build_schema(&self.schema).context(InvalidSchema)?;
+        }
+
+        Ok(ParquetEncoder {
+            schema: Some(self.schema.clone()).filter(|schema| !schema.is_empty()),
+            compression: self.compression,
+            row_group_size: self.row_group_size,
+        })
+    }
+}
+
+/// A log event waiting to be written to a Parquet file, with an estimate of
+/// the size of its values before compression.
+#[derive(Clone, Debug)]
+pub struct ParquetRow {
+    log: LogEvent,
+    size: usize,
+}
+
+impl From<LogEvent> for ParquetRow {
+    fn from(log: LogEvent) -> Self {
+        let size = log
+            .as_map()
+            .iter()
+            .map(|(name, value)| name.len() + value_size(value))
+            .sum();
+        Self { log, size }
+    }
+}
+
+impl From<ParquetRow> for LogEvent {
+    fn from(row: ParquetRow) -> Self {
+        row.log
+    }
+}
+
+impl EncodedLength for ParquetRow {
+    fn encoded_length(&self) -> usize {
+        self.size
+    }
+}
+
+fn value_size(value: &Value) -> usize {
+    match value {
+        Value::Bytes(bytes) => bytes.len(),
+        Value::Map(fields) => fields
+            .iter()
+            .map(|(name, value)| name.len() + value_size(value))
+            .sum(),
+        Value::Array(values) => values.iter().map(value_size).sum(),
+        _ => 8,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParquetEncoder {
+    schema: Option<IndexMap<String, ColumnType>>,
+    compression: ParquetCompression,
+    row_group_size: usize,
+}
+
+impl ParquetEncoder {
+    /// Writes the events to a Parquet file, converting each value to the
+    /// type of its column. Values that can't be converted are left null.
+    pub fn encode(&self, events: &[LogEvent]) -> Result<Vec<u8>, ParquetError> {
+        let columns = match &self.schema {
+            Some(columns) => columns.clone(),
+            None => infer_schema(events),
+        };
+        let schema = Rc::new(build_schema(&columns)?);
+        let properties = WriterProperties::builder()
+            .set_compression(self.compression.into())
+            .set_max_row_group_size(self.row_group_size)
+            .build();
+
+        let cursor = InMemoryWriteableCursor::default();
+        let mut writer = SerializedFileWriter::new(cursor.clone(), schema, Rc::new(properties))?;
+        for rows in events.chunks(self.row_group_size) {
+            let mut row_group = writer.next_row_group()?;
+            for (field, column_type) in &columns {
+                let mut column = row_group
+                    .next_column()?
+                    .expect("There is a column writer for every field in the schema.");
+                let values = rows.iter().map(|log| log.get(field));
+                write_column(&mut column, *column_type, values)?;
+                row_group.close_column(column)?;
+            }
+            writer.close_row_group(row_group)?;
+        }
+        writer.close()?;
+
+        Ok(cursor.data())
+    }
+}
+
+/// Infers a column from each top-level field, falling back to `String` for
+/// fields holding values of different types.
+fn infer_schema(events: &[LogEvent]) -> IndexMap<String, ColumnType> {
+    let mut columns = BTreeMap::new();
+    for log in events {
+        for (name, value) in log.as_map() {
+            let inferred = match ColumnType::of(value) {
+                Some(inferred) => inferred,
+                None => continue,
+            };
+            let column = columns.entry(name.clone()).or_insert(inferred);
+            *column = match (*column, inferred) {
+                (a, b) if a == b => a,
+                (ColumnType::Int64, ColumnType::Double)
+                | (ColumnType::Double, ColumnType::Int64) => ColumnType::Double,
+                _ => ColumnType::String,
+            };
+        }
+    }
+    columns.into_iter().collect()
+}
+
+impl ColumnType {
+    fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bytes(_) | Value::Map(_) | Value::Array(_) => Some(ColumnType::String),
+            Value::Integer(_) => Some(ColumnType::Int64),
+            Value::Float(_) => Some(ColumnType::Double),
+            Value::Boolean(_) => Some(ColumnType::Boolean),
+            Value::Timestamp(_) => Some(ColumnType::Timestamp),
+            Value::Null => None,
+        }
+    }
+}
+
+fn build_schema(columns: &IndexMap<String, ColumnType>) -> Result<Type, ParquetError> {
+    let mut fields = columns
+        .iter()
+        .map(|(name, column_type)| {
+            let (physical_type, logical_type) = match column_type {
+                ColumnType::String => (PhysicalType::BYTE_ARRAY, LogicalType::UTF8),
+                ColumnType::Int64 => (PhysicalType::INT64, LogicalType::NONE),
+                ColumnType::Double => (PhysicalType::DOUBLE, LogicalType::NONE),
+                ColumnType::Boolean => (PhysicalType::BOOLEAN, LogicalType::NONE),
+                ColumnType::Timestamp => (PhysicalType::INT64, LogicalType::TIMESTAMP_MILLIS),
+            };
+            Type::primitive_type_builder(name, physical_type)
+                .with_repetition(Repetition::OPTIONAL)
+                .with_logical_type(logical_type)
+                .build()
+                .map(Rc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Type::group_type_builder("event")
+        .with_fields(&mut fields)
+        .build()
+}
+
+fn write_column<'a>(
+    column: &mut ColumnWriter,
+    column_type: ColumnType,
+    values: impl Iterator<Item = Option<&'a Value>>,
+) -> Result<(), ParquetError> {
+    match (column, column_type) {
+        (ColumnWriter::ByteArrayColumnWriter(writer), ColumnType::String) => {
+            let (values, levels) = definition_levels(values, to_byte_array);
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        (ColumnWriter::Int64ColumnWriter(writer), ColumnType::Int64) => {
+            let (values, levels) = definition_levels(values, to_i64);
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        (ColumnWriter::Int64ColumnWriter(writer), ColumnType::Timestamp) => {
+            let (values, levels) = definition_levels(values, to_timestamp_millis);
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        (ColumnWriter::DoubleColumnWriter(writer), ColumnType::Double) => {
+            let (values, levels) = definition_levels(values, to_f64);
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        (ColumnWriter::BoolColumnWriter(writer), ColumnType::Boolean) => {
+            let (values, levels) = definition_levels(values, to_bool);
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        _ => unreachable!("Column writers are created from the same schema."),
+    }
+    Ok(())
+}
+
+/// Splits the values of an optional column into the non-null values and the
+/// definition level of every row.
+fn definition_levels<'a, T>(
+    values: impl Iterator<Item = Option<&'a Value>>,
+    convert: impl Fn(&Value) -> Option<T>,
+) -> (Vec<T>, Vec<i16>) {
+    let mut present = Vec::new();
+    let mut levels = Vec::new();
+    for value in values {
+        match value.and_then(|value| convert(value)) {
+            Some(value) => {
+                present.push(value);
+                levels.push(1);
+            }
+            None => levels.push(0),
+        }
+    }
+    (present, levels)
+}
+
+fn to_byte_array(value: &Value) -> Option<ByteArray> {
+    let bytes = match value {
+        Value::Null => return None,
+        Value::Bytes(bytes) => bytes.to_vec(),
+        Value::Map(_) | Value::Array(_) => serde_json::to_vec(value).ok()?,
+        value => value.to_string_lossy().into_bytes(),
+    };
+    Some(ByteArray::from(bytes))
+}
+
+fn to_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Integer(integer) => Some(*integer),
+        Value::Float(float) if float.fract() == 0.0 => Some(*float as i64),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(float) => Some(*float),
+        Value::Integer(integer) => Some(*integer as f64),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn to_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Boolean(boolean) => Some(*boolean),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn to_timestamp_millis(value: &Value) -> Option<i64> {
+    match value {
+        Value::Timestamp(timestamp) => Some(timestamp.timestamp_millis()),
+        Value::Bytes(bytes) => {
+            let timestamp = DateTime::parse_from_rfc3339(std::str::from_utf8(bytes).ok()?).ok()?;
+            Some(timestamp.with_timezone(&Utc).timestamp_millis())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Event;
+    use chrono::TimeZone;
+    use parquet::{
+        file::reader::{FileReader, SerializedFileReader},
+        record::RowAccessor,
+    };
+    use std::io::Cursor;
+
+    fn read(bytes: Vec<u8>) -> SerializedFileReader<Cursor<Vec<u8>>> {
+        SerializedFileReader::new(Cursor::new(bytes)).unwrap()
+    }
+
+    fn events() -> Vec<LogEvent> {
+        (0..5)
+            .map(|i| {
+                let mut event = Event::from(format!("line {}", i));
+                let log = event.as_mut_log();
+                log.insert("timestamp", Utc.timestamp(1_600_000_000 + i, 0));
+                log.insert("status", 200 + i);
+                if i % 2 == 0 {
+                    log.insert("duration", 1.5);
+                } else {
+                    log.insert("duration", 2);
+                }
+                event.into_log()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn infers_schema_from_batch() {
+        let columns = infer_schema(&events());
+        let expected: IndexMap<_, _> = vec![
+            ("duration".to_string(), ColumnType::Double),
+            ("message".to_string(), ColumnType::String),
+            ("status".to_string(), ColumnType::Int64),
+            ("timestamp".to_string(), ColumnType::Timestamp),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(columns, expected);
+    }
+
+    #[test]
+    fn writes_row_groups() {
+        let encoder = ParquetConfig {
+            row_group_size: 2,
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        let reader = read(encoder.encode(&events()).unwrap());
+
+        let metadata = reader.metadata();
+        assert_eq!(metadata.num_row_groups(), 3);
+        assert_eq!(metadata.file_metadata().num_rows(), 5);
+
+        let row = reader.get_row_iter(None).unwrap().nth(1).unwrap();
+        assert_eq!(row.get_double(0).unwrap(), 2.0);
+        assert_eq!(row.get_string(1).unwrap(), "line 1");
+        assert_eq!(row.get_long(2).unwrap(), 201);
+        assert_eq!(row.get_timestamp_millis(3).unwrap(), 1_600_000_001_000);
+    }
+
+    #[test]
+    fn converts_to_configured_schema() {
+        let encoder = ParquetConfig {
+            schema: vec![
+                ("status".to_string(), ColumnType::String),
+                ("message".to_string(), ColumnType::Int64),
+                ("missing".to_string(), ColumnType::Boolean),
+            ]
+            .into_iter()
+            .collect(),
+            compression: ParquetCompression::Zstd,
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        let reader = read(encoder.encode(&events()).unwrap());
+
+        let row = reader.get_row_iter(None).unwrap().next().unwrap();
+        assert_eq!(row.len(), 3);
+        assert_eq!(row.get_string(0).unwrap(), "200");
+        assert!(row.get_long(1).is_err());
+        assert!(row.get_bool(2).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_row_groups() {
+        let config = ParquetConfig {
+            row_group_size: 0,
+            ..Default::default()
+        };
+        assert!(matches!(config.build(), Err(BuildError::ZeroRowGroupSize)));
+    }
+}
@@ -2,6 +2,7 @@ use super::{
     adaptive_concurrency::{
         AdaptiveConcurrencyLimit, AdaptiveConcurrencyLimitLayer, AdaptiveConcurrencySettings,
     },
+    circuit_breaker::{CircuitBreaker, CircuitBreakerLayer, CircuitBreakerSettings},
     retries::{FixedRetryPolicy, RetryLogic},
     sink::Response,
     Batch, BatchSink, Partition, PartitionBatchSink,
@@ -21,7 +22,9 @@ use tower::{
     Service, ServiceBuilder,
 };
 
-pub type Svc<S, L> = RateLimit<Retry<FixedRetryPolicy<L>, AdaptiveConcurrencyLimit<Timeout<S>, L>>>;
+pub type Svc<S, L> = RateLimit<
+    Retry<FixedRetryPolicy<L>, CircuitBreaker<AdaptiveConcurrencyLimit<Timeout<S>, L>, L>>,
+>;
 pub type TowerBatchedSink<S, B, L, Request> = BatchSink<Svc<S, L>, B, Request>;
 pub type TowerPartitionSink<S, B, L, K, Request> = PartitionBatchSink<Svc<S, L>, B, K, Request>;
 
@@ -176,6 +179,8 @@ pub struct TowerRequestConfig<T: ConcurrencyOption = Concurrency> {
     pub retry_initial_backoff_secs: Option<u64>, // 1
     #[serde(default)]
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerSettings,
 }
 
 impl<T: ConcurrencyOption> TowerRequestConfig<T> {
@@ -204,6 +209,7 @@ impl<T: ConcurrencyOption> TowerRequestConfig<T> {
                     .unwrap_or(1),
             ),
             adaptive_concurrency: self.adaptive_concurrency,
+            circuit_breaker: self.circuit_breaker,
         }
     }
 }
@@ -218,6 +224,7 @@ pub struct TowerRequestSettings {
     pub retry_max_duration_secs: Duration,
     pub retry_initial_backoff_secs: Duration,
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
+    pub circuit_breaker: CircuitBreakerSettings,
 }
 
 impl TowerRequestSettings {
@@ -295,6 +302,10 @@ impl TowerRequestSettings {
         ServiceBuilder::new()
             .rate_limit(self.rate_limit_num, self.rate_limit_duration)
             .retry(policy)
+            .layer(CircuitBreakerLayer::new(
+                self.circuit_breaker,
+                retry_logic.clone(),
+            ))
             .layer(AdaptiveConcurrencyLimitLayer::new(
                 self.concurrency,
                 self.adaptive_concurrency,
@@ -333,6 +344,10 @@ where
                 self.settings.rate_limit_duration,
             )
             .retry(policy)
+            .layer(CircuitBreakerLayer::new(
+                self.settings.circuit_breaker,
+                self.retry_logic.clone(),
+            ))
             .timeout(self.settings.timeout)
             .service(inner);
 
@@ -407,6 +422,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sinks::util::circuit_breaker::OnOpen;
 
     #[test]
     fn concurrency_param_works() {
@@ -445,4 +461,33 @@ mod tests {
             .expect("Fixed concurrency failed for in_flight_limit param");
         assert_eq!(cfg.concurrency, Concurrency::Fixed(10));
     }
+
+    #[test]
+    fn adaptive_concurrency_settings_keep_defaults() {
+        type TowerRequestConfigTest = TowerRequestConfig<Concurrency>;
+
+        let cfg =
+            toml::from_str::<TowerRequestConfigTest>("adaptive_concurrency.max_concurrency = 50")
+                .unwrap();
+        assert_eq!(cfg.adaptive_concurrency.max_concurrency, 50);
+        assert_eq!(cfg.adaptive_concurrency.initial_concurrency, 1);
+        assert_eq!(cfg.adaptive_concurrency.decrease_ratio, 0.9);
+    }
+
+    #[test]
+    fn circuit_breaker_settings_keep_defaults() {
+        type TowerRequestConfigTest = TowerRequestConfig<Concurrency>;
+
+        let cfg = toml::from_str::<TowerRequestConfigTest>("").unwrap();
+        assert!(!cfg.circuit_breaker.enabled);
+
+        let cfg = toml::from_str::<TowerRequestConfigTest>(
+            r#"circuit_breaker = { enabled = true, on_open = "reject" }"#,
+        )
+        .unwrap();
+        assert!(cfg.circuit_breaker.enabled);
+        assert_eq!(cfg.circuit_breaker.on_open, OnOpen::Reject);
+        assert_eq!(cfg.circuit_breaker.window_requests, 20);
+        assert_eq!(cfg.circuit_breaker.cooldown_secs, 30);
+    }
 }
@@ -34,7 +34,7 @@ use std::{
 use tokio::{io::AsyncRead, net::TcpStream, time::delay_for};
 
 #[derive(Debug, Snafu)]
-enum TcpError {
+pub(crate) enum TcpError {
     #[snafu(display("Connect error: {}", source))]
     ConnectError { source: TlsError },
     #[snafu(display("Unable to resolve DNS: {}", source))]
@@ -78,14 +78,14 @@ impl TcpSinkConfig {
 }
 
 #[derive(Clone)]
-struct TcpConnector {
+pub(crate) struct TcpConnector {
     host: String,
     port: u16,
     tls: MaybeTlsSettings,
 }
 
 impl TcpConnector {
-    fn new(host: String, port: u16, tls: MaybeTlsSettings) -> Self {
+    pub(crate) fn new(host: String, port: u16, tls: MaybeTlsSettings) -> Self {
         Self { host, port, tls }
     }
 
@@ -96,7 +96,7 @@ impl TcpConnector {
             .max_delay(Duration::from_secs(60))
     }
 
-    async fn connect(&self) -> Result<MaybeTlsStream<TcpStream>, TcpError> {
+    pub(crate) async fn connect(&self) -> Result<MaybeTlsStream<TcpStream>, TcpError> {
         let ip = dns::Resolver
             .lookup_ip(self.host.clone())
             .await
@@ -129,7 +129,7 @@ impl TcpConnector {
         }
     }
 
-    async fn healthcheck(&self) -> crate::Result<()> {
+    pub(crate) async fn healthcheck(&self) -> crate::Result<()> {
         self.connect().await.map(|_| ()).map_err(Into::into)
     }
 }
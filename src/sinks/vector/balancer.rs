@@ -0,0 +1,489 @@
+use crate::{
+    buffers::Acker,
+    internal_events::{VectorEndpointEjected, VectorEndpointRestored, VectorSendFailed},
+    sinks::util::{
+        tcp::{TcpConnector, TcpError},
+        StreamSink,
+    },
+    tls::MaybeTlsStream,
+    Event,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{
+    future::BoxFuture,
+    stream::{self, BoxStream, FuturesUnordered},
+    FutureExt, SinkExt, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    net::TcpStream,
+    sync::mpsc::{self, error::TrySendError},
+    time::interval,
+};
+use tokio_util::codec::{BytesCodec, FramedWrite};
+
+/// The chunks queued for each endpoint besides the one being written.
+const QUEUE_DEPTH: usize = 2;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct LoadBalancingConfig {
+    pub strategy: Strategy,
+    /// Consecutive failures after which an endpoint is ejected.
+    #[derivative(Default(value = "3"))]
+    pub failure_threshold: usize,
+    /// How often ejected endpoints are probed to see if they're back.
+    #[derivative(Default(value = "5"))]
+    pub probe_interval_secs: u64,
+    /// How long writing a chunk may take before it's sent elsewhere.
+    #[derivative(Default(value = "30"))]
+    pub timeout_secs: u64,
+    /// The most events written to an endpoint at once.
+    #[derivative(Default(value = "100"))]
+    pub chunk_max_events: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Strategy {
+    /// Takes turns between the endpoints.
+    #[derivative(Default)]
+    RoundRobin,
+    /// Picks the endpoint with the fewest events waiting to be written.
+    LeastLoaded,
+}
+
+#[derive(Debug, Snafu)]
+enum SendError {
+    #[snafu(display("Connect error: {}", source))]
+    Connect { source: TcpError },
+    #[snafu(display("Write error: {}", source))]
+    Write { source: std::io::Error },
+    #[snafu(display("Timed out"))]
+    TimedOut,
+}
+
+/// Events written to an endpoint together.
+struct Chunk {
+    seqno: usize,
+    events: Vec<Bytes>,
+}
+
+struct Outcome {
+    endpoint: usize,
+    chunk: Chunk,
+    result: Result<(), SendError>,
+}
+
+pub struct Endpoint {
+    pub address: String,
+    pub connector: TcpConnector,
+}
+
+struct EndpointState {
+    address: String,
+    connector: TcpConnector,
+    chunks: Option<mpsc::Sender<Chunk>>,
+    /// Events queued for or being written to the endpoint.
+    load: usize,
+    failures: usize,
+    ejected: bool,
+    probing: bool,
+}
+
+/// Spreads events over several endpoints, each written to by a task of its
+/// own, and sends the events of an endpoint that fails to the others.
+/// Events are acknowledged once they're written, in the order they came in.
+pub struct BalancedSink {
+    endpoints: Vec<EndpointState>,
+    config: LoadBalancingConfig,
+    acker: Acker,
+    encode_event: Arc<dyn Fn(Event) -> Option<Bytes> + Send + Sync>,
+    /// The endpoint round robin tries first.
+    next: usize,
+}
+
+impl BalancedSink {
+    pub fn new(
+        endpoints: Vec<Endpoint>,
+        config: LoadBalancingConfig,
+        acker: Acker,
+        encode_event: impl Fn(Event) -> Option<Bytes> + Send + Sync + 'static,
+    ) -> Self {
+        let endpoints = endpoints
+            .into_iter()
+            .map(|endpoint| EndpointState {
+                address: endpoint.address,
+                connector: endpoint.connector,
+                chunks: None,
+                load: 0,
+                failures: 0,
+                ejected: false,
+                probing: false,
+            })
+            .collect();
+        Self {
+            endpoints,
+            config,
+            acker,
+            encode_event: Arc::new(encode_event),
+            next: 0,
+        }
+    }
+
+    /// The endpoints to try, most preferred first. Ejected endpoints are
+    /// left out.
+    fn candidates(&self) -> Vec<usize> {
+        let count = self.endpoints.len();
+        let mut candidates = (0..count)
+            .map(|offset| (self.next + offset) % count)
+            .filter(|&index| !self.endpoints[index].ejected)
+            .collect::<Vec<_>>();
+        if self.config.strategy == Strategy::LeastLoaded {
+            // The sort is stable, so ties are broken by taking turns.
+            candidates.sort_by_key(|&index| self.endpoints[index].load);
+        }
+        candidates
+    }
+
+    /// Queues the chunk for the first candidate with room for it, or hands
+    /// it back if there's none.
+    fn dispatch(&mut self, mut chunk: Chunk) -> Result<(), Chunk> {
+        for index in self.candidates() {
+            let endpoint = &mut self.endpoints[index];
+            let sender = match &mut endpoint.chunks {
+                Some(sender) => sender,
+                None => continue,
+            };
+            let len = chunk.events.len();
+            match sender.try_send(chunk) {
+                Ok(()) => {
+                    endpoint.load += len;
+                    self.next = (index + 1) % self.endpoints.len();
+                    return Ok(());
+                }
+                Err(TrySendError::Full(returned)) | Err(TrySendError::Closed(returned)) => {
+                    chunk = returned
+                }
+            }
+        }
+        Err(chunk)
+    }
+
+    /// Returns whether the endpoint was ejected by the failure.
+    fn record_failure(&mut self, index: usize, error: &SendError) -> bool {
+        let threshold = self.config.failure_threshold;
+        let endpoint = &mut self.endpoints[index];
+        endpoint.failures += 1;
+        emit!(VectorSendFailed {
+            address: &endpoint.address,
+            error: &error.to_string(),
+        });
+        if !endpoint.ejected && endpoint.failures >= threshold {
+            endpoint.ejected = true;
+            emit!(VectorEndpointEjected {
+                address: &endpoint.address,
+                failures: endpoint.failures,
+            });
+            true
+        } else {
+            false
+        }
+    }
+
+    fn restore(&mut self, index: usize) {
+        let endpoint = &mut self.endpoints[index];
+        endpoint.probing = false;
+        endpoint.failures = 0;
+        if endpoint.ejected {
+            endpoint.ejected = false;
+            emit!(VectorEndpointRestored {
+                address: &endpoint.address
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl StreamSink for BalancedSink {
+    async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let (outcome_tx, mut outcomes) = mpsc::unbounded_channel();
+        for (index, endpoint) in self.endpoints.iter_mut().enumerate() {
+            let (chunk_tx, chunks) = mpsc::channel(QUEUE_DEPTH);
+            endpoint.chunks = Some(chunk_tx);
+            tokio::spawn(run_endpoint(
+                index,
+                endpoint.connector.clone(),
+                timeout,
+                chunks,
+                outcome_tx.clone(),
+            ));
+        }
+        drop(outcome_tx);
+
+        let encode_event = Arc::clone(&self.encode_event);
+        let mut input = input
+            .filter_map(move |event| futures::future::ready(encode_event(event)))
+            .ready_chunks(self.config.chunk_max_events.max(1));
+        let mut input_done = false;
+        let mut acks = OrderedAcks::new(self.acker.clone());
+        // Chunks waiting for an endpoint with room for them.
+        let mut pending = VecDeque::new();
+        let mut probes = FuturesUnordered::<BoxFuture<'static, (usize, bool)>>::new();
+        let mut probe_ticks = interval(Duration::from_secs(self.config.probe_interval_secs.max(1)));
+
+        while !input_done || acks.outstanding() {
+            while let Some(chunk) = pending.pop_front() {
+                if let Err(chunk) = self.dispatch(chunk) {
+                    pending.push_front(chunk);
+                    break;
+                }
+            }
+
+            tokio::select! {
+                events = input.next(), if !input_done && pending.is_empty() => match events {
+                    Some(events) => pending.push_back(Chunk {
+                        seqno: acks.start(events.len()),
+                        events,
+                    }),
+                    None => input_done = true,
+                },
+                outcome = outcomes.recv() => {
+                    let Outcome { endpoint, chunk, result } = match outcome {
+                        Some(outcome) => outcome,
+                        None => break,
+                    };
+                    self.endpoints[endpoint].load -= chunk.events.len();
+                    match result {
+                        Ok(()) => {
+                            self.endpoints[endpoint].failures = 0;
+                            acks.finish(chunk.seqno);
+                        }
+                        Err(error) => {
+                            self.record_failure(endpoint, &error);
+                            // Chunks that failed are sent again before new ones.
+                            pending.push_front(chunk);
+                        }
+                    }
+                }
+                _ = probe_ticks.tick() => {
+                    for (index, endpoint) in self.endpoints.iter_mut().enumerate() {
+                        if endpoint.ejected && !endpoint.probing {
+                            endpoint.probing = true;
+                            let connector = endpoint.connector.clone();
+                            probes.push(Box::pin(async move {
+                                (index, connector.healthcheck().await.is_ok())
+                            }));
+                        }
+                    }
+                }
+                Some((index, healthy)) = probes.next(), if !probes.is_empty() => {
+                    if healthy {
+                        self.restore(index);
+                    } else {
+                        self.endpoints[index].probing = false;
+                    }
+                }
+            }
+        }
+
+        for endpoint in &mut self.endpoints {
+            endpoint.chunks = None;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the chunks queued for an endpoint over a connection kept open
+/// between them, reconnecting after errors.
+async fn run_endpoint(
+    index: usize,
+    connector: TcpConnector,
+    timeout: Duration,
+    mut chunks: mpsc::Receiver<Chunk>,
+    outcomes: mpsc::UnboundedSender<Outcome>,
+) {
+    let mut connection = None;
+    while let Some(chunk) = chunks.recv().await {
+        let result = tokio::time::timeout(timeout, write(&connector, &mut connection, &chunk))
+            .await
+            .unwrap_or(Err(SendError::TimedOut));
+        if result.is_err() {
+            connection = None;
+        }
+        let outcome = Outcome {
+            endpoint: index,
+            chunk,
+            result,
+        };
+        if outcomes.send(outcome).is_err() {
+            break;
+        }
+    }
+}
+
+async fn write(
+    connector: &TcpConnector,
+    connection: &mut Option<FramedWrite<MaybeTlsStream<TcpStream>, BytesCodec>>,
+    chunk: &Chunk,
+) -> Result<(), SendError> {
+    let framed = match connection {
+        Some(framed) => framed,
+        None => {
+            let stream = connector.connect().await.context(Connect)?;
+            connection.get_or_insert(FramedWrite::new(stream, BytesCodec::new()))
+        }
+    };
+    let mut events = stream::iter(chunk.events.clone()).map(Ok);
+    framed.send_all(&mut events).await.context(Write)
+}
+
+/// Acknowledges chunks in the order they came in, however they finish.
+struct OrderedAcks {
+    acker: Acker,
+    /// The sequence number of the next chunk.
+    head: usize,
+    /// The sequence number of the oldest unacknowledged chunk.
+    tail: usize,
+    /// The sizes of chunks, and whether they're finished.
+    chunks: HashMap<usize, (usize, bool)>,
+}
+
+impl OrderedAcks {
+    fn new(acker: Acker) -> Self {
+        Self {
+            acker,
+            head: 0,
+            tail: 0,
+            chunks: HashMap::new(),
+        }
+    }
+
+    fn start(&mut self, len: usize) -> usize {
+        let seqno = self.head;
+        self.head += 1;
+        self.chunks.insert(seqno, (len, false));
+        seqno
+    }
+
+    fn finish(&mut self, seqno: usize) {
+        if let Some(chunk) = self.chunks.get_mut(&seqno) {
+            chunk.1 = true;
+        }
+        let mut count = 0;
+        while let Some((len, true)) = self.chunks.get(&self.tail).copied() {
+            self.chunks.remove(&self.tail);
+            self.tail += 1;
+            count += len;
+        }
+        if count > 0 {
+            self.acker.ack(count);
+        }
+    }
+
+    fn outstanding(&self) -> bool {
+        self.tail < self.head
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{next_addr, random_lines_with_stream, trace_init, CountReceiver},
+        tls::MaybeTlsSettings,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn sink(addresses: &[std::net::SocketAddr], config: LoadBalancingConfig) -> BalancedSink {
+        let endpoints = addresses
+            .iter()
+            .map(|address| Endpoint {
+                address: address.to_string(),
+                connector: TcpConnector::new(
+                    address.ip().to_string(),
+                    address.port(),
+                    MaybeTlsSettings::Raw(()),
+                ),
+            })
+            .collect();
+        BalancedSink::new(endpoints, config, Acker::Null, |event: Event| {
+            Some(Bytes::from(format!(
+                "{}\n",
+                event.as_log()[crate::config::log_schema().message_key()].to_string_lossy()
+            )))
+        })
+    }
+
+    #[test]
+    fn acknowledges_in_order() {
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let mut acks = OrderedAcks::new(acker);
+        let first = acks.start(2);
+        let second = acks.start(3);
+
+        acks.finish(second);
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 0);
+        assert!(acks.outstanding());
+
+        acks.finish(first);
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 5);
+        assert!(!acks.outstanding());
+    }
+
+    #[test]
+    fn skips_ejected_endpoints() {
+        let addresses = [next_addr(), next_addr(), next_addr()];
+        let mut sink = sink(&addresses, Default::default());
+        assert_eq!(sink.candidates(), vec![0, 1, 2]);
+
+        sink.next = 2;
+        sink.endpoints[0].ejected = true;
+        assert_eq!(sink.candidates(), vec![2, 1]);
+
+        sink.config.strategy = Strategy::LeastLoaded;
+        sink.endpoints[2].load = 10;
+        assert_eq!(sink.candidates(), vec![1, 2]);
+    }
+
+    #[test]
+    fn ejects_after_failures() {
+        let mut sink = sink(&[next_addr()], Default::default());
+        assert!(!sink.record_failure(0, &SendError::TimedOut));
+        assert!(!sink.record_failure(0, &SendError::TimedOut));
+        assert!(sink.record_failure(0, &SendError::TimedOut));
+        assert!(sink.candidates().is_empty());
+
+        sink.restore(0);
+        assert_eq!(sink.candidates(), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_healthy_endpoints() {
+        trace_init();
+
+        let down = next_addr();
+        let up = next_addr();
+        let mut receiver = CountReceiver::receive_lines(up);
+
+        let mut sink = sink(&[down, up], Default::default());
+        let (mut lines, events) = random_lines_with_stream(10, 100);
+        sink.run(Box::pin(events)).await.unwrap();
+
+        receiver.connected().await;
+        let mut output = receiver.await;
+        output.sort();
+        lines.sort();
+        assert_eq!(output, lines);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::proto,
+    internal_events::VectorEventSent,
+    sinks::util::tcp::{TcpConnector, TcpSinkConfig},
+    tls::{MaybeTlsSettings, TlsConfig},
+    Event,
+};
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::future;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+mod balancer;
+
+use balancer::{BalancedSink, Endpoint};
+pub use balancer::{LoadBalancingConfig, Strategy};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct VectorSinkConfig {
+    pub address: Option<String>,
+    /// Events are spread over these addresses, instead of all going to `address`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub load_balancing: LoadBalancingConfig,
+    pub tls: Option<TlsConfig>,
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Missing host in address field"))]
+    MissingHost,
+    #[snafu(display("Missing port in address field"))]
+    MissingPort,
+    #[snafu(display("One of address or addresses must be set"))]
+    NoAddresses,
+    #[snafu(display("Only one of address and addresses may be set"))]
+    BothAddresses,
+}
+
+inventory::submit! {
+    SinkDescription::new::<VectorSinkConfig>("vector")
+}
+
+impl GenerateConfig for VectorSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: Some("127.0.0.1:5000".to_string()),
+            addresses: Vec::new(),
+            load_balancing: Default::default(),
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "vector")]
+impl SinkConfig for VectorSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        match (&self.address, self.addresses.is_empty()) {
+            (Some(address), true) => {
+                let sink_config = TcpSinkConfig::new(address.clone(), self.tls.clone());
+                sink_config.build(cx, encode_event)
+            }
+            (None, false) => self.build_balanced(cx),
+            (None, true) => Err(BuildError::NoAddresses.into()),
+            (Some(_), false) => Err(BuildError::BothAddresses.into()),
+        }
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "vector"
+    }
+}
+
+impl VectorSinkConfig {
+    fn build_balanced(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+        let endpoints = self
+            .addresses
+            .iter()
+            .map(|address| {
+                let uri = address.parse::<http::Uri>()?;
+                let host = uri.host().ok_or(BuildError::MissingHost)?.to_string();
+                let port = uri.port_u16().ok_or(BuildError::MissingPort)?;
+                Ok(Endpoint {
+                    address: address.clone(),
+                    connector: TcpConnector::new(host, port, tls.clone()),
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let connectors = endpoints
+            .iter()
+            .map(|endpoint| endpoint.connector.clone())
+            .collect::<Vec<_>>();
+        // Events keep flowing as long as any of the endpoints is up.
+        let healthcheck = async move {
+            future::select_ok(
+                connectors
+                    .iter()
+                    .map(|connector| Box::pin(connector.healthcheck())),
+            )
+            .await
+            .map(|_| ())
+        };
+
+        let sink = BalancedSink::new(
+            endpoints,
+            self.load_balancing.clone(),
+            cx.acker(),
+            encode_event,
+        );
+        Ok((
+            super::VectorSink::Stream(Box::new(sink)),
+            Box::pin(healthcheck),
+        ))
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("Connect error: {}", source))]
+    ConnectError { source: std::io::Error },
+}
+
+fn encode_event(event: Event) -> Option<Bytes> {
+    let event = proto::EventWrapper::from(event);
+    let event_len = event.encoded_len();
+    let full_len = event_len + 4;
+
+    emit!(VectorEventSent {
+        byte_size: full_len
+    });
+
+    let mut out = BytesMut::with_capacity(full_len);
+    out.put_u32(event_len as u32);
+    event.encode(&mut out).unwrap();
+
+    Some(out.into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<super::VectorSinkConfig>();
+    }
+
+    #[test]
+    fn parses_addresses() {
+        let config: VectorSinkConfig = toml::from_str(
+            r#"addresses = ["10.0.0.1:9000", "10.0.0.2:9000"]
+            load_balancing.strategy = "least_loaded"
+            load_balancing.failure_threshold = 5"#,
+        )
+        .unwrap();
+        assert_eq!(config.address, None);
+        assert_eq!(config.addresses.len(), 2);
+        assert_eq!(config.load_balancing.strategy, Strategy::LeastLoaded);
+        assert_eq!(config.load_balancing.failure_threshold, 5);
+        assert_eq!(config.load_balancing.probe_interval_secs, 5);
+    }
+}
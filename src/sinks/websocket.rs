@@ -0,0 +1,569 @@
+use crate::{
+    buffers::Acker,
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    dns,
+    internal_events::{
+        ConnectionOpen, OpenGauge, WebSocketClientConnected, WebSocketClientDisconnected,
+        WebSocketClientLagged, WebSocketConnectionError, WebSocketConnectionEstablished,
+        WebSocketConnectionFailed, WebSocketConnectionShutdown,
+    },
+    sinks::util::{
+        encode_event, encoding::EncodingConfig, retries::ExponentialBackoff, Encoding, StreamSink,
+        UriSerde,
+    },
+    tls::{MaybeTlsIncomingStream, MaybeTlsSettings, MaybeTlsStream, TlsConfig, TlsError},
+    Event,
+};
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, FutureExt, SinkExt, StreamExt};
+use http::Uri;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{net::SocketAddr, pin::Pin, time::Duration};
+use stream_cancel::{Trigger, Tripwire};
+use tokio::{
+    net::TcpStream,
+    sync::broadcast::{self, RecvError},
+    time::delay_for,
+};
+use tokio_tungstenite::{
+    tungstenite::{error::Error as WsError, Message},
+    WebSocketStream,
+};
+
+#[derive(Debug, Snafu)]
+enum WebSocketError {
+    #[snafu(display("Connect error: {}", source))]
+    ConnectError { source: TlsError },
+    #[snafu(display("Unable to resolve DNS: {}", source))]
+    DnsError { source: dns::DnsError },
+    #[snafu(display("No addresses returned."))]
+    NoAddresses,
+    #[snafu(display("WebSocket handshake failed: {}", source))]
+    HandshakeError { source: WsError },
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("URI scheme must be \"ws\" or \"wss\", found {:?}", scheme))]
+    InvalidScheme { scheme: Option<String> },
+    #[snafu(display("URI must have a host"))]
+    MissingHost,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+// TODO: add back when serde-rs/serde#1358 is addressed
+// #[serde(deny_unknown_fields)]
+pub struct WebSocketSinkConfig {
+    #[serde(flatten)]
+    pub mode: Mode,
+    pub encoding: EncodingConfig<Encoding>,
+    #[serde(default)]
+    pub message_type: MessageType,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Mode {
+    Connect(ConnectConfig),
+    Listen(ListenConfig),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectConfig {
+    pub uri: UriSerde,
+    pub tls: Option<TlsConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ListenConfig {
+    pub address: SocketAddr,
+    pub tls: Option<TlsConfig>,
+    #[serde(default = "default_client_buffer")]
+    pub client_buffer: usize,
+}
+
+fn default_client_buffer() -> usize {
+    1024
+}
+
+/// The kind of WebSocket frame each event is sent in.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageType {
+    Text,
+    Binary,
+}
+
+impl Default for MessageType {
+    fn default() -> Self {
+        MessageType::Text
+    }
+}
+
+inventory::submit! {
+    SinkDescription::new::<WebSocketSinkConfig>("websocket")
+}
+
+impl GenerateConfig for WebSocketSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"mode = "connect"
+            uri = "ws://127.0.0.1:9000/events"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "websocket")]
+impl SinkConfig for WebSocketSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let encoder = WebSocketEncoder {
+            encoding: self.encoding.clone(),
+            message_type: self.message_type,
+        };
+        match &self.mode {
+            Mode::Connect(config) => {
+                let connector = WebSocketConnector::new(config)?;
+                let sink = WebSocketSink {
+                    connector: connector.clone(),
+                    acker: cx.acker(),
+                    encoder,
+                };
+                Ok((
+                    super::VectorSink::Stream(Box::new(sink)),
+                    Box::pin(async move { connector.healthcheck().await }),
+                ))
+            }
+            Mode::Listen(config) => {
+                let sink = WebSocketListenSink {
+                    address: config.address,
+                    tls: MaybeTlsSettings::from_config(&config.tls, true)?,
+                    client_buffer: config.client_buffer,
+                    acker: cx.acker(),
+                    encoder,
+                };
+                Ok((
+                    super::VectorSink::Stream(Box::new(sink)),
+                    future::ok(()).boxed(),
+                ))
+            }
+        }
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "websocket"
+    }
+}
+
+#[derive(Clone)]
+struct WebSocketEncoder {
+    encoding: EncodingConfig<Encoding>,
+    message_type: MessageType,
+}
+
+impl WebSocketEncoder {
+    fn encode_event(&self, event: Event) -> Option<Message> {
+        let mut bytes = encode_event(event, &self.encoding)?.to_vec();
+        // Each event is a frame of its own, so the trailing newline is redundant.
+        if bytes.last() == Some(&b'\n') {
+            bytes.pop();
+        }
+        Some(match self.message_type {
+            MessageType::Text => Message::Text(match String::from_utf8(bytes) {
+                Ok(text) => text,
+                Err(error) => String::from_utf8_lossy(&error.into_bytes()).into_owned(),
+            }),
+            MessageType::Binary => Message::Binary(bytes),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct WebSocketConnector {
+    uri: Uri,
+    host: String,
+    port: u16,
+    tls: MaybeTlsSettings,
+}
+
+impl WebSocketConnector {
+    fn new(config: &ConnectConfig) -> crate::Result<Self> {
+        let uri: Uri = config.uri.clone().into();
+        let secure = match uri.scheme_str() {
+            Some("ws") => false,
+            Some("wss") => true,
+            scheme => {
+                return Err(BuildError::InvalidScheme {
+                    scheme: scheme.map(Into::into),
+                }
+                .into())
+            }
+        };
+        let host = uri.host().ok_or(BuildError::MissingHost)?.to_string();
+        let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+        let tls = match &config.tls {
+            None if secure => Some(TlsConfig::enabled()),
+            tls => tls.clone(),
+        };
+        let tls = MaybeTlsSettings::from_config(&tls, false)?;
+
+        Ok(Self {
+            uri,
+            host,
+            port,
+            tls,
+        })
+    }
+
+    fn fresh_backoff() -> ExponentialBackoff {
+        ExponentialBackoff::from_millis(2)
+            .factor(250)
+            .max_delay(Duration::from_secs(60))
+    }
+
+    async fn connect(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WebSocketError> {
+        let ip = dns::Resolver
+            .lookup_ip(self.host.clone())
+            .await
+            .context(DnsError)?
+            .next()
+            .ok_or(WebSocketError::NoAddresses)?;
+
+        let addr = SocketAddr::new(ip, self.port);
+        let stream = self
+            .tls
+            .connect(&self.host, &addr)
+            .await
+            .context(ConnectError)?;
+
+        let (ws, _response) = tokio_tungstenite::client_async(self.uri.clone(), stream)
+            .await
+            .context(HandshakeError)?;
+        Ok(ws)
+    }
+
+    async fn connect_backoff(&self) -> WebSocketStream<MaybeTlsStream<TcpStream>> {
+        let mut backoff = Self::fresh_backoff();
+        loop {
+            match self.connect().await {
+                Ok(ws) => {
+                    emit!(WebSocketConnectionEstablished);
+                    return ws;
+                }
+                Err(error) => {
+                    emit!(WebSocketConnectionFailed { error });
+                    delay_for(backoff.next().unwrap()).await;
+                }
+            }
+        }
+    }
+
+    async fn healthcheck(&self) -> crate::Result<()> {
+        let mut ws = self.connect().await?;
+        let _ = ws.close(None).await;
+        Ok(())
+    }
+}
+
+/// Streams events to a single WebSocket server, reconnecting whenever the
+/// connection is lost.
+struct WebSocketSink {
+    connector: WebSocketConnector,
+    acker: Acker,
+    encoder: WebSocketEncoder,
+}
+
+#[async_trait]
+impl StreamSink for WebSocketSink {
+    async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let encoder = self.encoder.clone();
+        let mut input = input.map(|event| encoder.encode_event(event)).peekable();
+        // A message that failed to send is retried on the next connection.
+        let mut pending = None;
+
+        // Only connect once there is something to send.
+        while pending.is_some() || Pin::new(&mut input).peek().await.is_some() {
+            let mut ws = self.connector.connect_backoff().await;
+            let _open_token = OpenGauge::new().open(|count| emit!(ConnectionOpen { count }));
+
+            if let Some(message) = pending.take() {
+                if let Err(error) = ws.send(message).await {
+                    emit!(WebSocketConnectionError { error });
+                    continue;
+                }
+                self.acker.ack(1);
+            }
+
+            loop {
+                tokio::select! {
+                    message = input.next() => match message {
+                        Some(Some(message)) => {
+                            if let Err(error) = ws.send(message.clone()).await {
+                                emit!(WebSocketConnectionError { error });
+                                pending = Some(message);
+                                break;
+                            }
+                            self.acker.ack(1);
+                        }
+                        // The event couldn't be encoded.
+                        Some(None) => self.acker.ack(1),
+                        None => {
+                            let _ = ws.close(None).await;
+                            return Ok(());
+                        }
+                    },
+                    // Pings are answered by the next write; anything else the
+                    // server sends is ignored.
+                    message = ws.next() => match message {
+                        Some(Ok(_)) => {}
+                        Some(Err(error)) => {
+                            emit!(WebSocketConnectionError { error });
+                            break;
+                        }
+                        None => {
+                            emit!(WebSocketConnectionShutdown);
+                            break;
+                        }
+                    },
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Accepts WebSocket clients and sends every event to all of them, dropping
+/// events for clients that fall more than `client_buffer` events behind.
+struct WebSocketListenSink {
+    address: SocketAddr,
+    tls: MaybeTlsSettings,
+    client_buffer: usize,
+    acker: Acker,
+    encoder: WebSocketEncoder,
+}
+
+impl WebSocketListenSink {
+    async fn handle_client(
+        stream: MaybeTlsIncomingStream<TcpStream>,
+        mut receiver: broadcast::Receiver<Message>,
+    ) {
+        let peer_addr = stream.peer_addr();
+        let mut ws = match tokio_tungstenite::accept_async(stream).await {
+            Ok(ws) => ws,
+            Err(error) => {
+                emit!(WebSocketConnectionError { error });
+                return;
+            }
+        };
+        emit!(WebSocketClientConnected { peer_addr });
+
+        loop {
+            tokio::select! {
+                message = receiver.recv() => match message {
+                    Ok(message) => {
+                        if let Err(error) = ws.send(message).await {
+                            emit!(WebSocketConnectionError { error });
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        emit!(WebSocketClientLagged { peer_addr, skipped });
+                    }
+                    // The sink is shutting down.
+                    Err(RecvError::Closed) => {
+                        let _ = ws.close(None).await;
+                        break;
+                    }
+                },
+                message = ws.next() => match message {
+                    Some(Ok(_)) => {}
+                    Some(Err(error)) => {
+                        emit!(WebSocketConnectionError { error });
+                        break;
+                    }
+                    None => break,
+                },
+            }
+        }
+
+        emit!(WebSocketClientDisconnected { peer_addr });
+    }
+}
+
+#[async_trait]
+impl StreamSink for WebSocketListenSink {
+    async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let listener = self.tls.bind(&self.address).await.map_err(|error| {
+            error!(message = "Failed to bind.", address = %self.address, %error);
+        })?;
+
+        let (sender, _) = broadcast::channel(self.client_buffer);
+        let (_trigger, tripwire): (Trigger, Tripwire) = Tripwire::new();
+        let connection_gauge = OpenGauge::new();
+        let clients = sender.clone();
+        tokio::spawn(
+            listener
+                .accept_stream()
+                .take_until(tripwire)
+                .for_each(move |connection| {
+                    match connection {
+                        Ok(stream) => {
+                            let open_token =
+                                connection_gauge.open(|count| emit!(ConnectionOpen { count }));
+                            tokio::spawn(
+                                Self::handle_client(stream, clients.subscribe())
+                                    .map(move |()| drop(open_token)),
+                            );
+                        }
+                        Err(error) => error!(message = "Failed to accept socket.", %error),
+                    }
+                    future::ready(())
+                }),
+        );
+
+        while let Some(event) = input.next().await {
+            if let Some(message) = self.encoder.encode_event(event) {
+                // Sending only fails when no client is connected.
+                let _ = sender.send(message);
+            }
+            self.acker.ack(1);
+        }
+
+        // Dropping the trigger and the last sender stops accepting clients
+        // and closes the connected ones.
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::SinkContext,
+        test_util::{next_addr, random_lines_with_stream, trace_init},
+    };
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebSocketSinkConfig>();
+    }
+
+    #[test]
+    fn wss_enables_tls() {
+        let config: ConnectConfig = toml::from_str(r#"uri = "wss://example.com/events""#).unwrap();
+        let connector = WebSocketConnector::new(&config).unwrap();
+        assert_eq!(connector.port, 443);
+        assert!(matches!(connector.tls, MaybeTlsSettings::Tls(_)));
+
+        let config: ConnectConfig = toml::from_str(r#"uri = "http://example.com""#).unwrap();
+        assert!(WebSocketConnector::new(&config).is_err());
+    }
+
+    #[test]
+    fn encodes_frames_without_newline() {
+        let encoder = WebSocketEncoder {
+            encoding: Encoding::Text.into(),
+            message_type: MessageType::Binary,
+        };
+        assert_eq!(
+            encoder.encode_event(Event::from("hello")),
+            Some(Message::Binary(b"hello".to_vec()))
+        );
+    }
+
+    #[tokio::test]
+    async fn connect_sends_text_frames() {
+        trace_init();
+
+        let addr = next_addr();
+        let mut listener = TcpListener::bind(addr).await.unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            receive_text(ws).await
+        });
+
+        let config: WebSocketSinkConfig = toml::from_str(&format!(
+            r#"
+            mode = "connect"
+            uri = "ws://{}/"
+            encoding.codec = "text"
+            "#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (lines, events) = random_lines_with_stream(10, 20);
+        sink.run(events).await.unwrap();
+
+        assert_eq!(server.await.unwrap(), lines);
+    }
+
+    #[tokio::test]
+    async fn listen_fans_out_to_clients() {
+        trace_init();
+
+        let addr = next_addr();
+        let config: WebSocketSinkConfig = toml::from_str(&format!(
+            r#"
+            mode = "listen"
+            address = "{}"
+            encoding.codec = "text"
+            "#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        let sink = tokio::spawn(sink.run(receiver));
+        delay_for(Duration::from_millis(100)).await;
+
+        let mut clients = Vec::new();
+        for _ in 0..2 {
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let (ws, _) = tokio_tungstenite::client_async(format!("ws://{}/", addr), stream)
+                .await
+                .unwrap();
+            clients.push(tokio::spawn(receive_text(ws)));
+        }
+        delay_for(Duration::from_millis(100)).await;
+
+        let (lines, events) = random_lines_with_stream(10, 5);
+        events.map(Ok).forward(sender).await.unwrap();
+        sink.await.unwrap().unwrap();
+
+        for client in clients {
+            assert_eq!(client.await.unwrap(), lines);
+        }
+    }
+
+    async fn receive_text<S>(ws: WebSocketStream<S>) -> Vec<String>
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        ws.filter_map(|message| async move {
+            match message.unwrap() {
+                Message::Text(text) => Some(text),
+                _ => None,
+            }
+        })
+        .collect()
+        .await
+    }
+}
@@ -0,0 +1,474 @@
+use crate::{
+    config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
+    event::{Event, LogEvent},
+    internal_events::{EbpfEventReceived, EbpfEventsLost, EbpfProbeAttachFailed},
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use chrono::Utc;
+use ebpf_probes::{Probe, Record, RecordData, DNS_LOOKUP_SYMBOL, EVENTS_MAP, LOST_RECORDS_MAP};
+use futures::{channel::oneshot, compat::Sink01CompatExt, SinkExt, StreamExt};
+use futures01::Sink;
+use libbpf_rs::{Link, MapFlags, Object, ObjectBuilder, RingBufferBuilder};
+use nix::unistd::{sysconf, SysconfVar};
+use object::{Object as _, ObjectSection as _};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    convert::TryInto,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc;
+
+const BTF_PATH: &str = "/sys/kernel/btf/vmlinux";
+const POLL_TIMEOUT: Duration = Duration::from_millis(100);
+const LOST_RECORDS_INTERVAL: Duration = Duration::from_secs(1);
+/// Records decoded but not yet sent on, beyond which they're dropped rather
+/// than holding up the kernel side.
+const QUEUE_SIZE: usize = 1024;
+
+/// Where the system libc is looked for, for the `dns_lookup` probe.
+const LIBC_PATHS: &[&str] = &[
+    "/lib/x86_64-linux-gnu/libc.so.6",
+    "/lib/aarch64-linux-gnu/libc.so.6",
+    "/lib64/libc.so.6",
+    "/usr/lib64/libc.so.6",
+    "/usr/lib/libc.so.6",
+    "/lib/libc.so.6",
+];
+
+const CAP_SYS_ADMIN: u32 = 21;
+const CAP_PERFMON: u32 = 38;
+const CAP_BPF: u32 = 39;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct EbpfConfig {
+    pub probes: ProbesConfig,
+    pub ring_buffer_pages: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct ProbesConfig {
+    pub process_exec: bool,
+    pub tcp_connect: bool,
+    pub tcp_accept: bool,
+    pub dns_lookup: bool,
+}
+
+impl Default for EbpfConfig {
+    fn default() -> Self {
+        Self {
+            probes: ProbesConfig::default(),
+            ring_buffer_pages: 64,
+        }
+    }
+}
+
+impl Default for ProbesConfig {
+    fn default() -> Self {
+        Self {
+            process_exec: true,
+            tcp_connect: true,
+            tcp_accept: true,
+            dns_lookup: false,
+        }
+    }
+}
+
+impl ProbesConfig {
+    fn enabled(&self, probe: Probe) -> bool {
+        match probe {
+            Probe::ProcessExec => self.process_exec,
+            Probe::TcpConnect => self.tcp_connect,
+            Probe::TcpAccept => self.tcp_accept,
+            Probe::DnsLookup => self.dns_lookup,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("No probe is enabled"))]
+    NoProbes,
+    #[snafu(display("`ring_buffer_pages` must be a power of two, found {}", pages))]
+    RingBufferPages { pages: usize },
+    #[snafu(display(
+        "The kernel doesn't expose its BTF at {:?}, it must be built with CONFIG_DEBUG_INFO_BTF",
+        BTF_PATH
+    ))]
+    NoBtf,
+    #[snafu(display("Loading eBPF programs requires CAP_SYS_ADMIN, or CAP_BPF and CAP_PERFMON"))]
+    MissingCapabilities,
+    #[snafu(display("Failed to load the eBPF programs: {}", source))]
+    Load { source: libbpf_rs::Error },
+    #[snafu(display("Could not find {} in the system libc", DNS_LOOKUP_SYMBOL))]
+    NoLibcSymbol,
+    #[snafu(display("None of the enabled probes could be attached"))]
+    NothingAttached,
+    #[snafu(display("The eBPF thread stopped while loading the programs"))]
+    ThreadStopped,
+}
+
+inventory::submit! {
+    SourceDescription::new::<EbpfConfig>("ebpf")
+}
+
+impl_generate_config_from_default!(EbpfConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "ebpf")]
+impl SourceConfig for EbpfConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        if !Probe::ALL.iter().any(|probe| self.probes.enabled(*probe)) {
+            return Err(BuildError::NoProbes.into());
+        }
+        if !self.ring_buffer_pages.is_power_of_two() {
+            return Err(BuildError::RingBufferPages {
+                pages: self.ring_buffer_pages,
+            }
+            .into());
+        }
+        if !Path::new(BTF_PATH).exists() {
+            return Err(BuildError::NoBtf.into());
+        }
+        let status = fs::read_to_string("/proc/self/status")?;
+        if !has_capabilities(&status) {
+            return Err(BuildError::MissingCapabilities.into());
+        }
+
+        // libbpf objects can't be sent across threads, so they are loaded
+        // on the thread polling the ring buffer, which reports back whether
+        // that worked.
+        let (loaded_tx, loaded_rx) = oneshot::channel();
+        let (tx, rx) = mpsc::channel(QUEUE_SIZE);
+        let stop = Arc::new(AtomicBool::new(false));
+        let config = self.clone();
+        let thread_stop = Arc::clone(&stop);
+        thread::Builder::new()
+            .name("ebpf".into())
+            .spawn(move || match config.load() {
+                Ok((object, links)) => {
+                    let _ = loaded_tx.send(Ok(()));
+                    if let Err(error) = poll(&object, tx, &thread_stop) {
+                        error!(message = "Failed to poll the eBPF ring buffer.", %error);
+                    }
+                    drop(links);
+                }
+                Err(error) => {
+                    let _ = loaded_tx.send(Err(error));
+                }
+            })?;
+        loaded_rx.await.map_err(|_| BuildError::ThreadStopped)??;
+
+        Ok(Box::pin(run(rx, stop, shutdown, out)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "ebpf"
+    }
+}
+
+impl EbpfConfig {
+    /// Loads the programs of the enabled probes and attaches them.
+    fn load(&self) -> crate::Result<(Object, Vec<Link>)> {
+        let mut open = ObjectBuilder::default()
+            .open_memory("ebpf_probes", ebpf_probes::OBJECT)
+            .context(Load)?;
+        for probe in Probe::ALL
+            .iter()
+            .filter(|probe| !self.probes.enabled(**probe))
+        {
+            for name in probe.programs() {
+                if let Some(program) = open.prog_mut(name) {
+                    program.set_autoload(false).context(Load)?;
+                }
+            }
+        }
+        let page_size = sysconf(SysconfVar::PAGE_SIZE)?.unwrap_or(4096) as u32;
+        if let Some(events) = open.map_mut(EVENTS_MAP) {
+            events
+                .set_max_entries(self.ring_buffer_pages as u32 * page_size)
+                .context(Load)?;
+        }
+        let mut object = open.load().context(Load)?;
+
+        let libc = if self.probes.dns_lookup {
+            Some(find_libc_symbol(DNS_LOOKUP_SYMBOL).ok_or(BuildError::NoLibcSymbol)?)
+        } else {
+            None
+        };
+
+        let mut links = Vec::new();
+        for probe in Probe::ALL
+            .iter()
+            .filter(|probe| self.probes.enabled(**probe))
+        {
+            for name in probe.programs() {
+                let program = match object.prog_mut(name) {
+                    Some(program) => program,
+                    None => continue,
+                };
+                let link = match (probe, &libc) {
+                    (Probe::DnsLookup, Some((path, offset))) => {
+                        program.attach_uprobe(false, -1, path, *offset)
+                    }
+                    _ => program.attach(),
+                };
+                match link {
+                    Ok(link) => links.push(link),
+                    Err(error) => emit!(EbpfProbeAttachFailed {
+                        probe: probe.name(),
+                        program: *name,
+                        error,
+                    }),
+                }
+            }
+        }
+        if links.is_empty() {
+            return Err(BuildError::NothingAttached.into());
+        }
+
+        Ok((object, links))
+    }
+}
+
+/// Polls the ring buffer until stopped, decoding the records into events.
+fn poll(object: &Object, tx: mpsc::Sender<Event>, stop: &AtomicBool) -> crate::Result<()> {
+    let hostname = crate::get_hostname().ok();
+    let mut tx = tx;
+    let mut builder = RingBufferBuilder::new();
+    builder.add(
+        object
+            .map(EVENTS_MAP)
+            .expect("the object has an events map"),
+        move |bytes: &[u8]| {
+            match Record::decode(bytes) {
+                Some(record) => {
+                    emit!(EbpfEventReceived {
+                        probe: record.probe.name(),
+                        byte_size: bytes.len(),
+                    });
+                    let event = record_to_event(record, hostname.as_deref());
+                    if tx.try_send(event).is_err() {
+                        emit!(EbpfEventsLost {
+                            count: 1,
+                            reason: "pipeline_full",
+                        });
+                    }
+                }
+                None => emit!(EbpfEventsLost {
+                    count: 1,
+                    reason: "invalid_record",
+                }),
+            }
+            0
+        },
+    )?;
+    let ring_buffer = builder.build()?;
+
+    let lost_records = object
+        .map(LOST_RECORDS_MAP)
+        .expect("the object has a lost records map");
+    let mut lost_total = 0;
+    let mut lost_checked = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        ring_buffer.poll(POLL_TIMEOUT)?;
+
+        if lost_checked.elapsed() >= LOST_RECORDS_INTERVAL {
+            lost_checked = Instant::now();
+            let lost = lost_records
+                .lookup_percpu(&0u32.to_ne_bytes(), MapFlags::ANY)?
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|value| value.as_slice().try_into().ok())
+                .map(u64::from_ne_bytes)
+                .sum::<u64>();
+            if lost > lost_total {
+                emit!(EbpfEventsLost {
+                    count: lost - lost_total,
+                    reason: "ring_buffer_full",
+                });
+                lost_total = lost;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run(
+    rx: mpsc::Receiver<Event>,
+    stop: Arc<AtomicBool>,
+    shutdown: ShutdownSignal,
+    out: Pipeline,
+) -> Result<(), ()> {
+    let mut out = out
+        .sink_map_err(|error| error!(message = "Error sending eBPF event.", %error))
+        .sink_compat();
+
+    let result = out.send_all(&mut rx.take_until(shutdown).map(Ok)).await;
+    // Dropping the links on the polling thread detaches the probes.
+    stop.store(true, Ordering::Relaxed);
+    result
+}
+
+fn record_to_event(record: Record, hostname: Option<&str>) -> Event {
+    let mut log = LogEvent::default();
+    log.insert(log_schema().timestamp_key(), Utc::now());
+    if let Some(hostname) = hostname {
+        log.insert(log_schema().host_key(), hostname);
+    }
+    log.insert("probe", record.probe.name());
+    log.insert("pid", i64::from(record.pid));
+    log.insert("uid", i64::from(record.uid));
+    log.insert("gid", i64::from(record.gid));
+    log.insert("comm", record.comm);
+
+    match record.data {
+        RecordData::ProcessExec {
+            ppid,
+            filename,
+            argv,
+        } => {
+            log.insert("ppid", i64::from(ppid));
+            log.insert("filename", filename);
+            log.insert("argv", argv);
+        }
+        RecordData::Tcp {
+            saddr,
+            sport,
+            daddr,
+            dport,
+        } => {
+            log.insert("saddr", saddr.to_string());
+            log.insert("sport", i64::from(sport));
+            log.insert("daddr", daddr.to_string());
+            log.insert("dport", i64::from(dport));
+        }
+        RecordData::DnsLookup { query } => {
+            log.insert("query", query);
+        }
+    }
+
+    Event::Log(log)
+}
+
+/// Checks the effective capabilities in `/proc/self/status` allow loading
+/// and attaching tracing programs.
+fn has_capabilities(status: &str) -> bool {
+    let effective = status
+        .lines()
+        .find(|line| line.starts_with("CapEff:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+        .unwrap_or(0);
+    let has = |capability: u32| effective & (1 << capability) != 0;
+    has(CAP_SYS_ADMIN) || (has(CAP_BPF) && has(CAP_PERFMON))
+}
+
+/// Finds the system libc and the file offset of `symbol` in it, which is
+/// where uprobes are attached.
+fn find_libc_symbol(symbol: &str) -> Option<(PathBuf, usize)> {
+    LIBC_PATHS.iter().find_map(|path| {
+        let data = fs::read(path).ok()?;
+        let offset = symbol_offset(&data, symbol)?;
+        Some((PathBuf::from(path), offset))
+    })
+}
+
+fn symbol_offset(data: &[u8], symbol: &str) -> Option<usize> {
+    let file = object::File::parse(data).ok()?;
+    let (_, found) = file
+        .dynamic_symbols()
+        .find(|(_, found)| found.name() == Some(symbol))?;
+    let section = file.section_by_index(found.section_index()?).ok()?;
+    let (section_offset, _) = section.file_range()?;
+    Some((found.address() - section.address() + section_offset) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<EbpfConfig>();
+    }
+
+    #[test]
+    fn parses_config() {
+        let config: EbpfConfig = toml::from_str(
+            r#"
+            probes.tcp_accept = false
+            probes.dns_lookup = true
+            "#,
+        )
+        .unwrap();
+        assert!(config.probes.enabled(Probe::ProcessExec));
+        assert!(config.probes.enabled(Probe::TcpConnect));
+        assert!(!config.probes.enabled(Probe::TcpAccept));
+        assert!(config.probes.enabled(Probe::DnsLookup));
+        assert_eq!(config.ring_buffer_pages, 64);
+    }
+
+    #[test]
+    fn checks_capabilities() {
+        let status = |cap_eff: &str| format!("Name:\tvector\nCapEff:\t{}\n", cap_eff);
+        assert!(has_capabilities(&status("000001ffffffffff")));
+        // CAP_SYS_ADMIN only.
+        assert!(has_capabilities(&status("0000000000200000")));
+        // CAP_BPF and CAP_PERFMON.
+        assert!(has_capabilities(&status("000000c000000000")));
+        // CAP_BPF only.
+        assert!(!has_capabilities(&status("0000008000000000")));
+        assert!(!has_capabilities(&status("0000000000000000")));
+        assert!(!has_capabilities("Name:\tvector\n"));
+    }
+
+    #[test]
+    fn converts_records_to_events() {
+        let record = Record {
+            probe: Probe::TcpConnect,
+            pid: 4242,
+            uid: 0,
+            gid: 0,
+            comm: "curl".into(),
+            data: RecordData::Tcp {
+                saddr: Ipv4Addr::new(10, 0, 0, 1).into(),
+                sport: 51234,
+                daddr: Ipv4Addr::new(93, 184, 216, 34).into(),
+                dport: 443,
+            },
+        };
+
+        let event = record_to_event(record, Some("web-1"));
+        let log = event.as_log();
+        assert_eq!(log[log_schema().host_key()], "web-1".into());
+        assert_eq!(log["probe"], "tcp_connect".into());
+        assert_eq!(log["pid"], 4242.into());
+        assert_eq!(log["comm"], "curl".into());
+        assert_eq!(log["daddr"], "93.184.216.34".into());
+        assert_eq!(log["dport"], 443.into());
+        assert!(log.contains(log_schema().timestamp_key()));
+    }
+}
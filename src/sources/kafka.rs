@@ -1,8 +1,13 @@
 use crate::{
+    avro::{AvroConfig, AvroDecoder},
     config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
     event::{Event, Value},
-    internal_events::{KafkaEventFailed, KafkaEventReceived, KafkaOffsetUpdateFailed},
+    internal_events::{
+        AvroDecodeFailed, KafkaEventFailed, KafkaEventReceived, KafkaOffsetUpdateFailed,
+        ProtobufDecodeFailed,
+    },
     kafka::KafkaAuthConfig,
+    protobuf::{ProtobufConfig, ProtobufDecoder},
     shutdown::ShutdownSignal,
     Pipeline,
 };
@@ -17,7 +22,10 @@ use rdkafka::{
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -25,6 +33,8 @@ enum BuildError {
     KafkaCreateError { source: rdkafka::error::KafkaError },
     #[snafu(display("Could not subscribe to Kafka topics: {}", source))]
     KafkaSubscribeError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Only one of `avro` and `protobuf` can be set"))]
+    ConflictingDecoders,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -48,6 +58,12 @@ pub struct KafkaSourceConfig {
     partition_key: Option<String>,
     offset_key: Option<String>,
     librdkafka_options: Option<HashMap<String, String>>,
+    /// Decode message payloads as Avro records in the Confluent wire format,
+    /// into fields at the root of the event.
+    avro: Option<AvroConfig>,
+    /// Decode message payloads as this protobuf message type, into fields
+    /// at the root of the event.
+    protobuf: Option<ProtobufConfig>,
     #[serde(flatten)]
     auth: KafkaAuthConfig,
 }
@@ -109,6 +125,12 @@ fn kafka_source(
     let topic_key = config.topic_key.clone();
     let partition_key = config.partition_key.clone();
     let offset_key = config.offset_key.clone();
+    let decoder = match (&config.avro, &config.protobuf) {
+        (Some(_), Some(_)) => return Err(BuildError::ConflictingDecoders.into()),
+        (Some(avro), None) => Some(PayloadDecoder::Avro(avro.build()?)),
+        (None, Some(protobuf)) => Some(PayloadDecoder::Protobuf(protobuf.build()?)),
+        (None, None) => None,
+    };
     let consumer = Arc::new(create_consumer(config)?);
 
     Ok(Box::pin(async move {
@@ -120,6 +142,7 @@ fn kafka_source(
                 let topic_key = topic_key.clone();
                 let partition_key = partition_key.clone();
                 let offset_key = offset_key.clone();
+                let decoder = decoder.clone();
                 let consumer = Arc::clone(&consumer);
 
                 async move {
@@ -140,10 +163,23 @@ fn kafka_source(
                             let mut event = Event::new_empty_log();
                             let log = event.as_mut_log();
 
-                            log.insert(
-                                log_schema().message_key(),
-                                Value::from(Bytes::from(payload.to_owned())),
-                            );
+                            let decoded = match &decoder {
+                                Some(decoder) => decoder.decode(payload).await,
+                                None => None,
+                            };
+                            match decoded {
+                                Some(fields) => {
+                                    for (key, value) in fields {
+                                        log.insert_flat(key, value);
+                                    }
+                                }
+                                None => {
+                                    log.insert(
+                                        log_schema().message_key(),
+                                        Value::from(Bytes::from(payload.to_owned())),
+                                    );
+                                }
+                            }
 
                             // Extract timestamp from kafka message
                             let timestamp = msg
@@ -210,6 +246,30 @@ fn kafka_source(
     }))
 }
 
+/// Decodes message payloads into event fields.
+#[derive(Clone, Debug)]
+enum PayloadDecoder {
+    Avro(AvroDecoder),
+    Protobuf(ProtobufDecoder),
+}
+
+impl PayloadDecoder {
+    /// Returns `None` if the payload can't be decoded.
+    async fn decode(&self, payload: &[u8]) -> Option<BTreeMap<String, Value>> {
+        match self {
+            PayloadDecoder::Avro(decoder) => decoder
+                .decode(payload)
+                .await
+                .map_err(|error| emit!(AvroDecodeFailed { error: &error }))
+                .ok(),
+            PayloadDecoder::Protobuf(decoder) => decoder
+                .decode(payload)
+                .map_err(|error| emit!(ProtobufDecodeFailed { error: &error }))
+                .ok(),
+        }
+    }
+}
+
 fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer> {
     let mut client_config = ClientConfig::new();
     client_config
@@ -246,7 +306,7 @@ fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer>
 #[cfg(test)]
 mod test {
     use super::{kafka_source, KafkaSourceConfig};
-    use crate::{shutdown::ShutdownSignal, Pipeline};
+    use crate::{avro::AvroConfig, protobuf::ProtobufConfig, shutdown::ShutdownSignal, Pipeline};
 
     #[test]
     fn generate_config() {
@@ -285,6 +345,31 @@ mod test {
         };
         assert!(kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0).is_err());
     }
+
+    #[test]
+    fn kafka_source_create_conflicting_decoders() {
+        let config = KafkaSourceConfig {
+            avro: Some(AvroConfig {
+                registry_url: "http://localhost:8081".into(),
+                ..Default::default()
+            }),
+            protobuf: Some(ProtobufConfig::default()),
+            ..make_config()
+        };
+        assert!(kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0).is_err());
+    }
+
+    #[test]
+    fn kafka_source_create_missing_descriptor_set() {
+        let config = KafkaSourceConfig {
+            protobuf: Some(ProtobufConfig {
+                descriptor_set: "/nonexistent/descriptors.pb".into(),
+                message_type: "test.Event".into(),
+            }),
+            ..make_config()
+        };
+        assert!(kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0).is_err());
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -182,7 +182,9 @@ mod test {
             addr,
             VectorConfig::new(addr.into(), None),
             VectorSinkConfig {
-                address: format!("localhost:{}", addr.port()),
+                address: Some(format!("localhost:{}", addr.port())),
+                addresses: Vec::new(),
+                load_balancing: Default::default(),
                 tls: None,
             },
         )
@@ -196,7 +198,9 @@ mod test {
             addr,
             VectorConfig::new(addr.into(), Some(TlsConfig::test_config())),
             VectorSinkConfig {
-                address: format!("localhost:{}", addr.port()),
+                address: Some(format!("localhost:{}", addr.port())),
+                addresses: Vec::new(),
+                load_balancing: Default::default(),
                 tls: Some(TlsConfig {
                     enabled: Some(true),
                     options: TlsOptions {
@@ -13,16 +13,24 @@ impl MaybeTlsSettings {
 
         match self {
             MaybeTlsSettings::Raw(()) => Ok(MaybeTlsStream::Raw(stream)),
-            MaybeTlsSettings::Tls(_) => {
-                let config = tls_connector(self)?;
-                let stream = tokio_openssl::connect(config, host, stream)
-                    .await
-                    .context(Handshake)?;
+            MaybeTlsSettings::Tls(_) => self.upgrade(host, stream).await,
+        }
+    }
 
-                debug!(message = "Negotiated TLS.");
+    /// Negotiates TLS over an established connection, for protocols that
+    /// switch to TLS in-band such as SMTP with `STARTTLS`.
+    pub(crate) async fn upgrade(
+        &self,
+        host: &str,
+        stream: TcpStream,
+    ) -> crate::tls::Result<MaybeTlsStream<TcpStream>> {
+        let config = tls_connector(self)?;
+        let stream = tokio_openssl::connect(config, host, stream)
+            .await
+            .context(Handshake)?;
 
-                Ok(MaybeTlsStream::Tls(stream))
-            }
-        }
+        debug!(message = "Negotiated TLS.");
+
+        Ok(MaybeTlsStream::Tls(stream))
     }
 }
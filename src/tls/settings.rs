@@ -137,6 +137,34 @@ impl TlsSettings {
         })
     }
 
+    /// The identity as a DER encoded PKCS#12 archive and its password, for
+    /// clients that build their own TLS connector.
+    pub fn identity_pkcs12(&self) -> Option<(&[u8], &str)> {
+        self.identity
+            .as_ref()
+            .map(|identity| (&identity.0[..], identity.1.as_str()))
+    }
+
+    /// The certificate authorities, concatenated in PEM format, for clients
+    /// that build their own TLS connector.
+    pub fn authorities_pem(&self) -> Option<String> {
+        if self.authorities.is_empty() {
+            return None;
+        }
+
+        let pem = self
+            .authorities
+            .iter()
+            .flat_map(|authority| {
+                // These were parsed from PEM or DER files, so they encode.
+                authority
+                    .to_pem()
+                    .expect("Could not encode parsed certificate as PEM")
+            })
+            .collect::<Vec<u8>>();
+        Some(String::from_utf8(pem).expect("PEM is ASCII"))
+    }
+
     pub(super) fn apply_context(&self, context: &mut SslContextBuilder) -> Result<()> {
         context.set_verify(if self.verify_certificate {
             SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT
@@ -6,42 +6,89 @@ use super::{
 use crate::{
     buffers,
     config::{DataType, SinkContext},
+    enrichment_tables,
     event::Event,
     shutdown::SourceShutdownCoordinator,
-    transforms::Transform,
+    transforms::{FunctionTransform, Transform, TransformOutputs},
     Pipeline,
 };
 use futures::{
-    compat::{Future01CompatExt, Stream01CompatExt},
-    future, FutureExt, StreamExt, TryFutureExt,
+    compat::{Future01CompatExt, Sink01CompatExt, Stream01CompatExt},
+    future::{self, AbortHandle, Abortable},
+    FutureExt, SinkExt, StreamExt, TryFutureExt,
+};
+use futures01::{sync::mpsc, Future as Future01, Poll, Stream as Stream01};
+use std::{
+    collections::HashMap,
+    future::ready,
+    sync::{Arc, Mutex},
 };
-use futures01::{sync::mpsc, Future as Future01, Stream as Stream01};
-use std::{collections::HashMap, future::ready};
 use tokio::time::{timeout, Duration};
 
+type BufferReader = Box<dyn Stream01<Item = Event, Error = ()> + Send>;
+
+/// The buffer of a sink. The running topology holds on to it, so that a sink
+/// rebuilt with the same buffer configuration reads on from where the sink it
+/// replaces stopped.
+#[derive(Clone)]
+pub struct SinkBuffer {
+    tx: buffers::BufferInputCloner,
+    rx: Arc<Mutex<BufferReader>>,
+    acker: buffers::Acker,
+}
+
+/// Reads a buffer shared with the topology.
+struct SharedReader(Arc<Mutex<BufferReader>>);
+
+impl Stream01 for SharedReader {
+    type Item = Event;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Event>, ()> {
+        self.0.lock().unwrap().poll()
+    }
+}
+
 pub struct Pieces {
     pub inputs: HashMap<String, (buffers::BufferInputCloner, Vec<String>)>,
     pub outputs: HashMap<String, fanout::ControlChannel>,
+    /// The full names of the named outputs of each transform and sink.
+    pub named_outputs: HashMap<String, Vec<String>>,
     pub tasks: HashMap<String, Task>,
     pub source_tasks: HashMap<String, Task>,
     pub healthchecks: HashMap<String, Task>,
     pub shutdown_coordinator: SourceShutdownCoordinator,
+    pub buffers: HashMap<String, SinkBuffer>,
+    /// End the input of each sink, leaving the events it hasn't read in its
+    /// buffer.
+    pub detach_handles: HashMap<String, AbortHandle>,
 }
 
-/// Builds only the new pieces, and doesn't check their topology.
+/// Builds only the new pieces, and doesn't check their topology. Sinks found
+/// in `reused_buffers` read from those instead of building their own.
 pub async fn build_pieces(
     config: &super::Config,
     diff: &ConfigDiff,
+    mut reused_buffers: HashMap<String, SinkBuffer>,
 ) -> Result<Pieces, Vec<String>> {
     let mut inputs = HashMap::new();
     let mut outputs = HashMap::new();
+    let mut named_outputs = HashMap::new();
     let mut tasks = HashMap::new();
     let mut source_tasks = HashMap::new();
     let mut healthchecks = HashMap::new();
     let mut shutdown_coordinator = SourceShutdownCoordinator::default();
+    let mut buffers = HashMap::new();
+    let mut detach_handles = HashMap::new();
 
     let mut errors = vec![];
 
+    // Transforms look up enrichment tables while being built, so these have to
+    // be loaded first. They are only committed once the topology is started.
+    if let Err(table_errors) = enrichment_tables::stage(&config.enrichment_tables) {
+        errors.extend(table_errors);
+    }
+
     // Build sources
     for (name, source) in config
         .sources
@@ -86,17 +133,17 @@ pub async fn build_pieces(
     }
 
     // Build transforms
-    for (name, transform) in config
+    for (name, transform_config) in config
         .transforms
         .iter()
         .filter(|(name, _)| diff.transforms.contains_new(&name))
     {
-        let trans_inputs = &transform.inputs;
+        let trans_inputs = &transform_config.inputs;
 
-        let typetag = transform.inner.transform_type();
+        let typetag = transform_config.inner.transform_type();
 
-        let input_type = transform.inner.input_type();
-        let transform = match transform.inner.build().await {
+        let input_type = transform_config.inner.input_type();
+        let transform = match transform_config.inner.build().await {
             Err(error) => {
                 errors.push(format!("Transform \"{}\": {}", name, error));
                 continue;
@@ -104,12 +151,37 @@ pub async fn build_pieces(
             Ok(transform) => transform,
         };
 
+        let transform_outputs = transform_config.inner.named_outputs();
+        if !transform_outputs.is_empty() && matches!(transform, Transform::Task(_)) {
+            errors.push(format!(
+                "Transform \"{}\": Named outputs are only supported by function transforms.",
+                name
+            ));
+            continue;
+        }
+
         let (input_tx, input_rx) = futures01::sync::mpsc::channel(100);
         let input_tx = buffers::BufferInputCloner::Memory(input_tx, buffers::WhenFull::Block);
 
         let (output, control) = Fanout::new();
 
+        let mut named = Vec::new();
+        for output_name in transform_outputs {
+            let full_name = format!("{}.{}", name, output_name);
+            let (output, control) = Fanout::new();
+            outputs.insert(full_name.clone(), control);
+            named_outputs
+                .entry(name.clone())
+                .or_insert_with(Vec::new)
+                .push(full_name);
+            named.push((output_name, output));
+        }
+
         let transform = match transform {
+            Transform::Function(t) if !named.is_empty() => {
+                let filtered = filter_event_type(input_rx, input_type);
+                run_with_named_outputs(t, filtered, output, named, name.clone()).boxed()
+            }
             Transform::Function(mut t) => {
                 let filtered = filter_event_type(input_rx, input_type);
                 #[allow(deprecated)]
@@ -122,17 +194,23 @@ pub async fn build_pieces(
                     })
                     .flatten()
                     .boxed();
-                transformed.forward(output)
+                transformed
+                    .forward(output)
+                    .map(|_| debug!("Finished."))
+                    .compat()
+                    .boxed()
             }
             Transform::Task(t) => {
                 let filtered = filter_event_type(input_rx, input_type);
                 let transformed: Box<dyn futures01::Stream<Item = _, Error = _> + Send> =
                     t.transform(filtered);
-                transformed.forward(output)
+                transformed
+                    .forward(output)
+                    .map(|_| debug!("Finished."))
+                    .compat()
+                    .boxed()
             }
-        }
-        .map(|_| debug!("Finished."))
-        .compat();
+        };
         let task = Task::new(name, typetag, transform);
 
         inputs.insert(name.clone(), (input_tx, trans_inputs.clone()));
@@ -152,16 +230,42 @@ pub async fn build_pieces(
         let typetag = sink.inner.sink_type();
         let input_type = sink.inner.input_type();
 
-        let buffer = sink.buffer.build(&config.global.data_dir, &name);
-        let (tx, rx, acker) = match buffer {
-            Err(error) => {
-                errors.push(format!("Sink \"{}\": {}", name, error));
-                continue;
-            }
-            Ok(buffer) => buffer,
+        let buffer = match reused_buffers.remove(name) {
+            Some(buffer) => buffer,
+            None => match sink.buffer.build(&config.global.data_dir, &name) {
+                Err(error) => {
+                    errors.push(format!("Sink \"{}\": {}", name, error));
+                    continue;
+                }
+                Ok((tx, rx, acker)) => SinkBuffer {
+                    tx,
+                    rx: Arc::new(Mutex::new(rx)),
+                    acker,
+                },
+            },
         };
 
-        let cx = SinkContext { acker };
+        // Named outputs are fed like sources, through a pipeline pumped into
+        // their fanout.
+        let mut sink_outputs = HashMap::new();
+        let mut pumps = Vec::new();
+        for output_name in sink.named_outputs() {
+            let full_name = format!("{}.{}", name, output_name);
+            let (output_tx, output_rx) = mpsc::channel(1000);
+            let (output, control) = Fanout::new();
+            outputs.insert(full_name.clone(), control);
+            named_outputs
+                .entry(name.clone())
+                .or_insert_with(Vec::new)
+                .push(full_name);
+            sink_outputs.insert(output_name, Pipeline::from_sender(output_tx, vec![]));
+            pumps.push(output_rx.forward(output).map(|_| ()).compat());
+        }
+
+        let cx = SinkContext {
+            acker: buffer.acker.clone(),
+            outputs: sink_outputs,
+        };
 
         let (sink, healthcheck) = match sink.inner.build(cx).await {
             Err(error) => {
@@ -171,13 +275,18 @@ pub async fn build_pieces(
             Ok((sink, healthcheck)) => (sink, healthcheck),
         };
 
-        let sink = sink
-            .run(
-                filter_event_type(rx, input_type)
-                    .compat()
-                    .take_while(|e| ready(e.is_ok()))
-                    .map(|x| x.unwrap()),
-            )
+        let (detach_handle, registration) = AbortHandle::new_pair();
+        let rx = SharedReader(Arc::clone(&buffer.rx));
+        let sink = sink.run(Abortable::new(
+            filter_event_type(rx, input_type)
+                .compat()
+                .take_while(|e| ready(e.is_ok()))
+                .map(|x| x.unwrap()),
+            registration,
+        ));
+        // The pumps finish once the sink drops its pipelines.
+        let sink = future::try_join(sink, future::try_join_all(pumps))
+            .map_ok(|_| ())
             .inspect(|_| debug!("Finished."));
         let task = Task::new(name, typetag, sink);
 
@@ -207,27 +316,79 @@ pub async fn build_pieces(
         };
         let healthcheck_task = Task::new(name, typetag, healthcheck_task);
 
-        inputs.insert(name.clone(), (tx, sink_inputs.clone()));
+        inputs.insert(name.clone(), (buffer.tx.clone(), sink_inputs.clone()));
         healthchecks.insert(name.clone(), healthcheck_task);
         tasks.insert(name.clone(), task);
+        buffers.insert(name.clone(), buffer);
+        detach_handles.insert(name.clone(), detach_handle);
     }
 
     if errors.is_empty() {
         let pieces = Pieces {
             inputs,
             outputs,
+            named_outputs,
             tasks,
             source_tasks,
             healthchecks,
             shutdown_coordinator,
+            buffers,
+            detach_handles,
         };
 
         Ok(pieces)
     } else {
+        enrichment_tables::discard();
         Err(errors)
     }
 }
 
+/// Runs a function transform that sends events to named outputs besides its
+/// main one.
+async fn run_with_named_outputs(
+    mut transform: Box<dyn FunctionTransform>,
+    input: Box<dyn Stream01<Item = Event, Error = ()> + Send>,
+    output: Fanout,
+    named: Vec<(String, Fanout)>,
+    name: String,
+) -> Result<(), ()> {
+    let mut input = input.compat();
+    let mut output = output.sink_compat();
+    let mut named = named
+        .into_iter()
+        .map(|(output_name, output)| (output_name, output.sink_compat()))
+        .collect::<HashMap<_, _>>();
+
+    let mut buf = TransformOutputs::new(name);
+    while let Some(event) = input.next().await {
+        transform.transform_outputs(&mut buf, event?);
+
+        for event in buf.primary.drain(..) {
+            output.send(event).await?;
+        }
+        for (output_name, events) in buf.named.iter_mut() {
+            match named.get_mut(output_name) {
+                Some(output) => {
+                    for event in events.drain(..) {
+                        output.send(event).await?;
+                    }
+                }
+                // The transform didn't declare this output, so nothing can
+                // be consuming it.
+                None => events.clear(),
+            }
+        }
+    }
+
+    output.close().await?;
+    for output in named.values_mut() {
+        output.close().await?;
+    }
+
+    debug!("Finished.");
+    Ok(())
+}
+
 fn filter_event_type<S>(
     stream: S,
     data_type: DataType,
@@ -13,10 +13,18 @@ mod task;
 use crate::{
     buffers,
     config::{Config, ConfigDiff, Resource},
+    enrichment_tables,
     shutdown::SourceShutdownCoordinator,
-    topology::{builder::Pieces, task::Task},
+    topology::{
+        builder::{Pieces, SinkBuffer},
+        task::Task,
+    },
+};
+use futures::{
+    compat::Future01CompatExt,
+    future::{self, AbortHandle},
+    FutureExt, StreamExt, TryFutureExt,
 };
-use futures::{compat::Future01CompatExt, future, FutureExt, StreamExt, TryFutureExt};
 use futures01::{sync::mpsc, Future};
 use std::{
     collections::{HashMap, HashSet},
@@ -35,6 +43,8 @@ pub struct RunningTopology {
     outputs: HashMap<String, fanout::ControlChannel>,
     source_tasks: HashMap<String, TaskHandle>,
     tasks: HashMap<String, TaskHandle>,
+    buffers: HashMap<String, SinkBuffer>,
+    detach_handles: HashMap<String, AbortHandle>,
     shutdown_coordinator: SourceShutdownCoordinator,
     config: Config,
     abort_tx: mpsc::UnboundedSender<()>,
@@ -55,6 +65,8 @@ pub async fn start_validated(
         shutdown_coordinator: SourceShutdownCoordinator::default(),
         source_tasks: HashMap::new(),
         tasks: HashMap::new(),
+        buffers: HashMap::new(),
+        detach_handles: HashMap::new(),
         abort_tx,
     };
 
@@ -62,16 +74,22 @@ pub async fn start_validated(
         .run_healthchecks(&diff, &mut pieces, require_healthy)
         .await
     {
+        enrichment_tables::discard();
         return None;
     }
+    enrichment_tables::commit();
     running_topology.connect_diff(&diff, &mut pieces).await;
     running_topology.spawn_diff(&diff, pieces);
 
     Some((running_topology, abort_rx))
 }
 
-pub async fn build_or_log_errors(config: &Config, diff: &ConfigDiff) -> Option<Pieces> {
-    match builder::build_pieces(config, diff).await {
+pub async fn build_or_log_errors(
+    config: &Config,
+    diff: &ConfigDiff,
+    buffers: HashMap<String, SinkBuffer>,
+) -> Option<Pieces> {
+    match builder::build_pieces(config, diff, buffers).await {
         Err(errors) => {
             for error in errors {
                 error!(message = "Configuration error.", %error);
@@ -247,8 +265,27 @@ impl RunningTopology {
             .map(|(_, name)| name.clone())
             .collect();
 
+        // Changed sinks whose buffer is configured the same hand it over to
+        // the sinks replacing them, so the events in it aren't lost.
+        let reuse_buffers = diff
+            .sinks
+            .to_change
+            .iter()
+            .filter(|&name| {
+                let old_buffer = serde_json::to_vec(&self.config.sinks[name].buffer).unwrap();
+                let new_buffer = serde_json::to_vec(&new_config.sinks[name].buffer).unwrap();
+                old_buffer == new_buffer
+            })
+            .cloned()
+            .collect::<HashSet<_>>();
+        let buffers = reuse_buffers
+            .iter()
+            .map(|name| (name.clone(), self.buffers[name].clone()))
+            .collect::<HashMap<_, _>>();
+
         // Checks passed so let's shutdown the difference.
-        self.shutdown_diff(&diff, &wait_for_sinks).await;
+        self.shutdown_diff(&diff, &wait_for_sinks, &reuse_buffers)
+            .await;
 
         // Gives windows some time to make available any port
         // released by shutdown componenets.
@@ -259,11 +296,13 @@ impl RunningTopology {
         }
 
         // Now let's actually build the new pieces.
-        if let Some(mut new_pieces) = build_or_log_errors(&new_config, &diff).await {
+        if let Some(mut new_pieces) = build_or_log_errors(&new_config, &diff, buffers.clone()).await
+        {
             if self
                 .run_healthchecks(&diff, &mut new_pieces, require_healthy)
                 .await
             {
+                enrichment_tables::commit();
                 self.connect_diff(&diff, &mut new_pieces).await;
                 self.spawn_diff(&diff, new_pieces);
                 self.config = new_config;
@@ -275,7 +314,10 @@ impl RunningTopology {
         // We need to rebuild the removed.
         info!("Rebuilding old configuration.");
         let diff = diff.flip();
-        if let Some(mut new_pieces) = build_or_log_errors(&self.config, &diff).await {
+        let old_pieces = build_or_log_errors(&self.config, &diff, buffers).await;
+        // The old enrichment tables are still the committed ones.
+        enrichment_tables::discard();
+        if let Some(mut new_pieces) = old_pieces {
             if self
                 .run_healthchecks(&diff, &mut new_pieces, require_healthy)
                 .await
@@ -322,7 +364,12 @@ impl RunningTopology {
     }
 
     /// Shutdowns removed and replaced pieces of topology.
-    async fn shutdown_diff(&mut self, diff: &ConfigDiff, wait_for_sinks: &HashSet<String>) {
+    async fn shutdown_diff(
+        &mut self,
+        diff: &ConfigDiff,
+        wait_for_sinks: &HashSet<String>,
+        reuse_buffers: &HashSet<String>,
+    ) {
         // Sources
         let timeout = Duration::from_secs(30); //sec
 
@@ -392,13 +439,22 @@ impl RunningTopology {
         for name in &diff.sinks.to_remove {
             info!(message = "Removing sink.", name = ?name);
             self.remove_inputs(&name);
+            self.remove_outputs(&name);
+            self.buffers.remove(name);
+            self.detach_handles.remove(name);
         }
 
-        // Detach changed sinks that we have to wait for.
+        // Detach changed sinks that we have to wait for. Those keeping their
+        // buffer stay attached to their inputs, and only stop reading it.
         for name in &diff.sinks.to_change {
-            if wait_for_sinks.contains(name) {
+            if reuse_buffers.contains(name) {
+                if let Some(handle) = self.detach_handles.remove(name) {
+                    handle.abort();
+                }
+            } else if wait_for_sinks.contains(name) {
                 self.detach_inputs(&name);
             }
+            self.remove_outputs(&name);
         }
 
         // Second pass for final cleanup
@@ -412,7 +468,7 @@ impl RunningTopology {
         }
 
         for name in &diff.sinks.to_change {
-            if wait_for_sinks.contains(name) {
+            if wait_for_sinks.contains(name) || reuse_buffers.contains(name) {
                 let previous = self.tasks.remove(name).unwrap();
                 previous.await.unwrap().unwrap();
             }
@@ -433,6 +489,13 @@ impl RunningTopology {
             self.setup_outputs(&name, new_pieces);
         }
 
+        // The named outputs of sinks can be inputs of transforms too.
+        for name in diff.sinks.changed_and_added() {
+            for output in new_pieces.named_outputs.remove(name).unwrap_or_default() {
+                self.setup_output(&output, new_pieces);
+            }
+        }
+
         for name in &diff.transforms.to_change {
             self.replace_inputs(&name, new_pieces);
         }
@@ -500,6 +563,11 @@ impl RunningTopology {
         if let Some(previous) = self.tasks.insert(name.to_string(), spawned) {
             drop(previous); // detach and forget
         }
+
+        let buffer = new_pieces.buffers.remove(name).unwrap();
+        self.buffers.insert(name.to_string(), buffer);
+        let detach_handle = new_pieces.detach_handles.remove(name).unwrap();
+        self.detach_handles.insert(name.to_string(), detach_handle);
     }
 
     fn spawn_transform(&mut self, name: &str, new_pieces: &mut builder::Pieces) {
@@ -543,6 +611,18 @@ impl RunningTopology {
 
     fn remove_outputs(&mut self, name: &str) {
         self.outputs.remove(name);
+
+        let named_outputs = match (
+            self.config.transforms.get(name),
+            self.config.sinks.get(name),
+        ) {
+            (Some(transform), _) => transform.inner.named_outputs(),
+            (None, Some(sink)) => sink.named_outputs(),
+            (None, None) => Vec::new(),
+        };
+        for output in named_outputs {
+            self.outputs.remove(&format!("{}.{}", name, output));
+        }
     }
 
     fn remove_inputs(&mut self, name: &str) {
@@ -564,6 +644,14 @@ impl RunningTopology {
     }
 
     fn setup_outputs(&mut self, name: &str, new_pieces: &mut builder::Pieces) {
+        self.setup_output(name, new_pieces);
+
+        for output in new_pieces.named_outputs.remove(name).unwrap_or_default() {
+            self.setup_output(&output, new_pieces);
+        }
+    }
+
+    fn setup_output(&mut self, name: &str, new_pieces: &mut builder::Pieces) {
         let output = new_pieces.outputs.remove(name).unwrap();
 
         for (sink_name, sink) in &self.config.sinks {
@@ -713,6 +801,8 @@ mod tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                pretty: Default::default(),
+                table: Default::default(),
             },
         );
         old_config.global.data_dir = Some(Path::new("/asdf").to_path_buf());
@@ -767,6 +857,8 @@ mod reload_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                pretty: Default::default(),
+                table: Default::default(),
             },
         );
 
@@ -778,6 +870,8 @@ mod reload_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                pretty: Default::default(),
+                table: Default::default(),
             },
         );
 
@@ -801,6 +895,8 @@ mod reload_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                pretty: Default::default(),
+                table: Default::default(),
             },
         );
 
@@ -812,6 +908,8 @@ mod reload_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                pretty: Default::default(),
+                table: Default::default(),
             },
         );
 
@@ -837,6 +935,8 @@ mod reload_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                pretty: Default::default(),
+                table: Default::default(),
             },
         );
 
@@ -999,6 +1099,8 @@ mod source_finished_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                pretty: Default::default(),
+                table: Default::default(),
             },
         );
 
@@ -1095,8 +1197,8 @@ mod transient_state_tests {
                 ..JsonParserConfig::default()
             },
         );
-        old_config.add_sink("out1", &["trans"], BlackholeConfig { print_amount: 1000 });
-        old_config.add_sink("out2", &["trans"], BlackholeConfig { print_amount: 1000 });
+        old_config.add_sink("out1", &["trans"], BlackholeConfig::default());
+        old_config.add_sink("out2", &["trans"], BlackholeConfig::default());
 
         let mut new_config = Config::builder();
         let (_trigger_new, source) = MockSourceConfig::new();
@@ -1109,7 +1211,7 @@ mod transient_state_tests {
                 ..JsonParserConfig::default()
             },
         );
-        new_config.add_sink("out1", &["trans"], BlackholeConfig { print_amount: 1000 });
+        new_config.add_sink("out1", &["trans"], BlackholeConfig::default());
 
         let (mut topology, _crash) = start_topology(old_config.build().unwrap(), false).await;
 
@@ -1137,8 +1239,8 @@ mod transient_state_tests {
                 ..JsonParserConfig::default()
             },
         );
-        old_config.add_sink("out1", &["trans"], BlackholeConfig { print_amount: 1000 });
-        old_config.add_sink("out2", &["trans"], BlackholeConfig { print_amount: 1000 });
+        old_config.add_sink("out1", &["trans"], BlackholeConfig::default());
+        old_config.add_sink("out2", &["trans"], BlackholeConfig::default());
 
         let mut new_config = Config::builder();
         new_config.add_source("in", StdinConfig::default());
@@ -1150,7 +1252,7 @@ mod transient_state_tests {
                 ..JsonParserConfig::default()
             },
         );
-        new_config.add_sink("out1", &["trans"], BlackholeConfig { print_amount: 1000 });
+        new_config.add_sink("out1", &["trans"], BlackholeConfig::default());
 
         let (mut topology, _crash) = start_topology(old_config.build().unwrap(), false).await;
         assert!(topology
@@ -1181,8 +1283,8 @@ mod transient_state_tests {
                 ..JsonParserConfig::default()
             },
         );
-        old_config.add_sink("out1", &["trans1"], BlackholeConfig { print_amount: 1000 });
-        old_config.add_sink("out2", &["trans2"], BlackholeConfig { print_amount: 1000 });
+        old_config.add_sink("out1", &["trans1"], BlackholeConfig::default());
+        old_config.add_sink("out2", &["trans2"], BlackholeConfig::default());
 
         let mut new_config = Config::builder();
         new_config.add_source("in", StdinConfig::default());
@@ -1194,7 +1296,7 @@ mod transient_state_tests {
                 ..JsonParserConfig::default()
             },
         );
-        new_config.add_sink("out1", &["trans1"], BlackholeConfig { print_amount: 1000 });
+        new_config.add_sink("out1", &["trans1"], BlackholeConfig::default());
 
         let (mut topology, _crash) = start_topology(old_config.build().unwrap(), false).await;
         assert!(topology
@@ -0,0 +1,387 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::discriminant::Discriminant,
+    event::{Event, LogEvent, Value},
+    internal_events::{AnomalyDetected, AnomalyEventProcessed},
+    transforms::{FunctionTransform, Transform, TransformOutputs},
+};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::collections::HashMap;
+
+/// The named output alert events are sent to.
+pub const ALERTS: &str = "alerts";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`smoothing` must be between 0 and 1"))]
+    InvalidSmoothing,
+    #[snafu(display("`threshold` must be greater than zero"))]
+    InvalidThreshold,
+    #[snafu(display("`rate_interval_secs` must be greater than zero"))]
+    ZeroRateInterval,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct AnomalyConfig {
+    /// The numeric field to watch. When unset, the rate of events is watched
+    /// instead.
+    pub field: Option<String>,
+    /// The length of the intervals events are counted over in rate mode.
+    #[derivative(Default(value = "60"))]
+    pub rate_interval_secs: u64,
+    /// An ordered list of fields to group events by. Each group has its own
+    /// baseline.
+    pub group_by: Vec<String>,
+    /// How much weight each new sample gets in the moving baseline.
+    #[derivative(Default(value = "0.1"))]
+    pub smoothing: f64,
+    /// How many standard deviations from the baseline a sample has to be to
+    /// count as an anomaly.
+    #[derivative(Default(value = "3.0"))]
+    pub threshold: f64,
+    /// How many samples a baseline needs before anomalies are reported.
+    #[derivative(Default(value = "30"))]
+    pub warmup_samples: u64,
+    /// In field mode, set this field to whether the event was anomalous.
+    pub flag_field: Option<String>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<AnomalyConfig>("anomaly")
+}
+
+impl GenerateConfig for AnomalyConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"field = "duration_ms"
+            group_by = ["service"]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "anomaly")]
+impl TransformConfig for AnomalyConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        Anomaly::new(self).map(Transform::function)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "anomaly"
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        vec![ALERTS.to_owned()]
+    }
+}
+
+/// An exponentially weighted moving mean and variance.
+#[derive(Clone, Debug, Default)]
+struct Baseline {
+    mean: f64,
+    variance: f64,
+    samples: u64,
+}
+
+impl Baseline {
+    /// Scores the sample against the baseline, then adds it.
+    fn observe(&mut self, value: f64, smoothing: f64) -> Option<f64> {
+        let score = if self.samples == 0 {
+            None
+        } else {
+            let stddev = self.variance.sqrt();
+            Some(if stddev > 0.0 {
+                (value - self.mean) / stddev
+            } else if value == self.mean {
+                0.0
+            } else {
+                f64::INFINITY.copysign(value - self.mean)
+            })
+        };
+
+        if self.samples == 0 {
+            self.mean = value;
+        } else {
+            let diff = value - self.mean;
+            let increment = smoothing * diff;
+            self.mean += increment;
+            self.variance = (1.0 - smoothing) * (self.variance + diff * increment);
+        }
+        self.samples += 1;
+
+        score
+    }
+}
+
+#[derive(Clone, Debug)]
+struct GroupState {
+    group: Vec<(String, Value)>,
+    baseline: Baseline,
+    /// The rate interval being counted and its count so far.
+    interval: i64,
+    count: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Anomaly {
+    field: Option<String>,
+    interval_ms: i64,
+    group_by: Vec<String>,
+    smoothing: f64,
+    threshold: f64,
+    warmup_samples: u64,
+    flag_field: Option<String>,
+    groups: HashMap<Discriminant, GroupState>,
+}
+
+impl Anomaly {
+    fn new(config: &AnomalyConfig) -> crate::Result<Self> {
+        if config.smoothing <= 0.0 || config.smoothing > 1.0 {
+            return Err(BuildError::InvalidSmoothing.into());
+        }
+        if config.threshold <= 0.0 {
+            return Err(BuildError::InvalidThreshold.into());
+        }
+        if config.rate_interval_secs == 0 {
+            return Err(BuildError::ZeroRateInterval.into());
+        }
+
+        Ok(Self {
+            field: config.field.clone(),
+            interval_ms: config.rate_interval_secs as i64 * 1000,
+            group_by: config.group_by.clone(),
+            smoothing: config.smoothing,
+            threshold: config.threshold,
+            warmup_samples: config.warmup_samples,
+            flag_field: config.flag_field.clone(),
+            groups: HashMap::new(),
+        })
+    }
+
+    /// Scores a sample, returning the alert to emit if it is anomalous.
+    fn score(&self, state: &mut GroupState, value: f64, timestamp: DateTime<Utc>) -> Option<Event> {
+        let mean = state.baseline.mean;
+        let stddev = state.baseline.variance.sqrt();
+        let warmed_up = state.baseline.samples >= self.warmup_samples;
+        let score = state.baseline.observe(value, self.smoothing)?;
+        if !warmed_up || score.abs() < self.threshold {
+            return None;
+        }
+
+        let watched = self.field.as_deref().unwrap_or("rate");
+        emit!(AnomalyDetected {
+            field: watched,
+            value,
+            score
+        });
+
+        let mut alert = Event::new_empty_log();
+        let log = alert.as_mut_log();
+        for (field, value) in &state.group {
+            log.insert(field, value.clone());
+        }
+        log.insert(
+            log_schema().message_key(),
+            format!(
+                "Anomalous {}: {} is {:.1} standard deviations from the baseline of {}.",
+                watched, value, score, mean
+            ),
+        );
+        log.insert(log_schema().timestamp_key(), timestamp);
+        log.insert("anomaly.field", watched);
+        log.insert("anomaly.value", value);
+        log.insert("anomaly.mean", mean);
+        log.insert("anomaly.stddev", stddev);
+        log.insert("anomaly.score", score);
+        Some(alert)
+    }
+
+    /// Processes the event, returning whether it was anomalous and any alert.
+    fn process(&mut self, log: &LogEvent) -> (bool, Option<Event>) {
+        let timestamp = match log.get(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(timestamp)) => *timestamp,
+            _ => Utc::now(),
+        };
+
+        let sample = match &self.field {
+            Some(field) => match log.get(field) {
+                Some(Value::Integer(value)) => Some(*value as f64),
+                Some(Value::Float(value)) => Some(*value),
+                _ => return (false, None),
+            },
+            None => None,
+        };
+
+        let discriminant = Discriminant::from_log_event(log, &self.group_by);
+        let mut state = match self.groups.remove(&discriminant) {
+            Some(state) => state,
+            None => GroupState {
+                group: self
+                    .group_by
+                    .iter()
+                    .filter_map(|field| log.get(field).map(|value| (field.clone(), value.clone())))
+                    .collect(),
+                baseline: Baseline::default(),
+                interval: timestamp.timestamp_millis().div_euclid(self.interval_ms),
+                count: 0,
+            },
+        };
+
+        let result = match sample {
+            Some(value) => {
+                let alert = self.score(&mut state, value, timestamp);
+                (alert.is_some(), alert)
+            }
+            None => {
+                // Rates are scored when the first event of a later interval
+                // arrives.
+                let interval = timestamp.timestamp_millis().div_euclid(self.interval_ms);
+                let mut alert = None;
+                if interval > state.interval {
+                    let end = Utc.timestamp_millis((state.interval + 1) * self.interval_ms);
+                    let count = state.count as f64;
+                    alert = self.score(&mut state, count, end);
+                    state.interval = interval;
+                    state.count = 0;
+                }
+                if interval == state.interval {
+                    state.count += 1;
+                }
+                (false, alert)
+            }
+        };
+
+        self.groups.insert(discriminant, state);
+        result
+    }
+}
+
+impl FunctionTransform for Anomaly {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        let mut outputs = TransformOutputs::new("anomaly");
+        self.transform_outputs(&mut outputs, event);
+        output.extend(outputs.primary);
+    }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, mut event: Event) {
+        let (anomalous, alert) = self.process(event.as_log());
+        if let Some(alert) = alert {
+            outputs.push_named(ALERTS, alert);
+        }
+        if let (Some(flag_field), Some(_)) = (&self.flag_field, &self.field) {
+            event.as_mut_log().insert(flag_field, anomalous);
+        }
+
+        emit!(AnomalyEventProcessed);
+
+        outputs.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AnomalyConfig>();
+    }
+
+    fn anomaly(config: &str) -> Anomaly {
+        Anomaly::new(&toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn event(secs: i64, service: &str, duration: f64) -> Event {
+        let mut event = Event::from("request");
+        event
+            .as_mut_log()
+            .insert(log_schema().timestamp_key(), Utc.timestamp(secs, 0));
+        event.as_mut_log().insert("service", service);
+        event.as_mut_log().insert("duration_ms", duration);
+        event
+    }
+
+    #[test]
+    fn flags_outlying_values() {
+        let mut transform = anomaly(
+            r#"
+            field = "duration_ms"
+            group_by = ["service"]
+            warmup_samples = 10
+            flag_field = "anomalous"
+            "#,
+        );
+
+        let mut outputs = TransformOutputs::new("anomaly");
+        for i in 0..20 {
+            let duration = if i % 2 == 0 { 100.0 } else { 110.0 };
+            transform.transform_outputs(&mut outputs, event(i, "api", duration));
+        }
+        // Far outside the baseline of another group, which is still warming up.
+        transform.transform_outputs(&mut outputs, event(20, "web", 1000.0));
+        transform.transform_outputs(&mut outputs, event(20, "web", 5000.0));
+        assert!(outputs.named.get(ALERTS).is_none());
+
+        transform.transform_outputs(&mut outputs, event(21, "api", 500.0));
+        assert_eq!(outputs.primary.len(), 23);
+        assert_eq!(outputs.primary[0].as_log()["anomalous"], false.into());
+        assert_eq!(outputs.primary[22].as_log()["anomalous"], true.into());
+
+        let alerts = &outputs.named[ALERTS];
+        assert_eq!(alerts.len(), 1);
+        let alert = alerts[0].as_log();
+        assert_eq!(alert["service"], "api".into());
+        assert_eq!(alert["anomaly.field"], "duration_ms".into());
+        assert_eq!(alert["anomaly.value"], Value::Float(500.0));
+        match alert["anomaly.score"] {
+            Value::Float(score) => assert!(score > 3.0),
+            ref value => panic!("unexpected score {:?}", value),
+        }
+    }
+
+    #[test]
+    fn flags_rate_spikes() {
+        let mut transform = anomaly(
+            r#"
+            rate_interval_secs = 1
+            warmup_samples = 5
+            "#,
+        );
+
+        let mut outputs = TransformOutputs::new("anomaly");
+        // Two or three events a second, then a burst of 50.
+        for second in 0..10 {
+            for _ in 0..(2 + second % 2) {
+                transform.transform_outputs(&mut outputs, event(second, "api", 1.0));
+            }
+        }
+        for _ in 0..50 {
+            transform.transform_outputs(&mut outputs, event(10, "api", 1.0));
+        }
+        assert!(outputs.named.get(ALERTS).is_none());
+
+        // The burst is scored when the next interval starts.
+        transform.transform_outputs(&mut outputs, event(11, "api", 1.0));
+        let alerts = &outputs.named[ALERTS];
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].as_log()["anomaly.field"], "rate".into());
+        assert_eq!(alerts[0].as_log()["anomaly.value"], Value::Float(50.0));
+        assert_eq!(
+            alerts[0].as_log()[log_schema().timestamp_key()],
+            Utc.timestamp(11, 0).into()
+        );
+    }
+}
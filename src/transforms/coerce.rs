@@ -0,0 +1,437 @@
+use crate::{
+    config::{DataType, Format, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{CoerceEventProcessed, CoerceFailed},
+    transforms::{FunctionTransform, Transform, TransformOutputs, DROPPED},
+    types::{Conversion, ConversionError},
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{collections::BTreeMap, net::IpAddr, path::PathBuf, str::FromStr};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to read schema file {:?}: {}", path.display(), source))]
+    ReadSchema {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Invalid schema file {:?}: {}", path.display(), errors.join("; ")))]
+    ParseSchema { path: PathBuf, errors: Vec<String> },
+    #[snafu(display("Invalid type for field {:?}: {}", field, source))]
+    InvalidType {
+        field: String,
+        source: ConversionError,
+    },
+}
+
+#[derive(Debug, Snafu)]
+pub enum CoerceError {
+    #[snafu(display("{}", source))]
+    Convert { source: crate::types::Error },
+    #[snafu(display("Invalid IP address {:?}", value))]
+    InvalidIp { value: String },
+    #[snafu(display("Expected a single value, found {}", kind))]
+    NotAScalar { kind: &'static str },
+    #[snafu(display("Expected a map of fields"))]
+    NotAMap,
+}
+
+/// What happens to events with values that can't be coerced.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Leave the value as it is.
+    Lenient,
+    /// Drop the event.
+    Strict,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Lenient
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CoerceConfig {
+    /// A TOML, YAML or JSON file mapping fields to types.
+    pub schema_file: PathBuf,
+    #[serde(default)]
+    pub mode: Mode,
+    /// Send events failing strict coercion to the `dropped` output.
+    #[serde(default)]
+    pub reroute_dropped: bool,
+}
+
+inventory::submit! {
+    TransformDescription::new::<CoerceConfig>("coerce")
+}
+
+impl GenerateConfig for CoerceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"schema_file = "/etc/vector/schemas/warehouse.toml""#).unwrap()
+    }
+}
+
+/// The schema file: a type name for each field, or a table of the fields of
+/// a nested map.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum SchemaNode {
+    Type(String),
+    Fields(BTreeMap<String, SchemaNode>),
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile {
+    fields: BTreeMap<String, SchemaNode>,
+}
+
+#[derive(Clone, Debug)]
+enum Type {
+    Conversion(Conversion),
+    Ip,
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Type(Type),
+    Fields(BTreeMap<String, Node>),
+}
+
+fn compile(
+    fields: BTreeMap<String, SchemaNode>,
+    prefix: &str,
+) -> Result<BTreeMap<String, Node>, BuildError> {
+    fields
+        .into_iter()
+        .map(|(name, node)| {
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", prefix, name)
+            };
+            let node = match node {
+                SchemaNode::Type(name) if name == "ip" => Node::Type(Type::Ip),
+                SchemaNode::Type(name) => Node::Type(Type::Conversion(
+                    Conversion::from_str(&name).context(InvalidType { field: &path })?,
+                )),
+                SchemaNode::Fields(fields) => Node::Fields(compile(fields, &path)?),
+            };
+            Ok((name, node))
+        })
+        .collect()
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "coerce")]
+impl TransformConfig for CoerceConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let path = &self.schema_file;
+        let schema = std::fs::read_to_string(path).context(ReadSchema { path })?;
+        let schema: SchemaFile =
+            crate::config::format::deserialize(&schema, Format::from_path(path).ok()).map_err(
+                |errors| BuildError::ParseSchema {
+                    path: path.clone(),
+                    errors,
+                },
+            )?;
+
+        Ok(Transform::function(Coerce {
+            fields: compile(schema.fields, "")?,
+            mode: self.mode,
+            reroute_dropped: self.reroute_dropped,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "coerce"
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        if self.reroute_dropped {
+            vec![DROPPED.to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Coerce {
+    fields: BTreeMap<String, Node>,
+    mode: Mode,
+    reroute_dropped: bool,
+}
+
+impl Type {
+    fn coerce(&self, value: &Value) -> Result<Value, CoerceError> {
+        match (self, value) {
+            (_, Value::Map(_)) => Err(CoerceError::NotAScalar { kind: "a map" }),
+            (_, Value::Array(_)) => Err(CoerceError::NotAScalar { kind: "an array" }),
+            // Values that already have the type are left alone.
+            (Type::Conversion(Conversion::Integer), Value::Integer(_))
+            | (Type::Conversion(Conversion::Float), Value::Float(_))
+            | (Type::Conversion(Conversion::Boolean), Value::Boolean(_))
+            | (Type::Conversion(Conversion::Bytes), Value::Bytes(_))
+            | (Type::Conversion(Conversion::Timestamp), Value::Timestamp(_))
+            | (Type::Conversion(Conversion::TimestampFmt(_)), Value::Timestamp(_))
+            | (Type::Conversion(Conversion::TimestampTZFmt(_)), Value::Timestamp(_)) => {
+                Ok(value.clone())
+            }
+            (Type::Conversion(Conversion::Float), Value::Integer(integer)) => {
+                Ok(Value::Float(*integer as f64))
+            }
+            (Type::Conversion(conversion), value) => {
+                conversion.convert(value.clone()).context(Convert)
+            }
+            (Type::Ip, value) => {
+                let value = String::from_utf8_lossy(&value.as_bytes()).into_owned();
+                match value.trim().parse::<IpAddr>() {
+                    Ok(ip) => Ok(Value::from(ip.to_string())),
+                    Err(_) => Err(CoerceError::InvalidIp { value }),
+                }
+            }
+        }
+    }
+}
+
+/// Coerces the fields of `map` in place, collecting the failures.
+fn coerce_fields(
+    map: &mut BTreeMap<String, Value>,
+    fields: &BTreeMap<String, Node>,
+    prefix: &str,
+    errors: &mut Vec<(String, CoerceError)>,
+) {
+    for (name, node) in fields {
+        if let Some(value) = map.get_mut(name) {
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", prefix, name)
+            };
+            coerce_value(value, node, &path, errors);
+        }
+    }
+}
+
+fn coerce_value(
+    value: &mut Value,
+    node: &Node,
+    path: &str,
+    errors: &mut Vec<(String, CoerceError)>,
+) {
+    match (node, value) {
+        // Nulls stand for missing values.
+        (_, Value::Null) => (),
+        (node, Value::Array(values)) => {
+            for (index, value) in values.iter_mut().enumerate() {
+                coerce_value(value, node, &format!("{}[{}]", path, index), errors);
+            }
+        }
+        (Node::Fields(fields), Value::Map(map)) => coerce_fields(map, fields, path, errors),
+        (Node::Fields(_), _) => errors.push((path.to_owned(), CoerceError::NotAMap)),
+        (Node::Type(ty), value) => match ty.coerce(value) {
+            Ok(coerced) => *value = coerced,
+            Err(error) => errors.push((path.to_owned(), error)),
+        },
+    }
+}
+
+impl Coerce {
+    /// Coerces the event, handing it back with the failures if strict
+    /// coercion fails.
+    fn coerce(&self, mut event: Event) -> Result<Event, (Event, Vec<String>)> {
+        emit!(CoerceEventProcessed);
+
+        let log = event.as_mut_log();
+        let mut map: BTreeMap<String, Value> = std::mem::take(log).into();
+        let mut errors = Vec::new();
+        let original = match self.mode {
+            Mode::Strict => Some(map.clone()),
+            Mode::Lenient => None,
+        };
+        coerce_fields(&mut map, &self.fields, "", &mut errors);
+
+        let errors = errors
+            .into_iter()
+            .map(|(field, error)| {
+                emit!(CoerceFailed {
+                    field: &field,
+                    error: &error
+                });
+                format!("{}: {}", field, error)
+            })
+            .collect::<Vec<_>>();
+
+        match original {
+            // Failed events are left as they were.
+            Some(original) if !errors.is_empty() => {
+                *log = LogEvent::from(original);
+                Err((event, errors))
+            }
+            _ => {
+                *log = LogEvent::from(map);
+                Ok(event)
+            }
+        }
+    }
+}
+
+impl FunctionTransform for Coerce {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        if let Ok(event) = self.coerce(event) {
+            output.push(event);
+        }
+    }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        match self.coerce(event) {
+            Ok(event) => outputs.push(event),
+            Err((event, errors)) if self.reroute_dropped => {
+                outputs.push_dropped(event, "coercion_failed", errors.join("; "))
+            }
+            Err(_) => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::{convert::TryFrom, io::Write};
+
+    const SCHEMA: &str = r#"
+        [fields]
+        status = "int"
+        duration = "float"
+        secure = "bool"
+        client_ip = "ip"
+        received = "timestamp|%Y-%m-%dT%H:%M:%S%z"
+
+        [fields.upstream]
+        port = "int"
+        retries = "int"
+    "#;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<CoerceConfig>();
+    }
+
+    async fn build(schema: &str, extension: &str, mode: Mode) -> Transform {
+        let mut file = tempfile::Builder::new()
+            .suffix(extension)
+            .tempfile()
+            .unwrap();
+        file.write_all(schema.as_bytes()).unwrap();
+
+        CoerceConfig {
+            schema_file: file.path().into(),
+            mode,
+            reroute_dropped: true,
+        }
+        .build()
+        .await
+        .unwrap()
+    }
+
+    fn event() -> Event {
+        Event::try_from(serde_json::json!({
+            "status": "200",
+            "duration": 3,
+            "secure": "yes",
+            "client_ip": "::ffff:0a00:0001",
+            "received": "2020-11-23T10:00:00+0100",
+            "upstream": [
+                { "port": "8080", "retries": "0" },
+                { "port": "8081", "retries": null },
+            ],
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn coerces_nested_fields() {
+        let mut transform = build(SCHEMA, ".toml", Mode::Strict).await;
+
+        let event = transform.as_function().transform_one(event()).unwrap();
+        let log = event.as_log();
+
+        assert_eq!(log["status"], Value::Integer(200));
+        assert_eq!(log["duration"], Value::Float(3.0));
+        assert_eq!(log["secure"], Value::Boolean(true));
+        assert_eq!(log["client_ip"], "::ffff:10.0.0.1".into());
+        assert_eq!(
+            log["received"],
+            Value::Timestamp(Utc.ymd(2020, 11, 23).and_hms(9, 0, 0))
+        );
+        assert_eq!(log["upstream[0].port"], Value::Integer(8080));
+        assert_eq!(log["upstream[1].port"], Value::Integer(8081));
+        assert_eq!(log["upstream[1].retries"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn lenient_mode_keeps_invalid_values() {
+        let schema = r#"{ "fields": { "status": "int", "secure": "bool" } }"#;
+        let mut transform = build(schema, ".json", Mode::Lenient).await;
+
+        let mut input = event();
+        input.as_mut_log().insert("status", "OK");
+        let event = transform.as_function().transform_one(input).unwrap();
+
+        assert_eq!(event.as_log()["status"], "OK".into());
+        assert_eq!(event.as_log()["secure"], Value::Boolean(true));
+    }
+
+    #[tokio::test]
+    async fn strict_mode_reroutes_invalid_events() {
+        let mut transform = build(SCHEMA, ".toml", Mode::Strict).await;
+
+        let mut input = event();
+        input.as_mut_log().insert("upstream[1].port", "http");
+        let mut outputs = TransformOutputs::new("coerce");
+        transform
+            .as_function()
+            .transform_outputs(&mut outputs, input);
+
+        assert!(outputs.primary.is_empty());
+        let dropped = &outputs.named[DROPPED];
+        assert_eq!(dropped.len(), 1);
+        // The event is left as it was.
+        assert_eq!(dropped[0].as_log()["status"], "200".into());
+        assert_eq!(
+            dropped[0].as_log()["metadata.dropped.reason"],
+            "coercion_failed".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_types() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"[fields]\nstatus = \"number\"").unwrap();
+
+        let error = CoerceConfig {
+            schema_file: file.path().into(),
+            mode: Mode::Strict,
+            reroute_dropped: false,
+        }
+        .build()
+        .await
+        .unwrap_err();
+
+        assert!(error.to_string().contains("\"status\""));
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{
+    config::{log_schema, DataType, TransformConfig, TransformDescription},
+    event::{Event, PathComponent, PathIter, Value},
+    internal_events::{
+        CsvParserConversionFailed, CsvParserEventProcessed, CsvParserFailedParse,
+        CsvParserFieldMissing, CsvParserHeaderRow,
+    },
+    transforms::{FunctionTransform, Transform, TransformOutputs, DROPPED},
+    types::{parse_conversion_map, Conversion},
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::collections::HashMap;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`delimiter` and `quote` must be ASCII characters"))]
+    NonAsciiDelimiter,
+    #[snafu(display("`field_names` must be set unless `header_row` is `true`"))]
+    NoFieldNames,
+}
+
+/// What to do with rows that don't have exactly one value per column.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RaggedRows {
+    /// Parse what's there: missing columns are left out and extra values
+    /// are ignored.
+    Lenient,
+    /// Treat the row as a parse failure.
+    Strict,
+}
+
+impl Default for RaggedRows {
+    fn default() -> Self {
+        RaggedRows::Lenient
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Derivative, Clone)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct CsvParserConfig {
+    pub field: Option<String>,
+    #[derivative(Default(value = "true"))]
+    pub drop_field: bool,
+    pub target_field: Option<String>,
+    /// The column names, in order.
+    pub field_names: Vec<String>,
+    /// The first row is a header. It is never emitted, and gives the column
+    /// names when `field_names` is empty. Later rows identical to the header,
+    /// as written when a file is rotated, are discarded as well.
+    pub header_row: bool,
+    #[derivative(Default(value = "','"))]
+    pub delimiter: char,
+    #[derivative(Default(value = "'\"'"))]
+    pub quote: char,
+    pub types: HashMap<String, String>,
+    pub ragged_rows: RaggedRows,
+    /// Send events that fail to parse to the `dropped` output.
+    pub reroute_dropped: bool,
+}
+
+inventory::submit! {
+    TransformDescription::new::<CsvParserConfig>("csv_parser")
+}
+
+impl_generate_config_from_default!(CsvParserConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "csv_parser")]
+impl TransformConfig for CsvParserConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        if !self.delimiter.is_ascii() || !self.quote.is_ascii() {
+            return Err(BuildError::NonAsciiDelimiter.into());
+        }
+        if self.field_names.is_empty() && !self.header_row {
+            return Err(BuildError::NoFieldNames.into());
+        }
+
+        let field = self
+            .field
+            .clone()
+            .unwrap_or_else(|| log_schema().message_key().to_string());
+        let types = parse_conversion_map(&self.types)?;
+
+        let mut parser = CsvParser {
+            field,
+            drop_field: self.drop_field,
+            target_field: self.target_field.clone(),
+            columns: Vec::new(),
+            header: None,
+            header_row: self.header_row,
+            delimiter: self.delimiter as u8,
+            quote: self.quote as u8,
+            types,
+            ragged_rows: self.ragged_rows,
+            reroute_dropped: self.reroute_dropped,
+        };
+        if !self.field_names.is_empty() {
+            parser.set_columns(self.field_names.clone());
+        }
+
+        Ok(Transform::function(parser))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "csv_parser"
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        if self.reroute_dropped {
+            vec![DROPPED.to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CsvParser {
+    field: String,
+    drop_field: bool,
+    target_field: Option<String>,
+    columns: Vec<(String, Vec<PathComponent>)>,
+    /// The header row, once seen.
+    header: Option<Vec<String>>,
+    header_row: bool,
+    delimiter: u8,
+    quote: u8,
+    types: HashMap<String, Conversion>,
+    ragged_rows: RaggedRows,
+    reroute_dropped: bool,
+}
+
+impl CsvParser {
+    fn set_columns(&mut self, names: Vec<String>) {
+        self.columns = names
+            .into_iter()
+            .map(|name| {
+                let path = match &self.target_field {
+                    Some(target) => format!("{}.{}", target, name),
+                    None => name.clone(),
+                };
+                let path = PathIter::new(&path).collect();
+                (name, path)
+            })
+            .collect();
+    }
+
+    fn read_row(&self, value: &[u8]) -> Result<Vec<String>, csv::Error> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .from_reader(value);
+
+        match reader.records().next() {
+            Some(record) => Ok(record?.iter().map(Into::into).collect()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Parses the event, returning `Ok(None)` for header rows, and handing
+    /// the event back with the reason if it can't be parsed.
+    fn parse(&mut self, mut event: Event) -> Result<Option<Event>, (Event, String)> {
+        let value = match event.as_log().get(&self.field) {
+            Some(value) => value.as_bytes(),
+            None => {
+                emit!(CsvParserFieldMissing { field: &self.field });
+                let message = format!("field {:?} is missing", self.field);
+                return Err((event, message));
+            }
+        };
+
+        let row = match self.read_row(&value) {
+            Ok(row) => row,
+            Err(error) => {
+                emit!(CsvParserFailedParse { error: &error });
+                return Err((event, error.to_string()));
+            }
+        };
+
+        if self.header_row {
+            match &self.header {
+                None => {
+                    if self.columns.is_empty() {
+                        self.set_columns(row.clone());
+                    }
+                    self.header = Some(row);
+                    emit!(CsvParserHeaderRow);
+                    return Ok(None);
+                }
+                Some(header) if *header == row => {
+                    emit!(CsvParserHeaderRow);
+                    return Ok(None);
+                }
+                Some(_) => (),
+            }
+        }
+
+        if self.ragged_rows == RaggedRows::Strict && row.len() != self.columns.len() {
+            let message = format!(
+                "row has {} values but there are {} columns",
+                row.len(),
+                self.columns.len()
+            );
+            return Err((event, message));
+        }
+
+        let log = event.as_mut_log();
+        // Don't drop the field if a column is written to it.
+        let overwritten =
+            self.target_field.is_none() && self.columns.iter().any(|(name, _)| *name == self.field);
+        if self.drop_field && !overwritten {
+            log.remove(&self.field);
+        }
+
+        for ((name, path), value) in self.columns.iter().zip(row.into_iter()) {
+            let conversion = self.types.get(name).unwrap_or(&Conversion::Bytes);
+            match conversion.convert(Value::from(value)) {
+                Ok(value) => {
+                    log.insert_path(path.clone(), value);
+                }
+                Err(error) => emit!(CsvParserConversionFailed { name, error }),
+            }
+        }
+
+        emit!(CsvParserEventProcessed);
+
+        Ok(Some(event))
+    }
+}
+
+impl FunctionTransform for CsvParser {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        match self.parse(event) {
+            Ok(Some(event)) => output.push(event),
+            Ok(None) => (),
+            Err((event, _)) if !self.reroute_dropped => output.push(event),
+            Err(_) => (),
+        }
+    }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        match self.parse(event) {
+            Ok(Some(event)) => outputs.push(event),
+            Ok(None) => (),
+            Err((event, message)) if self.reroute_dropped => {
+                outputs.push_dropped(event, "parse_failed", message)
+            }
+            Err((event, _)) => outputs.push(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvParserConfig, RaggedRows};
+    use crate::{
+        config::TransformConfig,
+        event::{Event, Value},
+        transforms::{TransformOutputs, DROPPED},
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<CsvParserConfig>();
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|&name| name.into()).collect()
+    }
+
+    #[tokio::test]
+    async fn csv_parser_parses_with_field_names() {
+        let mut parser = CsvParserConfig {
+            field_names: names(&["date", "account", "cost"]),
+            types: vec![("cost".into(), "float".into())].into_iter().collect(),
+            ..Default::default()
+        }
+        .build()
+        .await
+        .unwrap();
+        let parser = parser.as_function();
+
+        let event = parser
+            .transform_one(Event::from(r#"2020-11-01,"Acme, Inc.",12.5"#))
+            .unwrap();
+        let log = event.as_log();
+
+        assert_eq!(log["date"], "2020-11-01".into());
+        assert_eq!(log["account"], "Acme, Inc.".into());
+        assert_eq!(log["cost"], Value::Float(12.5));
+        assert!(log.get("message").is_none());
+    }
+
+    #[tokio::test]
+    async fn csv_parser_infers_header() {
+        let mut parser = CsvParserConfig {
+            header_row: true,
+            delimiter: ';',
+            target_field: Some("flow".into()),
+            ..Default::default()
+        }
+        .build()
+        .await
+        .unwrap();
+        let parser = parser.as_function();
+
+        assert!(parser.transform_one(Event::from("src;dst;bytes")).is_none());
+        let event = parser
+            .transform_one(Event::from("10.0.0.1;10.0.0.2;512"))
+            .unwrap();
+        assert_eq!(event.as_log()["flow.src"], "10.0.0.1".into());
+        assert_eq!(event.as_log()["flow.dst"], "10.0.0.2".into());
+        assert_eq!(event.as_log()["flow.bytes"], "512".into());
+
+        // A repeated header is discarded too.
+        assert!(parser.transform_one(Event::from("src;dst;bytes")).is_none());
+    }
+
+    #[tokio::test]
+    async fn csv_parser_handles_ragged_rows() {
+        let config = CsvParserConfig {
+            field_names: names(&["a", "b", "c"]),
+            ..Default::default()
+        };
+
+        let mut parser = config.build().await.unwrap();
+        let event = parser
+            .as_function()
+            .transform_one(Event::from("1,2"))
+            .unwrap();
+        assert_eq!(event.as_log()["a"], "1".into());
+        assert_eq!(event.as_log()["b"], "2".into());
+        assert!(event.as_log().get("c").is_none());
+
+        let mut parser = CsvParserConfig {
+            ragged_rows: RaggedRows::Strict,
+            reroute_dropped: true,
+            ..config
+        }
+        .build()
+        .await
+        .unwrap();
+        let mut outputs = TransformOutputs::new("csv");
+        parser
+            .as_function()
+            .transform_outputs(&mut outputs, Event::from("1,2"));
+
+        assert!(outputs.primary.is_empty());
+        let dropped = &outputs.named[DROPPED];
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].as_log()["message"], "1,2".into());
+        assert_eq!(
+            dropped[0].as_log()["metadata.dropped.reason"],
+            "parse_failed".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn csv_parser_requires_field_names_or_header() {
+        assert!(CsvParserConfig::default().build().await.is_err());
+    }
+}
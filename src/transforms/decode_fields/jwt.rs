@@ -0,0 +1,352 @@
+//! Decoding of JSON Web Tokens (RFC 7519), and verification of their
+//! signatures against a JSON Web Key Set (RFC 7517).
+
+use crate::event::Value;
+use openssl::{
+    bn::BigNum,
+    ec::EcKey,
+    ecdsa::EcdsaSig,
+    hash::{hash, MessageDigest},
+    memcmp,
+    pkey::{PKey, Public},
+    rsa::Rsa,
+    sign::{Signer, Verifier},
+};
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Snafu)]
+pub enum JwtError {
+    #[snafu(display("Token does not have three parts"))]
+    Malformed,
+    #[snafu(display("Invalid base64 in token: {}", source))]
+    TokenBase64 { source: base64::DecodeError },
+    #[snafu(display("Invalid JSON in token: {}", source))]
+    TokenJson { source: serde_json::Error },
+    #[snafu(display("Token header or claims are not an object"))]
+    NotAnObject,
+    #[snafu(display("Unsupported algorithm {:?}", alg))]
+    UnsupportedAlgorithm { alg: String },
+    #[snafu(display("No key matches the token"))]
+    NoMatchingKey,
+    #[snafu(display("Invalid signature"))]
+    InvalidSignature,
+    #[snafu(display("Token has expired"))]
+    Expired,
+    #[snafu(display("Token is not valid yet"))]
+    NotYetValid,
+    #[snafu(display("Failed to verify signature: {}", source))]
+    Openssl { source: openssl::error::ErrorStack },
+}
+
+#[derive(Debug, Snafu)]
+pub enum JwksError {
+    #[snafu(display("Invalid key set: {}", source))]
+    KeySetJson { source: serde_json::Error },
+    #[snafu(display("Invalid base64 in key {:?}: {}", kid, source))]
+    KeyBase64 {
+        kid: String,
+        source: base64::DecodeError,
+    },
+    #[snafu(display("Invalid key {:?}: {}", kid, source))]
+    InvalidKey {
+        kid: String,
+        source: openssl::error::ErrorStack,
+    },
+    #[snafu(display("Key {:?} is missing parameter {:?}", kid, parameter))]
+    MissingParameter {
+        kid: String,
+        parameter: &'static str,
+    },
+}
+
+/// A decoded, but not verified, token.
+#[derive(Debug)]
+pub struct Token {
+    pub header: BTreeMap<String, Value>,
+    pub claims: BTreeMap<String, Value>,
+    signing_input: String,
+    signature: Vec<u8>,
+}
+
+impl Token {
+    pub fn decode(token: &str) -> Result<Self, JwtError> {
+        let mut parts = token.split('.');
+        let (header, claims, signature) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(header), Some(claims), Some(signature)) if parts.next().is_none() => {
+                (header, claims, signature)
+            }
+            _ => return Err(JwtError::Malformed),
+        };
+
+        Ok(Self {
+            header: decode_object(header)?,
+            claims: decode_object(claims)?,
+            signing_input: format!("{}.{}", header, claims),
+            signature: decode_base64url(signature).context(TokenBase64)?,
+        })
+    }
+
+    fn header_str(&self, name: &str) -> Option<String> {
+        match self.header.get(name) {
+            Some(Value::Bytes(value)) => Some(String::from_utf8_lossy(value).into_owned()),
+            _ => None,
+        }
+    }
+
+    /// Checks the `exp` and `nbf` claims, allowing for `leeway_secs` of
+    /// clock skew.
+    pub fn check_times(&self, now: i64, leeway_secs: i64) -> Result<(), JwtError> {
+        if let Some(exp) = self.claims.get("exp").and_then(numeric_date) {
+            if now > exp + leeway_secs {
+                return Err(JwtError::Expired);
+            }
+        }
+        if let Some(nbf) = self.claims.get("nbf").and_then(numeric_date) {
+            if now + leeway_secs < nbf {
+                return Err(JwtError::NotYetValid);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn numeric_date(value: &Value) -> Option<i64> {
+    match value {
+        Value::Integer(value) => Some(*value),
+        Value::Float(value) => Some(*value as i64),
+        _ => None,
+    }
+}
+
+pub fn decode_base64url(value: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    base64::decode_config(value.trim_end_matches('='), base64::URL_SAFE_NO_PAD)
+}
+
+fn decode_object(part: &str) -> Result<BTreeMap<String, Value>, JwtError> {
+    let json = decode_base64url(part).context(TokenBase64)?;
+    match serde_json::from_slice::<serde_json::Value>(&json).context(TokenJson)? {
+        serde_json::Value::Object(fields) => Ok(fields
+            .into_iter()
+            .map(|(name, value)| (name, Value::from(value)))
+            .collect()),
+        _ => Err(JwtError::NotAnObject),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Family {
+    Hmac,
+    Rsa,
+    Ecdsa,
+}
+
+fn algorithm(alg: &str) -> Option<(Family, MessageDigest)> {
+    let family = match alg.get(..2) {
+        Some("HS") => Family::Hmac,
+        Some("RS") => Family::Rsa,
+        Some("ES") => Family::Ecdsa,
+        _ => return None,
+    };
+    let digest = match alg.get(2..) {
+        Some("256") => MessageDigest::sha256(),
+        Some("384") => MessageDigest::sha384(),
+        Some("512") => MessageDigest::sha512(),
+        _ => return None,
+    };
+    Some((family, digest))
+}
+
+#[derive(Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Deserialize)]
+struct Jwk {
+    kty: String,
+    kid: Option<String>,
+    alg: Option<String>,
+    #[serde(rename = "use")]
+    use_: Option<String>,
+    n: Option<String>,
+    e: Option<String>,
+    x: Option<String>,
+    y: Option<String>,
+    crv: Option<String>,
+    k: Option<String>,
+}
+
+impl Jwk {
+    fn parameter(&self, parameter: &'static str) -> Result<Vec<u8>, JwksError> {
+        let kid = self.kid.clone().unwrap_or_default();
+        let value = match parameter {
+            "n" => &self.n,
+            "e" => &self.e,
+            "x" => &self.x,
+            "y" => &self.y,
+            _ => &self.k,
+        };
+        match value {
+            Some(value) => decode_base64url(value).context(KeyBase64 { kid }),
+            None => Err(JwksError::MissingParameter { kid, parameter }),
+        }
+    }
+
+    fn bignum(&self, parameter: &'static str) -> Result<BigNum, JwksError> {
+        let kid = self.kid.clone().unwrap_or_default();
+        BigNum::from_slice(&self.parameter(parameter)?).context(InvalidKey { kid })
+    }
+
+    /// Returns `None` for keys that can't be used to verify signatures.
+    fn material(&self) -> Result<Option<KeyMaterial>, JwksError> {
+        let kid = self.kid.clone().unwrap_or_default();
+        let material = match self.kty.as_str() {
+            "oct" => KeyMaterial::Hmac(self.parameter("k")?),
+            "RSA" => {
+                let rsa = Rsa::from_public_components(self.bignum("n")?, self.bignum("e")?)
+                    .and_then(PKey::from_rsa)
+                    .context(InvalidKey { kid })?;
+                KeyMaterial::Rsa(rsa)
+            }
+            "EC" => {
+                let nid = match self.crv.as_deref() {
+                    Some("P-256") => openssl::nid::Nid::X9_62_PRIME256V1,
+                    Some("P-384") => openssl::nid::Nid::SECP384R1,
+                    Some("P-521") => openssl::nid::Nid::SECP521R1,
+                    _ => return Ok(None),
+                };
+                let (x, y) = (self.bignum("x")?, self.bignum("y")?);
+                let key = openssl::ec::EcGroup::from_curve_name(nid)
+                    .and_then(|group| EcKey::from_public_key_affine_coordinates(&group, &x, &y))
+                    .context(InvalidKey { kid })?;
+                KeyMaterial::Ec(key)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(material))
+    }
+}
+
+enum KeyMaterial {
+    Hmac(Vec<u8>),
+    Rsa(PKey<Public>),
+    Ec(EcKey<Public>),
+}
+
+impl KeyMaterial {
+    fn family(&self) -> Family {
+        match self {
+            KeyMaterial::Hmac(_) => Family::Hmac,
+            KeyMaterial::Rsa(_) => Family::Rsa,
+            KeyMaterial::Ec(_) => Family::Ecdsa,
+        }
+    }
+
+    fn verify(
+        &self,
+        digest: MessageDigest,
+        input: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, JwtError> {
+        match self {
+            KeyMaterial::Hmac(secret) => {
+                let key = PKey::hmac(secret).context(Openssl)?;
+                let mut signer = Signer::new(digest, &key).context(Openssl)?;
+                signer.update(input).context(Openssl)?;
+                let expected = signer.sign_to_vec().context(Openssl)?;
+                Ok(expected.len() == signature.len() && memcmp::eq(&expected, signature))
+            }
+            KeyMaterial::Rsa(key) => {
+                let mut verifier = Verifier::new(digest, key).context(Openssl)?;
+                verifier.update(input).context(Openssl)?;
+                // Malformed signatures are reported as errors by OpenSSL.
+                Ok(verifier.verify(signature).unwrap_or(false))
+            }
+            KeyMaterial::Ec(key) => {
+                // The signature is the big-endian `r` and `s`, concatenated.
+                if signature.is_empty() || signature.len() % 2 != 0 {
+                    return Ok(false);
+                }
+                let (r, s) = signature.split_at(signature.len() / 2);
+                let signature = BigNum::from_slice(r)
+                    .and_then(|r| Ok((r, BigNum::from_slice(s)?)))
+                    .and_then(|(r, s)| EcdsaSig::from_private_components(r, s))
+                    .context(Openssl)?;
+                let digest = hash(digest, input).context(Openssl)?;
+                Ok(signature.verify(&digest, key).unwrap_or(false))
+            }
+        }
+    }
+}
+
+struct Key {
+    kid: Option<String>,
+    alg: Option<String>,
+    material: KeyMaterial,
+}
+
+/// The keys of a JSON Web Key Set that can verify signatures.
+pub struct Jwks {
+    keys: Vec<Key>,
+}
+
+impl std::fmt::Debug for Jwks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kids: Vec<_> = self.keys.iter().map(|key| &key.kid).collect();
+        f.debug_struct("Jwks").field("kids", &kids).finish()
+    }
+}
+
+impl Jwks {
+    pub fn parse(json: &[u8]) -> Result<Self, JwksError> {
+        let set: JwkSet = serde_json::from_slice(json).context(KeySetJson)?;
+        let mut keys = Vec::new();
+        for jwk in set.keys {
+            if jwk.use_.as_deref().map_or(false, |use_| use_ != "sig") {
+                continue;
+            }
+            if let Some(material) = jwk.material()? {
+                keys.push(Key {
+                    kid: jwk.kid,
+                    alg: jwk.alg,
+                    material,
+                });
+            }
+        }
+        Ok(Self { keys })
+    }
+
+    /// Verifies the token's signature with the key named by its `kid`
+    /// header, or with any key of the right type if it has none.
+    pub fn verify(&self, token: &Token) -> Result<(), JwtError> {
+        let alg = token.header_str("alg").unwrap_or_default();
+        let (family, digest) = match algorithm(&alg) {
+            Some(algorithm) => algorithm,
+            None => return Err(JwtError::UnsupportedAlgorithm { alg }),
+        };
+        let kid = token.header_str("kid");
+
+        let mut candidates = self
+            .keys
+            .iter()
+            .filter(|key| kid.is_none() || key.kid == kid)
+            .filter(|key| key.alg.as_ref().map_or(true, |key_alg| *key_alg == alg))
+            .filter(|key| key.material.family() == family)
+            .peekable();
+        if candidates.peek().is_none() {
+            return Err(JwtError::NoMatchingKey);
+        }
+
+        for key in candidates {
+            if key
+                .material
+                .verify(digest, token.signing_input.as_bytes(), &token.signature)?
+            {
+                return Ok(());
+            }
+        }
+        Err(JwtError::InvalidSignature)
+    }
+}
@@ -0,0 +1,525 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    http::HttpClient,
+    internal_events::{
+        DecodeFieldsEventDiscarded, DecodeFieldsEventProcessed, DecodeFieldsFailed,
+        DecodeFieldsJwksRefreshFailed, DecodeFieldsUnverified,
+    },
+    tls::{TlsOptions, TlsSettings},
+    transforms::{FunctionTransform, Transform},
+};
+use http::{Request, StatusCode};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+use tokio::time::{delay_for, Duration};
+
+mod jwt;
+
+pub use self::jwt::JwtError;
+use self::jwt::{Jwks, Token};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Exactly one of `jwks.url` and `jwks.path` must be set"))]
+    JwksSource,
+    #[snafu(display("Fetching the key set returned {}", status))]
+    JwksStatus { status: StatusCode },
+}
+
+#[derive(Debug, Snafu)]
+pub enum DecodeError {
+    #[snafu(display("Invalid base64: {}", source))]
+    Base64 { source: base64::DecodeError },
+    #[snafu(display("Invalid token: {}", source))]
+    Jwt { source: JwtError },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DecodeFieldsConfig {
+    pub fields: Vec<FieldConfig>,
+    /// Verify the signatures of tokens against this key set.
+    pub jwks: Option<JwksConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FieldConfig {
+    pub field: String,
+    pub codec: Codec,
+    /// Where the decoded value is written. Defaults to `field`.
+    pub target_field: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Codec {
+    Base64,
+    Base64Url,
+    /// A JSON Web Token, decoded into its header and claims. A leading
+    /// `Bearer ` is ignored.
+    Jwt,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JwksConfig {
+    pub url: Option<String>,
+    pub path: Option<PathBuf>,
+    /// How often the key set is fetched again, to pick up rotated keys.
+    #[serde(default = "default_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+    /// Allowed clock skew when checking the `exp` and `nbf` claims.
+    #[serde(default = "default_leeway_secs")]
+    pub leeway_secs: u64,
+    /// Drop events with tokens that fail verification, rather than marking
+    /// them as unverified.
+    #[serde(default)]
+    pub drop_unverified: bool,
+    pub tls: Option<TlsOptions>,
+}
+
+const fn default_refresh_interval_secs() -> u64 {
+    3600
+}
+
+const fn default_leeway_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    TransformDescription::new::<DecodeFieldsConfig>("decode_fields")
+}
+
+impl GenerateConfig for DecodeFieldsConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"fields = [
+                { field = "authorization", codec = "jwt", target_field = "token" },
+                { field = "payload", codec = "base64" },
+            ]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "decode_fields")]
+impl TransformConfig for DecodeFieldsConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let verifier = match &self.jwks {
+            Some(config) => Some(TokenVerifier::new(config).await?),
+            None => None,
+        };
+
+        Ok(Transform::function(DecodeFields {
+            fields: self.fields.clone(),
+            verifier,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "decode_fields"
+    }
+}
+
+#[derive(Clone, Debug)]
+enum JwksSource {
+    Url { client: HttpClient, url: String },
+    Path(PathBuf),
+}
+
+impl JwksSource {
+    async fn load(&self) -> crate::Result<Jwks> {
+        let json = match self {
+            JwksSource::Url { client, url } => {
+                let request = Request::get(url.as_str()).body(Body::empty())?;
+                let response = client.send(request).await?;
+                let status = response.status();
+                if status != StatusCode::OK {
+                    return Err(BuildError::JwksStatus { status }.into());
+                }
+                hyper::body::to_bytes(response.into_body()).await?.to_vec()
+            }
+            JwksSource::Path(path) => tokio::fs::read(path).await?,
+        };
+        Ok(Jwks::parse(&json)?)
+    }
+}
+
+/// Verifies tokens against a key set that is refreshed in the background.
+#[derive(Clone, Debug)]
+struct TokenVerifier {
+    jwks: Arc<RwLock<Jwks>>,
+    leeway_secs: i64,
+    drop_unverified: bool,
+}
+
+impl TokenVerifier {
+    async fn new(config: &JwksConfig) -> crate::Result<Self> {
+        let source = match (&config.url, &config.path) {
+            (Some(url), None) => JwksSource::Url {
+                client: HttpClient::new(TlsSettings::from_options(&config.tls)?)?,
+                url: url.clone(),
+            },
+            (None, Some(path)) => JwksSource::Path(path.clone()),
+            _ => return Err(BuildError::JwksSource.into()),
+        };
+
+        let jwks = Arc::new(RwLock::new(source.load().await?));
+        if config.refresh_interval_secs > 0 {
+            let refresh_interval = Duration::from_secs(config.refresh_interval_secs);
+            let shared = Arc::downgrade(&jwks);
+            tokio::spawn(async move {
+                loop {
+                    delay_for(refresh_interval).await;
+                    // Stop once the transform is gone.
+                    let jwks = match shared.upgrade() {
+                        Some(jwks) => jwks,
+                        None => break,
+                    };
+                    match source.load().await {
+                        Ok(loaded) => *jwks.write().unwrap() = loaded,
+                        Err(error) => emit!(DecodeFieldsJwksRefreshFailed { error }),
+                    }
+                }
+            });
+        }
+
+        Ok(Self {
+            jwks,
+            leeway_secs: config.leeway_secs as i64,
+            drop_unverified: config.drop_unverified,
+        })
+    }
+
+    fn verify(&self, token: &Token) -> Result<(), JwtError> {
+        self.jwks.read().unwrap().verify(token)?;
+        token.check_times(chrono::Utc::now().timestamp(), self.leeway_secs)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DecodeFields {
+    fields: Vec<FieldConfig>,
+    verifier: Option<TokenVerifier>,
+}
+
+impl DecodeFields {
+    /// Decodes the value, also returning why a token failed verification.
+    fn decode(&self, codec: Codec, value: &str) -> Result<(Value, Option<JwtError>), DecodeError> {
+        match codec {
+            Codec::Base64 => {
+                let decoded =
+                    base64::decode_config(value.trim_end_matches('='), base64::STANDARD_NO_PAD)
+                        .context(Base64)?;
+                Ok((Value::Bytes(decoded.into()), None))
+            }
+            Codec::Base64Url => {
+                let decoded = jwt::decode_base64url(value).context(Base64)?;
+                Ok((Value::Bytes(decoded.into()), None))
+            }
+            Codec::Jwt => {
+                let value = match value.get(..7) {
+                    Some(prefix) if prefix.eq_ignore_ascii_case("bearer ") => value[7..].trim(),
+                    _ => value,
+                };
+                let token = Token::decode(value).context(Jwt)?;
+
+                let unverified = self
+                    .verifier
+                    .as_ref()
+                    .map(|verifier| verifier.verify(&token));
+                let mut decoded = BTreeMap::new();
+                if let Some(result) = &unverified {
+                    decoded.insert("verified".to_owned(), Value::Boolean(result.is_ok()));
+                }
+                decoded.insert("header".to_owned(), Value::Map(token.header));
+                decoded.insert("claims".to_owned(), Value::Map(token.claims));
+
+                Ok((Value::Map(decoded), unverified.and_then(Result::err)))
+            }
+        }
+    }
+}
+
+impl FunctionTransform for DecodeFields {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_mut_log();
+        for field in &self.fields {
+            let value = match log.get(&field.field) {
+                Some(value) => value.to_string_lossy(),
+                None => continue,
+            };
+
+            match self.decode(field.codec, value.trim()) {
+                Ok((decoded, unverified)) => {
+                    if let Some(error) = unverified {
+                        emit!(DecodeFieldsUnverified {
+                            field: &field.field,
+                            error: &error
+                        });
+                        if self.verifier.as_ref().map_or(false, |v| v.drop_unverified) {
+                            emit!(DecodeFieldsEventDiscarded);
+                            return;
+                        }
+                    }
+                    let target = field.target_field.as_ref().unwrap_or(&field.field);
+                    log.insert(target, decoded);
+                }
+                Err(error) => emit!(DecodeFieldsFailed {
+                    field: &field.field,
+                    error: &error
+                }),
+            }
+        }
+
+        emit!(DecodeFieldsEventProcessed);
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::next_addr;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use openssl::{
+        hash::MessageDigest,
+        pkey::{PKey, Private},
+        rsa::Rsa,
+        sign::Signer,
+    };
+    use serde_json::json;
+    use std::{convert::Infallible, io::Write};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<DecodeFieldsConfig>();
+    }
+
+    fn encode(json: serde_json::Value) -> String {
+        base64::encode_config(json.to_string(), base64::URL_SAFE_NO_PAD)
+    }
+
+    fn token(header: serde_json::Value, claims: serde_json::Value, key: &PKey<Private>) -> String {
+        let input = format!("{}.{}", encode(header), encode(claims));
+        let mut signer = Signer::new(MessageDigest::sha256(), key).unwrap();
+        signer.update(input.as_bytes()).unwrap();
+        let signature = signer.sign_to_vec().unwrap();
+        format!(
+            "{}.{}",
+            input,
+            base64::encode_config(signature, base64::URL_SAFE_NO_PAD)
+        )
+    }
+
+    fn hmac_token(claims: serde_json::Value) -> String {
+        let key = PKey::hmac(b"secret").unwrap();
+        token(json!({ "alg": "HS256", "kid": "k1" }), claims, &key)
+    }
+
+    fn hmac_jwks() -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let jwks = json!({ "keys": [{
+            "kty": "oct",
+            "kid": "k1",
+            "k": base64::encode_config(b"secret", base64::URL_SAFE_NO_PAD),
+        }]});
+        write!(file, "{}", jwks).unwrap();
+        file
+    }
+
+    async fn decode(config: &str, token: &str) -> Option<Event> {
+        let config = toml::from_str::<DecodeFieldsConfig>(config).unwrap();
+        let mut transform = config.build().await.unwrap();
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("authorization", token);
+        transform.as_function().transform_one(event)
+    }
+
+    #[tokio::test]
+    async fn decodes_base64() {
+        let config = toml::from_str::<DecodeFieldsConfig>(
+            r#"fields = [
+                { field = "standard", codec = "base64" },
+                { field = "url", codec = "base64_url", target_field = "decoded" },
+            ]"#,
+        )
+        .unwrap();
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("standard", "aGk/Pz8=");
+        event.as_mut_log().insert("url", "aGk_Pz8");
+
+        let mut transform = config.build().await.unwrap();
+        let event = transform.as_function().transform_one(event).unwrap();
+        assert_eq!(event.as_log()["standard"], "hi???".into());
+        assert_eq!(event.as_log()["decoded"], "hi???".into());
+    }
+
+    #[tokio::test]
+    async fn decodes_jwt_without_verification() {
+        let token = hmac_token(json!({ "sub": "jane", "scope": ["read"] }));
+        let config =
+            r#"fields = [{ field = "authorization", codec = "jwt", target_field = "token" }]"#;
+
+        let event = decode(config, &format!("Bearer {}", token)).await.unwrap();
+        let log = event.as_log();
+        assert_eq!(log["token.header.alg"], "HS256".into());
+        assert_eq!(log["token.claims.sub"], "jane".into());
+        assert_eq!(log["token.claims.scope[0]"], "read".into());
+        assert!(log.get("token.verified").is_none());
+    }
+
+    #[tokio::test]
+    async fn verifies_hmac_jwt() {
+        let jwks = hmac_jwks();
+        let config = format!(
+            r#"
+            fields = [{{ field = "authorization", codec = "jwt" }}]
+            jwks.path = {:?}
+            "#,
+            jwks.path()
+        );
+
+        let valid = hmac_token(json!({ "sub": "jane" }));
+        let event = decode(&config, &valid).await.unwrap();
+        assert_eq!(
+            event.as_log()["authorization.verified"],
+            Value::Boolean(true)
+        );
+
+        // Swap in other claims, keeping the signature.
+        let parts: Vec<_> = valid.split('.').collect();
+        let other = hmac_token(json!({ "sub": "bob" }));
+        let forged = format!(
+            "{}.{}.{}",
+            parts[0],
+            other.split('.').nth(1).unwrap(),
+            parts[2]
+        );
+        let event = decode(&config, &forged).await.unwrap();
+        assert_eq!(
+            event.as_log()["authorization.verified"],
+            Value::Boolean(false)
+        );
+        assert_eq!(event.as_log()["authorization.claims.sub"], "bob".into());
+
+        let config = format!("{}\njwks.drop_unverified = true", config);
+        assert!(decode(&config, &forged).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_expired_jwt() {
+        let jwks = hmac_jwks();
+        let config = format!(
+            r#"
+            fields = [{{ field = "authorization", codec = "jwt" }}]
+            jwks.path = {:?}
+            "#,
+            jwks.path()
+        );
+
+        let now = chrono::Utc::now().timestamp();
+        let expired = hmac_token(json!({ "sub": "jane", "exp": now - 120 }));
+        let event = decode(&config, &expired).await.unwrap();
+        assert_eq!(
+            event.as_log()["authorization.verified"],
+            Value::Boolean(false)
+        );
+
+        let within_leeway = hmac_token(json!({ "sub": "jane", "exp": now - 30 }));
+        let event = decode(&config, &within_leeway).await.unwrap();
+        assert_eq!(
+            event.as_log()["authorization.verified"],
+            Value::Boolean(true)
+        );
+    }
+
+    #[tokio::test]
+    async fn verifies_rsa_jwt_with_fetched_jwks() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let jwks = json!({ "keys": [{
+            "kty": "RSA",
+            "kid": "rsa1",
+            "use": "sig",
+            "n": base64::encode_config(rsa.n().to_vec(), base64::URL_SAFE_NO_PAD),
+            "e": base64::encode_config(rsa.e().to_vec(), base64::URL_SAFE_NO_PAD),
+        }]})
+        .to_string();
+        let key = PKey::from_rsa(rsa).unwrap();
+
+        let addr = next_addr();
+        let make_svc = make_service_fn(move |_| {
+            let jwks = jwks.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_| {
+                    let jwks = jwks.clone();
+                    async move { Ok::<_, Infallible>(Response::new(Body::from(jwks))) }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(make_svc));
+
+        let config = format!(
+            r#"
+            fields = [{{ field = "authorization", codec = "jwt" }}]
+            jwks.url = "http://{}/.well-known/jwks.json"
+            "#,
+            addr
+        );
+        let signed = token(
+            json!({ "alg": "RS256", "kid": "rsa1" }),
+            json!({ "sub": "jane" }),
+            &key,
+        );
+        let event = decode(&config, &signed).await.unwrap();
+        assert_eq!(
+            event.as_log()["authorization.verified"],
+            Value::Boolean(true)
+        );
+
+        // The `alg` header doesn't match the key.
+        let mismatched = signed.replacen(
+            &encode(json!({ "alg": "RS256", "kid": "rsa1" })),
+            &encode(json!({ "alg": "HS256", "kid": "rsa1" })),
+            1,
+        );
+        let event = decode(&config, &mismatched).await.unwrap();
+        assert_eq!(
+            event.as_log()["authorization.verified"],
+            Value::Boolean(false)
+        );
+    }
+
+    #[tokio::test]
+    async fn requires_one_jwks_source() {
+        let config = toml::from_str::<DecodeFieldsConfig>(
+            r#"
+            fields = [{ field = "authorization", codec = "jwt" }]
+            jwks.refresh_interval_secs = 60
+            "#,
+        )
+        .unwrap();
+        assert!(config.build().await.is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    field_encryption::{default_metadata_field, Algorithm, Key, KeySource},
+    internal_events::{DecryptFieldsEventProcessed, DecryptFieldsFailed, DecryptFieldsUnknownKey},
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DecryptFieldsConfig {
+    /// The keys events may have been encrypted with. Keeping retired keys
+    /// here allows rotating keys upstream.
+    pub keys: Vec<KeyConfig>,
+    #[serde(default = "default_metadata_field")]
+    pub metadata_field: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct KeyConfig {
+    pub key_id: String,
+    pub key: KeySource,
+}
+
+inventory::submit! {
+    TransformDescription::new::<DecryptFieldsConfig>("decrypt_fields")
+}
+
+impl GenerateConfig for DecryptFieldsConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"keys = [{ key_id = "2020-11", key.env = "VECTOR_FIELD_ENCRYPTION_KEY" }]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "decrypt_fields")]
+impl TransformConfig for DecryptFieldsConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let mut keys = HashMap::new();
+        for key in &self.keys {
+            keys.insert(key.key_id.clone(), key.key.load().await?);
+        }
+
+        Ok(Transform::function(DecryptFields {
+            keys,
+            metadata_field: self.metadata_field.clone(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "decrypt_fields"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DecryptFields {
+    keys: HashMap<String, Key>,
+    metadata_field: String,
+}
+
+impl DecryptFields {
+    /// Decrypts the fields listed in the metadata, leaving the event as it
+    /// was if any of them fails.
+    fn decrypt(&self, event: &mut Event) {
+        let log = event.as_mut_log();
+        let (key_id, algorithm, fields) = match log.get(&self.metadata_field) {
+            Some(Value::Map(metadata)) => (
+                metadata.get("key_id").map(Value::to_string_lossy),
+                metadata
+                    .get("algorithm")
+                    .and_then(|algorithm| Algorithm::from_name(&algorithm.to_string_lossy())),
+                match metadata.get("fields") {
+                    Some(Value::Array(fields)) => {
+                        fields.iter().map(Value::to_string_lossy).collect()
+                    }
+                    _ => Vec::new(),
+                },
+            ),
+            // Not encrypted.
+            _ => return,
+        };
+
+        let key = match key_id.as_ref().and_then(|key_id| self.keys.get(key_id)) {
+            Some(key) => key,
+            None => {
+                emit!(DecryptFieldsUnknownKey {
+                    key_id: key_id.as_deref().unwrap_or("")
+                });
+                return;
+            }
+        };
+        let algorithm = algorithm.unwrap_or_default();
+
+        let mut decrypted = Vec::with_capacity(fields.len());
+        for field in &fields {
+            let sealed = match log.get(field) {
+                Some(Value::Bytes(sealed)) => sealed,
+                _ => continue,
+            };
+            match key.decrypt(algorithm, field, sealed) {
+                Ok(value) => decrypted.push((field, value)),
+                Err(error) => {
+                    emit!(DecryptFieldsFailed { field, error });
+                    return;
+                }
+            }
+        }
+
+        for (field, value) in decrypted {
+            log.insert(field, value);
+        }
+        log.remove(&self.metadata_field);
+    }
+}
+
+impl FunctionTransform for DecryptFields {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        self.decrypt(&mut event);
+        emit!(DecryptFieldsEventProcessed);
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transforms::encrypt_fields::EncryptFieldsConfig;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<DecryptFieldsConfig>();
+    }
+
+    async fn encrypt(event: Event) -> Event {
+        std::env::set_var("DECRYPT_FIELDS_TEST_KEY_1", base64::encode(&[1; 32]));
+        let config: EncryptFieldsConfig = toml::from_str(
+            r#"
+            fields = ["card", "amount"]
+            algorithm = "chacha20_poly1305"
+            key_id = "k1"
+            key.env = "DECRYPT_FIELDS_TEST_KEY_1"
+            "#,
+        )
+        .unwrap();
+        let mut transform = config.build().await.unwrap();
+        transform.as_function().transform_one(event).unwrap()
+    }
+
+    async fn decrypter() -> Transform {
+        std::env::set_var("DECRYPT_FIELDS_TEST_KEY_1", base64::encode(&[1; 32]));
+        std::env::set_var("DECRYPT_FIELDS_TEST_KEY_2", base64::encode(&[2; 32]));
+        let config: DecryptFieldsConfig = toml::from_str(
+            r#"
+            keys = [
+                { key_id = "k1", key.env = "DECRYPT_FIELDS_TEST_KEY_1" },
+                { key_id = "k2", key.env = "DECRYPT_FIELDS_TEST_KEY_2" },
+            ]
+            "#,
+        )
+        .unwrap();
+        config.build().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn decrypts_encrypted_fields() {
+        let mut event = Event::from("payment");
+        event.as_mut_log().insert("card", "4111111111111111");
+        event.as_mut_log().insert("amount", 25);
+        let event = encrypt(event).await;
+
+        let event = decrypter()
+            .await
+            .as_function()
+            .transform_one(event)
+            .unwrap();
+        let log = event.as_log();
+        assert_eq!(log["card"], "4111111111111111".into());
+        assert_eq!(log["amount"], Value::Integer(25));
+        assert!(log.get("encryption").is_none());
+    }
+
+    #[tokio::test]
+    async fn leaves_event_on_failure() {
+        let mut event = Event::from("payment");
+        event.as_mut_log().insert("card", "4111111111111111");
+        let mut event = encrypt(event).await;
+        event.as_mut_log().insert("encryption.key_id", "k2");
+        let sealed = event.as_log()["card"].clone();
+
+        let event = decrypter()
+            .await
+            .as_function()
+            .transform_one(event)
+            .unwrap();
+        assert_eq!(event.as_log()["card"], sealed);
+        assert_eq!(event.as_log()["encryption.key_id"], "k2".into());
+    }
+}
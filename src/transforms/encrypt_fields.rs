@@ -0,0 +1,152 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    field_encryption::{default_metadata_field, Algorithm, Key, KeySource},
+    internal_events::{EncryptFieldsEventProcessed, EncryptFieldsFailed},
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptFieldsConfig {
+    pub fields: Vec<String>,
+    #[serde(default)]
+    pub algorithm: Algorithm,
+    /// Identifies the key to `decrypt_fields`, so keys can be rotated.
+    pub key_id: String,
+    pub key: KeySource,
+    #[serde(default = "default_metadata_field")]
+    pub metadata_field: String,
+}
+
+inventory::submit! {
+    TransformDescription::new::<EncryptFieldsConfig>("encrypt_fields")
+}
+
+impl GenerateConfig for EncryptFieldsConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"fields = ["user.email"]
+            key_id = "2020-11"
+            key.env = "VECTOR_FIELD_ENCRYPTION_KEY""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "encrypt_fields")]
+impl TransformConfig for EncryptFieldsConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let key = self.key.load().await?;
+        Ok(Transform::function(EncryptFields {
+            fields: self.fields.clone(),
+            algorithm: self.algorithm,
+            key_id: self.key_id.clone(),
+            key,
+            metadata_field: self.metadata_field.clone(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "encrypt_fields"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EncryptFields {
+    fields: Vec<String>,
+    algorithm: Algorithm,
+    key_id: String,
+    key: Key,
+    metadata_field: String,
+}
+
+impl FunctionTransform for EncryptFields {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_mut_log();
+
+        let mut encrypted = Vec::new();
+        for field in &self.fields {
+            let value = match log.get(field) {
+                Some(value) => value,
+                None => continue,
+            };
+            match self.key.encrypt(self.algorithm, field, value) {
+                Ok(sealed) => {
+                    log.insert(field, sealed);
+                    encrypted.push(Value::from(field.clone()));
+                }
+                Err(error) => {
+                    // Never let the plaintext through.
+                    emit!(EncryptFieldsFailed { field, error });
+                    log.remove(field);
+                }
+            }
+        }
+
+        if !encrypted.is_empty() {
+            log.insert(
+                format!("{}.key_id", self.metadata_field),
+                self.key_id.clone(),
+            );
+            log.insert(
+                format!("{}.algorithm", self.metadata_field),
+                self.algorithm.as_str(),
+            );
+            log.insert(
+                format!("{}.fields", self.metadata_field),
+                Value::Array(encrypted),
+            );
+        }
+
+        emit!(EncryptFieldsEventProcessed);
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<EncryptFieldsConfig>();
+    }
+
+    #[tokio::test]
+    async fn encrypts_fields() {
+        std::env::set_var("ENCRYPT_FIELDS_TEST_KEY", base64::encode(&[3; 32]));
+        let config: EncryptFieldsConfig = toml::from_str(
+            r#"
+            fields = ["card", "missing"]
+            key_id = "k1"
+            key.env = "ENCRYPT_FIELDS_TEST_KEY"
+            "#,
+        )
+        .unwrap();
+        let mut transform = config.build().await.unwrap();
+
+        let mut event = Event::from("payment");
+        event.as_mut_log().insert("card", "4111111111111111");
+        let event = transform.as_function().transform_one(event).unwrap();
+        let log = event.as_log();
+
+        assert_ne!(log["card"], "4111111111111111".into());
+        assert!(log.get("missing").is_none());
+        assert_eq!(log["message"], "payment".into());
+        assert_eq!(log["encryption.key_id"], "k1".into());
+        assert_eq!(log["encryption.algorithm"], "aes_256_gcm".into());
+        assert_eq!(log["encryption.fields"], Value::Array(vec!["card".into()]));
+    }
+}
@@ -0,0 +1,364 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{ExceptionFingerprintEventProcessed, ExceptionFingerprintNoStackTrace},
+    transforms::{FunctionTransform, Transform},
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct ExceptionFingerprintConfig {
+    /// The field holding the stack trace, defaults to the message.
+    pub field: Option<String>,
+    #[derivative(Default(value = "\"exception\".to_owned()"))]
+    pub target_field: String,
+    /// How many of the innermost frames are extracted and fingerprinted.
+    #[derivative(Default(value = "5"))]
+    pub frames: usize,
+}
+
+inventory::submit! {
+    TransformDescription::new::<ExceptionFingerprintConfig>("exception_fingerprint")
+}
+
+impl GenerateConfig for ExceptionFingerprintConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"target_field = "exception""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "exception_fingerprint")]
+impl TransformConfig for ExceptionFingerprintConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let field = self
+            .field
+            .clone()
+            .unwrap_or_else(|| log_schema().message_key().to_string());
+
+        Ok(Transform::function(ExceptionFingerprint {
+            field,
+            target_field: self.target_field.clone(),
+            frames: self.frames,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "exception_fingerprint"
+    }
+}
+
+lazy_static! {
+    static ref PYTHON_FRAME: Regex =
+        Regex::new(r#"^\s*File "([^"]+)", line \d+, in (\S+)"#).unwrap();
+    static ref PYTHON_EXCEPTION: Regex = Regex::new(r"^([A-Za-z_][\w.]*)(?::\s*(.*))?$").unwrap();
+    static ref GO_PANIC: Regex = Regex::new(r"^panic: (.*?)(?: \[recovered\])?$").unwrap();
+    static ref GO_FUNCTION: Regex = Regex::new(r"^(\S+)\([^()]*\)$").unwrap();
+    static ref NODE_FRAME: Regex = Regex::new(r"^\s+at (?:(.+?) \()?(.+?):\d+:\d+\)?$").unwrap();
+    static ref JAVA_FRAME: Regex = Regex::new(r"^\s+at ([\w$.<>/]+)\(.*\)$").unwrap();
+    static ref EXCEPTION_HEADER: Regex =
+        Regex::new(r"^(?:Exception in thread .*? )?([A-Za-z_$][\w$.]*)(?::\s*(.*))?$").unwrap();
+}
+
+/// An exception extracted from a stack trace, with its frames innermost
+/// first.
+#[derive(Debug, PartialEq)]
+struct Exception {
+    language: &'static str,
+    r#type: String,
+    message: Option<String>,
+    frames: Vec<String>,
+}
+
+impl Exception {
+    /// Frames are identified by function and file but not line, so a crash
+    /// keeps its fingerprint when unrelated code moves it.
+    fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.language);
+        hasher.update(b"\n");
+        hasher.update(&self.r#type);
+        for frame in &self.frames {
+            hasher.update(b"\n");
+            hasher.update(frame);
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+fn parse(text: &str, max_frames: usize) -> Option<Exception> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let exception = if text.contains("Traceback (most recent call last):") {
+        parse_python(&lines)
+    } else if lines.first().map_or(false, |line| GO_PANIC.is_match(line)) {
+        parse_go(&lines)
+    } else if lines.iter().any(|line| NODE_FRAME.is_match(line)) {
+        parse_header_and_frames(&lines, "javascript", &NODE_FRAME, |captures| {
+            // Anonymous functions are identified by their file.
+            captures
+                .get(1)
+                .unwrap_or_else(|| captures.get(2).unwrap())
+                .as_str()
+                .to_owned()
+        })
+    } else {
+        parse_header_and_frames(&lines, "java", &JAVA_FRAME, |captures| {
+            captures[1].to_owned()
+        })
+    };
+
+    exception.map(|mut exception| {
+        exception.frames.truncate(max_frames);
+        exception
+    })
+}
+
+fn parse_python(lines: &[&str]) -> Option<Exception> {
+    let start = lines
+        .iter()
+        .rposition(|line| line.starts_with("Traceback (most recent call last):"))?;
+
+    let mut frames = Vec::new();
+    let mut exception = None;
+    for line in &lines[start + 1..] {
+        if let Some(captures) = PYTHON_FRAME.captures(line) {
+            frames.push(format!("{}:{}", &captures[1], &captures[2]));
+        } else if !line.starts_with(' ') && !line.is_empty() {
+            exception = PYTHON_EXCEPTION.captures(line);
+            break;
+        }
+    }
+    let exception = exception?;
+    // Python lists the innermost frame last.
+    frames.reverse();
+
+    Some(Exception {
+        language: "python",
+        r#type: exception[1].to_owned(),
+        message: exception.get(2).map(|message| message.as_str().to_owned()),
+        frames,
+    })
+}
+
+fn parse_go(lines: &[&str]) -> Option<Exception> {
+    let message = GO_PANIC.captures(lines[0])?[1].to_owned();
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("goroutine "))?;
+
+    // Only the panicking goroutine, which is listed first. Each frame is a
+    // function line followed by an indented file line.
+    let mut frames = Vec::new();
+    for line in &lines[start + 1..] {
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some(captures) = GO_FUNCTION.captures(line) {
+            frames.push(captures[1].to_owned());
+        }
+    }
+
+    Some(Exception {
+        language: "go",
+        r#type: "panic".to_owned(),
+        message: Some(message),
+        frames,
+    })
+}
+
+/// Parses the Java and Node format, a `Type: message` line followed by
+/// `at` frames, innermost first.
+fn parse_header_and_frames(
+    lines: &[&str],
+    language: &'static str,
+    frame: &Regex,
+    function: impl Fn(&regex::Captures) -> String,
+) -> Option<Exception> {
+    let first_frame = lines.iter().position(|line| frame.is_match(line))?;
+    let header = lines[..first_frame]
+        .iter()
+        .rev()
+        .find_map(|line| EXCEPTION_HEADER.captures(line.trim_end()))?;
+
+    let frames = lines[first_frame..]
+        .iter()
+        .take_while(|line| !line.starts_with("Caused by:"))
+        .filter_map(|line| frame.captures(line))
+        .map(|captures| function(&captures))
+        .collect();
+
+    Some(Exception {
+        language,
+        r#type: header[1].to_owned(),
+        message: header.get(2).map(|message| message.as_str().to_owned()),
+        frames,
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct ExceptionFingerprint {
+    field: String,
+    target_field: String,
+    frames: usize,
+}
+
+impl FunctionTransform for ExceptionFingerprint {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let exception = event
+            .as_log()
+            .get(&self.field)
+            .and_then(|value| parse(&value.to_string_lossy(), self.frames));
+
+        match exception {
+            Some(exception) => {
+                let log = event.as_mut_log();
+                let target = &self.target_field;
+                log.insert(format!("{}.fingerprint", target), exception.fingerprint());
+                log.insert(format!("{}.language", target), exception.language);
+                log.insert(format!("{}.type", target), exception.r#type);
+                if let Some(message) = exception.message {
+                    log.insert(format!("{}.message", target), message);
+                }
+                let frames = exception.frames.into_iter().map(Value::from).collect();
+                log.insert(format!("{}.frames", target), Value::Array(frames));
+            }
+            None => emit!(ExceptionFingerprintNoStackTrace { field: &self.field }),
+        }
+
+        emit!(ExceptionFingerprintEventProcessed);
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ExceptionFingerprintConfig>();
+    }
+
+    const JAVA: &str = "Exception in thread \"main\" java.lang.IllegalStateException: Queue full
+\tat com.example.Queue.push(Queue.java:42)
+\tat com.example.Worker.run(Worker.java:17)
+\tat java.lang.Thread.run(Thread.java:748)
+Caused by: java.io.IOException: disk
+\tat com.example.Disk.write(Disk.java:9)";
+
+    const PYTHON: &str = "Traceback (most recent call last):
+  File \"/app/main.py\", line 10, in <module>
+    main()
+  File \"/app/main.py\", line 6, in main
+    int(value)
+ValueError: invalid literal for int() with base 10: 'x'";
+
+    const GO: &str = "panic: runtime error: index out of range [5] with length 3
+
+goroutine 1 [running]:
+main.(*Queue).Pop(...)
+\t/app/queue.go:12
+main.main()
+\t/app/main.go:8 +0x1d
+
+goroutine 2 [sleep]:
+main.idle()
+\t/app/main.go:20 +0x5";
+
+    const NODE: &str = "TypeError: Cannot read property 'id' of undefined
+    at getUser (/app/users.js:10:15)
+    at /app/index.js:20:3
+    at processTicksAndRejections (internal/process/task_queues.js:97:5)";
+
+    #[test]
+    fn parses_java() {
+        let exception = parse(JAVA, 5).unwrap();
+        assert_eq!(exception.language, "java");
+        assert_eq!(exception.r#type, "java.lang.IllegalStateException");
+        assert_eq!(exception.message.as_deref(), Some("Queue full"));
+        assert_eq!(
+            exception.frames,
+            vec![
+                "com.example.Queue.push",
+                "com.example.Worker.run",
+                "java.lang.Thread.run"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_python() {
+        let exception = parse(PYTHON, 5).unwrap();
+        assert_eq!(exception.language, "python");
+        assert_eq!(exception.r#type, "ValueError");
+        assert_eq!(
+            exception.frames,
+            vec!["/app/main.py:main", "/app/main.py:<module>"]
+        );
+    }
+
+    #[test]
+    fn parses_go() {
+        let exception = parse(GO, 5).unwrap();
+        assert_eq!(exception.language, "go");
+        assert_eq!(exception.r#type, "panic");
+        assert_eq!(
+            exception.message.as_deref(),
+            Some("runtime error: index out of range [5] with length 3")
+        );
+        assert_eq!(exception.frames, vec!["main.(*Queue).Pop", "main.main"]);
+    }
+
+    #[test]
+    fn parses_node() {
+        let exception = parse(NODE, 2).unwrap();
+        assert_eq!(exception.language, "javascript");
+        assert_eq!(exception.r#type, "TypeError");
+        assert_eq!(exception.frames, vec!["getUser", "/app/index.js"]);
+    }
+
+    #[tokio::test]
+    async fn fingerprint_ignores_lines_and_messages() {
+        let mut transform = ExceptionFingerprintConfig::default().build().await.unwrap();
+        let transform = transform.as_function();
+
+        let moved = JAVA
+            .replace("Queue full", "Queue full (1024 items)")
+            .replace("Queue.java:42", "Queue.java:57");
+        let first = transform.transform_one(Event::from(JAVA)).unwrap();
+        let second = transform.transform_one(Event::from(moved)).unwrap();
+        let other = transform.transform_one(Event::from(PYTHON)).unwrap();
+
+        let fingerprint = &first.as_log()["exception.fingerprint"];
+        assert_eq!(fingerprint, &second.as_log()["exception.fingerprint"]);
+        assert_ne!(fingerprint, &other.as_log()["exception.fingerprint"]);
+        assert_eq!(
+            first.as_log()["exception.frames[0]"],
+            "com.example.Queue.push".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn passes_other_events() {
+        let mut transform = ExceptionFingerprintConfig::default().build().await.unwrap();
+        let event = transform
+            .as_function()
+            .transform_one(Event::from("GET /health 200"))
+            .unwrap();
+        assert!(event.as_log().get("exception").is_none());
+    }
+}
@@ -0,0 +1,377 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{ExecEventProcessed, ExecFailed, ExecProcessSpawned},
+    transforms::{TaskTransform, Transform},
+};
+use chrono::{DateTime, Utc};
+use futures::{
+    compat::{Compat, Compat01As03},
+    stream, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    convert::TryFrom,
+    process::Stdio,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::{Child, ChildStdin, ChildStdout, Command},
+    time::{delay_until, timeout, Instant},
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`command` must not be empty"))]
+    EmptyCommand,
+    #[snafu(display("`concurrency` must be greater than zero"))]
+    ZeroConcurrency,
+}
+
+#[derive(Debug, Snafu)]
+pub enum ExecError {
+    #[snafu(display("Failed to start process: {}", source))]
+    Spawn { source: std::io::Error },
+    #[snafu(display("Failed to communicate with process: {}", source))]
+    Io { source: std::io::Error },
+    #[snafu(display("Process did not respond in time"))]
+    Timeout,
+    #[snafu(display("Process exited"))]
+    Exited,
+    #[snafu(display("Failed to encode event: {}", source))]
+    Encode { source: serde_json::Error },
+    #[snafu(display("Process wrote invalid JSON: {}", source))]
+    InvalidJson { source: serde_json::Error },
+    #[snafu(display("Process wrote something other than an object, array or null"))]
+    NotAnEvent,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct ExecConfig {
+    /// The program and its arguments.
+    pub command: Vec<String>,
+    /// How many processes are run, each handling one event at a time.
+    #[derivative(Default(value = "1"))]
+    pub concurrency: usize,
+    #[derivative(Default(value = "10"))]
+    pub timeout_secs: u64,
+    /// How long to wait before starting a process again after it failed.
+    #[derivative(Default(value = "1"))]
+    pub restart_delay_secs: u64,
+    /// Drop events that fail, instead of passing them through unchanged.
+    pub drop_on_error: bool,
+}
+
+inventory::submit! {
+    TransformDescription::new::<ExecConfig>("exec")
+}
+
+impl GenerateConfig for ExecConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"command = ["python3", "enrich.py"]"#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "exec")]
+impl TransformConfig for ExecConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        if self.command.is_empty() {
+            return Err(BuildError::EmptyCommand.into());
+        }
+        if self.concurrency == 0 {
+            return Err(BuildError::ZeroConcurrency.into());
+        }
+
+        Ok(Transform::task(Exec {
+            command: Arc::new(self.command.clone()),
+            concurrency: self.concurrency,
+            timeout: Duration::from_secs(self.timeout_secs),
+            restart_delay: Duration::from_secs(self.restart_delay_secs),
+            drop_on_error: self.drop_on_error,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "exec"
+    }
+}
+
+pub struct Exec {
+    command: Arc<Vec<String>>,
+    concurrency: usize,
+    timeout: Duration,
+    restart_delay: Duration,
+    drop_on_error: bool,
+}
+
+/// A running process. It's killed when dropped.
+struct Process {
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+/// Runs events through one process, starting it when needed.
+struct Worker {
+    command: Arc<Vec<String>>,
+    timeout: Duration,
+    restart_delay: Duration,
+    process: Option<Process>,
+    restart_at: Option<Instant>,
+}
+
+impl Worker {
+    fn spawn(&self) -> Result<Process, ExecError> {
+        let mut child = Command::new(&self.command[0])
+            .args(&self.command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .context(Spawn)?;
+        emit!(ExecProcessSpawned {
+            command: &self.command[0]
+        });
+
+        Ok(Process {
+            stdin: child.stdin.take().expect("stdin is piped"),
+            stdout: BufReader::new(child.stdout.take().expect("stdout is piped")),
+            _child: child,
+        })
+    }
+
+    async fn process(&mut self, event: &Event) -> Result<Vec<Event>, ExecError> {
+        let result = self.exchange(event).await;
+        if matches!(
+            result,
+            Err(ExecError::Spawn { .. })
+                | Err(ExecError::Io { .. })
+                | Err(ExecError::Timeout)
+                | Err(ExecError::Exited)
+        ) {
+            // The process can't be relied on to answer the next event.
+            self.process = None;
+            self.restart_at = Some(Instant::now() + self.restart_delay);
+        }
+        result
+    }
+
+    async fn exchange(&mut self, event: &Event) -> Result<Vec<Event>, ExecError> {
+        if self.process.is_none() {
+            if let Some(restart_at) = self.restart_at.take() {
+                delay_until(restart_at).await;
+            }
+            self.process = Some(self.spawn()?);
+        }
+        let process = self.process.as_mut().unwrap();
+
+        let mut line = serde_json::to_vec(event.as_log()).context(Encode)?;
+        line.push(b'\n');
+
+        let mut response = String::new();
+        let exchange = async {
+            process.stdin.write_all(&line).await?;
+            process.stdin.flush().await?;
+            process.stdout.read_line(&mut response).await
+        };
+        match timeout(self.timeout, exchange).await {
+            Err(_) => return Err(ExecError::Timeout),
+            Ok(Err(error)) => return Err(ExecError::Io { source: error }),
+            Ok(Ok(0)) => return Err(ExecError::Exited),
+            Ok(Ok(_)) => (),
+        }
+
+        match serde_json::from_str(&response).context(InvalidJson)? {
+            serde_json::Value::Null => Ok(Vec::new()),
+            serde_json::Value::Array(values) => values.into_iter().map(to_event).collect(),
+            value => Ok(vec![to_event(value)?]),
+        }
+    }
+}
+
+fn to_event(value: serde_json::Value) -> Result<Event, ExecError> {
+    if !value.is_object() {
+        return Err(ExecError::NotAnEvent);
+    }
+    let mut event = Event::try_from(value).map_err(|_| ExecError::NotAnEvent)?;
+
+    // Timestamps were written as strings, so read them back.
+    let log = event.as_mut_log();
+    let timestamp_key = log_schema().timestamp_key();
+    let timestamp = match log.get(timestamp_key) {
+        Some(Value::Bytes(bytes)) => {
+            DateTime::parse_from_rfc3339(&String::from_utf8_lossy(bytes)).ok()
+        }
+        _ => None,
+    };
+    if let Some(timestamp) = timestamp {
+        log.insert(timestamp_key, timestamp.with_timezone(&Utc));
+    }
+
+    Ok(event)
+}
+
+impl TaskTransform for Exec {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>
+    where
+        Self: 'static,
+    {
+        // At most `concurrency` events are in flight, so there's always an
+        // idle worker for the next one.
+        let workers = (0..self.concurrency)
+            .map(|_| Worker {
+                command: Arc::clone(&self.command),
+                timeout: self.timeout,
+                restart_delay: self.restart_delay,
+                process: None,
+                restart_at: None,
+            })
+            .collect::<Vec<_>>();
+        let workers = Arc::new(Mutex::new(workers));
+        let drop_on_error = self.drop_on_error;
+
+        let stream = Compat01As03::new(input_rx)
+            .map(move |event| {
+                let event = event.expect("Unexpected error reading channel");
+                let workers = Arc::clone(&workers);
+                async move {
+                    let mut worker = workers.lock().unwrap().pop().expect("a worker is idle");
+                    let result = worker.process(&event).await;
+                    workers.lock().unwrap().push(worker);
+
+                    emit!(ExecEventProcessed);
+                    match result {
+                        Ok(events) => events,
+                        Err(error) => {
+                            emit!(ExecFailed { error: &error });
+                            if drop_on_error {
+                                Vec::new()
+                            } else {
+                                vec![event]
+                            }
+                        }
+                    }
+                }
+            })
+            .buffered(self.concurrency)
+            .flat_map(stream::iter);
+
+        // Needed for compat
+        let try_stream = Box::pin(stream.map::<Result<Event, ()>, _>(Ok));
+
+        Box::new(Compat::new(try_stream))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use futures::compat::Stream01CompatExt;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ExecConfig>();
+    }
+
+    fn shell(script: &str) -> ExecConfig {
+        ExecConfig {
+            command: vec!["sh".into(), "-c".into(), script.into()],
+            restart_delay_secs: 0,
+            ..Default::default()
+        }
+    }
+
+    async fn run(config: ExecConfig, messages: &[&str]) -> Vec<Event> {
+        let transform = config.build().await.unwrap().into_task();
+        let events = messages
+            .iter()
+            .map(|&message| Event::from(message))
+            .collect::<Vec<_>>();
+        let in_stream = Box::new(futures01::stream::iter_ok(events));
+        transform
+            .transform(in_stream)
+            .compat()
+            .map(Result::unwrap)
+            .collect()
+            .await
+    }
+
+    fn messages(events: &[Event]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| event.as_log()["message"].to_string_lossy())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn round_trips_events() {
+        let input = Event::from("hello");
+        let output = run(
+            shell("while read -r line; do echo \"$line\"; done"),
+            &["hello"],
+        )
+        .await;
+
+        assert_eq!(output, vec![input]);
+    }
+
+    #[tokio::test]
+    async fn emits_any_number_of_events() {
+        let script = r#"while read -r line; do
+            case "$line" in
+              *drop*) echo null ;;
+              *) echo "[$line,$line]" ;;
+            esac
+        done"#;
+        let config = ExecConfig {
+            concurrency: 2,
+            ..shell(script)
+        };
+        let output = run(config, &["one", "drop", "two"]).await;
+
+        assert_eq!(messages(&output), vec!["one", "one", "two", "two"]);
+    }
+
+    #[tokio::test]
+    async fn restarts_after_exit() {
+        // Each process answers one event and exits, so the second event
+        // fails and passes through unchanged.
+        let script = r#"read -r line; echo "[$line,$line]""#;
+        let output = run(shell(script), &["one", "two", "three"]).await;
+
+        assert_eq!(
+            messages(&output),
+            vec!["one", "one", "two", "three", "three"]
+        );
+    }
+
+    #[tokio::test]
+    async fn times_out() {
+        let config = ExecConfig {
+            timeout_secs: 1,
+            drop_on_error: true,
+            ..shell("sleep 10")
+        };
+        let output = run(config, &["hello"]).await;
+
+        assert!(output.is_empty());
+    }
+}
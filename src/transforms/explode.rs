@@ -0,0 +1,228 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{ExplodeEventProcessed, ExplodeFieldNotArray},
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+
+/// What happens to the fields next to the array.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Siblings {
+    /// Every emitted event gets a copy of them.
+    Copy,
+    /// Emitted events only hold the element.
+    Drop,
+}
+
+impl Default for Siblings {
+    fn default() -> Self {
+        Siblings::Copy
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExplodeConfig {
+    /// The array field to explode.
+    pub field: String,
+    /// Where each element is written, defaults to `field`.
+    #[serde(default)]
+    pub target_field: Option<String>,
+    /// Merge the fields of map elements into the event, instead of writing
+    /// them to the target field.
+    #[serde(default)]
+    pub merge: bool,
+    #[serde(default)]
+    pub siblings: Siblings,
+    /// Where the element's position in the array is written, if set.
+    #[serde(default)]
+    pub index_field: Option<String>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<ExplodeConfig>("explode")
+}
+
+impl GenerateConfig for ExplodeConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"field = "records""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "explode")]
+impl TransformConfig for ExplodeConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        Ok(Transform::function(Explode {
+            field: self.field.clone(),
+            target_field: self
+                .target_field
+                .clone()
+                .unwrap_or_else(|| self.field.clone()),
+            merge: self.merge,
+            siblings: self.siblings,
+            index_field: self.index_field.clone(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "explode"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Explode {
+    field: String,
+    target_field: String,
+    merge: bool,
+    siblings: Siblings,
+    index_field: Option<String>,
+}
+
+impl FunctionTransform for Explode {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        emit!(ExplodeEventProcessed);
+
+        let elements = match event.as_mut_log().remove(&self.field) {
+            Some(Value::Array(elements)) => elements,
+            value => {
+                emit!(ExplodeFieldNotArray { field: &self.field });
+                if let Some(value) = value {
+                    event.as_mut_log().insert(&self.field, value);
+                }
+                output.push(event);
+                return;
+            }
+        };
+
+        if self.siblings == Siblings::Drop {
+            event = Event::new_empty_log();
+        }
+
+        // An empty array emits no events.
+        let count = elements.len();
+        for (index, element) in elements.into_iter().enumerate() {
+            let mut exploded = if index + 1 == count {
+                std::mem::replace(&mut event, Event::new_empty_log())
+            } else {
+                event.clone()
+            };
+
+            let log = exploded.as_mut_log();
+            match element {
+                Value::Map(fields) if self.merge => {
+                    for (key, value) in fields {
+                        log.insert_flat(key, value);
+                    }
+                }
+                element => {
+                    log.insert(&self.target_field, element);
+                }
+            }
+            if let Some(index_field) = &self.index_field {
+                log.insert(index_field, index as i64);
+            }
+
+            output.push(exploded);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ExplodeConfig>();
+    }
+
+    fn config(toml: &str) -> ExplodeConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    async fn explode(config: ExplodeConfig, input: serde_json::Value) -> Vec<Event> {
+        let mut transform = config.build().await.unwrap();
+        let mut output = Vec::new();
+        transform
+            .as_function()
+            .transform(&mut output, Event::try_from(input).unwrap());
+        output
+    }
+
+    fn events(values: Vec<serde_json::Value>) -> Vec<Event> {
+        values
+            .into_iter()
+            .map(|value| Event::try_from(value).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn explodes_copying_siblings() {
+        let output = explode(
+            config(
+                r#"
+                field = "records"
+                target_field = "record"
+                index_field = "index"
+                "#,
+            ),
+            json!({"source": "webhook", "records": [{"id": 1}, "two"]}),
+        )
+        .await;
+
+        assert_eq!(
+            output,
+            events(vec![
+                json!({"source": "webhook", "record": {"id": 1}, "index": 0}),
+                json!({"source": "webhook", "record": "two", "index": 1}),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn explodes_merging_elements() {
+        let output = explode(
+            config(
+                r#"
+                field = "records"
+                merge = true
+                siblings = "drop"
+                "#,
+            ),
+            json!({"source": "webhook", "records": [{"id": 1, "a.b": true}, 2]}),
+        )
+        .await;
+
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[0].as_log().get_flat("a.b"),
+            Some(&Value::Boolean(true))
+        );
+        assert_eq!(output[0].as_log()["id"], Value::Integer(1));
+        assert!(output[0].as_log().get("source").is_none());
+        assert_eq!(output[1], events(vec![json!({"records": 2})])[0]);
+    }
+
+    #[tokio::test]
+    async fn passes_events_without_array() {
+        let input = json!({"records": "none"});
+        let output = explode(config(r#"field = "records""#), input.clone()).await;
+        assert_eq!(output, events(vec![input]));
+
+        let output = explode(config(r#"field = "records""#), json!({"records": []})).await;
+        assert!(output.is_empty());
+    }
+}
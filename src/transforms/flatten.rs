@@ -0,0 +1,402 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{FlattenEventProcessed, FlattenKeyConflict},
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::collections::{btree_map::Entry, BTreeMap};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`separator` must not be empty"))]
+    EmptySeparator,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Turn nested fields into top level fields with joined keys.
+    Flatten,
+    /// Split top level keys on the separator into nested fields.
+    Unflatten,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Flatten
+    }
+}
+
+/// How arrays are handled.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Arrays {
+    /// Array elements are keyed by their index, and maps keyed by all the
+    /// indices from zero are unflattened into arrays.
+    Index,
+    /// Arrays are left as they are.
+    Keep,
+}
+
+impl Default for Arrays {
+    fn default() -> Self {
+        Arrays::Index
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct FlattenConfig {
+    pub mode: Mode,
+    #[derivative(Default(value = "\".\".to_owned()"))]
+    pub separator: String,
+    /// The number of levels of nesting that are flattened, or created when
+    /// unflattening. Unlimited if unset.
+    pub max_depth: Option<usize>,
+    pub arrays: Arrays,
+}
+
+inventory::submit! {
+    TransformDescription::new::<FlattenConfig>("flatten")
+}
+
+impl GenerateConfig for FlattenConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"mode = "flatten"
+separator = ".""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "flatten")]
+impl TransformConfig for FlattenConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        if self.separator.is_empty() {
+            return Err(BuildError::EmptySeparator.into());
+        }
+
+        Ok(Transform::function(Flatten {
+            mode: self.mode,
+            separator: self.separator.clone(),
+            max_depth: self.max_depth.unwrap_or(usize::MAX),
+            arrays: self.arrays,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "flatten"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Flatten {
+    mode: Mode,
+    separator: String,
+    max_depth: usize,
+    arrays: Arrays,
+}
+
+impl Flatten {
+    fn flatten(&self, fields: BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+        let mut flat = BTreeMap::new();
+        for (key, value) in fields {
+            self.flatten_value(&mut flat, key, value, 1);
+        }
+        flat
+    }
+
+    fn flatten_value(
+        &self,
+        flat: &mut BTreeMap<String, Value>,
+        key: String,
+        value: Value,
+        depth: usize,
+    ) {
+        // Empty maps and arrays are kept, so no field is lost.
+        let children: Vec<(String, Value)> = match value {
+            Value::Map(map) if !map.is_empty() && depth <= self.max_depth => {
+                map.into_iter().collect()
+            }
+            Value::Array(array)
+                if !array.is_empty() && depth <= self.max_depth && self.arrays == Arrays::Index =>
+            {
+                array
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, value)| (index.to_string(), value))
+                    .collect()
+            }
+            value => {
+                if flat.contains_key(&key) {
+                    emit!(FlattenKeyConflict { key: &key });
+                }
+                flat.insert(key, value);
+                return;
+            }
+        };
+
+        for (child, value) in children {
+            let key = format!("{}{}{}", key, self.separator, child);
+            self.flatten_value(flat, key, value, depth + 1);
+        }
+    }
+
+    fn unflatten(&self, fields: BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+        let mut root = BTreeMap::new();
+        // Keys sort before the keys they prefix, so a nested field is always
+        // merged into a map already at its parent.
+        for (key, value) in fields {
+            let path = key
+                .splitn(self.max_depth.saturating_add(1), self.separator.as_str())
+                .collect::<Vec<_>>();
+            if let Err(value) = insert(&mut root, &path, value) {
+                emit!(FlattenKeyConflict { key: &key });
+                root.entry(key).or_insert(Node::Leaf(value));
+            }
+        }
+
+        root.into_iter()
+            .map(|(key, node)| (key, node.into_value(self.arrays)))
+            .collect()
+    }
+}
+
+/// A field being unflattened. Branches are the maps created while
+/// unflattening, and the only ones that may become arrays.
+enum Node {
+    Leaf(Value),
+    Branch(BTreeMap<String, Node>),
+}
+
+impl Node {
+    fn into_value(self, arrays: Arrays) -> Value {
+        match self {
+            Node::Leaf(value) => value,
+            Node::Branch(children) => {
+                let mut indexed = children
+                    .iter()
+                    .map(|(key, _)| key.parse::<usize>().ok())
+                    .collect::<Option<Vec<_>>>()
+                    .unwrap_or_default();
+                indexed.sort_unstable();
+                let is_array = arrays == Arrays::Index
+                    && !indexed.is_empty()
+                    && indexed.iter().enumerate().all(|(i, index)| i == *index)
+                    // Reject keys like `01`, which wouldn't round trip.
+                    && children.keys().all(|key| key == "0" || !key.starts_with('0'));
+
+                if is_array {
+                    let mut children = children
+                        .into_iter()
+                        .map(|(key, node)| (key.parse::<usize>().unwrap(), node))
+                        .collect::<Vec<_>>();
+                    children.sort_unstable_by_key(|(index, _)| *index);
+                    Value::Array(
+                        children
+                            .into_iter()
+                            .map(|(_, node)| node.into_value(arrays))
+                            .collect(),
+                    )
+                } else {
+                    Value::Map(
+                        children
+                            .into_iter()
+                            .map(|(key, node)| (key, node.into_value(arrays)))
+                            .collect(),
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Inserts the value at the path, handing it back if the path runs into a
+/// field that isn't a map, or the field is already set.
+fn insert(branch: &mut BTreeMap<String, Node>, path: &[&str], value: Value) -> Result<(), Value> {
+    let (first, rest) = path.split_first().expect("paths are never empty");
+    if rest.is_empty() {
+        return match branch.entry((*first).to_owned()) {
+            Entry::Vacant(entry) => {
+                entry.insert(Node::Leaf(value));
+                Ok(())
+            }
+            Entry::Occupied(_) => Err(value),
+        };
+    }
+
+    let node = branch
+        .entry((*first).to_owned())
+        .or_insert_with(|| Node::Branch(BTreeMap::new()));
+    if let Node::Leaf(Value::Map(map)) = node {
+        let map = std::mem::take(map);
+        *node = Node::Branch(
+            map.into_iter()
+                .map(|(key, value)| (key, Node::Leaf(value)))
+                .collect(),
+        );
+    }
+
+    match node {
+        Node::Branch(children) => insert(children, rest, value),
+        Node::Leaf(_) => Err(value),
+    }
+}
+
+impl FunctionTransform for Flatten {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_mut_log();
+        let fields: BTreeMap<String, Value> = std::mem::take(log).into();
+        let fields = match self.mode {
+            Mode::Flatten => self.flatten(fields),
+            Mode::Unflatten => self.unflatten(fields),
+        };
+        *log = LogEvent::from(fields);
+
+        emit!(FlattenEventProcessed);
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<FlattenConfig>();
+    }
+
+    fn event(value: serde_json::Value) -> Event {
+        Event::try_from(value).unwrap()
+    }
+
+    async fn transform(config: FlattenConfig, input: serde_json::Value) -> Event {
+        let mut transform = config.build().await.unwrap();
+        transform.as_function().transform_one(event(input)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn flattens_maps_and_arrays() {
+        let output = transform(
+            FlattenConfig::default(),
+            json!({
+                "message": "hi",
+                "http": {"status": 200, "headers": {"host": "example.com"}},
+                "tags": ["a", {"b": true}],
+                "empty": {},
+            }),
+        )
+        .await;
+
+        assert_eq!(
+            output,
+            event(json!({
+                "message": "hi",
+                "http.status": 200,
+                "http.headers.host": "example.com",
+                "tags.0": "a",
+                "tags.1.b": true,
+                "empty": {},
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn flattens_to_depth_with_separator() {
+        let output = transform(
+            FlattenConfig {
+                separator: "_".into(),
+                max_depth: Some(1),
+                arrays: Arrays::Keep,
+                ..Default::default()
+            },
+            json!({"http": {"headers": {"host": "example.com"}, "codes": [1, 2]}}),
+        )
+        .await;
+
+        assert_eq!(
+            output,
+            event(json!({
+                "http_headers": {"host": "example.com"},
+                "http_codes": [1, 2],
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn unflatten_inverts_flatten() {
+        let nested = json!({
+            "message": "hi",
+            "http": {"status": 200, "headers": {"host": "example.com"}},
+            "tags": ["a", {"b": true}],
+            "ids": {"0": 1, "01": 2},
+        });
+        let flat = transform(FlattenConfig::default(), nested.clone()).await;
+        let mut unflatten = FlattenConfig {
+            mode: Mode::Unflatten,
+            ..Default::default()
+        }
+        .build()
+        .await
+        .unwrap();
+
+        assert_eq!(
+            unflatten.as_function().transform_one(flat).unwrap(),
+            event(nested)
+        );
+    }
+
+    #[tokio::test]
+    async fn unflatten_merges_and_keeps_conflicts() {
+        let output = transform(
+            FlattenConfig {
+                mode: Mode::Unflatten,
+                max_depth: Some(1),
+                ..Default::default()
+            },
+            json!({
+                "http": {"status": 200},
+                "http.method": "GET",
+                "http.headers.host": "example.com",
+                "status": 500,
+                "status.code": 500,
+            }),
+        )
+        .await;
+
+        assert_eq!(
+            output,
+            event(json!({
+                "http": {"status": 200, "method": "GET", "headers.host": "example.com"},
+                "status": 500,
+                "status.code": 500,
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_separator() {
+        let config = FlattenConfig {
+            separator: "".into(),
+            ..Default::default()
+        };
+        assert!(config.build().await.is_err());
+    }
+}
@@ -1,12 +1,16 @@
 use crate::{
     config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    enrichment_tables::Reloadable,
     event::Event,
-    internal_events::{GeoipEventProcessed, GeoipFieldDoesNotExist, GeoipIpAddressParseError},
+    internal_events::{
+        GeoipDatabaseReloadFailed, GeoipDatabaseReloaded, GeoipEventProcessed,
+        GeoipFieldDoesNotExist, GeoipIpAddressParseError,
+    },
     transforms::{FunctionTransform, Transform},
     Result,
 };
 use serde::{Deserialize, Serialize};
-use std::str::FromStr;
+use std::{net::IpAddr, path::Path, str::FromStr, sync::Arc, time::Duration};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -15,34 +19,36 @@ pub struct GeoipConfig {
     pub database: String,
     #[serde(default = "default_geoip_target_field")]
     pub target: String,
+    #[serde(default = "default_reload_interval_secs")]
+    pub reload_interval_secs: u64,
 }
 
-#[derive(Derivative)]
-#[derivative(Debug)]
+#[derive(Clone, Debug)]
 pub struct Geoip {
-    #[derivative(Debug = "ignore")]
-    pub dbreader: maxminddb::Reader<Vec<u8>>,
-    pub database: String,
+    database: Arc<Database>,
     pub source: String,
     pub target: String,
 }
 
-impl Clone for Geoip {
-    fn clone(&self) -> Self {
-        Self {
-            dbreader: maxminddb::Reader::open_readfile(self.database.clone())
-                .expect("Panicked while cloning GeoIP lookup database. Did you move the GeoIP database on disk during runtime?"),
-            database: self.database.clone(),
-            source: self.source.clone(),
-            target: self.target.clone()
-        }
-    }
-}
-
 fn default_geoip_target_field() -> String {
     "geoip".to_string()
 }
 
+fn default_reload_interval_secs() -> u64 {
+    60
+}
+
+/// A MaxMind database shared by all copies of the transform, which is
+/// reopened when the file on disk is replaced.
+type Database = Reloadable<maxminddb::Reader<Vec<u8>>>;
+
+fn emit_reload(path: &Path, result: crate::Result<()>) {
+    match result {
+        Ok(()) => emit!(GeoipDatabaseReloaded { path }),
+        Err(error) => emit!(GeoipDatabaseReloadFailed { path, error }),
+    }
+}
+
 inventory::submit! {
     TransformDescription::new::<GeoipConfig>("geoip")
 }
@@ -53,6 +59,7 @@ impl GenerateConfig for GeoipConfig {
             database: "/path/to/GeoLite2-City.mmdb".to_string(),
             source: "ip address".to_owned(),
             target: default_geoip_target_field(),
+            reload_interval_secs: default_reload_interval_secs(),
         })
         .unwrap()
     }
@@ -62,10 +69,11 @@ impl GenerateConfig for GeoipConfig {
 #[typetag::serde(name = "geoip")]
 impl TransformConfig for GeoipConfig {
     async fn build(&self) -> Result<Transform> {
-        Ok(Transform::function(Geoip::new(
+        Ok(Transform::function(Geoip::with_reload_interval(
             self.database.clone(),
             self.source.clone(),
             self.target.clone(),
+            Duration::from_secs(self.reload_interval_secs),
         )?))
     }
 
@@ -90,20 +98,38 @@ const ISP_DATABASE_TYPE: &str = "GeoIP2-ISP";
 
 impl Geoip {
     pub fn new(database: String, source: String, target: String) -> crate::Result<Self> {
-        Ok(Geoip {
-            dbreader: maxminddb::Reader::open_readfile(database.clone())?,
+        Self::with_reload_interval(
             database,
             source,
             target,
-        })
+            Duration::from_secs(default_reload_interval_secs()),
+        )
     }
 
-    fn has_isp_db(&self) -> bool {
-        self.dbreader.metadata.database_type == ASN_DATABASE_TYPE
-            || self.dbreader.metadata.database_type == ISP_DATABASE_TYPE
+    pub fn with_reload_interval(
+        database: String,
+        source: String,
+        target: String,
+        reload_interval: Duration,
+    ) -> crate::Result<Self> {
+        Ok(Geoip {
+            database: Arc::new(Database::with_on_reload(
+                database,
+                reload_interval,
+                |path| Ok(maxminddb::Reader::open_readfile(path)?),
+                emit_reload,
+            )?),
+            source,
+            target,
+        })
     }
 }
 
+fn has_isp_db(reader: &maxminddb::Reader<Vec<u8>>) -> bool {
+    reader.metadata.database_type == ASN_DATABASE_TYPE
+        || reader.metadata.database_type == ISP_DATABASE_TYPE
+}
+
 #[derive(Default, Serialize)]
 struct ISP<'a> {
     autonomous_system_number: i64,
@@ -123,80 +149,97 @@ struct City<'a> {
     postal_code: &'a str,
 }
 
+/// Looks up an address in the database, returning the ISP or City fields
+/// depending on the kind of database. Fields are left empty when the address
+/// is unknown or missing.
+pub(crate) fn lookup(
+    dbreader: &maxminddb::Reader<Vec<u8>>,
+    ip: Option<IpAddr>,
+) -> serde_json::Result<serde_json::Value> {
+    let mut isp: ISP = Default::default();
+    let mut city: City = Default::default();
+    if let Some(ip) = ip {
+        if has_isp_db(dbreader) {
+            if let Ok(data) = dbreader.lookup::<maxminddb::geoip2::Isp>(ip) {
+                if let Some(as_number) = data.autonomous_system_number {
+                    isp.autonomous_system_number = as_number as i64;
+                }
+                if let Some(as_organization) = data.autonomous_system_organization {
+                    isp.autonomous_system_organization = as_organization;
+                }
+                if let Some(isp_name) = data.isp {
+                    isp.isp = isp_name;
+                }
+                if let Some(organization) = data.organization {
+                    isp.organization = organization;
+                }
+            }
+        } else if let Ok(data) = dbreader.lookup::<maxminddb::geoip2::City>(ip) {
+            if let Some(city_names) = data.city.and_then(|c| c.names) {
+                if let Some(city_name) = city_names.get("en") {
+                    city.city_name = city_name;
+                }
+            }
+
+            if let Some(continent_code) = data.continent.and_then(|c| c.code) {
+                city.continent_code = continent_code;
+            }
+
+            if let Some(country_code) = data.country.and_then(|cy| cy.iso_code) {
+                city.country_code = country_code;
+            };
+
+            if let Some(time_zone) = data.location.clone().and_then(|loc| loc.time_zone) {
+                city.timezone = time_zone;
+            }
+
+            if let Some(latitude) = data.location.clone().and_then(|loc| loc.latitude) {
+                city.latitude = latitude.to_string();
+            }
+
+            if let Some(longitude) = data.location.clone().and_then(|loc| loc.longitude) {
+                city.longitude = longitude.to_string();
+            }
+
+            if let Some(postal_code) = data.postal.clone().and_then(|p| p.code) {
+                city.postal_code = postal_code;
+            }
+        }
+    }
+
+    if has_isp_db(dbreader) {
+        serde_json::to_value(isp)
+    } else {
+        serde_json::to_value(city)
+    }
+}
+
 impl FunctionTransform for Geoip {
     fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
-        let mut isp: ISP = Default::default();
-        let mut city: City = Default::default();
+        let dbreader = self.database.get();
         let target_field = self.target.clone();
         let ipaddress = event
             .as_log()
             .get(&self.source)
             .map(|s| s.to_string_lossy());
-        if let Some(ipaddress) = &ipaddress {
-            if let Ok(ip) = FromStr::from_str(ipaddress) {
-                if self.has_isp_db() {
-                    if let Ok(data) = self.dbreader.lookup::<maxminddb::geoip2::Isp>(ip) {
-                        if let Some(as_number) = data.autonomous_system_number {
-                            isp.autonomous_system_number = as_number as i64;
-                        }
-                        if let Some(as_organization) = data.autonomous_system_organization {
-                            isp.autonomous_system_organization = as_organization;
-                        }
-                        if let Some(isp_name) = data.isp {
-                            isp.isp = isp_name;
-                        }
-                        if let Some(organization) = data.organization {
-                            isp.organization = organization;
-                        }
-                    }
-                } else if let Ok(data) = self.dbreader.lookup::<maxminddb::geoip2::City>(ip) {
-                    if let Some(city_names) = data.city.and_then(|c| c.names) {
-                        if let Some(city_name) = city_names.get("en") {
-                            city.city_name = city_name;
-                        }
-                    }
-
-                    if let Some(continent_code) = data.continent.and_then(|c| c.code) {
-                        city.continent_code = continent_code;
-                    }
-
-                    if let Some(country_code) = data.country.and_then(|cy| cy.iso_code) {
-                        city.country_code = country_code;
-                    };
-
-                    if let Some(time_zone) = data.location.clone().and_then(|loc| loc.time_zone) {
-                        city.timezone = time_zone;
-                    }
-
-                    if let Some(latitude) = data.location.clone().and_then(|loc| loc.latitude) {
-                        city.latitude = latitude.to_string();
-                    }
-
-                    if let Some(longitude) = data.location.clone().and_then(|loc| loc.longitude) {
-                        city.longitude = longitude.to_string();
-                    }
-
-                    if let Some(postal_code) = data.postal.clone().and_then(|p| p.code) {
-                        city.postal_code = postal_code;
-                    }
+        let ip = if let Some(ipaddress) = &ipaddress {
+            match IpAddr::from_str(ipaddress) {
+                Ok(ip) => Some(ip),
+                Err(_) => {
+                    emit!(GeoipIpAddressParseError {
+                        address: &ipaddress
+                    });
+                    None
                 }
-            } else {
-                emit!(GeoipIpAddressParseError {
-                    address: &ipaddress
-                });
             }
         } else {
             emit!(GeoipFieldDoesNotExist {
                 field: &self.source
             });
+            None
         };
 
-        let json_value = if self.has_isp_db() {
-            serde_json::to_value(isp)
-        } else {
-            serde_json::to_value(city)
-        };
-        if let Ok(json_value) = json_value {
+        if let Ok(json_value) = lookup(&dbreader, ip) {
             event.as_mut_log().insert(target_field, json_value);
         }
 
@@ -394,4 +437,32 @@ mod tests {
             assert_eq!(&geodata, exp_geoip_attr.get(field).expect("fields exists"));
         }
     }
+
+    #[test]
+    fn geoip_reloads_modified_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("geoip.mmdb");
+        std::fs::copy("tests/data/GeoLite2-ASN-Test.mmdb", &path).unwrap();
+
+        let mut augment = Geoip::with_reload_interval(
+            path.to_str().unwrap().to_string(),
+            "remote_addr".to_string(),
+            "geo".to_string(),
+            Duration::from_millis(1),
+        )
+        .unwrap();
+
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("remote_addr", "2.125.160.216");
+
+        let new_event = augment.transform_one(event.clone()).unwrap();
+        assert!(new_event.as_log().get("geo.city_name").is_none());
+
+        std::thread::sleep(Duration::from_millis(50));
+        std::fs::copy("tests/data/GeoIP2-City-Test.mmdb", &path).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+
+        let new_event = augment.transform_one(event).unwrap();
+        assert_eq!(new_event.as_log()["geo.city_name"], "Boxford".into());
+    }
 }
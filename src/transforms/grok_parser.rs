@@ -5,10 +5,11 @@ use crate::{
         GrokParserConversionFailed, GrokParserEventProcessed, GrokParserFailedMatch,
         GrokParserMissingField,
     },
-    transforms::{FunctionTransform, Transform},
+    transforms::{FunctionTransform, Transform, TransformOutputs, DROPPED},
     types::{parse_conversion_map, Conversion},
 };
 use grok::Pattern;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::collections::HashMap;
@@ -18,6 +19,8 @@ use std::str;
 enum BuildError {
     #[snafu(display("Invalid grok pattern: {}", source))]
     InvalidGrok { source: grok::Error },
+    #[snafu(display("At least one of `pattern` or `patterns` must be set"))]
+    NoPatterns,
 }
 
 #[derive(Deserialize, Serialize, Debug, Derivative, Clone)]
@@ -25,10 +28,17 @@ enum BuildError {
 #[derivative(Default)]
 pub struct GrokParserConfig {
     pub pattern: String,
+    /// Patterns tried in order after `pattern`, the first one matching wins.
+    pub patterns: Vec<String>,
+    /// Definitions added to the bundled pattern library, usable as
+    /// `%{NAME}` in the patterns.
+    pub custom_patterns: IndexMap<String, String>,
     pub field: Option<String>,
     #[derivative(Default(value = "true"))]
     pub drop_field: bool,
     pub types: HashMap<String, String>,
+    /// Send events no pattern matches to the `dropped` output.
+    pub reroute_dropped: bool,
 }
 
 inventory::submit! {
@@ -46,19 +56,27 @@ impl TransformConfig for GrokParserConfig {
             .clone()
             .unwrap_or_else(|| log_schema().message_key().into());
 
-        let mut grok = grok::Grok::with_patterns();
+        let patterns = std::iter::once(&self.pattern)
+            .filter(|pattern| !pattern.is_empty())
+            .chain(self.patterns.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        if patterns.is_empty() {
+            return Err(BuildError::NoPatterns.into());
+        }
 
         let types = parse_conversion_map(&self.types)?;
 
-        Ok(grok
-            .compile(&self.pattern, true)
+        Ok(compile(&patterns, &self.custom_patterns)
             .map(|p| GrokParser {
-                pattern: self.pattern.clone(),
-                pattern_built: p,
+                patterns,
+                custom_patterns: self.custom_patterns.clone(),
+                patterns_built: p,
                 field: field.clone(),
                 drop_field: self.drop_field,
                 types,
                 paths: HashMap::new(),
+                reroute_dropped: self.reroute_dropped,
             })
             .map(Transform::function)
             .context(InvalidGrok)?)
@@ -75,74 +93,137 @@ impl TransformConfig for GrokParserConfig {
     fn transform_type(&self) -> &'static str {
         "grok_parser"
     }
+
+    fn named_outputs(&self) -> Vec<String> {
+        if self.reroute_dropped {
+            vec![DROPPED.to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+fn compile(
+    patterns: &[String],
+    custom_patterns: &IndexMap<String, String>,
+) -> Result<Vec<Pattern>, grok::Error> {
+    let mut grok = grok::Grok::with_patterns();
+    for (name, pattern) in custom_patterns {
+        grok.insert_definition(name.clone(), pattern.clone());
+    }
+
+    patterns
+        .iter()
+        .map(|pattern| grok.compile(pattern, true))
+        .collect()
 }
 
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct GrokParser {
     #[derivative(Debug = "ignore")]
-    pattern_built: Pattern,
-    pattern: String,
+    patterns_built: Vec<Pattern>,
+    patterns: Vec<String>,
+    custom_patterns: IndexMap<String, String>,
     field: String,
     drop_field: bool,
     types: HashMap<String, Conversion>,
     paths: HashMap<String, Vec<PathComponent>>,
+    reroute_dropped: bool,
 }
 
 impl Clone for GrokParser {
     fn clone(&self) -> Self {
         Self {
-            pattern_built: grok::Grok::with_patterns().compile(&self.pattern, true)
+            patterns_built: compile(&self.patterns, &self.custom_patterns)
                 .expect("Panicked while cloning an already valid Grok parser. For some reason, the pattern could not be built again."),
-            pattern: self.pattern.clone(),
+            patterns: self.patterns.clone(),
+            custom_patterns: self.custom_patterns.clone(),
             field: self.field.clone(),
             drop_field: self.drop_field,
             types: self.types.clone(),
             paths: self.paths.clone(),
+            reroute_dropped: self.reroute_dropped,
         }
     }
 }
 
-impl FunctionTransform for GrokParser {
-    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+impl GrokParser {
+    /// Adds the captures of the first matching pattern to the event, handing
+    /// the event back with the reason if none matches.
+    fn parse(&mut self, event: Event) -> Result<Event, (Event, String)> {
         let mut event = event.into_log();
         let value = event.get(&self.field).map(|s| s.to_string_lossy());
         emit!(GrokParserEventProcessed);
 
-        if let Some(value) = value {
-            if let Some(matches) = self.pattern_built.match_against(&value) {
-                let drop_field = self.drop_field && matches.get(&self.field).is_none();
-                for (name, value) in matches.iter() {
-                    let conv = self.types.get(name).unwrap_or(&Conversion::Bytes);
-                    match conv.convert(value.to_string().into()) {
-                        Ok(value) => {
-                            if let Some(path) = self.paths.get(name) {
-                                event.insert_path(path.to_vec(), value.clone());
-                            } else {
-                                let path = PathIter::new(name).collect::<Vec<_>>();
-                                self.paths.insert(name.to_string(), path.clone());
-                                event.insert_path(path, value);
-                            }
-                        }
-                        Err(error) => emit!(GrokParserConversionFailed { name, error }),
-                    }
-                }
-
-                if drop_field {
-                    event.remove(&self.field);
-                }
-            } else {
+        let value = match value {
+            Some(value) => value,
+            None => {
+                emit!(GrokParserMissingField {
+                    field: self.field.as_ref()
+                });
+                let message = format!("field {:?} is missing", self.field);
+                return Err((Event::Log(event), message));
+            }
+        };
+
+        let matches = match self
+            .patterns_built
+            .iter()
+            .find_map(|pattern| pattern.match_against(&value))
+        {
+            Some(matches) => matches,
+            None => {
                 emit!(GrokParserFailedMatch {
                     value: value.as_ref()
                 });
+                let message = "no pattern matched the field".to_owned();
+                return Err((Event::Log(event), message));
             }
-        } else {
-            emit!(GrokParserMissingField {
-                field: self.field.as_ref()
-            });
+        };
+
+        let drop_field = self.drop_field && matches.get(&self.field).is_none();
+        for (name, value) in matches.iter() {
+            let conv = self.types.get(name).unwrap_or(&Conversion::Bytes);
+            match conv.convert(value.to_string().into()) {
+                Ok(value) => {
+                    if let Some(path) = self.paths.get(name) {
+                        event.insert_path(path.to_vec(), value.clone());
+                    } else {
+                        let path = PathIter::new(name).collect::<Vec<_>>();
+                        self.paths.insert(name.to_string(), path.clone());
+                        event.insert_path(path, value);
+                    }
+                }
+                Err(error) => emit!(GrokParserConversionFailed { name, error }),
+            }
+        }
+
+        if drop_field {
+            event.remove(&self.field);
         }
 
-        output.push(Event::Log(event));
+        Ok(Event::Log(event))
+    }
+}
+
+impl FunctionTransform for GrokParser {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        match self.parse(event) {
+            Ok(event) => output.push(event),
+            Err((event, _)) if !self.reroute_dropped => output.push(event),
+            Err(_) => (),
+        }
+    }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        match self.parse(event) {
+            Ok(event) => outputs.push(event),
+            Err((event, message)) if self.reroute_dropped => {
+                outputs.push_dropped(event, "match_failed", message)
+            }
+            Err((event, _)) => outputs.push(event),
+        }
     }
 }
 
@@ -150,6 +231,7 @@ impl FunctionTransform for GrokParser {
 mod tests {
     use super::GrokParserConfig;
     use crate::event::LogEvent;
+    use crate::transforms::{TransformOutputs, DROPPED};
     use crate::{
         config::{log_schema, TransformConfig},
         event, Event,
@@ -175,6 +257,7 @@ mod tests {
             field: field.map(|s| s.into()),
             drop_field,
             types: types.iter().map(|&(k, v)| (k.into(), v.into())).collect(),
+            ..Default::default()
         }
         .build()
         .await
@@ -323,4 +406,75 @@ mod tests {
 
         assert_eq!(expected, serde_json::to_value(&event.all_fields()).unwrap());
     }
+
+    #[tokio::test]
+    async fn grok_parser_tries_patterns_in_order() {
+        let mut parser = GrokParserConfig {
+            patterns: vec![
+                "%{NUMBER:status} %{WORD:verb}".into(),
+                "%{WORD:verb} %{DATA:path}".into(),
+            ],
+            ..Default::default()
+        }
+        .build()
+        .await
+        .unwrap();
+        let parser = parser.as_function();
+
+        let event = parser.transform_one(Event::from("200 GET")).unwrap();
+        assert_eq!(event.as_log()["status"], "200".into());
+
+        let event = parser.transform_one(Event::from("GET /index")).unwrap();
+        assert_eq!(event.as_log()["verb"], "GET".into());
+        assert_eq!(event.as_log()["path"], "/index".into());
+        assert!(event.as_log().get("status").is_none());
+    }
+
+    #[tokio::test]
+    async fn grok_parser_uses_custom_patterns() {
+        let mut parser = GrokParserConfig {
+            pattern: "%{SERVICE:service}: %{GREEDYDATA:rest}".into(),
+            custom_patterns: vec![("SERVICE".to_owned(), "[a-z]+-[0-9]+".to_owned())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }
+        .build()
+        .await
+        .unwrap();
+        let parser = parser.as_function();
+
+        let event = parser
+            .transform_one(Event::from("api-01: started"))
+            .unwrap();
+        assert_eq!(event.as_log()["service"], "api-01".into());
+        assert_eq!(event.as_log()["rest"], "started".into());
+    }
+
+    #[tokio::test]
+    async fn grok_parser_reroutes_failed_matches() {
+        let mut parser = GrokParserConfig {
+            pattern: "%{NUMBER:status}".into(),
+            reroute_dropped: true,
+            ..Default::default()
+        }
+        .build()
+        .await
+        .unwrap();
+        let parser = parser.as_function();
+
+        let mut outputs = TransformOutputs::new("grok");
+        parser.transform_outputs(&mut outputs, Event::from("not a number"));
+
+        assert!(outputs.primary.is_empty());
+        assert_eq!(
+            outputs.named[DROPPED][0].as_log()["metadata.dropped.reason"],
+            "match_failed".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn grok_parser_requires_a_pattern() {
+        assert!(GrokParserConfig::default().build().await.is_err());
+    }
 }
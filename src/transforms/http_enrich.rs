@@ -0,0 +1,406 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, LogEvent},
+    http::{Auth, HttpClient},
+    internal_events::{
+        HttpEnrichCacheHit, HttpEnrichCacheMiss, HttpEnrichEventProcessed, HttpEnrichMissingKeys,
+        HttpEnrichRequestFailed,
+    },
+    template::Template,
+    tls::{TlsOptions, TlsSettings},
+    transforms::{TaskTransform, Transform},
+};
+use futures::{
+    compat::{Compat, Compat01As03},
+    StreamExt,
+};
+use http::{Request, StatusCode};
+use hyper::Body;
+use indexmap::IndexMap;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid `url` template: {}", source))]
+    InvalidUrl {
+        source: crate::template::TemplateError,
+    },
+    #[snafu(display("`concurrency` must be greater than zero"))]
+    ZeroConcurrency,
+    #[snafu(display("`cache_size` must be greater than zero"))]
+    ZeroCacheSize,
+}
+
+#[derive(Debug, Snafu)]
+pub enum LookupError {
+    #[snafu(display("Failed to build request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Request failed: {}", source))]
+    SendRequest { source: crate::http::HttpError },
+    #[snafu(display("Request timed out"))]
+    Timeout,
+    #[snafu(display("Endpoint returned {}", status))]
+    Status { status: StatusCode },
+    #[snafu(display("Failed to read response: {}", source))]
+    ReadBody { source: hyper::Error },
+    #[snafu(display("Response is not a JSON object: {}", source))]
+    InvalidBody { source: crate::Error },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct HttpEnrichConfig {
+    /// The URL to look up, templated with event fields.
+    pub url: String,
+    pub headers: IndexMap<String, String>,
+    pub auth: Option<Auth>,
+    pub tls: Option<TlsOptions>,
+    /// The response fields merged into the event, all of them if empty.
+    pub fields: Vec<String>,
+    /// Where response fields are written, the top level if unset.
+    pub target_field: Option<String>,
+    #[derivative(Default(value = "300"))]
+    pub cache_ttl_secs: u64,
+    /// How long lookups that found nothing are cached.
+    #[derivative(Default(value = "60"))]
+    pub negative_cache_ttl_secs: u64,
+    /// The maximum number of cached lookups.
+    #[derivative(Default(value = "10000"))]
+    pub cache_size: usize,
+    /// The maximum number of requests in flight.
+    #[derivative(Default(value = "10"))]
+    pub concurrency: usize,
+    #[derivative(Default(value = "5"))]
+    pub timeout_secs: u64,
+}
+
+inventory::submit! {
+    TransformDescription::new::<HttpEnrichConfig>("http_enrich")
+}
+
+impl GenerateConfig for HttpEnrichConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"url = "http://cmdb.internal/hosts/{{ host }}""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "http_enrich")]
+impl TransformConfig for HttpEnrichConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        if self.concurrency == 0 {
+            return Err(BuildError::ZeroConcurrency.into());
+        }
+        if self.cache_size == 0 {
+            return Err(BuildError::ZeroCacheSize.into());
+        }
+        let url = Template::try_from(self.url.as_str()).context(InvalidUrl)?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+
+        Ok(Transform::task(HttpEnrich {
+            lookup: Arc::new(Lookup {
+                client: HttpClient::new(tls)?,
+                headers: self.headers.clone(),
+                auth: self.auth.clone(),
+                timeout: Duration::from_secs(self.timeout_secs),
+                cache: Mutex::new(LruCache::new(self.cache_size)),
+                cache_ttl: Duration::from_secs(self.cache_ttl_secs),
+                negative_cache_ttl: Duration::from_secs(self.negative_cache_ttl_secs),
+            }),
+            url,
+            fields: self.fields.clone(),
+            target_field: self.target_field.clone(),
+            concurrency: self.concurrency,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "http_enrich"
+    }
+}
+
+/// A cached response, or `None` if the endpoint returned 404.
+#[derive(Clone)]
+struct CacheEntry {
+    response: Option<Arc<LogEvent>>,
+    expires_at: Instant,
+}
+
+struct Lookup {
+    client: HttpClient,
+    headers: IndexMap<String, String>,
+    auth: Option<Auth>,
+    timeout: Duration,
+    cache: Mutex<LruCache<String, CacheEntry>>,
+    cache_ttl: Duration,
+    negative_cache_ttl: Duration,
+}
+
+impl Lookup {
+    async fn get(&self, url: String) -> Result<Option<Arc<LogEvent>>, LookupError> {
+        let cached = self.cache.lock().unwrap().get(&url).cloned();
+        match cached {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                emit!(HttpEnrichCacheHit);
+                return Ok(entry.response);
+            }
+            _ => emit!(HttpEnrichCacheMiss),
+        }
+
+        let response = self.fetch(&url).await?;
+        let ttl = match response {
+            Some(_) => self.cache_ttl,
+            None => self.negative_cache_ttl,
+        };
+        let entry = CacheEntry {
+            response,
+            expires_at: Instant::now() + ttl,
+        };
+        self.cache.lock().unwrap().put(url, entry.clone());
+        Ok(entry.response)
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Option<Arc<LogEvent>>, LookupError> {
+        let mut request = Request::get(url);
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let mut request = request.body(Body::empty()).context(BuildRequest)?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = tokio::time::timeout(self.timeout, self.client.send(request))
+            .await
+            .map_err(|_| LookupError::Timeout)?
+            .context(SendRequest)?;
+        match response.status() {
+            StatusCode::OK => (),
+            StatusCode::NOT_FOUND => return Ok(None),
+            status => return Err(LookupError::Status { status }),
+        }
+
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(ReadBody)?;
+        let json = serde_json::from_slice::<serde_json::Value>(&body)
+            .map_err(Into::into)
+            .and_then(Event::try_from)
+            .context(InvalidBody)?;
+        Ok(Some(Arc::new(json.into_log())))
+    }
+}
+
+pub struct HttpEnrich {
+    lookup: Arc<Lookup>,
+    url: Template,
+    fields: Vec<String>,
+    target_field: Option<String>,
+    concurrency: usize,
+}
+
+impl HttpEnrich {
+    fn target(&self, field: &str) -> String {
+        match &self.target_field {
+            Some(target) => format!("{}.{}", target, field),
+            None => field.to_owned(),
+        }
+    }
+
+    fn merge(&self, event: &mut Event, response: &LogEvent) {
+        let log = event.as_mut_log();
+        if self.fields.is_empty() {
+            for (key, value) in response.as_map() {
+                log.insert(self.target(key), value.clone());
+            }
+        } else {
+            for field in &self.fields {
+                if let Some(value) = response.get(field) {
+                    log.insert(self.target(field), value.clone());
+                }
+            }
+        }
+    }
+}
+
+impl TaskTransform for HttpEnrich {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>
+    where
+        Self: 'static,
+    {
+        let me = Arc::new(*self);
+        let concurrency = me.concurrency;
+
+        let stream = Compat01As03::new(input_rx)
+            .map(move |event| {
+                let mut event = event.expect("Unexpected error reading channel");
+                let me = Arc::clone(&me);
+                async move {
+                    emit!(HttpEnrichEventProcessed);
+
+                    let url = match me.url.render_string(&event) {
+                        Ok(url) => url,
+                        Err(missing_keys) => {
+                            emit!(HttpEnrichMissingKeys {
+                                keys: &missing_keys
+                            });
+                            return event;
+                        }
+                    };
+                    // Events are passed through unchanged when the lookup fails.
+                    match me.lookup.get(url).await {
+                        Ok(Some(response)) => me.merge(&mut event, &response),
+                        Ok(None) => (),
+                        Err(error) => emit!(HttpEnrichRequestFailed { error }),
+                    }
+                    event
+                }
+            })
+            .buffered(concurrency);
+
+        // Needed for compat
+        let try_stream = Box::pin(stream.map::<Result<Event, ()>, _>(Ok));
+
+        Box::new(Compat::new(try_stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::Value, test_util::next_addr};
+    use futures::compat::Stream01CompatExt;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use std::{
+        convert::Infallible,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<HttpEnrichConfig>();
+    }
+
+    /// Knows the user with id 1, counting the requests.
+    async fn user_service() -> (String, Arc<AtomicUsize>) {
+        let addr = next_addr();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let make_svc = make_service_fn(move |_| {
+            let counter = Arc::clone(&counter);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        let response = if req.uri().path() == "/users/1" {
+                            let body = serde_json::json!({
+                                "name": "Jane",
+                                "team": {"name": "payments", "oncall": "bob"},
+                            });
+                            Response::new(Body::from(body.to_string()))
+                        } else {
+                            let mut response = Response::new(Body::empty());
+                            *response.status_mut() = StatusCode::NOT_FOUND;
+                            response
+                        };
+                        Ok::<_, Infallible>(response)
+                    }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(make_svc));
+        (format!("http://{}", addr), requests)
+    }
+
+    async fn run(config: HttpEnrichConfig, user_ids: &[i64]) -> Vec<Event> {
+        let transform = config.build().await.unwrap().into_task();
+        let events = user_ids
+            .iter()
+            .map(|&id| {
+                let mut event = Event::from("login");
+                event.as_mut_log().insert("user_id", id);
+                event
+            })
+            .collect::<Vec<_>>();
+        let in_stream = Box::new(futures01::stream::iter_ok(events));
+        transform
+            .transform(in_stream)
+            .compat()
+            .map(Result::unwrap)
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn merges_selected_fields_and_caches() {
+        let (endpoint, requests) = user_service().await;
+        let config = HttpEnrichConfig {
+            url: format!("{}/users/{{{{ user_id }}}}", endpoint),
+            fields: vec!["team.name".into()],
+            target_field: Some("user".into()),
+            concurrency: 1,
+            ..Default::default()
+        };
+
+        let output = run(config, &[1, 2, 1, 2]).await;
+
+        assert_eq!(output.len(), 4);
+        assert_eq!(output[0].as_log()["user.team.name"], "payments".into());
+        assert!(output[0].as_log().get("user.name").is_none());
+        assert!(output[1].as_log().get("user").is_none());
+        assert_eq!(output[2].as_log()["user.team.name"], "payments".into());
+        // The second lookups of both users, found or not, are cached.
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn merges_whole_response() {
+        let (endpoint, _) = user_service().await;
+        let config = HttpEnrichConfig {
+            url: format!("{}/users/{{{{ user_id }}}}", endpoint),
+            ..Default::default()
+        };
+
+        let output = run(config, &[1]).await;
+
+        assert_eq!(output[0].as_log()["name"], "Jane".into());
+        assert_eq!(output[0].as_log()["team.oncall"], "bob".into());
+        assert_eq!(output[0].as_log()["user_id"], Value::Integer(1));
+    }
+
+    #[tokio::test]
+    async fn passes_through_on_failure() {
+        let config = HttpEnrichConfig {
+            url: "http://127.0.0.1:1/users/{{ user_id }}".into(),
+            timeout_secs: 1,
+            ..Default::default()
+        };
+
+        let output = run(config, &[1]).await;
+
+        assert_eq!(output.len(), 1);
+        assert!(output[0].as_log().get("name").is_none());
+    }
+}
@@ -0,0 +1,346 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{IpClassifyEventProcessed, IpClassifyFieldMissing, IpClassifyInvalidAddress},
+    transforms::{FunctionTransform, Transform},
+};
+use cidr_utils::cidr::IpCidr;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{net::IpAddr, path::PathBuf, str::FromStr};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Set {:?} has an invalid CIDR {:?}: {}", set, cidr, message))]
+    InvalidCidr {
+        set: String,
+        cidr: String,
+        message: String,
+    },
+    #[snafu(display("Could not read set {:?} from {:?}: {}", set, path, source))]
+    ReadFile {
+        set: String,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Set {:?} is empty", set))]
+    EmptySet { set: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct IpClassifyConfig {
+    /// The field holding the IP address.
+    pub field: String,
+    /// The field the names of the matching sets are written to.
+    #[serde(default = "default_target_field")]
+    pub target_field: String,
+    /// Named sets of CIDRs, checked in order.
+    pub sets: IndexMap<String, CidrSetConfig>,
+    /// Written to `target_field` when no set matches.
+    #[serde(default)]
+    pub default_class: Option<String>,
+    /// Write the names of all matching sets, as an array, rather than only
+    /// the first.
+    #[serde(default)]
+    pub all_matches: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct CidrSetConfig {
+    pub cidrs: Vec<String>,
+    /// A file with a CIDR or address per line. Blank lines and lines
+    /// starting with `#` are ignored.
+    pub file: Option<PathBuf>,
+    pub builtin: Vec<BuiltinSet>,
+}
+
+/// Well-known address ranges.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinSet {
+    /// RFC 1918 and unique local IPv6 addresses.
+    Private,
+    Loopback,
+    LinkLocal,
+    Multicast,
+    /// RFC 6598 shared address space, used for carrier-grade NAT.
+    SharedAddressSpace,
+    /// The ranges reserved for documentation by RFC 5737 and RFC 3849.
+    Documentation,
+}
+
+impl BuiltinSet {
+    fn cidrs(self) -> &'static [&'static str] {
+        match self {
+            BuiltinSet::Private => &["10.0.0.0/8", "172.16.0.0/12", "192.168.0.0/16", "fc00::/7"],
+            BuiltinSet::Loopback => &["127.0.0.0/8", "::1/128"],
+            BuiltinSet::LinkLocal => &["169.254.0.0/16", "fe80::/10"],
+            BuiltinSet::Multicast => &["224.0.0.0/4", "ff00::/8"],
+            BuiltinSet::SharedAddressSpace => &["100.64.0.0/10"],
+            BuiltinSet::Documentation => &[
+                "192.0.2.0/24",
+                "198.51.100.0/24",
+                "203.0.113.0/24",
+                "2001:db8::/32",
+            ],
+        }
+    }
+}
+
+fn default_target_field() -> String {
+    "ip_class".into()
+}
+
+inventory::submit! {
+    TransformDescription::new::<IpClassifyConfig>("ip_classify")
+}
+
+impl GenerateConfig for IpClassifyConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"field = "src_ip"
+            default_class = "public"
+
+            [sets.private]
+            builtin = ["private", "loopback"]
+
+            [sets.corporate]
+            cidrs = ["203.0.113.0/24"]"#,
+        )
+        .unwrap()
+    }
+}
+
+impl CidrSetConfig {
+    fn build(&self, set: &str) -> Result<Vec<IpCidr>, BuildError> {
+        let mut cidrs: Vec<String> = self
+            .builtin
+            .iter()
+            .flat_map(|builtin| builtin.cidrs().iter().map(|&cidr| cidr.to_owned()))
+            .collect();
+        cidrs.extend(self.cidrs.iter().cloned());
+        if let Some(path) = &self.file {
+            let contents = std::fs::read_to_string(path).context(ReadFile { set, path })?;
+            cidrs.extend(
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(Into::into),
+            );
+        }
+        if cidrs.is_empty() {
+            return Err(BuildError::EmptySet { set: set.into() });
+        }
+
+        cidrs
+            .into_iter()
+            .map(|cidr| {
+                IpCidr::from_str(&cidr).map_err(|error| BuildError::InvalidCidr {
+                    set: set.into(),
+                    message: error.to_string(),
+                    cidr,
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "ip_classify")]
+impl TransformConfig for IpClassifyConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let sets = self
+            .sets
+            .iter()
+            .map(|(name, set)| Ok((name.clone(), set.build(name)?)))
+            .collect::<Result<_, BuildError>>()?;
+
+        Ok(Transform::function(IpClassify {
+            field: self.field.clone(),
+            target_field: self.target_field.clone(),
+            sets,
+            default_class: self.default_class.clone(),
+            all_matches: self.all_matches,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "ip_classify"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IpClassify {
+    field: String,
+    target_field: String,
+    sets: Vec<(String, Vec<IpCidr>)>,
+    default_class: Option<String>,
+    all_matches: bool,
+}
+
+impl IpClassify {
+    fn classify(&self, address: IpAddr) -> Option<Value> {
+        let mut matching = self
+            .sets
+            .iter()
+            .filter(|(_, cidrs)| cidrs.iter().any(|cidr| cidr.contains(address)))
+            .map(|(name, _)| Value::from(name.as_str()));
+
+        if self.all_matches {
+            let matching: Vec<Value> = matching.collect();
+            if matching.is_empty() {
+                self.default_class
+                    .as_deref()
+                    .map(|class| Value::Array(vec![class.into()]))
+            } else {
+                Some(Value::Array(matching))
+            }
+        } else {
+            matching
+                .next()
+                .or_else(|| self.default_class.as_deref().map(Into::into))
+        }
+    }
+}
+
+impl FunctionTransform for IpClassify {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_mut_log();
+        let address = match log.get(&self.field) {
+            Some(value) => value.to_string_lossy(),
+            None => {
+                emit!(IpClassifyFieldMissing { field: &self.field });
+                output.push(event);
+                return;
+            }
+        };
+
+        match IpAddr::from_str(address.trim()) {
+            Ok(address) => {
+                if let Some(class) = self.classify(address) {
+                    log.insert(&self.target_field, class);
+                }
+            }
+            Err(_) => emit!(IpClassifyInvalidAddress { address: &address }),
+        }
+
+        emit!(IpClassifyEventProcessed);
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<IpClassifyConfig>();
+    }
+
+    fn event(address: &str) -> Event {
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("src_ip", address);
+        event
+    }
+
+    async fn classify(config: &str, address: &str) -> Option<Value> {
+        let config = toml::from_str::<IpClassifyConfig>(config).unwrap();
+        let mut transform = config.build().await.unwrap();
+        let event = transform
+            .as_function()
+            .transform_one(event(address))
+            .unwrap();
+        event.as_log().get("ip_class").cloned()
+    }
+
+    const CONFIG: &str = r#"
+        field = "src_ip"
+        default_class = "public"
+
+        [sets.private]
+        builtin = ["private", "loopback"]
+
+        [sets.corporate]
+        cidrs = ["10.20.0.0/16", "2001:db8:1::/48"]
+    "#;
+
+    #[tokio::test]
+    async fn classifies_first_match() {
+        assert_eq!(
+            classify(CONFIG, "192.168.1.7").await,
+            Some("private".into())
+        );
+        assert_eq!(classify(CONFIG, "::1").await, Some("private".into()));
+        assert_eq!(classify(CONFIG, "10.20.3.4").await, Some("private".into()));
+        assert_eq!(
+            classify(CONFIG, "2001:db8:1::5").await,
+            Some("corporate".into())
+        );
+        assert_eq!(classify(CONFIG, "8.8.8.8").await, Some("public".into()));
+    }
+
+    #[tokio::test]
+    async fn classifies_all_matches() {
+        let config = format!("all_matches = true\n{}", CONFIG);
+        assert_eq!(
+            classify(&config, "10.20.3.4").await,
+            Some(Value::Array(vec!["private".into(), "corporate".into()]))
+        );
+        assert_eq!(
+            classify(&config, "8.8.8.8").await,
+            Some(Value::Array(vec!["public".into()]))
+        );
+    }
+
+    #[tokio::test]
+    async fn leaves_invalid_addresses_unclassified() {
+        assert_eq!(classify(CONFIG, "not an ip").await, None);
+    }
+
+    #[tokio::test]
+    async fn loads_sets_from_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "# Office ranges\n198.51.100.0/24\n\n198.18.0.1").unwrap();
+        let config = format!(
+            r#"
+            field = "src_ip"
+            sets.office.file = {:?}
+            "#,
+            file.path()
+        );
+
+        assert_eq!(
+            classify(&config, "198.51.100.9").await,
+            Some("office".into())
+        );
+        assert_eq!(classify(&config, "198.18.0.1").await, Some("office".into()));
+        assert_eq!(classify(&config, "198.18.0.2").await, None);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_cidrs() {
+        let config = toml::from_str::<IpClassifyConfig>(
+            r#"
+            field = "src_ip"
+            sets.office.cidrs = ["10.0.0.0/33"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.build().await.is_err());
+    }
+}
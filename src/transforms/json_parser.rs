@@ -2,7 +2,7 @@ use crate::{
     config::{log_schema, DataType, TransformConfig, TransformDescription},
     event::Event,
     internal_events::{JsonParserEventProcessed, JsonParserFailedParse, JsonParserTargetExists},
-    transforms::{FunctionTransform, Transform},
+    transforms::{FunctionTransform, Transform, TransformOutputs, DROPPED},
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -17,6 +17,9 @@ pub struct JsonParserConfig {
     pub drop_field: bool,
     pub target_field: Option<String>,
     pub overwrite_target: Option<bool>,
+    /// Send events that fail to parse to the `dropped` output instead of
+    /// passing or dropping them.
+    pub reroute_dropped: bool,
 }
 
 inventory::submit! {
@@ -43,6 +46,14 @@ impl TransformConfig for JsonParserConfig {
     fn transform_type(&self) -> &'static str {
         "json_parser"
     }
+
+    fn named_outputs(&self) -> Vec<String> {
+        if self.reroute_dropped {
+            vec![DROPPED.to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -52,6 +63,7 @@ pub struct JsonParser {
     drop_field: bool,
     target_field: Option<String>,
     overwrite_target: bool,
+    reroute_dropped: bool,
 }
 
 impl From<JsonParserConfig> for JsonParser {
@@ -66,69 +78,94 @@ impl From<JsonParserConfig> for JsonParser {
             drop_field: config.drop_field,
             target_field: config.target_field,
             overwrite_target: config.overwrite_target.unwrap_or(false),
+            reroute_dropped: config.reroute_dropped,
         }
     }
 }
 
-impl FunctionTransform for JsonParser {
-    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+impl JsonParser {
+    /// Parses the event's field, handing the event back with the reason
+    /// when it doesn't hold a JSON object.
+    fn parse(&mut self, mut event: Event) -> Result<Event, (Event, String)> {
         let log = event.as_mut_log();
         let value = log.get(&self.field);
 
         emit!(JsonParserEventProcessed);
 
-        let parsed = value
-            .and_then(|value| {
+        let drop_invalid = self.drop_invalid || self.reroute_dropped;
+        let parsed = match value {
+            Some(value) => {
                 let to_parse = value.as_bytes();
-                serde_json::from_slice::<Value>(to_parse.as_ref())
-                    .map_err(|error| {
-                        emit!(JsonParserFailedParse {
-                            field: &self.field,
-                            value: value.to_string_lossy().as_str(),
-                            error,
-                            drop_invalid: self.drop_invalid,
-                        })
-                    })
-                    .ok()
-            })
-            .and_then(|value| {
-                if let Value::Object(object) = value {
-                    Some(object)
-                } else {
-                    None
-                }
-            });
+                serde_json::from_slice::<Value>(to_parse.as_ref()).map_err(|error| {
+                    let message = error.to_string();
+                    emit!(JsonParserFailedParse {
+                        field: &self.field,
+                        value: value.to_string_lossy().as_str(),
+                        error,
+                        drop_invalid,
+                    });
+                    message
+                })
+            }
+            None => Err(format!("field {:?} is missing", self.field)),
+        }
+        .and_then(|value| match value {
+            Value::Object(object) => Ok(object),
+            _ => Err("the parsed JSON is not an object".to_owned()),
+        });
 
-        if let Some(object) = parsed {
-            match self.target_field {
-                Some(ref target_field) => {
-                    let contains_target = log.contains(&target_field);
+        let object = match parsed {
+            Ok(object) => object,
+            Err(message) => return Err((event, message)),
+        };
 
-                    if contains_target && !self.overwrite_target {
-                        emit!(JsonParserTargetExists { target_field })
-                    } else {
-                        if self.drop_field {
-                            log.remove(&self.field);
-                        }
+        match self.target_field {
+            Some(ref target_field) => {
+                let contains_target = log.contains(&target_field);
 
-                        log.insert(&target_field, Value::Object(object));
-                    }
-                }
-                None => {
+                if contains_target && !self.overwrite_target {
+                    emit!(JsonParserTargetExists { target_field })
+                } else {
                     if self.drop_field {
                         log.remove(&self.field);
                     }
 
-                    for (key, value) in object {
-                        log.insert_flat(key, value);
-                    }
+                    log.insert(&target_field, Value::Object(object));
+                }
+            }
+            None => {
+                if self.drop_field {
+                    log.remove(&self.field);
+                }
+
+                for (key, value) in object {
+                    log.insert_flat(key, value);
                 }
             }
-        } else if self.drop_invalid {
-            return;
         }
 
-        output.push(event);
+        Ok(event)
+    }
+}
+
+impl FunctionTransform for JsonParser {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        match self.parse(event) {
+            Ok(event) => output.push(event),
+            Err((event, _)) if !self.drop_invalid && !self.reroute_dropped => output.push(event),
+            Err(_) => (),
+        }
+    }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        match self.parse(event) {
+            Ok(event) => outputs.push(event),
+            Err((event, message)) if self.reroute_dropped => {
+                outputs.push_dropped(event, "parse_failed", message)
+            }
+            Err((event, _)) if !self.drop_invalid => outputs.push(event),
+            Err(_) => (),
+        }
     }
 }
 
@@ -370,6 +407,34 @@ mod test {
         assert!(parser.transform_one(event).is_none());
     }
 
+    #[test]
+    fn json_parser_reroutes_dropped() {
+        let config = JsonParserConfig {
+            reroute_dropped: true,
+            ..Default::default()
+        };
+        assert_eq!(config.named_outputs(), vec![DROPPED.to_owned()]);
+        let mut parser = JsonParser::from(config);
+
+        let mut outputs = TransformOutputs::new("parser");
+        parser.transform_outputs(&mut outputs, Event::from(r#"{"greeting": "hello"}"#));
+        parser.transform_outputs(&mut outputs, Event::from(r#"{"greeting": "hello","#));
+
+        assert_eq!(outputs.primary.len(), 1);
+        assert_eq!(outputs.primary[0].as_log()["greeting"], "hello".into());
+
+        let dropped = &outputs.named[DROPPED];
+        assert_eq!(dropped.len(), 1);
+        let log = dropped[0].as_log();
+        assert_eq!(
+            log[log_schema().message_key()],
+            r#"{"greeting": "hello","#.into()
+        );
+        assert_eq!(log["metadata.dropped.component"], "parser".into());
+        assert_eq!(log["metadata.dropped.reason"], "parse_failed".into());
+        assert!(log.contains("metadata.dropped.message"));
+    }
+
     #[test]
     fn json_parser_chained() {
         let mut parser1 = JsonParser::from(JsonParserConfig {
@@ -22,6 +22,9 @@ pub struct KeyValueConfig {
     pub field_split: Option<String>,
     #[derivative(Default(value = "true"))]
     pub overwrite_target: bool,
+    /// Don't split pairs on separators inside double quotes, and remove the
+    /// quotes around keys and values.
+    pub respect_quotes: bool,
     pub separator: Option<String>,
     pub target_field: Option<String>,
     pub trim_key: Option<String>,
@@ -70,6 +73,7 @@ impl TransformConfig for KeyValueConfig {
             field,
             field_split,
             overwrite_target,
+            respect_quotes: self.respect_quotes,
             separator,
             target_field,
             trim_key,
@@ -97,6 +101,7 @@ pub struct KeyValue {
     field: String,
     field_split: String,
     overwrite_target: bool,
+    respect_quotes: bool,
     separator: String,
     target_field: Option<String>,
     trim_key: Option<Vec<char>>,
@@ -114,12 +119,13 @@ impl KeyValue {
         if key.is_empty() {
             return None;
         }
+        let key = self.unquote(key);
         let key = match &self.trim_key {
             Some(trim_key) => key.trim_matches(trim_key as &[_]),
             None => key,
         };
 
-        let val = pair[split_index + field_split.len()..].trim();
+        let val = self.unquote(pair[split_index + field_split.len()..].trim());
         let val = match &self.trim_value {
             Some(trim_value) => val.trim_matches(trim_value as &[_]),
             None => val,
@@ -127,6 +133,43 @@ impl KeyValue {
 
         Some((key.to_string(), val.to_string()))
     }
+
+    fn unquote<'a>(&self, s: &'a str) -> &'a str {
+        if self.respect_quotes && s.len() >= 2 && s.starts_with('"') && s.ends_with('"') {
+            &s[1..s.len() - 1]
+        } else {
+            s
+        }
+    }
+
+    fn split_pairs<'a>(&self, value: &'a str) -> Vec<&'a str> {
+        if !self.respect_quotes {
+            return value.split(&self.separator).collect();
+        }
+
+        let mut pairs = Vec::new();
+        let mut in_quotes = false;
+        let mut escaped = false;
+        let mut start = 0;
+        for (i, c) in value.char_indices() {
+            if i < start {
+                // Still inside the separator that was just split on.
+                continue;
+            }
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_quotes = !in_quotes;
+            } else if !in_quotes && value[i..].starts_with(self.separator.as_str()) {
+                pairs.push(&value[start..i]);
+                start = i + self.separator.len();
+            }
+        }
+        pairs.push(&value[start..]);
+        pairs
+    }
 }
 
 impl FunctionTransform for KeyValue {
@@ -135,9 +178,11 @@ impl FunctionTransform for KeyValue {
         let value = log.get(&self.field).map(|s| s.to_string_lossy());
 
         if let Some(value) = &value {
-            let pairs = value
-                .split(&self.separator)
-                .filter_map(|pair| self.parse_pair(pair));
+            let pairs = self
+                .split_pairs(value)
+                .into_iter()
+                .filter_map(|pair| self.parse_pair(pair))
+                .collect::<Vec<_>>();
 
             if let Some(target_field) = &self.target_field {
                 if log.contains(target_field) {
@@ -213,6 +258,7 @@ mod tests {
             types: types.iter().map(|&(k, v)| (k.into(), v.into())).collect(),
             target_field,
             overwrite_target: false,
+            respect_quotes: false,
             trim_key,
             trim_value,
         }
@@ -390,4 +436,29 @@ mod tests {
         assert_eq!(log["bop"], Value::Bytes("beep".into()));
         assert_eq!(log["score"], Value::Integer(78));
     }
+
+    #[tokio::test]
+    async fn it_respects_quotes() {
+        let mut parser = KeyValueConfig {
+            respect_quotes: true,
+            ..Default::default()
+        }
+        .build()
+        .await
+        .unwrap();
+        let parser = parser.as_function();
+
+        let event = Event::from(
+            r#"date=2020-10-14 devname="FG 100E" msg="User \"admin\" logged in" "user id"=7"#,
+        );
+        let log = parser.transform_one(event).unwrap().into_log();
+
+        assert_eq!(log["date"], Value::Bytes("2020-10-14".into()));
+        assert_eq!(log["devname"], Value::Bytes("FG 100E".into()));
+        assert_eq!(
+            log["msg"],
+            Value::Bytes(r#"User \"admin\" logged in"#.into())
+        );
+        assert_eq!(log["user id"], Value::Bytes("7".into()));
+    }
 }
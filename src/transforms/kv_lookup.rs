@@ -0,0 +1,594 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{
+        KvLookupCacheHit, KvLookupCacheMiss, KvLookupEventProcessed, KvLookupFailed,
+        KvLookupMissingKeys,
+    },
+    template::Template,
+    transforms::{TaskTransform, Transform},
+};
+use bytes::Bytes;
+use futures::{
+    compat::{Compat, Compat01As03},
+    stream, StreamExt,
+};
+use lru::LruCache;
+use redis::aio::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid `key` template: {}", source))]
+    InvalidKey {
+        source: crate::template::TemplateError,
+    },
+    #[snafu(display("Invalid Redis endpoint: {}", source))]
+    InvalidRedisEndpoint { source: redis::RedisError },
+    #[snafu(display("memcached only supports the `string` and `json` value types"))]
+    MemcachedHash,
+    #[snafu(display("`concurrency` must be greater than zero"))]
+    ZeroConcurrency,
+    #[snafu(display("`cache_size` must be greater than zero"))]
+    ZeroCacheSize,
+}
+
+#[derive(Debug, Snafu)]
+pub enum LookupError {
+    #[snafu(display("Lookup timed out"))]
+    Timeout,
+    #[snafu(display("Redis error: {}", source))]
+    Redis { source: redis::RedisError },
+    #[snafu(display("memcached connection error: {}", source))]
+    MemcachedIo { source: std::io::Error },
+    #[snafu(display("memcached returned an unexpected response {:?}", response))]
+    MemcachedResponse { response: String },
+    #[snafu(display("Key {:?} is not a valid memcached key", key))]
+    MemcachedKey { key: String },
+    #[snafu(display("Value is not a JSON object: {}", source))]
+    InvalidJson { source: crate::Error },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    Redis,
+    Memcached,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+    /// The value is written as a string.
+    String,
+    /// The value is a JSON object, written as a map.
+    Json,
+    /// A Redis hash, written as a map.
+    Hash,
+}
+
+impl Default for ValueType {
+    fn default() -> Self {
+        ValueType::String
+    }
+}
+
+/// What happens to events whose key isn't found.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MissPolicy {
+    Pass,
+    Drop,
+    /// Set `mark_field` to `true`.
+    Mark,
+}
+
+impl Default for MissPolicy {
+    fn default() -> Self {
+        MissPolicy::Pass
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct KvLookupConfig {
+    pub backend: Backend,
+    /// A `redis://` URL, or the `host:port` of a memcached server.
+    pub endpoint: String,
+    /// The key looked up, templated with event fields.
+    pub key: String,
+    /// Where the value is written.
+    pub target_field: String,
+    #[serde(default)]
+    pub value_type: ValueType,
+    #[serde(default)]
+    pub miss_policy: MissPolicy,
+    #[serde(default = "default_mark_field")]
+    pub mark_field: String,
+    #[serde(default = "default_cache_size")]
+    pub cache_size: usize,
+    /// How long values, and misses, are cached locally.
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    /// The maximum number of lookups in flight.
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_mark_field() -> String {
+    "lookup_miss".into()
+}
+
+const fn default_cache_size() -> usize {
+    10000
+}
+
+const fn default_cache_ttl_secs() -> u64 {
+    60
+}
+
+const fn default_concurrency() -> usize {
+    10
+}
+
+const fn default_timeout_secs() -> u64 {
+    1
+}
+
+inventory::submit! {
+    TransformDescription::new::<KvLookupConfig>("kv_lookup")
+}
+
+impl GenerateConfig for KvLookupConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"backend = "redis"
+endpoint = "redis://127.0.0.1:6379"
+key = "user:{{ user_id }}"
+target_field = "user""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "kv_lookup")]
+impl TransformConfig for KvLookupConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        if self.concurrency == 0 {
+            return Err(BuildError::ZeroConcurrency.into());
+        }
+        if self.cache_size == 0 {
+            return Err(BuildError::ZeroCacheSize.into());
+        }
+        let client = match self.backend {
+            Backend::Redis => Client::Redis {
+                client: redis::Client::open(self.endpoint.as_str())
+                    .context(InvalidRedisEndpoint)?,
+                connection: tokio::sync::Mutex::new(None),
+            },
+            Backend::Memcached if self.value_type == ValueType::Hash => {
+                return Err(BuildError::MemcachedHash.into())
+            }
+            Backend::Memcached => Client::Memcached {
+                address: self.endpoint.clone(),
+                connection: tokio::sync::Mutex::new(None),
+            },
+        };
+
+        Ok(Transform::task(KvLookup {
+            client: Arc::new(client),
+            cache: Arc::new(Mutex::new(LruCache::new(self.cache_size))),
+            cache_ttl: Duration::from_secs(self.cache_ttl_secs),
+            key: Template::try_from(self.key.as_str()).context(InvalidKey)?,
+            target_field: self.target_field.clone(),
+            value_type: self.value_type,
+            miss_policy: self.miss_policy,
+            mark_field: self.mark_field.clone(),
+            concurrency: self.concurrency,
+            timeout: Duration::from_secs(self.timeout_secs),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "kv_lookup"
+    }
+}
+
+/// A client connecting on first use, and again after errors.
+enum Client {
+    Redis {
+        client: redis::Client,
+        connection: tokio::sync::Mutex<Option<ConnectionManager>>,
+    },
+    Memcached {
+        address: String,
+        connection: tokio::sync::Mutex<Option<BufReader<TcpStream>>>,
+    },
+}
+
+impl Client {
+    async fn get(&self, key: &str, value_type: ValueType) -> Result<Option<Value>, LookupError> {
+        match self {
+            Client::Redis { client, connection } => {
+                // The connection manager reconnects by itself once created.
+                let mut manager = {
+                    let mut connection = connection.lock().await;
+                    match &*connection {
+                        Some(manager) => manager.clone(),
+                        None => {
+                            let manager = ConnectionManager::new(client.clone())
+                                .await
+                                .context(Redis)?;
+                            *connection = Some(manager.clone());
+                            manager
+                        }
+                    }
+                };
+
+                if value_type == ValueType::Hash {
+                    let hash = redis::cmd("HGETALL")
+                        .arg(key)
+                        .query_async::<_, HashMap<String, String>>(&mut manager)
+                        .await
+                        .context(Redis)?;
+                    // Redis doesn't tell missing hashes from empty ones.
+                    return Ok(if hash.is_empty() {
+                        None
+                    } else {
+                        Some(Value::Map(
+                            hash.into_iter()
+                                .map(|(field, value)| (field, Value::from(value)))
+                                .collect(),
+                        ))
+                    });
+                }
+
+                let value = redis::cmd("GET")
+                    .arg(key)
+                    .query_async::<_, Option<Vec<u8>>>(&mut manager)
+                    .await
+                    .context(Redis)?;
+                value.map(|value| decode(value, value_type)).transpose()
+            }
+            Client::Memcached {
+                address,
+                connection,
+            } => {
+                let mut connection = connection.lock().await;
+                if connection.is_none() {
+                    let stream = TcpStream::connect(address.as_str())
+                        .await
+                        .context(MemcachedIo)?;
+                    *connection = Some(BufReader::new(stream));
+                }
+
+                let result = memcached_get(connection.as_mut().unwrap(), key).await;
+                if let Err(LookupError::MemcachedIo { .. })
+                | Err(LookupError::MemcachedResponse { .. }) = result
+                {
+                    // The connection may be out of step with its responses.
+                    *connection = None;
+                }
+                result?.map(|value| decode(value, value_type)).transpose()
+            }
+        }
+    }
+}
+
+/// Gets a key with the memcached text protocol.
+async fn memcached_get(
+    connection: &mut BufReader<TcpStream>,
+    key: &str,
+) -> Result<Option<Vec<u8>>, LookupError> {
+    if key.is_empty()
+        || key.len() > 250
+        || key
+            .bytes()
+            .any(|byte| byte.is_ascii_whitespace() || byte.is_ascii_control())
+    {
+        return Err(LookupError::MemcachedKey { key: key.into() });
+    }
+
+    let request = format!("get {}\r\n", key);
+    connection
+        .get_mut()
+        .write_all(request.as_bytes())
+        .await
+        .context(MemcachedIo)?;
+
+    let mut line = String::new();
+    connection.read_line(&mut line).await.context(MemcachedIo)?;
+    if line == "END\r\n" {
+        return Ok(None);
+    }
+
+    // VALUE <key> <flags> <bytes>
+    let length = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["VALUE", _, _, length, ..] => length.parse::<usize>().ok(),
+        _ => None,
+    };
+    let length = length.ok_or_else(|| LookupError::MemcachedResponse {
+        response: line.clone(),
+    })?;
+
+    let mut value = vec![0; length + 2];
+    connection
+        .read_exact(&mut value)
+        .await
+        .context(MemcachedIo)?;
+    value.truncate(length);
+
+    line.clear();
+    connection.read_line(&mut line).await.context(MemcachedIo)?;
+    if line != "END\r\n" {
+        return Err(LookupError::MemcachedResponse { response: line });
+    }
+
+    Ok(Some(value))
+}
+
+fn decode(value: Vec<u8>, value_type: ValueType) -> Result<Value, LookupError> {
+    match value_type {
+        ValueType::Json => {
+            let value = serde_json::from_slice::<serde_json::Value>(&value)
+                .map_err(crate::Error::from)
+                .and_then(Event::try_from)
+                .context(InvalidJson)?;
+            let fields: BTreeMap<String, Value> = value.into_log().into();
+            Ok(Value::Map(fields))
+        }
+        _ => Ok(Value::Bytes(Bytes::from(value))),
+    }
+}
+
+/// A cached value, or `None` for a miss.
+#[derive(Clone)]
+struct CacheEntry {
+    value: Option<Value>,
+    expires_at: Instant,
+}
+
+pub struct KvLookup {
+    client: Arc<Client>,
+    cache: Arc<Mutex<LruCache<String, CacheEntry>>>,
+    cache_ttl: Duration,
+    key: Template,
+    target_field: String,
+    value_type: ValueType,
+    miss_policy: MissPolicy,
+    mark_field: String,
+    concurrency: usize,
+    timeout: Duration,
+}
+
+impl KvLookup {
+    async fn lookup(&self, key: String) -> Result<Option<Value>, LookupError> {
+        let cached = self.cache.lock().unwrap().get(&key).cloned();
+        match cached {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                emit!(KvLookupCacheHit);
+                return Ok(entry.value);
+            }
+            _ => emit!(KvLookupCacheMiss),
+        }
+
+        let value = tokio::time::timeout(self.timeout, self.client.get(&key, self.value_type))
+            .await
+            .map_err(|_| LookupError::Timeout)??;
+        let entry = CacheEntry {
+            value,
+            expires_at: Instant::now() + self.cache_ttl,
+        };
+        self.cache.lock().unwrap().put(key, entry.clone());
+        Ok(entry.value)
+    }
+
+    async fn enrich(&self, mut event: Event) -> Option<Event> {
+        emit!(KvLookupEventProcessed);
+
+        let key = match self.key.render_string(&event) {
+            Ok(key) => key,
+            Err(missing_keys) => {
+                emit!(KvLookupMissingKeys {
+                    keys: &missing_keys
+                });
+                return Some(event);
+            }
+        };
+
+        // Failed lookups aren't misses, so events are passed through.
+        match self.lookup(key).await {
+            Ok(Some(value)) => {
+                event.as_mut_log().insert(&self.target_field, value);
+            }
+            Ok(None) => match self.miss_policy {
+                MissPolicy::Pass => (),
+                MissPolicy::Drop => return None,
+                MissPolicy::Mark => {
+                    event.as_mut_log().insert(&self.mark_field, true);
+                }
+            },
+            Err(error) => emit!(KvLookupFailed { error }),
+        }
+        Some(event)
+    }
+}
+
+impl TaskTransform for KvLookup {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>
+    where
+        Self: 'static,
+    {
+        let me = Arc::new(*self);
+        let concurrency = me.concurrency;
+
+        let stream = Compat01As03::new(input_rx)
+            .map(move |event| {
+                let event = event.expect("Unexpected error reading channel");
+                let me = Arc::clone(&me);
+                async move { me.enrich(event).await }
+            })
+            .buffered(concurrency)
+            .filter_map(|event| async move { event });
+
+        // Needed for compat
+        let try_stream = Box::pin(stream.map::<Result<Event, ()>, _>(Ok));
+
+        Box::new(Compat::new(try_stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::next_addr;
+    use futures::compat::Stream01CompatExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<KvLookupConfig>();
+    }
+
+    /// A memcached server knowing `user:1` and `user:2`, counting the gets.
+    async fn memcached() -> (String, Arc<AtomicUsize>) {
+        let addr = next_addr();
+        let mut listener = TcpListener::bind(addr).await.unwrap();
+        let gets = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&gets);
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                let counter = Arc::clone(&counter);
+                tokio::spawn(async move {
+                    let mut socket = BufReader::new(socket);
+                    let mut line = String::new();
+                    while socket.read_line(&mut line).await.unwrap() > 0 {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        let value = match line.trim_end() {
+                            "get user:1" => Some(r#"{"name":"Jane","team":"payments"}"#),
+                            "get user:2" => Some("Bob"),
+                            _ => None,
+                        };
+                        let response = match value {
+                            Some(value) => format!(
+                                "VALUE {} 0 {}\r\n{}\r\nEND\r\n",
+                                &line[4..line.len() - 2],
+                                value.len(),
+                                value
+                            ),
+                            None => "END\r\n".into(),
+                        };
+                        socket
+                            .get_mut()
+                            .write_all(response.as_bytes())
+                            .await
+                            .unwrap();
+                        line.clear();
+                    }
+                });
+            }
+        });
+        (addr.to_string(), gets)
+    }
+
+    fn config(endpoint: String, value_type: ValueType, miss_policy: MissPolicy) -> KvLookupConfig {
+        KvLookupConfig {
+            backend: Backend::Memcached,
+            endpoint,
+            key: "user:{{ user_id }}".into(),
+            target_field: "user".into(),
+            value_type,
+            miss_policy,
+            mark_field: default_mark_field(),
+            cache_size: default_cache_size(),
+            cache_ttl_secs: default_cache_ttl_secs(),
+            // Concurrent lookups of one key would both miss the cache.
+            concurrency: 1,
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+
+    async fn run(config: KvLookupConfig, user_ids: &[i64]) -> Vec<Event> {
+        let transform = config.build().await.unwrap().into_task();
+        let events = user_ids
+            .iter()
+            .map(|&id| {
+                let mut event = Event::from("login");
+                event.as_mut_log().insert("user_id", id);
+                event
+            })
+            .collect::<Vec<_>>();
+        let in_stream = Box::new(futures01::stream::iter_ok(events));
+        transform
+            .transform(in_stream)
+            .compat()
+            .map(Result::unwrap)
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn enriches_from_memcached_with_cache() {
+        let (endpoint, gets) = memcached().await;
+        let config = config(endpoint, ValueType::Json, MissPolicy::Pass);
+
+        let output = run(config, &[1, 1, 3]).await;
+
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0].as_log()["user.name"], "Jane".into());
+        assert_eq!(output[1].as_log()["user.team"], "payments".into());
+        assert!(output[2].as_log().get("user").is_none());
+        assert_eq!(gets.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn applies_miss_policy() {
+        let (endpoint, _) = memcached().await;
+
+        let drop = config(endpoint.clone(), ValueType::String, MissPolicy::Drop);
+        let output = run(drop, &[2, 3]).await;
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["user"], "Bob".into());
+
+        let mark = config(endpoint, ValueType::String, MissPolicy::Mark);
+        let output = run(mark, &[3]).await;
+        assert_eq!(output[0].as_log()["lookup_miss"], true.into());
+    }
+
+    #[tokio::test]
+    async fn passes_through_when_unreachable() {
+        let config = config("127.0.0.1:1".into(), ValueType::String, MissPolicy::Drop);
+
+        let output = run(config, &[1]).await;
+
+        assert_eq!(output.len(), 1);
+        assert!(output[0].as_log().get("user").is_none());
+    }
+}
@@ -55,6 +55,9 @@ pub struct HistogramConfig {
     name: Option<String>,
     namespace: Option<String>,
     tags: Option<IndexMap<String, String>>,
+    /// The upper bounds of the buckets to count values into. Without them,
+    /// the values are passed on as a distribution.
+    buckets: Option<Vec<f64>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -69,6 +72,7 @@ pub struct SummaryConfig {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum MetricConfig {
     Counter(CounterConfig),
+    #[serde(alias = "distribution")]
     Histogram(HistogramConfig),
     Gauge(GaugeConfig),
     Set(SetConfig),
@@ -107,6 +111,23 @@ impl GenerateConfig for LogToMetricConfig {
 #[typetag::serde(name = "log_to_metric")]
 impl TransformConfig for LogToMetricConfig {
     async fn build(&self) -> crate::Result<Transform> {
+        for metric in &self.metrics {
+            if let MetricConfig::Histogram(HistogramConfig {
+                field,
+                buckets: Some(buckets),
+                ..
+            }) = metric
+            {
+                if buckets.is_empty() || buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+                    return Err(format!(
+                        "the buckets of the histogram for field {:?} must be increasing",
+                        field
+                    )
+                    .into());
+                }
+            }
+        }
+
         Ok(Transform::function(LogToMetric::new(self.clone())))
     }
 
@@ -251,17 +272,34 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
 
             let tags = render_tags(&hist.tags, &event)?;
 
+            let value = match &hist.buckets {
+                Some(buckets) => {
+                    // Values above the last bucket only show up in the count.
+                    let mut counts = vec![0; buckets.len()];
+                    if let Some(i) = buckets.iter().position(|bucket| value <= *bucket) {
+                        counts[i] = 1;
+                    }
+                    MetricValue::AggregatedHistogram {
+                        buckets: buckets.clone(),
+                        counts,
+                        count: 1,
+                        sum: value,
+                    }
+                }
+                None => MetricValue::Distribution {
+                    values: vec![value],
+                    sample_rates: vec![1],
+                    statistic: StatisticKind::Histogram,
+                },
+            };
+
             Ok(Metric {
                 name,
                 namespace,
                 timestamp,
                 tags,
                 kind: MetricKind::Incremental,
-                value: MetricValue::Distribution {
-                    values: vec![value],
-                    sample_rates: vec![1],
-                    statistic: StatisticKind::Histogram,
-                },
+                value,
             })
         }
         MetricConfig::Summary(summary) => {
@@ -777,6 +815,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn response_time_histogram_buckets() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            field = "response_time"
+            name = "{{ service }}_response_time"
+            buckets = [1.0, 2.5, 5.0]
+            "#,
+        );
+
+        let mut transform = LogToMetric::new(config);
+        let mut metric_for = |value: &str| {
+            let mut event = create_event("response_time", value);
+            event.as_mut_log().insert("service", "api");
+            transform.transform_one(event).unwrap().into_metric()
+        };
+
+        let metric = metric_for("2.5");
+        assert_eq!(metric.name, "api_response_time");
+        assert_eq!(
+            metric.value,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![1.0, 2.5, 5.0],
+                counts: vec![0, 1, 0],
+                count: 1,
+                sum: 2.5,
+            }
+        );
+
+        let metric = metric_for("7");
+        assert_eq!(
+            metric.value,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![1.0, 2.5, 5.0],
+                counts: vec![0, 0, 0],
+                count: 1,
+                sum: 7.0,
+            }
+        );
+    }
+
+    #[test]
+    fn response_time_distribution() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "distribution"
+            field = "response_time"
+            "#,
+        );
+
+        let event = create_event("response_time", "2.5");
+        let mut transform = LogToMetric::new(config);
+        let metric = transform.transform_one(event).unwrap();
+
+        assert_eq!(
+            metric.into_metric().value,
+            MetricValue::Distribution {
+                values: vec![2.5],
+                sample_rates: vec![1],
+                statistic: StatisticKind::Histogram
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_unordered_buckets() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            field = "response_time"
+            buckets = [5.0, 1.0]
+            "#,
+        );
+
+        assert!(config.build().await.is_err());
+    }
+
     #[test]
     fn response_time_summary() {
         let config = parse_config(
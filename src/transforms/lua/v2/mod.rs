@@ -29,6 +29,8 @@ pub enum BuildError {
     InvalidHooksShutdown { source: rlua::Error },
     #[snafu(display("Cannot evaluate Lua code defining timer handler: {}", source))]
     InvalidTimerHandler { source: rlua::Error },
+    #[snafu(display("Timer \"interval_seconds\" must be greater than zero"))]
+    InvalidTimerInterval,
 
     #[snafu(display("Runtime error in \"hooks.init\" function: {}", source))]
     RuntimeErrorHooksInit { source: rlua::Error },
@@ -166,6 +168,9 @@ impl Lua {
             }
 
             for (id, timer) in config.timers.iter().enumerate() {
+                if timer.interval_seconds == 0 {
+                    return Err(BuildError::InvalidTimerInterval.into());
+                }
                 let handler: rlua::Function<'_> = ctx
                     .load(&timer.handler)
                     .eval()
@@ -301,7 +306,7 @@ impl RuntimeTransform for Lua {
                     }
                 })
             })
-            .context(RuntimeErrorHooksInit)
+            .context(RuntimeErrorHooksShutdown)
             .map_err(|error| error!(%error, rate_limit = 30));
 
         self.attempt_gc();
@@ -837,4 +842,53 @@ mod tests {
         assert_eq!(output.len(), n);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn lua_state_flushed_on_shutdown() -> crate::Result<()> {
+        trace_init();
+
+        let transform = from_config(
+            r#"
+            hooks.init = """function (emit)
+                count = 0
+            end
+            """
+            hooks.process = """function (event, emit)
+                count = count + 1
+            end
+            """
+            hooks.shutdown = """function (emit)
+                emit({ log = { message = "seen " .. count } })
+            end
+            """
+            "#,
+        )
+        .unwrap();
+
+        let events = (0..3).map(|i| Event::from(format!("program me {}", i)));
+        let in_stream = Box::new(futures01::stream::iter_ok(events));
+        let mut out_stream = transform.transform(in_stream).compat();
+        let output = out_stream.next().await.unwrap().unwrap();
+
+        assert_eq!(output.as_log()["message"], "seen 3".into());
+        assert!(out_stream.next().await.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn lua_zero_timer_interval() {
+        let err = from_config(
+            r#"
+            hooks.process = "function (event, emit) end"
+            [[timers]]
+            interval_seconds = 0
+            handler = "function (emit) end"
+            "#,
+        )
+        .map(|_| ())
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("must be greater than zero"), err);
+    }
 }
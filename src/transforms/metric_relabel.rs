@@ -0,0 +1,436 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{metric::Metric, Event},
+    internal_events::{MetricRelabelEventDiscarded, MetricRelabelEventProcessed},
+    transforms::{FunctionTransform, Transform},
+};
+use md5::{Digest, Md5};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::convert::TryInto;
+
+/// The label standing for the metric's name, as in Prometheus.
+const NAME_LABEL: &str = "__name__";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid regex in rule {}: {}", index, source))]
+    InvalidRegex { index: usize, source: regex::Error },
+    #[snafu(display("Rule {} needs `target_label` for the {:?} action", index, action))]
+    MissingTargetLabel { index: usize, action: Action },
+    #[snafu(display("Rule {} needs a non-zero `modulus` for the hashmod action", index))]
+    MissingModulus { index: usize },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Set `target_label` to `replacement` when `regex` matches.
+    Replace,
+    /// Drop metrics `regex` doesn't match.
+    Keep,
+    /// Drop metrics `regex` matches.
+    Drop,
+    /// Copy the tags whose names `regex` matches to the names given by
+    /// `replacement`.
+    Labelmap,
+    /// Set `target_label` to a hash of the source labels modulo `modulus`.
+    Hashmod,
+}
+
+impl Default for Action {
+    fn default() -> Self {
+        Action::Replace
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct RelabelConfig {
+    pub action: Action,
+    /// The labels whose values are joined and matched against `regex`.
+    /// `__name__` is the metric's name.
+    pub source_labels: Vec<String>,
+    #[derivative(Default(value = "\";\".into()"))]
+    pub separator: String,
+    #[derivative(Default(value = "\"(.*)\".into()"))]
+    pub regex: String,
+    pub target_label: Option<String>,
+    #[derivative(Default(value = "\"$1\".into()"))]
+    pub replacement: String,
+    pub modulus: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MetricRelabelConfig {
+    /// The rules, applied in order.
+    pub relabel: Vec<RelabelConfig>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<MetricRelabelConfig>("metric_relabel")
+}
+
+impl GenerateConfig for MetricRelabelConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"[[relabel]]
+            source_labels = ["instance"]
+            regex = "([^:]+):\\d+"
+            target_label = "host""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "metric_relabel")]
+impl TransformConfig for MetricRelabelConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let rules = self
+            .relabel
+            .iter()
+            .enumerate()
+            .map(|(index, config)| Rule::new(index, config))
+            .collect::<Result<_, _>>()?;
+        Ok(Transform::function(MetricRelabel { rules }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "metric_relabel"
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    action: Action,
+    source_labels: Vec<String>,
+    separator: String,
+    regex: Regex,
+    target_label: String,
+    replacement: String,
+    modulus: u64,
+}
+
+impl Rule {
+    fn new(index: usize, config: &RelabelConfig) -> Result<Self, BuildError> {
+        // Like Prometheus, the regex has to match the whole value.
+        let regex =
+            Regex::new(&format!("^(?:{})$", config.regex)).context(InvalidRegex { index })?;
+
+        let needs_target = matches!(config.action, Action::Replace | Action::Hashmod);
+        let target_label = match &config.target_label {
+            Some(target_label) => target_label.clone(),
+            None if needs_target => {
+                return Err(BuildError::MissingTargetLabel {
+                    index,
+                    action: config.action,
+                })
+            }
+            None => String::new(),
+        };
+
+        let modulus = match config.modulus {
+            Some(modulus) if modulus > 0 => modulus,
+            _ if config.action == Action::Hashmod => {
+                return Err(BuildError::MissingModulus { index })
+            }
+            _ => 1,
+        };
+
+        Ok(Self {
+            action: config.action,
+            source_labels: config.source_labels.clone(),
+            separator: config.separator.clone(),
+            regex,
+            target_label,
+            replacement: config.replacement.clone(),
+            modulus,
+        })
+    }
+
+    /// Applies the rule, returning `false` if the metric is dropped.
+    fn apply(&self, metric: &mut Metric) -> bool {
+        let value = self
+            .source_labels
+            .iter()
+            .map(|label| get_label(metric, label).unwrap_or(""))
+            .collect::<Vec<_>>()
+            .join(&self.separator);
+
+        match self.action {
+            Action::Keep => self.regex.is_match(&value),
+            Action::Drop => !self.regex.is_match(&value),
+            Action::Replace => {
+                if let Some(captures) = self.regex.captures(&value) {
+                    let mut target = String::new();
+                    captures.expand(&self.target_label, &mut target);
+                    let mut replacement = String::new();
+                    captures.expand(&self.replacement, &mut replacement);
+                    set_label(metric, &target, replacement);
+                }
+                true
+            }
+            Action::Hashmod => {
+                let hash = Md5::digest(value.as_bytes());
+                // The low 64 bits of the hash, as Prometheus uses.
+                let hash = u64::from_be_bytes(hash[8..].try_into().unwrap());
+                set_label(
+                    metric,
+                    &self.target_label,
+                    (hash % self.modulus).to_string(),
+                );
+                true
+            }
+            Action::Labelmap => {
+                let mapped = metric
+                    .tags
+                    .iter()
+                    .flatten()
+                    .filter_map(|(name, value)| {
+                        self.regex.captures(name).map(|captures| {
+                            let mut target = String::new();
+                            captures.expand(&self.replacement, &mut target);
+                            (target, value.clone())
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                for (name, value) in mapped {
+                    set_label(metric, &name, value);
+                }
+                true
+            }
+        }
+    }
+}
+
+fn get_label<'a>(metric: &'a Metric, label: &str) -> Option<&'a str> {
+    if label == NAME_LABEL {
+        Some(&metric.name)
+    } else {
+        metric
+            .tags
+            .as_ref()
+            .and_then(|tags| tags.get(label))
+            .map(String::as_str)
+    }
+}
+
+/// Sets a label, removing it if the value is empty, as Prometheus does.
+/// Metrics always keep a name.
+fn set_label(metric: &mut Metric, label: &str, value: String) {
+    if label.is_empty() {
+        return;
+    }
+    if label == NAME_LABEL {
+        if !value.is_empty() {
+            metric.name = value;
+        }
+    } else if value.is_empty() {
+        if let Some(tags) = &mut metric.tags {
+            tags.remove(label);
+        }
+    } else {
+        metric
+            .tags
+            .get_or_insert_with(Default::default)
+            .insert(label.to_owned(), value);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MetricRelabel {
+    rules: Vec<Rule>,
+}
+
+impl FunctionTransform for MetricRelabel {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        emit!(MetricRelabelEventProcessed);
+
+        let mut metric = event.into_metric();
+        if self.rules.iter().all(|rule| rule.apply(&mut metric)) {
+            output.push(Event::Metric(metric));
+        } else {
+            emit!(MetricRelabelEventDiscarded);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::{MetricKind, MetricValue};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MetricRelabelConfig>();
+    }
+
+    fn metric(name: &str, tags: &[(&str, &str)]) -> Event {
+        Event::Metric(Metric {
+            name: name.into(),
+            namespace: None,
+            timestamp: None,
+            tags: Some(
+                tags.iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            ),
+            kind: MetricKind::Absolute,
+            value: MetricValue::Gauge { value: 1.0 },
+        })
+    }
+
+    async fn relabel(config: &str, event: Event) -> Option<Metric> {
+        let mut transform = toml::from_str::<MetricRelabelConfig>(config)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        transform
+            .as_function()
+            .transform_one(event)
+            .map(Event::into_metric)
+    }
+
+    fn tags(metric: &Metric) -> Vec<(&str, &str)> {
+        metric
+            .tags
+            .iter()
+            .flatten()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn replaces_labels() {
+        let metric = relabel(
+            r#"
+            [[relabel]]
+            source_labels = ["instance"]
+            regex = "([^:]+):\\d+"
+            target_label = "host"
+
+            [[relabel]]
+            source_labels = ["__name__", "job"]
+            separator = "_"
+            target_label = "__name__"
+            replacement = "${1}_total"
+
+            [[relabel]]
+            source_labels = ["instance"]
+            regex = "web.*"
+            target_label = "instance"
+            replacement = ""
+            "#,
+            metric(
+                "http_requests",
+                &[("instance", "web-1:9100"), ("job", "node")],
+            ),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(metric.name, "http_requests_node_total");
+        assert_eq!(tags(&metric), vec![("host", "web-1"), ("job", "node")]);
+    }
+
+    #[tokio::test]
+    async fn keeps_and_drops_metrics() {
+        let config = r#"
+            [[relabel]]
+            action = "keep"
+            source_labels = ["__name__"]
+            regex = "node_.*"
+
+            [[relabel]]
+            action = "drop"
+            source_labels = ["mountpoint"]
+            regex = "/(proc|sys).*"
+            "#;
+
+        assert!(relabel(config, metric("node_load1", &[])).await.is_some());
+        assert!(relabel(config, metric("go_goroutines", &[]))
+            .await
+            .is_none());
+        assert!(relabel(
+            config,
+            metric("node_filesystem_free", &[("mountpoint", "/proc")])
+        )
+        .await
+        .is_none());
+    }
+
+    #[tokio::test]
+    async fn maps_labels() {
+        let metric = relabel(
+            r#"
+            [[relabel]]
+            action = "labelmap"
+            regex = "__meta_kubernetes_pod_label_(.+)"
+            "#,
+            metric(
+                "up",
+                &[("__meta_kubernetes_pod_label_app", "api"), ("job", "k8s")],
+            ),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            tags(&metric),
+            vec![
+                ("__meta_kubernetes_pod_label_app", "api"),
+                ("app", "api"),
+                ("job", "k8s")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn hashes_labels() {
+        let config = r#"
+            [[relabel]]
+            action = "hashmod"
+            source_labels = ["instance"]
+            modulus = 4
+            target_label = "shard"
+            "#;
+
+        let first = relabel(config, metric("up", &[("instance", "web-1")]))
+            .await
+            .unwrap();
+        let second = relabel(config, metric("up", &[("instance", "web-1")]))
+            .await
+            .unwrap();
+
+        let shard = first.tags.as_ref().unwrap()["shard"]
+            .parse::<u64>()
+            .unwrap();
+        assert!(shard < 4);
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn rejects_incomplete_rules() {
+        for config in &[
+            "[[relabel]]\nsource_labels = [\"job\"]",
+            "[[relabel]]\naction = \"hashmod\"\ntarget_label = \"shard\"",
+            "[[relabel]]\naction = \"keep\"\nregex = \"(\"",
+        ] {
+            let config = toml::from_str::<MetricRelabelConfig>(config).unwrap();
+            assert!(config.build().await.is_err());
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::Event;
 use snafu::Snafu;
+use std::{collections::HashMap, fmt::Display};
 
 pub mod util;
 
@@ -7,30 +8,56 @@ pub mod util;
 pub mod add_fields;
 #[cfg(feature = "transforms-add_tags")]
 pub mod add_tags;
+#[cfg(feature = "transforms-anomaly")]
+pub mod anomaly;
 #[cfg(feature = "transforms-ansi_stripper")]
 pub mod ansi_stripper;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
 pub mod aws_cloudwatch_logs_subscription_parser;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
 pub mod aws_ec2_metadata;
+#[cfg(feature = "transforms-coerce")]
+pub mod coerce;
 #[cfg(feature = "transforms-coercer")]
 pub mod coercer;
 #[cfg(feature = "transforms-concat")]
 pub mod concat;
+#[cfg(feature = "transforms-csv_parser")]
+pub mod csv_parser;
+#[cfg(feature = "transforms-decode_fields")]
+pub mod decode_fields;
+#[cfg(feature = "transforms-decrypt_fields")]
+pub mod decrypt_fields;
 #[cfg(feature = "transforms-dedupe")]
 pub mod dedupe;
+#[cfg(feature = "transforms-encrypt_fields")]
+pub mod encrypt_fields;
+#[cfg(feature = "transforms-exception_fingerprint")]
+pub mod exception_fingerprint;
+#[cfg(feature = "transforms-exec")]
+pub mod exec;
+#[cfg(feature = "transforms-explode")]
+pub mod explode;
 #[cfg(feature = "transforms-field_filter")]
 pub mod field_filter;
 #[cfg(feature = "transforms-filter")]
 pub mod filter;
+#[cfg(feature = "transforms-flatten")]
+pub mod flatten;
 #[cfg(feature = "transforms-geoip")]
 pub mod geoip;
 #[cfg(feature = "transforms-grok_parser")]
 pub mod grok_parser;
+#[cfg(feature = "transforms-http_enrich")]
+pub mod http_enrich;
+#[cfg(feature = "transforms-ip_classify")]
+pub mod ip_classify;
 #[cfg(feature = "transforms-json_parser")]
 pub mod json_parser;
 #[cfg(feature = "transforms-key_value_parser")]
 pub mod key_value_parser;
+#[cfg(feature = "transforms-kv_lookup")]
+pub mod kv_lookup;
 #[cfg(feature = "transforms-log_to_metric")]
 pub mod log_to_metric;
 #[cfg(feature = "transforms-logfmt_parser")]
@@ -39,8 +66,18 @@ pub mod logfmt_parser;
 pub mod lua;
 #[cfg(feature = "transforms-merge")]
 pub mod merge;
+#[cfg(feature = "transforms-metric_relabel")]
+pub mod metric_relabel;
 #[cfg(feature = "transforms-metric_to_log")]
 pub mod metric_to_log;
+#[cfg(feature = "transforms-project")]
+pub mod project;
+#[cfg(feature = "transforms-protobuf_parser")]
+pub mod protobuf_parser;
+#[cfg(feature = "transforms-rate")]
+pub mod rate;
+#[cfg(feature = "transforms-redact")]
+pub mod redact;
 #[cfg(feature = "transforms-reduce")]
 pub mod reduce;
 #[cfg(feature = "transforms-regex_parser")]
@@ -53,18 +90,46 @@ pub mod remove_fields;
 pub mod remove_tags;
 #[cfg(feature = "transforms-rename_fields")]
 pub mod rename_fields;
+#[cfg(feature = "transforms-route")]
+pub mod route;
 #[cfg(feature = "transforms-sampler")]
 pub mod sampler;
+#[cfg(feature = "transforms-sanitize_keys")]
+pub mod sanitize_keys;
+#[cfg(feature = "transforms-schema_validate")]
+pub mod schema_validate;
+#[cfg(feature = "transforms-session")]
+pub mod session;
+#[cfg(feature = "transforms-size_guard")]
+pub mod size_guard;
 #[cfg(feature = "transforms-split")]
 pub mod split;
+#[cfg(feature = "transforms-sql")]
+pub mod sql;
 #[cfg(feature = "transforms-swimlanes")]
 pub mod swimlanes;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub mod tag_cardinality_limit;
+#[cfg(feature = "transforms-throttle")]
+pub mod throttle;
+#[cfg(feature = "transforms-timestamp")]
+pub mod timestamp;
 #[cfg(feature = "transforms-tokenizer")]
 pub mod tokenizer;
+#[cfg(feature = "transforms-top_k")]
+pub mod top_k;
+#[cfg(feature = "transforms-trace_sampler")]
+pub mod trace_sampler;
+#[cfg(feature = "transforms-unit_convert")]
+pub mod unit_convert;
+#[cfg(feature = "transforms-url_parser")]
+pub mod url_parser;
+#[cfg(feature = "transforms-user_agent_parser")]
+pub mod user_agent_parser;
 #[cfg(feature = "wasm")]
 pub mod wasm;
+#[cfg(feature = "transforms-window")]
+pub mod window;
 
 /// Transforms come in two variants. Functions, or tasks.
 ///
@@ -168,10 +233,69 @@ pub trait FunctionTransform: Send + dyn_clone::DynClone + Sync {
         self.transform(&mut buf, event);
         buf.into_iter().next()
     }
+
+    /// Like `transform`, but can also send events to the named outputs the
+    /// transform's config declares. By default everything goes to the main
+    /// output.
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        self.transform(&mut outputs.primary, event)
+    }
 }
 
 dyn_clone::clone_trait_object!(FunctionTransform);
 
+/// The named output transforms send the events they failed to process to.
+pub const DROPPED: &str = "dropped";
+
+/// The events a function transform produced, split by the output they are
+/// sent to.
+#[derive(Debug)]
+pub struct TransformOutputs {
+    component: String,
+    /// The events sent to the transform's main output.
+    pub primary: Vec<Event>,
+    /// The events sent to each of the transform's named outputs.
+    pub named: HashMap<String, Vec<Event>>,
+}
+
+impl TransformOutputs {
+    pub fn new(component: impl Into<String>) -> Self {
+        Self {
+            component: component.into(),
+            primary: Vec::with_capacity(1),
+            named: HashMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, event: Event) {
+        self.primary.push(event);
+    }
+
+    pub fn push_named(&mut self, output: &str, event: Event) {
+        self.named.entry(output.to_owned()).or_default().push(event);
+    }
+
+    /// Sends an event the transform failed to process to its `dropped`
+    /// output, recording which component dropped it and why.
+    pub fn push_dropped(&mut self, mut event: Event, reason: &str, message: impl Display) {
+        let message = message.to_string();
+        match &mut event {
+            Event::Log(log) => {
+                log.insert("metadata.dropped.component", self.component.clone());
+                log.insert("metadata.dropped.reason", reason.to_owned());
+                log.insert("metadata.dropped.message", message);
+            }
+            Event::Metric(metric) => {
+                let tags = metric.tags.get_or_insert_with(Default::default);
+                tags.insert("dropped_component".to_owned(), self.component.clone());
+                tags.insert("dropped_reason".to_owned(), reason.to_owned());
+                tags.insert("dropped_message".to_owned(), message);
+            }
+        }
+        self.push_named(DROPPED, event);
+    }
+}
+
 /// Transforms that tend to be more complicated runtime style components.
 ///
 /// These require coordination and map a stream of some `T` to some `U`.
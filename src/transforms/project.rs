@@ -0,0 +1,190 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, LogEvent},
+    internal_events::ProjectEventProcessed,
+    transforms::{FunctionTransform, Transform},
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+//------------------------------------------------------------------------------
+
+/// The fields one variant of the events keeps.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct VariantConfig {
+    /// The only fields kept. All fields are kept if empty.
+    fields: Vec<String>,
+    /// Fields removed, after `fields` is applied.
+    drop_fields: Vec<String>,
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "project_variant")]
+impl TransformConfig for VariantConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        Ok(Transform::function(Variant {
+            fields: self.fields.clone(),
+            drop_fields: self.drop_fields.clone(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "project_variant"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Variant {
+    fields: Vec<String>,
+    drop_fields: Vec<String>,
+}
+
+impl FunctionTransform for Variant {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        emit!(ProjectEventProcessed);
+
+        let log = event.as_mut_log();
+        if !self.fields.is_empty() {
+            let mut projected = LogEvent::default();
+            for field in &self.fields {
+                if let Some(value) = log.remove(field) {
+                    projected.insert(field, value);
+                }
+            }
+            *log = projected;
+        }
+        for field in &self.drop_fields {
+            log.remove(field);
+        }
+
+        output.push(event);
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    variants: IndexMap<String, VariantConfig>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<ProjectConfig>("project")
+}
+
+impl GenerateConfig for ProjectConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"[variants.full]
+
+            [variants.minimal]
+            fields = ["timestamp", "message"]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "project")]
+impl TransformConfig for ProjectConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        Err("this transform must be expanded".into())
+    }
+
+    fn expand(&mut self) -> crate::Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
+        if self.variants.is_empty() {
+            return Err("must specify at least one variant".into());
+        }
+
+        Ok(Some(
+            self.variants
+                .drain(..)
+                .map(|(name, variant)| (name, Box::new(variant) as Box<dyn TransformConfig>))
+                .collect(),
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "project"
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ProjectConfig>();
+    }
+
+    fn expand(config: &str) -> crate::Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
+        toml::from_str::<ProjectConfig>(config).unwrap().expand()
+    }
+
+    #[tokio::test]
+    async fn projects_variants() {
+        let mut variants = expand(
+            r#"
+            [variants.full]
+
+            [variants.minimal]
+            fields = ["message", "request.status", "user"]
+            drop_fields = ["user.email"]
+            "#,
+        )
+        .unwrap()
+        .unwrap();
+
+        let names = variants.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(names, vec!["full", "minimal"]);
+
+        let event = Event::try_from(serde_json::json!({
+            "message": "GET /",
+            "request": { "status": 200, "path": "/" },
+            "user": { "id": 7, "email": "jane@example.com" },
+            "body": "<html>",
+        }))
+        .unwrap();
+
+        let mut full = variants["full"].build().await.unwrap();
+        let output = full.as_function().transform_one(event.clone()).unwrap();
+        assert_eq!(output, event);
+
+        let mut minimal = variants.remove("minimal").unwrap().build().await.unwrap();
+        let output = minimal.as_function().transform_one(event).unwrap();
+        let expected = Event::try_from(serde_json::json!({
+            "message": "GET /",
+            "request": { "status": 200 },
+            "user": { "id": 7 },
+        }))
+        .unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn rejects_no_variants() {
+        assert!(expand("variants = {}").is_err());
+    }
+}
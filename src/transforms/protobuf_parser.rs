@@ -0,0 +1,239 @@
+use crate::{
+    config::{log_schema, DataType, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{
+        ProtobufDecodeFailed, ProtobufParserEventProcessed, ProtobufParserFieldMissing,
+    },
+    protobuf::{ProtobufConfig, ProtobufDecoder},
+    transforms::{FunctionTransform, Transform, TransformOutputs, DROPPED},
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(deny_unknown_fields)]
+#[derivative(Default)]
+pub struct ProtobufParserConfig {
+    pub field: Option<String>,
+    #[serde(default = "crate::serde::default_true")]
+    #[derivative(Default(value = "true"))]
+    pub drop_field: bool,
+    pub target_field: Option<String>,
+    /// Path to the compiled `FileDescriptorSet`.
+    pub descriptor_set: PathBuf,
+    /// Fully qualified name of the message type to decode.
+    pub message_type: String,
+    /// Send events that fail to decode to the `dropped` output instead of
+    /// passing them through.
+    #[serde(default)]
+    pub reroute_dropped: bool,
+}
+
+inventory::submit! {
+    TransformDescription::new::<ProtobufParserConfig>("protobuf_parser")
+}
+
+impl_generate_config_from_default!(ProtobufParserConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "protobuf_parser")]
+impl TransformConfig for ProtobufParserConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let field = self
+            .field
+            .clone()
+            .unwrap_or_else(|| log_schema().message_key().to_string());
+
+        Ok(Transform::function(ProtobufParser {
+            field,
+            drop_field: self.drop_field,
+            target_field: self.target_field.clone(),
+            decoder: ProtobufConfig {
+                descriptor_set: self.descriptor_set.clone(),
+                message_type: self.message_type.clone(),
+            }
+            .build()?,
+            reroute_dropped: self.reroute_dropped,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "protobuf_parser"
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        if self.reroute_dropped {
+            vec![DROPPED.to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProtobufParser {
+    field: String,
+    drop_field: bool,
+    target_field: Option<String>,
+    decoder: ProtobufDecoder,
+    reroute_dropped: bool,
+}
+
+impl ProtobufParser {
+    /// Decodes the event's field, handing the event back with the reason
+    /// when it can't be decoded.
+    fn parse(&self, mut event: Event) -> Result<Event, (Event, String)> {
+        let log = event.as_mut_log();
+
+        let decoded = match log.get(&self.field) {
+            Some(value) => self.decoder.decode(&value.as_bytes()),
+            None => {
+                emit!(ProtobufParserFieldMissing { field: &self.field });
+                let message = format!("field {:?} is missing", self.field);
+                return Err((event, message));
+            }
+        };
+
+        let fields = match decoded {
+            Ok(fields) => fields,
+            Err(error) => {
+                emit!(ProtobufDecodeFailed { error: &error });
+                return Err((event, error.to_string()));
+            }
+        };
+
+        if self.drop_field {
+            log.remove(&self.field);
+        }
+
+        match &self.target_field {
+            Some(target_field) => {
+                log.insert(target_field, Value::Map(fields));
+            }
+            None => {
+                for (key, value) in fields {
+                    log.insert_flat(key, value);
+                }
+            }
+        }
+
+        emit!(ProtobufParserEventProcessed);
+
+        Ok(event)
+    }
+}
+
+impl FunctionTransform for ProtobufParser {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        match self.parse(event) {
+            Ok(event) => output.push(event),
+            Err((event, _)) if !self.reroute_dropped => output.push(event),
+            Err(_) => (),
+        }
+    }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        match self.parse(event) {
+            Ok(event) => outputs.push(event),
+            Err((event, message)) if self.reroute_dropped => {
+                outputs.push_dropped(event, "decode_failed", message)
+            }
+            Err((event, _)) => outputs.push(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protobuf::test::{descriptor_set, encoded_event};
+    use bytes::Bytes;
+    use prost::Message;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ProtobufParserConfig>();
+    }
+
+    async fn parser(target_field: Option<&str>, reroute_dropped: bool) -> Transform {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("descriptors.pb");
+        let mut bytes = Vec::new();
+        descriptor_set().encode(&mut bytes).unwrap();
+        std::fs::write(&path, bytes).unwrap();
+
+        ProtobufParserConfig {
+            target_field: target_field.map(Into::into),
+            descriptor_set: path,
+            message_type: "test.Event".into(),
+            reroute_dropped,
+            ..Default::default()
+        }
+        .build()
+        .await
+        .unwrap()
+    }
+
+    fn event(bytes: Vec<u8>) -> Event {
+        let mut event = Event::new_empty_log();
+        event
+            .as_mut_log()
+            .insert(log_schema().message_key(), Bytes::from(bytes));
+        event
+    }
+
+    #[tokio::test]
+    async fn protobuf_parser_decodes_field() {
+        let mut parser = parser(None, false).await;
+        let event = parser
+            .as_function()
+            .transform_one(event(encoded_event()))
+            .unwrap();
+        let log = event.as_log();
+
+        // The decoded `message` field replaces the encoded one.
+        assert_eq!(log["message"], "hello".into());
+        assert_eq!(log["level"], "ERROR".into());
+        assert_eq!(log["host.name"], "web".into());
+        assert_eq!(log["labels.env"], "prod".into());
+    }
+
+    #[tokio::test]
+    async fn protobuf_parser_decodes_into_target_field() {
+        let mut parser = parser(Some("payload"), false).await;
+        let event = parser
+            .as_function()
+            .transform_one(event(encoded_event()))
+            .unwrap();
+        let log = event.as_log();
+
+        assert!(log.get("message").is_none());
+        assert_eq!(log["payload.delta"], Value::Integer(-3));
+        assert_eq!(log["payload.codes[1]"], Value::Integer(300));
+    }
+
+    #[tokio::test]
+    async fn protobuf_parser_reroutes_invalid_messages() {
+        let mut parser = parser(None, true).await;
+        let mut outputs = TransformOutputs::new("protobuf");
+        parser
+            .as_function()
+            .transform_outputs(&mut outputs, event(vec![0x0a, 0x05, b'h']));
+
+        assert!(outputs.primary.is_empty());
+        let dropped = &outputs.named[DROPPED];
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(
+            dropped[0].as_log()["metadata.dropped.reason"],
+            "decode_failed".into()
+        );
+    }
+}
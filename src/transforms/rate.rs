@@ -0,0 +1,296 @@
+use crate::{
+    config::{DataType, TransformConfig, TransformDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event,
+    },
+    internal_events::{RateCounterReset, RateEventProcessed},
+    transforms::{FunctionTransform, Transform},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+/// What counters are converted into.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// The increase per second since the previous sample.
+    Rate,
+    /// The increase since the previous sample.
+    Delta,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Rate
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Derivative, Clone)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct RateConfig {
+    pub mode: Mode,
+    /// Series that haven't been seen for this long are forgotten, and their
+    /// next sample is treated as their first.
+    #[derivative(Default(value = "300"))]
+    pub expire_after_secs: u64,
+}
+
+inventory::submit! {
+    TransformDescription::new::<RateConfig>("rate")
+}
+
+impl_generate_config_from_default!(RateConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "rate")]
+impl TransformConfig for RateConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        Ok(Transform::function(Rate::new(
+            self.mode,
+            Duration::from_secs(self.expire_after_secs),
+        )))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "rate"
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct SeriesKey {
+    name: String,
+    namespace: Option<String>,
+    tags: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Clone, Debug)]
+struct Sample {
+    /// The counter's total, for absolute counters.
+    value: f64,
+    timestamp: Option<DateTime<Utc>>,
+    seen: Instant,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rate {
+    mode: Mode,
+    expire_after: Duration,
+    series: HashMap<SeriesKey, Sample>,
+    last_expiry: Instant,
+}
+
+impl Rate {
+    pub fn new(mode: Mode, expire_after: Duration) -> Self {
+        Self {
+            mode,
+            expire_after,
+            series: HashMap::new(),
+            last_expiry: Instant::now(),
+        }
+    }
+
+    fn expire(&mut self, now: Instant) {
+        // Scanning every series on each event would be wasteful, so stale
+        // ones are only looked for once per expiry period.
+        if now.duration_since(self.last_expiry) < self.expire_after {
+            return;
+        }
+        let expire_after = self.expire_after;
+        self.series
+            .retain(|_, sample| now.duration_since(sample.seen) < expire_after);
+        self.last_expiry = now;
+    }
+
+    /// Converts a counter sample, returning `None` while there's no previous
+    /// sample to compare it with.
+    fn convert(&mut self, metric: Metric, now: Instant) -> Option<Metric> {
+        let value = match metric.value {
+            MetricValue::Counter { value } => value,
+            _ => return Some(metric),
+        };
+
+        let key = SeriesKey {
+            name: metric.name.clone(),
+            namespace: metric.namespace.clone(),
+            tags: metric.tags.clone(),
+        };
+        let sample = Sample {
+            value,
+            timestamp: metric.timestamp,
+            seen: now,
+        };
+        let previous = match self.series.insert(key, sample) {
+            Some(previous) if now.duration_since(previous.seen) < self.expire_after => previous,
+            _ if metric.kind == MetricKind::Incremental && self.mode == Mode::Delta => {
+                // Incremental counters are already deltas.
+                return Some(gauge(metric, value));
+            }
+            _ => return None,
+        };
+
+        let delta = match metric.kind {
+            MetricKind::Incremental => value,
+            MetricKind::Absolute if value < previous.value => {
+                emit!(RateCounterReset {
+                    name: &metric.name,
+                    previous: previous.value,
+                    value,
+                });
+                value
+            }
+            MetricKind::Absolute => value - previous.value,
+        };
+
+        match self.mode {
+            Mode::Delta => Some(gauge(metric, delta)),
+            Mode::Rate => {
+                let elapsed = match (previous.timestamp, metric.timestamp) {
+                    (Some(previous), Some(current)) => {
+                        (current - previous).num_milliseconds() as f64 / 1000.0
+                    }
+                    _ => now.duration_since(previous.seen).as_secs_f64(),
+                };
+                if elapsed > 0.0 {
+                    Some(gauge(metric, delta / elapsed))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+fn gauge(metric: Metric, value: f64) -> Metric {
+    Metric {
+        kind: MetricKind::Absolute,
+        value: MetricValue::Gauge { value },
+        ..metric
+    }
+}
+
+impl FunctionTransform for Rate {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        emit!(RateEventProcessed);
+
+        let now = Instant::now();
+        self.expire(now);
+        if let Some(metric) = self.convert(event.into_metric(), now) {
+            output.push(Event::Metric(metric));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RateConfig>();
+    }
+
+    fn counter(kind: MetricKind, value: f64, secs: i64) -> Metric {
+        Metric {
+            name: "requests_total".into(),
+            namespace: None,
+            timestamp: Some(Utc.timestamp(1_600_000_000 + secs, 0)),
+            tags: Some(
+                vec![("host".to_owned(), "web-1".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind,
+            value: MetricValue::Counter { value },
+        }
+    }
+
+    fn values(rate: &mut Rate, metrics: Vec<Metric>) -> Vec<Option<f64>> {
+        let now = Instant::now();
+        metrics
+            .into_iter()
+            .map(|metric| {
+                rate.convert(metric, now).map(|metric| match metric.value {
+                    MetricValue::Gauge { value } => value,
+                    value => panic!("unexpected value {:?}", value),
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn converts_absolute_counters_to_rates() {
+        let mut rate = Rate::new(Mode::Rate, Duration::from_secs(300));
+
+        let output = values(
+            &mut rate,
+            vec![
+                counter(MetricKind::Absolute, 100.0, 0),
+                counter(MetricKind::Absolute, 160.0, 10),
+                // The counter was reset.
+                counter(MetricKind::Absolute, 20.0, 20),
+            ],
+        );
+
+        assert_eq!(output, vec![None, Some(6.0), Some(2.0)]);
+    }
+
+    #[test]
+    fn converts_counters_to_deltas() {
+        let mut rate = Rate::new(Mode::Delta, Duration::from_secs(300));
+
+        let output = values(
+            &mut rate,
+            vec![
+                counter(MetricKind::Absolute, 100.0, 0),
+                counter(MetricKind::Absolute, 160.0, 10),
+            ],
+        );
+        assert_eq!(output, vec![None, Some(60.0)]);
+
+        let output = values(&mut rate, vec![counter(MetricKind::Incremental, 5.0, 20)]);
+        assert_eq!(output, vec![Some(5.0)]);
+    }
+
+    #[test]
+    fn passes_other_metrics() {
+        let mut rate = Rate::new(Mode::Rate, Duration::from_secs(300));
+        let gauge = Metric {
+            value: MetricValue::Gauge { value: 1.5 },
+            ..counter(MetricKind::Absolute, 0.0, 0)
+        };
+
+        let output = rate.transform_one(Event::Metric(gauge.clone()));
+
+        assert_eq!(output, Some(Event::Metric(gauge)));
+    }
+
+    #[test]
+    fn expires_stale_series() {
+        let mut rate = Rate::new(Mode::Delta, Duration::from_secs(60));
+        let start = Instant::now();
+
+        rate.convert(counter(MetricKind::Absolute, 100.0, 0), start);
+        let later = start + Duration::from_secs(61);
+        rate.expire(later);
+
+        assert!(rate.series.is_empty());
+        assert!(rate
+            .convert(counter(MetricKind::Absolute, 160.0, 61), later)
+            .is_none());
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{RedactEventProcessed, RedactFieldsRedacted},
+    transforms::{FunctionTransform, Transform},
+};
+use hmac::{Hmac, Mac, NewMac};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use snafu::{ResultExt, Snafu};
+use std::{net::Ipv6Addr, str::FromStr, sync::Arc};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid regex {:?}: {}", pattern, source))]
+    InvalidRegex {
+        pattern: String,
+        source: regex::Error,
+    },
+    #[snafu(display("`pattern` must be set for `regex` matchers"))]
+    MissingPattern,
+    #[snafu(display("`hash_key` must be set to use the `hash` action"))]
+    MissingHashKey,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RedactConfig {
+    /// The fields to scrub, defaults to the message.
+    #[serde(default)]
+    pub fields: Vec<String>,
+    pub matchers: Vec<MatcherConfig>,
+    /// Key for the HMAC used by the `hash` action.
+    pub hash_key: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MatcherConfig {
+    pub r#type: MatcherType,
+    /// The regular expression for `regex` matchers.
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub action: Action,
+    /// The replacement used by the `replace` action.
+    #[serde(default = "default_placeholder")]
+    pub placeholder: String,
+    /// The number of trailing characters the `truncate` action keeps.
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatcherType {
+    CreditCard,
+    Email,
+    Ip,
+    UsSsn,
+    Regex,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Replace the match with the placeholder.
+    Replace,
+    /// Replace the match with its hex encoded HMAC-SHA256, so equal values
+    /// can still be correlated.
+    Hash,
+    /// Mask all but the last `keep` characters of the match with `*`.
+    Truncate,
+}
+
+impl Default for Action {
+    fn default() -> Self {
+        Action::Replace
+    }
+}
+
+fn default_placeholder() -> String {
+    "[REDACTED]".to_owned()
+}
+
+fn default_keep() -> usize {
+    4
+}
+
+lazy_static! {
+    static ref CREDIT_CARD: Regex = Regex::new(r"\b(?:\d[ -]?){12,18}\d\b").unwrap();
+    static ref EMAIL: Regex =
+        Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b").unwrap();
+    static ref IP: Regex = Regex::new(
+        r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b|(?:[0-9A-Fa-f]{0,4}:){2,7}[0-9A-Fa-f]{0,4}"
+    )
+    .unwrap();
+    static ref US_SSN: Regex = Regex::new(r"\b(\d{3})-(\d{2})-(\d{4})\b").unwrap();
+}
+
+inventory::submit! {
+    TransformDescription::new::<RedactConfig>("redact")
+}
+
+impl GenerateConfig for RedactConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"fields = ["message"]
+            matchers = [{ type = "email" }, { type = "credit_card", action = "truncate" }]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "redact")]
+impl TransformConfig for RedactConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let hash_key = self
+            .hash_key
+            .as_ref()
+            .map(|key| Arc::new(key.clone().into_bytes()));
+        let matchers = self
+            .matchers
+            .iter()
+            .map(|matcher| Matcher::new(matcher, hash_key.clone()))
+            .collect::<Result<_, _>>()?;
+        let fields = if self.fields.is_empty() {
+            vec![log_schema().message_key().to_owned()]
+        } else {
+            self.fields.clone()
+        };
+
+        Ok(Transform::function(Redact { fields, matchers }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "redact"
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Matcher {
+    r#type: MatcherType,
+    regex: Regex,
+    action: Action,
+    placeholder: String,
+    keep: usize,
+    hash_key: Option<Arc<Vec<u8>>>,
+}
+
+impl Matcher {
+    fn new(config: &MatcherConfig, hash_key: Option<Arc<Vec<u8>>>) -> Result<Self, BuildError> {
+        let regex = match config.r#type {
+            MatcherType::CreditCard => CREDIT_CARD.clone(),
+            MatcherType::Email => EMAIL.clone(),
+            MatcherType::Ip => IP.clone(),
+            MatcherType::UsSsn => US_SSN.clone(),
+            MatcherType::Regex => {
+                let pattern = config.pattern.as_ref().ok_or(BuildError::MissingPattern)?;
+                Regex::new(pattern).context(InvalidRegex { pattern })?
+            }
+        };
+        if config.action == Action::Hash && hash_key.is_none() {
+            return Err(BuildError::MissingHashKey);
+        }
+
+        Ok(Self {
+            r#type: config.r#type,
+            regex,
+            action: config.action,
+            placeholder: config.placeholder.clone(),
+            keep: config.keep,
+            hash_key,
+        })
+    }
+
+    /// Filters out matches of the built-in patterns that aren't valid values
+    /// of their kind.
+    fn is_valid(&self, captures: &Captures) -> bool {
+        let matched = &captures[0];
+        match self.r#type {
+            MatcherType::CreditCard => luhn(matched),
+            // The IPv6 candidate also matches things like times and `a::b`
+            // paths, so require a real address with at least two groups.
+            MatcherType::Ip if matched.contains(':') => {
+                Ipv6Addr::from_str(matched).is_ok()
+                    && matched.split(':').filter(|group| !group.is_empty()).count() >= 2
+            }
+            MatcherType::UsSsn => {
+                let area = &captures[1];
+                area != "000"
+                    && area != "666"
+                    && !area.starts_with('9')
+                    && &captures[2] != "00"
+                    && &captures[3] != "0000"
+            }
+            _ => true,
+        }
+    }
+
+    fn redact(&self, matched: &str) -> String {
+        match self.action {
+            Action::Replace => self.placeholder.clone(),
+            Action::Hash => {
+                let key = self.hash_key.as_ref().expect("checked at build");
+                let mut mac = Hmac::<Sha256>::new_varkey(key).expect("HMAC accepts any key length");
+                mac.update(matched.as_bytes());
+                hex::encode(mac.finalize().into_bytes())
+            }
+            Action::Truncate => {
+                let len = matched.chars().count();
+                matched
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| if i + self.keep < len { '*' } else { c })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Checks the Luhn checksum of a card number, ignoring separators.
+fn luhn(number: &str) -> bool {
+    let digits = number
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect::<Vec<_>>();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+#[derive(Clone, Debug)]
+pub struct Redact {
+    fields: Vec<String>,
+    matchers: Vec<Matcher>,
+}
+
+impl FunctionTransform for Redact {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_mut_log();
+        let mut count = 0;
+        for field in &self.fields {
+            let mut text = match log.get(field) {
+                Some(Value::Bytes(bytes)) => String::from_utf8_lossy(bytes).into_owned(),
+                _ => continue,
+            };
+
+            let mut redacted = false;
+            for matcher in &self.matchers {
+                let replaced = matcher.regex.replace_all(&text, |captures: &Captures| {
+                    if matcher.is_valid(captures) {
+                        count += 1;
+                        matcher.redact(&captures[0])
+                    } else {
+                        captures[0].to_owned()
+                    }
+                });
+                if let std::borrow::Cow::Owned(replaced) = replaced {
+                    text = replaced;
+                    redacted = true;
+                }
+            }
+
+            if redacted {
+                log.insert(field, text);
+            }
+        }
+
+        if count > 0 {
+            emit!(RedactFieldsRedacted { count });
+        }
+        emit!(RedactEventProcessed);
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedactConfig>();
+    }
+
+    async fn redact(config: &str, message: &str) -> String {
+        let config: RedactConfig = toml::from_str(config).unwrap();
+        let mut transform = config.build().await.unwrap();
+        let event = transform
+            .as_function()
+            .transform_one(Event::from(message))
+            .unwrap();
+        event.as_log()[log_schema().message_key()].to_string_lossy()
+    }
+
+    #[tokio::test]
+    async fn redacts_builtin_matchers() {
+        let config = r#"
+            matchers = [
+                { type = "credit_card", action = "truncate" },
+                { type = "email" },
+                { type = "ip", placeholder = "<ip>" },
+                { type = "us_ssn" },
+            ]
+        "#;
+
+        assert_eq!(
+            redact(
+                config,
+                "card 4111 1111 1111 1111 paid by jane.doe@example.com"
+            )
+            .await,
+            "card ***************1111 paid by [REDACTED]"
+        );
+        assert_eq!(
+            redact(config, "from 10.1.2.3 and fe80::1ff:fe23:4567:890a").await,
+            "from <ip> and <ip>"
+        );
+        assert_eq!(
+            redact(config, "ssn 123-45-6789, not 000-12-3456").await,
+            "ssn [REDACTED], not 000-12-3456"
+        );
+        // Fails the Luhn check.
+        assert_eq!(
+            redact(config, "order 4111111111111112").await,
+            "order 4111111111111112"
+        );
+        // Times and paths aren't IPv6 addresses.
+        assert_eq!(
+            redact(config, "at 12:30:45 in std::io").await,
+            "at 12:30:45 in std::io"
+        );
+    }
+
+    #[tokio::test]
+    async fn hashes_with_key() {
+        let config = r#"
+            hash_key = "secret"
+            matchers = [{ type = "regex", pattern = "user=\\w+", action = "hash" }]
+        "#;
+
+        let first = redact(config, "login user=bob").await;
+        let second = redact(config, "logout user=bob").await;
+        assert!(!first.contains("bob"));
+        assert_eq!(first.len(), "login ".len() + 64);
+        assert_eq!(&first["login ".len()..], &second["logout ".len()..]);
+    }
+
+    #[tokio::test]
+    async fn hash_requires_key() {
+        let config: RedactConfig =
+            toml::from_str(r#"matchers = [{ type = "email", action = "hash" }]"#).unwrap();
+        assert!(config.build().await.is_err());
+    }
+}
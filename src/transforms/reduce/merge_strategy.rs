@@ -6,7 +6,10 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum MergeStrategy {
+    #[serde(alias = "first")]
     Discard,
+    #[serde(alias = "last")]
+    Retain,
     Sum,
     Max,
     Min,
@@ -41,6 +44,31 @@ impl ReduceValueMerger for DiscardMerger {
 
 //------------------------------------------------------------------------------
 
+#[derive(Debug, Clone)]
+struct RetainMerger {
+    v: Value,
+}
+
+impl RetainMerger {
+    fn new(v: Value) -> Self {
+        Self { v }
+    }
+}
+
+impl ReduceValueMerger for RetainMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        self.v = v;
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
+        v.insert(k, self.v);
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+
 #[derive(Debug, Clone)]
 struct ConcatMerger {
     v: BytesMut,
@@ -419,6 +447,7 @@ pub fn get_value_merger(v: Value, m: &MergeStrategy) -> Result<Box<dyn ReduceVal
         },
         MergeStrategy::Array => Ok(Box::new(ArrayMerger::new(v))),
         MergeStrategy::Discard => Ok(Box::new(DiscardMerger::new(v))),
+        MergeStrategy::Retain => Ok(Box::new(RetainMerger::new(v))),
     }
 }
 
@@ -431,6 +460,7 @@ mod test {
     #[test]
     fn initial_values() {
         assert!(get_value_merger("foo".into(), &MergeStrategy::Discard).is_ok());
+        assert!(get_value_merger("foo".into(), &MergeStrategy::Retain).is_ok());
         assert!(get_value_merger("foo".into(), &MergeStrategy::Sum).is_err());
         assert!(get_value_merger("foo".into(), &MergeStrategy::Max).is_err());
         assert!(get_value_merger("foo".into(), &MergeStrategy::Min).is_err());
@@ -500,6 +530,14 @@ mod test {
             merge("foo".into(), "bar".into(), &MergeStrategy::Discard),
             Ok("foo".into())
         );
+        assert_eq!(
+            merge("foo".into(), "bar".into(), &MergeStrategy::Retain),
+            Ok("bar".into())
+        );
+        assert_eq!(
+            merge(json!({}).into(), 42.into(), &MergeStrategy::Retain),
+            Ok(42.into())
+        );
         assert_eq!(
             merge("foo".into(), "bar".into(), &MergeStrategy::Array),
             Ok(json!(["foo", "bar"]).into())
@@ -573,6 +611,20 @@ mod test {
         );
     }
 
+    #[test]
+    fn parses_first_and_last_aliases() {
+        let strategies: std::collections::HashMap<String, MergeStrategy> = toml::from_str(
+            r#"
+            a = "first"
+            b = "last"
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(strategies["a"], MergeStrategy::Discard));
+        assert!(matches!(strategies["b"], MergeStrategy::Retain));
+    }
+
     fn merge(initial: Value, additional: Value, strategy: &MergeStrategy) -> Result<Value, String> {
         let mut merger = get_value_merger(initial, strategy)?;
         merger.add(additional)?;
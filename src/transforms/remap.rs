@@ -2,7 +2,7 @@ use crate::{
     config::{DataType, TransformConfig, TransformDescription},
     event::Event,
     internal_events::{RemapEventProcessed, RemapFailedMapping},
-    transforms::{FunctionTransform, Transform},
+    transforms::{FunctionTransform, Transform, TransformOutputs, DROPPED},
     Result,
 };
 use remap::{value, Program, Runtime, TypeConstraint, TypeDef};
@@ -14,6 +14,9 @@ use serde::{Deserialize, Serialize};
 pub struct RemapConfig {
     pub source: String,
     pub drop_on_err: bool,
+    /// Send events the program fails on to the `dropped` output, as they
+    /// were before the program ran.
+    pub reroute_dropped: bool,
 }
 
 inventory::submit! {
@@ -40,12 +43,21 @@ impl TransformConfig for RemapConfig {
     fn transform_type(&self) -> &'static str {
         "remap"
     }
+
+    fn named_outputs(&self) -> Vec<String> {
+        if self.reroute_dropped {
+            vec![DROPPED.to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Remap {
     program: Program,
     drop_on_err: bool,
+    reroute_dropped: bool,
 }
 
 impl Remap {
@@ -63,6 +75,7 @@ impl Remap {
         Ok(Remap {
             program,
             drop_on_err: config.drop_on_err,
+            reroute_dropped: config.reroute_dropped,
         })
     }
 }
@@ -75,17 +88,40 @@ impl FunctionTransform for Remap {
 
         if let Err(error) = runtime.execute(&mut event, &self.program) {
             emit!(RemapFailedMapping {
-                event_dropped: self.drop_on_err,
+                event_dropped: self.drop_on_err || self.reroute_dropped,
                 error: error.to_string(),
             });
 
-            if self.drop_on_err {
+            if self.drop_on_err || self.reroute_dropped {
                 return;
             }
         }
 
         output.push(event);
     }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        if !self.reroute_dropped {
+            return self.transform(&mut outputs.primary, event);
+        }
+
+        emit!(RemapEventProcessed);
+
+        let mut runtime = Runtime::default();
+        let mut mapped = event.clone();
+
+        match runtime.execute(&mut mapped, &self.program) {
+            Ok(_) => outputs.push(mapped),
+            Err(error) => {
+                let error = error.to_string();
+                emit!(RemapFailedMapping {
+                    event_dropped: true,
+                    error: error.clone(),
+                });
+                outputs.push_dropped(event, "mapping_failed", error);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -116,6 +152,7 @@ mod tests {
 "#
             .to_string(),
             drop_on_err: true,
+            reroute_dropped: false,
         };
         let mut tform = Remap::new(conf).unwrap();
 
@@ -126,4 +163,27 @@ mod tests {
         assert_eq!(get_field_string(&result, "bar"), "baz");
         assert_eq!(get_field_string(&result, "copy"), "buz");
     }
+
+    #[test]
+    fn remap_reroutes_dropped() {
+        let conf = RemapConfig {
+            source: ".foo = \"bar\"\n.bar = parse_json(.message)".to_string(),
+            drop_on_err: false,
+            reroute_dropped: true,
+        };
+        let mut tform = Remap::new(conf).unwrap();
+
+        let mut outputs = TransformOutputs::new("remap");
+        tform.transform_outputs(&mut outputs, Event::from("fail me"));
+
+        assert!(outputs.primary.is_empty());
+        let dropped = &outputs.named[DROPPED];
+        assert_eq!(dropped.len(), 1);
+        assert!(!dropped[0].as_log().contains("foo"));
+        assert_eq!(get_field_string(&dropped[0], "message"), "fail me");
+        assert_eq!(
+            get_field_string(&dropped[0], "metadata.dropped.reason"),
+            "mapping_failed"
+        );
+    }
 }
@@ -0,0 +1,171 @@
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::Event,
+    internal_events::{RouteEventDiscarded, RouteEventProcessed},
+    transforms::{FunctionTransform, Transform, TransformOutputs},
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// The name of the output receiving events that match none of the routes.
+pub const UNMATCHED_ROUTE: &str = "_unmatched";
+
+//------------------------------------------------------------------------------
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RouteConfig {
+    route: IndexMap<String, AnyCondition>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<RouteConfig>("route")
+}
+
+impl GenerateConfig for RouteConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            route: IndexMap::new(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "route")]
+impl TransformConfig for RouteConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        if self.route.is_empty() {
+            return Err("must specify at least one route".into());
+        }
+        if self.route.contains_key(UNMATCHED_ROUTE) {
+            return Err(format!("the route name `{}` is reserved", UNMATCHED_ROUTE).into());
+        }
+
+        let routes = self
+            .route
+            .iter()
+            .map(|(name, condition)| Ok((name.clone(), condition.build()?)))
+            .collect::<crate::Result<_>>()?;
+        Ok(Transform::function(Route::new(routes)))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "route"
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        self.route
+            .keys()
+            .cloned()
+            .chain(std::iter::once(UNMATCHED_ROUTE.to_owned()))
+            .collect()
+    }
+}
+
+/// Sends each event to the outputs of the routes whose condition it matches,
+/// or to the unmatched output if there are none.
+#[derive(Clone, Derivative)]
+#[derivative(Debug)]
+pub struct Route {
+    #[derivative(Debug = "ignore")]
+    routes: Vec<(String, Box<dyn Condition>)>,
+}
+
+impl Route {
+    pub fn new(routes: Vec<(String, Box<dyn Condition>)>) -> Self {
+        Self { routes }
+    }
+}
+
+impl FunctionTransform for Route {
+    fn transform(&mut self, _output: &mut Vec<Event>, _event: Event) {
+        // Events are only ever sent to the named outputs.
+        emit!(RouteEventDiscarded);
+    }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        emit!(RouteEventProcessed);
+        let mut matched = false;
+        for (name, condition) in &self.routes {
+            if condition.check(&event) {
+                outputs.push_named(name, event.clone());
+                matched = true;
+            }
+        }
+        if !matched {
+            outputs.push_named(UNMATCHED_ROUTE, event);
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RouteConfig>();
+    }
+
+    async fn route(config: &str) -> crate::Result<Transform> {
+        toml::from_str::<RouteConfig>(config).unwrap().build().await
+    }
+
+    #[tokio::test]
+    async fn routes_events_by_condition() {
+        let config = toml::from_str::<RouteConfig>(
+            r#"
+            route.errors."level.eq" = "error"
+            route.warnings."level.eq" = "warn"
+            route.important."priority.eq" = "high"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.named_outputs(),
+            vec!["errors", "warnings", "important", "_unmatched"]
+        );
+
+        let mut transform = config.build().await.unwrap();
+        let transform = transform.as_function();
+        let mut outputs = TransformOutputs::new("route");
+        for (level, priority) in vec![("error", "high"), ("warn", "low"), ("info", "low")] {
+            let mut event = Event::from("message");
+            event.as_mut_log().insert("level", level);
+            event.as_mut_log().insert("priority", priority);
+            transform.transform_outputs(&mut outputs, event);
+        }
+
+        let levels = |output: &str| {
+            outputs.named[output]
+                .iter()
+                .map(|event| event.as_log()["level"].to_string_lossy())
+                .collect::<Vec<_>>()
+        };
+        assert!(outputs.primary.is_empty());
+        assert_eq!(levels("errors"), vec!["error"]);
+        assert_eq!(levels("warnings"), vec!["warn"]);
+        assert_eq!(levels("important"), vec!["error"]);
+        assert_eq!(levels("_unmatched"), vec!["info"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_reserved_route_name() {
+        assert!(route(r#"route._unmatched."level.eq" = "error""#)
+            .await
+            .is_err());
+        assert!(route("route = {}").await.is_err());
+    }
+}
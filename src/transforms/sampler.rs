@@ -1,5 +1,5 @@
 use crate::{
-    conditions::{CheckFieldsConfig, Condition, ConditionConfig},
+    conditions::{AnyCondition, Condition},
     config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
     event::Event,
     internal_events::{SamplerEventDiscarded, SamplerEventProcessed},
@@ -10,19 +10,33 @@ use serde::{Deserialize, Serialize};
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct SamplerConfig {
-    pub rate: u64,
+    /// Forward one out of every `rate` events.
+    pub rate: Option<u64>,
+    /// Forward this fraction of events, between 0 and 1.
+    pub ratio: Option<f64>,
     pub key_field: Option<String>,
-    pub exclude: Option<CheckFieldsConfig>,
+    pub exclude: Option<AnyCondition>,
 }
 
+/// The `sample` transform, which is the same as `sampler` under the name
+/// other transforms follow.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct SampleConfig(SamplerConfig);
+
 inventory::submit! {
     TransformDescription::new::<SamplerConfig>("sampler")
 }
 
+inventory::submit! {
+    TransformDescription::new::<SampleConfig>("sample")
+}
+
 impl GenerateConfig for SamplerConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
-            rate: 10,
+            rate: Some(10),
+            ratio: None,
             key_field: None,
             exclude: None,
         })
@@ -30,18 +44,39 @@ impl GenerateConfig for SamplerConfig {
     }
 }
 
+impl GenerateConfig for SampleConfig {
+    fn generate_config() -> toml::Value {
+        SamplerConfig::generate_config()
+    }
+}
+
+impl SamplerConfig {
+    fn build_sampler(&self) -> crate::Result<Sampler> {
+        let exclude = self
+            .exclude
+            .as_ref()
+            .map(|condition| condition.build())
+            .transpose()?;
+
+        match (self.rate, self.ratio) {
+            (Some(rate), None) if rate > 0 => {
+                Ok(Sampler::new(rate, self.key_field.clone(), exclude))
+            }
+            (None, Some(ratio)) if ratio > 0.0 && ratio <= 1.0 => {
+                Ok(Sampler::with_ratio(ratio, self.key_field.clone(), exclude))
+            }
+            (Some(_), None) => Err("`rate` must be greater than zero".into()),
+            (None, Some(_)) => Err("`ratio` must be greater than 0 and at most 1".into()),
+            _ => Err("exactly one of `rate` and `ratio` must be provided".into()),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "sampler")]
 impl TransformConfig for SamplerConfig {
     async fn build(&self) -> crate::Result<Transform> {
-        Ok(Transform::function(Sampler::new(
-            self.rate,
-            self.key_field.clone(),
-            self.exclude
-                .as_ref()
-                .map(|condition| condition.build())
-                .transpose()?,
-        )))
+        self.build_sampler().map(Transform::function)
     }
 
     fn input_type(&self) -> DataType {
@@ -57,9 +92,35 @@ impl TransformConfig for SamplerConfig {
     }
 }
 
+#[async_trait::async_trait]
+#[typetag::serde(name = "sample")]
+impl TransformConfig for SampleConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        self.0.build_sampler().map(Transform::function)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "sample"
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Rate {
+    OneIn(u64),
+    Ratio(f64),
+}
+
 #[derive(Clone)]
 pub struct Sampler {
-    rate: u64,
+    rate: Rate,
     key_field: Option<String>,
     exclude: Option<Box<dyn Condition>>,
     count: u64,
@@ -68,7 +129,20 @@ pub struct Sampler {
 impl Sampler {
     pub fn new(rate: u64, key_field: Option<String>, exclude: Option<Box<dyn Condition>>) -> Self {
         Self {
-            rate,
+            rate: Rate::OneIn(rate),
+            key_field,
+            exclude,
+            count: 0,
+        }
+    }
+
+    pub fn with_ratio(
+        ratio: f64,
+        key_field: Option<String>,
+        exclude: Option<Box<dyn Condition>>,
+    ) -> Self {
+        Self {
+            rate: Rate::Ratio(ratio),
             key_field,
             exclude,
             count: 0,
@@ -87,24 +161,37 @@ impl FunctionTransform for Sampler {
             }
         }
 
-        let value = self
+        // Hashing the key keeps or drops all events with the same value
+        // together, for example all events of a trace.
+        let hash = self
             .key_field
             .as_ref()
             .and_then(|key_field| event.as_log().get(key_field))
-            .map(|v| v.to_string_lossy());
+            .map(|v| seahash::hash(v.to_string_lossy().as_bytes()));
 
-        let num = if let Some(value) = value {
-            seahash::hash(value.as_bytes())
-        } else {
-            self.count
+        let (keep, sample_rate) = match self.rate {
+            Rate::OneIn(rate) => {
+                let num = hash.unwrap_or(self.count);
+                self.count = (self.count + 1) % rate;
+                (num % rate == 0, rate.to_string())
+            }
+            Rate::Ratio(ratio) => {
+                let keep = match hash {
+                    Some(hash) => (hash as f64 / u64::MAX as f64) < ratio,
+                    // Keep an event each time the running total of kept
+                    // events falls behind the ratio.
+                    None => {
+                        let count = self.count as f64;
+                        self.count = self.count.wrapping_add(1);
+                        ((count + 1.0) * ratio).floor() > (count * ratio).floor()
+                    }
+                };
+                (keep, (1.0 / ratio).to_string())
+            }
         };
 
-        self.count = (self.count + 1) % self.rate;
-
-        if num % self.rate == 0 {
-            event
-                .as_mut_log()
-                .insert("sample_rate", self.rate.to_string());
+        if keep {
+            event.as_mut_log().insert("sample_rate", sample_rate);
             output.push(event);
         } else {
             emit!(SamplerEventDiscarded);
@@ -116,7 +203,9 @@ impl FunctionTransform for Sampler {
 mod tests {
     use super::*;
     use crate::{
-        conditions::check_fields::CheckFieldsPredicateArg, config::log_schema, event::Event,
+        conditions::{check_fields::CheckFieldsPredicateArg, CheckFieldsConfig, ConditionConfig},
+        config::log_schema,
+        event::Event,
         test_util::random_lines,
     };
     use approx::assert_relative_eq;
@@ -139,6 +228,78 @@ mod tests {
     #[test]
     fn genreate_config() {
         crate::test_util::test_generate_config::<SamplerConfig>();
+        crate::test_util::test_generate_config::<SampleConfig>();
+    }
+
+    #[test]
+    fn requires_one_of_rate_and_ratio() {
+        let build = |config: &str| {
+            toml::from_str::<SamplerConfig>(config)
+                .unwrap()
+                .build_sampler()
+        };
+
+        assert!(build("rate = 10").is_ok());
+        assert!(build("ratio = 0.5").is_ok());
+        assert!(build("rate = 0").is_err());
+        assert!(build("ratio = 1.5").is_err());
+        assert!(build("rate = 10\nratio = 0.5").is_err());
+        assert!(build("key_field = \"message\"").is_err());
+    }
+
+    #[test]
+    fn ratio_samples_at_the_configured_ratio() {
+        let mut sampler = Sampler::with_ratio(0.25, None, None);
+        let total_passed = random_events(1000)
+            .into_iter()
+            .filter_map(|event| sampler.transform_one(event))
+            .count();
+        assert_eq!(total_passed, 250);
+
+        let num_events = 10000;
+        let mut sampler = Sampler::with_ratio(0.1, Some(log_schema().message_key().into()), None);
+        let total_passed = random_events(num_events)
+            .into_iter()
+            .filter_map(|event| sampler.transform_one(event))
+            .count();
+        let actual = total_passed as f64 / num_events as f64;
+        assert_relative_eq!(0.1, actual, epsilon = 0.05);
+    }
+
+    #[test]
+    fn ratio_consistently_samples_events_with_the_same_key() {
+        let mut sampler = Sampler::with_ratio(0.5, Some("trace_id".into()), None);
+        for trace_id in 0..100 {
+            let passed = (0..10)
+                .filter_map(|_| {
+                    let mut event = Event::from("span");
+                    event.as_mut_log().insert("trace_id", trace_id);
+                    sampler.transform_one(event)
+                })
+                .count();
+            assert!(passed == 0 || passed == 10);
+        }
+    }
+
+    #[test]
+    fn excludes_events_matching_any_condition() {
+        let mut sampler = toml::from_str::<SamplerConfig>(
+            r#"
+            rate = 1000000
+            exclude."level.eq" = "error"
+            "#,
+        )
+        .unwrap()
+        .build_sampler()
+        .unwrap();
+
+        // The first event is always forwarded.
+        assert!(sampler.transform_one(Event::from("first")).is_some());
+
+        let mut event = Event::from("something failed");
+        event.as_mut_log().insert("level", "error");
+        assert!(sampler.transform_one(event).is_some());
+        assert!(sampler.transform_one(Event::from("second")).is_none());
     }
 
     #[test]
@@ -0,0 +1,301 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{SanitizeKeysConflict, SanitizeKeysEventProcessed},
+    transforms::{FunctionTransform, Transform},
+};
+use regex::{NoExpand, Regex};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid `allowed_characters` {:?}: {}", allowed, source))]
+    InvalidAllowedCharacters {
+        allowed: String,
+        source: regex::Error,
+    },
+}
+
+/// The key rules of a destination.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// Dots are read as object paths, so keys like `a` and `a.b` conflict.
+    Elasticsearch,
+    /// Label names must match `[a-zA-Z_][a-zA-Z0-9_]*`.
+    Prometheus,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct SanitizeKeysConfig {
+    /// Defaults for the other options, which take precedence when set.
+    pub preset: Option<Preset>,
+    pub dot_replacement: Option<String>,
+    /// The body of a regex character class, like `a-zA-Z0-9_`. Other
+    /// characters are replaced with `replacement`.
+    pub allowed_characters: Option<String>,
+    pub replacement: Option<String>,
+    pub lowercase: Option<bool>,
+    /// Keys are truncated to this many characters.
+    pub max_length: Option<usize>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<SanitizeKeysConfig>("sanitize_keys")
+}
+
+impl GenerateConfig for SanitizeKeysConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"preset = "elasticsearch""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sanitize_keys")]
+impl TransformConfig for SanitizeKeysConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let (dot_replacement, allowed_characters) = match self.preset {
+            Some(Preset::Elasticsearch) => (Some("_"), None),
+            Some(Preset::Prometheus) => (None, Some("a-zA-Z0-9_")),
+            None => (None, None),
+        };
+        let dot_replacement = self
+            .dot_replacement
+            .clone()
+            .or_else(|| dot_replacement.map(Into::into));
+        let disallowed = self
+            .allowed_characters
+            .as_deref()
+            .or(allowed_characters)
+            .map(|allowed| {
+                Regex::new(&format!("[^{}]", allowed)).context(InvalidAllowedCharacters { allowed })
+            })
+            .transpose()?;
+
+        Ok(Transform::function(SanitizeKeys {
+            dot_replacement,
+            disallowed,
+            replacement: self.replacement.clone().unwrap_or_else(|| "_".into()),
+            lowercase: self.lowercase.unwrap_or(false),
+            max_length: self.max_length,
+            leading_letter: self.preset == Some(Preset::Prometheus),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "sanitize_keys"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SanitizeKeys {
+    dot_replacement: Option<String>,
+    disallowed: Option<Regex>,
+    replacement: String,
+    lowercase: bool,
+    max_length: Option<usize>,
+    /// Prefix keys that start with a digit with an underscore.
+    leading_letter: bool,
+}
+
+impl SanitizeKeys {
+    fn sanitize(&self, key: &str) -> String {
+        let mut key = match &self.dot_replacement {
+            Some(replacement) => key.replace('.', replacement),
+            None => key.to_owned(),
+        };
+        if let Some(disallowed) = &self.disallowed {
+            key = disallowed
+                .replace_all(&key, NoExpand(&self.replacement))
+                .into_owned();
+        }
+        if self.lowercase {
+            key = key.to_lowercase();
+        }
+        if self.leading_letter && key.starts_with(|c: char| c.is_ascii_digit()) {
+            key.insert(0, '_');
+        }
+        if let Some(max_length) = self.max_length {
+            if let Some((end, _)) = key.char_indices().nth(max_length) {
+                key.truncate(end);
+            }
+        }
+        if key.is_empty() {
+            key.push('_');
+        }
+        key
+    }
+
+    fn sanitize_map<V>(
+        &self,
+        map: BTreeMap<String, V>,
+        mut value: impl FnMut(V) -> V,
+    ) -> BTreeMap<String, V> {
+        let mut sanitized = BTreeMap::new();
+        for (key, field) in map {
+            let new_key = self.sanitize(&key);
+            if sanitized.insert(new_key, value(field)).is_some() {
+                emit!(SanitizeKeysConflict { key: &key });
+            }
+        }
+        sanitized
+    }
+
+    fn sanitize_value(&self, value: Value) -> Value {
+        match value {
+            Value::Map(map) => {
+                Value::Map(self.sanitize_map(map, |value| self.sanitize_value(value)))
+            }
+            Value::Array(values) => Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| self.sanitize_value(value))
+                    .collect(),
+            ),
+            value => value,
+        }
+    }
+}
+
+impl FunctionTransform for SanitizeKeys {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        match &mut event {
+            Event::Log(log) => {
+                let fields: BTreeMap<String, Value> = std::mem::take(log).into();
+                *log =
+                    LogEvent::from(self.sanitize_map(fields, |value| self.sanitize_value(value)));
+            }
+            Event::Metric(metric) => {
+                metric.tags = metric
+                    .tags
+                    .take()
+                    .map(|tags| self.sanitize_map(tags, |value| value));
+            }
+        }
+
+        emit!(SanitizeKeysEventProcessed);
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::{Metric, MetricKind, MetricValue};
+    use serde_json::json;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SanitizeKeysConfig>();
+    }
+
+    async fn sanitize(config: SanitizeKeysConfig, event: Event) -> Event {
+        let mut transform = config.build().await.unwrap();
+        transform.as_function().transform_one(event).unwrap()
+    }
+
+    #[tokio::test]
+    async fn elasticsearch_preset_replaces_dots() {
+        let event = Event::try_from(json!({
+            "message": "hi",
+            "kubernetes": {"labels": {"app.kubernetes.io/name": "web"}},
+        }))
+        .unwrap();
+        let mut expected = LogEvent::default();
+        expected.insert_flat("message", "hi");
+        expected.insert(
+            "kubernetes.labels",
+            Value::Map(
+                vec![("app_kubernetes_io/name".into(), "web".into())]
+                    .into_iter()
+                    .collect(),
+            ),
+        );
+
+        let output = sanitize(
+            SanitizeKeysConfig {
+                preset: Some(Preset::Elasticsearch),
+                ..Default::default()
+            },
+            event,
+        )
+        .await;
+        assert_eq!(output, Event::Log(expected));
+    }
+
+    #[tokio::test]
+    async fn prometheus_preset_sanitizes_tags() {
+        let metric = Metric {
+            name: "requests".into(),
+            namespace: None,
+            timestamp: None,
+            tags: Some(
+                vec![
+                    ("2xx".to_owned(), "1".to_owned()),
+                    ("http.status-code".to_owned(), "200".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        };
+
+        let output = sanitize(
+            SanitizeKeysConfig {
+                preset: Some(Preset::Prometheus),
+                ..Default::default()
+            },
+            Event::Metric(metric),
+        )
+        .await;
+        let tags = output.as_metric().tags.as_ref().unwrap();
+        assert_eq!(
+            tags.keys().collect::<Vec<_>>(),
+            vec!["_2xx", "http_status_code"]
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_options() {
+        let event = Event::try_from(json!({"User Name!": "jane", "RequestIdentifier": 1})).unwrap();
+        let output = sanitize(
+            SanitizeKeysConfig {
+                allowed_characters: Some("a-zA-Z".into()),
+                replacement: Some("".into()),
+                lowercase: Some(true),
+                max_length: Some(7),
+                ..Default::default()
+            },
+            event,
+        )
+        .await;
+
+        assert_eq!(
+            output,
+            Event::try_from(json!({"username": "jane", "request": 1})).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_allowed_characters() {
+        let config = SanitizeKeysConfig {
+            allowed_characters: Some("z-a".into()),
+            ..Default::default()
+        };
+        assert!(config.build().await.is_err());
+    }
+}
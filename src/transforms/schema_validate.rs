@@ -0,0 +1,236 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{SchemaValidateEventProcessed, SchemaValidateInvalidEvent},
+    transforms::{FunctionTransform, Transform, TransformOutputs, DROPPED},
+};
+use jsonschema::JSONSchema;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{fmt, path::PathBuf, sync::Arc};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Exactly one of `schema` and `schema_file` must be set"))]
+    SchemaSource,
+    #[snafu(display("Failed to read schema file {:?}: {}", path.display(), source))]
+    ReadSchema {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Schema is not valid JSON: {}", source))]
+    ParseSchema { source: serde_json::Error },
+    #[snafu(display("Invalid JSON Schema: {:?}", error))]
+    CompileSchema { error: jsonschema::CompilationError },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchemaValidateConfig {
+    /// The JSON Schema, inline.
+    pub schema: Option<String>,
+    /// A file holding the JSON Schema.
+    pub schema_file: Option<PathBuf>,
+    /// Write the validation errors of invalid events to this field.
+    pub errors_field: Option<String>,
+    /// Send invalid events to the `dropped` output.
+    pub reroute_dropped: bool,
+}
+
+inventory::submit! {
+    TransformDescription::new::<SchemaValidateConfig>("schema_validate")
+}
+
+impl GenerateConfig for SchemaValidateConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"schema_file = "/etc/vector/schemas/event.json"
+            errors_field = "schema_errors""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "schema_validate")]
+impl TransformConfig for SchemaValidateConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let schema = match (&self.schema, &self.schema_file) {
+            (Some(schema), None) => schema.clone(),
+            (None, Some(path)) => std::fs::read_to_string(path).context(ReadSchema { path })?,
+            _ => return Err(BuildError::SchemaSource.into()),
+        };
+        let schema: serde_json::Value = serde_json::from_str(&schema).context(ParseSchema)?;
+        // The compiled schema borrows the document, so it has to live for as
+        // long as the transform may. It is leaked once per (re)load.
+        let schema: &'static serde_json::Value = Box::leak(Box::new(schema));
+        let compiled = JSONSchema::compile(schema, None)
+            .map_err(|error| BuildError::CompileSchema { error })?;
+
+        Ok(Transform::function(SchemaValidate {
+            schema: Arc::new(compiled),
+            errors_field: self.errors_field.clone(),
+            reroute_dropped: self.reroute_dropped,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "schema_validate"
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        if self.reroute_dropped {
+            vec![DROPPED.to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SchemaValidate {
+    schema: Arc<JSONSchema<'static>>,
+    errors_field: Option<String>,
+    reroute_dropped: bool,
+}
+
+impl fmt::Debug for SchemaValidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SchemaValidate")
+            .field("errors_field", &self.errors_field)
+            .field("reroute_dropped", &self.reroute_dropped)
+            .finish()
+    }
+}
+
+impl SchemaValidate {
+    /// Validates the event, handing it back with the reasons if it is
+    /// invalid.
+    fn validate(&self, mut event: Event) -> Result<Event, (Event, Vec<String>)> {
+        emit!(SchemaValidateEventProcessed);
+
+        let instance = match serde_json::to_value(event.as_log()) {
+            Ok(instance) => instance,
+            Err(error) => return Err((event, vec![error.to_string()])),
+        };
+        let errors = match self.schema.validate(&instance) {
+            Ok(()) => return Ok(event),
+            Err(errors) => errors.map(|error| error.to_string()).collect::<Vec<_>>(),
+        };
+
+        emit!(SchemaValidateInvalidEvent { errors: &errors });
+        if let Some(field) = &self.errors_field {
+            let values = errors.iter().cloned().map(Value::from).collect();
+            event.as_mut_log().insert(field, Value::Array(values));
+        }
+        Err((event, errors))
+    }
+}
+
+impl FunctionTransform for SchemaValidate {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        match self.validate(event) {
+            Ok(event) => output.push(event),
+            Err((event, _)) if !self.reroute_dropped => output.push(event),
+            Err(_) => (),
+        }
+    }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        match self.validate(event) {
+            Ok(event) => outputs.push(event),
+            Err((event, errors)) if self.reroute_dropped => {
+                outputs.push_dropped(event, "schema_violation", errors.join("; "))
+            }
+            Err((event, _)) => outputs.push(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const SCHEMA: &str = r#"{
+        "type": "object",
+        "required": ["message", "status"],
+        "properties": {
+            "status": { "type": "integer", "minimum": 100, "maximum": 599 }
+        }
+    }"#;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SchemaValidateConfig>();
+    }
+
+    fn event(status: Value) -> Event {
+        let mut event = Event::from("request");
+        event.as_mut_log().insert("status", status);
+        event
+    }
+
+    #[tokio::test]
+    async fn annotates_invalid_events() {
+        let mut transform = SchemaValidateConfig {
+            schema: Some(SCHEMA.into()),
+            errors_field: Some("schema_errors".into()),
+            ..Default::default()
+        }
+        .build()
+        .await
+        .unwrap();
+        let transform = transform.as_function();
+
+        let valid = transform.transform_one(event(200.into())).unwrap();
+        assert!(valid.as_log().get("schema_errors").is_none());
+
+        let invalid = transform.transform_one(event("ok".into())).unwrap();
+        match &invalid.as_log()["schema_errors"] {
+            Value::Array(errors) => assert_eq!(errors.len(), 1),
+            value => panic!("unexpected errors field {:?}", value),
+        }
+    }
+
+    #[tokio::test]
+    async fn reroutes_invalid_events() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(SCHEMA.as_bytes()).unwrap();
+        let mut transform = SchemaValidateConfig {
+            schema_file: Some(file.path().into()),
+            reroute_dropped: true,
+            ..Default::default()
+        }
+        .build()
+        .await
+        .unwrap();
+
+        let mut outputs = TransformOutputs::new("schema");
+        let transform = transform.as_function();
+        transform.transform_outputs(&mut outputs, event(200.into()));
+        transform.transform_outputs(&mut outputs, event(700.into()));
+
+        assert_eq!(outputs.primary.len(), 1);
+        let dropped = &outputs.named[DROPPED];
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].as_log()["status"], Value::Integer(700));
+        assert_eq!(
+            dropped[0].as_log()["metadata.dropped.reason"],
+            "schema_violation".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn requires_one_schema_source() {
+        assert!(SchemaValidateConfig::default().build().await.is_err());
+    }
+}
@@ -0,0 +1,357 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{discriminant::Discriminant, Event, LogEvent, Value},
+    internal_events::{SessionClosed, SessionEventProcessed},
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::{DateTime, Utc};
+use futures::{
+    compat::{Compat, Compat01As03},
+    stream, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`group_by` must not be empty"))]
+    NoGroupBy,
+    #[snafu(display("`inactivity_gap_secs` must be greater than zero"))]
+    ZeroGap,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SessionConfig {
+    /// The fields identifying whose session an event belongs to.
+    pub group_by: Vec<String>,
+    /// Sessions are closed after this long without events.
+    #[serde(default = "default_inactivity_gap_secs")]
+    pub inactivity_gap_secs: u64,
+    /// Fields whose values in the first event of a session are kept.
+    #[serde(default)]
+    pub first_fields: Vec<String>,
+    /// Fields whose values in the last event of a session are kept.
+    #[serde(default)]
+    pub last_fields: Vec<String>,
+    #[serde(default = "default_flush_period_ms")]
+    pub flush_period_ms: u64,
+}
+
+const fn default_inactivity_gap_secs() -> u64 {
+    1800
+}
+
+const fn default_flush_period_ms() -> u64 {
+    1000
+}
+
+inventory::submit! {
+    TransformDescription::new::<SessionConfig>("session")
+}
+
+impl GenerateConfig for SessionConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"group_by = ["user_id"]
+            first_fields = ["referrer"]
+            last_fields = ["path"]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "session")]
+impl TransformConfig for SessionConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        if self.group_by.is_empty() {
+            return Err(BuildError::NoGroupBy.into());
+        }
+        if self.inactivity_gap_secs == 0 {
+            return Err(BuildError::ZeroGap.into());
+        }
+
+        Ok(Transform::task(Session {
+            group_by: self.group_by.clone(),
+            gap: Duration::from_secs(self.inactivity_gap_secs),
+            first_fields: self.first_fields.clone(),
+            last_fields: self.last_fields.clone(),
+            flush_period: Duration::from_millis(self.flush_period_ms),
+            sessions: HashMap::new(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "session"
+    }
+}
+
+#[derive(Debug)]
+struct SessionState {
+    /// The values of the `group_by` fields.
+    key: Vec<(String, Value)>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    count: i64,
+    first: BTreeMap<String, Value>,
+    last: BTreeMap<String, Value>,
+    stale_since: Instant,
+}
+
+impl SessionState {
+    fn into_event(self) -> Event {
+        let mut log = LogEvent::default();
+        for (field, value) in self.key {
+            log.insert(field, value);
+        }
+        log.insert(log_schema().timestamp_key(), self.end);
+        log.insert("session.start", self.start);
+        log.insert("session.end", self.end);
+        let duration = (self.end - self.start).num_milliseconds() as f64 / 1000.0;
+        log.insert("session.duration_secs", duration);
+        log.insert("session.event_count", self.count);
+        if !self.first.is_empty() {
+            log.insert("session.first", Value::Map(self.first));
+        }
+        if !self.last.is_empty() {
+            log.insert("session.last", Value::Map(self.last));
+        }
+        Event::Log(log)
+    }
+}
+
+pub struct Session {
+    group_by: Vec<String>,
+    gap: Duration,
+    first_fields: Vec<String>,
+    last_fields: Vec<String>,
+    flush_period: Duration,
+    sessions: HashMap<Discriminant, SessionState>,
+}
+
+impl Session {
+    fn add(&mut self, output: &mut Vec<Event>, event: Event) {
+        emit!(SessionEventProcessed);
+
+        let log = event.as_log();
+        let timestamp = match log.get(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(timestamp)) => *timestamp,
+            _ => Utc::now(),
+        };
+        let discriminant = Discriminant::from_log_event(log, &self.group_by);
+
+        // Events far enough apart in time start a new session, even if they
+        // arrive together, as when replaying old logs.
+        let gap =
+            chrono::Duration::from_std(self.gap).unwrap_or_else(|_| chrono::Duration::max_value());
+        let expired = self
+            .sessions
+            .get(&discriminant)
+            .map_or(false, |session| timestamp - session.end > gap);
+        if expired {
+            if let Some(session) = self.sessions.remove(&discriminant) {
+                emit!(SessionClosed);
+                output.push(session.into_event());
+            }
+        }
+
+        let group_by = &self.group_by;
+        let first_fields = &self.first_fields;
+        let session = self
+            .sessions
+            .entry(discriminant)
+            .or_insert_with(|| SessionState {
+                key: group_by
+                    .iter()
+                    .filter_map(|field| log.get(field).map(|value| (field.clone(), value.clone())))
+                    .collect(),
+                start: timestamp,
+                end: timestamp,
+                count: 0,
+                first: first_fields
+                    .iter()
+                    .filter_map(|field| log.get(field).map(|value| (field.clone(), value.clone())))
+                    .collect(),
+                last: BTreeMap::new(),
+                stale_since: Instant::now(),
+            });
+
+        session.start = session.start.min(timestamp);
+        session.end = session.end.max(timestamp);
+        session.count += 1;
+        for field in &self.last_fields {
+            if let Some(value) = log.get(field) {
+                session.last.insert(field.clone(), value.clone());
+            }
+        }
+        session.stale_since = Instant::now();
+    }
+
+    /// Closes the sessions that have been inactive for the gap.
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let gap = self.gap;
+        let stale = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| session.stale_since.elapsed() >= gap)
+            .map(|(discriminant, _)| discriminant.clone())
+            .collect::<Vec<_>>();
+        for discriminant in stale {
+            if let Some(session) = self.sessions.remove(&discriminant) {
+                emit!(SessionClosed);
+                output.push(session.into_event());
+            }
+        }
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        for (_, session) in self.sessions.drain() {
+            emit!(SessionClosed);
+            output.push(session.into_event());
+        }
+    }
+}
+
+impl TaskTransform for Session {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(me.flush_period);
+        let mut input_stream = Compat01As03::new(input_rx);
+
+        let stream = stream! {
+          loop {
+            let mut output = Vec::new();
+            let done = tokio::select! {
+                _ = flush_stream.next() => {
+                  me.flush_into(&mut output);
+                  false
+                }
+                maybe_event = input_stream.next() => {
+                  match maybe_event {
+                    None => {
+                      me.flush_all_into(&mut output);
+                      true
+                    }
+                    Some(Ok(event)) => {
+                      me.add(&mut output, event);
+                      false
+                    }
+                    Some(Err(())) => panic!("Unexpected error reading channel"),
+                  }
+                }
+            };
+            yield stream::iter(output.into_iter());
+            if done { break }
+          }
+        }
+        .flatten();
+
+        // Needed for compat
+        let try_stream = Box::pin(stream.map::<Result<Event, ()>, _>(Ok));
+
+        Box::new(Compat::new(try_stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use futures::compat::Stream01CompatExt;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SessionConfig>();
+    }
+
+    fn click(user: &str, path: &str, secs: i64) -> Event {
+        let mut event = Event::new_empty_log();
+        let log = event.as_mut_log();
+        log.insert("user_id", user);
+        log.insert("path", path);
+        log.insert("timestamp", Utc.timestamp(1_600_000_000 + secs, 0));
+        event
+    }
+
+    #[tokio::test]
+    async fn summarizes_sessions() {
+        let config = toml::from_str::<SessionConfig>(
+            r#"
+            group_by = ["user_id"]
+            inactivity_gap_secs = 60
+            first_fields = ["path"]
+            last_fields = ["path"]
+            "#,
+        )
+        .unwrap();
+        let transform = config.build().await.unwrap().into_task();
+
+        let clicks = vec![
+            click("jane", "/", 0),
+            click("bob", "/docs", 5),
+            click("jane", "/pricing", 30),
+            click("jane", "/signup", 50),
+            // More than the gap after the previous click.
+            click("jane", "/", 200),
+        ];
+        let in_stream = Box::new(futures01::stream::iter_ok(clicks));
+        let mut output: Vec<Event> = transform
+            .transform(in_stream)
+            .compat()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        output.sort_by_key(|event| {
+            let log = event.as_log();
+            (
+                log["user_id"].to_string_lossy(),
+                log["session.start"].to_string_lossy(),
+            )
+        });
+
+        assert_eq!(output.len(), 3);
+        let bob = output[0].as_log();
+        assert_eq!(bob["session.event_count"], Value::Integer(1));
+        assert_eq!(bob["session.duration_secs"], Value::Float(0.0));
+
+        let jane = output[1].as_log();
+        assert_eq!(jane["user_id"], "jane".into());
+        assert_eq!(jane["session.event_count"], Value::Integer(3));
+        assert_eq!(jane["session.duration_secs"], Value::Float(50.0));
+        assert_eq!(jane["session.first.path"], "/".into());
+        assert_eq!(jane["session.last.path"], "/signup".into());
+        assert_eq!(
+            jane["timestamp"],
+            Value::Timestamp(Utc.timestamp(1_600_000_050, 0))
+        );
+
+        assert_eq!(output[2].as_log()["session.event_count"], Value::Integer(1));
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_group_by() {
+        let config = toml::from_str::<SessionConfig>("group_by = []").unwrap();
+        assert!(config.build().await.is_err());
+    }
+}
@@ -0,0 +1,377 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{SizeGuardEventDiscarded, SizeGuardEventProcessed, SizeGuardOversizedEvent},
+    transforms::{FunctionTransform, Transform, TransformOutputs},
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::collections::BTreeMap;
+
+/// The name of the output receiving oversized events with the `reroute`
+/// action.
+pub const OVERFLOW: &str = "overflow";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`max_bytes` must be greater than zero"))]
+    ZeroMaxBytes,
+    #[snafu(display("`fields` must not be empty for the {:?} action", action))]
+    NoFields { action: Action },
+}
+
+/// What happens to events over `max_bytes`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Shorten `fields`, in order, until the event fits.
+    Truncate,
+    /// Split the first of `fields` across as many events as needed.
+    Split,
+    /// Send the event to the `overflow` output.
+    Reroute,
+}
+
+impl Default for Action {
+    fn default() -> Self {
+        Action::Truncate
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SizeGuardConfig {
+    /// The maximum size of an event, serialized as JSON.
+    pub max_bytes: usize,
+    #[serde(default)]
+    pub action: Action,
+    /// The string fields shortened or split. Defaults to the message.
+    #[serde(default = "default_fields")]
+    pub fields: Vec<String>,
+    /// Set to `true` on truncated events.
+    #[serde(default = "default_truncated_field")]
+    pub truncated_field: String,
+    /// Set to the index and count of the parts of split events.
+    #[serde(default = "default_part_field")]
+    pub part_field: String,
+}
+
+fn default_fields() -> Vec<String> {
+    vec![log_schema().message_key().to_owned()]
+}
+
+fn default_truncated_field() -> String {
+    "truncated".into()
+}
+
+fn default_part_field() -> String {
+    "part".into()
+}
+
+inventory::submit! {
+    TransformDescription::new::<SizeGuardConfig>("size_guard")
+}
+
+impl GenerateConfig for SizeGuardConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"max_bytes = 1048576"#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "size_guard")]
+impl TransformConfig for SizeGuardConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        if self.max_bytes == 0 {
+            return Err(BuildError::ZeroMaxBytes.into());
+        }
+        if self.fields.is_empty() && self.action != Action::Reroute {
+            return Err(BuildError::NoFields {
+                action: self.action,
+            }
+            .into());
+        }
+
+        Ok(Transform::function(SizeGuard {
+            max_bytes: self.max_bytes,
+            action: self.action,
+            fields: self.fields.clone(),
+            truncated_field: self.truncated_field.clone(),
+            part_field: self.part_field.clone(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "size_guard"
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        if self.action == Action::Reroute {
+            vec![OVERFLOW.to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SizeGuard {
+    max_bytes: usize,
+    action: Action,
+    fields: Vec<String>,
+    truncated_field: String,
+    part_field: String,
+}
+
+fn size(event: &Event) -> usize {
+    serde_json::to_vec(event.as_log())
+        .map(|json| json.len())
+        .unwrap_or(0)
+}
+
+/// The length of the character in a JSON string.
+fn escaped_len(c: char) -> usize {
+    match c {
+        '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 2,
+        c if (c as u32) < 0x20 => 6,
+        c => c.len_utf8(),
+    }
+}
+
+/// The length in bytes of the longest prefix of `s` taking at most `budget`
+/// bytes in a JSON string.
+fn prefix_within(s: &str, budget: usize) -> usize {
+    let mut used = 0;
+    for (index, c) in s.char_indices() {
+        used += escaped_len(c);
+        if used > budget {
+            return index;
+        }
+    }
+    s.len()
+}
+
+fn string_field(event: &Event, field: &str) -> Option<String> {
+    match event.as_log().get(field) {
+        Some(value @ Value::Bytes(_)) => Some(value.to_string_lossy()),
+        _ => None,
+    }
+}
+
+impl SizeGuard {
+    /// Guards the event, returning the events passed on, or `Err` with the
+    /// event if it has to be rerouted.
+    fn guard(&self, mut event: Event) -> Result<Vec<Event>, Event> {
+        emit!(SizeGuardEventProcessed);
+
+        let size = size(&event);
+        if size <= self.max_bytes {
+            return Ok(vec![event]);
+        }
+        emit!(SizeGuardOversizedEvent {
+            size,
+            max_bytes: self.max_bytes,
+        });
+
+        let events = match self.action {
+            Action::Reroute => return Err(event),
+            Action::Truncate => self.truncate(&mut event).map(|()| vec![event]),
+            Action::Split => self.split(event),
+        };
+        Ok(events.unwrap_or_else(|| {
+            emit!(SizeGuardEventDiscarded);
+            Vec::new()
+        }))
+    }
+
+    /// Shortens the fields in order, returning `None` if the event still
+    /// doesn't fit.
+    fn truncate(&self, event: &mut Event) -> Option<()> {
+        event.as_mut_log().insert(&self.truncated_field, true);
+        let mut excess = size(event).saturating_sub(self.max_bytes);
+
+        for field in &self.fields {
+            if excess == 0 {
+                break;
+            }
+            let value = match string_field(event, field) {
+                Some(value) => value,
+                None => continue,
+            };
+            let length = value.chars().map(escaped_len).sum::<usize>();
+            let end = prefix_within(&value, length.saturating_sub(excess));
+            let kept = value[..end].chars().map(escaped_len).sum::<usize>();
+            excess = excess.saturating_sub(length - kept);
+            event.as_mut_log().insert(field, value[..end].to_owned());
+        }
+
+        if excess == 0 {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Splits the first field across events, returning `None` if the rest
+    /// of the event doesn't leave room for it.
+    fn split(&self, mut event: Event) -> Option<Vec<Event>> {
+        let field = &self.fields[0];
+        let value = string_field(&event, field)?;
+
+        // Leave room for the largest possible part numbers.
+        let mut part = BTreeMap::new();
+        part.insert("index".to_owned(), Value::Integer(i64::MAX));
+        part.insert("count".to_owned(), Value::Integer(i64::MAX));
+        event
+            .as_mut_log()
+            .insert(&self.part_field, Value::Map(part));
+        event.as_mut_log().insert(field, "");
+        let budget = self
+            .max_bytes
+            .checked_sub(size(&event))
+            .filter(|&budget| budget > 0)?;
+
+        let mut chunks = Vec::new();
+        let mut rest = value.as_str();
+        while !rest.is_empty() {
+            let end = prefix_within(rest, budget);
+            if end == 0 {
+                // A single character doesn't fit.
+                return None;
+            }
+            chunks.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+
+        let count = chunks.len();
+        Some(
+            chunks
+                .into_iter()
+                .enumerate()
+                .map(|(index, chunk)| {
+                    let mut event = event.clone();
+                    let log = event.as_mut_log();
+                    log.insert(field, chunk.to_owned());
+                    log.insert(format!("{}.index", self.part_field), index as i64);
+                    log.insert(format!("{}.count", self.part_field), count as i64);
+                    event
+                })
+                .collect(),
+        )
+    }
+}
+
+impl FunctionTransform for SizeGuard {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        match self.guard(event) {
+            Ok(events) => output.extend(events),
+            Err(_) => emit!(SizeGuardEventDiscarded),
+        }
+    }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        match self.guard(event) {
+            Ok(events) => events.into_iter().for_each(|event| outputs.push(event)),
+            Err(event) => outputs.push_named(OVERFLOW, event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SizeGuardConfig>();
+    }
+
+    fn guard(max_bytes: usize, action: Action, fields: &[&str]) -> SizeGuard {
+        SizeGuard {
+            max_bytes,
+            action,
+            fields: fields.iter().map(|&field| field.into()).collect(),
+            truncated_field: default_truncated_field(),
+            part_field: default_part_field(),
+        }
+    }
+
+    fn event(message: &str) -> Event {
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("message", message);
+        event.as_mut_log().insert("stack", "at main\nat run");
+        event
+    }
+
+    #[test]
+    fn passes_small_events() {
+        let mut guard = guard(100, Action::Truncate, &["message"]);
+        let input = event("hello");
+
+        assert_eq!(guard.transform_one(input.clone()), Some(input));
+    }
+
+    #[test]
+    fn truncates_fields_in_order() {
+        let mut guard = guard(80, Action::Truncate, &["stack", "message"]);
+
+        let output = guard.transform_one(event(&"é".repeat(40))).unwrap();
+
+        assert!(size(&output) <= 80);
+        assert_eq!(output.as_log()["stack"], "".into());
+        assert_eq!(output.as_log()["truncated"], true.into());
+        let message = output.as_log()["message"].to_string_lossy();
+        assert!(!message.is_empty() && "é".repeat(40).starts_with(&message));
+    }
+
+    #[test]
+    fn drops_events_that_cannot_fit() {
+        let mut guard = guard(30, Action::Truncate, &["message"]);
+
+        assert_eq!(guard.transform_one(event(&"x".repeat(100))), None);
+    }
+
+    #[test]
+    fn splits_field_across_events() {
+        let mut guard = guard(120, Action::Split, &["message"]);
+        let message = "line \"quoted\" ".repeat(10);
+
+        let mut output = Vec::new();
+        guard.transform(&mut output, event(&message));
+
+        assert!(output.len() > 1);
+        let mut joined = String::new();
+        for (index, event) in output.iter().enumerate() {
+            assert!(size(event) <= 120);
+            assert_eq!(event.as_log()["part.index"], Value::Integer(index as i64));
+            assert_eq!(
+                event.as_log()["part.count"],
+                Value::Integer(output.len() as i64)
+            );
+            assert_eq!(event.as_log()["stack"], "at main\nat run".into());
+            joined.push_str(&event.as_log()["message"].to_string_lossy());
+        }
+        assert_eq!(joined, message);
+    }
+
+    #[test]
+    fn reroutes_oversized_events() {
+        let mut guard = guard(50, Action::Reroute, &[]);
+        let input = event(&"x".repeat(100));
+
+        let mut outputs = TransformOutputs::new("guard");
+        guard.transform_outputs(&mut outputs, input.clone());
+        guard.transform_outputs(&mut outputs, event("ok"));
+
+        assert_eq!(outputs.primary, vec![event("ok")]);
+        assert_eq!(outputs.named[OVERFLOW], vec![input]);
+    }
+}
@@ -0,0 +1,462 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{discriminant::Discriminant, Event, LogEvent, Value},
+    internal_events::{SqlEventDiscarded, SqlEventProcessed},
+    transforms::{FunctionTransform, TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::Utc;
+use futures::{
+    compat::{Compat, Compat01As03},
+    stream, StreamExt,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::time::Duration;
+
+mod parser;
+mod query;
+
+use query::{as_number, AggregateFunction, Projection, Query, Selection};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("{}", source))]
+    InvalidQuery { source: parser::ParseError },
+    #[snafu(display("`*` can't be selected with aggregates"))]
+    StarWithAggregates,
+    #[snafu(display("{:?} must be in GROUP BY to be selected with aggregates", field))]
+    NotGrouped { field: String },
+    #[snafu(display("`window_secs` must be greater than zero"))]
+    ZeroWindow,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SqlConfig {
+    /// The `SELECT` statement run over the events.
+    pub query: String,
+    /// The length of the windows aggregates are computed over.
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+}
+
+const fn default_window_secs() -> u64 {
+    10
+}
+
+inventory::submit! {
+    TransformDescription::new::<SqlConfig>("sql")
+}
+
+impl GenerateConfig for SqlConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"query = "SELECT * FROM events WHERE status >= 500""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sql")]
+impl TransformConfig for SqlConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let query = parser::parse(&self.query).context(InvalidQuery)?;
+        if !query.is_aggregate() {
+            return Ok(Transform::function(Sql { query }));
+        }
+
+        if self.window_secs == 0 {
+            return Err(BuildError::ZeroWindow.into());
+        }
+        for projection in &query.projections {
+            match projection {
+                Projection::All => return Err(BuildError::StarWithAggregates.into()),
+                Projection::Selected {
+                    selection: Selection::Field(field),
+                    ..
+                } if !query.group_by.contains(field) => {
+                    return Err(BuildError::NotGrouped {
+                        field: field.clone(),
+                    }
+                    .into())
+                }
+                _ => (),
+            }
+        }
+
+        Ok(Transform::task(SqlAggregate {
+            query,
+            window: Duration::from_secs(self.window_secs),
+            groups: IndexMap::new(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "sql"
+    }
+}
+
+/// Runs queries without aggregates, event by event.
+#[derive(Clone, Debug)]
+pub struct Sql {
+    query: Query,
+}
+
+impl FunctionTransform for Sql {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        emit!(SqlEventProcessed);
+
+        let log = event.as_log();
+        if !self.query.matches(log) {
+            emit!(SqlEventDiscarded);
+            return;
+        }
+
+        let keep_all = self
+            .query
+            .projections
+            .iter()
+            .any(|projection| matches!(projection, Projection::All));
+        let mut projected = if keep_all {
+            log.clone()
+        } else {
+            LogEvent::default()
+        };
+        for projection in &self.query.projections {
+            if let Projection::Selected {
+                selection: Selection::Field(field),
+                alias,
+            } = projection
+            {
+                match (log.get(field), alias) {
+                    (Some(value), Some(alias)) => {
+                        projected.insert(alias, value.clone());
+                    }
+                    (Some(value), None) if !keep_all => {
+                        projected.insert(field, value.clone());
+                    }
+                    _ => (),
+                }
+            }
+        }
+        output.push(Event::Log(projected));
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Accumulator {
+    Count(i64),
+    Sum(f64),
+    Avg { sum: f64, count: u64 },
+    Min(Option<f64>),
+    Max(Option<f64>),
+}
+
+impl Accumulator {
+    fn new(function: AggregateFunction) -> Self {
+        match function {
+            AggregateFunction::Count => Accumulator::Count(0),
+            AggregateFunction::Sum => Accumulator::Sum(0.0),
+            AggregateFunction::Avg => Accumulator::Avg { sum: 0.0, count: 0 },
+            AggregateFunction::Min => Accumulator::Min(None),
+            AggregateFunction::Max => Accumulator::Max(None),
+        }
+    }
+
+    /// Adds an event's value, or the event itself for `count(*)`. Values
+    /// that aren't numbers are only counted.
+    fn add(&mut self, value: Option<&Value>) {
+        if let Accumulator::Count(count) = self {
+            *count += 1;
+            return;
+        }
+        let number = match value.and_then(as_number) {
+            Some(number) => number,
+            None => return,
+        };
+        match self {
+            Accumulator::Count(_) => (),
+            Accumulator::Sum(sum) => *sum += number,
+            Accumulator::Avg { sum, count } => {
+                *sum += number;
+                *count += 1;
+            }
+            Accumulator::Min(min) => *min = Some(min.map_or(number, |min| min.min(number))),
+            Accumulator::Max(max) => *max = Some(max.map_or(number, |max| max.max(number))),
+        }
+    }
+
+    fn value(&self) -> Value {
+        match self {
+            Accumulator::Count(count) => Value::Integer(*count),
+            Accumulator::Sum(sum) => Value::Float(*sum),
+            Accumulator::Avg { count: 0, .. } => Value::Null,
+            Accumulator::Avg { sum, count } => Value::Float(sum / *count as f64),
+            Accumulator::Min(value) | Accumulator::Max(value) => {
+                value.map_or(Value::Null, Value::Float)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Group {
+    /// The values of the `GROUP BY` fields.
+    values: Vec<Option<Value>>,
+    accumulators: Vec<Accumulator>,
+}
+
+/// Runs queries with aggregates over tumbling windows.
+pub struct SqlAggregate {
+    query: Query,
+    window: Duration,
+    groups: IndexMap<Discriminant, Group>,
+}
+
+impl SqlAggregate {
+    fn add(&mut self, event: Event) {
+        emit!(SqlEventProcessed);
+
+        let log = event.as_log();
+        if !self.query.matches(log) {
+            emit!(SqlEventDiscarded);
+            return;
+        }
+
+        let query = &self.query;
+        let group = self
+            .groups
+            .entry(Discriminant::from_log_event(log, &query.group_by))
+            .or_insert_with(|| Group {
+                values: query
+                    .group_by
+                    .iter()
+                    .map(|field| log.get(field).cloned())
+                    .collect(),
+                accumulators: query
+                    .projections
+                    .iter()
+                    .filter_map(|projection| match projection {
+                        Projection::Selected {
+                            selection: Selection::Aggregate { function, .. },
+                            ..
+                        } => Some(Accumulator::new(*function)),
+                        _ => None,
+                    })
+                    .collect(),
+            });
+
+        let fields = query
+            .projections
+            .iter()
+            .filter_map(|projection| match projection {
+                Projection::Selected {
+                    selection: Selection::Aggregate { field, function },
+                    ..
+                } => Some((field, function)),
+                _ => None,
+            });
+        for (accumulator, (field, function)) in group.accumulators.iter_mut().zip(fields) {
+            match field {
+                // `count(field)` only counts events where it's set.
+                Some(field) => match log.get(field) {
+                    None | Some(Value::Null) if *function == AggregateFunction::Count => (),
+                    value => accumulator.add(value),
+                },
+                None => accumulator.add(None),
+            }
+        }
+    }
+
+    /// Emits a row for each group of the window.
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let timestamp = Utc::now();
+        for (_, group) in self.groups.drain(..) {
+            let mut log = LogEvent::default();
+            log.insert(log_schema().timestamp_key(), timestamp);
+
+            let mut accumulators = group.accumulators.iter();
+            for projection in &self.query.projections {
+                if let Projection::Selected { selection, alias } = projection {
+                    let name = alias.clone().unwrap_or_else(|| selection.default_name());
+                    let value = match selection {
+                        Selection::Field(field) => self
+                            .query
+                            .group_by
+                            .iter()
+                            .position(|grouped| grouped == field)
+                            .and_then(|index| group.values[index].clone()),
+                        Selection::Aggregate { .. } => accumulators.next().map(Accumulator::value),
+                    };
+                    if let Some(value) = value {
+                        log.insert(name, value);
+                    }
+                }
+            }
+            output.push(Event::Log(log));
+        }
+    }
+}
+
+impl TaskTransform for SqlAggregate {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(me.window);
+        let mut input_stream = Compat01As03::new(input_rx);
+
+        let stream = stream! {
+          loop {
+            let mut output = Vec::new();
+            let done = tokio::select! {
+                _ = flush_stream.next() => {
+                  me.flush_into(&mut output);
+                  false
+                }
+                maybe_event = input_stream.next() => {
+                  match maybe_event {
+                    None => {
+                      me.flush_into(&mut output);
+                      true
+                    }
+                    Some(Ok(event)) => {
+                      me.add(event);
+                      false
+                    }
+                    Some(Err(())) => panic!("Unexpected error reading channel"),
+                  }
+                }
+            };
+            yield stream::iter(output.into_iter());
+            if done { break }
+          }
+        }
+        .flatten();
+
+        // Needed for compat
+        let try_stream = Box::pin(stream.map::<Result<Event, ()>, _>(Ok));
+
+        Box::new(Compat::new(try_stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::compat::Stream01CompatExt;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SqlConfig>();
+    }
+
+    fn config(query: &str) -> SqlConfig {
+        SqlConfig {
+            query: query.into(),
+            window_secs: default_window_secs(),
+        }
+    }
+
+    fn events() -> Vec<Event> {
+        vec![
+            serde_json::json!({"service": "api", "status": 200, "duration": 10, "user": {"id": 1}}),
+            serde_json::json!({"service": "api", "status": 503, "duration": 30}),
+            serde_json::json!({"service": "web", "status": "500", "duration": 5}),
+            serde_json::json!({"service": "api", "status": 502, "duration": "20"}),
+        ]
+        .into_iter()
+        .map(|event| Event::try_from(event).unwrap())
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn filters_and_projects_events() {
+        let mut transform =
+            config("SELECT service, user.id AS user FROM events WHERE status < 500")
+                .build()
+                .await
+                .unwrap();
+        let transform = transform.as_function();
+
+        let output = events()
+            .into_iter()
+            .filter_map(|event| transform.transform_one(event))
+            .collect::<Vec<_>>();
+
+        let expected = Event::try_from(serde_json::json!({"service": "api", "user": 1})).unwrap();
+        assert_eq!(output, vec![expected]);
+    }
+
+    #[tokio::test]
+    async fn keeps_whole_events_with_star() {
+        let mut transform = config("SELECT *, duration AS took WHERE service = 'web'")
+            .build()
+            .await
+            .unwrap();
+
+        let output = transform
+            .as_function()
+            .transform_one(events().remove(2))
+            .unwrap();
+
+        assert_eq!(output.as_log()["status"], "500".into());
+        assert_eq!(output.as_log()["took"], Value::Integer(5));
+        assert_eq!(output.as_log()["duration"], Value::Integer(5));
+    }
+
+    #[tokio::test]
+    async fn aggregates_groups() {
+        let transform = config(
+            "SELECT service, count(*) AS errors, avg(duration), max(duration) \
+             WHERE status >= 500 GROUP BY service",
+        )
+        .build()
+        .await
+        .unwrap()
+        .into_task();
+
+        let in_stream = Box::new(futures01::stream::iter_ok(events()));
+        let output: Vec<Event> = transform
+            .transform(in_stream)
+            .compat()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 2);
+        let api = output[0].as_log();
+        assert_eq!(api["service"], "api".into());
+        assert_eq!(api["errors"], Value::Integer(2));
+        assert_eq!(api["avg_duration"], Value::Float(25.0));
+        assert_eq!(api["max_duration"], Value::Float(30.0));
+        let web = output[1].as_log();
+        assert_eq!(web["service"], "web".into());
+        assert_eq!(web["errors"], Value::Integer(1));
+    }
+
+    #[tokio::test]
+    async fn rejects_ungrouped_fields() {
+        for query in &[
+            "SELECT service, count(*)",
+            "SELECT *, count(*) GROUP BY service",
+            "SELECT service FROM",
+        ] {
+            assert!(config(query).build().await.is_err(), "{:?} built", query);
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use super::query::{AggregateFunction, CompareOp, Expr, Operand, Projection, Query, Selection};
+use crate::event::Value;
+use pest::{
+    iterators::{Pair, Pairs},
+    Parser,
+};
+use regex::Regex;
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+pub enum ParseError {
+    #[snafu(display("Invalid SQL statement: {}", message))]
+    Syntax { message: String },
+    #[snafu(display("Invalid number {:?}", number))]
+    InvalidNumber { number: String },
+}
+
+#[derive(Parser)]
+#[grammar = "./transforms/sql/grammar.pest"]
+struct SqlParser;
+
+pub fn parse(statement: &str) -> Result<Query, ParseError> {
+    let mut pairs =
+        SqlParser::parse(Rule::statement, statement).map_err(|error| ParseError::Syntax {
+            message: error.to_string(),
+        })?;
+    let select = pairs.next().expect("statement has a select");
+
+    let mut query = Query::default();
+    for pair in select.into_inner() {
+        match pair.as_rule() {
+            Rule::projections => {
+                query.projections = pair
+                    .into_inner()
+                    .map(projection)
+                    .collect::<Result<_, _>>()?;
+            }
+            Rule::where_clause => {
+                let expr = significant(pair.into_inner())
+                    .next()
+                    .expect("where has a condition");
+                query.filter = Some(expr_from_pair(expr)?);
+            }
+            Rule::group_by => query.group_by = significant(pair.into_inner()).map(field).collect(),
+            // The table name is ignored, as there's only the event stream.
+            _ => (),
+        }
+    }
+    Ok(query)
+}
+
+/// Skips the keywords, which are only there for the grammar.
+fn significant(pairs: Pairs<Rule>) -> impl Iterator<Item = Pair<Rule>> {
+    pairs.filter(|pair| {
+        !matches!(
+            pair.as_rule(),
+            Rule::kw_select
+                | Rule::kw_from
+                | Rule::kw_where
+                | Rule::kw_group
+                | Rule::kw_by
+                | Rule::kw_as
+                | Rule::kw_and
+                | Rule::kw_or
+                | Rule::kw_is
+                | Rule::kw_null
+                | Rule::kw_in
+                | Rule::kw_like
+        )
+    })
+}
+
+fn projection(pair: Pair<Rule>) -> Result<Projection, ParseError> {
+    let mut inner = pair.into_inner();
+    let selected = inner.next().expect("projection has a selection");
+    let alias = inner.next().map(|alias| {
+        name(
+            significant(alias.into_inner())
+                .next()
+                .expect("alias has a name"),
+        )
+    });
+
+    let selection = match selected.as_rule() {
+        Rule::star => return Ok(Projection::All),
+        Rule::field => Selection::Field(field(selected)),
+        Rule::aggregate => {
+            let mut inner = selected.into_inner();
+            let function = match inner
+                .next()
+                .expect("aggregate has a function")
+                .as_str()
+                .to_lowercase()
+                .as_str()
+            {
+                "count" => AggregateFunction::Count,
+                "sum" => AggregateFunction::Sum,
+                "avg" => AggregateFunction::Avg,
+                "min" => AggregateFunction::Min,
+                "max" => AggregateFunction::Max,
+                function => unreachable!("unexpected aggregate function {:?}", function),
+            };
+            let argument = inner.next().expect("aggregate has an argument");
+            let field = match argument.as_rule() {
+                Rule::star => None,
+                _ => Some(field(argument)),
+            };
+            Selection::Aggregate { function, field }
+        }
+        rule => unreachable!("unexpected projection {:?}", rule),
+    };
+    Ok(Projection::Selected { selection, alias })
+}
+
+fn expr_from_pair(pair: Pair<Rule>) -> Result<Expr, ParseError> {
+    match pair.as_rule() {
+        Rule::expr => {
+            let mut exprs = significant(pair.into_inner())
+                .map(expr_from_pair)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(if exprs.len() == 1 {
+                exprs.remove(0)
+            } else {
+                Expr::Or(exprs)
+            })
+        }
+        Rule::conjunction => {
+            let mut exprs = significant(pair.into_inner())
+                .map(expr_from_pair)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(if exprs.len() == 1 {
+                exprs.remove(0)
+            } else {
+                Expr::And(exprs)
+            })
+        }
+        Rule::negation => {
+            let mut negations = 0;
+            let mut expr = None;
+            for pair in pair.into_inner() {
+                match pair.as_rule() {
+                    Rule::not => negations += 1,
+                    _ => expr = Some(expr_from_pair(pair)?),
+                }
+            }
+            let expr = expr.expect("negation has a predicate");
+            Ok(if negations % 2 == 1 {
+                Expr::Not(Box::new(expr))
+            } else {
+                expr
+            })
+        }
+        Rule::group => expr_from_pair(pair.into_inner().next().expect("group has an expression")),
+        Rule::is_null => {
+            let mut inner = significant(pair.into_inner());
+            let field = field(inner.next().expect("is null has a field"));
+            let negated = inner.next().is_some();
+            Ok(Expr::IsNull { field, negated })
+        }
+        Rule::in_list => {
+            let mut field_name = None;
+            let mut negated = false;
+            let mut values = Vec::new();
+            for pair in significant(pair.into_inner()) {
+                match pair.as_rule() {
+                    Rule::field => field_name = Some(field(pair)),
+                    Rule::not => negated = true,
+                    _ => values.push(literal(pair)?),
+                }
+            }
+            Ok(Expr::In {
+                field: field_name.expect("in has a field"),
+                values,
+                negated,
+            })
+        }
+        Rule::like => {
+            let mut field_name = None;
+            let mut negated = false;
+            let mut pattern = String::new();
+            for pair in significant(pair.into_inner()) {
+                match pair.as_rule() {
+                    Rule::field => field_name = Some(field(pair)),
+                    Rule::not => negated = true,
+                    _ => pattern = string(pair),
+                }
+            }
+            Ok(Expr::Like {
+                field: field_name.expect("like has a field"),
+                pattern: like_regex(&pattern),
+                negated,
+            })
+        }
+        Rule::comparison => {
+            let mut inner = pair.into_inner();
+            let left = operand(inner.next().expect("comparison has a left operand"))?;
+            let op = match inner.next().expect("comparison has an operator").as_str() {
+                "=" => CompareOp::Eq,
+                "!=" | "<>" => CompareOp::Ne,
+                "<" => CompareOp::Lt,
+                "<=" => CompareOp::Le,
+                ">" => CompareOp::Gt,
+                ">=" => CompareOp::Ge,
+                op => unreachable!("unexpected operator {:?}", op),
+            };
+            let right = operand(inner.next().expect("comparison has a right operand"))?;
+            Ok(Expr::Compare { left, op, right })
+        }
+        Rule::field => Ok(Expr::Truthy(field(pair))),
+        rule => unreachable!("unexpected expression {:?}", rule),
+    }
+}
+
+fn operand(pair: Pair<Rule>) -> Result<Operand, ParseError> {
+    match pair.as_rule() {
+        Rule::field => Ok(Operand::Field(field(pair))),
+        _ => literal(pair).map(Operand::Literal),
+    }
+}
+
+fn literal(pair: Pair<Rule>) -> Result<Value, ParseError> {
+    match pair.as_rule() {
+        Rule::string => Ok(Value::from(string(pair))),
+        Rule::number => {
+            let number = pair.as_str();
+            if number.contains('.') {
+                number.parse::<f64>().map(Value::Float).ok()
+            } else {
+                number.parse::<i64>().map(Value::Integer).ok()
+            }
+            .ok_or_else(|| ParseError::InvalidNumber {
+                number: number.to_owned(),
+            })
+        }
+        Rule::boolean => Ok(Value::Boolean(pair.as_str().eq_ignore_ascii_case("true"))),
+        Rule::null => Ok(Value::Null),
+        rule => unreachable!("unexpected literal {:?}", rule),
+    }
+}
+
+fn string(pair: Pair<Rule>) -> String {
+    let quoted = pair.as_str();
+    quoted[1..quoted.len() - 1].replace("''", "'")
+}
+
+fn name(pair: Pair<Rule>) -> String {
+    let name = pair.as_str();
+    match pair.as_rule() {
+        Rule::quoted_name => name[1..name.len() - 1].to_owned(),
+        _ => name.to_owned(),
+    }
+}
+
+/// Turns a field's names into an event path.
+fn field(pair: Pair<Rule>) -> String {
+    pair.into_inner()
+        .map(|pair| name(pair).replace(".", "\\."))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Turns a `LIKE` pattern, where `%` matches any characters and `_` a single
+/// one, into an anchored regex.
+fn like_regex(pattern: &str) -> Regex {
+    let mut regex = String::from("(?s)^");
+    for c in pattern.chars() {
+        match c {
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).expect("escaped pattern is a valid regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_projections_and_filters() {
+        let query = parse(
+            r#"select status, "user.name" AS user, request.path
+               FROM events
+               WHERE (status >= 500 OR level = 'error') AND NOT path LIKE '/health%'"#,
+        )
+        .unwrap();
+
+        assert_eq!(query.projections.len(), 3);
+        assert!(matches!(
+            &query.projections[1],
+            Projection::Selected { selection: Selection::Field(field), alias: Some(alias) }
+                if field == "user\\.name" && alias == "user"
+        ));
+        assert!(matches!(
+            &query.projections[2],
+            Projection::Selected { selection: Selection::Field(field), alias: None }
+                if field == "request.path"
+        ));
+        match query.filter.unwrap() {
+            Expr::And(exprs) => {
+                assert!(matches!(&exprs[0], Expr::Or(or) if or.len() == 2));
+                assert!(matches!(&exprs[1], Expr::Not(_)));
+            }
+            expr => panic!("unexpected filter {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn parses_aggregates() {
+        let query =
+            parse("SELECT service, count(*), avg(duration) AS latency FROM logs GROUP BY service;")
+                .unwrap();
+
+        assert_eq!(query.group_by, vec!["service"]);
+        assert!(matches!(
+            &query.projections[1],
+            Projection::Selected {
+                selection: Selection::Aggregate {
+                    function: AggregateFunction::Count,
+                    field: None
+                },
+                alias: None,
+            }
+        ));
+        assert!(matches!(
+            &query.projections[2],
+            Projection::Selected {
+                selection: Selection::Aggregate { function: AggregateFunction::Avg, field: Some(_) },
+                alias: Some(_),
+            }
+        ));
+    }
+
+    #[test]
+    fn parses_predicates() {
+        let query = parse(
+            "SELECT * WHERE user IS NOT NULL AND status NOT IN (200, 204) AND 'x' <> name AND secure",
+        )
+        .unwrap();
+
+        match query.filter.unwrap() {
+            Expr::And(exprs) => {
+                assert!(matches!(&exprs[0], Expr::IsNull { negated: true, .. }));
+                assert!(
+                    matches!(&exprs[1], Expr::In { negated: true, values, .. } if values.len() == 2)
+                );
+                assert!(matches!(&exprs[2], Expr::Compare { op: CompareOp::Ne, .. }));
+                assert!(matches!(&exprs[3], Expr::Truthy(field) if field == "secure"));
+            }
+            expr => panic!("unexpected filter {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_statements() {
+        for statement in &[
+            "SELECT",
+            "SELECT status WHERE",
+            "DELETE FROM events",
+            "SELECT status FROM events WHERE status = 'open",
+            "SELECT select FROM events",
+        ] {
+            assert!(parse(statement).is_err(), "{:?} parsed", statement);
+        }
+    }
+}
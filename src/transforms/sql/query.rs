@@ -0,0 +1,250 @@
+use crate::event::{LogEvent, Value};
+use regex::Regex;
+use std::cmp::Ordering;
+
+/// A parsed `SELECT` statement.
+#[derive(Clone, Debug, Default)]
+pub struct Query {
+    pub projections: Vec<Projection>,
+    pub filter: Option<Expr>,
+    pub group_by: Vec<String>,
+}
+
+impl Query {
+    pub fn is_aggregate(&self) -> bool {
+        self.projections.iter().any(|projection| {
+            matches!(
+                projection,
+                Projection::Selected {
+                    selection: Selection::Aggregate { .. },
+                    ..
+                }
+            )
+        }) || !self.group_by.is_empty()
+    }
+
+    pub fn matches(&self, log: &LogEvent) -> bool {
+        self.filter.as_ref().map_or(true, |filter| filter.eval(log))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Projection {
+    /// `*`, the whole event.
+    All,
+    Selected {
+        selection: Selection,
+        alias: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub enum Selection {
+    Field(String),
+    /// An aggregate of a field, or of the events for `count(*)`.
+    Aggregate {
+        function: AggregateFunction,
+        field: Option<String>,
+    },
+}
+
+impl Selection {
+    /// The field the selection is written to without an alias.
+    pub fn default_name(&self) -> String {
+        match self {
+            Selection::Field(field) => field.clone(),
+            Selection::Aggregate { function, field } => match field {
+                Some(field) => format!(
+                    "{}_{}",
+                    function.name(),
+                    field.replace("\\.", "_").replace('.', "_")
+                ),
+                None => function.name().to_owned(),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    fn name(self) -> &'static str {
+        match self {
+            AggregateFunction::Count => "count",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug)]
+pub enum Operand {
+    Field(String),
+    Literal(Value),
+}
+
+impl Operand {
+    fn resolve<'a>(&'a self, log: &'a LogEvent) -> Option<&'a Value> {
+        match self {
+            Operand::Field(field) => log.get(field),
+            Operand::Literal(value) => Some(value),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Expr {
+    Or(Vec<Expr>),
+    And(Vec<Expr>),
+    Not(Box<Expr>),
+    Compare {
+        left: Operand,
+        op: CompareOp,
+        right: Operand,
+    },
+    IsNull {
+        field: String,
+        negated: bool,
+    },
+    In {
+        field: String,
+        values: Vec<Value>,
+        negated: bool,
+    },
+    Like {
+        field: String,
+        pattern: Regex,
+        negated: bool,
+    },
+    /// A bare field, which is true when it's `true`.
+    Truthy(String),
+}
+
+impl Expr {
+    pub fn eval(&self, log: &LogEvent) -> bool {
+        match self {
+            Expr::Or(exprs) => exprs.iter().any(|expr| expr.eval(log)),
+            Expr::And(exprs) => exprs.iter().all(|expr| expr.eval(log)),
+            Expr::Not(expr) => !expr.eval(log),
+            Expr::Compare { left, op, right } => match (left.resolve(log), right.resolve(log)) {
+                (Some(left), Some(right)) => {
+                    compare(left, right).map_or(false, |ordering| match op {
+                        CompareOp::Eq => ordering == Ordering::Equal,
+                        CompareOp::Ne => ordering != Ordering::Equal,
+                        CompareOp::Lt => ordering == Ordering::Less,
+                        CompareOp::Le => ordering != Ordering::Greater,
+                        CompareOp::Gt => ordering == Ordering::Greater,
+                        CompareOp::Ge => ordering != Ordering::Less,
+                    })
+                }
+                _ => false,
+            },
+            Expr::IsNull { field, negated } => {
+                let null = matches!(log.get(field), None | Some(Value::Null));
+                null != *negated
+            }
+            Expr::In {
+                field,
+                values,
+                negated,
+            } => match log.get(field) {
+                Some(value) => {
+                    let found = values
+                        .iter()
+                        .any(|candidate| compare(value, candidate) == Some(Ordering::Equal));
+                    found != *negated
+                }
+                None => false,
+            },
+            Expr::Like {
+                field,
+                pattern,
+                negated,
+            } => match log.get(field) {
+                Some(value @ Value::Bytes(_)) => {
+                    pattern.is_match(&value.to_string_lossy()) != *negated
+                }
+                _ => false,
+            },
+            Expr::Truthy(field) => matches!(log.get(field), Some(Value::Boolean(true))),
+        }
+    }
+}
+
+/// Reads a value as a number. Strings holding numbers count, as fields
+/// parsed out of text usually are.
+pub fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(integer) => Some(*integer as f64),
+        Value::Float(float) => Some(*float),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Compares two values like SQL does: `NULL` isn't comparable to anything,
+/// and neither are values of unrelated types.
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Null, _) | (_, Value::Null) => None,
+        (Value::Bytes(left), Value::Bytes(right)) => Some(left.cmp(right)),
+        (Value::Boolean(left), Value::Boolean(right)) => Some(left.cmp(right)),
+        (Value::Timestamp(left), Value::Timestamp(right)) => Some(left.cmp(right)),
+        (left, right) => as_number(left)?.partial_cmp(&as_number(right)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::parse;
+    use crate::event::Event;
+    use std::convert::TryFrom;
+
+    fn matches(filter: &str, event: serde_json::Value) -> bool {
+        let query = parse(&format!("SELECT * WHERE {}", filter)).unwrap();
+        query.matches(Event::try_from(event).unwrap().as_log())
+    }
+
+    #[test]
+    fn evaluates_filters() {
+        let event = serde_json::json!({
+            "status": "503",
+            "duration": 1.5,
+            "path": "/api/users",
+            "secure": true,
+            "user": null,
+        });
+
+        assert!(matches("status >= 500", event.clone()));
+        assert!(matches("status = '503'", event.clone()));
+        assert!(matches("duration < 2 AND secure", event.clone()));
+        assert!(matches("path LIKE '/api/%' OR status = 200", event.clone()));
+        assert!(matches("status IN (502, 503)", event.clone()));
+        assert!(matches("user IS NULL AND missing IS NULL", event.clone()));
+        assert!(matches("NOT (status < 500)", event.clone()));
+
+        assert!(!matches("path LIKE '/api'", event.clone()));
+        assert!(!matches("user = NULL", event.clone()));
+        assert!(!matches("missing != 1", event.clone()));
+        assert!(!matches("path > 1", event));
+    }
+}
@@ -40,7 +40,8 @@ pub enum Mode {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct BloomFilterConfig {
-    #[serde(default = "default_cache_size")]
+    /// Formerly documented as `cache_size_per_tag`.
+    #[serde(default = "default_cache_size", alias = "cache_size_per_tag")]
     pub cache_size_per_key: usize,
 }
 
@@ -66,9 +67,13 @@ fn default_value_limit() -> u32 {
 }
 
 fn default_cache_size() -> usize {
-    5000 * 1024 // 5KB
+    5000 * 1024 // 5MB
 }
 
+/// Bloom filters of large caches get an optimal number of hashes in the
+/// hundreds, while a few already keep false positives negligible.
+const MAX_NUM_HASHES: u32 = 8;
+
 inventory::submit! {
     TransformDescription::new::<TagCardinalityLimitConfig>("tag_cardinality_limit")
 }
@@ -133,8 +138,9 @@ impl TagValueSet {
                 num_elements: 0,
             },
             Mode::Probabilistic(config) => {
-                let num_bits = config.cache_size_per_key / 8; // Convert bytes to bits
-                let num_hashes = bloom::optimal_num_hashes(num_bits, value_limit);
+                let num_bits = config.cache_size_per_key * 8; // Convert bytes to bits
+                let num_hashes =
+                    bloom::optimal_num_hashes(num_bits, value_limit).min(MAX_NUM_HASHES);
 
                 Self {
                     storage: TagValueSetStorage::Bloom(BloomFilter::with_size(
@@ -214,14 +220,28 @@ impl TagCardinalityLimit {
         }
     }
 
+    /// Checks whether the value would be rejected for this key, without
+    /// recording it.
+    fn tag_limit_exceeded(&self, key: &str, value: Cow<'_, String>) -> bool {
+        match self.accepted_tags.get(key) {
+            Some(tag_value_set) => {
+                !tag_value_set.contains(value)
+                    && tag_value_set.len() >= self.config.value_limit as usize
+            }
+            None => false,
+        }
+    }
+
     fn transform_one(&mut self, mut event: Event) -> Option<Event> {
         emit!(TagCardinalityLimitEventProcessed);
         match event.as_mut_metric().tags {
             Some(ref mut tags_map) => {
                 match self.config.limit_exceeded_action {
                     LimitExceededAction::DropEvent => {
-                        for (key, value) in tags_map {
-                            if !self.try_accept_tag(key, Cow::Borrowed(value)) {
+                        // Check every tag before accepting any, so the values of
+                        // dropped events don't count towards the limits.
+                        for (key, value) in tags_map.iter() {
+                            if self.tag_limit_exceeded(key, Cow::Borrowed(value)) {
                                 emit!(TagCardinalityLimitRejectingEvent {
                                     tag_key: &key,
                                     tag_value: &value,
@@ -229,6 +249,9 @@ impl TagCardinalityLimit {
                                 return None;
                             }
                         }
+                        for (key, value) in tags_map.iter() {
+                            self.try_accept_tag(key, Cow::Borrowed(value));
+                        }
                     }
                     LimitExceededAction::DropTag => {
                         let mut to_delete = Vec::new();
@@ -278,6 +301,21 @@ mod tests {
         crate::test_util::test_generate_config::<TagCardinalityLimitConfig>();
     }
 
+    #[test]
+    fn parses_cache_size_per_tag() {
+        let config: TagCardinalityLimitConfig = toml::from_str(
+            r#"
+            mode = "probabilistic"
+            cache_size_per_tag = 1024
+            "#,
+        )
+        .unwrap();
+        match config.mode {
+            Mode::Probabilistic(config) => assert_eq!(config.cache_size_per_key, 1024),
+            Mode::Exact => panic!("Expected the probabilistic mode"),
+        }
+    }
+
     fn make_metric(tags: BTreeMap<String, String>) -> Event {
         Event::Metric(Metric {
             name: "event".into(),
@@ -346,6 +384,51 @@ mod tests {
         assert_eq!(None, new_event3);
     }
 
+    #[test]
+    fn tag_cardinality_limit_drop_event_keeps_values_of_dropped_events_hashset() {
+        drop_event_keeps_values_of_dropped_events(make_transform_hashset(
+            1,
+            LimitExceededAction::DropEvent,
+        ));
+    }
+
+    #[test]
+    fn tag_cardinality_limit_drop_event_keeps_values_of_dropped_events_bloom() {
+        drop_event_keeps_values_of_dropped_events(make_transform_bloom(
+            1,
+            LimitExceededAction::DropEvent,
+        ));
+    }
+
+    /// Test that the values of a dropped event's other tags aren't accepted,
+    /// so they don't use up the limit of those tags.
+    fn drop_event_keeps_values_of_dropped_events(mut transform: TagCardinalityLimit) {
+        let tags1: BTreeMap<String, String> =
+            vec![("tag1".into(), "val1".into())].into_iter().collect();
+        let event1 = make_metric(tags1);
+
+        let tags2: BTreeMap<String, String> = vec![
+            ("tag1".into(), "val2".into()),
+            ("tag2".into(), "val2".into()),
+        ]
+        .into_iter()
+        .collect();
+        let event2 = make_metric(tags2);
+
+        let tags3: BTreeMap<String, String> = vec![
+            ("tag1".into(), "val1".into()),
+            ("tag2".into(), "val3".into()),
+        ]
+        .into_iter()
+        .collect();
+        let event3 = make_metric(tags3);
+
+        assert_eq!(transform.transform_one(event1.clone()), Some(event1));
+        // Rejected for "tag1", so "tag2" must not have accepted "val2".
+        assert_eq!(transform.transform_one(event2), None);
+        assert_eq!(transform.transform_one(event3.clone()), Some(event3));
+    }
+
     #[test]
     fn tag_cardinality_limit_drop_tag_hashset() {
         drop_tag(make_transform_hashset(2, LimitExceededAction::DropTag));
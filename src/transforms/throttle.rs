@@ -0,0 +1,309 @@
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::Event,
+    internal_events::{ThrottleEventDiscarded, ThrottleEventProcessed},
+    template::Template,
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use futures::{
+    compat::{Compat, Compat01As03},
+    stream, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ThrottleConfig {
+    /// The number of events allowed per key in each window.
+    pub threshold: u64,
+    pub window_secs: u64,
+    /// Events are throttled separately for each rendered value of the key.
+    pub key_field: Option<Template>,
+    /// Events matching this condition are never throttled.
+    pub exclude: Option<AnyCondition>,
+    #[serde(default)]
+    pub on_excess: ExcessBehavior,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExcessBehavior {
+    /// Drop events over the threshold.
+    Drop,
+    /// Drop events over the threshold, and emit a single event per key at
+    /// the end of the window reporting how many were dropped.
+    Summarize,
+}
+
+impl Default for ExcessBehavior {
+    fn default() -> Self {
+        ExcessBehavior::Drop
+    }
+}
+
+inventory::submit! {
+    TransformDescription::new::<ThrottleConfig>("throttle")
+}
+
+impl GenerateConfig for ThrottleConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            threshold: 100,
+            window_secs: 1,
+            key_field: None,
+            exclude: None,
+            on_excess: ExcessBehavior::Drop,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "throttle")]
+impl TransformConfig for ThrottleConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        Throttle::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "throttle"
+    }
+}
+
+#[derive(Debug, Default)]
+struct KeyState {
+    count: u64,
+    suppressed: u64,
+}
+
+pub struct Throttle {
+    threshold: u64,
+    window: Duration,
+    key_field: Option<Template>,
+    exclude: Option<Box<dyn Condition>>,
+    on_excess: ExcessBehavior,
+    keys: HashMap<Option<String>, KeyState>,
+}
+
+impl Throttle {
+    pub fn new(config: &ThrottleConfig) -> crate::Result<Self> {
+        if config.window_secs == 0 {
+            return Err("`window_secs` must be greater than zero".into());
+        }
+
+        Ok(Self {
+            threshold: config.threshold,
+            window: Duration::from_secs(config.window_secs),
+            key_field: config.key_field.clone(),
+            exclude: config.exclude.as_ref().map(|c| c.build()).transpose()?,
+            on_excess: config.on_excess,
+            keys: HashMap::new(),
+        })
+    }
+
+    fn transform_one(&mut self, output: &mut Vec<Event>, event: Event) {
+        emit!(ThrottleEventProcessed);
+
+        if let Some(condition) = self.exclude.as_ref() {
+            if condition.check(&event) {
+                output.push(event);
+                return;
+            }
+        }
+
+        // Events the key can't be rendered for are throttled together.
+        let key = self
+            .key_field
+            .as_ref()
+            .and_then(|template| template.render_string(&event).ok());
+
+        let state = self.keys.entry(key).or_default();
+        if state.count < self.threshold {
+            state.count += 1;
+            output.push(event);
+        } else {
+            state.suppressed += 1;
+            emit!(ThrottleEventDiscarded);
+        }
+    }
+
+    /// Starts a new window, summarizing the events suppressed in the last one.
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        for (key, state) in self.keys.drain() {
+            if self.on_excess == ExcessBehavior::Summarize && state.suppressed > 0 {
+                let mut event = Event::from(format!(
+                    "{} events suppressed by throttle",
+                    state.suppressed
+                ));
+                let log = event.as_mut_log();
+                log.insert("suppressed_count", state.suppressed as i64);
+                if let Some(key) = key {
+                    log.insert("key", key);
+                }
+                output.push(event);
+            }
+        }
+    }
+}
+
+impl TaskTransform for Throttle {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut window_stream =
+            tokio::time::interval_at(tokio::time::Instant::now() + me.window, me.window);
+        let mut input_stream = Compat01As03::new(input_rx);
+
+        let stream = stream! {
+          loop {
+            let mut output = Vec::new();
+            let done = tokio::select! {
+                _ = window_stream.next() => {
+                  me.flush_into(&mut output);
+                  false
+                }
+                maybe_event = input_stream.next() => {
+                  match maybe_event {
+                    None => {
+                      me.flush_into(&mut output);
+                      true
+                    }
+                    Some(Ok(event)) => {
+                      me.transform_one(&mut output, event);
+                      false
+                    }
+                    Some(Err(())) => panic!("Unexpected error reading channel"),
+                  }
+                }
+            };
+            yield stream::iter(output.into_iter());
+            if done { break }
+          }
+        }
+        .flatten();
+
+        // Needed for compat
+        let try_stream = Box::pin(stream.map::<Result<Event, ()>, _>(Ok));
+
+        Box::new(Compat::new(try_stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::log_schema;
+    use futures::compat::Stream01CompatExt;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ThrottleConfig>();
+    }
+
+    async fn run(config: &str, events: Vec<Event>) -> Vec<Event> {
+        let throttle = toml::from_str::<ThrottleConfig>(config)
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .into_task();
+
+        throttle
+            .transform(Box::new(futures01::stream::iter_ok(events)))
+            .compat()
+            .map(Result::unwrap)
+            .collect()
+            .await
+    }
+
+    fn event(message: &str, app: &str) -> Event {
+        let mut event = Event::from(message);
+        event.as_mut_log().insert("app", app);
+        event
+    }
+
+    #[tokio::test]
+    async fn throttles_per_key() {
+        let output = run(
+            r#"
+            threshold = 2
+            window_secs = 60
+            key_field = "{{ app }}"
+            "#,
+            vec![
+                event("1", "a"),
+                event("2", "a"),
+                event("3", "b"),
+                event("4", "a"),
+                event("5", "b"),
+            ],
+        )
+        .await;
+
+        let messages = output
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["1", "2", "3", "5"]);
+    }
+
+    #[tokio::test]
+    async fn summarizes_excess_events() {
+        let output = run(
+            r#"
+            threshold = 1
+            window_secs = 60
+            key_field = "{{ app }}"
+            on_excess = "summarize"
+            "#,
+            vec![event("1", "a"), event("2", "a"), event("3", "a")],
+        )
+        .await;
+
+        assert_eq!(output.len(), 2);
+        let summary = output[1].as_log();
+        assert_eq!(
+            summary[log_schema().message_key()],
+            "2 events suppressed by throttle".into()
+        );
+        assert_eq!(summary["suppressed_count"], 2.into());
+        assert_eq!(summary["key"], "a".into());
+    }
+
+    #[tokio::test]
+    async fn never_throttles_excluded_events() {
+        let output = run(
+            r#"
+            threshold = 1
+            window_secs = 60
+            exclude."app.eq" = "critical"
+            "#,
+            vec![
+                event("1", "a"),
+                event("2", "critical"),
+                event("3", "a"),
+                event("4", "critical"),
+            ],
+        )
+        .await;
+
+        assert_eq!(output.len(), 3);
+    }
+}
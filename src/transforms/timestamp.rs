@@ -0,0 +1,393 @@
+use crate::{
+    config::{log_schema, DataType, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{TimestampEventProcessed, TimestampParseFailed},
+    transforms::{FunctionTransform, Transform, TransformOutputs, DROPPED},
+    types::format_has_zone,
+};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Unknown timestamp format {:?}", format))]
+    UnknownFormat { format: String },
+    #[snafu(display("`formats` must not be empty"))]
+    NoFormats,
+    #[snafu(display("Unknown time zone {:?}", timezone))]
+    UnknownTimezone { timezone: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct TimestampConfig {
+    /// The field to parse, defaults to the timestamp.
+    pub field: Option<String>,
+    /// Where the parsed timestamp is written, defaults to the timestamp.
+    pub target_field: Option<String>,
+    /// The formats to try, in order.
+    #[derivative(Default(value = "vec![\"rfc3339\".into(), \"unix\".into()]"))]
+    pub formats: Vec<String>,
+    /// The time zone of timestamps that don't include one, an IANA name or
+    /// `local`.
+    #[derivative(Default(value = "\"UTC\".into()"))]
+    pub timezone: String,
+    /// Remove `field` once it's parsed.
+    pub drop_field: bool,
+    /// Send events that fail to parse to the `dropped` output.
+    pub reroute_dropped: bool,
+}
+
+inventory::submit! {
+    TransformDescription::new::<TimestampConfig>("timestamp")
+}
+
+impl_generate_config_from_default!(TimestampConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "timestamp")]
+impl TransformConfig for TimestampConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        if self.formats.is_empty() {
+            return Err(BuildError::NoFormats.into());
+        }
+        let formats = self
+            .formats
+            .iter()
+            .map(|format| Format::parse(format))
+            .collect::<Result<Vec<_>, _>>()?;
+        let timezone = match self.timezone.as_str() {
+            "local" => Zone::Local,
+            timezone => Zone::Named(timezone.parse().map_err(|_| BuildError::UnknownTimezone {
+                timezone: timezone.to_owned(),
+            })?),
+        };
+
+        let timestamp_key = log_schema().timestamp_key();
+        Ok(Transform::function(Timestamp {
+            field: self.field.clone().unwrap_or_else(|| timestamp_key.into()),
+            target_field: self
+                .target_field
+                .clone()
+                .unwrap_or_else(|| timestamp_key.into()),
+            formats,
+            timezone,
+            drop_field: self.drop_field,
+            reroute_dropped: self.reroute_dropped,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "timestamp"
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        if self.reroute_dropped {
+            vec![DROPPED.to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Format {
+    Rfc3339,
+    Rfc2822,
+    /// Unix time, in units of a second divided by the value.
+    Unix(i64),
+    Strptime {
+        format: String,
+        has_zone: bool,
+    },
+}
+
+impl Format {
+    fn parse(format: &str) -> Result<Self, BuildError> {
+        Ok(match format {
+            "rfc3339" => Format::Rfc3339,
+            "rfc2822" => Format::Rfc2822,
+            "unix" => Format::Unix(1),
+            "unix_ms" => Format::Unix(1_000),
+            "unix_us" => Format::Unix(1_000_000),
+            "unix_ns" => Format::Unix(1_000_000_000),
+            format if format.contains('%') => Format::Strptime {
+                format: format.to_owned(),
+                has_zone: format_has_zone(format),
+            },
+            format => {
+                return Err(BuildError::UnknownFormat {
+                    format: format.to_owned(),
+                })
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Zone {
+    Local,
+    Named(Tz),
+}
+
+impl Zone {
+    fn localize(&self, naive: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        // Times repeated by a daylight saving change resolve to the first.
+        match self {
+            Zone::Local => Local
+                .from_local_datetime(naive)
+                .earliest()
+                .map(|datetime| datetime.with_timezone(&Utc)),
+            Zone::Named(tz) => tz
+                .from_local_datetime(naive)
+                .earliest()
+                .map(|datetime| datetime.with_timezone(&Utc)),
+        }
+    }
+}
+
+/// ISO 8601 without an offset, read in the configured time zone.
+const RFC3339_WITHOUT_ZONE: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+fn from_unix(value: &Value, per_second: i64) -> Option<DateTime<Utc>> {
+    let (seconds, nanos) = match value {
+        Value::Integer(value) => (
+            value.div_euclid(per_second),
+            value.rem_euclid(per_second) * (1_000_000_000 / per_second),
+        ),
+        Value::Float(value) => from_float(*value / per_second as f64)?,
+        Value::Bytes(bytes) => {
+            let s = String::from_utf8_lossy(bytes);
+            let s = s.trim();
+            return match s.parse::<i64>() {
+                Ok(value) => from_unix(&Value::Integer(value), per_second),
+                Err(_) => from_unix(&Value::Float(s.parse().ok()?), per_second),
+            };
+        }
+        _ => return None,
+    };
+    Utc.timestamp_opt(seconds, nanos as u32).single()
+}
+
+fn from_float(seconds: f64) -> Option<(i64, i64)> {
+    if !seconds.is_finite() {
+        return None;
+    }
+    let whole = seconds.floor();
+    Some((whole as i64, ((seconds - whole) * 1e9) as i64))
+}
+
+#[derive(Clone, Debug)]
+pub struct Timestamp {
+    field: String,
+    target_field: String,
+    formats: Vec<Format>,
+    timezone: Zone,
+    drop_field: bool,
+    reroute_dropped: bool,
+}
+
+impl Timestamp {
+    fn parse_value(&self, value: &Value) -> Option<DateTime<Utc>> {
+        if let Value::Timestamp(timestamp) = value {
+            return Some(*timestamp);
+        }
+
+        let s = String::from_utf8_lossy(&value.as_bytes()).into_owned();
+        let s = s.trim();
+        self.formats.iter().find_map(|format| match format {
+            Format::Unix(per_second) => from_unix(value, *per_second),
+            Format::Rfc3339 => DateTime::parse_from_rfc3339(s)
+                .map(|datetime| datetime.with_timezone(&Utc))
+                .ok()
+                .or_else(|| {
+                    RFC3339_WITHOUT_ZONE.iter().find_map(|format| {
+                        NaiveDateTime::parse_from_str(s, format)
+                            .ok()
+                            .and_then(|naive| self.timezone.localize(&naive))
+                    })
+                }),
+            Format::Rfc2822 => DateTime::parse_from_rfc2822(s)
+                .map(|datetime| datetime.with_timezone(&Utc))
+                .ok(),
+            Format::Strptime { format, has_zone } if *has_zone => {
+                DateTime::parse_from_str(s, format)
+                    .map(|datetime| datetime.with_timezone(&Utc))
+                    .ok()
+            }
+            Format::Strptime { format, .. } => NaiveDateTime::parse_from_str(s, format)
+                .ok()
+                .and_then(|naive| self.timezone.localize(&naive)),
+        })
+    }
+
+    /// Parses the field, handing the event back with the reason if it
+    /// can't be.
+    fn parse(&self, mut event: Event) -> Result<Event, (Event, String)> {
+        let log = event.as_mut_log();
+        let timestamp = match log.get(&self.field) {
+            Some(value) => match self.parse_value(value) {
+                Some(timestamp) => timestamp,
+                None => {
+                    emit!(TimestampParseFailed {
+                        field: &self.field,
+                        value,
+                    });
+                    let message = format!("no format matches {:?}", value.to_string_lossy());
+                    return Err((event, message));
+                }
+            },
+            None => {
+                let message = format!("field {:?} is missing", self.field);
+                return Err((event, message));
+            }
+        };
+
+        if self.drop_field && self.field != self.target_field {
+            log.remove(&self.field);
+        }
+        log.insert(&self.target_field, timestamp);
+
+        emit!(TimestampEventProcessed);
+
+        Ok(event)
+    }
+}
+
+impl FunctionTransform for Timestamp {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        match self.parse(event) {
+            Ok(event) => output.push(event),
+            Err((event, _)) if !self.reroute_dropped => output.push(event),
+            Err(_) => (),
+        }
+    }
+
+    fn transform_outputs(&mut self, outputs: &mut TransformOutputs, event: Event) {
+        match self.parse(event) {
+            Ok(event) => outputs.push(event),
+            Err((event, message)) if self.reroute_dropped => {
+                outputs.push_dropped(event, "timestamp_parse_failed", message)
+            }
+            Err((event, _)) => outputs.push(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TimestampConfig>();
+    }
+
+    fn config(formats: &[&str], timezone: &str) -> TimestampConfig {
+        TimestampConfig {
+            field: Some("time".into()),
+            formats: formats.iter().map(|&format| format.into()).collect(),
+            timezone: timezone.into(),
+            ..Default::default()
+        }
+    }
+
+    async fn parse(config: TimestampConfig, time: impl Into<Value>) -> Option<DateTime<Utc>> {
+        let mut transform = config.build().await.unwrap();
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("time", time.into());
+        let event = transform.as_function().transform_one(event).unwrap();
+        match event.as_log()[log_schema().timestamp_key()] {
+            Value::Timestamp(timestamp) => Some(timestamp),
+            _ => None,
+        }
+    }
+
+    fn utc(s: &str) -> Option<DateTime<Utc>> {
+        Some(DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc))
+    }
+
+    #[tokio::test]
+    async fn parses_rfc3339_with_and_without_zone() {
+        let config = config(&["rfc3339"], "Europe/Berlin");
+        assert_eq!(
+            parse(config.clone(), "2020-11-30T10:00:00.5-05:00").await,
+            utc("2020-11-30T15:00:00.5Z")
+        );
+        assert_eq!(
+            parse(config, "2020-07-01 12:00:00").await,
+            utc("2020-07-01T10:00:00Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn parses_unix_units() {
+        assert_eq!(
+            parse(config(&["unix"], "UTC"), "1606730400.25").await,
+            utc("2020-11-30T10:00:00.25Z")
+        );
+        assert_eq!(
+            parse(config(&["unix_ms"], "UTC"), 1606730400250i64).await,
+            utc("2020-11-30T10:00:00.25Z")
+        );
+        assert_eq!(
+            parse(config(&["unix_ns"], "UTC"), "1606730400000000001").await,
+            utc("2020-11-30T10:00:00.000000001Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn tries_formats_in_order() {
+        let config = config(&["%d/%b/%Y:%H:%M:%S %z", "%Y%m%d %H%M%S", "unix"], "UTC");
+        assert_eq!(
+            parse(config.clone(), "30/Nov/2020:10:00:00 +0100").await,
+            utc("2020-11-30T09:00:00Z")
+        );
+        assert_eq!(
+            parse(config.clone(), "20201130 100000").await,
+            utc("2020-11-30T10:00:00Z")
+        );
+        assert_eq!(parse(config, "0").await, utc("1970-01-01T00:00:00Z"));
+    }
+
+    #[tokio::test]
+    async fn reroutes_unparseable_events() {
+        let mut transform = TimestampConfig {
+            reroute_dropped: true,
+            ..config(&["rfc3339"], "UTC")
+        }
+        .build()
+        .await
+        .unwrap();
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("time", "yesterday");
+
+        let mut outputs = TransformOutputs::new("timestamp");
+        transform
+            .as_function()
+            .transform_outputs(&mut outputs, event);
+
+        assert!(outputs.primary.is_empty());
+        assert_eq!(
+            outputs.named[DROPPED][0].as_log()["metadata.dropped.reason"],
+            "timestamp_parse_failed".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_config() {
+        assert!(config(&["iso"], "UTC").build().await.is_err());
+        assert!(config(&["rfc3339"], "Mars/Olympus").build().await.is_err());
+    }
+}
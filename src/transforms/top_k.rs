@@ -0,0 +1,314 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{TopKEventProcessed, TopKFieldMissing, TopKSummaryEmitted},
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::{DateTime, Utc};
+use futures::{
+    compat::{Compat, Compat01As03},
+    stream, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`k` must be greater than zero"))]
+    ZeroK,
+    #[snafu(display("`counters` must be at least `k`"))]
+    TooFewCounters,
+    #[snafu(display("`window_secs` must be greater than zero"))]
+    ZeroWindow,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TopKConfig {
+    /// The field whose most frequent values are tracked.
+    pub field: String,
+    /// How many values each summary lists.
+    #[serde(default = "default_k")]
+    pub k: usize,
+    /// How many values are counted at once. More counters give more accurate
+    /// counts at the cost of memory.
+    pub counters: Option<usize>,
+    /// How often a summary is emitted. Counts are reset after each one.
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+}
+
+fn default_k() -> usize {
+    10
+}
+
+fn default_window_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    TransformDescription::new::<TopKConfig>("top_k")
+}
+
+impl GenerateConfig for TopKConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"field = "host""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "top_k")]
+impl TransformConfig for TopKConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        TopK::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "top_k"
+    }
+}
+
+/// The Misra-Gries summary: a bounded set of counters that is guaranteed to
+/// hold every value occurring more than `total / (capacity + 1)` times, with
+/// counts that are low by at most that much.
+#[derive(Debug)]
+struct MisraGries {
+    capacity: usize,
+    counters: HashMap<String, u64>,
+    total: u64,
+}
+
+impl MisraGries {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            counters: HashMap::with_capacity(capacity),
+            total: 0,
+        }
+    }
+
+    fn insert(&mut self, value: String) {
+        self.total += 1;
+        if let Some(count) = self.counters.get_mut(&value) {
+            *count += 1;
+        } else if self.counters.len() < self.capacity {
+            self.counters.insert(value, 1);
+        } else {
+            self.counters.retain(|_, count| {
+                *count -= 1;
+                *count > 0
+            });
+        }
+    }
+
+    /// The `k` values with the highest counts, highest first.
+    fn top(&self, k: usize) -> Vec<(&str, u64)> {
+        let mut top = self
+            .counters
+            .iter()
+            .map(|(value, count)| (value.as_str(), *count))
+            .collect::<Vec<_>>();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        top.truncate(k);
+        top
+    }
+}
+
+pub struct TopK {
+    field: String,
+    k: usize,
+    window: Duration,
+    sketch: MisraGries,
+    window_start: DateTime<Utc>,
+}
+
+impl TopK {
+    fn new(config: &TopKConfig) -> crate::Result<Self> {
+        let counters = config.counters.unwrap_or(config.k * 10);
+        if config.k == 0 {
+            return Err(BuildError::ZeroK.into());
+        }
+        if counters < config.k {
+            return Err(BuildError::TooFewCounters.into());
+        }
+        if config.window_secs == 0 {
+            return Err(BuildError::ZeroWindow.into());
+        }
+
+        Ok(Self {
+            field: config.field.clone(),
+            k: config.k,
+            window: Duration::from_secs(config.window_secs),
+            sketch: MisraGries::new(counters),
+            window_start: Utc::now(),
+        })
+    }
+
+    fn add_event(&mut self, event: Event) {
+        match event.as_log().get(&self.field) {
+            Some(value) => self.sketch.insert(value.to_string_lossy()),
+            None => emit!(TopKFieldMissing { field: &self.field }),
+        }
+        emit!(TopKEventProcessed);
+    }
+
+    /// Emits the summary of the window ending `now` and starts a new one.
+    fn flush_into(&mut self, output: &mut Vec<Event>, now: DateTime<Utc>) {
+        let sketch = std::mem::replace(&mut self.sketch, MisraGries::new(self.sketch.capacity));
+        let start = std::mem::replace(&mut self.window_start, now);
+        if sketch.total == 0 {
+            return;
+        }
+
+        let top = sketch
+            .top(self.k)
+            .into_iter()
+            .map(|(value, count)| {
+                let mut entry = BTreeMap::new();
+                entry.insert("value".to_owned(), Value::from(value));
+                entry.insert("count".to_owned(), Value::Integer(count as i64));
+                Value::Map(entry)
+            })
+            .collect();
+
+        let mut log = Event::new_empty_log().into_log();
+        log.insert(log_schema().timestamp_key(), now);
+        log.insert("field", self.field.clone());
+        log.insert("window.start", start);
+        log.insert("window.end", now);
+        log.insert("total", sketch.total as i64);
+        log.insert("top", Value::Array(top));
+        output.push(log.into());
+
+        emit!(TopKSummaryEmitted);
+    }
+}
+
+impl TaskTransform for TopK {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream =
+            tokio::time::interval_at(tokio::time::Instant::now() + me.window, me.window);
+        let mut input_stream = Compat01As03::new(input_rx);
+
+        let stream = stream! {
+          loop {
+            let mut output = Vec::new();
+            let done = tokio::select! {
+                _ = flush_stream.next() => {
+                  me.flush_into(&mut output, Utc::now());
+                  false
+                }
+                maybe_event = input_stream.next() => {
+                  match maybe_event {
+                    None => {
+                      me.flush_into(&mut output, Utc::now());
+                      true
+                    }
+                    Some(Ok(event)) => {
+                      me.add_event(event);
+                      false
+                    }
+                    Some(Err(())) => panic!("Unexpected error reading channel"),
+                  }
+                }
+            };
+            yield stream::iter(output.into_iter());
+            if done { break }
+          }
+        }
+        .flatten();
+
+        // Needed for compat
+        let try_stream = Box::pin(stream.map::<Result<Event, ()>, _>(Ok));
+
+        Box::new(Compat::new(try_stream))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::compat::Stream01CompatExt;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TopKConfig>();
+    }
+
+    #[test]
+    fn misra_gries_keeps_heavy_hitters() {
+        let mut sketch = MisraGries::new(2);
+        let values = "a b a c a d a b e a b".split(' ');
+        for value in values {
+            sketch.insert(value.to_owned());
+        }
+
+        let top = sketch.top(2);
+        assert_eq!(sketch.total, 11);
+        assert_eq!(top[0].0, "a");
+        // Counts are lower bounds, off by at most total / (capacity + 1).
+        assert!(top[0].1 <= 5 && top[0].1 >= 5 - 11 / 3);
+    }
+
+    #[tokio::test]
+    async fn emits_summary() {
+        let transform = toml::from_str::<TopKConfig>(
+            r#"
+            field = "host"
+            k = 2
+            "#,
+        )
+        .unwrap()
+        .build()
+        .await
+        .unwrap()
+        .into_task();
+
+        let inputs = ["web-1", "web-2", "web-1", "db-1", "web-1", "web-2"]
+            .iter()
+            .map(|&host| {
+                let mut event = Event::from("request");
+                event.as_mut_log().insert("host", host);
+                event
+            })
+            .collect::<Vec<_>>();
+        let in_stream = futures01::stream::iter_ok(inputs);
+        let output = transform
+            .transform(Box::new(in_stream))
+            .compat()
+            .map(|event| event.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let log = output[0].as_log();
+        assert_eq!(log["field"], "host".into());
+        assert_eq!(log["total"], Value::Integer(6));
+        assert_eq!(log["top[0].value"], "web-1".into());
+        assert_eq!(log["top[0].count"], Value::Integer(3));
+        assert_eq!(log["top[1].value"], "web-2".into());
+        assert_eq!(log["top[1].count"], Value::Integer(2));
+        assert!(log.get("top[2]").is_none());
+    }
+}
@@ -0,0 +1,461 @@
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{TraceSamplerEventProcessed, TraceSamplerTraceDecided},
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use futures::{
+    compat::{Compat, Compat01As03},
+    stream, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`sample_ratio` must be between 0 and 1"))]
+    InvalidRatio,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(default, deny_unknown_fields)]
+#[derivative(Default)]
+pub struct TraceSamplerConfig {
+    #[derivative(Default(value = "\"trace_id\".to_owned()"))]
+    pub trace_id_field: String,
+    /// How long to buffer a trace's spans, from its first span, before
+    /// deciding whether to keep it.
+    #[derivative(Default(value = "10"))]
+    pub decision_wait_secs: u64,
+    /// The most traces to buffer at once. Past it, the oldest traces are
+    /// decided early.
+    #[derivative(Default(value = "100_000"))]
+    pub max_traces: usize,
+    /// Traces with a span where this field is `true` are kept.
+    #[derivative(Default(value = "\"error\".to_owned()"))]
+    pub error_field: String,
+    /// Traces with a span lasting at least this long are kept.
+    pub latency_threshold_ms: Option<f64>,
+    #[derivative(Default(value = "\"duration_ms\".to_owned()"))]
+    pub duration_field: String,
+    /// Traces with a span matching this condition are kept.
+    pub keep_when: Option<AnyCondition>,
+    /// The fraction of the remaining traces to keep.
+    #[derivative(Default(value = "0.1"))]
+    pub sample_ratio: f64,
+    #[derivative(Default(value = "1000"))]
+    pub flush_period_ms: u64,
+}
+
+inventory::submit! {
+    TransformDescription::new::<TraceSamplerConfig>("trace_sampler")
+}
+
+impl GenerateConfig for TraceSamplerConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"latency_threshold_ms = 1000.0
+            sample_ratio = 0.1"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "trace_sampler")]
+impl TransformConfig for TraceSamplerConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        TraceSampler::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "trace_sampler"
+    }
+}
+
+/// Why a trace was kept.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Decision {
+    Error,
+    Latency,
+    Condition,
+    Sampled,
+    Dropped,
+}
+
+impl Decision {
+    fn as_str(self) -> &'static str {
+        match self {
+            Decision::Error => "error",
+            Decision::Latency => "latency",
+            Decision::Condition => "condition",
+            Decision::Sampled => "sampled",
+            Decision::Dropped => "dropped",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingTrace {
+    first_seen: Instant,
+    spans: Vec<Event>,
+    /// Set once any span makes the trace worth keeping.
+    keep: Option<Decision>,
+}
+
+pub struct TraceSampler {
+    trace_id_field: String,
+    decision_wait: Duration,
+    max_traces: usize,
+    error_field: String,
+    latency_threshold_ms: Option<f64>,
+    duration_field: String,
+    keep_when: Option<Box<dyn Condition>>,
+    sample_ratio: f64,
+    flush_period: Duration,
+    pending: HashMap<String, PendingTrace>,
+    /// Recent decisions, applied to spans that arrive late.
+    decided: HashMap<String, (Decision, Instant)>,
+}
+
+impl TraceSampler {
+    fn new(config: &TraceSamplerConfig) -> crate::Result<Self> {
+        if config.sample_ratio < 0.0 || config.sample_ratio > 1.0 {
+            return Err(BuildError::InvalidRatio.into());
+        }
+
+        Ok(Self {
+            trace_id_field: config.trace_id_field.clone(),
+            decision_wait: Duration::from_secs(config.decision_wait_secs),
+            max_traces: config.max_traces,
+            error_field: config.error_field.clone(),
+            latency_threshold_ms: config.latency_threshold_ms,
+            duration_field: config.duration_field.clone(),
+            keep_when: config.keep_when.as_ref().map(|c| c.build()).transpose()?,
+            sample_ratio: config.sample_ratio,
+            flush_period: Duration::from_millis(config.flush_period_ms),
+            pending: HashMap::new(),
+            decided: HashMap::new(),
+        })
+    }
+
+    /// Checks whether a span alone is reason to keep its trace.
+    fn rule_match(&self, span: &Event) -> Option<Decision> {
+        let log = span.as_log();
+        if let Some(Value::Boolean(true)) = log.get(&self.error_field) {
+            return Some(Decision::Error);
+        }
+        if let Some(threshold) = self.latency_threshold_ms {
+            let duration = match log.get(&self.duration_field) {
+                Some(Value::Integer(duration)) => Some(*duration as f64),
+                Some(Value::Float(duration)) => Some(*duration),
+                _ => None,
+            };
+            if duration.map_or(false, |duration| duration >= threshold) {
+                return Some(Decision::Latency);
+            }
+        }
+        match &self.keep_when {
+            Some(condition) if condition.check(span) => Some(Decision::Condition),
+            _ => None,
+        }
+    }
+
+    /// Hashing the trace id gives every instance the same decision for the
+    /// same trace.
+    fn sample(&self, trace_id: &str) -> Decision {
+        let hash = seahash::hash(trace_id.as_bytes());
+        if (hash as f64 / u64::MAX as f64) < self.sample_ratio {
+            Decision::Sampled
+        } else {
+            Decision::Dropped
+        }
+    }
+
+    fn add_span(&mut self, output: &mut Vec<Event>, span: Event, now: Instant) {
+        emit!(TraceSamplerEventProcessed);
+
+        let trace_id = match span.as_log().get(&self.trace_id_field) {
+            Some(trace_id) => trace_id.to_string_lossy(),
+            // Not part of a trace.
+            None => {
+                output.push(span);
+                return;
+            }
+        };
+
+        if let Some((decision, _)) = self.decided.get(&trace_id) {
+            let decision = *decision;
+            self.emit_span(output, span, decision);
+            return;
+        }
+
+        let rule = self.rule_match(&span);
+        let trace = self
+            .pending
+            .entry(trace_id)
+            .or_insert_with(|| PendingTrace {
+                first_seen: now,
+                spans: Vec::new(),
+                keep: None,
+            });
+        trace.keep = trace.keep.or(rule);
+        trace.spans.push(span);
+
+        if self.pending.len() > self.max_traces {
+            let oldest = self
+                .pending
+                .iter()
+                .min_by_key(|(_, trace)| trace.first_seen)
+                .map(|(trace_id, _)| trace_id.clone());
+            if let Some(trace_id) = oldest {
+                self.decide(output, trace_id, now);
+            }
+        }
+    }
+
+    fn decide(&mut self, output: &mut Vec<Event>, trace_id: String, now: Instant) {
+        let trace = match self.pending.remove(&trace_id) {
+            Some(trace) => trace,
+            None => return,
+        };
+        let decision = trace.keep.unwrap_or_else(|| self.sample(&trace_id));
+        emit!(TraceSamplerTraceDecided {
+            decision: decision.as_str(),
+            spans: trace.spans.len(),
+        });
+        for span in trace.spans {
+            self.emit_span(output, span, decision);
+        }
+        self.decided.insert(trace_id, (decision, now));
+    }
+
+    fn emit_span(&self, output: &mut Vec<Event>, mut span: Event, decision: Decision) {
+        let sample_rate = match decision {
+            Decision::Dropped => return,
+            Decision::Sampled => 1.0 / self.sample_ratio,
+            _ => 1.0,
+        };
+        let log = span.as_mut_log();
+        log.insert("sample_rate", sample_rate.to_string());
+        log.insert("sampling_reason", decision.as_str());
+        output.push(span);
+    }
+
+    /// Decides the traces that have waited long enough and forgets old
+    /// decisions.
+    fn flush_into(&mut self, output: &mut Vec<Event>, now: Instant) {
+        let wait = self.decision_wait;
+        let ready = self
+            .pending
+            .iter()
+            .filter(|(_, trace)| now.duration_since(trace.first_seen) >= wait)
+            .map(|(trace_id, _)| trace_id.clone())
+            .collect::<Vec<_>>();
+        for trace_id in ready {
+            self.decide(output, trace_id, now);
+        }
+        self.decided
+            .retain(|_, (_, decided_at)| now.duration_since(*decided_at) < wait);
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        let now = Instant::now();
+        let trace_ids = self.pending.keys().cloned().collect::<Vec<_>>();
+        for trace_id in trace_ids {
+            self.decide(output, trace_id, now);
+        }
+    }
+}
+
+impl TaskTransform for TraceSampler {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(me.flush_period);
+        let mut input_stream = Compat01As03::new(input_rx);
+
+        let stream = stream! {
+          loop {
+            let mut output = Vec::new();
+            let done = tokio::select! {
+                _ = flush_stream.next() => {
+                  me.flush_into(&mut output, Instant::now());
+                  false
+                }
+                maybe_event = input_stream.next() => {
+                  match maybe_event {
+                    None => {
+                      me.flush_all_into(&mut output);
+                      true
+                    }
+                    Some(Ok(event)) => {
+                      me.add_span(&mut output, event, Instant::now());
+                      false
+                    }
+                    Some(Err(())) => panic!("Unexpected error reading channel"),
+                  }
+                }
+            };
+            yield stream::iter(output.into_iter());
+            if done { break }
+          }
+        }
+        .flatten();
+
+        // Needed for compat
+        let try_stream = Box::pin(stream.map::<Result<Event, ()>, _>(Ok));
+
+        Box::new(Compat::new(try_stream))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::compat::Stream01CompatExt;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TraceSamplerConfig>();
+    }
+
+    fn sampler(config: &str) -> TraceSampler {
+        TraceSampler::new(&toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn span(trace_id: &str, duration_ms: i64, error: bool) -> Event {
+        let mut event = Event::from("span");
+        event.as_mut_log().insert("trace_id", trace_id);
+        event.as_mut_log().insert("duration_ms", duration_ms);
+        event.as_mut_log().insert("error", error);
+        event
+    }
+
+    fn trace_ids(output: &[Event]) -> Vec<String> {
+        output
+            .iter()
+            .map(|event| event.as_log()["trace_id"].to_string_lossy())
+            .collect()
+    }
+
+    #[test]
+    fn keeps_whole_interesting_traces() {
+        let mut sampler = sampler(
+            r#"
+            latency_threshold_ms = 500.0
+            sample_ratio = 0.0
+            "#,
+        );
+        let start = Instant::now();
+        let mut output = Vec::new();
+        sampler.add_span(&mut output, span("a", 10, false), start);
+        sampler.add_span(&mut output, span("a", 20, true), start);
+        sampler.add_span(&mut output, span("b", 10, false), start);
+        sampler.add_span(&mut output, span("b", 900, false), start);
+        sampler.add_span(&mut output, span("c", 10, false), start);
+        sampler.add_span(&mut output, Event::from("not a span"), start);
+        assert_eq!(output.len(), 1);
+
+        let mut output = Vec::new();
+        sampler.flush_into(&mut output, start + Duration::from_secs(5));
+        assert!(output.is_empty());
+
+        sampler.flush_into(&mut output, start + Duration::from_secs(10));
+        let mut ids = trace_ids(&output);
+        ids.sort();
+        assert_eq!(ids, vec!["a", "a", "b", "b"]);
+        let reasons = output
+            .iter()
+            .filter(|event| event.as_log()["trace_id"] == "a".into())
+            .map(|event| event.as_log()["sampling_reason"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(reasons, vec!["error".into(), "error".into()]);
+
+        // Late spans follow the decision made for their trace.
+        let mut output = Vec::new();
+        let later = start + Duration::from_secs(11);
+        sampler.add_span(&mut output, span("a", 5, false), later);
+        sampler.add_span(&mut output, span("c", 5, false), later);
+        assert_eq!(trace_ids(&output), vec!["a"]);
+    }
+
+    #[test]
+    fn samples_remaining_traces() {
+        let mut sampler = sampler("sample_ratio = 0.5");
+        let start = Instant::now();
+        let mut output = Vec::new();
+        for i in 0..1000 {
+            sampler.add_span(&mut output, span(&format!("trace-{}", i), 10, false), start);
+        }
+        sampler.flush_into(&mut output, start + Duration::from_secs(10));
+
+        assert!(output.len() > 400 && output.len() < 600);
+        assert_eq!(output[0].as_log()["sample_rate"], "2".into());
+        assert_eq!(output[0].as_log()["sampling_reason"], "sampled".into());
+    }
+
+    #[test]
+    fn decides_oldest_trace_when_full() {
+        let mut sampler = sampler(
+            r#"
+            max_traces = 2
+            sample_ratio = 1.0
+            "#,
+        );
+        let start = Instant::now();
+        let mut output = Vec::new();
+        sampler.add_span(&mut output, span("a", 10, false), start);
+        sampler.add_span(
+            &mut output,
+            span("b", 10, false),
+            start + Duration::from_secs(1),
+        );
+        sampler.add_span(
+            &mut output,
+            span("c", 10, false),
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(trace_ids(&output), vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn flushes_at_end_of_stream() {
+        let sampler = toml::from_str::<TraceSamplerConfig>("sample_ratio = 1.0")
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .into_task();
+
+        let inputs = vec![span("a", 10, false), span("a", 20, false)];
+        let in_stream = futures01::stream::iter_ok(inputs);
+        let output = sampler
+            .transform(Box::new(in_stream))
+            .compat()
+            .map(|event| event.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(output.len(), 2);
+    }
+}
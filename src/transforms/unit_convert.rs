@@ -0,0 +1,541 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event,
+    },
+    internal_events::UnitConvertEventProcessed,
+    transforms::{FunctionTransform, Transform},
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid regex in conversion {}: {}", index, source))]
+    InvalidRegex { index: usize, source: regex::Error },
+    #[snafu(display("Conversion {} can't convert {} to {}", index, from, to))]
+    IncompatibleUnits { index: usize, from: Unit, to: Unit },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Unknown unit {:?}", name))]
+pub struct UnknownUnit {
+    name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub enum Unit {
+    Bits,
+    Bytes,
+    Kilobytes,
+    Megabytes,
+    Gigabytes,
+    Terabytes,
+    Kibibytes,
+    Mebibytes,
+    Gibibytes,
+    Tebibytes,
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+    Ratio,
+    Percent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dimension {
+    Data,
+    Time,
+    Temperature,
+    Fraction,
+}
+
+impl Unit {
+    pub fn name(self) -> &'static str {
+        match self {
+            Unit::Bits => "bits",
+            Unit::Bytes => "bytes",
+            Unit::Kilobytes => "kilobytes",
+            Unit::Megabytes => "megabytes",
+            Unit::Gigabytes => "gigabytes",
+            Unit::Terabytes => "terabytes",
+            Unit::Kibibytes => "kibibytes",
+            Unit::Mebibytes => "mebibytes",
+            Unit::Gibibytes => "gibibytes",
+            Unit::Tebibytes => "tebibytes",
+            Unit::Nanoseconds => "nanoseconds",
+            Unit::Microseconds => "microseconds",
+            Unit::Milliseconds => "milliseconds",
+            Unit::Seconds => "seconds",
+            Unit::Minutes => "minutes",
+            Unit::Hours => "hours",
+            Unit::Days => "days",
+            Unit::Celsius => "celsius",
+            Unit::Fahrenheit => "fahrenheit",
+            Unit::Kelvin => "kelvin",
+            Unit::Ratio => "ratio",
+            Unit::Percent => "percent",
+        }
+    }
+
+    /// The dimension, and the scale and offset converting a value to the
+    /// dimension's base unit.
+    fn base(self) -> (Dimension, f64, f64) {
+        const KB: f64 = 1e3;
+        const KIB: f64 = 1024.0;
+        match self {
+            Unit::Bits => (Dimension::Data, 1.0 / 8.0, 0.0),
+            Unit::Bytes => (Dimension::Data, 1.0, 0.0),
+            Unit::Kilobytes => (Dimension::Data, KB, 0.0),
+            Unit::Megabytes => (Dimension::Data, KB * KB, 0.0),
+            Unit::Gigabytes => (Dimension::Data, KB * KB * KB, 0.0),
+            Unit::Terabytes => (Dimension::Data, KB * KB * KB * KB, 0.0),
+            Unit::Kibibytes => (Dimension::Data, KIB, 0.0),
+            Unit::Mebibytes => (Dimension::Data, KIB * KIB, 0.0),
+            Unit::Gibibytes => (Dimension::Data, KIB * KIB * KIB, 0.0),
+            Unit::Tebibytes => (Dimension::Data, KIB * KIB * KIB * KIB, 0.0),
+            Unit::Nanoseconds => (Dimension::Time, 1e-9, 0.0),
+            Unit::Microseconds => (Dimension::Time, 1e-6, 0.0),
+            Unit::Milliseconds => (Dimension::Time, 1e-3, 0.0),
+            Unit::Seconds => (Dimension::Time, 1.0, 0.0),
+            Unit::Minutes => (Dimension::Time, 60.0, 0.0),
+            Unit::Hours => (Dimension::Time, 3600.0, 0.0),
+            Unit::Days => (Dimension::Time, 86400.0, 0.0),
+            Unit::Celsius => (Dimension::Temperature, 1.0, 273.15),
+            Unit::Fahrenheit => (Dimension::Temperature, 5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0),
+            Unit::Kelvin => (Dimension::Temperature, 1.0, 0.0),
+            Unit::Ratio => (Dimension::Fraction, 1.0, 0.0),
+            Unit::Percent => (Dimension::Fraction, 0.01, 0.0),
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = UnknownUnit;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name.to_lowercase().as_str() {
+            "bits" | "bit" => Unit::Bits,
+            "bytes" | "byte" | "by" => Unit::Bytes,
+            "kilobytes" | "kb" => Unit::Kilobytes,
+            "megabytes" | "mb" => Unit::Megabytes,
+            "gigabytes" | "gb" => Unit::Gigabytes,
+            "terabytes" | "tb" => Unit::Terabytes,
+            "kibibytes" | "kib" => Unit::Kibibytes,
+            "mebibytes" | "mib" => Unit::Mebibytes,
+            "gibibytes" | "gib" => Unit::Gibibytes,
+            "tebibytes" | "tib" => Unit::Tebibytes,
+            "nanoseconds" | "ns" => Unit::Nanoseconds,
+            "microseconds" | "us" => Unit::Microseconds,
+            "milliseconds" | "ms" => Unit::Milliseconds,
+            "seconds" | "s" => Unit::Seconds,
+            "minutes" | "min" => Unit::Minutes,
+            "hours" | "h" => Unit::Hours,
+            "days" | "d" => Unit::Days,
+            "celsius" => Unit::Celsius,
+            "fahrenheit" => Unit::Fahrenheit,
+            "kelvin" => Unit::Kelvin,
+            "ratio" => Unit::Ratio,
+            "percent" | "%" => Unit::Percent,
+            _ => return Err(UnknownUnit { name: name.into() }),
+        })
+    }
+}
+
+impl TryFrom<String> for Unit {
+    type Error = UnknownUnit;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        name.parse()
+    }
+}
+
+impl From<Unit> for String {
+    fn from(unit: Unit) -> Self {
+        unit.name().into()
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ConversionConfig {
+    /// A regex matching the whole name of the metrics to convert.
+    pub name: String,
+    pub from: Unit,
+    pub to: Unit,
+    /// The new name of the metric. `$1` and so on are replaced by the groups
+    /// captured by `name`.
+    pub rename: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UnitConvertConfig {
+    /// The conversions. Each metric is converted by the first one whose
+    /// `name` matches it.
+    pub conversions: Vec<ConversionConfig>,
+    /// The tag holding the metric's unit. Metrics with a different unit
+    /// in it than `from` aren't converted, and converted metrics get the
+    /// new unit. Set to `""` to ignore units in tags.
+    #[serde(default = "default_unit_tag")]
+    pub unit_tag: String,
+}
+
+fn default_unit_tag() -> String {
+    "unit".into()
+}
+
+inventory::submit! {
+    TransformDescription::new::<UnitConvertConfig>("unit_convert")
+}
+
+impl GenerateConfig for UnitConvertConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"[[conversions]]
+            name = "(.*)_milliseconds"
+            from = "milliseconds"
+            to = "seconds"
+            rename = "${1}_seconds""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "unit_convert")]
+impl TransformConfig for UnitConvertConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let conversions = self
+            .conversions
+            .iter()
+            .enumerate()
+            .map(|(index, config)| Conversion::new(index, config))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Transform::function(UnitConvert {
+            conversions,
+            unit_tag: Some(self.unit_tag.clone()).filter(|tag| !tag.is_empty()),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "unit_convert"
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Conversion {
+    name: Regex,
+    from: Unit,
+    to: Unit,
+    rename: Option<String>,
+    /// Values are converted by multiplying by `scale` and adding `offset`.
+    scale: f64,
+    offset: f64,
+}
+
+impl Conversion {
+    fn new(index: usize, config: &ConversionConfig) -> Result<Self, BuildError> {
+        let name = Regex::new(&format!("^(?:{})$", config.name)).context(InvalidRegex { index })?;
+
+        let (from_dimension, from_scale, from_offset) = config.from.base();
+        let (to_dimension, to_scale, to_offset) = config.to.base();
+        if from_dimension != to_dimension {
+            return Err(BuildError::IncompatibleUnits {
+                index,
+                from: config.from,
+                to: config.to,
+            });
+        }
+
+        Ok(Self {
+            name,
+            from: config.from,
+            to: config.to,
+            rename: config.rename.clone(),
+            scale: from_scale / to_scale,
+            offset: (from_offset - to_offset) / to_scale,
+        })
+    }
+
+    fn point(&self, value: f64) -> f64 {
+        value * self.scale + self.offset
+    }
+
+    /// Differences, such as counter increments, don't move with the offset.
+    fn difference(&self, value: f64) -> f64 {
+        value * self.scale
+    }
+
+    fn convert(&self, metric: &mut Metric) {
+        let incremental = metric.kind == MetricKind::Incremental;
+        match &mut metric.value {
+            MetricValue::Counter { value } => *value = self.difference(*value),
+            MetricValue::Gauge { value } if incremental => *value = self.difference(*value),
+            MetricValue::Gauge { value } => *value = self.point(*value),
+            MetricValue::Set { .. } => (),
+            MetricValue::Distribution { values, .. } => {
+                for value in values {
+                    *value = self.point(*value);
+                }
+            }
+            MetricValue::AggregatedHistogram {
+                buckets,
+                count,
+                sum,
+                ..
+            } => {
+                for bucket in buckets {
+                    *bucket = self.point(*bucket);
+                }
+                *sum = self.difference(*sum) + self.offset * *count as f64;
+            }
+            MetricValue::AggregatedSummary {
+                values, count, sum, ..
+            } => {
+                for value in values {
+                    *value = self.point(*value);
+                }
+                *sum = self.difference(*sum) + self.offset * *count as f64;
+            }
+        }
+
+        if let Some(rename) = &self.rename {
+            metric.name = self
+                .name
+                .replace(&metric.name, rename.as_str())
+                .into_owned();
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct UnitConvert {
+    conversions: Vec<Conversion>,
+    unit_tag: Option<String>,
+}
+
+impl FunctionTransform for UnitConvert {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let metric = event.as_mut_metric();
+        let conversion = self
+            .conversions
+            .iter()
+            .find(|conversion| conversion.name.is_match(&metric.name));
+
+        if let Some(conversion) = conversion {
+            let tagged = self.unit_tag.as_ref().and_then(|unit_tag| {
+                metric
+                    .tags
+                    .as_ref()
+                    .and_then(|tags| tags.get(unit_tag))
+                    .map(|unit| unit.parse::<Unit>().ok())
+            });
+            // A metric tagged with another unit is either converted already or
+            // wasn't meant to be.
+            if tagged.map_or(true, |unit| unit == Some(conversion.from)) {
+                conversion.convert(metric);
+                if let Some(unit_tag) = &self.unit_tag {
+                    metric
+                        .tags
+                        .get_or_insert_with(Default::default)
+                        .insert(unit_tag.clone(), conversion.to.name().into());
+                }
+            }
+        }
+
+        emit!(UnitConvertEventProcessed);
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<UnitConvertConfig>();
+    }
+
+    fn metric_event(name: &str, value: MetricValue, tags: Option<&[(&str, &str)]>) -> Event {
+        Event::Metric(Metric {
+            name: name.into(),
+            namespace: None,
+            timestamp: None,
+            tags: tags.map(|tags| {
+                tags.iter()
+                    .map(|&(name, value)| (name.into(), value.into()))
+                    .collect::<BTreeMap<_, _>>()
+            }),
+            kind: MetricKind::Absolute,
+            value,
+        })
+    }
+
+    async fn convert(config: &str, event: Event) -> Metric {
+        let config = toml::from_str::<UnitConvertConfig>(config).unwrap();
+        let mut transform = config.build().await.unwrap();
+        transform
+            .as_function()
+            .transform_one(event)
+            .unwrap()
+            .into_metric()
+    }
+
+    #[tokio::test]
+    async fn converts_and_renames() {
+        let config = r#"
+            [[conversions]]
+            name = "(.*)_ms"
+            from = "ms"
+            to = "seconds"
+            rename = "${1}_seconds"
+        "#;
+
+        let metric = convert(
+            config,
+            metric_event(
+                "request_duration_ms",
+                MetricValue::AggregatedHistogram {
+                    buckets: vec![100.0, 1000.0],
+                    counts: vec![3, 1],
+                    count: 4,
+                    sum: 1500.0,
+                },
+                None,
+            ),
+        )
+        .await;
+
+        assert_eq!(metric.name, "request_duration_seconds");
+        assert_eq!(
+            metric.value,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![0.1, 1.0],
+                counts: vec![3, 1],
+                count: 4,
+                sum: 1.5,
+            }
+        );
+        assert_eq!(metric.tags.unwrap()["unit"], "seconds");
+    }
+
+    #[tokio::test]
+    async fn converts_temperatures() {
+        let config = r#"
+            [[conversions]]
+            name = "temperature"
+            from = "fahrenheit"
+            to = "celsius"
+        "#;
+
+        let metric = convert(
+            config,
+            metric_event("temperature", MetricValue::Gauge { value: 212.0 }, None),
+        )
+        .await;
+        match metric.value {
+            MetricValue::Gauge { value } => assert!((value - 100.0).abs() < 1e-9),
+            value => panic!("unexpected value {:?}", value),
+        }
+
+        let metric = convert(
+            config,
+            metric_event(
+                "temperature",
+                MetricValue::AggregatedSummary {
+                    quantiles: vec![0.5],
+                    values: vec![32.0],
+                    count: 2,
+                    sum: 64.0,
+                },
+                None,
+            ),
+        )
+        .await;
+        match metric.value {
+            MetricValue::AggregatedSummary { values, sum, .. } => {
+                assert!(values[0].abs() < 1e-9);
+                assert!(sum.abs() < 1e-9);
+            }
+            value => panic!("unexpected value {:?}", value),
+        }
+    }
+
+    #[tokio::test]
+    async fn checks_unit_tag() {
+        let config = r#"
+            [[conversions]]
+            name = "memory_used"
+            from = "bytes"
+            to = "mebibytes"
+        "#;
+
+        let metric = convert(
+            config,
+            metric_event(
+                "memory_used",
+                MetricValue::Gauge { value: 2097152.0 },
+                Some(&[("unit", "bytes")]),
+            ),
+        )
+        .await;
+        assert_eq!(metric.value, MetricValue::Gauge { value: 2.0 });
+        assert_eq!(metric.tags.unwrap()["unit"], "mebibytes");
+
+        // Already converted.
+        let metric = convert(
+            config,
+            metric_event(
+                "memory_used",
+                MetricValue::Gauge { value: 2.0 },
+                Some(&[("unit", "MiB")]),
+            ),
+        )
+        .await;
+        assert_eq!(metric.value, MetricValue::Gauge { value: 2.0 });
+    }
+
+    #[tokio::test]
+    async fn rejects_incompatible_units() {
+        let config = toml::from_str::<UnitConvertConfig>(
+            r#"
+            [[conversions]]
+            name = "latency"
+            from = "seconds"
+            to = "bytes"
+            "#,
+        )
+        .unwrap();
+        assert!(config.build().await.is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{UrlParserEventProcessed, UrlParserFailedParse, UrlParserFieldMissing},
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use url::{ParseError, Position, Url};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UrlParserConfig {
+    pub field: String,
+    #[serde(default = "default_target_field")]
+    pub target_field: String,
+    #[serde(default = "crate::serde::default_true")]
+    pub drop_field: bool,
+    /// Query parameters whose values are replaced with `[REDACTED]`, in the
+    /// parsed fields and in `field` if it's kept.
+    #[serde(default)]
+    pub redact_query_keys: Vec<String>,
+}
+
+fn default_target_field() -> String {
+    "url".to_owned()
+}
+
+inventory::submit! {
+    TransformDescription::new::<UrlParserConfig>("url_parser")
+}
+
+impl GenerateConfig for UrlParserConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            field: "request".to_owned(),
+            target_field: default_target_field(),
+            drop_field: true,
+            redact_query_keys: vec!["token".to_owned()],
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "url_parser")]
+impl TransformConfig for UrlParserConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        Ok(Transform::function(UrlParser {
+            field: self.field.clone(),
+            target_field: self.target_field.clone(),
+            drop_field: self.drop_field && self.field != self.target_field,
+            redact_query_keys: self.redact_query_keys.iter().cloned().collect(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "url_parser"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct UrlParser {
+    field: String,
+    target_field: String,
+    drop_field: bool,
+    redact_query_keys: HashSet<String>,
+}
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Only used to parse the path and query of relative URLs, as found in
+/// access logs.
+const RELATIVE_BASE: &str = "http://relative.invalid";
+
+impl UrlParser {
+    /// Parses the URL, returning the parsed fields and the URL with the
+    /// configured query parameters redacted if there are any.
+    fn parse(&self, value: &str) -> Result<(BTreeMap<String, Value>, Option<String>), ParseError> {
+        let (mut url, relative) = match Url::parse(value) {
+            Ok(url) => (url, false),
+            Err(ParseError::RelativeUrlWithoutBase) => {
+                (Url::parse(RELATIVE_BASE)?.join(value)?, true)
+            }
+            Err(error) => return Err(error),
+        };
+
+        let mut fields = BTreeMap::new();
+        if !relative {
+            fields.insert("scheme".to_owned(), Value::from(url.scheme()));
+            if let Some(host) = url.host_str() {
+                fields.insert("host".to_owned(), Value::from(host));
+            }
+            if let Some(port) = url.port_or_known_default() {
+                fields.insert("port".to_owned(), Value::Integer(port as i64));
+            }
+            if !url.username().is_empty() {
+                fields.insert("user".to_owned(), Value::from(url.username()));
+            }
+        }
+        fields.insert("path".to_owned(), Value::from(url.path()));
+        if let Some(fragment) = url.fragment() {
+            fields.insert("fragment".to_owned(), Value::from(fragment));
+        }
+
+        let mut redacted = false;
+        let pairs = url
+            .query_pairs()
+            .map(|(key, value)| {
+                if self.redact_query_keys.contains(key.as_ref()) {
+                    redacted = true;
+                    (key.into_owned(), REDACTED.to_owned())
+                } else {
+                    (key.into_owned(), value.into_owned())
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if url.query().is_some() {
+            let mut query = BTreeMap::new();
+            for (key, value) in &pairs {
+                // Repeated keys are collected into an array.
+                match query.remove(key) {
+                    None => query.insert(key.clone(), Value::from(value.as_str())),
+                    Some(Value::Array(mut values)) => {
+                        values.push(Value::from(value.as_str()));
+                        query.insert(key.clone(), Value::Array(values))
+                    }
+                    Some(first) => query.insert(
+                        key.clone(),
+                        Value::Array(vec![first, Value::from(value.as_str())]),
+                    ),
+                };
+            }
+            fields.insert("query".to_owned(), Value::Map(query));
+        }
+
+        let redacted = if redacted {
+            url.query_pairs_mut().clear().extend_pairs(&pairs);
+            let url = if relative {
+                url[Position::BeforePath..].to_owned()
+            } else {
+                url.to_string()
+            };
+            Some(url)
+        } else {
+            None
+        };
+
+        Ok((fields, redacted))
+    }
+}
+
+impl FunctionTransform for UrlParser {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_mut_log();
+        match log.get(&self.field).map(|value| value.to_string_lossy()) {
+            Some(value) => match self.parse(&value) {
+                Ok((fields, redacted)) => {
+                    if self.drop_field {
+                        log.remove(&self.field);
+                    } else if let Some(redacted) = redacted {
+                        log.insert(&self.field, redacted);
+                    }
+                    log.insert(&self.target_field, Value::Map(fields));
+                }
+                Err(error) => emit!(UrlParserFailedParse {
+                    field: &self.field,
+                    error
+                }),
+            },
+            None => emit!(UrlParserFieldMissing { field: &self.field }),
+        }
+
+        emit!(UrlParserEventProcessed);
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<UrlParserConfig>();
+    }
+
+    async fn parse(url: &str, drop_field: bool, redact_query_keys: &[&str]) -> LogEvent {
+        let mut parser = UrlParserConfig {
+            field: "request".to_owned(),
+            target_field: default_target_field(),
+            drop_field,
+            redact_query_keys: redact_query_keys.iter().map(|&key| key.into()).collect(),
+        }
+        .build()
+        .await
+        .unwrap();
+
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("request", url);
+        parser
+            .as_function()
+            .transform_one(event)
+            .unwrap()
+            .into_log()
+    }
+
+    #[tokio::test]
+    async fn url_parser_decomposes_url() {
+        let log = parse(
+            "https://user@example.com/search/a%20b?q=vector%20rs&tag=a&tag=b#top",
+            true,
+            &[],
+        )
+        .await;
+
+        assert!(log.get("request").is_none());
+        assert_eq!(log["url.scheme"], "https".into());
+        assert_eq!(log["url.host"], "example.com".into());
+        assert_eq!(log["url.port"], Value::Integer(443));
+        assert_eq!(log["url.user"], "user".into());
+        assert_eq!(log["url.path"], "/search/a%20b".into());
+        assert_eq!(log["url.fragment"], "top".into());
+        assert_eq!(log["url.query.q"], "vector rs".into());
+        assert_eq!(
+            log["url.query.tag"],
+            Value::Array(vec!["a".into(), "b".into()])
+        );
+    }
+
+    #[tokio::test]
+    async fn url_parser_parses_relative_url() {
+        let log = parse("/api/v1/users?id=42", true, &[]).await;
+
+        assert!(log.get("url.scheme").is_none());
+        assert!(log.get("url.host").is_none());
+        assert_eq!(log["url.path"], "/api/v1/users".into());
+        assert_eq!(log["url.query.id"], "42".into());
+    }
+
+    #[tokio::test]
+    async fn url_parser_redacts_query_keys() {
+        let log = parse("/login?user=bob&token=s3cret", false, &["token"]).await;
+
+        assert_eq!(log["url.query.user"], "bob".into());
+        assert_eq!(log["url.query.token"], "[REDACTED]".into());
+        assert_eq!(
+            log["request"],
+            "/login?user=bob&token=%5BREDACTED%5D".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn url_parser_passes_invalid_url() {
+        let log = parse("http://[::1", true, &[]).await;
+
+        assert_eq!(log["request"], "http://[::1".into());
+        assert!(log.get("url").is_none());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{Event, Value},
+    internal_events::{UserAgentParserEventProcessed, UserAgentParserFieldMissing},
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+use uaparser::{Parser, UserAgentParser as Regexes};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UserAgentParserConfig {
+    pub field: String,
+    /// Path to uap-core's `regexes.yaml`.
+    pub regexes: String,
+    #[serde(default = "default_target_field")]
+    pub target_field: String,
+    /// Add an `is_bot` field, `true` for crawlers and other automated
+    /// clients.
+    #[serde(default)]
+    pub detect_bots: bool,
+    #[serde(default = "crate::serde::default_true")]
+    pub drop_field: bool,
+}
+
+fn default_target_field() -> String {
+    "user_agent".to_owned()
+}
+
+inventory::submit! {
+    TransformDescription::new::<UserAgentParserConfig>("user_agent_parser")
+}
+
+impl GenerateConfig for UserAgentParserConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            field: "agent".to_owned(),
+            regexes: "/path/to/regexes.yaml".to_owned(),
+            target_field: default_target_field(),
+            detect_bots: false,
+            drop_field: true,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "user_agent_parser")]
+impl TransformConfig for UserAgentParserConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let regexes = Regexes::from_yaml(&self.regexes)
+            .map_err(|error| format!("Invalid regexes file {:?}: {:?}", self.regexes, error))?;
+
+        Ok(Transform::function(UserAgentParser {
+            regexes: Arc::new(regexes),
+            field: self.field.clone(),
+            target_field: self.target_field.clone(),
+            detect_bots: self.detect_bots,
+            drop_field: self.drop_field && self.field != self.target_field,
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "user_agent_parser"
+    }
+}
+
+#[derive(Derivative, Clone)]
+#[derivative(Debug)]
+pub struct UserAgentParser {
+    #[derivative(Debug = "ignore")]
+    regexes: Arc<Regexes>,
+    field: String,
+    target_field: String,
+    detect_bots: bool,
+    drop_field: bool,
+}
+
+/// The device family uap-core gives crawlers and other bots.
+const BOT_DEVICE_FAMILY: &str = "Spider";
+
+impl UserAgentParser {
+    fn parse(&self, user_agent: &str) -> BTreeMap<String, Value> {
+        let client = self.regexes.parse(user_agent);
+
+        let mut browser = BTreeMap::new();
+        browser.insert(
+            "family".to_owned(),
+            client.user_agent.family.to_string().into(),
+        );
+        insert_version(
+            &mut browser,
+            &[
+                &client.user_agent.major,
+                &client.user_agent.minor,
+                &client.user_agent.patch,
+            ],
+        );
+
+        let mut os = BTreeMap::new();
+        os.insert("family".to_owned(), client.os.family.to_string().into());
+        insert_version(
+            &mut os,
+            &[
+                &client.os.major,
+                &client.os.minor,
+                &client.os.patch,
+                &client.os.patch_minor,
+            ],
+        );
+
+        let mut device = BTreeMap::new();
+        device.insert("family".to_owned(), client.device.family.to_string().into());
+        if let Some(brand) = &client.device.brand {
+            device.insert("brand".to_owned(), brand.to_string().into());
+        }
+        if let Some(model) = &client.device.model {
+            device.insert("model".to_owned(), model.to_string().into());
+        }
+
+        let mut parsed = BTreeMap::new();
+        if self.detect_bots {
+            let is_bot = client.device.family == BOT_DEVICE_FAMILY;
+            parsed.insert("is_bot".to_owned(), Value::Boolean(is_bot));
+        }
+        parsed.insert("browser".to_owned(), Value::Map(browser));
+        parsed.insert("os".to_owned(), Value::Map(os));
+        parsed.insert("device".to_owned(), Value::Map(device));
+        parsed
+    }
+}
+
+/// Inserts the major, minor, ... parts of a version that are present, and
+/// the version joined with dots.
+fn insert_version<T: ToString>(fields: &mut BTreeMap<String, Value>, parts: &[&Option<T>]) {
+    let names = ["major", "minor", "patch", "patch_minor"];
+    let mut version = Vec::new();
+    for (name, part) in names.iter().zip(parts.iter()) {
+        match part {
+            Some(part) => {
+                let part = part.to_string();
+                fields.insert((*name).to_owned(), part.clone().into());
+                version.push(part);
+            }
+            None => break,
+        }
+    }
+    if version.is_empty() {
+        return;
+    }
+    fields.insert("version".to_owned(), version.join(".").into());
+}
+
+impl FunctionTransform for UserAgentParser {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_mut_log();
+        match log.get(&self.field).map(|value| value.to_string_lossy()) {
+            Some(user_agent) => {
+                let parsed = self.parse(&user_agent);
+                if self.drop_field {
+                    log.remove(&self.field);
+                }
+                log.insert(&self.target_field, Value::Map(parsed));
+            }
+            None => emit!(UserAgentParserFieldMissing { field: &self.field }),
+        }
+
+        emit!(UserAgentParserEventProcessed);
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<UserAgentParserConfig>();
+    }
+
+    async fn parse(user_agent: &str, detect_bots: bool) -> LogEvent {
+        let mut parser = UserAgentParserConfig {
+            field: "agent".to_owned(),
+            regexes: "tests/data/user_agent_parser/regexes.yaml".to_owned(),
+            target_field: default_target_field(),
+            detect_bots,
+            drop_field: true,
+        }
+        .build()
+        .await
+        .unwrap();
+
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("agent", user_agent);
+        parser
+            .as_function()
+            .transform_one(event)
+            .unwrap()
+            .into_log()
+    }
+
+    #[tokio::test]
+    async fn user_agent_parser_parses_browser_os_and_device() {
+        let log = parse(
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36",
+            false,
+        )
+        .await;
+
+        assert!(log.get("agent").is_none());
+        assert!(log.get("user_agent.is_bot").is_none());
+        assert_eq!(log["user_agent.browser.family"], "Chrome".into());
+        assert_eq!(log["user_agent.browser.major"], "87".into());
+        assert_eq!(log["user_agent.browser.version"], "87.0.4280".into());
+        assert_eq!(log["user_agent.os.family"], "Mac OS X".into());
+        assert_eq!(log["user_agent.os.version"], "10.15.7".into());
+        assert_eq!(log["user_agent.device.family"], "Mac".into());
+        assert_eq!(log["user_agent.device.brand"], "Apple".into());
+    }
+
+    #[tokio::test]
+    async fn user_agent_parser_detects_bots() {
+        let log = parse(
+            "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+            true,
+        )
+        .await;
+        assert_eq!(log["user_agent.is_bot"], Value::Boolean(true));
+        assert_eq!(log["user_agent.browser.family"], "Googlebot".into());
+
+        let log = parse(
+            "Mozilla/5.0 (Linux; Android 11; Pixel 5) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.101 Mobile Safari/537.36",
+            true,
+        )
+        .await;
+        assert_eq!(log["user_agent.is_bot"], Value::Boolean(false));
+        assert_eq!(log["user_agent.os.family"], "Android".into());
+        assert_eq!(log["user_agent.os.version"], "11".into());
+        assert_eq!(log["user_agent.device.model"], "Pixel 5".into());
+    }
+
+    #[tokio::test]
+    async fn user_agent_parser_handles_unknown_agents() {
+        let log = parse("curl/7.64.1", true).await;
+        assert_eq!(log["user_agent.browser.family"], "Other".into());
+        assert!(log.get("user_agent.browser.version").is_none());
+        assert_eq!(log["user_agent.is_bot"], Value::Boolean(false));
+    }
+}
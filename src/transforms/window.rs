@@ -0,0 +1,572 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::discriminant::Discriminant,
+    event::{Event, LogEvent, Value},
+    internal_events::{WindowEventProcessed, WindowFlushed, WindowLateEventDropped},
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{
+    compat::{Compat, Compat01As03},
+    stream, StreamExt,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`window_secs` must be greater than zero"))]
+    ZeroWindow,
+    #[snafu(display("`slide_secs` must be greater than zero and at most `window_secs`"))]
+    InvalidSlide,
+    #[snafu(display("Aggregate {:?} needs a `field`", name))]
+    MissingField { name: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WindowConfig {
+    /// The length of each window.
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    /// How far apart windows start. Defaults to `window_secs`, giving
+    /// tumbling windows; smaller values give overlapping sliding windows.
+    pub slide_secs: Option<u64>,
+    /// How long to wait past the end of a window for late events before
+    /// emitting it.
+    #[serde(default)]
+    pub allowed_lateness_secs: u64,
+    /// An ordered list of fields to group events by. Each group has its own
+    /// windows.
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    /// The aggregates to compute, keyed by the output field name.
+    pub aggregates: IndexMap<String, AggregateConfig>,
+    #[serde(default = "default_flush_period_ms")]
+    pub flush_period_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AggregateConfig {
+    pub function: Function,
+    /// The field to aggregate, required by every function but `count`.
+    pub field: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Function {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+    DistinctCount,
+}
+
+fn default_window_secs() -> u64 {
+    60
+}
+
+fn default_flush_period_ms() -> u64 {
+    1000
+}
+
+inventory::submit! {
+    TransformDescription::new::<WindowConfig>("window")
+}
+
+impl GenerateConfig for WindowConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"window_secs = 60
+            group_by = ["service"]
+            aggregates.events = { function = "count" }"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "window")]
+impl TransformConfig for WindowConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        Window::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "window"
+    }
+}
+
+/// The running state of one aggregate.
+#[derive(Debug)]
+enum Aggregate {
+    Count(i64),
+    Sum(Number),
+    Avg(f64, i64),
+    Min(Option<Number>),
+    Max(Option<Number>),
+    DistinctCount(HashSet<Bytes>),
+}
+
+/// A number that stays an integer as long as every input is one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Integer(i64),
+    Float(f64),
+}
+
+impl Number {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Integer(value) => Some(Number::Integer(*value)),
+            Value::Float(value) => Some(Number::Float(*value)),
+            _ => None,
+        }
+    }
+
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Integer(value) => value as f64,
+            Number::Float(value) => value,
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other.as_f64() < self.as_f64() {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other.as_f64() > self.as_f64() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl std::ops::Add for Number {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        match (self, other) {
+            (Number::Integer(a), Number::Integer(b)) => Number::Integer(a.saturating_add(b)),
+            (a, b) => Number::Float(a.as_f64() + b.as_f64()),
+        }
+    }
+}
+
+impl From<Number> for Value {
+    fn from(number: Number) -> Self {
+        match number {
+            Number::Integer(value) => Value::Integer(value),
+            Number::Float(value) => Value::Float(value),
+        }
+    }
+}
+
+impl Aggregate {
+    fn new(function: Function) -> Self {
+        match function {
+            Function::Count => Aggregate::Count(0),
+            Function::Sum => Aggregate::Sum(Number::Integer(0)),
+            Function::Avg => Aggregate::Avg(0.0, 0),
+            Function::Min => Aggregate::Min(None),
+            Function::Max => Aggregate::Max(None),
+            Function::DistinctCount => Aggregate::DistinctCount(HashSet::new()),
+        }
+    }
+
+    /// Adds the event's value of the aggregated field, if it has one.
+    fn add(&mut self, value: Option<&Value>) {
+        match (self, value) {
+            (Aggregate::Count(count), _) => *count += 1,
+            (Aggregate::DistinctCount(values), Some(value)) => {
+                values.insert(value.as_bytes());
+            }
+            (aggregate, Some(value)) => {
+                let number = match Number::from_value(value) {
+                    Some(number) => number,
+                    None => return,
+                };
+                match aggregate {
+                    Aggregate::Sum(sum) => *sum = *sum + number,
+                    Aggregate::Avg(sum, count) => {
+                        *sum += number.as_f64();
+                        *count += 1;
+                    }
+                    Aggregate::Min(min) => *min = Some(min.map_or(number, |min| min.min(number))),
+                    Aggregate::Max(max) => *max = Some(max.map_or(number, |max| max.max(number))),
+                    Aggregate::Count(_) | Aggregate::DistinctCount(_) => unreachable!(),
+                }
+            }
+            (_, None) => (),
+        }
+    }
+
+    fn value(self) -> Value {
+        match self {
+            Aggregate::Count(count) => Value::Integer(count),
+            Aggregate::Sum(sum) => sum.into(),
+            Aggregate::Avg(_, 0) => Value::Null,
+            Aggregate::Avg(sum, count) => Value::Float(sum / count as f64),
+            Aggregate::Min(number) | Aggregate::Max(number) => {
+                number.map(Into::into).unwrap_or(Value::Null)
+            }
+            Aggregate::DistinctCount(values) => Value::Integer(values.len() as i64),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct WindowState {
+    group: Vec<(String, Value)>,
+    aggregates: Vec<Aggregate>,
+}
+
+pub struct Window {
+    window_ms: i64,
+    slide_ms: i64,
+    lateness_ms: i64,
+    flush_period: Duration,
+    group_by: Vec<String>,
+    aggregates: Vec<(String, Function, Option<String>)>,
+    /// Open windows, keyed by group and start time in milliseconds.
+    windows: HashMap<(Discriminant, i64), WindowState>,
+}
+
+impl Window {
+    fn new(config: &WindowConfig) -> crate::Result<Self> {
+        let slide_secs = config.slide_secs.unwrap_or(config.window_secs);
+        if config.window_secs == 0 {
+            return Err(BuildError::ZeroWindow.into());
+        }
+        if slide_secs == 0 || slide_secs > config.window_secs {
+            return Err(BuildError::InvalidSlide.into());
+        }
+
+        let aggregates = config
+            .aggregates
+            .iter()
+            .map(|(name, aggregate)| {
+                if aggregate.function != Function::Count && aggregate.field.is_none() {
+                    return Err(BuildError::MissingField { name: name.clone() });
+                }
+                Ok((name.clone(), aggregate.function, aggregate.field.clone()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            window_ms: config.window_secs as i64 * 1000,
+            slide_ms: slide_secs as i64 * 1000,
+            lateness_ms: config.allowed_lateness_secs as i64 * 1000,
+            flush_period: Duration::from_millis(config.flush_period_ms),
+            group_by: config.group_by.clone(),
+            aggregates,
+            windows: HashMap::new(),
+        })
+    }
+
+    /// Whether a window starting at `start` has already been emitted.
+    fn is_closed(&self, start: i64, now: DateTime<Utc>) -> bool {
+        start + self.window_ms + self.lateness_ms <= now.timestamp_millis()
+    }
+
+    fn add_event(&mut self, event: LogEvent, now: DateTime<Utc>) {
+        let timestamp = match event.get(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(timestamp)) => *timestamp,
+            _ => now,
+        }
+        .timestamp_millis();
+        let discriminant = Discriminant::from_log_event(&event, &self.group_by);
+
+        // Every window containing the timestamp, latest first.
+        let mut start = timestamp.div_euclid(self.slide_ms) * self.slide_ms;
+        while start + self.window_ms > timestamp {
+            if self.is_closed(start, now) {
+                emit!(WindowLateEventDropped);
+            } else {
+                let aggregates = &self.aggregates;
+                let group_by = &self.group_by;
+                let state = self
+                    .windows
+                    .entry((discriminant.clone(), start))
+                    .or_insert_with(|| WindowState {
+                        group: group_by
+                            .iter()
+                            .filter_map(|field| {
+                                event.get(field).map(|value| (field.clone(), value.clone()))
+                            })
+                            .collect(),
+                        aggregates: aggregates
+                            .iter()
+                            .map(|(_, function, _)| Aggregate::new(*function))
+                            .collect(),
+                    });
+                for ((_, _, field), aggregate) in
+                    self.aggregates.iter().zip(state.aggregates.iter_mut())
+                {
+                    aggregate.add(field.as_ref().and_then(|field| event.get(field)));
+                }
+            }
+            start -= self.slide_ms;
+        }
+
+        emit!(WindowEventProcessed);
+    }
+
+    fn flush_window(&self, start: i64, state: WindowState) -> Event {
+        let mut log = Event::new_empty_log().into_log();
+        for (field, value) in state.group {
+            log.insert(field, value);
+        }
+        let end = Utc.timestamp_millis(start + self.window_ms);
+        log.insert("window.start", Utc.timestamp_millis(start));
+        log.insert("window.end", end);
+        log.insert(log_schema().timestamp_key(), end);
+        for ((name, _, _), aggregate) in self.aggregates.iter().zip(state.aggregates) {
+            log.insert(name, aggregate.value());
+        }
+        Event::Log(log)
+    }
+
+    /// Emits the windows that are closed at `now`, oldest first.
+    fn flush_into(&mut self, output: &mut Vec<Event>, now: DateTime<Utc>) {
+        let mut closed = self
+            .windows
+            .keys()
+            .filter(|(_, start)| self.is_closed(*start, now))
+            .cloned()
+            .collect::<Vec<_>>();
+        closed.sort_by_key(|(_, start)| *start);
+        if !closed.is_empty() {
+            emit!(WindowFlushed {
+                count: closed.len()
+            });
+        }
+        for key in closed {
+            if let Some(state) = self.windows.remove(&key) {
+                output.push(self.flush_window(key.1, state));
+            }
+        }
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        let mut windows = self.windows.drain().collect::<Vec<_>>();
+        windows.sort_by_key(|((_, start), _)| *start);
+        for ((_, start), state) in windows {
+            output.push(self.flush_window(start, state));
+        }
+    }
+}
+
+impl TaskTransform for Window {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let mut flush_stream = tokio::time::interval(me.flush_period);
+        let mut input_stream = Compat01As03::new(input_rx);
+
+        let stream = stream! {
+          loop {
+            let mut output = Vec::new();
+            let done = tokio::select! {
+                _ = flush_stream.next() => {
+                  me.flush_into(&mut output, Utc::now());
+                  false
+                }
+                maybe_event = input_stream.next() => {
+                  match maybe_event {
+                    None => {
+                      me.flush_all_into(&mut output);
+                      true
+                    }
+                    Some(Ok(event)) => {
+                      me.add_event(event.into_log(), Utc::now());
+                      false
+                    }
+                    Some(Err(())) => panic!("Unexpected error reading channel"),
+                  }
+                }
+            };
+            yield stream::iter(output.into_iter());
+            if done { break }
+          }
+        }
+        .flatten();
+
+        // Needed for compat
+        let try_stream = Box::pin(stream.map::<Result<Event, ()>, _>(Ok));
+
+        Box::new(Compat::new(try_stream))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::compat::Stream01CompatExt;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WindowConfig>();
+    }
+
+    fn window(config: &str) -> Window {
+        Window::new(&toml::from_str::<WindowConfig>(config).unwrap()).unwrap()
+    }
+
+    fn event(secs: i64, service: &str, latency: Value) -> LogEvent {
+        let mut event = Event::from("request").into_log();
+        event.insert(log_schema().timestamp_key(), Utc.timestamp(secs, 0));
+        event.insert("service", service);
+        event.insert("latency", latency);
+        event
+    }
+
+    #[test]
+    fn aggregates_tumbling_windows() {
+        let mut window = window(
+            r#"
+            window_secs = 60
+            group_by = ["service"]
+            aggregates.requests = { function = "count" }
+            aggregates.total = { function = "sum", field = "latency" }
+            aggregates.mean = { function = "avg", field = "latency" }
+            aggregates.slowest = { function = "max", field = "latency" }
+            aggregates.latencies = { function = "distinct_count", field = "latency" }
+            "#,
+        );
+        let now = Utc.timestamp(100, 0);
+        window.add_event(event(0, "api", 10.into()), now);
+        window.add_event(event(30, "api", 30.into()), now);
+        window.add_event(event(59, "api", 30.into()), now);
+        window.add_event(event(61, "api", 5.into()), now);
+
+        let mut output = Vec::new();
+        window.flush_into(&mut output, now);
+        assert_eq!(output.len(), 1);
+        let log = output[0].as_log();
+        assert_eq!(log["service"], "api".into());
+        assert_eq!(log["window.start"], Utc.timestamp(0, 0).into());
+        assert_eq!(log["window.end"], Utc.timestamp(60, 0).into());
+        assert_eq!(log["requests"], Value::Integer(3));
+        assert_eq!(log["total"], Value::Integer(70));
+        assert_eq!(log["mean"], Value::Float(70.0 / 3.0));
+        assert_eq!(log["slowest"], Value::Integer(30));
+        assert_eq!(log["latencies"], Value::Integer(2));
+
+        // The second window is still open.
+        let mut output = Vec::new();
+        window.flush_all_into(&mut output);
+        assert_eq!(output[0].as_log()["requests"], Value::Integer(1));
+    }
+
+    #[test]
+    fn assigns_events_to_sliding_windows() {
+        let mut window = window(
+            r#"
+            window_secs = 60
+            slide_secs = 30
+            aggregates.requests = { function = "count" }
+            "#,
+        );
+        let now = Utc.timestamp(0, 0);
+        window.add_event(event(45, "api", Value::Null), now);
+
+        let mut output = Vec::new();
+        window.flush_all_into(&mut output);
+        let starts = output
+            .iter()
+            .map(|event| event.as_log()["window.start"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            starts,
+            vec![Utc.timestamp(0, 0).into(), Utc.timestamp(30, 0).into()]
+        );
+    }
+
+    #[test]
+    fn drops_late_events() {
+        let mut window = window(
+            r#"
+            allowed_lateness_secs = 10
+            aggregates.requests = { function = "count" }
+            "#,
+        );
+        window.add_event(event(5, "api", Value::Null), Utc.timestamp(65, 0));
+        window.add_event(event(5, "api", Value::Null), Utc.timestamp(75, 0));
+
+        let mut output = Vec::new();
+        window.flush_all_into(&mut output);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["requests"], Value::Integer(1));
+    }
+
+    #[tokio::test]
+    async fn flushes_at_end_of_stream() {
+        let window = toml::from_str::<WindowConfig>(
+            r#"
+            group_by = ["service"]
+            aggregates.requests = { function = "count" }
+            "#,
+        )
+        .unwrap()
+        .build()
+        .await
+        .unwrap()
+        .into_task();
+
+        let inputs = vec![
+            Event::from(event(0, "api", Value::Null)),
+            Event::from(event(1, "web", Value::Null)),
+            Event::from(event(2, "api", Value::Null)),
+        ];
+        let in_stream = futures01::stream::iter_ok(inputs);
+        let mut output = window
+            .transform(Box::new(in_stream))
+            .compat()
+            .map(|event| event.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        output.sort_by_key(|event| event.as_log()["service"].to_string_lossy());
+
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].as_log()["requests"], Value::Integer(2));
+        assert_eq!(output[1].as_log()["requests"], Value::Integer(1));
+    }
+
+    #[test]
+    fn requires_field() {
+        let config =
+            toml::from_str::<WindowConfig>(r#"aggregates.total = { function = "sum" }"#).unwrap();
+        assert!(Window::new(&config).is_err());
+    }
+}
@@ -188,7 +188,7 @@ fn parse_bool(s: &str) -> Result<bool, Error> {
 }
 
 /// Does the format specifier have a time zone option?
-fn format_has_zone(fmt: &str) -> bool {
+pub(crate) fn format_has_zone(fmt: &str) -> bool {
     fmt.find("%Z").is_some()
         || fmt.find("%z").is_some()
         || fmt.find("%:z").is_some()
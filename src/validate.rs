@@ -1,10 +1,11 @@
 use crate::{
     config::{self, Config, ConfigDiff},
+    enrichment_tables,
     topology::{self, builder::Pieces},
 };
 use colored::*;
 use exitcode::ExitCode;
-use std::{fmt, fs::remove_dir_all, path::PathBuf};
+use std::{collections::HashMap, fmt, fs::remove_dir_all, path::PathBuf};
 use structopt::StructOpt;
 
 const TEMPORARY_DIRECTORY: &str = "validate_tmp";
@@ -21,6 +22,11 @@ pub struct Opts {
     #[structopt(short, long)]
     deny_warnings: bool,
 
+    /// Fail validation when the config references environment variables that
+    /// aren't set, as Vector would on startup with `--strict-env-vars`.
+    #[structopt(long, env = "VECTOR_STRICT_ENV_VARS")]
+    strict_env_vars: bool,
+
     /// Vector config files in TOML format to validate.
     #[structopt(name = "config-toml", long)]
     paths_toml: Vec<PathBuf>,
@@ -33,6 +39,10 @@ pub struct Opts {
     #[structopt(name = "config-yaml", long)]
     paths_yaml: Vec<PathBuf>,
 
+    /// Directories of Vector config files to validate.
+    #[structopt(name = "config-dir", short = "C", long)]
+    config_dirs: Vec<PathBuf>,
+
     /// Any number of Vector config files to validate.
     /// Format is detected from the file name.
     /// If none are specified the default config path `/etc/vector/vector.toml`
@@ -47,6 +57,7 @@ impl Opts {
             (&self.paths_toml, Some(config::Format::TOML)),
             (&self.paths_json, Some(config::Format::JSON)),
             (&self.paths_yaml, Some(config::Format::YAML)),
+            (&self.config_dirs, None),
         ])
     }
 }
@@ -57,7 +68,7 @@ pub async fn validate(opts: &Opts, color: bool) -> ExitCode {
 
     let mut validated = true;
 
-    let mut config = match validate_config(opts, &mut fmt) {
+    let mut config = match validate_config(opts, &mut fmt).await {
         Some(config) => config,
         None => return exitcode::CONFIG,
     };
@@ -81,7 +92,7 @@ pub async fn validate(opts: &Opts, color: bool) -> ExitCode {
 
 /// Ok if all configs were successfully validated.
 /// Err Some contains only successfully validated configs.
-fn validate_config(opts: &Opts, fmt: &mut Formatter) -> Option<Config> {
+async fn validate_config(opts: &Opts, fmt: &mut Formatter) -> Option<Config> {
     // Prepare paths
     let paths = opts.paths_with_formats();
     let paths = if let Some(paths) = config::process_paths(&paths) {
@@ -92,7 +103,7 @@ fn validate_config(opts: &Opts, fmt: &mut Formatter) -> Option<Config> {
     };
 
     let paths_list: Vec<_> = paths.iter().map(|(path, _)| path).collect();
-    match config::load_from_paths(&paths, opts.deny_warnings) {
+    match config::load_from_paths(&paths, opts.deny_warnings, opts.strict_env_vars).await {
         Ok(config) => {
             fmt.success(format!("Loaded {:?}", &paths_list));
             Some(config)
@@ -126,7 +137,10 @@ async fn validate_components(
         .set(config.global.log_schema.clone())
         .expect("Couldn't set schema");
 
-    match topology::builder::build_pieces(config, diff).await {
+    let pieces = topology::builder::build_pieces(config, diff, HashMap::new()).await;
+    // Nothing is started, so the enrichment tables are left as they are.
+    enrichment_tables::discard();
+    match pieces {
         Ok(pieces) => {
             fmt.success("Component configuration");
             Some(pieces)
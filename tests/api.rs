@@ -74,7 +74,7 @@ mod tests {
         c.api.address = Some(next_addr());
 
         let diff = config::ConfigDiff::initial(&c);
-        let pieces = vector::topology::build_or_log_errors(&c, &diff)
+        let pieces = vector::topology::build_or_log_errors(&c, &diff, Default::default())
             .await
             .unwrap();
 
@@ -134,3 +134,87 @@ fn test_buffering() {
         assert_eq!(input_events2, &output_events[num_events..]);
     });
 }
+
+#[test]
+fn test_reload_keeps_buffer() {
+    trace_init();
+
+    let data_dir = tempdir().unwrap();
+    let data_dir = data_dir.path().to_path_buf();
+
+    let num_events: usize = 10;
+    let line_length = 100;
+    let max_size = 10_000;
+
+    let (in_tx, source_config) = support::source();
+    let (out_rx, sink_config) = support::sink(10);
+    let config = {
+        let mut config = config::Config::builder();
+        config.add_source("in", source_config);
+        config.add_sink("out", &["in"], sink_config);
+        config.sinks["out"].buffer = BufferConfig::Disk {
+            max_size,
+            when_full: Default::default(),
+        };
+        config.global.data_dir = Some(data_dir.clone());
+        config.build().unwrap()
+    };
+
+    let mut rt = runtime();
+    rt.block_on(async move {
+        let (mut topology, _crash) = start_topology(config, false).await;
+        let output_events = CountReceiver::receive_events(out_rx);
+
+        let (input_events, input_events_stream) =
+            random_events_with_stream(line_length, num_events);
+        let mut input_events_stream = input_events_stream.map(Ok);
+        let _ = in_tx
+            .clone()
+            .sink_compat()
+            .sink_map_err(|error| panic!(error))
+            .send_all(&mut input_events_stream)
+            .await
+            .unwrap();
+
+        // Changing the inputs of the sink replaces it, but its buffer is
+        // configured the same so the new sink picks up where the old one
+        // left off.
+        let (_in_tx2, source_config) = support::source();
+        let (out_rx2, sink_config) = support::sink(10);
+        let config = {
+            let mut config = config::Config::builder();
+            config.add_source("in", support::source().1);
+            config.add_source("in2", source_config);
+            config.add_sink("out", &["in", "in2"], sink_config);
+            config.sinks["out"].buffer = BufferConfig::Disk {
+                max_size,
+                when_full: Default::default(),
+            };
+            config.global.data_dir = Some(data_dir);
+            config.build().unwrap()
+        };
+        assert!(topology
+            .reload_config_and_respawn(config, false)
+            .await
+            .unwrap());
+        let output_events2 = CountReceiver::receive_events(out_rx2);
+
+        let (input_events2, input_events_stream) =
+            random_events_with_stream(line_length, num_events);
+        let mut input_events_stream = input_events_stream.map(Ok);
+        let _ = in_tx
+            .sink_compat()
+            .sink_map_err(|error| panic!(error))
+            .send_all(&mut input_events_stream)
+            .await
+            .unwrap();
+
+        topology.stop().compat().await.unwrap();
+
+        let mut output_events = output_events.await;
+        output_events.extend(output_events2.await);
+        assert_eq!(num_events * 2, output_events.len());
+        assert_eq!(input_events, &output_events[..num_events]);
+        assert_eq!(input_events2, &output_events[num_events..]);
+    });
+}
@@ -91,3 +91,54 @@ fn validate_cleanup() {
             .collect::<Vec<_>>()
     );
 }
+
+#[test]
+fn validate_config_dir() {
+    let dir = support::create_directory();
+    let mut path = dir.clone();
+    path.push("sources.toml");
+    support::overwrite_file(
+        path.clone(),
+        r#"
+[sources.in]
+    type = "stdin"
+"#,
+    );
+    path.set_file_name("sinks.yaml");
+    support::overwrite_file(
+        path.clone(),
+        r#"
+sinks:
+  out:
+    inputs: ["in"]
+    type: "blackhole"
+    print_amount: 10000
+"#,
+    );
+    path.set_file_name("README.md");
+    support::overwrite_file(path.clone(), "Not a config file.");
+
+    let mut cmd = Command::cargo_bin("vector").unwrap();
+    cmd.arg("validate")
+        .arg("--no-environment")
+        .arg("--config-dir")
+        .arg(dir.clone());
+    assert!(cmd.output().unwrap().status.success());
+
+    // Components defined in several files are duplicates.
+    path.set_file_name("more_sources.toml");
+    support::overwrite_file(
+        path,
+        r#"
+[sources.in]
+    type = "stdin"
+"#,
+    );
+
+    let mut cmd = Command::cargo_bin("vector").unwrap();
+    cmd.arg("validate")
+        .arg("--no-environment")
+        .arg("--config-dir")
+        .arg(dir);
+    assert!(!cmd.output().unwrap().status.success());
+}
@@ -198,6 +198,49 @@ async fn topology_transform_chain() {
     assert_eq!(vec!["this first second"], res);
 }
 
+#[tokio::test]
+async fn topology_transform_dropped_output() {
+    let (in1, source1) = source();
+    let (out1, sink1) = sink(10);
+    let (dropped, sink2) = sink(10);
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1);
+    config.add_transform(
+        "parser",
+        &["in1"],
+        vector::transforms::json_parser::JsonParserConfig {
+            reroute_dropped: true,
+            ..Default::default()
+        },
+    );
+    config.add_sink("out1", &["parser"], sink1);
+    config.add_sink("dead_letter", &["parser.dropped"], sink2);
+
+    let (topology, _crash) = start_topology(config.build().unwrap(), false).await;
+
+    let in1 = in1
+        .send(Event::from(r#"{"status": "ok"}"#))
+        .compat()
+        .await
+        .unwrap();
+    in1.send(Event::from("not json")).compat().await.unwrap();
+
+    topology.stop().compat().await.unwrap();
+
+    let res = out1.collect().compat().await.unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].as_log()["status"], "ok".into());
+
+    let res = dropped.collect().compat().await.unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(into_message(res[0].clone()), "not json");
+    assert_eq!(
+        res[0].as_log()["metadata.dropped.component"],
+        "parser".into()
+    );
+}
+
 #[tokio::test]
 async fn topology_remove_one_source() {
     let (in1, source1) = source();
@@ -492,7 +535,9 @@ async fn topology_swap_transform_is_atomic() {
 async fn topology_required_healthcheck_fails_start() {
     let config = basic_config_with_sink_failing_healthcheck();
     let diff = vector::config::ConfigDiff::initial(&config);
-    let pieces = topology::build_or_log_errors(&config, &diff).await.unwrap();
+    let pieces = topology::build_or_log_errors(&config, &diff, Default::default())
+        .await
+        .unwrap();
     assert!(topology::start_validated(config, diff, pieces, true)
         .await
         .is_none());
@@ -502,7 +547,9 @@ async fn topology_required_healthcheck_fails_start() {
 async fn topology_optional_healthcheck_does_not_fail_start() {
     let config = basic_config_with_sink_failing_healthcheck();
     let diff = vector::config::ConfigDiff::initial(&config);
-    let pieces = topology::build_or_log_errors(&config, &diff).await.unwrap();
+    let pieces = topology::build_or_log_errors(&config, &diff, Default::default())
+        .await
+        .unwrap();
     assert!(topology::start_validated(config, diff, pieces, false)
         .await
         .is_some());